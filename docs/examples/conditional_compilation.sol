@@ -0,0 +1,16 @@
+contract chain {
+    @if(target == "solana")
+    function name() public pure returns (string) {
+        return "Solana";
+    }
+
+    @if(target != "solana")
+    function name() public pure returns (string) {
+        return "not Solana";
+    }
+
+    @if(target == "polkadot" || target == "evm")
+    function caller() public view returns (address) {
+        return msg.sender;
+    }
+}
@@ -0,0 +1,11 @@
+contract powers {
+    function table(uint64 x) public pure returns (uint64[8] memory powers) {
+        uint64 power = 1;
+
+        // this loop runs 8 times, so it is unrolled
+        for (uint32 i = 0; i < 8; i++) {
+            powers[i] = power;
+            power *= x;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+contract ballot {
+    @enumerable(true)
+    mapping(address => uint32) votes;
+
+    function vote(address voter, uint32 count) public {
+        // the key is added the first time a value is assigned to it
+        votes[voter] = count;
+    }
+
+    function forget(address voter) public {
+        // the key is removed again
+        delete votes[voter];
+    }
+
+    function total() public view returns (uint64 sum) {
+        for (uint i = 0; i < votes.length; i++) {
+            sum += votes[votes.keyAt(i)];
+        }
+    }
+
+    function voters() public view returns (address[] memory) {
+        return votes.keys();
+    }
+}
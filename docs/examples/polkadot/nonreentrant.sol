@@ -0,0 +1,17 @@
+contract Vault {
+    mapping(address => uint128) balances;
+
+    function deposit() public payable {
+        balances[msg.sender] += msg.value;
+    }
+
+    @nonreentrant(true)
+    function withdraw() public {
+        uint128 amount = balances[msg.sender];
+
+        // the receiver cannot call withdraw() again before its balance is cleared
+        payable(msg.sender).transfer(amount);
+
+        balances[msg.sender] = 0;
+    }
+}
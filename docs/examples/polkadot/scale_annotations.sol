@@ -0,0 +1,19 @@
+contract registry {
+    mapping(uint32 => string) names;
+
+    function register(@compact uint32 id, string name) public {
+        names[id] = name;
+    }
+
+    function lookup(@compact uint32 id) public view returns (@option bool found, string name) {
+        name = names[id];
+        found = bytes(name).length != 0;
+    }
+
+    function total(uint64 a, uint64 b) public pure returns (@result bool ok, @compact uint64 sum) {
+        unchecked {
+            sum = a + b;
+        }
+        ok = sum >= a;
+    }
+}
@@ -0,0 +1,12 @@
+contract token {
+    uint256 totalSupply;
+    mapping(address => uint256) balances;
+
+    // totalSupply is loaded once before the loop, and stored once after it
+    function mint(address[] memory to, uint256 amount) public {
+        for (uint256 i = 0; i < to.length; i++) {
+            balances[to[i]] += amount;
+            totalSupply += amount;
+        }
+    }
+}
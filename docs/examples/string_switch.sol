@@ -0,0 +1,16 @@
+contract router {
+    function route(string memory method, uint64 a, uint64 b) public pure returns (uint64) {
+        // becomes a switch on the hash of method, followed by a single string comparison
+        if (method == "add") {
+            return a + b;
+        } else if (method == "sub") {
+            return a - b;
+        } else if (method == "mul") {
+            return a * b;
+        } else if (method == "div") {
+            return a / b;
+        } else {
+            revert("unknown method");
+        }
+    }
+}
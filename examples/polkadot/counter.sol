@@ -0,0 +1,31 @@
+contract counter {
+	uint64 private count;
+	address private owner;
+
+	/// Emitted each time the counter is incremented.
+	event Incremented(address indexed by, uint64 count);
+
+	/// The account which deploys the contract is its owner.
+	constructor() {
+		owner = msg.sender;
+	}
+
+	/// Anyone may increment the counter.
+	function increment() public {
+		count += 1;
+
+		emit Incremented(msg.sender, count);
+	}
+
+	/// Only the owner may set the counter back to zero.
+	function reset() public {
+		require(msg.sender == owner, "only the owner can reset the counter");
+
+		count = 0;
+	}
+
+	/// Returns the current value of the counter.
+	function get() public view returns (uint64) {
+		return count;
+	}
+}
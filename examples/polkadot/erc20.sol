@@ -0,0 +1,61 @@
+/// A fungible token which follows the ERC-20 standard.
+contract erc20 {
+	string public name;
+	string public symbol;
+	uint8 public constant decimals = 18;
+	uint256 public totalSupply;
+
+	mapping(address => uint256) public balanceOf;
+	mapping(address => mapping(address => uint256)) public allowance;
+
+	event Transfer(address indexed from, address indexed to, uint256 value);
+	event Approval(address indexed owner, address indexed spender, uint256 value);
+
+	/// The whole initial supply belongs to the account which deploys the contract.
+	constructor(string memory _name, string memory _symbol, uint256 initial_supply) {
+		name = _name;
+		symbol = _symbol;
+		totalSupply = initial_supply;
+		balanceOf[msg.sender] = initial_supply;
+
+		emit Transfer(address(0), msg.sender, initial_supply);
+	}
+
+	/// Move `value` tokens from the caller to `to`.
+	function transfer(address to, uint256 value) public returns (bool) {
+		_transfer(msg.sender, to, value);
+
+		return true;
+	}
+
+	/// Allow `spender` to move up to `value` tokens of the caller.
+	function approve(address spender, uint256 value) public returns (bool) {
+		allowance[msg.sender][spender] = value;
+
+		emit Approval(msg.sender, spender, value);
+
+		return true;
+	}
+
+	/// Move `value` tokens from `from` to `to`, using the allowance of the caller.
+	function transferFrom(address from, address to, uint256 value) public returns (bool) {
+		uint256 allowed = allowance[from][msg.sender];
+
+		require(allowed >= value, "insufficient allowance");
+
+		allowance[from][msg.sender] = allowed - value;
+
+		_transfer(from, to, value);
+
+		return true;
+	}
+
+	function _transfer(address from, address to, uint256 value) internal {
+		require(balanceOf[from] >= value, "insufficient balance");
+
+		balanceOf[from] -= value;
+		balanceOf[to] += value;
+
+		emit Transfer(from, to, value);
+	}
+}
@@ -0,0 +1,33 @@
+contract counter {
+	uint64 private count;
+	address private owner;
+
+	/// Emitted each time the counter is incremented.
+	event Incremented(uint64 count);
+
+	/// The account which pays for the data account is the owner of the counter.
+	@payer(payer)
+	constructor() {
+		owner = tx.accounts.payer.key;
+	}
+
+	/// Anyone may increment the counter.
+	function increment() public {
+		count += 1;
+
+		emit Incremented(count);
+	}
+
+	/// Only the owner may set the counter back to zero. The owner has to sign the transaction.
+	@signer(owner)
+	function reset() external {
+		require(tx.accounts.owner.key == owner, "only the owner can reset the counter");
+
+		count = 0;
+	}
+
+	/// Returns the current value of the counter.
+	function get() public view returns (uint64) {
+		return count;
+	}
+}
@@ -0,0 +1,74 @@
+/// A fungible token in the style of ERC-20, which keeps the balances in the data account of the
+/// contract. Instead of `msg.sender`, the owner of the tokens signs the transaction. For tokens
+/// which other Solana programs and wallets understand, use the spl-token-wrapper template.
+contract erc20 {
+	string public name;
+	string public symbol;
+	uint8 public constant decimals = 9;
+	uint64 public totalSupply;
+
+	mapping(address => uint64) public balanceOf;
+	mapping(address => mapping(address => uint64)) public allowance;
+
+	event Transfer(address from, address to, uint64 value);
+	event Approval(address owner, address spender, uint64 value);
+
+	/// The whole initial supply belongs to the account which pays for the data account. The data
+	/// account must have space for the balances and allowances.
+	@payer(payer)
+	@space(10240)
+	constructor(string _name, string _symbol, uint64 initial_supply) {
+		name = _name;
+		symbol = _symbol;
+		address owner = tx.accounts.payer.key;
+
+		totalSupply = initial_supply;
+		balanceOf[owner] = initial_supply;
+
+		emit Transfer(address(0), owner, initial_supply);
+	}
+
+	/// Move `value` tokens from the signer to `to`.
+	@signer(owner)
+	function transfer(address to, uint64 value) external returns (bool) {
+		_transfer(tx.accounts.owner.key, to, value);
+
+		return true;
+	}
+
+	/// Allow `spender` to move up to `value` tokens of the signer.
+	@signer(owner)
+	function approve(address spender, uint64 value) external returns (bool) {
+		address owner = tx.accounts.owner.key;
+
+		allowance[owner][spender] = value;
+
+		emit Approval(owner, spender, value);
+
+		return true;
+	}
+
+	/// Move `value` tokens from `from` to `to`, using the allowance of the signer.
+	@signer(spender)
+	function transferFrom(address from, address to, uint64 value) external returns (bool) {
+		address spender = tx.accounts.spender.key;
+		uint64 allowed = allowance[from][spender];
+
+		require(allowed >= value, "insufficient allowance");
+
+		allowance[from][spender] = allowed - value;
+
+		_transfer(from, to, value);
+
+		return true;
+	}
+
+	function _transfer(address from, address to, uint64 value) internal {
+		require(balanceOf[from] >= value, "insufficient balance");
+
+		balanceOf[from] -= value;
+		balanceOf[to] += value;
+
+		emit Transfer(from, to, value);
+	}
+}
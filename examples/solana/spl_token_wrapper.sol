@@ -0,0 +1,56 @@
+import './spl_token.sol';
+
+/// A program which mints, transfers and burns the tokens of an SPL-Token mint, so that other
+/// Solana programs and wallets understand them. The mint authority of the mint must sign the
+/// transactions which mint new tokens.
+contract spl_token_wrapper {
+	address mint;
+
+	/// The mint of the token is created client-side, and given to the constructor.
+	@payer(payer)
+	constructor(address _mint) {
+		mint = _mint;
+	}
+
+	/// Returns the total supply of the token.
+	@account(mint)
+	function total_supply() external view returns (uint64) {
+		require(tx.accounts.mint.key == mint, "wrong mint account");
+
+		return SplToken.total_supply(tx.accounts.mint);
+	}
+
+	/// Returns the balance of a token account.
+	@account(account)
+	function get_balance() external view returns (uint64) {
+		return SplToken.get_balance(tx.accounts.account);
+	}
+
+	/// Mint new tokens into a token account.
+	@mutableAccount(mint)
+	@mutableAccount(account)
+	@signer(authority)
+	function mint_to(uint64 amount) external {
+		require(tx.accounts.mint.key == mint, "wrong mint account");
+
+		SplToken.mint_to(tx.accounts.mint.key, tx.accounts.account.key, tx.accounts.authority.key, amount);
+	}
+
+	/// Move tokens between two token accounts. The owner of the tokens must sign.
+	@mutableAccount(from)
+	@mutableAccount(to)
+	@signer(owner)
+	function transfer(uint64 amount) external {
+		SplToken.transfer(tx.accounts.from.key, tx.accounts.to.key, tx.accounts.owner.key, amount);
+	}
+
+	/// Burn tokens of a token account. The owner of the tokens must sign.
+	@mutableAccount(account)
+	@mutableAccount(mint)
+	@signer(owner)
+	function burn(uint64 amount) external {
+		require(tx.accounts.mint.key == mint, "wrong mint account");
+
+		SplToken.burn(tx.accounts.account.key, tx.accounts.mint.key, tx.accounts.owner.key, amount);
+	}
+}
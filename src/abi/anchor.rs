@@ -37,6 +37,12 @@ pub fn event_discriminator(name: &str) -> Vec<u8> {
     discriminator("event", name)
 }
 
+/// Generate the discriminator of the data account of a contract. Anchor uses this 8 byte value
+/// to identify the type of an account, so it must match anchor's behaviour.
+pub fn account_discriminator(name: &str) -> Vec<u8> {
+    discriminator("account", name)
+}
+
 fn discriminator(namespace: &'static str, name: &str) -> Vec<u8> {
     let mut hasher = Sha256::new();
     hasher.update(format!("{namespace}:{name}"));
@@ -64,7 +70,7 @@ pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version:
         docs,
         constants: vec![],
         instructions,
-        accounts: vec![],
+        accounts: idl_accounts(contract),
         types: type_manager.generate_custom_idl_types(),
         events,
         errors: None,
@@ -72,6 +78,25 @@ pub fn generate_anchor_idl(contract_no: usize, ns: &Namespace, contract_version:
     }
 }
 
+/// The data account of the contract, if it starts with an Anchor discriminator. Anchor finds the
+/// discriminator from the name of the account. The storage variables are not Borsh encoded, so
+/// they are not listed as fields.
+fn idl_accounts(contract: &Contract) -> Vec<IdlTypeDefinition> {
+    if contract.account_discriminator.is_none() {
+        return Vec::new();
+    }
+
+    vec![IdlTypeDefinition {
+        name: contract.id.name.clone(),
+        docs: Some(vec![format!(
+            "Data account of contract {}; see the storage layout for its fields",
+            contract.id.name
+        )]),
+        ty: IdlTypeDefinitionTy::Struct { fields: Vec::new() },
+        generics: None,
+    }]
+}
+
 /// Generate IDL events for a contract.
 fn idl_events(
     contract: &Contract,
@@ -0,0 +1,241 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// a single json file per contract with everything a deployment framework needs, like the
+// artifacts hardhat writes
+use super::ethereum::{gen_abi, ABI};
+use super::selectors::{hex_selector, keccak256};
+use super::storage_layout::{gen_storage_layout, StorageLayout};
+use crate::sema::ast::{Function, Namespace, Parameter, Tag, Type};
+use crate::Target;
+use serde::Serialize;
+use serde_json::Value;
+use solang_parser::pt;
+use std::collections::BTreeMap;
+
+pub const BUNDLE_FORMAT: &str = "solang-bundle-1";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    #[serde(rename = "_format")]
+    pub format: &'static str,
+    pub contract_name: String,
+    pub source_name: String,
+    pub compiler: String,
+    pub target: String,
+    /// The binary, as 0x prefixed hex
+    pub bytecode: String,
+    pub abi: Vec<ABI>,
+    /// The ink! metadata on Polkadot, the Anchor IDL on Solana, and the ABI on other targets
+    pub metadata: Value,
+    /// The keccak256 hash of the metadata file, as written by `solang compile`
+    pub metadata_hash: String,
+    pub storage_layout: StorageLayout,
+    pub source_map: Vec<SourceMapEntry>,
+    pub userdoc: UserDoc,
+    pub devdoc: DevDoc,
+    /// The contracts which this contract creates, and whose code is linked into its binary
+    pub linked_contracts: Vec<String>,
+}
+
+/// Where an external function is declared. Lines and columns start at 1.
+#[derive(Serialize)]
+pub struct SourceMapEntry {
+    pub name: String,
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+#[derive(Serialize)]
+pub struct UserDoc {
+    pub kind: &'static str,
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notice: Option<String>,
+    pub methods: BTreeMap<String, MethodUserDoc>,
+}
+
+#[derive(Serialize)]
+pub struct MethodUserDoc {
+    pub notice: String,
+}
+
+#[derive(Serialize)]
+pub struct DevDoc {
+    pub kind: &'static str,
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    pub methods: BTreeMap<String, MethodDevDoc>,
+}
+
+#[derive(Serialize)]
+pub struct MethodDevDoc {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub returns: BTreeMap<String, String>,
+}
+
+/// Generate the bundle for a contract, from its binary and the metadata generated for it. The
+/// contract must have been through codegen, for the storage layout.
+pub fn gen_bundle(contract_no: usize, ns: &Namespace, code: &[u8], metadata: &str) -> Bundle {
+    let contract = &ns.contracts[contract_no];
+    let has_selectors = ns.target != Target::Soroban;
+
+    let functions: Vec<&Function> = contract
+        .functions
+        .iter()
+        .filter(|function_no| ns.functions[**function_no].is_constructor())
+        .chain(
+            contract
+                .all_functions
+                .keys()
+                .filter(|function_no| !ns.functions[**function_no].is_constructor()),
+        )
+        .filter(|function_no| ns.function_externally_callable(contract_no, Some(**function_no)))
+        .map(|function_no| &ns.functions[*function_no])
+        .collect();
+
+    let source_map = functions
+        .iter()
+        .filter_map(|func| {
+            let pt::Loc::File(file_no, start, end) = func.loc else {
+                return None;
+            };
+
+            let file = &ns.files[file_no];
+            let (line, column) = file.offset_to_line_column(start);
+            let (end_line, end_column) = file.offset_to_line_column(end);
+
+            // fallback and receive functions do not have a selector
+            let selector = (has_selectors
+                && !matches!(func.ty, pt::FunctionTy::Fallback | pt::FunctionTy::Receive))
+            .then(|| hex_selector(&func.selector(ns, &contract_no)));
+
+            Some(SourceMapEntry {
+                name: function_name(func),
+                signature: function_signature(func),
+                selector,
+                file: file.file_name(),
+                line: line + 1,
+                column: column + 1,
+                end_line: end_line + 1,
+                end_column: end_column + 1,
+            })
+        })
+        .collect();
+
+    let mut userdoc = UserDoc {
+        kind: "user",
+        version: 1,
+        notice: tag(&contract.tags, "notice"),
+        methods: BTreeMap::new(),
+    };
+
+    let mut devdoc = DevDoc {
+        kind: "dev",
+        version: 1,
+        title: tag(&contract.tags, "title"),
+        author: tag(&contract.tags, "author"),
+        details: tag(&contract.tags, "dev"),
+        methods: BTreeMap::new(),
+    };
+
+    for func in &functions {
+        let signature = function_signature(func);
+
+        if let Some(notice) = tag(&func.tags, "notice") {
+            userdoc
+                .methods
+                .insert(signature.clone(), MethodUserDoc { notice });
+        }
+
+        let doc = MethodDevDoc {
+            details: tag(&func.tags, "dev"),
+            params: param_tags(&func.tags, "param", &func.params),
+            returns: param_tags(&func.tags, "return", &func.returns),
+        };
+
+        if doc.details.is_some() || !doc.params.is_empty() || !doc.returns.is_empty() {
+            devdoc.methods.insert(signature, doc);
+        }
+    }
+
+    Bundle {
+        format: BUNDLE_FORMAT,
+        contract_name: contract.id.name.clone(),
+        source_name: ns.files[contract.loc.file_no()].file_name(),
+        compiler: format!("solang {}", env!("CARGO_PKG_VERSION")),
+        target: ns.target.to_string(),
+        bytecode: format!("0x{}", hex::encode(code)),
+        abi: gen_abi(contract_no, ns),
+        metadata: serde_json::from_str(metadata).unwrap_or(Value::Null),
+        metadata_hash: hex::encode(keccak256(metadata)),
+        storage_layout: gen_storage_layout(contract_no, ns),
+        source_map,
+        userdoc,
+        devdoc,
+        linked_contracts: contract
+            .creates
+            .iter()
+            .map(|contract_no| ns.contracts[*contract_no].id.name.clone())
+            .collect(),
+    }
+}
+
+/// Constructors are called new, unless they have a name
+fn function_name(func: &Function) -> String {
+    if func.is_constructor() && func.id.name.is_empty() {
+        "new".into()
+    } else {
+        func.id.name.clone()
+    }
+}
+
+fn function_signature(func: &Function) -> String {
+    if func.is_constructor() && func.id.name.is_empty() {
+        format!("new{}", func.signature)
+    } else {
+        func.signature.clone()
+    }
+}
+
+/// All the tags with the given name, joined with newlines like solc does
+fn tag(tags: &[Tag], name: &str) -> Option<String> {
+    let values: Vec<&str> = tags
+        .iter()
+        .filter(|tag| tag.tag == name)
+        .map(|tag| tag.value.as_str())
+        .collect();
+
+    (!values.is_empty()).then(|| values.join("\n"))
+}
+
+/// The param or return tags, by parameter name. Unnamed parameters are called `_0`, `_1`, etc.
+fn param_tags(tags: &[Tag], name: &str, params: &[Parameter<Type>]) -> BTreeMap<String, String> {
+    tags.iter()
+        .filter(|tag| tag.tag == name)
+        .map(|tag| {
+            let param = params
+                .get(tag.no)
+                .and_then(|param| param.id.as_ref())
+                .map(|id| id.name.clone())
+                .unwrap_or_else(|| format!("_{}", tag.no));
+
+            (param, tag.value.clone())
+        })
+        .collect()
+}
@@ -0,0 +1,500 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! How to encode the arguments of the constructors of a contract, for deployment tooling. The
+//! description is written by `solang compile --constructor-args`, and can be read back and passed
+//! to [`encode_constructor_args`] to build the payload of a deploy:
+//! - On Polkadot, the input of the instantiate call is the selector of the constructor, followed
+//!   by the arguments in the SCALE encoding.
+//! - On Solana, the instruction data is the Anchor discriminator of `new`, followed by the
+//!   arguments in the Borsh encoding. The instruction needs the accounts listed for the
+//!   constructor.
+//! - On EVM and Stylus, the arguments are ABI encoded, and are appended to the code of the
+//!   contract.
+//!
+//! The values of the arguments are given as json. Integers are numbers, or strings in decimal or
+//! in hex with `0x`. Addresses, fixed length byte arrays, `bytes` and external functions are hex
+//! strings; on Solana, addresses can also be in base58. Arrays and structs are json arrays, with
+//! the fields of a struct in the order they are declared.
+
+use super::ethereum::{parameter_to_abi, ABIParam};
+use super::events::ParamType;
+use super::selectors::hex_selector;
+use crate::sema::ast::{Function, Namespace, ScaleAnnotation};
+use crate::Target;
+use base58::FromBase58;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Zero};
+use parity_scale_codec::{Compact, Encode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize)]
+pub struct ConstructorArgs {
+    pub contract: String,
+    pub target: String,
+    pub encoding: ArgsEncoding,
+    /// The length of an address in bytes
+    pub address_length: usize,
+    pub constructors: Vec<Constructor>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgsEncoding {
+    /// SCALE, on Polkadot
+    Scale,
+    /// Borsh, on Solana
+    Borsh,
+    /// The Ethereum ABI encoding, on EVM and Stylus
+    Abi,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Constructor {
+    /// Constructors are called new, unless they have a name
+    pub name: String,
+    /// The selector or discriminator in hex, which comes before the arguments. On EVM and Stylus
+    /// there is none, since a contract has only one constructor.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub selector: Option<String>,
+    pub payable: bool,
+    pub inputs: Vec<ABIParam>,
+    /// The positions of the inputs with the `@compact` annotation, which are SCALE compact
+    /// integers
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub compact: Vec<usize>,
+    /// The accounts the instruction needs on Solana, in order
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub accounts: Vec<ConstructorAccount>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ConstructorAccount {
+    pub name: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Describe how to encode the arguments of the constructors of a contract. Soroban is not
+/// supported, since contracts are deployed without calling a constructor.
+pub fn gen_constructor_args(contract_no: usize, ns: &Namespace) -> Option<ConstructorArgs> {
+    let encoding = match ns.target {
+        Target::Polkadot { .. } => ArgsEncoding::Scale,
+        Target::Solana => ArgsEncoding::Borsh,
+        Target::EVM | Target::Stylus => ArgsEncoding::Abi,
+        Target::Soroban => return None,
+    };
+
+    let contract = &ns.contracts[contract_no];
+
+    let constructor = |func: &Function| Constructor {
+        name: if func.id.name.is_empty() {
+            "new".into()
+        } else {
+            func.id.name.clone()
+        },
+        selector: (encoding != ArgsEncoding::Abi)
+            .then(|| hex_selector(&func.selector(ns, &contract_no))),
+        payable: func.is_payable(),
+        inputs: func
+            .params
+            .iter()
+            .map(|param| parameter_to_abi(param, ns))
+            .collect(),
+        compact: func
+            .params
+            .iter()
+            .enumerate()
+            .filter(|(_, param)| param.scale_annotation() == Some(ScaleAnnotation::Compact))
+            .map(|(param_no, _)| param_no)
+            .collect(),
+        accounts: func
+            .solana_accounts
+            .borrow()
+            .iter()
+            .map(|(name, account)| ConstructorAccount {
+                name: name.clone(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writer,
+            })
+            .collect(),
+    };
+
+    let mut constructors: Vec<Constructor> = contract
+        .functions
+        .iter()
+        .filter(|function_no| {
+            ns.functions[**function_no].is_constructor()
+                && ns.function_externally_callable(contract_no, Some(**function_no))
+        })
+        .map(|function_no| constructor(&ns.functions[*function_no]))
+        .collect();
+
+    if constructors.is_empty() && contract.is_concrete() {
+        let mut default = constructor(&ns.default_constructor(contract_no));
+
+        // the default constructor only initializes the data account
+        if ns.target == Target::Solana {
+            default.accounts.push(ConstructorAccount {
+                name: "dataAccount".into(),
+                is_signer: false,
+                is_writable: true,
+            });
+        }
+
+        constructors.push(default);
+    }
+
+    Some(ConstructorArgs {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        encoding,
+        address_length: ns.address_length,
+        constructors,
+    })
+}
+
+/// Encode the arguments for a constructor of the contract, with the selector or discriminator
+/// in front if the target has one. The constructor is the one which takes as many arguments as
+/// there are values.
+pub fn encode_constructor_args(
+    contract: &ConstructorArgs,
+    values: &[Value],
+) -> Result<Vec<u8>, String> {
+    let mut candidates = contract
+        .constructors
+        .iter()
+        .filter(|constructor| constructor.inputs.len() == values.len());
+
+    let constructor = match (candidates.next(), candidates.next()) {
+        (Some(constructor), None) => constructor,
+        (None, _) => {
+            return Err(format!(
+                "contract '{}' has no constructor with {} arguments",
+                contract.contract,
+                values.len()
+            ))
+        }
+        (Some(_), Some(_)) => {
+            return Err(format!(
+                "contract '{}' has more than one constructor with {} arguments",
+                contract.contract,
+                values.len()
+            ))
+        }
+    };
+
+    let mut data = match &constructor.selector {
+        Some(selector) => hex::decode(selector.strip_prefix("0x").unwrap_or(selector))
+            .map_err(|_| format!("invalid selector '{selector}'"))?,
+        None => Vec::new(),
+    };
+
+    let encoder = Encoder {
+        encoding: contract.encoding,
+        address_length: contract.address_length,
+    };
+
+    let mut args = Vec::new();
+
+    for (param_no, (param, value)) in constructor.inputs.iter().zip(values).enumerate() {
+        let ty = ParamType::new(param)?;
+
+        let encoded = if constructor.compact.contains(&param_no) {
+            encoder.compact(&ty, value)
+        } else if encoder.encoding == ArgsEncoding::Abi {
+            encoder.ethereum_value(&ty, value)
+        } else {
+            let mut encoded = Vec::new();
+            encoder.packed(&ty, value, &mut encoded).map(|_| encoded)
+        };
+
+        let encoded = encoded.map_err(|err| {
+            if param.name.is_empty() {
+                format!("argument {}: {err}", param_no + 1)
+            } else {
+                format!("argument '{}': {err}", param.name)
+            }
+        })?;
+
+        args.push((ty, encoded));
+    }
+
+    if encoder.encoding == ArgsEncoding::Abi {
+        data.extend(ethereum_tuple(args));
+    } else {
+        data.extend(args.into_iter().flat_map(|(_, encoded)| encoded));
+    }
+
+    Ok(data)
+}
+
+struct Encoder {
+    encoding: ArgsEncoding,
+    address_length: usize,
+}
+
+impl Encoder {
+    /// The length of the selector in an external function value
+    fn selector_length(&self) -> usize {
+        if self.encoding == ArgsEncoding::Borsh {
+            8
+        } else {
+            4
+        }
+    }
+
+    fn address(&self, value: &Value) -> Result<Vec<u8>, String> {
+        let address = match value.as_str() {
+            Some(address) if self.encoding == ArgsEncoding::Borsh && !address.starts_with("0x") => {
+                address
+                    .from_base58()
+                    .map_err(|_| format!("{value} is not a valid base58 address"))?
+            }
+            _ => hex_bytes(value)?,
+        };
+
+        fixed_length(address, self.address_length, value)
+    }
+
+    /// A SCALE compact integer
+    fn compact(&self, ty: &ParamType, value: &Value) -> Result<Vec<u8>, String> {
+        let ParamType::Int { signed, bits } = ty else {
+            return Err(format!("'{}' cannot be a compact integer", ty.signature()));
+        };
+
+        let int = int(value, *signed, *bits)?;
+
+        Ok(Compact(u128::try_from(&int).map_err(|_| format!("{int} is too large"))?).encode())
+    }
+
+    /// Encode a value in place, as SCALE or Borsh. Integers are little endian and as wide as the
+    /// next power of two.
+    fn packed(&self, ty: &ParamType, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+        match ty {
+            ParamType::Bool => out.push(bool(value)? as u8),
+            ParamType::Int { signed, bits } => out.extend(int_bytes_le(
+                &int(value, *signed, *bits)?,
+                bits.next_power_of_two() / 8,
+            )),
+            ParamType::Address => out.extend(self.address(value)?),
+            ParamType::FixedBytes(len) => out.extend(fixed_length(hex_bytes(value)?, *len, value)?),
+            ParamType::Function => out.extend(fixed_length(
+                hex_bytes(value)?,
+                self.address_length + self.selector_length(),
+                value,
+            )?),
+            ParamType::Bytes => {
+                let bytes = hex_bytes(value)?;
+                self.len(bytes.len(), out);
+                out.extend(bytes);
+            }
+            ParamType::String => {
+                let string = string(value)?;
+                self.len(string.len(), out);
+                out.extend(string.as_bytes());
+            }
+            ParamType::Array(elem, len) => {
+                let values = elements(value, *len)?;
+
+                if len.is_none() {
+                    self.len(values.len(), out);
+                }
+
+                for value in values {
+                    self.packed(elem, value, out)?;
+                }
+            }
+            ParamType::Tuple(tys) => {
+                for (ty, value) in tys.iter().zip(elements(value, Some(tys.len()))?) {
+                    self.packed(ty, value, out)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn len(&self, len: usize, out: &mut Vec<u8>) {
+        if self.encoding == ArgsEncoding::Borsh {
+            out.extend((len as u32).to_le_bytes());
+        } else {
+            out.extend(Compact(len as u32).encode());
+        }
+    }
+
+    /// Encode a value with the Ethereum ABI encoding. Static types are encoded in place, and
+    /// dynamic types are encoded as they are in the tail.
+    fn ethereum_value(&self, ty: &ParamType, value: &Value) -> Result<Vec<u8>, String> {
+        Ok(match ty {
+            ParamType::Bool => word(bool(value)? as usize).to_vec(),
+            ParamType::Int { signed, bits } => {
+                let mut bytes = int_bytes_le(&int(value, *signed, *bits)?, 32);
+                bytes.reverse();
+                bytes
+            }
+            ParamType::Address => {
+                let address = self.address(value)?;
+                let mut word = vec![0; 32usize.saturating_sub(address.len())];
+                word.extend(address);
+                word
+            }
+            ParamType::FixedBytes(len) => padded(&fixed_length(hex_bytes(value)?, *len, value)?),
+            ParamType::Function => padded(&fixed_length(hex_bytes(value)?, 24, value)?),
+            ParamType::Bytes => {
+                let bytes = hex_bytes(value)?;
+                [word(bytes.len()).to_vec(), padded(&bytes)].concat()
+            }
+            ParamType::String => {
+                let string = string(value)?;
+                [word(string.len()).to_vec(), padded(string.as_bytes())].concat()
+            }
+            ParamType::Array(elem, len) => {
+                let values = elements(value, *len)?;
+
+                let encoded = values
+                    .iter()
+                    .map(|value| Ok((*elem.clone(), self.ethereum_value(elem, value)?)))
+                    .collect::<Result<_, String>>()?;
+
+                match len {
+                    Some(_) => ethereum_tuple(encoded),
+                    None => [word(values.len()).to_vec(), ethereum_tuple(encoded)].concat(),
+                }
+            }
+            ParamType::Tuple(tys) => {
+                let encoded = tys
+                    .iter()
+                    .zip(elements(value, Some(tys.len()))?)
+                    .map(|(ty, value)| Ok((ty.clone(), self.ethereum_value(ty, value)?)))
+                    .collect::<Result<_, String>>()?;
+
+                ethereum_tuple(encoded)
+            }
+        })
+    }
+}
+
+/// Lay out encoded values in the head and tail of the Ethereum ABI encoding. Dynamic values are
+/// in the tail, with their offset in the head.
+fn ethereum_tuple(values: Vec<(ParamType, Vec<u8>)>) -> Vec<u8> {
+    let head_size: usize = values.iter().map(|(ty, _)| ty.head_size()).sum();
+
+    let mut head = Vec::with_capacity(head_size);
+    let mut tail = Vec::new();
+
+    for (ty, encoded) in values {
+        if ty.is_static() {
+            head.extend(encoded);
+        } else {
+            head.extend(word(head_size + tail.len()));
+            tail.extend(encoded);
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+/// A length or offset as a word of the Ethereum ABI encoding
+fn word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+/// Pad bytes on the right to a multiple of 32
+fn padded(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize((bytes.len() + 31) / 32 * 32, 0);
+    padded
+}
+
+/// Two's complement little endian, sign extended or truncated to the length. The value must fit.
+fn int_bytes_le(value: &BigInt, len: usize) -> Vec<u8> {
+    let mut bytes = value.to_signed_bytes_le();
+    let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+    bytes.resize(len, fill);
+    bytes
+}
+
+fn int(value: &Value, signed: bool, bits: usize) -> Result<BigInt, String> {
+    let int = match value {
+        Value::Number(number) => number.to_string().parse().ok(),
+        Value::String(string) => parse_int(string),
+        _ => None,
+    }
+    .ok_or_else(|| format!("expected an integer, not {value}"))?;
+
+    let (min, max) = if signed {
+        let max = BigInt::one() << (bits - 1);
+        (-max.clone(), max - 1)
+    } else {
+        (BigInt::zero(), (BigInt::one() << bits) - 1)
+    };
+
+    if int < min || int > max {
+        return Err(format!(
+            "{int} does not fit into {}int{bits}",
+            if signed { "" } else { "u" }
+        ));
+    }
+
+    Ok(int)
+}
+
+/// An integer in decimal, or in hex with `0x`
+fn parse_int(string: &str) -> Option<BigInt> {
+    let (negative, digits) = match string.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, string),
+    };
+
+    let int = match digits.strip_prefix("0x") {
+        Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16)?,
+        None => BigInt::parse_bytes(digits.as_bytes(), 10)?,
+    };
+
+    Some(if negative { -int } else { int })
+}
+
+fn bool(value: &Value) -> Result<bool, String> {
+    value
+        .as_bool()
+        .ok_or_else(|| format!("expected a bool, not {value}"))
+}
+
+fn string(value: &Value) -> Result<&str, String> {
+    value
+        .as_str()
+        .ok_or_else(|| format!("expected a string, not {value}"))
+}
+
+fn hex_bytes(value: &Value) -> Result<Vec<u8>, String> {
+    value
+        .as_str()
+        .and_then(|hex| hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).ok())
+        .ok_or_else(|| format!("expected a hex string, not {value}"))
+}
+
+fn fixed_length(bytes: Vec<u8>, len: usize, value: &Value) -> Result<Vec<u8>, String> {
+    if bytes.len() == len {
+        Ok(bytes)
+    } else {
+        Err(format!("expected {len} bytes, not {value}"))
+    }
+}
+
+/// The elements of an array, or the fields of a struct
+fn elements(value: &Value, len: Option<usize>) -> Result<&Vec<Value>, String> {
+    let values = value
+        .as_array()
+        .ok_or_else(|| format!("expected an array, not {value}"))?;
+
+    match len {
+        Some(len) if values.len() != len => {
+            Err(format!("expected {len} elements, not {}", values.len()))
+        }
+        _ => Ok(values),
+    }
+}
@@ -2,38 +2,39 @@
 
 // ethereum style ABIs
 use crate::sema::ast::{Namespace, Parameter, StructType, Type};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use solang_parser::pt;
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct ABIParam {
+    #[serde(default)]
     pub name: String,
     #[serde(rename = "type")]
     pub ty: String,
-    #[serde(rename = "internalType")]
+    #[serde(rename = "internalType", default)]
     pub internal_ty: String,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub components: Vec<ABIParam>,
-    #[serde(skip_serializing_if = "is_false")]
+    #[serde(skip_serializing_if = "is_false", default)]
     pub indexed: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct ABI {
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(skip_serializing_if = "String::is_empty", default)]
     pub name: String,
     #[serde(rename = "type")]
     pub ty: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub inputs: Option<Vec<ABIParam>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub outputs: Option<Vec<ABIParam>>,
     #[serde(rename = "stateMutability")]
-    #[serde(skip_serializing_if = "String::is_empty")]
+    #[serde(skip_serializing_if = "String::is_empty", default)]
     pub mutability: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none", default)]
     pub anonymous: Option<bool>,
 }
 
@@ -53,26 +54,28 @@ impl Type {
     }
 }
 
-pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
-    fn parameter_to_abi(param: &Parameter<Type>, ns: &Namespace) -> ABIParam {
-        let components = if let Some(n) = param.ty.is_struct_or_array_of_struct() {
-            ns.structs[n]
-                .fields
-                .iter()
-                .map(|p| parameter_to_abi(p, ns))
-                .collect::<Vec<ABIParam>>()
-        } else {
-            Vec::new()
-        };
+pub(super) fn parameter_to_abi(param: &Parameter<Type>, ns: &Namespace) -> ABIParam {
+    let components = if let Some(n) = param.ty.is_struct_or_array_of_struct() {
+        ns.structs[n]
+            .fields
+            .iter()
+            .map(|p| parameter_to_abi(p, ns))
+            .collect::<Vec<ABIParam>>()
+    } else {
+        Vec::new()
+    };
 
-        ABIParam {
-            name: param.name_as_str().to_owned(),
-            ty: param.ty.to_signature_string(true, ns),
-            internal_ty: param.ty.to_string(ns),
-            components,
-            indexed: param.indexed,
-        }
+    ABIParam {
+        name: param.name_as_str().to_owned(),
+        ty: param.ty.to_signature_string(true, ns),
+        internal_ty: param.ty.to_string(ns),
+        components,
+        indexed: param.indexed,
     }
+}
+
+pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
+    let bases = ns.contract_bases(contract_no);
 
     ns.contracts[contract_no]
         .all_functions
@@ -134,5 +137,28 @@ pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
                     }
                 }),
         )
+        .chain(
+            ns.errors
+                .iter()
+                // errors declared outside of a contract are only listed if they are used
+                .filter(|error| match error.contract {
+                    Some(error_contract_no) => bases.contains(&error_contract_no),
+                    None => error.used,
+                })
+                .map(|error| ABI {
+                    name: error.name.to_owned(),
+                    mutability: String::new(),
+                    inputs: Some(
+                        error
+                            .fields
+                            .iter()
+                            .map(|p| parameter_to_abi(p, ns))
+                            .collect(),
+                    ),
+                    outputs: None,
+                    ty: "error".to_owned(),
+                    anonymous: None,
+                }),
+        )
         .collect()
 }
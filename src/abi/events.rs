@@ -0,0 +1,633 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decode the events which contracts emit into their fields, using the event definitions in the
+//! Ethereum style ABI of the contract. This is what `solang compile --abi solc` writes, and what
+//! [`gen_abi`](super::ethereum::gen_abi) returns.
+//!
+//! Each target encodes events differently:
+//! - With the Ethereum ABI encoding, the first topic is the hash of the event signature, unless
+//!   the event is anonymous. Indexed fields are in the other topics, and the rest is in the data.
+//! - On Polkadot, the data starts with the index of the event in the contract, followed by all
+//!   the fields in the SCALE encoding.
+//! - On Solana, the data logged with `sol_log_data` starts with the 8 byte Anchor discriminator of
+//!   the event, followed by all the fields in the Borsh encoding. The runtime logs it in base64,
+//!   as `Program data: <base64>`.
+
+use super::anchor::event_discriminator;
+use super::ethereum::{ABIParam, ABI};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use num_bigint::{BigInt, Sign};
+use parity_scale_codec::{Compact, Decode};
+use std::fmt;
+use tiny_keccak::{Hasher, Keccak};
+
+/// A decoded event
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DecodedEvent {
+    pub name: String,
+    /// The names of the fields, which may be empty, and their values
+    pub fields: Vec<(String, Value)>,
+}
+
+/// The value of an event field
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Value {
+    Bool(bool),
+    Int(BigInt),
+    Address(Vec<u8>),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// An external function, which is an address and a selector
+    Function(Vec<u8>),
+    Array(Vec<Value>),
+    Tuple(Vec<Value>),
+    /// An indexed field of a type like `string` or a struct has only its hash in the topic
+    Hash(Vec<u8>),
+}
+
+impl fmt::Display for DecodedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+
+        for (no, (name, value)) in self.fields.iter().enumerate() {
+            if no > 0 {
+                write!(f, ", ")?;
+            }
+
+            if !name.is_empty() {
+                write!(f, "{name}: ")?;
+            }
+
+            write!(f, "{value}")?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+/// Write the values separated by commas
+fn list(f: &mut fmt::Formatter, values: &[Value]) -> fmt::Result {
+    for (no, value) in values.iter().enumerate() {
+        if no > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{value}")?;
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Address(bytes)
+            | Value::FixedBytes(bytes)
+            | Value::Bytes(bytes)
+            | Value::Function(bytes)
+            | Value::Hash(bytes) => write!(f, "0x{}", hex::encode(bytes)),
+            Value::String(value) => write!(f, "{value:?}"),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                list(f, values)?;
+                write!(f, "]")
+            }
+            Value::Tuple(values) => {
+                write!(f, "(")?;
+                list(f, values)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// The type of a field, parsed from the ABI
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub(super) enum ParamType {
+    Bool,
+    Int { signed: bool, bits: usize },
+    Address,
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Function,
+    Array(Box<ParamType>, Option<usize>),
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    pub(super) fn new(param: &ABIParam) -> Result<Self, String> {
+        let (base, dims) = match param.ty.find('[') {
+            Some(pos) => param.ty.split_at(pos),
+            None => (param.ty.as_str(), ""),
+        };
+
+        let mut ty = match base {
+            "bool" => ParamType::Bool,
+            "address" => ParamType::Address,
+            "bytes" => ParamType::Bytes,
+            "string" => ParamType::String,
+            "function" => ParamType::Function,
+            "tuple" => ParamType::Tuple(
+                param
+                    .components
+                    .iter()
+                    .map(ParamType::new)
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => {
+                // `uint` is `uint256`
+                let bits = |bits: &str| match bits {
+                    "" => Ok(256),
+                    _ => bits
+                        .parse()
+                        .ok()
+                        .filter(|bits| bits % 8 == 0 && (8..=256).contains(bits))
+                        .ok_or_else(|| unknown_type(param)),
+                };
+
+                if let Some(width) = base.strip_prefix("uint") {
+                    ParamType::Int {
+                        signed: false,
+                        bits: bits(width)?,
+                    }
+                } else if let Some(width) = base.strip_prefix("int") {
+                    ParamType::Int {
+                        signed: true,
+                        bits: bits(width)?,
+                    }
+                } else if let Some(len) = base.strip_prefix("bytes") {
+                    ParamType::FixedBytes(
+                        len.parse()
+                            .ok()
+                            .filter(|len| (1..=32).contains(len))
+                            .ok_or_else(|| unknown_type(param))?,
+                    )
+                } else {
+                    return Err(unknown_type(param));
+                }
+            }
+        };
+
+        // `uint8[2][]` is a dynamic array of arrays of two elements
+        for dim in dims.split_terminator(']') {
+            let len = dim.strip_prefix('[').ok_or_else(|| unknown_type(param))?;
+
+            let len = if len.is_empty() {
+                None
+            } else {
+                Some(len.parse().map_err(|_| unknown_type(param))?)
+            };
+
+            ty = ParamType::Array(Box::new(ty), len);
+        }
+
+        Ok(ty)
+    }
+
+    /// The type as it is in the event signature, with tuples written out
+    pub(super) fn signature(&self) -> String {
+        match self {
+            ParamType::Bool => "bool".into(),
+            ParamType::Int { signed, bits } => {
+                format!("{}int{bits}", if *signed { "" } else { "u" })
+            }
+            ParamType::Address => "address".into(),
+            ParamType::FixedBytes(len) => format!("bytes{len}"),
+            ParamType::Bytes => "bytes".into(),
+            ParamType::String => "string".into(),
+            ParamType::Function => "function".into(),
+            ParamType::Array(ty, Some(len)) => format!("{}[{len}]", ty.signature()),
+            ParamType::Array(ty, None) => format!("{}[]", ty.signature()),
+            ParamType::Tuple(tys) => format!(
+                "({})",
+                tys.iter()
+                    .map(ParamType::signature)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    /// Is the type encoded in place in the Ethereum ABI encoding
+    pub(super) fn is_static(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_, None) => false,
+            ParamType::Array(ty, Some(_)) => ty.is_static(),
+            ParamType::Tuple(tys) => tys.iter().all(ParamType::is_static),
+            _ => true,
+        }
+    }
+
+    /// The size of the type in the head of the Ethereum ABI encoding
+    pub(super) fn head_size(&self) -> usize {
+        match self {
+            ParamType::Array(ty, Some(len)) if self.is_static() => ty.head_size() * len,
+            ParamType::Tuple(tys) if self.is_static() => tys.iter().map(ParamType::head_size).sum(),
+            _ => 32,
+        }
+    }
+}
+
+fn unknown_type(param: &ABIParam) -> String {
+    format!("unknown type '{}' of field '{}'", param.ty, param.name)
+}
+
+/// An event from the ABI, with the types of its fields
+struct EventType<'a> {
+    abi: &'a ABI,
+    fields: Vec<(&'a ABIParam, ParamType)>,
+}
+
+impl<'a> EventType<'a> {
+    /// The events in the ABI, in the order they are listed
+    fn all(abi: &'a [ABI]) -> Result<Vec<Self>, String> {
+        abi.iter()
+            .filter(|entry| entry.ty == "event")
+            .map(|abi| {
+                let fields = abi
+                    .inputs
+                    .iter()
+                    .flatten()
+                    .map(|param| Ok((param, ParamType::new(param)?)))
+                    .collect::<Result<_, String>>()?;
+
+                Ok(EventType { abi, fields })
+            })
+            .collect()
+    }
+
+    fn anonymous(&self) -> bool {
+        self.abi.anonymous.unwrap_or(false)
+    }
+
+    /// The hash of the event signature, which is the first topic
+    fn topic(&self) -> [u8; 32] {
+        let signature = format!(
+            "{}({})",
+            self.abi.name,
+            self.fields
+                .iter()
+                .map(|(_, ty)| ty.signature())
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let mut hash = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(signature.as_bytes());
+        hasher.finalize(&mut hash);
+
+        hash
+    }
+
+    fn decoded(&self, values: Vec<Value>) -> DecodedEvent {
+        DecodedEvent {
+            name: self.abi.name.clone(),
+            fields: self
+                .fields
+                .iter()
+                .map(|(param, _)| param.name.clone())
+                .zip(values)
+                .collect(),
+        }
+    }
+
+    /// Decode the fields from a buffer with all the fields, in the SCALE or Borsh encoding
+    fn decode_packed(&self, mut reader: PackedReader) -> Result<DecodedEvent, String> {
+        let values = self
+            .fields
+            .iter()
+            .map(|(_, ty)| reader.value(ty))
+            .collect::<Result<_, _>>()?;
+
+        if !reader.data.is_empty() {
+            return Err(format!(
+                "{} bytes left after decoding event '{}'",
+                reader.data.len(),
+                self.abi.name
+            ));
+        }
+
+        Ok(self.decoded(values))
+    }
+}
+
+/// Decode an event with the Ethereum ABI encoding, as emitted on EVM and Stylus. Events which are
+/// not anonymous are found by their first topic; for anonymous events, the first one which
+/// decodes is returned.
+pub fn decode_event(abi: &[ABI], topics: &[[u8; 32]], data: &[u8]) -> Result<DecodedEvent, String> {
+    let events = EventType::all(abi)?;
+
+    if let Some(topic) = topics.first() {
+        if let Some(event) = events
+            .iter()
+            .find(|event| !event.anonymous() && event.topic() == *topic)
+        {
+            return decode_ethereum(event, &topics[1..], data);
+        }
+    }
+
+    events
+        .iter()
+        .filter(|event| event.anonymous())
+        .find_map(|event| decode_ethereum(event, topics, data).ok())
+        .ok_or_else(|| "event not found in the ABI".to_string())
+}
+
+fn decode_ethereum(
+    event: &EventType,
+    topics: &[[u8; 32]],
+    data: &[u8],
+) -> Result<DecodedEvent, String> {
+    let indexed = event
+        .fields
+        .iter()
+        .filter(|(param, _)| param.indexed)
+        .count();
+
+    if indexed != topics.len() {
+        return Err(format!(
+            "event '{}' has {indexed} indexed fields, but there are {} topics",
+            event.abi.name,
+            topics.len()
+        ));
+    }
+
+    let types: Vec<ParamType> = event
+        .fields
+        .iter()
+        .filter(|(param, _)| !param.indexed)
+        .map(|(_, ty)| ty.clone())
+        .collect();
+
+    let mut values = decode_ethereum_tuple(&types, data)?.into_iter();
+    let mut topics = topics.iter();
+
+    let values = event
+        .fields
+        .iter()
+        .map(|(param, ty)| {
+            if !param.indexed {
+                return Ok(values.next().unwrap());
+            }
+
+            let topic = topics.next().unwrap();
+
+            if matches!(
+                ty,
+                ParamType::Array(..) | ParamType::Tuple(..) | ParamType::Bytes | ParamType::String
+            ) {
+                Ok(Value::Hash(topic.to_vec()))
+            } else {
+                decode_ethereum_value(ty, topic)
+            }
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok(event.decoded(values))
+}
+
+/// Decode the head and tail encoding of the Ethereum ABI
+fn decode_ethereum_tuple(types: &[ParamType], data: &[u8]) -> Result<Vec<Value>, String> {
+    let mut offset = 0;
+
+    types
+        .iter()
+        .map(|ty| {
+            let value = if ty.is_static() {
+                decode_ethereum_value(ty, data.get(offset..).ok_or_else(truncated)?)?
+            } else {
+                let start = word(data, offset)?;
+
+                decode_ethereum_value(ty, data.get(start..).ok_or_else(truncated)?)?
+            };
+
+            offset += ty.head_size();
+
+            Ok(value)
+        })
+        .collect()
+}
+
+fn decode_ethereum_value(ty: &ParamType, data: &[u8]) -> Result<Value, String> {
+    let bytes = |range: std::ops::Range<usize>| {
+        data.get(range)
+            .map(|bytes| bytes.to_vec())
+            .ok_or_else(truncated)
+    };
+
+    Ok(match ty {
+        ParamType::Bool => Value::Bool(word(data, 0)? != 0),
+        ParamType::Int { signed: false, .. } => {
+            Value::Int(BigInt::from_bytes_be(Sign::Plus, &bytes(0..32)?))
+        }
+        ParamType::Int { signed: true, .. } => {
+            Value::Int(BigInt::from_signed_bytes_be(&bytes(0..32)?))
+        }
+        ParamType::Address => Value::Address(bytes(12..32)?),
+        ParamType::FixedBytes(len) => Value::FixedBytes(bytes(0..*len)?),
+        ParamType::Function => Value::Function(bytes(0..24)?),
+        ParamType::Bytes => Value::Bytes(bytes(32..32 + word(data, 0)?)?),
+        ParamType::String => Value::String(
+            String::from_utf8(bytes(32..32 + word(data, 0)?)?)
+                .map_err(|_| "string is not valid utf-8".to_string())?,
+        ),
+        ParamType::Array(elem, Some(len)) => {
+            Value::Array(decode_ethereum_tuple(&vec![*elem.clone(); *len], data)?)
+        }
+        ParamType::Array(elem, None) => {
+            let len = word(data, 0)?;
+
+            // each element takes at least a word
+            if len > data.len() / 32 {
+                return Err(truncated());
+            }
+
+            Value::Array(decode_ethereum_tuple(
+                &vec![*elem.clone(); len],
+                &data[32..],
+            )?)
+        }
+        ParamType::Tuple(tys) => Value::Tuple(decode_ethereum_tuple(tys, data)?),
+    })
+}
+
+/// A word of the Ethereum ABI encoding, as an offset or length
+fn word(data: &[u8], offset: usize) -> Result<usize, String> {
+    let word = data.get(offset..offset + 32).ok_or_else(truncated)?;
+
+    if word[..24].iter().any(|byte| *byte != 0) {
+        return Err("offset or length is too large".into());
+    }
+
+    Ok(u64::from_be_bytes(word[24..].try_into().unwrap()) as usize)
+}
+
+fn truncated() -> String {
+    "event data is truncated".into()
+}
+
+/// Decode an event emitted on Polkadot, from its data. The topics are not needed, since the data
+/// has all the fields. Addresses are `address_length` bytes long.
+pub fn decode_polkadot_event(
+    abi: &[ABI],
+    data: &[u8],
+    address_length: usize,
+) -> Result<DecodedEvent, String> {
+    let events = EventType::all(abi)?;
+
+    let (index, data) = data.split_first().ok_or_else(truncated)?;
+
+    let event = events
+        .get(*index as usize)
+        .ok_or_else(|| format!("event {index} not found in the ABI"))?;
+
+    event.decode_packed(PackedReader {
+        data,
+        address_length,
+        selector_length: 4,
+        encoding: Encoding::Scale,
+    })
+}
+
+/// Decode an event emitted on Solana, from the data which was logged
+pub fn decode_solana_event(abi: &[ABI], data: &[u8]) -> Result<DecodedEvent, String> {
+    let events = EventType::all(abi)?;
+
+    if data.len() < 8 {
+        return Err(truncated());
+    }
+
+    let (discriminator, data) = data.split_at(8);
+
+    let event = events
+        .iter()
+        .find(|event| event_discriminator(&event.abi.name) == discriminator)
+        .ok_or_else(|| {
+            format!(
+                "event with discriminator {} not found in the ABI",
+                hex::encode(discriminator)
+            )
+        })?;
+
+    event.decode_packed(PackedReader {
+        data,
+        address_length: 32,
+        selector_length: 8,
+        encoding: Encoding::Borsh,
+    })
+}
+
+/// Decode an event from a line of the Solana program logs. Returns `None` if the line is not
+/// `Program data: <base64>`.
+pub fn decode_solana_log(abi: &[ABI], line: &str) -> Option<Result<DecodedEvent, String>> {
+    let encoded = line.trim().strip_prefix("Program data: ")?;
+
+    Some(
+        STANDARD
+            .decode(encoded)
+            .map_err(|err| format!("invalid base64: {err}"))
+            .and_then(|data| decode_solana_event(abi, &data)),
+    )
+}
+
+enum Encoding {
+    /// Lengths are compact integers
+    Scale,
+    /// Lengths are 32 bit integers
+    Borsh,
+}
+
+/// Reads the fields of an event in order. Integers are little endian, and as wide as the next
+/// power of two.
+struct PackedReader<'a> {
+    data: &'a [u8],
+    address_length: usize,
+    selector_length: usize,
+    encoding: Encoding,
+}
+
+impl PackedReader<'_> {
+    fn bytes(&mut self, len: usize) -> Result<Vec<u8>, String> {
+        if len > self.data.len() {
+            return Err(truncated());
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+
+        Ok(bytes.to_vec())
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        match self.encoding {
+            Encoding::Scale => <Compact<u32>>::decode(&mut self.data)
+                .map(|len| len.0 as usize)
+                .map_err(|_| "invalid length".to_string()),
+            Encoding::Borsh => {
+                let bytes = self.bytes(4)?;
+
+                Ok(u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+            }
+        }
+    }
+
+    fn value(&mut self, ty: &ParamType) -> Result<Value, String> {
+        Ok(match ty {
+            ParamType::Bool => Value::Bool(self.bytes(1)?[0] != 0),
+            ParamType::Int { signed, bits } => {
+                let bytes = self.bytes(bits.next_power_of_two() / 8)?;
+
+                Value::Int(if *signed {
+                    BigInt::from_signed_bytes_le(&bytes)
+                } else {
+                    BigInt::from_bytes_le(Sign::Plus, &bytes)
+                })
+            }
+            ParamType::Address => Value::Address(self.bytes(self.address_length)?),
+            ParamType::FixedBytes(len) => Value::FixedBytes(self.bytes(*len)?),
+            ParamType::Function => {
+                Value::Function(self.bytes(self.address_length + self.selector_length)?)
+            }
+            ParamType::Bytes => {
+                let len = self.len()?;
+
+                Value::Bytes(self.bytes(len)?)
+            }
+            ParamType::String => {
+                let len = self.len()?;
+
+                Value::String(
+                    String::from_utf8(self.bytes(len)?)
+                        .map_err(|_| "string is not valid utf-8".to_string())?,
+                )
+            }
+            ParamType::Array(elem, len) => {
+                let len = match len {
+                    Some(len) => *len,
+                    None => self.len()?,
+                };
+
+                // each element takes at least a byte
+                if len > self.data.len() {
+                    return Err(truncated());
+                }
+
+                Value::Array(
+                    (0..len)
+                        .map(|_| self.value(elem))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            ParamType::Tuple(tys) => Value::Tuple(
+                tys.iter()
+                    .map(|ty| self.value(ty))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
@@ -4,9 +4,19 @@ use crate::sema::ast::Namespace;
 use crate::Target;
 
 pub mod anchor;
+pub mod bundle;
+pub mod constructor;
 pub mod ethereum;
+pub mod events;
 pub mod polkadot;
+pub mod selectors;
+pub mod storage_layout;
 mod tests;
+pub mod upgrade;
+
+pub use events::{
+    decode_event, decode_polkadot_event, decode_solana_event, decode_solana_log, DecodedEvent,
+};
 
 pub fn generate_abi(
     contract_no: usize,
@@ -15,7 +15,8 @@ use num_bigint::BigInt;
 use num_traits::ToPrimitive;
 use scale_info::{
     form::PortableForm, Field, Path, PortableRegistryBuilder, Type, TypeDef, TypeDefArray,
-    TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple, TypeDefVariant, Variant,
+    TypeDefCompact, TypeDefComposite, TypeDefPrimitive, TypeDefSequence, TypeDefTuple,
+    TypeDefVariant, TypeParameter, Variant,
 };
 use semver::Version;
 use solang_parser::pt;
@@ -23,7 +24,7 @@ use solang_parser::pt;
 use crate::{
     codegen::revert::{SolidityError, ERROR_SELECTOR, PANIC_SELECTOR},
     sema::{
-        ast::{self, ArrayLength, EventDecl, Function},
+        ast::{self, ArrayLength, EventDecl, Function, Parameter, ScaleAnnotation},
         tags::render,
     },
 };
@@ -285,6 +286,64 @@ fn type_to_storage_layout(
 }
 
 /// Generate `InkProject` from `ast::Type` and `ast::Namespace`
+/// Resolve the type of a parameter, which is `Compact<T>` if annotated with `@compact`
+fn resolve_param(
+    param: &Parameter<ast::Type>,
+    ns: &ast::Namespace,
+    registry: &mut PortableRegistryBuilder,
+) -> u32 {
+    let ty = resolve_ast(&param.ty, ns, registry);
+
+    if param.scale_annotation() == Some(ScaleAnnotation::Compact) {
+        let compact = TypeDef::Compact(TypeDefCompact::new(ty.into()));
+        registry.register_type(Type::new(Path::default(), vec![], compact, vec![]))
+    } else {
+        ty
+    }
+}
+
+/// Register `Option<T>` or `Result<T, ()>` for return values annotated with `@option` or `@result`
+fn optional_ty(
+    annotation: ScaleAnnotation,
+    value: u32,
+    registry: &mut PortableRegistryBuilder,
+) -> u32 {
+    let variant = |name: &str, fields: Vec<u32>, index: u8| Variant {
+        name: name.into(),
+        fields: fields
+            .into_iter()
+            .map(|ty| Field::new(None, ty.into(), None, vec![]))
+            .collect(),
+        index,
+        docs: Default::default(),
+    };
+
+    let (path, type_params, variants) = match annotation {
+        ScaleAnnotation::Option => (
+            path!("Option"),
+            vec![TypeParameter::new_portable("T".into(), Some(value.into()))],
+            vec![variant("None", vec![], 0), variant("Some", vec![value], 1)],
+        ),
+        ScaleAnnotation::Result => {
+            let unit = TypeDef::Tuple(TypeDefTuple::new_portable(vec![]));
+            let unit = registry.register_type(Type::new(Path::default(), vec![], unit, vec![]));
+
+            (
+                path!("Result"),
+                vec![
+                    TypeParameter::new_portable("T".into(), Some(value.into())),
+                    TypeParameter::new_portable("E".into(), Some(unit.into())),
+                ],
+                vec![variant("Ok", vec![value], 0), variant("Err", vec![unit], 1)],
+            )
+        }
+        ScaleAnnotation::Compact => unreachable!(),
+    };
+
+    let variant = TypeDef::Variant(TypeDefVariant::new(variants));
+    registry.register_type(Type::new(path, type_params, variant, vec![]))
+}
+
 pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
     let mut registry = PortableRegistryBuilder::new();
 
@@ -318,7 +377,7 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
             .params
             .iter()
             .map(|p| {
-                let ty = resolve_ast(&p.ty, ns, &mut registry);
+                let ty = resolve_param(p, ns, &mut registry);
 
                 let path = registry.get(ty).unwrap().path.clone();
                 let spec = TypeSpec::new(ty.into(), path);
@@ -371,10 +430,20 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
             f.mutability,
             ast::Mutability::Payable(_) | ast::Mutability::Nonpayable(_)
         );
-        let ret_spec: Option<TypeSpec<PortableForm>> = match f.returns.len() {
-            0 => None,
-            1 => {
-                let ty = resolve_ast(&f.returns[0].ty, ns, &mut registry);
+        let optional = f.returns.first().and_then(|r| {
+            r.scale_annotation()
+                .filter(|annotation| *annotation != ScaleAnnotation::Compact)
+        });
+        let ret_spec: Option<TypeSpec<PortableForm>> = match (f.returns.len(), optional) {
+            (0, _) => None,
+            (2, Some(annotation)) => {
+                let value = resolve_param(&f.returns[1], ns, &mut registry);
+                let ty = optional_ty(annotation, value, &mut registry);
+                let path = registry.get(ty).unwrap().path.clone();
+                Some(TypeSpec::new(ty.into(), path))
+            }
+            (1, _) => {
+                let ty = resolve_param(&f.returns[0], ns, &mut registry);
                 let path = registry.get(ty).unwrap().path.clone();
                 Some(TypeSpec::new(ty.into(), path))
             }
@@ -383,7 +452,7 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
                     .returns
                     .iter()
                     .map(|r_p| {
-                        let ty = resolve_ast(&r_p.ty, ns, &mut registry);
+                        let ty = resolve_param(r_p, ns, &mut registry);
 
                         ty.into()
                     })
@@ -412,7 +481,7 @@ pub fn gen_project(contract_no: usize, ns: &ast::Namespace) -> InkProject {
             .params
             .iter()
             .map(|p| {
-                let ty = resolve_ast(&p.ty, ns, &mut registry);
+                let ty = resolve_param(p, ns, &mut registry);
                 let path = registry.get(ty).unwrap().path.clone();
                 let spec = TypeSpec::new(ty.into(), path);
 
@@ -0,0 +1,146 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// selectors of the external functions, events and errors of a contract, for building routers
+// and decoding on-chain data
+use crate::codegen::events::event_selector;
+use crate::sema::ast::{Function, Namespace};
+use crate::Target;
+use serde::{Deserialize, Serialize};
+use solang_parser::pt;
+use tiny_keccak::{Hasher, Keccak};
+
+#[derive(Serialize)]
+pub struct SelectorList {
+    pub contract: String,
+    pub target: String,
+    pub functions: Vec<Selector>,
+    pub events: Vec<Selector>,
+    pub errors: Vec<Selector>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Selector {
+    pub name: String,
+    pub signature: String,
+    /// The selector, discriminator, or first topic, in hex. This is omitted for anonymous events
+    /// and on Soroban, where functions are called by name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+}
+
+/// List the selectors of everything a contract exposes. On Solana, these are the Anchor
+/// discriminators. Constructors are only listed on targets where they have a selector.
+pub fn gen_selectors(contract_no: usize, ns: &Namespace) -> SelectorList {
+    let contract = &ns.contracts[contract_no];
+    let has_selectors = ns.target != Target::Soroban;
+    let constructor_selectors =
+        !matches!(ns.target, Target::EVM | Target::Stylus | Target::Soroban);
+
+    let selector = |func: &Function| {
+        // constructors are called new, unless they have a name
+        let (name, signature) = if func.is_constructor() && func.id.name.is_empty() {
+            ("new".to_string(), format!("new{}", func.signature))
+        } else {
+            (func.id.name.clone(), func.signature.clone())
+        };
+
+        Selector {
+            name,
+            signature,
+            selector: has_selectors.then(|| hex_selector(&func.selector(ns, &contract_no))),
+        }
+    };
+
+    let mut functions: Vec<Selector> = Vec::new();
+
+    if constructor_selectors {
+        let mut constructors = contract
+            .functions
+            .iter()
+            .filter(|function_no| {
+                ns.functions[**function_no].is_constructor()
+                    && ns.function_externally_callable(contract_no, Some(**function_no))
+            })
+            .peekable();
+
+        if constructors.peek().is_none() && contract.is_concrete() {
+            functions.push(selector(&ns.default_constructor(contract_no)));
+        } else {
+            functions.extend(constructors.map(|function_no| selector(&ns.functions[*function_no])));
+        }
+    }
+
+    functions.extend(
+        contract
+            .all_functions
+            .keys()
+            .filter(|function_no| ns.function_externally_callable(contract_no, Some(**function_no)))
+            .map(|function_no| &ns.functions[*function_no])
+            // fallback and receive functions do not have a selector
+            .filter(|func| func.ty == pt::FunctionTy::Function)
+            .map(selector),
+    );
+
+    let events = contract
+        .emits_events
+        .iter()
+        .map(|event_no| {
+            let event = &ns.events[*event_no];
+
+            let selector = match ns.target {
+                Target::Soroban => None,
+                Target::Solana => Some(event_selector(*event_no, contract_no, ns)),
+                _ if event.anonymous => None,
+                // the emitter for Polkadot is also used for EVM, but Ethereum tooling expects
+                // the keccak256 hash of the signature
+                Target::EVM => Some(keccak256(&event.signature).to_vec()),
+                _ => Some(event_selector(*event_no, contract_no, ns)),
+            };
+
+            Selector {
+                name: event.id.name.clone(),
+                signature: event.signature.clone(),
+                selector: selector.map(|selector| hex_selector(&selector)),
+            }
+        })
+        .collect();
+
+    let bases = ns.contract_bases(contract_no);
+
+    let errors = ns
+        .errors
+        .iter()
+        // errors declared outside of a contract are only listed if they are used
+        .filter(|error| match error.contract {
+            Some(error_contract_no) => bases.contains(&error_contract_no),
+            None => error.used,
+        })
+        .map(|error| Selector {
+            name: error.name.clone(),
+            signature: ns.signature(&error.name, &error.fields),
+            selector: has_selectors.then(|| hex_selector(&error.selector(ns))),
+        })
+        .collect();
+
+    SelectorList {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        functions,
+        events,
+        errors,
+    }
+}
+
+pub(super) fn keccak256(data: &str) -> [u8; 32] {
+    let mut res = [0u8; 32];
+
+    let mut hasher = Keccak::v256();
+    hasher.update(data.as_bytes());
+    hasher.finalize(&mut res);
+
+    res
+}
+
+pub(super) fn hex_selector(selector: &[u8]) -> String {
+    format!("0x{}", hex::encode(selector))
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// storage layout of a contract, for upgrade tooling and explorers
+use crate::sema::ast::{ArrayLength, Mapping, Namespace, PackedSlot, Type};
+use crate::Target;
+use num_bigint::BigInt;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize, Deserialize)]
+pub struct StorageLayout {
+    pub contract: String,
+    pub target: String,
+    /// The number of bytes of account data used by the fixed fields, only on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+    pub storage: Vec<StorageItem>,
+    pub types: BTreeMap<String, StorageType>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageItem {
+    pub label: String,
+    /// The contract which declares the variable. For struct members, this is omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+    /// The storage key, on targets with key-value storage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slot: Option<String>,
+    /// The byte offset in the account data on Solana, or in the storage slot if the value
+    /// shares its slot with other values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageType {
+    pub encoding: String,
+    /// Number of bytes on Solana, and number of storage slots on other targets
+    pub length: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<StorageItem>>,
+}
+
+/// Generate the storage layout of a contract. The contract must have been through codegen,
+/// since that is where the layout is determined.
+pub fn gen_storage_layout(contract_no: usize, ns: &Namespace) -> StorageLayout {
+    let contract = &ns.contracts[contract_no];
+    let mut types = BTreeMap::new();
+
+    let storage = contract
+        .layout
+        .iter()
+        .map(|layout| {
+            let var = &ns.contracts[layout.contract_no].variables[layout.var_no];
+
+            add_type(&layout.ty, ns, &mut types);
+
+            storage_item(
+                &var.name,
+                Some(ns.contracts[layout.contract_no].id.name.clone()),
+                &layout.slot,
+                layout.packed,
+                &layout.ty,
+                ns,
+            )
+        })
+        .collect();
+
+    StorageLayout {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        size: if ns.target == Target::Solana {
+            Some(contract.fixed_layout_size.to_string())
+        } else {
+            None
+        },
+        storage,
+        types,
+    }
+}
+
+fn storage_item(
+    label: &str,
+    contract: Option<String>,
+    position: &BigInt,
+    packed: Option<PackedSlot>,
+    ty: &Type,
+    ns: &Namespace,
+) -> StorageItem {
+    let (slot, offset) = if ns.target == Target::Solana {
+        (None, Some(position.to_string()))
+    } else {
+        (
+            Some(position.to_string()),
+            packed.map(|packed| packed.offset.to_string()),
+        )
+    };
+
+    StorageItem {
+        label: label.to_owned(),
+        contract,
+        slot,
+        offset,
+        ty: ty.to_string(ns),
+    }
+}
+
+/// Add the type and any types it refers to. Types refer to each other by name, so recursive
+/// structs are only visited once.
+fn add_type(ty: &Type, ns: &Namespace, types: &mut BTreeMap<String, StorageType>) {
+    let name = ty.to_string(ns);
+
+    if types.contains_key(&name) {
+        return;
+    }
+
+    let mut entry = StorageType {
+        encoding: "inplace".into(),
+        length: ty.storage_slots(ns).to_string(),
+        key: None,
+        value: None,
+        base: None,
+        members: None,
+    };
+    let mut refers = Vec::new();
+
+    match ty {
+        Type::Mapping(Mapping { key, value, .. }) => {
+            entry.encoding = "mapping".into();
+            entry.key = Some(key.to_string(ns));
+            entry.value = Some(value.to_string(ns));
+            refers.push(key.as_ref().clone());
+            refers.push(value.as_ref().clone());
+        }
+        Type::Array(_, dims) => {
+            let elem = ty.array_elem();
+
+            if dims.last() == Some(&ArrayLength::Dynamic) {
+                entry.encoding = "dynamic_array".into();
+            } else if ns.target == Target::Solana && ty.is_sparse_solana(ns) {
+                entry.encoding = "sparse_array".into();
+            }
+
+            entry.base = Some(elem.to_string(ns));
+            refers.push(elem);
+        }
+        Type::String | Type::DynamicBytes => {
+            entry.encoding = "bytes".into();
+        }
+        Type::Struct(struct_ty) => {
+            let def = struct_ty.definition(ns);
+            let (packing, _) = def.storage_packing(ns);
+
+            entry.members = Some(
+                def.fields
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, field)| !field.infinite_size)
+                    .map(|(field_no, field)| {
+                        let (position, packed) = if ns.target == Target::Solana {
+                            (def.storage_offsets[field_no].clone(), None)
+                        } else {
+                            packing[field_no].clone()
+                        };
+
+                        refers.push(field.ty.clone());
+
+                        storage_item(field.name_as_str(), None, &position, packed, &field.ty, ns)
+                    })
+                    .collect(),
+            );
+        }
+        _ => (),
+    }
+
+    types.insert(name, entry);
+
+    for ty in refers {
+        add_type(&ty, ns, types);
+    }
+}
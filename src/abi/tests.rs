@@ -286,7 +286,7 @@ fn instructions_and_types() {
         Some(IdlType::Defined("multipleReturns_returns".to_string()))
     );
 
-    assert!(idl.accounts.is_empty());
+    assert_eq!(idl.accounts.len(), 1);
 
     assert_eq!(idl.types.len(), 1);
 
@@ -385,7 +385,7 @@ contract caller {
     );
     assert!(idl.instructions[1].returns.is_none());
 
-    assert!(idl.accounts.is_empty());
+    assert_eq!(idl.accounts.len(), 1);
 
     assert_eq!(idl.types.len(), 1);
 
@@ -520,7 +520,7 @@ fn types() {
         ]
     );
     assert!(idl.instructions[1].returns.is_none());
-    assert!(idl.accounts.is_empty());
+    assert_eq!(idl.accounts.len(), 1);
     assert!(idl.types.is_empty());
     assert_eq!(
         idl.events.unwrap(),
@@ -592,7 +592,18 @@ fn constructor() {
     assert!(idl.instructions[1].args.is_empty());
     assert_eq!(idl.instructions[1].returns, Some(IdlType::U64));
 
-    assert!(idl.accounts.is_empty());
+    assert_eq!(
+        idl.accounts,
+        vec![IdlTypeDefinition {
+            name: "caller".to_string(),
+            docs: Some(vec![
+                "Data account of contract caller; see the storage layout for its fields"
+                    .to_string()
+            ]),
+            ty: IdlTypeDefinitionTy::Struct { fields: vec![] },
+            generics: None,
+        }]
+    );
     assert!(idl.types.is_empty());
     assert!(idl.events.is_none());
     assert!(idl.errors.is_none());
@@ -2128,3 +2139,869 @@ fn idl_account(name: &str, is_mut: bool, is_signer: bool) -> IdlAccountItem {
         relations: vec![],
     })
 }
+
+#[test]
+fn solc_abi() {
+    let src = r#"
+error Unauthorized(address caller);
+error NeverUsed();
+
+abstract contract Base {
+    error InsufficientBalance(uint64 available, uint64 required);
+}
+
+contract Token is Base {
+    event Transfer(address indexed from, uint64 amount);
+
+    function transfer(address to, uint64 amount) public returns (bool) {
+        if (amount == 0) {
+            revert Unauthorized(to);
+        }
+        emit Transfer(to, amount);
+        return true;
+    }
+
+    function balance() public view returns (uint64) {
+        return 1;
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+    let abi = crate::abi::ethereum::gen_abi(1, &ns);
+
+    assert_eq!(
+        serde_json::to_value(abi).unwrap(),
+        json!([
+            {
+                "name": "transfer",
+                "type": "function",
+                "inputs": [
+                    { "name": "to", "type": "address", "internalType": "address" },
+                    { "name": "amount", "type": "uint64", "internalType": "uint64" }
+                ],
+                "outputs": [
+                    { "name": "", "type": "bool", "internalType": "bool" }
+                ],
+                "stateMutability": "nonpayable"
+            },
+            {
+                "name": "balance",
+                "type": "function",
+                "inputs": [],
+                "outputs": [
+                    { "name": "", "type": "uint64", "internalType": "uint64" }
+                ],
+                "stateMutability": "view"
+            },
+            {
+                "name": "Transfer",
+                "type": "event",
+                "inputs": [
+                    { "name": "from", "type": "address", "internalType": "address", "indexed": true },
+                    { "name": "amount", "type": "uint64", "internalType": "uint64" }
+                ],
+                "anonymous": false
+            },
+            {
+                "name": "Unauthorized",
+                "type": "error",
+                "inputs": [
+                    { "name": "caller", "type": "address", "internalType": "address" }
+                ]
+            },
+            {
+                "name": "InsufficientBalance",
+                "type": "error",
+                "inputs": [
+                    { "name": "available", "type": "uint64", "internalType": "uint64" },
+                    { "name": "required", "type": "uint64", "internalType": "uint64" }
+                ]
+            }
+        ])
+    );
+}
+
+#[test]
+fn storage_layout() {
+    let src = r#"
+struct Point {
+    uint64 x;
+    bool y;
+}
+
+abstract contract Base {
+    uint64 count;
+}
+
+contract Storage is Base {
+    mapping(address => uint128) balances;
+    Point[] points;
+    string name;
+}
+    "#;
+
+    let mut ns = generate_namespace(src);
+    codegen(&mut ns, &Options::default());
+    let layout = crate::abi::storage_layout::gen_storage_layout(1, &ns);
+
+    assert_eq!(
+        serde_json::to_value(layout).unwrap(),
+        json!({
+            "contract": "Storage",
+            "target": "Solana",
+            "size": "1036",
+            "storage": [
+                { "label": "count", "contract": "Base", "offset": "16", "type": "uint64" },
+                {
+                    "label": "balances",
+                    "contract": "Storage",
+                    "offset": "24",
+                    "type": "mapping(address => uint128)"
+                },
+                { "label": "points", "contract": "Storage", "offset": "1028", "type": "struct Point[]" },
+                { "label": "name", "contract": "Storage", "offset": "1032", "type": "string" }
+            ],
+            "types": {
+                "address": { "encoding": "inplace", "length": "32" },
+                "bool": { "encoding": "inplace", "length": "1" },
+                "mapping(address => uint128)": {
+                    "encoding": "mapping",
+                    "length": "1004",
+                    "key": "address",
+                    "value": "uint128"
+                },
+                "string": { "encoding": "bytes", "length": "4" },
+                "struct Point": {
+                    "encoding": "inplace",
+                    "length": "16",
+                    "members": [
+                        { "label": "x", "offset": "0", "type": "uint64" },
+                        { "label": "y", "offset": "8", "type": "bool" }
+                    ]
+                },
+                "struct Point[]": {
+                    "encoding": "dynamic_array",
+                    "length": "4",
+                    "base": "struct Point"
+                },
+                "uint128": { "encoding": "inplace", "length": "16" },
+                "uint64": { "encoding": "inplace", "length": "8" }
+            }
+        })
+    );
+}
+
+#[test]
+fn storage_layout_packed() {
+    let src = r#"
+struct Point {
+    uint64 x;
+    bool y;
+    uint256 z;
+}
+
+contract Storage {
+    bool paused;
+    uint128 total;
+    bytes4 tag;
+    uint256 supply;
+    Point origin;
+    uint8 public last;
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+    let mut ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+    codegen(&mut ns, &Options::default());
+    let layout = crate::abi::storage_layout::gen_storage_layout(0, &ns);
+
+    assert_eq!(
+        serde_json::to_value(layout).unwrap(),
+        json!({
+            "contract": "Storage",
+            "target": "Polkadot",
+            "storage": [
+                { "label": "paused", "contract": "Storage", "slot": "0", "offset": "0", "type": "bool" },
+                { "label": "total", "contract": "Storage", "slot": "0", "offset": "1", "type": "uint128" },
+                { "label": "tag", "contract": "Storage", "slot": "0", "offset": "17", "type": "bytes4" },
+                { "label": "supply", "contract": "Storage", "slot": "1", "type": "uint256" },
+                { "label": "origin", "contract": "Storage", "slot": "2", "type": "struct Point" },
+                { "label": "last", "contract": "Storage", "slot": "4", "type": "uint8" }
+            ],
+            "types": {
+                "bool": { "encoding": "inplace", "length": "1" },
+                "bytes4": { "encoding": "inplace", "length": "1" },
+                "struct Point": {
+                    "encoding": "inplace",
+                    "length": "2",
+                    "members": [
+                        { "label": "x", "slot": "0", "offset": "0", "type": "uint64" },
+                        { "label": "y", "slot": "0", "offset": "8", "type": "bool" },
+                        { "label": "z", "slot": "1", "type": "uint256" }
+                    ]
+                },
+                "uint128": { "encoding": "inplace", "length": "1" },
+                "uint256": { "encoding": "inplace", "length": "1" },
+                "uint64": { "encoding": "inplace", "length": "1" },
+                "uint8": { "encoding": "inplace", "length": "1" }
+            }
+        })
+    );
+}
+
+#[test]
+fn selectors() {
+    let src = r#"
+error Unauthorized(address caller);
+
+contract Token {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Anonymous(uint256 value) anonymous;
+
+    function transfer(address to, uint256 amount) public returns (bool) {
+        if (amount == 0) {
+            revert Unauthorized(to);
+        }
+        emit Transfer(msg.sender, to, amount);
+        emit Anonymous(amount);
+        return true;
+    }
+
+    function helper() internal {}
+
+    fallback() external {}
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::EVM);
+    let selectors = crate::abi::selectors::gen_selectors(0, &ns);
+
+    assert_eq!(
+        serde_json::to_value(selectors).unwrap(),
+        json!({
+            "contract": "Token",
+            "target": "EVM",
+            "functions": [
+                { "name": "transfer", "signature": "transfer(address,uint256)", "selector": "0xa9059cbb" }
+            ],
+            "events": [
+                {
+                    "name": "Transfer",
+                    "signature": "Transfer(address,address,uint256)",
+                    "selector": "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+                },
+                { "name": "Anonymous", "signature": "Anonymous(uint256)" }
+            ],
+            "errors": [
+                { "name": "Unauthorized", "signature": "Unauthorized(address)", "selector": "0x8e4a23d6" }
+            ]
+        })
+    );
+
+    let src = r#"
+contract Counter {
+    event Incremented(uint64 count);
+
+    uint64 count;
+
+    constructor(uint64 start) {
+        count = start;
+    }
+
+    function incrementBy(uint64 value) public {
+        count += value;
+        emit Incremented(count);
+    }
+}
+    "#;
+
+    let ns = generate_namespace(src);
+    let selectors = crate::abi::selectors::gen_selectors(0, &ns);
+
+    assert_eq!(
+        serde_json::to_value(selectors).unwrap(),
+        json!({
+            "contract": "Counter",
+            "target": "Solana",
+            "functions": [
+                { "name": "new", "signature": "new(uint64)", "selector": "0x872ccdc6190148bc" },
+                { "name": "incrementBy", "signature": "incrementBy(uint64)", "selector": "0x67527c37e732928a" }
+            ],
+            "events": [
+                { "name": "Incremented", "signature": "Incremented(uint64)", "selector": "0x5ccf77cc47cd6c0f" }
+            ],
+            "errors": []
+        })
+    );
+}
+
+#[test]
+fn bundle() {
+    let src = r#"
+/// @title Counter
+/// @author Solang
+/// @notice Counts things
+contract Counter {
+    uint64 count;
+
+    /// @notice Add to the count
+    /// @dev Wraps on overflow
+    /// @param value The amount to add
+    /// @return The new count
+    function incrementBy(uint64 value) public returns (uint64) {
+        unchecked {
+            count += value;
+        }
+        return count;
+    }
+}
+    "#;
+
+    let mut ns = generate_namespace(src);
+    codegen(&mut ns, &Options::default());
+
+    let bundle = crate::abi::bundle::gen_bundle(0, &ns, &[0xde, 0xad], "{\"version\": 1}");
+    let bundle = serde_json::to_value(bundle).unwrap();
+
+    assert_eq!(bundle["_format"], "solang-bundle-1");
+    assert_eq!(bundle["contractName"], "Counter");
+    assert_eq!(bundle["sourceName"], "test.sol");
+    assert_eq!(bundle["target"], "Solana");
+    assert_eq!(bundle["bytecode"], "0xdead");
+    assert_eq!(bundle["metadata"], json!({ "version": 1 }));
+    assert_eq!(
+        bundle["metadataHash"],
+        "4cf816c616d3c367cdda7049dabad10faf0fb2c45c30eb43265fd8b8c820d469"
+    );
+    assert_eq!(bundle["storageLayout"]["contract"], "Counter");
+    assert_eq!(bundle["linkedContracts"], json!([]));
+    assert_eq!(bundle["abi"][0]["name"], "incrementBy");
+
+    assert_eq!(
+        bundle["sourceMap"],
+        json!([
+            {
+                "name": "incrementBy",
+                "signature": "incrementBy(uint64)",
+                "selector": "0x67527c37e732928a",
+                "file": "test.sol",
+                "line": 12,
+                "column": 5,
+                "end_line": 17,
+                "end_column": 6
+            }
+        ])
+    );
+
+    assert_eq!(
+        bundle["userdoc"],
+        json!({
+            "kind": "user",
+            "version": 1,
+            "notice": "Counts things",
+            "methods": {
+                "incrementBy(uint64)": { "notice": "Add to the count" }
+            }
+        })
+    );
+    assert_eq!(
+        bundle["devdoc"],
+        json!({
+            "kind": "dev",
+            "version": 1,
+            "title": "Counter",
+            "author": "Solang",
+            "methods": {
+                "incrementBy(uint64)": {
+                    "details": "Wraps on overflow",
+                    "params": { "value": "The amount to add" },
+                    "returns": { "_0": "The new count" }
+                }
+            }
+        })
+    );
+
+    // on Polkadot, the code of created contracts is included in the binary
+    let mut cache = FileResolver::default();
+    cache.set_file_contents(
+        "test.sol",
+        "contract A { function make() public { new B(); } } contract B { function f() public {} }"
+            .into(),
+    );
+    let mut ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+    codegen(&mut ns, &Options::default());
+
+    let bundle = crate::abi::bundle::gen_bundle(0, &ns, &[], "{}");
+
+    assert_eq!(bundle.linked_contracts, vec!["B".to_string()]);
+}
+
+#[test]
+fn decode_events() {
+    use crate::abi::ethereum::gen_abi;
+    use crate::abi::events::Value;
+    use crate::abi::{
+        decode_event, decode_polkadot_event, decode_solana_event, decode_solana_log, DecodedEvent,
+    };
+    use base64::{engine::general_purpose::STANDARD, Engine};
+    use num_bigint::BigInt;
+    use tiny_keccak::{Hasher, Keccak};
+
+    let src = r#"
+        contract C {
+            event Transfer(address indexed from, address indexed to, uint256 value);
+            event Note(string indexed tag, string text, int8[2] values, bool ok);
+
+            function f(address from, address to) public {
+                emit Transfer(from, to, 1000);
+                emit Note("tag", "hello", [int8(-1), 5], true);
+            }
+        }"#;
+
+    let abi = |target| {
+        let mut cache = FileResolver::default();
+        cache.set_file_contents("test.sol", src.to_string());
+        let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+        assert!(!ns.diagnostics.any_errors());
+
+        gen_abi(0, &ns)
+    };
+
+    let keccak = |data: &[u8]| {
+        let mut hash = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(data);
+        hasher.finalize(&mut hash);
+        hash
+    };
+
+    let word = |byte: u8, len: usize| {
+        let mut word = [0u8; 32];
+        word[32 - len..].fill(byte);
+        word
+    };
+
+    let note = || DecodedEvent {
+        name: "Note".into(),
+        fields: vec![
+            ("tag".into(), Value::String("tag".into())),
+            ("text".into(), Value::String("hello".into())),
+            (
+                "values".into(),
+                Value::Array(vec![Value::Int(BigInt::from(-1)), Value::Int(5.into())]),
+            ),
+            ("ok".into(), Value::Bool(true)),
+        ],
+    };
+
+    // Ethereum ABI encoding
+    let evm = abi(Target::EVM);
+
+    let mut value = [0u8; 32];
+    value[30..].copy_from_slice(&1000u16.to_be_bytes());
+
+    let transfer = decode_event(
+        &evm,
+        &[
+            keccak(b"Transfer(address,address,uint256)"),
+            word(0x11, 20),
+            word(0x22, 20),
+        ],
+        &value,
+    )
+    .unwrap();
+
+    assert_eq!(
+        transfer.to_string(),
+        format!(
+            "Transfer(from: 0x{}, to: 0x{}, value: 1000)",
+            "11".repeat(20),
+            "22".repeat(20)
+        )
+    );
+
+    let mut data = Vec::new();
+    data.extend(word(0x80, 1));
+    data.extend(word(0xff, 32));
+    data.extend(word(5, 1));
+    data.extend(word(1, 1));
+    data.extend(word(5, 1));
+    data.extend(b"hello");
+    data.extend([0u8; 27]);
+
+    let tag = keccak(b"tag");
+
+    let event = decode_event(
+        &evm,
+        &[keccak(b"Note(string,string,int8[2],bool)"), tag],
+        &data,
+    )
+    .unwrap();
+
+    let mut expected = note();
+    expected.fields[0].1 = Value::Hash(tag.to_vec());
+    assert_eq!(event, expected);
+
+    assert_eq!(
+        event.to_string(),
+        format!(
+            "Note(tag: 0x{}, text: \"hello\", values: [-1, 5], ok: true)",
+            hex::encode(tag)
+        )
+    );
+
+    assert_eq!(
+        decode_event(&evm, &[[0; 32]], &data).unwrap_err(),
+        "event not found in the ABI"
+    );
+    assert_eq!(
+        decode_event(
+            &evm,
+            &[keccak(b"Note(string,string,int8[2],bool)"), tag],
+            &data[..100]
+        )
+        .unwrap_err(),
+        "event data is truncated"
+    );
+
+    // SCALE encoding on Polkadot, starting with the index of the event
+    let polkadot = abi(Target::default_polkadot());
+
+    let data = [
+        &[1u8, 0x0c][..],
+        b"tag",
+        &[0x14],
+        b"hello",
+        &[0xff, 0x05, 0x01],
+    ]
+    .concat();
+
+    assert_eq!(decode_polkadot_event(&polkadot, &data, 32).unwrap(), note());
+    assert_eq!(
+        decode_polkadot_event(&polkadot, &[data.as_slice(), &[0]].concat(), 32).unwrap_err(),
+        "1 bytes left after decoding event 'Note'"
+    );
+
+    // Borsh encoding on Solana, starting with the discriminator of the event
+    let solana = abi(Target::Solana);
+
+    let data = [
+        &crate::abi::anchor::event_discriminator("Note")[..],
+        &[3, 0, 0, 0],
+        b"tag",
+        &[5, 0, 0, 0],
+        b"hello",
+        &[0xff, 0x05, 0x01],
+    ]
+    .concat();
+
+    assert_eq!(decode_solana_event(&solana, &data).unwrap(), note());
+    assert_eq!(
+        decode_solana_log(
+            &solana,
+            &format!("Program data: {}", STANDARD.encode(&data))
+        ),
+        Some(Ok(note()))
+    );
+    assert_eq!(decode_solana_log(&solana, "Program log: hello"), None);
+}
+
+#[test]
+fn constructor_args() {
+    use crate::abi::anchor::function_discriminator;
+    use crate::abi::constructor::{
+        encode_constructor_args, gen_constructor_args, ArgsEncoding, ConstructorArgs,
+    };
+
+    let src = r#"
+        contract C {
+            constructor(uint16 a, int8 b, string s, address addr, uint32[] v) {}
+
+            function f() public {}
+        }
+
+        contract D {
+            function f() public {}
+        }"#;
+
+    let constructor_args = |target, contract_no| {
+        let mut cache = FileResolver::default();
+        cache.set_file_contents("test.sol", src.to_string());
+        let mut ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+        assert!(!ns.diagnostics.any_errors());
+        codegen(&mut ns, &Options::default());
+
+        // the description is read back by deployment tooling
+        let json = serde_json::to_string(&gen_constructor_args(contract_no, &ns).unwrap()).unwrap();
+        serde_json::from_str::<ConstructorArgs>(&json).unwrap()
+    };
+
+    let address = "11".repeat(32);
+    let values = [
+        json!(1000),
+        json!("-2"),
+        json!("hi"),
+        json!(format!("0x{address}")),
+        json!([1, "0x2"]),
+    ];
+
+    // SCALE encoding on Polkadot, after the selector
+    let polkadot = constructor_args(Target::default_polkadot(), 0);
+    assert_eq!(polkadot.encoding, ArgsEncoding::Scale);
+    assert_eq!(polkadot.constructors.len(), 1);
+    assert_eq!(polkadot.constructors[0].name, "new");
+    assert_eq!(polkadot.constructors[0].inputs[4].ty, "uint32[]");
+
+    let selector = hex::decode(&polkadot.constructors[0].selector.as_ref().unwrap()[2..]).unwrap();
+    assert_eq!(selector.len(), 4);
+
+    assert_eq!(
+        encode_constructor_args(&polkadot, &values).unwrap(),
+        [
+            &selector[..],
+            &[0xe8, 0x03, 0xfe, 0x08],
+            b"hi",
+            &[0x11; 32],
+            &[0x08, 1, 0, 0, 0, 2, 0, 0, 0],
+        ]
+        .concat()
+    );
+
+    assert_eq!(
+        encode_constructor_args(&polkadot, &values[..2]).unwrap_err(),
+        "contract 'C' has no constructor with 2 arguments"
+    );
+
+    let mut wrong = values.clone();
+    wrong[0] = json!(70000);
+    assert_eq!(
+        encode_constructor_args(&polkadot, &wrong).unwrap_err(),
+        "argument 'a': 70000 does not fit into uint16"
+    );
+
+    wrong = values.clone();
+    wrong[3] = json!("0x1111");
+    assert_eq!(
+        encode_constructor_args(&polkadot, &wrong).unwrap_err(),
+        "argument 'addr': expected 32 bytes, not \"0x1111\""
+    );
+
+    // Borsh encoding on Solana, after the discriminator of new
+    let solana = constructor_args(Target::Solana, 0);
+    assert_eq!(solana.encoding, ArgsEncoding::Borsh);
+    assert_eq!(solana.constructors[0].accounts[0].name, "dataAccount");
+    assert!(solana.constructors[0].accounts[0].is_writable);
+
+    let mut base58 = values.clone();
+    base58[3] = json!("29d2S7vB453rNYFdR5Ycwt7y9haRT5fwVwL9zTmBhfV2");
+
+    let encoded = [
+        &function_discriminator("new")[..],
+        &[0xe8, 0x03, 0xfe, 2, 0, 0, 0],
+        b"hi",
+        &[0x11; 32],
+        &[2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0],
+    ]
+    .concat();
+
+    assert_eq!(encode_constructor_args(&solana, &values).unwrap(), encoded);
+    assert_eq!(encode_constructor_args(&solana, &base58).unwrap(), encoded);
+
+    // the default constructor
+    let solana = constructor_args(Target::Solana, 1);
+    assert_eq!(solana.constructors.len(), 1);
+    assert!(solana.constructors[0].inputs.is_empty());
+    assert_eq!(
+        encode_constructor_args(&solana, &[]).unwrap(),
+        function_discriminator("new")
+    );
+
+    // Ethereum ABI encoding without a selector
+    let evm = constructor_args(Target::EVM, 0);
+    assert_eq!(evm.encoding, ArgsEncoding::Abi);
+    assert_eq!(evm.constructors[0].selector, None);
+
+    let mut values = values;
+    values[3] = json!(format!("0x{}", "11".repeat(20)));
+
+    let word = |value: u8| {
+        let mut word = [0u8; 32];
+        word[31] = value;
+        word
+    };
+
+    let mut address = [0u8; 32];
+    address[12..].fill(0x11);
+
+    let mut hi = [0u8; 32];
+    hi[..2].copy_from_slice(b"hi");
+
+    let mut thousand = [0u8; 32];
+    thousand[30..].copy_from_slice(&1000u16.to_be_bytes());
+
+    let mut minus_two = [0xffu8; 32];
+    minus_two[31] = 0xfe;
+
+    assert_eq!(
+        encode_constructor_args(&evm, &values).unwrap(),
+        [
+            thousand.to_vec(),
+            minus_two.to_vec(),
+            word(0xa0).to_vec(),
+            address.to_vec(),
+            word(0xe0).to_vec(),
+            word(2).to_vec(),
+            hi.to_vec(),
+            word(2).to_vec(),
+            word(1).to_vec(),
+            word(2).to_vec(),
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn constructor_args_compact() {
+    use crate::abi::constructor::{encode_constructor_args, gen_constructor_args};
+
+    let src = r#"
+        contract C {
+            constructor(@compact uint64 a, bool b) {}
+            constructor(int64 a) {}
+
+            function f() public {}
+        }"#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+    assert!(!ns.diagnostics.any_errors());
+
+    let args = gen_constructor_args(0, &ns).unwrap();
+    assert_eq!(args.constructors.len(), 2);
+    assert_eq!(args.constructors[0].compact, vec![0]);
+
+    let encoded = encode_constructor_args(&args, &[json!(1000), json!(true)]).unwrap();
+    assert_eq!(encoded[4..], [0xa1, 0x0f, 1]);
+
+    let encoded = encode_constructor_args(&args, &[json!(-1)]).unwrap();
+    assert_eq!(encoded[4..], [0xff; 8]);
+}
+
+#[test]
+fn upgrade_check() {
+    use crate::abi::upgrade::{check_upgrade, gen_upgrade_info, UpgradeInfo, UpgradeIssue};
+
+    let upgrade_info = |src: &str| {
+        let mut cache = FileResolver::default();
+        cache.set_file_contents("test.sol", src.to_string());
+        let mut ns = parse_and_resolve(
+            OsStr::new("test.sol"),
+            &mut cache,
+            Target::default_polkadot(),
+        );
+        codegen(&mut ns, &Options::default());
+        assert!(!ns.diagnostics.any_errors());
+
+        // go through json, like the bundle of a deployed contract
+        let json = serde_json::to_string(&gen_upgrade_info(0, &ns)).unwrap();
+        UpgradeInfo::from_json(&json).unwrap()
+    };
+
+    let old = upgrade_info(
+        r#"
+        contract C {
+            uint256 count;
+            string name;
+            int256 total;
+
+            function inc() public { count += 1; }
+            function get() public returns (uint256) { return count; }
+            function setName(string n) public { name = n; }
+        }"#,
+    );
+
+    // appending storage and functions is fine
+    let new = upgrade_info(
+        r#"
+        contract C {
+            uint256 count;
+            string name;
+            int256 total;
+            bool paused;
+
+            function inc() public { count += 1; }
+            function get() public returns (uint256) { return count; }
+            function setName(string n) public { name = n; }
+            function pause(bool p) public { paused = p; }
+        }"#,
+    );
+
+    assert_eq!(check_upgrade(&old, &new), vec![]);
+
+    let new = upgrade_info(
+        r#"
+        contract C {
+            string name;
+            int128 count;
+
+            function inc() public { count += 1; }
+            @selector([1, 2, 3, 4])
+            function get() public returns (int128) { return count; }
+        }"#,
+    );
+
+    assert_eq!(
+        check_upgrade(&old, &new),
+        vec![
+            UpgradeIssue::StorageMoved {
+                label: "C.count".into(),
+                old: "slot 0".into(),
+                new: "slot 1".into(),
+            },
+            UpgradeIssue::StorageTypeChanged {
+                label: "C.count".into(),
+                old: "uint256".into(),
+                new: "int128".into(),
+            },
+            UpgradeIssue::StorageMoved {
+                label: "C.name".into(),
+                old: "slot 1".into(),
+                new: "slot 0".into(),
+            },
+            UpgradeIssue::StorageRemoved {
+                label: "C.total".into()
+            },
+            UpgradeIssue::SelectorChanged {
+                signature: "get()".into(),
+                old: "0x6d4ce63c".into(),
+                new: "0x01020304".into(),
+            },
+            UpgradeIssue::FunctionRemoved {
+                signature: "setName(string)".into(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,242 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// compare a deployed version of a contract with a new version, to find the changes which make
+// it unsafe to upgrade to the new version
+use super::selectors::{gen_selectors, Selector};
+use super::storage_layout::{gen_storage_layout, StorageItem, StorageLayout};
+use crate::sema::ast::Namespace;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// The parts of a contract which must stay the same across upgrades. This is read from the
+/// bundle written by `solang compile --bundle`, whose source map lists the selector of each
+/// external function.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeInfo {
+    pub contract_name: String,
+    pub storage_layout: StorageLayout,
+    /// The external functions and constructors. This is not known if the old version was read
+    /// from a storage layout file.
+    #[serde(rename = "sourceMap")]
+    pub functions: Option<Vec<Selector>>,
+}
+
+impl UpgradeInfo {
+    /// Read a bundle, or a storage layout written with `--emit storage-layout`
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+
+        if value.get("storageLayout").is_some() {
+            serde_json::from_value(value).map_err(|err| format!("not a valid bundle: {err}"))
+        } else {
+            let storage_layout: StorageLayout = serde_json::from_value(value)
+                .map_err(|err| format!("not a valid bundle or storage layout: {err}"))?;
+
+            Ok(UpgradeInfo {
+                contract_name: storage_layout.contract.clone(),
+                storage_layout,
+                functions: None,
+            })
+        }
+    }
+}
+
+/// Generate the upgrade information of a contract. The contract must have been through codegen,
+/// for the storage layout.
+pub fn gen_upgrade_info(contract_no: usize, ns: &Namespace) -> UpgradeInfo {
+    UpgradeInfo {
+        contract_name: ns.contracts[contract_no].id.name.clone(),
+        storage_layout: gen_storage_layout(contract_no, ns),
+        functions: Some(gen_selectors(contract_no, ns).functions),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UpgradeIssue {
+    /// A storage variable of the old version is not in the new version
+    StorageRemoved { label: String },
+    /// A storage variable is at a different slot or offset
+    StorageMoved {
+        label: String,
+        old: String,
+        new: String,
+    },
+    /// A storage variable has a different type, or its type has a different layout
+    StorageTypeChanged {
+        label: String,
+        old: String,
+        new: String,
+    },
+    /// An external function of the old version is not in the new version
+    FunctionRemoved { signature: String },
+    /// An external function has the same signature but a different selector
+    SelectorChanged {
+        signature: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl fmt::Display for UpgradeIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpgradeIssue::StorageRemoved { label } => {
+                write!(f, "storage variable '{label}' has been removed")
+            }
+            UpgradeIssue::StorageMoved { label, old, new } => {
+                write!(
+                    f,
+                    "storage variable '{label}' has moved from {old} to {new}"
+                )
+            }
+            UpgradeIssue::StorageTypeChanged { label, old, new } => {
+                if old == new {
+                    write!(
+                        f,
+                        "storage variable '{label}' has type '{old}' whose layout has changed"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "storage variable '{label}' has changed type from '{old}' to '{new}'"
+                    )
+                }
+            }
+            UpgradeIssue::FunctionRemoved { signature } => {
+                write!(f, "external function '{signature}' has been removed")
+            }
+            UpgradeIssue::SelectorChanged {
+                signature,
+                old,
+                new,
+            } => write!(
+                f,
+                "external function '{signature}' has changed selector from {old} to {new}"
+            ),
+        }
+    }
+}
+
+/// List the changes which make it unsafe to replace the code of the old version with the new
+/// version. Storage variables may be added after the existing ones, and functions may be added;
+/// anything else which changes the storage layout or removes a selector is reported.
+pub fn check_upgrade(old: &UpgradeInfo, new: &UpgradeInfo) -> Vec<UpgradeIssue> {
+    let mut issues = Vec::new();
+
+    for old_item in &old.storage_layout.storage {
+        let label = storage_label(old_item);
+
+        let Some(new_item) = new
+            .storage_layout
+            .storage
+            .iter()
+            .find(|new_item| storage_label(new_item) == label)
+        else {
+            issues.push(UpgradeIssue::StorageRemoved { label });
+            continue;
+        };
+
+        if old_item.slot != new_item.slot || old_item.offset != new_item.offset {
+            issues.push(UpgradeIssue::StorageMoved {
+                label: label.clone(),
+                old: storage_position(old_item),
+                new: storage_position(new_item),
+            });
+        }
+
+        if old_item.ty != new_item.ty
+            || !same_type_layout(
+                &old_item.ty,
+                &old.storage_layout,
+                &new.storage_layout,
+                &mut HashSet::new(),
+            )
+        {
+            issues.push(UpgradeIssue::StorageTypeChanged {
+                label,
+                old: old_item.ty.clone(),
+                new: new_item.ty.clone(),
+            });
+        }
+    }
+
+    if let (Some(old_functions), Some(new_functions)) = (&old.functions, &new.functions) {
+        let new_functions: BTreeMap<&str, &Selector> = new_functions
+            .iter()
+            .map(|func| (func.signature.as_str(), func))
+            .collect();
+
+        // constructors are not called on a deployed contract
+        for old_func in old_functions
+            .iter()
+            .filter(|func| !func.signature.starts_with("new("))
+        {
+            match new_functions.get(old_func.signature.as_str()) {
+                None => issues.push(UpgradeIssue::FunctionRemoved {
+                    signature: old_func.signature.clone(),
+                }),
+                Some(new_func) if new_func.selector != old_func.selector => {
+                    issues.push(UpgradeIssue::SelectorChanged {
+                        signature: old_func.signature.clone(),
+                        old: old_func.selector.clone().unwrap_or_default(),
+                        new: new_func.selector.clone().unwrap_or_default(),
+                    })
+                }
+                Some(_) => (),
+            }
+        }
+    }
+
+    issues
+}
+
+/// Variables with the same name can be declared in different base contracts
+fn storage_label(item: &StorageItem) -> String {
+    match &item.contract {
+        Some(contract) => format!("{contract}.{}", item.label),
+        None => item.label.clone(),
+    }
+}
+
+fn storage_position(item: &StorageItem) -> String {
+    match (&item.slot, &item.offset) {
+        (Some(slot), Some(offset)) => format!("slot {slot} offset {offset}"),
+        (Some(slot), None) => format!("slot {slot}"),
+        (None, Some(offset)) => format!("offset {offset}"),
+        (None, None) => "nowhere".into(),
+    }
+}
+
+/// Compare a type by name in both layouts, including the types it refers to. Recursive structs
+/// are only compared once.
+fn same_type_layout(
+    name: &str,
+    old: &StorageLayout,
+    new: &StorageLayout,
+    visited: &mut HashSet<String>,
+) -> bool {
+    if !visited.insert(name.to_owned()) {
+        return true;
+    }
+
+    let (Some(old_ty), Some(new_ty)) = (old.types.get(name), new.types.get(name)) else {
+        return !old.types.contains_key(name) && !new.types.contains_key(name);
+    };
+
+    if old_ty != new_ty {
+        return false;
+    }
+
+    let members = old_ty.members.iter().flatten().map(|member| &member.ty);
+
+    old_ty
+        .key
+        .iter()
+        .chain(old_ty.value.iter())
+        .chain(old_ty.base.iter())
+        .chain(members)
+        .all(|ty| same_type_layout(ty, old, new, visited))
+}
@@ -12,11 +12,20 @@ use itertools::Itertools;
 use semver::Version;
 use serde::Deserialize;
 use solang::{
-    codegen::{OptimizationLevel, Options},
+    codegen::{llvm_pipeline_passes, HeapAllocator, OptimizationLevel, Options},
     file_resolver::FileResolver,
+    sema::{
+        ast::Level,
+        warnings::{WarningCode, WarningConfig, ALL_WARNINGS},
+    },
     Target,
 };
-use std::{ffi::OsString, path::PathBuf, process::exit};
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::exit,
+};
 
 mod test;
 #[derive(Parser)]
@@ -46,6 +55,46 @@ pub enum Commands {
 
     #[command(about = "Create a new Solang project")]
     New(New),
+
+    #[cfg(feature = "test_runner")]
+    #[command(about = "Run the tests in Solidity test contracts")]
+    Test(Test),
+
+    #[cfg(feature = "formatter")]
+    #[command(about = "Format Solidity source files")]
+    Fmt(Fmt),
+
+    #[command(about = "Check Solidity source files for suspicious code")]
+    Lint(Lint),
+
+    #[command(about = "Print the inheritance, call or import graph of Solidity source files")]
+    Graph(Graph),
+
+    #[command(about = "Print the selectors of the functions, events and errors of contracts")]
+    Selectors(Selectors),
+
+    #[command(
+        about = "Flatten Solidity source files and their imports into a single file for verification"
+    )]
+    Flatten(Flatten),
+
+    #[command(
+        about = "Print the metadata, dispatch table, sections and imports of compiled contracts"
+    )]
+    Inspect(Inspect),
+
+    #[command(about = "Build contracts from source and check they match a compiled artifact")]
+    Verify(Verify),
+
+    #[command(
+        about = "Check that a new version of a contract keeps the storage layout and selectors of a deployed version"
+    )]
+    CheckUpgrade(CheckUpgrade),
+
+    #[command(
+        about = "Write an lcov coverage report from the logs of contracts compiled with --coverage"
+    )]
+    Coverage(Coverage),
 }
 
 #[derive(Args)]
@@ -55,6 +104,9 @@ pub struct New {
 
     #[arg(name = "INPUT", help = "Name of the project", num_args = 1, value_parser = ValueParser::os_string())]
     pub project_name: Option<OsString>,
+
+    #[arg(name = "TEMPLATE", help = "Contract to start the project with", long = "template", value_parser = ["flipper", "counter", "erc20", "spl-token-wrapper"], num_args = 1, default_value = "flipper")]
+    pub template: String,
 }
 
 #[derive(Args)]
@@ -66,6 +118,173 @@ pub struct IdlCommand {
     pub output: Option<PathBuf>,
 }
 
+#[derive(Args)]
+pub struct Fmt {
+    #[arg(name = "INPUT", help = "Solidity input files", required = true, value_parser = ValueParser::path_buf(), num_args = 1..)]
+    pub input: Vec<PathBuf>,
+
+    #[arg(name = "CHECK", help = "Do not write the files, but exit with an error if any are not formatted", long = "check", action = ArgAction::SetTrue)]
+    pub check: bool,
+
+    #[arg(name = "TABWIDTH", help = "Number of spaces per indentation level", long = "tab-width", num_args = 1, value_parser = value_parser!(usize), default_value = "4")]
+    pub tab_width: usize,
+}
+
+#[derive(Args)]
+pub struct Lint {
+    #[arg(name = "CONFFILE", help = "Take lint settings from configuration file", long = "config-file", value_parser = ValueParser::os_string(), num_args = 1, default_value = "solang.toml")]
+    pub configuration_file: Option<OsString>,
+
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "RULE", help = "Set the severity of a lint rule [format: rule=error|warning|info|off]", value_parser = ValueParser::new(parse_rule_severity), action = ArgAction::Append, long = "rule", num_args = 1)]
+    pub rules: Option<Vec<(String, String)>>,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", value_parser = ["text", "json", "sarif"], num_args = 1)]
+    pub format: Option<String>,
+
+    #[arg(name = "FAILON", help = "Exit with an error if there are findings of this severity or higher", long = "fail-on", value_parser = ["error", "warning", "never"], num_args = 1)]
+    pub fail_on: Option<String>,
+}
+
+#[derive(Args)]
+pub struct Graph {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "KIND", help = "Graph of contract inheritance, function calls or file imports", long = "kind", value_parser = ["inheritance", "calls", "imports"], num_args = 1, default_value = "inheritance")]
+    pub kind: String,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", value_parser = ["dot", "json"], num_args = 1, default_value = "dot")]
+    pub format: String,
+
+    #[arg(name = "OUTPUT", help = "Output directory; if not given, the graphs are printed to STDOUT", short = 'o', long = "output", num_args = 1, value_parser = ValueParser::path_buf())]
+    pub output_directory: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct Selectors {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", value_parser = ["text", "json"], num_args = 1, default_value = "text")]
+    pub format: String,
+}
+
+#[derive(Args)]
+pub struct Flatten {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(name = "OUTPUT", help = "Output directory; if not given, the flattened files are printed to STDOUT", short = 'o', long = "output", num_args = 1, value_parser = ValueParser::path_buf())]
+    pub output_directory: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct Inspect {
+    #[arg(name = "INPUT", help = "Compiled .so, .wasm or .contract files", required = true, value_parser = ValueParser::path_buf(), num_args = 1..)]
+    pub input: Vec<PathBuf>,
+
+    #[arg(name = "METADATA", help = "The .contract file or Anchor IDL for the binary; by default, this is looked for next to the binary", long = "metadata", num_args = 1, value_parser = ValueParser::path_buf())]
+    pub metadata: Option<PathBuf>,
+
+    #[arg(name = "FORMAT", help = "Output format", long = "format", value_parser = ["text", "json"], num_args = 1, default_value = "text")]
+    pub format: String,
+}
+
+#[derive(Args)]
+pub struct Coverage {
+    #[arg(name = "INPUT", help = "Files with the logs of the contracts", required = true, value_parser = ValueParser::path_buf(), num_args = 1..)]
+    pub input: Vec<PathBuf>,
+
+    #[arg(name = "MAP", help = "The .coverage.json files written when compiling the contracts", long = "map", required = true, value_parser = ValueParser::path_buf(), num_args = 1..)]
+    pub maps: Vec<PathBuf>,
+
+    #[arg(name = "OUTPUT", help = "Output file; if not given, the report is printed to STDOUT", short = 'o', long = "output", num_args = 1, value_parser = ValueParser::path_buf())]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct Verify {
+    #[arg(name = "ARTIFACT", help = "The .so, .wasm or .contract file to verify", long = "artifact", required = true, num_args = 1, value_parser = ValueParser::path_buf())]
+    pub artifact: PathBuf,
+
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[clap(flatten)]
+    pub debug_features: DebugFeatures,
+
+    #[clap(flatten)]
+    pub optimizations: Optimizations,
+}
+
+#[derive(Args)]
+pub struct CheckUpgrade {
+    #[arg(name = "OLD", help = "The bundle or storage layout json of the deployed version", required = true, num_args = 1, value_parser = ValueParser::path_buf())]
+    pub old: PathBuf,
+
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+}
+
+/// The `[lint]` section of solang.toml
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LintSettings {
+    #[serde(default)]
+    pub rules: HashMap<String, String>,
+
+    pub format: Option<String>,
+
+    #[serde(rename(deserialize = "fail-on"))]
+    pub fail_on: Option<String>,
+}
+
+impl LintSettings {
+    /// Options given on the command line override the configuration file
+    pub fn overwrite_with_args(&mut self, lint_args: &Lint) -> &mut LintSettings {
+        if let Some(rules) = &lint_args.rules {
+            self.rules.extend(rules.iter().cloned());
+        }
+
+        if lint_args.format.is_some() {
+            self.format = lint_args.format.clone();
+        }
+
+        if lint_args.fail_on.is_some() {
+            self.fail_on = lint_args.fail_on.clone();
+        }
+
+        self
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct LintConfigFile {
+    #[serde(default)]
+    pub lint: LintSettings,
+}
+
 #[derive(Args)]
 pub struct LanguageServerCommand {
     #[clap(flatten)]
@@ -97,6 +316,53 @@ pub struct Doc {
 
     #[arg(name = "OUTPUT",help = "output directory", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::string())]
     pub output_directory: Option<OsString>,
+
+    #[arg(name = "STORAGE", help = "Include the storage layout of each contract", long = "storage", action = ArgAction::SetTrue)]
+    pub storage: bool,
+}
+
+#[derive(Args)]
+pub struct Test {
+    #[clap(flatten)]
+    pub package: DocPackage,
+
+    #[clap(flatten)]
+    pub target: TargetArg,
+
+    #[arg(
+        name = "MATCH",
+        help = "Only run tests whose name contains this string",
+        long = "match",
+        num_args = 1
+    )]
+    pub filter: Option<String>,
+
+    #[arg(name = "VERBOSE", help = "show the output of passing tests", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
+    pub verbose: bool,
+
+    #[arg(name = "COVERAGE", help = "Write an lcov report of the lines which the tests run", long = "coverage", num_args = 0..=1, default_missing_value = "lcov.info", value_parser = ValueParser::path_buf())]
+    pub coverage: Option<PathBuf>,
+
+    #[arg(name = "RUNS", help = "Number of call sequences to check each invariant with", long = "runs", default_value = "64", value_parser = value_parser!(usize))]
+    pub runs: usize,
+
+    #[arg(name = "DEPTH", help = "Number of calls in each sequence", long = "depth", default_value = "16", value_parser = value_parser!(usize))]
+    pub depth: usize,
+
+    #[arg(name = "SEED", help = "Seed for generating call sequences, random if not given", long = "seed", value_parser = value_parser!(u64))]
+    pub seed: Option<u64>,
+
+    #[arg(name = "GASSNAPSHOT", help = "Write the gas used by each test which passes to a snapshot file", long = "gas-snapshot", num_args = 0..=1, default_missing_value = ".gas-snapshot", value_parser = ValueParser::path_buf())]
+    pub gas_snapshot: Option<PathBuf>,
+
+    #[arg(name = "CHECKGASSNAPSHOT", help = "Fail if tests use more gas than in a snapshot file", long = "check-gas-snapshot", num_args = 0..=1, default_missing_value = ".gas-snapshot", value_parser = ValueParser::path_buf())]
+    pub check_gas_snapshot: Option<PathBuf>,
+
+    #[arg(name = "GASTOLERANCE", help = "Percentage of extra gas which is not a regression", long = "gas-tolerance", default_value = "0", value_parser = value_parser!(f64))]
+    pub gas_tolerance: f64,
+
+    #[arg(name = "GASWARN", help = "Only warn about gas regressions", long = "gas-warn", action = ArgAction::SetTrue)]
+    pub gas_warn: bool,
 }
 
 #[derive(Args, Deserialize, Debug, PartialEq)]
@@ -126,9 +392,57 @@ pub struct Compile {
     #[clap(flatten)]
     #[serde(default = "Optimizations::default")]
     pub optimizations: Optimizations,
+
+    #[clap(flatten)]
+    #[serde(default)]
+    pub warnings: Warnings,
+
+    #[arg(skip)]
+    #[serde(default, rename(deserialize = "contract"))]
+    pub contract_overrides: HashMap<String, ContractOverrides>,
 }
 
 impl Compile {
+    /// Paths in a configuration file are relative to the directory of the configuration file,
+    /// so that the project can be compiled from any directory.
+    pub fn relative_to(&mut self, dir: &Path) -> &mut Compile {
+        if dir.as_os_str().is_empty() {
+            return self;
+        }
+
+        let join = |path: &mut PathBuf| {
+            if path.is_relative() {
+                *path = dir.join(&path);
+            }
+        };
+
+        if let Some(input) = &mut self.package.input {
+            input.iter_mut().for_each(join);
+        }
+
+        if let Some(import_path) = &mut self.package.import_path {
+            import_path.iter_mut().for_each(join);
+        }
+
+        if let Some(import_map) = &mut self.package.import_map {
+            import_map.iter_mut().for_each(|(_, path)| join(path));
+        }
+
+        for output in [
+            &mut self.compiler_output.output_directory,
+            &mut self.compiler_output.output_meta,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if Path::new(output).is_relative() {
+                *output = dir.join(&output).to_string_lossy().to_string();
+            }
+        }
+
+        self
+    }
+
     /// loop over args explicitly provided at runtime and update Compile accordingly.
     pub fn overwrite_with_matches(&mut self, matches: &ArgMatches) -> &mut Compile {
         for id in explicit_args(matches) {
@@ -175,9 +489,75 @@ impl Compile {
                     self.compiler_output.std_json_output =
                         *matches.get_one::<bool>("STD-JSON").unwrap()
                 }
+                "MESSAGE-FORMAT" => {
+                    self.compiler_output.message_format =
+                        matches.get_one::<String>("MESSAGE-FORMAT").cloned()
+                }
                 "VERBOSE" => {
                     self.compiler_output.verbose = *matches.get_one::<bool>("VERBOSE").unwrap()
                 }
+                "ABI" => self.compiler_output.abi = matches.get_one::<String>("ABI").cloned(),
+                "ABSTRACT-ABI" => {
+                    self.compiler_output.abstract_abi =
+                        *matches.get_one::<bool>("ABSTRACT-ABI").unwrap()
+                }
+                "COSTS" => self.compiler_output.costs = matches.get_one::<String>("COSTS").cloned(),
+                "STACK-USAGE" => {
+                    self.compiler_output.stack_usage =
+                        matches.get_one::<String>("STACK-USAGE").cloned()
+                }
+                "HEAP-ALLOCATIONS" => {
+                    self.compiler_output.heap_allocations =
+                        matches.get_one::<String>("HEAP-ALLOCATIONS").cloned()
+                }
+                "EMBED-BUILD-INFO" => {
+                    self.compiler_output.embed_build_info =
+                        *matches.get_one::<bool>("EMBED-BUILD-INFO").unwrap()
+                }
+                "METADATA" => {
+                    self.compiler_output.metadata = *matches.get_one::<bool>("METADATA").unwrap()
+                }
+                "BUNDLE" => {
+                    self.compiler_output.bundle = *matches.get_one::<bool>("BUNDLE").unwrap()
+                }
+                "SOURCE-MAP" => {
+                    self.compiler_output.source_map =
+                        *matches.get_one::<bool>("SOURCE-MAP").unwrap()
+                }
+                "CONSTRUCTOR-ARGS" => {
+                    self.compiler_output.constructor_args =
+                        *matches.get_one::<bool>("CONSTRUCTOR-ARGS").unwrap()
+                }
+                "SIZE-REPORT" => {
+                    self.compiler_output.size_report =
+                        matches.get_one::<String>("SIZE-REPORT").cloned()
+                }
+                "WATCH" => self.compiler_output.watch = *matches.get_one::<bool>("WATCH").unwrap(),
+                "WATCH-JSON" => {
+                    self.compiler_output.watch_json =
+                        *matches.get_one::<bool>("WATCH-JSON").unwrap()
+                }
+
+                // Warnings args
+                "DENY" => {
+                    self.warnings.deny = matches
+                        .get_many::<String>("DENY")
+                        .map(|names| names.map(String::from).collect())
+                }
+                "WARN" => {
+                    self.warnings.warn = matches
+                        .get_many::<String>("WARN")
+                        .map(|names| names.map(String::from).collect())
+                }
+                "ALLOW" => {
+                    self.warnings.allow = matches
+                        .get_many::<String>("ALLOW")
+                        .map(|names| names.map(String::from).collect())
+                }
+                "STRICTCONVERSIONS" => {
+                    self.warnings.strict_conversions =
+                        *matches.get_one::<bool>("STRICTCONVERSIONS").unwrap()
+                }
 
                 // DebugFeatures args
                 "NOLOGRUNTIMEERRORS" => {
@@ -187,10 +567,18 @@ impl Compile {
                 "NOPRINTS" => {
                     self.debug_features.log_prints = *matches.get_one::<bool>("NOPRINTS").unwrap()
                 }
+                "NOREVERTDATA" => {
+                    self.debug_features.revert_data =
+                        *matches.get_one::<bool>("NOREVERTDATA").unwrap()
+                }
                 "GENERATEDEBUGINFORMATION" => {
                     self.debug_features.generate_debug_info =
                         *matches.get_one::<bool>("GENERATEDEBUGINFORMATION").unwrap()
                 }
+                "COVERAGE" => {
+                    self.debug_features.coverage = *matches.get_one::<bool>("COVERAGE").unwrap()
+                }
+                "TRACE" => self.debug_features.trace = *matches.get_one::<bool>("TRACE").unwrap(),
                 "RELEASE" => {
                     self.debug_features.release = *matches.get_one::<bool>("RELEASE").unwrap()
                 }
@@ -212,12 +600,46 @@ impl Compile {
                     self.optimizations.vector_to_slice =
                         *matches.get_one::<bool>("VECTORTOSLICE").unwrap()
                 }
+                "STORAGECACHE" => {
+                    self.optimizations.storage_cache =
+                        *matches.get_one::<bool>("STORAGECACHE").unwrap()
+                }
+                "STRINGSWITCH" => {
+                    self.optimizations.string_switch =
+                        *matches.get_one::<bool>("STRINGSWITCH").unwrap()
+                }
+                "LOOPUNROLL" => {
+                    self.optimizations.loop_unroll = *matches.get_one::<bool>("LOOPUNROLL").unwrap()
+                }
+                "COLDSPLIT" => {
+                    self.optimizations.cold_split = *matches.get_one::<bool>("COLDSPLIT").unwrap()
+                }
+                "STRIPDEADSTORAGE" => {
+                    self.optimizations.strip_dead_storage =
+                        *matches.get_one::<bool>("STRIPDEADSTORAGE").unwrap()
+                }
                 "COMMONSUBEXPRESSIONELIMINATION" => {
                     self.optimizations.common_subexpression_elimination = *matches
                         .get_one::<bool>("COMMONSUBEXPRESSIONELIMINATION")
                         .unwrap()
                 }
                 "OPT" => self.optimizations.opt_level = matches.get_one::<String>("OPT").cloned(),
+                "LLVM-PASSES" => {
+                    self.optimizations.llvm_passes =
+                        matches.get_one::<String>("LLVM-PASSES").cloned()
+                }
+                "INLINE-THRESHOLD" => {
+                    self.optimizations.inline_threshold =
+                        matches.get_one::<u32>("INLINE-THRESHOLD").copied()
+                }
+                "HEAP-ALLOCATOR" => {
+                    self.optimizations.heap_allocator =
+                        matches.get_one::<String>("HEAP-ALLOCATOR").cloned()
+                }
+                "ACCOUNTDISCRIMINATOR" => {
+                    self.optimizations.account_discriminator =
+                        *matches.get_one::<bool>("ACCOUNTDISCRIMINATOR").unwrap()
+                }
 
                 "TARGET" => self.target_arg.name = matches.get_one::<String>("TARGET").cloned(),
                 "ADDRESS_LENGTH" => {
@@ -238,7 +660,7 @@ impl Compile {
 
 #[derive(Args, Deserialize, Default, Debug, PartialEq)]
 pub struct CompilerOutput {
-    #[arg(name = "EMIT", help = "Emit compiler state at early stage", long = "emit", num_args = 1, value_parser = ["ast-dot", "cfg", "llvm-ir", "llvm-bc", "object", "asm"])]
+    #[arg(name = "EMIT", help = "Emit compiler state at early stage", long = "emit", num_args = 1, value_parser = ["ast-dot", "cfg", "llvm-ir", "llvm-ir-per-pass", "llvm-bc", "object", "asm", "storage-layout", "lir", "ast-json"])]
     #[serde(deserialize_with = "deserialize_emit", default)]
     pub emit: Option<String>,
 
@@ -246,6 +668,14 @@ pub struct CompilerOutput {
     #[serde(default)]
     pub std_json_output: bool,
 
+    #[arg(name = "MESSAGE-FORMAT", help = "Print the diagnostics in this format", long = "message-format", num_args = 1, value_parser = ["human", "sarif"], conflicts_with_all = ["STD-JSON", "WATCH"])]
+    #[serde(
+        deserialize_with = "deserialize_message_format",
+        default,
+        rename(deserialize = "message-format")
+    )]
+    pub message_format: Option<String>,
+
     #[arg(name = "OUTPUT",help = "output directory", short = 'o', long = "output", num_args = 1, value_parser =ValueParser::string())]
     #[serde(default)]
     pub output_directory: Option<String>,
@@ -257,11 +687,75 @@ pub struct CompilerOutput {
     #[arg(name = "VERBOSE" ,help = "show debug messages", short = 'v', action = ArgAction::SetTrue, long = "verbose")]
     #[serde(default)]
     pub verbose: bool,
+
+    #[arg(name = "ABI", help = "Also write the contract ABI in the given format", long = "abi", num_args = 1, value_parser = ["solc"])]
+    #[serde(deserialize_with = "deserialize_abi", default)]
+    pub abi: Option<String>,
+
+    #[arg(name = "ABSTRACT-ABI", help = "Also write the ABI of abstract contracts, interfaces and libraries, which have no binary", long = "abstract-abi", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default, rename(deserialize = "abstract-abi"))]
+    pub abstract_abi: bool,
+
+    #[arg(name = "COSTS", help = "Print the estimated worst-case cost of each external function, as a table or as json", long = "costs", num_args = 0..=1, default_missing_value = "table", value_parser = ["table", "json"], conflicts_with = "STD-JSON")]
+    #[serde(deserialize_with = "deserialize_costs", default)]
+    pub costs: Option<String>,
+
+    #[arg(name = "STACK-USAGE", help = "Print the estimated stack usage and call depth of each function, as a table or as json", long = "stack-usage", num_args = 0..=1, default_missing_value = "table", value_parser = ["table", "json"], conflicts_with = "STD-JSON")]
+    #[serde(
+        deserialize_with = "deserialize_stack_usage",
+        default,
+        rename(deserialize = "stack-usage")
+    )]
+    pub stack_usage: Option<String>,
+
+    #[arg(name = "HEAP-ALLOCATIONS", help = "Print the sites in each function which allocate memory on the heap, as a table or as json", long = "heap-allocations", num_args = 0..=1, default_missing_value = "table", value_parser = ["table", "json"], conflicts_with = "STD-JSON")]
+    #[serde(
+        deserialize_with = "deserialize_heap_allocations",
+        default,
+        rename(deserialize = "heap-allocations")
+    )]
+    pub heap_allocations: Option<String>,
+
+    #[arg(name = "EMBED-BUILD-INFO", help = "Embed the compiler version, options and source hashes in the binary, for solang verify", long = "embed-build-info", action = ArgAction::SetTrue)]
+    #[serde(default, rename(deserialize = "embed-build-info"))]
+    pub embed_build_info: bool,
+
+    #[arg(name = "METADATA", help = "Embed the IPFS hash of the solc style metadata in the binary, and write the metadata file", long = "metadata", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default)]
+    pub metadata: bool,
+
+    #[arg(name = "BUNDLE", help = "Also write a json bundle for each contract with the binary, ABI, metadata, storage layout, source map and NatSpec", long = "bundle", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default)]
+    pub bundle: bool,
+
+    #[arg(name = "SOURCE-MAP", help = "Also write a source map for each contract, in the solc format and as json", long = "source-map", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default, rename(deserialize = "source-map"))]
+    pub source_map: bool,
+
+    #[arg(name = "CONSTRUCTOR-ARGS", help = "Also write a json description of how to encode the constructor arguments of each contract, for deployment tooling", long = "constructor-args", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default, rename(deserialize = "constructor-args"))]
+    pub constructor_args: bool,
+
+    #[arg(name = "SIZE-REPORT", help = "Print the size of the code of each contract by the function it comes from, as a table or as json", long = "size-report", num_args = 0..=1, default_missing_value = "table", value_parser = ["table", "json"], conflicts_with = "STD-JSON")]
+    #[serde(
+        deserialize_with = "deserialize_size_report",
+        default,
+        rename(deserialize = "size-report")
+    )]
+    pub size_report: Option<String>,
+
+    #[arg(name = "WATCH", help = "Watch the source files and recompile when they change", long = "watch", action = ArgAction::SetTrue, conflicts_with = "STD-JSON")]
+    #[serde(default)]
+    pub watch: bool,
+
+    #[arg(name = "WATCH-JSON", help = "In watch mode, report each rebuild as a line of json on stdout", long = "watch-json", action = ArgAction::SetTrue, requires = "WATCH")]
+    #[serde(default)]
+    pub watch_json: bool,
 }
 
 #[derive(Args)]
 pub struct TargetArg {
-    #[arg(name = "TARGET",required= true, long = "target", value_parser = ["solana", "polkadot", "evm"], help = "Target to build for [possible values: solana, polkadot]", num_args = 1, hide_possible_values = true)]
+    #[arg(name = "TARGET",required= true, long = "target", value_parser = ["solana", "polkadot", "evm", "stylus"], help = "Target to build for [possible values: solana, polkadot]", num_args = 1, hide_possible_values = true)]
     pub name: String,
 
     #[arg(name = "ADDRESS_LENGTH", help = "Address length on the Polkadot Parachain", long = "address-length", num_args = 1, value_parser = value_parser!(u64).range(4..1024))]
@@ -273,7 +767,7 @@ pub struct TargetArg {
 
 #[derive(Args, Deserialize, Debug, PartialEq)]
 pub struct CompileTargetArg {
-    #[arg(name = "TARGET", long = "target", value_parser = ["solana", "polkadot", "evm", "soroban"], help = "Target to build for [possible values: solana, polkadot]", num_args = 1, hide_possible_values = true)]
+    #[arg(name = "TARGET", long = "target", value_parser = ["solana", "polkadot", "evm", "soroban", "stylus"], help = "Target to build for [possible values: solana, polkadot]", num_args = 1, hide_possible_values = true)]
     pub name: Option<String>,
 
     #[arg(name = "ADDRESS_LENGTH", help = "Address length on the Polkadot Parachain", long = "address-length", num_args = 1, value_parser = value_parser!(u64).range(4..1024))]
@@ -323,6 +817,25 @@ pub struct CompilePackage {
     pub version: Option<String>,
 }
 
+#[derive(Args, Deserialize, Default, Debug, PartialEq)]
+pub struct Warnings {
+    #[arg(name = "DENY", help = "Report warnings with these codes or groups as errors", long = "deny", value_delimiter = ',', action = ArgAction::Append, value_parser = ValueParser::new(parse_warning_name))]
+    #[serde(default)]
+    pub deny: Option<Vec<String>>,
+
+    #[arg(name = "WARN", help = "Report warnings with these codes or groups as warnings", long = "warn", value_delimiter = ',', action = ArgAction::Append, value_parser = ValueParser::new(parse_warning_name))]
+    #[serde(default)]
+    pub warn: Option<Vec<String>>,
+
+    #[arg(name = "ALLOW", help = "Do not report warnings with these codes or groups", long = "allow", value_delimiter = ',', action = ArgAction::Append, value_parser = ValueParser::new(parse_warning_name))]
+    #[serde(default)]
+    pub allow: Option<Vec<String>>,
+
+    #[arg(name = "STRICTCONVERSIONS", help = "Report an error for implicit conversions which solc does not allow, like from unsigned to signed integers", long = "strict-conversions", action = ArgAction::SetTrue)]
+    #[serde(default, rename(deserialize = "strict-conversions"))]
+    pub strict_conversions: bool,
+}
+
 #[derive(Args, Deserialize, Debug, PartialEq)]
 pub struct DebugFeatures {
     #[arg(name = "NOLOGRUNTIMEERRORS", help = "Disable logging runtime errors in the environment", long = "no-log-runtime-errors", action = ArgAction::SetFalse)]
@@ -333,10 +846,22 @@ pub struct DebugFeatures {
     #[serde(default = "default_true", rename(deserialize = "prints"))]
     pub log_prints: bool,
 
+    #[arg(name = "NOREVERTDATA", help = "Revert without data when checks inserted by the compiler fail, like overflow and array bounds checks", long = "no-revert-data", action = ArgAction::SetFalse)]
+    #[serde(default = "default_true", rename(deserialize = "revert-data"))]
+    pub revert_data: bool,
+
     #[arg(name = "GENERATEDEBUGINFORMATION", help = "Enable generating debug information for LLVM IR", long = "generate-debug-info", action = ArgAction::SetTrue, short = 'g')]
     #[serde(default, rename(deserialize = "generate-debug-info"))]
     pub generate_debug_info: bool,
 
+    #[arg(name = "COVERAGE", help = "Report in the logs which blocks of code run, and write a coverage map for each contract", long = "coverage", action = ArgAction::SetTrue, conflicts_with = "RELEASE")]
+    #[serde(default)]
+    pub coverage: bool,
+
+    #[arg(name = "TRACE", help = "Print the arguments and return values of function calls, and storage writes, for functions not annotated with @trace(false)", long = "trace", action = ArgAction::SetTrue, conflicts_with = "RELEASE")]
+    #[serde(default)]
+    pub trace: bool,
+
     #[arg(name = "RELEASE", help = "Disable all debugging features such as prints, logging runtime errors, and logging api return codes", long = "release", action = ArgAction::SetTrue)]
     #[serde(default)]
     pub release: bool,
@@ -347,7 +872,10 @@ impl Default for DebugFeatures {
         DebugFeatures {
             log_runtime_errors: true,
             log_prints: true,
+            revert_data: true,
             generate_debug_info: false,
+            coverage: false,
+            trace: false,
             release: false,
         }
     }
@@ -378,10 +906,61 @@ pub struct Optimizations {
     )]
     pub common_subexpression_elimination: bool,
 
+    #[arg(name = "STORAGECACHE", help = "Disable caching storage slots in local variables", long = "no-storage-cache", action = ArgAction::SetFalse, display_order = 6)]
+    #[serde(default = "default_true", rename(deserialize = "storage-cache"))]
+    pub storage_cache: bool,
+
+    #[arg(name = "STRINGSWITCH", help = "Disable lowering string comparisons to a switch on the hash", long = "no-string-switch", action = ArgAction::SetFalse, display_order = 7)]
+    #[serde(default = "default_true", rename(deserialize = "string-switch"))]
+    pub string_switch: bool,
+
+    #[arg(name = "LOOPUNROLL", help = "Disable unrolling loops with a small constant number of iterations", long = "no-loop-unroll", action = ArgAction::SetFalse, display_order = 8)]
+    #[serde(default = "default_true", rename(deserialize = "loop-unroll"))]
+    pub loop_unroll: bool,
+
+    #[arg(name = "COLDSPLIT", help = "Disable moving the code which reverts out of the hot path", long = "no-cold-split", action = ArgAction::SetFalse, display_order = 9)]
+    #[serde(default = "default_true", rename(deserialize = "cold-split"))]
+    pub cold_split: bool,
+
+    #[arg(name = "STRIPDEADSTORAGE", help = "Leave private storage variables which are never read or never written out of the storage layout", long = "strip-dead-storage", action = ArgAction::SetTrue, display_order = 10)]
+    #[serde(default, rename(deserialize = "strip-dead-storage"))]
+    pub strip_dead_storage: bool,
+
     #[arg(name = "OPT", help = "Set llvm optimizer level ", short = 'O', default_value = "default", value_parser = ["none", "less", "default", "aggressive"], num_args = 1)]
-    #[serde(rename(deserialize = "llvm-IR-optimization-level"))]
+    #[serde(
+        default,
+        rename(deserialize = "llvm-IR-optimization-level"),
+        deserialize_with = "deserialize_opt_level"
+    )]
     pub opt_level: Option<String>,
 
+    #[arg(name = "LLVM-PASSES", help = "Run this pipeline of llvm passes instead of the default one, e.g. 'mem2reg,inline,globaldce'", long = "llvm-passes", num_args = 1, value_parser = ValueParser::new(parse_llvm_passes))]
+    #[serde(
+        default,
+        rename(deserialize = "llvm-passes"),
+        deserialize_with = "deserialize_llvm_passes"
+    )]
+    pub llvm_passes: Option<String>,
+
+    #[arg(name = "INLINE-THRESHOLD", help = "Set the inline threshold of the default llvm passes", long = "inline-threshold", num_args = 1, value_parser = value_parser!(u32), conflicts_with = "LLVM-PASSES")]
+    #[serde(default, rename(deserialize = "inline-threshold"))]
+    pub inline_threshold: Option<u32>,
+
+    #[arg(name = "HEAP-ALLOCATOR", help = "Set the heap allocator on Solana", long = "heap-allocator", num_args = 1, value_parser = ["bump", "free-list"])]
+    #[serde(
+        default,
+        rename(deserialize = "heap-allocator"),
+        deserialize_with = "deserialize_heap_allocator"
+    )]
+    pub heap_allocator: Option<String>,
+
+    #[arg(name = "ACCOUNTDISCRIMINATOR", help = "Use the legacy layout of data accounts on Solana, which starts with a 4 byte magic number rather than the Anchor account discriminator", long = "no-account-discriminator", action = ArgAction::SetFalse)]
+    #[serde(
+        default = "default_true",
+        rename(deserialize = "account-discriminator")
+    )]
+    pub account_discriminator: bool,
+
     #[cfg(feature = "wasm_opt")]
     #[arg(
         name = "WASM_OPT",
@@ -393,6 +972,55 @@ pub struct Optimizations {
     pub wasm_opt_passes: Option<OptimizationPasses>,
 }
 
+/// Settings in the configuration file which apply to a single contract. These override the
+/// settings for the whole project.
+#[derive(Deserialize, Default, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ContractOverrides {
+    #[serde(default)]
+    pub authors: Option<Vec<String>>,
+
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<String>,
+
+    #[serde(
+        default,
+        rename(deserialize = "llvm-IR-optimization-level"),
+        deserialize_with = "deserialize_opt_level"
+    )]
+    pub opt_level: Option<String>,
+
+    #[serde(default, rename(deserialize = "generate-debug-info"))]
+    pub generate_debug_info: Option<bool>,
+
+    #[cfg(feature = "wasm_opt")]
+    #[serde(default, rename(deserialize = "wasm-opt"))]
+    pub wasm_opt_passes: Option<OptimizationPasses>,
+}
+
+impl ContractOverrides {
+    /// The options for generating code for this contract. Only the options which are used after
+    /// codegen can be overridden, since codegen is done for all contracts in a file at once.
+    pub fn options(&self, opt: &Options) -> Options {
+        let mut opt = opt.clone();
+
+        if self.opt_level.is_some() {
+            opt.opt_level = opt_level(&self.opt_level);
+        }
+
+        if let Some(generate_debug_info) = self.generate_debug_info {
+            opt.generate_debug_information = generate_debug_info;
+        }
+
+        #[cfg(feature = "wasm_opt")]
+        if self.wasm_opt_passes.is_some() {
+            opt.wasm_opt = self.wasm_opt_passes;
+        }
+
+        opt
+    }
+}
+
 pub trait TargetArgTrait {
     fn get_name(&self) -> &String;
     fn get_address_length(&self) -> &Option<u64>;
@@ -435,7 +1063,7 @@ impl TargetArgTrait for CompileTargetArg {
 pub(crate) fn target_arg<T: TargetArgTrait>(target_arg: &T) -> Target {
     let target_name = target_arg.get_name();
 
-    if target_name == "solana" || target_name == "evm" {
+    if target_name == "solana" || target_name == "evm" || target_name == "stylus" {
         if target_arg.get_address_length().is_some() {
             eprintln!("error: address length cannot be modified except for polkadot target");
             exit(1);
@@ -455,6 +1083,7 @@ pub(crate) fn target_arg<T: TargetArgTrait>(target_arg: &T) -> Target {
         },
         "evm" => solang::Target::EVM,
         "soroban" => solang::Target::Soroban,
+        "stylus" => solang::Target::Stylus,
         _ => unreachable!(),
     };
 
@@ -506,8 +1135,12 @@ impl PackageTrait for DocPackage {
 }
 
 pub fn imports_arg<T: PackageTrait>(package: &T) -> FileResolver {
-    let mut resolver = FileResolver::default();
+    imports_arg_into(package, FileResolver::default())
+}
 
+/// Add the import paths and import maps of the package to a resolver, for example one which
+/// reads files through a session
+pub fn imports_arg_into<T: PackageTrait>(package: &T, mut resolver: FileResolver) -> FileResolver {
     if let Some(paths) = package.get_import_path() {
         let dups: Vec<_> = paths.iter().duplicates().collect();
 
@@ -545,8 +1178,8 @@ pub fn imports_arg<T: PackageTrait>(package: &T) -> FileResolver {
     resolver
 }
 
-pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Options {
-    let opt_level = if let Some(level) = &optimizations.opt_level {
+fn opt_level(level: &Option<String>) -> OptimizationLevel {
+    if let Some(level) = level {
         match level.as_str() {
             "none" => OptimizationLevel::None,
             "less" => OptimizationLevel::Less,
@@ -556,7 +1189,11 @@ pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Opti
         }
     } else {
         OptimizationLevel::Default
-    };
+    }
+}
+
+pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Options {
+    let opt_level = opt_level(&optimizations.opt_level);
 
     Options {
         dead_storage: optimizations.dead_storage,
@@ -564,10 +1201,26 @@ pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Opti
         strength_reduce: optimizations.strength_reduce,
         vector_to_slice: optimizations.vector_to_slice,
         common_subexpression_elimination: optimizations.common_subexpression_elimination,
+        storage_cache: optimizations.storage_cache,
+        string_switch: optimizations.string_switch,
+        loop_unroll: optimizations.loop_unroll,
+        cold_split: optimizations.cold_split,
+        account_discriminator: optimizations.account_discriminator,
+        strip_dead_storage: optimizations.strip_dead_storage,
         generate_debug_information: debug.generate_debug_info,
+        generate_line_tables: false,
         opt_level,
         log_runtime_errors: debug.log_runtime_errors && !debug.release,
         log_prints: debug.log_prints && !debug.release,
+        revert_data: debug.revert_data && !debug.release,
+        coverage: debug.coverage,
+        trace: debug.trace,
+        llvm_passes: optimizations.llvm_passes.clone(),
+        inline_threshold: optimizations.inline_threshold,
+        heap_allocator: match optimizations.heap_allocator.as_deref() {
+            Some("free-list") => HeapAllocator::FreeList,
+            _ => HeapAllocator::Bump,
+        },
         #[cfg(feature = "wasm_opt")]
         wasm_opt: optimizations.wasm_opt_passes.or(if debug.release {
             Some(OptimizationPasses::Z)
@@ -577,6 +1230,67 @@ pub fn options_arg(debug: &DebugFeatures, optimizations: &Optimizations) -> Opti
     }
 }
 
+/// The severity of the warnings. A code takes precedence over its group, and a group over
+/// `warnings`; otherwise, deny takes precedence over warn, and warn over allow.
+pub fn warnings_arg(warnings: &Warnings) -> WarningConfig {
+    match warning_config(warnings) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("error: {message}");
+            exit(1);
+        }
+    }
+}
+
+/// The severity of the warnings, or an error for an unknown code or group
+pub fn warning_config(warnings: &Warnings) -> Result<WarningConfig, String> {
+    let mut settings: Vec<(&str, Option<Level>)> = [
+        (&warnings.allow, None),
+        (&warnings.warn, Some(Level::Warning)),
+        (&warnings.deny, Some(Level::Error)),
+    ]
+    .into_iter()
+    .flat_map(|(names, level)| {
+        names
+            .iter()
+            .flatten()
+            .map(move |name| (name.as_str(), level.clone()))
+    })
+    .collect();
+
+    settings.sort_by_key(|(name, _)| {
+        if *name == ALL_WARNINGS {
+            0
+        } else if name.parse::<WarningCode>().is_err() {
+            1
+        } else {
+            2
+        }
+    });
+
+    let mut config = WarningConfig::default();
+
+    for (name, level) in settings {
+        config.set(name, level)?;
+    }
+
+    config.strict_conversions = warnings.strict_conversions;
+
+    Ok(config)
+}
+
+fn parse_warning_name(name: &str) -> Result<String, String> {
+    WarningCode::resolve(name)?;
+
+    Ok(name.to_owned())
+}
+
+fn parse_llvm_passes(pipeline: &str) -> Result<String, String> {
+    llvm_pipeline_passes(pipeline)?;
+
+    Ok(pipeline.to_owned())
+}
+
 // Parse the import map argument. This takes the form
 /// --import-map openzeppelin=/opt/openzeppelin-contracts/contract,
 /// and returns the name of the map and the path.
@@ -588,6 +1302,14 @@ fn parse_import_map(map: &str) -> Result<(String, PathBuf), String> {
     }
 }
 
+fn parse_rule_severity(rule: &str) -> Result<(String, String), String> {
+    if let Some((rule, severity)) = rule.split_once('=') {
+        Ok((rule.to_owned(), severity.to_owned()))
+    } else {
+        Err("contains no '='".to_owned())
+    }
+}
+
 fn parse_version(version: &str) -> Result<String, String> {
     match Version::parse(version) {
         Ok(version) => Ok(version.to_string()),
@@ -648,16 +1370,142 @@ where
     match str {
         Some(value) => {
             match value.as_str() {
-                "ast-dot"|"cfg"|"llvm-ir"|"llvm-bc"|"object"|"asm" =>
+                "ast-dot"|"cfg"|"llvm-ir"|"llvm-ir-per-pass"|"llvm-bc"|"object"|"asm"|"storage-layout"|"lir"|"ast-json" =>
                     Ok(Some(value))
                 ,
-                _ => Err(serde::de::Error::custom("Invalid option for `emit`. Valid options are: `ast-dot`, `cfg`, `llvm-ir`, `llvm-bc`, `object`, `asm`"))
+                _ => Err(serde::de::Error::custom("Invalid option for `emit`. Valid options are: `ast-dot`, `cfg`, `llvm-ir`, `llvm-ir-per-pass`, `llvm-bc`, `object`, `asm`, `storage-layout`, `lir`, `ast-json`"))
             }
         }
         None => Ok(None),
     }
 }
 
+fn deserialize_opt_level<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "none" | "less" | "default" | "aggressive" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `llvm-IR-optimization-level`. Valid options are: `none`, `less`, `default`, `aggressive`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_llvm_passes<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match llvm_pipeline_passes(&value) {
+            Ok(_) => Ok(Some(value)),
+            Err(err) => Err(serde::de::Error::custom(err)),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_abi<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "solc" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `abi`. Valid options are: `solc`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_message_format<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "human" | "sarif" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `message-format`. Valid options are: `human`, `sarif`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_costs<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "table" | "json" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `costs`. Valid options are: `table`, `json`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_stack_usage<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "table" | "json" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `stack-usage`. Valid options are: `table`, `json`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_heap_allocations<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "table" | "json" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `heap-allocations`. Valid options are: `table`, `json`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
+fn deserialize_heap_allocator<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "bump" | "free-list" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `heap-allocator`. Valid options are: `bump`, `free-list`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
+
 fn default_true() -> bool {
     true
 }
@@ -674,3 +1522,19 @@ fn explicit_args(matches: &ArgMatches) -> Vec<&Id> {
         })
         .collect()
 }
+
+fn deserialize_size_report<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let str: Option<String> = Option::deserialize(deserializer)?;
+    match str {
+        Some(value) => match value.as_str() {
+            "table" | "json" => Ok(Some(value)),
+            _ => Err(serde::de::Error::custom(
+                "Invalid option for `size-report`. Valid options are: `table`, `json`",
+            )),
+        },
+        None => Ok(None),
+    }
+}
@@ -3,10 +3,12 @@
 #[cfg(test)]
 
 mod tests {
-    use crate::{cli, options_arg, Cli, Commands};
+    use crate::{cli, options_arg, warnings_arg, Cli, Commands};
     use clap::{CommandFactory, Parser};
-    use solang::codegen::Options;
-    use std::path::PathBuf;
+    use solang::codegen::{HeapAllocator, Options};
+    use solang::sema::{ast::Level, warnings::WarningCode};
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
 
     #[test]
     fn test() {
@@ -15,7 +17,7 @@ mod tests {
 
     #[test]
     fn parse_compile_options() {
-        let mut command: Vec<&str> = "solang compile flipper.sol --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse -O aggressive".split(' ').collect();
+        let mut command: Vec<&str> = "solang compile flipper.sol --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse --no-storage-cache --no-string-switch --no-loop-unroll --no-cold-split --strip-dead-storage -O aggressive".split(' ').collect();
         let mut cli = Cli::parse_from(command);
 
         if let Commands::Compile(compile_args) = cli.command {
@@ -31,16 +33,22 @@ mod tests {
             assert!(!compile_args.optimizations.dead_storage);
             assert!(!compile_args.optimizations.vector_to_slice);
             assert!(!compile_args.optimizations.strength_reduce);
+            assert!(!compile_args.optimizations.storage_cache);
+            assert!(!compile_args.optimizations.string_switch);
+            assert!(!compile_args.optimizations.loop_unroll);
+            assert!(!compile_args.optimizations.cold_split);
+            assert!(compile_args.optimizations.strip_dead_storage);
             assert_eq!(compile_args.optimizations.opt_level.unwrap(), "aggressive");
         }
 
-        command = "solang compile flipper.sol --target polkadot --no-log-runtime-errors --no-prints -g --release".split(' ').collect();
+        command = "solang compile flipper.sol --target polkadot --no-log-runtime-errors --no-prints --no-revert-data -g --release".split(' ').collect();
         cli = Cli::parse_from(command);
 
         if let Commands::Compile(compile_args) = cli.command {
             assert!(compile_args.debug_features.generate_debug_info);
             assert!(!compile_args.debug_features.log_prints);
             assert!(!compile_args.debug_features.log_runtime_errors);
+            assert!(!compile_args.debug_features.revert_data);
             assert!(compile_args.debug_features.release);
         }
     }
@@ -154,6 +162,15 @@ mod tests {
         emit = "ast-dot"
         output_directory = "output"
         output_meta = "metadata"
+        abi = "solc"
+        abstract-abi = true
+        costs = "json"
+        stack-usage = "table"
+        heap-allocations = "json"
+        constructor-args = true
+        size-report = "table"
+        watch = true
+        message-format = "sarif"
         "#;
 
         let out: cli::CompilerOutput = toml::from_str(compiler_out).unwrap();
@@ -163,11 +180,35 @@ mod tests {
         assert_eq!(out.emit, Some("ast-dot".to_owned()));
         assert_eq!(out.output_directory, Some("output".to_owned()));
         assert_eq!(out.output_meta, Some("metadata".to_owned()));
+        assert_eq!(out.abi, Some("solc".to_owned()));
+        assert!(out.abstract_abi);
+        assert_eq!(out.costs, Some("json".to_owned()));
+        assert_eq!(out.stack_usage, Some("table".to_owned()));
+        assert_eq!(out.heap_allocations, Some("json".to_owned()));
+        assert!(out.constructor_args);
+        assert_eq!(out.size_report, Some("table".to_owned()));
+        assert!(out.watch);
+        assert!(!out.watch_json);
+        assert_eq!(out.message_format, Some("sarif".to_owned()));
 
         let default_out: cli::CompilerOutput = toml::from_str("").unwrap();
 
         assert!(!default_out.verbose);
         assert!(!default_out.std_json_output);
+        assert_eq!(default_out.abi, None);
+        assert!(!default_out.abstract_abi);
+        assert_eq!(default_out.costs, None);
+        assert_eq!(default_out.stack_usage, None);
+        assert_eq!(default_out.heap_allocations, None);
+        assert!(!default_out.constructor_args);
+        assert_eq!(default_out.size_report, None);
+        assert!(!default_out.watch);
+        assert_eq!(default_out.message_format, None);
+
+        assert!(toml::from_str::<cli::CompilerOutput>(r#"message-format = "xml""#).is_err());
+        assert!(toml::from_str::<cli::CompilerOutput>(r#"costs = "csv""#).is_err());
+        assert!(toml::from_str::<cli::CompilerOutput>(r#"stack-usage = "csv""#).is_err());
+        assert!(toml::from_str::<cli::CompilerOutput>(r#"size-report = "csv""#).is_err());
     }
 
     #[test]
@@ -190,9 +231,23 @@ mod tests {
                 compiler_output: cli::CompilerOutput {
                     emit: None,
                     std_json_output: false,
+                    message_format: None,
                     output_directory: None,
                     output_meta: None,
-                    verbose: false
+                    verbose: false,
+                    abi: None,
+                    abstract_abi: false,
+                    costs: None,
+                    stack_usage: None,
+                    heap_allocations: None,
+                    embed_build_info: false,
+                    metadata: false,
+                    bundle: false,
+                    source_map: false,
+                    constructor_args: false,
+                    size_report: None,
+                    watch: false,
+                    watch_json: false
                 },
                 target_arg: cli::CompileTargetArg {
                     name: Some("solana".to_owned()),
@@ -202,7 +257,10 @@ mod tests {
                 debug_features: cli::DebugFeatures {
                     log_runtime_errors: true,
                     log_prints: true,
+                    revert_data: true,
                     generate_debug_info: false,
+                    coverage: false,
+                    trace: false,
                     release: false
                 },
                 optimizations: cli::Optimizations {
@@ -211,14 +269,25 @@ mod tests {
                     strength_reduce: true,
                     vector_to_slice: true,
                     common_subexpression_elimination: true,
+                    storage_cache: true,
+                    string_switch: true,
+                    loop_unroll: true,
+                    cold_split: true,
+                    strip_dead_storage: false,
                     opt_level: Some("aggressive".to_owned()),
+                    llvm_passes: None,
+                    inline_threshold: None,
+                    heap_allocator: None,
+                    account_discriminator: true,
                     #[cfg(feature = "wasm_opt")]
                     wasm_opt_passes: None
-                }
+                },
+                warnings: cli::Warnings::default(),
+                contract_overrides: HashMap::new(),
             }
         );
 
-        let command = "solang compile flipper.sol sesa.sol --config-file solang.toml --contract-authors not_sesa --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse -O aggressive".split(' ');
+        let command = "solang compile flipper.sol sesa.sol --config-file solang.toml --contract-authors not_sesa --target polkadot --value-length=31 --address-length=33 --no-dead-storage --no-constant-folding --no-strength-reduce --no-vector-to-slice --no-cse --no-storage-cache --no-string-switch --no-loop-unroll --no-cold-split --strip-dead-storage -O aggressive".split(' ');
 
         let matches = Cli::command().get_matches_from(command);
 
@@ -244,9 +313,23 @@ mod tests {
                 compiler_output: cli::CompilerOutput {
                     emit: None,
                     std_json_output: false,
+                    message_format: None,
                     output_directory: None,
                     output_meta: None,
-                    verbose: false
+                    verbose: false,
+                    abi: None,
+                    abstract_abi: false,
+                    costs: None,
+                    stack_usage: None,
+                    heap_allocations: None,
+                    embed_build_info: false,
+                    metadata: false,
+                    bundle: false,
+                    source_map: false,
+                    constructor_args: false,
+                    size_report: None,
+                    watch: false,
+                    watch_json: false
                 },
                 target_arg: cli::CompileTargetArg {
                     name: Some("polkadot".to_owned()),
@@ -256,7 +339,10 @@ mod tests {
                 debug_features: cli::DebugFeatures {
                     log_runtime_errors: true,
                     log_prints: true,
+                    revert_data: true,
                     generate_debug_info: false,
+                    coverage: false,
+                    trace: false,
                     release: false
                 },
                 optimizations: cli::Optimizations {
@@ -265,11 +351,370 @@ mod tests {
                     strength_reduce: false,
                     vector_to_slice: false,
                     common_subexpression_elimination: false,
+                    storage_cache: false,
+                    string_switch: false,
+                    loop_unroll: false,
+                    cold_split: false,
+                    strip_dead_storage: true,
                     opt_level: Some("aggressive".to_owned()),
+                    llvm_passes: None,
+                    inline_threshold: None,
+                    heap_allocator: None,
+                    account_discriminator: true,
                     #[cfg(feature = "wasm_opt")]
                     wasm_opt_passes: None
-                }
+                },
+                warnings: cli::Warnings::default(),
+                contract_overrides: HashMap::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn contract_overrides() {
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+        import_path = ["lib", "/usr/include/solidity"]
+        import_map = { "@openzeppelin" = "node_modules/@openzeppelin" }
+        version = "0.1.0"
+
+        [target]
+        name = "polkadot"
+
+        [compiler-output]
+        output_directory = "target"
+
+        [contract.flipper]
+        version = "1.0.0"
+        authors = ["sesa"]
+        llvm-IR-optimization-level = "none"
+        "#;
+
+        let mut compile_config: cli::Compile = toml::from_str(toml).unwrap();
+
+        let flipper = &compile_config.contract_overrides["flipper"];
+
+        assert_eq!(flipper.version, Some("1.0.0".to_owned()));
+        assert_eq!(flipper.authors, Some(vec!["sesa".to_owned()]));
+
+        let opt = flipper.options(&Options::default());
+
+        assert_eq!(opt.opt_level, solang::codegen::OptimizationLevel::None);
+
+        compile_config.relative_to(Path::new("/project"));
+
+        assert_eq!(
+            compile_config.package.input,
+            Some(vec![PathBuf::from("/project/flipper.sol")])
+        );
+        assert_eq!(
+            compile_config.package.import_path,
+            Some(vec![
+                PathBuf::from("/project/lib"),
+                PathBuf::from("/usr/include/solidity")
+            ])
+        );
+        assert_eq!(
+            compile_config.package.import_map,
+            Some(vec![(
+                "@openzeppelin".to_owned(),
+                PathBuf::from("/project/node_modules/@openzeppelin")
+            )])
+        );
+        assert_eq!(
+            compile_config.compiler_output.output_directory,
+            Some("/project/target".to_owned())
+        );
+
+        let bad = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [target]
+        name = "polkadot"
+
+        [contract.flipper]
+        llvm-IR-optimization-level = "fast"
+        "#;
+
+        assert!(toml::from_str::<cli::Compile>(bad).is_err());
+    }
+
+    #[test]
+    fn warning_flags() {
+        let command = "solang compile flipper.sol --target polkadot --deny warnings --warn unused --allow unused-parameter,shadowing".split(' ');
+
+        let cli = Cli::parse_from(command);
+
+        let Commands::Compile(compile_args) = cli.command else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            compile_args.warnings,
+            cli::Warnings {
+                deny: Some(vec!["warnings".to_owned()]),
+                warn: Some(vec!["unused".to_owned()]),
+                allow: Some(vec!["unused-parameter".to_owned(), "shadowing".to_owned()]),
+                strict_conversions: false,
+            }
+        );
+
+        let config = warnings_arg(&compile_args.warnings);
+
+        assert_eq!(config.level(WarningCode::Deprecated), Some(Level::Error));
+        assert_eq!(
+            config.level(WarningCode::UnusedVariable),
+            Some(Level::Warning)
+        );
+        assert_eq!(config.level(WarningCode::UnusedParameter), None);
+        assert_eq!(config.level(WarningCode::Shadowing), None);
+        assert_eq!(config.level(WarningCode::Redefinition), Some(Level::Error));
+
+        let command = "solang compile flipper.sol --target polkadot --deny unused-foo".split(' ');
+
+        assert!(Cli::try_parse_from(command).is_err());
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [target]
+        name = "polkadot"
+
+        [warnings]
+        deny = ["unused"]
+        allow = ["unused-event"]
+        strict-conversions = true
+        "#;
+
+        let compile_config: cli::Compile = toml::from_str(toml).unwrap();
+        let config = warnings_arg(&compile_config.warnings);
+
+        assert!(config.strict_conversions);
+
+        assert_eq!(config.level(WarningCode::UnusedStorage), Some(Level::Error));
+        assert_eq!(config.level(WarningCode::UnusedEvent), None);
+        assert_eq!(config.level(WarningCode::Mutability), Some(Level::Warning));
+    }
+
+    #[test]
+    fn llvm_pipeline_flags() {
+        let command = "solang compile flipper.sol --target solana --emit llvm-ir-per-pass --llvm-passes mem2reg,function(sroa,instcombine)".split(' ');
+
+        let cli = Cli::parse_from(command);
+
+        let Commands::Compile(compile_args) = cli.command else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            compile_args.compiler_output.emit.as_deref(),
+            Some("llvm-ir-per-pass")
+        );
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert_eq!(
+            opt.llvm_passes.as_deref(),
+            Some("mem2reg,function(sroa,instcombine)")
+        );
+        assert_eq!(opt.inline_threshold, None);
+
+        let command = "solang compile flipper.sol --target solana --inline-threshold 50".split(' ');
+
+        let Commands::Compile(compile_args) = Cli::parse_from(command).command else {
+            unreachable!()
+        };
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert_eq!(opt.llvm_passes, None);
+        assert_eq!(opt.inline_threshold, Some(50));
+
+        // unbalanced pipeline
+        let command =
+            "solang compile flipper.sol --target solana --llvm-passes function(sroa".split(' ');
+
+        assert!(Cli::try_parse_from(command).is_err());
+
+        // the inline threshold is for the default pipeline
+        let command =
+            "solang compile flipper.sol --target solana --llvm-passes inline --inline-threshold 50"
+                .split(' ');
+
+        assert!(Cli::try_parse_from(command).is_err());
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [target]
+        name = "solana"
+
+        [optimizations]
+        llvm-passes = "default<O2>"
+        inline-threshold = 100
+        "#;
+
+        let compile_config: cli::Compile = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            compile_config.optimizations.llvm_passes.as_deref(),
+            Some("default<O2>")
+        );
+        assert_eq!(compile_config.optimizations.inline_threshold, Some(100));
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [optimizations]
+        llvm-passes = "inline,"
+        "#;
+
+        assert!(toml::from_str::<cli::Compile>(toml).is_err());
+    }
+
+    #[test]
+    fn heap_allocator_flag() {
+        let command = "solang compile flipper.sol --target solana".split(' ');
+
+        let Commands::Compile(compile_args) = Cli::parse_from(command).command else {
+            unreachable!()
+        };
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert_eq!(opt.heap_allocator, HeapAllocator::Bump);
+
+        let command =
+            "solang compile flipper.sol --target solana --heap-allocator free-list".split(' ');
+
+        let Commands::Compile(compile_args) = Cli::parse_from(command).command else {
+            unreachable!()
+        };
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert_eq!(opt.heap_allocator, HeapAllocator::FreeList);
+
+        let command =
+            "solang compile flipper.sol --target solana --heap-allocator buddy".split(' ');
+
+        assert!(Cli::try_parse_from(command).is_err());
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [optimizations]
+        heap-allocator = "free-list"
+        "#;
+
+        let compile_config: cli::Compile = toml::from_str(toml).unwrap();
+
+        assert_eq!(
+            compile_config.optimizations.heap_allocator.as_deref(),
+            Some("free-list")
+        );
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [optimizations]
+        heap-allocator = "buddy"
+        "#;
+
+        assert!(toml::from_str::<cli::Compile>(toml).is_err());
+    }
+
+    #[test]
+    fn account_discriminator_flag() {
+        let command = "solang compile flipper.sol --target solana".split(' ');
+
+        let Commands::Compile(compile_args) = Cli::parse_from(command).command else {
+            unreachable!()
+        };
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert!(opt.account_discriminator);
+
+        let command =
+            "solang compile flipper.sol --target solana --no-account-discriminator".split(' ');
+
+        let Commands::Compile(compile_args) = Cli::parse_from(command).command else {
+            unreachable!()
+        };
+
+        let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+
+        assert!(!opt.account_discriminator);
+
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [optimizations]
+        account-discriminator = false
+        "#;
+
+        let compile_config: cli::Compile = toml::from_str(toml).unwrap();
+
+        assert!(!compile_config.optimizations.account_discriminator);
+    }
+
+    #[test]
+    fn lint_settings() {
+        let toml = r#"
+        [package]
+        input_files = ["flipper.sol"]
+
+        [target]
+        name = "polkadot"
+
+        [lint]
+        fail-on = "warning"
+
+        [lint.rules]
+        tx-origin = "error"
+        reentrancy = "off"
+        "#;
+
+        let mut settings = toml::from_str::<cli::LintConfigFile>(toml).unwrap().lint;
+
+        // the lint section does not stop the file being used for compiling
+        assert!(toml::from_str::<cli::Compile>(toml).is_ok());
+
+        let command = "solang lint flipper.sol --target polkadot --rule reentrancy=warning --rule unchecked-call=error --format sarif".split(' ');
+
+        if let Commands::Lint(lint_args) = Cli::parse_from(command).command {
+            settings.overwrite_with_args(&lint_args);
+        } else {
+            unreachable!();
+        }
+
+        assert_eq!(
+            settings,
+            cli::LintSettings {
+                rules: HashMap::from([
+                    ("tx-origin".to_owned(), "error".to_owned()),
+                    ("reentrancy".to_owned(), "warning".to_owned()),
+                    ("unchecked-call".to_owned(), "error".to_owned()),
+                ]),
+                format: Some("sarif".to_owned()),
+                fail_on: Some("warning".to_owned()),
             }
         );
+
+        let bad = r#"
+        [lint]
+        fail_on = "warning"
+        "#;
+
+        assert!(toml::from_str::<cli::LintConfigFile>(bad).is_err());
     }
 }
@@ -10,6 +10,8 @@ use std::path::Path;
 use solang::sema::ast;
 use solang_parser::pt;
 
+mod storage;
+
 #[derive(Serialize)]
 struct Field<'a> {
     name: &'a str,
@@ -85,6 +87,8 @@ struct Contract<'a> {
     functions: Vec<Function<'a>>,
     base_functions: Vec<Function<'a>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<storage::Storage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     title: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     notice: Option<&'a str>,
@@ -155,8 +159,9 @@ fn get_tag_no<'a>(name: &str, no: usize, tags: &'a [ast::Tag]) -> Option<&'a str
 }
 
 /// Generate documentation from the doccomments. This may be replaced with force-doc
-/// one day (once it exists)
-pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool) {
+/// one day (once it exists). If `storage` is set, the namespaces must have been through codegen
+/// and the storage layout of each contract is included.
+pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], storage: bool, verbose: bool) {
     let mut top = Top {
         contracts: Vec::new(),
         events: Vec::new(),
@@ -386,6 +391,11 @@ pub fn generate_docs(outdir: &OsString, files: &[ast::Namespace], verbose: bool)
                 base_variables,
                 functions,
                 base_functions,
+                storage: if storage {
+                    storage::gen_storage(contract_no, file)
+                } else {
+                    None
+                },
             });
         }
     }
@@ -433,6 +443,22 @@ Base contract: {{base_contract}}<p>
 {{#if dev}}Development note: {{dev}}<p>{{/if}}
 {{#if author}}Author: {{author}}<p>{{/if}}
 {{/each}}
+{{#if storage}}
+<h4>Storage</h4>
+<table>
+{{#each storage.stats}}<tr><td>{{name}}</td><td>{{value}}</td></tr>
+{{/each}}</table>
+<p>
+<table border="1">
+<tr><th>{{storage.position}}</th><th colspan="32">bytes</th></tr>
+{{#each storage.rows}}<tr><td>{{position}}</td>{{#each cells}}<td colspan="{{span}}"{{#unless used}} bgcolor="lightgrey"{{/unless}}>{{label}}</td>{{/each}}</tr>
+{{/each}}</table>
+<p>
+<table border="1">
+<tr><th>variable</th><th>type</th><th>{{storage.position}}</th><th>bytes</th><th>elements</th></tr>
+{{#each storage.variables}}<tr><td>{{contract}}.{{name}}</td><td><code>{{ty}}</code></td><td>{{position}}</td><td>{{bytes}}</td><td>{{#if elements}}{{elements}}{{/if}}</td></tr>
+{{/each}}</table>
+{{/if}}
 {{/each}}
 <h2>Events</h2>
 {{#each events}}
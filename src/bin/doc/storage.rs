@@ -0,0 +1,409 @@
+// SPDX-License-Identifier: Apache-2.0
+
+// The storage section of the documentation: a map of the storage slots or account data, where
+// each variable is, and how the elements of mappings and arrays are found
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use solang::codegen::SOLANA_FIRST_OFFSET;
+use solang::sema::ast::{self, ArrayLength, Mapping, Type};
+use solang::Target;
+use std::mem;
+
+#[derive(Serialize)]
+pub struct Storage {
+    /// What the rows of the slot map are, `slot` or `offset`
+    position: &'static str,
+    stats: Vec<Stat>,
+    rows: Vec<Row>,
+    variables: Vec<StorageVariable>,
+}
+
+#[derive(Serialize)]
+struct Stat {
+    name: &'static str,
+    value: String,
+}
+
+/// A row of the slot map, which is a storage slot or 32 bytes of account data
+#[derive(Serialize)]
+struct Row {
+    position: String,
+    cells: Vec<Cell>,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    label: String,
+    span: u64,
+    used: bool,
+}
+
+#[derive(Serialize)]
+struct StorageVariable {
+    name: String,
+    contract: String,
+    ty: String,
+    position: String,
+    bytes: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elements: Option<String>,
+}
+
+/// Generate the storage section for a contract. The contract must have been through codegen,
+/// since that is where the layout is determined.
+pub fn gen_storage(contract_no: usize, ns: &ast::Namespace) -> Option<Storage> {
+    let contract = &ns.contracts[contract_no];
+
+    if contract.layout.is_empty() {
+        return None;
+    }
+
+    let storage = if ns.target == Target::Solana {
+        account_storage(contract, ns)
+    } else {
+        slot_storage(contract, ns)
+    };
+
+    Some(storage)
+}
+
+/// On Solana, the variables are at an offset in the account data
+fn account_storage(contract: &ast::Contract, ns: &ast::Namespace) -> Storage {
+    let mut variables = Vec::new();
+    let mut segments = vec![(0, SOLANA_FIRST_OFFSET, "header".to_owned(), true)];
+    let mut padding = 0;
+    let mut heap = false;
+
+    for layout in &contract.layout {
+        let (name, base) = variable_name(layout, ns);
+        let offset = to_u64(&layout.slot);
+        let size = to_u64(&layout.ty.storage_slots(ns));
+        let end = offset.saturating_add(size);
+        let elements = elements(&layout.ty, offset.to_string(), ns);
+
+        heap |= elements.is_some() && !is_fixed_array(&layout.ty, ns);
+
+        if let Some((_, last_end, _, _)) = segments.last() {
+            if *last_end < offset {
+                padding += offset - last_end;
+                segments.push((*last_end, offset, String::new(), false));
+            }
+        }
+
+        segments.push((offset, end, name.clone(), true));
+
+        variables.push(StorageVariable {
+            name,
+            contract: base,
+            ty: layout.ty.to_string(ns),
+            position: offset.to_string(),
+            bytes: byte_range(offset, size),
+            elements,
+        });
+    }
+
+    let mut rows = Vec::new();
+    let mut cells = Vec::new();
+    let mut row_start = 0;
+
+    for (start, end, label, used) in segments {
+        let mut start = start;
+
+        while start < end {
+            // a variable which covers several rows is shown as one row
+            if start == row_start && end - start >= 64 {
+                let whole = (end - start) / 32 * 32;
+
+                rows.push(Row {
+                    position: format!("{start}–{}", start + whole - 1),
+                    cells: vec![Cell {
+                        label: label.clone(),
+                        span: 32,
+                        used,
+                    }],
+                });
+
+                start += whole;
+                row_start += whole;
+                continue;
+            }
+
+            let row_end = row_start + 32;
+            let cell_end = end.min(row_end);
+
+            cells.push(Cell {
+                label: label.clone(),
+                span: cell_end - start,
+                used,
+            });
+
+            start = cell_end;
+
+            if start == row_end {
+                rows.push(Row {
+                    position: row_start.to_string(),
+                    cells: mem::take(&mut cells),
+                });
+                row_start = row_end;
+            }
+        }
+    }
+
+    if !cells.is_empty() {
+        rows.push(Row {
+            position: row_start.to_string(),
+            cells,
+        });
+    }
+
+    let space = contract
+        .constructors(ns)
+        .first()
+        .and_then(|function_no| ns.functions[*function_no].annotations.space.as_ref())
+        .and_then(|(_, expr)| match expr {
+            ast::Expression::NumberLiteral { value, .. } => Some(value.to_string()),
+            _ => None,
+        });
+
+    let account_size = match space {
+        Some(space) => format!("{space} bytes, set with @space"),
+        None if heap => format!(
+            "at least {} bytes, plus the data on the account heap",
+            contract.fixed_layout_size
+        ),
+        None => format!("{} bytes", contract.fixed_layout_size),
+    };
+
+    Storage {
+        position: "offset",
+        stats: vec![
+            Stat {
+                name: "Variables",
+                value: variables.len().to_string(),
+            },
+            Stat {
+                name: "Fixed account data",
+                value: format!(
+                    "{} bytes, including the {SOLANA_FIRST_OFFSET} byte header",
+                    contract.fixed_layout_size
+                ),
+            },
+            Stat {
+                name: "Alignment padding",
+                value: format!("{padding} bytes"),
+            },
+            Stat {
+                name: "Estimated account size",
+                value: account_size,
+            },
+        ],
+        rows,
+        variables,
+    }
+}
+
+/// On other targets, the variables are in 32 byte storage slots. Small variables may share a slot.
+fn slot_storage(contract: &ast::Contract, ns: &ast::Namespace) -> Storage {
+    let mut variables = Vec::new();
+    let mut rows: Vec<Row> = Vec::new();
+    // the slot which is shared by the last variables, and the bytes used so far
+    let mut shared: Option<(BigInt, u64)> = None;
+    let mut shared_slots = 0;
+    let mut unused = 0;
+
+    for layout in &contract.layout {
+        let (name, base) = variable_name(layout, ns);
+        let slots = layout.ty.storage_slots(ns);
+
+        let (bytes, row) = if let Some(packed) = layout.packed {
+            let offset = u64::from(packed.offset);
+            let size = u64::from(layout.ty.packed_storage_bytes(ns).unwrap_or(32));
+
+            (byte_range(offset, size), Some((offset, size)))
+        } else if slots == BigInt::from(1) {
+            ("0–31".to_owned(), None)
+        } else {
+            (format!("{slots} slots"), None)
+        };
+
+        if let Some((slot, used)) = &shared {
+            if row.is_none() || *slot != layout.slot {
+                unused += 32 - used;
+                fill_cells(rows.last_mut().unwrap(), 32 - used);
+                shared = None;
+            }
+        }
+
+        match row {
+            Some((offset, size)) => {
+                if shared.is_none() {
+                    shared = Some((layout.slot.clone(), 0));
+                    shared_slots += 1;
+                    rows.push(Row {
+                        position: layout.slot.to_string(),
+                        cells: Vec::new(),
+                    });
+                }
+
+                let (_, used) = shared.as_mut().unwrap();
+                let row = rows.last_mut().unwrap();
+
+                fill_cells(row, offset - *used);
+                row.cells.push(Cell {
+                    label: name.clone(),
+                    span: size,
+                    used: true,
+                });
+
+                *used = offset + size;
+            }
+            None => {
+                let position = if slots > BigInt::from(1) {
+                    format!("{}–{}", layout.slot, &layout.slot + &slots - 1)
+                } else {
+                    layout.slot.to_string()
+                };
+
+                rows.push(Row {
+                    position,
+                    cells: vec![Cell {
+                        label: name.clone(),
+                        span: 32,
+                        used: true,
+                    }],
+                });
+            }
+        }
+
+        variables.push(StorageVariable {
+            name,
+            contract: base,
+            ty: layout.ty.to_string(ns),
+            position: layout.slot.to_string(),
+            bytes,
+            elements: elements(&layout.ty, layout.slot.to_string(), ns),
+        });
+    }
+
+    if let Some((_, used)) = shared {
+        unused += 32 - used;
+        fill_cells(rows.last_mut().unwrap(), 32 - used);
+    }
+
+    Storage {
+        position: "slot",
+        stats: vec![
+            Stat {
+                name: "Variables",
+                value: variables.len().to_string(),
+            },
+            Stat {
+                name: "Storage slots",
+                value: contract.fixed_layout_size.to_string(),
+            },
+            Stat {
+                name: "Slots shared by several variables",
+                value: shared_slots.to_string(),
+            },
+            Stat {
+                name: "Unused bytes in shared slots",
+                value: unused.to_string(),
+            },
+        ],
+        rows,
+        variables,
+    }
+}
+
+/// Bytes in a shared slot which no variable uses
+fn fill_cells(row: &mut Row, span: u64) {
+    if span > 0 {
+        row.cells.push(Cell {
+            label: String::new(),
+            span,
+            used: false,
+        });
+    }
+}
+
+/// The name of the variable and the contract which declares it
+fn variable_name(layout: &ast::Layout, ns: &ast::Namespace) -> (String, String) {
+    let contract = &ns.contracts[layout.contract_no];
+
+    (
+        contract.variables[layout.var_no].name.clone(),
+        contract.id.name.clone(),
+    )
+}
+
+/// Where the elements of a mapping or array are, given where the variable is
+fn elements(ty: &Type, base: String, ns: &ast::Namespace) -> Option<String> {
+    if ns.target == Target::Solana {
+        match ty {
+            Type::Mapping(..) => Some(format!(
+                "hash table with buckets at {base}, entries on the account heap"
+            )),
+            Type::String | Type::DynamicBytes => {
+                Some(format!("account heap, at the offset stored at {base}"))
+            }
+            Type::Array(_, dims) if dims.last() == Some(&ArrayLength::Dynamic) => {
+                Some(format!("account heap, at the offset stored at {base}"))
+            }
+            Type::Array(..) if ty.is_sparse_solana(ns) => Some(format!(
+                "hash table with buckets at {base}, entries on the account heap"
+            )),
+            Type::Array(..) => Some(format!(
+                "{base} + index × {}",
+                ty.array_elem().storage_slots(ns)
+            )),
+            _ => None,
+        }
+    } else {
+        match ty {
+            Type::Mapping(Mapping {
+                key_name, value, ..
+            }) => {
+                let key = key_name.as_ref().map_or("key", |id| id.name.as_str());
+                let elem = format!("keccak256({base} . {key})");
+
+                elements(value, elem.clone(), ns).or(Some(elem))
+            }
+            Type::Array(_, dims) if dims.last() == Some(&ArrayLength::Dynamic) => Some(format!(
+                "length at {base}, elements at keccak256({base}){}",
+                index_offset(&ty.array_elem(), ns)
+            )),
+            Type::Array(..) => Some(format!("{base}{}", index_offset(&ty.array_elem(), ns))),
+            Type::String | Type::DynamicBytes => Some(format!("stored as one value at {base}")),
+            _ => None,
+        }
+    }
+}
+
+fn index_offset(elem: &Type, ns: &ast::Namespace) -> String {
+    let slots = elem.storage_slots(ns);
+
+    if slots == BigInt::from(1) {
+        " + index".to_owned()
+    } else {
+        format!(" + index × {slots}")
+    }
+}
+
+/// Fixed length arrays which are stored in the account data itself
+fn is_fixed_array(ty: &Type, ns: &ast::Namespace) -> bool {
+    matches!(ty, Type::Array(_, dims) if dims.last() != Some(&ArrayLength::Dynamic))
+        && !ty.is_sparse_solana(ns)
+}
+
+fn byte_range(offset: u64, size: u64) -> String {
+    if size > 1 {
+        format!("{offset}–{}", offset.saturating_add(size - 1))
+    } else {
+        offset.to_string()
+    }
+}
+
+fn to_u64(n: &BigInt) -> u64 {
+    n.to_u64().unwrap_or(u64::MAX)
+}
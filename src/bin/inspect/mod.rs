@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::Inspect;
+use serde_json::Value;
+use solang::inspect::{inspect_contract, inspect_elf, inspect_wasm, Inspection};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+/// Print what is in the artifacts produced by `solang compile`. The dispatch table comes from
+/// the metadata, which is in the `.contract` file on Polkadot, and in the Anchor IDL on Solana.
+/// The metadata is looked for next to the binary, unless it is given with `--metadata`.
+pub(crate) fn inspect(inspect_args: &Inspect) {
+    let mut inspections = Vec::new();
+
+    for filename in &inspect_args.input {
+        let inspection = match inspect_file(filename, inspect_args.metadata.as_deref()) {
+            Ok(inspection) => inspection,
+            Err(err) => {
+                eprintln!("{}: error: {err}", filename.display());
+                exit(1);
+            }
+        };
+
+        if inspect_args.format == "json" {
+            inspections.push(inspection);
+        } else {
+            print_inspection(filename, &inspection);
+        }
+    }
+
+    if inspect_args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&inspections).unwrap());
+    }
+}
+
+fn inspect_file(filename: &Path, metadata: Option<&Path>) -> Result<Inspection, String> {
+    let contents = fs::read(filename).map_err(|err| format!("cannot read file: {err}"))?;
+
+    if filename.extension().is_some_and(|ext| ext == "contract") {
+        return inspect_contract(&String::from_utf8_lossy(&contents));
+    }
+
+    let (mut inspection, metadata_ext) = if contents.starts_with(b"\0asm") {
+        (inspect_wasm(&contents)?, "contract")
+    } else if contents.starts_with(b"\x7fELF") {
+        (inspect_elf(&contents)?, "json")
+    } else {
+        return Err("not a wasm or ELF binary, or a .contract file".into());
+    };
+
+    let metadata_file = match metadata {
+        Some(metadata) => metadata.to_path_buf(),
+        None => {
+            let sibling = filename.with_extension(metadata_ext);
+
+            if !sibling.exists() {
+                return Ok(inspection);
+            }
+
+            sibling
+        }
+    };
+
+    let json = read_metadata(&metadata_file)?;
+
+    if inspection.format == "wasm" {
+        inspection.add_ink_metadata(&json);
+    } else {
+        inspection.add_anchor_idl(&json);
+    }
+
+    Ok(inspection)
+}
+
+fn read_metadata(path: &PathBuf) -> Result<Value, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("cannot read metadata '{}': {err}", path.display()))?;
+
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("invalid metadata '{}': {err}", path.display()))
+}
+
+fn print_inspection(filename: &Path, inspection: &Inspection) {
+    println!(
+        "{}: {} binary, {} bytes",
+        filename.display(),
+        inspection.format,
+        inspection.size
+    );
+
+    if !inspection.metadata.is_empty() {
+        println!("metadata:");
+
+        for (key, value) in &inspection.metadata {
+            println!("  {key}: {value}");
+        }
+    }
+
+    if !inspection.dispatch.is_empty() {
+        println!("dispatch:");
+
+        for entry in &inspection.dispatch {
+            println!("  {:<11} {} {}", entry.kind, entry.selector, entry.name);
+        }
+    }
+
+    if let Some(info) = &inspection.build_info {
+        println!("build info:");
+        println!("  compiler: solang {}", info.compiler);
+        println!("  target: {}", info.target);
+        println!("  contract: {}", info.contract);
+        println!("  optimization level: {}", info.options.opt_level);
+
+        for source in &info.sources {
+            println!("  source: {} {}", source.keccak256, source.path);
+        }
+    }
+
+    if let Some(hash) = &inspection.metadata_hash {
+        println!("metadata hash:");
+        println!("  ipfs: {}", hash.ipfs);
+        println!("  compiler: solang {}", hash.solang);
+    }
+
+    println!("sections:");
+
+    let width = inspection
+        .sections
+        .iter()
+        .map(|section| section.name.len())
+        .max()
+        .unwrap_or(0);
+
+    for section in &inspection.sections {
+        println!("  {:<width$} {:>8}", section.name, section.size);
+    }
+
+    println!("exports:");
+
+    for export in &inspection.exports {
+        println!("  {export}");
+    }
+
+    println!("imports:");
+
+    for import in &inspection.imports {
+        match &import.module {
+            Some(module) => println!("  {module}.{}", import.name),
+            None => println!("  {}", import.name),
+        }
+    }
+}
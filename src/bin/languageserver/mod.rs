@@ -1,28 +1,35 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use forge_fmt::{format, parse, FormatterConfig};
 use itertools::Itertools;
 use num_traits::ToPrimitive;
 use rust_lapper::{Interval, Lapper};
+use serde::Deserialize;
 use serde_json::Value;
 use solang::{
-    codegen::{self, codegen, Expression},
+    codegen::{self, Expression},
     file_resolver::FileResolver,
-    parse_and_resolve,
+    formatter::{format_source, FormatError},
     sema::{
         ast::{self, RetrieveType, StructType, Type},
-        builtin::{get_prototype, BUILTIN_FUNCTIONS, BUILTIN_METHODS, BUILTIN_VARIABLE},
+        builtin::{self, get_prototype, BuiltinKind, Prototype, BUILTIN_VARIABLE},
         builtin_structs::BUILTIN_STRUCTS,
-        symtable,
+        symtable::{self, VariableUsage},
         tags::render,
+        warnings::{WarningCode, WarningConfig, STRICT_CONVERSION_NOTE},
     },
+    session::Session,
     Target,
 };
-use solang_parser::pt;
+use solang_parser::pt::{self, CodeLocation};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{hash_map::DefaultHasher, BTreeMap, BTreeSet, HashMap, HashSet},
     ffi::OsString,
-    path::PathBuf,
+    fs,
+    hash::{Hash, Hasher},
+    iter,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 use tokio::sync::Mutex;
 use tower_lsp::{
@@ -32,24 +39,37 @@ use tower_lsp::{
             GotoDeclarationParams, GotoDeclarationResponse, GotoImplementationParams,
             GotoImplementationResponse, GotoTypeDefinitionParams, GotoTypeDefinitionResponse,
         },
-        CompletionContext, CompletionItem, CompletionOptions, CompletionParams, CompletionResponse,
-        CompletionTriggerKind, DeclarationCapability, Diagnostic, DiagnosticRelatedInformation,
-        DiagnosticSeverity, DidChangeConfigurationParams, DidChangeTextDocumentParams,
-        DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams, DidCloseTextDocumentParams,
-        DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
-        ExecuteCommandOptions, ExecuteCommandParams, GotoDefinitionParams, GotoDefinitionResponse,
-        Hover, HoverContents, HoverParams, HoverProviderCapability,
+        CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+        CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+        CallHierarchyServerCapability, CodeAction, CodeActionKind, CodeActionOptions,
+        CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability, CodeActionResponse,
+        CompletionContext, CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams,
+        CompletionResponse, CompletionTriggerKind, ConfigurationItem, DeclarationCapability,
+        Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, DidChangeConfigurationParams,
+        DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams,
+        DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+        DocumentFormattingParams, DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse,
+        Documentation, ExecuteCommandOptions, ExecuteCommandParams, GotoDefinitionParams,
+        GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
         ImplementationProviderCapability, InitializeParams, InitializeResult, InitializedParams,
-        Location, MarkedString, MessageType, OneOf, Position, Range, ReferenceParams, RenameParams,
-        ServerCapabilities, SignatureHelpOptions, TextDocumentContentChangeEvent,
+        InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams, InsertTextFormat, Location,
+        MarkedString, MarkupContent, MarkupKind, MessageType, NumberOrString, OneOf,
+        ParameterInformation, ParameterLabel, Position, Range, ReferenceParams, Registration,
+        RenameParams, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+        SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
+        SemanticTokensParams, SemanticTokensResult, SemanticTokensServerCapabilities,
+        ServerCapabilities, SignatureHelp, SignatureHelpOptions, SignatureHelpParams,
+        SignatureInformation, SymbolInformation, SymbolKind, TextDocumentContentChangeEvent,
         TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit,
-        TypeDefinitionProviderCapability, Url, WorkspaceEdit, WorkspaceFoldersServerCapabilities,
-        WorkspaceServerCapabilities,
+        TypeDefinitionProviderCapability, TypeHierarchyItem, TypeHierarchyPrepareParams,
+        TypeHierarchyRegistrationOptions, TypeHierarchySubtypesParams,
+        TypeHierarchySupertypesParams, Url, WorkspaceEdit, WorkspaceFoldersServerCapabilities,
+        WorkspaceServerCapabilities, WorkspaceSymbolParams,
     },
     Client, LanguageServer, LspService, Server,
 };
 
-use crate::cli::{target_arg, LanguageServerCommand};
+use crate::cli::{target_arg, warning_config, LanguageServerCommand, Warnings};
 
 /// Represents the type of the code object that a reference points to
 /// Here "code object" refers to contracts, functions, structs, enums etc., that are defined and used within a namespace.
@@ -76,6 +96,8 @@ enum DefinitionType {
     Event(usize),
     UserType(usize),
     DynamicBytes,
+    // file index in Namespace::files, for import paths
+    File(usize),
 }
 
 /// Uniquely identifies a code object.
@@ -120,12 +142,180 @@ type Types = HashMap<DefinitionIndex, DefinitionIndex>;
 type Declarations = HashMap<DefinitionIndex, Vec<DefinitionIndex>>;
 /// Stores all the fields, variants, methods etc. defined for a code object
 type Properties = HashMap<DefinitionIndex, HashMap<String, Option<DefinitionIndex>>>;
+/// Stores why renaming a code object is unsafe
+type RenameHazards = HashMap<DefinitionIndex, String>;
+/// Stores how calls to functions are completed
+type Snippets = HashMap<DefinitionIndex, CallSnippet>;
+/// Stores the functions and contracts shown in the call and type hierarchies
+type HierarchyItems = HashMap<DefinitionIndex, DocumentSymbol>;
+/// Stores the functions called by each function, with the ranges of the calls. The functions
+/// called are stored as items, as they may be numbered differently in the namespace which
+/// analysed them last.
+type Calls = HashMap<DefinitionIndex, Vec<(CallHierarchyItem, Range)>>;
+/// Stores the contracts which each contract inherits from directly
+type Bases = HashMap<DefinitionIndex, Vec<TypeHierarchyItem>>;
+
+/// The signatures of the functions, constructors, events and errors which can be called, by name.
+/// Builtins are listed with their namespace, e.g. `abi.encode`.
+type Signatures = HashMap<String, Vec<SignatureInformation>>;
+
+/// The types of semantic tokens, in the order of the legend sent to the client
+const TOKEN_TYPES: [SemanticTokenType; 14] = [
+    SemanticTokenType::CLASS,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::ENUM,
+    SemanticTokenType::ENUM_MEMBER,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::EVENT,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::MODIFIER,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+];
+
+/// The modifiers of semantic tokens, in the order of the legend sent to the client. The data
+/// location of variables and the kind of read-only variables are not standard modifiers.
+const TOKEN_MODIFIERS: [SemanticTokenModifier; 7] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    SemanticTokenModifier::new("constant"),
+    SemanticTokenModifier::new("immutable"),
+    SemanticTokenModifier::new("storage"),
+    SemanticTokenModifier::new("memory"),
+];
+
+/// A token to highlight, with its type and modifiers as indices into the legend
+#[derive(Clone, Debug)]
+struct TokenEntry {
+    start: usize,
+    /// The end of the token, exclusive
+    end: usize,
+    token_type: u32,
+    modifiers: u32,
+}
+
+impl TokenEntry {
+    fn new(
+        start: usize,
+        end: usize,
+        token_type: SemanticTokenType,
+        modifiers: &[SemanticTokenModifier],
+    ) -> Self {
+        TokenEntry {
+            start,
+            end,
+            // the legend has every type and modifier used
+            token_type: TOKEN_TYPES.iter().position(|ty| *ty == token_type).unwrap() as u32,
+            modifiers: modifiers
+                .iter()
+                .map(|modifier| TOKEN_MODIFIERS.iter().position(|m| m == modifier).unwrap())
+                .fold(0, |bits, no| bits | 1 << no),
+        }
+    }
+}
+
+/// The completion of a call to a function, with a placeholder for each argument
+#[derive(Clone, Debug)]
+struct CallSnippet {
+    /// The signature of the function, shown next to the completion
+    detail: String,
+    /// The snippet inserted, e.g. `foo(${1:a}, ${2:b})`
+    snippet: String,
+}
+
+impl CallSnippet {
+    fn new(detail: String, name: &str, args: impl Iterator<Item = String>) -> Self {
+        // `$`, `}` and `\` have a meaning in snippets
+        let escape = |text: &str| {
+            text.replace('\\', "\\\\")
+                .replace('$', "\\$")
+                .replace('}', "\\}")
+        };
+
+        let args = args
+            .enumerate()
+            .map(|(no, arg)| format!("${{{}:{}}}", no + 1, escape(&arg)))
+            .join(", ");
+
+        CallSnippet {
+            detail,
+            snippet: format!("{}({args})", escape(name)),
+        }
+    }
+
+    fn completion_item(&self, label: String) -> CompletionItem {
+        CompletionItem {
+            label,
+            kind: Some(CompletionItemKind::FUNCTION),
+            detail: Some(self.detail.clone()),
+            insert_text: Some(self.snippet.clone()),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        }
+    }
+}
+
+/// A change which fixes a diagnostic, offered as a code action
+#[derive(Clone, Debug)]
+struct Fix {
+    title: String,
+    /// The range and message of the diagnostic which is fixed
+    range: Range,
+    message: String,
+    edits: Vec<TextEdit>,
+    /// Whether the fix is certain, so it can be applied along with the other fixes in the file
+    preferred: bool,
+}
+
+/// The command which renames a code object even if it is unsafe. The argument is the
+/// `RenameParams` of the rename request.
+const FORCE_RENAME_COMMAND: &str = "solang.forceRename";
+
+/// How long to wait for more changes to a file before analysing it, so that it is not analysed
+/// on every keystroke
+const DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// Stores information used by language server for every opened file
 #[derive(Default)]
 struct Files {
     caches: HashMap<PathBuf, FileCache>,
     text_buffers: HashMap<PathBuf, String>,
+    /// The symbols of every file seen in the workspace, for workspace symbol search. Unlike the
+    /// caches, these are kept when a file is closed.
+    symbols: HashMap<PathBuf, Vec<SymbolInformation>>,
+    /// The number of changes made to each open file, so that only the last of a burst of changes
+    /// analyses the file
+    changes: HashMap<PathBuf, usize>,
+    /// The files imported by each analysed file, directly or not
+    dependencies: HashMap<PathBuf, BTreeSet<PathBuf>>,
+    /// The hash of each analysed file and the open files it imports, as they were analysed
+    analysed: HashMap<PathBuf, u64>,
+}
+
+impl Files {
+    /// A hash of the contents of a file and of the open files it imports, which changes when the
+    /// file has to be analysed again
+    fn unit_hash(&self, path: &Path) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        for file in iter::once(path).chain(
+            self.dependencies
+                .get(path)
+                .into_iter()
+                .flatten()
+                .map(PathBuf::as_path),
+        ) {
+            file.hash(&mut hasher);
+            self.text_buffers.get(file).hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
 }
 
 #[derive(Debug)]
@@ -135,6 +325,30 @@ struct FileCache {
     references: Lapper<usize, DefinitionIndex>,
     scopes: Lapper<usize, Vec<(String, Option<DefinitionIndex>)>>,
     top_level_code_objects: HashMap<String, Option<DefinitionIndex>>,
+    /// The definitions of the code objects referenced in this file, from the namespace this file
+    /// was last resolved in
+    definitions: Definitions,
+    rename_hazards: RenameHazards,
+    /// The outline of the file
+    symbols: Vec<DocumentSymbol>,
+    /// The signatures which can be called from this file, for signature help
+    signatures: Signatures,
+    inlay_hints: Vec<InlayHint>,
+    semantic_tokens: Vec<SemanticToken>,
+    fixes: Vec<Fix>,
+    /// The range and message of the diagnostics about names which could not be resolved. These
+    /// may be fixed by importing a file of the workspace which declares the name.
+    unresolved: Vec<(Range, String)>,
+}
+
+impl FileCache {
+    /// The location of the definition of a code object referenced in this file
+    fn definition(&self, reference: &DefinitionIndex) -> Option<Location> {
+        let range = self.definitions.get(reference)?;
+        let uri = Url::from_file_path(&reference.def_path).ok()?;
+
+        Some(Location { uri, range: *range })
+    }
 }
 
 /// Stores information used by the language server to service requests (eg: `Go to Definitions`) received from the client.
@@ -150,6 +364,11 @@ struct FileCache {
 /// * `declarations` maps the `DefinitionIndex` of a `Contract` method to a list of methods that it overrides. The overridden methods belong to the parent `Contract`s
 /// * `implementations` maps the `DefinitionIndex` of a `Contract` to the `DefinitionIndex`s of methods defined as part of the `Contract`.
 /// * `properties` maps the `DefinitionIndex` of a code objects to the name and type of fields, variants or methods defined in the code object.
+/// * `snippets` maps the `DefinitionIndex` of a function to the snippet which completes a call to it.
+/// * `builtin_snippets` maps the name of a builtin function, e.g. `abi.encode`, to the snippet which completes a call to it.
+/// * `hierarchy_items` maps the `DefinitionIndex` of a function or contract to its symbol, as shown in the call and type hierarchies.
+/// * `calls` maps the `DefinitionIndex` of a function to the functions it calls and where it calls them.
+/// * `bases` maps the `DefinitionIndex` of a contract to the contracts it inherits from directly.
 #[derive(Default)]
 struct GlobalCache {
     definitions: Definitions,
@@ -157,6 +376,11 @@ struct GlobalCache {
     declarations: Declarations,
     implementations: Implementations,
     properties: Properties,
+    snippets: Snippets,
+    builtin_snippets: HashMap<String, CallSnippet>,
+    hierarchy_items: HierarchyItems,
+    calls: Calls,
+    bases: Bases,
 }
 
 impl GlobalCache {
@@ -166,6 +390,24 @@ impl GlobalCache {
         self.declarations.extend(other.declarations);
         self.implementations.extend(other.implementations);
         self.properties.extend(other.properties);
+        self.snippets.extend(other.snippets);
+        self.builtin_snippets.extend(other.builtin_snippets);
+
+        // the code objects are numbered differently in each namespace, so the hierarchies of the
+        // files which were analysed again are replaced rather than extended
+        let paths: HashSet<PathBuf> = other
+            .hierarchy_items
+            .keys()
+            .map(|di| di.def_path.clone())
+            .collect();
+        self.hierarchy_items
+            .retain(|di, _| !paths.contains(&di.def_path));
+        self.calls.retain(|di, _| !paths.contains(&di.def_path));
+        self.bases.retain(|di, _| !paths.contains(&di.def_path));
+
+        self.hierarchy_items.extend(other.hierarchy_items);
+        self.calls.extend(other.calls);
+        self.bases.extend(other.bases);
     }
 }
 
@@ -191,11 +433,166 @@ impl GlobalCache {
 // More information can be found here: https://github.com/hyperledger/solang/pull/1411
 pub struct SolangServer {
     client: Client,
+    /// The configuration given on the command line
+    args: Config,
+    /// The configuration files are analysed with, which is the configuration given on the
+    /// command line with the settings of the client
+    config: Mutex<Config>,
+    files: Mutex<Files>,
+    global_cache: Mutex<GlobalCache>,
+    /// The workspace folders, which are searched for Solidity files to index
+    workspace_folders: Mutex<Vec<PathBuf>>,
+    client_support: Mutex<ClientSupport>,
+    /// Files on disk are only read again once they have been modified
+    session: Session,
+}
+
+/// How files are analysed
+#[derive(Clone, Debug, PartialEq)]
+struct Config {
     target: Target,
     importpaths: Vec<PathBuf>,
     importmaps: Vec<(String, PathBuf)>,
-    files: Mutex<Files>,
-    global_cache: Mutex<GlobalCache>,
+    warnings: WarningConfig,
+}
+
+impl Config {
+    /// Imports are resolved against the directory of the importing file first, then the import
+    /// paths and maps
+    fn add_import_paths(&self, resolver: &mut FileResolver, dir: &Path) {
+        resolver.add_import_path(dir);
+
+        for path in &self.importpaths {
+            resolver.add_import_path(path);
+        }
+
+        for (map, path) in &self.importmaps {
+            resolver.add_import_map(OsString::from(map), PathBuf::from(path));
+        }
+    }
+
+    /// Complete the path of an import from the files on disk. Relative paths are resolved against
+    /// the directory of the importing file, and other paths against the import paths and maps.
+    fn import_path_completions(&self, file: &Path, prefix: &str) -> Vec<CompletionItem> {
+        // the directory part of the path which has been typed
+        let dir = prefix.rfind('/').map_or("", |pos| &prefix[..=pos]);
+        let file_dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let mut bases = Vec::new();
+
+        if prefix.starts_with("./") || prefix.starts_with("../") {
+            bases.push(file_dir.join(dir));
+        } else {
+            // the directory of the file is an import path too
+            bases.extend(
+                iter::once(&file_dir)
+                    .chain(&self.importpaths)
+                    .map(|path| path.join(dir)),
+            );
+
+            for (map, path) in &self.importmaps {
+                if let Some(rest) = dir
+                    .strip_prefix(map.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                {
+                    bases.push(path.join(rest));
+                }
+            }
+        }
+
+        let mut items = BTreeMap::new();
+
+        if dir.is_empty() {
+            for (map, _) in &self.importmaps {
+                items.insert(format!("{map}/"), CompletionItemKind::MODULE);
+            }
+        }
+
+        for base in bases {
+            let Ok(entries) = fs::read_dir(&base) else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                if name.starts_with('.') {
+                    continue;
+                }
+
+                if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                    items.insert(format!("{name}/"), CompletionItemKind::FOLDER);
+                } else if name.ends_with(".sol") {
+                    items.insert(name, CompletionItemKind::FILE);
+                }
+            }
+        }
+
+        items
+            .into_iter()
+            .map(|(label, kind)| CompletionItem {
+                label,
+                kind: Some(kind),
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// The `solang` settings of the client, e.g.
+/// `{ "target": "solana", "importPaths": ["lib"], "warnings": { "deny": ["unused"] } }`
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Settings {
+    target: Option<String>,
+    #[serde(default)]
+    import_paths: Vec<PathBuf>,
+    #[serde(default)]
+    import_maps: BTreeMap<String, PathBuf>,
+    #[serde(default)]
+    warnings: Warnings,
+}
+
+impl Settings {
+    /// The configuration given on the command line, with these settings. Import paths and maps
+    /// are added to those given on the command line.
+    fn config(self, args: &Config) -> std::result::Result<Config, String> {
+        let target = match &self.target {
+            // the address and value lengths of Polkadot are kept
+            Some(name) => match Target::from(name) {
+                Some(target) if target == args.target => args.target,
+                Some(target) => target,
+                None => return Err(format!("unknown target '{name}'")),
+            },
+            None => args.target,
+        };
+
+        Ok(Config {
+            target,
+            importpaths: args
+                .importpaths
+                .iter()
+                .cloned()
+                .chain(self.import_paths)
+                .collect(),
+            importmaps: args
+                .importmaps
+                .iter()
+                .cloned()
+                .chain(self.import_maps)
+                .collect(),
+            warnings: warning_config(&self.warnings)?,
+        })
+    }
+}
+
+/// What the client supports, from its capabilities
+#[derive(Default)]
+struct ClientSupport {
+    /// The type hierarchy can be registered once initialized
+    type_hierarchy_registration: bool,
+    /// The settings can be requested with `workspace/configuration`
+    configuration: bool,
 }
 
 #[tokio::main(flavor = "current_thread")]
@@ -218,15 +615,24 @@ pub async fn start_server(language_args: &LanguageServerCommand) -> ! {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let target = target_arg(&language_args.target);
+    let args = Config {
+        target: target_arg(&language_args.target),
+        importpaths,
+        importmaps,
+        warnings: WarningConfig::default(),
+    };
+
+    let target = args.target;
 
     let (service, socket) = LspService::new(|client| SolangServer {
         client,
-        target,
-        importpaths,
-        importmaps,
+        config: Mutex::new(args.clone()),
+        args,
         files: Mutex::new(Default::default()),
         global_cache: Mutex::new(Default::default()),
+        workspace_folders: Mutex::new(Vec::new()),
+        client_support: Mutex::new(Default::default()),
+        session: Session::new(target, Default::default()),
     });
 
     Server::new(stdin, stdout, socket).serve(service).await;
@@ -235,94 +641,276 @@ pub async fn start_server(language_args: &LanguageServerCommand) -> ! {
 }
 
 impl SolangServer {
-    /// Parse file
+    /// Parse file, unless neither the file nor the open files it imports have changed since it was
+    /// last parsed
     async fn parse_file(&self, uri: Url) {
-        let mut resolver = FileResolver::default();
-        for (path, contents) in &self.files.lock().await.text_buffers {
-            resolver.set_file_contents(path.to_str().unwrap(), contents.clone());
-        }
-        if let Ok(path) = uri.to_file_path() {
-            let dir = path.parent().unwrap();
+        let Ok(path) = uri.to_file_path() else {
+            return;
+        };
 
-            resolver.add_import_path(dir);
+        let config = self.config.lock().await.clone();
+        let session = self.session.with_target(config.target);
+        let mut resolver = session.file_resolver();
 
-            let mut diags = Vec::new();
+        let unit_hash = {
+            let files = self.files.lock().await;
+            let unit_hash = files.unit_hash(&path);
 
-            for path in &self.importpaths {
-                resolver.add_import_path(path);
+            if files.caches.contains_key(&path) && files.analysed.get(&path) == Some(&unit_hash) {
+                return;
             }
 
-            for (map, path) in &self.importmaps {
-                resolver.add_import_map(OsString::from(map), PathBuf::from(path));
+            for (path, contents) in &files.text_buffers {
+                resolver.set_file_contents(path.to_str().unwrap(), contents.clone());
             }
 
-            let os_str = path.file_name().unwrap();
+            unit_hash
+        };
+
+        config.add_import_paths(&mut resolver, path.parent().unwrap());
+
+        let mut diags = Vec::new();
+
+        let os_str = path.file_name().unwrap();
 
-            let mut ns = parse_and_resolve(os_str, &mut resolver, self.target);
+        let mut ns = ast::Namespace::new(session.target());
+        ns.strict_conversions = config.warnings.strict_conversions;
+
+        let mut ns = solang::parse_and_resolve_into(os_str, &mut resolver, ns);
+
+        // codegen all the contracts; some additional errors/warnings will be detected here
+        session.codegen(&mut ns);
+
+        diags.extend(ns.diagnostics.iter().filter_map(|diag| {
+            if diag.loc.file_no() != ns.top_file_no() {
+                // The first file is the one we wanted to parse; others are imported
+                return None;
+            }
 
-            // codegen all the contracts; some additional errors/warnings will be detected here
-            codegen(&mut ns, &Default::default());
+            // warnings are allowed or denied by their code in the settings
+            let (level, code) = if diag.level == ast::Level::Warning {
+                let code = WarningCode::of(diag);
+                (
+                    config.warnings.level(code)?,
+                    Some(NumberOrString::String(code.to_string())),
+                )
+            } else {
+                (diag.level.clone(), None)
+            };
 
-            diags.extend(ns.diagnostics.iter().filter_map(|diag| {
-                if diag.loc.file_no() != ns.top_file_no() {
-                    // The first file is the one we wanted to parse; others are imported
+            let severity = match level {
+                ast::Level::Info => Some(DiagnosticSeverity::INFORMATION),
+                ast::Level::Warning => Some(DiagnosticSeverity::WARNING),
+                ast::Level::Error => Some(DiagnosticSeverity::ERROR),
+                ast::Level::Debug => {
                     return None;
                 }
+            };
 
-                let severity = match diag.level {
-                    ast::Level::Info => Some(DiagnosticSeverity::INFORMATION),
-                    ast::Level::Warning => Some(DiagnosticSeverity::WARNING),
-                    ast::Level::Error => Some(DiagnosticSeverity::ERROR),
-                    ast::Level::Debug => {
-                        return None;
-                    }
-                };
+            let related_information = if diag.notes.is_empty() {
+                None
+            } else {
+                Some(
+                    diag.notes
+                        .iter()
+                        .map(|note| DiagnosticRelatedInformation {
+                            message: note.message.to_string(),
+                            location: Location {
+                                uri: Url::from_file_path(&ns.files[note.loc.file_no()].path)
+                                    .unwrap(),
+                                range: loc_to_range(&note.loc, &ns.files[ns.top_file_no()]),
+                            },
+                        })
+                        .collect(),
+                )
+            };
 
-                let related_information = if diag.notes.is_empty() {
-                    None
-                } else {
-                    Some(
-                        diag.notes
-                            .iter()
-                            .map(|note| DiagnosticRelatedInformation {
-                                message: note.message.to_string(),
-                                location: Location {
-                                    uri: Url::from_file_path(&ns.files[note.loc.file_no()].path)
-                                        .unwrap(),
-                                    range: loc_to_range(&note.loc, &ns.files[ns.top_file_no()]),
-                                },
-                            })
-                            .collect(),
-                    )
-                };
+            let range = loc_to_range(&diag.loc, &ns.files[ns.top_file_no()]);
+
+            Some(Diagnostic {
+                range,
+                message: diag.message.to_string(),
+                severity,
+                code,
+                related_information,
+                ..Default::default()
+            })
+        }));
+
+        let res = self.client.publish_diagnostics(uri, diags, None);
+
+        let (file_caches, global_cache) = Builder::new(&ns, &resolver).build();
+
+        let mut files = self.files.lock().await;
+
+        files.dependencies.insert(
+            path.clone(),
+            ns.files
+                .iter()
+                .filter(|file| file.cache_no.is_some() && file.path != path)
+                .map(|file| file.path.clone())
+                .collect(),
+        );
+        files.analysed.insert(path.clone(), unit_hash);
+
+        for (f, c) in ns.files.iter().zip(file_caches.into_iter()) {
+            if f.cache_no.is_some() {
+                if let Ok(uri) = Url::from_file_path(&f.path) {
+                    files
+                        .symbols
+                        .insert(f.path.clone(), symbol_information(&uri, &c.symbols, None));
+                }
+                files.caches.insert(f.path.clone(), c);
+            }
+        }
 
-                let range = loc_to_range(&diag.loc, &ns.files[ns.top_file_no()]);
+        let mut gc = self.global_cache.lock().await;
+        gc.extend(global_cache);
 
-                Some(Diagnostic {
-                    range,
-                    message: diag.message.to_string(),
-                    severity,
-                    related_information,
-                    ..Default::default()
+        res.await;
+    }
+
+    /// Parse the open files which import the given file, as their diagnostics may have changed
+    async fn parse_dependents(&self, path: &Path) {
+        let dependents = {
+            let files = self.files.lock().await;
+
+            files
+                .dependencies
+                .iter()
+                .filter(|(file, dependencies)| {
+                    files.text_buffers.contains_key(*file) && dependencies.contains(path)
                 })
-            }));
+                .filter_map(|(file, _)| Url::from_file_path(file).ok())
+                .collect_vec()
+        };
+
+        for uri in dependents {
+            self.parse_file(uri).await;
+        }
+    }
+
+    /// The `solang` settings of the client, if it can be asked for them
+    async fn fetch_settings(&self) -> Option<Value> {
+        if !self.client_support.lock().await.configuration {
+            return None;
+        }
+
+        let item = ConfigurationItem {
+            scope_uri: None,
+            section: Some("solang".to_string()),
+        };
+
+        self.client
+            .configuration(vec![item])
+            .await
+            .ok()?
+            .into_iter()
+            .next()
+    }
 
-            let res = self.client.publish_diagnostics(uri, diags, None);
+    /// Analyse files with the settings of the client. When the configuration changes, e.g. to
+    /// another target, all the open files are analysed again.
+    async fn apply_settings(&self, settings: Value) {
+        // there may be no settings at all
+        let settings = if settings.is_null() {
+            Value::Object(Default::default())
+        } else {
+            settings
+        };
+
+        let config = match serde_json::from_value::<Settings>(settings)
+            .map_err(|err| err.to_string())
+            .and_then(|settings| settings.config(&self.args))
+        {
+            Ok(config) => config,
+            Err(message) => {
+                self.client
+                    .show_message(
+                        MessageType::ERROR,
+                        format!("invalid solang settings: {message}"),
+                    )
+                    .await;
+                return;
+            }
+        };
 
-            let (file_caches, global_cache) = Builder::new(&ns).build();
+        {
+            let mut current = self.config.lock().await;
 
+            if *current == config {
+                return;
+            }
+
+            *current = config;
+        }
+
+        self.parse_open_files().await;
+    }
+
+    /// Analyse all the open files again, even if they have not changed
+    async fn parse_open_files(&self) {
+        let uris = {
             let mut files = self.files.lock().await;
-            for (f, c) in ns.files.iter().zip(file_caches.into_iter()) {
-                if f.cache_no.is_some() {
-                    files.caches.insert(f.path.clone(), c);
-                }
+            files.analysed.clear();
+            files
+                .text_buffers
+                .keys()
+                .filter_map(|path| Url::from_file_path(path).ok())
+                .collect_vec()
+        };
+
+        for uri in uris {
+            self.parse_file(uri).await;
+        }
+    }
+
+    /// Add the symbols of the Solidity files in the given folders to the workspace symbols.
+    /// Files which have already been indexed are skipped, as are open files.
+    async fn index_folders(&self, folders: &[PathBuf]) -> usize {
+        let config = self.config.lock().await.clone();
+        let mut paths = Vec::new();
+
+        for folder in folders {
+            solidity_files(folder, &mut paths);
+        }
+
+        let mut indexed = 0;
+
+        for path in paths {
+            if self.files.lock().await.symbols.contains_key(&path) {
+                continue;
             }
 
-            let mut gc = self.global_cache.lock().await;
-            gc.extend(global_cache);
+            let (Some(dir), Some(file_name), Ok(uri)) =
+                (path.parent(), path.file_name(), Url::from_file_path(&path))
+            else {
+                continue;
+            };
+
+            let session = self.session.with_target(config.target);
+            let mut resolver = session.file_resolver();
+            config.add_import_paths(&mut resolver, dir);
+
+            let ns = session.parse_and_resolve(file_name, &mut resolver);
+
+            // the file could not be read
+            let Some(top_file_no) = ns.files.iter().position(|file| file.cache_no.is_some()) else {
+                continue;
+            };
+
+            let symbols = document_symbols(&ns).swap_remove(top_file_no);
 
-            res.await;
+            self.files
+                .lock()
+                .await
+                .symbols
+                .insert(path, symbol_information(&uri, &symbols, None));
+
+            indexed += 1;
         }
+
+        indexed
     }
 
     /// Common code for goto_{definitions, implementations, declarations, type_definitions}
@@ -355,6 +943,74 @@ impl SolangServer {
         }
         Ok(None)
     }
+
+    /// Common code for rename and the force rename command. Unless forced, renaming is refused
+    /// when it could change the contract ABI or break overrides.
+    async fn rename_edit(
+        &self,
+        params: RenameParams,
+        force: bool,
+    ) -> Result<Option<WorkspaceEdit>> {
+        let uri = params.text_document_position.text_document.uri.clone();
+
+        // fetch the `DefinitionIndex` of the code object in question
+        let def_params: GotoDefinitionParams = GotoDefinitionParams {
+            text_document_position_params: params.text_document_position,
+            work_done_progress_params: params.work_done_progress_params,
+            partial_result_params: Default::default(),
+        };
+        let Some(reference) = self.get_reference_from_params(def_params).await? else {
+            return Ok(None);
+        };
+
+        // import paths are not symbols
+        if matches!(reference.def_type, DefinitionType::File(_)) {
+            return Ok(None);
+        }
+
+        let caches = &self.files.lock().await.caches;
+
+        let Some(cache) = uri.to_file_path().ok().and_then(|path| caches.get(&path)) else {
+            return Ok(None);
+        };
+
+        let Some(definition) = cache.definition(&reference) else {
+            return Ok(None);
+        };
+
+        if !force {
+            if let Some(hazard) = cache.rename_hazards.get(&reference) {
+                return Err(Error {
+                    code: ErrorCode::InvalidRequest,
+                    message: format!(
+                        "{hazard}; use the '{FORCE_RENAME_COMMAND}' command to rename it anyway"
+                    )
+                    .into(),
+                    data: None,
+                });
+            }
+        }
+
+        // the new name of the code object
+        let new_text = params.new_name;
+
+        // create `TextEdit` instances that represent the changes to be made for every occurrence of the old symbol
+        // these `TextEdit` objects are then grouped into separate list per source file to which they belong.
+        // References which span more than the name, like external function calls, cannot be
+        // renamed in place.
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+
+        for location in references_to(caches, &definition) {
+            if range_width(&location.range) == range_width(&definition.range) {
+                changes.entry(location.uri).or_default().push(TextEdit {
+                    range: location.range,
+                    new_text: new_text.clone(),
+                });
+            }
+        }
+
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
 }
 
 struct Builder<'a> {
@@ -369,25 +1025,50 @@ struct Builder<'a> {
     declarations: Declarations,
     implementations: Implementations,
     properties: Properties,
+    rename_hazards: RenameHazards,
+    snippets: Snippets,
+    hierarchy_items: HierarchyItems,
+    calls: Calls,
+    bases: Bases,
+    signatures: Signatures,
+    inlay_hints: Vec<(usize, InlayHint)>,
+    tokens: Vec<(usize, TokenEntry)>,
+    /// The declarations of local variables which can be removed without changing what the code
+    /// does, by the location of the name of the variable
+    removable_declarations: HashMap<pt::Loc, pt::Loc>,
+    /// The functions called by the function being traversed, and the locations of the calls
+    call_sites: Vec<(usize, pt::Loc)>,
 
     ns: &'a ast::Namespace,
+    resolver: &'a FileResolver,
 }
 
 impl<'a> Builder<'a> {
-    fn new(ns: &'a ast::Namespace) -> Self {
+    fn new(ns: &'a ast::Namespace, resolver: &'a FileResolver) -> Self {
         Self {
             hovers: Vec::new(),
             references: Vec::new(),
             scopes: Vec::new(),
             top_level_code_objects: Vec::new(),
+            inlay_hints: Vec::new(),
+            tokens: Vec::new(),
+            removable_declarations: HashMap::new(),
+            call_sites: Vec::new(),
 
             definitions: HashMap::new(),
             types: HashMap::new(),
             declarations: HashMap::new(),
             implementations: HashMap::new(),
             properties: HashMap::new(),
+            rename_hazards: HashMap::new(),
+            snippets: HashMap::new(),
+            hierarchy_items: HashMap::new(),
+            calls: HashMap::new(),
+            bases: HashMap::new(),
+            signatures: HashMap::new(),
 
             ns,
+            resolver,
         }
     }
 
@@ -405,6 +1086,26 @@ impl<'a> Builder<'a> {
                     self.expression(exp, symtab);
                 }
 
+                // evaluating the initializer must not have an effect
+                let removable = match expr {
+                    Some(expr) => matches!(
+                        **expr,
+                        ast::Expression::BoolLiteral { .. }
+                            | ast::Expression::BytesLiteral { .. }
+                            | ast::Expression::NumberLiteral { .. }
+                            | ast::Expression::RationalNumberLiteral { .. }
+                            | ast::Expression::Variable { .. }
+                            | ast::Expression::ConstantVariable { .. }
+                    ),
+                    None => true,
+                };
+
+                if removable {
+                    if let Some(var) = symtab.vars.get(var_no) {
+                        self.removable_declarations.insert(var.id.loc, *loc);
+                    }
+                }
+
                 let constant = self
                     .ns
                     .var_constants
@@ -438,7 +1139,7 @@ impl<'a> Builder<'a> {
                     HoverEntry {
                         start: param.loc.start(),
                         stop: param.loc.exclusive_end(),
-                        val: make_code_block(val),
+                        val: format!("{}{}", make_code_block(val), self.type_note(&param.ty)),
                     },
                 ));
                 if let Some(id) = &param.id {
@@ -555,10 +1256,15 @@ impl<'a> Builder<'a> {
             ast::Statement::Return(_, Some(expr)) => {
                 self.expression(expr, symtab);
             }
-            ast::Statement::Revert { args, .. } => {
+            ast::Statement::Revert { error_no, args, .. } => {
                 for arg in args {
                     self.expression(arg, symtab);
                 }
+
+                if let Some(error_no) = error_no {
+                    let ns = self.ns;
+                    self.argument_names(&ns.errors[*error_no].fields, args);
+                }
             }
             ast::Statement::Emit {
                 event_no,
@@ -613,6 +1319,9 @@ impl<'a> Builder<'a> {
                 for arg in args {
                     self.expression(arg, symtab);
                 }
+
+                let ns = self.ns;
+                self.argument_names(&ns.events[*event_no].fields, args);
             }
             ast::Statement::TryCatch(_, _, try_stmt) => {
                 self.expression(&try_stmt.expr, symtab);
@@ -801,6 +1510,7 @@ impl<'a> Builder<'a> {
                 ty,
                 left,
                 right,
+                ..
             } => {
                 self.hovers.push((
                     loc.file_no(),
@@ -905,7 +1615,7 @@ impl<'a> Builder<'a> {
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: make_code_block(val),
+                        val: format!("{}{}", make_code_block(val), self.type_note(ty)),
                     },
                 ));
 
@@ -922,15 +1632,19 @@ impl<'a> Builder<'a> {
                 ));
             }
             ast::Expression::ConstantVariable { loc, ty, contract_no, var_no } => {
-                let (contract, name) = if let Some(contract_no) = contract_no {
+                let (contract, variable) = if let Some(contract_no) = contract_no {
                     let contract = format!("{}.", self.ns.contracts[*contract_no].id);
-                    let name = &self.ns.contracts[*contract_no].variables[*var_no].name;
-                    (contract, name)
+                    let variable = &self.ns.contracts[*contract_no].variables[*var_no];
+                    (contract, variable)
                 } else {
                     let contract = String::new();
-                    let name = &self.ns.constants[*var_no].name;
-                    (contract, name)
+                    let variable = &self.ns.constants[*var_no];
+                    (contract, variable)
                 };
+                let mut tags = render(&variable.tags[..]);
+                if !tags.is_empty() {
+                    tags.push_str("\n\n");
+                }
                 let constant = self
                     .ns
                     .var_constants
@@ -938,13 +1652,13 @@ impl<'a> Builder<'a> {
                     .and_then(get_constants)
                     .map(|s| format!(" = {s}"))
                     .unwrap_or_default();
-                let val = format!("{} constant {}{}{}", ty.to_string(self.ns), contract, name, constant);
+                let val = format!("{} constant {}{}{}", ty.to_string(self.ns), contract, variable.name, constant);
                 self.hovers.push((
                     loc.file_no(),
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: make_code_block(val),
+                        val: format!("{}{}{}", tags, make_code_block(val), self.type_note(ty)),
                     },
                 ));
                 self.references.push((
@@ -961,14 +1675,24 @@ impl<'a> Builder<'a> {
             }
             ast::Expression::StorageVariable { loc, ty, contract_no, var_no } => {
                 let contract = &self.ns.contracts[*contract_no];
-                let name = &contract.variables[*var_no].name;
-                let val = format!("{} {}.{}", ty.to_string(self.ns), contract.id, name);
+                let variable = &contract.variables[*var_no];
+                let mut tags = render(&variable.tags[..]);
+                if !tags.is_empty() {
+                    tags.push_str("\n\n");
+                }
+                let val = format!("{} {}.{}", ty.to_string(self.ns), contract.id, variable.name);
                 self.hovers.push((
                     loc.file_no(),
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: make_code_block(val),
+                        val: format!(
+                            "{}{}{}{}",
+                            tags,
+                            make_code_block(val),
+                            self.storage_note(*contract_no, *var_no),
+                            self.type_note(ty)
+                        ),
                     },
                 ));
                 self.references.push((
@@ -1077,33 +1801,16 @@ impl<'a> Builder<'a> {
 
             ast::Expression::InternalFunction {id, function_no, ..} => {
                 let fnc = &self.ns.functions[*function_no];
-                let mut msg_tg = render(&fnc.tags[..]);
-                if !msg_tg.is_empty() {
-                    msg_tg.push_str("\n\n");
-                }
+                let val = self.function_hover(*function_no);
 
-                let params = fnc.params.iter().map(|parm| format!("{} {}", parm.ty.to_string(self.ns), parm.name_as_str())).join(", ");
-
-                let rets = fnc.returns.iter().map(|ret| {
-                        let mut msg = ret.ty.to_string(self.ns);
-                        if ret.name_as_str() != "" {
-                            msg = format!("{} {}", msg, ret.name_as_str());
-                        }
-                        msg
-                    }).join(", ");
-
-                let contract = fnc.contract_no.map(|contract_no| format!("{}.", self.ns.contracts[contract_no].id)).unwrap_or_default();
-
-                let val = format!("{} {}{}({}) returns ({})\n", fnc.ty, contract, fnc.id, params, rets);
-
-                let func_loc = id.identifiers.last().unwrap().loc;
+                let func_loc = id.identifiers.last().unwrap().loc;
 
                 self.hovers.push((
                     func_loc.file_no(),
                     HoverEntry {
                         start: func_loc.start(),
                         stop: func_loc.exclusive_end(),
-                        val: format!("{}{}", msg_tg, make_code_block(val)),
+                        val,
                     },
                 ));
                 self.references.push((
@@ -1117,6 +1824,15 @@ impl<'a> Builder<'a> {
                         },
                     },
                 ));
+
+                // `Library.func()` or `Base.func()`
+                if let (Some(contract_no), [.., qualifier, _]) =
+                    (fnc.contract_no, id.identifiers.as_slice())
+                {
+                    if qualifier.name == self.ns.contracts[contract_no].id.name {
+                        self.contract_reference(&qualifier.loc, contract_no);
+                    }
+                }
             }
 
             // Function call expression
@@ -1132,36 +1848,23 @@ impl<'a> Builder<'a> {
                 for arg in args {
                     self.expression(arg, symtab);
                 }
-            }
 
-            ast::Expression::ExternalFunction { loc, address, function_no, .. } => {
-                // modifiers do not have mutability, bases or modifiers themselves
-                let fnc = &self.ns.functions[*function_no];
-                let mut msg_tg = render(&fnc.tags[..]);
-                if !msg_tg.is_empty() {
-                    msg_tg.push_str("\n\n");
+                if let ast::Expression::InternalFunction { id, function_no, .. } = function.as_ref() {
+                    let ns = self.ns;
+                    self.argument_names(&ns.functions[*function_no].params, args);
+                    self.call_sites.push((*function_no, id.identifiers.last().unwrap().loc));
                 }
+            }
 
-                let params = fnc.params.iter().map(|parm| format!("{} {}", parm.ty.to_string(self.ns), parm.name_as_str())).join(", ");
-
-                let rets = fnc.returns.iter().map(|ret| {
-                        let mut msg = ret.ty.to_string(self.ns);
-                        if ret.name_as_str() != "" {
-                            msg = format!("{} {}", msg, ret.name_as_str());
-                        }
-                        msg
-                    }).join(", ");
-
-                let contract = fnc.contract_no.map(|contract_no| format!("{}.", self.ns.contracts[contract_no].id)).unwrap_or_default();
-
-                let val = format!("{} {}{}({}) returns ({})\n", fnc.ty, contract, fnc.id, params, rets);
+            ast::Expression::ExternalFunction { loc, address, function_no, .. } => {
+                let val = self.function_hover(*function_no);
 
                 self.hovers.push((
                     loc.file_no(),
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: format!("{}{}", msg_tg, make_code_block(val)),
+                        val,
                     },
                 ));
                 self.references.push((
@@ -1191,6 +1894,11 @@ impl<'a> Builder<'a> {
                 for arg in args {
                     self.expression(arg, symtab);
                 }
+                if let ast::Expression::ExternalFunction { loc, function_no, .. } = function.as_ref() {
+                    let ns = self.ns;
+                    self.argument_names(&ns.functions[*function_no].params, args);
+                    self.call_sites.push((*function_no, *loc));
+                }
                 if let Some(value) = &call_args.value {
                     self.expression(value, symtab);
                 }
@@ -1214,7 +1922,10 @@ impl<'a> Builder<'a> {
                 }
             }
             ast::Expression::Constructor {
-                args, call_args, ..
+                constructor_no,
+                args,
+                call_args,
+                ..
             } => {
                 if let Some(gas) = &call_args.gas {
                     self.expression(gas, symtab);
@@ -1222,6 +1933,10 @@ impl<'a> Builder<'a> {
                 for arg in args {
                     self.expression(arg, symtab);
                 }
+                if let Some(constructor_no) = constructor_no {
+                    let ns = self.ns;
+                    self.argument_names(&ns.functions[*constructor_no].params, args);
+                }
                 if let Some(optval) = &call_args.value {
                     self.expression(optval, symtab);
                 }
@@ -1231,6 +1946,9 @@ impl<'a> Builder<'a> {
                 if let Some(seeds) = &call_args.seeds {
                     self.expression(seeds, symtab);
                 }
+                if let Some(storage_deposit_limit) = &call_args.storage_deposit_limit {
+                    self.expression(storage_deposit_limit, symtab);
+                }
             }
             ast::Expression::Builtin { loc, kind, args, .. } => {
                 let (rets, name, params, doc) = if let Some(protval) = get_prototype(*kind) {
@@ -1257,6 +1975,10 @@ impl<'a> Builder<'a> {
                     },
                 ));
 
+                if let Some(prototype) = get_prototype(*kind) {
+                    self.builtin_token(loc, prototype);
+                }
+
                 for expr in args {
                     self.expression(expr, symtab);
                 }
@@ -1292,19 +2014,23 @@ impl<'a> Builder<'a> {
             variable.ty.to_string(self.ns),
             variable.name
         ));
+        let storage = contract_no
+            .map(|contract_no| self.storage_note(contract_no, var_no))
+            .unwrap_or_default();
+        let note = self.type_note(&variable.ty);
 
         if let Some(expr) = &variable.initializer {
             self.expression(expr, symtab);
         }
 
-        let file_no = variable.loc.file_no();
+        let file_no = variable.name_loc.file_no();
         let file = &self.ns.files[file_no];
         self.hovers.push((
             file_no,
             HoverEntry {
-                start: variable.loc.start(),
-                stop: variable.loc.start() + variable.name.len(),
-                val: format!("{tags}{val}"),
+                start: variable.name_loc.start(),
+                stop: variable.name_loc.exclusive_end(),
+                val: format!("{tags}{val}{storage}{note}"),
             },
         ));
 
@@ -1313,7 +2039,16 @@ impl<'a> Builder<'a> {
             def_type: DefinitionType::NonLocalVariable(contract_no, var_no),
         };
         self.definitions
-            .insert(di.clone(), loc_to_range(&variable.loc, file));
+            .insert(di.clone(), loc_to_range(&variable.name_loc, file));
+        if contract_no.is_some() && matches!(variable.visibility, pt::Visibility::Public(_)) {
+            self.rename_hazards.insert(
+                di.clone(),
+                format!(
+                    "state variable '{}' is public, so renaming it changes the contract ABI",
+                    variable.name
+                ),
+            );
+        }
         if let Some(dt) = get_type_definition(&variable.ty) {
             self.types.insert(di, dt.into());
         }
@@ -1372,8 +2107,435 @@ impl<'a> Builder<'a> {
         }
     }
 
+    /// A reference to a contract, e.g. a base contract or a library
+    fn contract_reference(&mut self, loc: &pt::Loc, contract_no: usize) {
+        self.references.push((
+            loc.file_no(),
+            ReferenceEntry {
+                start: loc.start(),
+                stop: loc.exclusive_end(),
+                val: DefinitionType::Contract(contract_no).into(),
+            },
+        ));
+    }
+
+    /// References to the library or the functions attached to a type with `using`
+    fn using(&mut self, using: &ast::Using) {
+        match &using.list {
+            ast::UsingList::Library(loc, library_no) => {
+                self.contract_reference(loc, *library_no);
+            }
+            ast::UsingList::Functions(functions) => {
+                for function in functions {
+                    self.references.push((
+                        function.loc.file_no(),
+                        ReferenceEntry {
+                            start: function.loc.start(),
+                            stop: function.loc.exclusive_end(),
+                            val: DefinitionType::Function(function.function_no).into(),
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
     /// Traverses namespace to extract information used later by the language server
     /// This includes hover messages, locations where code objects are declared and used
+    /// Add an inlay hint at an offset in a file. Parameter names go before the argument, and
+    /// other hints after the code they are about; type annotations like `: u256` are not padded.
+    fn inlay_hint(&mut self, file_no: usize, offset: usize, label: String, kind: InlayHintKind) {
+        let file = &self.ns.files[file_no];
+        let parameter = kind == InlayHintKind::PARAMETER;
+        let annotation = label.starts_with(':');
+
+        self.inlay_hints.push((
+            file_no,
+            InlayHint {
+                position: get_range(offset, offset, file).start,
+                label: InlayHintLabel::String(label),
+                kind: Some(kind),
+                text_edits: None,
+                tooltip: None,
+                padding_left: Some(!parameter && !annotation),
+                padding_right: Some(parameter),
+                data: None,
+            },
+        ));
+    }
+
+    /// Inlay hints with the names of the parameters, for the arguments which are literals
+    fn argument_names(&mut self, params: &[ast::Parameter<Type>], args: &[ast::Expression]) {
+        for (param, arg) in params.iter().zip(args) {
+            let Some(name) = param.id.as_ref().map(|id| &id.name) else {
+                continue;
+            };
+
+            let pt::Loc::File(file_no, start, _) = arg.loc() else {
+                continue;
+            };
+
+            let literal = matches!(
+                arg,
+                ast::Expression::BoolLiteral { .. }
+                    | ast::Expression::BytesLiteral { .. }
+                    | ast::Expression::NumberLiteral { .. }
+                    | ast::Expression::RationalNumberLiteral { .. }
+            );
+
+            // named arguments already have the name
+            let named = self
+                .contents(file_no)
+                .is_some_and(|text| text[..start].trim_end().ends_with(':'));
+
+            if literal && !named && !name.is_empty() {
+                self.inlay_hint(file_no, start, format!("{name}:"), InlayHintKind::PARAMETER);
+            }
+        }
+    }
+
+    /// Inlay hints for the data location of the parameters of reference types which do not have one,
+    /// and for the type of yul variables which are declared without one
+    fn function_inlay_hints(
+        &mut self,
+        params: &[ast::Parameter<Type>],
+        symtab: &symtable::Symtable,
+    ) {
+        for (param, var_no) in params.iter().zip(&symtab.arguments) {
+            let Some(var) = var_no.and_then(|var_no| symtab.vars.get(&var_no)) else {
+                continue;
+            };
+
+            if let (None, Some(pt::Loc::File(file_no, _, end))) =
+                (&var.storage_location, param.ty_loc)
+            {
+                if param.ty.can_have_data_location() {
+                    self.inlay_hint(file_no, end, "memory".into(), InlayHintKind::TYPE);
+                }
+            }
+        }
+
+        for var in symtab.vars.values() {
+            let pt::Loc::File(file_no, _, end) = var.id.loc else {
+                continue;
+            };
+
+            if !matches!(var.usage_type, VariableUsage::YulLocalVariable) {
+                continue;
+            }
+
+            // `let x: u32 := 1` has a type, `let x := 1` does not
+            let typed = self.contents(file_no).is_some_and(|text| {
+                let after = text[end..].trim_start();
+                after.starts_with(':') && !after.starts_with(":=")
+            });
+
+            if !typed {
+                let ty = match var.ty {
+                    Type::Uint(bits) => format!("u{bits}"),
+                    Type::Int(bits) => format!("s{bits}"),
+                    _ => var.ty.to_string(self.ns),
+                };
+
+                self.inlay_hint(file_no, end, format!(": {ty}"), InlayHintKind::TYPE);
+            }
+        }
+    }
+
+    /// The text of a file of the namespace
+    fn contents(&self, file_no: usize) -> Option<Arc<str>> {
+        self.ns.files[file_no]
+            .cache_no
+            .and_then(|cache_no| self.resolver.get_contents_of_file_no(cache_no))
+    }
+
+    /// Highlights the name of a builtin function or variable, e.g. `abi.encode` or `msg.sender`. Builtins
+    /// called as methods are not highlighted, as the location of the builtin starts at the object.
+    fn builtin_token(&mut self, loc: &pt::Loc, prototype: &Prototype) {
+        if !prototype.method.is_empty() {
+            return;
+        }
+
+        let name = match prototype.namespace {
+            Some(namespace) => format!("{namespace}.{}", prototype.name),
+            None => prototype.name.to_string(),
+        };
+
+        let Some(contents) = self.contents(loc.file_no()) else {
+            return;
+        };
+
+        if !contents
+            .get(loc.start()..)
+            .is_some_and(|text| text.starts_with(&name))
+        {
+            return;
+        }
+
+        let token = if BUILTIN_VARIABLE
+            .iter()
+            .any(|variable| variable.builtin == prototype.builtin)
+        {
+            TokenEntry::new(
+                loc.start(),
+                loc.start() + name.len(),
+                SemanticTokenType::VARIABLE,
+                &[
+                    SemanticTokenModifier::READONLY,
+                    SemanticTokenModifier::DEFAULT_LIBRARY,
+                ],
+            )
+        } else {
+            TokenEntry::new(
+                loc.start(),
+                loc.start() + name.len(),
+                SemanticTokenType::FUNCTION,
+                &[SemanticTokenModifier::DEFAULT_LIBRARY],
+            )
+        };
+
+        self.tokens.push((loc.file_no(), token));
+    }
+
+    /// Classifies the code objects referenced and declared in the files for semantic highlighting.
+    /// `declarations` are the locations of the declarations, as file number and offset.
+    fn semantic_tokens(&mut self, declarations: &HashSet<(usize, usize)>) {
+        let ns = self.ns;
+
+        let variables: HashMap<usize, &symtable::Variable> = ns
+            .functions
+            .iter()
+            .map(|func| &func.symtable)
+            .chain(ns.yul_functions.iter().map(|func| &func.symtable))
+            .flat_map(|symtab| symtab.vars.iter())
+            .map(|(var_no, var)| (*var_no, var))
+            .collect();
+
+        for (file_no, reference) in &self.references {
+            let Some((token_type, mut modifiers)) =
+                token_classification(&reference.val.def_type, ns, &variables)
+            else {
+                continue;
+            };
+
+            if declarations.contains(&(*file_no, reference.start)) {
+                modifiers.push(SemanticTokenModifier::DECLARATION);
+            }
+
+            // the stop of a reference is one past the end of the code object, and the location of
+            // a type may include its data location, e.g. `S storage`
+            let end = self
+                .contents(*file_no)
+                .and_then(|contents| {
+                    contents
+                        .get(reference.start..reference.stop - 1)?
+                        .find(char::is_whitespace)
+                })
+                .map_or(reference.stop - 1, |len| reference.start + len);
+
+            self.tokens.push((
+                *file_no,
+                TokenEntry::new(reference.start, end, token_type, &modifiers),
+            ));
+        }
+    }
+
+    /// The fixes of the diagnostics which can be fixed where they are: a missing `override` or override
+    /// list, and an unused local variable
+    fn fixes(&self) -> Vec<(usize, Fix)> {
+        let mut fixes = Vec::new();
+
+        for diag in self.ns.diagnostics.iter() {
+            let pt::Loc::File(file_no, start, end) = diag.loc else {
+                continue;
+            };
+
+            let Some(contents) = self.contents(file_no) else {
+                continue;
+            };
+
+            let file = &self.ns.files[file_no];
+            let edit = |start, end, new_text: String| TextEdit {
+                range: get_range(start, end, file),
+                new_text,
+            };
+
+            let fix = if diag.message.ends_with("should specify 'override'") {
+                params_end(&contents, start, end).map(|offset| {
+                    (
+                        "Add 'override'".to_string(),
+                        edit(offset, offset, " override".into()),
+                    )
+                })
+            } else if let Some(list) = override_list(&diag.message) {
+                if contents[start..end].starts_with("override") {
+                    Some((format!("Change to '{list}'"), edit(start, end, list.into())))
+                } else {
+                    params_end(&contents, start, end).map(|offset| {
+                        (
+                            format!("Add '{list}'"),
+                            edit(offset, offset, format!(" {list}")),
+                        )
+                    })
+                }
+            } else if diag.message.starts_with("local variable '")
+                && diag.message.contains("' is unused")
+            {
+                self.removable_declarations.get(&diag.loc).map(|decl| {
+                    let (start, end) = statement_extent(&contents, decl.start(), decl.end());
+                    (
+                        format!(
+                            "Remove unused variable '{}'",
+                            &contents[diag.loc.start()..diag.loc.end()]
+                        ),
+                        edit(start, end, String::new()),
+                    )
+                })
+            } else {
+                diag.notes
+                    .iter()
+                    .find_map(|note| explicit_conversion(&note.message))
+                    .map(|ty| {
+                        (
+                            format!("Convert explicitly to '{ty}'"),
+                            edit(start, end, format!("{ty}({})", &contents[start..end])),
+                        )
+                    })
+            };
+
+            if let Some((title, edit)) = fix {
+                fixes.push((
+                    file_no,
+                    Fix {
+                        title,
+                        range: loc_to_range(&diag.loc, file),
+                        message: diag.message.clone(),
+                        edits: vec![edit],
+                        preferred: true,
+                    },
+                ));
+            }
+        }
+
+        fixes
+    }
+
+    /// The signatures of the functions, constructors, events, errors and builtins, for signature help
+    fn signatures(&mut self) {
+        let ns = self.ns;
+
+        let param = |param: &ast::Parameter<Type>| {
+            let ty = param.ty.to_string(ns);
+
+            match &param.id {
+                Some(id) if param.indexed => format!("{ty} indexed {id}"),
+                Some(id) => format!("{ty} {id}"),
+                None if param.indexed => format!("{ty} indexed"),
+                None => ty,
+            }
+        };
+
+        for func in &ns.functions {
+            let name = match func.ty {
+                pt::FunctionTy::Constructor => match func.contract_no {
+                    Some(contract_no) => ns.contracts[contract_no].id.name.clone(),
+                    None => continue,
+                },
+                pt::FunctionTy::Function | pt::FunctionTy::Modifier => func.id.name.clone(),
+                pt::FunctionTy::Fallback | pt::FunctionTy::Receive => continue,
+            };
+
+            let contract = func
+                .contract_no
+                .filter(|_| func.ty != pt::FunctionTy::Constructor)
+                .map(|contract_no| format!("{}.", ns.contracts[contract_no].id))
+                .unwrap_or_default();
+
+            let returns = if func.returns.is_empty() {
+                String::new()
+            } else {
+                format!(" returns ({})", func.returns.iter().map(param).join(", "))
+            };
+
+            let signature = signature_information(
+                format!("{} {contract}{name}", func.ty),
+                func.params.iter().map(param),
+                &returns,
+                render(&func.tags),
+            );
+
+            self.signatures.entry(name).or_default().push(signature);
+        }
+
+        // contracts without a constructor have a default one
+        for contract in &ns.contracts {
+            if contract.is_concrete() && !self.signatures.contains_key(&contract.id.name) {
+                let signature = signature_information(
+                    format!("constructor {}", contract.id),
+                    iter::empty(),
+                    "",
+                    render(&contract.tags),
+                );
+
+                self.signatures
+                    .insert(contract.id.name.clone(), vec![signature]);
+            }
+        }
+
+        for event in &ns.events {
+            let signature = signature_information(
+                format!("event {}", event.id),
+                event.fields.iter().map(param),
+                "",
+                render(&event.tags),
+            );
+
+            self.signatures
+                .entry(event.id.name.clone())
+                .or_default()
+                .push(signature);
+        }
+
+        for error in &ns.errors {
+            let signature = signature_information(
+                format!("error {}", error.name),
+                error.fields.iter().map(param),
+                "",
+                render(&error.tags),
+            );
+
+            self.signatures
+                .entry(error.name.clone())
+                .or_default()
+                .push(signature);
+        }
+
+        for function in builtin::available(ns.target)
+            .into_iter()
+            .filter(|builtin| builtin.kind == BuiltinKind::Function && builtin.method.is_none())
+        {
+            let name = function.full_name();
+
+            let returns = if function.ret.is_empty() || function.ret == [Type::Void] {
+                String::new()
+            } else {
+                format!(
+                    " returns ({})",
+                    function.ret.iter().map(|ty| ty.to_string(ns)).join(", ")
+                )
+            };
+
+            let signature = signature_information(
+                name.clone(),
+                function.params.iter().map(|ty| ty.to_string(ns)),
+                &returns,
+                function.doc.to_string(),
+            );
+
+            self.signatures.entry(name).or_default().push(signature);
+        }
+    }
+
     fn build(mut self) -> (Vec<FileCache>, GlobalCache) {
         for (ei, enum_decl) in self.ns.enums.iter().enumerate() {
             for (discriminant, (nam, loc)) in enum_decl.values.iter().enumerate() {
@@ -1487,6 +2649,20 @@ impl<'a> Builder<'a> {
                 continue;
             }
 
+            self.function_inlay_hints(&func.params, &func.symtable);
+
+            // constructors, fallback and receive functions have no name
+            if !func.id.name.is_empty() {
+                self.hovers.push((
+                    func.id.loc.file_no(),
+                    HoverEntry {
+                        start: func.id.loc.start(),
+                        stop: func.id.loc.exclusive_end(),
+                        val: self.function_hover(i),
+                    },
+                ));
+            }
+
             if let Some(bump) = &func.annotations.bump {
                 self.expression(&bump.1, &func.symtable);
             }
@@ -1517,7 +2693,11 @@ impl<'a> Builder<'a> {
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: self.expanded_ty(&param.ty),
+                        val: format!(
+                            "{}{}",
+                            self.expanded_ty(&param.ty),
+                            self.type_note(&param.ty)
+                        ),
                     },
                 ));
 
@@ -1558,7 +2738,7 @@ impl<'a> Builder<'a> {
                     HoverEntry {
                         start: loc.start(),
                         stop: loc.exclusive_end(),
-                        val: self.expanded_ty(&ret.ty),
+                        val: format!("{}{}", self.expanded_ty(&ret.ty), self.type_note(&ret.ty)),
                     },
                 ));
 
@@ -1592,19 +2772,108 @@ impl<'a> Builder<'a> {
                 }
             }
 
+            self.call_sites.clear();
+
+            for modifier in &func.modifiers {
+                self.expression(modifier, &func.symtable);
+            }
+
+            for (base_no, (loc, _, args)) in &func.bases {
+                self.contract_reference(loc, *base_no);
+
+                for arg in args {
+                    self.expression(arg, &func.symtable);
+                }
+            }
+
             for stmt in &func.body {
                 self.statement(stmt, &func.symtable);
             }
 
             let file_no = func.id.loc.file_no();
             let file = &self.ns.files[file_no];
-            self.definitions.insert(
-                DefinitionIndex {
-                    def_path: file.path.clone(),
-                    def_type: DefinitionType::Function(i),
-                },
-                loc_to_range(&func.id.loc, file),
-            );
+            let di = DefinitionIndex {
+                def_path: file.path.clone(),
+                def_type: DefinitionType::Function(i),
+            };
+            self.definitions
+                .insert(di.clone(), loc_to_range(&func.id.loc, file));
+
+            self.hierarchy_items
+                .insert(di.clone(), function_symbol(self.ns, func));
+
+            let calls = self
+                .call_sites
+                .drain(..)
+                .filter_map(|(function_no, loc)| {
+                    let callee = &self.ns.functions[function_no];
+                    // accessor functions are synthetic
+                    if callee.is_accessor {
+                        return None;
+                    }
+                    let path = &self.ns.files[callee.loc.try_file_no()?].path;
+                    let callee = call_hierarchy_item(path, function_symbol(self.ns, callee))?;
+                    Some((callee, loc_to_range(&loc, file)))
+                })
+                .collect();
+            self.calls.insert(di.clone(), calls);
+
+            if func.ty == pt::FunctionTy::Function {
+                let params = func
+                    .params
+                    .iter()
+                    .map(|param| format!("{} {}", param.ty.to_string(self.ns), param.name_as_str()))
+                    .join(", ");
+
+                let returns = func
+                    .returns
+                    .iter()
+                    .map(|ret| ret.ty.to_string(self.ns))
+                    .join(", ");
+
+                let detail = if returns.is_empty() {
+                    format!("function {}({params})", func.id)
+                } else {
+                    format!("function {}({params}) returns ({returns})", func.id)
+                };
+
+                let args = func.params.iter().map(|param| {
+                    if param.name_as_str().is_empty() {
+                        param.ty.to_string(self.ns)
+                    } else {
+                        param.name_as_str().to_string()
+                    }
+                });
+
+                self.snippets
+                    .insert(di.clone(), CallSnippet::new(detail, &func.id.name, args));
+            }
+
+            let externally_visible = matches!(
+                func.visibility,
+                pt::Visibility::Public(_) | pt::Visibility::External(_)
+            ) && func
+                .contract_no
+                .is_some_and(|contract_no| !self.ns.contracts[contract_no].is_library());
+
+            if func.is_virtual || func.is_override.is_some() {
+                self.rename_hazards.insert(
+                    di,
+                    format!(
+                        "{} '{}' is virtual or overrides another {}, and the functions which \
+                        override each other would no longer match",
+                        func.ty, func.id, func.ty
+                    ),
+                );
+            } else if externally_visible && func.ty == pt::FunctionTy::Function {
+                self.rename_hazards.insert(
+                    di,
+                    format!(
+                        "function '{}' is {}, so renaming it changes the contract ABI",
+                        func.id, func.visibility
+                    ),
+                );
+            }
 
             self.scopes.extend(func.symtable.scopes.iter().map(|scope| {
                 let loc = scope.loc.unwrap();
@@ -1628,8 +2897,16 @@ impl<'a> Builder<'a> {
             }));
 
             if func.contract_no.is_none() {
-                self.top_level_code_objects
-                    .push((file_no, (func.id.name.clone(), None)))
+                self.top_level_code_objects.push((
+                    file_no,
+                    (
+                        func.id.name.clone(),
+                        Some(DefinitionIndex {
+                            def_path: file.path.clone(),
+                            def_type: DefinitionType::Function(i),
+                        }),
+                    ),
+                ))
             }
         }
 
@@ -1652,20 +2929,21 @@ impl<'a> Builder<'a> {
                         )),
                     },
                 ));
-                self.references.push((
-                    file_no,
-                    ReferenceEntry {
-                        start: base.loc.start(),
-                        stop: base.loc.exclusive_end(),
-                        val: DefinitionIndex {
-                            def_path: Default::default(),
-                            def_type: DefinitionType::Contract(base.contract_no),
-                        },
-                    },
-                ));
+                self.contract_reference(&base.loc, base.contract_no);
+
+                if let Some((_, args)) = &base.constructor {
+                    let symtable = symtable::Symtable::default();
+                    for arg in args {
+                        self.expression(arg, &symtable);
+                    }
+                }
             }
 
-            for (i, variable) in contract.variables.iter().enumerate() {
+            for using in &contract.using {
+                self.using(using);
+            }
+
+            for (i, variable) in contract.variables.iter().enumerate() {
                 let symtable = symtable::Symtable::default();
                 self.contract_variable(variable, &symtable, Some(ci), i);
             }
@@ -1691,6 +2969,22 @@ impl<'a> Builder<'a> {
                 loc_to_range(&contract.id.loc, file),
             );
 
+            self.hierarchy_items.insert(
+                contract_def_index.clone(),
+                contract_symbol(self.ns, contract),
+            );
+
+            let bases = contract
+                .bases
+                .iter()
+                .filter_map(|base| {
+                    let base = &self.ns.contracts[base.contract_no];
+                    let path = &self.ns.files[base.loc.file_no()].path;
+                    type_hierarchy_item(path, contract_symbol(self.ns, base))
+                })
+                .collect();
+            self.bases.insert(contract_def_index.clone(), bases);
+
             let impls = contract
                 .functions
                 .iter()
@@ -1759,7 +3053,7 @@ impl<'a> Builder<'a> {
                 self.ns
                     .functions
                     .get(fno)
-                    .map(|func| (func.id.name.clone(), None))
+                    .map(|func| (func.id.name.clone(), Some(DefinitionType::Function(fno))))
             });
 
             let structs = self
@@ -1841,6 +3135,32 @@ impl<'a> Builder<'a> {
             ));
         }
 
+        for using in &self.ns.using {
+            self.using(using);
+        }
+
+        for (file_no, file) in self.ns.files.iter().enumerate() {
+            // going to the definition of an import path opens the imported file
+            self.definitions.insert(
+                DefinitionIndex {
+                    def_path: file.path.clone(),
+                    def_type: DefinitionType::File(file_no),
+                },
+                Range::default(),
+            );
+
+            for (loc, import_no) in &file.imports {
+                self.references.push((
+                    file_no,
+                    ReferenceEntry {
+                        start: loc.start(),
+                        stop: loc.exclusive_end(),
+                        val: DefinitionType::File(*import_no).into(),
+                    },
+                ));
+            }
+        }
+
         for (ei, event) in self.ns.events.iter().enumerate() {
             for (fi, field) in event.fields.iter().enumerate() {
                 self.field(ei, fi, field);
@@ -1863,6 +3183,13 @@ impl<'a> Builder<'a> {
             };
             self.definitions
                 .insert(def_index.clone(), loc_to_range(&event.id.loc, file));
+            self.rename_hazards.insert(
+                def_index.clone(),
+                format!(
+                    "event '{}' is part of the contract ABI, so renaming it changes its signature",
+                    event.id
+                ),
+            );
 
             if event.contract.is_none() {
                 self.top_level_code_objects
@@ -1904,15 +3231,23 @@ impl<'a> Builder<'a> {
             }
         }
 
+        let mut declarations = HashSet::new();
+
         for (di, range) in &self.definitions {
+            if matches!(di.def_type, DefinitionType::File(_)) {
+                continue;
+            }
+
             if let Some(&file_no) = defs_to_file_nos.get(&di.def_path) {
                 let file = &self.ns.files[file_no];
+                let start = file
+                    .get_offset(range.start.line as usize, range.start.character as usize)
+                    .unwrap();
+                declarations.insert((file_no, start));
                 self.references.push((
                     file_no,
                     ReferenceEntry {
-                        start: file
-                            .get_offset(range.start.line as usize, range.start.character as usize)
-                            .unwrap(),
+                        start,
                         // 1 is added to account for the fact that `Lapper` expects half open ranges of the type:  [`start`, `stop`)
                         // i.e, `start` included but `stop` excluded.
                         stop: file
@@ -1925,68 +3260,135 @@ impl<'a> Builder<'a> {
             }
         }
 
+        for func in &self.ns.yul_functions {
+            self.function_inlay_hints(&func.params, &func.symtable);
+        }
+
+        self.signatures();
+        self.semantic_tokens(&declarations);
+        let fixes = self.fixes();
+
         let file_caches = self
             .ns
             .files
             .iter()
             .enumerate()
-            .map(|(i, f)| FileCache {
-                file: f.clone(),
-                // get `hovers` that belong to the current file
-                hovers: Lapper::new(
-                    self.hovers
-                        .iter()
-                        .filter(|h| h.0 == i)
-                        .map(|(_, i)| i.clone())
-                        .collect(),
-                ),
+            .zip(document_symbols(self.ns))
+            .map(|((i, f), symbols)| {
                 // get `references` that belong to the current file
-                references: Lapper::new(
-                    self.references
-                        .iter()
-                        .filter(|reference| reference.0 == i)
-                        .map(|(_, i)| {
-                            let mut i = i.clone();
-                            if let Some(def_path) = defs_to_files.get(&i.val.def_type) {
-                                i.val.def_path = def_path.clone();
+                let references: Vec<ReferenceEntry> = self
+                    .references
+                    .iter()
+                    .filter(|reference| reference.0 == i)
+                    .map(|(_, i)| {
+                        let mut i = i.clone();
+                        if let Some(def_path) = defs_to_files.get(&i.val.def_type) {
+                            i.val.def_path = def_path.clone();
+                        }
+                        i
+                    })
+                    .collect();
+
+                // the numbering of code objects differs between namespaces, so the definitions
+                // are kept with the references which use them
+                let definitions = references
+                    .iter()
+                    .filter_map(|reference| {
+                        self.definitions
+                            .get(&reference.val)
+                            .map(|range| (reference.val.clone(), *range))
+                    })
+                    .collect();
+
+                let rename_hazards = references
+                    .iter()
+                    .filter_map(|reference| {
+                        self.rename_hazards
+                            .get(&reference.val)
+                            .map(|hazard| (reference.val.clone(), hazard.clone()))
+                    })
+                    .collect();
+
+                FileCache {
+                    file: f.clone(),
+                    // get `hovers` that belong to the current file
+                    hovers: Lapper::new(
+                        self.hovers
+                            .iter()
+                            .filter(|h| h.0 == i)
+                            .map(|(_, i)| i.clone())
+                            .collect(),
+                    ),
+                    references: Lapper::new(references),
+                    scopes: Lapper::new(
+                        self.scopes
+                            .iter()
+                            .filter(|scope| scope.0 == i)
+                            .map(|(_, scope)| {
+                                let mut scope = scope.clone();
+                                for val in &mut scope.val {
+                                    if let Some(val) = &mut val.1 {
+                                        if let Some(def_path) = defs_to_files.get(&val.def_type) {
+                                            val.def_path = def_path.clone();
+                                        }
+                                    }
+                                }
+                                scope
+                            })
+                            .collect(),
+                    ),
+                    top_level_code_objects: self
+                        .top_level_code_objects
+                        .iter_mut()
+                        .filter(|code_object| code_object.0 == i)
+                        .map(|code_object| {
+                            if let Some(DefinitionIndex { def_path, def_type }) =
+                                &mut code_object.1 .1
+                            {
+                                if def_path.to_str().unwrap() == "" {
+                                    if let Some(dp) = defs_to_files.get(def_type) {
+                                        *def_path = dp.clone();
+                                    }
+                                }
                             }
-                            i
+                            code_object.1.clone()
                         })
                         .collect(),
-                ),
-                scopes: Lapper::new(
-                    self.scopes
+                    definitions,
+                    rename_hazards,
+                    symbols,
+                    signatures: self.signatures.clone(),
+                    inlay_hints: self
+                        .inlay_hints
                         .iter()
-                        .filter(|scope| scope.0 == i)
-                        .map(|(_, scope)| {
-                            let mut scope = scope.clone();
-                            for val in &mut scope.val {
-                                if let Some(val) = &mut val.1 {
-                                    if let Some(def_path) = defs_to_files.get(&val.def_type) {
-                                        val.def_path = def_path.clone();
-                                    }
-                                }
-                            }
-                            scope
+                        .filter(|hint| hint.0 == i)
+                        .map(|(_, hint)| hint.clone())
+                        .sorted_by_key(|hint| hint.position)
+                        .collect(),
+                    semantic_tokens: encode_semantic_tokens(
+                        self.tokens
+                            .iter()
+                            .filter(|token| token.0 == i)
+                            .map(|(_, token)| token.clone())
+                            .collect(),
+                        f,
+                    ),
+                    fixes: fixes
+                        .iter()
+                        .filter(|fix| fix.0 == i)
+                        .map(|(_, fix)| fix.clone())
+                        .collect(),
+                    unresolved: self
+                        .ns
+                        .diagnostics
+                        .iter()
+                        .filter(|diag| {
+                            diag.loc.try_file_no() == Some(i)
+                                && unresolved_name(&diag.message).is_some()
                         })
+                        .map(|diag| (loc_to_range(&diag.loc, f), diag.message.clone()))
                         .collect(),
-                ),
-                top_level_code_objects: self
-                    .top_level_code_objects
-                    .iter_mut()
-                    .filter(|code_object| code_object.0 == i)
-                    .map(|code_object| {
-                        if let Some(DefinitionIndex { def_path, def_type }) = &mut code_object.1 .1
-                        {
-                            if def_path.to_str().unwrap() == "" {
-                                if let Some(dp) = defs_to_files.get(def_type) {
-                                    *def_path = dp.clone();
-                                }
-                            }
-                        }
-                        code_object.1.clone()
-                    })
-                    .collect(),
+                }
             })
             .collect();
 
@@ -2000,17 +3402,149 @@ impl<'a> Builder<'a> {
             }
         }
 
+        // `wrap` and `unwrap` are methods of user types, so they have no snippet of their own
+        let builtin_snippets = builtin::available(self.ns.target)
+            .into_iter()
+            .filter(|builtin| builtin.kind == BuiltinKind::Function && builtin.method.is_none())
+            .map(|function| {
+                let name = function.full_name();
+
+                let params = function
+                    .params
+                    .iter()
+                    .map(|ty| ty.to_string(self.ns))
+                    .collect_vec();
+
+                let detail = format!("{name}({})", params.join(", "));
+
+                (
+                    name,
+                    CallSnippet::new(detail, function.name, params.into_iter()),
+                )
+            })
+            .collect();
+
         let global_cache = GlobalCache {
             definitions: self.definitions,
             types: self.types,
             declarations: self.declarations,
             implementations: self.implementations,
             properties: self.properties,
+            snippets: self.snippets,
+            builtin_snippets,
+            hierarchy_items: self.hierarchy_items,
+            calls: self.calls,
+            bases: self.bases,
         };
 
         (file_caches, global_cache)
     }
 
+    /// The hover for a function: its NatSpec, its prototype, and its selector if it can be
+    /// called externally
+    fn function_hover(&self, function_no: usize) -> String {
+        let fnc = &self.ns.functions[function_no];
+        let mut tags = render(&fnc.tags[..]);
+        if !tags.is_empty() {
+            tags.push_str("\n\n");
+        }
+
+        let params = fnc
+            .params
+            .iter()
+            .map(|parm| format!("{} {}", parm.ty.to_string(self.ns), parm.name_as_str()))
+            .join(", ");
+
+        let rets = fnc
+            .returns
+            .iter()
+            .map(|ret| {
+                let mut msg = ret.ty.to_string(self.ns);
+                if ret.name_as_str() != "" {
+                    msg = format!("{} {}", msg, ret.name_as_str());
+                }
+                msg
+            })
+            .join(", ");
+
+        let contract = fnc
+            .contract_no
+            .map(|contract_no| format!("{}.", self.ns.contracts[contract_no].id))
+            .unwrap_or_default();
+
+        let val = make_code_block(format!(
+            "{} {}{}({}) returns ({})\n",
+            fnc.ty, contract, fnc.id, params, rets
+        ));
+
+        // library functions are not called externally, fallback and receive functions do not
+        // have a selector, and Soroban calls functions by name
+        let selector = match fnc.contract_no {
+            Some(contract_no)
+                if self
+                    .ns
+                    .function_externally_callable(contract_no, Some(function_no))
+                    && matches!(
+                        fnc.ty,
+                        pt::FunctionTy::Function | pt::FunctionTy::Constructor
+                    )
+                    && self.ns.target != Target::Soroban =>
+            {
+                let kind = if self.ns.target == Target::Solana {
+                    "discriminator"
+                } else {
+                    "selector"
+                };
+                format!(
+                    "\n\n{kind}: `0x{}`",
+                    hex::encode(fnc.selector(self.ns, &contract_no))
+                )
+            }
+            _ => String::new(),
+        };
+
+        format!("{tags}{val}{selector}")
+    }
+
+    /// Where a state variable is stored: its slot, or its offset in the account data on Solana.
+    /// Constants and immutables are not in storage.
+    fn storage_note(&self, contract_no: usize, var_no: usize) -> String {
+        self.ns.contracts[contract_no]
+            .layout
+            .iter()
+            .find(|layout| layout.contract_no == contract_no && layout.var_no == var_no)
+            .map(|layout| {
+                if self.ns.target == Target::Solana {
+                    format!("\n\noffset in account data: {}", layout.slot)
+                } else {
+                    format!("\n\nstorage slot: {}", layout.slot)
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    /// What a type resolves to, when its name does not say: the underlying type of a user
+    /// defined type, and the width of an address on the target
+    fn type_note(&self, ty: &ast::Type) -> String {
+        match ty {
+            ast::Type::Ref(ty) | ast::Type::StorageRef(_, ty) => self.type_note(ty),
+            ast::Type::UserType(no) => {
+                let underlying = &self.ns.user_types[*no].ty;
+                format!(
+                    "\n\n`{}` is `{}`{}",
+                    ty.to_string(self.ns),
+                    underlying.to_string(self.ns),
+                    self.type_note(underlying)
+                )
+            }
+            ast::Type::Address(_) | ast::Type::Contract(_) => format!(
+                "\n\n`address` is {} bytes on {}",
+                self.ns.address_length, self.ns.target
+            ),
+            _ => String::new(),
+        }
+    }
+
     /// Render the type with struct/enum fields expanded
     fn expanded_ty(&self, ty: &ast::Type) -> String {
         match ty {
@@ -2056,7 +3590,34 @@ impl<'a> Builder<'a> {
 
 #[tower_lsp::async_trait]
 impl LanguageServer for SolangServer {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let mut folders = params
+            .workspace_folders
+            .iter()
+            .flatten()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect_vec();
+
+        if folders.is_empty() {
+            folders.extend(params.root_uri.and_then(|uri| uri.to_file_path().ok()));
+        }
+
+        *self.workspace_folders.lock().await = folders;
+
+        *self.client_support.lock().await = ClientSupport {
+            type_hierarchy_registration: params
+                .capabilities
+                .text_document
+                .and_then(|text_document| text_document.type_hierarchy)
+                .and_then(|type_hierarchy| type_hierarchy.dynamic_registration)
+                .unwrap_or(false),
+            configuration: params
+                .capabilities
+                .workspace
+                .and_then(|workspace| workspace.configuration)
+                .unwrap_or(false),
+        };
+
         Ok(InitializeResult {
             server_info: None,
             capabilities: ServerCapabilities {
@@ -2066,20 +3627,50 @@ impl LanguageServer for SolangServer {
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
-                    trigger_characters: Some(vec![".".to_string()]),
+                    // quotes and slashes complete import paths
+                    trigger_characters: Some(vec![
+                        ".".to_string(),
+                        "\"".to_string(),
+                        "'".to_string(),
+                        "/".to_string(),
+                    ]),
                     all_commit_characters: None,
                     work_done_progress_options: Default::default(),
                     completion_item: None,
                 }),
                 signature_help_provider: Some(SignatureHelpOptions {
-                    trigger_characters: None,
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
                     retrigger_characters: None,
                     work_done_progress_options: Default::default(),
                 }),
                 document_highlight_provider: None,
+                document_symbol_provider: Some(OneOf::Left(true)),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            CodeActionKind::QUICKFIX,
+                            CodeActionKind::REFACTOR_REWRITE,
+                            CodeActionKind::SOURCE_FIX_ALL,
+                        ]),
+                        ..Default::default()
+                    },
+                )),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: SemanticTokensLegend {
+                                token_types: TOKEN_TYPES.to_vec(),
+                                token_modifiers: TOKEN_MODIFIERS.to_vec(),
+                            },
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
                 workspace_symbol_provider: Some(OneOf::Left(true)),
                 execute_command_provider: Some(ExecuteCommandOptions {
-                    commands: vec![],
+                    commands: vec![FORCE_RENAME_COMMAND.to_string()],
                     work_done_progress_options: Default::default(),
                 }),
                 workspace: Some(WorkspaceServerCapabilities {
@@ -2094,6 +3685,7 @@ impl LanguageServer for SolangServer {
                 implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
                 declaration_provider: Some(DeclarationCapability::Simple(true)),
                 references_provider: Some(OneOf::Left(true)),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
                 rename_provider: Some(OneOf::Left(true)),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 ..ServerCapabilities::default()
@@ -2111,22 +3703,88 @@ impl LanguageServer for SolangServer {
                 ),
             )
             .await;
+
+        // the workspace is indexed for the target in the settings
+        if let Some(settings) = self.fetch_settings().await {
+            self.apply_settings(settings).await;
+        }
+
+        let folders = self.workspace_folders.lock().await.clone();
+
+        if !folders.is_empty() {
+            let indexed = self.index_folders(&folders).await;
+
+            self.client
+                .log_message(
+                    MessageType::INFO,
+                    format!("indexed {indexed} Solidity files for workspace symbols"),
+                )
+                .await;
+        }
+
+        // the server capabilities cannot declare the type hierarchy, so it is registered
+        if self.client_support.lock().await.type_hierarchy_registration {
+            let registration = Registration {
+                id: "typeHierarchy".to_string(),
+                method: "textDocument/prepareTypeHierarchy".to_string(),
+                register_options: serde_json::to_value(TypeHierarchyRegistrationOptions::default())
+                    .ok(),
+            };
+
+            if let Err(err) = self.client.register_capability(vec![registration]).await {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("could not register the type hierarchy: {err}"),
+                    )
+                    .await;
+            }
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
 
-    async fn did_change_workspace_folders(&self, _: DidChangeWorkspaceFoldersParams) {
-        self.client
-            .log_message(MessageType::INFO, "workspace folders changed!")
-            .await;
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let added = params
+            .event
+            .added
+            .iter()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect_vec();
+        let removed = params
+            .event
+            .removed
+            .iter()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect_vec();
+
+        {
+            let mut folders = self.workspace_folders.lock().await;
+            folders.retain(|folder| !removed.contains(folder));
+            folders.extend(added.iter().cloned());
+        }
+
+        self.files
+            .lock()
+            .await
+            .symbols
+            .retain(|path, _| !removed.iter().any(|folder| path.starts_with(folder)));
+
+        self.index_folders(&added).await;
     }
 
-    async fn did_change_configuration(&self, _: DidChangeConfigurationParams) {
-        self.client
-            .log_message(MessageType::INFO, "configuration changed!")
-            .await;
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        // some clients send the new settings, others only say that they have changed
+        let settings = match params.settings.get("solang") {
+            Some(settings) => Some(settings.clone()),
+            None => self.fetch_settings().await,
+        };
+
+        if let Some(settings) = settings {
+            self.apply_settings(settings).await;
+        }
     }
 
     async fn did_change_watched_files(&self, _: DidChangeWatchedFilesParams) {
@@ -2135,7 +3793,26 @@ impl LanguageServer for SolangServer {
             .await;
     }
 
-    async fn execute_command(&self, _: ExecuteCommandParams) -> Result<Option<Value>> {
+    async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+        if params.command == FORCE_RENAME_COMMAND {
+            let rename = params
+                .arguments
+                .into_iter()
+                .next()
+                .and_then(|arg| serde_json::from_value::<RenameParams>(arg).ok())
+                .ok_or_else(|| {
+                    Error::invalid_params(format!(
+                        "{FORCE_RENAME_COMMAND} expects the parameters of a rename request"
+                    ))
+                })?;
+
+            if let Some(edit) = self.rename_edit(rename, true).await? {
+                self.client.apply_edit(edit).await?;
+            }
+
+            return Ok(None);
+        }
+
         self.client
             .log_message(MessageType::INFO, "command executed!")
             .await;
@@ -2167,13 +3844,28 @@ impl LanguageServer for SolangServer {
 
         match uri.to_file_path() {
             Ok(path) => {
-                if let Some(text_buf) = self.files.lock().await.text_buffers.get_mut(&path) {
-                    *text_buf = params
-                        .content_changes
-                        .into_iter()
-                        .fold(text_buf.clone(), update_file_contents);
+                let change_no = {
+                    let mut files = self.files.lock().await;
+
+                    if let Some(text_buf) = files.text_buffers.get_mut(&path) {
+                        *text_buf = params
+                            .content_changes
+                            .into_iter()
+                            .fold(text_buf.clone(), update_file_contents);
+                    }
+
+                    let change_no = files.changes.entry(path.clone()).or_default();
+                    *change_no += 1;
+                    *change_no
+                };
+
+                // the file is analysed once the changes stop, by the last change
+                tokio::time::sleep(DEBOUNCE).await;
+
+                if self.files.lock().await.changes.get(&path) == Some(&change_no) {
+                    self.parse_file(uri).await;
+                    self.parse_dependents(&path).await;
                 }
-                self.parse_file(uri).await;
             }
             Err(_) => {
                 self.client
@@ -2194,7 +3886,11 @@ impl LanguageServer for SolangServer {
             }
         }
 
-        self.parse_file(uri).await;
+        self.parse_file(uri.clone()).await;
+
+        if let Ok(path) = uri.to_file_path() {
+            self.parse_dependents(&path).await;
+        }
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
@@ -2204,6 +3900,9 @@ impl LanguageServer for SolangServer {
             let mut files = self.files.lock().await;
             files.caches.remove(&path);
             files.text_buffers.remove(&path);
+            files.changes.remove(&path);
+            files.dependencies.remove(&path);
+            files.analysed.remove(&path);
         }
 
         self.client.publish_diagnostics(uri, vec![], None).await;
@@ -2224,8 +3923,22 @@ impl LanguageServer for SolangServer {
             data: None,
         })?;
 
+        let config = self.config.lock().await.clone();
         let files = self.files.lock().await;
 
+        // import paths are completed from the files on disk
+        if let Some(line) = files
+            .text_buffers
+            .get(&path)
+            .and_then(|text| line_before(text, params.text_document_position.position))
+        {
+            if let Some(prefix) = import_path_prefix(&line) {
+                return Ok(Some(CompletionResponse::Array(
+                    config.import_path_completions(&path, prefix),
+                )));
+            }
+        }
+
         let Some(cache) = files.caches.get(&path) else {
             return Ok(None);
         };
@@ -2238,14 +3951,15 @@ impl LanguageServer for SolangServer {
             )
             .unwrap();
 
-        let builtin_functions = BUILTIN_FUNCTIONS
-            .iter()
-            .filter(|function| function.target.is_empty() || function.target.contains(&self.target))
-            .map(|function| (function.name.to_string(), None));
-        let builtin_variables = BUILTIN_VARIABLE
+        let builtins = builtin::available(config.target)
+            .into_iter()
+            .filter(|builtin| builtin.kind != BuiltinKind::Method)
+            .collect_vec();
+
+        // builtins like `msg.sender` are completed after their namespace
+        let builtin_names = builtins
             .iter()
-            .filter(|var| var.target.is_empty() || var.target.contains(&self.target))
-            .map(|var| (var.name.to_string(), None));
+            .map(|builtin| (builtin.namespace.unwrap_or(builtin.name).to_string(), None));
 
         // Get all the code objects available from the lexical scope from which the request was raised.
         let code_objects_in_scope = cache
@@ -2256,8 +3970,7 @@ impl LanguageServer for SolangServer {
             // get the top level code objects in the file
             .chain(cache.top_level_code_objects.clone())
             // builtins
-            .chain(builtin_functions)
-            .chain(builtin_variables)
+            .chain(builtin_names)
             .collect::<HashMap<_, _>>();
 
         let global_cache = self.global_cache.lock().await;
@@ -2273,10 +3986,11 @@ impl LanguageServer for SolangServer {
 
                 let mut builtin_methods =
                     HashMap::<DefinitionType, HashMap<String, Option<DefinitionIndex>>>::new();
-                for method in BUILTIN_METHODS.iter().filter(|method| {
-                    method.target.is_empty() || method.target.contains(&self.target)
-                }) {
-                    if let Some(def_type) = get_type_definition(&method.method[0]) {
+                for method in builtin::available(config.target)
+                    .into_iter()
+                    .filter(|builtin| builtin.kind == BuiltinKind::Method)
+                {
+                    if let Some(def_type) = method.method.as_ref().and_then(get_type_definition) {
                         builtin_methods
                             .entry(def_type)
                             .or_default()
@@ -2320,6 +4034,33 @@ impl LanguageServer for SolangServer {
                     name
                 };
 
+                // members of `msg`, `block`, `tx`, `abi` etc.
+                let builtin_members = builtins
+                    .iter()
+                    .filter(|builtin| builtin.namespace == Some(code_object.as_str()))
+                    .map(|builtin| {
+                        let label = builtin.name.to_string();
+
+                        match global_cache
+                            .builtin_snippets
+                            .get(&format!("{code_object}.{label}"))
+                        {
+                            Some(snippet) => snippet.completion_item(label),
+                            None => CompletionItem {
+                                label,
+                                kind: Some(CompletionItemKind::PROPERTY),
+                                detail: Some(builtin.doc.to_string()),
+                                ..Default::default()
+                            },
+                        }
+                    })
+                    .unique_by(|item| item.label.clone())
+                    .collect_vec();
+
+                if !builtin_members.is_empty() {
+                    return Ok(Some(CompletionResponse::Array(builtin_members)));
+                }
+
                 // Get an iterator that iterates over all parts of the code object.
                 // The parts are basically a field, a variant or a method defined on the previous part.
                 let mut code_object_parts = code_object.split('.');
@@ -2352,10 +4093,18 @@ impl LanguageServer for SolangServer {
                 // Return a list of suggestions using the `properties` extracted previously by converting them into the expected format.
                 properties.map(|properties| {
                     properties
-                        .keys()
-                        .map(|name| CompletionItem {
-                            label: name.clone(),
-                            ..Default::default()
+                        .iter()
+                        .map(|(name, def_index)| {
+                            match def_index
+                                .as_ref()
+                                .and_then(|def_index| global_cache.snippets.get(def_index))
+                            {
+                                Some(snippet) => snippet.completion_item(name.clone()),
+                                None => CompletionItem {
+                                    label: name.clone(),
+                                    ..Default::default()
+                                },
+                            }
                         })
                         .collect_vec()
                 })
@@ -2365,10 +4114,20 @@ impl LanguageServer for SolangServer {
                 ..
             }) => {
                 let suggestions = code_objects_in_scope
-                    .into_keys()
-                    .map(|label| CompletionItem {
-                        label: label.clone(),
-                        ..Default::default()
+                    .into_iter()
+                    .map(|(label, def_index)| {
+                        let snippet = match &def_index {
+                            Some(def_index) => global_cache.snippets.get(def_index),
+                            None => global_cache.builtin_snippets.get(&label),
+                        };
+
+                        match snippet {
+                            Some(snippet) => snippet.completion_item(label),
+                            None => CompletionItem {
+                                label,
+                                ..Default::default()
+                            },
+                        }
                     })
                     .collect_vec();
                 Some(suggestions)
@@ -2428,18 +4187,44 @@ impl LanguageServer for SolangServer {
         &self,
         params: GotoDefinitionParams,
     ) -> Result<Option<GotoDefinitionResponse>> {
+        let path = params
+            .text_document_position_params
+            .text_document
+            .uri
+            .to_file_path()
+            .ok();
+
         // fetch the `DefinitionIndex` of the code object
         let Some(reference) = self.get_reference_from_params(params).await? else {
             return Ok(None);
         };
 
-        // get the location of the definition of the code object in source code
-        let definitions = &self.global_cache.lock().await.definitions;
-        let location = definitions
-            .get(&reference)
+        // get the location of the definition of the code object in source code. The definitions
+        // from the namespace of the requesting file come first, since code objects are numbered
+        // differently in other namespaces.
+        let range = self
+            .files
+            .lock()
+            .await
+            .caches
+            .get(&path.unwrap_or_default())
+            .and_then(|cache| cache.definitions.get(&reference).copied());
+
+        let range = match range {
+            Some(range) => Some(range),
+            None => self
+                .global_cache
+                .lock()
+                .await
+                .definitions
+                .get(&reference)
+                .copied(),
+        };
+
+        let location = range
             .map(|range| {
                 let uri = Url::from_file_path(&reference.def_path).unwrap();
-                Location { uri, range: *range }
+                Location { uri, range }
             })
             .map(GotoTypeDefinitionResponse::Scalar);
 
@@ -2616,6 +4401,8 @@ impl LanguageServer for SolangServer {
     /// * Returns `Err` when an invalid file path is received.
     /// * Returns `Ok(None)` when no valid references are found.
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let uri = params.text_document_position.text_document.uri.clone();
+
         // fetch the `DefinitionIndex` of the code object in question
         let def_params: GotoDefinitionParams = GotoDefinitionParams {
             text_document_position_params: params.text_document_position,
@@ -2626,32 +4413,24 @@ impl LanguageServer for SolangServer {
             return Ok(None);
         };
 
+        let caches = &self.files.lock().await.caches;
+
+        let Some(definition) = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| caches.get(&path))
+            .and_then(|cache| cache.definition(&reference))
+        else {
+            return Ok(None);
+        };
+
         // fetch all the locations in source code where the code object is referenced
         // this includes the definition location of the code object
-        let caches = &self.files.lock().await.caches;
-        let mut locations: Vec<_> = caches
-            .iter()
-            .flat_map(|(p, cache)| {
-                let uri = Url::from_file_path(p).unwrap();
-                cache
-                    .references
-                    .iter()
-                    .filter(|r| r.val == reference)
-                    .map(move |r| Location {
-                        uri: uri.clone(),
-                        range: get_range_exclusive(r.start, r.stop, &cache.file),
-                    })
-            })
-            .collect();
+        let mut locations = references_to(caches, &definition);
 
         // remove the definition location if `include_declaration` is `false`
         if !params.context.include_declaration {
-            let definitions = &self.global_cache.lock().await.definitions;
-            let uri = Url::from_file_path(&reference.def_path).unwrap();
-            if let Some(range) = definitions.get(&reference) {
-                let def = Location { uri, range: *range };
-                locations.retain(|loc| loc != &def);
-            }
+            locations.retain(|loc| loc != &definition);
         }
 
         // return `None` if the list of locations is empty
@@ -2677,40 +4456,485 @@ impl LanguageServer for SolangServer {
     /// * Returns `Err` when an invalid file path is received.
     /// * Returns `Ok(None)` when the definition of code object is not found in user code.
     async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
-        // fetch the `DefinitionIndex` of the code object in question
-        let def_params: GotoDefinitionParams = GotoDefinitionParams {
-            text_document_position_params: params.text_document_position,
-            work_done_progress_params: params.work_done_progress_params,
-            partial_result_params: Default::default(),
-        };
-        let Some(reference) = self.get_reference_from_params(def_params).await? else {
+        self.rename_edit(params, false).await
+    }
+
+    /// Called when the user opens the call hierarchy of a function.
+    ///
+    /// Returns the function referenced at the position, whose callers and callees are then requested.
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let Some(reference) = self
+            .get_reference_from_params(GotoDefinitionParams {
+                text_document_position_params: params.text_document_position_params,
+                work_done_progress_params: params.work_done_progress_params,
+                partial_result_params: Default::default(),
+            })
+            .await?
+        else {
             return Ok(None);
         };
 
-        // the new name of the code object
-        let new_text = params.new_name;
+        if !matches!(reference.def_type, DefinitionType::Function(_)) {
+            return Ok(None);
+        }
 
-        // create `TextEdit` instances that represent the changes to be made for every occurrence of the old symbol
-        // these `TextEdit` objects are then grouped into separate list per source file to which they belong
-        let caches = &self.files.lock().await.caches;
-        let ws = caches
+        let gc = self.global_cache.lock().await;
+
+        Ok(gc
+            .hierarchy_items
+            .get(&reference)
+            .and_then(|symbol| call_hierarchy_item(&reference.def_path, symbol.clone()))
+            .map(|item| vec![item]))
+    }
+
+    /// Returns the functions which call the function of a call hierarchy item, with the ranges of the calls.
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let gc = self.global_cache.lock().await;
+        let mut incoming: Vec<CallHierarchyIncomingCall> = Vec::new();
+
+        for (caller, calls) in &gc.calls {
+            let from_ranges = calls
+                .iter()
+                .filter(|(callee, _)| {
+                    callee.uri == params.item.uri
+                        && callee.selection_range == params.item.selection_range
+                })
+                .map(|(_, range)| *range)
+                .collect_vec();
+
+            if from_ranges.is_empty() {
+                continue;
+            }
+
+            let Some(from) = gc
+                .hierarchy_items
+                .get(caller)
+                .and_then(|symbol| call_hierarchy_item(&caller.def_path, symbol.clone()))
+            else {
+                continue;
+            };
+
+            // a file may be part of more than one namespace
+            if !incoming.iter().any(|call| call.from == from) {
+                incoming.push(CallHierarchyIncomingCall { from, from_ranges });
+            }
+        }
+
+        incoming.sort_by(|a, b| {
+            (a.from.uri.as_str(), a.from.range.start)
+                .cmp(&(b.from.uri.as_str(), b.from.range.start))
+        });
+
+        Ok(Some(incoming))
+    }
+
+    /// Returns the functions called by the function of a call hierarchy item, in the order they are first called,
+    /// with the ranges of the calls.
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let gc = self.global_cache.lock().await;
+        let mut outgoing: Vec<CallHierarchyOutgoingCall> = Vec::new();
+
+        let calls = hierarchy_index(
+            &gc.hierarchy_items,
+            &params.item.uri,
+            params.item.selection_range,
+        )
+        .and_then(|caller| gc.calls.get(&caller));
+
+        for (to, range) in calls.into_iter().flatten() {
+            match outgoing.iter_mut().find(|call| call.to == *to) {
+                Some(call) => call.from_ranges.push(*range),
+                None => outgoing.push(CallHierarchyOutgoingCall {
+                    to: to.clone(),
+                    from_ranges: vec![*range],
+                }),
+            }
+        }
+
+        Ok(Some(outgoing))
+    }
+
+    /// Called when the user opens the type hierarchy of a contract.
+    ///
+    /// Returns the contract referenced at the position, whose bases and derived contracts are then requested.
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        let Some(reference) = self
+            .get_reference_from_params(GotoDefinitionParams {
+                text_document_position_params: params.text_document_position_params,
+                work_done_progress_params: params.work_done_progress_params,
+                partial_result_params: Default::default(),
+            })
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        if !matches!(reference.def_type, DefinitionType::Contract(_)) {
+            return Ok(None);
+        }
+
+        let gc = self.global_cache.lock().await;
+
+        Ok(gc
+            .hierarchy_items
+            .get(&reference)
+            .and_then(|symbol| type_hierarchy_item(&reference.def_path, symbol.clone()))
+            .map(|item| vec![item]))
+    }
+
+    /// Returns the contracts which the contract of a type hierarchy item inherits from directly.
+    async fn supertypes(
+        &self,
+        params: TypeHierarchySupertypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        let gc = self.global_cache.lock().await;
+
+        let bases = hierarchy_index(
+            &gc.hierarchy_items,
+            &params.item.uri,
+            params.item.selection_range,
+        )
+        .and_then(|contract| gc.bases.get(&contract))
+        .cloned()
+        .unwrap_or_default();
+
+        Ok(Some(bases))
+    }
+
+    /// Returns the contracts which inherit directly from the contract of a type hierarchy item.
+    async fn subtypes(
+        &self,
+        params: TypeHierarchySubtypesParams,
+    ) -> Result<Option<Vec<TypeHierarchyItem>>> {
+        let gc = self.global_cache.lock().await;
+        let mut subtypes: Vec<TypeHierarchyItem> = Vec::new();
+
+        for (contract, bases) in &gc.bases {
+            if !bases.iter().any(|base| {
+                base.uri == params.item.uri && base.selection_range == params.item.selection_range
+            }) {
+                continue;
+            }
+
+            let Some(item) = gc
+                .hierarchy_items
+                .get(contract)
+                .and_then(|symbol| type_hierarchy_item(&contract.def_path, symbol.clone()))
+            else {
+                continue;
+            };
+
+            // a file may be part of more than one namespace
+            if !subtypes.contains(&item) {
+                subtypes.push(item);
+            }
+        }
+
+        subtypes
+            .sort_by(|a, b| (a.uri.as_str(), a.range.start).cmp(&(b.uri.as_str(), b.range.start)));
+
+        Ok(Some(subtypes))
+    }
+
+    /// Called when the user types the arguments of a call.
+    ///
+    /// Returns the signatures of the functions, constructors, events or errors with the name called, with the
+    /// parameter which the cursor is in. Overloaded functions have a signature each, and the first one with enough
+    /// parameters is active unless the user has chosen another.
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let path = uri.to_file_path().map_err(|_| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Received invalid URI: {uri}").into(),
+            data: None,
+        })?;
+
+        let files = self.files.lock().await;
+
+        let (Some(cache), Some(text)) = (files.caches.get(&path), files.text_buffers.get(&path))
+        else {
+            return Ok(None);
+        };
+
+        let Some((name, argument)) =
+            text_before(text, params.text_document_position_params.position).and_then(call_at)
+        else {
+            return Ok(None);
+        };
+
+        // methods are called with the contract or variable before the name
+        let Some(signatures) = cache.signatures.get(name).or_else(|| {
+            name.rsplit('.')
+                .next()
+                .and_then(|name| cache.signatures.get(name))
+        }) else {
+            return Ok(None);
+        };
+
+        let fits = |signature: &SignatureInformation| {
+            signature
+                .parameters
+                .as_ref()
+                .is_some_and(|params| params.len() > argument as usize)
+        };
+
+        let chosen = params
+            .context
+            .and_then(|context| context.active_signature_help)
+            .and_then(|help| help.active_signature)
+            .filter(|active| {
+                signatures
+                    .get(*active as usize)
+                    .is_some_and(|signature| argument == 0 || fits(signature))
+            });
+
+        let active_signature = chosen
+            .or_else(|| signatures.iter().position(fits).map(|no| no as u32))
+            .unwrap_or(0);
+
+        Ok(Some(SignatureHelp {
+            signatures: signatures.clone(),
+            active_signature: Some(active_signature),
+            active_parameter: Some(argument),
+        }))
+    }
+
+    /// Called when the client shows the inlay hints of a file.
+    ///
+    /// Returns the hints in the requested range: the names of parameters before literal arguments, the implicit
+    /// data location of reference parameters, and the type of yul variables declared without one.
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let uri = params.text_document.uri;
+        let path = uri.to_file_path().map_err(|_| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Received invalid URI: {uri}").into(),
+            data: None,
+        })?;
+
+        let files = self.files.lock().await;
+
+        Ok(files.caches.get(&path).map(|cache| {
+            cache
+                .inlay_hints
+                .iter()
+                .filter(|hint| {
+                    params.range.start <= hint.position && hint.position <= params.range.end
+                })
+                .cloned()
+                .collect()
+        }))
+    }
+
+    /// Called when the client wants the actions available in a range of a file.
+    ///
+    /// Returns the fixes of the diagnostics in the range, adding the implicit data location to the reference
+    /// parameters in the range, and an action which applies all the certain fixes of the file at once.
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let path = uri.to_file_path().map_err(|_| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Received invalid URI: {uri}").into(),
+            data: None,
+        })?;
+
+        let files = self.files.lock().await;
+
+        let Some(cache) = files.caches.get(&path) else {
+            return Ok(None);
+        };
+
+        let text = files
+            .text_buffers
+            .get(&path)
+            .map(String::as_str)
+            .unwrap_or_default();
+
+        let fixes = cache
+            .fixes
             .iter()
-            .map(|(p, cache)| {
-                let uri = Url::from_file_path(p).unwrap();
-                let text_edits: Vec<_> = cache
-                    .references
+            .cloned()
+            .chain(import_fixes(&path, text, &cache.unresolved, &files.symbols))
+            .collect_vec();
+
+        let wanted = |kind: &CodeActionKind| match &params.context.only {
+            Some(only) => only
+                .iter()
+                .any(|only| kind.as_str().starts_with(only.as_str())),
+            None => true,
+        };
+
+        let in_range =
+            |range: &Range| range.start <= params.range.end && params.range.start <= range.end;
+
+        let action = |title: String, kind: CodeActionKind, edits: Vec<TextEdit>| CodeAction {
+            title,
+            kind: Some(kind),
+            edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+            ..Default::default()
+        };
+
+        let mut actions = Vec::new();
+
+        if wanted(&CodeActionKind::QUICKFIX) {
+            for fix in fixes.iter().filter(|fix| in_range(&fix.range)) {
+                let diagnostics = params
+                    .context
+                    .diagnostics
                     .iter()
-                    .filter(|r| r.val == reference)
-                    .map(|r| TextEdit {
-                        range: get_range_exclusive(r.start, r.stop, &cache.file),
-                        new_text: new_text.clone(),
-                    })
+                    .filter(|diag| diag.range == fix.range && diag.message == fix.message)
+                    .cloned()
                     .collect();
-                (uri, text_edits)
+
+                actions.push(CodeAction {
+                    diagnostics: Some(diagnostics),
+                    is_preferred: Some(fix.preferred),
+                    ..action(
+                        fix.title.clone(),
+                        CodeActionKind::QUICKFIX,
+                        fix.edits.clone(),
+                    )
+                });
+            }
+        }
+
+        if wanted(&CodeActionKind::REFACTOR_REWRITE) {
+            for hint in &cache.inlay_hints {
+                if matches!(&hint.label, InlayHintLabel::String(label) if label == "memory")
+                    && in_range(&Range::new(hint.position, hint.position))
+                {
+                    actions.push(action(
+                        "Add data location 'memory'".into(),
+                        CodeActionKind::REFACTOR_REWRITE,
+                        vec![TextEdit {
+                            range: Range::new(hint.position, hint.position),
+                            new_text: " memory".into(),
+                        }],
+                    ));
+                }
+            }
+        }
+
+        if wanted(&CodeActionKind::SOURCE_FIX_ALL) {
+            let mut edits: Vec<TextEdit> = Vec::new();
+
+            // fixes of the same problem give the same edit, and edits may not overlap
+            for edit in fixes
+                .iter()
+                .filter(|fix| fix.preferred)
+                .flat_map(|fix| &fix.edits)
+            {
+                if !edits.iter().any(|other| {
+                    other == edit
+                        || (other.range.start < edit.range.end
+                            && edit.range.start < other.range.end)
+                }) {
+                    edits.push(edit.clone());
+                }
+            }
+
+            if !edits.is_empty() {
+                actions.push(action(
+                    "Fix all problems in file".into(),
+                    CodeActionKind::SOURCE_FIX_ALL,
+                    edits,
+                ));
+            }
+        }
+
+        Ok(Some(
+            actions
+                .into_iter()
+                .map(CodeActionOrCommand::CodeAction)
+                .collect(),
+        ))
+    }
+
+    /// Called when the client needs the semantic tokens of a file, for highlighting.
+    ///
+    /// Returns the tokens of the code objects which were resolved in the file: state variables, locals and
+    /// parameters with their data location, constants and immutables, events, modifiers, types and builtins.
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let uri = params.text_document.uri;
+        let path = uri.to_file_path().map_err(|_| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Received invalid URI: {uri}").into(),
+            data: None,
+        })?;
+
+        let files = self.files.lock().await;
+
+        Ok(files.caches.get(&path).map(|cache| {
+            SemanticTokensResult::Tokens(SemanticTokens {
+                result_id: None,
+                data: cache.semantic_tokens.clone(),
             })
-            .collect::<HashMap<_, _>>();
+        }))
+    }
+
+    /// Called when the client needs the outline of a file.
+    ///
+    /// Returns the contracts of the file with the functions, events, variables and types declared in them,
+    /// and the code objects declared at file level.
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let uri = params.text_document.uri;
+        let path = uri.to_file_path().map_err(|_| Error {
+            code: ErrorCode::InvalidRequest,
+            message: format!("Received invalid URI: {uri}").into(),
+            data: None,
+        })?;
+
+        let files = self.files.lock().await;
+
+        Ok(files
+            .caches
+            .get(&path)
+            .map(|cache| DocumentSymbolResponse::Nested(cache.symbols.clone())))
+    }
+
+    /// Called when the user searches for a symbol in the workspace.
+    ///
+    /// Returns the symbols of the indexed files whose name contains the characters of the query in order,
+    /// ignoring case.
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let files = self.files.lock().await;
 
-        Ok(Some(WorkspaceEdit::new(ws)))
+        let symbols = files
+            .symbols
+            .values()
+            .flatten()
+            .filter(|symbol| fuzzy_match(&params.query, &symbol.name))
+            .sorted_by(|a, b| {
+                (a.name.len(), &a.name, a.location.uri.as_str()).cmp(&(
+                    b.name.len(),
+                    &b.name,
+                    b.location.uri.as_str(),
+                ))
+            })
+            .cloned()
+            .collect();
+
+        Ok(Some(symbols))
     }
 
     /// Called when "Format Document" is called by the user on the client side.
@@ -2729,7 +4953,7 @@ impl LanguageServer for SolangServer {
     ///     * parsing the file fails.
     ///     * formatting the file fails.
     async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
-        // get parse tree for the input file
+        // read the input file
         let uri = params.text_document.uri;
         let source_path = uri.to_file_path().map_err(|_| Error {
             code: ErrorCode::InvalidRequest,
@@ -2741,30 +4965,21 @@ impl LanguageServer for SolangServer {
             message: format!("Failed to read file: {uri}").into(),
             data: Some(Value::String(format!("{:?}", err))),
         })?;
-        let source_parsed = parse(&source).map_err(|err| {
-            let err = err
-                .into_iter()
-                .map(|e| Value::String(e.message))
-                .collect::<Vec<_>>();
-            Error {
-                code: ErrorCode::InternalError,
-                message: format!("Failed to parse file: {uri}").into(),
-                data: Some(Value::Array(err)),
-            }
-        })?;
-
-        // get the formatted text
-        let config = FormatterConfig {
-            line_length: 80,
-            tab_width: params.options.tab_size as _,
-            ..Default::default()
-        };
-        let mut source_formatted = String::new();
-        format(&mut source_formatted, source_parsed, config).map_err(|err| Error {
-            code: ErrorCode::InternalError,
-            message: format!("Failed to format file: {uri}").into(),
-            data: Some(Value::String(format!("{:?}", err))),
-        })?;
+        let source_formatted =
+            format_source(&source, params.options.tab_size as _).map_err(|err| match err {
+                FormatError::Parse(messages) => Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Failed to parse file: {uri}").into(),
+                    data: Some(Value::Array(
+                        messages.into_iter().map(Value::String).collect(),
+                    )),
+                },
+                FormatError::Format(message) => Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Failed to format file: {uri}").into(),
+                    data: Some(Value::String(message)),
+                },
+            })?;
 
         // create a `TextEdit` instance that replaces the contents of the file with the formatted text
         let text_edit = TextEdit {
@@ -2786,6 +5001,306 @@ impl LanguageServer for SolangServer {
 }
 
 /// Calculate the line and column from the Loc offset received from the parser
+/// The offset after the parameter list of a function prototype
+fn params_end(text: &str, start: usize, end: usize) -> Option<usize> {
+    let prototype = text.get(start..end)?;
+    let open = prototype.find('(')?;
+    let mut depth = 0;
+
+    for (offset, ch) in prototype[open..].char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(start + open + offset + 1);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// The override list suggested by a diagnostic, e.g. `override(A,B)`
+fn override_list(message: &str) -> Option<&str> {
+    let start = message.rfind("'override(")?;
+
+    message[start + 1..].strip_suffix('\'')
+}
+
+/// The type to convert to explicitly, from the note of a strict conversion error
+fn explicit_conversion(note: &str) -> Option<&str> {
+    note.strip_prefix(STRICT_CONVERSION_NOTE)?
+        .strip_prefix('\'')?
+        .strip_suffix("(...)'")
+}
+
+/// The extent of a statement with its semicolon, or of its line if nothing else is on the line
+fn statement_extent(text: &str, start: usize, end: usize) -> (usize, usize) {
+    let rest = text[end..].trim_start_matches([' ', '\t']);
+    let end = if rest.starts_with(';') {
+        text.len() - rest.len() + 1
+    } else {
+        end
+    };
+
+    let line_start = text[..start].rfind('\n').map_or(0, |offset| offset + 1);
+    let line_end = text[end..]
+        .find('\n')
+        .map_or(text.len(), |offset| end + offset + 1);
+
+    if text[line_start..start].trim().is_empty() && text[end..line_end].trim().is_empty() {
+        (line_start, line_end)
+    } else {
+        (start, end)
+    }
+}
+
+/// The name which could not be resolved, from the message of a diagnostic
+fn unresolved_name(message: &str) -> Option<&str> {
+    if let Some(name) = message.strip_prefix("unknown function or type '") {
+        return name.strip_suffix('\'');
+    }
+
+    let name = message.strip_suffix("' not found")?;
+    let name = ["'", "type '", "event '", "error '"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))?;
+
+    // members like `Foo.bar` are not declared at file level
+    (!name.contains(['\'', '.'])).then_some(name)
+}
+
+/// The fixes which import a file of the workspace declaring a name which could not be resolved.
+/// If more than one file declares the name, it is up to the user which one to import.
+fn import_fixes(
+    path: &Path,
+    text: &str,
+    unresolved: &[(Range, String)],
+    symbols: &HashMap<PathBuf, Vec<SymbolInformation>>,
+) -> Vec<Fix> {
+    let Some(dir) = path.parent() else {
+        return Vec::new();
+    };
+
+    let position = import_position(text);
+    let mut fixes = Vec::new();
+
+    for (range, message) in unresolved {
+        let Some(name) = unresolved_name(message) else {
+            continue;
+        };
+
+        let imports = symbols
+            .iter()
+            .filter(|(file, symbols)| {
+                *file != path
+                    && symbols
+                        .iter()
+                        .any(|symbol| symbol.name == name && symbol.container_name.is_none())
+            })
+            .filter_map(|(file, _)| import_path(dir, file))
+            .sorted()
+            .collect_vec();
+
+        for import in &imports {
+            fixes.push(Fix {
+                title: format!("Import '{name}' from \"{import}\""),
+                range: *range,
+                message: message.clone(),
+                edits: vec![TextEdit {
+                    range: Range::new(position, position),
+                    new_text: format!("import \"{import}\";\n"),
+                }],
+                preferred: imports.len() == 1,
+            });
+        }
+    }
+
+    fixes
+}
+
+/// Where an import directive is added: after the pragmas and imports at the start of the file
+fn import_position(text: &str) -> Position {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .take_while(|line| {
+            line.is_empty()
+                || line.starts_with("//")
+                || line.starts_with("pragma ")
+                || line.starts_with("import ")
+        })
+        .enumerate()
+        .filter(|(_, line)| line.starts_with("pragma ") || line.starts_with("import "))
+        .last()
+        .map_or(0, |(line_no, _)| line_no + 1);
+
+    Position::new(line as u32, 0)
+}
+
+/// The path of a file as imported from a directory, e.g. `./lib/Token.sol` or `../Token.sol`
+fn import_path(dir: &Path, file: &Path) -> Option<String> {
+    let dir = dir.components().collect_vec();
+    let file = file.components().collect_vec();
+    let common = dir.iter().zip(&file).take_while(|(a, b)| a == b).count();
+
+    let mut import = if common == dir.len() {
+        "./".to_string()
+    } else {
+        "../".repeat(dir.len() - common)
+    };
+
+    let rest = file[common..]
+        .iter()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+
+    import.push_str(&rest.join("/"));
+
+    Some(import)
+}
+
+/// The semantic token type and modifiers of a code object. Variables are told apart by where they
+/// are declared, whether they can be changed, and where their data is.
+fn token_classification(
+    def_type: &DefinitionType,
+    ns: &ast::Namespace,
+    variables: &HashMap<usize, &symtable::Variable>,
+) -> Option<(SemanticTokenType, Vec<SemanticTokenModifier>)> {
+    let classification = match def_type {
+        DefinitionType::Function(function_no) => {
+            let func = &ns.functions[*function_no];
+
+            match func.ty {
+                pt::FunctionTy::Modifier => (SemanticTokenType::MODIFIER, vec![]),
+                pt::FunctionTy::Function if func.contract_no.is_some() => {
+                    (SemanticTokenType::METHOD, vec![])
+                }
+                pt::FunctionTy::Function => (SemanticTokenType::FUNCTION, vec![]),
+                // constructors, fallback and receive functions are keywords
+                _ => return None,
+            }
+        }
+        DefinitionType::Variable(var_no) => {
+            let var = variables.get(var_no)?;
+
+            let token_type = match var.usage_type {
+                VariableUsage::Parameter => SemanticTokenType::PARAMETER,
+                _ => SemanticTokenType::VARIABLE,
+            };
+
+            let modifiers = if matches!(var.ty, Type::StorageRef(..)) {
+                vec![SemanticTokenModifier::new("storage")]
+            } else if var.ty.is_reference_type(ns)
+                && !matches!(var.storage_location, Some(pt::StorageLocation::Calldata(_)))
+            {
+                vec![SemanticTokenModifier::new("memory")]
+            } else {
+                vec![]
+            };
+
+            (token_type, modifiers)
+        }
+        DefinitionType::NonLocalVariable(Some(contract_no), var_no) => {
+            let var = &ns.contracts[*contract_no].variables[*var_no];
+
+            let modifiers = if var.constant {
+                vec![
+                    SemanticTokenModifier::READONLY,
+                    SemanticTokenModifier::new("constant"),
+                ]
+            } else if var.immutable {
+                vec![
+                    SemanticTokenModifier::READONLY,
+                    SemanticTokenModifier::new("immutable"),
+                ]
+            } else {
+                vec![SemanticTokenModifier::new("storage")]
+            };
+
+            (SemanticTokenType::PROPERTY, modifiers)
+        }
+        DefinitionType::NonLocalVariable(None, _) => (
+            SemanticTokenType::VARIABLE,
+            vec![
+                SemanticTokenModifier::READONLY,
+                SemanticTokenModifier::new("constant"),
+            ],
+        ),
+        DefinitionType::Struct(StructType::UserDefined(_)) => (SemanticTokenType::STRUCT, vec![]),
+        DefinitionType::Struct(_) => (
+            SemanticTokenType::STRUCT,
+            vec![SemanticTokenModifier::DEFAULT_LIBRARY],
+        ),
+        DefinitionType::Field(..) => (SemanticTokenType::PROPERTY, vec![]),
+        DefinitionType::Enum(_) => (SemanticTokenType::ENUM, vec![]),
+        DefinitionType::Variant(..) => (SemanticTokenType::ENUM_MEMBER, vec![]),
+        DefinitionType::Contract(contract_no) => match ns.contracts[*contract_no].ty {
+            pt::ContractTy::Interface(_) => (SemanticTokenType::INTERFACE, vec![]),
+            pt::ContractTy::Library(_) => (SemanticTokenType::NAMESPACE, vec![]),
+            _ => (SemanticTokenType::CLASS, vec![]),
+        },
+        DefinitionType::Event(_) => (SemanticTokenType::EVENT, vec![]),
+        DefinitionType::UserType(_) => (SemanticTokenType::TYPE, vec![]),
+        DefinitionType::DynamicBytes | DefinitionType::File(_) => return None,
+    };
+
+    Some(classification)
+}
+
+/// Encodes the tokens of a file as the client expects them: each token is relative to the one
+/// before it. Tokens which span lines or overlap the token before them are dropped, and the
+/// modifiers of tokens found more than once are merged.
+fn encode_semantic_tokens(mut tokens: Vec<TokenEntry>, file: &ast::File) -> Vec<SemanticToken> {
+    tokens.sort_by_key(|token| (token.start, token.end));
+
+    let mut merged: Vec<TokenEntry> = Vec::new();
+
+    for token in tokens {
+        match merged.last_mut() {
+            Some(last) if last.start == token.start && last.end == token.end => {
+                last.modifiers |= token.modifiers;
+            }
+            Some(last) if token.start < last.end => (),
+            _ => merged.push(token),
+        }
+    }
+
+    let mut data = Vec::new();
+    let mut previous = (0, 0);
+
+    for token in merged {
+        let (line, column) = file.offset_to_line_column(token.start);
+        let (end_line, end_column) = file.offset_to_line_column(token.end);
+
+        if line != end_line || end_column <= column {
+            continue;
+        }
+
+        let (line, column) = (line as u32, column as u32);
+
+        data.push(SemanticToken {
+            delta_line: line - previous.0,
+            delta_start: if line == previous.0 {
+                column - previous.1
+            } else {
+                column
+            },
+            length: end_column as u32 - column,
+            token_type: token.token_type,
+            token_modifiers_bitset: token.modifiers,
+        });
+
+        previous = (line, column);
+    }
+
+    data
+}
+
 fn loc_to_range(loc: &pt::Loc, file: &ast::File) -> Range {
     get_range(loc.start(), loc.end(), file)
 }
@@ -2805,6 +5320,502 @@ fn get_range_exclusive(start: usize, end: usize, file: &ast::File) -> Range {
     get_range(start, end - 1, file)
 }
 
+/// The text on the line of the position, before the position
+fn line_before(text: &str, position: Position) -> Option<String> {
+    let line = text.lines().nth(position.line as usize)?;
+
+    Some(line.chars().take(position.character as usize).collect())
+}
+
+/// The text of the document before the position
+fn text_before(text: &str, position: Position) -> Option<&str> {
+    let mut offset = 0;
+    let mut lines = text.split_inclusive('\n');
+
+    for _ in 0..position.line {
+        offset += lines.next()?.len();
+    }
+
+    let line = lines
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches(['\r', '\n']);
+    let column = line
+        .char_indices()
+        .nth(position.character as usize)
+        .map_or(line.len(), |(column, _)| column);
+
+    Some(&text[..offset + column])
+}
+
+/// If the text ends in the arguments of a call, the name called with any qualifiers like `abi.`,
+/// and the number of the argument
+fn call_at(text: &str) -> Option<(&str, u32)> {
+    let mut depth = 0;
+    let mut argument = 0;
+
+    for (offset, c) in text.char_indices().rev() {
+        match c {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth > 0 => depth -= 1,
+            '(' => {
+                let callee = text[..offset].trim_end();
+                let before = callee.trim_end_matches(|c: char| {
+                    c.is_alphanumeric() || c == '_' || c == '$' || c == '.'
+                });
+                let name = &callee[before.len()..];
+
+                // the parameters of a declaration are not arguments
+                let declaration = ["function", "modifier", "event", "error"]
+                    .iter()
+                    .any(|keyword| before.trim_end().ends_with(keyword));
+
+                return (!name.is_empty() && !declaration).then_some((name, argument));
+            }
+            ',' if depth == 0 => argument += 1,
+            // the text is not in a call
+            '[' | '{' | ';' => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+/// The signature of something which can be called. The parameters are given as offsets in the
+/// label, so that the active one can be highlighted.
+fn signature_information(
+    name: String,
+    params: impl Iterator<Item = String>,
+    returns: &str,
+    documentation: String,
+) -> SignatureInformation {
+    let mut label = format!("{name}(");
+    let mut parameters = Vec::new();
+
+    for (no, param) in params.enumerate() {
+        if no > 0 {
+            label.push_str(", ");
+        }
+
+        let start = label.encode_utf16().count() as u32;
+        label.push_str(&param);
+        let end = label.encode_utf16().count() as u32;
+
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, end]),
+            documentation: None,
+        });
+    }
+
+    label.push(')');
+    label.push_str(returns);
+
+    SignatureInformation {
+        label,
+        documentation: (!documentation.is_empty()).then_some(Documentation::MarkupContent(
+            MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: documentation,
+            },
+        )),
+        parameters: Some(parameters),
+        active_parameter: None,
+    }
+}
+
+/// If the end of the line is in the path of an import directive, the part of the path on the line
+fn import_path_prefix(line: &str) -> Option<&str> {
+    let quote = line.rfind(['"', '\''])?;
+    let before = &line[..quote];
+
+    // the quote must open a string, and the string must be part of an import
+    if before.matches(['"', '\'']).count() % 2 != 0 || !before.trim_start().starts_with("import") {
+        return None;
+    }
+
+    Some(&line[quote + 1..])
+}
+
+/// The width of a range on a single line, or `None` if it spans lines
+fn range_width(range: &Range) -> Option<u32> {
+    (range.start.line == range.end.line).then(|| range.end.character - range.start.character)
+}
+
+/// All the references to the code object with the given definition, in all the files. Code
+/// objects are numbered differently in each namespace, so references are matched by the
+/// location of their definition.
+fn references_to(caches: &HashMap<PathBuf, FileCache>, definition: &Location) -> Vec<Location> {
+    caches
+        .iter()
+        .flat_map(|(p, cache)| {
+            let uri = Url::from_file_path(p).unwrap();
+            cache
+                .references
+                .iter()
+                .filter(|r| cache.definition(&r.val).as_ref() == Some(definition))
+                .map(move |r| Location {
+                    uri: uri.clone(),
+                    range: get_range_exclusive(r.start, r.stop, &cache.file),
+                })
+        })
+        .collect()
+}
+
+/// The outline of each file of the namespace. The code objects declared in a contract are its
+/// children, and the symbols are ordered as in the source.
+fn document_symbols(ns: &ast::Namespace) -> Vec<Vec<DocumentSymbol>> {
+    let in_file = |loc: &pt::Loc| matches!(loc, pt::Loc::File(..));
+
+    let mut contracts = Vec::new();
+    let mut items = Vec::new();
+
+    for contract in ns
+        .contracts
+        .iter()
+        .filter(|contract| in_file(&contract.loc))
+    {
+        contracts.push((contract.loc, contract_symbol(ns, contract)));
+
+        for var in &contract.variables {
+            items.push((var.loc, variable_symbol(ns, var)));
+        }
+    }
+
+    for var in &ns.constants {
+        items.push((var.loc, variable_symbol(ns, var)));
+    }
+
+    for func in &ns.functions {
+        // accessor functions are synthetic
+        if func.is_accessor || !in_file(&func.loc) {
+            continue;
+        }
+
+        items.push((func.loc, function_symbol(ns, func)));
+    }
+
+    for event in ns.events.iter().filter(|event| in_file(&event.loc)) {
+        items.push((
+            event.loc,
+            new_symbol(
+                ns,
+                &event.id.name,
+                SymbolKind::EVENT,
+                Some(event.signature.clone()),
+                &event.loc,
+                &event.id.loc,
+            ),
+        ));
+    }
+
+    for error in ns.errors.iter().filter(|error| in_file(&error.loc)) {
+        items.push((
+            error.loc,
+            new_symbol(
+                ns,
+                &error.name,
+                SymbolKind::OBJECT,
+                Some(ns.signature(&error.name, &error.fields)),
+                &error.loc,
+                &error.loc,
+            ),
+        ));
+    }
+
+    for struct_decl in ns.structs.iter().filter(|decl| in_file(&decl.loc)) {
+        // the location of a struct is its name, so extend it over the fields
+        let end = struct_decl
+            .fields
+            .iter()
+            .map(|field| field.loc.end())
+            .fold(struct_decl.loc.end(), usize::max);
+        let loc = pt::Loc::File(struct_decl.loc.file_no(), struct_decl.loc.start(), end);
+
+        let mut symbol = new_symbol(
+            ns,
+            &struct_decl.id.name,
+            SymbolKind::STRUCT,
+            None,
+            &loc,
+            &struct_decl.id.loc,
+        );
+
+        symbol.children = Some(
+            struct_decl
+                .fields
+                .iter()
+                .filter_map(|field| {
+                    let id = field.id.as_ref()?;
+
+                    Some(new_symbol(
+                        ns,
+                        &id.name,
+                        SymbolKind::FIELD,
+                        Some(field.ty.to_string(ns)),
+                        &field.loc,
+                        &id.loc,
+                    ))
+                })
+                .collect(),
+        );
+
+        items.push((loc, symbol));
+    }
+
+    for enum_decl in ns.enums.iter().filter(|decl| in_file(&decl.loc)) {
+        let mut symbol = new_symbol(
+            ns,
+            &enum_decl.id.name,
+            SymbolKind::ENUM,
+            None,
+            &enum_decl.loc,
+            &enum_decl.id.loc,
+        );
+
+        symbol.children = Some(
+            enum_decl
+                .values
+                .iter()
+                .map(|(name, loc)| new_symbol(ns, name, SymbolKind::ENUM_MEMBER, None, loc, loc))
+                .collect(),
+        );
+
+        items.push((enum_decl.loc, symbol));
+    }
+
+    for user_type in ns.user_types.iter().filter(|decl| in_file(&decl.loc)) {
+        items.push((
+            user_type.loc,
+            new_symbol(
+                ns,
+                &user_type.name,
+                SymbolKind::TYPE_PARAMETER,
+                Some(user_type.ty.to_string(ns)),
+                &user_type.loc,
+                &user_type.loc,
+            ),
+        ));
+    }
+
+    let mut symbols = vec![Vec::new(); ns.files.len()];
+
+    for (loc, symbol) in items {
+        let contract = contracts.iter_mut().find(|(contract_loc, _)| {
+            contract_loc.file_no() == loc.file_no()
+                && contract_loc.start() <= loc.start()
+                && loc.end() <= contract_loc.end()
+        });
+
+        match contract {
+            Some((_, contract)) => contract.children.get_or_insert_with(Vec::new).push(symbol),
+            None => symbols[loc.file_no()].push(symbol),
+        }
+    }
+
+    for (loc, contract) in contracts {
+        symbols[loc.file_no()].push(contract);
+    }
+
+    for file_symbols in &mut symbols {
+        file_symbols.sort_by_key(|symbol| symbol.range.start);
+
+        for symbol in file_symbols.iter_mut() {
+            if let Some(children) = &mut symbol.children {
+                children.sort_by_key(|symbol| symbol.range.start);
+            }
+        }
+    }
+
+    symbols
+}
+
+fn contract_symbol(ns: &ast::Namespace, contract: &ast::Contract) -> DocumentSymbol {
+    let kind = match contract.ty {
+        pt::ContractTy::Interface(_) => SymbolKind::INTERFACE,
+        pt::ContractTy::Library(_) => SymbolKind::MODULE,
+        _ => SymbolKind::CLASS,
+    };
+
+    new_symbol(
+        ns,
+        &contract.id.name,
+        kind,
+        Some(contract.ty.to_string()),
+        &contract.loc,
+        &contract.id.loc,
+    )
+}
+
+fn function_symbol(ns: &ast::Namespace, func: &ast::Function) -> DocumentSymbol {
+    let kind = match func.ty {
+        pt::FunctionTy::Constructor => SymbolKind::CONSTRUCTOR,
+        _ if func.contract_no.is_some() => SymbolKind::METHOD,
+        _ => SymbolKind::FUNCTION,
+    };
+
+    // constructors, fallback and receive functions usually have no name
+    let name = if func.id.name.is_empty() {
+        func.ty.to_string()
+    } else {
+        func.id.name.clone()
+    };
+
+    let name_loc = if func.id.name.is_empty() {
+        &func.loc_prototype
+    } else {
+        &func.id.loc
+    };
+
+    let detail = matches!(func.ty, pt::FunctionTy::Function | pt::FunctionTy::Modifier)
+        .then(|| func.signature.clone());
+
+    new_symbol(ns, &name, kind, detail, &func.loc, name_loc)
+}
+
+#[allow(deprecated)]
+fn new_symbol(
+    ns: &ast::Namespace,
+    name: &str,
+    kind: SymbolKind,
+    detail: Option<String>,
+    loc: &pt::Loc,
+    name_loc: &pt::Loc,
+) -> DocumentSymbol {
+    let file = &ns.files[loc.file_no()];
+
+    DocumentSymbol {
+        name: name.to_string(),
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: loc_to_range(loc, file),
+        selection_range: loc_to_range(name_loc, file),
+        children: None,
+    }
+}
+
+fn variable_symbol(ns: &ast::Namespace, var: &ast::Variable) -> DocumentSymbol {
+    let kind = if var.constant {
+        SymbolKind::CONSTANT
+    } else {
+        SymbolKind::FIELD
+    };
+
+    new_symbol(
+        ns,
+        &var.name,
+        kind,
+        Some(var.ty.to_string(ns)),
+        &var.loc,
+        &var.name_loc,
+    )
+}
+
+fn call_hierarchy_item(path: &Path, symbol: DocumentSymbol) -> Option<CallHierarchyItem> {
+    Some(CallHierarchyItem {
+        name: symbol.name,
+        kind: symbol.kind,
+        tags: None,
+        detail: symbol.detail,
+        uri: Url::from_file_path(path).ok()?,
+        range: symbol.range,
+        selection_range: symbol.selection_range,
+        data: None,
+    })
+}
+
+fn type_hierarchy_item(path: &Path, symbol: DocumentSymbol) -> Option<TypeHierarchyItem> {
+    Some(TypeHierarchyItem {
+        name: symbol.name,
+        kind: symbol.kind,
+        tags: None,
+        detail: symbol.detail,
+        uri: Url::from_file_path(path).ok()?,
+        range: symbol.range,
+        selection_range: symbol.selection_range,
+        data: None,
+    })
+}
+
+/// The function or contract of a call or type hierarchy item. Items are sent back by the client,
+/// so they are found by where they are declared.
+fn hierarchy_index(
+    items: &HierarchyItems,
+    uri: &Url,
+    selection_range: Range,
+) -> Option<DefinitionIndex> {
+    items
+        .iter()
+        .find(|(di, symbol)| {
+            Url::from_file_path(&di.def_path).as_ref() == Ok(uri)
+                && symbol.selection_range == selection_range
+        })
+        .map(|(di, _)| di.clone())
+}
+
+/// Flatten the outline of a file into the symbols for workspace symbol search
+#[allow(deprecated)]
+fn symbol_information(
+    uri: &Url,
+    symbols: &[DocumentSymbol],
+    container_name: Option<&str>,
+) -> Vec<SymbolInformation> {
+    symbols
+        .iter()
+        .flat_map(|symbol| {
+            let info = SymbolInformation {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                deprecated: None,
+                location: Location {
+                    uri: uri.clone(),
+                    range: symbol.range,
+                },
+                container_name: container_name.map(str::to_string),
+            };
+
+            let children = symbol.children.as_deref().unwrap_or_default();
+
+            iter::once(info).chain(symbol_information(uri, children, Some(&symbol.name)))
+        })
+        .collect()
+}
+
+/// Does the name contain the characters of the query in order, ignoring case
+fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|c| name.any(|n| n == c))
+}
+
+/// Find the Solidity files in a directory and its subdirectories, except for hidden directories
+/// and `node_modules`
+fn solidity_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "node_modules" {
+                solidity_files(&path, files);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "sol") {
+            files.push(path);
+        }
+    }
+}
+
 fn get_type_definition(ty: &Type) -> Option<DefinitionType> {
     match ty {
         Type::Enum(id) => Some(DefinitionType::Enum(*id)),
@@ -3002,4 +6013,40 @@ mod test {
             ),
         );
     }
+
+    #[test]
+    fn unit_hash() {
+        let mut files = Files::default();
+        let (a, b, c) = (
+            PathBuf::from("/a.sol"),
+            PathBuf::from("/b.sol"),
+            PathBuf::from("/c.sol"),
+        );
+
+        files
+            .text_buffers
+            .insert(a.clone(), "import \"b.sol\";".to_string());
+        files
+            .text_buffers
+            .insert(b.clone(), "contract B {}".to_string());
+        files
+            .text_buffers
+            .insert(c.clone(), "contract C {}".to_string());
+        files
+            .dependencies
+            .insert(a.clone(), BTreeSet::from([b.clone()]));
+
+        let hash = files.unit_hash(&a);
+
+        // files which are not imported do not matter
+        files
+            .text_buffers
+            .insert(c.clone(), "contract C { }".to_string());
+        assert_eq!(hash, files.unit_hash(&a));
+
+        files
+            .text_buffers
+            .insert(b.clone(), "contract B { }".to_string());
+        assert_ne!(hash, files.unit_hash(&a));
+    }
 }
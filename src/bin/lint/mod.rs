@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::{imports_arg, target_arg, Lint, LintConfigFile, LintSettings};
+use crate::find_config_file;
+use clap::ArgMatches;
+use serde_json::{json, Value};
+use solang::{
+    sarif::{lint_rules, SarifLog},
+    sema::{
+        ast::{Level, Namespace},
+        diagnostics::Diagnostics,
+        lint::{lint as lint_namespace, Finding, LintConfig, Rule},
+    },
+};
+use solang_parser::pt::Loc;
+use std::{fs, path::Path, process::exit};
+
+/// Run the lint rules over the input files, and print the findings in the requested format.
+/// Exits with an error if any input file has errors, or if any finding is at least as severe
+/// as the `fail-on` setting.
+pub(crate) fn lint(lint_args: &Lint, matches: &ArgMatches) {
+    let mut settings =
+        if let Some(conf_file) = find_config_file(&lint_args.configuration_file, matches) {
+            read_lint_settings(&conf_file)
+        } else {
+            LintSettings::default()
+        };
+
+    settings.overwrite_with_args(lint_args);
+
+    let config = lint_config(&settings);
+
+    let format = settings.format.as_deref().unwrap_or("text");
+
+    if !matches!(format, "text" | "json" | "sarif") {
+        eprintln!("error: unknown lint output format '{format}'");
+        exit(1);
+    }
+
+    let fail_on = match settings.fail_on.as_deref().unwrap_or("error") {
+        "error" => Some(Level::Error),
+        "warning" => Some(Level::Warning),
+        "never" => None,
+        fail_on => {
+            eprintln!("error: unknown fail-on setting '{fail_on}'");
+            exit(1);
+        }
+    };
+
+    let target = target_arg(&lint_args.target);
+    let mut resolver = imports_arg(&lint_args.package);
+
+    let mut errors = false;
+    let mut failed = false;
+    let mut json_results = Vec::new();
+    let mut sarif_results = Vec::new();
+
+    for filename in &lint_args.package.input {
+        let filepath = match filename.canonicalize() {
+            Ok(filename) => filename,
+            Err(_) => filename.to_path_buf(),
+        };
+
+        let mut ns = solang::parse_and_resolve(filepath.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            errors = true;
+            continue;
+        }
+
+        let findings: Vec<Finding> = lint_namespace(&ns, &config)
+            .into_iter()
+            .filter(|finding| match &lint_args.package.contracts {
+                Some(names) => finding
+                    .contract_no
+                    .is_some_and(|contract_no| names.contains(&ns.contracts[contract_no].id.name)),
+                None => true,
+            })
+            .collect();
+
+        failed |= findings.iter().any(|finding| {
+            fail_on
+                .as_ref()
+                .is_some_and(|level| finding.diagnostic.level >= *level)
+        });
+
+        match format {
+            "json" => {
+                json_results.extend(findings.iter().map(|finding| json_finding(finding, &ns)))
+            }
+            "sarif" => sarif_results.extend(ns.findings_as_sarif(&findings)),
+            _ => {
+                // report the lint findings rather than the compiler warnings
+                ns.diagnostics = Diagnostics::default();
+
+                for finding in findings {
+                    let mut diagnostic = finding.diagnostic;
+                    diagnostic.message = format!("{} [{}]", diagnostic.message, finding.rule);
+                    ns.diagnostics.push(diagnostic);
+                }
+
+                ns.print_diagnostics(&resolver, false);
+            }
+        }
+    }
+
+    match format {
+        "json" => println!("{}", serde_json::to_string(&json_results).unwrap()),
+        "sarif" => {
+            let log = SarifLog::new(env!("SOLANG_VERSION"), lint_rules(), sarif_results);
+
+            println!("{}", serde_json::to_string_pretty(&log).unwrap());
+        }
+        _ => (),
+    }
+
+    if errors || failed {
+        exit(1);
+    }
+}
+
+fn read_lint_settings(path: &Path) -> LintSettings {
+    let toml_data = fs::read_to_string(path).unwrap();
+
+    match toml::from_str::<LintConfigFile>(&toml_data) {
+        Ok(config_file) => config_file.lint,
+        Err(err) => {
+            eprintln!("{err}");
+            exit(1);
+        }
+    }
+}
+
+fn lint_config(settings: &LintSettings) -> LintConfig {
+    let mut config = LintConfig::default();
+
+    for (name, severity) in &settings.rules {
+        let rule = match name.parse::<Rule>() {
+            Ok(rule) => rule,
+            Err(message) => {
+                eprintln!("error: {message}");
+                exit(1);
+            }
+        };
+
+        let level = match severity.as_str() {
+            "error" => Some(Level::Error),
+            "warning" => Some(Level::Warning),
+            "info" => Some(Level::Info),
+            "off" => None,
+            _ => {
+                eprintln!("error: unknown severity '{severity}' for lint rule '{name}'");
+                exit(1);
+            }
+        };
+
+        config.set(rule, level);
+    }
+
+    config
+}
+
+fn json_finding(finding: &Finding, ns: &Namespace) -> Value {
+    let (file, [line, column, end_line, end_column]) =
+        if let Loc::File(file_no, start, end) = finding.diagnostic.loc {
+            let file = &ns.files[file_no];
+            let (start_line, start_column) = file.offset_to_line_column(start);
+            let (end_line, end_column) = file.offset_to_line_column(end);
+
+            (
+                file.to_string(),
+                [
+                    start_line + 1,
+                    start_column + 1,
+                    end_line + 1,
+                    end_column + 1,
+                ],
+            )
+        } else {
+            (String::new(), [0; 4])
+        };
+
+    json!({
+        "rule": finding.rule.name(),
+        "severity": finding.diagnostic.level.as_str(),
+        "message": finding.diagnostic.message,
+        "file": file,
+        "line": line,
+        "column": column,
+        "endLine": end_line,
+        "endColumn": end_column,
+    })
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Differential testing of solang against reference encoders and solc
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use itertools::Itertools;
+use solang::file_resolver::FileResolver;
+use solang::testing::differential::{compare_with_solc, fuzz};
+use solang::Target;
+use std::path::PathBuf;
+use std::process::exit;
+
+#[derive(Parser)]
+#[command(author = env!("CARGO_PKG_AUTHORS"), version = concat!("version ", env!("SOLANG_VERSION")), about = "Differential testing of the Solang Solidity Compiler", subcommand_required = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(
+        about = "Compare the ABI encoding of random arguments with the reference encoder of the target"
+    )]
+    Abi(Abi),
+    #[command(about = "Compare the Ethereum ABI of contracts with the ABI generated by solc")]
+    Solc(Solc),
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FuzzTarget {
+    Polkadot,
+    Solana,
+}
+
+#[derive(Args)]
+struct Abi {
+    #[arg(
+        name = "TARGET",
+        long = "target",
+        value_enum,
+        default_value = "polkadot",
+        help = "Target to test"
+    )]
+    target: FuzzTarget,
+
+    #[arg(
+        name = "RUNS",
+        long = "runs",
+        default_value = "100",
+        help = "Number of functions to test"
+    )]
+    runs: usize,
+
+    #[arg(
+        name = "SEED",
+        long = "seed",
+        help = "Seed of the random generator, random if not given"
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        name = "MAXARGS",
+        long = "max-args",
+        default_value = "4",
+        help = "Maximum number of arguments of a function"
+    )]
+    max_args: usize,
+}
+
+#[derive(Args)]
+struct Solc {
+    #[arg(
+        name = "SOLC",
+        long = "solc",
+        default_value = "solc",
+        help = "Path to the solc binary"
+    )]
+    solc: PathBuf,
+
+    #[arg(name = "INPUT", help = "Solidity input files", required = true, num_args = 1..)]
+    input: Vec<PathBuf>,
+
+    #[arg(name = "IMPORTPATH", short = 'I', long = "importpath", num_args = 1)]
+    import_path: Vec<PathBuf>,
+}
+
+fn main() {
+    let failed = match Cli::parse().command {
+        Commands::Abi(args) => abi(&args),
+        Commands::Solc(args) => solc(&args),
+    };
+
+    if failed {
+        exit(1);
+    }
+}
+
+fn abi(args: &Abi) -> bool {
+    let target = match args.target {
+        FuzzTarget::Polkadot => Target::default_polkadot(),
+        FuzzTarget::Solana => Target::Solana,
+    };
+
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    println!(
+        "testing {} functions on {target} with seed {seed}",
+        args.runs
+    );
+
+    let failures = fuzz(
+        &target,
+        seed,
+        args.runs,
+        args.max_args,
+        |run, case, reason| {
+            println!(
+                "run {run} failed: {reason}\n{}arguments: {:?}\n",
+                case.source(&target),
+                case.args
+            );
+        },
+    );
+
+    println!("{failures} of {} functions failed", args.runs);
+
+    failures != 0
+}
+
+fn solc(args: &Solc) -> bool {
+    let mut failed = false;
+
+    for input in &args.input {
+        let mut resolver = FileResolver::default();
+
+        for path in args.import_path.iter().unique() {
+            resolver.add_import_path(path);
+        }
+
+        match compare_with_solc(&args.solc, input, &mut resolver) {
+            Ok(differences) => {
+                for difference in &differences {
+                    println!("{}: {difference}", input.display());
+                }
+
+                failed |= !differences.is_empty();
+            }
+            Err(err) => {
+                eprintln!("{}: error: {err}", input.display());
+                failed = true;
+            }
+        }
+    }
+
+    failed
+}
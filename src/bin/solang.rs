@@ -1,16 +1,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::{Command, CommandFactory, FromArgMatches};
+use clap::{parser::ValueSource, ArgMatches, Command, CommandFactory, FromArgMatches};
 
 use clap_complete::generate;
 use cli::PackageTrait;
 use itertools::Itertools;
 use solang::{
     abi,
+    build_info::{self, BuildInfo},
     codegen::{codegen, Options},
+    coverage,
     emit::Generate,
     file_resolver::FileResolver,
-    sema::{ast::Namespace, file::PathDisplay},
+    lir::{
+        cost::{gen_cost_report, CostReport},
+        heap::{gen_heap_report, HeapReport},
+        stack::{gen_stack_report, FunctionStack, StackReport},
+    },
+    metadata::{self, Metadata},
+    sarif::{diagnostic_rules, SarifLog},
+    sema::{ast::Namespace, file::PathDisplay, warnings::WarningConfig},
+    session::Session,
+    size_report::{gen_size_report, SizeReport},
+    source_map,
     standard_json::{EwasmContract, JsonContract, JsonResult},
 };
 use std::{
@@ -23,15 +35,19 @@ use std::{
 };
 
 use crate::cli::{
-    imports_arg, options_arg, target_arg, Cli, Commands, Compile, CompilerOutput, Doc, New,
-    ShellComplete,
+    imports_arg, options_arg, target_arg, warnings_arg, Cli, Commands, Compile, CompilerOutput,
+    ContractOverrides, Doc, New, ShellComplete,
 };
 
 mod cli;
 mod doc;
 mod idl;
+mod inspect;
 #[cfg(feature = "language_server")]
 mod languageserver;
+mod lint;
+mod verify;
+mod watch;
 
 fn main() {
     let matches = Cli::command().get_matches();
@@ -42,17 +58,18 @@ fn main() {
         Commands::Doc(doc_args) => doc(doc_args),
         Commands::Compile(compile_args) => {
             // Read config from configuration file. If extra args exist, only overwrite the fields that the user explicitly provides.
-            let config = if let Some(conf_file) = &compile_args.configuration_file {
-                if PathBuf::from(conf_file).exists() {
-                    eprintln!("info: reading default config from toml file");
-                    let debug = matches.subcommand_matches("compile").unwrap();
-                    let mut compile = read_toml_config(conf_file);
-                    compile.overwrite_with_matches(debug);
-
-                    compile
-                } else {
-                    compile_args
-                }
+            let debug = matches.subcommand_matches("compile").unwrap();
+
+            let config = if let Some(conf_file) =
+                find_config_file(&compile_args.configuration_file, debug)
+            {
+                eprintln!("info: reading default config from toml file");
+                let mut compile = read_toml_config(&conf_file);
+                compile
+                    .relative_to(conf_file.parent().unwrap())
+                    .overwrite_with_matches(debug);
+
+                compile
             } else {
                 compile_args
             };
@@ -63,10 +80,49 @@ fn main() {
         Commands::LanguageServer(server_args) => languageserver::start_server(&server_args),
         Commands::Idl(idl_args) => idl::idl(&idl_args),
         Commands::New(new_arg) => new_command(new_arg),
+        #[cfg(feature = "test_runner")]
+        Commands::Test(test_args) => test(test_args),
+        #[cfg(feature = "formatter")]
+        Commands::Fmt(fmt_args) => fmt(fmt_args),
+        Commands::Lint(lint_args) => {
+            lint::lint(&lint_args, matches.subcommand_matches("lint").unwrap())
+        }
+        Commands::Graph(graph_args) => graph(graph_args),
+        Commands::Selectors(selectors_args) => selectors(selectors_args),
+        Commands::Flatten(flatten_args) => flatten(flatten_args),
+        Commands::Inspect(inspect_args) => inspect::inspect(&inspect_args),
+        Commands::Verify(verify_args) => verify::verify(&verify_args),
+        Commands::CheckUpgrade(check_args) => check_upgrade(check_args),
+        Commands::Coverage(coverage_args) => coverage_report(coverage_args),
+    }
+}
+
+/// Find the configuration file. If the configuration file was not given on the command line and
+/// there is no solang.toml in the current directory, look for one in the parent directories, so
+/// that a project can be compiled from any of its subdirectories.
+pub(crate) fn find_config_file(
+    configuration_file: &Option<OsString>,
+    matches: &ArgMatches,
+) -> Option<PathBuf> {
+    let conf_file = PathBuf::from(configuration_file.as_ref()?);
+
+    if conf_file.exists() {
+        return Some(conf_file);
+    }
+
+    if matches.value_source("CONFFILE") == Some(ValueSource::CommandLine) {
+        return None;
     }
+
+    std::env::current_dir()
+        .ok()?
+        .ancestors()
+        .skip(1)
+        .map(|dir| dir.join(&conf_file))
+        .find(|path| path.exists())
 }
 
-fn read_toml_config(path: &OsString) -> Compile {
+fn read_toml_config(path: &Path) -> Compile {
     let toml_data = fs::read_to_string(path).unwrap();
 
     let res: Result<Compile, _> = toml::from_str(&toml_data);
@@ -83,6 +139,51 @@ fn read_toml_config(path: &OsString) -> Compile {
 fn new_command(args: New) {
     let target = args.target_name.as_str();
 
+    if target == "evm" {
+        eprintln!("EVM target is not supported yet!");
+        exit(1);
+    }
+
+    // The source files of the project. The first file has the contract to build, which has the
+    // same name as the file.
+    let sources: &[(&str, &str)] = match (args.template.as_str(), target) {
+        ("flipper", "solana") => &[(
+            "flipper.sol",
+            include_str!("../../examples/solana/flipper.sol"),
+        )],
+        ("flipper", "polkadot") => &[(
+            "flipper.sol",
+            include_str!("../../examples/polkadot/flipper.sol"),
+        )],
+        ("counter", "solana") => &[(
+            "counter.sol",
+            include_str!("../../examples/solana/counter.sol"),
+        )],
+        ("counter", "polkadot") => &[(
+            "counter.sol",
+            include_str!("../../examples/polkadot/counter.sol"),
+        )],
+        ("erc20", "solana") => &[("erc20.sol", include_str!("../../examples/solana/erc20.sol"))],
+        ("erc20", "polkadot") => &[(
+            "erc20.sol",
+            include_str!("../../examples/polkadot/erc20.sol"),
+        )],
+        ("spl-token-wrapper", "solana") => &[
+            (
+                "spl_token_wrapper.sol",
+                include_str!("../../examples/solana/spl_token_wrapper.sol"),
+            ),
+            (
+                "spl_token.sol",
+                include_str!("../../solana-library/spl_token.sol"),
+            ),
+        ],
+        (template, _) => {
+            eprintln!("the {template} template is only available for Solana");
+            exit(1);
+        }
+    };
+
     // Default project name is "solana_project" or "polkadot_project"
     let default_path = OsString::from(format!("{target}_project"));
 
@@ -93,20 +194,11 @@ fn new_command(args: New) {
         exit(1);
     }
 
-    let flipper = match target {
-        "solana" => include_str!("../../examples/solana/flipper.sol"),
-        "polkadot" => include_str!("../../examples/polkadot/flipper.sol"),
-        "evm" => {
-            eprintln!("EVM target is not supported yet!");
-            exit(1);
-        }
-        _ => unreachable!(),
-    };
-
-    let mut flipper_file = create_file(&Path::new(&dir_path).join("flipper.sol"));
-    flipper_file
-        .write_all(flipper.to_string().as_bytes())
-        .expect("failed to write flipper example");
+    for (name, source) in sources {
+        let mut file = create_file(&Path::new(&dir_path).join(name));
+        file.write_all(source.as_bytes())
+            .expect("failed to write example contract");
+    }
 
     let mut toml_file = create_file(&Path::new(&dir_path).join("solang.toml"));
 
@@ -115,8 +207,12 @@ fn new_command(args: New) {
         "polkadot" => include_str!("../../examples/polkadot/polkadot_config.toml"),
         _ => unreachable!(),
     };
+
+    // the example configuration builds flipper
+    let toml_content = toml_content.replace("flipper", sources[0].0.trim_end_matches(".sol"));
+
     toml_file
-        .write_all(toml_content.to_string().as_bytes())
+        .write_all(toml_content.as_bytes())
         .expect("failed to write example toml configuration file");
 }
 
@@ -129,7 +225,12 @@ fn doc(doc_args: Doc) {
     let mut files = Vec::new();
 
     for filename in doc_args.package.input {
-        let ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        // the storage layout is determined in codegen
+        if doc_args.storage {
+            codegen(&mut ns, &Options::default());
+        }
 
         ns.print_diagnostics(&resolver, verbose);
 
@@ -150,12 +251,478 @@ fn doc(doc_args: Doc) {
                 .output_directory
                 .unwrap_or_else(|| OsString::from(".")),
             &files,
+            doc_args.storage,
             verbose,
         );
     }
 }
 
+fn graph(graph_args: cli::Graph) {
+    let target = target_arg(&graph_args.target);
+    let mut resolver: FileResolver = imports_arg(&graph_args.package);
+
+    let mut success = true;
+
+    for filename in &graph_args.package.input {
+        let ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            success = false;
+            continue;
+        }
+
+        let graph = match graph_args.kind.as_str() {
+            "calls" => ns.call_graph(),
+            "imports" => ns.import_graph(),
+            _ => ns.inheritance_graph(),
+        };
+
+        let (output, extension) = if graph_args.format == "json" {
+            (graph.json(), "json")
+        } else {
+            (graph.dot(), "dot")
+        };
+
+        if let Some(output_directory) = &graph_args.output_directory {
+            let stem = filename.file_stem().unwrap().to_string_lossy();
+            let path = output_directory.join(format!("{stem}.{}.{extension}", graph_args.kind));
+
+            if let Err(err) =
+                create_dir_all(output_directory).and_then(|_| fs::write(&path, output))
+            {
+                eprintln!("error: cannot write '{}': {err}", path.display());
+                exit(1);
+            }
+        } else {
+            println!("{}", output.trim_end());
+        }
+    }
+
+    if !success {
+        exit(1);
+    }
+}
+
+fn selectors(selectors_args: cli::Selectors) {
+    let target = target_arg(&selectors_args.target);
+    let mut resolver: FileResolver = imports_arg(&selectors_args.package);
+
+    let mut success = true;
+    let mut lists = Vec::new();
+
+    for filename in &selectors_args.package.input {
+        let ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            success = false;
+            continue;
+        }
+
+        // contracts that were imported are not listed
+        lists.extend(
+            ns.contracts
+                .iter()
+                .enumerate()
+                .filter(|(_, contract)| {
+                    contract.loc.try_file_no() == Some(ns.top_file_no())
+                        && match &selectors_args.package.contracts {
+                            Some(names) => names.contains(&contract.id.name),
+                            None => true,
+                        }
+                })
+                .map(|(contract_no, _)| abi::selectors::gen_selectors(contract_no, &ns)),
+        );
+    }
+
+    if selectors_args.format == "json" {
+        println!("{}", serde_json::to_string_pretty(&lists).unwrap());
+    } else {
+        for list in &lists {
+            println!("{}:", list.contract);
+
+            for (kind, selectors) in [
+                ("function", &list.functions),
+                ("event", &list.events),
+                ("error", &list.errors),
+            ] {
+                for selector in selectors {
+                    println!(
+                        "  {kind:<8} {:<10} {}",
+                        selector.selector.as_deref().unwrap_or("-"),
+                        selector.signature
+                    );
+                }
+            }
+        }
+    }
+
+    if !success {
+        exit(1);
+    }
+}
+
+fn flatten(flatten_args: cli::Flatten) {
+    let target = target_arg(&flatten_args.target);
+    let mut resolver: FileResolver = imports_arg(&flatten_args.package);
+
+    let mut success = true;
+
+    for filename in &flatten_args.package.input {
+        let ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            success = false;
+            continue;
+        }
+
+        let output = match solang::flatten::flatten(&ns, &resolver) {
+            Ok(output) => output,
+            Err(err) => {
+                eprintln!("error: {err}");
+                success = false;
+                continue;
+            }
+        };
+
+        if let Some(output_directory) = &flatten_args.output_directory {
+            let stem = filename.file_stem().unwrap().to_string_lossy();
+            let path = output_directory.join(format!("{stem}.flat.sol"));
+
+            if let Err(err) =
+                create_dir_all(output_directory).and_then(|_| fs::write(&path, output))
+            {
+                eprintln!("error: cannot write '{}': {err}", path.display());
+                exit(1);
+            }
+        } else {
+            print!("{output}");
+        }
+    }
+
+    if !success {
+        exit(1);
+    }
+}
+
+fn check_upgrade(check_args: cli::CheckUpgrade) {
+    let old = fs::read_to_string(&check_args.old)
+        .map_err(|err| err.to_string())
+        .and_then(|json| abi::upgrade::UpgradeInfo::from_json(&json))
+        .unwrap_or_else(|err| {
+            eprintln!("{}: error: {err}", check_args.old.display());
+            exit(1);
+        });
+
+    let target = target_arg(&check_args.target);
+
+    if old.storage_layout.target != target.to_string() {
+        eprintln!(
+            "error: '{}' was compiled for target {}, not {}",
+            check_args.old.display(),
+            old.storage_layout.target,
+            target
+        );
+        exit(1);
+    }
+
+    // the contract has the same name in the new version, unless given on the command line
+    let name = check_args
+        .package
+        .contracts
+        .as_ref()
+        .and_then(|contracts| contracts.first())
+        .unwrap_or(&old.contract_name);
+
+    let mut resolver: FileResolver = imports_arg(&check_args.package);
+    let mut new = None;
+
+    for filename in &check_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        // the storage layout is determined during codegen
+        codegen(&mut ns, &Options::default());
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            exit(1);
+        }
+
+        if let Some(contract_no) = ns
+            .contracts
+            .iter()
+            .position(|contract| contract.id.name == *name)
+        {
+            new = Some(abi::upgrade::gen_upgrade_info(contract_no, &ns));
+        }
+    }
+
+    let Some(new) = new else {
+        eprintln!("error: contract '{name}' not found");
+        exit(1);
+    };
+
+    let issues = abi::upgrade::check_upgrade(&old, &new);
+
+    if issues.is_empty() {
+        println!("contract '{name}' can be upgraded safely");
+    } else {
+        for issue in &issues {
+            println!("error: {issue}");
+        }
+
+        exit(1);
+    }
+}
+
+#[cfg(feature = "formatter")]
+fn fmt(fmt_args: cli::Fmt) {
+    let mut errors = false;
+    let mut unformatted = false;
+
+    for filename in &fmt_args.input {
+        let source = match fs::read_to_string(filename) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("error: cannot read '{}': {err}", filename.display());
+                errors = true;
+                continue;
+            }
+        };
+
+        let formatted = match solang::formatter::format_source(&source, fmt_args.tab_width) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                eprintln!("error: cannot format '{}': {err}", filename.display());
+                errors = true;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if fmt_args.check {
+            println!("{} is not formatted", filename.display());
+            unformatted = true;
+        } else if let Err(err) = fs::write(filename, formatted) {
+            eprintln!("error: cannot write '{}': {err}", filename.display());
+            errors = true;
+        }
+    }
+
+    if errors || unformatted {
+        exit(1);
+    }
+}
+
+#[cfg(feature = "test_runner")]
+fn test(test_args: cli::Test) {
+    let target = target_arg(&test_args.target);
+    let mut resolver = imports_arg(&test_args.package);
+
+    let opt = Options {
+        log_runtime_errors: true,
+        coverage: test_args.coverage.is_some(),
+        ..Default::default()
+    };
+
+    let fuzz_options = solang::test_runner::FuzzOptions {
+        runs: test_args.runs,
+        depth: test_args.depth,
+        seed: test_args.seed.unwrap_or_else(rand::random),
+    };
+
+    let mut errors = false;
+    let mut broken_invariants = false;
+    let mut gas_snapshot = solang::test_runner::GasSnapshot::default();
+    let mut passed = 0;
+    let mut failed = 0;
+    let mut report = coverage::Report::default();
+
+    for filename in &test_args.package.input {
+        let mut ns = solang::parse_and_resolve(filename.as_os_str(), &mut resolver, target);
+
+        codegen(&mut ns, &opt);
+
+        ns.print_diagnostics(&resolver, false);
+
+        if ns.diagnostics.any_errors() {
+            errors = true;
+            continue;
+        }
+
+        // the test contracts themselves are not part of the report
+        for contract_no in 0..ns.contracts.len() {
+            if ns.contracts[contract_no].instantiable
+                && !solang::test_runner::is_test_contract(contract_no, &ns)
+            {
+                report.add_map(coverage::gen_coverage_map(contract_no, &ns));
+            }
+        }
+
+        let results = match solang::test_runner::run_tests_with_fuzzing(
+            &ns,
+            &opt,
+            test_args.filter.as_deref(),
+            &fuzz_options,
+        ) {
+            Ok(results) => results,
+            Err(message) => {
+                eprintln!("error: {message}");
+                exit(1);
+            }
+        };
+
+        let results: Vec<_> = results
+            .into_iter()
+            .filter(|result| {
+                test_args
+                    .package
+                    .contracts
+                    .as_ref()
+                    .map_or(true, |names| names.contains(&result.contract))
+            })
+            .collect();
+
+        if results.is_empty() {
+            continue;
+        }
+
+        gas_snapshot.record(&results);
+
+        println!("running {} tests in {}", results.len(), filename.display());
+
+        for result in results {
+            let name = format!("{}.{}()", result.contract, result.function);
+
+            if let Some(reason) = &result.failure {
+                println!("[FAIL: {reason}] {name} (gas: {})", result.gas);
+                failed += 1;
+            } else {
+                println!("[PASS] {name} (gas: {})", result.gas);
+                passed += 1;
+            }
+
+            let logs = report.add_logs(&result.logs);
+
+            if !result.passed() || test_args.verbose {
+                for call in &result.calls {
+                    println!("    call: {call}");
+                }
+
+                for line in logs.lines() {
+                    println!("    {line}");
+                }
+
+                for event in &result.events {
+                    println!("    event: {event}");
+                }
+            }
+
+            broken_invariants |= !result.calls.is_empty();
+        }
+    }
+
+    if !errors && passed + failed == 0 {
+        eprintln!("error: no tests found");
+        exit(1);
+    }
+
+    println!("test result: {passed} passed; {failed} failed");
+
+    if broken_invariants {
+        println!("invariants were checked with --seed {}", fuzz_options.seed);
+    }
+
+    if let Some(path) = &test_args.coverage {
+        for (file, lines) in report.lines() {
+            let hit = lines.values().filter(|hits| **hits > 0).count();
+
+            println!("coverage: {hit} of {} lines in {file}", lines.len());
+        }
+
+        if let Err(err) = fs::write(path, report.lcov()) {
+            eprintln!("error: cannot write {}: {err}", path.display());
+            exit(1);
+        }
+    }
+
+    if let Some(path) = &test_args.check_gas_snapshot {
+        let before = solang::test_runner::GasSnapshot::load(path).unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            exit(1);
+        });
+
+        let regressions = before.regressions(&gas_snapshot, test_args.gas_tolerance);
+
+        for regression in &regressions {
+            if test_args.gas_warn {
+                eprintln!("warning: gas regression: {regression}");
+            } else {
+                eprintln!("error: gas regression: {regression}");
+            }
+        }
+
+        if !regressions.is_empty() && !test_args.gas_warn {
+            exit(1);
+        }
+    }
+
+    if let Some(path) = &test_args.gas_snapshot {
+        if let Err(err) = gas_snapshot.save(path) {
+            eprintln!("error: {err}");
+            exit(1);
+        }
+    }
+
+    if errors || failed > 0 {
+        exit(1);
+    }
+}
+
+fn coverage_report(coverage_args: cli::Coverage) {
+    let mut report = coverage::Report::default();
+
+    for path in &coverage_args.maps {
+        let map = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| serde_json::from_str(&contents).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                eprintln!("error: cannot read coverage map {}: {err}", path.display());
+                exit(1);
+            });
+
+        report.add_map(map);
+    }
+
+    for path in &coverage_args.input {
+        let logs = fs::read(path).unwrap_or_else(|err| {
+            eprintln!("error: cannot read {}: {err}", path.display());
+            exit(1);
+        });
+
+        report.add_logs(&String::from_utf8_lossy(&logs));
+    }
+
+    if let Some(path) = &coverage_args.output {
+        if let Err(err) = fs::write(path, report.lcov()) {
+            eprintln!("error: cannot write {}: {err}", path.display());
+            exit(1);
+        }
+    } else {
+        print!("{}", report.lcov());
+    }
+}
+
 fn compile(compile_args: &Compile) {
+    if compile_args.compiler_output.watch {
+        watch::watch(compile_args);
+    }
+
     let target = target_arg(&compile_args.target_arg);
 
     let mut json = JsonResult {
@@ -169,9 +736,14 @@ fn compile(compile_args: &Compile) {
         eprintln!("info: Solang version {}", env!("SOLANG_VERSION"));
     }
 
-    let mut resolver = imports_arg(&compile_args.package);
+    let session = Session::new(
+        target,
+        options_arg(&compile_args.debug_features, &compile_args.optimizations),
+    );
 
-    let opt = options_arg(&compile_args.debug_features, &compile_args.optimizations);
+    let mut resolver = imports_arg_into(&compile_args.package, session.file_resolver());
+
+    let warnings = warnings_arg(&compile_args.warnings);
 
     let mut namespaces = Vec::new();
 
@@ -189,9 +761,9 @@ fn compile(compile_args: &Compile) {
         let ns = process_file(
             filename,
             &mut resolver,
-            target,
+            &session,
             &compile_args.compiler_output,
-            &opt,
+            &warnings,
         );
 
         namespaces.push(ns);
@@ -200,11 +772,15 @@ fn compile(compile_args: &Compile) {
     let mut json_contracts = HashMap::new();
 
     let std_json = compile_args.compiler_output.std_json_output;
+    let sarif = compile_args.compiler_output.message_format.as_deref() == Some("sarif");
+    let mut sarif_results = Vec::new();
 
     for ns in &namespaces {
         if std_json {
             let mut out = ns.diagnostics_as_json(&resolver);
             json.errors.append(&mut out);
+        } else if sarif {
+            sarif_results.extend(ns.diagnostics_as_sarif());
         } else {
             ns.print_diagnostics(&resolver, compile_args.compiler_output.verbose);
         }
@@ -214,6 +790,12 @@ fn compile(compile_args: &Compile) {
         }
     }
 
+    if sarif {
+        let log = SarifLog::new(env!("SOLANG_VERSION"), diagnostic_rules(), sarif_results);
+
+        println!("{}", serde_json::to_string_pretty(&log).unwrap());
+    }
+
     if let Some("ast-dot") = compile_args.compiler_output.emit.as_deref() {
         exit(0);
     }
@@ -240,38 +822,57 @@ fn compile(compile_args: &Compile) {
         errors = true;
     }
 
+    // abstract contracts have no binary, so they cannot be deployed
+    if !errors && !compile_args.compiler_output.abstract_abi {
+        for contract in namespaces
+            .iter()
+            .flat_map(|ns| ns.contracts.iter())
+            .filter(|contract| {
+                contract_names.contains(contract.id.name.as_str()) && !contract.instantiable
+            })
+        {
+            eprintln!(
+                "error: {} {} cannot be deployed, use --abstract-abi to write its ABI only",
+                contract.ty, contract.id
+            );
+            errors = true;
+        }
+    }
+
+    for name in compile_args.contract_overrides.keys() {
+        if !namespaces
+            .iter()
+            .flat_map(|ns| ns.contracts.iter())
+            .any(|contract| *name == contract.id.name)
+        {
+            eprintln!("warning: contract {name} in configuration file not found");
+        }
+    }
+
     if !errors {
         let mut seen_contracts = HashMap::new();
 
-        let authors = if let Some(authors) = &compile_args.package.authors {
-            if !target.is_polkadot() {
-                eprintln!(
-                    "warning: the `authors` flag will be ignored for {} target",
-                    target
-                )
-            }
-            authors.clone()
-        } else {
-            vec!["unknown".to_string()]
-        };
-
-        let version = if let Some(version) = &compile_args.package.version {
-            version
-        } else {
-            "0.0.1"
-        };
+        let (authors, version) = authors_and_version(compile_args, target);
 
         for ns in &mut namespaces {
             for contract_no in 0..ns.contracts.len() {
+                if !contract_names.is_empty()
+                    && !contract_names.contains(ns.contracts[contract_no].id.name.as_str())
+                {
+                    continue;
+                }
+
                 contract_results(
                     contract_no,
                     &compile_args.compiler_output,
                     ns,
+                    &resolver,
                     &mut json_contracts,
                     &mut seen_contracts,
-                    &opt,
+                    session.options(),
                     &authors,
                     version,
+                    &compile_args.contract_overrides,
                 );
             }
         }
@@ -287,11 +888,282 @@ fn compile(compile_args: &Compile) {
     }
 }
 
+/// The contract authors and version to put in the metadata
+fn authors_and_version(compile_args: &Compile, target: solang::Target) -> (Vec<String>, &str) {
+    let authors = if let Some(authors) = &compile_args.package.authors {
+        if !target.is_polkadot() {
+            eprintln!(
+                "warning: the `authors` flag will be ignored for {} target",
+                target
+            )
+        }
+        authors.clone()
+    } else {
+        vec!["unknown".to_string()]
+    };
+
+    let version = if let Some(version) = &compile_args.package.version {
+        version
+    } else {
+        "0.0.1"
+    };
+
+    (authors, version)
+}
+
 fn shell_complete(mut app: Command, args: ShellComplete) {
     let name = app.get_name().to_string();
     generate(args.shell_complete, &mut app, name, &mut std::io::stdout());
 }
 
+/// Print the cost estimate of a contract as a table
+fn print_costs(report: &CostReport) {
+    let header = [
+        "function", "total", "storage", "hashing", "encoding", "calls", "events", "compute",
+    ];
+
+    let rows: Vec<[String; 8]> = report
+        .functions
+        .iter()
+        .map(|f| {
+            [
+                f.function.clone(),
+                if f.bounded {
+                    f.total.to_string()
+                } else {
+                    format!("{}+", f.total)
+                },
+                f.cost.storage.to_string(),
+                f.cost.hashing.to_string(),
+                f.cost.encoding.to_string(),
+                f.cost.calls.to_string(),
+                f.cost.events.to_string(),
+                f.cost.compute.to_string(),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    println!(
+        "contract {}: estimated worst-case cost in {}",
+        report.contract, report.unit
+    );
+
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column == 0 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .join("  ");
+
+        println!("  {line}");
+    }
+
+    if report.functions.iter().any(|f| !f.bounded) {
+        println!("  + has loops or recursion, which are counted as running once");
+    }
+}
+
+/// Print the stack usage of a contract as a table
+fn print_stack_usage(report: &StackReport) {
+    let header = ["function", "frame", "stack", "depth"];
+
+    let frame_over = |f: &FunctionStack| report.frame_limit.is_some_and(|limit| f.frame > limit);
+    let depth_over =
+        |f: &FunctionStack| report.call_depth_limit.is_some_and(|limit| f.depth > limit);
+
+    let rows: Vec<[String; 4]> = report
+        .functions
+        .iter()
+        .map(|f| {
+            [
+                f.function.clone(),
+                if frame_over(f) {
+                    format!("{}!", f.frame)
+                } else {
+                    f.frame.to_string()
+                },
+                if f.bounded {
+                    f.stack.to_string()
+                } else {
+                    format!("{}+", f.stack)
+                },
+                if depth_over(f) {
+                    format!("{}!", f.depth)
+                } else {
+                    f.depth.to_string()
+                },
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    println!(
+        "contract {}: estimated stack usage in bytes",
+        report.contract
+    );
+
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column == 0 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .join("  ");
+
+        println!("  {line}");
+    }
+
+    if let (Some(frame_limit), Some(call_depth_limit), true) = (
+        report.frame_limit,
+        report.call_depth_limit,
+        report
+            .functions
+            .iter()
+            .any(|f| frame_over(f) || depth_over(f)),
+    ) {
+        println!(
+            "  ! exceeds the {} limit of {frame_limit} bytes per frame or {call_depth_limit} nested calls",
+            report.target
+        );
+    }
+
+    if report.functions.iter().any(|f| !f.bounded) {
+        println!("  + has recursion or calls through function pointers");
+    }
+}
+
+/// Print the heap allocation sites of each function of a contract
+fn print_heap_allocations(report: &HeapReport) {
+    match report.heap_size {
+        Some(heap_size) => println!(
+            "contract {}: heap allocations, with a heap of {heap_size} bytes",
+            report.contract
+        ),
+        None => println!("contract {}: heap allocations", report.contract),
+    }
+
+    let sites = || report.functions.iter().flat_map(|f| &f.sites);
+    let loc_width = sites().map(|site| site.loc.len()).max().unwrap_or(0);
+    let kind_width = sites().map(|site| site.kind.len()).max().unwrap_or(0);
+
+    for f in &report.functions {
+        println!("  {}: {} bytes of known size", f.function, f.fixed);
+
+        for site in &f.sites {
+            let size = site
+                .size
+                .map_or_else(|| "?".to_string(), |size| size.to_string());
+
+            println!(
+                "    {:<loc$}  {:<kind$}  {size}{}",
+                site.loc,
+                site.kind,
+                if site.in_loop { " *" } else { "" },
+                loc = loc_width,
+                kind = kind_width,
+            );
+        }
+    }
+
+    if sites().any(|site| site.in_loop) {
+        println!("  * in a loop, so it may allocate many times");
+    }
+}
+
+/// Print the code size of a contract as a table
+fn print_size_report(report: &SizeReport) {
+    let header = ["function", "kind", "size", "cold", "share"];
+
+    let rows: Vec<[String; 5]> = report
+        .entries
+        .iter()
+        .map(|entry| {
+            let share = if report.code_size == 0 {
+                0.0
+            } else {
+                entry.size as f64 * 100.0 / report.code_size as f64
+            };
+
+            [
+                match &entry.loc {
+                    Some(loc) => format!("{} ({loc})", entry.name),
+                    None => entry.name.clone(),
+                },
+                entry.kind.to_string(),
+                entry.size.to_string(),
+                entry.cold_size.to_string(),
+                format!("{share:.1}%"),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].len())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    println!(
+        "contract {}: {} bytes of code, of which {} bytes on the hot path",
+        report.contract,
+        report.code_size,
+        report.code_size - report.cold_size
+    );
+
+    for row in std::iter::once(header.map(String::from)).chain(rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                if column < 2 {
+                    format!("{cell:<width$}")
+                } else {
+                    format!("{cell:>width$}")
+                }
+            })
+            .join("  ");
+
+        println!("  {line}");
+    }
+}
+
 fn output_file(compiler_output: &CompilerOutput, stem: &str, ext: &str, meta: bool) -> PathBuf {
     let dir = if meta {
         compiler_output
@@ -307,9 +1179,9 @@ fn output_file(compiler_output: &CompilerOutput, stem: &str, ext: &str, meta: bo
 fn process_file(
     filename: &Path,
     resolver: &mut FileResolver,
-    target: solang::Target,
+    session: &Session,
     compiler_output: &CompilerOutput,
-    opt: &Options,
+    warnings: &WarningConfig,
 ) -> Namespace {
     let verbose = compiler_output.verbose;
 
@@ -319,10 +1191,16 @@ fn process_file(
     };
 
     // resolve phase
-    let mut ns = solang::parse_and_resolve(filepath.as_os_str(), resolver, target);
+    let mut ns = Namespace::new(session.target());
+    ns.strict_conversions = warnings.strict_conversions;
+
+    let mut ns = session.parse_and_resolve_into(filepath.as_os_str(), resolver, ns);
+
+    // denied warnings are errors, so this must be done before codegen
+    ns.apply_warning_config(warnings);
 
     // codegen all the contracts; some additional errors/warnings will be detected here
-    codegen(&mut ns, opt);
+    session.codegen(&mut ns);
 
     if let Some("ast-dot") = compiler_output.emit.as_deref() {
         let stem = filepath.file_stem().unwrap().to_string_lossy();
@@ -349,21 +1227,19 @@ fn contract_results(
     contract_no: usize,
     compiler_output: &CompilerOutput,
     ns: &mut Namespace,
+    resolver: &FileResolver,
     json_contracts: &mut HashMap<String, JsonContract>,
     seen_contracts: &mut HashMap<String, String>,
     opt: &Options,
     default_authors: &[String],
     version: &str,
+    contract_overrides: &HashMap<String, ContractOverrides>,
 ) {
     let verbose = compiler_output.verbose;
     let std_json = compiler_output.std_json_output;
 
     let resolved_contract = &ns.contracts[contract_no];
 
-    if !resolved_contract.instantiable {
-        return;
-    }
-
     if ns.top_file_no() != resolved_contract.loc.file_no() {
         // contracts that were imported should not be considered. For example, if we have a file
         // a.sol which imports b.sol, and b.sol defines contract B, then:
@@ -372,6 +1248,31 @@ fn contract_results(
         return;
     }
 
+    if !resolved_contract.instantiable {
+        // abstract contracts, interfaces and libraries only have an ABI
+        if compiler_output.abstract_abi {
+            let abi_filename =
+                output_file(compiler_output, &resolved_contract.id.name, "abi", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving ABI {} for {} {}",
+                    abi_filename.display(),
+                    resolved_contract.ty,
+                    resolved_contract.id
+                );
+            }
+
+            let abi = abi::ethereum::gen_abi(contract_no, ns);
+
+            let mut file = create_file(&abi_filename);
+            file.write_all(serde_json::to_string(&abi).unwrap().as_bytes())
+                .unwrap();
+        }
+
+        return;
+    }
+
     let loc = ns.loc_to_string(PathDisplay::FullPath, &resolved_contract.loc);
 
     if let Some(other_loc) = seen_contracts.get(&resolved_contract.id.name) {
@@ -389,6 +1290,149 @@ fn contract_results(
         return;
     }
 
+    if let Some("lir") = compiler_output.emit.as_deref() {
+        let lir_filename = output_file(compiler_output, &resolved_contract.id.name, "lir", false);
+
+        if verbose {
+            eprintln!(
+                "info: Saving LIR {} for contract {}",
+                lir_filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let mut file = create_file(&lir_filename);
+        file.write_all(resolved_contract.print_lir(ns).as_bytes())
+            .unwrap();
+        return;
+    }
+
+    if let Some("ast-json") = compiler_output.emit.as_deref() {
+        let ast_filename = output_file(
+            compiler_output,
+            &resolved_contract.id.name,
+            "ast.json",
+            false,
+        );
+
+        if verbose {
+            eprintln!(
+                "info: Saving AST {} for contract {}",
+                ast_filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let mut file = create_file(&ast_filename);
+        file.write_all(ns.ast_json(contract_no).as_bytes()).unwrap();
+        return;
+    }
+
+    if let Some("storage-layout") = compiler_output.emit.as_deref() {
+        let layout_filename = output_file(
+            compiler_output,
+            &resolved_contract.id.name,
+            "storage.json",
+            true,
+        );
+
+        if verbose {
+            eprintln!(
+                "info: Saving storage layout {} for contract {}",
+                layout_filename.display(),
+                resolved_contract.id
+            );
+        }
+
+        let layout = abi::storage_layout::gen_storage_layout(contract_no, ns);
+
+        let mut file = create_file(&layout_filename);
+        file.write_all(serde_json::to_string_pretty(&layout).unwrap().as_bytes())
+            .unwrap();
+        return;
+    }
+
+    if let Some(format) = compiler_output.costs.as_deref() {
+        let report = gen_cost_report(contract_no, ns);
+
+        if format == "json" {
+            let costs_filename = output_file(
+                compiler_output,
+                &resolved_contract.id.name,
+                "costs.json",
+                true,
+            );
+
+            if verbose {
+                eprintln!(
+                    "info: Saving cost estimate {} for contract {}",
+                    costs_filename.display(),
+                    resolved_contract.id
+                );
+            }
+
+            let mut file = create_file(&costs_filename);
+            file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+                .unwrap();
+        } else {
+            print_costs(&report);
+        }
+    }
+
+    if let Some(format) = compiler_output.stack_usage.as_deref() {
+        let report = gen_stack_report(contract_no, ns);
+
+        if format == "json" {
+            let stack_filename = output_file(
+                compiler_output,
+                &resolved_contract.id.name,
+                "stack.json",
+                true,
+            );
+
+            if verbose {
+                eprintln!(
+                    "info: Saving stack usage {} for contract {}",
+                    stack_filename.display(),
+                    resolved_contract.id
+                );
+            }
+
+            let mut file = create_file(&stack_filename);
+            file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+                .unwrap();
+        } else {
+            print_stack_usage(&report);
+        }
+    }
+
+    if let Some(format) = compiler_output.heap_allocations.as_deref() {
+        let report = gen_heap_report(contract_no, ns);
+
+        if format == "json" {
+            let heap_filename = output_file(
+                compiler_output,
+                &resolved_contract.id.name,
+                "heap.json",
+                true,
+            );
+
+            if verbose {
+                eprintln!(
+                    "info: Saving heap allocations {} for contract {}",
+                    heap_filename.display(),
+                    resolved_contract.id
+                );
+            }
+
+            let mut file = create_file(&heap_filename);
+            file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+                .unwrap();
+        } else {
+            print_heap_allocations(&report);
+        }
+    }
+
     if verbose {
         if ns.target == solang::Target::Solana {
             eprintln!(
@@ -403,6 +1447,21 @@ fn contract_results(
         );
     }
 
+    let overrides = contract_overrides.get(&resolved_contract.id.name);
+
+    let mut opt = overrides.map_or_else(|| opt.clone(), |overrides| overrides.options(opt));
+
+    // the source map and the size report are read from the line tables
+    opt.generate_line_tables |= compiler_output.source_map || compiler_output.size_report.is_some();
+
+    let opt = &opt;
+    let authors = overrides
+        .and_then(|overrides| overrides.authors.as_deref())
+        .unwrap_or(default_authors);
+    let version = overrides
+        .and_then(|overrides| overrides.version.as_deref())
+        .unwrap_or(version);
+
     let context = inkwell::context::Context::create();
 
     let binary = resolved_contract.binary(ns, &context, opt, contract_no);
@@ -411,7 +1470,30 @@ fn contract_results(
         return;
     }
 
-    let code = binary.code(Generate::Linked).expect("llvm build");
+    let mut code = binary.code(Generate::Linked).expect("llvm build");
+
+    if compiler_output.embed_build_info {
+        let info = BuildInfo::new(contract_no, ns, resolver, opt);
+
+        code = build_info::embed(&code, &info).unwrap_or_else(|err| {
+            eprintln!("error: cannot embed build information: {err}");
+            exit(1);
+        });
+    }
+
+    // the metadata has the hash of the code, so its hash is embedded last
+    let solc_metadata = if compiler_output.metadata {
+        let metadata = Metadata::new(contract_no, ns, resolver, opt, &code);
+
+        code = metadata::embed(&code, &metadata).unwrap_or_else(|err| {
+            eprintln!("error: cannot embed metadata hash: {err}");
+            exit(1);
+        });
+
+        Some(metadata)
+    } else {
+        None
+    };
 
     #[cfg(feature = "wasm_opt")]
     if let Some(level) = opt.wasm_opt.filter(|_| ns.target.is_polkadot() && verbose) {
@@ -453,7 +1535,7 @@ fn contract_results(
         file.write_all(&code).unwrap();
 
         let (metadata, meta_ext) =
-            abi::generate_abi(contract_no, ns, &code, verbose, default_authors, version);
+            abi::generate_abi(contract_no, ns, &code, verbose, authors, version);
         let meta_filename = output_file(compiler_output, &binary.name, meta_ext, true);
 
         if verbose {
@@ -466,6 +1548,159 @@ fn contract_results(
 
         let mut file = create_file(&meta_filename);
         file.write_all(metadata.as_bytes()).unwrap();
+
+        if let Some(solc_metadata) = &solc_metadata {
+            let metadata_filename =
+                output_file(compiler_output, &binary.name, "metadata.json", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving solc style metadata {} for contract {}",
+                    metadata_filename.display(),
+                    binary.name
+                );
+            }
+
+            let mut file = create_file(&metadata_filename);
+            file.write_all(solc_metadata.canonical_json().as_bytes())
+                .unwrap();
+        }
+
+        if compiler_output.bundle {
+            let bundle_filename = output_file(compiler_output, &binary.name, "bundle.json", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving bundle {} for contract {}",
+                    bundle_filename.display(),
+                    binary.name
+                );
+            }
+
+            let bundle = abi::bundle::gen_bundle(contract_no, ns, &code, &metadata);
+
+            let mut file = create_file(&bundle_filename);
+            file.write_all(serde_json::to_string_pretty(&bundle).unwrap().as_bytes())
+                .unwrap();
+        }
+
+        if opt.coverage {
+            let map_filename = output_file(compiler_output, &binary.name, "coverage.json", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving coverage map {} for contract {}",
+                    map_filename.display(),
+                    binary.name
+                );
+            }
+
+            let map = coverage::gen_coverage_map(contract_no, ns);
+
+            let mut file = create_file(&map_filename);
+            file.write_all(serde_json::to_string_pretty(&map).unwrap().as_bytes())
+                .unwrap();
+        }
+
+        if let Some(format) = compiler_output.size_report.as_deref() {
+            let report = gen_size_report(contract_no, ns, &code).unwrap_or_else(|err| {
+                eprintln!(
+                    "error: cannot generate size report for contract {}: {err}",
+                    binary.name
+                );
+                exit(1);
+            });
+
+            if format == "json" {
+                let size_filename = output_file(compiler_output, &binary.name, "size.json", true);
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving size report {} for contract {}",
+                        size_filename.display(),
+                        binary.name
+                    );
+                }
+
+                let mut file = create_file(&size_filename);
+                file.write_all(serde_json::to_string_pretty(&report).unwrap().as_bytes())
+                    .unwrap();
+            } else {
+                print_size_report(&report);
+            }
+        }
+
+        if compiler_output.source_map {
+            let source_map =
+                source_map::gen_source_map(contract_no, ns, &code).unwrap_or_else(|err| {
+                    eprintln!(
+                        "error: cannot generate source map for contract {}: {err}",
+                        binary.name
+                    );
+                    exit(1);
+                });
+
+            let map_filename = output_file(compiler_output, &binary.name, "sourcemap", true);
+            let json_filename = output_file(compiler_output, &binary.name, "sourcemap.json", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving source maps {} and {} for contract {}",
+                    map_filename.display(),
+                    json_filename.display(),
+                    binary.name
+                );
+            }
+
+            let mut file = create_file(&map_filename);
+            file.write_all(source_map.source_map.as_bytes()).unwrap();
+
+            let mut file = create_file(&json_filename);
+            file.write_all(
+                serde_json::to_string_pretty(&source_map)
+                    .unwrap()
+                    .as_bytes(),
+            )
+            .unwrap();
+        }
+
+        if compiler_output.constructor_args {
+            if let Some(args) = abi::constructor::gen_constructor_args(contract_no, ns) {
+                let args_filename =
+                    output_file(compiler_output, &binary.name, "constructor.json", true);
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving constructor arguments {} for contract {}",
+                        args_filename.display(),
+                        binary.name
+                    );
+                }
+
+                let mut file = create_file(&args_filename);
+                file.write_all(serde_json::to_string_pretty(&args).unwrap().as_bytes())
+                    .unwrap();
+            }
+        }
+
+        // On EVM, the metadata is already the solc ABI
+        if compiler_output.abi.as_deref() == Some("solc") && meta_ext != "abi" {
+            let abi_filename = output_file(compiler_output, &binary.name, "abi", true);
+
+            if verbose {
+                eprintln!(
+                    "info: Saving ABI {} for contract {}",
+                    abi_filename.display(),
+                    binary.name
+                );
+            }
+
+            let abi = abi::ethereum::gen_abi(contract_no, ns);
+
+            let mut file = create_file(&abi_filename);
+            file.write_all(serde_json::to_string(&abi).unwrap().as_bytes())
+                .unwrap();
+        }
     }
 }
 
@@ -492,6 +1727,40 @@ fn save_intermediates(
             true
         }
 
+        Some("llvm-ir-per-pass") => {
+            let files = binary
+                .dump_llvm_per_pass(|step, pass| {
+                    // pass names can have brackets and commas
+                    let pass: String = pass
+                        .chars()
+                        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                        .collect();
+
+                    output_file(
+                        compiler_output,
+                        &binary.name,
+                        &format!("{step:02}.{pass}.ll"),
+                        false,
+                    )
+                })
+                .unwrap_or_else(|err| {
+                    eprintln!("error: {err}");
+                    exit(1);
+                });
+
+            if verbose {
+                for file in files {
+                    eprintln!(
+                        "info: Saving LLVM IR {} for contract {}",
+                        file.display(),
+                        binary.name
+                    );
+                }
+            }
+
+            true
+        }
+
         Some("llvm-bc") => {
             let bc_filename = output_file(compiler_output, &binary.name, "bc", false);
 
@@ -0,0 +1,232 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::{imports_arg, options_arg, target_arg, PackageTrait, Verify};
+use serde_json::Value;
+use solang::{
+    build_info::{self, BuildInfo},
+    codegen::codegen,
+    inspect::contract_code,
+    metadata::{self, Metadata},
+    Target,
+};
+use std::{fs, path::Path, process::exit};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Build a contract again from its sources, and check that the result is identical to the given
+/// artifact. If the artifact has embedded build information, the options from it are used, and
+/// the hashes of the sources are compared first, so that changed sources are reported by name.
+pub(crate) fn verify(verify_args: &Verify) {
+    let artifact = read_artifact(&verify_args.artifact).unwrap_or_else(|err| {
+        eprintln!("{}: error: {err}", verify_args.artifact.display());
+        exit(1);
+    });
+
+    let build_info = build_info::extract(&artifact).unwrap_or_else(|err| {
+        eprintln!("{}: error: {err}", verify_args.artifact.display());
+        exit(1);
+    });
+
+    let mut target = target_arg(&verify_args.target);
+
+    if target == Target::EVM {
+        eprintln!("error: verifying EVM contracts is not supported");
+        exit(1);
+    }
+
+    let opt = if let Some(info) = &build_info {
+        if info.compiler != env!("CARGO_PKG_VERSION") {
+            eprintln!(
+                "error: artifact was built with solang {}, this is solang {}",
+                info.compiler,
+                env!("CARGO_PKG_VERSION")
+            );
+            exit(1);
+        }
+
+        match info.target() {
+            // use the address and value length from the artifact
+            Ok(info_target) if info_target == target => target = info_target,
+            _ => {
+                eprintln!(
+                    "error: artifact was built for target {}, not {}",
+                    info.target, verify_args.target.name
+                );
+                exit(1);
+            }
+        }
+
+        info.options.options().unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            exit(1);
+        })
+    } else {
+        options_arg(&verify_args.debug_features, &verify_args.optimizations)
+    };
+
+    let mut resolver = imports_arg(&verify_args.package);
+    let mut namespaces = Vec::new();
+
+    for filename in verify_args.package.get_input() {
+        let filepath = match filename.canonicalize() {
+            Ok(filename) => filename,
+            Err(_) => filename.to_path_buf(),
+        };
+
+        let mut ns = solang::parse_and_resolve(filepath.as_os_str(), &mut resolver, target);
+
+        codegen(&mut ns, &opt);
+
+        if ns.diagnostics.any_errors() {
+            ns.print_diagnostics(&resolver, false);
+            exit(1);
+        }
+
+        namespaces.push(ns);
+    }
+
+    // the contract name is in the build information; otherwise, it can be given with --contract
+    let contract_names = match &build_info {
+        Some(info) => vec![info.contract.clone()],
+        None => verify_args.package.contracts.clone().unwrap_or_default(),
+    };
+
+    let mut contracts = Vec::new();
+
+    for ns in &namespaces {
+        for (contract_no, contract) in ns.contracts.iter().enumerate() {
+            if contract.instantiable
+                && contract.loc.file_no() == ns.top_file_no()
+                && (contract_names.is_empty() || contract_names.contains(&contract.id.name))
+            {
+                contracts.push((ns, contract_no));
+            }
+        }
+    }
+
+    let (ns, contract_no) = match contracts.as_slice() {
+        [contract] => *contract,
+        [] => {
+            eprintln!("error: contract {} not found", contract_names.join(", "));
+            exit(1);
+        }
+        _ => {
+            eprintln!("error: more than one contract found; use --contract to select one");
+            exit(1);
+        }
+    };
+
+    let name = &ns.contracts[contract_no].id.name;
+
+    let mut code = ns.contracts[contract_no].emit(ns, &opt, contract_no);
+
+    if let Some(info) = &build_info {
+        let local = BuildInfo::new(contract_no, ns, &resolver, &opt);
+
+        let mut differs = false;
+
+        for source in &info.sources {
+            match local.sources.iter().find(|local| local.path == source.path) {
+                Some(local) if local.keccak256 == source.keccak256 => (),
+                Some(_) => {
+                    eprintln!("error: source {} differs from the artifact", source.path);
+                    differs = true;
+                }
+                None => {
+                    eprintln!(
+                        "error: source {} of the artifact was not found",
+                        source.path
+                    );
+                    differs = true;
+                }
+            }
+        }
+
+        for source in &local.sources {
+            if !info.sources.iter().any(|info| info.path == source.path) {
+                eprintln!("error: source {} is not used by the artifact", source.path);
+                differs = true;
+            }
+        }
+
+        if differs {
+            exit(1);
+        }
+
+        code = build_info::embed(&code, &local).unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            exit(1);
+        });
+    }
+
+    let metadata_hash = metadata::extract(&artifact).unwrap_or_else(|err| {
+        eprintln!("{}: error: {err}", verify_args.artifact.display());
+        exit(1);
+    });
+
+    if let Some(hash) = &metadata_hash {
+        let local = Metadata::new(contract_no, ns, &resolver, &opt, &code);
+
+        code = metadata::embed(&code, &local).unwrap_or_else(|err| {
+            eprintln!("error: {err}");
+            exit(1);
+        });
+
+        if metadata::ipfs_hash(local.canonical_json().as_bytes()) != hash.ipfs {
+            eprintln!(
+                "error: metadata differs from the artifact, which has ipfs hash {}",
+                hash.ipfs
+            );
+        }
+    }
+
+    if code == artifact {
+        println!(
+            "{}: verified contract {name}, keccak256 {}",
+            verify_args.artifact.display(),
+            hex::encode(keccak256(&code))
+        );
+    } else {
+        eprintln!(
+            "{}: error: contract {name} does not match; artifact is {} bytes with keccak256 {}, \
+            build is {} bytes with keccak256 {}",
+            verify_args.artifact.display(),
+            artifact.len(),
+            hex::encode(keccak256(&artifact)),
+            code.len(),
+            hex::encode(keccak256(&code))
+        );
+
+        if build_info.is_none() {
+            eprintln!(
+                "info: artifact has no build information, so the options must be the same as \
+                when it was built"
+            );
+        }
+
+        exit(1);
+    }
+}
+
+/// The code from a `.so` or `.wasm` file, or from a Polkadot `.contract` file
+fn read_artifact(path: &Path) -> Result<Vec<u8>, String> {
+    let contents = fs::read(path).map_err(|err| format!("cannot read file: {err}"))?;
+
+    if path.extension().is_some_and(|ext| ext == "contract") {
+        let json: Value = serde_json::from_slice(&contents)
+            .map_err(|err| format!("invalid contract file: {err}"))?;
+
+        contract_code(&json)
+    } else {
+        Ok(contents)
+    }
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut res = [0u8; 32];
+
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    hasher.finalize(&mut res);
+
+    res
+}
@@ -0,0 +1,172 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::{imports_arg_into, options_arg, target_arg, warnings_arg, Compile, PackageTrait};
+use crate::{authors_and_version, contract_results, process_file};
+use serde_json::json;
+use solang::session::Session;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// How often the source files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Each input file is resolved into its own namespace. When any of the files it imports changes,
+/// only that input file is compiled again.
+struct Unit {
+    input: PathBuf,
+    /// All the files used by the last compile, with their modification time
+    files: HashMap<PathBuf, Option<SystemTime>>,
+}
+
+impl Unit {
+    /// Return the files which have been modified since the last compile
+    fn changed_files(&self) -> Vec<&PathBuf> {
+        self.files
+            .iter()
+            .filter(|(path, time)| modified(path) != **time)
+            .map(|(path, _)| path)
+            .collect()
+    }
+}
+
+/// Compile all input files and keep compiling the input files affected by changes, until the
+/// process is killed. The files which have not changed are not read again, since all builds
+/// share a session.
+pub(crate) fn watch(compile_args: &Compile) -> ! {
+    let json = compile_args.compiler_output.watch_json;
+
+    let session = Session::new(
+        target_arg(&compile_args.target_arg),
+        options_arg(&compile_args.debug_features, &compile_args.optimizations),
+    );
+
+    let mut units: Vec<Unit> = compile_args
+        .package
+        .get_input()
+        .iter()
+        .map(|input| Unit {
+            input: input.clone(),
+            files: HashMap::new(),
+        })
+        .collect();
+
+    let mut affected: Vec<usize> = (0..units.len()).collect();
+
+    loop {
+        for unit_no in affected {
+            build(&mut units[unit_no], compile_args, &session);
+        }
+
+        if !json {
+            eprintln!("info: watching for changes");
+        }
+
+        affected = loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let mut changed_files = Vec::new();
+            let mut affected = Vec::new();
+
+            for (unit_no, unit) in units.iter().enumerate() {
+                let changed = unit.changed_files();
+
+                if !changed.is_empty() {
+                    affected.push(unit_no);
+
+                    for path in changed {
+                        if !changed_files.contains(&path) {
+                            changed_files.push(path);
+                        }
+                    }
+                }
+            }
+
+            if !affected.is_empty() {
+                if json {
+                    println!("{}", json!({ "event": "changed", "files": changed_files }));
+                } else {
+                    for path in changed_files {
+                        eprintln!("info: {} changed", path.display());
+                    }
+                }
+
+                break affected;
+            }
+        };
+    }
+}
+
+/// Compile a single input file, report the diagnostics and write the artifacts
+fn build(unit: &mut Unit, compile_args: &Compile, session: &Session) {
+    let compiler_output = &compile_args.compiler_output;
+    let warnings = warnings_arg(&compile_args.warnings);
+
+    // Use a new resolver, as it keeps the files it has read; the session only reads the files
+    // again which have been modified
+    let mut resolver = imports_arg_into(&compile_args.package, session.file_resolver());
+
+    let mut ns = process_file(
+        &unit.input,
+        &mut resolver,
+        session,
+        compiler_output,
+        &warnings,
+    );
+
+    unit.files = ns
+        .files
+        .iter()
+        .filter(|file| file.cache_no.is_some())
+        .map(|file| (file.path.clone(), modified(&file.path)))
+        .collect();
+
+    // If the input file could not be read, wait for it to appear
+    if unit.files.is_empty() {
+        unit.files.insert(unit.input.clone(), modified(&unit.input));
+    }
+
+    let errors = ns.diagnostics.any_errors();
+
+    if compiler_output.watch_json {
+        println!(
+            "{}",
+            json!({
+                "event": "compiled",
+                "file": unit.input,
+                "errors": errors,
+                "diagnostics": ns.diagnostics_as_json(&resolver),
+            })
+        );
+    } else {
+        ns.print_diagnostics(&resolver, compiler_output.verbose);
+    }
+
+    if !errors {
+        let (authors, version) = authors_and_version(compile_args, session.target());
+        let mut seen_contracts = HashMap::new();
+
+        for contract_no in 0..ns.contracts.len() {
+            contract_results(
+                contract_no,
+                compiler_output,
+                &mut ns,
+                &resolver,
+                &mut HashMap::new(),
+                &mut seen_contracts,
+                session.options(),
+                &authors,
+                version,
+                &compile_args.contract_overrides,
+            );
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,581 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Build information which can be embedded in a compiled contract: the compiler version, the
+//! target, the options, and a hash of each source file. With the sources, this is enough to
+//! build the contract again and check that the result is identical, which is what
+//! `solang verify` does.
+//!
+//! Paths are stored relative to the directory of the file which was compiled, or to the import
+//! path the file was found in, so that the build information does not depend on where the
+//! sources are on disk.
+
+use crate::codegen::{HeapAllocator, OptimizationLevel, Options};
+use crate::file_resolver::FileResolver;
+use crate::inspect::Elf;
+use crate::sema::ast::Namespace;
+use crate::Target;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tiny_keccak::{Hasher, Keccak};
+use wasm_encoder::{CustomSection, Encode, Section};
+use wasmparser::{Parser, Payload};
+
+/// The name of the wasm custom section with the build information. On Solana, the ELF section
+/// has the same name, with a leading dot; the Solana loader does not allow section names longer
+/// than 15 bytes.
+pub const SECTION_NAME: &str = "solang.build";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct BuildInfo {
+    /// The version of Solang
+    pub compiler: String,
+    /// The target, as given on the command line
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value_length: Option<usize>,
+    pub contract: String,
+    pub options: BuildOptions,
+    pub sources: Vec<SourceHash>,
+}
+
+/// The options which change the code that is generated
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct BuildOptions {
+    pub opt_level: String,
+    pub dead_storage: bool,
+    pub constant_folding: bool,
+    pub strength_reduce: bool,
+    pub vector_to_slice: bool,
+    pub common_subexpression_elimination: bool,
+    #[serde(default = "default_true")]
+    pub storage_cache: bool,
+    #[serde(default = "default_true")]
+    pub string_switch: bool,
+    #[serde(default = "default_true")]
+    pub loop_unroll: bool,
+    #[serde(default = "default_true")]
+    pub cold_split: bool,
+    #[serde(default)]
+    pub strip_dead_storage: bool,
+    pub generate_debug_information: bool,
+    #[serde(default)]
+    pub generate_line_tables: bool,
+    pub log_runtime_errors: bool,
+    pub log_prints: bool,
+    #[serde(default = "default_true")]
+    pub revert_data: bool,
+    #[serde(default)]
+    pub coverage: bool,
+    #[serde(default)]
+    pub trace: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub llvm_passes: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub inline_threshold: Option<u32>,
+    /// Missing from the build information of compilers which only had the free-list allocator
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub heap_allocator: Option<String>,
+    /// Missing from the build information of compilers which only had the legacy account layout
+    #[serde(default)]
+    pub account_discriminator: bool,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub wasm_opt: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct SourceHash {
+    pub path: String,
+    pub keccak256: String,
+}
+
+impl BuildInfo {
+    /// The build information for a contract. The sources are all the files which were read to
+    /// compile the contract.
+    pub fn new(contract_no: usize, ns: &Namespace, resolver: &FileResolver, opt: &Options) -> Self {
+        let top_dir = ns.files[ns.top_file_no()].path.parent();
+
+        let sources = ns
+            .files
+            .iter()
+            .filter_map(|file| {
+                let contents = resolver.get_contents_of_file_no(file.cache_no?)?;
+
+                Some(SourceHash {
+                    path: source_path(&file.path, file.import_no, top_dir, resolver),
+                    keccak256: hex::encode(keccak256(contents.as_bytes())),
+                })
+            })
+            .collect();
+
+        let (address_length, value_length) = match ns.target {
+            Target::Polkadot {
+                address_length,
+                value_length,
+            } => (Some(address_length), Some(value_length)),
+            _ => (None, None),
+        };
+
+        BuildInfo {
+            compiler: env!("CARGO_PKG_VERSION").into(),
+            target: target_name(ns.target).into(),
+            address_length,
+            value_length,
+            contract: ns.contracts[contract_no].id.name.clone(),
+            options: opt.into(),
+            sources,
+        }
+    }
+
+    /// The target the contract was built for
+    pub fn target(&self) -> Result<Target, String> {
+        match self.target.as_str() {
+            "solana" => Ok(Target::Solana),
+            "polkadot" => Ok(Target::Polkadot {
+                address_length: self.address_length.unwrap_or(32),
+                value_length: self.value_length.unwrap_or(16),
+            }),
+            "evm" => Ok(Target::EVM),
+            "soroban" => Ok(Target::Soroban),
+            "stylus" => Ok(Target::Stylus),
+            target => Err(format!("unknown target '{target}'")),
+        }
+    }
+}
+
+fn target_name(target: Target) -> &'static str {
+    match target {
+        Target::Solana => "solana",
+        Target::Polkadot { .. } => "polkadot",
+        Target::EVM => "evm",
+        Target::Soroban => "soroban",
+        Target::Stylus => "stylus",
+    }
+}
+
+/// The path of a source file, relative to the directory of the file which was compiled, or
+/// else to the import path it was found in. If neither works, only the file name is used.
+pub(crate) fn source_path(
+    path: &Path,
+    import_no: Option<usize>,
+    top_dir: Option<&Path>,
+    resolver: &FileResolver,
+) -> String {
+    let relative = |base: &Path| {
+        path.strip_prefix(base).ok().map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+    };
+
+    if let Some(relative) = top_dir.and_then(relative) {
+        return relative;
+    }
+
+    if let Some((map, import_path)) = import_no.and_then(|no| resolver.get_import_path(no)) {
+        if let Some(relative) = relative(import_path) {
+            return match map {
+                Some(map) => format!("{}/{relative}", map.to_string_lossy()),
+                None => relative,
+            };
+        }
+    }
+
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Build information from before revert data could be left out always has it
+fn default_true() -> bool {
+    true
+}
+
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut res = [0u8; 32];
+
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    hasher.finalize(&mut res);
+
+    res
+}
+
+impl From<&Options> for BuildOptions {
+    fn from(opt: &Options) -> Self {
+        BuildOptions {
+            opt_level: match opt.opt_level {
+                OptimizationLevel::None => "none",
+                OptimizationLevel::Less => "less",
+                OptimizationLevel::Default => "default",
+                OptimizationLevel::Aggressive => "aggressive",
+            }
+            .into(),
+            dead_storage: opt.dead_storage,
+            constant_folding: opt.constant_folding,
+            strength_reduce: opt.strength_reduce,
+            vector_to_slice: opt.vector_to_slice,
+            common_subexpression_elimination: opt.common_subexpression_elimination,
+            storage_cache: opt.storage_cache,
+            string_switch: opt.string_switch,
+            loop_unroll: opt.loop_unroll,
+            cold_split: opt.cold_split,
+            account_discriminator: opt.account_discriminator,
+            strip_dead_storage: opt.strip_dead_storage,
+            generate_debug_information: opt.generate_debug_information,
+            generate_line_tables: opt.generate_line_tables,
+            log_runtime_errors: opt.log_runtime_errors,
+            log_prints: opt.log_prints,
+            revert_data: opt.revert_data,
+            coverage: opt.coverage,
+            trace: opt.trace,
+            llvm_passes: opt.llvm_passes.clone(),
+            inline_threshold: opt.inline_threshold,
+            heap_allocator: Some(
+                match opt.heap_allocator {
+                    HeapAllocator::Bump => "bump",
+                    HeapAllocator::FreeList => "free-list",
+                }
+                .into(),
+            ),
+            #[cfg(feature = "wasm_opt")]
+            wasm_opt: opt.wasm_opt.map(|passes| passes.to_string()),
+            #[cfg(not(feature = "wasm_opt"))]
+            wasm_opt: None,
+        }
+    }
+}
+
+impl BuildOptions {
+    /// The codegen options to build the contract again
+    pub fn options(&self) -> Result<Options, String> {
+        let opt_level = match self.opt_level.as_str() {
+            "none" => OptimizationLevel::None,
+            "less" => OptimizationLevel::Less,
+            "default" => OptimizationLevel::Default,
+            "aggressive" => OptimizationLevel::Aggressive,
+            level => return Err(format!("unknown optimization level '{level}'")),
+        };
+
+        let heap_allocator = match self.heap_allocator.as_deref() {
+            Some("bump") => HeapAllocator::Bump,
+            Some("free-list") | None => HeapAllocator::FreeList,
+            Some(allocator) => return Err(format!("unknown heap allocator '{allocator}'")),
+        };
+
+        #[cfg(feature = "wasm_opt")]
+        let wasm_opt = self
+            .wasm_opt
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|err| format!("{err}"))?;
+
+        #[cfg(not(feature = "wasm_opt"))]
+        if self.wasm_opt.is_some() {
+            return Err("this build of solang does not support wasm-opt".into());
+        }
+
+        Ok(Options {
+            dead_storage: self.dead_storage,
+            constant_folding: self.constant_folding,
+            strength_reduce: self.strength_reduce,
+            vector_to_slice: self.vector_to_slice,
+            common_subexpression_elimination: self.common_subexpression_elimination,
+            storage_cache: self.storage_cache,
+            string_switch: self.string_switch,
+            loop_unroll: self.loop_unroll,
+            cold_split: self.cold_split,
+            account_discriminator: self.account_discriminator,
+            strip_dead_storage: self.strip_dead_storage,
+            generate_debug_information: self.generate_debug_information,
+            generate_line_tables: self.generate_line_tables,
+            opt_level,
+            log_runtime_errors: self.log_runtime_errors,
+            log_prints: self.log_prints,
+            revert_data: self.revert_data,
+            coverage: self.coverage,
+            trace: self.trace,
+            llvm_passes: self.llvm_passes.clone(),
+            inline_threshold: self.inline_threshold,
+            heap_allocator,
+            #[cfg(feature = "wasm_opt")]
+            wasm_opt,
+        })
+    }
+}
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_NOBITS: u32 = 8;
+const SHF_ALLOC: u64 = 2;
+const SECTION_HEADER_SIZE: usize = 64;
+
+/// Add the build information to a wasm module or Solana ELF file
+pub fn embed(code: &[u8], info: &BuildInfo) -> Result<Vec<u8>, String> {
+    embed_section(code, SECTION_NAME, &serde_json::to_vec(info).unwrap())
+}
+
+/// Add a section to a wasm module or Solana ELF file. On wasm, this is a custom section at the
+/// end of the module. On Solana, the section is not loaded, and gets the name with a leading dot.
+/// The section names, and any sections after them, are moved to the end of the file, since the
+/// names need to be extended and the sections have to stay in order.
+pub(crate) fn embed_section(
+    code: &[u8],
+    section_name: &str,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    if code.starts_with(b"\0asm") {
+        let section = CustomSection {
+            name: section_name.into(),
+            data: data.into(),
+        };
+
+        let mut res = code.to_vec();
+
+        res.push(section.id());
+        section.encode(&mut res);
+
+        return Ok(res);
+    }
+
+    let elf = Elf::new(code)?;
+
+    if elf.section_header_size != SECTION_HEADER_SIZE {
+        return Err(format!(
+            "unexpected ELF section header size {}",
+            elf.section_header_size
+        ));
+    }
+
+    let sections = elf.sections()?;
+    let moved = elf.string_section..sections.len();
+
+    if sections[moved.clone()]
+        .iter()
+        .any(|section| section.flags & SHF_ALLOC != 0)
+    {
+        return Err("ELF sections after the section names are loaded".into());
+    }
+
+    let mut headers = code
+        .get(elf.header(0)..elf.header(sections.len()))
+        .ok_or("ELF section headers are truncated")?
+        .to_vec();
+
+    let mut res = code.to_vec();
+    let mut name = 0;
+
+    for section_no in moved {
+        let section = &sections[section_no];
+
+        if section.ty == SHT_NOBITS {
+            continue;
+        }
+
+        let mut contents = code
+            .get(section.offset..section.offset.saturating_add(section.size))
+            .ok_or_else(|| format!("ELF section {} is truncated", section.name))?
+            .to_vec();
+
+        if section_no == elf.string_section {
+            name = contents.len();
+            contents.extend_from_slice(format!(".{section_name}\0").as_bytes());
+        }
+
+        align(&mut res, section.align);
+
+        let header = section_no * SECTION_HEADER_SIZE;
+        headers[header + 24..header + 32].copy_from_slice(&(res.len() as u64).to_le_bytes());
+        headers[header + 32..header + 40].copy_from_slice(&(contents.len() as u64).to_le_bytes());
+
+        res.extend_from_slice(&contents);
+    }
+
+    let mut header = [0u8; SECTION_HEADER_SIZE];
+    header[0..4].copy_from_slice(&(name as u32).to_le_bytes());
+    header[4..8].copy_from_slice(&SHT_PROGBITS.to_le_bytes());
+    header[24..32].copy_from_slice(&(res.len() as u64).to_le_bytes());
+    header[32..40].copy_from_slice(&(data.len() as u64).to_le_bytes());
+    header[48..56].copy_from_slice(&1u64.to_le_bytes());
+    headers.extend_from_slice(&header);
+
+    res.extend_from_slice(data);
+
+    align(&mut res, 8);
+
+    let headers_offset = res.len() as u64;
+
+    res[0x28..0x30].copy_from_slice(&headers_offset.to_le_bytes());
+    res[0x3c..0x3e].copy_from_slice(&((sections.len() + 1) as u16).to_le_bytes());
+
+    res.extend_from_slice(&headers);
+
+    Ok(res)
+}
+
+/// Pad with zeros to the given alignment
+fn align(code: &mut Vec<u8>, align: usize) {
+    if align > 1 {
+        code.resize((code.len() + align - 1) / align * align, 0);
+    }
+}
+
+/// Read the build information from a wasm module or Solana ELF file, if it has any
+pub fn extract(code: &[u8]) -> Result<Option<BuildInfo>, String> {
+    extract_section(code, SECTION_NAME)?
+        .map(|data| {
+            serde_json::from_slice(data).map_err(|err| format!("invalid build information: {err}"))
+        })
+        .transpose()
+}
+
+/// Read a section which was added with [`embed_section`], if there is one
+pub(crate) fn extract_section<'a>(
+    code: &'a [u8],
+    section_name: &str,
+) -> Result<Option<&'a [u8]>, String> {
+    if code.starts_with(b"\0asm") {
+        let mut data = None;
+
+        for payload in Parser::new(0).parse_all(code) {
+            if let Payload::CustomSection(reader) =
+                payload.map_err(|err| format!("invalid wasm: {err}"))?
+            {
+                if reader.name() == section_name {
+                    data = Some(reader.data());
+                }
+            }
+        }
+
+        Ok(data)
+    } else {
+        let elf = Elf::new(code)?;
+        let name = format!(".{section_name}");
+
+        elf.sections()?
+            .into_iter()
+            .find(|section| section.name == name)
+            .map(|section| {
+                code.get(section.offset..section.offset.saturating_add(section.size))
+                    .ok_or_else(|| "ELF file is truncated".to_string())
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{inspect, parse_and_resolve};
+    use std::ffi::OsStr;
+
+    fn build_info() -> BuildInfo {
+        let mut resolver = FileResolver::default();
+
+        resolver.set_file_contents("a.sol", "import \"b.sol\"; contract A is B {}".into());
+        resolver.set_file_contents("b.sol", "contract B {}".into());
+
+        let ns = parse_and_resolve(OsStr::new("a.sol"), &mut resolver, Target::Solana);
+
+        assert!(!ns.diagnostics.any_errors());
+
+        BuildInfo::new(0, &ns, &resolver, &Options::default())
+    }
+
+    /// A Solana program with no code, only the section names
+    fn elf() -> Vec<u8> {
+        let names = b"\0.shstrtab\0";
+
+        let mut code = vec![0u8; 64];
+        code[..8].copy_from_slice(b"\x7fELF\x02\x01\x01\0");
+        code[0x28..0x30].copy_from_slice(&80u64.to_le_bytes());
+        code[0x3a..0x3c].copy_from_slice(&64u16.to_le_bytes());
+        code[0x3c..0x3e].copy_from_slice(&2u16.to_le_bytes());
+        code[0x3e..0x40].copy_from_slice(&1u16.to_le_bytes());
+
+        code.extend_from_slice(names);
+        code.resize(80, 0);
+
+        // section 0 is empty, section 1 has the names
+        let mut header = [0u8; 128];
+        header[64..68].copy_from_slice(&1u32.to_le_bytes());
+        header[68..72].copy_from_slice(&3u32.to_le_bytes());
+        header[88..96].copy_from_slice(&64u64.to_le_bytes());
+        header[96..104].copy_from_slice(&(names.len() as u64).to_le_bytes());
+        code.extend_from_slice(&header);
+
+        code
+    }
+
+    #[test]
+    fn sources() {
+        let info = build_info();
+
+        assert_eq!(info.compiler, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.target, "solana");
+        assert_eq!(info.contract, "A");
+        assert_eq!(info.address_length, None);
+        assert_eq!(
+            info.sources
+                .iter()
+                .map(|source| source.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.sol", "b.sol"]
+        );
+        assert_eq!(
+            info.sources[1].keccak256,
+            hex::encode(keccak256(b"contract B {}"))
+        );
+
+        assert_eq!(info.target().unwrap(), Target::Solana);
+        assert_eq!(
+            BuildOptions::from(&info.options.options().unwrap()),
+            info.options
+        );
+    }
+
+    #[test]
+    fn embed_elf() {
+        let code = elf();
+        let info = build_info();
+
+        assert_eq!(extract(&code).unwrap(), None);
+
+        let embedded = embed(&code, &info).unwrap();
+
+        assert_eq!(extract(&embedded).unwrap(), Some(info.clone()));
+
+        let inspection = inspect::inspect_elf(&embedded).unwrap();
+
+        assert_eq!(
+            inspection
+                .sections
+                .iter()
+                .map(|section| section.name.as_str())
+                .collect::<Vec<_>>(),
+            vec![".shstrtab", ".solang.build"]
+        );
+        assert_eq!(inspection.build_info, Some(info));
+    }
+
+    #[test]
+    fn embed_wasm() {
+        let code = wasm_encoder::Module::new().finish();
+        let info = build_info();
+
+        let embedded = embed(&code, &info).unwrap();
+
+        assert_eq!(extract(&embedded).unwrap(), Some(info.clone()));
+
+        let inspection = inspect::inspect_wasm(&embedded).unwrap();
+
+        assert_eq!(inspection.sections[0].name, "custom solang.build");
+        assert_eq!(inspection.build_info, Some(info));
+
+        assert!(embed(b"not a contract", &build_info()).is_err());
+    }
+}
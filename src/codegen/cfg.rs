@@ -2,9 +2,10 @@
 
 use super::statements::{statement, LoopScopes};
 use super::{
-    constant_folding, dead_storage,
+    constant_folding, dead_storage, devirtualize, encoding,
     expression::expression,
-    reaching_definitions, strength_reduce,
+    instcombine, loop_unroll, reaching_definitions, reentrancy, storage_cache, strength_reduce,
+    trace,
     vartable::{Vars, Vartable},
     vector_to_slice, Options,
 };
@@ -18,7 +19,7 @@ use crate::sema::{contracts::collect_base_args, diagnostics::Diagnostics, Recurs
 use crate::{sema::ast, Target};
 use indexmap::IndexMap;
 use num_bigint::BigInt;
-use num_traits::One;
+use num_traits::{One, Zero};
 use parse_display::Display;
 use solang_parser::pt::CodeLocation;
 use solang_parser::pt::Loc;
@@ -100,6 +101,7 @@ pub enum Instr {
     },
     /// Push element on memory array
     PushMemory {
+        loc: Loc,
         res: usize,
         ty: Type,
         array: usize,
@@ -113,6 +115,14 @@ pub enum Instr {
         array: usize,
         loc: Loc,
     },
+    /// Make room for at least size elements in a memory array, so that pushing
+    /// up to size elements does not reallocate it
+    ReserveMemory {
+        loc: Loc,
+        ty: Type,
+        array: usize,
+        size: Expression,
+    },
     /// Create contract and call constructor. If creating the contract fails,
     /// either store the result in success or abort success.
     Constructor {
@@ -127,6 +137,7 @@ pub enum Instr {
         address: Option<Expression>,
         seeds: Option<Expression>,
         accounts: ExternalCallAccounts<Expression>,
+        storage_deposit_limit: Option<Expression>,
         loc: Loc,
     },
     /// Call external functions. If the call fails, set the success failure
@@ -237,6 +248,10 @@ impl Instr {
                 expr.recurse(cx, f);
             }
 
+            Instr::ReserveMemory { size: expr, .. } => {
+                expr.recurse(cx, f);
+            }
+
             Instr::SetStorage {
                 value: item_1,
                 storage: item_2,
@@ -283,6 +298,7 @@ impl Instr {
                 salt,
                 address,
                 accounts,
+                storage_deposit_limit,
                 ..
             } => {
                 encoded_args.recurse(cx, f);
@@ -302,6 +318,10 @@ impl Instr {
                 if let ExternalCallAccounts::Present(expr) = accounts {
                     expr.recurse(cx, f);
                 }
+
+                if let Some(expr) = storage_deposit_limit {
+                    expr.recurse(cx, f);
+                }
             }
 
             Instr::ExternalCall {
@@ -420,6 +440,8 @@ pub struct ControlFlowGraph {
     pub array_lengths_temps: ArrayLengthVars,
     /// Is this a modifier dispatch for which function number?
     pub modifier: Option<usize>,
+    /// Scratch buffers for encoding call payloads, mapping the size to the variable number
+    pub scratch_buffers: HashMap<usize, usize>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -492,6 +514,7 @@ impl ControlFlowGraph {
             current: 0,
             array_lengths_temps: IndexMap::new(),
             modifier: None,
+            scratch_buffers: HashMap::new(),
         };
 
         cfg.new_basic_block("entry".to_string());
@@ -515,6 +538,7 @@ impl ControlFlowGraph {
             current: 0,
             array_lengths_temps: IndexMap::new(),
             modifier: None,
+            scratch_buffers: HashMap::new(),
         }
     }
 
@@ -550,6 +574,44 @@ impl ControlFlowGraph {
 
     /// Add an instruction to the CFG
     pub fn add(&mut self, vartab: &mut Vartable, ins: Instr) {
+        let ins = match ins {
+            // storage variables which were left out of the layout are never read or never
+            // written, so writes can be dropped and reads give the default value
+            Instr::SetStorage {
+                storage: Expression::Poison,
+                ..
+            }
+            | Instr::ClearStorage {
+                storage: Expression::Poison,
+                ..
+            } => return,
+            Instr::LoadStorage {
+                res,
+                ty,
+                storage: Expression::Poison,
+            } => Instr::Set {
+                loc: Loc::Codegen,
+                res,
+                expr: match ty {
+                    Type::Bool => Expression::BoolLiteral {
+                        loc: Loc::Codegen,
+                        value: false,
+                    },
+                    Type::Bytes(n) => Expression::BytesLiteral {
+                        loc: Loc::Codegen,
+                        ty,
+                        value: vec![0; n as usize],
+                    },
+                    _ => Expression::NumberLiteral {
+                        loc: Loc::Codegen,
+                        ty,
+                        value: BigInt::zero(),
+                    },
+                },
+            },
+            ins => ins,
+        };
+
         if let Instr::Set { res, .. } = ins {
             vartab.set_dirty(res);
         }
@@ -561,6 +623,42 @@ impl ControlFlowGraph {
         self.current
     }
 
+    /// The blocks which are cold: every path from them ends in a revert, or in a return code
+    /// other than success. These only run when the function fails.
+    pub fn cold_blocks(&self) -> Vec<bool> {
+        let successors: Vec<Vec<usize>> =
+            self.blocks.iter().map(|block| block.successors()).collect();
+
+        let mut cold: Vec<bool> = self
+            .blocks
+            .iter()
+            .map(|block| match block.instr.last() {
+                Some(Instr::AssertFailure { .. }) => true,
+                Some(Instr::ReturnCode { code }) => *code != ReturnCode::Success,
+                _ => false,
+            })
+            .collect();
+
+        // a loop which does not leave to a cold block never becomes cold
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for (block_no, successors) in successors.iter().enumerate() {
+                if !cold[block_no]
+                    && !successors.is_empty()
+                    && successors.iter().all(|succ| cold[*succ])
+                {
+                    cold[block_no] = true;
+                    changed = true;
+                }
+            }
+        }
+
+        cold
+    }
+
     /// Function to modify array length temp by inserting an add/sub instruction in the cfg right after a push/pop instruction.
     /// The operands of the add/sub instruction are the temp variable, and +/- 1.
     pub fn modify_temp_array_length(
@@ -1100,6 +1198,7 @@ impl ControlFlowGraph {
                 ty,
                 array,
                 value,
+                ..
             } => format!(
                 "%{}, %{} = push array ty:{} value:{}",
                 self.vars[res].id.name,
@@ -1113,6 +1212,12 @@ impl ControlFlowGraph {
                 self.vars[array].id.name,
                 ty.to_string(ns),
             ),
+            Instr::ReserveMemory { ty, array, size, .. } => format!(
+                "%{} = reserve array ty:{} size:{}",
+                self.vars[array].id.name,
+                ty.to_string(ns),
+                self.expr_to_string(contract, ns, size),
+            ),
             Instr::AssertFailure { encoded_args: None } => "assert-failure".to_string(),
             Instr::AssertFailure { encoded_args: Some(expr) } => {
                 format!("assert-failure: buffer: {}",
@@ -1247,9 +1352,10 @@ impl ControlFlowGraph {
                 address,seeds,
                 accounts,
                 constructor_no,
+                storage_deposit_limit,
                 loc:_
             } => format!(
-                "%{}, {} = constructor(no: {}) salt:{} value:{} gas:{} address:{} seeds:{} {} encoded buffer: {} accounts: {}",
+                "%{}, {} = constructor(no: {}) salt:{} value:{} gas:{} address:{} seeds:{} {} encoded buffer: {} accounts: {}{}",
                 self.vars[res].id.name,
                 match success {
                     Some(i) => format!("%{}", self.vars[i].id.name),
@@ -1284,6 +1390,14 @@ impl ControlFlowGraph {
                     self.expr_to_string(contract, ns, accounts)
                 } else {
                     String::new()
+                },
+                if let Some(limit) = storage_deposit_limit {
+                    format!(
+                        " storage_deposit_limit:{}",
+                        self.expr_to_string(contract, ns, limit)
+                    )
+                } else {
+                    String::new()
                 }
             ),
             Instr::SelfDestruct { recipient } => format!(
@@ -1477,6 +1591,13 @@ pub fn generate_cfg(
     }
 
     let mut cfg = function_cfg(contract_no, function_no, ns, opt);
+
+    if let Some(function_no) = function_no {
+        if trace::is_traced(&ns.functions[function_no], opt) {
+            trace::instrument(&mut cfg, function_no, ns);
+        }
+    }
+
     let ast_fn = function_no
         .map(ASTFunction::SolidityFunction)
         .unwrap_or(ASTFunction::None);
@@ -1567,8 +1688,19 @@ pub fn optimize_and_check_cfg(
     // do not depend which passes are enabled. If the constant_folding is not enabled, run it
     // dry mode.
     constant_folding::constant_folding(cfg, !opt.constant_folding, ns);
+    if opt.loop_unroll && loop_unroll::loop_unroll(cfg, ns) && opt.constant_folding {
+        // The diagnostics were given for the loop as it was written, so only fold the
+        // iterations, without giving the diagnostics again for each of them.
+        let diagnostics = std::mem::take(&mut ns.diagnostics);
+        constant_folding::constant_folding(cfg, false, ns);
+        ns.diagnostics = diagnostics;
+    }
+    if opt.constant_folding {
+        devirtualize::devirtualize(cfg);
+    }
     if opt.vector_to_slice {
         vector_to_slice::vector_to_slice(cfg, ns);
+        vector_to_slice::return_data_to_slice(cfg, ns);
     }
     if opt.strength_reduce {
         strength_reduce::strength_reduce(cfg, ns);
@@ -1576,11 +1708,16 @@ pub fn optimize_and_check_cfg(
     if opt.dead_storage {
         dead_storage::dead_storage(cfg, ns);
     }
+    if opt.storage_cache {
+        storage_cache::storage_cache(cfg, ns);
+    }
 
     // If the function is a default constructor, there is nothing to optimize.
     if opt.common_subexpression_elimination && func_no != ASTFunction::None {
         common_sub_expression_elimination(cfg, ns);
     }
+
+    instcombine::instcombine(cfg);
 }
 
 /// Generate the CFG for a function. If function_no is None, generate the implicit default
@@ -1643,6 +1780,22 @@ fn function_cfg(
     cfg.returns = func.returns.clone();
     cfg.selector = func.selector(ns, &contract_no);
 
+    let slice_params = read_only_param_slices(func, ns, opt);
+
+    if !slice_params.is_empty() {
+        let mut params = func.params.as_ref().clone();
+
+        for (param_no, ty) in &slice_params {
+            params[*param_no].ty = ty.clone();
+
+            if let Some(var_no) = func.symtable.arguments[*param_no] {
+                vartab.vars[&var_no].ty = ty.clone();
+            }
+        }
+
+        cfg.params = Arc::new(params);
+    }
+
     cfg.public = ns.function_externally_callable(contract_no, function_no);
     cfg.ty = func.ty;
     cfg.nonpayable = !func.is_payable();
@@ -1650,6 +1803,13 @@ fn function_cfg(
     // populate the argument variables
     populate_arguments(func, &mut cfg, &mut vartab);
 
+    // with modifiers, the guard is in the first modifier
+    let guarded = reentrancy::is_guarded(func) && func.modifiers.is_empty();
+
+    if guarded {
+        reentrancy::enter(func, &mut cfg, &mut vartab, ns, opt);
+    }
+
     // Hold your breath, this is the trickest part of the codegen ahead.
     // For each contract, the top-level constructor calls the base constructors. The base
     // constructors do not call their base constructors; everything is called from the top
@@ -1817,12 +1977,56 @@ fn function_cfg(
         );
     }
 
+    if reentrancy::is_guarded(func) {
+        reentrancy::exit(&mut cfg, guarded, ns);
+    }
+
     vartab.finalize(ns, &mut cfg);
 
+    if let Some(function_no) = function_no {
+        let symtable = &mut ns.functions[function_no].symtable;
+
+        for (param_no, _) in &slice_params {
+            if let Some(var_no) = symtable.arguments[*param_no] {
+                symtable.vars[&var_no].slice = true;
+            }
+        }
+    }
+
     // walk cfg to check for use for before initialize
     cfg
 }
 
+/// On Solana, read-only `bytes` and array parameters of external functions are decoded as
+/// slices which point into the input buffer, so they do not have to be copied to the heap.
+/// This is only possible if the elements are encoded exactly as they are laid out in memory.
+fn read_only_param_slices(
+    func: &ast::Function,
+    ns: &Namespace,
+    opt: &Options,
+) -> Vec<(usize, Type)> {
+    if ns.target != Target::Solana || !opt.vector_to_slice {
+        return Vec::new();
+    }
+
+    (0..func.params.len())
+        .filter(|param_no| func.read_only_params.contains(param_no))
+        .filter_map(|param_no| {
+            let elem_ty = match &func.params[param_no].ty {
+                Type::DynamicBytes => Type::Bytes(1),
+                Type::Array(elem_ty, _) => elem_ty.as_ref().clone(),
+                _ => return None,
+            };
+
+            if encoding::allow_memcpy(&elem_ty, ns) {
+                Some((param_no, Type::Slice(elem_ty.into())))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 /// Populate the arguments of a function
 pub(crate) fn populate_arguments<T: FunctionAttributes>(
     func: &T,
@@ -1924,6 +2128,13 @@ fn generate_modifier_dispatch(
         }
     }
 
+    // the reentrancy guard is checked before any modifier runs
+    let guarded = chain_no == 0 && reentrancy::is_guarded(func);
+
+    if guarded {
+        reentrancy::enter(func, &mut cfg, &mut vartab, ns, opt);
+    }
+
     // now set the modifier args
     for (i, arg) in modifier.symtable.arguments.iter().enumerate() {
         if let Some(pos) = arg {
@@ -2023,6 +2234,10 @@ fn generate_modifier_dispatch(
         );
     }
 
+    if reentrancy::is_guarded(func) {
+        reentrancy::exit(&mut cfg, guarded, ns);
+    }
+
     vartab.finalize(ns, &mut cfg);
 
     cfg
@@ -2101,11 +2316,27 @@ impl Contract {
             .iter()
             .find(|l| l.contract_no == var_contract_no && l.var_no == var_no)
         {
+            let value = if ns.target == Target::Soroban {
+                // The contract data key is the slot number as a U32Val: the number
+                // goes in the upper 32 bits and the lower bits hold the tag (4)
+                (layout.slot.clone() << 32) | BigInt::from(4u8)
+            } else {
+                layout.slot.clone()
+            };
+
             Expression::NumberLiteral {
                 loc,
                 ty: ty.unwrap_or_else(|| ns.storage_type()),
-                value: layout.slot.clone(),
+                value,
             }
+        } else if self.stripped_variables.contains(&(var_contract_no, var_no))
+            || self
+                .immutables_in_code
+                .contains_key(&(var_contract_no, var_no))
+        {
+            // the variable was left out of the layout; the storage instructions which use this
+            // are removed when they are added to the cfg
+            Expression::Poison
         } else {
             panic!("get_storage_slot called on non-storage variable");
         }
@@ -2152,10 +2383,11 @@ impl Namespace {
 
     /// Type storage
     pub fn storage_type(&self) -> Type {
-        if self.target == Target::Solana {
-            Type::Uint(32)
-        } else {
-            Type::Uint(256)
+        match self.target {
+            Target::Solana => Type::Uint(32),
+            // Soroban storage keys are Soroban values
+            Target::Soroban => Type::Uint(64),
+            _ => Type::Uint(256),
         }
     }
 
@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::cfg::{ControlFlowGraph, Instr};
+use super::expression::merge_format_segments;
 use super::reaching_definitions;
 use crate::codegen::{Builtin, Expression};
 use crate::sema::{
@@ -201,6 +202,7 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
                     }
                 }
                 Instr::PushMemory {
+                    loc,
                     res,
                     ty,
                     array,
@@ -210,6 +212,7 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
 
                     if !dry_run {
                         cfg.blocks[block_no].instr[instr_no] = Instr::PushMemory {
+                            loc: *loc,
                             res: *res,
                             ty: ty.clone(),
                             array: *array,
@@ -217,6 +220,23 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
                         };
                     }
                 }
+                Instr::ReserveMemory {
+                    loc,
+                    ty,
+                    array,
+                    size,
+                } => {
+                    let (size, _) = expression(size, Some(&vars), cfg, ns);
+
+                    if !dry_run {
+                        cfg.blocks[block_no].instr[instr_no] = Instr::ReserveMemory {
+                            loc: *loc,
+                            ty: ty.clone(),
+                            array: *array,
+                            size,
+                        };
+                    }
+                }
                 Instr::Constructor {
                     success,
                     res,
@@ -230,6 +250,7 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
                     loc,
                     accounts,
                     constructor_no,
+                    storage_deposit_limit,
                 } => {
                     let encoded_args = expression(encoded_args, Some(&vars), cfg, ns).0;
                     let value = value
@@ -246,6 +267,9 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
                         .as_ref()
                         .map(|expr| expression(expr, Some(&vars), cfg, ns).0);
                     let accounts = accounts.map(|expr| expression(expr, Some(&vars), cfg, ns).0);
+                    let storage_deposit_limit = storage_deposit_limit
+                        .as_ref()
+                        .map(|expr| expression(expr, Some(&vars), cfg, ns).0);
 
                     if !dry_run {
                         cfg.blocks[block_no].instr[instr_no] = Instr::Constructor {
@@ -261,6 +285,7 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, dry_run: bool, ns: &mut Name
                             seeds,
                             loc: *loc,
                             accounts,
+                            storage_deposit_limit,
                         };
                     }
                 }
@@ -499,13 +524,14 @@ fn expression(
             ty,
             left,
             right,
-        } => divide(loc, ty, left, right, vars, cfg, ns, false),
+        } => divide(loc, ty, left, right, vars, cfg, ns, false, false),
         Expression::SignedDivide {
             loc,
             ty,
+            overflowing,
             left,
             right,
-        } => divide(loc, ty, left, right, vars, cfg, ns, true),
+        } => divide(loc, ty, left, right, vars, cfg, ns, true, *overflowing),
         Expression::SignedModulo {
             loc,
             ty,
@@ -649,9 +675,23 @@ fn expression(
         | Expression::BytesLiteral { .. }
         | Expression::FunctionArg { .. } => (expr.clone(), true),
 
+        Expression::FormatString { loc, args } => {
+            let args = args
+                .iter()
+                .map(|(spec, arg)| (*spec, expression(arg, vars, cfg, ns).0))
+                .collect();
+
+            (
+                Expression::FormatString {
+                    loc: *loc,
+                    args: merge_format_segments(args),
+                },
+                false,
+            )
+        }
+
         Expression::ReturnData { .. }
         | Expression::Undefined { .. }
-        | Expression::FormatString { .. }
         | Expression::GetRef { .. }
         | Expression::InternalFunctionCfg { .. } => (expr.clone(), false),
         // nothing else is permitted in cfg
@@ -1095,6 +1135,7 @@ fn divide(
     cfg: &ControlFlowGraph,
     ns: &mut Namespace,
     signed: bool,
+    overflowing: bool,
 ) -> (Expression, bool) {
     let left = expression(left, vars, cfg, ns);
     let right = expression(right, vars, cfg, ns);
@@ -1104,7 +1145,8 @@ fn divide(
             ns.diagnostics
                 .push(Diagnostic::error(*loc, String::from("divide by zero")));
         } else if let Expression::NumberLiteral { value: left, .. } = &left.0 {
-            return bigint_to_expression(loc, ty, left.div(right), false, ns);
+            // only type(int).min / -1 can overflow
+            return bigint_to_expression(loc, ty, left.div(right), overflowing, ns);
         }
     }
     (
@@ -1112,6 +1154,7 @@ fn divide(
             Expression::SignedDivide {
                 loc: *loc,
                 ty: ty.clone(),
+                overflowing,
                 left: Box::new(left.0),
                 right: Box::new(right.0),
             }
@@ -1634,10 +1677,15 @@ fn bytes_cast(
         value,
     } = expr
     {
-        let (_, mut bs) = value.to_bytes_be();
+        // Sign extended values are negative, so use two's complement
+        let (mut bs, pad) = if value.sign() == Sign::Minus {
+            (value.to_signed_bytes_be(), 0xff)
+        } else {
+            (value.to_bytes_be().1, 0)
+        };
 
         while bs.len() < len as usize {
-            bs.insert(0, 0);
+            bs.insert(0, pad);
         }
 
         (
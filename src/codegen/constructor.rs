@@ -6,13 +6,13 @@ use crate::codegen::vartable::Vartable;
 use crate::codegen::{Expression, Options};
 use crate::sema::{
     ast,
-    ast::{CallArgs, Function, Namespace, Type},
+    ast::{CallArgs, Function, Namespace, Parameter, Type},
 };
 use crate::Target;
 use num_bigint::{BigInt, Sign};
 use solang_parser::pt::Loc;
 
-use super::encoding::abi_encode;
+use super::encoding::abi_encode_payload;
 
 /// This function encodes the constructor arguments and place an instruction in the CFG to
 /// call the constructor of a contract.
@@ -75,6 +75,10 @@ pub(super) fn call_constructor(
     let accounts = call_args
         .accounts
         .map(|expr| expression(expr, cfg, contract_no, func, ns, vartab, opt));
+    let storage_deposit_limit = call_args
+        .storage_deposit_limit
+        .as_ref()
+        .map(|e| expression(e, cfg, callee_contract_no, func, ns, vartab, opt));
 
     let mut constructor_args = constructor_args
         .iter()
@@ -99,7 +103,18 @@ pub(super) fn call_constructor(
 
     args.append(&mut constructor_args);
 
-    let (encoded_args, _) = abi_encode(loc, args, ns, vartab, cfg, false);
+    // the selector is not annotated
+    let mut annotations = vec![None];
+    if let Some(func_no) = constructor_no {
+        annotations.extend(
+            ns.functions[*func_no]
+                .params
+                .iter()
+                .map(Parameter::scale_annotation),
+        );
+    }
+
+    let (encoded_args, _) = abi_encode_payload(loc, args, &annotations, ns, vartab, cfg);
     cfg.add(
         vartab,
         Instr::Constructor {
@@ -115,6 +130,7 @@ pub(super) fn call_constructor(
             seeds,
             loc: *loc,
             accounts,
+            storage_deposit_limit,
         },
     );
 }
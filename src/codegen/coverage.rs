@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::Expression;
+use crate::coverage::counter_log;
+use crate::sema::ast::{Namespace, Type};
+use num_bigint::BigInt;
+use solang_parser::pt::{self, CodeLocation};
+
+/// Add a coverage counter to each basic block which has code from the source. When the block
+/// runs, the counter is reported in the logs, so that the report can count how often each line
+/// ran. The locations of the code counted by each counter are kept in the contract.
+///
+/// This should be done after the optimization passes, since the instructions are not tracked in
+/// the reaching definitions.
+pub(super) fn instrument(contract_no: usize, all_cfg: &mut [ControlFlowGraph], ns: &mut Namespace) {
+    let mut counters = Vec::new();
+
+    for cfg in all_cfg.iter_mut() {
+        for block in &mut cfg.blocks {
+            let locs: Vec<pt::Loc> = block
+                .instr
+                .iter()
+                .map(|instr| instr.loc())
+                .filter(|loc| matches!(loc, pt::Loc::File(..)))
+                .collect();
+
+            if locs.is_empty() {
+                continue;
+            }
+
+            let mut message = counter_log(&ns.contracts[contract_no].id.name, counters.len());
+
+            // the debug buffer on Polkadot is not split into lines
+            if ns.target.is_polkadot() {
+                message.push('\n');
+            }
+
+            let message = message.into_bytes();
+
+            block.instr.insert(
+                0,
+                Instr::Print {
+                    expr: Expression::AllocDynamicBytes {
+                        loc: pt::Loc::Codegen,
+                        ty: Type::Slice(Box::new(Type::Bytes(1))),
+                        size: Box::new(Expression::NumberLiteral {
+                            loc: pt::Loc::Codegen,
+                            ty: Type::Uint(32),
+                            value: BigInt::from(message.len()),
+                        }),
+                        initializer: Some(message),
+                    },
+                },
+            );
+
+            counters.push(locs);
+        }
+    }
+
+    ns.contracts[contract_no].coverage_counters = counters;
+}
@@ -235,7 +235,7 @@ fn instr_transfers(block_no: usize, block: &BasicBlock) -> Vec<Vec<Transfer>> {
 
                 v
             }
-            Instr::PopMemory { array, .. } => {
+            Instr::PopMemory { array, .. } | Instr::ReserveMemory { array, .. } => {
                 vec![Transfer::Kill { var_no: *array }]
             }
             Instr::ExternalCall {
@@ -297,7 +297,7 @@ fn instr_transfers(block_no: usize, block: &BasicBlock) -> Vec<Vec<Transfer>> {
                     },
                 ]
             }
-            Instr::Return { .. } => {
+            Instr::Return { .. } | Instr::ReturnData { .. } => {
                 vec![Transfer::Store { def, expr: None }]
             }
             _ => Vec::new(),
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr, InternalCallTy};
+use super::reaching_definitions;
+use crate::codegen::Expression;
+
+/// Replace a call through an internal function pointer with a direct call, when the pointer can
+/// only point to one function. This is the case when every definition of the variable which
+/// reaches the call assigns the same function, e.g. a library function assigned to a local
+/// variable. A direct call can be inlined, and the functions which are never called directly or
+/// through a pointer are left out of the binary.
+pub(super) fn devirtualize(cfg: &mut ControlFlowGraph) {
+    for block_no in 0..cfg.blocks.len() {
+        let mut vars = cfg.blocks[block_no].defs.clone();
+
+        for instr_no in 0..cfg.blocks[block_no].instr.len() {
+            if let Instr::Call {
+                call: InternalCallTy::Dynamic(expr),
+                ..
+            } = &cfg.blocks[block_no].instr[instr_no]
+            {
+                if let Some(cfg_no) = function_cfg(expr, Some(&vars), cfg) {
+                    if let Instr::Call { call, .. } = &mut cfg.blocks[block_no].instr[instr_no] {
+                        *call = InternalCallTy::Static { cfg_no };
+                    }
+                }
+            }
+
+            reaching_definitions::apply_transfers(
+                &cfg.blocks[block_no].transfers[instr_no],
+                &mut vars,
+            );
+        }
+    }
+}
+
+/// The cfg of the function the expression points to, if it can only be one function
+fn function_cfg(
+    expr: &Expression,
+    vars: Option<&reaching_definitions::VarDefs>,
+    cfg: &ControlFlowGraph,
+) -> Option<usize> {
+    match expr {
+        Expression::InternalFunctionCfg { cfg_no, .. } => Some(*cfg_no),
+        Expression::Cast { expr, .. } => function_cfg(expr, vars, cfg),
+        Expression::Variable { var_no, .. } => {
+            let defs = vars?.get(var_no)?;
+            let mut function = None;
+
+            // There must be at least one definition, and all should be the same function
+            for def in defs.keys() {
+                let Instr::Set { expr, .. } = &cfg.blocks[def.block_no].instr[def.instr_no] else {
+                    return None;
+                };
+
+                let cfg_no = function_cfg(expr, None, cfg)?;
+
+                if function.is_some_and(|function| function != cfg_no) {
+                    return None;
+                }
+
+                function = Some(cfg_no);
+            }
+
+            function
+        }
+        _ => None,
+    }
+}
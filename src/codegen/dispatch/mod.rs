@@ -1,22 +1,296 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{cfg::ControlFlowGraph, Options};
-use crate::{sema::ast::Namespace, Target};
+use super::{
+    cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy},
+    encoding::abi_decode_annotated,
+    vartable::Vartable,
+    Builtin, Expression, Options,
+};
+use crate::{
+    sema::ast::{Namespace, Parameter, ScaleAnnotation, Type},
+    Target,
+};
+use solang_parser::pt::{FunctionTy, Loc::Codegen};
+use std::sync::Arc;
 
 pub(crate) mod polkadot;
 pub(super) mod solana;
 
+/// The prefix of the names of the cfgs which decode the arguments of functions
+const DECODER_CFG_PREFIX: &str = "abi_decode_args_";
+
+/// Is the cfg with this name one of the dispatch functions, which select the function to run
+/// from the call data
+pub(crate) fn is_dispatch_cfg(name: &str) -> bool {
+    name == solana::SOLANA_DISPATCH_CFG_NAME
+        || name == polkadot::DispatchType::Deploy.to_string()
+        || name == polkadot::DispatchType::Call.to_string()
+}
+
+/// Is the cfg with this name one of the argument decoders, which are shared by the functions
+/// with the same parameter types
+pub(crate) fn is_decoder_cfg(name: &str) -> bool {
+    name.starts_with(DECODER_CFG_PREFIX)
+}
+
 pub(super) fn function_dispatch(
     contract_no: usize,
     all_cfg: &[ControlFlowGraph],
     ns: &mut Namespace,
     opt: &Options,
 ) -> Vec<ControlFlowGraph> {
-    match &ns.target {
-        Target::Solana => vec![solana::function_dispatch(contract_no, all_cfg, ns, opt)],
-        Target::Polkadot { .. } | Target::EVM => {
-            polkadot::function_dispatch(contract_no, all_cfg, ns, opt)
+    if ns.target == Target::Soroban {
+        return vec![];
+    }
+
+    // The decoders are numbered right after the existing cfgs, so they must come first
+    let decoders = ArgDecoders::new(all_cfg, ns, opt);
+
+    let dispatch = match &ns.target {
+        Target::Solana => vec![solana::function_dispatch(
+            contract_no,
+            all_cfg,
+            &decoders,
+            ns,
+            opt,
+        )],
+        _ => polkadot::function_dispatch(contract_no, all_cfg, &decoders, ns, opt),
+    };
+
+    decoders.cfgs.into_iter().chain(dispatch).collect()
+}
+
+/// Call the fallback function. The `fallback(bytes calldata input) returns (bytes memory output)`
+/// form gets the raw call data, and returns the variable which holds its output. The output is
+/// returned to the caller as it is, without ABI encoding it.
+fn call_fallback(
+    cfg_no: usize,
+    all_cfg: &[ControlFlowGraph],
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Option<Expression> {
+    let fallback_cfg = &all_cfg[cfg_no];
+
+    let args = if fallback_cfg.params.is_empty() {
+        Vec::new()
+    } else {
+        vec![Expression::Builtin {
+            loc: Codegen,
+            tys: vec![Type::DynamicBytes],
+            kind: Builtin::Calldata,
+            args: Vec::new(),
+        }]
+    };
+
+    let output = if fallback_cfg.returns.is_empty() {
+        None
+    } else {
+        Some(vartab.temp_name("fallback_output", &Type::DynamicBytes))
+    };
+
+    cfg.add(
+        vartab,
+        Instr::Call {
+            res: output.into_iter().collect(),
+            return_tys: fallback_cfg
+                .returns
+                .iter()
+                .map(|ret| ret.ty.clone())
+                .collect(),
+            call: InternalCallTy::Static { cfg_no },
+            args,
+        },
+    );
+
+    output.map(|var_no| Expression::Variable {
+        loc: Codegen,
+        ty: Type::DynamicBytes,
+        var_no,
+    })
+}
+
+/// The parameter types and SCALE annotations of a function, which determine how its arguments
+/// are decoded
+type DecoderKey = (Vec<Type>, Vec<Option<ScaleAnnotation>>);
+
+fn decoder_key(func_cfg: &ControlFlowGraph) -> DecoderKey {
+    (
+        func_cfg
+            .params
+            .iter()
+            .map(|param| param.ty.clone())
+            .collect(),
+        func_cfg
+            .params
+            .iter()
+            .map(Parameter::scale_annotation)
+            .collect(),
+    )
+}
+
+/// Functions and constructors with the same parameter types share a function which decodes
+/// their arguments, rather than having the decoder inlined into each dispatch case. This
+/// reduces the code size of contracts with many similar functions. Parameter types which are
+/// used only once are still decoded inline.
+pub(super) struct ArgDecoders {
+    /// The parameters of each shared decoder, and its cfg number
+    decoders: Vec<(DecoderKey, usize)>,
+    cfgs: Vec<ControlFlowGraph>,
+}
+
+impl ArgDecoders {
+    fn new(all_cfg: &[ControlFlowGraph], ns: &mut Namespace, opt: &Options) -> Self {
+        let mut uses: Vec<(DecoderKey, usize)> = Vec::new();
+
+        for func_cfg in all_cfg {
+            if !func_cfg.public
+                || !matches!(func_cfg.ty, FunctionTy::Function | FunctionTy::Constructor)
+                || func_cfg.params.is_empty()
+            {
+                continue;
+            }
+
+            let key = decoder_key(func_cfg);
+
+            if let Some((_, count)) = uses.iter_mut().find(|(k, _)| *k == key) {
+                *count += 1;
+            } else {
+                uses.push((key, 1));
+            }
+        }
+
+        let mut decoders = ArgDecoders {
+            decoders: Vec::new(),
+            cfgs: Vec::new(),
+        };
+
+        for (key, count) in uses {
+            if count > 1 {
+                let cfg_no = all_cfg.len() + decoders.cfgs.len();
+                let cfg = decoder_cfg(decoders.cfgs.len(), &key, ns, opt);
+
+                decoders.cfgs.push(cfg);
+                decoders.decoders.push((key, cfg_no));
+            }
         }
-        Target::Soroban => vec![],
+
+        decoders
+    }
+
+    /// Decode the arguments of the function from the buffer, by calling the shared decoder
+    /// for its parameter types if there is one.
+    fn decode(
+        &self,
+        func_cfg: &ControlFlowGraph,
+        buffer: &Expression,
+        buffer_size: Expression,
+        ns: &Namespace,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+        opt: &Options,
+    ) -> Vec<Expression> {
+        let (tys, annotations) = decoder_key(func_cfg);
+
+        let Some(cfg_no) = self
+            .decoders
+            .iter()
+            .find(|((key_tys, key_annotations), _)| {
+                *key_tys == tys && *key_annotations == annotations
+            })
+            .map(|(_, cfg_no)| *cfg_no)
+        else {
+            return abi_decode_annotated(
+                &Codegen,
+                buffer,
+                &tys,
+                &annotations,
+                ns,
+                vartab,
+                cfg,
+                Some(buffer_size),
+                opt,
+            );
+        };
+
+        let res: Vec<usize> = tys.iter().map(|ty| vartab.temp_anonymous(ty)).collect();
+
+        let args = res
+            .iter()
+            .zip(&tys)
+            .map(|(var_no, ty)| Expression::Variable {
+                loc: Codegen,
+                ty: ty.clone(),
+                var_no: *var_no,
+            })
+            .collect();
+
+        cfg.add(
+            vartab,
+            Instr::Call {
+                res,
+                call: InternalCallTy::Static { cfg_no },
+                args: vec![buffer.clone(), buffer_size],
+                return_tys: tys,
+            },
+        );
+
+        args
     }
 }
+
+/// Create a function which decodes arguments of the given types from the buffer given as its
+/// first argument, with the size given as its second argument, and returns them.
+fn decoder_cfg(
+    decoder_no: usize,
+    (tys, annotations): &DecoderKey,
+    ns: &mut Namespace,
+    opt: &Options,
+) -> ControlFlowGraph {
+    // Solidity functions have `::` in their cfg names, so this cannot clash with one
+    let mut cfg = ControlFlowGraph::new(
+        format!("{DECODER_CFG_PREFIX}{decoder_no}"),
+        ASTFunction::None,
+    );
+    let mut vartab = Vartable::new(ns.next_id);
+
+    let param = |ty: &Type| Parameter {
+        loc: Codegen,
+        id: None,
+        ty: ty.clone(),
+        ty_loc: None,
+        indexed: false,
+        readonly: false,
+        infinite_size: false,
+        recursive: false,
+        annotation: None,
+    };
+
+    cfg.params = Arc::new(vec![param(&Type::BufferPointer), param(&Type::Uint(32))]);
+    cfg.returns = Arc::new(tys.iter().map(param).collect());
+
+    let args = abi_decode_annotated(
+        &Codegen,
+        &Expression::FunctionArg {
+            loc: Codegen,
+            ty: Type::BufferPointer,
+            arg_no: 0,
+        },
+        tys,
+        annotations,
+        ns,
+        &mut vartab,
+        &mut cfg,
+        Some(Expression::FunctionArg {
+            loc: Codegen,
+            ty: Type::Uint(32),
+            arg_no: 1,
+        }),
+        opt,
+    );
+
+    cfg.add(&mut vartab, Instr::Return { value: args });
+
+    vartab.finalize(ns, &mut cfg);
+
+    cfg
+}
@@ -1,9 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use super::ArgDecoders;
 use crate::{
     codegen::{
         cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy, ReturnCode},
-        encoding::{abi_decode, abi_encode},
+        encoding::abi_encode_annotated,
         revert::log_runtime_error,
         vartable::Vartable,
         Builtin, Expression, Options,
@@ -57,15 +58,16 @@ impl From<FunctionTy> for DispatchType {
 /// 5. Return the result:
 ///     - On success, ABI encode the result (if any) and return.
 ///     - On failure, trap the contract.
-pub(crate) fn function_dispatch(
+pub(super) fn function_dispatch(
     _contract_no: usize,
     all_cfg: &[ControlFlowGraph],
+    decoders: &ArgDecoders,
     ns: &mut Namespace,
     opt: &Options,
 ) -> Vec<ControlFlowGraph> {
     vec![
-        Dispatch::new(all_cfg, ns, opt, FunctionTy::Constructor).build(),
-        Dispatch::new(all_cfg, ns, opt, FunctionTy::Function).build(),
+        Dispatch::new(all_cfg, decoders, ns, opt, FunctionTy::Constructor).build(),
+        Dispatch::new(all_cfg, decoders, ns, opt, FunctionTy::Function).build(),
     ]
 }
 
@@ -77,6 +79,7 @@ struct Dispatch<'a> {
     vartab: Vartable,
     cfg: ControlFlowGraph,
     all_cfg: &'a [ControlFlowGraph],
+    decoders: &'a ArgDecoders,
     ns: &'a mut Namespace,
     selector_len: Box<Expression>,
     opt: &'a Options,
@@ -112,6 +115,7 @@ impl<'a> Dispatch<'a> {
     /// `ty` specifies whether to include constructors or functions.
     fn new(
         all_cfg: &'a [ControlFlowGraph],
+        decoders: &'a ArgDecoders,
         ns: &'a mut Namespace,
         opt: &'a Options,
         ty: FunctionTy,
@@ -187,6 +191,7 @@ impl<'a> Dispatch<'a> {
             value,
             cfg,
             all_cfg,
+            decoders,
             ns,
             selector_len,
             opt,
@@ -310,18 +315,18 @@ impl<'a> Dispatch<'a> {
                 left: buf_len.into(),
                 right: self.selector_len.clone(),
             };
-            args = abi_decode(
-                &Codegen,
+            args = self.decoders.decode(
+                cfg,
                 &self.input_ptr,
-                &cfg.params.iter().map(|p| p.ty.clone()).collect::<Vec<_>>(),
-                self.ns,
-                &mut self.vartab,
-                &mut self.cfg,
-                Some(Expression::Trunc {
+                Expression::Trunc {
                     loc: Codegen,
                     ty: Uint(32),
                     expr: arg_len.into(),
-                }),
+                },
+                self.ns,
+                &mut self.vartab,
+                &mut self.cfg,
+                self.opt,
             );
         }
 
@@ -360,13 +365,16 @@ impl<'a> Dispatch<'a> {
             };
             self.add(Instr::ReturnData { data, data_len })
         } else {
-            let (data, data_len) = abi_encode(
+            let (data, data_len) = abi_encode_annotated(
                 &Codegen,
                 returns_expr,
+                &cfg.returns
+                    .iter()
+                    .map(Parameter::scale_annotation)
+                    .collect::<Vec<_>>(),
                 self.ns,
                 &mut self.vartab,
                 &mut self.cfg,
-                false,
             );
             self.add(Instr::ReturnData { data, data_len });
         }
@@ -408,9 +416,14 @@ impl<'a> Dispatch<'a> {
         self.cfg.set_basic_block(true_block);
         let function_name = self.all_cfg[func_no].name.split("::").last().unwrap();
         let function_type = self.all_cfg[func_no].ty;
+        let reason = if function_type == FunctionTy::Fallback {
+            format!("runtime_error: non payable {function_type} received value")
+        } else {
+            format!("runtime_error: non payable {function_type} {function_name} received value")
+        };
         log_runtime_error(
             self.opt.log_runtime_errors,
-            &format!("runtime_error: non payable {function_type} {function_name} received value"),
+            &reason,
             Codegen,
             &mut self.cfg,
             &mut self.vartab,
@@ -422,6 +435,10 @@ impl<'a> Dispatch<'a> {
     }
 
     /// Build calls to fallback or receive functions (if they are present in the contract).
+    ///
+    /// As in Solidity, `receive()` is called if the call data is empty. On Polkadot, value can
+    /// only be sent along with call data, so `receive()` is also called for any call with value.
+    /// Otherwise `fallback()` is called, which reverts if it is not payable and value was sent.
     fn fallback_or_receive(&mut self) {
         let (fallback_cfg, receive_cfg) = self.all_cfg.iter().enumerate().fold(
             (None, None),
@@ -440,76 +457,107 @@ impl<'a> Dispatch<'a> {
             return self.selector_invalid();
         }
 
-        let fallback_block = self.cfg.new_basic_block("fallback".into());
-        let receive_block = self.cfg.new_basic_block("receive".into());
-        self.add(Instr::BranchCond {
-            cond: Expression::More {
-                loc: Codegen,
-                signed: false,
-                left: Expression::Variable {
-                    loc: Codegen,
-                    ty: self.ns.value_type(),
-                    var_no: self.value,
-                }
-                .into(),
-                right: Expression::NumberLiteral {
+        if let Some(cfg_no) = receive_cfg {
+            let polkadot = self.ns.target.is_polkadot();
+            let receive_block = self.cfg.new_basic_block("receive".into());
+            let has_input = polkadot.then(|| self.cfg.new_basic_block("has_input".into()));
+            let fallback_block = self.cfg.new_basic_block("fallback".into());
+
+            self.add(Instr::BranchCond {
+                cond: Expression::Equal {
                     loc: Codegen,
-                    ty: self.ns.value_type(),
-                    value: 0.into(),
-                }
-                .into(),
-            },
-            true_block: receive_block,
-            false_block: fallback_block,
-        });
+                    left: Expression::Variable {
+                        loc: Codegen,
+                        ty: Uint(32),
+                        var_no: self.input_len,
+                    }
+                    .into(),
+                    right: Expression::NumberLiteral {
+                        loc: Codegen,
+                        ty: Uint(32),
+                        value: 0.into(),
+                    }
+                    .into(),
+                },
+                true_block: receive_block,
+                false_block: has_input.unwrap_or(fallback_block),
+            });
+
+            if let Some(has_input) = has_input {
+                self.cfg.set_basic_block(has_input);
+                self.add(Instr::BranchCond {
+                    cond: Expression::More {
+                        loc: Codegen,
+                        signed: false,
+                        left: Expression::Variable {
+                            loc: Codegen,
+                            ty: self.ns.value_type(),
+                            var_no: self.value,
+                        }
+                        .into(),
+                        right: Expression::NumberLiteral {
+                            loc: Codegen,
+                            ty: self.ns.value_type(),
+                            value: 0.into(),
+                        }
+                        .into(),
+                    },
+                    true_block: receive_block,
+                    false_block: fallback_block,
+                });
+            }
 
-        self.cfg.set_basic_block(fallback_block);
-        if let Some(cfg_no) = fallback_cfg {
+            self.cfg.set_basic_block(receive_block);
             self.add(Instr::Call {
                 res: vec![],
                 return_tys: vec![],
                 call: InternalCallTy::Static { cfg_no },
                 args: vec![],
             });
-            let data_len = Expression::NumberLiteral {
-                loc: Codegen,
-                ty: Uint(32),
-                value: 0.into(),
-            };
-            let data = Expression::AllocDynamicBytes {
-                loc: Codegen,
-                ty: Type::DynamicBytes,
-                size: data_len.clone().into(),
-                initializer: None,
-            };
-            self.add(Instr::ReturnData { data, data_len })
-        } else {
-            self.selector_invalid();
+            self.return_empty();
+
+            self.cfg.set_basic_block(fallback_block);
         }
 
-        self.cfg.set_basic_block(receive_block);
-        if let Some(cfg_no) = receive_cfg {
-            self.add(Instr::Call {
-                res: vec![],
-                return_tys: vec![],
-                call: InternalCallTy::Static { cfg_no },
-                args: vec![],
-            });
-            let data_len = Expression::NumberLiteral {
-                loc: Codegen,
-                ty: Uint(32),
-                value: 0.into(),
-            };
-            let data = Expression::AllocDynamicBytes {
-                loc: Codegen,
-                ty: Type::DynamicBytes,
-                size: data_len.clone().into(),
-                initializer: None,
-            };
-            self.add(Instr::ReturnData { data, data_len })
-        } else {
-            self.selector_invalid()
+        let Some(cfg_no) = fallback_cfg else {
+            return self.selector_invalid();
+        };
+
+        // On Polkadot, any value already went to receive()
+        if receive_cfg.is_none() || !self.ns.target.is_polkadot() {
+            self.abort_if_value_transfer(cfg_no);
         }
+
+        match super::call_fallback(cfg_no, self.all_cfg, &mut self.vartab, &mut self.cfg) {
+            Some(output) => {
+                let data_len = Expression::Builtin {
+                    loc: Codegen,
+                    tys: vec![Uint(32)],
+                    kind: Builtin::ArrayLength,
+                    args: vec![output.clone()],
+                };
+                self.add(Instr::ReturnData {
+                    data: output,
+                    data_len,
+                });
+            }
+            None => self.return_empty(),
+        }
+    }
+
+    fn return_empty(&mut self) {
+        let data_len = Expression::NumberLiteral {
+            loc: Codegen,
+            ty: Uint(32),
+            value: 0.into(),
+        };
+        let data = Expression::AllocDynamicBytes {
+            loc: Codegen,
+            ty: Type::DynamicBytes,
+            size: data_len.clone().into(),
+            initializer: None,
+        };
+        self.add(Instr::ReturnData { data, data_len })
     }
 
     fn selector_invalid(&mut self) {
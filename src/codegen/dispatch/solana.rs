@@ -2,7 +2,7 @@
 
 use crate::codegen::{
     cfg::{ASTFunction, ControlFlowGraph, Instr, InternalCallTy, ReturnCode},
-    solana_deploy::solana_deploy,
+    solana_deploy::{account_magic, solana_deploy},
     vartable::Vartable,
     Builtin, Expression, Options,
 };
@@ -11,7 +11,8 @@ use num_bigint::{BigInt, Sign};
 use num_traits::Zero;
 use solang_parser::{pt, pt::Loc};
 
-use crate::codegen::encoding::{abi_decode, abi_encode};
+use super::ArgDecoders;
+use crate::codegen::encoding::abi_encode;
 use crate::sema::solana_accounts::BuiltinAccounts;
 
 pub const SOLANA_DISPATCH_CFG_NAME: &str = "solang_dispatch";
@@ -20,6 +21,7 @@ pub const SOLANA_DISPATCH_CFG_NAME: &str = "solang_dispatch";
 pub(crate) fn function_dispatch(
     contract_no: usize,
     all_cfg: &[ControlFlowGraph],
+    decoders: &ArgDecoders,
     ns: &mut Namespace,
     opt: &Options,
 ) -> ControlFlowGraph {
@@ -160,9 +162,11 @@ pub(crate) fn function_dispatch(
                 &argsdata,
                 argslen.clone(),
                 contract_no,
+                decoders,
                 ns,
                 &mut vartab,
                 &mut cfg,
+                opt,
             )
         } else if func_cfg.ty == pt::FunctionTy::Constructor {
             add_constructor_dispatch_case(
@@ -171,6 +175,7 @@ pub(crate) fn function_dispatch(
                 &argsdata,
                 argslen.clone(),
                 func_cfg,
+                decoders,
                 ns,
                 &mut vartab,
                 &mut cfg,
@@ -232,25 +237,44 @@ pub(crate) fn function_dispatch(
                 .borrow()
                 .contains_key(BuiltinAccounts::DataAccount.as_str())
             {
-                check_magic(ns.contracts[contract_no].selector(), &mut cfg, &mut vartab);
+                check_magic(
+                    account_magic(&ns.contracts[contract_no]),
+                    &mut cfg,
+                    &mut vartab,
+                );
             }
 
-            cfg.add(
-                &mut vartab,
-                Instr::Call {
-                    res: vec![],
-                    return_tys: vec![],
-                    args: vec![],
-                    call: InternalCallTy::Static { cfg_no },
-                },
-            );
-
-            cfg.add(
-                &mut vartab,
-                Instr::ReturnCode {
-                    code: ReturnCode::Success,
-                },
-            );
+            match super::call_fallback(cfg_no, all_cfg, &mut vartab, &mut cfg) {
+                Some(output) => {
+                    let data_len = Expression::ZeroExt {
+                        loc: Loc::Codegen,
+                        ty: Type::Uint(64),
+                        expr: Expression::Builtin {
+                            loc: Loc::Codegen,
+                            tys: vec![Type::Uint(32)],
+                            kind: Builtin::ArrayLength,
+                            args: vec![output.clone()],
+                        }
+                        .into(),
+                    };
+
+                    cfg.add(
+                        &mut vartab,
+                        Instr::ReturnData {
+                            data: output,
+                            data_len,
+                        },
+                    );
+                }
+                None => {
+                    cfg.add(
+                        &mut vartab,
+                        Instr::ReturnCode {
+                            code: ReturnCode::Success,
+                        },
+                    );
+                }
+            }
         }
         None => {
             cfg.add(
@@ -274,9 +298,11 @@ fn add_function_dispatch_case(
     argsdata: &Expression,
     argslen: Expression,
     contract_no: usize,
+    decoders: &ArgDecoders,
     ns: &Namespace,
     vartab: &mut Vartable,
     cfg: &mut ControlFlowGraph,
+    opt: &Options,
 ) -> usize {
     let entry = cfg.new_basic_block(format!("function_cfg_{cfg_no}"));
     cfg.set_basic_block(entry);
@@ -294,7 +320,7 @@ fn add_function_dispatch_case(
         .borrow()
         .contains_key(BuiltinAccounts::DataAccount.as_str())
     {
-        check_magic(ns.contracts[contract_no].selector(), cfg, vartab);
+        check_magic(account_magic(&ns.contracts[contract_no]), cfg, vartab);
     }
 
     let truncated_len = Expression::Trunc {
@@ -303,20 +329,7 @@ fn add_function_dispatch_case(
         expr: Box::new(argslen),
     };
 
-    let tys = func_cfg
-        .params
-        .iter()
-        .map(|e| e.ty.clone())
-        .collect::<Vec<Type>>();
-    let decoded = abi_decode(
-        &Loc::Codegen,
-        argsdata,
-        &tys,
-        ns,
-        vartab,
-        cfg,
-        Some(truncated_len),
-    );
+    let decoded = decoders.decode(func_cfg, argsdata, truncated_len, ns, vartab, cfg, opt);
 
     let mut returns: Vec<usize> = Vec::with_capacity(func_cfg.returns.len());
     let mut return_tys: Vec<Type> = Vec::with_capacity(func_cfg.returns.len());
@@ -397,6 +410,7 @@ fn add_constructor_dispatch_case(
     argsdata: &Expression,
     argslen: Expression,
     func_cfg: &ControlFlowGraph,
+    decoders: &ArgDecoders,
     ns: &mut Namespace,
     vartab: &mut Vartable,
     cfg: &mut ControlFlowGraph,
@@ -405,30 +419,19 @@ fn add_constructor_dispatch_case(
     let entry = cfg.new_basic_block(format!("constructor_cfg_{cfg_no}"));
     cfg.set_basic_block(entry);
 
-    check_magic(0, cfg, vartab);
+    // the data account must not have been used by a contract yet
+    let (ty, _) = account_magic(&ns.contracts[contract_no]);
+    check_magic((ty, BigInt::zero()), cfg, vartab);
 
     let mut returns: Vec<Expression> = Vec::new();
 
     if !func_cfg.params.is_empty() {
-        let tys = func_cfg
-            .params
-            .iter()
-            .map(|e| e.ty.clone())
-            .collect::<Vec<Type>>();
         let truncated_len = Expression::Trunc {
             loc: Loc::Codegen,
             ty: Type::Uint(32),
             expr: Box::new(argslen),
         };
-        returns = abi_decode(
-            &Loc::Codegen,
-            argsdata,
-            &tys,
-            ns,
-            vartab,
-            cfg,
-            Some(truncated_len),
-        );
+        returns = decoders.decode(func_cfg, argsdata, truncated_len, ns, vartab, cfg, opt);
     }
 
     if let ASTFunction::SolidityFunction(function_no) = func_cfg.function_no {
@@ -474,18 +477,22 @@ fn add_constructor_dispatch_case(
     entry
 }
 
-fn check_magic(magic_value: u32, cfg: &mut ControlFlowGraph, vartab: &mut Vartable) {
+fn check_magic(
+    (ty, magic_value): (Type, BigInt),
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+) {
     // check for magic in data account, to see if data account is initialized
     let magic_ok = cfg.new_basic_block("magic_ok".into());
     let magic_bad = cfg.new_basic_block("magic_bad".into());
 
-    let magic = vartab.temp_name("magic", &Type::Uint(32));
+    let magic = vartab.temp_name("magic", &ty);
 
     cfg.add(
         vartab,
         Instr::LoadStorage {
             res: magic,
-            ty: Type::Uint(32),
+            ty: ty.clone(),
             storage: Expression::NumberLiteral {
                 loc: Loc::Codegen,
                 ty: Type::Uint(32),
@@ -501,14 +508,14 @@ fn check_magic(magic_value: u32, cfg: &mut ControlFlowGraph, vartab: &mut Vartab
                 loc: Loc::Codegen,
                 left: Expression::Variable {
                     loc: Loc::Codegen,
-                    ty: Type::Uint(32),
+                    ty: ty.clone(),
                     var_no: magic,
                 }
                 .into(),
                 right: Expression::NumberLiteral {
                     loc: Loc::Codegen,
-                    ty: Type::Uint(32),
-                    value: magic_value.into(),
+                    ty,
+                    value: magic_value,
                 }
                 .into(),
             },
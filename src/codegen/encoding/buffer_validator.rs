@@ -3,11 +3,12 @@
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
 use crate::codegen::revert::{assert_failure, PanicCode, SolidityError};
 use crate::codegen::vartable::Vartable;
-use crate::codegen::Expression;
-use crate::sema::ast::{Namespace, Type};
+use crate::codegen::{Expression, Options};
+use crate::sema::ast::{FormatArg, Namespace, Type};
 use num_bigint::BigInt;
 use num_traits::Zero;
 use solang_parser::pt::Loc;
+use std::collections::BTreeSet;
 use std::ops::AddAssign;
 
 /// When we are decoding serialized data from a bytes array, we must constantly verify if
@@ -22,10 +23,23 @@ pub(crate) struct BufferValidator<'a> {
     verified_until: Option<usize>,
     /// The argument we are analysing presently
     current_arg: usize,
+    /// The index of the first of `types` among all the arguments being decoded
+    first_arg: usize,
+    /// When validating struct fields, the argument which contains the struct
+    parent_arg: Option<usize>,
+    /// Where to report decoding failures, if runtime errors are logged
+    failure: Option<DecodingFailure>,
+    /// Whether failures without a report revert with `Panic(uint256)` data
+    revert_data: bool,
 }
 
 impl BufferValidator<'_> {
-    pub fn new(buffer_size_var: usize, types: &[Type]) -> BufferValidator {
+    pub fn new<'a>(
+        buffer_size_var: usize,
+        types: &'a [Type],
+        failure: Option<DecodingFailure>,
+        opt: &Options,
+    ) -> BufferValidator<'a> {
         BufferValidator {
             buffer_length: Expression::Variable {
                 loc: Loc::Codegen,
@@ -35,9 +49,20 @@ impl BufferValidator<'_> {
             types,
             verified_until: None,
             current_arg: 0,
+            first_arg: 0,
+            parent_arg: None,
+            failure,
+            revert_data: opt.revert_data,
         }
     }
 
+    /// Set the index of the first of the validator's types among all the arguments being
+    /// decoded. This is needed when each argument is validated separately.
+    pub(super) fn for_argument(mut self, arg_no: usize) -> Self {
+        self.first_arg = arg_no;
+        self
+    }
+
     /// Set which item we are currently reading from the buffer
     pub(super) fn set_argument_number(&mut self, arg_no: usize) {
         self.current_arg = arg_no;
@@ -125,10 +150,12 @@ impl BufferValidator<'_> {
         let cond = Expression::Less {
             loc: Loc::Codegen,
             signed: false,
-            left: Box::new(end_offset),
+            left: Box::new(end_offset.clone()),
             right: Box::new(self.buffer_length.clone()),
         };
 
+        self.save_offset(end_offset, vartab, cfg);
+
         let invalid = cfg.new_basic_block("not_all_bytes_read".to_string());
         let valid = cfg.new_basic_block("buffer_read".to_string());
         cfg.add(
@@ -141,10 +168,9 @@ impl BufferValidator<'_> {
         );
 
         cfg.set_basic_block(invalid);
-
-        // TODO: This needs a proper error message
-        let error = SolidityError::Panic(PanicCode::Generic);
-        assert_failure(&Loc::Codegen, error, ns, cfg, vartab);
+        // Trailing bytes are reported as a failure at the field after the last one
+        let field = self.first_arg + self.types.len();
+        self.fail(field, ns, vartab, cfg);
 
         cfg.set_basic_block(valid);
     }
@@ -205,10 +231,12 @@ impl BufferValidator<'_> {
         let cond = Expression::LessEqual {
             loc: Loc::Codegen,
             signed: false,
-            left: Box::new(offset),
+            left: Box::new(offset.clone()),
             right: Box::new(self.buffer_length.clone()),
         };
 
+        self.save_offset(offset, vartab, cfg);
+
         let inbounds_block = cfg.new_basic_block("inbounds".to_string());
         let out_of_bounds_block = cfg.new_basic_block("out_of_bounds".to_string());
 
@@ -222,12 +250,65 @@ impl BufferValidator<'_> {
         );
 
         cfg.set_basic_block(out_of_bounds_block);
-        // TODO: Add an error message here
-        let error = SolidityError::Panic(PanicCode::Generic);
-        assert_failure(&Loc::Codegen, error, ns, cfg, vartab);
+        let field = self.parent_arg.unwrap_or(self.first_arg + self.current_arg);
+        self.fail(field, ns, vartab, cfg);
         cfg.set_basic_block(inbounds_block);
     }
 
+    /// Save the offset which is about to be checked, so that it can be reported if the check
+    /// fails. This is done before branching, so the offset is evaluated where it is checked.
+    fn save_offset(&self, offset: Expression, vartab: &mut Vartable, cfg: &mut ControlFlowGraph) {
+        if let Some(failure) = &self.failure {
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Loc::Codegen,
+                    res: failure.offset,
+                    expr: offset,
+                },
+            );
+        }
+    }
+
+    /// Fail decoding at the given field. If runtime errors are logged, this jumps to the block
+    /// which reports them; otherwise, this is a generic panic.
+    fn fail(
+        &self,
+        field: usize,
+        ns: &Namespace,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+    ) {
+        let Some(failure) = &self.failure else {
+            let error = if self.revert_data {
+                SolidityError::Panic(PanicCode::Generic)
+            } else {
+                SolidityError::Empty
+            };
+            assert_failure(&Loc::Codegen, error, ns, cfg, vartab);
+            return;
+        };
+
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Loc::Codegen,
+                res: failure.field,
+                expr: Expression::NumberLiteral {
+                    loc: Loc::Codegen,
+                    ty: Type::Uint(32),
+                    value: field.into(),
+                },
+            },
+        );
+        cfg.add(
+            vartab,
+            Instr::Branch {
+                block: failure.block,
+            },
+        );
+    }
+
     /// Create a new buffer validator to validate struct fields.
     pub(super) fn create_sub_validator<'a>(&self, types: &'a [Type]) -> BufferValidator<'a> {
         // If the struct has been previously validated, there is no need to validate it again,
@@ -235,6 +316,10 @@ impl BufferValidator<'_> {
         BufferValidator {
             buffer_length: self.buffer_length.clone(),
             types,
+            first_arg: 0,
+            parent_arg: Some(self.parent_arg.unwrap_or(self.first_arg + self.current_arg)),
+            failure: self.failure,
+            revert_data: self.revert_data,
             verified_until: if self.validation_necessary() {
                 None
             } else {
@@ -248,3 +333,116 @@ impl BufferValidator<'_> {
         }
     }
 }
+
+/// In debug builds, every failure while decoding a buffer jumps to a single block, which reports
+/// the failing field and byte offset. The report is printed and, where the target supports it and
+/// revert data is not left out, returned as `Error(string)` revert data.
+#[derive(Clone, Copy)]
+pub(crate) struct DecodingFailure {
+    block: usize,
+    /// Variable holding the index of the argument that failed to decode
+    field: usize,
+    /// Variable holding the offset in the buffer at which decoding failed
+    offset: usize,
+    /// Whether the report is returned as revert data
+    revert_data: bool,
+}
+
+impl DecodingFailure {
+    /// Create the failure block, if runtime errors are logged
+    pub(super) fn new(
+        opt: &Options,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+    ) -> Option<DecodingFailure> {
+        if !opt.log_runtime_errors {
+            return None;
+        }
+
+        Some(DecodingFailure {
+            block: cfg.new_basic_block("decoding_failed".to_string()),
+            field: vartab.temp_name("failed_field", &Type::Uint(32)),
+            offset: vartab.temp_name("failed_offset", &Type::Uint(32)),
+            revert_data: opt.revert_data,
+        })
+    }
+
+    /// Insert the reporting code into the failure block. This must be called once all the
+    /// validation checks have been generated.
+    pub(super) fn insert(
+        &self,
+        buffer_size_var: usize,
+        ns: &Namespace,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+    ) {
+        let current = cfg.current_block();
+        cfg.set_basic_block(self.block);
+        cfg.set_phis(self.block, BTreeSet::from([self.field, self.offset]));
+
+        let variable = |var_no| Expression::Variable {
+            loc: Loc::Codegen,
+            ty: Type::Uint(32),
+            var_no,
+        };
+
+        let reason = vartab.temp_name("decoding_error", &Type::String);
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Loc::Codegen,
+                res: reason,
+                expr: Expression::FormatString {
+                    loc: Loc::Codegen,
+                    args: vec![
+                        string_arg("abi decoding failed at field "),
+                        (FormatArg::Default, variable(self.field)),
+                        string_arg(" offset "),
+                        (FormatArg::Default, variable(self.offset)),
+                        string_arg(" buffer length "),
+                        (FormatArg::Default, variable(buffer_size_var)),
+                    ],
+                },
+            },
+        );
+        let reason = Expression::Variable {
+            loc: Loc::Codegen,
+            ty: Type::String,
+            var_no: reason,
+        };
+
+        cfg.add(
+            vartab,
+            Instr::Print {
+                expr: Expression::FormatString {
+                    loc: Loc::Codegen,
+                    args: vec![
+                        string_arg("runtime_error: "),
+                        (FormatArg::Default, reason.clone()),
+                        string_arg(",\n"),
+                    ],
+                },
+            },
+        );
+
+        let error = if self.revert_data {
+            SolidityError::String(reason)
+        } else {
+            SolidityError::Empty
+        };
+        assert_failure(&Loc::Codegen, error, ns, cfg, vartab);
+
+        cfg.set_basic_block(current);
+    }
+}
+
+fn string_arg(string: &str) -> (FormatArg, Expression) {
+    (
+        FormatArg::StringLiteral,
+        Expression::BytesLiteral {
+            loc: Loc::Codegen,
+            ty: Type::Bytes(string.len() as u8),
+            value: string.as_bytes().to_vec(),
+        },
+    )
+}
@@ -0,0 +1,511 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::vartable::Vartable;
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, RetrieveType, Type, Type::Uint};
+use num_bigint::{BigInt, Sign};
+use solang_parser::pt::{Loc, Loc::Codegen};
+
+use super::buffer_validator::BufferValidator;
+use super::{alloc_buffer, allocate_array, array_outer_length};
+
+/// In the Ethereum ABI, every value is padded to a 32 byte word
+const WORD_SIZE: usize = 32;
+
+/// Encode `args` following the Ethereum ABI. The head has one word for each argument. The contents
+/// of `bytes` and `string` go into the tail, and their head word holds the offset of the contents.
+/// If there is a `selector`, it is written before the arguments without padding, and the offsets
+/// are relative to the arguments after it. Returns the encoded buffer and its size.
+pub(super) fn encode_args(
+    loc: &Loc,
+    selector: Option<&Expression>,
+    args: &[Expression],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    let base = if selector.is_some() {
+        ns.target.selector_length() as usize
+    } else {
+        0
+    };
+
+    let lengths = args
+        .iter()
+        .map(|arg| is_dynamic(&arg.ty(), ns).then(|| array_outer_length(arg, vartab, cfg)))
+        .collect::<Vec<Option<Expression>>>();
+
+    // each dynamic argument needs a word for its length, and its contents padded to whole words
+    let size = lengths.iter().flatten().fold(
+        number_literal(base + WORD_SIZE * args.len()),
+        |size, length| {
+            size.add_u32(number_literal(WORD_SIZE))
+                .add_u32(padded(length.clone()))
+        },
+    );
+
+    let buffer = alloc_buffer(loc, &size, vartab, cfg);
+
+    if let Some(selector) = selector {
+        write_buffer(selector.clone(), &buffer, number_literal(0), vartab, cfg);
+    }
+
+    let tail_var = vartab.temp_anonymous(&Uint(32));
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: tail_var,
+            expr: number_literal(WORD_SIZE * args.len()),
+        },
+    );
+    let tail = u32_variable(tail_var);
+
+    for (arg_no, (arg, length)) in args.iter().zip(lengths).enumerate() {
+        let head = number_literal(base + WORD_SIZE * arg_no);
+
+        let Some(length) = length else {
+            encode_value(arg, &buffer, head, ns, vartab, cfg);
+            continue;
+        };
+
+        write_word(&tail, &buffer, head, ns, vartab, cfg);
+
+        let length_offset = tail.clone().add_u32(number_literal(base));
+        write_word(&length, &buffer, length_offset.clone(), ns, vartab, cfg);
+
+        cfg.add(
+            vartab,
+            Instr::MemCopy {
+                source: arg.clone(),
+                destination: Expression::AdvancePointer {
+                    pointer: buffer.clone().into(),
+                    bytes_offset: length_offset.add_u32(number_literal(WORD_SIZE)).into(),
+                },
+                bytes: length.clone(),
+            },
+        );
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: tail_var,
+                expr: tail
+                    .clone()
+                    .add_u32(number_literal(WORD_SIZE))
+                    .add_u32(padded(length)),
+            },
+        );
+    }
+
+    (buffer, size)
+}
+
+/// Encode `args` like `abi.encodePacked()` does on Ethereum. Values are not padded to words,
+/// integers are big endian, and the contents of `bytes` and `string` have no length.
+pub(super) fn encode_packed(
+    loc: &Loc,
+    args: &[Expression],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    let sizes = args
+        .iter()
+        .map(|arg| {
+            if is_dynamic(&arg.ty(), ns) {
+                array_outer_length(arg, vartab, cfg)
+            } else {
+                number_literal(arg.ty().bytes(ns) as usize)
+            }
+        })
+        .collect::<Vec<Expression>>();
+
+    let size = sizes.iter().fold(number_literal(0), |size, arg_size| {
+        size.add_u32(arg_size.clone())
+    });
+
+    let buffer = alloc_buffer(loc, &size, vartab, cfg);
+
+    let mut offset = number_literal(0);
+
+    for (arg, arg_size) in args.iter().zip(sizes) {
+        match arg.ty().unwrap_user_type(ns) {
+            Type::DynamicBytes | Type::String => cfg.add(
+                vartab,
+                Instr::MemCopy {
+                    source: arg.clone(),
+                    destination: Expression::AdvancePointer {
+                        pointer: buffer.clone().into(),
+                        bytes_offset: offset.clone().into(),
+                    },
+                    bytes: arg_size.clone(),
+                },
+            ),
+            Type::Int(_) | Type::Uint(_) | Type::Value => {
+                let value = Expression::Cast {
+                    loc: Codegen,
+                    ty: Type::Bytes(arg.ty().bytes(ns)),
+                    expr: arg.clone().into(),
+                };
+                write_buffer(value, &buffer, offset.clone(), vartab, cfg);
+            }
+            _ => write_buffer(arg.clone(), &buffer, offset.clone(), vartab, cfg),
+        }
+
+        offset = offset.add_u32(arg_size);
+    }
+
+    (buffer, size)
+}
+
+/// Encode the `selector` followed by `args` at compile time, if all the arguments are literals
+pub(super) fn const_encode(selector: &Expression, args: &[Expression]) -> Option<Vec<u8>> {
+    let mut result = match selector {
+        Expression::NumberLiteral {
+            ty: Type::Bytes(length),
+            value,
+            ..
+        } => const_bytes(value, *length as usize)?,
+        _ => return None,
+    };
+
+    let mut tail = Vec::new();
+
+    for arg in args {
+        match arg {
+            Expression::NumberLiteral {
+                ty: Type::Bytes(length),
+                value,
+                ..
+            } => {
+                let mut word = const_bytes(value, *length as usize)?;
+                word.resize(WORD_SIZE, 0);
+                result.extend(word);
+            }
+            Expression::NumberLiteral {
+                ty: Type::Int(_) | Type::Uint(_),
+                value,
+                ..
+            } => result.extend(const_word(value)?),
+            Expression::BoolLiteral { value, .. } => {
+                result.extend(const_word(&BigInt::from(*value as u8))?)
+            }
+            Expression::AllocDynamicBytes {
+                ty: Type::String | Type::DynamicBytes,
+                initializer: Some(data),
+                ..
+            } => {
+                let offset = WORD_SIZE * args.len() + tail.len();
+                result.extend(const_word(&offset.into())?);
+                tail.extend(const_word(&data.len().into())?);
+                tail.extend(data);
+                tail.resize(
+                    tail.len() + (WORD_SIZE - tail.len() % WORD_SIZE) % WORD_SIZE,
+                    0,
+                );
+            }
+            _ => return None,
+        }
+    }
+
+    result.extend(tail);
+
+    Some(result)
+}
+
+/// Decode values of the given `types` from an Ethereum ABI encoded `buffer`. Every offset read
+/// from the buffer is checked against the buffer length.
+pub(super) fn decode_args(
+    buffer: &Expression,
+    types: &[Type],
+    validator: &mut BufferValidator,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Vec<Expression> {
+    validator.validate_offset(number_literal(WORD_SIZE * types.len()), ns, vartab, cfg);
+
+    types
+        .iter()
+        .enumerate()
+        .map(|(arg_no, ty)| {
+            validator.set_argument_number(arg_no);
+            let head = number_literal(WORD_SIZE * arg_no);
+            let ty = ty.clone().unwrap_user_type(ns);
+
+            if !is_dynamic(&ty, ns) {
+                return decode_value(&ty, buffer, head, ns, vartab, cfg);
+            }
+
+            let offset = u32_variable(read_u32(buffer, head, vartab, cfg));
+            let contents = offset.clone().add_u32(number_literal(WORD_SIZE));
+            validator.validate_offset(contents.clone(), ns, vartab, cfg);
+
+            let length_var = read_u32(buffer, offset, vartab, cfg);
+            let length = u32_variable(length_var);
+            validator.validate_offset(contents.clone().add_u32(length.clone()), ns, vartab, cfg);
+
+            let array = allocate_array(&ty, length_var, vartab, cfg);
+            cfg.add(
+                vartab,
+                Instr::MemCopy {
+                    source: Expression::AdvancePointer {
+                        pointer: buffer.clone().into(),
+                        bytes_offset: contents.into(),
+                    },
+                    destination: Expression::Variable {
+                        loc: Codegen,
+                        ty: ty.clone(),
+                        var_no: array,
+                    },
+                    bytes: length,
+                },
+            );
+
+            Expression::Variable {
+                loc: Codegen,
+                ty,
+                var_no: array,
+            }
+        })
+        .collect()
+}
+
+/// Only `bytes` and `string` are supported as dynamic types
+fn is_dynamic(ty: &Type, ns: &Namespace) -> bool {
+    matches!(
+        ty.clone().unwrap_user_type(ns),
+        Type::DynamicBytes | Type::String
+    )
+}
+
+/// Write a value type into the word at `offset`. The buffer is zeroed on allocation, so
+/// only the bytes of the value itself need writing.
+fn encode_value(
+    expr: &Expression,
+    buffer: &Expression,
+    offset: Expression,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    match expr.ty().unwrap_user_type(ns) {
+        // fixed length byte arrays are left aligned
+        Type::Bytes(_) => write_buffer(expr.clone(), buffer, offset, vartab, cfg),
+        Type::Bool => {
+            let offset = offset.add_u32(number_literal(WORD_SIZE - 1));
+            write_buffer(expr.clone(), buffer, offset, vartab, cfg);
+        }
+        Type::Address(_) | Type::Contract(_) => {
+            let offset = offset.add_u32(number_literal(WORD_SIZE - ns.address_length));
+            write_buffer(expr.clone(), buffer, offset, vartab, cfg);
+        }
+        _ => write_word(expr, buffer, offset, ns, vartab, cfg),
+    }
+}
+
+/// Read a value type from the word at `offset`
+fn decode_value(
+    ty: &Type,
+    buffer: &Expression,
+    offset: Expression,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    let value = match ty {
+        Type::Bytes(_) => read_buffer(ty, buffer, offset),
+        Type::Bool => read_buffer(ty, buffer, offset.add_u32(number_literal(WORD_SIZE - 1))),
+        Type::Address(_) | Type::Contract(_) => {
+            let offset = offset.add_u32(number_literal(WORD_SIZE - ns.address_length));
+            read_buffer(ty, buffer, offset)
+        }
+        _ => {
+            let word_ty = if ty.is_signed_int(ns) {
+                Type::Int(256)
+            } else {
+                Type::Uint(256)
+            };
+            let word = Expression::Cast {
+                loc: Codegen,
+                ty: word_ty,
+                expr: read_buffer(&Type::Bytes(32), buffer, offset).into(),
+            };
+
+            if ty.bits(ns) == 256 {
+                word
+            } else {
+                Expression::Trunc {
+                    loc: Codegen,
+                    ty: ty.clone(),
+                    expr: word.into(),
+                }
+            }
+        }
+    };
+
+    let read_var = vartab.temp_anonymous(ty);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: read_var,
+            expr: value,
+        },
+    );
+
+    Expression::Variable {
+        loc: Codegen,
+        ty: ty.clone(),
+        var_no: read_var,
+    }
+}
+
+/// Write an integer as a 256 bit big endian word. Byte arrays are written big endian, so the
+/// extended integer is written as `bytes32`.
+fn write_word(
+    expr: &Expression,
+    buffer: &Expression,
+    offset: Expression,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    let word = match expr.ty().unwrap_user_type(ns) {
+        Type::Int(256) | Type::Uint(256) => expr.clone(),
+        Type::Int(_) => Expression::SignExt {
+            loc: Codegen,
+            ty: Type::Int(256),
+            expr: expr.clone().into(),
+        },
+        _ => Expression::ZeroExt {
+            loc: Codegen,
+            ty: Type::Uint(256),
+            expr: expr.clone().into(),
+        },
+    };
+
+    let word = Expression::Cast {
+        loc: Codegen,
+        ty: Type::Bytes(32),
+        expr: word.into(),
+    };
+
+    write_buffer(word, buffer, offset, vartab, cfg);
+}
+
+/// Read the last four bytes of the word at `offset` as a `uint32`, which is enough for
+/// offsets and lengths within the buffer. Returns the variable number holding the value.
+fn read_u32(
+    buffer: &Expression,
+    offset: Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> usize {
+    let offset = offset.add_u32(number_literal(WORD_SIZE - 4));
+    let value = Expression::Cast {
+        loc: Codegen,
+        ty: Uint(32),
+        expr: read_buffer(&Type::Bytes(4), buffer, offset).into(),
+    };
+
+    let read_var = vartab.temp_anonymous(&Uint(32));
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: read_var,
+            expr: value,
+        },
+    );
+
+    read_var
+}
+
+fn write_buffer(
+    value: Expression,
+    buffer: &Expression,
+    offset: Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    cfg.add(
+        vartab,
+        Instr::WriteBuffer {
+            buf: buffer.clone(),
+            offset,
+            value,
+        },
+    );
+}
+
+fn read_buffer(ty: &Type, buffer: &Expression, offset: Expression) -> Expression {
+    Expression::Builtin {
+        loc: Codegen,
+        tys: vec![ty.clone()],
+        kind: Builtin::ReadFromBuffer,
+        args: vec![buffer.clone(), offset],
+    }
+}
+
+/// A constant integer as a big endian word. Negative values are sign extended.
+fn const_word(value: &BigInt) -> Option<Vec<u8>> {
+    let bytes = value.to_signed_bytes_be();
+
+    if bytes.len() > WORD_SIZE {
+        return None;
+    }
+
+    let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+    let mut word = vec![fill; WORD_SIZE - bytes.len()];
+    word.extend(bytes);
+
+    Some(word)
+}
+
+/// The big endian bytes of a constant `bytesN` value
+fn const_bytes(value: &BigInt, length: usize) -> Option<Vec<u8>> {
+    let (_, bytes) = value.to_bytes_be();
+
+    if bytes.len() > length {
+        return None;
+    }
+
+    let mut result = vec![0; length - bytes.len()];
+    result.extend(bytes);
+
+    Some(result)
+}
+
+/// Round `length` up to a whole number of words
+fn padded(length: Expression) -> Expression {
+    Expression::BitwiseAnd {
+        loc: Codegen,
+        ty: Uint(32),
+        left: length.add_u32(number_literal(WORD_SIZE - 1)).into(),
+        right: Expression::NumberLiteral {
+            loc: Codegen,
+            ty: Uint(32),
+            value: BigInt::from(!(WORD_SIZE as u32 - 1)),
+        }
+        .into(),
+    }
+}
+
+fn number_literal(value: usize) -> Expression {
+    Expression::NumberLiteral {
+        loc: Codegen,
+        ty: Uint(32),
+        value: value.into(),
+    }
+}
+
+fn u32_variable(var_no: usize) -> Expression {
+    Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no,
+    }
+}
@@ -4,12 +4,16 @@
 /// The module is organized as follows:
 ///
 /// - `fn abi_encode()` and `fn abi_decode()` are entry points for wherever there is
-///   something to be encoded or decoded.
+///   something to be encoded or decoded. `fn abi_encode_with()` and `fn abi_decode_with()`
+///   do the same with an `Encoding` other than the native one of the target.
+///   `fn abi_encode_payload()` encodes the payload of an external call.
 /// - `AbiEncoding` defines the encoding and decoding API and must be implemented by all schemes.
 /// - There are some helper functions to work with more complex types.
 ///   Any such helper function should work fine regardless of the encoding scheme being used.
 mod borsh_encoding;
 mod buffer_validator;
+mod eth_encoding;
+mod scale_annotations;
 pub(super) mod scale_encoding;
 
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
@@ -17,16 +21,43 @@ use crate::codegen::encoding::borsh_encoding::BorshEncoding;
 use crate::codegen::encoding::scale_encoding::ScaleEncoding;
 use crate::codegen::expression::load_storage;
 use crate::codegen::vartable::Vartable;
-use crate::codegen::{Builtin, Expression};
-use crate::sema::ast::{ArrayLength, Namespace, RetrieveType, StructType, Type, Type::Uint};
+use crate::codegen::{Builtin, Expression, Options};
+use crate::sema::ast::{
+    ArrayLength, Namespace, RetrieveType, ScaleAnnotation, StructType, Type, Type::Uint,
+};
 use crate::Target;
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_integer::Integer;
-use num_traits::{One, Zero};
+use num_traits::{One, ToPrimitive, Zero};
 use solang_parser::pt::{Loc, Loc::Codegen};
 use std::ops::{AddAssign, MulAssign, Sub};
 
-use self::buffer_validator::BufferValidator;
+use self::buffer_validator::{BufferValidator, DecodingFailure};
+
+/// The encoding schemes which can be used for encoding and decoding
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Scale,
+    Borsh,
+    /// The Ethereum ABI, which is available on every target with `abi.ethEncode()` and
+    /// `abi.ethDecode()`
+    Ethereum,
+}
+
+impl Encoding {
+    /// The encoding the target uses for function arguments, return values and `abi.encode()`
+    pub(crate) fn native(target: &Target) -> Self {
+        match target {
+            Target::Solana => Encoding::Borsh,
+            Target::Stylus => Encoding::Ethereum,
+            // All other targets are using the SCALE encoding, because we have tests for a
+            // fake Ethereum target that checks the presence of Instr::AbiDecode and
+            // Expression::AbiEncode.
+            // If a new target is added, this piece of code needs to change.
+            _ => Encoding::Scale,
+        }
+    }
+}
 
 /// Insert encoding instructions into the `cfg` for any `Expression` in `args`.
 /// Returns a pointer to the encoded data and the size as a 32bit integer.
@@ -38,45 +69,250 @@ pub(super) fn abi_encode(
     cfg: &mut ControlFlowGraph,
     packed: bool,
 ) -> (Expression, Expression) {
-    let mut encoder = create_encoder(ns, packed);
+    let encoding = Encoding::native(&ns.target);
+    abi_encode_with(encoding, loc, args, ns, vartab, cfg, packed)
+}
+
+/// Insert encoding instructions into the `cfg` for `args`, using the given encoding.
+pub(super) fn abi_encode_with(
+    encoding: Encoding,
+    loc: &Loc,
+    args: Vec<Expression>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+    packed: bool,
+) -> (Expression, Expression) {
+    if encoding == Encoding::Ethereum {
+        return if packed {
+            eth_encoding::encode_packed(loc, &args, ns, vartab, cfg)
+        } else {
+            eth_encoding::encode_args(loc, None, &args, ns, vartab, cfg)
+        };
+    }
+
+    if packed {
+        if let Some(bytes) = const_encode_packed(&args) {
+            let size = Expression::NumberLiteral {
+                loc: *loc,
+                ty: Uint(32),
+                value: bytes.len().into(),
+            };
+            let encoded_bytes = vartab.temp_name("abi_encoded", &Type::DynamicBytes);
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res: encoded_bytes,
+                    expr: Expression::AllocDynamicBytes {
+                        loc: *loc,
+                        ty: Type::DynamicBytes,
+                        size: size.clone().into(),
+                        initializer: Some(bytes),
+                    },
+                },
+            );
+            let buffer = Expression::Variable {
+                loc: *loc,
+                ty: Type::DynamicBytes,
+                var_no: encoded_bytes,
+            };
+            return (buffer, size);
+        }
+    }
+
+    let mut encoder = create_encoder_with(encoding, packed);
     let size = calculate_size_args(&mut encoder, &args, ns, vartab, cfg);
-    let encoded_bytes = vartab.temp_name("abi_encoded", &Type::DynamicBytes);
-    let expr = Expression::AllocDynamicBytes {
-        loc: *loc,
-        ty: Type::DynamicBytes,
-        size: size.clone().into(),
-        initializer: None,
+    let buffer = alloc_buffer(loc, &size, vartab, cfg);
+    encode_into(&mut encoder, &args, &buffer, ns, vartab, cfg);
+
+    (buffer, size)
+}
+
+/// Encode `args` after the `selector`, like the data of a revert or the result of
+/// `abi.encodeWithSelector()`. The Ethereum ABI does not pad the selector to a word.
+pub(super) fn abi_encode_after_selector(
+    loc: &Loc,
+    selector: Expression,
+    mut args: Vec<Expression>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    if Encoding::native(&ns.target) == Encoding::Ethereum {
+        return eth_encoding::encode_args(loc, Some(&selector), &args, ns, vartab, cfg);
+    }
+
+    args.insert(0, selector);
+    abi_encode(loc, args, ns, vartab, cfg, false)
+}
+
+/// Encode the `selector` followed by `args` at compile time. Returns `None` if the arguments are
+/// not constant, or cannot be encoded at compile time.
+pub(crate) fn const_encode_with_selector(
+    selector: Expression,
+    args: &[Expression],
+    ns: &Namespace,
+) -> Option<Vec<u8>> {
+    if Encoding::native(&ns.target) == Encoding::Ethereum {
+        return eth_encoding::const_encode(&selector, args);
+    }
+
+    let mut encoder_args = Vec::with_capacity(args.len() + 1);
+    encoder_args.push(selector);
+    encoder_args.extend_from_slice(args);
+    create_encoder(ns, false).const_encode(&encoder_args)
+}
+
+/// Encode the payload of an external call or contract creation. The payload is not used after
+/// the call, so with Borsh a payload of a size known at compile time is written into a scratch
+/// buffer. All payloads of the same size in a function share the scratch buffer, which saves an
+/// allocation for each call.
+pub(super) fn abi_encode_payload(
+    loc: &Loc,
+    args: Vec<Expression>,
+    annotations: &[Option<ScaleAnnotation>],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    match Encoding::native(&ns.target) {
+        Encoding::Borsh => (),
+        // the first argument is the selector, which is not padded to a word
+        Encoding::Ethereum => {
+            let (selector, args) = args.split_first().unwrap();
+            return eth_encoding::encode_args(loc, Some(selector), args, ns, vartab, cfg);
+        }
+        _ => return abi_encode_annotated(loc, args, annotations, ns, vartab, cfg),
+    }
+
+    let mut encoder = create_encoder_with(Encoding::Borsh, false);
+    let size = calculate_size_args(&mut encoder, &args, ns, vartab, cfg);
+    let buffer = match &size {
+        Expression::NumberLiteral { value, .. } => scratch_buffer(loc, value, vartab, cfg),
+        _ => alloc_buffer(loc, &size, vartab, cfg),
     };
+    encode_into(&mut encoder, &args, &buffer, ns, vartab, cfg);
+
+    (buffer, size)
+}
+
+/// Allocate a buffer for encoded data of the given size
+fn alloc_buffer(
+    loc: &Loc,
+    size: &Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    let encoded_bytes = vartab.temp_name("abi_encoded", &Type::DynamicBytes);
     cfg.add(
         vartab,
         Instr::Set {
             loc: *loc,
             res: encoded_bytes,
-            expr,
+            expr: Expression::AllocDynamicBytes {
+                loc: *loc,
+                ty: Type::DynamicBytes,
+                size: size.clone().into(),
+                initializer: None,
+            },
         },
     );
 
-    let mut offset = Expression::NumberLiteral {
+    Expression::Variable {
         loc: *loc,
-        ty: Uint(32),
-        value: BigInt::zero(),
+        ty: Type::DynamicBytes,
+        var_no: encoded_bytes,
+    }
+}
+
+/// Return the scratch buffer of the given size, which is allocated in the entry block of the
+/// function the first time it is needed. Encoding a value of a static size writes every byte,
+/// so nothing of the previous contents remains.
+fn scratch_buffer(
+    loc: &Loc,
+    size: &BigInt,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    let size = size.to_usize().unwrap();
+
+    let var_no = match cfg.scratch_buffers.get(&size) {
+        Some(var_no) => *var_no,
+        None => {
+            let var_no = vartab.temp_name("scratch_buffer", &Type::DynamicBytes);
+            // not added with cfg.add(), as the buffer itself never changes after the entry block
+            cfg.blocks[0].instr.insert(
+                0,
+                Instr::Set {
+                    loc: *loc,
+                    res: var_no,
+                    expr: Expression::AllocDynamicBytes {
+                        loc: *loc,
+                        ty: Type::DynamicBytes,
+                        size: Expression::NumberLiteral {
+                            loc: *loc,
+                            ty: Uint(32),
+                            value: size.into(),
+                        }
+                        .into(),
+                        initializer: None,
+                    },
+                },
+            );
+            cfg.scratch_buffers.insert(size, var_no);
+            var_no
+        }
     };
-    let buffer = Expression::Variable {
+
+    Expression::Variable {
         loc: *loc,
         ty: Type::DynamicBytes,
-        var_no: encoded_bytes,
+        var_no,
+    }
+}
+
+/// Encode `args` one after the other into `buffer`
+fn encode_into(
+    encoder: &mut Box<dyn AbiEncoding>,
+    args: &[Expression],
+    buffer: &Expression,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    let mut offset = Expression::NumberLiteral {
+        loc: Codegen,
+        ty: Uint(32),
+        value: BigInt::zero(),
     };
     for (arg_no, item) in args.iter().enumerate() {
-        let advance = encoder.encode(item, &buffer, &offset, arg_no, ns, vartab, cfg);
+        let advance = encoder.encode(item, buffer, &offset, arg_no, ns, vartab, cfg);
         offset = Expression::Add {
-            loc: *loc,
+            loc: Codegen,
             ty: Uint(32),
             overflowing: false,
             left: offset.into(),
             right: advance.into(),
         };
     }
-    (buffer, size)
+}
+
+/// Encode the arguments or return values of a public function. On Polkadot, parameters can have
+/// annotations which change their SCALE encoding, like `@compact`.
+pub(super) fn abi_encode_annotated(
+    loc: &Loc,
+    args: Vec<Expression>,
+    annotations: &[Option<ScaleAnnotation>],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    if !ns.target.is_polkadot() || annotations.iter().all(Option::is_none) {
+        return abi_encode(loc, args, ns, vartab, cfg, false);
+    }
+
+    scale_annotations::encode_args(loc, &args, annotations, ns, vartab, cfg)
 }
 
 /// Insert decoding routines into the `cfg` for the `Expression`s in `args`.
@@ -89,35 +325,101 @@ pub(super) fn abi_decode(
     vartab: &mut Vartable,
     cfg: &mut ControlFlowGraph,
     buffer_size_expr: Option<Expression>,
+    opt: &Options,
 ) -> Vec<Expression> {
-    let buffer_size = vartab.temp_anonymous(&Uint(32));
-    if let Some(length_expression) = buffer_size_expr {
-        cfg.add(
-            vartab,
-            Instr::Set {
-                loc: Codegen,
-                res: buffer_size,
-                expr: length_expression,
-            },
-        );
+    let encoding = Encoding::native(&ns.target);
+    abi_decode_with(
+        encoding,
+        loc,
+        buffer,
+        types,
+        ns,
+        vartab,
+        cfg,
+        buffer_size_expr,
+        opt,
+    )
+}
+
+/// Decode the arguments or return values of a public function. On Polkadot, parameters can have
+/// annotations which change their SCALE encoding, like `@compact`.
+pub(super) fn abi_decode_annotated(
+    loc: &Loc,
+    buffer: &Expression,
+    types: &[Type],
+    annotations: &[Option<ScaleAnnotation>],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+    buffer_size_expr: Option<Expression>,
+    opt: &Options,
+) -> Vec<Expression> {
+    if !ns.target.is_polkadot() || annotations.iter().all(Option::is_none) {
+        return abi_decode(loc, buffer, types, ns, vartab, cfg, buffer_size_expr, opt);
+    }
+
+    let buffer_size = buffer_size_var(buffer, buffer_size_expr, vartab, cfg);
+    scale_annotations::decode_args(
+        buffer,
+        types,
+        annotations,
+        buffer_size,
+        ns,
+        vartab,
+        cfg,
+        opt,
+    )
+}
+
+/// Insert decoding routines into the `cfg` for the given `types`, using the given encoding.
+pub(super) fn abi_decode_with(
+    encoding: Encoding,
+    loc: &Loc,
+    buffer: &Expression,
+    types: &[Type],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+    buffer_size_expr: Option<Expression>,
+    opt: &Options,
+) -> Vec<Expression> {
+    let buffer_size = buffer_size_var(buffer, buffer_size_expr, vartab, cfg);
+    let failure = DecodingFailure::new(opt, vartab, cfg);
+    let mut validator = BufferValidator::new(buffer_size, types, failure, opt);
+
+    let read_items = if encoding == Encoding::Ethereum {
+        eth_encoding::decode_args(buffer, types, &mut validator, ns, vartab, cfg)
     } else {
-        cfg.add(
+        decode_args(
+            loc,
+            buffer,
+            types,
+            &mut validator,
+            encoding,
+            ns,
             vartab,
-            Instr::Set {
-                loc: Codegen,
-                res: buffer_size,
-                expr: Expression::Builtin {
-                    loc: Codegen,
-                    tys: vec![Uint(32)],
-                    kind: Builtin::ArrayLength,
-                    args: vec![buffer.clone()],
-                },
-            },
-        );
+            cfg,
+        )
+    };
+
+    if let Some(failure) = failure {
+        failure.insert(buffer_size, ns, vartab, cfg);
     }
 
-    let mut validator = BufferValidator::new(buffer_size, types);
+    read_items
+}
 
+/// Decode the `types` from the buffer, one after another
+fn decode_args(
+    loc: &Loc,
+    buffer: &Expression,
+    types: &[Type],
+    validator: &mut BufferValidator,
+    encoding: Encoding,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Vec<Expression> {
     let mut read_items: Vec<Expression> = vec![Expression::Poison; types.len()];
     let mut offset = Expression::NumberLiteral {
         loc: *loc,
@@ -126,13 +428,13 @@ pub(super) fn abi_decode(
     };
 
     validator.initialize_validation(&offset, ns, vartab, cfg);
-    let encoder = create_encoder(ns, false);
+    let encoder = create_encoder_with(encoding, false);
 
     for (item_no, item) in types.iter().enumerate() {
         validator.set_argument_number(item_no);
         validator.validate_buffer(&offset, ns, vartab, cfg);
         let (read_item, advance) =
-            encoder.read_from_buffer(buffer, &offset, item, &mut validator, ns, vartab, cfg);
+            encoder.read_from_buffer(buffer, &offset, item, validator, ns, vartab, cfg);
         read_items[item_no] = read_item;
         offset = Expression::Add {
             loc: *loc,
@@ -148,7 +450,83 @@ pub(super) fn abi_decode(
     read_items
 }
 
-/// Calculate the size of a set of arguments to encoding functions
+/// Store the size of the buffer to decode in a new variable, and return the variable number. If
+/// no size is given, it is the length of the buffer.
+fn buffer_size_var(
+    buffer: &Expression,
+    buffer_size_expr: Option<Expression>,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> usize {
+    let buffer_size = vartab.temp_anonymous(&Uint(32));
+    let expr = buffer_size_expr.unwrap_or_else(|| Expression::Builtin {
+        loc: Codegen,
+        tys: vec![Uint(32)],
+        kind: Builtin::ArrayLength,
+        args: vec![buffer.clone()],
+    });
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: buffer_size,
+            expr,
+        },
+    );
+
+    buffer_size
+}
+
+/// Packed encode `args` at compile time, if they are all constants. This is the same for SCALE
+/// and Borsh: integers are little endian, and strings and byte arrays have no length prefix.
+fn const_encode_packed(args: &[Expression]) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+
+    for arg in args {
+        match arg {
+            Expression::NumberLiteral {
+                ty: Type::Uint(width) | Type::Int(width),
+                value,
+                ..
+            } => {
+                let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+                let mut bytes = value.to_signed_bytes_le();
+                bytes.resize(width.next_power_of_two() as usize / 8, fill);
+                result.extend_from_slice(&bytes);
+            }
+            Expression::NumberLiteral {
+                ty: Type::Bytes(length),
+                value,
+                ..
+            } => {
+                let bytes = value.to_bytes_be().1;
+                let length = *length as usize;
+                if bytes.len() > length {
+                    return None;
+                }
+                result.resize(result.len() + length - bytes.len(), 0);
+                result.extend_from_slice(&bytes);
+            }
+            Expression::BytesLiteral {
+                ty: Type::Bytes(_),
+                value,
+                ..
+            }
+            | Expression::AllocDynamicBytes {
+                ty: Type::String | Type::DynamicBytes,
+                initializer: Some(value),
+                ..
+            } => result.extend_from_slice(value),
+            Expression::BoolLiteral { value, .. } => result.push(*value as u8),
+            _ => return None,
+        }
+    }
+
+    Some(result)
+}
+
+/// Calculate the size of a set of arguments to encoding functions. The sizes known at compile
+/// time are added up into a single constant.
 fn calculate_size_args(
     encoder: &mut Box<dyn AbiEncoding>,
     args: &[Expression],
@@ -156,18 +534,35 @@ fn calculate_size_args(
     vartab: &mut Vartable,
     cfg: &mut ControlFlowGraph,
 ) -> Expression {
-    let mut size = encoder.get_expr_size(0, &args[0], ns, vartab, cfg);
-    for (i, item) in args.iter().enumerate().skip(1) {
-        let additional = encoder.get_expr_size(i, item, ns, vartab, cfg);
-        size = Expression::Add {
+    let mut static_size = BigInt::zero();
+    let mut dynamic_size: Option<Expression> = None;
+
+    for (i, item) in args.iter().enumerate() {
+        match encoder.get_expr_size(i, item, ns, vartab, cfg) {
+            Expression::NumberLiteral { value, .. } => static_size += value,
+            additional => {
+                dynamic_size = Some(match dynamic_size {
+                    Some(size) => size.add_u32(additional),
+                    None => additional,
+                });
+            }
+        }
+    }
+
+    match dynamic_size {
+        Some(size) if static_size.is_zero() => size,
+        Some(size) => Expression::NumberLiteral {
             loc: Codegen,
             ty: Uint(32),
-            overflowing: false,
-            left: size.into(),
-            right: additional.into(),
-        };
+            value: static_size,
+        }
+        .add_u32(size),
+        None => Expression::NumberLiteral {
+            loc: Codegen,
+            ty: Uint(32),
+            value: static_size,
+        },
     }
-    size
 }
 
 /// This trait should be implemented by all encoding methods (ethabi, SCALE and Borsh), so that
@@ -857,6 +1252,10 @@ pub(crate) trait AbiEncoding {
                 buffer, offset, ty, elem_ty, dims, validator, ns, vartab, cfg,
             ),
 
+            Type::Slice(elem_ty) if allow_memcpy(elem_ty, ns) => {
+                self.decode_slice(buffer, offset, ty, elem_ty, validator, ns, vartab, cfg)
+            }
+
             Type::Slice(elem_ty) => {
                 let dims = vec![ArrayLength::Dynamic];
                 self.decode_array(
@@ -898,6 +1297,61 @@ pub(crate) trait AbiEncoding {
         cfg: &mut ControlFlowGraph,
     ) -> (usize, Expression);
 
+    /// Given the buffer and the offset, create a slice which points to the encoded elements
+    /// in the buffer, so nothing needs to be copied. The elements must be laid out in the
+    /// buffer exactly as they would be in memory.
+    /// The function returns an expression containing the slice and the number of bytes read.
+    fn decode_slice(
+        &self,
+        buffer: &Expression,
+        offset: &Expression,
+        slice_ty: &Type,
+        elem_ty: &Type,
+        validator: &mut BufferValidator,
+        ns: &Namespace,
+        vartab: &mut Vartable,
+        cfg: &mut ControlFlowGraph,
+    ) -> (Expression, Expression) {
+        let (array_length, size_width) = self.retrieve_array_length(buffer, offset, vartab, cfg);
+        let array_start = offset.clone().add_u32(size_width.clone());
+        validator.validate_offset(array_start.clone(), ns, vartab, cfg);
+
+        let array_bytes_size = calculate_array_bytes_size(array_length, elem_ty, ns);
+        validator.validate_offset_plus_size(&array_start, &array_bytes_size, ns, vartab, cfg);
+
+        let slice = vartab.temp_anonymous(slice_ty);
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: slice,
+                expr: Expression::Builtin {
+                    loc: Codegen,
+                    tys: vec![slice_ty.clone()],
+                    kind: Builtin::SliceFromBuffer,
+                    args: vec![
+                        buffer.clone(),
+                        array_start,
+                        Expression::Variable {
+                            loc: Codegen,
+                            ty: Uint(32),
+                            var_no: array_length,
+                        },
+                    ],
+                },
+            },
+        );
+
+        (
+            Expression::Variable {
+                loc: Codegen,
+                ty: slice_ty.clone(),
+                var_no: slice,
+            },
+            array_bytes_size.add_u32(size_width),
+        )
+    }
+
     /// Given the buffer and the offset, decode an array.
     /// The function returns an expression containing the array and the number of bytes read.
     fn decode_array(
@@ -1472,7 +1926,11 @@ pub(crate) trait AbiEncoding {
 
         // Check if the array contains only fixed sized elements
         let primitive_size = if elem_ty.is_primitive() && direct_assessment {
-            Some(elem_ty.memory_size_of(ns))
+            Some(match elem_ty {
+                // integers are encoded with a power of two width, see `encode_int()`
+                Type::Int(width) | Type::Uint(width) => (width.next_power_of_two() / 8).into(),
+                _ => elem_ty.memory_size_of(ns),
+            })
         } else if let Type::Struct(struct_ty) = elem_ty {
             if direct_assessment {
                 ns.calculate_struct_non_padded_size(struct_ty)
@@ -1484,6 +1942,20 @@ pub(crate) trait AbiEncoding {
         };
 
         if let Some(compile_type_size) = primitive_size {
+            if dyn_dims == 0 {
+                // the size of a fixed size array is known at compile time
+                let elements = dims
+                    .iter()
+                    .map(|d| d.array_length().unwrap())
+                    .product::<BigInt>();
+
+                return Expression::NumberLiteral {
+                    loc: Codegen,
+                    ty: Uint(32),
+                    value: elements * compile_type_size,
+                };
+            }
+
             // If the array saves primitive-type elements, its size is sizeof(type)*vec.length
             let mut size = if let ArrayLength::Fixed(dim) = &dims.last().unwrap() {
                 Expression::NumberLiteral {
@@ -1515,7 +1987,9 @@ pub(crate) trait AbiEncoding {
                 };
             }
 
-            let size_width = self.size_width(&size, vartab, cfg);
+            let size_width = (!self.is_packed()
+                && matches!(dims.last(), Some(ArrayLength::Dynamic)))
+            .then(|| self.size_width(&size, vartab, cfg));
 
             let type_size = Expression::NumberLiteral {
                 loc: Codegen,
@@ -1543,15 +2017,15 @@ pub(crate) trait AbiEncoding {
                 ty: Uint(32),
                 var_no: size_var,
             };
-            if self.is_packed() || !matches!(&dims.last().unwrap(), ArrayLength::Dynamic) {
-                return size_var;
-            }
-            Expression::Add {
-                loc: Codegen,
-                ty: Uint(32),
-                overflowing: false,
-                left: size_var.into(),
-                right: size_width.into(),
+            match size_width {
+                Some(size_width) => Expression::Add {
+                    loc: Codegen,
+                    ty: Uint(32),
+                    overflowing: false,
+                    left: size_var.into(),
+                    right: size_width.into(),
+                },
+                None => size_var,
             }
         } else {
             let size_var =
@@ -1744,14 +2218,16 @@ pub(crate) trait AbiEncoding {
 
 /// This function should return the correct encoder, given the target
 pub(crate) fn create_encoder(ns: &Namespace, packed: bool) -> Box<dyn AbiEncoding> {
-    match &ns.target {
-        Target::Solana => Box::new(BorshEncoding::new(packed)),
-        // Solana utilizes Borsh encoding and Polkadot, SCALE encoding.
-        // All other targets are using the SCALE encoding, because we have tests for a
-        // fake Ethereum target that checks the presence of Instr::AbiDecode and
-        // Expression::AbiEncode.
-        // If a new target is added, this piece of code needs to change.
-        _ => Box::new(ScaleEncoding::new(packed)),
+    create_encoder_with(Encoding::native(&ns.target), packed)
+}
+
+fn create_encoder_with(encoding: Encoding, packed: bool) -> Box<dyn AbiEncoding> {
+    match encoding {
+        Encoding::Scale => Box::new(ScaleEncoding::new(packed)),
+        Encoding::Borsh => Box::new(BorshEncoding::new(packed)),
+        // The Ethereum ABI places dynamic values in a tail after the head, so it does not fit
+        // the sequential layout of the AbiEncoding trait.
+        Encoding::Ethereum => unreachable!("Ethereum ABI is encoded by eth_encoding"),
     }
 }
 
@@ -2027,7 +2503,7 @@ fn array_outer_length(
 }
 
 /// Check if we can MemCpy a type to/from a buffer
-fn allow_memcpy(ty: &Type, ns: &Namespace) -> bool {
+pub(super) fn allow_memcpy(ty: &Type, ns: &Namespace) -> bool {
     match ty {
         Type::Struct(struct_ty) => {
             if let Some(no_padded_size) = ns.calculate_struct_non_padded_size(struct_ty) {
@@ -2049,6 +2525,8 @@ fn allow_memcpy(ty: &Type, ns: &Namespace) -> bool {
         // If the array is not dynamic, we mempcy if its elements allow it
         Type::Array(t, _) => allow_memcpy(t, ns),
         Type::UserType(t) => allow_memcpy(&ns.user_types[*t].ty, ns),
+        // Integers like int24 are padded in memory, but not in the encoded data
+        Type::Int(width) | Type::Uint(width) => width.is_power_of_two(),
         _ => ty.is_primitive(),
     }
 }
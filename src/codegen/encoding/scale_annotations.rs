@@ -0,0 +1,1026 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::vartable::Vartable;
+use crate::codegen::{Builtin, Expression, Options};
+use crate::sema::ast::{Namespace, RetrieveType, ScaleAnnotation, Type, Type::Uint};
+use num_bigint::BigInt;
+use solang_parser::pt::{Loc, Loc::Codegen};
+
+use super::buffer_validator::{BufferValidator, DecodingFailure};
+use super::create_encoder;
+
+/// Encode the arguments or return values of a public function, following their SCALE annotations.
+/// `@compact` integers use the compact encoding. For `@option` and `@result`, the first value is
+/// the discriminant, and the second value is only encoded when it is present.
+/// Returns the encoded buffer and its size.
+pub(super) fn encode_args(
+    loc: &Loc,
+    args: &[Expression],
+    annotations: &[Option<ScaleAnnotation>],
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    let mut encoder = create_encoder(ns, false);
+
+    let mut sizes = args
+        .iter()
+        .zip(annotations)
+        .enumerate()
+        .map(|(arg_no, (arg, annotation))| {
+            if *annotation == Some(ScaleAnnotation::Compact) {
+                compact_size(arg, vartab, cfg)
+            } else {
+                encoder.get_expr_size(arg_no, arg, ns, vartab, cfg)
+            }
+        })
+        .collect::<Vec<Expression>>();
+
+    let optional = is_optional(annotations);
+
+    if optional {
+        sizes[1] = Expression::Multiply {
+            loc: Codegen,
+            ty: Uint(32),
+            overflowing: false,
+            left: sizes[1].clone().into(),
+            right: Expression::ZeroExt {
+                loc: Codegen,
+                ty: Uint(32),
+                expr: args[0].clone().into(),
+            }
+            .into(),
+        };
+    }
+
+    let size = sizes
+        .iter()
+        .cloned()
+        .reduce(|size, arg_size| size.add_u32(arg_size))
+        .unwrap();
+
+    let encoded_bytes = vartab.temp_name("abi_encoded", &Type::DynamicBytes);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: *loc,
+            res: encoded_bytes,
+            expr: Expression::AllocDynamicBytes {
+                loc: *loc,
+                ty: Type::DynamicBytes,
+                size: size.clone().into(),
+                initializer: None,
+            },
+        },
+    );
+    let buffer = Expression::Variable {
+        loc: *loc,
+        ty: Type::DynamicBytes,
+        var_no: encoded_bytes,
+    };
+
+    let mut offset = number_literal(&Uint(32), 0);
+
+    for (arg_no, (arg, annotation)) in args.iter().zip(annotations).enumerate() {
+        match annotation {
+            Some(ScaleAnnotation::Option) => {
+                encoder.encode(arg, &buffer, &offset, arg_no, ns, vartab, cfg);
+            }
+            Some(ScaleAnnotation::Result) => {
+                // Ok is the first variant, so the discriminant is the inverse of the flag
+                let discriminant = Expression::Not {
+                    loc: Codegen,
+                    expr: arg.clone().into(),
+                };
+                encoder.encode(&discriminant, &buffer, &offset, arg_no, ns, vartab, cfg);
+            }
+            Some(ScaleAnnotation::Compact) if !optional => {
+                encode_compact(arg, &sizes[arg_no], &buffer, &offset, vartab, cfg);
+            }
+            None if !optional => {
+                encoder.encode(arg, &buffer, &offset, arg_no, ns, vartab, cfg);
+            }
+            _ => {
+                // the value of an `@option` or `@result` is only encoded when it is present
+                let present = cfg.new_basic_block("present".into());
+                let done = cfg.new_basic_block("done".into());
+
+                vartab.new_dirty_tracker();
+                cfg.add(
+                    vartab,
+                    Instr::BranchCond {
+                        cond: args[0].clone(),
+                        true_block: present,
+                        false_block: done,
+                    },
+                );
+
+                cfg.set_basic_block(present);
+                if *annotation == Some(ScaleAnnotation::Compact) {
+                    encode_compact(arg, &sizes[arg_no], &buffer, &offset, vartab, cfg);
+                } else {
+                    encoder.encode(arg, &buffer, &offset, arg_no, ns, vartab, cfg);
+                }
+                cfg.add(vartab, Instr::Branch { block: done });
+
+                cfg.set_basic_block(done);
+                cfg.set_phis(done, vartab.pop_dirty_tracker());
+            }
+        }
+
+        offset = offset.add_u32(sizes[arg_no].clone());
+    }
+
+    (buffer, size)
+}
+
+/// Decode values of the given `types` from `buffer`, following their SCALE annotations. When the
+/// value of an `@option` or `@result` is absent, its default value is returned.
+pub(super) fn decode_args(
+    buffer: &Expression,
+    types: &[Type],
+    annotations: &[Option<ScaleAnnotation>],
+    buffer_size: usize,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+    opt: &Options,
+) -> Vec<Expression> {
+    let mut values = Vec::with_capacity(types.len());
+    let mut offset = number_literal(&Uint(32), 0);
+    let failure = DecodingFailure::new(opt, vartab, cfg);
+
+    for (item_no, (ty, annotation)) in types.iter().zip(annotations).enumerate() {
+        let mut validator =
+            BufferValidator::new(buffer_size, std::slice::from_ref(ty), failure, opt)
+                .for_argument(item_no);
+
+        if matches!(
+            annotation,
+            Some(ScaleAnnotation::Option | ScaleAnnotation::Result)
+        ) {
+            let present = decode_discriminant(
+                *annotation == Some(ScaleAnnotation::Option),
+                buffer,
+                &offset,
+                &validator,
+                ns,
+                vartab,
+                cfg,
+            );
+
+            values.push(present);
+            offset = offset.add_u32(number_literal(&Uint(32), 1));
+        } else if item_no == 1 && is_optional(annotations) {
+            let value_var = vartab.temp_anonymous(ty);
+            let offset_var = vartab.temp_anonymous(&Uint(32));
+            let present = cfg.new_basic_block("present".into());
+            let done = cfg.new_basic_block("done".into());
+
+            vartab.new_dirty_tracker();
+
+            let default = ty
+                .clone()
+                .unwrap_user_type(ns)
+                .default(ns)
+                .expect("sema only allows value types with a default value");
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Codegen,
+                    res: value_var,
+                    expr: default,
+                },
+            );
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Codegen,
+                    res: offset_var,
+                    expr: offset.clone(),
+                },
+            );
+            cfg.add(
+                vartab,
+                Instr::BranchCond {
+                    cond: values[0].clone(),
+                    true_block: present,
+                    false_block: done,
+                },
+            );
+
+            cfg.set_basic_block(present);
+            let (value, advance) = decode_value(
+                buffer,
+                &offset,
+                ty,
+                *annotation,
+                &mut validator,
+                ns,
+                vartab,
+                cfg,
+            );
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Codegen,
+                    res: value_var,
+                    expr: value,
+                },
+            );
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Codegen,
+                    res: offset_var,
+                    expr: offset.add_u32(advance),
+                },
+            );
+            cfg.add(vartab, Instr::Branch { block: done });
+
+            cfg.set_basic_block(done);
+            cfg.set_phis(done, vartab.pop_dirty_tracker());
+
+            values.push(Expression::Variable {
+                loc: Codegen,
+                ty: ty.clone(),
+                var_no: value_var,
+            });
+            offset = Expression::Variable {
+                loc: Codegen,
+                ty: Uint(32),
+                var_no: offset_var,
+            };
+        } else {
+            let (value, advance) = decode_value(
+                buffer,
+                &offset,
+                ty,
+                *annotation,
+                &mut validator,
+                ns,
+                vartab,
+                cfg,
+            );
+
+            values.push(value);
+            offset = offset.add_u32(advance);
+        }
+    }
+
+    BufferValidator::new(buffer_size, types, failure, opt)
+        .validate_all_bytes_read(offset, ns, vartab, cfg);
+
+    if let Some(failure) = failure {
+        failure.insert(buffer_size, ns, vartab, cfg);
+    }
+
+    values
+}
+
+/// Is the first value the discriminant of an `@option` or `@result`
+fn is_optional(annotations: &[Option<ScaleAnnotation>]) -> bool {
+    matches!(
+        annotations.first(),
+        Some(Some(ScaleAnnotation::Option | ScaleAnnotation::Result))
+    )
+}
+
+fn decode_value(
+    buffer: &Expression,
+    offset: &Expression,
+    ty: &Type,
+    annotation: Option<ScaleAnnotation>,
+    validator: &mut BufferValidator,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    if annotation == Some(ScaleAnnotation::Compact) {
+        decode_compact(buffer, offset, ty, validator, ns, vartab, cfg)
+    } else {
+        create_encoder(ns, false).read_from_buffer(buffer, offset, ty, validator, ns, vartab, cfg)
+    }
+}
+
+/// Read the discriminant byte of an `Option` or `Result`, and return whether the value is present.
+/// `Some` is the second variant of `Option`, and `Ok` is the first variant of `Result`.
+fn decode_discriminant(
+    is_option: bool,
+    buffer: &Expression,
+    offset: &Expression,
+    validator: &BufferValidator,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    validator.validate_offset(
+        offset.clone().add_u32(number_literal(&Uint(32), 1)),
+        ns,
+        vartab,
+        cfg,
+    );
+
+    let discriminant = vartab.temp_anonymous(&Uint(8));
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: discriminant,
+            expr: read_buffer(&Uint(8), buffer, offset.clone()),
+        },
+    );
+    let discriminant = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(8),
+        var_no: discriminant,
+    };
+
+    fail_if(
+        Expression::More {
+            loc: Codegen,
+            signed: false,
+            left: discriminant.clone().into(),
+            right: number_literal(&Uint(8), 1).into(),
+        },
+        vartab,
+        cfg,
+    );
+
+    let present = vartab.temp_anonymous(&Type::Bool);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: present,
+            expr: Expression::Equal {
+                loc: Codegen,
+                left: discriminant.into(),
+                right: number_literal(&Uint(8), u8::from(is_option)).into(),
+            },
+        },
+    );
+
+    Expression::Variable {
+        loc: Codegen,
+        ty: Type::Bool,
+        var_no: present,
+    }
+}
+
+/// Compact integers are handled with at least 32 bits, so that the three single mode encodings
+/// need no special casing
+fn compact_width(ty: &Type) -> u16 {
+    match ty {
+        Uint(n) => n.next_power_of_two().max(32),
+        _ => unreachable!("only unsigned integers can be compact"),
+    }
+}
+
+/// The single modes of the compact encoding: the encoded size in bytes, and the largest number
+/// of bits the value can have in that mode. Larger values use the big integer mode.
+const COMPACT_MODES: [(u8, u32); 3] = [(1, 6), (2, 14), (4, 30)];
+
+/// Calculate the number of bytes needed to compact encode `expr`
+fn compact_size(
+    expr: &Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    let width = compact_width(&expr.ty());
+    let value = widen(expr, width);
+    let size_var = vartab.temp_name("compact_size", &Uint(32));
+    let done = cfg.new_basic_block("compact_size_done".into());
+
+    vartab.new_dirty_tracker();
+
+    for (size, bits) in COMPACT_MODES {
+        let fits = cfg.new_basic_block(format!("compact_{size}_bytes"));
+        let next = cfg.new_basic_block("compact_larger".into());
+        cfg.add(
+            vartab,
+            Instr::BranchCond {
+                cond: Expression::Less {
+                    loc: Codegen,
+                    signed: false,
+                    left: value.clone().into(),
+                    right: number_literal(&Uint(width), 1u64 << bits).into(),
+                },
+                true_block: fits,
+                false_block: next,
+            },
+        );
+
+        cfg.set_basic_block(fits);
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: size_var,
+                expr: number_literal(&Uint(32), size),
+            },
+        );
+        cfg.add(vartab, Instr::Branch { block: done });
+
+        cfg.set_basic_block(next);
+    }
+
+    // In the big integer mode, a length byte is followed by the significant bytes of the value
+    let bytes = significant_bytes(&value, width, vartab, cfg);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: size_var,
+            expr: bytes.add_u32(number_literal(&Uint(32), 1)),
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: done });
+
+    cfg.set_basic_block(done);
+    cfg.set_phis(done, vartab.pop_dirty_tracker());
+
+    Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: size_var,
+    }
+}
+
+/// The number of bytes needed for `value` in the big integer mode, which is at least four
+fn significant_bytes(
+    value: &Expression,
+    width: u16,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> Expression {
+    if width == 32 {
+        return number_literal(&Uint(32), 4);
+    }
+
+    let bytes_var = vartab.temp_name("compact_bytes", &Uint(32));
+    let bytes = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: bytes_var,
+    };
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: bytes_var,
+            expr: number_literal(&Uint(32), 4),
+        },
+    );
+
+    let cond = cfg.new_basic_block("compact_cond".into());
+    let next = cfg.new_basic_block("compact_next".into());
+    let end = cfg.new_basic_block("compact_end".into());
+
+    vartab.new_dirty_tracker();
+    cfg.add(vartab, Instr::Branch { block: cond });
+
+    // Shifting by the width of the value is undefined, so check if the top byte so far is larger
+    // than 255, rather than if the value shifted by all the bytes so far is non-zero
+    cfg.set_basic_block(cond);
+    let top_byte = multiply_u32(subtract_u32(bytes.clone(), 1), 8);
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::More {
+                loc: Codegen,
+                signed: false,
+                left: Expression::ShiftRight {
+                    loc: Codegen,
+                    ty: Uint(width),
+                    left: value.clone().into(),
+                    right: widen(&top_byte, width).into(),
+                    signed: false,
+                }
+                .into(),
+                right: number_literal(&Uint(width), 255).into(),
+            },
+            true_block: next,
+            false_block: end,
+        },
+    );
+
+    cfg.set_basic_block(next);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: bytes_var,
+            expr: bytes.clone().add_u32(number_literal(&Uint(32), 1)),
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: cond });
+
+    cfg.set_basic_block(end);
+    let phis = vartab.pop_dirty_tracker();
+    cfg.set_phis(cond, phis.clone());
+    cfg.set_phis(end, phis);
+
+    bytes
+}
+
+/// Write `expr` into `buffer` at `offset` in the compact encoding, where `size` was calculated
+/// by [`compact_size`]
+fn encode_compact(
+    expr: &Expression,
+    size: &Expression,
+    buffer: &Expression,
+    offset: &Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    let width = compact_width(&expr.ty());
+    let value = widen(expr, width);
+    let shifted = Expression::ShiftLeft {
+        loc: Codegen,
+        ty: Uint(width),
+        left: value.clone().into(),
+        right: number_literal(&Uint(width), 2).into(),
+    };
+
+    let done = cfg.new_basic_block("compact_done".into());
+    let big = cfg.new_basic_block("compact_big".into());
+    let cases = COMPACT_MODES
+        .iter()
+        .map(|(size, _)| {
+            let block = cfg.new_basic_block(format!("compact_{size}_bytes"));
+            (number_literal(&Uint(32), *size), block)
+        })
+        .collect::<Vec<_>>();
+
+    vartab.new_dirty_tracker();
+    cfg.add(
+        vartab,
+        Instr::Switch {
+            cond: size.clone(),
+            cases: cases.clone(),
+            default: big,
+        },
+    );
+
+    // The two lowest bits of the first byte are the mode
+    for (mode, (size, block)) in cases.into_iter().enumerate() {
+        let Expression::NumberLiteral { value: size, .. } = size else {
+            unreachable!();
+        };
+        let ty = Uint(u16::try_from(size * 8).unwrap());
+
+        cfg.set_basic_block(block);
+        write_buffer(
+            Expression::Trunc {
+                loc: Codegen,
+                ty: ty.clone(),
+                expr: Expression::BitwiseOr {
+                    loc: Codegen,
+                    ty: Uint(width),
+                    left: shifted.clone().into(),
+                    right: number_literal(&Uint(width), mode).into(),
+                }
+                .into(),
+            },
+            buffer,
+            offset.clone(),
+            vartab,
+            cfg,
+        );
+        cfg.add(vartab, Instr::Branch { block: done });
+    }
+
+    // In the big integer mode, the upper six bits of the first byte are the number of bytes
+    // following minus four
+    cfg.set_basic_block(big);
+    let bytes = subtract_u32(size.clone(), 1);
+    let length = Expression::BitwiseOr {
+        loc: Codegen,
+        ty: Uint(32),
+        left: Expression::ShiftLeft {
+            loc: Codegen,
+            ty: Uint(32),
+            left: subtract_u32(bytes.clone(), 4).into(),
+            right: number_literal(&Uint(32), 2).into(),
+        }
+        .into(),
+        right: number_literal(&Uint(32), 3).into(),
+    };
+    write_buffer(
+        Expression::Trunc {
+            loc: Codegen,
+            ty: Uint(8),
+            expr: length.into(),
+        },
+        buffer,
+        offset.clone(),
+        vartab,
+        cfg,
+    );
+
+    let contents = offset.clone().add_u32(number_literal(&Uint(32), 1));
+    if width == 32 {
+        write_buffer(value, buffer, contents, vartab, cfg);
+    } else {
+        byte_loop(&bytes, vartab, cfg, |index, vartab, cfg| {
+            let byte = Expression::Trunc {
+                loc: Codegen,
+                ty: Uint(8),
+                expr: Expression::ShiftRight {
+                    loc: Codegen,
+                    ty: Uint(width),
+                    left: value.clone().into(),
+                    right: widen(&multiply_u32(index.clone(), 8), width).into(),
+                    signed: false,
+                }
+                .into(),
+            };
+            write_buffer(byte, buffer, contents.clone().add_u32(index), vartab, cfg);
+        });
+    }
+    cfg.add(vartab, Instr::Branch { block: done });
+
+    cfg.set_basic_block(done);
+    cfg.set_phis(done, vartab.pop_dirty_tracker());
+}
+
+/// Read a compact integer of type `ty` from `buffer` at `offset`. Returns the value and the
+/// number of bytes read. Values which do not fit into `ty` are rejected.
+fn decode_compact(
+    buffer: &Expression,
+    offset: &Expression,
+    ty: &Type,
+    validator: &BufferValidator,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) -> (Expression, Expression) {
+    let width = compact_width(ty);
+    let value_var = vartab.temp_name("compact_value", &Uint(width));
+    let size_var = vartab.temp_name("compact_size", &Uint(32));
+    let value = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(width),
+        var_no: value_var,
+    };
+    let size = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: size_var,
+    };
+
+    validator.validate_offset(
+        offset.clone().add_u32(number_literal(&Uint(32), 1)),
+        ns,
+        vartab,
+        cfg,
+    );
+    let first_var = vartab.temp_anonymous(&Uint(32));
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: first_var,
+            expr: Expression::ZeroExt {
+                loc: Codegen,
+                ty: Uint(32),
+                expr: read_buffer(&Uint(8), buffer, offset.clone()).into(),
+            },
+        },
+    );
+    let first = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: first_var,
+    };
+    let upper_bits = Expression::ShiftRight {
+        loc: Codegen,
+        ty: Uint(32),
+        left: first.clone().into(),
+        right: number_literal(&Uint(32), 2).into(),
+        signed: false,
+    };
+
+    let done = cfg.new_basic_block("compact_done".into());
+    let big = cfg.new_basic_block("compact_big".into());
+    let cases = COMPACT_MODES
+        .iter()
+        .enumerate()
+        .map(|(mode, (size, _))| {
+            let block = cfg.new_basic_block(format!("compact_{size}_bytes"));
+            (number_literal(&Uint(32), mode), block, *size)
+        })
+        .collect::<Vec<_>>();
+
+    vartab.new_dirty_tracker();
+    cfg.add(
+        vartab,
+        Instr::Switch {
+            cond: Expression::BitwiseAnd {
+                loc: Codegen,
+                ty: Uint(32),
+                left: first.clone().into(),
+                right: number_literal(&Uint(32), 3).into(),
+            },
+            cases: cases
+                .iter()
+                .map(|(mode, block, _)| (mode.clone(), *block))
+                .collect(),
+            default: big,
+        },
+    );
+
+    for (_, block, bytes) in cases {
+        cfg.set_basic_block(block);
+        let size_expr = number_literal(&Uint(32), bytes);
+        let ty = Uint(u16::from(bytes) * 8);
+
+        let encoded = if bytes == 1 {
+            upper_bits.clone()
+        } else {
+            validator.validate_offset(offset.clone().add_u32(size_expr.clone()), ns, vartab, cfg);
+            Expression::ShiftRight {
+                loc: Codegen,
+                ty: Uint(32),
+                left: widen(&read_buffer(&ty, buffer, offset.clone()), 32).into(),
+                right: number_literal(&Uint(32), 2).into(),
+                signed: false,
+            }
+        };
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: value_var,
+                expr: widen(&encoded, width),
+            },
+        );
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: size_var,
+                expr: size_expr,
+            },
+        );
+        cfg.add(vartab, Instr::Branch { block: done });
+    }
+
+    cfg.set_basic_block(big);
+    let bytes_var = vartab.temp_anonymous(&Uint(32));
+    let bytes = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: bytes_var,
+    };
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: bytes_var,
+            expr: upper_bits.add_u32(number_literal(&Uint(32), 4)),
+        },
+    );
+    fail_if(
+        Expression::More {
+            loc: Codegen,
+            signed: false,
+            left: bytes.clone().into(),
+            right: number_literal(&Uint(32), width / 8).into(),
+        },
+        vartab,
+        cfg,
+    );
+    let contents = offset.clone().add_u32(number_literal(&Uint(32), 1));
+    validator.validate_offset(contents.clone().add_u32(bytes.clone()), ns, vartab, cfg);
+
+    if width == 32 {
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: value_var,
+                expr: read_buffer(&Uint(32), buffer, contents),
+            },
+        );
+    } else {
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: Codegen,
+                res: value_var,
+                expr: number_literal(&Uint(width), 0),
+            },
+        );
+        byte_loop(&bytes, vartab, cfg, |index, vartab, cfg| {
+            let byte = read_buffer(&Uint(8), buffer, contents.clone().add_u32(index.clone()));
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: Codegen,
+                    res: value_var,
+                    expr: Expression::BitwiseOr {
+                        loc: Codegen,
+                        ty: Uint(width),
+                        left: value.clone().into(),
+                        right: Expression::ShiftLeft {
+                            loc: Codegen,
+                            ty: Uint(width),
+                            left: widen(&byte, width).into(),
+                            right: widen(&multiply_u32(index, 8), width).into(),
+                        }
+                        .into(),
+                    },
+                },
+            );
+        });
+    }
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: size_var,
+            expr: bytes.add_u32(number_literal(&Uint(32), 1)),
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: done });
+
+    cfg.set_basic_block(done);
+    cfg.set_phis(done, vartab.pop_dirty_tracker());
+
+    let bits = ty.bits(ns);
+    if bits == width {
+        return (value, size);
+    }
+
+    fail_if(
+        Expression::More {
+            loc: Codegen,
+            signed: false,
+            left: value.clone().into(),
+            right: Expression::NumberLiteral {
+                loc: Codegen,
+                ty: Uint(width),
+                value: (BigInt::from(1) << bits) - 1,
+            }
+            .into(),
+        },
+        vartab,
+        cfg,
+    );
+
+    let value = Expression::Trunc {
+        loc: Codegen,
+        ty: ty.clone(),
+        expr: value.into(),
+    };
+
+    (value, size)
+}
+
+/// Generate a loop which runs `body` for each index from zero up to `count`
+fn byte_loop<F>(count: &Expression, vartab: &mut Vartable, cfg: &mut ControlFlowGraph, mut body: F)
+where
+    F: FnMut(Expression, &mut Vartable, &mut ControlFlowGraph),
+{
+    let index_var = vartab.temp_name("compact_i", &Uint(32));
+    let index = Expression::Variable {
+        loc: Codegen,
+        ty: Uint(32),
+        var_no: index_var,
+    };
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: index_var,
+            expr: number_literal(&Uint(32), 0),
+        },
+    );
+
+    let cond = cfg.new_basic_block("cond".into());
+    let body_block = cfg.new_basic_block("body".into());
+    let end = cfg.new_basic_block("end_for".into());
+
+    vartab.new_dirty_tracker();
+    cfg.add(vartab, Instr::Branch { block: cond });
+
+    cfg.set_basic_block(cond);
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::Less {
+                loc: Codegen,
+                signed: false,
+                left: index.clone().into(),
+                right: count.clone().into(),
+            },
+            true_block: body_block,
+            false_block: end,
+        },
+    );
+
+    cfg.set_basic_block(body_block);
+    body(index.clone(), vartab, cfg);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: Codegen,
+            res: index_var,
+            expr: index.add_u32(number_literal(&Uint(32), 1)),
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: cond });
+
+    cfg.set_basic_block(end);
+    let phis = vartab.pop_dirty_tracker();
+    cfg.set_phis(cond, phis.clone());
+    cfg.set_phis(end, phis);
+}
+
+/// Abort the contract if `cond` is true
+fn fail_if(cond: Expression, vartab: &mut Vartable, cfg: &mut ControlFlowGraph) {
+    let fail = cfg.new_basic_block("fail".into());
+    let ok = cfg.new_basic_block("ok".into());
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond,
+            true_block: fail,
+            false_block: ok,
+        },
+    );
+
+    cfg.set_basic_block(fail);
+    cfg.add(vartab, Instr::AssertFailure { encoded_args: None });
+
+    cfg.set_basic_block(ok);
+}
+
+/// Zero extend an unsigned integer to `width` bits, if it is narrower
+fn widen(expr: &Expression, width: u16) -> Expression {
+    match expr.ty() {
+        Uint(n) if n < width => Expression::ZeroExt {
+            loc: Codegen,
+            ty: Uint(width),
+            expr: expr.clone().into(),
+        },
+        _ => expr.clone(),
+    }
+}
+
+fn write_buffer(
+    value: Expression,
+    buffer: &Expression,
+    offset: Expression,
+    vartab: &mut Vartable,
+    cfg: &mut ControlFlowGraph,
+) {
+    cfg.add(
+        vartab,
+        Instr::WriteBuffer {
+            buf: buffer.clone(),
+            offset,
+            value,
+        },
+    );
+}
+
+fn read_buffer(ty: &Type, buffer: &Expression, offset: Expression) -> Expression {
+    Expression::Builtin {
+        loc: Codegen,
+        tys: vec![ty.clone()],
+        kind: Builtin::ReadFromBuffer,
+        args: vec![buffer.clone(), offset],
+    }
+}
+
+fn subtract_u32(expr: Expression, value: u8) -> Expression {
+    Expression::Subtract {
+        loc: Codegen,
+        ty: Uint(32),
+        overflowing: false,
+        left: expr.into(),
+        right: number_literal(&Uint(32), value).into(),
+    }
+}
+
+fn multiply_u32(expr: Expression, value: u8) -> Expression {
+    Expression::Multiply {
+        loc: Codegen,
+        ty: Uint(32),
+        overflowing: false,
+        left: expr.into(),
+        right: number_literal(&Uint(32), value).into(),
+    }
+}
+
+fn number_literal<T: Into<BigInt>>(ty: &Type, value: T) -> Expression {
+    Expression::NumberLiteral {
+        loc: Codegen,
+        ty: ty.clone(),
+        value: value.into(),
+    }
+}
@@ -2,10 +2,12 @@
 
 mod polkadot;
 mod solana;
+mod stylus;
 
 use crate::codegen::cfg::ControlFlowGraph;
 use crate::codegen::events::polkadot::PolkadotEventEmitter;
 use crate::codegen::events::solana::SolanaEventEmitter;
+use crate::codegen::events::stylus::StylusEventEmitter;
 use crate::codegen::vartable::Vartable;
 use crate::codegen::Options;
 use crate::sema::ast;
@@ -51,6 +53,18 @@ pub(super) fn new_event_emitter<'a>(
             event_no,
         }),
 
+        Target::Stylus => Box::new(StylusEventEmitter { args, ns, event_no }),
+
         Target::Soroban => todo!(),
     }
 }
+
+/// The selector of an event as emitted by the given contract: the first topic on Polkadot and
+/// Stylus, or the discriminator on Solana.
+pub(crate) fn event_selector(
+    event_no: usize,
+    emitting_contract_no: usize,
+    ns: &Namespace,
+) -> Vec<u8> {
+    new_event_emitter(&pt::Loc::Codegen, event_no, &[], ns).selector(emitting_contract_no)
+}
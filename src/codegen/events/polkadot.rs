@@ -123,100 +123,158 @@ impl EventEmitter for PolkadotEventEmitter<'_> {
                 continue;
             }
 
-            let encoded = abi_encode(&loc, vec![value], self.ns, vartab, cfg, false).0;
-            let first_prefix = topic_prefixes.pop_front().unwrap();
-            let prefix = Expression::AllocDynamicBytes {
-                loc,
-                ty: Type::Slice(Type::Bytes(1).into()),
-                size: Expression::NumberLiteral {
-                    loc,
-                    ty: Type::Uint(32),
-                    value: first_prefix.len().into(),
-                }
-                .into(),
-                initializer: Some(first_prefix),
-            };
-            let concatenated = Expression::Builtin {
+            let prefix = topic_prefixes.pop_front().unwrap();
+            topics.push(self.topic(prefix, value, cfg, vartab));
+        }
+
+        let data = abi_encode(&loc, data, self.ns, vartab, cfg, false).0;
+        cfg.add(
+            vartab,
+            Instr::EmitEvent {
+                event_no: self.event_no,
+                data,
+                topics,
+            },
+        );
+    }
+}
+
+impl PolkadotEventEmitter<'_> {
+    /// Turn the value of an indexed field into a topic, following the rules of ink!. The topic is
+    /// the SCALE encoded `prefix` followed by the SCALE encoded value. It is zero padded to 32
+    /// bytes if it fits, and replaced by its blake2x256 hash otherwise. The encoded size of most
+    /// types is known at compile time, in which case only one of the two is generated.
+    fn topic(
+        &self,
+        prefix: Vec<u8>,
+        value: Expression,
+        cfg: &mut ControlFlowGraph,
+        vartab: &mut Vartable,
+    ) -> Expression {
+        let loc = pt::Loc::Builtin;
+        let hash_len = Box::new(Expression::NumberLiteral {
+            loc,
+            ty: Type::Uint(32),
+            value: 32.into(),
+        });
+
+        let prefix_len = prefix.len();
+        let (encoded, encoded_len) = abi_encode(&loc, vec![value], self.ns, vartab, cfg, false);
+        let prefix = Expression::AllocDynamicBytes {
+            loc,
+            ty: Type::Slice(Type::Bytes(1).into()),
+            size: Expression::NumberLiteral {
                 loc,
-                kind: Builtin::Concat,
-                tys: vec![Type::DynamicBytes],
-                args: vec![prefix, encoded],
-            };
+                ty: Type::Uint(32),
+                value: prefix_len.into(),
+            }
+            .into(),
+            initializer: Some(prefix),
+        };
 
-            vartab.new_dirty_tracker();
-            let var_buffer = vartab.temp_anonymous(&Type::DynamicBytes);
-            cfg.add(
-                vartab,
-                Instr::Set {
+        let var_buffer = vartab.temp_anonymous(&Type::DynamicBytes);
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc,
+                res: var_buffer,
+                expr: Expression::Builtin {
                     loc,
-                    res: var_buffer,
-                    expr: concatenated,
+                    kind: Builtin::Concat,
+                    tys: vec![Type::DynamicBytes],
+                    args: vec![prefix, encoded],
                 },
-            );
-            let buffer = Expression::Variable {
-                loc,
-                ty: Type::DynamicBytes,
-                var_no: var_buffer,
-            };
-            let compare = Expression::More {
+            },
+        );
+        let buffer = Expression::Variable {
+            loc,
+            ty: Type::DynamicBytes,
+            var_no: var_buffer,
+        };
+        let buffer_len = Expression::Builtin {
+            loc,
+            tys: vec![Type::Uint(32)],
+            kind: Builtin::ArrayLength,
+            args: vec![buffer.clone()],
+        };
+
+        let var_topic = vartab.temp_anonymous(&Type::DynamicBytes);
+        cfg.add(
+            vartab,
+            Instr::Set {
                 loc,
-                signed: false,
-                left: Expression::Builtin {
+                res: var_topic,
+                expr: Expression::AllocDynamicBytes {
                     loc,
-                    tys: vec![Type::Uint(32)],
-                    kind: Builtin::ArrayLength,
-                    args: vec![buffer.clone()],
-                }
-                .into(),
-                right: hash_len.clone(),
-            };
-
-            let hash_topic_block = cfg.new_basic_block("hash_topic".into());
-            let done_block = cfg.new_basic_block("done".into());
-            cfg.add(
-                vartab,
-                Instr::BranchCond {
-                    cond: compare,
-                    true_block: hash_topic_block,
-                    false_block: done_block,
+                    ty: Type::DynamicBytes,
+                    size: hash_len.clone(),
+                    initializer: None,
                 },
-            );
+            },
+        );
+        let topic = Expression::Variable {
+            loc,
+            ty: Type::DynamicBytes,
+            var_no: var_topic,
+        };
 
-            cfg.set_basic_block(hash_topic_block);
-            cfg.add(
-                vartab,
-                Instr::WriteBuffer {
-                    buf: buffer.clone(),
-                    offset: Expression::NumberLiteral {
-                        loc,
-                        ty: Type::Uint(32),
-                        value: 0.into(),
-                    },
-                    value: Expression::Builtin {
-                        loc,
-                        tys: vec![Type::Bytes(32)],
-                        kind: Builtin::Blake2_256,
-                        args: vec![buffer.clone()],
-                    },
-                },
-            );
-            vartab.set_dirty(var_buffer);
-            cfg.add(vartab, Instr::Branch { block: done_block });
+        let hash = Instr::WriteBuffer {
+            buf: topic.clone(),
+            offset: Expression::NumberLiteral {
+                loc,
+                ty: Type::Uint(32),
+                value: 0.into(),
+            },
+            value: Expression::Builtin {
+                loc,
+                tys: vec![Type::Bytes(32)],
+                kind: Builtin::Blake2_256,
+                args: vec![buffer.clone()],
+            },
+        };
+        let pad = Instr::MemCopy {
+            source: buffer,
+            destination: topic.clone(),
+            bytes: buffer_len.clone(),
+        };
 
-            cfg.set_basic_block(done_block);
-            cfg.set_phis(done_block, vartab.pop_dirty_tracker());
+        if let Expression::NumberLiteral { value, .. } = encoded_len {
+            if value + prefix_len > 32.into() {
+                cfg.add(vartab, hash);
+            } else {
+                cfg.add(vartab, pad);
+            }
 
-            topics.push(buffer);
+            return topic;
         }
 
-        let data = abi_encode(&loc, data, self.ns, vartab, cfg, false).0;
+        let hash_topic_block = cfg.new_basic_block("hash_topic".into());
+        let pad_topic_block = cfg.new_basic_block("pad_topic".into());
+        let done_block = cfg.new_basic_block("done".into());
         cfg.add(
             vartab,
-            Instr::EmitEvent {
-                event_no: self.event_no,
-                data,
-                topics,
+            Instr::BranchCond {
+                cond: Expression::More {
+                    loc,
+                    signed: false,
+                    left: buffer_len.into(),
+                    right: hash_len,
+                },
+                true_block: hash_topic_block,
+                false_block: pad_topic_block,
             },
         );
+
+        cfg.set_basic_block(hash_topic_block);
+        cfg.add(vartab, hash);
+        cfg.add(vartab, Instr::Branch { block: done_block });
+
+        cfg.set_basic_block(pad_topic_block);
+        cfg.add(vartab, pad);
+        cfg.add(vartab, Instr::Branch { block: done_block });
+
+        cfg.set_basic_block(done_block);
+
+        topic
     }
 }
@@ -0,0 +1,193 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::cfg::{ControlFlowGraph, Instr};
+use crate::codegen::encoding::abi_encode;
+use crate::codegen::events::EventEmitter;
+use crate::codegen::expression::expression;
+use crate::codegen::vartable::Vartable;
+use crate::codegen::{Builtin, Expression, Options};
+use crate::sema::ast::{self, Function, Namespace, RetrieveType, Type};
+use solang_parser::pt;
+use tiny_keccak::{Hasher, Keccak};
+
+/// This struct implements the trait 'EventEmitter' in order to handle the emission of events
+/// for Stylus. Like on Ethereum, the first topic is the keccak256 hash of the event signature
+/// and every indexed field is turned into a 32 bytes topic.
+pub(super) struct StylusEventEmitter<'a> {
+    /// Arguments passed to the event
+    pub(super) args: &'a [ast::Expression],
+    pub(super) ns: &'a Namespace,
+    pub(super) event_no: usize,
+}
+
+impl EventEmitter for StylusEventEmitter<'_> {
+    fn selector(&self, _emitting_contract_no: usize) -> Vec<u8> {
+        let mut res = [0u8; 32];
+
+        let mut hasher = Keccak::v256();
+        hasher.update(self.ns.events[self.event_no].signature.as_bytes());
+        hasher.finalize(&mut res);
+
+        res.to_vec()
+    }
+
+    fn emit(
+        &self,
+        contract_no: usize,
+        func: &Function,
+        cfg: &mut ControlFlowGraph,
+        vartab: &mut Vartable,
+        opt: &Options,
+    ) {
+        let loc = pt::Loc::Builtin;
+        let event = &self.ns.events[self.event_no];
+        let mut data = vec![];
+        let mut topics = vec![];
+
+        if !event.anonymous {
+            topics.push(Expression::AllocDynamicBytes {
+                loc,
+                ty: Type::Slice(Type::Uint(8).into()),
+                size: Expression::NumberLiteral {
+                    loc,
+                    ty: Type::Uint(32),
+                    value: 32.into(),
+                }
+                .into(),
+                initializer: Some(self.selector(contract_no)),
+            });
+        }
+
+        for (ast_exp, field) in self.args.iter().zip(event.fields.iter()) {
+            let value_exp = expression(ast_exp, cfg, contract_no, Some(func), self.ns, vartab, opt);
+            let value_var = vartab.temp_anonymous(&value_exp.ty());
+            let value = Expression::Variable {
+                loc,
+                ty: value_exp.ty(),
+                var_no: value_var,
+            };
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc,
+                    res: value_var,
+                    expr: value_exp,
+                },
+            );
+
+            if field.indexed {
+                topics.push(self.topic(value, cfg, vartab));
+            } else {
+                data.push(value);
+            }
+        }
+
+        let data = if data.is_empty() {
+            Expression::AllocDynamicBytes {
+                loc,
+                ty: Type::DynamicBytes,
+                size: Expression::NumberLiteral {
+                    loc,
+                    ty: Type::Uint(32),
+                    value: 0.into(),
+                }
+                .into(),
+                initializer: Some(vec![]),
+            }
+        } else {
+            abi_encode(&loc, data, self.ns, vartab, cfg, false).0
+        };
+
+        cfg.add(
+            vartab,
+            Instr::EmitEvent {
+                event_no: self.event_no,
+                data,
+                topics,
+            },
+        );
+    }
+}
+
+impl StylusEventEmitter<'_> {
+    /// Turn the value of an indexed field into a topic. Value types are padded to 32 bytes,
+    /// any other type is hashed using keccak256.
+    fn topic(
+        &self,
+        value: Expression,
+        cfg: &mut ControlFlowGraph,
+        vartab: &mut Vartable,
+    ) -> Expression {
+        let loc = pt::Loc::Builtin;
+        let word = Type::Bytes(32);
+
+        let word_value = match value.ty().unwrap_user_type(self.ns) {
+            Type::Int(256) | Type::Uint(256) => Expression::Cast {
+                loc,
+                ty: word.clone(),
+                expr: value.into(),
+            },
+            Type::Int(_) => Expression::SignExt {
+                loc,
+                ty: word.clone(),
+                expr: value.into(),
+            },
+            Type::Uint(_) | Type::Value | Type::Enum(_) | Type::Bool => Expression::ZeroExt {
+                loc,
+                ty: word.clone(),
+                expr: value.into(),
+            },
+            Type::Address(_) | Type::Contract(_) => Expression::ZeroExt {
+                loc,
+                ty: word.clone(),
+                expr: Expression::Cast {
+                    loc,
+                    ty: Type::Uint(self.ns.address_length as u16 * 8),
+                    expr: value.into(),
+                }
+                .into(),
+            },
+            // bytesN values are left aligned
+            Type::Bytes(32) => value,
+            Type::Bytes(n) => Expression::ShiftLeft {
+                loc,
+                ty: word.clone(),
+                left: Expression::ZeroExt {
+                    loc,
+                    ty: word.clone(),
+                    expr: value.into(),
+                }
+                .into(),
+                right: Expression::NumberLiteral {
+                    loc,
+                    ty: word.clone(),
+                    value: ((32 - n as u32) * 8).into(),
+                }
+                .into(),
+            },
+            Type::String | Type::DynamicBytes => Expression::Builtin {
+                loc,
+                tys: vec![word.clone()],
+                kind: Builtin::Keccak256,
+                args: vec![value],
+            },
+            _ => {
+                let encoded = abi_encode(&loc, vec![value], self.ns, vartab, cfg, true).0;
+
+                Expression::Builtin {
+                    loc,
+                    tys: vec![word.clone()],
+                    kind: Builtin::Keccak256,
+                    args: vec![encoded],
+                }
+            }
+        };
+
+        Expression::BytesCast {
+            loc,
+            ty: Type::DynamicBytes,
+            from: word,
+            expr: word_value.into(),
+        }
+    }
+}
@@ -1,11 +1,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use super::encoding::{abi_decode, abi_encode};
+use super::encoding::{
+    abi_decode, abi_decode_annotated, abi_decode_with, abi_encode, abi_encode_after_selector,
+    abi_encode_payload, abi_encode_with, Encoding,
+};
 use super::revert::{
     assert_failure, expr_assert, log_runtime_error, require, PanicCode, SolidityError,
 };
 use super::storage::{
-    array_offset, array_pop, array_push, storage_slots_array_pop, storage_slots_array_push,
+    array_offset, array_pop, array_push, enumerable_mapping_insert, load_packed_storage,
+    packed_storage, set_packed_storage, storage_slots_array_pop, storage_slots_array_push,
 };
 use super::{
     cfg::{ControlFlowGraph, Instr, InternalCallTy},
@@ -21,8 +25,8 @@ use crate::sema::ast::ExternalCallAccounts;
 use crate::sema::{
     ast,
     ast::{
-        ArrayLength, CallTy, FormatArg, Function, Namespace, RetrieveType, StringLocation,
-        StructType, Type,
+        ArrayLength, CallTy, FormatArg, Function, Namespace, Parameter, RetrieveType,
+        StringLocation, StructType, Type,
     },
     diagnostics::Diagnostics,
     eval::{eval_const_number, eval_const_rational, eval_constants_in_expression},
@@ -32,8 +36,10 @@ use crate::sema::{
 use crate::Target;
 use num_bigint::{BigInt, Sign};
 use num_traits::{FromPrimitive, One, ToPrimitive, Zero};
+use parity_scale_codec::{Compact, Encode};
 use solang_parser::pt::{self, CodeLocation, Loc};
-use std::{cmp::Ordering, ops::Mul};
+use std::{cmp::Ordering, iter, ops::Mul};
+use tiny_keccak::{Hasher, Keccak};
 
 pub fn expression(
     expr: &ast::Expression,
@@ -58,9 +64,28 @@ pub fn expression(
             ns.contracts[contract_no].get_storage_slot(*loc, *var_contract_no, *var_no, ns, None)
         }
         ast::Expression::StorageLoad { loc, ty, expr } => {
+            if let ast::Expression::StorageVariable {
+                contract_no: var_contract_no,
+                var_no,
+                ..
+            } = expr.as_ref()
+            {
+                if let Some(value) = ns.contracts[contract_no]
+                    .immutables_in_code
+                    .get(&(*var_contract_no, *var_no))
+                {
+                    // the value of the immutable is part of the code, like a constant
+                    return expression(value, cfg, contract_no, func, ns, vartab, opt);
+                }
+            }
+
             let storage = expression(expr, cfg, contract_no, func, ns, vartab, opt);
 
-            load_storage(loc, ty, storage, cfg, vartab)
+            if let Some(packed) = packed_storage(expr, contract_no, ns) {
+                load_packed_storage(loc, ty, storage, packed, cfg, vartab, ns)
+            } else {
+                load_storage(loc, ty, storage, cfg, vartab)
+            }
         }
         ast::Expression::Add {
             loc,
@@ -128,6 +153,7 @@ pub fn expression(
         ast::Expression::Divide {
             loc,
             ty,
+            unchecked,
             left,
             right,
         } => {
@@ -137,6 +163,7 @@ pub fn expression(
                 Expression::SignedDivide {
                     loc: *loc,
                     ty: ty.clone(),
+                    overflowing: *unchecked,
                     left: Box::new(l),
                     right: Box::new(r),
                 }
@@ -228,7 +255,16 @@ pub fn expression(
             loc: *loc,
             ty: ty.clone(),
             left: Box::new(expression(left, cfg, contract_no, func, ns, vartab, opt)),
-            right: Box::new(expression(right, cfg, contract_no, func, ns, vartab, opt)),
+            right: Box::new(shift_amount(
+                right,
+                ty,
+                cfg,
+                contract_no,
+                func,
+                ns,
+                vartab,
+                opt,
+            )),
         },
         ast::Expression::ShiftRight {
             loc,
@@ -240,7 +276,16 @@ pub fn expression(
             loc: *loc,
             ty: ty.clone(),
             left: Box::new(expression(left, cfg, contract_no, func, ns, vartab, opt)),
-            right: Box::new(expression(right, cfg, contract_no, func, ns, vartab, opt)),
+            right: Box::new(shift_amount(
+                right,
+                ty,
+                cfg,
+                contract_no,
+                func,
+                ns,
+                vartab,
+                opt,
+            )),
             signed: *sign,
         },
         ast::Expression::Equal { loc, left, right } => Expression::Equal {
@@ -408,19 +453,23 @@ pub fn expression(
             ty,
             unchecked,
             expr: var,
-        } => pre_incdec(
-            vartab,
-            ty,
-            var,
-            cfg,
-            contract_no,
-            func,
-            ns,
-            loc,
-            expr,
-            *unchecked,
-            opt,
-        ),
+        } => {
+            let inserted = enumerable_mapping_insert(var, cfg, contract_no, func, ns, vartab, opt);
+
+            pre_incdec(
+                vartab,
+                ty,
+                inserted.as_ref().unwrap_or(var),
+                cfg,
+                contract_no,
+                func,
+                ns,
+                loc,
+                expr,
+                *unchecked,
+                opt,
+            )
+        }
         ast::Expression::PostDecrement {
             loc,
             ty,
@@ -432,19 +481,23 @@ pub fn expression(
             ty,
             unchecked,
             expr: var,
-        } => post_incdec(
-            vartab,
-            ty,
-            var,
-            cfg,
-            contract_no,
-            func,
-            ns,
-            loc,
-            expr,
-            *unchecked,
-            opt,
-        ),
+        } => {
+            let inserted = enumerable_mapping_insert(var, cfg, contract_no, func, ns, vartab, opt);
+
+            post_incdec(
+                vartab,
+                ty,
+                inserted.as_ref().unwrap_or(var),
+                cfg,
+                contract_no,
+                func,
+                ns,
+                loc,
+                expr,
+                *unchecked,
+                opt,
+            )
+        }
         ast::Expression::Constructor {
             loc,
             contract_no: constructor_contract,
@@ -535,7 +588,7 @@ pub fn expression(
                 },
                 Type::Array(_, dim) => match dim.last().unwrap() {
                     ArrayLength::Dynamic => {
-                        if ns.target == Target::Solana {
+                        if matches!(ns.target, Target::Solana | Target::Soroban) {
                             Expression::StorageArrayLength {
                                 loc: *loc,
                                 ty: ty.clone(),
@@ -610,7 +663,7 @@ pub fn expression(
         | ast::Expression::ExternalFunctionCall { .. }
         | ast::Expression::ExternalFunctionCallRaw { .. }
         | ast::Expression::Builtin {
-            kind: ast::Builtin::AbiDecode,
+            kind: ast::Builtin::AbiDecode | ast::Builtin::AbiEthDecode,
             ..
         } => {
             let mut returns = emit_function_call(expr, contract_no, cfg, func, ns, vartab, opt);
@@ -669,11 +722,9 @@ pub fn expression(
                 let offset = if ns.target == Target::Solana {
                     struct_ty.definition(ns).storage_offsets[*field_no].clone()
                 } else {
-                    struct_ty.definition(ns).fields[..*field_no]
-                        .iter()
-                        .filter(|field| !field.infinite_size)
-                        .map(|field| field.ty.storage_slots(ns))
-                        .sum()
+                    struct_ty.definition(ns).storage_packing(ns).0[*field_no]
+                        .0
+                        .clone()
                 };
 
                 Expression::Add {
@@ -842,7 +893,19 @@ pub fn expression(
             args,
         } => {
             if args[0].ty().is_contract_storage() {
-                if ns.target == Target::Solana || args[0].ty().is_storage_bytes() {
+                // pushing to a value in an enumerable mapping adds its key
+                let inserted =
+                    enumerable_mapping_insert(&args[0], cfg, contract_no, func, ns, vartab, opt)
+                        .map(|array| {
+                            let mut args = args.clone();
+                            args[0] = array;
+                            args
+                        });
+                let args = inserted.as_deref().unwrap_or(args);
+
+                if matches!(ns.target, Target::Solana | Target::Soroban)
+                    || args[0].ty().is_storage_bytes()
+                {
                     array_push(loc, args, cfg, contract_no, func, ns, vartab, opt)
                 } else {
                     storage_slots_array_push(loc, args, cfg, contract_no, func, ns, vartab, opt)
@@ -874,7 +937,9 @@ pub fn expression(
             args,
         } => {
             if args[0].ty().is_contract_storage() {
-                if ns.target == Target::Solana || args[0].ty().is_storage_bytes() {
+                if matches!(ns.target, Target::Solana | Target::Soroban)
+                    || args[0].ty().is_storage_bytes()
+                {
                     array_pop(loc, args, &ty[0], cfg, contract_no, func, ns, vartab, opt)
                 } else {
                     storage_slots_array_pop(
@@ -920,6 +985,34 @@ pub fn expression(
                 }
             }
         }
+        ast::Expression::Builtin {
+            loc,
+            kind: ast::Builtin::ArrayReserve,
+            args,
+            ..
+        } => {
+            let array_pos = match expression(&args[0], cfg, contract_no, func, ns, vartab, opt) {
+                Expression::Variable { var_no, .. } => {
+                    vartab.set_dirty(var_no);
+
+                    var_no
+                }
+                _ => unreachable!(),
+            };
+            let size = expression(&args[1], cfg, contract_no, func, ns, vartab, opt);
+
+            cfg.add(
+                vartab,
+                Instr::ReserveMemory {
+                    loc: *loc,
+                    ty: args[0].ty(),
+                    array: array_pos,
+                    size,
+                },
+            );
+
+            Expression::Poison
+        }
         ast::Expression::Builtin {
             kind: ast::Builtin::Assert,
             args,
@@ -972,6 +1065,19 @@ pub fn expression(
             args,
             ..
         } => abi_encode_many(args, cfg, contract_no, func, ns, vartab, loc, opt),
+        ast::Expression::Builtin {
+            loc,
+            kind: ast::Builtin::AbiEthEncode,
+            args,
+            ..
+        } => {
+            let args = args
+                .iter()
+                .map(|v| expression(v, cfg, contract_no, func, ns, vartab, opt))
+                .collect::<Vec<Expression>>();
+
+            abi_encode_with(Encoding::Ethereum, loc, args, ns, vartab, cfg, false).0
+        }
         ast::Expression::Builtin {
             loc,
             kind: ast::Builtin::AbiEncodePacked,
@@ -1002,9 +1108,15 @@ pub fn expression(
             kind: ast::Builtin::Gasprice,
             args: expr,
             ..
-        } if expr.len() == 1 && ns.target == Target::EVM => {
+        } if expr.len() == 1 && matches!(ns.target, Target::EVM | Target::Stylus) => {
             builtin_evm_gasprice(loc, expr, cfg, contract_no, func, ns, vartab, opt)
         }
+        ast::Expression::Builtin {
+            loc,
+            kind: ast::Builtin::Create2Address,
+            args,
+            ..
+        } => create2_address(loc, args, cfg, contract_no, func, ns, vartab, opt),
         ast::Expression::Builtin {
             loc,
             tys,
@@ -1186,6 +1298,7 @@ fn memory_array_push(
     cfg.add(
         vartab,
         Instr::PushMemory {
+            loc: *loc,
             res: address_res,
             ty: array.ty(),
             array: array_pos,
@@ -1222,7 +1335,10 @@ fn post_incdec(
             ty: ty.as_ref().clone(),
             expr: Box::new(v),
         },
-        Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        Type::StorageRef(_, ty) => match packed_storage(var, contract_no, ns) {
+            Some(packed) => load_packed_storage(&var.loc(), &ty, v, packed, cfg, vartab, ns),
+            None => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        },
         _ => v,
     };
     cfg.add(
@@ -1288,18 +1404,24 @@ fn post_incdec(
 
             match var.ty() {
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: *loc,
+                    let value = Expression::Variable {
+                        loc: *loc,
+                        ty: ty.clone(),
+                        var_no: res,
+                    };
+
+                    if let Some(packed) = packed_storage(var, contract_no, ns) {
+                        set_packed_storage(loc, ty, Some(value), dest, packed, cfg, vartab, ns);
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
                                 ty: ty.clone(),
-                                var_no: res,
+                                storage: dest,
                             },
-                            ty: ty.clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -1346,7 +1468,10 @@ fn pre_incdec(
             ty: ty.as_ref().clone(),
             expr: Box::new(v),
         },
-        Type::StorageRef(_, ty) => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        Type::StorageRef(_, ty) => match packed_storage(var, contract_no, ns) {
+            Some(packed) => load_packed_storage(&var.loc(), &ty, v, packed, cfg, vartab, ns),
+            None => load_storage(&var.loc(), ty.as_ref(), v, cfg, vartab),
+        },
         _ => v,
     };
     let one = Box::new(Expression::NumberLiteral {
@@ -1399,18 +1524,24 @@ fn pre_incdec(
 
             match var.ty() {
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: *loc,
+                    let value = Expression::Variable {
+                        loc: *loc,
+                        ty: ty.clone(),
+                        var_no: res,
+                    };
+
+                    if let Some(packed) = packed_storage(var, contract_no, ns) {
+                        set_packed_storage(loc, ty, Some(value), dest, packed, cfg, vartab, ns);
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
                                 ty: ty.clone(),
-                                var_no: res,
+                                storage: dest,
                             },
-                            ty: ty.clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -1497,6 +1628,91 @@ fn expr_or(
     }
 }
 
+/// Shifting by the width of the value or more gives zero, or -1 for negative signed values. Sema
+/// truncates a shift amount which is wider than the value, so saturate it instead, else
+/// `uint8(1) << uint256(256)` would shift by 0.
+fn shift_amount(
+    right: &ast::Expression,
+    ty: &Type,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Expression {
+    let ast::Expression::Trunc { loc, to, expr } = right else {
+        return expression(right, cfg, contract_no, func, ns, vartab, opt);
+    };
+
+    let bits = BigInt::from(ty.bits(ns));
+    let amount = expression(expr, cfg, contract_no, func, ns, vartab, opt);
+
+    if let Expression::NumberLiteral { value, .. } = &amount {
+        return Expression::NumberLiteral {
+            loc: *loc,
+            ty: to.clone(),
+            value: if *value >= bits { bits } else { value.clone() },
+        };
+    }
+
+    let pos = vartab.temp_anonymous(to);
+    vartab.new_dirty_tracker();
+    let in_range = cfg.new_basic_block("shift_in_range".to_string());
+    let end = cfg.new_basic_block("shift_end".to_string());
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: *loc,
+            res: pos,
+            expr: Expression::NumberLiteral {
+                loc: *loc,
+                ty: to.clone(),
+                value: bits.clone(),
+            },
+        },
+    );
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::MoreEqual {
+                loc: *loc,
+                signed: false,
+                left: Box::new(amount.clone()),
+                right: Box::new(Expression::NumberLiteral {
+                    loc: *loc,
+                    ty: amount.ty(),
+                    value: bits,
+                }),
+            },
+            true_block: end,
+            false_block: in_range,
+        },
+    );
+    cfg.set_basic_block(in_range);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: *loc,
+            res: pos,
+            expr: Expression::Trunc {
+                loc: *loc,
+                ty: to.clone(),
+                expr: Box::new(amount),
+            },
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: end });
+    cfg.set_basic_block(end);
+    cfg.set_phis(end, vartab.pop_dirty_tracker());
+
+    Expression::Variable {
+        loc: *loc,
+        ty: to.clone(),
+        var_no: pos,
+    }
+}
+
 fn and(
     left: &ast::Expression,
     cfg: &mut ControlFlowGraph,
@@ -1592,8 +1808,8 @@ fn payable_send(
         &Type::Uint(32),
     );
 
-    // Ethereum can only transfer via external call
-    if ns.target == Target::EVM {
+    // Ethereum and Stylus can only transfer via external call
+    if matches!(ns.target, Target::EVM | Target::Stylus) {
         cfg.add(
             vartab,
             Instr::ExternalCall {
@@ -1623,6 +1839,26 @@ fn payable_send(
                 flags: None,
             },
         );
+
+        // On Stylus the call returns a status code, which is zero on success
+        if ns.target == Target::Stylus {
+            return Expression::Equal {
+                loc: *loc,
+                left: Expression::Variable {
+                    loc: *loc,
+                    ty: Type::Uint(32),
+                    var_no: success,
+                }
+                .into(),
+                right: Expression::NumberLiteral {
+                    loc: *loc,
+                    ty: Type::Uint(32),
+                    value: BigInt::zero(),
+                }
+                .into(),
+            };
+        }
+
         return Expression::Variable {
             loc: *loc,
             ty: Type::Bool,
@@ -1639,10 +1875,25 @@ fn payable_send(
         },
     );
 
-    if ns.target != Target::Solana {
-        polkadot::check_transfer_ret(loc, success, cfg, ns, opt, vartab, false).unwrap()
+    if ns.target == Target::Solana {
+        // On Solana the status is zero on success
+        Expression::Equal {
+            loc: *loc,
+            left: Expression::Variable {
+                loc: *loc,
+                ty: Type::Uint(32),
+                var_no: success,
+            }
+            .into(),
+            right: Expression::NumberLiteral {
+                loc: *loc,
+                ty: Type::Uint(32),
+                value: BigInt::zero(),
+            }
+            .into(),
+        }
     } else {
-        unreachable!("Value transfer does not exist on Solana");
+        polkadot::check_transfer_ret(loc, success, cfg, ns, opt, vartab, false).unwrap()
     }
 }
 
@@ -1658,8 +1909,8 @@ fn payable_transfer(
 ) -> Expression {
     let address = expression(&args[0], cfg, contract_no, func, ns, vartab, opt);
     let value = expression(&args[1], cfg, contract_no, func, ns, vartab, opt);
-    if ns.target == Target::EVM {
-        // Ethereum can only transfer via external call
+    if matches!(ns.target, Target::EVM | Target::Stylus) {
+        // Ethereum and Stylus can only transfer via external call
         cfg.add(
             vartab,
             Instr::ExternalCall {
@@ -1747,48 +1998,215 @@ fn abi_encode_packed(
     encoded
 }
 
-fn encode_many_with_selector(
+/// The address a contract is deployed at, derived from the deployer, salt and code hash following
+/// the rules of the target. If all the arguments are constant, the address is computed here.
+fn create2_address(
     loc: &pt::Loc,
-    selector: Expression,
-    mut args: Vec<Expression>,
-    ns: &Namespace,
-    vartab: &mut Vartable,
-    cfg: &mut ControlFlowGraph,
-) -> Expression {
-    let mut encoder_args: Vec<Expression> = Vec::with_capacity(args.len() + 1);
-    encoder_args.push(selector);
-    encoder_args.append(&mut args);
-    abi_encode(loc, encoder_args, ns, vartab, cfg, false).0
-}
-
-fn abi_encode_with_selector(
     args: &[ast::Expression],
     cfg: &mut ControlFlowGraph,
     contract_no: usize,
     func: Option<&Function>,
     ns: &Namespace,
     vartab: &mut Vartable,
-    loc: &pt::Loc,
     opt: &Options,
 ) -> Expression {
-    let mut args_iter = args.iter();
-    let selector = expression(
-        args_iter.next().unwrap(),
-        cfg,
-        contract_no,
-        func,
-        ns,
-        vartab,
-        opt,
-    );
-    let args = args_iter
+    let args = args
+        .iter()
         .map(|v| expression(v, cfg, contract_no, func, ns, vartab, opt))
         .collect::<Vec<Expression>>();
-    encode_many_with_selector(loc, selector, args, ns, vartab, cfg)
-}
 
-fn abi_encode_with_signature(
-    args: &[ast::Expression],
+    if let Some(address) = constant_create2_address(&args, ns) {
+        return Expression::NumberLiteral {
+            loc: *loc,
+            ty: Type::Address(false),
+            value: BigInt::from_bytes_be(Sign::Plus, &address),
+        };
+    }
+
+    let mut args = args.into_iter();
+    let deployer = args.next().unwrap();
+    let salt = args.next().unwrap();
+    let code_hash = args.next().unwrap();
+
+    if ns.target.is_polkadot() {
+        // The contracts pallet hashes the SCALE encoding of a prefix, the deployer, the code hash,
+        // the constructor input and the salt
+        let input = args.next().unwrap();
+
+        let prefix = Expression::NumberLiteral {
+            loc: Loc::Codegen,
+            ty: Type::Bytes(16),
+            value: BigInt::from_bytes_be(Sign::Plus, POLKADOT_ADDRESS_PREFIX),
+        };
+
+        let salt = Expression::BytesCast {
+            loc: Loc::Codegen,
+            ty: Type::DynamicBytes,
+            from: Type::Bytes(32),
+            expr: salt.into(),
+        };
+
+        let (preimage, _) = abi_encode(
+            loc,
+            vec![prefix, deployer, code_hash, input, salt],
+            ns,
+            vartab,
+            cfg,
+            false,
+        );
+
+        let hash = Expression::Builtin {
+            loc: *loc,
+            tys: vec![Type::Bytes(32)],
+            kind: Builtin::Blake2_256,
+            args: vec![preimage],
+        };
+
+        Expression::Cast {
+            loc: *loc,
+            ty: Type::Address(false),
+            expr: hash.into(),
+        }
+    } else {
+        let prefix = Expression::NumberLiteral {
+            loc: Loc::Codegen,
+            ty: Type::Bytes(1),
+            value: BigInt::from(0xff),
+        };
+
+        let (preimage, _) = abi_encode(
+            loc,
+            vec![prefix, deployer, salt, code_hash],
+            ns,
+            vartab,
+            cfg,
+            true,
+        );
+
+        let hash = Expression::Builtin {
+            loc: *loc,
+            tys: vec![Type::Bytes(32)],
+            kind: Builtin::Keccak256,
+            args: vec![preimage],
+        };
+
+        // the address is the last 20 bytes of the hash
+        let hash = Expression::Cast {
+            loc: *loc,
+            ty: Type::Uint(256),
+            expr: hash.into(),
+        };
+
+        let address = Expression::Trunc {
+            loc: *loc,
+            ty: Type::Uint(ns.address_length as u16 * 8),
+            expr: hash.into(),
+        };
+
+        Expression::Cast {
+            loc: *loc,
+            ty: Type::Address(false),
+            expr: address.into(),
+        }
+    }
+}
+
+const POLKADOT_ADDRESS_PREFIX: &[u8; 16] = b"contract_addr_v1";
+
+/// Compute the result of `create2_address()` if all arguments are constant
+fn constant_create2_address(args: &[Expression], ns: &Namespace) -> Option<Vec<u8>> {
+    let deployer = constant_bytes(&args[0], ns.address_length)?;
+    let salt = constant_bytes(&args[1], 32)?;
+    let code_hash = constant_bytes(&args[2], 32)?;
+
+    if ns.target.is_polkadot() {
+        let input = match &args[3] {
+            Expression::AllocDynamicBytes {
+                initializer: Some(value),
+                ..
+            }
+            | Expression::BytesLiteral { value, .. } => value.as_slice(),
+            _ => return None,
+        };
+
+        // the input and the salt are encoded with their compact length
+        let preimage = [
+            &POLKADOT_ADDRESS_PREFIX[..],
+            &deployer,
+            &code_hash,
+            &Compact(input.len() as u32).encode(),
+            input,
+            &Compact(salt.len() as u32).encode(),
+            &salt,
+        ]
+        .concat();
+
+        Some(
+            blake2_rfc::blake2b::blake2b(32, &[], &preimage)
+                .as_bytes()
+                .to_vec(),
+        )
+    } else {
+        let mut hasher = Keccak::v256();
+        hasher.update(&[0xff]);
+        hasher.update(&deployer);
+        hasher.update(&salt);
+        hasher.update(&code_hash);
+        let mut hash = [0u8; 32];
+        hasher.finalize(&mut hash);
+
+        Some(hash[32 - ns.address_length..].to_vec())
+    }
+}
+
+/// The big endian bytes of a constant value of the given length
+fn constant_bytes(expr: &Expression, len: usize) -> Option<Vec<u8>> {
+    match expr {
+        Expression::NumberLiteral { value, .. } => {
+            let (_, bs) = value.to_bytes_be();
+
+            if bs.len() > len {
+                return None;
+            }
+
+            let mut padded = vec![0u8; len - bs.len()];
+            padded.extend(bs);
+
+            Some(padded)
+        }
+        Expression::BytesLiteral { value, .. } if value.len() == len => Some(value.clone()),
+        _ => None,
+    }
+}
+
+fn abi_encode_with_selector(
+    args: &[ast::Expression],
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    loc: &pt::Loc,
+    opt: &Options,
+) -> Expression {
+    let mut args_iter = args.iter();
+    let selector = expression(
+        args_iter.next().unwrap(),
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+    let args = args_iter
+        .map(|v| expression(v, cfg, contract_no, func, ns, vartab, opt))
+        .collect::<Vec<Expression>>();
+    abi_encode_after_selector(loc, selector, args, ns, vartab, cfg).0
+}
+
+fn abi_encode_with_signature(
+    args: &[ast::Expression],
     loc: &pt::Loc,
     cfg: &mut ControlFlowGraph,
     contract_no: usize,
@@ -1815,7 +2233,7 @@ fn abi_encode_with_signature(
     let args = args_iter
         .map(|v| expression(v, cfg, contract_no, func, ns, vartab, opt))
         .collect::<Vec<Expression>>();
-    encode_many_with_selector(loc, selector, args, ns, vartab, cfg)
+    abi_encode_after_selector(loc, selector, args, ns, vartab, cfg).0
 }
 
 fn abi_encode_call(
@@ -1846,7 +2264,7 @@ fn abi_encode_call(
     let args = args_iter
         .map(|v| expression(v, cfg, contract_no, func, ns, vartab, opt))
         .collect::<Vec<Expression>>();
-    encode_many_with_selector(loc, selector, args, ns, vartab, cfg)
+    abi_encode_after_selector(loc, selector, args, ns, vartab, cfg).0
 }
 
 fn builtin_evm_gasprice(
@@ -1948,7 +2366,7 @@ fn expr_builtin(
                 vartab,
                 ns,
             );
-            let error = SolidityError::Panic(PanicCode::ArrayIndexOob);
+            let error = SolidityError::panic(PanicCode::ArrayIndexOob, opt);
             assert_failure(loc, error, ns, cfg, vartab);
 
             cfg.set_basic_block(in_bounds);
@@ -2009,7 +2427,7 @@ fn expr_builtin(
                 vartab,
                 ns,
             );
-            let error = SolidityError::Panic(PanicCode::ArrayIndexOob);
+            let error = SolidityError::panic(PanicCode::ArrayIndexOob, opt);
             assert_failure(loc, error, ns, cfg, vartab);
 
             cfg.set_basic_block(in_bounds);
@@ -2087,7 +2505,7 @@ fn expr_builtin(
                 vartab,
                 ns,
             );
-            let error = SolidityError::Panic(PanicCode::ArrayIndexOob);
+            let error = SolidityError::panic(PanicCode::ArrayIndexOob, opt);
             assert_failure(loc, error, ns, cfg, vartab);
 
             cfg.set_basic_block(in_bounds);
@@ -2099,6 +2517,108 @@ fn expr_builtin(
                 args: vec![buf, offset],
             }
         }
+        ast::Builtin::BytesSlice => {
+            let buf = expression(&args[0], cfg, contract_no, func, ns, vartab, opt);
+            let start = expression(&args[1], cfg, contract_no, func, ns, vartab, opt);
+            let end = expression(&args[2], cfg, contract_no, func, ns, vartab, opt);
+
+            let out_of_bounds = cfg.new_basic_block("out_of_bounds".to_string());
+            let start_in_bounds = cfg.new_basic_block("start_in_bounds".to_string());
+            let in_bounds = cfg.new_basic_block("in_bounds".to_string());
+
+            // range check: start <= end <= length
+            cfg.add(
+                vartab,
+                Instr::BranchCond {
+                    cond: Expression::LessEqual {
+                        loc: *loc,
+                        signed: false,
+                        left: Box::new(start.clone()),
+                        right: Box::new(end.clone()),
+                    },
+                    true_block: start_in_bounds,
+                    false_block: out_of_bounds,
+                },
+            );
+
+            cfg.set_basic_block(start_in_bounds);
+            cfg.add(
+                vartab,
+                Instr::BranchCond {
+                    cond: Expression::LessEqual {
+                        loc: *loc,
+                        signed: false,
+                        left: Box::new(end.clone()),
+                        right: Box::new(Expression::Builtin {
+                            loc: *loc,
+                            tys: vec![Type::Uint(32)],
+                            kind: Builtin::ArrayLength,
+                            args: vec![buf.clone()],
+                        }),
+                    },
+                    true_block: in_bounds,
+                    false_block: out_of_bounds,
+                },
+            );
+
+            cfg.set_basic_block(out_of_bounds);
+            log_runtime_error(
+                opt.log_runtime_errors,
+                "slice out of bounds",
+                *loc,
+                cfg,
+                vartab,
+                ns,
+            );
+            let error = SolidityError::panic(PanicCode::ArrayIndexOob, opt);
+            assert_failure(loc, error, ns, cfg, vartab);
+
+            cfg.set_basic_block(in_bounds);
+
+            let size = Expression::Subtract {
+                loc: *loc,
+                ty: Type::Uint(32),
+                overflowing: true,
+                left: Box::new(end),
+                right: Box::new(start.clone()),
+            };
+
+            let res = vartab.temp_anonymous(&tys[0]);
+
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res,
+                    expr: Expression::AllocDynamicBytes {
+                        loc: *loc,
+                        ty: tys[0].clone(),
+                        size: Box::new(size.clone()),
+                        initializer: None,
+                    },
+                },
+            );
+
+            let slice = Expression::Variable {
+                loc: *loc,
+                ty: tys[0].clone(),
+                var_no: res,
+            };
+
+            cfg.add(
+                vartab,
+                Instr::MemCopy {
+                    source: Expression::AdvancePointer {
+                        pointer: Box::new(buf),
+                        bytes_offset: Box::new(start),
+                    },
+                    destination: slice.clone(),
+                    bytes: size,
+                },
+            );
+
+            slice
+        }
         ast::Builtin::AddMod | ast::Builtin::MulMod => {
             let arguments: Vec<Expression> = args
                 .iter()
@@ -2396,19 +2916,16 @@ fn checking_trunc(
     vartab: &mut Vartable,
     opt: &Options,
 ) -> Expression {
-    let bits = match ty {
-        Type::Uint(bits) => *bits as u32,
-        Type::Value => ns.value_length as u32 * 8,
+    let (bits, signed) = match ty {
+        Type::Uint(bits) => (*bits as u32, false),
+        Type::Int(bits) => (*bits as u32, true),
+        Type::Value => (ns.value_length as u32 * 8, false),
         _ => unreachable!(),
     };
 
     let source_ty = expr.ty();
-
-    let overflow = Expression::NumberLiteral {
-        loc: *loc,
-        ty: source_ty.clone(),
-        value: BigInt::from(2u32).pow(bits),
-    };
+    let source_bits = source_ty.bits(ns) as u32;
+    let source_signed = source_ty.is_signed_int(ns);
 
     let pos = vartab.temp(
         &pt::Identifier {
@@ -2429,49 +2946,104 @@ fn checking_trunc(
         },
     );
 
-    let out_of_bounds = cfg.new_basic_block("out_of_bounds".to_string());
-    let in_bounds = cfg.new_basic_block("in_bounds".to_string());
+    let value = Expression::Variable {
+        loc: *loc,
+        ty: source_ty.clone(),
+        var_no: pos,
+    };
 
-    cfg.add(
-        vartab,
-        Instr::BranchCond {
-            cond: Expression::MoreEqual {
+    let mut out_of_range = Vec::new();
+
+    // the smallest value which does not fit
+    if source_bits - u32::from(source_signed) > bits - u32::from(signed) {
+        out_of_range.push(Expression::MoreEqual {
+            loc: *loc,
+            signed: source_signed,
+            left: Box::new(value.clone()),
+            right: Box::new(Expression::NumberLiteral {
                 loc: *loc,
-                signed: false,
-                left: Box::new(Expression::Variable {
-                    loc: *loc,
-                    ty: source_ty.clone(),
-                    var_no: pos,
-                }),
-                right: Box::new(overflow),
-            },
-            true_block: out_of_bounds,
-            false_block: in_bounds,
-        },
-    );
+                ty: source_ty.clone(),
+                value: BigInt::from(2u32).pow(bits - u32::from(signed)),
+            }),
+        });
+    }
 
-    cfg.set_basic_block(out_of_bounds);
-    log_runtime_error(
-        opt.log_runtime_errors,
-        "truncated type overflows",
-        *loc,
-        cfg,
-        vartab,
-        ns,
-    );
-    let error = SolidityError::Panic(PanicCode::MathOverflow);
-    assert_failure(loc, error, ns, cfg, vartab);
+    // the largest negative value which does not fit
+    if source_signed && (!signed || source_bits > bits) {
+        out_of_range.push(Expression::Less {
+            loc: *loc,
+            signed: true,
+            left: Box::new(value.clone()),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: source_ty.clone(),
+                value: if signed {
+                    -BigInt::from(2u32).pow(bits - 1)
+                } else {
+                    BigInt::zero()
+                },
+            }),
+        });
+    }
 
-    cfg.set_basic_block(in_bounds);
+    if !out_of_range.is_empty() {
+        let out_of_bounds = cfg.new_basic_block("out_of_bounds".to_string());
 
-    Expression::Trunc {
-        loc: *loc,
-        ty: ty.clone(),
-        expr: Box::new(Expression::Variable {
+        for cond in out_of_range {
+            let in_bounds = cfg.new_basic_block("in_bounds".to_string());
+
+            cfg.add(
+                vartab,
+                Instr::BranchCond {
+                    cond,
+                    true_block: out_of_bounds,
+                    false_block: in_bounds,
+                },
+            );
+
+            cfg.set_basic_block(in_bounds);
+        }
+
+        let in_bounds = cfg.current_block();
+
+        cfg.set_basic_block(out_of_bounds);
+        log_runtime_error(
+            opt.log_runtime_errors,
+            "truncated type overflows",
+            *loc,
+            cfg,
+            vartab,
+            ns,
+        );
+        let error = SolidityError::panic(PanicCode::MathOverflow, opt);
+        assert_failure(loc, error, ns, cfg, vartab);
+
+        cfg.set_basic_block(in_bounds);
+    }
+
+    let expr = Box::new(value);
+
+    match source_bits.cmp(&bits) {
+        Ordering::Greater => Expression::Trunc {
             loc: *loc,
-            ty: source_ty,
-            var_no: pos,
-        }),
+            ty: ty.clone(),
+            expr,
+        },
+        Ordering::Less if source_signed => Expression::SignExt {
+            loc: *loc,
+            ty: ty.clone(),
+            expr,
+        },
+        Ordering::Less => Expression::ZeroExt {
+            loc: *loc,
+            ty: ty.clone(),
+            expr,
+        },
+        Ordering::Equal => Expression::Cast {
+            loc: *loc,
+            ty: ty.clone(),
+            expr,
+        },
     }
 }
 
@@ -2494,7 +3066,112 @@ fn format_string(
             )
         })
         .collect();
-    Expression::FormatString { loc: *loc, args }
+    Expression::FormatString {
+        loc: *loc,
+        args: merge_format_segments(args),
+    }
+}
+
+/// Turn the arguments of a format string which are known at compile time into string literals,
+/// and merge adjacent string literals. The constant parts of the string are then emitted as a
+/// single segment of static data, rather than formatted at runtime.
+pub(super) fn merge_format_segments(
+    args: Vec<(FormatArg, Expression)>,
+) -> Vec<(FormatArg, Expression)> {
+    let mut segments: Vec<(FormatArg, Expression)> = Vec::new();
+
+    for (spec, arg) in args {
+        let Some(literal) = format_literal(spec, &arg) else {
+            segments.push((spec, arg));
+            continue;
+        };
+
+        match segments.last_mut() {
+            Some((FormatArg::StringLiteral, Expression::BytesLiteral { ty, value, .. })) => {
+                // segments which are not all strings are printed as hex in the cfg
+                if !(*ty == Type::String
+                    && (spec != FormatArg::StringLiteral || arg.ty() == Type::String))
+                {
+                    *ty = Type::DynamicBytes;
+                }
+
+                value.extend(literal);
+            }
+            _ if spec == FormatArg::StringLiteral => segments.push((spec, arg)),
+            _ => segments.push((
+                FormatArg::StringLiteral,
+                Expression::BytesLiteral {
+                    loc: arg.loc(),
+                    ty: Type::String,
+                    value: literal,
+                },
+            )),
+        }
+    }
+
+    segments
+}
+
+/// The formatted text of a format string argument, if it is a constant. This must match the
+/// output of the formatting functions in stdlib/format.c.
+fn format_literal(spec: FormatArg, arg: &Expression) -> Option<Vec<u8>> {
+    let text = match (spec, arg) {
+        (FormatArg::StringLiteral, Expression::BytesLiteral { value, .. })
+        | (
+            FormatArg::Default,
+            Expression::BytesLiteral {
+                ty: Type::String,
+                value,
+                ..
+            },
+        ) => return Some(value.clone()),
+        (FormatArg::Default, Expression::BoolLiteral { value, .. }) => value.to_string(),
+        (
+            FormatArg::Default,
+            Expression::NumberLiteral {
+                ty: Type::Uint(_) | Type::Int(_) | Type::Enum(_),
+                value,
+                ..
+            },
+        ) => value.to_string(),
+        // bytesN is hex encoded with leading zeros
+        (
+            FormatArg::Default,
+            Expression::NumberLiteral {
+                ty: Type::Bytes(size),
+                value,
+                ..
+            },
+        ) => format!("{:0>1$}", value.to_str_radix(16), *size as usize * 2),
+        (
+            FormatArg::Hex,
+            Expression::NumberLiteral {
+                ty: Type::Uint(_) | Type::Int(_),
+                value,
+                ..
+            },
+        ) => {
+            let sign = if value.sign() == Sign::Minus { "-" } else { "" };
+
+            format!("{sign}0x{}", value.magnitude().to_str_radix(16))
+        }
+        // zero is formatted as just "0b" at runtime
+        (
+            FormatArg::Binary,
+            Expression::NumberLiteral {
+                ty: Type::Uint(_) | Type::Int(_),
+                value,
+                ..
+            },
+        ) if !value.is_zero() => {
+            let sign = if value.sign() == Sign::Minus { "-" } else { "" };
+
+            format!("{sign}0b{}", value.magnitude().to_str_radix(2))
+        }
+        _ => return None,
+    };
+
+    Some(text.into_bytes())
 }
 
 fn conditional_operator(
@@ -2626,6 +3303,10 @@ pub fn assign_single(
             }
         }
         _ => {
+            // assigning to a value in an enumerable mapping adds its key
+            let inserted = enumerable_mapping_insert(left, cfg, contract_no, func, ns, vartab, opt);
+            let left = inserted.as_ref().unwrap_or(left);
+
             let left_ty = left.ty();
             let ty = cfg_right.ty();
 
@@ -2684,18 +3365,33 @@ pub fn assign_single(
                     }
                 }
                 Type::StorageRef(..) => {
-                    cfg.add(
-                        vartab,
-                        Instr::SetStorage {
-                            value: Expression::Variable {
-                                loc: left.loc(),
-                                ty: ty.clone(),
-                                var_no: pos,
+                    let value = Expression::Variable {
+                        loc: left.loc(),
+                        ty: ty.clone(),
+                        var_no: pos,
+                    };
+
+                    if let Some(packed) = packed_storage(left, contract_no, ns) {
+                        set_packed_storage(
+                            &left.loc(),
+                            ty.deref_any(),
+                            Some(value),
+                            dest,
+                            packed,
+                            cfg,
+                            vartab,
+                            ns,
+                        );
+                    } else {
+                        cfg.add(
+                            vartab,
+                            Instr::SetStorage {
+                                value,
+                                ty: ty.deref_any().clone(),
+                                storage: dest,
                             },
-                            ty: ty.deref_any().clone(),
-                            storage: dest,
-                        },
-                    );
+                        );
+                    }
                 }
                 Type::Ref(_) => {
                     cfg.add(
@@ -2733,14 +3429,19 @@ pub fn emit_function_call(
     opt: &Options,
 ) -> Vec<Expression> {
     match expr {
-        ast::Expression::InternalFunctionCall { function, args, .. } => {
+        ast::Expression::InternalFunctionCall {
+            loc,
+            function,
+            args,
+            ..
+        } => {
             if let ast::Expression::InternalFunction {
                 function_no,
                 signature,
                 ..
             } = function.as_ref()
             {
-                let args = args
+                let mut args: Vec<Expression> = args
                     .iter()
                     .map(|a| expression(a, cfg, caller_contract_no, func, ns, vartab, opt))
                     .collect();
@@ -2756,7 +3457,33 @@ pub fn emit_function_call(
 
                 let ftype = &ns.functions[function_no];
 
-                let call = if ns.functions[function_no].loc_prototype == pt::Loc::Builtin {
+                // Some Polkadot builtins are lowered to existing instructions and expressions
+                if ns.target.is_polkadot() && ftype.loc_prototype == pt::Loc::Builtin {
+                    match ftype.id.name.as_str() {
+                        "terminate" => {
+                            let recipient = args.swap_remove(0);
+                            cfg.add(vartab, Instr::SelfDestruct { recipient });
+
+                            // terminate does not return; anything following it is unreachable
+                            let unreachable = cfg.new_basic_block("terminated".into());
+                            cfg.set_basic_block(unreachable);
+
+                            return vec![Expression::Poison];
+                        }
+                        "minimum_balance" => {
+                            return vec![Expression::Builtin {
+                                loc: *loc,
+                                tys: vec![Type::Value],
+                                kind: Builtin::MinimumBalance,
+                                args: Vec::new(),
+                            }];
+                        }
+                        _ => (),
+                    }
+                }
+
+                // Syscalls are provided by the chain, like builtins
+                let call = if ftype.loc_prototype == pt::Loc::Builtin || ftype.syscall.is_some() {
                     InternalCallTy::Builtin {
                         ast_func_no: function_no,
                     }
@@ -2923,7 +3650,7 @@ pub fn emit_function_call(
                 },
             );
 
-            let success = if ns.target.is_polkadot() {
+            let success = if ns.target.is_polkadot() || ns.target == Target::Stylus {
                 let ret_code = Expression::Variable {
                     loc: *loc,
                     ty: Type::Uint(32),
@@ -3009,7 +3736,11 @@ pub fn emit_function_call(
                     },
                 );
 
-                let (payload, _) = abi_encode(loc, args, ns, vartab, cfg, false);
+                // the selector is not annotated
+                let annotations = iter::once(None)
+                    .chain(dest_func.params.iter().map(Parameter::scale_annotation))
+                    .collect::<Vec<_>>();
+                let (payload, _) = abi_encode_payload(loc, args, &annotations, ns, vartab, cfg);
 
                 let flags = call_args
                     .flags
@@ -3053,14 +3784,21 @@ pub fn emit_function_call(
                         .iter()
                         .map(|e| e.ty.clone())
                         .collect::<Vec<Type>>();
-                    abi_decode(
+                    let annotations = dest_func
+                        .returns
+                        .iter()
+                        .map(Parameter::scale_annotation)
+                        .collect::<Vec<_>>();
+                    abi_decode_annotated(
                         loc,
                         &Expression::ReturnData { loc: *loc },
                         &tys,
+                        &annotations,
                         ns,
                         vartab,
                         cfg,
                         None,
+                        opt,
                     )
                 } else {
                     vec![Expression::Poison]
@@ -3097,7 +3835,7 @@ pub fn emit_function_call(
                 tys.insert(0, Type::Bytes(ns.target.selector_length()));
                 args.insert(0, selector);
 
-                let (payload, _) = abi_encode(loc, args, ns, vartab, cfg, false);
+                let (payload, _) = abi_encode_payload(loc, args, &[], ns, vartab, cfg);
 
                 let flags = call_args
                     .flags
@@ -3142,6 +3880,7 @@ pub fn emit_function_call(
                         vartab,
                         cfg,
                         None,
+                        opt,
                     )
                 } else {
                     vec![Expression::Poison]
@@ -3157,7 +3896,26 @@ pub fn emit_function_call(
             args,
         } => {
             let data = expression(&args[0], cfg, caller_contract_no, func, ns, vartab, opt);
-            abi_decode(loc, &data, tys, ns, vartab, cfg, None)
+            abi_decode(loc, &data, tys, ns, vartab, cfg, None, opt)
+        }
+        ast::Expression::Builtin {
+            loc,
+            tys,
+            kind: ast::Builtin::AbiEthDecode,
+            args,
+        } => {
+            let data = expression(&args[0], cfg, caller_contract_no, func, ns, vartab, opt);
+            abi_decode_with(
+                Encoding::Ethereum,
+                loc,
+                &data,
+                tys,
+                ns,
+                vartab,
+                cfg,
+                None,
+                opt,
+            )
         }
         _ => unreachable!(),
     }
@@ -3168,7 +3926,7 @@ pub fn default_gas(ns: &Namespace) -> Expression {
         loc: pt::Loc::Codegen,
         ty: Type::Uint(64),
         // See EIP150
-        value: if ns.target == Target::EVM {
+        value: if matches!(ns.target, Target::EVM | Target::Stylus) {
             BigInt::from(i64::MAX)
         } else {
             BigInt::zero()
@@ -3204,7 +3962,7 @@ fn array_subscript(
         let array = expression(array, cfg, contract_no, func, ns, vartab, opt);
         let index = expression(index, cfg, contract_no, func, ns, vartab, opt);
 
-        return if ns.target == Target::Solana {
+        return if matches!(ns.target, Target::Solana | Target::Soroban) {
             Expression::Subscript {
                 loc: *loc,
                 ty: elem_ty.clone(),
@@ -3244,7 +4002,7 @@ fn array_subscript(
         Type::Array(..) => match array_ty.array_length() {
             None => {
                 if let Type::StorageRef(..) = array_ty {
-                    if ns.target == Target::Solana {
+                    if matches!(ns.target, Target::Solana | Target::Soroban) {
                         Expression::StorageArrayLength {
                             loc: *loc,
                             ty: ns.storage_type(),
@@ -3369,7 +4127,7 @@ fn array_subscript(
         vartab,
         ns,
     );
-    let error = SolidityError::Panic(PanicCode::ArrayIndexOob);
+    let error = SolidityError::panic(PanicCode::ArrayIndexOob, opt);
     assert_failure(loc, error, ns, cfg, vartab);
 
     cfg.set_basic_block(in_bounds);
@@ -3495,6 +4253,22 @@ fn array_subscript(
                     }
                 }
             }
+        } else if ns.target == Target::Soroban {
+            // Every array element has its own contract data key
+            Expression::Subscript {
+                loc: *loc,
+                ty: elem_ty,
+                array_ty: array_ty.clone(),
+                expr: Box::new(array),
+                index: Box::new(
+                    Expression::Variable {
+                        loc: index_loc,
+                        ty: coerced_ty,
+                        var_no: pos,
+                    }
+                    .cast(&slot_ty, ns),
+                ),
+            }
         } else {
             let elem_size = elem_ty.storage_slots(ns);
 
@@ -3762,12 +4536,12 @@ fn add_prefix_and_delimiter_to_print(mut expr: Expression) -> Expression {
 
         Expression::FormatString {
             loc: *loc,
-            args: new_vec,
+            args: merge_format_segments(new_vec),
         }
     } else {
         Expression::FormatString {
             loc: Loc::Codegen,
-            args: vec![
+            args: merge_format_segments(vec![
                 (
                     FormatArg::StringLiteral,
                     Expression::BytesLiteral {
@@ -3785,7 +4559,7 @@ fn add_prefix_and_delimiter_to_print(mut expr: Expression) -> Expression {
                         value: delimiter.to_vec(),
                     },
                 ),
-            ],
+            ]),
         }
     }
 }
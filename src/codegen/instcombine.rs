@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::reaching_definitions;
+use crate::codegen::Expression;
+use std::collections::{HashMap, HashSet};
+
+/// Combine the instructions which compute the condition of a branch with the branch:
+///
+/// - A comparison which is assigned to a variable that is only used by the branch right after it,
+///   like `%c = a < b; branchcond %c`, becomes the condition of the branch, so the variable is
+///   never written.
+/// - A branch on a boolean not branches on the operand instead, with the blocks swapped.
+pub(super) fn instcombine(cfg: &mut ControlFlowGraph) {
+    let mut uses = HashMap::new();
+
+    for block in &cfg.blocks {
+        for instr in &block.instr {
+            instr.recurse_expressions(&mut uses, count_uses);
+        }
+    }
+
+    // variables in phis are read when the block is entered
+    let phis: HashSet<usize> = cfg
+        .blocks
+        .iter()
+        .filter_map(|block| block.phis.as_ref())
+        .flatten()
+        .copied()
+        .collect();
+
+    let mut changed = false;
+
+    for block in &mut cfg.blocks {
+        let len = block.instr.len();
+
+        if len >= 2 {
+            if let [Instr::Set { res, expr, .. }, Instr::BranchCond {
+                cond: Expression::Variable { var_no, .. },
+                ..
+            }] = &block.instr[len - 2..]
+            {
+                if res == var_no
+                    && uses.get(var_no) == Some(&1)
+                    && !phis.contains(var_no)
+                    && is_condition(expr)
+                {
+                    let expr = expr.clone();
+
+                    if let Some(Instr::BranchCond { cond, .. }) = block.instr.last_mut() {
+                        *cond = expr;
+                    }
+
+                    block.instr.remove(len - 2);
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some(Instr::BranchCond {
+            cond,
+            true_block,
+            false_block,
+        }) = block.instr.last_mut()
+        {
+            while let Expression::Not { expr, .. } = cond {
+                *cond = expr.as_ref().clone();
+
+                std::mem::swap(true_block, false_block);
+            }
+        }
+    }
+
+    if changed {
+        for block in &mut cfg.blocks {
+            block.defs.clear();
+            block.transfers.clear();
+            block.loop_reaching_variables.clear();
+        }
+
+        reaching_definitions::find(cfg);
+    }
+}
+
+fn count_uses(expr: &Expression, uses: &mut HashMap<usize, usize>) -> bool {
+    if let Expression::Variable { var_no, .. } = expr {
+        *uses.entry(*var_no).or_default() += 1;
+    }
+
+    true
+}
+
+/// Is the expression a comparison, or the negation of one
+fn is_condition(expr: &Expression) -> bool {
+    match expr {
+        Expression::Less { .. }
+        | Expression::LessEqual { .. }
+        | Expression::More { .. }
+        | Expression::MoreEqual { .. }
+        | Expression::Equal { .. }
+        | Expression::NotEqual { .. } => true,
+        Expression::Not { expr, .. } => is_condition(expr),
+        _ => false,
+    }
+}
@@ -0,0 +1,439 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ASTFunction, ControlFlowGraph, Instr};
+use super::reaching_definitions::{self, Transfer};
+use super::storage_cache::find_loops;
+use crate::codegen::Expression;
+use crate::sema::ast::{Namespace, Type};
+use crate::sema::diagnostics::Diagnostics;
+use crate::Target;
+use num_bigint::BigInt;
+use num_traits::One;
+use solang_parser::diagnostics::Diagnostic;
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashMap};
+
+/// The tag which sets the most iterations of the loops of a function which are unrolled. The
+/// code size budget does not apply to these loops, and `@custom:unroll 0` disables unrolling.
+pub const UNROLL_TAG: &str = "custom:unroll";
+
+/// Loops which run more often than this are not unrolled
+const MAX_TRIP_COUNT: usize = 16;
+
+/// The most instructions an unrolled loop may have. Solana programs are limited by compute
+/// units, so the branches saved are worth more than on wasm, where the contract code is stored
+/// on chain and a larger contract costs more to deploy.
+fn size_budget(target: Target) -> usize {
+    match target {
+        Target::Solana => 256,
+        _ => 128,
+    }
+}
+
+/// Loops with a constant number of iterations which is small, like the initialization of a
+/// fixed-size array or the building of seeds, are unrolled fully. The loop must count a variable
+/// from a constant to a constant with a constant step, and the variable is not changed anywhere
+/// else in the loop. Each iteration is a copy of the loop body, in which the loop variable is a
+/// constant that can be folded into the array indexes, seeds and comparisons of that iteration.
+///
+/// Inner loops are unrolled first, and the loop around them may then be unrolled as well if it
+/// still fits in the budget. Returns true if any loop was unrolled.
+pub fn loop_unroll(cfg: &mut ControlFlowGraph, ns: &Namespace) -> bool {
+    let (max_trips, budget) = match unroll_tag(cfg, ns) {
+        Some(Ok(max_trips)) => (max_trips, usize::MAX),
+        Some(Err(_)) | None => (MAX_TRIP_COUNT, size_budget(ns.target)),
+    };
+
+    if max_trips == 0 {
+        return false;
+    }
+
+    let mut changed = false;
+
+    // Unrolling a loop changes the body of the loops around it, so find the loops again
+    // after each one
+    'outer: loop {
+        for (header, body) in find_loops(cfg).into_iter().rev() {
+            let Some(unrollable) = unrollable(cfg, header, &body, max_trips) else {
+                continue;
+            };
+
+            let size: usize = body
+                .iter()
+                .map(|block_no| cfg.blocks[*block_no].instr.len())
+                .sum();
+
+            if size.saturating_mul(unrollable.trips) > budget {
+                continue;
+            }
+
+            unroll(cfg, header, &body, unrollable);
+            changed = true;
+
+            continue 'outer;
+        }
+
+        break;
+    }
+
+    if changed {
+        // New blocks were added, so the reaching definitions need to be calculated again for
+        // the passes which follow
+        for block in &mut cfg.blocks {
+            block.defs.clear();
+            block.transfers.clear();
+            block.loop_reaching_variables.clear();
+        }
+
+        reaching_definitions::find(cfg);
+    }
+
+    changed
+}
+
+/// Give an error for each function with an invalid unroll tag
+pub fn check_unroll_tags(ns: &mut Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for function_no in 0..ns.functions.len() {
+        if let Some(Err(diagnostic)) = function_unroll_tag(function_no, ns) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The number of iterations set by the unroll tag of the function of a cfg, if it has one
+fn unroll_tag(cfg: &ControlFlowGraph, ns: &Namespace) -> Option<Result<usize, Diagnostic>> {
+    match cfg.function_no {
+        ASTFunction::SolidityFunction(function_no) => function_unroll_tag(function_no, ns),
+        _ => None,
+    }
+}
+
+fn function_unroll_tag(function_no: usize, ns: &Namespace) -> Option<Result<usize, Diagnostic>> {
+    let tag = ns.functions[function_no]
+        .tags
+        .iter()
+        .find(|tag| tag.tag == UNROLL_TAG)?;
+
+    Some(tag.value.trim().parse().map_err(|_| {
+        Diagnostic::error(
+            tag.loc,
+            format!(
+                "invalid unroll count '{}', expected a number of iterations",
+                tag.value
+            ),
+        )
+    }))
+}
+
+/// A loop which can be unrolled
+struct Unrollable {
+    /// The number of times the body of the loop runs
+    trips: usize,
+    /// The block in the loop the header branches to
+    first: usize,
+    /// The block after the loop
+    exit: usize,
+}
+
+/// Can this loop be unrolled, and how many times does it run
+fn unrollable(
+    cfg: &ControlFlowGraph,
+    header: usize,
+    body: &BTreeSet<usize>,
+    max_trips: usize,
+) -> Option<Unrollable> {
+    // the header only has the condition of the loop
+    let [Instr::BranchCond {
+        cond,
+        true_block,
+        false_block,
+    }] = cfg.blocks[header].instr.as_slice()
+    else {
+        return None;
+    };
+
+    let (first, exit, continue_if) = match (body.contains(true_block), body.contains(false_block)) {
+        (true, false) => (*true_block, *false_block, true),
+        (false, true) => (*false_block, *true_block, false),
+        _ => return None,
+    };
+
+    let var_no = loop_variable(cond)?;
+
+    let (min, max) = match &cfg.vars[&var_no].ty {
+        Type::Uint(bits) => (BigInt::from(0), BigInt::one() << *bits),
+        Type::Int(bits) => (
+            -(BigInt::one() << (*bits - 1)),
+            BigInt::one() << (*bits - 1),
+        ),
+        _ => return None,
+    };
+
+    let mut outside = Vec::new();
+    let mut latches = Vec::new();
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        if block.successors().contains(&header) {
+            if body.contains(&block_no) {
+                latches.push(block_no);
+            } else {
+                outside.push(block_no);
+            }
+        }
+    }
+
+    // the loop variable is set to a constant just before the loop
+    let [preheader] = outside.as_slice() else {
+        return None;
+    };
+
+    let Instr::Set {
+        expr: Expression::NumberLiteral { value: init, .. },
+        ..
+    } = last_def(cfg, *preheader, cfg.blocks[*preheader].instr.len(), var_no)?
+    else {
+        return None;
+    };
+
+    // The loop variable is changed once in each iteration, on the way back to the header. The
+    // latch has no other successor, so it cannot be part of a loop inside this loop.
+    let [latch] = latches.as_slice() else {
+        return None;
+    };
+
+    if cfg.blocks[*latch].successors() != [header] {
+        return None;
+    }
+
+    let mut defs = body.iter().flat_map(|block_no| {
+        cfg.blocks[*block_no]
+            .transfers
+            .iter()
+            .enumerate()
+            .filter(|(_, transfers)| defines(transfers, var_no))
+            .map(|(instr_no, _)| (*block_no, instr_no))
+    });
+
+    let (Some((def_block, def_instr)), None) = (defs.next(), defs.next()) else {
+        return None;
+    };
+
+    if def_block != *latch {
+        return None;
+    }
+
+    let step = step(cfg, *latch, def_instr, var_no)?;
+
+    let mut value = init.clone();
+    let mut trips = 0;
+
+    loop {
+        if compare(cond, var_no, &value)? != continue_if {
+            break;
+        }
+
+        trips += 1;
+
+        if trips > max_trips {
+            return None;
+        }
+
+        value += &step;
+
+        // the increment would overflow
+        if value < min || value >= max {
+            return None;
+        }
+    }
+
+    // a loop which never runs is left for the unreachable code to be removed
+    if trips == 0 {
+        return None;
+    }
+
+    Some(Unrollable { trips, first, exit })
+}
+
+/// The variable the condition of the loop compares with a constant
+fn loop_variable(cond: &Expression) -> Option<usize> {
+    let (left, right) = match cond {
+        Expression::Less { left, right, .. }
+        | Expression::LessEqual { left, right, .. }
+        | Expression::More { left, right, .. }
+        | Expression::MoreEqual { left, right, .. }
+        | Expression::Equal { left, right, .. }
+        | Expression::NotEqual { left, right, .. } => (left, right),
+        _ => return None,
+    };
+
+    match (left.as_ref(), right.as_ref()) {
+        (Expression::Variable { var_no, .. }, Expression::NumberLiteral { .. })
+        | (Expression::NumberLiteral { .. }, Expression::Variable { var_no, .. }) => Some(*var_no),
+        _ => None,
+    }
+}
+
+/// Evaluate the condition of the loop for a value of the loop variable
+fn compare(cond: &Expression, var_no: usize, value: &BigInt) -> Option<bool> {
+    let operand = |expr: &Expression| match expr {
+        Expression::Variable { var_no: no, .. } if *no == var_no => Some(value.clone()),
+        Expression::NumberLiteral { value, .. } => Some(value.clone()),
+        _ => None,
+    };
+
+    let (ordering, left, right) = match cond {
+        Expression::Less { left, right, .. } => (&[Ordering::Less][..], left, right),
+        Expression::LessEqual { left, right, .. } => {
+            (&[Ordering::Less, Ordering::Equal][..], left, right)
+        }
+        Expression::More { left, right, .. } => (&[Ordering::Greater][..], left, right),
+        Expression::MoreEqual { left, right, .. } => {
+            (&[Ordering::Greater, Ordering::Equal][..], left, right)
+        }
+        Expression::Equal { left, right, .. } => (&[Ordering::Equal][..], left, right),
+        Expression::NotEqual { left, right, .. } => {
+            (&[Ordering::Less, Ordering::Greater][..], left, right)
+        }
+        _ => return None,
+    };
+
+    Some(ordering.contains(&operand(left)?.cmp(&operand(right)?)))
+}
+
+/// The constant the loop variable changes by in each iteration, from the instruction which
+/// sets it. This is either `i = i + 1`, or `t = i; i = t + 1` for a postfix increment.
+fn step(cfg: &ControlFlowGraph, block_no: usize, instr_no: usize, var_no: usize) -> Option<BigInt> {
+    let Instr::Set { res, expr, .. } = &cfg.blocks[block_no].instr[instr_no] else {
+        return None;
+    };
+
+    if *res != var_no {
+        return None;
+    }
+
+    let (src, step) = match expr {
+        Expression::Add { left, right, .. } => match (left.as_ref(), right.as_ref()) {
+            (Expression::Variable { var_no, .. }, Expression::NumberLiteral { value, .. })
+            | (Expression::NumberLiteral { value, .. }, Expression::Variable { var_no, .. }) => {
+                (*var_no, value.clone())
+            }
+            _ => return None,
+        },
+        Expression::Subtract { left, right, .. } => match (left.as_ref(), right.as_ref()) {
+            (Expression::Variable { var_no, .. }, Expression::NumberLiteral { value, .. }) => {
+                (*var_no, -value)
+            }
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if src == var_no {
+        return Some(step);
+    }
+
+    // the temporary must be a copy of the loop variable
+    match last_def(cfg, block_no, instr_no, src)? {
+        Instr::Set {
+            expr: Expression::Variable { var_no: no, .. },
+            ..
+        } if *no == var_no => Some(step),
+        _ => None,
+    }
+}
+
+/// The last instruction before `instr_no` in a block which defines a variable
+fn last_def(
+    cfg: &ControlFlowGraph,
+    block_no: usize,
+    instr_no: usize,
+    var_no: usize,
+) -> Option<&Instr> {
+    let block = &cfg.blocks[block_no];
+
+    (0..instr_no)
+        .rev()
+        .find(|instr_no| defines(&block.transfers[*instr_no], var_no))
+        .map(|instr_no| &block.instr[instr_no])
+}
+
+/// Does the instruction with these transfers define or modify the variable
+fn defines(transfers: &[Transfer], var_no: usize) -> bool {
+    transfers.iter().any(|transfer| match transfer {
+        Transfer::Gen { var_no: no, .. }
+        | Transfer::Copy { var_no: no, .. }
+        | Transfer::Mod { var_no: no }
+        | Transfer::Kill { var_no: no } => *no == var_no,
+    })
+}
+
+/// Replace the loop with a copy of its body for each iteration. The first iteration uses the
+/// blocks of the loop. The header of each iteration branches straight into the body, and the
+/// body branches to the header of the next iteration; after the last one, to the exit.
+fn unroll(cfg: &mut ControlFlowGraph, header: usize, body: &BTreeSet<usize>, loop_: Unrollable) {
+    let mut iterations: Vec<HashMap<usize, usize>> = vec![body.iter().map(|b| (*b, *b)).collect()];
+
+    for _ in 1..loop_.trips {
+        let iteration = body
+            .iter()
+            .map(|block_no| {
+                cfg.blocks.push(cfg.blocks[*block_no].clone());
+
+                (*block_no, cfg.blocks.len() - 1)
+            })
+            .collect();
+
+        iterations.push(iteration);
+    }
+
+    // after the last iteration, the header leaves the loop
+    let mut done = cfg.blocks[header].clone();
+    done.instr = vec![Instr::Branch { block: loop_.exit }];
+    done.transfers = vec![Vec::new()];
+    cfg.blocks.push(done);
+
+    let done = cfg.blocks.len() - 1;
+
+    for (no, iteration) in iterations.iter().enumerate() {
+        let next = iterations.get(no + 1).map_or(done, |next| next[&header]);
+
+        cfg.blocks[iteration[&header]].instr = vec![Instr::Branch { block: loop_.first }];
+
+        for block_no in iteration.values() {
+            retarget(cfg.blocks[*block_no].instr.last_mut().unwrap(), |block| {
+                if block == header {
+                    next
+                } else {
+                    iteration.get(&block).copied().unwrap_or(block)
+                }
+            });
+        }
+    }
+}
+
+/// Change the blocks an instruction branches to
+fn retarget(instr: &mut Instr, target: impl Fn(usize) -> usize) {
+    match instr {
+        Instr::Branch { block } => *block = target(*block),
+        Instr::BranchCond {
+            true_block,
+            false_block,
+            ..
+        } => {
+            *true_block = target(*true_block);
+            *false_block = target(*false_block);
+        }
+        Instr::Switch { cases, default, .. } => {
+            *default = target(*default);
+
+            for (_, block) in cases {
+                *block = target(*block);
+            }
+        }
+        _ => (),
+    }
+}
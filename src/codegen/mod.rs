@@ -4,21 +4,30 @@ mod array_boundary;
 pub mod cfg;
 mod constant_folding;
 mod constructor;
+mod coverage;
 mod dead_storage;
+mod devirtualize;
 pub(crate) mod dispatch;
 pub(crate) mod encoding;
-mod events;
+pub(crate) mod events;
 mod expression;
+mod instcombine;
+mod loop_unroll;
 pub(super) mod polkadot;
+#[cfg(feature = "llvm")]
+pub(crate) mod reachable;
 mod reaching_definitions;
+mod reentrancy;
 pub mod revert;
 mod solana_accounts;
 mod solana_deploy;
 mod statements;
 mod storage;
+mod storage_cache;
 mod strength_reduce;
 pub(crate) mod subexpression_elimination;
 mod tests;
+mod trace;
 mod undefined_variable;
 mod unused_variable;
 pub(crate) mod vartable;
@@ -30,6 +39,7 @@ use self::{
     dispatch::function_dispatch,
     expression::expression,
     solana_accounts::account_collection::collect_accounts_from_contract,
+    storage::set_packed_storage,
     vartable::Vartable,
 };
 use crate::sema::ast::{
@@ -37,12 +47,17 @@ use crate::sema::ast::{
 };
 use crate::{sema::ast, Target};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet};
 
+use crate::abi::anchor::account_discriminator;
 use crate::codegen::cfg::ASTFunction;
 use crate::codegen::solana_accounts::account_management::manage_contract_accounts;
 use crate::codegen::yul::generate_yul_function_cfg;
+use crate::lir::heap::check_heap_sizes;
+use crate::lir::stack::check_stack_budgets;
 use crate::sema::diagnostics::Diagnostics;
 use crate::sema::eval::eval_const_number;
+use crate::sema::storage_usage;
 use crate::sema::Recurse;
 #[cfg(feature = "wasm_opt")]
 use contract_build::OptimizationPasses;
@@ -94,6 +109,17 @@ impl From<inkwell::OptimizationLevel> for OptimizationLevel {
     }
 }
 
+/// How memory is allocated on the heap of Solana programs
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub enum HeapAllocator {
+    /// Allocate upwards from the start of the heap; memory is only reclaimed when the most recent
+    /// allocation is freed
+    #[default]
+    Bump,
+    /// Keep a list of chunks, so that freed memory can be reused
+    FreeList,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Options {
     pub dead_storage: bool,
@@ -101,10 +127,42 @@ pub struct Options {
     pub strength_reduce: bool,
     pub vector_to_slice: bool,
     pub common_subexpression_elimination: bool,
+    /// Keep the storage slots which are accessed in a loop in variables while the loop runs
+    pub storage_cache: bool,
+    /// Lower chains of comparisons of a string with literals to a switch on the hash of the string
+    pub string_switch: bool,
+    /// Unroll loops which run a small, constant number of times
+    pub loop_unroll: bool,
+    /// Move the code which reverts out of the functions, after the code which runs when they
+    /// succeed
+    pub cold_split: bool,
+    /// Start the data accounts of contracts on Solana with the Anchor account discriminator,
+    /// rather than the 4 byte magic number of the legacy layout
+    pub account_discriminator: bool,
+    /// Leave out the private storage variables which are never read, or never written, from the
+    /// storage layout
+    pub strip_dead_storage: bool,
     pub generate_debug_information: bool,
+    /// Emit the DWARF line tables without the rest of the debug information, e.g. for source maps
+    pub generate_line_tables: bool,
     pub opt_level: OptimizationLevel,
     pub log_runtime_errors: bool,
     pub log_prints: bool,
+    /// Return `Panic(uint256)` revert data, and the `Error(string)` reason of decoding failures,
+    /// when a check inserted by the compiler fails. Otherwise, these checks revert without data.
+    pub revert_data: bool,
+    /// Count how often each basic block runs, and report the counters in the logs
+    pub coverage: bool,
+    /// Print the arguments and return values of calls, and the storage writes, of all functions
+    /// which are not annotated with `@trace(false)`
+    pub trace: bool,
+    /// Run this pipeline of llvm passes instead of the default one, in the syntax of
+    /// `opt -passes`
+    pub llvm_passes: Option<String>,
+    /// The inline threshold for the default pipeline of llvm passes
+    pub inline_threshold: Option<u32>,
+    /// The heap allocator on Solana
+    pub heap_allocator: HeapAllocator,
     #[cfg(feature = "wasm_opt")]
     pub wasm_opt: Option<OptimizationPasses>,
 }
@@ -117,16 +175,66 @@ impl Default for Options {
             strength_reduce: true,
             vector_to_slice: true,
             common_subexpression_elimination: true,
+            storage_cache: true,
+            string_switch: true,
+            loop_unroll: true,
+            cold_split: true,
+            account_discriminator: true,
+            strip_dead_storage: false,
             generate_debug_information: false,
+            generate_line_tables: false,
             opt_level: OptimizationLevel::Default,
             log_runtime_errors: false,
             log_prints: true,
+            revert_data: true,
+            coverage: false,
+            trace: false,
+            llvm_passes: None,
+            inline_threshold: None,
+            heap_allocator: HeapAllocator::Bump,
             #[cfg(feature = "wasm_opt")]
             wasm_opt: None,
         }
     }
 }
 
+/// The passes in a pipeline of llvm passes, like `mem2reg,function(instcombine),globaldce`. Passes
+/// can have nested pipelines and parameters, so only the commas outside of brackets separate
+/// passes.
+pub fn llvm_pipeline_passes(pipeline: &str) -> Result<Vec<&str>, String> {
+    let mut passes = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (offset, c) in pipeline.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| format!("unbalanced '{c}' in llvm pipeline '{pipeline}'"))?;
+            }
+            ',' if depth == 0 => {
+                passes.push(pipeline[start..offset].trim());
+                start = offset + 1;
+            }
+            _ => (),
+        }
+    }
+
+    if depth != 0 {
+        return Err(format!("unbalanced brackets in llvm pipeline '{pipeline}'"));
+    }
+
+    passes.push(pipeline[start..].trim());
+
+    if passes.iter().any(|pass| pass.is_empty()) {
+        return Err(format!("empty pass in llvm pipeline '{pipeline}'"));
+    }
+
+    Ok(passes)
+}
+
 /// The contracts are fully resolved but they do not have any CFGs which is needed for
 /// the llvm code emitter. This will also do additional code checks.
 pub fn codegen(ns: &mut Namespace, opt: &Options) {
@@ -162,6 +270,8 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
             contract(contract_no, ns, opt);
 
             if ns.diagnostics.any_errors() {
+                // the same expression may have been folded more than once
+                ns.diagnostics.dedup();
                 return;
             }
 
@@ -170,6 +280,8 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
     }
 
     if ns.target == Target::Solana {
+        note_immutables_in_account_data(ns);
+
         for contract_no in 0..ns.contracts.len() {
             if ns.contracts[contract_no].instantiable {
                 let diag = collect_accounts_from_contract(contract_no, ns);
@@ -182,13 +294,27 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
                 manage_contract_accounts(contract_no, ns);
             }
         }
+
+        check_heap_sizes(ns);
+    }
+
+    loop_unroll::check_unroll_tags(ns);
+
+    if !ns.diagnostics.any_errors() {
+        check_stack_budgets(ns);
     }
+
     ns.diagnostics.sort_and_dedup();
 }
 
 fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
     if !ns.diagnostics.any_errors() && ns.contracts[contract_no].instantiable {
-        layout(contract_no, ns);
+        layout(contract_no, ns, opt);
+
+        if ns.target == Target::Solana && opt.account_discriminator {
+            ns.contracts[contract_no].account_discriminator =
+                Some(account_discriminator(&ns.contracts[contract_no].id.name));
+        }
 
         let mut cfg_no = 0;
         let mut all_cfg = Vec::new();
@@ -252,6 +378,10 @@ fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
             all_cfg.push(dispatch_cfg);
         }
 
+        if opt.coverage {
+            coverage::instrument(contract_no, &mut all_cfg, ns);
+        }
+
         ns.contracts[contract_no].cfg = all_cfg;
     }
 }
@@ -276,14 +406,27 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
 
             let value = expression(init, &mut cfg, contract_no, None, ns, &mut vartab, opt);
 
-            cfg.add(
-                &mut vartab,
-                Instr::SetStorage {
-                    value,
-                    ty: var.ty.clone(),
+            if let Some(packed) = layout.packed {
+                set_packed_storage(
+                    &pt::Loc::Codegen,
+                    &var.ty,
+                    Some(value),
                     storage,
-                },
-            );
+                    packed,
+                    &mut cfg,
+                    &mut vartab,
+                    ns,
+                );
+            } else {
+                cfg.add(
+                    &mut vartab,
+                    Instr::SetStorage {
+                        value,
+                        ty: var.ty.clone(),
+                        storage,
+                    },
+                );
+            }
         }
     }
 
@@ -297,40 +440,83 @@ fn storage_initializer(contract_no: usize, ns: &mut Namespace, opt: &Options) ->
 }
 
 /// Layout the contract. We determine the layout of variables and deal with overriding variables
-fn layout(contract_no: usize, ns: &mut Namespace) {
-    let mut slot = if ns.target == Target::Solana {
-        BigInt::from(SOLANA_FIRST_OFFSET)
+fn layout(contract_no: usize, ns: &mut Namespace, opt: &Options) {
+    let stripped = if opt.strip_dead_storage {
+        strippable_variables(contract_no, ns)
     } else {
-        BigInt::zero()
+        Vec::new()
     };
 
-    for base_contract_no in ns.contract_bases(contract_no) {
-        for var_no in 0..ns.contracts[base_contract_no].variables.len() {
-            if !ns.contracts[base_contract_no].variables[var_no].constant {
-                let ty = ns.contracts[base_contract_no].variables[var_no].ty.clone();
+    let immutables_in_code = if ns.target.immutables_in_code() {
+        constant_immutables(contract_no, ns)
+    } else {
+        BTreeMap::new()
+    };
 
-                if ns.target == Target::Solana {
-                    // elements need to be aligned on solana
-                    let alignment = ty.align_of(ns);
+    let vars: Vec<(usize, usize, Type)> = ns
+        .contract_bases(contract_no)
+        .into_iter()
+        .flat_map(|base_contract_no| {
+            ns.contracts[base_contract_no]
+                .variables
+                .iter()
+                .enumerate()
+                .filter(|(_, var)| !var.constant)
+                .map(move |(var_no, var)| (base_contract_no, var_no, var.ty.clone()))
+        })
+        .filter(|(base_contract_no, var_no, _)| {
+            let var = (*base_contract_no, *var_no);
 
-                    let offset = slot.clone() % alignment;
+            !stripped.contains(&var) && !immutables_in_code.contains_key(&var)
+        })
+        .collect();
 
-                    if offset > BigInt::zero() {
-                        slot += alignment - offset;
-                    }
-                }
+    ns.contracts[contract_no].stripped_variables = stripped;
+    ns.contracts[contract_no].immutables_in_code = immutables_in_code;
 
-                ns.contracts[contract_no].layout.push(Layout {
-                    slot: slot.clone(),
-                    contract_no: base_contract_no,
-                    var_no,
-                    ty: ty.clone(),
-                });
+    let slot = if ns.target == Target::Solana {
+        let mut slot = BigInt::from(SOLANA_FIRST_OFFSET);
+
+        for (base_contract_no, var_no, ty) in vars {
+            // elements need to be aligned on solana
+            let alignment = ty.align_of(ns);
+
+            let offset = slot.clone() % alignment;
 
-                slot += ty.storage_slots(ns);
+            if offset > BigInt::zero() {
+                slot += alignment - offset;
             }
+
+            let size = ty.storage_slots(ns);
+
+            ns.contracts[contract_no].layout.push(Layout {
+                slot: slot.clone(),
+                contract_no: base_contract_no,
+                var_no,
+                ty,
+                packed: None,
+            });
+
+            slot += size;
+        }
+
+        slot
+    } else {
+        // small variables next to each other share a slot
+        let (packing, slots) = ns.pack_storage(vars.iter().map(|(_, _, ty)| Some(ty)));
+
+        for ((base_contract_no, var_no, ty), (slot, packed)) in vars.into_iter().zip(packing) {
+            ns.contracts[contract_no].layout.push(Layout {
+                slot,
+                contract_no: base_contract_no,
+                var_no,
+                ty,
+                packed,
+            });
         }
-    }
+
+        slots
+    };
 
     let constructors = ns.contracts[contract_no].constructors(ns);
     if !constructors.is_empty() {
@@ -356,6 +542,131 @@ fn layout(contract_no: usize, ns: &mut Namespace) {
     ns.contracts[contract_no].fixed_layout_size = slot;
 }
 
+/// The private storage variables which are never read or never written, and which can be left out
+/// of the layout without changing what the contract does. The reads of such a variable give its
+/// default value, so it must be a value type. An initializer is not evaluated once the variable is
+/// left out, so it must be a literal.
+fn strippable_variables(contract_no: usize, ns: &Namespace) -> Vec<(usize, usize)> {
+    storage_usage::dead_storage(contract_no, ns)
+        .into_iter()
+        .filter(|(var_contract_no, var_no, _)| {
+            let var = &ns.contracts[*var_contract_no].variables[*var_no];
+
+            matches!(var.visibility, pt::Visibility::Private(_))
+                && !var.immutable
+                && matches!(
+                    var.ty,
+                    Type::Bool
+                        | Type::Int(_)
+                        | Type::Uint(_)
+                        | Type::Bytes(_)
+                        | Type::Enum(_)
+                        | Type::Value
+                )
+                && matches!(
+                    var.initializer,
+                    None | Some(
+                        ast::Expression::BoolLiteral { .. }
+                            | ast::Expression::NumberLiteral { .. }
+                            | ast::Expression::BytesLiteral { .. }
+                    )
+                )
+        })
+        .map(|(var_contract_no, var_no, _)| (var_contract_no, var_no))
+        .collect()
+}
+
+/// The immutable variables whose value is a compile-time constant, with that value. This is
+/// either the initializer of an immutable which is never assigned, or the value which is assigned
+/// to it by the constructor. The value is the same for every instance of the contract, so it can
+/// be part of the code, and the assignment in the constructor does nothing. The value of an
+/// immutable which depends on the arguments of the constructor is only known when the contract is
+/// deployed; since the code of a contract is stored once and shared by all its instances, these
+/// stay in storage.
+fn constant_immutables(
+    contract_no: usize,
+    ns: &Namespace,
+) -> BTreeMap<(usize, usize), ast::Expression> {
+    let Some(written) = storage_usage::written_storage(contract_no, ns) else {
+        return BTreeMap::new();
+    };
+
+    let mut immutables: BTreeMap<(usize, usize), ast::Expression> = ns
+        .contract_bases(contract_no)
+        .into_iter()
+        .flat_map(|base_contract_no| {
+            ns.contracts[base_contract_no]
+                .variables
+                .iter()
+                .enumerate()
+                .map(move |(var_no, var)| ((base_contract_no, var_no), var))
+        })
+        .filter(|(var_no, var)| var.immutable && !written.contains(var_no))
+        .filter_map(|(var_no, var)| {
+            let init = var.initializer.as_ref()?;
+
+            is_constant(init, &var.ty, ns).then(|| (var_no, init.clone()))
+        })
+        .collect();
+
+    for ((var_contract_no, var_no), value) in
+        storage_usage::constructor_assigned_immutables(contract_no, ns)
+    {
+        let var = &ns.contracts[var_contract_no].variables[var_no];
+
+        // the initializer is not evaluated if the immutable is part of the code
+        if is_constant(value, &var.ty, ns)
+            && var
+                .initializer
+                .as_ref()
+                .map_or(true, |init| is_constant(init, &var.ty, ns))
+        {
+            immutables.insert((var_contract_no, var_no), value.clone());
+        }
+    }
+
+    immutables
+}
+
+/// Is the expression a compile-time constant
+fn is_constant(expr: &ast::Expression, ty: &Type, ns: &Namespace) -> bool {
+    match expr {
+        ast::Expression::BoolLiteral { .. }
+        | ast::Expression::BytesLiteral { .. }
+        | ast::Expression::NumberLiteral { .. } => true,
+        _ => {
+            matches!(ty, Type::Int(_) | Type::Uint(_))
+                && eval_const_number(expr, ns, &mut Diagnostics::default()).is_ok()
+        }
+    }
+}
+
+/// On Solana, immutable variables are stored in the account data like any other variable. Each
+/// immutable is noted once, even if it is inherited by more than one contract.
+fn note_immutables_in_account_data(ns: &mut Namespace) {
+    let mut noted = HashSet::new();
+
+    for contract_no in 0..ns.contracts.len() {
+        if !ns.contracts[contract_no].instantiable {
+            continue;
+        }
+
+        for base_contract_no in ns.contract_bases(contract_no) {
+            for (var_no, var) in ns.contracts[base_contract_no].variables.iter().enumerate() {
+                if var.immutable && noted.insert((base_contract_no, var_no)) {
+                    ns.diagnostics.push(Diagnostic::info(
+                        var.loc,
+                        format!(
+                            "immutable variable '{}' is stored in the account data on Solana",
+                            var.name
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+}
+
 trait LLVMName {
     fn llvm_symbol(&self, ns: &Namespace) -> String;
 }
@@ -466,6 +777,8 @@ pub enum Expression {
     SignedDivide {
         loc: pt::Loc,
         ty: Type,
+        /// Wrap `type(int).min / -1` rather than checking for overflow
+        overflowing: bool,
         left: Box<Expression>,
         right: Box<Expression>,
     },
@@ -1385,11 +1698,13 @@ impl Expression {
                 Expression::SignedDivide {
                     loc,
                     ty,
+                    overflowing,
                     left,
                     right,
                 } => Expression::SignedDivide {
                     loc: *loc,
                     ty: ty.clone(),
+                    overflowing: *overflowing,
                     left: Box::new(filter(left, ctx)),
                     right: Box::new(filter(right, ctx)),
                 },
@@ -1733,6 +2048,9 @@ pub enum Builtin {
     ExtCodeSize,
     MinimumBalance,
     MulMod,
+    MulDiv,
+    Sqrt,
+    Log2,
     Keccak256,
     Origin,
     ReadFromBuffer,
@@ -1742,6 +2060,13 @@ pub enum Builtin {
     Sha256,
     Signature,
     SignatureVerify,
+    /// ReturnDataSlice is the return data of the last external call as a slice, which may point
+    /// to the return data buffer of the runtime. It is only valid until the next host function
+    /// call.
+    ReturnDataSlice,
+    /// SliceFromBuffer creates a slice of the given number of elements which points into a
+    /// buffer at an offset, without copying the data.
+    SliceFromBuffer,
     Timestamp,
     Value,
     WriteAddress,
@@ -1758,6 +2083,11 @@ pub enum Builtin {
     WriteUint256LE,
     WriteBytes,
     Concat,
+    /// IndexOf returns the offset of the first occurrence of the second argument in the first
+    /// as an int64, or -1 if there is none.
+    IndexOf,
+    /// ParseInt parses a decimal string into an int256, and reverts if it is not valid.
+    ParseInt,
 }
 
 impl From<&ast::Builtin> for Builtin {
@@ -1780,6 +2110,9 @@ impl From<&ast::Builtin> for Builtin {
             ast::Builtin::GetAddress => Builtin::GetAddress,
             ast::Builtin::MinimumBalance => Builtin::MinimumBalance,
             ast::Builtin::MulMod => Builtin::MulMod,
+            ast::Builtin::MulDiv => Builtin::MulDiv,
+            ast::Builtin::Sqrt => Builtin::Sqrt,
+            ast::Builtin::Log2 => Builtin::Log2,
             ast::Builtin::Keccak256 => Builtin::Keccak256,
             ast::Builtin::Origin => Builtin::Origin,
             ast::Builtin::ReadAddress
@@ -1820,6 +2153,8 @@ impl From<&ast::Builtin> for Builtin {
             ast::Builtin::PrevRandao => Builtin::PrevRandao,
             ast::Builtin::ContractCode => Builtin::ContractCode,
             ast::Builtin::StringConcat | ast::Builtin::BytesConcat => Builtin::Concat,
+            ast::Builtin::IndexOf => Builtin::IndexOf,
+            ast::Builtin::ParseInt => Builtin::ParseInt,
             _ => panic!("Builtin should not be in the cfg"),
         }
     }
@@ -0,0 +1,64 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ASTFunction, Instr, InternalCallTy};
+use super::Expression;
+use crate::sema::ast::Contract;
+use crate::sema::Recurse;
+
+/// Find the cfgs of a contract which can run, so that the others are left out of the binary.
+///
+/// The entry points are the public functions and the functions generated by the compiler, like
+/// the dispatch and the storage initializer. From there, every function which is called or whose
+/// pointer is taken can run. Virtual calls are resolved to the overriding function during codegen,
+/// so a function of a base contract which is overridden is not reachable, nor are the functions
+/// of libraries which are never called. Contracts which are only created by unreachable functions
+/// are not embedded in the binary either.
+pub(crate) fn reachable_cfgs(contract: &Contract) -> Vec<bool> {
+    let mut reachable = vec![false; contract.cfg.len()];
+
+    let mut work: Vec<usize> = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| cfg.public || cfg.function_no == ASTFunction::None)
+        .map(|(cfg_no, _)| cfg_no)
+        .collect();
+
+    while let Some(cfg_no) = work.pop() {
+        if reachable[cfg_no] {
+            continue;
+        }
+
+        reachable[cfg_no] = true;
+
+        for instr in contract.cfg[cfg_no]
+            .blocks
+            .iter()
+            .flat_map(|block| &block.instr)
+        {
+            match instr {
+                Instr::Call {
+                    call: InternalCallTy::Static { cfg_no },
+                    ..
+                } => work.push(*cfg_no),
+                Instr::Call {
+                    call: InternalCallTy::Dynamic(expr),
+                    ..
+                } => expr.recurse(&mut work, function_pointer),
+                _ => (),
+            }
+
+            instr.recurse_expressions(&mut work, function_pointer);
+        }
+    }
+
+    reachable
+}
+
+fn function_pointer(expr: &Expression, work: &mut Vec<usize>) -> bool {
+    if let Expression::InternalFunctionCfg { cfg_no, .. } = expr {
+        work.push(*cfg_no);
+    }
+
+    true
+}
@@ -143,7 +143,7 @@ fn instr_transfers(block_no: usize, block: &BasicBlock) -> Vec<Vec<Transfer>> {
 
                 v
             }
-            Instr::PopMemory { array, .. } => {
+            Instr::PopMemory { array, .. } | Instr::ReserveMemory { array, .. } => {
                 vec![Transfer::Mod { var_no: *array }]
             }
             Instr::ExternalCall {
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::revert::{assert_failure, log_runtime_error, SolidityError};
+use super::vartable::Vartable;
+use super::{Expression, Options};
+use crate::sema::ast::{Function, Namespace, Type};
+use num_bigint::{BigInt, Sign};
+use solang_parser::pt::Loc;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Is the function guarded against reentrant calls by `@nonreentrant(true)`
+pub(super) fn is_guarded(func: &Function) -> bool {
+    func.nonreentrant.is_some_and(|(_, guarded)| guarded)
+}
+
+/// All the guarded functions of a contract share one flag, stored in the slot
+/// `keccak256("solang.reentrancy.guard")` so that it does not collide with the state variables.
+fn guard_slot(ns: &Namespace) -> Expression {
+    let mut hasher = Keccak::v256();
+    hasher.update(b"solang.reentrancy.guard");
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+
+    Expression::NumberLiteral {
+        loc: Loc::Codegen,
+        ty: ns.storage_type(),
+        value: BigInt::from_bytes_be(Sign::Plus, &hash),
+    }
+}
+
+/// Revert if a guarded function of the contract is already running, else set the flag. This is
+/// generated at the entry of the function, before any of its modifiers run.
+pub(super) fn enter(
+    func: &Function,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    ns: &Namespace,
+    opt: &Options,
+) {
+    let loc = func.nonreentrant.map_or(func.loc_prototype, |(loc, _)| loc);
+    let storage = guard_slot(ns);
+    let entered = vartab.temp_name("reentrancy_guard", &Type::Bool);
+
+    cfg.add(
+        vartab,
+        Instr::LoadStorage {
+            res: entered,
+            ty: Type::Bool,
+            storage: storage.clone(),
+        },
+    );
+
+    let reentered = cfg.new_basic_block("reentered".to_string());
+    let guarded = cfg.new_basic_block("guarded".to_string());
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::Variable {
+                loc: Loc::Codegen,
+                ty: Type::Bool,
+                var_no: entered,
+            },
+            true_block: reentered,
+            false_block: guarded,
+        },
+    );
+
+    cfg.set_basic_block(reentered);
+
+    let reason = "reentrant call";
+
+    log_runtime_error(opt.log_runtime_errors, reason, loc, cfg, vartab, ns);
+
+    let error = if opt.revert_data {
+        SolidityError::String(Expression::AllocDynamicBytes {
+            loc: Loc::Codegen,
+            ty: Type::String,
+            size: Expression::NumberLiteral {
+                loc: Loc::Codegen,
+                ty: Type::Uint(32),
+                value: reason.len().into(),
+            }
+            .into(),
+            initializer: Some(reason.as_bytes().to_vec()),
+        })
+    } else {
+        SolidityError::Empty
+    };
+
+    assert_failure(&loc, error, ns, cfg, vartab);
+
+    cfg.set_basic_block(guarded);
+
+    cfg.add(
+        vartab,
+        Instr::SetStorage {
+            ty: Type::Bool,
+            value: Expression::BoolLiteral {
+                loc: Loc::Codegen,
+                value: true,
+            },
+            storage,
+        },
+    );
+}
+
+/// Clear the flag before each return of the function, including a `return()` from assembly.
+/// Nothing needs to be done when the function reverts, since the flag is set in storage and the
+/// storage changes are discarded. When the flag is set by the first modifier, the function body
+/// and the other modifiers return to it, so they only clear the flag when assembly returns from
+/// the call.
+pub(super) fn exit(cfg: &mut ControlFlowGraph, sets_guard: bool, ns: &Namespace) {
+    let storage = guard_slot(ns);
+
+    for block in &mut cfg.blocks {
+        let mut instrs = Vec::with_capacity(block.instr.len() + 1);
+
+        for instr in block.instr.drain(..) {
+            let clear = match instr {
+                Instr::Return { .. } => sets_guard,
+                Instr::ReturnData { .. } => true,
+                _ => false,
+            };
+
+            if clear {
+                instrs.push(Instr::ClearStorage {
+                    ty: Type::Bool,
+                    storage: storage.clone(),
+                });
+            }
+
+            instrs.push(instr);
+        }
+
+        block.instr = instrs;
+    }
+}
@@ -3,7 +3,7 @@
 //! Releated to code that ultimately compiles to the target
 //! equivalent instruction of EVM revert (0xfd).
 
-use super::encoding::{abi_encode, create_encoder};
+use super::encoding::{abi_encode_after_selector, const_encode_with_selector};
 use super::expression::expression;
 use super::Options;
 use super::{
@@ -21,7 +21,6 @@ use crate::Target;
 use num_bigint::{BigInt, Sign};
 use parse_display::Display;
 use solang_parser::pt::{CodeLocation, Loc, Loc::Codegen};
-use tiny_keccak::{Hasher, Keccak};
 
 /// Signature of `Keccak256('Error(string)')[:4]`
 pub(crate) const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
@@ -48,6 +47,16 @@ pub enum SolidityError {
 }
 
 impl SolidityError {
+    /// The error of a check inserted by the compiler, like an overflow or array bounds check.
+    /// Without revert data, the check reverts with empty error data.
+    pub(super) fn panic(code: PanicCode, opt: &Options) -> Self {
+        if opt.revert_data {
+            Self::Panic(code)
+        } else {
+            Self::Empty
+        }
+    }
+
     /// Return the selector expression of the error.
     pub fn selector_expression(&self, ns: &Namespace) -> Expression {
         Expression::NumberLiteral {
@@ -63,15 +72,7 @@ impl SolidityError {
             Self::Empty => unreachable!("empty return data has no selector"),
             Self::String(_) => ERROR_SELECTOR,
             Self::Panic(_) => PANIC_SELECTOR,
-            Self::Custom { error_no, .. } => {
-                let mut buf = [0u8; 32];
-                let mut hasher = Keccak::v256();
-                let signature =
-                    ns.signature(&ns.errors[*error_no].name, &ns.errors[*error_no].fields);
-                hasher.update(signature.as_bytes());
-                hasher.finalize(&mut buf);
-                [buf[0], buf[1], buf[2], buf[3]]
-            }
+            Self::Custom { error_no, .. } => ns.errors[*error_no].selector(ns),
         }
     }
 
@@ -88,9 +89,8 @@ impl SolidityError {
         match self {
             Self::Empty => None,
             Self::String(expr) => {
-                let args = vec![self.selector_expression(ns), expr.clone()];
-                create_encoder(ns, false)
-                    .const_encode(&args)
+                let args = vec![expr.clone()];
+                const_encode_with_selector(self.selector_expression(ns), &args, ns)
                     .map(|bytes| {
                         let size = Expression::NumberLiteral {
                             loc: Codegen,
@@ -104,13 +104,16 @@ impl SolidityError {
                             initializer: bytes.into(),
                         }
                     })
-                    .or_else(|| abi_encode(loc, args, ns, vartab, cfg, false).0.into())
+                    .or_else(|| {
+                        let selector = self.selector_expression(ns);
+                        abi_encode_after_selector(loc, selector, args, ns, vartab, cfg)
+                            .0
+                            .into()
+                    })
             }
             Self::Custom { exprs, .. } => {
-                let mut args = exprs.to_owned();
-                args.insert(0, self.selector_expression(ns));
-                create_encoder(ns, false)
-                    .const_encode(&args)
+                let args = exprs.to_owned();
+                const_encode_with_selector(self.selector_expression(ns), &args, ns)
                     .map(|bytes| {
                         let size = Expression::NumberLiteral {
                             loc: Codegen,
@@ -124,7 +127,12 @@ impl SolidityError {
                             initializer: bytes.into(),
                         }
                     })
-                    .or_else(|| abi_encode(loc, args, ns, vartab, cfg, false).0.into())
+                    .or_else(|| {
+                        let selector = self.selector_expression(ns);
+                        abi_encode_after_selector(loc, selector, args, ns, vartab, cfg)
+                            .0
+                            .into()
+                    })
             }
             Self::Panic(code) => {
                 let code = Expression::NumberLiteral {
@@ -132,21 +140,19 @@ impl SolidityError {
                     ty: Type::Uint(256),
                     value: (*code as u8).into(),
                 };
-                create_encoder(ns, false)
-                    .const_encode(&[self.selector_expression(ns), code])
-                    .map(|bytes| {
-                        let size = Expression::NumberLiteral {
-                            loc: Codegen,
-                            ty: Type::Uint(32),
-                            value: bytes.len().into(),
-                        };
-                        Expression::AllocDynamicBytes {
-                            loc: Codegen,
-                            ty: Type::Slice(Type::Bytes(1).into()),
-                            size: size.into(),
-                            initializer: bytes.into(),
-                        }
-                    })
+                const_encode_with_selector(self.selector_expression(ns), &[code], ns).map(|bytes| {
+                    let size = Expression::NumberLiteral {
+                        loc: Codegen,
+                        ty: Type::Uint(32),
+                        value: bytes.len().into(),
+                    };
+                    Expression::AllocDynamicBytes {
+                        loc: Codegen,
+                        ty: Type::Slice(Type::Bytes(1).into()),
+                        size: size.into(),
+                        initializer: bytes.into(),
+                    }
+                })
             }
         }
     }
@@ -177,6 +183,28 @@ pub enum PanicCode {
     InternalFunctionUninitialized = 0x51,
 }
 
+impl TryFrom<u8> for PanicCode {
+    type Error = u8;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        [
+            Self::Generic,
+            Self::Assertion,
+            Self::MathOverflow,
+            Self::DivisionByZero,
+            Self::EnumCastOob,
+            Self::StorageBytesEncodingIncorrect,
+            Self::EmptyArrayPop,
+            Self::ArrayIndexOob,
+            Self::OutOfMemory,
+            Self::InternalFunctionUninitialized,
+        ]
+        .into_iter()
+        .find(|panic| *panic as u8 == code)
+        .ok_or(code)
+    }
+}
+
 /// This function encodes the arguments for the assert-failure instruction
 /// and inserts it in the CFG.
 pub(super) fn assert_failure(
@@ -225,7 +253,7 @@ pub(super) fn expr_assert(
         vartab,
         ns,
     );
-    let error = SolidityError::Panic(PanicCode::Assertion);
+    let error = SolidityError::panic(PanicCode::Assertion, opt);
     assert_failure(&Codegen, error, ns, cfg, vartab);
     cfg.set_basic_block(true_);
     Expression::Poison
@@ -444,6 +472,16 @@ mod tests {
         assert_eq!(0x51, PanicCode::InternalFunctionUninitialized as u8);
     }
 
+    #[test]
+    fn panic_code_from_byte() {
+        assert_eq!(Ok(PanicCode::MathOverflow), PanicCode::try_from(0x11));
+        assert_eq!(
+            Ok(PanicCode::InternalFunctionUninitialized),
+            PanicCode::try_from(0x51)
+        );
+        assert_eq!(Err(0x02), PanicCode::try_from(0x02));
+    }
+
     #[test]
     fn default_error_selector_expression() {
         let ns = Namespace::new(Target::default_polkadot());
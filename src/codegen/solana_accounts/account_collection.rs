@@ -208,6 +208,7 @@ fn check_instruction(instr: &Instr, data: &mut RecurseData) {
         | Instr::BranchCond { cond: expr, .. }
         | Instr::PopStorage { storage: expr, .. }
         | Instr::SelfDestruct { recipient: expr }
+        | Instr::ReserveMemory { size: expr, .. }
         | Instr::Set { expr, .. } => {
             expr.recurse(data, check_expression);
         }
@@ -441,7 +442,10 @@ fn check_instruction(instr: &Instr, data: &mut RecurseData) {
             }
         }
 
-        Instr::ValueTransfer { .. } => unreachable!("Value transfer does not exist on Solana"),
+        Instr::ValueTransfer { address, value, .. } => {
+            address.recurse(data, check_expression);
+            value.recurse(data, check_expression);
+        }
         Instr::AccountAccess { .. } => (),
     }
 }
@@ -9,8 +9,8 @@ use crate::codegen::solana_accounts::account_management::{
     account_meta_literal, retrieve_key_from_account_info,
 };
 use crate::sema::ast::{
-    self, ArrayLength, CallTy, ExternalCallAccounts, Function, FunctionAttributes, Namespace,
-    StructType,
+    self, ArrayLength, CallTy, Contract, ExternalCallAccounts, Function, FunctionAttributes,
+    Namespace, StructType,
 };
 use crate::sema::diagnostics::Diagnostics;
 use crate::sema::eval::eval_const_number;
@@ -30,7 +30,7 @@ const EXEMPTION_THRESHOLD: u64 = 2;
 /// Special code for Solana constructors like creating the account
 ///
 /// On Solana, prepare the data account after deploy; ensure the account is
-/// large enough and write magic to it to show the account has been deployed.
+/// large enough and write the account discriminator to it to show the account has been deployed.
 pub(super) fn solana_deploy(
     func: &Function,
     constructor_args: &[Expression],
@@ -624,16 +624,18 @@ pub(super) fn solana_deploy(
 
     cfg.set_basic_block(account_ok);
 
-    // Write contract magic number to offset 0
+    // Write the account discriminator, or the contract magic number, to offset 0
+    let (ty, magic) = account_magic(contract);
+
     cfg.add(
         vartab,
         Instr::SetStorage {
-            ty: Type::Uint(32),
             value: Expression::NumberLiteral {
                 loc: Loc::Codegen,
-                ty: Type::Uint(64),
-                value: BigInt::from(contract.selector()),
+                ty: ty.clone(),
+                value: magic,
             },
+            ty,
             storage: Expression::NumberLiteral {
                 loc: Loc::Codegen,
                 ty: Type::Uint(64),
@@ -666,3 +668,17 @@ pub(super) fn solana_deploy(
         },
     );
 }
+
+/// The value at the start of the data account, which shows that the account belongs to the
+/// contract: the 8 byte Anchor account discriminator, or the 4 byte magic number of the contract
+/// with the legacy layout. The value is zero before the contract is deployed.
+pub(super) fn account_magic(contract: &Contract) -> (Type, BigInt) {
+    if let Some(discriminator) = &contract.account_discriminator {
+        (
+            Type::Uint(64),
+            BigInt::from_bytes_le(Sign::Plus, discriminator),
+        )
+    } else {
+        (Type::Uint(32), BigInt::from(contract.selector()))
+    }
+}
@@ -5,6 +5,7 @@ use super::{
     events::new_event_emitter,
     expression::{assign_single, emit_function_call, expression},
     revert::revert,
+    storage::{enumerable_mapping_remove, packed_storage, set_packed_storage},
     unused_variable::{
         should_remove_assignment, should_remove_variable, SideEffectsCheckParameters,
     },
@@ -22,6 +23,7 @@ use num_bigint::BigInt;
 use num_traits::Zero;
 use solang_parser::pt::{self, CodeLocation, Loc, Loc::Codegen};
 
+mod string_switch;
 mod try_catch;
 
 /// Resolve a statement, which might be a block of statements or an entire body of a function
@@ -214,15 +216,24 @@ pub(crate) fn statement(
             let _ = expression(expr, cfg, contract_no, Some(func), ns, vartab, opt);
         }
         Statement::Delete(_, ty, expr) => {
+            // deleting a value from an enumerable mapping removes its key
+            let removed =
+                enumerable_mapping_remove(expr, cfg, contract_no, Some(func), ns, vartab, opt);
+            let expr = removed.as_ref().unwrap_or(expr);
+
             let var_expr = expression(expr, cfg, contract_no, Some(func), ns, vartab, opt);
 
-            cfg.add(
-                vartab,
-                Instr::ClearStorage {
-                    ty: ty.clone(),
-                    storage: var_expr,
-                },
-            );
+            if let Some(packed) = packed_storage(expr, contract_no, ns) {
+                set_packed_storage(&expr.loc(), ty, None, var_expr, packed, cfg, vartab, ns);
+            } else {
+                cfg.add(
+                    vartab,
+                    Instr::ClearStorage {
+                        ty: ty.clone(),
+                        storage: var_expr,
+                    },
+                );
+            }
         }
         Statement::Break(_) => {
             cfg.add(
@@ -255,6 +266,20 @@ pub(crate) fn statement(
                 opt,
             );
         }
+        Statement::If(..) if opt.string_switch && string_switch::is_string_switch(stmt, ns) => {
+            string_switch::string_switch(
+                stmt,
+                func,
+                cfg,
+                contract_no,
+                ns,
+                vartab,
+                loops,
+                placeholder,
+                return_override,
+                opt,
+            )
+        }
         Statement::If(_, _, cond, then_stmt, else_stmt) => if_then_else(
             cond,
             then_stmt,
@@ -816,7 +841,7 @@ fn returns(
         }
 
         ast::Expression::Builtin {
-            kind: ast::Builtin::AbiDecode,
+            kind: ast::Builtin::AbiDecode | ast::Builtin::AbiEthDecode,
             ..
         }
         | ast::Expression::InternalFunctionCall { .. }
@@ -1213,6 +1238,7 @@ pub fn process_side_effects_expressions(
             ast::Builtin::PayableSend
             | ast::Builtin::ArrayPush
             | ast::Builtin::ArrayPop
+            | ast::Builtin::ArrayReserve
             // PayableTransfer, Revert, Require and SelfDestruct do not occur inside an expression
             // for they return no value. They should not bother the unused variable elimination.
             | ast::Builtin::PayableTransfer
@@ -0,0 +1,282 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{statement, Builtin, LoopScopes, Options};
+use crate::codegen::{
+    cfg::{ControlFlowGraph, Instr},
+    expression::expression,
+    vartable::Vartable,
+    Expression,
+};
+use crate::sema::ast::{self, Function, Namespace, Statement, StringLocation, Type};
+use crate::Target;
+use num_bigint::BigInt;
+use solang_parser::pt::CodeLocation;
+use std::collections::HashSet;
+use tiny_keccak::{Hasher, Keccak};
+
+/// Below this number of cases, comparing the strings one after another is cheaper than hashing
+const MIN_CASES: usize = 4;
+
+/// A chain of `if (s == "a") { .. } else if (s == "b") { .. } else { .. }`
+struct StringChain<'a> {
+    scrutinee: &'a ast::Expression,
+    cases: Vec<(&'a [u8], &'a [Statement])>,
+    default: &'a [Statement],
+}
+
+/// Can this if statement be lowered to a switch on the hash of a string
+pub(super) fn is_string_switch(stmt: &Statement, ns: &Namespace) -> bool {
+    string_chain(stmt, ns).is_some()
+}
+
+/// Lower a chain of string comparisons to a switch on the hash of the string. Each case compares
+/// the string with the literal again, since another string may have the same hash.
+pub(super) fn string_switch(
+    stmt: &Statement,
+    func: &Function,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    loops: &mut LoopScopes,
+    placeholder: Option<&Instr>,
+    return_override: Option<&Instr>,
+    opt: &Options,
+) {
+    let chain = string_chain(stmt, ns).unwrap();
+    let loc = stmt.loc();
+
+    let scrutinee = expression(
+        chain.scrutinee,
+        cfg,
+        contract_no,
+        Some(func),
+        ns,
+        vartab,
+        opt,
+    );
+
+    // the last 8 bytes of the keccak256 hash of the string
+    let hash = Expression::Trunc {
+        loc,
+        ty: Type::Uint(64),
+        expr: Expression::Cast {
+            loc,
+            ty: Type::Uint(256),
+            expr: Expression::Builtin {
+                loc,
+                tys: vec![Type::Bytes(32)],
+                kind: Builtin::Keccak256,
+                args: vec![scrutinee.clone()],
+            }
+            .into(),
+        }
+        .into(),
+    };
+
+    let else_ = cfg.new_basic_block("else".to_string());
+    let endif = cfg.new_basic_block("endif".to_string());
+
+    let cases = chain
+        .cases
+        .iter()
+        .map(|(value, _)| {
+            (
+                Expression::NumberLiteral {
+                    loc,
+                    ty: Type::Uint(64),
+                    value: BigInt::from(hash64(value)),
+                },
+                cfg.new_basic_block("string_case".to_string()),
+            )
+        })
+        .collect::<Vec<(Expression, usize)>>();
+
+    cfg.add(
+        vartab,
+        Instr::Switch {
+            cond: hash,
+            cases: cases.clone(),
+            default: else_,
+        },
+    );
+
+    vartab.new_dirty_tracker();
+
+    for ((value, body), (_, case_block)) in chain.cases.iter().zip(cases) {
+        let then = cfg.new_basic_block("then".to_string());
+
+        cfg.set_basic_block(case_block);
+
+        cfg.add(
+            vartab,
+            Instr::BranchCond {
+                cond: Expression::StringCompare {
+                    loc,
+                    left: StringLocation::RunTime(Box::new(scrutinee.clone())),
+                    right: StringLocation::CompileTime(value.to_vec()),
+                },
+                true_block: then,
+                false_block: else_,
+            },
+        );
+
+        cfg.set_basic_block(then);
+
+        if statements(
+            body,
+            func,
+            cfg,
+            contract_no,
+            ns,
+            vartab,
+            loops,
+            placeholder,
+            return_override,
+            opt,
+        ) {
+            cfg.add(vartab, Instr::Branch { block: endif });
+        }
+    }
+
+    cfg.set_basic_block(else_);
+
+    if statements(
+        chain.default,
+        func,
+        cfg,
+        contract_no,
+        ns,
+        vartab,
+        loops,
+        placeholder,
+        return_override,
+        opt,
+    ) {
+        cfg.add(vartab, Instr::Branch { block: endif });
+    }
+
+    cfg.set_phis(endif, vartab.pop_dirty_tracker());
+
+    cfg.set_basic_block(endif);
+}
+
+/// Generate the statements of a branch, and return whether the end is reachable
+fn statements(
+    stmts: &[Statement],
+    func: &Function,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    loops: &mut LoopScopes,
+    placeholder: Option<&Instr>,
+    return_override: Option<&Instr>,
+    opt: &Options,
+) -> bool {
+    let mut reachable = true;
+
+    for stmt in stmts {
+        statement(
+            stmt,
+            func,
+            cfg,
+            contract_no,
+            ns,
+            vartab,
+            loops,
+            placeholder,
+            return_override,
+            opt,
+        );
+
+        reachable = stmt.reachable();
+    }
+
+    reachable
+}
+
+/// Find the chain of comparisons of the same string variable with string literals. The chain
+/// is only used if it is long enough, and the hashes of the literals are all different.
+fn string_chain<'a>(stmt: &'a Statement, ns: &Namespace) -> Option<StringChain<'a>> {
+    // the hash is only available as a builtin on these targets
+    if !matches!(
+        ns.target,
+        Target::Solana | Target::Polkadot { .. } | Target::EVM
+    ) {
+        return None;
+    }
+
+    let Statement::If(_, _, cond, ..) = stmt else {
+        return None;
+    };
+
+    let (scrutinee, _) = string_case(cond, None)?;
+
+    let ast::Expression::Variable { var_no, .. } = scrutinee else {
+        return None;
+    };
+
+    let mut cases = Vec::new();
+    let mut hashes = HashSet::new();
+    let mut literals = HashSet::new();
+    let mut stmts = std::slice::from_ref(stmt);
+
+    while let [Statement::If(_, _, cond, then_stmt, else_stmt)] = stmts {
+        let Some((_, value)) = string_case(cond, Some(*var_no)) else {
+            break;
+        };
+
+        // a literal which was compared before can never match again
+        if literals.insert(value) {
+            if !hashes.insert(hash64(value)) {
+                return None;
+            }
+
+            cases.push((value, then_stmt.as_slice()));
+        }
+
+        stmts = else_stmt;
+    }
+
+    if cases.len() < MIN_CASES {
+        return None;
+    }
+
+    Some(StringChain {
+        scrutinee,
+        cases,
+        default: stmts,
+    })
+}
+
+/// Is the condition a comparison of a string variable with a literal
+fn string_case(cond: &ast::Expression, var_no: Option<usize>) -> Option<(&ast::Expression, &[u8])> {
+    let ast::Expression::StringCompare { left, right, .. } = cond else {
+        return None;
+    };
+
+    let (expr, value) = match (left, right) {
+        (StringLocation::RunTime(expr), StringLocation::CompileTime(value))
+        | (StringLocation::CompileTime(value), StringLocation::RunTime(expr)) => (expr, value),
+        _ => return None,
+    };
+
+    match expr.as_ref() {
+        ast::Expression::Variable { var_no: no, .. } if var_no.is_none() || var_no == Some(*no) => {
+            Some((expr, value))
+        }
+        _ => None,
+    }
+}
+
+/// The last 8 bytes of the keccak256 hash, as the switch sees them
+fn hash64(value: &[u8]) -> u64 {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+
+    hasher.update(value);
+    hasher.finalize(&mut hash);
+
+    u64::from_be_bytes(hash[24..].try_into().unwrap())
+}
@@ -171,7 +171,7 @@ fn insert_try_expression(
         }
 
         let buf = &Expression::ReturnData { loc: Codegen };
-        let decoded = abi_decode(&Codegen, buf, &return_types, ns, vartab, cfg, None);
+        let decoded = abi_decode(&Codegen, buf, &return_types, ns, vartab, cfg, None, opt);
         for instruction in res.iter().zip(decoded).map(|(var, expr)| Instr::Set {
             loc: Codegen,
             res: *var,
@@ -455,7 +455,7 @@ fn insert_catch_clauses(
                 res: clause
                     .param_pos
                     .unwrap_or_else(|| vartab.temp_anonymous(&clause.param.as_ref().unwrap().ty)),
-                expr: abi_decode(&Codegen, &buffer, types, ns, vartab, cfg, None)[1].clone(),
+                expr: abi_decode(&Codegen, &buffer, types, ns, vartab, cfg, None, opt)[1].clone(),
             };
             cfg.add(vartab, instruction);
 
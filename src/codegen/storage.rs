@@ -7,7 +7,7 @@ use num_traits::FromPrimitive;
 use num_traits::One;
 use num_traits::Zero;
 
-use super::expression::{expression, load_storage};
+use super::expression::{assign_single, expression, load_storage};
 use super::revert::PanicCode;
 use super::revert::SolidityError;
 use super::Options;
@@ -16,8 +16,9 @@ use super::{
     vartable::Vartable,
 };
 use crate::codegen::revert::{assert_failure, log_runtime_error};
-use crate::sema::ast::{Function, Namespace, RetrieveType, Type};
-use solang_parser::pt;
+use crate::sema::ast::{EnumerableMapping, Function, Namespace, PackedSlot, RetrieveType, Type};
+use crate::Target;
+use solang_parser::pt::{self, CodeLocation};
 
 /// Given a storage slot which is the start of the array, calculate the
 /// offset of the array element. This function exists to avoid doing
@@ -254,7 +255,7 @@ pub fn storage_slots_array_pop(
         vartab,
         ns,
     );
-    let error = SolidityError::Panic(PanicCode::EmptyArrayPop);
+    let error = SolidityError::panic(PanicCode::EmptyArrayPop, opt);
     assert_failure(loc, error, ns, cfg, vartab);
 
     cfg.set_basic_block(has_elements);
@@ -467,3 +468,642 @@ pub fn array_pop(
         Expression::Undefined { ty }
     }
 }
+
+/// If an expression is a value in an `@enumerable` mapping, e.g. `m[k]` or `m[k].x`, add the key
+/// to the keys of the mapping, unless it is there already. The key is evaluated here, so the
+/// returned expression, which refers to the evaluated key, must be used instead of `expr`.
+pub fn enumerable_mapping_insert(
+    expr: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Option<ast::Expression> {
+    if !enumerable_mapping_value(expr, ns) {
+        return None;
+    }
+
+    let mut expr = expr.clone();
+    let (enumerable, key) = enumerable_mapping_key(&mut expr, ns)?;
+    let loc = key.loc();
+    let key = evaluate_key(key, cfg, contract_no, func, ns, vartab, opt);
+    let position_ty = ns.storage_type();
+
+    let position = load_position(&enumerable, &key, cfg, contract_no, func, ns, vartab, opt);
+
+    let insert = cfg.new_basic_block("enumerable_insert".to_string());
+    let done = cfg.new_basic_block("enumerable_done".to_string());
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::Equal {
+                loc,
+                left: Box::new(position),
+                right: Box::new(Expression::NumberLiteral {
+                    loc,
+                    ty: position_ty.clone(),
+                    value: BigInt::zero(),
+                }),
+            },
+            true_block: insert,
+            false_block: done,
+        },
+    );
+
+    cfg.set_basic_block(insert);
+
+    let keys = enumerable.keys_array(loc, ns);
+    let key_ty = key.ty();
+
+    expression(
+        &ast::Expression::Builtin {
+            loc,
+            tys: vec![Type::Void],
+            kind: ast::Builtin::ArrayPush,
+            args: vec![keys.clone(), key.clone()],
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    // the position of the new key is the length of the keys array
+    let length = expression(
+        &ast::Expression::StorageArrayLength {
+            loc,
+            ty: position_ty,
+            array: Box::new(keys),
+            elem_ty: key_ty,
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    assign_single(
+        &positions_subscript(&enumerable, &key, ns),
+        length,
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    cfg.add(vartab, Instr::Branch { block: done });
+
+    cfg.set_basic_block(done);
+
+    Some(expr)
+}
+
+/// If `delete` is applied to a value in an `@enumerable` mapping, e.g. `delete m[k]`, remove the
+/// key from the keys of the mapping. The last key is moved into its position, so the order of the
+/// keys changes. Like [`enumerable_mapping_insert`], the returned expression must be used
+/// instead of `expr`.
+pub fn enumerable_mapping_remove(
+    expr: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Option<ast::Expression> {
+    let ast::Expression::Subscript { array, .. } = expr else {
+        return None;
+    };
+
+    let enumerable = array.enumerable_mapping(ns)?;
+
+    let mut expr = expr.clone();
+    let ast::Expression::Subscript { index, .. } = &mut expr else {
+        unreachable!();
+    };
+    let loc = index.loc();
+    let key = evaluate_key(index, cfg, contract_no, func, ns, vartab, opt);
+    let key_ty = key.ty();
+    let position_ty = ns.storage_type();
+    let index_ty = Type::Uint(256);
+
+    let position = vartab.temp_name("position", &position_ty);
+    let expr_position = load_position(&enumerable, &key, cfg, contract_no, func, ns, vartab, opt);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc,
+            res: position,
+            expr: expr_position,
+        },
+    );
+
+    let position = Expression::Variable {
+        loc,
+        ty: position_ty.clone(),
+        var_no: position,
+    };
+
+    let remove = cfg.new_basic_block("enumerable_remove".to_string());
+    let done = cfg.new_basic_block("enumerable_done".to_string());
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond: Expression::Equal {
+                loc,
+                left: Box::new(position.clone()),
+                right: Box::new(Expression::NumberLiteral {
+                    loc,
+                    ty: position_ty.clone(),
+                    value: BigInt::zero(),
+                }),
+            },
+            true_block: done,
+            false_block: remove,
+        },
+    );
+
+    cfg.set_basic_block(remove);
+
+    let keys = enumerable.keys_array(loc, ns);
+
+    let length = expression(
+        &ast::Expression::StorageArrayLength {
+            loc,
+            ty: position_ty.clone(),
+            array: Box::new(keys.clone()),
+            elem_ty: key_ty.clone(),
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    // move the last key into the position of the removed key; if the removed key is the last
+    // one, this is a no-op
+    let last_index = index_variable(
+        "last_index",
+        Expression::Subtract {
+            loc,
+            ty: index_ty.clone(),
+            overflowing: true,
+            left: Box::new(length.cast(&index_ty, ns)),
+            right: Box::new(Expression::NumberLiteral {
+                loc,
+                ty: index_ty.clone(),
+                value: BigInt::one(),
+            }),
+        },
+        cfg,
+        vartab,
+    );
+
+    let last_key = vartab.temp_name("last_key", &key_ty);
+    let expr_last_key = expression(
+        &ast::Expression::StorageLoad {
+            loc,
+            ty: key_ty.clone(),
+            expr: Box::new(keys_subscript(&enumerable, last_index, ns)),
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc,
+            res: last_key,
+            expr: expr_last_key,
+        },
+    );
+
+    let last_key = ast::Expression::Variable {
+        loc,
+        ty: key_ty,
+        var_no: last_key,
+    };
+
+    let removed_index = index_variable(
+        "removed_index",
+        Expression::Subtract {
+            loc,
+            ty: index_ty.clone(),
+            overflowing: true,
+            left: Box::new(position.cast(&index_ty, ns)),
+            right: Box::new(Expression::NumberLiteral {
+                loc,
+                ty: index_ty,
+                value: BigInt::one(),
+            }),
+        },
+        cfg,
+        vartab,
+    );
+
+    let cfg_last_key = expression(&last_key, cfg, contract_no, func, ns, vartab, opt);
+
+    assign_single(
+        &keys_subscript(&enumerable, removed_index, ns),
+        cfg_last_key,
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    assign_single(
+        &positions_subscript(&enumerable, &last_key, ns),
+        position,
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    expression(
+        &ast::Expression::Builtin {
+            loc,
+            tys: vec![Type::Void],
+            kind: ast::Builtin::ArrayPop,
+            args: vec![keys],
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    let storage = expression(
+        &positions_subscript(&enumerable, &key, ns),
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    );
+
+    cfg.add(
+        vartab,
+        Instr::ClearStorage {
+            ty: position_ty,
+            storage,
+        },
+    );
+
+    cfg.add(vartab, Instr::Branch { block: done });
+
+    cfg.set_basic_block(done);
+
+    Some(expr)
+}
+
+/// Is this storage expression a value in an enumerable mapping
+fn enumerable_mapping_value(expr: &ast::Expression, ns: &Namespace) -> bool {
+    match expr {
+        ast::Expression::Subscript { array, .. } => {
+            array.enumerable_mapping(ns).is_some() || enumerable_mapping_value(array, ns)
+        }
+        ast::Expression::StructMember { expr, .. } => enumerable_mapping_value(expr, ns),
+        _ => false,
+    }
+}
+
+/// Find the enumerable mapping that a storage expression is a value of, and the key
+fn enumerable_mapping_key<'a>(
+    expr: &'a mut ast::Expression,
+    ns: &Namespace,
+) -> Option<(EnumerableMapping, &'a mut ast::Expression)> {
+    match expr {
+        ast::Expression::Subscript { array, index, .. } => {
+            if let Some(enumerable) = array.enumerable_mapping(ns) {
+                Some((enumerable, index))
+            } else {
+                enumerable_mapping_key(array, ns)
+            }
+        }
+        ast::Expression::StructMember { expr, .. } => enumerable_mapping_key(expr, ns),
+        _ => None,
+    }
+}
+
+/// Evaluate the key of a mapping subscript into a variable, and replace the key with it
+fn evaluate_key(
+    key: &mut ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> ast::Expression {
+    let loc = key.loc();
+    let ty = key.ty();
+    let var_no = vartab.temp_name("key", &ty);
+    let expr = expression(key, cfg, contract_no, func, ns, vartab, opt);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc,
+            res: var_no,
+            expr,
+        },
+    );
+
+    *key = ast::Expression::Variable { loc, ty, var_no };
+
+    key.clone()
+}
+
+/// Load the position of a key in the keys array, plus one
+fn load_position(
+    enumerable: &EnumerableMapping,
+    key: &ast::Expression,
+    cfg: &mut ControlFlowGraph,
+    contract_no: usize,
+    func: Option<&Function>,
+    ns: &Namespace,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Expression {
+    expression(
+        &ast::Expression::StorageLoad {
+            loc: key.loc(),
+            ty: ns.storage_type(),
+            expr: Box::new(positions_subscript(enumerable, key, ns)),
+        },
+        cfg,
+        contract_no,
+        func,
+        ns,
+        vartab,
+        opt,
+    )
+}
+
+/// Store an index into the keys array in a variable
+fn index_variable(
+    name: &str,
+    expr: Expression,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+) -> ast::Expression {
+    let loc = expr.loc();
+    let ty = expr.ty();
+    let var_no = vartab.temp_name(name, &ty);
+
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc,
+            res: var_no,
+            expr,
+        },
+    );
+
+    ast::Expression::Variable { loc, ty, var_no }
+}
+
+fn positions_subscript(
+    enumerable: &EnumerableMapping,
+    key: &ast::Expression,
+    ns: &Namespace,
+) -> ast::Expression {
+    let positions = enumerable.positions_mapping(key.loc(), ns);
+    let array_ty = positions.ty();
+
+    ast::Expression::Subscript {
+        loc: key.loc(),
+        ty: array_ty.storage_array_elem(),
+        array_ty,
+        array: Box::new(positions),
+        index: Box::new(key.clone()),
+    }
+}
+
+fn keys_subscript(
+    enumerable: &EnumerableMapping,
+    index: ast::Expression,
+    ns: &Namespace,
+) -> ast::Expression {
+    let keys = enumerable.keys_array(index.loc(), ns);
+    let array_ty = keys.ty();
+
+    ast::Expression::Subscript {
+        loc: index.loc(),
+        ty: array_ty.storage_array_elem(),
+        array_ty,
+        array: Box::new(keys),
+        index: Box::new(index),
+    }
+}
+
+/// Where a value in storage is in its slot, if it shares the slot with its neighbours
+pub fn packed_storage(
+    expr: &ast::Expression,
+    contract_no: usize,
+    ns: &Namespace,
+) -> Option<PackedSlot> {
+    match expr {
+        ast::Expression::StorageVariable {
+            contract_no: var_contract_no,
+            var_no,
+            ..
+        } => ns.contracts[contract_no]
+            .layout
+            .iter()
+            .find(|layout| layout.contract_no == *var_contract_no && layout.var_no == *var_no)
+            .and_then(|layout| layout.packed),
+        ast::Expression::StructMember {
+            ty, expr, field, ..
+        } if ty.is_contract_storage() && ns.target != Target::Solana => {
+            if let Type::Struct(struct_ty) = expr.ty().deref_any() {
+                struct_ty.definition(ns).storage_packing(ns).0[*field].1
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Load a value which shares its storage slot: load the whole slot and take the value out of it
+pub fn load_packed_storage(
+    loc: &pt::Loc,
+    ty: &Type,
+    storage: Expression,
+    packed: PackedSlot,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    ns: &Namespace,
+) -> Expression {
+    let slot_ty = Type::Uint(u16::from(packed.width) * 8);
+    let value_ty = Type::Uint(u16::from(ty.packed_storage_bytes(ns).unwrap()) * 8);
+
+    let slot = load_storage(loc, &slot_ty, storage, cfg, vartab);
+
+    let value = Expression::Trunc {
+        loc: *loc,
+        ty: value_ty.clone(),
+        expr: Box::new(Expression::ShiftRight {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            left: Box::new(slot),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: slot_ty,
+                value: BigInt::from(u32::from(packed.offset) * 8),
+            }),
+            signed: false,
+        }),
+    };
+
+    match packed_base_type(ty, ns) {
+        Type::Bool => Expression::NotEqual {
+            loc: *loc,
+            left: Box::new(value),
+            right: Box::new(Expression::NumberLiteral {
+                loc: *loc,
+                ty: value_ty,
+                value: BigInt::zero(),
+            }),
+        },
+        Type::Uint(_) => value,
+        _ => Expression::Cast {
+            loc: *loc,
+            ty: ty.clone(),
+            expr: Box::new(value),
+        },
+    }
+}
+
+/// Store a value which shares its storage slot, leaving the other values in the slot as they
+/// are. Without a value, the value is cleared.
+pub fn set_packed_storage(
+    loc: &pt::Loc,
+    ty: &Type,
+    value: Option<Expression>,
+    storage: Expression,
+    packed: PackedSlot,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    ns: &Namespace,
+) {
+    let slot_ty = Type::Uint(u16::from(packed.width) * 8);
+    let value_ty = Type::Uint(u16::from(ty.packed_storage_bytes(ns).unwrap()) * 8);
+    let shift = u32::from(packed.offset) * 8;
+
+    // the slot is loaded and stored, so only evaluate it once
+    let storage = match storage {
+        Expression::NumberLiteral { .. } => storage,
+        _ => {
+            let storage_ty = storage.ty();
+            let var_no = vartab.temp_anonymous(&storage_ty);
+
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: *loc,
+                    res: var_no,
+                    expr: storage,
+                },
+            );
+
+            Expression::Variable {
+                loc: *loc,
+                ty: storage_ty,
+                var_no,
+            }
+        }
+    };
+
+    let slot = load_storage(loc, &slot_ty, storage.clone(), cfg, vartab);
+
+    let all_bits = (BigInt::one() << (u32::from(packed.width) * 8)) - 1;
+    let value_bits = ((BigInt::one() << value_ty.bits(ns)) - 1) << shift;
+
+    let mut new = Expression::BitwiseAnd {
+        loc: *loc,
+        ty: slot_ty.clone(),
+        left: Box::new(slot),
+        right: Box::new(Expression::NumberLiteral {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            value: all_bits ^ value_bits,
+        }),
+    };
+
+    if let Some(value) = value {
+        let value = match packed_base_type(ty, ns) {
+            Type::Bool | Type::Uint(_) => value,
+            _ => Expression::Cast {
+                loc: *loc,
+                ty: value_ty,
+                expr: Box::new(value),
+            },
+        };
+
+        new = Expression::BitwiseOr {
+            loc: *loc,
+            ty: slot_ty.clone(),
+            left: Box::new(new),
+            right: Box::new(Expression::ShiftLeft {
+                loc: *loc,
+                ty: slot_ty.clone(),
+                left: Box::new(Expression::ZeroExt {
+                    loc: *loc,
+                    ty: slot_ty.clone(),
+                    expr: Box::new(value),
+                }),
+                right: Box::new(Expression::NumberLiteral {
+                    loc: *loc,
+                    ty: slot_ty.clone(),
+                    value: BigInt::from(shift),
+                }),
+            }),
+        };
+    }
+
+    cfg.add(
+        vartab,
+        Instr::SetStorage {
+            ty: slot_ty,
+            value: new,
+            storage,
+        },
+    );
+}
+
+/// The type of a packed value, looking through user defined types
+fn packed_base_type<'a>(ty: &'a Type, ns: &'a Namespace) -> &'a Type {
+    match ty {
+        Type::UserType(no) => packed_base_type(&ns.user_types[*no].ty, ns),
+        _ => ty,
+    }
+}
@@ -0,0 +1,1079 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::reaching_definitions;
+use super::vartable::{Storage, Variable};
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, Type};
+use crate::Target;
+use num_bigint::{BigInt, Sign};
+use solang_parser::pt::{Identifier, Loc};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+/// State variables which are accessed more than once in a function are kept in local variables.
+/// The first load of a slot is kept in a variable, and later loads of the slot read the variable.
+/// A slot which is read in a loop is loaded once before the loop. When a slot is written more than
+/// once, e.g. in a loop, the stores only write the variable, and the slot is stored once before
+/// the function returns.
+///
+/// Calls to other functions or contracts might access the slot too, so the slot is stored before
+/// such a call if it was written, and loaded again after the call. The same is done for any other
+/// storage access which might refer to the slot. Slots of mappings and dynamic arrays are derived
+/// from a keccak256 hash, so they are assumed not to collide with the slots of state variables.
+/// Nothing has to be stored when the function reverts, since the storage changes are discarded.
+///
+/// When a slot is written on some paths only, a flag records whether it was written, and the slot
+/// is only stored if it was.
+///
+/// This is only done for the targets where storage is accessed by slot; on Solana, the storage is
+/// the account data, which is as cheap to access as memory.
+pub fn storage_cache(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
+    if !matches!(ns.target, Target::Polkadot { .. } | Target::EVM) {
+        return;
+    }
+
+    let hashed = HashedSlots::new(cfg, ns);
+    let mut slots = candidate_slots(cfg, &hashed, ns);
+
+    if slots.is_empty() {
+        return;
+    }
+
+    load_before_loops(cfg, &mut slots, &hashed, ns);
+
+    let effects = effects(cfg, &slots, &hashed, ns);
+    let states = states(cfg, &effects);
+
+    // Only cache the slots which are loaded again, or stored more than once
+    walk(&effects, &states, |effect, state| match effect {
+        Effect::Load(no) if state.loaded.contains(no) => slots[*no].cached = true,
+        Effect::Store(no) if state.maybe_dirty.contains(no) => {
+            slots[*no].cached = true;
+            slots[*no].deferred = true;
+        }
+        _ => (),
+    });
+
+    // The flag is needed where the slot is written on some of the paths only
+    let mut flagged = vec![false; slots.len()];
+
+    walk(&effects, &states, |effect, state| {
+        let stored = match effect {
+            Effect::Load(no) if !state.loaded.contains(no) => vec![*no],
+            Effect::Barrier(nos) => nos.clone(),
+            Effect::Return => (0..slots.len()).collect(),
+            _ => Vec::new(),
+        };
+
+        for no in stored {
+            if slots[no].deferred && state.maybe_dirty.contains(&no) && !state.dirty.contains(&no) {
+                flagged[no] = true;
+            }
+        }
+    });
+
+    for (no, slot) in slots.iter_mut().enumerate() {
+        if !slot.cached {
+            continue;
+        }
+
+        if slot.value.is_none() {
+            slot.value = Some(new_variable(
+                cfg,
+                format!("{}.storage_cache", slot.slot),
+                slot.ty.clone(),
+                ns,
+            ));
+        }
+
+        if flagged[no] {
+            slot.dirty = Some(new_variable(
+                cfg,
+                format!("{}.storage_dirty", slot.slot),
+                Type::Bool,
+                ns,
+            ));
+        }
+    }
+
+    if !slots.iter().any(|slot| slot.cached) {
+        return;
+    }
+
+    rewrite(cfg, &slots, effects, states, ns);
+
+    // Nothing has been written when the function starts
+    for slot in &slots {
+        if let Some(dirty) = slot.dirty {
+            cfg.blocks[0].instr.insert(0, set_flag(dirty, false));
+        }
+    }
+
+    // New blocks and variables were added, so the reaching definitions need to be calculated
+    // again for the passes which follow
+    for block in &mut cfg.blocks {
+        block.defs.clear();
+        block.transfers.clear();
+        block.loop_reaching_variables.clear();
+    }
+
+    reaching_definitions::find(cfg);
+
+    // The variables are set in different blocks, so they need a phi where blocks join
+    let vars: Vec<usize> = slots
+        .iter()
+        .filter(|slot| slot.cached)
+        .flat_map(|slot| slot.value.into_iter().chain(slot.dirty))
+        .collect();
+
+    let predecessors = predecessors(cfg);
+
+    for (block_no, block) in cfg.blocks.iter_mut().enumerate() {
+        if predecessors[block_no].len() < 2 {
+            continue;
+        }
+
+        for var_no in &vars {
+            if block.defs.contains_key(var_no) {
+                block.phis.get_or_insert_with(BTreeSet::new).insert(*var_no);
+            }
+        }
+    }
+}
+
+/// A state variable which might be kept in a local variable
+struct CachedSlot {
+    slot: BigInt,
+    ty: Type,
+    /// Is the slot kept in a variable
+    cached: bool,
+    /// Are the stores of the slot deferred, or is the slot stored right away as well
+    deferred: bool,
+    /// The variable which holds the value of the slot
+    value: Option<usize>,
+    /// The variable which records whether the slot was written, if that is not known
+    dirty: Option<usize>,
+}
+
+impl CachedSlot {
+    fn storage(&self, ns: &Namespace) -> Expression {
+        Expression::NumberLiteral {
+            loc: Loc::Codegen,
+            ty: ns.storage_type(),
+            value: self.slot.clone(),
+        }
+    }
+}
+
+/// The slots of state variables with a value type, which are loaded or stored in the function
+fn candidate_slots(
+    cfg: &ControlFlowGraph,
+    hashed: &HashedSlots,
+    ns: &Namespace,
+) -> Vec<CachedSlot> {
+    let mut slots: BTreeMap<BigInt, Type> = BTreeMap::new();
+
+    for block in &cfg.blocks {
+        for instr in &block.instr {
+            if let Instr::LoadStorage {
+                ty,
+                storage: Expression::NumberLiteral { value, .. },
+                ..
+            }
+            | Instr::SetStorage {
+                ty,
+                storage: Expression::NumberLiteral { value, .. },
+                ..
+            } = instr
+            {
+                if !ty.is_reference_type(ns) && *value < hashed.fixed_layout_end {
+                    slots.entry(value.clone()).or_insert_with(|| ty.clone());
+                }
+            }
+        }
+    }
+
+    slots
+        .into_iter()
+        .map(|(slot, ty)| CachedSlot {
+            slot,
+            ty,
+            cached: false,
+            deferred: false,
+            value: None,
+            dirty: None,
+        })
+        .collect()
+}
+
+/// What an instruction does with the slots
+#[derive(Clone, Debug, PartialEq)]
+enum Effect {
+    None,
+    /// Load the slot
+    Load(usize),
+    /// Store the slot
+    Store(usize),
+    /// The slots might be accessed in some other way, e.g. by a call
+    Barrier(Vec<usize>),
+    /// The function returns, so the slots must be stored
+    Return,
+}
+
+fn effects(
+    cfg: &ControlFlowGraph,
+    slots: &[CachedSlot],
+    hashed: &HashedSlots,
+    ns: &Namespace,
+) -> Vec<Vec<Effect>> {
+    cfg.blocks
+        .iter()
+        .map(|block| {
+            block
+                .instr
+                .iter()
+                .map(|instr| effect(instr, slots, hashed, ns))
+                .collect()
+        })
+        .collect()
+}
+
+fn effect(instr: &Instr, slots: &[CachedSlot], hashed: &HashedSlots, ns: &Namespace) -> Effect {
+    if matches!(
+        instr,
+        Instr::Return { .. } | Instr::ReturnData { .. } | Instr::ReturnCode { .. }
+    ) {
+        return Effect::Return;
+    }
+
+    let mut accesses = StorageAccesses::default();
+
+    accesses.instruction(instr, hashed, ns);
+
+    if accesses.unknown {
+        return Effect::Barrier((0..slots.len()).collect());
+    }
+
+    let exact = match instr {
+        Instr::LoadStorage {
+            ty,
+            storage: Expression::NumberLiteral { value, .. },
+            ..
+        }
+        | Instr::SetStorage {
+            ty,
+            storage: Expression::NumberLiteral { value, .. },
+            ..
+        } => slots
+            .iter()
+            .position(|slot| slot.slot == *value && slot.ty == *ty),
+        _ => None,
+    };
+
+    let barrier: Vec<usize> = slots
+        .iter()
+        .enumerate()
+        .filter(|(no, slot)| {
+            accesses.clobbered(&slot.slot)
+                || (Some(*no) != exact && accesses.slots.contains_key(&slot.slot))
+        })
+        .map(|(no, _)| no)
+        .collect();
+
+    match (exact, instr) {
+        (Some(no), _) if !barrier.is_empty() => {
+            // The slot is accessed directly by the instruction, so it cannot be cached here
+            let mut barrier = barrier;
+            barrier.push(no);
+            Effect::Barrier(barrier)
+        }
+        (_, _) if !barrier.is_empty() => Effect::Barrier(barrier),
+        (Some(no), Instr::LoadStorage { .. }) => Effect::Load(no),
+        (Some(no), _) => Effect::Store(no),
+        (None, _) => Effect::None,
+    }
+}
+
+/// What is known about the slots at some point of the function
+#[derive(Clone, Debug, Default, PartialEq)]
+struct State {
+    /// The variable holds the value of the slot
+    loaded: BTreeSet<usize>,
+    /// The variable might have been written, but not stored yet
+    maybe_dirty: BTreeSet<usize>,
+    /// The variable has been written but not stored yet, on every path
+    dirty: BTreeSet<usize>,
+}
+
+impl State {
+    fn apply(&mut self, effect: &Effect) {
+        match effect {
+            Effect::Load(no) => {
+                self.loaded.insert(*no);
+            }
+            Effect::Store(no) => {
+                self.loaded.insert(*no);
+                self.maybe_dirty.insert(*no);
+                self.dirty.insert(*no);
+            }
+            Effect::Barrier(nos) => {
+                for no in nos {
+                    self.loaded.remove(no);
+                    self.maybe_dirty.remove(no);
+                    self.dirty.remove(no);
+                }
+            }
+            Effect::None | Effect::Return => (),
+        }
+    }
+
+    /// Join the state of another path
+    fn join(&mut self, other: &State) {
+        self.loaded.retain(|no| other.loaded.contains(no));
+        self.maybe_dirty.extend(&other.maybe_dirty);
+        self.dirty.retain(|no| other.dirty.contains(no));
+    }
+}
+
+/// The state at the start of each block, or None if the block cannot be reached
+fn states(cfg: &ControlFlowGraph, effects: &[Vec<Effect>]) -> Vec<Option<State>> {
+    let mut states: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+    let mut outgoing: Vec<Option<State>> = vec![None; cfg.blocks.len()];
+    let predecessors = predecessors(cfg);
+    let entry = State::default();
+    let mut todo = VecDeque::from([0]);
+
+    while let Some(block_no) = todo.pop_front() {
+        let mut incoming = (block_no == 0).then_some(&entry).into_iter().chain(
+            predecessors[block_no]
+                .iter()
+                .filter_map(|pred| outgoing[*pred].as_ref()),
+        );
+
+        let Some(first) = incoming.next() else {
+            continue;
+        };
+
+        let mut state = first.clone();
+
+        for other in incoming {
+            state.join(other);
+        }
+
+        states[block_no] = Some(state.clone());
+
+        for effect in &effects[block_no] {
+            state.apply(effect);
+        }
+
+        if outgoing[block_no].as_ref() != Some(&state) {
+            todo.extend(cfg.blocks[block_no].successors());
+            outgoing[block_no] = Some(state);
+        }
+    }
+
+    states
+}
+
+/// Call the function for each instruction which can be reached, with the state before it
+fn walk(effects: &[Vec<Effect>], states: &[Option<State>], mut f: impl FnMut(&Effect, &State)) {
+    for (block_no, effects) in effects.iter().enumerate() {
+        let Some(mut state) = states[block_no].clone() else {
+            continue;
+        };
+
+        for effect in effects {
+            f(effect, &state);
+            state.apply(effect);
+        }
+    }
+}
+
+/// The storage accesses of an instruction
+#[derive(Default)]
+struct StorageAccesses {
+    /// The constant slots with a value type which are loaded or stored, with the type and
+    /// whether the slot is stored
+    slots: BTreeMap<BigInt, (Type, bool)>,
+    /// The ranges of constant slots which are accessed in some other way
+    clobbers: Vec<(BigInt, BigInt)>,
+    /// Storage might be accessed anywhere, e.g. by a call or an unknown slot
+    unknown: bool,
+}
+
+impl StorageAccesses {
+    fn instruction(&mut self, instr: &Instr, hashed: &HashedSlots, ns: &Namespace) {
+        match instr {
+            Instr::Call { .. }
+            | Instr::ExternalCall { .. }
+            | Instr::Constructor { .. }
+            | Instr::ValueTransfer { .. }
+            | Instr::SelfDestruct { .. }
+            | Instr::Unimplemented { .. } => {
+                self.unknown = true;
+            }
+            Instr::LoadStorage { ty, storage, .. } => {
+                self.access(storage, ty, false, hashed, ns);
+            }
+            Instr::SetStorage { ty, storage, .. } => {
+                self.access(storage, ty, true, hashed, ns);
+            }
+            Instr::ClearStorage { ty, storage } => {
+                self.clobber(storage, Some(ty), hashed, ns);
+            }
+            Instr::SetStorageBytes { storage, .. }
+            | Instr::PushStorage { storage, .. }
+            | Instr::PopStorage { storage, .. } => {
+                self.clobber(storage, None, hashed, ns);
+            }
+            _ => (),
+        }
+
+        // Some expressions read storage, too
+        let mut arrays = Vec::new();
+
+        instr.recurse_expressions(&mut arrays, storage_expressions);
+
+        for array in &arrays {
+            self.clobber(array, None, hashed, ns);
+        }
+    }
+
+    /// Record a load or store of a value in storage
+    fn access(
+        &mut self,
+        storage: &Expression,
+        ty: &Type,
+        written: bool,
+        hashed: &HashedSlots,
+        ns: &Namespace,
+    ) {
+        match storage {
+            Expression::NumberLiteral { value, .. }
+                if !ty.is_reference_type(ns) && *value < hashed.fixed_layout_end =>
+            {
+                let entry = self
+                    .slots
+                    .entry(value.clone())
+                    .or_insert_with(|| (ty.clone(), false));
+
+                if entry.0 != *ty {
+                    // The same slot is accessed with different types, e.g. a packed slot
+                    self.clobbers.push((value.clone(), value + 1));
+                }
+
+                entry.1 |= written;
+            }
+            _ => self.clobber(storage, Some(ty), hashed, ns),
+        }
+    }
+
+    /// Record a storage access which cannot be cached
+    fn clobber(
+        &mut self,
+        storage: &Expression,
+        ty: Option<&Type>,
+        hashed: &HashedSlots,
+        ns: &Namespace,
+    ) {
+        match storage {
+            Expression::NumberLiteral { value, .. } if *value < hashed.fixed_layout_end => {
+                let slots = ty.map_or(BigInt::from(1), |ty| ty.storage_slots(ns));
+
+                self.clobbers.push((value.clone(), value + slots));
+            }
+            // mappings and dynamic arrays do not overlap with state variables
+            _ if hashed.contains(storage) => (),
+            _ => self.unknown = true,
+        }
+    }
+
+    fn clobbered(&self, slot: &BigInt) -> bool {
+        self.clobbers
+            .iter()
+            .any(|(start, end)| start <= slot && slot < end)
+    }
+}
+
+/// Collect the slots of the expressions which read storage
+fn storage_expressions(expr: &Expression, arrays: &mut Vec<Expression>) -> bool {
+    match expr {
+        Expression::StorageArrayLength { array, .. } => {
+            arrays.push(array.as_ref().clone());
+        }
+        Expression::Subscript {
+            array_ty,
+            expr: array,
+            ..
+        } if array_ty.is_storage_bytes() => {
+            arrays.push(array.as_ref().clone());
+        }
+        _ => (),
+    }
+
+    true
+}
+
+/// Recognizes the slots which are derived from a keccak256 hash, like the slots of mapping
+/// entries and dynamic array elements
+struct HashedSlots {
+    /// The variables which are only ever set to a hashed slot
+    vars: HashSet<usize>,
+    /// The slots of state variables are below this slot; a constant slot beyond it has been
+    /// folded from a hash
+    fixed_layout_end: BigInt,
+}
+
+impl HashedSlots {
+    fn new(cfg: &ControlFlowGraph, ns: &Namespace) -> Self {
+        let mut defs: HashMap<usize, Vec<&Expression>> = HashMap::new();
+        let mut opaque = HashSet::new();
+
+        for block in &cfg.blocks {
+            for instr in &block.instr {
+                match instr {
+                    Instr::Set { res, expr, .. } => {
+                        defs.entry(*res).or_default().push(expr);
+                    }
+                    Instr::Call { res, .. } => {
+                        opaque.extend(res.iter().copied());
+                    }
+                    Instr::LoadStorage { res, .. }
+                    | Instr::PushStorage { res, .. }
+                    | Instr::PopStorage { res: Some(res), .. } => {
+                        opaque.insert(*res);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let mut hashed = HashedSlots {
+            vars: defs
+                .keys()
+                .filter(|var_no| !opaque.contains(var_no))
+                .copied()
+                .collect(),
+            fixed_layout_end: ns
+                .contracts
+                .iter()
+                .map(|contract| contract.fixed_layout_size.clone())
+                .max()
+                .unwrap_or_default(),
+        };
+
+        // Variables can be defined in terms of each other, so remove variables until nothing
+        // changes
+        loop {
+            let not_hashed: Vec<usize> = hashed
+                .vars
+                .iter()
+                .filter(|var_no| !defs[var_no].iter().all(|expr| hashed.contains(expr)))
+                .copied()
+                .collect();
+
+            if not_hashed.is_empty() {
+                return hashed;
+            }
+
+            for var_no in not_hashed {
+                hashed.vars.remove(&var_no);
+            }
+        }
+    }
+
+    /// Is this slot derived from a keccak256 hash
+    fn contains(&self, expr: &Expression) -> bool {
+        match expr {
+            Expression::Keccak256 { .. }
+            | Expression::Builtin {
+                kind: Builtin::Keccak256,
+                ..
+            } => true,
+            Expression::NumberLiteral { value, .. } => *value >= self.fixed_layout_end,
+            Expression::BytesLiteral { value, .. } => {
+                BigInt::from_bytes_be(Sign::Plus, value) >= self.fixed_layout_end
+            }
+            Expression::Add { left, right, .. } => self.contains(left) || self.contains(right),
+            Expression::Subscript { expr, .. }
+            | Expression::Cast { expr, .. }
+            | Expression::ZeroExt { expr, .. }
+            | Expression::SignExt { expr, .. }
+            | Expression::Trunc { expr, .. } => self.contains(expr),
+            Expression::Variable { var_no, .. } => self.vars.contains(var_no),
+            _ => false,
+        }
+    }
+}
+
+/// Find the natural loops of the cfg, as the loop header with the blocks of the loop. The outer
+/// loops come first.
+pub(super) fn find_loops(cfg: &ControlFlowGraph) -> Vec<(usize, BTreeSet<usize>)> {
+    let successors: Vec<Vec<usize>> = cfg.blocks.iter().map(|b| b.successors()).collect();
+
+    // reverse postorder of the reachable blocks
+    let mut postorder = Vec::new();
+    let mut visited = vec![false; cfg.blocks.len()];
+    let mut stack = vec![(0, 0)];
+    visited[0] = true;
+
+    while let Some((block_no, edge)) = stack.pop() {
+        if let Some(next) = successors[block_no].get(edge) {
+            stack.push((block_no, edge + 1));
+
+            if !visited[*next] {
+                visited[*next] = true;
+                stack.push((*next, 0));
+            }
+        } else {
+            postorder.push(block_no);
+        }
+    }
+
+    let order: Vec<usize> = postorder.into_iter().rev().collect();
+    let mut position = vec![usize::MAX; cfg.blocks.len()];
+
+    for (pos, block_no) in order.iter().enumerate() {
+        position[*block_no] = pos;
+    }
+
+    let mut predecessors = vec![Vec::new(); cfg.blocks.len()];
+
+    for block_no in &order {
+        for succ in &successors[*block_no] {
+            predecessors[*succ].push(*block_no);
+        }
+    }
+
+    // immediate dominators, see "A Simple, Fast Dominance Algorithm" by Cooper, Harvey and Kennedy
+    let mut idom: Vec<Option<usize>> = vec![None; cfg.blocks.len()];
+    idom[0] = Some(0);
+
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        for block_no in order.iter().skip(1) {
+            let mut new_idom: Option<usize> = None;
+
+            for pred in &predecessors[*block_no] {
+                if idom[*pred].is_none() {
+                    continue;
+                }
+
+                new_idom = Some(match new_idom {
+                    None => *pred,
+                    Some(mut other) => {
+                        let mut pred = *pred;
+
+                        while pred != other {
+                            while position[pred] > position[other] {
+                                pred = idom[pred].unwrap();
+                            }
+                            while position[other] > position[pred] {
+                                other = idom[other].unwrap();
+                            }
+                        }
+
+                        pred
+                    }
+                });
+            }
+
+            if idom[*block_no] != new_idom {
+                idom[*block_no] = new_idom;
+                changed = true;
+            }
+        }
+    }
+
+    let dominates = |dominator: usize, mut block_no: usize| loop {
+        if block_no == dominator {
+            return true;
+        }
+        if block_no == 0 {
+            return false;
+        }
+        block_no = idom[block_no].unwrap();
+    };
+
+    let mut loops: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+
+    for block_no in &order {
+        for header in &successors[*block_no] {
+            // The entry block has no predecessor to put the loads in
+            if *header == 0 || !dominates(*header, *block_no) {
+                continue;
+            }
+
+            let body = loops
+                .entry(*header)
+                .or_insert_with(|| BTreeSet::from([*header]));
+
+            let mut todo = vec![*block_no];
+
+            while let Some(block_no) = todo.pop() {
+                if body.insert(block_no) {
+                    todo.extend(predecessors[block_no].iter().copied());
+                }
+            }
+        }
+    }
+
+    let mut loops: Vec<(usize, BTreeSet<usize>)> = loops.into_iter().collect();
+
+    loops.sort_by_key(|(_, blocks)| std::cmp::Reverse(blocks.len()));
+
+    loops
+}
+
+/// Load the slots which are read in a loop before the loop, if nothing else in the loop might
+/// access them. The outer loops are done first, so that a slot is loaded before the largest loop
+/// possible.
+fn load_before_loops(
+    cfg: &mut ControlFlowGraph,
+    slots: &mut [CachedSlot],
+    hashed: &HashedSlots,
+    ns: &mut Namespace,
+) {
+    let effects = effects(cfg, slots, hashed, ns);
+    let mut loaded: Vec<(BTreeSet<usize>, BTreeSet<usize>)> = Vec::new();
+
+    for (header, body) in find_loops(cfg) {
+        let mut read = BTreeSet::new();
+        let mut accessed = BTreeSet::new();
+
+        for block_no in &body {
+            for effect in &effects[*block_no] {
+                match effect {
+                    Effect::Load(no) => {
+                        read.insert(*no);
+                    }
+                    Effect::Barrier(nos) => accessed.extend(nos.iter().copied()),
+                    _ => (),
+                }
+            }
+        }
+
+        // The slots which are loaded before an enclosing loop are loaded already
+        let load: BTreeSet<usize> = read
+            .difference(&accessed)
+            .filter(|no| {
+                !loaded
+                    .iter()
+                    .any(|(outer, slots)| outer.contains(&header) && slots.contains(no))
+            })
+            .copied()
+            .collect();
+
+        if load.is_empty() {
+            continue;
+        }
+
+        let preheader = cfg.new_basic_block("storage_cache".to_string());
+
+        for no in &load {
+            let slot = &mut slots[*no];
+            let value = match slot.value {
+                Some(value) => value,
+                None => new_variable(
+                    cfg,
+                    format!("{}.storage_cache", slot.slot),
+                    slot.ty.clone(),
+                    ns,
+                ),
+            };
+
+            slot.value = Some(value);
+            slot.cached = true;
+
+            cfg.blocks[preheader].instr.push(Instr::LoadStorage {
+                res: value,
+                ty: slot.ty.clone(),
+                storage: slot.storage(ns),
+            });
+        }
+
+        cfg.blocks[preheader]
+            .instr
+            .push(Instr::Branch { block: header });
+
+        let entries: Vec<usize> = predecessors(cfg)[header]
+            .iter()
+            .filter(|block_no| !body.contains(block_no) && **block_no != preheader)
+            .copied()
+            .collect();
+
+        if entries.len() > 1 {
+            cfg.blocks[preheader].phis = cfg.blocks[header].phis.clone();
+        }
+
+        for block_no in entries {
+            redirect(cfg, block_no, header, preheader);
+        }
+
+        loaded.push((body, load));
+    }
+}
+
+/// Replace the loads and stores of the cached slots with the variables, and store the slots
+/// which were written before the function returns or storage might be accessed otherwise
+fn rewrite(
+    cfg: &mut ControlFlowGraph,
+    slots: &[CachedSlot],
+    effects: Vec<Vec<Effect>>,
+    states: Vec<Option<State>>,
+    ns: &Namespace,
+) {
+    for (block_no, (effects, state)) in effects.into_iter().zip(states).enumerate() {
+        let Some(mut state) = state else {
+            continue;
+        };
+
+        let mut block = Rewrite {
+            cfg: &mut *cfg,
+            block_no,
+            instrs: Vec::new(),
+        };
+
+        for (instr, effect) in std::mem::take(&mut block.cfg.blocks[block_no].instr)
+            .into_iter()
+            .zip(effects)
+        {
+            let effect = match effect {
+                Effect::Load(no) | Effect::Store(no) if !slots[no].cached => Effect::None,
+                Effect::Barrier(nos) => {
+                    Effect::Barrier(nos.into_iter().filter(|no| slots[*no].cached).collect())
+                }
+                Effect::Return => {
+                    Effect::Barrier((0..slots.len()).filter(|no| slots[*no].cached).collect())
+                }
+                effect => effect,
+            };
+
+            match (&effect, instr) {
+                (Effect::Load(no), Instr::LoadStorage { res, ty, storage }) => {
+                    let slot = &slots[*no];
+                    let value = slot.value.unwrap();
+
+                    if !state.loaded.contains(no) {
+                        let load = Instr::LoadStorage {
+                            res: value,
+                            ty: ty.clone(),
+                            storage,
+                        };
+
+                        block.load(slot, load, slot.deferred && state.maybe_dirty.contains(no));
+                    }
+
+                    if res != value {
+                        block.instrs.push(Instr::Set {
+                            loc: Loc::Codegen,
+                            res,
+                            expr: Expression::Variable {
+                                loc: Loc::Codegen,
+                                ty,
+                                var_no: value,
+                            },
+                        });
+                    }
+                }
+                (
+                    Effect::Store(no),
+                    Instr::SetStorage {
+                        ty,
+                        value: expr,
+                        storage,
+                    },
+                ) => {
+                    let slot = &slots[*no];
+                    let value = slot.value.unwrap();
+
+                    block.instrs.push(Instr::Set {
+                        loc: Loc::Codegen,
+                        res: value,
+                        expr,
+                    });
+
+                    if !slot.deferred {
+                        block.instrs.push(Instr::SetStorage {
+                            value: Expression::Variable {
+                                loc: Loc::Codegen,
+                                ty: ty.clone(),
+                                var_no: value,
+                            },
+                            ty,
+                            storage,
+                        });
+                    } else if let Some(dirty) = slot.dirty {
+                        block.instrs.push(set_flag(dirty, true));
+                    }
+                }
+                (Effect::Barrier(nos), instr) => {
+                    // After a return, the flags do not matter anymore
+                    let returns = matches!(
+                        instr,
+                        Instr::Return { .. } | Instr::ReturnData { .. } | Instr::ReturnCode { .. }
+                    );
+
+                    for no in nos {
+                        if slots[*no].deferred && state.maybe_dirty.contains(no) {
+                            block.store(&slots[*no], state.dirty.contains(no), !returns, ns);
+                        }
+                    }
+
+                    block.instrs.push(instr);
+                }
+                (_, instr) => block.instrs.push(instr),
+            }
+
+            state.apply(&effect);
+        }
+
+        block.cfg.blocks[block.block_no].instr = block.instrs;
+    }
+}
+
+/// The instructions of a block which is being rewritten, which might be split into more blocks
+struct Rewrite<'a> {
+    cfg: &'a mut ControlFlowGraph,
+    /// The block which the instructions go into
+    block_no: usize,
+    instrs: Vec<Instr>,
+}
+
+impl Rewrite<'_> {
+    /// End the block with a branch on the flag, and continue in the next block
+    fn branch(&mut self, flag: usize, true_block: usize, false_block: usize, next: usize) {
+        self.instrs.push(Instr::BranchCond {
+            cond: Expression::Variable {
+                loc: Loc::Codegen,
+                ty: Type::Bool,
+                var_no: flag,
+            },
+            true_block,
+            false_block,
+        });
+
+        self.cfg.blocks[self.block_no].instr = std::mem::take(&mut self.instrs);
+        self.block_no = next;
+    }
+
+    /// Load the slot into its variable. If the slot might have been written already, the variable
+    /// holds the value if the flag is set.
+    fn load(&mut self, slot: &CachedSlot, load: Instr, maybe_dirty: bool) {
+        if !maybe_dirty {
+            self.instrs.push(load);
+            return;
+        }
+
+        let load_block = self.cfg.new_basic_block("storage_cache_load".to_string());
+        let next = self.cfg.new_basic_block("storage_cache_loaded".to_string());
+
+        self.cfg.blocks[load_block].instr = vec![load, Instr::Branch { block: next }];
+
+        self.branch(slot.dirty.unwrap(), next, load_block, next);
+    }
+
+    /// Store the slot from its variable. If the slot might not have been written, it is only
+    /// stored if the flag is set.
+    fn store(&mut self, slot: &CachedSlot, dirty: bool, reset: bool, ns: &Namespace) {
+        let mut instrs = vec![Instr::SetStorage {
+            ty: slot.ty.clone(),
+            value: Expression::Variable {
+                loc: Loc::Codegen,
+                ty: slot.ty.clone(),
+                var_no: slot.value.unwrap(),
+            },
+            storage: slot.storage(ns),
+        }];
+
+        if let Some(flag) = slot.dirty.filter(|_| reset) {
+            instrs.push(set_flag(flag, false));
+        }
+
+        if dirty {
+            self.instrs.extend(instrs);
+            return;
+        }
+
+        let store = self.cfg.new_basic_block("storage_cache_store".to_string());
+        let next = self.cfg.new_basic_block("storage_cache_stored".to_string());
+
+        instrs.push(Instr::Branch { block: next });
+        self.cfg.blocks[store].instr = instrs;
+
+        self.branch(slot.dirty.unwrap(), store, next, next);
+    }
+}
+
+fn set_flag(flag: usize, value: bool) -> Instr {
+    Instr::Set {
+        loc: Loc::Codegen,
+        res: flag,
+        expr: Expression::BoolLiteral {
+            loc: Loc::Codegen,
+            value,
+        },
+    }
+}
+
+fn new_variable(cfg: &mut ControlFlowGraph, name: String, ty: Type, ns: &mut Namespace) -> usize {
+    let var_no = ns.next_id;
+    ns.next_id += 1;
+
+    cfg.vars.insert(
+        var_no,
+        Variable {
+            id: Identifier {
+                loc: Loc::Codegen,
+                name,
+            },
+            ty,
+            storage: Storage::Local,
+        },
+    );
+
+    var_no
+}
+
+fn predecessors(cfg: &ControlFlowGraph) -> Vec<Vec<usize>> {
+    let mut predecessors = vec![Vec::new(); cfg.blocks.len()];
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        for succ in block.successors() {
+            predecessors[succ].push(block_no);
+        }
+    }
+
+    predecessors
+}
+
+/// Make the block jump to `to` rather than `from`
+fn redirect(cfg: &mut ControlFlowGraph, block_no: usize, from: usize, to: usize) {
+    let update = |block: &mut usize| {
+        if *block == from {
+            *block = to;
+        }
+    };
+
+    match cfg.blocks[block_no].instr.last_mut() {
+        Some(Instr::Branch { block }) => update(block),
+        Some(Instr::BranchCond {
+            true_block,
+            false_block,
+            ..
+        }) => {
+            update(true_block);
+            update(false_block);
+        }
+        Some(Instr::Switch { cases, default, .. }) => {
+            for (_, block) in cases {
+                update(block);
+            }
+            update(default);
+        }
+        _ => (),
+    }
+}
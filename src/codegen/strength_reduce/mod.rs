@@ -13,6 +13,7 @@ use expression_values::expression_values;
 use num_bigint::{BigInt, Sign};
 use num_traits::{One, ToPrimitive};
 use reaching_values::{reaching_values, transfer};
+use solang_parser::pt::Loc;
 use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use value::{get_max_signed, get_max_unsigned, is_single_constant, Value};
@@ -22,6 +23,7 @@ use value::{get_max_signed, get_max_unsigned, is_single_constant, Value};
 
   Currently implemented:
   - Replace 256/128 bit multiply/divide/modulo with smaller width operations
+  - Replace powers with small constant exponents with multiplications
 
 */
 
@@ -70,7 +72,6 @@ use value::{get_max_signed, get_max_unsigned, is_single_constant, Value};
     and check if the result fits into 64 bit
   - Conditions like "if (i < 100) { ... }" are not used to know the bounds of i
   - The pass does not work across function calls
-  - Can we replace Expression::Power() with a cheaper one
   - Can we replace Expression::BitwiseAnd() with a cheaper one if either side fits into u64
 */
 
@@ -159,12 +160,16 @@ fn block_reduce(
             Instr::PushMemory { value, .. } => {
                 *value = Box::new(expression_reduce(value, &vars, ns));
             }
+            Instr::ReserveMemory { size, .. } => {
+                *size = expression_reduce(size, &vars, ns);
+            }
             Instr::Constructor {
                 encoded_args,
                 value,
                 gas,
                 salt,
                 accounts,
+                storage_deposit_limit,
                 ..
             } => {
                 *encoded_args = expression_reduce(encoded_args, &vars, ns);
@@ -177,6 +182,9 @@ fn block_reduce(
                 if let ExternalCallAccounts::Present(accounts) = accounts {
                     *accounts = expression_reduce(accounts, &vars, ns);
                 }
+                if let Some(limit) = storage_deposit_limit {
+                    *limit = expression_reduce(limit, &vars, ns);
+                }
                 *gas = expression_reduce(gas, &vars, ns);
             }
             Instr::ExternalCall {
@@ -342,6 +350,7 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
                 ty,
                 left,
                 right,
+                ..
             } => {
                 let bits = ty.bits(ns) as usize;
 
@@ -551,6 +560,36 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
 
                 expr.clone()
             }
+            Expression::Power {
+                loc,
+                ty,
+                overflowing,
+                base,
+                exp,
+            } if matches!(
+                base.as_ref(),
+                Expression::Variable { .. }
+                    | Expression::FunctionArg { .. }
+                    | Expression::NumberLiteral { .. }
+            ) =>
+            {
+                let exp_values = expression_values(exp, vars, ns);
+
+                match is_single_constant(&exp_values).and_then(|exp| exp.to_u32()) {
+                    Some(exp) if exp <= MAX_POWER_EXPONENT => {
+                        ns.hover_overrides.insert(
+                            *loc,
+                            format!("{} power optimized to multiply", ty.to_string(ns)),
+                        );
+
+                        let mul = power_to_multiply(loc, ty, *overflowing, base, exp);
+
+                        // the multiplications may be done with smaller widths
+                        expression_reduce(&mul, vars, ns)
+                    }
+                    _ => expr.clone(),
+                }
+            }
             _ => expr.clone(),
         }
     };
@@ -558,6 +597,53 @@ fn expression_reduce(expr: &Expression, vars: &Variables, ns: &mut Namespace) ->
     expr.copy_filter(ns, filter)
 }
 
+/// Powers with a constant exponent up to this are replaced with multiplications
+const MAX_POWER_EXPONENT: u32 = 8;
+
+/// Replace `base ** exp` with multiplications by squaring. The base is repeated in the expression,
+/// so it must not have side effects; the repeated squares are shared by common subexpression
+/// elimination. Multiplying with overflow checking is equivalent, since the intermediate values
+/// are never larger than the result.
+fn power_to_multiply(
+    loc: &Loc,
+    ty: &Type,
+    overflowing: bool,
+    base: &Expression,
+    exp: u32,
+) -> Expression {
+    match exp {
+        0 => Expression::NumberLiteral {
+            loc: *loc,
+            ty: ty.clone(),
+            value: BigInt::one(),
+        },
+        1 => base.clone(),
+        _ => {
+            let half = power_to_multiply(loc, ty, overflowing, base, exp / 2);
+
+            let square = Expression::Multiply {
+                loc: *loc,
+                ty: ty.clone(),
+                overflowing,
+                left: Box::new(half.clone()),
+                right: Box::new(half),
+            };
+
+            if exp % 2 == 0 {
+                square
+            } else {
+                Expression::Multiply {
+                    loc: *loc,
+                    ty: ty.clone(),
+                    overflowing,
+                    left: Box::new(square),
+                    right: Box::new(base.clone()),
+                }
+            }
+        }
+    }
+}
+
 /// This optimization pass only tracks bools and integers variables.
 /// Other types (e.g. bytes) is not relevant for strength reduce. Bools are only
 /// tracked so we can following branching after integer compare.
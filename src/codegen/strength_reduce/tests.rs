@@ -2,7 +2,7 @@
 
 #![cfg(test)]
 use super::expression_values::expression_values;
-use super::{highest_set_bit, Variables};
+use super::{expression_reduce, highest_set_bit, Variables};
 use crate::codegen::strength_reduce::value::Value;
 use crate::codegen::Expression;
 use crate::sema::ast::{Namespace, Type};
@@ -1271,3 +1271,60 @@ fn expression_known_bits() {
     assert!(v.known_bits[0]);
     assert!(v.value[0]);
 }
+
+#[test]
+fn power_with_constant_exponent() {
+    use crate::Target;
+    use solang_parser::pt::Loc;
+
+    let mut ns = Namespace::new(Target::default_polkadot());
+    let loc = Loc::Codegen;
+    let vars: Variables = HashMap::new();
+
+    let x = Expression::FunctionArg {
+        loc,
+        ty: Type::Uint(256),
+        arg_no: 0,
+    };
+
+    let power = |exp: u32| Expression::Power {
+        loc,
+        ty: Type::Uint(256),
+        overflowing: false,
+        base: Box::new(x.clone()),
+        exp: Box::new(Expression::NumberLiteral {
+            loc,
+            ty: Type::Uint(256),
+            value: BigInt::from(exp),
+        }),
+    };
+
+    let mul = |left: Expression, right: Expression| Expression::Multiply {
+        loc,
+        ty: Type::Uint(256),
+        overflowing: false,
+        left: Box::new(left),
+        right: Box::new(right),
+    };
+
+    assert_eq!(
+        expression_reduce(&power(0), &vars, &mut ns),
+        Expression::NumberLiteral {
+            loc,
+            ty: Type::Uint(256),
+            value: BigInt::from(1),
+        }
+    );
+
+    assert_eq!(expression_reduce(&power(1), &vars, &mut ns), x);
+
+    let square = mul(x.clone(), x.clone());
+
+    assert_eq!(
+        expression_reduce(&power(5), &vars, &mut ns),
+        mul(mul(square.clone(), square), x.clone())
+    );
+
+    // large exponents still use the power function
+    assert_eq!(expression_reduce(&power(9), &vars, &mut ns), power(9));
+}
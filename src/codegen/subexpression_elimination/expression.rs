@@ -95,10 +95,14 @@ impl Expression {
             },
 
             Expression::SignedDivide {
-                loc, ty: expr_type, ..
+                loc,
+                ty: expr_type,
+                overflowing,
+                ..
             } => Expression::SignedDivide {
                 loc: *loc,
                 ty: expr_type.clone(),
+                overflowing: *overflowing,
                 left: Box::new(left.clone()),
                 right: Box::new(right.clone()),
             },
@@ -64,6 +64,10 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                 let _ = self.gen_expression(expr, ave, cst);
             }
 
+            Instr::ReserveMemory { size: expr, .. } => {
+                let _ = self.gen_expression(expr, ave, cst);
+            }
+
             Instr::SetStorage {
                 value: item_1,
                 storage: item_2,
@@ -110,6 +114,7 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                 salt,
                 address,
                 accounts,
+                storage_deposit_limit,
                 ..
             } => {
                 let _ = self.gen_expression(encoded_args, ave, cst);
@@ -130,6 +135,10 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                 if let ExternalCallAccounts::Present(expr) = accounts {
                     let _ = self.gen_expression(expr, ave, cst);
                 }
+
+                if let Some(expr) = storage_deposit_limit {
+                    let _ = self.gen_expression(expr, ave, cst);
+                }
             }
 
             Instr::ExternalCall {
@@ -315,17 +324,31 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
             },
 
             Instr::PushMemory {
+                loc,
                 res,
                 ty,
                 array,
                 value,
             } => Instr::PushMemory {
+                loc: *loc,
                 res: *res,
                 ty: ty.clone(),
                 array: *array,
                 value: Box::new(self.regenerate_expression(value, ave, cst).1),
             },
 
+            Instr::ReserveMemory {
+                loc,
+                ty,
+                array,
+                size,
+            } => Instr::ReserveMemory {
+                loc: *loc,
+                ty: ty.clone(),
+                array: *array,
+                size: self.regenerate_expression(size, ave, cst).1,
+            },
+
             Instr::Constructor {
                 success,
                 res,
@@ -339,6 +362,7 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                 loc,
                 accounts,
                 constructor_no,
+                storage_deposit_limit,
             } => {
                 let new_value = value
                     .as_ref()
@@ -360,6 +384,10 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                     .as_ref()
                     .map(|expr| self.regenerate_expression(expr, ave, cst).1);
 
+                let new_storage_deposit_limit = storage_deposit_limit
+                    .as_ref()
+                    .map(|expr| self.regenerate_expression(expr, ave, cst).1);
+
                 Instr::Constructor {
                     success: *success,
                     res: *res,
@@ -373,6 +401,7 @@ impl<'a, 'b: 'a> AvailableExpressionSet<'a> {
                     seeds: new_seeds,
                     loc: *loc,
                     accounts: new_accounts,
+                    storage_deposit_limit: new_storage_deposit_limit,
                 }
             }
 
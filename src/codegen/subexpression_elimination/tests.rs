@@ -242,6 +242,7 @@ fn not_tracked() {
     };
 
     let instr = Instr::PushMemory {
+        loc: Loc::Codegen,
         res: 0,
         ty: Type::Bool,
         array: 0,
@@ -294,6 +295,7 @@ fn complex_expression() {
     let div = Expression::SignedDivide {
         loc: Loc::Codegen,
         ty: Type::Int(8),
+        overflowing: false,
         left: Box::new(sum.clone()),
         right: Box::new(sub.clone()),
     };
@@ -422,6 +424,7 @@ fn string() {
         seeds: None,
         loc: Loc::Codegen,
         accounts: ExternalCallAccounts::AbsentArgument,
+        storage_deposit_limit: None,
     };
 
     let mut ave = AvailableExpression::default();
@@ -472,6 +475,7 @@ fn kill() {
     let div = Expression::SignedDivide {
         loc: Loc::Codegen,
         ty: Type::Int(8),
+        overflowing: false,
         left: Box::new(sum.clone()),
         right: Box::new(sub.clone()),
     };
@@ -582,6 +586,7 @@ fn clone() {
     let div = Expression::SignedDivide {
         loc: Loc::Codegen,
         ty: Type::Int(8),
+        overflowing: false,
         left: Box::new(sum.clone()),
         right: Box::new(sub.clone()),
     };
@@ -690,6 +695,7 @@ fn intersect() {
     let div = Expression::SignedDivide {
         loc: Loc::Codegen,
         ty: Type::Int(8),
+        overflowing: false,
         left: Box::new(sum.clone()),
         right: Box::new(sub.clone()),
     };
@@ -772,6 +778,7 @@ fn intersect() {
     };
 
     let instr3 = Instr::PushMemory {
+        loc: Loc::Codegen,
         res: 0,
         ty: Type::Bool,
         array: 0,
@@ -1081,6 +1088,7 @@ fn unite_expressions() {
     let div = Expression::SignedDivide {
         loc: Loc::Codegen,
         ty: Type::Int(8),
+        overflowing: false,
         left: Box::new(sum),
         right: Box::new(sub),
     };
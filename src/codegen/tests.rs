@@ -23,6 +23,9 @@ fn test_builtin_conversion() {
         ast::Builtin::GetAddress,
         ast::Builtin::MinimumBalance,
         ast::Builtin::MulMod,
+        ast::Builtin::MulDiv,
+        ast::Builtin::Sqrt,
+        ast::Builtin::Log2,
         ast::Builtin::Keccak256,
         ast::Builtin::Origin,
         ast::Builtin::ReadAddress,
@@ -59,6 +62,8 @@ fn test_builtin_conversion() {
         ast::Builtin::WriteUint256LE,
         ast::Builtin::WriteString,
         ast::Builtin::WriteBytes,
+        ast::Builtin::IndexOf,
+        ast::Builtin::ParseInt,
     ];
 
     let output: Vec<codegen::Builtin> = vec![
@@ -79,6 +84,9 @@ fn test_builtin_conversion() {
         codegen::Builtin::GetAddress,
         codegen::Builtin::MinimumBalance,
         codegen::Builtin::MulMod,
+        codegen::Builtin::MulDiv,
+        codegen::Builtin::Sqrt,
+        codegen::Builtin::Log2,
         codegen::Builtin::Keccak256,
         codegen::Builtin::Origin,
         codegen::Builtin::ReadFromBuffer,
@@ -115,9 +123,208 @@ fn test_builtin_conversion() {
         codegen::Builtin::WriteUint256LE,
         codegen::Builtin::WriteBytes,
         codegen::Builtin::WriteBytes,
+        codegen::Builtin::IndexOf,
+        codegen::Builtin::ParseInt,
     ];
 
     for (i, item) in input.iter().enumerate() {
         assert_eq!(codegen::Builtin::from(item), output[i]);
     }
 }
+
+#[test]
+fn llvm_pipeline() {
+    assert_eq!(
+        codegen::llvm_pipeline_passes("mem2reg,inline,globaldce"),
+        Ok(vec!["mem2reg", "inline", "globaldce"])
+    );
+    assert_eq!(
+        codegen::llvm_pipeline_passes("default<O2>, function(instcombine,sroa),constmerge"),
+        Ok(vec![
+            "default<O2>",
+            "function(instcombine,sroa)",
+            "constmerge"
+        ])
+    );
+    assert_eq!(
+        codegen::llvm_pipeline_passes("function(sroa"),
+        Err("unbalanced brackets in llvm pipeline 'function(sroa'".to_string())
+    );
+    assert_eq!(
+        codegen::llvm_pipeline_passes("sroa),inline"),
+        Err("unbalanced ')' in llvm pipeline 'sroa),inline'".to_string())
+    );
+    assert_eq!(
+        codegen::llvm_pipeline_passes("mem2reg,,inline"),
+        Err("empty pass in llvm pipeline 'mem2reg,,inline'".to_string())
+    );
+}
+
+#[cfg(feature = "llvm")]
+#[test]
+fn reachable_functions() {
+    use crate::codegen::{codegen, reachable::reachable_cfgs, Options};
+    use crate::file_resolver::FileResolver;
+    use crate::{parse_and_resolve, Target};
+    use std::ffi::OsStr;
+
+    let src = r#"
+        library L {
+            function used(uint a) internal pure returns (uint) { return a + 1; }
+            function unused(uint a) internal pure returns (uint) { return a * 2; }
+        }
+
+        contract Base {
+            function f() public virtual returns (uint) { return helper(); }
+            function helper() internal virtual returns (uint) { return 1; }
+        }
+
+        contract C is Base {
+            function() internal returns (uint) ptr;
+
+            function f() public override returns (uint) {
+                ptr = pointed;
+                return L.used(helper());
+            }
+
+            function helper() internal override returns (uint) { return 2; }
+            function pointed() internal returns (uint) { return 3; }
+            function dead() private returns (uint) { return L.unused(3); }
+        }"#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+    let mut ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::EVM);
+    codegen(&mut ns, &Options::default());
+    assert!(!ns.diagnostics.any_errors());
+
+    let contract = ns.contracts.iter().find(|c| c.id.name == "C").unwrap();
+    let reachable = reachable_cfgs(contract);
+
+    let unreachable: Vec<&str> = contract
+        .cfg
+        .iter()
+        .zip(reachable)
+        .filter(|(_, reachable)| !reachable)
+        .map(|(cfg, _)| cfg.name.as_str())
+        .collect();
+
+    // the overridden functions of the base contract, the private function which is never called,
+    // and the library function which it calls
+    assert_eq!(
+        unreachable,
+        [
+            "C::L::function::unused__uint256",
+            "C::Base::function::f",
+            "C::Base::function::helper",
+            "C::C::function::dead"
+        ]
+    );
+}
+
+#[test]
+fn immutables_in_code() {
+    use crate::codegen::{codegen, Options};
+    use crate::file_resolver::FileResolver;
+    use crate::sema::ast::Level;
+    use crate::{parse_and_resolve, Target};
+    use num_bigint::BigInt;
+    use std::ffi::OsStr;
+
+    let src = r#"
+        contract C {
+            uint64 immutable constant_value = 1 << 40;
+            bool public immutable flag = true;
+            address immutable owner;
+            int immutable assigned = 5;
+            uint32 immutable limit;
+            int64 immutable seen;
+            int64 copy;
+
+            constructor(address o) {
+                owner = o;
+                assigned = 7;
+                limit = 100 * 3;
+                seen = 9;
+                copy = seen;
+            }
+
+            function f() public view returns (uint64, bool, address, int, uint32, int64) {
+                return (constant_value, flag, owner, assigned, limit, seen);
+            }
+        }
+
+        contract D is C {
+            constructor() C(address(0)) {}
+        }"#;
+
+    let compile = |target| {
+        let mut cache = FileResolver::default();
+        cache.set_file_contents("test.sol", src.to_string());
+        let mut ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, target);
+        codegen(&mut ns, &Options::default());
+        assert!(!ns.diagnostics.any_errors());
+        ns
+    };
+
+    let ns = compile(Target::default_polkadot());
+
+    let names = |vars: Vec<(usize, usize)>| -> Vec<String> {
+        vars.into_iter()
+            .map(|(contract_no, var_no)| ns.contracts[contract_no].variables[var_no].name.clone())
+            .collect()
+    };
+
+    // an immutable which depends on the arguments of the constructor, or which is read while
+    // the contract is deployed, stays in storage
+    for contract_no in [0, 1] {
+        assert_eq!(
+            names(
+                ns.contracts[contract_no]
+                    .immutables_in_code
+                    .keys()
+                    .copied()
+                    .collect()
+            ),
+            ["constant_value", "flag", "assigned", "limit"]
+        );
+        assert_eq!(
+            names(
+                ns.contracts[contract_no]
+                    .layout
+                    .iter()
+                    .map(|layout| (layout.contract_no, layout.var_no))
+                    .collect()
+            ),
+            ["owner", "seen", "copy"]
+        );
+    }
+
+    // the value assigned in the constructor replaces the initializer
+    assert!(matches!(
+        &ns.contracts[0].immutables_in_code[&(0, 3)],
+        ast::Expression::NumberLiteral { value, .. } if *value == BigInt::from(7)
+    ));
+
+    let ns = compile(Target::Solana);
+
+    assert!(ns.contracts[0].immutables_in_code.is_empty());
+    assert_eq!(ns.contracts[0].layout.len(), 7);
+
+    // each immutable is noted once, although both contracts inherit it
+    assert_eq!(
+        ns.diagnostics
+            .iter()
+            .filter(|diag| diag.level == Level::Info && diag.message.starts_with("immutable"))
+            .map(|diag| diag.message.as_str())
+            .collect::<Vec<_>>(),
+        [
+            "immutable variable 'constant_value' is stored in the account data on Solana",
+            "immutable variable 'flag' is stored in the account data on Solana",
+            "immutable variable 'owner' is stored in the account data on Solana",
+            "immutable variable 'assigned' is stored in the account data on Solana",
+            "immutable variable 'limit' is stored in the account data on Solana",
+            "immutable variable 'seen' is stored in the account data on Solana",
+        ]
+    );
+}
@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::cfg::{ControlFlowGraph, Instr};
+use super::{Expression, Options};
+use crate::sema::ast::{FormatArg, Function, Namespace, RetrieveType, Type};
+use solang_parser::pt;
+
+/// Should calls to the function be traced. The `@trace` annotation of the function overrides the
+/// `--trace` option. Nothing is traced when prints are disabled, e.g. in release builds.
+pub(super) fn is_traced(func: &Function, opt: &Options) -> bool {
+    opt.log_prints && func.trace.map_or(opt.trace, |(_, trace)| trace)
+}
+
+/// Print the arguments when the function is entered, the return values when it returns, and
+/// each value it writes to storage.
+pub(super) fn instrument(cfg: &mut ControlFlowGraph, function_no: usize, ns: &Namespace) {
+    let func = &ns.functions[function_no];
+
+    let name = match (func.ty, func.contract_no) {
+        (pt::FunctionTy::Function, Some(contract_no)) => {
+            format!("{}.{}", ns.contracts[contract_no].id.name, func.id.name)
+        }
+        (pt::FunctionTy::Function, None) => func.id.name.clone(),
+        (ty, Some(contract_no)) => format!("{}.{ty}", ns.contracts[contract_no].id.name),
+        (ty, None) => ty.to_string(),
+    };
+
+    let args = cfg
+        .params
+        .iter()
+        .enumerate()
+        .map(|(arg_no, param)| Expression::FunctionArg {
+            loc: pt::Loc::Codegen,
+            ty: param.ty.clone(),
+            arg_no,
+        })
+        .collect::<Vec<_>>();
+
+    let mut enter = Message::new(format!("enter {name}("));
+    enter.values(&args, ns);
+    enter.literal(")");
+
+    cfg.blocks[0].instr.insert(0, enter.print(ns));
+
+    for block in &mut cfg.blocks {
+        let mut instrs = Vec::with_capacity(block.instr.len());
+
+        for instr in block.instr.drain(..) {
+            match &instr {
+                Instr::Return { value } => {
+                    let mut exit = Message::new(format!("exit {name}"));
+
+                    if !value.is_empty() {
+                        exit.literal(" returns (");
+                        exit.values(value, ns);
+                        exit.literal(")");
+                    }
+
+                    instrs.push(exit.print(ns));
+                }
+                Instr::ReturnData { .. } => {
+                    instrs.push(Message::new(format!("exit {name}")).print(ns));
+                }
+                Instr::SetStorage { value, storage, .. } => {
+                    let mut store = Message::new(format!("{name} stores "));
+                    store.values(std::slice::from_ref(value), ns);
+                    store.literal(" in slot ");
+                    store.values(std::slice::from_ref(storage), ns);
+
+                    instrs.push(store.print(ns));
+                }
+                Instr::ClearStorage { storage, .. } => {
+                    let mut clear = Message::new(format!("{name} clears slot "));
+                    clear.values(std::slice::from_ref(storage), ns);
+
+                    instrs.push(clear.print(ns));
+                }
+                _ => (),
+            }
+
+            instrs.push(instr);
+        }
+
+        block.instr = instrs;
+    }
+}
+
+/// A trace message, as a format string
+struct Message {
+    args: Vec<(FormatArg, Expression)>,
+}
+
+impl Message {
+    fn new(text: String) -> Self {
+        let mut message = Message { args: Vec::new() };
+
+        message.literal(&format!("trace: {text}"));
+
+        message
+    }
+
+    fn literal(&mut self, text: &str) {
+        self.args.push((
+            FormatArg::StringLiteral,
+            Expression::BytesLiteral {
+                loc: pt::Loc::Codegen,
+                ty: Type::Bytes(text.len() as u8),
+                value: text.as_bytes().to_vec(),
+            },
+        ));
+    }
+
+    /// Values which cannot be formatted are shown as their type
+    fn values(&mut self, values: &[Expression], ns: &Namespace) {
+        for (no, value) in values.iter().enumerate() {
+            if no > 0 {
+                self.literal(", ");
+            }
+
+            let ty = value.ty();
+
+            if matches!(
+                ty,
+                Type::Bool
+                    | Type::Int(_)
+                    | Type::Uint(_)
+                    | Type::Value
+                    | Type::Address(_)
+                    | Type::Contract(_)
+                    | Type::Bytes(_)
+                    | Type::String
+                    | Type::DynamicBytes
+                    | Type::Enum(_)
+            ) {
+                self.args.push((FormatArg::Default, value.clone()));
+            } else {
+                self.literal(&format!("<{}>", ty.to_string(ns)));
+            }
+        }
+    }
+
+    fn print(mut self, ns: &Namespace) -> Instr {
+        // the debug buffer on Polkadot is not split into lines
+        if ns.target.is_polkadot() {
+            self.literal("\n");
+        }
+
+        Instr::Print {
+            expr: Expression::FormatString {
+                loc: pt::Loc::Codegen,
+                args: self.args,
+            },
+        }
+    }
+}
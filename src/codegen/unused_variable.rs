@@ -49,7 +49,7 @@ pub fn should_remove_assignment(
         } => should_remove_assignment(&args[0], func, opt, ns),
 
         Expression::Builtin {
-            kind: Builtin::ArrayPop | Builtin::ArrayPush,
+            kind: Builtin::ArrayPop | Builtin::ArrayPush | Builtin::ArrayReserve,
             args,
             ..
         } => {
@@ -1,10 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::cfg::{BasicBlock, ControlFlowGraph, Instr};
-use super::reaching_definitions::{Def, Transfer};
+use super::reaching_definitions::{self, Def, Transfer};
 use crate::codegen::cfg::ASTFunction;
-use crate::codegen::Expression;
-use crate::sema::ast::{Namespace, Type};
+use crate::codegen::{Builtin, Expression};
+use crate::sema::ast::{Namespace, RetrieveType, Type};
+use crate::sema::Recurse;
 use indexmap::IndexMap;
 use std::collections::HashSet;
 
@@ -104,6 +105,7 @@ fn find_writable_vectors(
             | Instr::BranchCond { .. }
             | Instr::Switch { .. }
             | Instr::PopMemory { .. }
+            | Instr::ReserveMemory { .. }
             | Instr::LoadStorage { .. }
             | Instr::SetStorage { .. }
             | Instr::ClearStorage { .. }
@@ -243,3 +245,282 @@ fn update_vectors_to_slice(
         }
     }
 }
+
+/// `Expression::ReturnData` copies the return data of an external call into a new vector. When
+/// the vector is only read, and no host function is called between the external call and the
+/// last read, the return data can be read where the runtime left it. This is the case for
+/// `(bool ok, bytes ret) = addr.call(...)` followed by `abi.decode(ret, (...))`, which then
+/// decodes straight from the return data buffer.
+pub fn return_data_to_slice(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
+    let mut return_data = HashSet::new();
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        for (instr_no, instr) in block.instr.iter().enumerate() {
+            if let Instr::Set {
+                expr: Expression::ReturnData { .. },
+                ..
+            } = instr
+            {
+                return_data.insert(Def {
+                    block_no,
+                    instr_no,
+                    assignment_no: 0,
+                });
+            }
+        }
+    }
+
+    if return_data.is_empty() {
+        return;
+    }
+
+    let overwritten = overwritten_return_data(cfg, &return_data);
+    let mut rejected = HashSet::new();
+
+    for (block_no, block) in cfg.blocks.iter().enumerate() {
+        let mut vars = block.defs.clone();
+        let mut overwritten = overwritten[block_no].clone();
+
+        for (instr_no, instr) in block.instr.iter().enumerate() {
+            let mut uses = Uses::default();
+
+            instr_uses(instr, &mut uses);
+
+            for var_no in &uses.other {
+                if let Some(defs) = vars.get(var_no) {
+                    rejected.extend(defs.keys());
+                }
+            }
+
+            for var_no in &uses.read {
+                if let Some(defs) = vars.get(var_no) {
+                    rejected.extend(defs.keys().filter(|def| overwritten.contains(def)));
+                }
+            }
+
+            for transfer in &block.transfers[instr_no] {
+                if let Transfer::Mod { var_no } = transfer {
+                    if let Some(defs) = vars.get(var_no) {
+                        rejected.extend(defs.keys());
+                    }
+                }
+            }
+
+            overwrite_return_data(block_no, instr_no, instr, &return_data, &mut overwritten);
+
+            reaching_definitions::apply_transfers(&block.transfers[instr_no], &mut vars);
+        }
+
+        // the variables in phis have the type of the vector
+        if let Some(phis) = &block.phis {
+            for phi in phis {
+                if let Some(defs) = block.defs.get(phi) {
+                    rejected.extend(defs.keys());
+                }
+            }
+        }
+    }
+
+    for def in return_data.difference(&rejected) {
+        if let Instr::Set { loc, res, .. } = &cfg.blocks[def.block_no].instr[def.instr_no] {
+            let (loc, res) = (*loc, *res);
+
+            cfg.blocks[def.block_no].instr[def.instr_no] = Instr::Set {
+                loc,
+                res,
+                expr: Expression::Builtin {
+                    loc,
+                    tys: vec![Type::Slice(Box::new(Type::Bytes(1)))],
+                    kind: Builtin::ReturnDataSlice,
+                    args: Vec::new(),
+                },
+            };
+
+            if let ASTFunction::SolidityFunction(function_no) = cfg.function_no {
+                if let Some(var) = ns.functions[function_no].symtable.vars.get_mut(&res) {
+                    var.slice = true;
+                }
+            }
+        }
+    }
+}
+
+/// For each block, the return data defs which may have been overwritten when the block is entered
+fn overwritten_return_data(
+    cfg: &ControlFlowGraph,
+    return_data: &HashSet<Def>,
+) -> Vec<HashSet<Def>> {
+    let mut entry = vec![HashSet::new(); cfg.blocks.len()];
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        for (block_no, block) in cfg.blocks.iter().enumerate() {
+            let mut overwritten = entry[block_no].clone();
+
+            for (instr_no, instr) in block.instr.iter().enumerate() {
+                overwrite_return_data(block_no, instr_no, instr, return_data, &mut overwritten);
+            }
+
+            for succ in block.successors() {
+                if !overwritten.is_subset(&entry[succ]) {
+                    entry[succ].extend(overwritten.iter().copied());
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    entry
+}
+
+fn overwrite_return_data(
+    block_no: usize,
+    instr_no: usize,
+    instr: &Instr,
+    return_data: &HashSet<Def>,
+    overwritten: &mut HashSet<Def>,
+) {
+    if calls_host(instr) {
+        overwritten.clone_from(return_data);
+    }
+
+    overwritten.remove(&Def {
+        block_no,
+        instr_no,
+        assignment_no: 0,
+    });
+}
+
+/// Can the instruction call a host function. On Polkadot, many host functions write to the
+/// scratch buffer which holds the return data, so it is not safe to read it afterwards.
+fn calls_host(instr: &Instr) -> bool {
+    match instr {
+        Instr::Set { .. }
+        | Instr::Nop
+        | Instr::Branch { .. }
+        | Instr::BranchCond { .. }
+        | Instr::Switch { .. }
+        | Instr::Store { .. }
+        | Instr::PushMemory { .. }
+        | Instr::PopMemory { .. }
+        | Instr::ReserveMemory { .. }
+        | Instr::MemCopy { .. }
+        | Instr::WriteBuffer { .. }
+        // these end the function
+        | Instr::Return { .. }
+        | Instr::ReturnData { .. }
+        | Instr::ReturnCode { .. }
+        | Instr::AssertFailure { .. } => {
+            let mut host = false;
+
+            instr.recurse_expressions(&mut host, expr_calls_host);
+
+            host
+        }
+        _ => true,
+    }
+}
+
+fn expr_calls_host(expr: &Expression, host: &mut bool) -> bool {
+    match expr {
+        Expression::Builtin {
+            kind:
+                Builtin::ArrayLength
+                | Builtin::ReadFromBuffer
+                | Builtin::SliceFromBuffer
+                | Builtin::ReturnDataSlice
+                | Builtin::Concat
+                | Builtin::AddMod
+                | Builtin::MulMod
+                | Builtin::MulDiv
+                | Builtin::Sqrt
+                | Builtin::Log2
+                | Builtin::IndexOf
+                | Builtin::ParseInt,
+            ..
+        } => (),
+        Expression::Builtin { .. }
+        | Expression::Keccak256 { .. }
+        | Expression::StorageArrayLength { .. } => {
+            *host = true;
+        }
+        _ => (),
+    }
+
+    !*host
+}
+
+/// The variables an instruction uses. Variables in `read` are only used as a buffer to read
+/// from, which works with a slice.
+#[derive(Default)]
+struct Uses {
+    read: HashSet<usize>,
+    other: HashSet<usize>,
+}
+
+fn instr_uses(instr: &Instr, uses: &mut Uses) {
+    match instr {
+        // copies are followed by the reaching definitions
+        Instr::Set {
+            expr: Expression::Variable { .. },
+            ..
+        } => (),
+        Instr::MemCopy {
+            source,
+            destination,
+            bytes,
+        } => {
+            match source {
+                Expression::AdvancePointer {
+                    pointer,
+                    bytes_offset,
+                } if matches!(pointer.as_ref(), Expression::Variable { .. }) => {
+                    if let Expression::Variable { var_no, .. } = pointer.as_ref() {
+                        uses.read.insert(*var_no);
+                    }
+                    bytes_offset.recurse(uses, expr_uses);
+                }
+                Expression::Variable { var_no, .. } if source.ty().is_dynamic_memory() => {
+                    uses.read.insert(*var_no);
+                }
+                _ => source.recurse(uses, expr_uses),
+            }
+
+            destination.recurse(uses, expr_uses);
+            bytes.recurse(uses, expr_uses);
+        }
+        Instr::WriteBuffer { buf, .. } => {
+            buf.recurse(uses, expr_uses);
+            instr.recurse_expressions(uses, expr_uses);
+        }
+        _ => instr.recurse_expressions(uses, expr_uses),
+    }
+}
+
+fn expr_uses(expr: &Expression, uses: &mut Uses) -> bool {
+    match expr {
+        Expression::Builtin {
+            kind: Builtin::ArrayLength | Builtin::ReadFromBuffer,
+            args,
+            ..
+        } if matches!(args.first(), Some(Expression::Variable { .. })) => {
+            if let Expression::Variable { var_no, .. } = &args[0] {
+                uses.read.insert(*var_no);
+            }
+
+            for arg in &args[1..] {
+                arg.recurse(uses, expr_uses);
+            }
+
+            false
+        }
+        Expression::Variable { var_no, .. } => {
+            uses.other.insert(*var_no);
+
+            true
+        }
+        _ => true,
+    }
+}
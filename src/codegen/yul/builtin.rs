@@ -92,6 +92,70 @@ pub(crate) fn process_builtin(
             byte_builtin(loc, args, contract_no, ns, cfg, vartab, opt)
         }
 
+        // Outside of EVM, sema only allows forwarding a bytes memory variable, or no data at all
+        YulBuiltInFunction::Revert if ns.target != Target::EVM => {
+            let encoded_args = args
+                .first()
+                .map(|arg| expression(arg, contract_no, ns, vartab, cfg, opt));
+            cfg.add(vartab, Instr::AssertFailure { encoded_args });
+            Expression::Poison
+        }
+
+        YulBuiltInFunction::Return if ns.target != Target::EVM => {
+            let data = match args.first() {
+                Some(arg) => expression(arg, contract_no, ns, vartab, cfg, opt),
+                None => Expression::AllocDynamicBytes {
+                    loc: *loc,
+                    ty: Type::DynamicBytes,
+                    size: Box::new(Expression::NumberLiteral {
+                        loc: *loc,
+                        ty: Type::Uint(32),
+                        value: BigInt::from(0),
+                    }),
+                    initializer: Some(vec![]),
+                },
+            };
+            let data_len = Expression::Builtin {
+                loc: *loc,
+                tys: vec![Type::Uint(32)],
+                kind: Builtin::ArrayLength,
+                args: vec![data.clone()],
+            };
+            cfg.add(vartab, Instr::ReturnData { data, data_len });
+            Expression::Poison
+        }
+
+        YulBuiltInFunction::ReturnDataSize if ns.target != Target::EVM => {
+            let data = Expression::ReturnData { loc: *loc };
+            Expression::Builtin {
+                loc: *loc,
+                tys: vec![Type::Uint(32)],
+                kind: Builtin::ArrayLength,
+                args: vec![data],
+            }
+            .cast(&Type::Uint(256), ns)
+        }
+
+        YulBuiltInFunction::CallDataSize if ns.target != Target::EVM => {
+            let data = Expression::Builtin {
+                loc: *loc,
+                tys: vec![Type::DynamicBytes],
+                kind: Builtin::Calldata,
+                args: vec![],
+            };
+            Expression::Builtin {
+                loc: *loc,
+                tys: vec![Type::Uint(32)],
+                kind: Builtin::ArrayLength,
+                args: vec![data],
+            }
+            .cast(&Type::Uint(256), ns)
+        }
+
+        YulBuiltInFunction::CallDataLoad if ns.target != Target::EVM => {
+            calldataload(loc, args, contract_no, ns, cfg, vartab, opt)
+        }
+
         YulBuiltInFunction::SignExtend
         | YulBuiltInFunction::Keccak256
         | YulBuiltInFunction::Pop
@@ -198,7 +262,7 @@ pub(crate) fn process_builtin(
             log_runtime_error(opt.log_runtime_errors,  "reached invalid instruction", *loc, cfg,
             vartab,
             ns);
-            assert_failure(loc, SolidityError::Panic(PanicCode::Generic), ns, cfg, vartab);
+            assert_failure(loc, SolidityError::panic(PanicCode::Generic, opt), ns, cfg, vartab);
             Expression::Poison
         }
 
@@ -238,6 +302,141 @@ pub(crate) fn process_builtin(
     }
 }
 
+/// Read 32 bytes of call data from the given offset as a big endian number. Like on EVM, the
+/// bytes past the end of the call data are zero.
+fn calldataload(
+    loc: &pt::Loc,
+    args: &[ast::YulExpression],
+    contract_no: usize,
+    ns: &Namespace,
+    cfg: &mut ControlFlowGraph,
+    vartab: &mut Vartable,
+    opt: &Options,
+) -> Expression {
+    let offset = expression(&args[0], contract_no, ns, vartab, cfg, opt).cast(&Type::Uint(256), ns);
+
+    // Append 32 zero bytes, so that 32 bytes can be read from any offset in the call data
+    let padded = vartab.temp_anonymous(&Type::DynamicBytes);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: *loc,
+            res: padded,
+            expr: Expression::Builtin {
+                loc: *loc,
+                tys: vec![Type::DynamicBytes],
+                kind: Builtin::Concat,
+                args: vec![
+                    Expression::Builtin {
+                        loc: *loc,
+                        tys: vec![Type::DynamicBytes],
+                        kind: Builtin::Calldata,
+                        args: vec![],
+                    },
+                    Expression::AllocDynamicBytes {
+                        loc: *loc,
+                        ty: Type::DynamicBytes,
+                        size: Box::new(Expression::NumberLiteral {
+                            loc: *loc,
+                            ty: Type::Uint(32),
+                            value: BigInt::from(32),
+                        }),
+                        initializer: None,
+                    },
+                ],
+            },
+        },
+    );
+    let padded = Expression::Variable {
+        loc: *loc,
+        ty: Type::DynamicBytes,
+        var_no: padded,
+    };
+
+    let calldata_len = Expression::Subtract {
+        loc: *loc,
+        ty: Type::Uint(32),
+        overflowing: true,
+        left: Box::new(Expression::Builtin {
+            loc: *loc,
+            tys: vec![Type::Uint(32)],
+            kind: Builtin::ArrayLength,
+            args: vec![padded.clone()],
+        }),
+        right: Box::new(Expression::NumberLiteral {
+            loc: *loc,
+            ty: Type::Uint(32),
+            value: BigInt::from(32),
+        }),
+    };
+
+    let cond = Expression::MoreEqual {
+        loc: *loc,
+        signed: false,
+        left: Box::new(offset.clone()),
+        right: Box::new(calldata_len.cast(&Type::Uint(256), ns)),
+    };
+
+    let temp = vartab.temp_anonymous(&Type::Uint(256));
+
+    let then = cfg.new_basic_block("then".to_string());
+    let else_ = cfg.new_basic_block("else".to_string());
+    let endif = cfg.new_basic_block("endif".to_string());
+
+    cfg.add(
+        vartab,
+        Instr::BranchCond {
+            cond,
+            true_block: then,
+            false_block: else_,
+        },
+    );
+
+    cfg.set_basic_block(then);
+    vartab.new_dirty_tracker();
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: pt::Loc::Codegen,
+            res: temp,
+            expr: Expression::NumberLiteral {
+                loc: pt::Loc::Codegen,
+                ty: Type::Uint(256),
+                value: BigInt::zero(),
+            },
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: endif });
+
+    cfg.set_basic_block(else_);
+    cfg.add(
+        vartab,
+        Instr::Set {
+            loc: pt::Loc::Codegen,
+            res: temp,
+            expr: Expression::Cast {
+                loc: *loc,
+                ty: Type::Uint(256),
+                expr: Box::new(Expression::Builtin {
+                    loc: *loc,
+                    tys: vec![Type::Bytes(32)],
+                    kind: Builtin::ReadFromBuffer,
+                    args: vec![padded, offset.cast(&Type::Uint(32), ns)],
+                }),
+            },
+        },
+    );
+    cfg.add(vartab, Instr::Branch { block: endif });
+    cfg.set_phis(endif, vartab.pop_dirty_tracker());
+    cfg.set_basic_block(endif);
+
+    Expression::Variable {
+        loc: pt::Loc::Codegen,
+        ty: Type::Uint(256),
+        var_no: temp,
+    }
+}
+
 /// Process arithmetic operations
 fn process_arithmetic(
     loc: &pt::Loc,
@@ -292,6 +491,8 @@ fn process_arithmetic(
             let expr = Expression::SignedDivide {
                 loc: *loc,
                 ty: left.ty(),
+                // sdiv wraps on overflow
+                overflowing: true,
                 left: Box::new(left),
                 right: Box::new(right.clone()),
             };
@@ -8,6 +8,7 @@ use crate::codegen::{Builtin, Expression, Options};
 use crate::sema::ast::{ArrayLength, Namespace, Type};
 use crate::sema::yul::ast;
 use crate::sema::yul::ast::YulSuffix;
+use crate::Target;
 use num_bigint::{BigInt, Sign};
 use solang_parser::pt;
 use solang_parser::pt::{Loc, StorageLocation};
@@ -144,8 +145,26 @@ fn process_suffix_access(
             _ => (),
         },
         YulSuffix::Offset => match expr {
-            ast::YulExpression::StorageVariable(..)
-            | ast::YulExpression::SolidityLocalVariable(
+            ast::YulExpression::StorageVariable(_, _, var_contract_no, var_no) => {
+                // small variables may share their slot, except on Solana
+                let offset = if ns.target == Target::Solana {
+                    0
+                } else {
+                    ns.contracts[contract_no]
+                        .layout
+                        .iter()
+                        .find(|l| l.contract_no == *var_contract_no && l.var_no == *var_no)
+                        .and_then(|l| l.packed)
+                        .map_or(0, |packed| packed.offset)
+                };
+
+                return Expression::NumberLiteral {
+                    loc: Loc::Codegen,
+                    ty: Type::Uint(256),
+                    value: BigInt::from(offset),
+                };
+            }
+            ast::YulExpression::SolidityLocalVariable(
                 _,
                 _,
                 Some(StorageLocation::Storage(_)),
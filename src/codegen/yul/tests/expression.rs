@@ -14,6 +14,7 @@ use crate::{sema, Target};
 use num_bigint::{BigInt, Sign};
 use once_cell::unsync::OnceCell;
 use solang_parser::pt::{self, ContractTy, Loc, StorageLocation, Visibility};
+use std::collections::BTreeMap;
 
 #[test]
 fn bool_literal() {
@@ -122,6 +123,7 @@ fn contract_constant_variable() {
         tags: vec![],
         name: "const_var".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Uint(64),
         visibility: Visibility::Public(None),
         constant: false,
@@ -133,6 +135,7 @@ fn contract_constant_variable() {
         }),
         assigned: false,
         read: false,
+        enumerable: None,
     };
 
     let contract = Contract {
@@ -146,6 +149,8 @@ fn contract_constant_variable() {
         bases: vec![],
         using: vec![],
         layout: vec![],
+        stripped_variables: vec![],
+        immutables_in_code: BTreeMap::new(),
         fixed_layout_size: Default::default(),
         functions: vec![],
         all_functions: Default::default(),
@@ -157,9 +162,11 @@ fn contract_constant_variable() {
         initializer: None,
         default_constructor: None,
         cfg: vec![],
+        coverage_counters: vec![],
         code: OnceCell::new(),
         instantiable: true,
         program_id: None,
+        account_discriminator: None,
     };
     ns.contracts.push(contract);
 
@@ -187,6 +194,7 @@ fn global_constant_variable() {
         tags: vec![],
         name: "const_var".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Uint(64),
         visibility: Visibility::Public(None),
         constant: false,
@@ -198,6 +206,7 @@ fn global_constant_variable() {
         }),
         assigned: false,
         read: false,
+        enumerable: None,
     };
     ns.constants.push(var);
     let expr = ast::YulExpression::ConstantVariable(loc, Type::Uint(64), None, 0);
@@ -272,6 +281,7 @@ fn slot_suffix() {
         contract_no: 0,
         var_no: 0,
         ty: Type::Uint(256),
+        packed: None,
     };
     let contract = Contract {
         tags: vec![],
@@ -284,6 +294,8 @@ fn slot_suffix() {
         bases: vec![],
         using: vec![],
         layout: vec![layout],
+        stripped_variables: vec![],
+        immutables_in_code: BTreeMap::new(),
         fixed_layout_size: Default::default(),
         functions: vec![],
         all_functions: Default::default(),
@@ -295,9 +307,11 @@ fn slot_suffix() {
         initializer: None,
         default_constructor: None,
         cfg: vec![],
+        coverage_counters: vec![],
         code: OnceCell::new(),
         instantiable: true,
         program_id: None,
+        account_discriminator: None,
     };
     ns.contracts.push(contract);
 
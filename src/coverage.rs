@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage reports for contracts compiled with coverage counters. Each basic block with code
+//! from the source reports its counter in the logs when it runs, as `coverage: <contract>
+//! <counter>`. The coverage map of a contract has the source lines of the code counted by each
+//! counter, so the counters in the logs can be turned into the number of times each line ran.
+//!
+//! The report is written in the lcov format, which is understood by most coverage tools.
+
+use crate::sema::ast::Namespace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The start of the log message of a coverage counter
+pub const COUNTER_PREFIX: &str = "coverage: ";
+
+/// The source lines of the code counted by each coverage counter of a contract
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CoverageMap {
+    pub contract: String,
+    /// The source files, by file number
+    pub sources: Vec<String>,
+    pub counters: Vec<Vec<SourceLine>>,
+}
+
+/// Lines start at 1
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceLine {
+    pub file: usize,
+    pub line: usize,
+}
+
+/// The log message of a coverage counter
+pub fn counter_log(contract: &str, counter: usize) -> String {
+    format!("{COUNTER_PREFIX}{contract} {counter}")
+}
+
+/// Generate the coverage map of a contract, which must have been through codegen with coverage
+pub fn gen_coverage_map(contract_no: usize, ns: &Namespace) -> CoverageMap {
+    let contract = &ns.contracts[contract_no];
+
+    let counters = contract
+        .coverage_counters
+        .iter()
+        .map(|locs| {
+            let mut lines: Vec<SourceLine> = locs
+                .iter()
+                .map(|loc| {
+                    let file = loc.file_no();
+                    let (line, _) = ns.files[file].offset_to_line_column(loc.start());
+
+                    SourceLine {
+                        file,
+                        line: line + 1,
+                    }
+                })
+                .collect();
+
+            lines.sort();
+            lines.dedup();
+
+            lines
+        })
+        .collect();
+
+    CoverageMap {
+        contract: contract.id.name.clone(),
+        sources: ns.files.iter().map(|file| file.to_string()).collect(),
+        counters,
+    }
+}
+
+/// How often the counters of contracts were reported in the logs
+#[derive(Default)]
+pub struct Report {
+    maps: Vec<(CoverageMap, Vec<u64>)>,
+}
+
+impl Report {
+    /// Add the coverage map of a contract. When contracts have the same name, the counters in
+    /// the logs are counted for the contract which was added last.
+    pub fn add_map(&mut self, map: CoverageMap) {
+        let counts = vec![0; map.counters.len()];
+
+        self.maps.push((map, counts));
+    }
+
+    /// Count the coverage counters in the logs. Returns the logs without the counters.
+    pub fn add_logs(&mut self, logs: &str) -> String {
+        let mut rest = String::new();
+
+        for line in logs.lines() {
+            match self.counter(line) {
+                Some((map_no, counter)) => self.maps[map_no].1[counter] += 1,
+                None => {
+                    rest.push_str(line);
+                    rest.push('\n');
+                }
+            }
+        }
+
+        rest
+    }
+
+    /// The coverage map and counter of a log line. On Solana, the message is prefixed by the
+    /// runtime.
+    fn counter(&self, line: &str) -> Option<(usize, usize)> {
+        let (_, message) = line.split_once(COUNTER_PREFIX)?;
+        let (contract, counter) = message.trim().rsplit_once(' ')?;
+        let counter: usize = counter.parse().ok()?;
+
+        let map_no = self
+            .maps
+            .iter()
+            .rposition(|(map, _)| map.contract == contract && counter < map.counters.len())?;
+
+        Some((map_no, counter))
+    }
+
+    /// How often each line ran, by source file. A line which is counted by more than one counter
+    /// ran as often as the counter which was reported most.
+    pub fn lines(&self) -> BTreeMap<&str, BTreeMap<usize, u64>> {
+        let mut files: BTreeMap<&str, BTreeMap<usize, u64>> = BTreeMap::new();
+
+        for (map, counts) in &self.maps {
+            for (lines, count) in map.counters.iter().zip(counts) {
+                for line in lines {
+                    let hits = files
+                        .entry(map.sources[line.file].as_str())
+                        .or_default()
+                        .entry(line.line)
+                        .or_default();
+
+                    *hits = (*hits).max(*count);
+                }
+            }
+        }
+
+        files
+    }
+
+    /// The report in the lcov format
+    pub fn lcov(&self) -> String {
+        let mut out = String::new();
+
+        for (file, lines) in self.lines() {
+            writeln!(out, "SF:{file}").unwrap();
+
+            for (line, hits) in &lines {
+                writeln!(out, "DA:{line},{hits}").unwrap();
+            }
+
+            writeln!(out, "LF:{}", lines.len()).unwrap();
+            writeln!(
+                out,
+                "LH:{}",
+                lines.values().filter(|hits| **hits > 0).count()
+            )
+            .unwrap();
+            writeln!(out, "end_of_record").unwrap();
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> CoverageMap {
+        CoverageMap {
+            contract: "Counter".into(),
+            sources: vec!["counter.sol".into()],
+            counters: vec![
+                vec![
+                    SourceLine { file: 0, line: 3 },
+                    SourceLine { file: 0, line: 4 },
+                ],
+                vec![SourceLine { file: 0, line: 4 }],
+                vec![SourceLine { file: 0, line: 6 }],
+            ],
+        }
+    }
+
+    #[test]
+    fn counters_in_logs() {
+        let mut report = Report::default();
+
+        report.add_map(map());
+
+        let logs = report.add_logs(
+            "coverage: Counter 0\nprint: hello,\ncoverage: Counter 1\n\
+             Program log: coverage: Counter 1\ncoverage: Other 0\ncoverage: Counter 7\n",
+        );
+
+        assert_eq!(
+            logs,
+            "print: hello,\ncoverage: Other 0\ncoverage: Counter 7\n"
+        );
+
+        assert_eq!(
+            report.lcov(),
+            "SF:counter.sol\nDA:3,1\nDA:4,2\nDA:6,0\nLF:3\nLH:2\nend_of_record\n"
+        );
+    }
+}
@@ -1,11 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::codegen::encoding::create_encoder;
+use crate::codegen::encoding::const_encode_with_selector;
 use crate::codegen::revert::{error_msg_with_loc, PanicCode, SolidityError};
 use crate::codegen::Expression;
 use crate::sema::ast::{ArrayLength, Contract, Namespace, StructType, Type};
 use std::cell::RefCell;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str;
 
 use num_bigint::BigInt;
@@ -16,23 +16,26 @@ use tempfile::tempdir;
 #[cfg(feature = "wasm_opt")]
 use wasm_opt::OptimizationOptions;
 
-use crate::codegen::{cfg::ReturnCode, Options};
+use crate::codegen::{cfg::ReturnCode, llvm_pipeline_passes, HeapAllocator, Options};
 use crate::emit::{polkadot, TargetRuntime};
-use crate::emit::{solana, BinaryOp, Generate};
+use crate::emit::{solana, stylus, BinaryOp, Generate};
 use crate::linker::link;
 use crate::Target;
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::debug_info::DICompileUnit;
 use inkwell::debug_info::DebugInfoBuilder;
 use inkwell::memory_buffer::MemoryBuffer;
 use inkwell::module::{Linkage, Module};
-use inkwell::passes::PassManager;
-use inkwell::targets::{CodeModel, FileType, RelocMode};
+use inkwell::passes::{PassBuilderOptions, PassManager, PassManagerBuilder};
+use inkwell::targets::{CodeModel, FileType, RelocMode, TargetMachine};
 use inkwell::types::{
     ArrayType, BasicMetadataTypeEnum, BasicType, BasicTypeEnum, FunctionType, IntType, StringRadix,
 };
-use inkwell::values::{BasicValueEnum, FunctionValue, GlobalValue, IntValue, PointerValue};
+use inkwell::values::{
+    BasicValueEnum, FunctionValue, GlobalValue, IntValue, PointerValue, StructValue,
+};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
 use inkwell::OptimizationLevel;
@@ -166,6 +169,8 @@ pub struct Binary<'a> {
     /// No initializer for vector_new
     pub(crate) vector_init_empty: PointerValue<'a>,
     global_constant_strings: RefCell<HashMap<Vec<u8>, PointerValue<'a>>>,
+    /// Functions which revert with constant error data, by the error data
+    revert_stubs: RefCell<HashMap<Vec<u8>, FunctionValue<'a>>>,
 }
 
 impl<'a> Binary<'a> {
@@ -175,17 +180,20 @@ impl<'a> Binary<'a> {
         contract: &'a Contract,
         ns: &'a Namespace,
         opt: &'a Options,
-        _contract_no: usize,
+        contract_no: usize,
     ) -> Self {
-        let std_lib = load_stdlib(context, &ns.target);
+        let std_lib = load_stdlib(context, &ns.target, opt.heap_allocator);
         match ns.target {
             Target::Polkadot { .. } => {
                 polkadot::PolkadotTarget::build(context, &std_lib, contract, ns, opt)
             }
-            Target::Solana => solana::SolanaTarget::build(context, &std_lib, contract, ns, opt),
+            Target::Solana => {
+                solana::SolanaTarget::build(context, &std_lib, contract, ns, opt, contract_no)
+            }
+            Target::Stylus => stylus::StylusTarget::build(context, &std_lib, contract, ns, opt),
             #[cfg(feature = "soroban")]
             Target::Soroban => {
-                soroban::SorobanTarget::build(context, &std_lib, contract, ns, opt, _contract_no)
+                soroban::SorobanTarget::build(context, &std_lib, contract, ns, opt, contract_no)
             }
             _ => unimplemented!("target not implemented"),
         }
@@ -201,33 +209,13 @@ impl<'a> Binary<'a> {
             return Ok(self.code.borrow().clone());
         }
 
-        match self.options.opt_level.into() {
-            OptimizationLevel::Default | OptimizationLevel::Aggressive => {
-                let pass_manager = PassManager::create(());
-
-                pass_manager.add_promote_memory_to_register_pass();
-                pass_manager.add_function_inlining_pass();
-                pass_manager.add_global_dce_pass();
-                pass_manager.add_constant_merge_pass();
+        let target_machine = self.target_machine();
 
-                pass_manager.run_on(&self.module);
-            }
-            _ => {}
+        match &self.options.llvm_passes {
+            Some(pipeline) => self.run_llvm_pipeline(&target_machine, pipeline)?,
+            None => self.run_default_passes(&self.default_passes())?,
         }
 
-        let target = inkwell::targets::Target::from_name(self.target.llvm_target_name()).unwrap();
-
-        let target_machine = target
-            .create_target_machine(
-                &self.target.llvm_target_triple(),
-                "",
-                self.target.llvm_features(),
-                self.options.opt_level.into(),
-                RelocMode::Default,
-                CodeModel::Default,
-            )
-            .unwrap();
-
         let code = target_machine
             .write_to_memory_buffer(
                 &self.module,
@@ -260,7 +248,9 @@ impl<'a> Binary<'a> {
             OptimizationOptions::from(level)
                 .mvp_features_only()
                 .zero_filled_memory(true)
-                .debug_info(self.options.generate_debug_information)
+                .debug_info(
+                    self.options.generate_debug_information || self.options.generate_line_tables,
+                )
                 .run(&infile, &outfile)
                 .map_err(|err| format!("wasm-opt for binary {} failed: {}", self.name, err))?;
 
@@ -270,6 +260,126 @@ impl<'a> Binary<'a> {
         Ok(code)
     }
 
+    /// Save the llvm IR before the llvm passes are run, and again after each pass. The file for
+    /// each step is given by `path`, from the number of the step and the name of the pass. The
+    /// module is left optimized.
+    pub fn dump_llvm_per_pass(
+        &self,
+        path: impl Fn(usize, &str) -> PathBuf,
+    ) -> Result<Vec<PathBuf>, String> {
+        let target_machine = self.target_machine();
+        let mut files = Vec::new();
+
+        let mut dump = |pass: &str| -> Result<(), String> {
+            let file = path(files.len(), pass);
+            self.dump_llvm(&file)?;
+            files.push(file);
+            Ok(())
+        };
+
+        dump("before")?;
+
+        match &self.options.llvm_passes {
+            Some(pipeline) => {
+                for pass in llvm_pipeline_passes(pipeline)? {
+                    self.run_llvm_pipeline(&target_machine, pass)?;
+                    dump(pass)?;
+                }
+            }
+            None => {
+                for pass in self.default_passes() {
+                    self.run_default_passes(&[pass])?;
+                    dump(pass)?;
+                }
+            }
+        }
+
+        Ok(files)
+    }
+
+    fn target_machine(&self) -> TargetMachine {
+        let target = inkwell::targets::Target::from_name(self.target.llvm_target_name()).unwrap();
+
+        target
+            .create_target_machine(
+                &self.target.llvm_target_triple(),
+                "",
+                self.target.llvm_features(),
+                self.options.opt_level.into(),
+                RelocMode::Default,
+                CodeModel::Default,
+            )
+            .unwrap()
+    }
+
+    /// The llvm passes which are run when no pipeline is given. These are only run when
+    /// optimizing.
+    fn default_passes(&self) -> Vec<&'static str> {
+        match self.options.opt_level.into() {
+            OptimizationLevel::Default | OptimizationLevel::Aggressive => {
+                let mut passes = vec!["mem2reg", "inline", "globaldce", "constmerge"];
+
+                // outline the cold blocks after inlining, so the revert paths of the inlined
+                // functions are moved too
+                if self.options.cold_split {
+                    passes.push("hotcoldsplit");
+                }
+
+                passes
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Run some of the default passes, with the legacy pass manager. The hot/cold splitting pass
+    /// is only available with the new pass manager, so it runs after the others.
+    fn run_default_passes(&self, passes: &[&str]) -> Result<(), String> {
+        if passes.is_empty() {
+            return Ok(());
+        }
+
+        let pass_manager = PassManager::create(());
+
+        for pass in passes {
+            match *pass {
+                "mem2reg" => pass_manager.add_promote_memory_to_register_pass(),
+                "inline" => match self.options.inline_threshold {
+                    Some(threshold) => {
+                        // at optimization level none, the builder only adds the inliner
+                        let builder = PassManagerBuilder::create();
+                        builder.set_optimization_level(OptimizationLevel::None);
+                        builder.set_inliner_with_threshold(threshold);
+                        builder.populate_module_pass_manager(&pass_manager);
+                    }
+                    None => pass_manager.add_function_inlining_pass(),
+                },
+                "globaldce" => pass_manager.add_global_dce_pass(),
+                "constmerge" => pass_manager.add_constant_merge_pass(),
+                "hotcoldsplit" => (),
+                _ => unreachable!(),
+            }
+        }
+
+        pass_manager.run_on(&self.module);
+
+        if passes.contains(&"hotcoldsplit") {
+            self.run_llvm_pipeline(&self.target_machine(), "hotcoldsplit")?;
+        }
+
+        Ok(())
+    }
+
+    /// Run a pipeline of passes given on the command line, with the new pass manager
+    fn run_llvm_pipeline(
+        &self,
+        target_machine: &TargetMachine,
+        pipeline: &str,
+    ) -> Result<(), String> {
+        self.module
+            .run_passes(pipeline, target_machine, PassBuilderOptions::create())
+            .map_err(|err| format!("llvm passes '{pipeline}' failed: {err}"))
+    }
+
     /// Mark all functions as internal unless they're in the export_list. This helps the
     /// llvm globaldce pass eliminate unnecessary functions and reduce the wasm output.
     pub(crate) fn internalize(&self, export_list: &[&str]) {
@@ -369,7 +479,11 @@ impl<'a> Binary<'a> {
             "",
             0,
             "",
-            inkwell::debug_info::DWARFEmissionKind::Full,
+            if opt.generate_debug_information {
+                inkwell::debug_info::DWARFEmissionKind::Full
+            } else {
+                inkwell::debug_info::DWARFEmissionKind::LineTablesOnly
+            },
             0,
             false,
             false,
@@ -435,6 +549,7 @@ impl<'a> Binary<'a> {
                 .ptr_type(AddressSpace::default())
                 .const_null(),
             global_constant_strings: RefCell::new(HashMap::new()),
+            revert_stubs: RefCell::new(HashMap::new()),
         }
     }
 
@@ -788,7 +903,7 @@ impl<'a> Binary<'a> {
 
         if ns.target == Target::Soroban {
             match returns.iter().next() {
-                Some(ret) => return self.llvm_type(ret, ns).fn_type(&args, false),
+                Some(ret) => return self.llvm_var_ty(ret, ns).fn_type(&args, false),
                 None => return self.context.void_type().fn_type(&args, false),
             }
         }
@@ -1108,6 +1223,42 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// Number of elements there is room for in a vector, without reallocating it
+    pub(crate) fn vector_capacity(&self, vector: BasicValueEnum<'a>) -> IntValue<'a> {
+        // field 1 is the size of the allocation
+        let vector = vector.into_pointer_value();
+        let vector_type = self.module.get_struct_type("struct.vector").unwrap();
+
+        let size = unsafe {
+            self.builder
+                .build_gep(
+                    vector_type,
+                    vector,
+                    &[
+                        self.context.i32_type().const_zero(),
+                        self.context.i32_type().const_int(1, false),
+                    ],
+                    "vector_size",
+                )
+                .unwrap()
+        };
+
+        self.builder
+            .build_select(
+                self.builder
+                    .build_is_null(vector, "vector_is_null")
+                    .unwrap(),
+                self.context.i32_type().const_zero(),
+                self.builder
+                    .build_load(self.context.i32_type(), size, "vector_size")
+                    .unwrap()
+                    .into_int_value(),
+                "capacity",
+            )
+            .unwrap()
+            .into_int_value()
+    }
+
     /// Return the pointer to the actual bytes in the vector
     pub(crate) fn vector_bytes(&self, vector: BasicValueEnum<'a>) -> PointerValue<'a> {
         if vector.is_struct_value() {
@@ -1135,6 +1286,38 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// Create a slice of bytes from a pointer and a 32 bit length
+    pub(crate) fn slice(
+        &self,
+        data: PointerValue<'a>,
+        len: IntValue<'a>,
+        ns: &Namespace,
+    ) -> StructValue<'a> {
+        let len = self
+            .builder
+            .build_int_z_extend(
+                len,
+                self.context
+                    .custom_width_int_type(ns.target.ptr_size().into()),
+                "len",
+            )
+            .unwrap();
+
+        let slice_ty = self
+            .llvm_type(&Type::Slice(Box::new(Type::Bytes(1))), ns)
+            .into_struct_type();
+
+        let slice = self
+            .builder
+            .build_insert_value(slice_ty.get_undef(), data, 0, "slice")
+            .unwrap();
+
+        self.builder
+            .build_insert_value(slice, len, 1, "slice")
+            .unwrap()
+            .into_struct_value()
+    }
+
     /// Dereference an array
     pub(crate) fn array_subscript(
         &self,
@@ -1219,22 +1402,26 @@ impl<'a> Binary<'a> {
         );
     }
 
-    /// Emit encoded error data of "Panic(uint256)" as interned global string.
+    /// Revert with the encoded error data of "Panic(uint256)".
     ///
-    /// On Solana, because reverts do not return data, a nil ptr is returned.
-    pub(super) fn panic_data_const(
+    /// On Solana, because reverts do not return data, or when revert data is left out, the
+    /// revert has no data.
+    pub(super) fn assert_panic<T: TargetRuntime<'a> + ?Sized>(
         &self,
+        target: &T,
         ns: &Namespace,
         code: PanicCode,
-    ) -> (PointerValue<'a>, IntValue<'a>) {
-        if ns.target == Target::Solana {
-            return (
+    ) {
+        if ns.target == Target::Solana || !self.options.revert_data {
+            target.assert_failure(
+                self,
                 self.context
                     .i8_type()
                     .ptr_type(AddressSpace::default())
                     .const_null(),
                 self.context.i32_type().const_zero(),
             );
+            return;
         }
 
         let expr = Expression::NumberLiteral {
@@ -1242,25 +1429,90 @@ impl<'a> Binary<'a> {
             ty: Type::Uint(256),
             value: (code as u8).into(),
         };
-        let bytes = create_encoder(ns, false)
-            .const_encode(&[SolidityError::Panic(code).selector_expression(ns), expr])
-            .unwrap();
-        (
-            self.emit_global_string(&code.to_string(), &bytes, true),
-            self.context.i32_type().const_int(bytes.len() as u64, false),
-        )
+        let selector = SolidityError::Panic(code).selector_expression(ns);
+        let bytes = const_encode_with_selector(selector, &[expr], ns).unwrap();
+
+        self.assert_failure_const(target, &bytes, ns);
+    }
+
+    /// Revert with constant error data, like an encoded reason string or panic. On targets where
+    /// the revert does not return from the function, the reverts with the same data all call one
+    /// function which reverts with it, so that a contract with many `require()` calls with the
+    /// same reason string has the data and the code which passes it to the host only once.
+    pub(crate) fn assert_failure_const<T: TargetRuntime<'a> + ?Sized>(
+        &self,
+        target: &T,
+        data: &[u8],
+        ns: &Namespace,
+    ) {
+        let len = self.context.i32_type().const_int(data.len() as u64, false);
+
+        if !matches!(ns.target, Target::Polkadot { .. } | Target::Stylus) {
+            let ptr = self.emit_global_string("revert_data", data, true);
+            target.assert_failure(self, ptr, len);
+            return;
+        }
+
+        let existing = self.revert_stubs.borrow().get(data).copied();
+
+        let stub = existing.unwrap_or_else(|| {
+            let pos = self.builder.get_insert_block().unwrap();
+            // the stub has no debug info, so it must not get the location of the caller
+            let debug_loc = self.builder.get_current_debug_location();
+            self.builder.unset_current_debug_location();
+
+            let name = format!("revert_data_{}", self.revert_stubs.borrow().len());
+            let stub = self.module.add_function(
+                &name,
+                self.context.void_type().fn_type(&[], false),
+                Some(Linkage::Internal),
+            );
+
+            // inlining the stub into each caller would undo the sharing
+            for attr in ["noinline", "noreturn", "cold"] {
+                stub.add_attribute(
+                    AttributeLoc::Function,
+                    self.context
+                        .create_enum_attribute(Attribute::get_named_enum_kind_id(attr), 0),
+                );
+            }
+
+            self.builder
+                .position_at_end(self.context.append_basic_block(stub, "entry"));
+
+            let ptr = self.emit_global_string("revert_data", data, true);
+            target.assert_failure(self, ptr, len);
+
+            self.builder.position_at_end(pos);
+
+            if let Some(debug_loc) = debug_loc {
+                self.builder.set_current_debug_location(debug_loc);
+            }
+
+            self.revert_stubs.borrow_mut().insert(data.to_vec(), stub);
+
+            stub
+        });
+
+        self.builder.build_call(stub, &[], "").unwrap();
+        self.builder.build_unreachable().unwrap();
     }
 }
 
 /// Return the stdlib as parsed llvm module. The solidity standard library is hardcoded into
 /// the solang library
-fn load_stdlib<'a>(context: &'a Context, target: &Target) -> Module<'a> {
+fn load_stdlib<'a>(context: &'a Context, target: &Target, allocator: HeapAllocator) -> Module<'a> {
     if *target == Target::Solana {
         let memory = MemoryBuffer::create_from_memory_range(BPF_IR[0], "bpf_bc");
 
         let module = Module::parse_bitcode_from_buffer(&memory, context).unwrap();
 
-        for bc in BPF_IR.iter().skip(1) {
+        let heap = match allocator {
+            HeapAllocator::Bump => BPF_BUMP_IR,
+            HeapAllocator::FreeList => BPF_HEAP_IR,
+        };
+
+        for bc in BPF_IR.iter().skip(1).chain(std::iter::once(&heap)) {
             let memory = MemoryBuffer::create_from_memory_range(bc, "bpf_bc");
 
             module
@@ -1283,8 +1535,8 @@ fn load_stdlib<'a>(context: &'a Context, target: &Target) -> Module<'a> {
             .unwrap();
     }
 
-    if let Target::Polkadot { .. } = *target {
-        // contracts pallet does not provide ripemd160
+    if let Target::Polkadot { .. } | Target::Stylus = *target {
+        // neither the contracts pallet nor Stylus provide ripemd160
         let memory = MemoryBuffer::create_from_memory_range(RIPEMD160_IR, "ripemd160");
 
         module
@@ -1295,15 +1547,17 @@ fn load_stdlib<'a>(context: &'a Context, target: &Target) -> Module<'a> {
     module
 }
 
-static BPF_IR: [&[u8]; 6] = [
+static BPF_IR: [&[u8]; 5] = [
     include_bytes!("../../target/bpf/stdlib.bc"),
     include_bytes!("../../target/bpf/bigint.bc"),
     include_bytes!("../../target/bpf/format.bc"),
     include_bytes!("../../target/bpf/solana.bc"),
     include_bytes!("../../target/bpf/ripemd160.bc"),
-    include_bytes!("../../target/bpf/heap.bc"),
 ];
 
+static BPF_HEAP_IR: &[u8] = include_bytes!("../../target/bpf/heap.bc");
+static BPF_BUMP_IR: &[u8] = include_bytes!("../../target/bpf/bump.bc");
+
 static WASM_IR: [&[u8]; 4] = [
     include_bytes!("../../target/wasm/stdlib.bc"),
     include_bytes!("../../target/wasm/heap.bc"),
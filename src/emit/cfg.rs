@@ -1,16 +1,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::codegen::{cfg::ControlFlowGraph, vartable::Storage};
+use crate::codegen::{
+    cfg::{ControlFlowGraph, Instr},
+    vartable::Storage,
+};
 use crate::emit::binary::Binary;
+use crate::emit::debug_info::FunctionDebugInfo;
 use crate::emit::instructions::process_instruction;
 use crate::emit::{TargetRuntime, Variable};
 use crate::sema::ast::{Contract, Namespace};
 use crate::Target;
-use inkwell::debug_info::{AsDIScope, DISubprogram, DIType};
 use inkwell::types::BasicType;
 use inkwell::values::{FunctionValue, PhiValue};
 use inkwell::AddressSpace;
-use solang_parser::pt;
 use solang_parser::pt::CodeLocation;
 use std::collections::{HashMap, VecDeque};
 
@@ -33,74 +35,9 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
     function: FunctionValue<'a>,
     ns: &Namespace,
 ) {
-    let dibuilder = &bin.dibuilder;
-    let compile_unit = &bin.compile_unit;
-    let file = compile_unit.get_file();
-    let mut di_func_scope: Option<DISubprogram<'_>> = None;
-
-    if bin.options.generate_debug_information {
-        let return_type = function.get_type().get_return_type();
-        match return_type {
-            None => {}
-            Some(return_type) => {
-                let return_type_size = return_type.size_of().unwrap();
-                let size = return_type_size.get_type().get_bit_width();
-                let mut type_name = "size_".to_owned();
-                type_name.push_str(&size.to_string());
-                let di_flags = if cfg.public {
-                    inkwell::debug_info::DIFlagsConstants::PUBLIC
-                } else {
-                    inkwell::debug_info::DIFlagsConstants::PRIVATE
-                };
-
-                let di_return_type = dibuilder
-                    .create_basic_type(&type_name, size as u64, 0x00, di_flags)
-                    .unwrap();
-                let param_types = function.get_type().get_param_types();
-                let di_param_types: Vec<DIType<'_>> = param_types
-                    .iter()
-                    .map(|typ| {
-                        let mut param_tname = "size_".to_owned();
-                        let param_size = typ.size_of().unwrap().get_type().get_bit_width();
-                        param_tname.push_str(&size.to_string());
-                        dibuilder
-                            .create_basic_type(&param_tname, param_size as u64, 0x00, di_flags)
-                            .unwrap()
-                            .as_type()
-                    })
-                    .collect();
-                let di_func_type = dibuilder.create_subroutine_type(
-                    file,
-                    Some(di_return_type.as_type()),
-                    di_param_types.as_slice(),
-                    di_flags,
-                );
-
-                let func_loc = cfg.blocks[0].instr.first().unwrap().loc();
-                let line_num = if let pt::Loc::File(file_offset, offset, _) = func_loc {
-                    let (line, _) = ns.files[file_offset].offset_to_line_column(offset);
-                    line
-                } else {
-                    0
-                };
-
-                di_func_scope = Some(dibuilder.create_function(
-                    compile_unit.as_debug_info_scope(),
-                    function.get_name().to_str().unwrap(),
-                    None,
-                    file,
-                    line_num.try_into().unwrap(),
-                    di_func_type,
-                    true,
-                    true,
-                    line_num.try_into().unwrap(),
-                    di_flags,
-                    false,
-                ));
-                function.set_subprogram(di_func_scope.unwrap());
-            }
-        }
-    }
+    let mut debug_info = (bin.options.generate_debug_information
+        || bin.options.generate_line_tables)
+        .then(|| FunctionDebugInfo::new(bin, cfg, function, ns));
 
     let mut blocks: HashMap<usize, BasicBlock> = HashMap::new();
 
@@ -169,6 +106,12 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
         }
     }
 
+    let cold = if bin.options.cold_split {
+        cfg.cold_blocks()
+    } else {
+        vec![false; cfg.blocks.len()]
+    };
+
     work.push_back(Work { block_no: 0, vars });
 
     while let Some(mut w) = work.pop_front() {
@@ -181,32 +124,20 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
         }
 
         for ins in &cfg.blocks[w.block_no].instr {
-            if bin.options.generate_debug_information {
-                let debug_loc = ins.loc();
-                if let pt::Loc::File(file_offset, offset, _) = debug_loc {
-                    let (line, col) = ns.files[file_offset].offset_to_line_column(offset);
-                    let debug_loc = dibuilder.create_debug_location(
-                        bin.context,
-                        line as u32,
-                        col as u32,
-                        di_func_scope.unwrap().as_debug_info_scope(),
-                        None,
-                    );
-                    bin.builder.set_current_debug_location(debug_loc);
-                } else {
-                    // For instructions that do not have a location, insert a debug location pointing to line 0.
-                    // If -g flag is enabled, every instruction should have a debug location. This is necessary
-                    // because llvm's inliner pass requires function call instructions to have a debug location.
-                    let debug_loc = dibuilder.create_debug_location(
-                        bin.context,
-                        0_u32,
-                        0_u32,
-                        di_func_scope.unwrap().as_debug_info_scope(),
-                        None,
-                    );
-                    bin.builder.set_current_debug_location(debug_loc);
-                }
-            }
+            // Every instruction needs a debug location, even if it has no location in the source,
+            // because llvm's inliner pass requires function call instructions to have one.
+            let debug_loc = debug_info.as_mut().map(|debug_info| {
+                let debug_loc = debug_info.location(bin, ins.loc(), ns);
+                bin.builder.set_current_debug_location(debug_loc);
+                debug_loc
+            });
+
+            // the values of the variables shown in the debugger before the instruction
+            let before: Vec<_> = debug_info
+                .iter()
+                .flat_map(|debug_info| debug_info.variables())
+                .map(|var_no| (var_no, w.vars[&var_no].value))
+                .collect();
 
             process_instruction(
                 target,
@@ -220,10 +151,57 @@ pub(super) fn emit_cfg<'a, T: TargetRuntime<'a> + ?Sized>(
                 &mut blocks,
                 contract,
             );
-            bin.builder.unset_current_debug_location();
-            dibuilder.finalize();
+
+            if let Instr::BranchCond {
+                true_block,
+                false_block,
+                ..
+            } = ins
+            {
+                if cold[*true_block] != cold[*false_block] {
+                    set_branch_weights(bin, cold[*false_block]);
+                }
+            }
+
+            if let (Some(debug_info), Some(debug_loc)) = (&mut debug_info, debug_loc) {
+                for (var_no, value) in before {
+                    if w.vars[&var_no].value != value {
+                        debug_info.assign(bin, function, var_no, &w.vars[&var_no], debug_loc);
+                    }
+                }
+
+                bin.builder.unset_current_debug_location();
+            }
         }
     }
+
+    if debug_info.is_some() {
+        bin.dibuilder.finalize();
+    }
+}
+
+/// Annotate the conditional branch which was just built with the way it is likely to go, when
+/// one of its destinations only leads to a revert. llvm places the cold destination after the
+/// code which runs when the function succeeds, or moves it out of the function altogether.
+fn set_branch_weights(bin: &Binary, likely_true: bool) {
+    let branch = bin
+        .builder
+        .get_insert_block()
+        .and_then(|bb| bb.get_terminator())
+        .unwrap();
+
+    // the same weights as clang uses for __builtin_expect()
+    let (true_weight, false_weight) = if likely_true { (2000, 1) } else { (1, 2000) };
+
+    let weights = bin.context.metadata_node(&[
+        bin.context.metadata_string("branch_weights").into(),
+        bin.context.i32_type().const_int(true_weight, false).into(),
+        bin.context.i32_type().const_int(false_weight, false).into(),
+    ]);
+
+    branch
+        .set_metadata(weights, bin.context.get_kind_id("prof"))
+        .unwrap();
 }
 
 pub(super) fn create_block<'a>(
@@ -0,0 +1,322 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! DWARF debug information for the functions of a contract, so that debuggers can map the code
+//! back to the Solidity source lines, and show the values of local variables.
+//!
+//! Each named local variable gets a stack slot, which is updated when the variable is assigned.
+//! When optimizing, llvm turns the slots back into registers and keeps track of where the value
+//! of the variable lives. When only the line tables are generated, there are no variables.
+
+use crate::codegen::cfg::{ASTFunction, ControlFlowGraph};
+use crate::codegen::vartable::Storage;
+use crate::codegen::OptimizationLevel;
+use crate::emit::binary::Binary;
+use crate::emit::Variable;
+use crate::sema::ast::{Namespace, Type};
+use inkwell::debug_info::{
+    AsDIScope, DIFile, DIFlags, DIFlagsConstants, DILocalVariable, DILocation, DIScope,
+    DISubprogram, DIType,
+};
+use inkwell::types::BasicTypeEnum;
+use inkwell::values::{FunctionValue, PointerValue};
+use inkwell::AddressSpace;
+use solang_parser::pt::{self, CodeLocation};
+use std::collections::HashMap;
+
+/// The DWARF encodings of basic types
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x07;
+
+pub(super) struct FunctionDebugInfo<'a> {
+    subprogram: DISubprogram<'a>,
+    /// The file the function is declared in
+    file_no: Option<usize>,
+    /// Scopes for code from other files, e.g. the initializers of inherited state variables
+    file_scopes: HashMap<usize, DIScope<'a>>,
+    /// The variables which are shown in the debugger, with their names
+    variables: HashMap<usize, (DILocalVariable<'a>, String)>,
+    /// The stack slot of each variable, once it has been assigned
+    slots: HashMap<usize, (PointerValue<'a>, BasicTypeEnum<'a>)>,
+}
+
+impl<'a> FunctionDebugInfo<'a> {
+    /// Create the debug information for a function, and attach it to the function
+    pub(super) fn new(
+        bin: &Binary<'a>,
+        cfg: &ControlFlowGraph,
+        function: FunctionValue<'a>,
+        ns: &Namespace,
+    ) -> Self {
+        let dibuilder = &bin.dibuilder;
+
+        // the debugger shows the name from the source, if there is one
+        let (name, loc) = match cfg.function_no {
+            ASTFunction::SolidityFunction(function_no) => {
+                let func = &ns.functions[function_no];
+
+                (func.id.name.as_str(), func.loc_prototype)
+            }
+            ASTFunction::YulFunction(function_no) => {
+                let func = &ns.yul_functions[function_no];
+
+                (func.name.as_str(), func.loc)
+            }
+            ASTFunction::None => (
+                "",
+                cfg.blocks
+                    .iter()
+                    .flat_map(|block| &block.instr)
+                    .map(|instr| instr.loc())
+                    .find(|loc| matches!(loc, pt::Loc::File(..)))
+                    .unwrap_or(pt::Loc::Codegen),
+            ),
+        };
+
+        let name = if name.is_empty() {
+            cfg.name.as_str()
+        } else {
+            name
+        };
+
+        let (file_no, file, line) = match loc {
+            pt::Loc::File(file_no, offset, _) => {
+                let (line, _) = ns.files[file_no].offset_to_line_column(offset);
+
+                (Some(file_no), di_file(bin, file_no, ns), line as u32 + 1)
+            }
+            _ => (None, bin.compile_unit.get_file(), 0),
+        };
+
+        let flags = if cfg.public {
+            DIFlags::PUBLIC
+        } else {
+            DIFlags::PRIVATE
+        };
+
+        let return_type = match cfg.returns.as_slice() {
+            [ret] => di_type(bin, &ret.ty, ns),
+            _ => None,
+        };
+
+        let param_types: Vec<DIType> = cfg
+            .params
+            .iter()
+            .filter_map(|param| di_type(bin, &param.ty, ns))
+            .collect();
+
+        let subroutine_type =
+            dibuilder.create_subroutine_type(file, return_type, &param_types, flags);
+
+        let subprogram = dibuilder.create_function(
+            file.as_debug_info_scope(),
+            name,
+            function.get_name().to_str().ok(),
+            file,
+            line,
+            subroutine_type,
+            true,
+            true,
+            line,
+            flags,
+            bin.options.opt_level != OptimizationLevel::None,
+        );
+
+        function.set_subprogram(subprogram);
+
+        let mut debug_info = FunctionDebugInfo {
+            subprogram,
+            file_no,
+            file_scopes: HashMap::new(),
+            variables: HashMap::new(),
+            slots: HashMap::new(),
+        };
+
+        // line tables do not have variables
+        if !bin.options.generate_debug_information {
+            return debug_info;
+        }
+
+        for (var_no, var) in &cfg.vars {
+            if !matches!(var.storage, Storage::Local) || !is_named(&var.id) {
+                continue;
+            }
+
+            let (pt::Loc::File(file_no, offset, _), Some(ty)) =
+                (var.id.loc, di_type(bin, &var.ty, ns))
+            else {
+                continue;
+            };
+
+            let (line, _) = ns.files[file_no].offset_to_line_column(offset);
+            let scope = debug_info.scope(bin, file_no, ns);
+
+            let variable = dibuilder.create_auto_variable(
+                scope,
+                &var.id.name,
+                di_file(bin, file_no, ns),
+                line as u32 + 1,
+                ty,
+                true,
+                DIFlags::ZERO,
+                0,
+            );
+
+            debug_info
+                .variables
+                .insert(*var_no, (variable, var.id.name.clone()));
+        }
+
+        debug_info
+    }
+
+    /// The debug location of some code. Code without a location gets line 0, since llvm requires
+    /// calls to have a location once a function has debug information.
+    pub(super) fn location(
+        &mut self,
+        bin: &Binary<'a>,
+        loc: pt::Loc,
+        ns: &Namespace,
+    ) -> DILocation<'a> {
+        let (line, column, scope) = match loc {
+            pt::Loc::File(file_no, offset, _) => {
+                let (line, column) = ns.files[file_no].offset_to_line_column(offset);
+
+                (
+                    line as u32 + 1,
+                    column as u32 + 1,
+                    self.scope(bin, file_no, ns),
+                )
+            }
+            _ => (0, 0, self.subprogram.as_debug_info_scope()),
+        };
+
+        bin.dibuilder
+            .create_debug_location(bin.context, line, column, scope, None)
+    }
+
+    /// The variables which are shown in the debugger
+    pub(super) fn variables(&self) -> impl Iterator<Item = usize> + '_ {
+        self.variables.keys().copied()
+    }
+
+    /// Store the new value of a variable in its stack slot, so that the debugger can find it
+    pub(super) fn assign(
+        &mut self,
+        bin: &Binary<'a>,
+        function: FunctionValue<'a>,
+        var_no: usize,
+        var: &Variable<'a>,
+        loc: DILocation<'a>,
+    ) {
+        let Some((variable, name)) = self.variables.get(&var_no) else {
+            return;
+        };
+
+        let block = bin.builder.get_insert_block().unwrap();
+
+        if block.get_terminator().is_some() {
+            return;
+        }
+
+        let ty = var.value.get_type();
+
+        let (slot, slot_ty) = *self.slots.entry(var_no).or_insert_with(|| {
+            let slot = bin.build_alloca(function, ty, name);
+
+            bin.dibuilder
+                .insert_declare_at_end(slot, Some(*variable), None, loc, block);
+
+            (slot, ty)
+        });
+
+        // the value of a variable should always have the same type
+        if slot_ty == ty {
+            bin.builder.build_store(slot, var.value).unwrap();
+        }
+    }
+
+    /// The scope for code in a file. Code in the file of the function is in the function itself,
+    /// other files get a lexical block in the function.
+    fn scope(&mut self, bin: &Binary<'a>, file_no: usize, ns: &Namespace) -> DIScope<'a> {
+        if self.file_no == Some(file_no) {
+            return self.subprogram.as_debug_info_scope();
+        }
+
+        let subprogram = self.subprogram;
+
+        *self.file_scopes.entry(file_no).or_insert_with(|| {
+            bin.dibuilder
+                .create_lexical_block(
+                    subprogram.as_debug_info_scope(),
+                    di_file(bin, file_no, ns),
+                    0,
+                    0,
+                )
+                .as_debug_info_scope()
+        })
+    }
+}
+
+/// Variables which are declared in the source, rather than temporaries made by codegen
+fn is_named(id: &pt::Identifier) -> bool {
+    !id.name.is_empty() && !id.name.contains('.')
+}
+
+fn di_file<'a>(bin: &Binary<'a>, file_no: usize, ns: &Namespace) -> DIFile<'a> {
+    let path = &ns.files[file_no].path;
+
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let directory = path
+        .parent()
+        .map(|dir| dir.to_string_lossy())
+        .unwrap_or_default();
+
+    bin.dibuilder.create_file(&name, &directory)
+}
+
+/// The debug type of a Solidity type. Value types are shown as numbers, and reference types as
+/// pointers.
+fn di_type<'a>(bin: &Binary<'a>, ty: &Type, ns: &Namespace) -> Option<DIType<'a>> {
+    let basic = |name: &str, bits: u64, encoding: u32| {
+        bin.dibuilder
+            .create_basic_type(name, bits, encoding, DIFlags::ZERO)
+            .ok()
+            .map(|ty| ty.as_type())
+    };
+
+    match ty {
+        Type::Bool => basic("bool", 8, DW_ATE_BOOLEAN),
+        Type::Int(bits) => basic(&ty.to_string(ns), *bits as u64, DW_ATE_SIGNED),
+        Type::Uint(bits) => basic(&ty.to_string(ns), *bits as u64, DW_ATE_UNSIGNED),
+        Type::Bytes(n) => basic(&ty.to_string(ns), *n as u64 * 8, DW_ATE_UNSIGNED),
+        Type::Address(_) | Type::Contract(_) => basic(
+            &ty.to_string(ns),
+            ns.address_length as u64 * 8,
+            DW_ATE_UNSIGNED,
+        ),
+        Type::Value => basic("value", ns.value_length as u64 * 8, DW_ATE_UNSIGNED),
+        Type::Enum(_) => basic(&ty.to_string(ns), 8, DW_ATE_UNSIGNED),
+        Type::UserType(no) => di_type(bin, &ns.user_types[*no].ty, ns),
+        Type::Unreachable | Type::Void | Type::Unresolved => None,
+        _ => {
+            let pointee = basic("byte", 8, DW_ATE_UNSIGNED)?;
+            let size = ns.target.ptr_size() as u64;
+
+            Some(
+                bin.dibuilder
+                    .create_pointer_type(
+                        &ty.to_string(ns),
+                        pointee,
+                        size,
+                        size as u32,
+                        AddressSpace::default(),
+                    )
+                    .as_type(),
+            )
+        }
+    }
+}
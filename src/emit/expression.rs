@@ -4,7 +4,10 @@ use crate::codegen::cfg::{HashTy, ReturnCode};
 use crate::codegen::revert::PanicCode;
 use crate::codegen::{Builtin, Expression};
 use crate::emit::binary::Binary;
-use crate::emit::math::{build_binary_op_with_overflow_check, multiply, power};
+use crate::emit::math::{
+    build_binary_op_with_overflow_check, divide, log2, mul_div, multiply, power, shift,
+    signed_divide_overflow_check, sqrt,
+};
 use crate::emit::strings::{format_string, string_location};
 use crate::emit::{loop_builder::LoopBuilder, BinaryOp, TargetRuntime, Variable};
 use crate::emit_context;
@@ -225,96 +228,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             let bits = left.get_type().get_bit_width();
 
             if bits > 64 {
-                let div_bits = if bits <= 128 { 128 } else { 256 };
-
-                let name = format!("udivmod{div_bits}");
-
-                let f = bin
-                    .module
-                    .get_function(&name)
-                    .expect("div function missing");
-
-                let ty = bin.context.custom_width_int_type(div_bits);
-
-                let dividend = bin.build_alloca(function, ty, "dividend");
-                let divisor = bin.build_alloca(function, ty, "divisor");
-                let rem = bin.build_alloca(function, ty, "remainder");
-                let quotient = bin.build_alloca(function, ty, "quotient");
-
-                bin.builder
-                    .build_store(
-                        dividend,
-                        if bits < div_bits {
-                            bin.builder.build_int_z_extend(left, ty, "").unwrap()
-                        } else {
-                            left
-                        },
-                    )
-                    .unwrap();
-
-                bin.builder
-                    .build_store(
-                        divisor,
-                        if bits < div_bits {
-                            bin.builder.build_int_z_extend(right, ty, "").unwrap()
-                        } else {
-                            right
-                        },
-                    )
-                    .unwrap();
-
-                let ret = bin
-                    .builder
-                    .build_call(
-                        f,
-                        &[dividend.into(), divisor.into(), rem.into(), quotient.into()],
-                        "udiv",
-                    )
-                    .unwrap()
-                    .try_as_basic_value()
-                    .left()
-                    .unwrap();
-
-                let success = bin
-                    .builder
-                    .build_int_compare(
-                        IntPredicate::EQ,
-                        ret.into_int_value(),
-                        bin.context.i32_type().const_zero(),
-                        "success",
-                    )
-                    .unwrap();
-
-                let success_block = bin.context.append_basic_block(function, "success");
-                let bail_block = bin.context.append_basic_block(function, "bail");
-                bin.builder
-                    .build_conditional_branch(success, success_block, bail_block)
-                    .unwrap();
-
-                bin.builder.position_at_end(bail_block);
-
-                // throw division by zero error should be an assert
-                bin.log_runtime_error(target, "division by zero".to_string(), Some(*loc), ns);
-                let (revert_out, revert_out_len) =
-                    bin.panic_data_const(ns, PanicCode::DivisionByZero);
-                target.assert_failure(bin, revert_out, revert_out_len);
-
-                bin.builder.position_at_end(success_block);
-
-                let quotient = bin
-                    .builder
-                    .build_load(ty, quotient, "quotient")
-                    .unwrap()
-                    .into_int_value();
-
-                if bits < div_bits {
-                    bin.builder
-                        .build_int_truncate(quotient, left.get_type(), "")
-                        .unwrap()
-                } else {
-                    quotient
-                }
-                .into()
+                divide(target, bin, function, left, right, false, ns, *loc)
+                    .0
+                    .into()
             } else {
                 bin.builder
                     .build_int_unsigned_div(left, right, "")
@@ -323,104 +239,25 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             }
         }
         Expression::SignedDivide {
-            loc, left, right, ..
+            loc,
+            overflowing,
+            left,
+            right,
+            ..
         } => {
             let left = expression(target, bin, left, vartab, function, ns).into_int_value();
             let right = expression(target, bin, right, vartab, function, ns).into_int_value();
 
             let bits = left.get_type().get_bit_width();
 
-            if bits > 64 {
-                let div_bits = if bits <= 128 { 128 } else { 256 };
-
-                let name = format!("sdivmod{div_bits}");
-
-                let f = bin
-                    .module
-                    .get_function(&name)
-                    .expect("div function missing");
-
-                let ty = bin.context.custom_width_int_type(div_bits);
-
-                let dividend = bin.build_alloca(function, ty, "dividend");
-                let divisor = bin.build_alloca(function, ty, "divisor");
-                let rem = bin.build_alloca(function, ty, "remainder");
-                let quotient = bin.build_alloca(function, ty, "quotient");
-
-                bin.builder
-                    .build_store(
-                        dividend,
-                        if bits < div_bits {
-                            bin.builder.build_int_s_extend(left, ty, "").unwrap()
-                        } else {
-                            left
-                        },
-                    )
-                    .unwrap();
-
-                bin.builder
-                    .build_store(
-                        divisor,
-                        if bits < div_bits {
-                            bin.builder.build_int_s_extend(right, ty, "").unwrap()
-                        } else {
-                            right
-                        },
-                    )
-                    .unwrap();
-
-                let ret = bin
-                    .builder
-                    .build_call(
-                        f,
-                        &[dividend.into(), divisor.into(), rem.into(), quotient.into()],
-                        "udiv",
-                    )
-                    .unwrap()
-                    .try_as_basic_value()
-                    .left()
-                    .unwrap();
-
-                let success = bin
-                    .builder
-                    .build_int_compare(
-                        IntPredicate::EQ,
-                        ret.into_int_value(),
-                        bin.context.i32_type().const_zero(),
-                        "success",
-                    )
-                    .unwrap();
-
-                let success_block = bin.context.append_basic_block(function, "success");
-                let bail_block = bin.context.append_basic_block(function, "bail");
-                bin.builder
-                    .build_conditional_branch(success, success_block, bail_block)
-                    .unwrap();
-
-                bin.builder.position_at_end(bail_block);
-
-                // throw division by zero error should be an assert
-                bin.log_runtime_error(target, "division by zero".to_string(), Some(*loc), ns);
-                let (revert_out, revert_out_len) =
-                    bin.panic_data_const(ns, PanicCode::DivisionByZero);
-                target.assert_failure(bin, revert_out, revert_out_len);
-
-                bin.builder.position_at_end(success_block);
-
-                let quotient = bin
-                    .builder
-                    .build_load(ty, quotient, "quotient")
-                    .unwrap()
-                    .into_int_value();
+            if !overflowing {
+                signed_divide_overflow_check(target, bin, function, left, right, ns, *loc);
+            }
 
-                if bits < div_bits {
-                    bin.builder
-                        .build_int_truncate(quotient, left.get_type(), "")
-                        .unwrap()
-                } else {
-                    quotient
-                }
-                .into()
+            if bits > 64 {
+                divide(target, bin, function, left, right, true, ns, *loc)
+                    .0
+                    .into()
             } else if ns.target == Target::Solana {
                 // no signed div on BPF; do abs udev and then negate if needed
                 let left_negative = bin
@@ -481,10 +318,38 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     )
                     .unwrap()
             } else {
+                // sdiv is undefined for type(int).min / -1, so divide by 1 and negate instead
+                let minus_one = bin
+                    .builder
+                    .build_int_compare(
+                        IntPredicate::EQ,
+                        right,
+                        right.get_type().const_all_ones(),
+                        "minus_one",
+                    )
+                    .unwrap();
+
+                let divisor = bin
+                    .builder
+                    .build_select(
+                        minus_one,
+                        right.get_type().const_int(1, false),
+                        right,
+                        "divisor",
+                    )
+                    .unwrap()
+                    .into_int_value();
+
+                let res = bin.builder.build_int_signed_div(left, divisor, "").unwrap();
+
                 bin.builder
-                    .build_int_signed_div(left, right, "")
+                    .build_select(
+                        minus_one,
+                        bin.builder.build_int_neg(left, "").unwrap(),
+                        res,
+                        "res",
+                    )
                     .unwrap()
-                    .into()
             }
         }
         Expression::UnsignedModulo {
@@ -496,96 +361,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             let bits = left.get_type().get_bit_width();
 
             if bits > 64 {
-                let div_bits = if bits <= 128 { 128 } else { 256 };
-
-                let name = format!("udivmod{div_bits}");
-
-                let f = bin
-                    .module
-                    .get_function(&name)
-                    .expect("div function missing");
-
-                let ty = bin.context.custom_width_int_type(div_bits);
-
-                let dividend = bin.build_alloca(function, ty, "dividend");
-                let divisor = bin.build_alloca(function, ty, "divisor");
-                let rem = bin.build_alloca(function, ty, "remainder");
-                let quotient = bin.build_alloca(function, ty, "quotient");
-
-                bin.builder
-                    .build_store(
-                        dividend,
-                        if bits < div_bits {
-                            bin.builder.build_int_z_extend(left, ty, "").unwrap()
-                        } else {
-                            left
-                        },
-                    )
-                    .unwrap();
-
-                bin.builder
-                    .build_store(
-                        divisor,
-                        if bits < div_bits {
-                            bin.builder.build_int_z_extend(right, ty, "").unwrap()
-                        } else {
-                            right
-                        },
-                    )
-                    .unwrap();
-
-                let ret = bin
-                    .builder
-                    .build_call(
-                        f,
-                        &[dividend.into(), divisor.into(), rem.into(), quotient.into()],
-                        "udiv",
-                    )
-                    .unwrap()
-                    .try_as_basic_value()
-                    .left()
-                    .unwrap();
-
-                let success = bin
-                    .builder
-                    .build_int_compare(
-                        IntPredicate::EQ,
-                        ret.into_int_value(),
-                        bin.context.i32_type().const_zero(),
-                        "success",
-                    )
-                    .unwrap();
-
-                let success_block = bin.context.append_basic_block(function, "success");
-                let bail_block = bin.context.append_basic_block(function, "bail");
-                bin.builder
-                    .build_conditional_branch(success, success_block, bail_block)
-                    .unwrap();
-
-                bin.builder.position_at_end(bail_block);
-
-                // throw division by zero error should be an assert
-                bin.log_runtime_error(target, "division by zero".to_string(), Some(*loc), ns);
-                let (revert_out, revert_out_len) =
-                    bin.panic_data_const(ns, PanicCode::DivisionByZero);
-                target.assert_failure(bin, revert_out, revert_out_len);
-
-                bin.builder.position_at_end(success_block);
-
-                let rem = bin
-                    .builder
-                    .build_load(ty, rem, "urem")
-                    .unwrap()
-                    .into_int_value();
-
-                if bits < div_bits {
-                    bin.builder
-                        .build_int_truncate(rem, bin.context.custom_width_int_type(bits), "")
-                        .unwrap()
-                } else {
-                    rem
-                }
-                .into()
+                divide(target, bin, function, left, right, false, ns, *loc)
+                    .1
+                    .into()
             } else {
                 bin.builder
                     .build_int_unsigned_rem(left, right, "")
@@ -602,96 +380,9 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             let bits = left.get_type().get_bit_width();
 
             if bits > 64 {
-                let div_bits = if bits <= 128 { 128 } else { 256 };
-
-                let name = format!("sdivmod{div_bits}");
-
-                let f = bin
-                    .module
-                    .get_function(&name)
-                    .expect("div function missing");
-
-                let ty = bin.context.custom_width_int_type(div_bits);
-
-                let dividend = bin.build_alloca(function, ty, "dividend");
-                let divisor = bin.build_alloca(function, ty, "divisor");
-                let rem = bin.build_alloca(function, ty, "remainder");
-                let quotient = bin.build_alloca(function, ty, "quotient");
-
-                bin.builder
-                    .build_store(
-                        dividend,
-                        if bits < div_bits {
-                            bin.builder.build_int_s_extend(left, ty, "").unwrap()
-                        } else {
-                            left
-                        },
-                    )
-                    .unwrap();
-
-                bin.builder
-                    .build_store(
-                        divisor,
-                        if bits < div_bits {
-                            bin.builder.build_int_s_extend(right, ty, "").unwrap()
-                        } else {
-                            right
-                        },
-                    )
-                    .unwrap();
-
-                let ret = bin
-                    .builder
-                    .build_call(
-                        f,
-                        &[dividend.into(), divisor.into(), rem.into(), quotient.into()],
-                        "sdiv",
-                    )
-                    .unwrap()
-                    .try_as_basic_value()
-                    .left()
-                    .unwrap();
-
-                let success = bin
-                    .builder
-                    .build_int_compare(
-                        IntPredicate::EQ,
-                        ret.into_int_value(),
-                        bin.context.i32_type().const_zero(),
-                        "success",
-                    )
-                    .unwrap();
-
-                let success_block = bin.context.append_basic_block(function, "success");
-                let bail_block = bin.context.append_basic_block(function, "bail");
-                bin.builder
-                    .build_conditional_branch(success, success_block, bail_block)
-                    .unwrap();
-
-                bin.builder.position_at_end(bail_block);
-
-                // throw division by zero error should be an assert
-                bin.log_runtime_error(target, "division by zero".to_string(), Some(*loc), ns);
-                let (revert_out, revert_out_len) =
-                    bin.panic_data_const(ns, PanicCode::DivisionByZero);
-                target.assert_failure(bin, revert_out, revert_out_len);
-
-                bin.builder.position_at_end(success_block);
-
-                let rem = bin
-                    .builder
-                    .build_load(ty, rem, "srem")
-                    .unwrap()
-                    .into_int_value();
-
-                if bits < div_bits {
-                    bin.builder
-                        .build_int_truncate(rem, bin.context.custom_width_int_type(bits), "")
-                        .unwrap()
-                } else {
-                    rem
-                }
-                .into()
+                divide(target, bin, function, left, right, true, ns, *loc)
+                    .1
+                    .into()
             } else if ns.target == Target::Solana {
                 // no signed rem on BPF; do abs udev and then negate if needed
                 let left_negative = bin
@@ -748,8 +439,31 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     )
                     .unwrap()
             } else {
+                // srem is undefined for type(int).min % -1; any value modulo -1 is 0, which is
+                // the same as modulo 1
+                let minus_one = bin
+                    .builder
+                    .build_int_compare(
+                        IntPredicate::EQ,
+                        right,
+                        right.get_type().const_all_ones(),
+                        "minus_one",
+                    )
+                    .unwrap();
+
+                let divisor = bin
+                    .builder
+                    .build_select(
+                        minus_one,
+                        right.get_type().const_int(1, false),
+                        right,
+                        "divisor",
+                    )
+                    .unwrap()
+                    .into_int_value();
+
                 bin.builder
-                    .build_int_signed_rem(left, right, "")
+                    .build_int_signed_rem(left, divisor, "")
                     .unwrap()
                     .into()
             }
@@ -809,8 +523,7 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             bin.builder.position_at_end(error_block);
 
             bin.log_runtime_error(target, "math overflow".to_string(), Some(*loc), ns);
-            let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::MathOverflow);
-            target.assert_failure(bin, revert_out, revert_out_len);
+            bin.assert_panic(target, ns, PanicCode::MathOverflow);
 
             bin.builder.position_at_end(return_block);
 
@@ -1255,8 +968,7 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
 
             bin.builder.position_at_end(error);
             bin.log_runtime_error(target, "bytes cast error".to_string(), Some(*loc), ns);
-            let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::Generic);
-            target.assert_failure(bin, revert_out, revert_out_len);
+            bin.assert_panic(target, ns, PanicCode::Generic);
 
             bin.builder.position_at_end(cast);
             let bytes_ptr = bin.vector_bytes(array);
@@ -1309,10 +1021,7 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             let left = expression(target, bin, left, vartab, function, ns).into_int_value();
             let right = expression(target, bin, right, vartab, function, ns).into_int_value();
 
-            bin.builder
-                .build_left_shift(left, right, "")
-                .unwrap()
-                .into()
+            shift(bin, left, right, true, false).into()
         }
         Expression::ShiftRight {
             left,
@@ -1323,10 +1032,7 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
             let left = expression(target, bin, left, vartab, function, ns).into_int_value();
             let right = expression(target, bin, right, vartab, function, ns).into_int_value();
 
-            bin.builder
-                .build_right_shift(left, right, *signed, "")
-                .unwrap()
-                .into()
+            shift(bin, left, right, false, *signed).into()
         }
         Expression::Subscript {
             loc,
@@ -1625,6 +1331,51 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                     .unwrap()
             }
         }
+        Expression::Builtin {
+            tys: returns,
+            kind: Builtin::SliceFromBuffer,
+            args,
+            ..
+        } => {
+            let v = expression(target, bin, &args[0], vartab, function, ns);
+            let offset = expression(target, bin, &args[1], vartab, function, ns).into_int_value();
+            let len = expression(target, bin, &args[2], vartab, function, ns).into_int_value();
+
+            let data = if args[0].ty().is_dynamic_memory() {
+                bin.vector_bytes(v)
+            } else {
+                v.into_pointer_value()
+            };
+
+            let start = unsafe {
+                bin.builder
+                    .build_gep(bin.context.i8_type(), data, &[offset], "start")
+                    .unwrap()
+            };
+
+            let len = bin
+                .builder
+                .build_int_z_extend(
+                    len,
+                    bin.context
+                        .custom_width_int_type(ns.target.ptr_size().into()),
+                    "len",
+                )
+                .unwrap();
+
+            let slice_ty = bin.llvm_type(&returns[0], ns).into_struct_type();
+
+            let slice = bin
+                .builder
+                .build_insert_value(slice_ty.get_undef(), start, 0, "slice")
+                .unwrap();
+
+            bin.builder
+                .build_insert_value(slice, len, 1, "slice")
+                .unwrap()
+                .into_struct_value()
+                .into()
+        }
         Expression::Keccak256 { exprs, .. } => {
             let mut length = bin.context.i32_type().const_zero();
             let mut values: Vec<(BasicValueEnum, IntValue, Type)> = Vec::new();
@@ -1707,11 +1458,26 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                 .unwrap()
         }
         Expression::ReturnData { .. } => target.return_data(bin, function).into(),
-        Expression::StorageArrayLength { array, elem_ty, .. } => {
+        Expression::Builtin {
+            kind: Builtin::ReturnDataSlice,
+            ..
+        } => target.return_data_slice(bin, function, ns).into(),
+        Expression::StorageArrayLength {
+            array, elem_ty, ty, ..
+        } => {
             let slot = expression(target, bin, array, vartab, function, ns).into_int_value();
 
-            target
-                .storage_array_length(bin, function, slot, elem_ty, ns)
+            let length = target.storage_array_length(bin, function, slot, elem_ty, ns);
+
+            // the target may give the length with a different width
+            bin.builder
+                .build_int_cast_sign_flag(
+                    length,
+                    bin.llvm_type(ty, ns).into_int_type(),
+                    false,
+                    "length",
+                )
+                .unwrap()
                 .into()
         }
         Expression::Builtin {
@@ -1837,6 +1603,111 @@ pub(super) fn expression<'a, T: TargetRuntime<'a> + ?Sized>(
                 .unwrap()
                 .into()
         }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::MulDiv,
+            args,
+            ..
+        } => {
+            let a = expression(target, bin, &args[0], vartab, function, ns).into_int_value();
+            let b = expression(target, bin, &args[1], vartab, function, ns).into_int_value();
+            let denominator =
+                expression(target, bin, &args[2], vartab, function, ns).into_int_value();
+
+            mul_div(target, bin, function, a, b, denominator, ns, *loc).into()
+        }
+        Expression::Builtin {
+            kind: Builtin::Sqrt,
+            args,
+            ..
+        } => {
+            let x = expression(target, bin, &args[0], vartab, function, ns).into_int_value();
+
+            sqrt(bin, x).into()
+        }
+        Expression::Builtin {
+            kind: Builtin::Log2,
+            args,
+            ..
+        } => {
+            let x = expression(target, bin, &args[0], vartab, function, ns).into_int_value();
+
+            log2(bin, x).into()
+        }
+        Expression::Builtin {
+            kind: Builtin::IndexOf,
+            args,
+            ..
+        } => {
+            let haystack = expression(target, bin, &args[0], vartab, function, ns);
+            let needle = expression(target, bin, &args[1], vartab, function, ns);
+
+            bin.builder
+                .build_call(
+                    bin.module.get_function("__index_of").unwrap(),
+                    &[
+                        bin.vector_bytes(haystack).into(),
+                        bin.vector_len(haystack).into(),
+                        bin.vector_bytes(needle).into(),
+                        bin.vector_len(needle).into(),
+                    ],
+                    "index",
+                )
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+        }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::ParseInt,
+            args,
+            ..
+        } => {
+            let v = expression(target, bin, &args[0], vartab, function, ns);
+            let int_ty = bin.context.custom_width_int_type(256);
+            let output = bin.build_alloca(function, int_ty, "parsed");
+
+            let success = bin
+                .builder
+                .build_call(
+                    bin.module.get_function("dec2int256").unwrap(),
+                    &[
+                        bin.vector_bytes(v).into(),
+                        bin.vector_len(v).into(),
+                        output.into(),
+                    ],
+                    "success",
+                )
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_int_value();
+
+            let success_block = bin.context.append_basic_block(function, "success");
+            let error_block = bin.context.append_basic_block(function, "error");
+
+            bin.builder
+                .build_conditional_branch(success, success_block, error_block)
+                .unwrap();
+
+            bin.builder.position_at_end(error_block);
+
+            bin.log_runtime_error(target, "invalid integer".to_string(), Some(*loc), ns);
+            target.assert_failure(
+                bin,
+                bin.context
+                    .i8_type()
+                    .ptr_type(AddressSpace::default())
+                    .const_null(),
+                i32_zero!(),
+            );
+
+            bin.builder.position_at_end(success_block);
+
+            bin.builder.build_load(int_ty, output, "value").unwrap()
+        }
         Expression::Builtin {
             kind: Builtin::MulMod,
             args,
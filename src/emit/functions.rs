@@ -1,12 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    codegen::reachable::reachable_cfgs,
     emit::{binary::Binary, cfg::emit_cfg, TargetRuntime},
     sema::ast::{Contract, Namespace, Type},
 };
 use inkwell::module::Linkage;
 
-/// Emit all functions, constructors, fallback and receiver
+/// Emit all functions, constructors, fallback and receiver, except the functions which can never
+/// run
 pub(super) fn emit_functions<'a, T: TargetRuntime<'a>>(
     target: &mut T,
     bin: &mut Binary<'a>,
@@ -14,9 +16,10 @@ pub(super) fn emit_functions<'a, T: TargetRuntime<'a>>(
     ns: &Namespace,
 ) {
     let mut defines = Vec::new();
+    let reachable = reachable_cfgs(contract);
 
     for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
-        if !cfg.is_placeholder() {
+        if !cfg.is_placeholder() && reachable[cfg_no] {
             let ftype = bin.function_type(
                 &cfg.params
                     .iter()
@@ -8,6 +8,7 @@ use crate::codegen::{
 use crate::emit::binary::Binary;
 use crate::emit::cfg::{create_block, BasicBlock, Work};
 use crate::emit::expression::expression;
+use crate::emit::syscall::syscall;
 use crate::emit::{ContractArgs, TargetRuntime, Variable};
 use crate::sema::ast::{Contract, ExternalCallAccounts, Namespace, RetrieveType, Type};
 use crate::Target;
@@ -180,13 +181,13 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             ty,
             array,
             value,
+            ..
         } => {
             let arr = w.vars[array].value;
 
             let llvm_ty = bin.llvm_type(ty, ns);
             let elem_ty = ty.array_elem();
 
-            // Calculate total size for reallocation
             let llvm_elem_ty = bin.llvm_field_ty(&elem_ty, ns);
             let elem_size = llvm_elem_ty
                 .size_of()
@@ -197,22 +198,27 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 .builder
                 .build_int_add(len, bin.context.i32_type().const_int(1, false), "")
                 .unwrap();
-            let vec_size = bin
-                .module
-                .get_struct_type("struct.vector")
-                .unwrap()
-                .size_of()
-                .unwrap()
-                .const_cast(bin.context.i32_type(), false);
-            let size = bin.builder.build_int_mul(elem_size, new_len, "").unwrap();
-            let size = bin.builder.build_int_add(size, vec_size, "").unwrap();
 
-            // Reallocate and reassign the array pointer
-            let new = bin
+            // Only reallocate when the vector is full; its room is at least doubled then
+            let is_full = bin
+                .builder
+                .build_int_compare(IntPredicate::UGE, len, bin.vector_capacity(arr), "is_full")
+                .unwrap();
+
+            let entry = bin.builder.get_insert_block().unwrap();
+            let grow = bin.context.append_basic_block(function, "grow");
+            let push = bin.context.append_basic_block(function, "push");
+
+            bin.builder
+                .build_conditional_branch(is_full, grow, push)
+                .unwrap();
+
+            bin.builder.position_at_end(grow);
+            let grown = bin
                 .builder
                 .build_call(
-                    bin.module.get_function("__realloc").unwrap(),
-                    &[arr.into(), size.into()],
+                    bin.module.get_function("vector_reserve").unwrap(),
+                    &[arr.into(), new_len.into(), elem_size.into()],
                     "",
                 )
                 .unwrap()
@@ -220,6 +226,12 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 .left()
                 .unwrap()
                 .into_pointer_value();
+            bin.builder.build_unconditional_branch(push).unwrap();
+
+            bin.builder.position_at_end(push);
+            let new = bin.builder.build_phi(arr.get_type(), "array").unwrap();
+            new.add_incoming(&[(&arr, entry), (&grown, grow)]);
+            let new = new.as_basic_value().into_pointer_value();
             w.vars.get_mut(array).unwrap().value = new.into();
 
             // Store the value into the last element
@@ -250,7 +262,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             };
             bin.builder.build_store(slot_ptr, value).unwrap();
 
-            // Update the len and size field of the vector struct
+            // Update the len field of the vector struct
             let len_ptr = unsafe {
                 bin.builder
                     .build_gep(
@@ -265,21 +277,31 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                     .unwrap()
             };
             bin.builder.build_store(len_ptr, new_len).unwrap();
+        }
+        Instr::ReserveMemory {
+            array, size, ty, ..
+        } => {
+            let arr = w.vars[array].value;
 
-            let size_ptr = unsafe {
-                bin.builder
-                    .build_gep(
-                        llvm_ty,
-                        new,
-                        &[
-                            bin.context.i32_type().const_zero(),
-                            bin.context.i32_type().const_int(1, false),
-                        ],
-                        "size",
-                    )
-                    .unwrap()
-            };
-            bin.builder.build_store(size_ptr, new_len).unwrap();
+            let elem_size = bin
+                .llvm_field_ty(&ty.array_elem(), ns)
+                .size_of()
+                .unwrap()
+                .const_cast(bin.context.i32_type(), false);
+            let size = expression(target, bin, size, &w.vars, function, ns);
+
+            let new = bin
+                .builder
+                .build_call(
+                    bin.module.get_function("vector_reserve").unwrap(),
+                    &[arr.into(), size.into(), elem_size.into()],
+                    "",
+                )
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap();
+            w.vars.get_mut(array).unwrap().value = new;
         }
         Instr::PopMemory {
             res,
@@ -325,8 +347,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
 
             bin.builder.position_at_end(error);
             bin.log_runtime_error(target, "pop from empty array".to_string(), Some(*loc), ns);
-            let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::EmptyArrayPop);
-            target.assert_failure(bin, revert_out, revert_out_len);
+            bin.assert_panic(target, ns, PanicCode::EmptyArrayPop);
 
             bin.builder.position_at_end(pop);
             let llvm_ty = bin.llvm_type(ty, ns);
@@ -334,7 +355,6 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             let elem_ty = ty.array_elem();
             let llvm_elem_ty = bin.llvm_field_ty(&elem_ty, ns);
 
-            // Calculate total size for reallocation
             let elem_size = llvm_elem_ty
                 .size_of()
                 .unwrap()
@@ -343,15 +363,6 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 .builder
                 .build_int_sub(len, bin.context.i32_type().const_int(1, false), "")
                 .unwrap();
-            let vec_size = bin
-                .module
-                .get_struct_type("struct.vector")
-                .unwrap()
-                .size_of()
-                .unwrap()
-                .const_cast(bin.context.i32_type(), false);
-            let size = bin.builder.build_int_mul(elem_size, new_len, "").unwrap();
-            let size = bin.builder.build_int_add(size, vec_size, "").unwrap();
 
             // Get the pointer to the last element and return it
             let slot_ptr = unsafe {
@@ -378,27 +389,12 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 w.vars.get_mut(res).unwrap().value = ret_val;
             }
 
-            // Reallocate and reassign the array pointer
-            let new = bin
-                .builder
-                .build_call(
-                    bin.module.get_function("__realloc").unwrap(),
-                    &[a.into(), size.into()],
-                    "",
-                )
-                .unwrap()
-                .try_as_basic_value()
-                .left()
-                .unwrap()
-                .into_pointer_value();
-            w.vars.get_mut(array).unwrap().value = new.into();
-
-            // Update the len and size field of the vector struct
+            // Update the len field of the vector struct; the room is kept for the next push
             let len_ptr = unsafe {
                 bin.builder
                     .build_gep(
                         llvm_ty,
-                        new,
+                        a,
                         &[
                             bin.context.i32_type().const_zero(),
                             bin.context.i32_type().const_zero(),
@@ -408,21 +404,6 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                     .unwrap()
             };
             bin.builder.build_store(len_ptr, new_len).unwrap();
-
-            let size_ptr = unsafe {
-                bin.builder
-                    .build_gep(
-                        llvm_ty,
-                        new,
-                        &[
-                            bin.context.i32_type().const_zero(),
-                            bin.context.i32_type().const_int(1, false),
-                        ],
-                        "size",
-                    )
-                    .unwrap()
-            };
-            bin.builder.build_store(size_ptr, new_len).unwrap();
         }
         Instr::AssertFailure { encoded_args: None } => {
             target.assert_failure(
@@ -434,6 +415,16 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 bin.context.i32_type().const_zero(),
             );
         }
+        Instr::AssertFailure {
+            encoded_args:
+                Some(Expression::AllocDynamicBytes {
+                    ty: Type::Slice(_),
+                    initializer: Some(data),
+                    ..
+                }),
+        } => {
+            bin.assert_failure_const(target, data, ns);
+        }
         Instr::AssertFailure {
             encoded_args: Some(expr),
         } => {
@@ -448,6 +439,40 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
 
             target.print(bin, bin.vector_bytes(expr), bin.vector_len(expr));
         }
+        Instr::Call {
+            res,
+            call: InternalCallTy::Static { cfg_no },
+            args,
+            ..
+        } if ns.target == Target::Soroban => {
+            // Soroban functions return their single value directly
+            let parms = args
+                .iter()
+                .map(|p| expression(target, bin, p, &w.vars, function, ns).into())
+                .collect::<Vec<BasicMetadataValueEnum>>();
+
+            let ret = bin
+                .builder
+                .build_call(bin.functions[cfg_no], &parms, "")
+                .unwrap()
+                .try_as_basic_value()
+                .left();
+
+            if let (Some(res), Some(ret)) = (res.first(), ret) {
+                let ty = &contract.cfg[*cfg_no].returns[0].ty;
+                let dest = w.vars[res].value;
+
+                if dest.is_pointer_value()
+                    && !(ty.is_reference_type(ns) || matches!(ty, Type::ExternalFunction { .. }))
+                {
+                    bin.builder
+                        .build_store(dest.into_pointer_value(), ret)
+                        .unwrap();
+                } else {
+                    w.vars.get_mut(res).unwrap().value = ret;
+                }
+            }
+        }
         Instr::Call {
             res,
             call: InternalCallTy::Static { cfg_no },
@@ -534,6 +559,31 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 }
             }
         }
+        Instr::Call {
+            res,
+            call: InternalCallTy::Builtin { ast_func_no },
+            args,
+            ..
+        } if ns.functions[*ast_func_no].syscall.is_some() => {
+            let parms = args
+                .iter()
+                .map(|p| expression(target, bin, p, &w.vars, function, ns))
+                .collect::<Vec<BasicValueEnum>>();
+
+            let ret = syscall(bin, function, &ns.functions[*ast_func_no], &parms, ns);
+
+            if let (Some(res), Some(ret)) = (res.first(), ret) {
+                let dest = w.vars[res].value;
+
+                if dest.is_pointer_value() {
+                    bin.builder
+                        .build_store(dest.into_pointer_value(), ret)
+                        .unwrap();
+                } else {
+                    w.vars.get_mut(res).unwrap().value = ret;
+                }
+            }
+        }
         Instr::Call {
             res,
             call: InternalCallTy::Builtin { ast_func_no },
@@ -684,9 +734,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                 None,
                 ns,
             );
-            let (revert_out, revert_out_len) =
-                bin.panic_data_const(ns, PanicCode::InternalFunctionUninitialized);
-            target.assert_failure(bin, revert_out, revert_out_len);
+            bin.assert_panic(target, ns, PanicCode::InternalFunctionUninitialized);
 
             bin.builder.position_at_end(ptr_ok);
             let ret = bin
@@ -750,6 +798,7 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             seeds,
             loc,
             accounts,
+            storage_deposit_limit,
             constructor_no: _,
         } => {
             let encoded_args = expression(target, bin, encoded_args, &w.vars, function, ns);
@@ -764,6 +813,9 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             let salt = salt
                 .as_ref()
                 .map(|v| expression(target, bin, v, &w.vars, function, ns).into_int_value());
+            let storage_deposit_limit = storage_deposit_limit
+                .as_ref()
+                .map(|v| expression(target, bin, v, &w.vars, function, ns).into_int_value());
 
             let llvm_accounts = process_account_metas(target, accounts, bin, &w.vars, function, ns);
 
@@ -858,6 +910,8 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                     salt,
                     seeds,
                     flags: None,
+                    code_hash: None,
+                    storage_deposit_limit,
                 },
                 ns,
                 *loc,
@@ -886,23 +940,53 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
             let payload_ty = payload.ty();
             let payload = expression(target, bin, payload, &w.vars, function, ns);
 
-            let address = if let Some(address) = address {
-                let address = expression(target, bin, address, &w.vars, function, ns);
+            let (address, code_hash) = match address {
+                Some(address) => match address.ty().unwrap_user_type(ns) {
+                    // A delegatecall on Polkadot may target a code hash rather than an address
+                    Type::Bytes(hash_len) => {
+                        let code_hash = expression(target, bin, address, &w.vars, function, ns)
+                            .into_int_value();
+
+                        let value_ptr =
+                            bin.build_alloca(function, code_hash.get_type(), "code_hash");
+                        bin.builder.build_store(value_ptr, code_hash).unwrap();
+
+                        let hash_len = bin.context.i32_type().const_int(hash_len as u64, false);
+                        let hash = bin.build_array_alloca(
+                            function,
+                            bin.context.i8_type(),
+                            hash_len,
+                            "code_hash_be",
+                        );
 
-                let addr = bin.build_array_alloca(
-                    function,
-                    bin.context.i8_type(),
-                    bin.context
-                        .i32_type()
-                        .const_int(ns.address_length as u64, false),
-                    "address",
-                );
+                        bin.builder
+                            .build_call(
+                                bin.module.get_function("__leNtobeN").unwrap(),
+                                &[value_ptr.into(), hash.into(), hash_len.into()],
+                                "",
+                            )
+                            .unwrap();
 
-                bin.builder.build_store(addr, address).unwrap();
+                        (None, Some(hash))
+                    }
+                    _ => {
+                        let address = expression(target, bin, address, &w.vars, function, ns);
 
-                Some(addr)
-            } else {
-                None
+                        let addr = bin.build_array_alloca(
+                            function,
+                            bin.context.i8_type(),
+                            bin.context
+                                .i32_type()
+                                .const_int(ns.address_length as u64, false),
+                            "address",
+                        );
+
+                        bin.builder.build_store(addr, address).unwrap();
+
+                        (Some(addr), None)
+                    }
+                },
+                None => (None, None),
             };
 
             let accounts = process_account_metas(target, accounts, bin, &w.vars, function, ns);
@@ -950,6 +1034,8 @@ pub(super) fn process_instruction<'a, T: TargetRuntime<'a> + ?Sized>(
                     seeds,
                     accounts,
                     flags,
+                    code_hash,
+                    storage_deposit_limit: None,
                 },
                 callty.clone(),
                 ns,
@@ -13,8 +13,7 @@ use solang_parser::pt::Loc;
 /// 1- Do an unsigned multiplication first, This step will check if the generated value will fit in N bits. (unsigned overflow)
 /// 2- Get the result, and negate it if needed.
 /// 3- Check for signed overflow, by checking for an unexpected change in the sign of the result.
-fn signed_ovf_detect<'a, T: TargetRuntime<'a> + ?Sized>(
-    target: &T,
+fn signed_mul_overflow<'a>(
     bin: &Binary<'a>,
     mul_ty: IntType<'a>,
     mul_bits: u32,
@@ -22,9 +21,7 @@ fn signed_ovf_detect<'a, T: TargetRuntime<'a> + ?Sized>(
     right: IntValue<'a>,
     bits: u32,
     function: FunctionValue<'a>,
-    ns: &Namespace,
-    loc: Loc,
-) -> IntValue<'a> {
+) -> (IntValue<'a>, IntValue<'a>) {
     // We check for signed overflow based on the facts:
     //  - * - = +
     //  + * + = +
@@ -149,9 +146,6 @@ fn signed_ovf_detect<'a, T: TargetRuntime<'a> + ?Sized>(
         )
         .unwrap();
 
-    let error_block = bin.context.append_basic_block(function, "error");
-    let return_block = bin.context.append_basic_block(function, "return_block");
-
     // Extract sign bit of the operands and the result
     let left_sign_bit = extract_sign_bit(bin, left, left.get_type());
     let right_sign_bit = extract_sign_bit(bin, right, right.get_type());
@@ -220,8 +214,9 @@ fn signed_ovf_detect<'a, T: TargetRuntime<'a> + ?Sized>(
         .unwrap();
 
     // Here, we disregard the last rule mentioned above if there is a multiplication by zero.
-    bin.builder
-        .build_conditional_branch(
+    let overflow = bin
+        .builder
+        .build_not(
             bin.builder
                 .build_and(
                     bin.builder
@@ -231,22 +226,16 @@ fn signed_ovf_detect<'a, T: TargetRuntime<'a> + ?Sized>(
                     "",
                 )
                 .unwrap(),
-            return_block,
-            error_block,
+            "overflow",
         )
         .unwrap();
 
-    bin.builder.position_at_end(error_block);
-
-    bin.log_runtime_error(target, "multiplication overflow".to_string(), Some(loc), ns);
-    let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::MathOverflow);
-    target.assert_failure(bin, revert_out, revert_out_len);
-
-    bin.builder.position_at_end(return_block);
-
-    bin.builder
+    let res = bin
+        .builder
         .build_int_truncate(res.into_int_value(), left.get_type(), "")
-        .unwrap()
+        .unwrap();
+
+    (res, overflow)
 }
 
 /// Call void __mul32 and return the result.
@@ -315,139 +304,63 @@ pub(super) fn multiply<'a, T: TargetRuntime<'a> + ?Sized>(
 
     // Mul with overflow is not supported beyond this bit range, so we implement our own function
     if bits > 32 {
-        // Round up the number of bits to the next 32
-        let mul_bits = (bits + 31) & !31;
-        let mul_ty = bin.context.custom_width_int_type(mul_bits);
-
-        // Round up bits
-        let l = bin.build_alloca(function, mul_ty, "");
-        let r = bin.build_alloca(function, mul_ty, "");
-        let o = bin.build_alloca(function, mul_ty, "");
-
-        if mul_bits == bits {
-            bin.builder.build_store(l, left).unwrap();
-            bin.builder.build_store(r, right).unwrap();
-        }
-        // LLVM-IR can handle multiplication of sizes up to 64 bits. If the size is larger, we need to implement our own mutliplication function.
-        // We divide the operands into sizes of 32 bits (check __mul32 in stdlib/bigint.c documentation).
-        // If the size is not divisble by 32, we extend it to the next 32 bits. For example, int72 will be extended to int96.
-        // Here, we zext the operands to the nearest 32 bits. zext is called instead of sext because we need to do unsigned multiplication by default.
-        // It will not matter in terms of mul without overflow, because we always truncate the result to the bit size of the operands.
-        // In mul with overflow however, it is needed so that overflow can be detected if the most significant bits of the result are not zeros.
-        else {
-            bin.builder
-                .build_store(l, bin.builder.build_int_z_extend(left, mul_ty, "").unwrap())
-                .unwrap();
-            bin.builder
-                .build_store(
-                    r,
-                    bin.builder.build_int_z_extend(right, mul_ty, "").unwrap(),
-                )
-                .unwrap();
-        }
-
         if !unchecked {
-            if signed {
-                return signed_ovf_detect(
-                    target, bin, mul_ty, mul_bits, left, right, bits, function, ns, loc,
-                );
-            }
+            let mul = mul_with_overflow(bin, bits, signed);
 
-            // Unsigned overflow detection Approach:
-            // If the size is a multiple of 32, we call __mul32_with_builtin_ovf and it returns an overflow flag (check __mul32_with_builtin_ovf in stdlib/bigint.c documentation)
-            // If that is not the case, some extra work has to be done. We have to check the extended bits for any set bits. If there is any, an overflow occured.
-            // For example, if we have uint72, it will be extended to uint96. __mul32 with ovf will raise an ovf flag if the result overflows 96 bits, not 72.
-            // We account for that by checking the extended leftmost bits. In the example mentioned, they will be 96-72=24 bits.
-            let return_val = bin
+            let res = bin
                 .builder
-                .build_call(
-                    bin.module.get_function("__mul32_with_builtin_ovf").unwrap(),
-                    &[
-                        l.into(),
-                        r.into(),
-                        o.into(),
-                        bin.context
-                            .i32_type()
-                            .const_int(mul_bits as u64 / 32, false)
-                            .into(),
-                    ],
-                    "ovf",
-                )
-                .unwrap();
+                .build_call(mul, &[left.into(), right.into()], "res")
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_struct_value();
 
-            let res = bin.builder.build_load(mul_ty, o, "mul").unwrap();
+            let overflow = bin
+                .builder
+                .build_extract_value(res, 1, "overflow")
+                .unwrap()
+                .into_int_value();
 
             let error_block = bin.context.append_basic_block(function, "error");
             let return_block = bin.context.append_basic_block(function, "return_block");
 
-            // If the operands were extended to nearest 32 bit size, check the most significant N bits, where N equals bit width after extension minus original bit width.
-            let ovf_any_type = if mul_bits != bits {
-                // If there are any set bits, then there is an overflow.
-                let check_ovf = bin
-                    .builder
-                    .build_right_shift(
-                        res.into_int_value(),
-                        mul_ty.const_int((bits).into(), false),
-                        false,
-                        "",
-                    )
-                    .unwrap();
-                bin.builder
-                    .build_int_compare(
-                        IntPredicate::NE,
-                        check_ovf,
-                        check_ovf.get_type().const_zero(),
-                        "",
-                    )
-                    .unwrap()
-            } else {
-                // If no size extension took place, there is no overflow in most significant N bits
-                bin.context.bool_type().const_zero()
-            };
-
-            // Until this point, we only checked the extended bits for ovf. But mul ovf can take place any where from bit size to double bit size.
-            // For example: If we have uint72, it will be extended to uint96. We only checked the most significant 24 bits for overflow, which can happen up to 72*2=144 bits.
-            // bool __mul32_with_builtin_ovf takes care of overflowing bits beyond 96.
-            // What is left now is to or these two ovf flags, and check if any one of them is set. If so, an overflow occured.
-            let lowbit = bin
-                .builder
-                .build_int_truncate(
-                    bin.builder
-                        .build_or(
-                            ovf_any_type,
-                            return_val
-                                .try_as_basic_value()
-                                .left()
-                                .unwrap()
-                                .into_int_value(),
-                            "",
-                        )
-                        .unwrap(),
-                    bin.context.bool_type(),
-                    "bit",
-                )
-                .unwrap();
-
             // If ovf, raise an error, else return the result.
             bin.builder
-                .build_conditional_branch(lowbit, error_block, return_block)
+                .build_conditional_branch(overflow, error_block, return_block)
                 .unwrap();
 
             bin.builder.position_at_end(error_block);
 
             bin.log_runtime_error(target, "multiplication overflow".to_string(), Some(loc), ns);
 
-            let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::MathOverflow);
-            target.assert_failure(bin, revert_out, revert_out_len);
+            bin.assert_panic(target, ns, PanicCode::MathOverflow);
 
             bin.builder.position_at_end(return_block);
 
-            bin.builder
-                .build_int_truncate(res.into_int_value(), left.get_type(), "")
+            return bin
+                .builder
+                .build_extract_value(res, 0, "res")
                 .unwrap()
-        } else {
-            return call_mul32_without_ovf(bin, l, r, o, mul_bits, mul_ty, left.get_type());
+                .into_int_value();
         }
+
+        // Round up the number of bits to the next 32
+        let mul_bits = (bits + 31) & !31;
+        let mul_ty = bin.context.custom_width_int_type(mul_bits);
+
+        let l = bin.build_alloca(function, mul_ty, "");
+        let r = bin.build_alloca(function, mul_ty, "");
+        let o = bin.build_alloca(function, mul_ty, "");
+
+        // LLVM-IR can handle multiplication of sizes up to 64 bits. If the size is larger, we need to implement our own mutliplication function.
+        // We divide the operands into sizes of 32 bits (check __mul32 in stdlib/bigint.c documentation).
+        // If the size is not divisble by 32, we extend it to the next 32 bits. For example, int72 will be extended to int96.
+        // It will not matter in terms of mul without overflow, because we always truncate the result to the bit size of the operands.
+        store_extended(bin, l, left, mul_ty);
+        store_extended(bin, r, right, mul_ty);
+
+        call_mul32_without_ovf(bin, l, r, o, mul_bits, mul_ty, left.get_type())
     } else if !unchecked {
         build_binary_op_with_overflow_check(
             target,
@@ -465,6 +378,636 @@ pub(super) fn multiply<'a, T: TargetRuntime<'a> + ?Sized>(
     }
 }
 
+/// Store the value zero extended to the given type, if it is not already of that type
+fn store_extended<'a>(bin: &Binary<'a>, ptr: PointerValue<'a>, val: IntValue<'a>, ty: IntType<'a>) {
+    let val = if val.get_type() == ty {
+        val
+    } else {
+        bin.builder.build_int_z_extend(val, ty, "").unwrap()
+    };
+
+    bin.builder.build_store(ptr, val).unwrap();
+}
+
+/// Get the function which multiplies two integers of the given width and returns the result
+/// along with an overflow flag, like the `llvm.umul.with.overflow` intrinsics which llvm does not
+/// support for large widths. It is emitted once for each width and signedness, so that every
+/// checked multiplication does not expand to a copy of it.
+fn mul_with_overflow<'a>(bin: &Binary<'a>, bits: u32, signed: bool) -> FunctionValue<'a> {
+    let name = format!(
+        "__{}mul{}_with_overflow",
+        if signed { 's' } else { 'u' },
+        bits
+    );
+
+    if let Some(f) = bin.module.get_function(&name) {
+        return f;
+    }
+
+    let pos = bin.builder.get_insert_block().unwrap();
+
+    let ty = bin.context.custom_width_int_type(bits);
+    let ret_ty = bin
+        .context
+        .struct_type(&[ty.into(), bin.context.bool_type().into()], false);
+
+    let function =
+        bin.module
+            .add_function(&name, ret_ty.fn_type(&[ty.into(), ty.into()], false), None);
+
+    let entry = bin.context.append_basic_block(function, "entry");
+
+    bin.builder.position_at_end(entry);
+
+    let left = function.get_nth_param(0).unwrap().into_int_value();
+    let right = function.get_nth_param(1).unwrap().into_int_value();
+
+    // Round up the number of bits to the next 32
+    let mul_bits = (bits + 31) & !31;
+    let mul_ty = bin.context.custom_width_int_type(mul_bits);
+
+    let (res, overflow) = if signed {
+        signed_mul_overflow(bin, mul_ty, mul_bits, left, right, bits, function)
+    } else {
+        unsigned_mul_overflow(bin, mul_ty, mul_bits, left, right, bits, function)
+    };
+
+    let ret = bin
+        .builder
+        .build_insert_value(ret_ty.get_undef(), res, 0, "")
+        .unwrap();
+    let ret = bin
+        .builder
+        .build_insert_value(ret, overflow, 1, "")
+        .unwrap()
+        .into_struct_value();
+
+    bin.builder.build_return(Some(&ret)).unwrap();
+
+    bin.builder.position_at_end(pos);
+
+    function
+}
+
+/// Unsigned overflow detection Approach:
+/// If the size is a multiple of 32, we call __mul32_with_builtin_ovf and it returns an overflow flag (check __mul32_with_builtin_ovf in stdlib/bigint.c documentation)
+/// If that is not the case, some extra work has to be done. We have to check the extended bits for any set bits. If there is any, an overflow occured.
+/// For example, if we have uint72, it will be extended to uint96. __mul32 with ovf will raise an ovf flag if the result overflows 96 bits, not 72.
+/// We account for that by checking the extended leftmost bits. In the example mentioned, they will be 96-72=24 bits.
+fn unsigned_mul_overflow<'a>(
+    bin: &Binary<'a>,
+    mul_ty: IntType<'a>,
+    mul_bits: u32,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    bits: u32,
+    function: FunctionValue<'a>,
+) -> (IntValue<'a>, IntValue<'a>) {
+    let l = bin.build_alloca(function, mul_ty, "");
+    let r = bin.build_alloca(function, mul_ty, "");
+    let o = bin.build_alloca(function, mul_ty, "");
+
+    // zext is needed so that overflow can be detected if the most significant bits of the result are not zeros.
+    store_extended(bin, l, left, mul_ty);
+    store_extended(bin, r, right, mul_ty);
+
+    let return_val = bin
+        .builder
+        .build_call(
+            bin.module.get_function("__mul32_with_builtin_ovf").unwrap(),
+            &[
+                l.into(),
+                r.into(),
+                o.into(),
+                bin.context
+                    .i32_type()
+                    .const_int(mul_bits as u64 / 32, false)
+                    .into(),
+            ],
+            "ovf",
+        )
+        .unwrap();
+
+    let res = bin.builder.build_load(mul_ty, o, "mul").unwrap();
+
+    // If the operands were extended to nearest 32 bit size, check the most significant N bits, where N equals bit width after extension minus original bit width.
+    let ovf_any_type = if mul_bits != bits {
+        // If there are any set bits, then there is an overflow.
+        let check_ovf = bin
+            .builder
+            .build_right_shift(
+                res.into_int_value(),
+                mul_ty.const_int((bits).into(), false),
+                false,
+                "",
+            )
+            .unwrap();
+        bin.builder
+            .build_int_compare(
+                IntPredicate::NE,
+                check_ovf,
+                check_ovf.get_type().const_zero(),
+                "",
+            )
+            .unwrap()
+    } else {
+        // If no size extension took place, there is no overflow in most significant N bits
+        bin.context.bool_type().const_zero()
+    };
+
+    // Until this point, we only checked the extended bits for ovf. But mul ovf can take place any where from bit size to double bit size.
+    // For example: If we have uint72, it will be extended to uint96. We only checked the most significant 24 bits for overflow, which can happen up to 72*2=144 bits.
+    // bool __mul32_with_builtin_ovf takes care of overflowing bits beyond 96.
+    // What is left now is to or these two ovf flags, and check if any one of them is set. If so, an overflow occured.
+    let overflow = bin
+        .builder
+        .build_int_truncate(
+            bin.builder
+                .build_or(
+                    ovf_any_type,
+                    return_val
+                        .try_as_basic_value()
+                        .left()
+                        .unwrap()
+                        .into_int_value(),
+                    "",
+                )
+                .unwrap(),
+            bin.context.bool_type(),
+            "overflow",
+        )
+        .unwrap();
+
+    let res = bin
+        .builder
+        .build_int_truncate(res.into_int_value(), left.get_type(), "")
+        .unwrap();
+
+    (res, overflow)
+}
+
+/// Divide using the division functions from stdlib/bigint.c, which are shared by all divisions
+/// of the same width rather than expanded inline by llvm. Returns the quotient and the remainder.
+pub(super) fn divide<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    signed: bool,
+    ns: &Namespace,
+    loc: Loc,
+) -> (IntValue<'a>, IntValue<'a>) {
+    let (success, quotient, rem) = call_divmod(bin, function, left, right, signed);
+
+    let success_block = bin.context.append_basic_block(function, "success");
+    let bail_block = bin.context.append_basic_block(function, "bail");
+    bin.builder
+        .build_conditional_branch(success, success_block, bail_block)
+        .unwrap();
+
+    bin.builder.position_at_end(bail_block);
+
+    // throw division by zero error should be an assert
+    bin.log_runtime_error(target, "division by zero".to_string(), Some(loc), ns);
+    bin.assert_panic(target, ns, PanicCode::DivisionByZero);
+
+    bin.builder.position_at_end(success_block);
+
+    (quotient, rem)
+}
+
+/// Call the stdlib division function for the width. Returns whether the divisor was not zero,
+/// the quotient and the remainder.
+fn call_divmod<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    signed: bool,
+) -> (IntValue<'a>, IntValue<'a>, IntValue<'a>) {
+    let bits = left.get_type().get_bit_width();
+
+    // intermediate values may be wider than 256 bits
+    let div_bits = if bits <= 128 {
+        128
+    } else if bits <= 256 {
+        256
+    } else {
+        512
+    };
+
+    let name = format!("{}divmod{div_bits}", if signed { 's' } else { 'u' });
+
+    let f = bin
+        .module
+        .get_function(&name)
+        .expect("div function missing");
+
+    let ty = bin.context.custom_width_int_type(div_bits);
+
+    let dividend = bin.build_alloca(function, ty, "dividend");
+    let divisor = bin.build_alloca(function, ty, "divisor");
+    let rem = bin.build_alloca(function, ty, "remainder");
+    let quotient = bin.build_alloca(function, ty, "quotient");
+
+    let extend = |val: IntValue<'a>| {
+        if bits == div_bits {
+            val
+        } else if signed {
+            bin.builder.build_int_s_extend(val, ty, "").unwrap()
+        } else {
+            bin.builder.build_int_z_extend(val, ty, "").unwrap()
+        }
+    };
+
+    bin.builder.build_store(dividend, extend(left)).unwrap();
+    bin.builder.build_store(divisor, extend(right)).unwrap();
+
+    let ret = bin
+        .builder
+        .build_call(
+            f,
+            &[dividend.into(), divisor.into(), rem.into(), quotient.into()],
+            "div",
+        )
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+        .unwrap();
+
+    let success = bin
+        .builder
+        .build_int_compare(
+            IntPredicate::EQ,
+            ret.into_int_value(),
+            bin.context.i32_type().const_zero(),
+            "success",
+        )
+        .unwrap();
+
+    let quotient = bin
+        .builder
+        .build_load(ty, quotient, "quotient")
+        .unwrap()
+        .into_int_value();
+    let rem = bin
+        .builder
+        .build_load(ty, rem, "rem")
+        .unwrap()
+        .into_int_value();
+
+    if bits == div_bits {
+        (success, quotient, rem)
+    } else {
+        (
+            success,
+            bin.builder
+                .build_int_truncate(quotient, left.get_type(), "")
+                .unwrap(),
+            bin.builder
+                .build_int_truncate(rem, left.get_type(), "")
+                .unwrap(),
+        )
+    }
+}
+
+/// Signed division overflows only for `type(int).min / -1`. Revert with a panic if so; unchecked
+/// divisions skip this check and wrap to `type(int).min`.
+pub(super) fn signed_divide_overflow_check<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    ns: &Namespace,
+    loc: Loc,
+) {
+    let ty = left.get_type();
+    let bits = ty.get_bit_width();
+
+    let min = bin
+        .builder
+        .build_left_shift(
+            ty.const_int(1, false),
+            ty.const_int(bits as u64 - 1, false),
+            "min",
+        )
+        .unwrap();
+
+    let left_min = bin
+        .builder
+        .build_int_compare(IntPredicate::EQ, left, min, "left_min")
+        .unwrap();
+
+    let right_minus_one = bin
+        .builder
+        .build_int_compare(
+            IntPredicate::EQ,
+            right,
+            ty.const_all_ones(),
+            "right_minus_one",
+        )
+        .unwrap();
+
+    let overflow = bin
+        .builder
+        .build_and(left_min, right_minus_one, "overflow")
+        .unwrap();
+
+    let success_block = bin.context.append_basic_block(function, "success");
+    let error_block = bin.context.append_basic_block(function, "error");
+
+    bin.builder
+        .build_conditional_branch(overflow, error_block, success_block)
+        .unwrap();
+
+    bin.builder.position_at_end(error_block);
+
+    bin.log_runtime_error(target, "math overflow".to_string(), Some(loc), ns);
+    bin.assert_panic(target, ns, PanicCode::MathOverflow);
+
+    bin.builder.position_at_end(success_block);
+}
+
+/// Shifting by the width of the value or more gives zero, or -1 for negative values shifted
+/// right arithmetically. llvm gives poison for such shifts, so the amount is replaced with zero
+/// and the result selected.
+pub(super) fn shift<'a>(
+    bin: &Binary<'a>,
+    left: IntValue<'a>,
+    right: IntValue<'a>,
+    shift_left: bool,
+    signed: bool,
+) -> IntValue<'a> {
+    let ty = left.get_type();
+    let bits = ty.get_bit_width();
+
+    let too_far = bin
+        .builder
+        .build_int_compare(
+            IntPredicate::UGE,
+            right,
+            ty.const_int(bits as u64, false),
+            "too_far",
+        )
+        .unwrap();
+
+    let amount = bin
+        .builder
+        .build_select(too_far, ty.const_zero(), right, "amount")
+        .unwrap()
+        .into_int_value();
+
+    let (res, saturated) = if shift_left {
+        (
+            bin.builder.build_left_shift(left, amount, "").unwrap(),
+            ty.const_zero(),
+        )
+    } else if signed {
+        (
+            bin.builder
+                .build_right_shift(left, amount, true, "")
+                .unwrap(),
+            bin.builder
+                .build_right_shift(left, ty.const_int(bits as u64 - 1, false), true, "sign")
+                .unwrap(),
+        )
+    } else {
+        (
+            bin.builder
+                .build_right_shift(left, amount, false, "")
+                .unwrap(),
+            ty.const_zero(),
+        )
+    };
+
+    bin.builder
+        .build_select(too_far, saturated, res, "")
+        .unwrap()
+        .into_int_value()
+}
+
+/// `math.mulDiv(a, b, denominator)`: the product is calculated with 512 bits so it cannot
+/// overflow, and only the quotient has to fit into 256 bits.
+pub(super) fn mul_div<'a, T: TargetRuntime<'a> + ?Sized>(
+    target: &T,
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    a: IntValue<'a>,
+    b: IntValue<'a>,
+    denominator: IntValue<'a>,
+    ns: &Namespace,
+    loc: Loc,
+) -> IntValue<'a> {
+    let ty = a.get_type();
+    let wide_ty = bin.context.custom_width_int_type(ty.get_bit_width() * 2);
+
+    let a = bin
+        .builder
+        .build_int_z_extend(a, wide_ty, "wide_a")
+        .unwrap();
+    let b = bin
+        .builder
+        .build_int_z_extend(b, wide_ty, "wide_b")
+        .unwrap();
+    let denominator = bin
+        .builder
+        .build_int_z_extend(denominator, wide_ty, "wide_denominator")
+        .unwrap();
+
+    let product = multiply(target, bin, function, true, a, b, false, ns, loc);
+
+    let (quotient, _) = divide(target, bin, function, product, denominator, false, ns, loc);
+
+    let high = bin
+        .builder
+        .build_right_shift(
+            quotient,
+            wide_ty.const_int(ty.get_bit_width() as u64, false),
+            false,
+            "high",
+        )
+        .unwrap();
+
+    let overflow = bin
+        .builder
+        .build_int_compare(IntPredicate::NE, high, wide_ty.const_zero(), "overflow")
+        .unwrap();
+
+    let success_block = bin.context.append_basic_block(function, "success");
+    let error_block = bin.context.append_basic_block(function, "error");
+
+    bin.builder
+        .build_conditional_branch(overflow, error_block, success_block)
+        .unwrap();
+
+    bin.builder.position_at_end(error_block);
+
+    bin.log_runtime_error(target, "math overflow".to_string(), Some(loc), ns);
+    bin.assert_panic(target, ns, PanicCode::MathOverflow);
+
+    bin.builder.position_at_end(success_block);
+
+    bin.builder.build_int_truncate(quotient, ty, "").unwrap()
+}
+
+/// `math.log2(x)`, rounded down. The logarithm of 0 is 0, like the Solidity libraries.
+pub(super) fn log2<'a>(bin: &Binary<'a>, x: IntValue<'a>) -> IntValue<'a> {
+    let ty = x.get_type();
+    let bits = ty.get_bit_width();
+
+    let leading_zeros = bin
+        .builder
+        .build_call(
+            bin.llvm_ctlz(bits),
+            &[x.into(), bin.context.bool_type().const_zero().into()],
+            "leading_zeros",
+        )
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value();
+
+    let log = bin
+        .builder
+        .build_int_sub(ty.const_int(bits as u64 - 1, false), leading_zeros, "log")
+        .unwrap();
+
+    let is_zero = bin
+        .builder
+        .build_int_compare(IntPredicate::EQ, x, ty.const_zero(), "is_zero")
+        .unwrap();
+
+    bin.builder
+        .build_select(is_zero, ty.const_zero(), log, "")
+        .unwrap()
+        .into_int_value()
+}
+
+/// `math.sqrt(x)`, rounded down. This calls a function which is emitted once for each width,
+/// since the loop and the wide division in it are too large to repeat at each call.
+pub(super) fn sqrt<'a>(bin: &Binary<'a>, x: IntValue<'a>) -> IntValue<'a> {
+    let ty = x.get_type();
+    let bits = ty.get_bit_width();
+    let name = format!("__sqrt{bits}");
+
+    let function = bin.module.get_function(&name).unwrap_or_else(|| {
+        let pos = bin.builder.get_insert_block().unwrap();
+        // the function has no debug info, so it must not get the location of the caller
+        let debug_loc = bin.builder.get_current_debug_location();
+        bin.builder.unset_current_debug_location();
+
+        let function = bin
+            .module
+            .add_function(&name, ty.fn_type(&[ty.into()], false), None);
+
+        let entry = bin.context.append_basic_block(function, "entry");
+        let small = bin.context.append_basic_block(function, "small");
+        let estimate = bin.context.append_basic_block(function, "estimate");
+        let body = bin.context.append_basic_block(function, "loop");
+        let done = bin.context.append_basic_block(function, "done");
+
+        bin.builder.position_at_end(entry);
+
+        let x = function.get_nth_param(0).unwrap().into_int_value();
+
+        // 0 and 1 are their own square root
+        let is_small = bin
+            .builder
+            .build_int_compare(IntPredicate::ULT, x, ty.const_int(2, false), "is_small")
+            .unwrap();
+
+        bin.builder
+            .build_conditional_branch(is_small, small, estimate)
+            .unwrap();
+
+        bin.builder.position_at_end(small);
+        bin.builder.build_return(Some(&x)).unwrap();
+
+        // Start from 2^(log2(x) / 2 + 1), which is larger than the square root. Newton's method
+        // then decreases the estimate on each step, until it no longer does.
+        bin.builder.position_at_end(estimate);
+
+        let shift = bin
+            .builder
+            .build_int_add(
+                bin.builder
+                    .build_right_shift(log2(bin, x), ty.const_int(1, false), false, "")
+                    .unwrap(),
+                ty.const_int(1, false),
+                "shift",
+            )
+            .unwrap();
+
+        let first = bin
+            .builder
+            .build_left_shift(ty.const_int(1, false), shift, "first")
+            .unwrap();
+
+        bin.builder.build_unconditional_branch(body).unwrap();
+
+        bin.builder.position_at_end(body);
+
+        let z = bin.builder.build_phi(ty, "z").unwrap();
+
+        // the estimate is never zero, and a revert cannot be emitted here since on Solana it
+        // returns from the current function
+        let (_, quotient, _) =
+            call_divmod(bin, function, x, z.as_basic_value().into_int_value(), false);
+
+        let next = bin
+            .builder
+            .build_right_shift(
+                bin.builder
+                    .build_int_add(z.as_basic_value().into_int_value(), quotient, "")
+                    .unwrap(),
+                ty.const_int(1, false),
+                false,
+                "next",
+            )
+            .unwrap();
+
+        let decreased = bin
+            .builder
+            .build_int_compare(
+                IntPredicate::ULT,
+                next,
+                z.as_basic_value().into_int_value(),
+                "decreased",
+            )
+            .unwrap();
+
+        z.add_incoming(&[(&first, estimate), (&next, body)]);
+
+        bin.builder
+            .build_conditional_branch(decreased, body, done)
+            .unwrap();
+
+        bin.builder.position_at_end(done);
+        bin.builder
+            .build_return(Some(&z.as_basic_value().into_int_value()))
+            .unwrap();
+
+        bin.builder.position_at_end(pos);
+
+        if let Some(debug_loc) = debug_loc {
+            bin.builder.set_current_debug_location(debug_loc);
+        }
+
+        function
+    });
+
+    bin.builder
+        .build_call(function, &[x.into()], "sqrt")
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value()
+}
+
 pub(super) fn power<'a, T: TargetRuntime<'a> + ?Sized>(
     target: &T,
     bin: &Binary<'a>,
@@ -676,8 +1219,7 @@ pub(super) fn build_binary_op_with_overflow_check<'a, T: TargetRuntime<'a> + ?Si
     bin.builder.position_at_end(error_block);
 
     bin.log_runtime_error(target, "math overflow".to_string(), Some(loc), ns);
-    let (revert_out, revert_out_len) = bin.panic_data_const(ns, PanicCode::MathOverflow);
-    target.assert_failure(bin, revert_out, revert_out_len);
+    bin.assert_panic(target, ns, PanicCode::MathOverflow);
 
     bin.builder.position_at_end(success_block);
 
@@ -11,11 +11,13 @@ use inkwell::targets::TargetTriple;
 use inkwell::types::{BasicTypeEnum, IntType};
 use inkwell::values::{
     ArrayValue, BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
+    StructValue,
 };
 use solang_parser::pt::Loc;
 
 pub mod binary;
 mod cfg;
+mod debug_info;
 mod expression;
 mod functions;
 mod instructions;
@@ -23,11 +25,13 @@ mod loop_builder;
 mod math;
 pub mod polkadot;
 pub mod solana;
+pub mod stylus;
 
 #[cfg(feature = "soroban")]
 pub mod soroban;
 mod storage;
 mod strings;
+mod syscall;
 
 use crate::codegen::{cfg::HashTy, Options};
 use crate::emit::binary::Binary;
@@ -46,6 +50,8 @@ pub struct ContractArgs<'b> {
     seeds: Option<(PointerValue<'b>, IntValue<'b>)>,
     accounts: Option<(PointerValue<'b>, IntValue<'b>)>,
     flags: Option<IntValue<'b>>,
+    code_hash: Option<PointerValue<'b>>,
+    storage_deposit_limit: Option<IntValue<'b>>,
 }
 
 #[derive(Clone, Copy)]
@@ -214,7 +220,7 @@ pub trait TargetRuntime<'a> {
     );
 
     /// Prints a string
-    fn print(&self, bin: &Binary, string: PointerValue, length: IntValue);
+    fn print<'b>(&self, bin: &Binary<'b>, string: PointerValue<'b>, length: IntValue<'b>);
 
     /// Return success without any result
     fn return_empty_abi(&self, bin: &Binary);
@@ -290,6 +296,20 @@ pub trait TargetRuntime<'a> {
     /// Return the return data from an external call (either revert error or return values)
     fn return_data<'b>(&self, bin: &Binary<'b>, function: FunctionValue<'b>) -> PointerValue<'b>;
 
+    /// Return the return data from an external call as a slice. Codegen only uses this when the
+    /// return data is read before any other host function is called, so a target which keeps the
+    /// return data in a buffer can return a slice of it rather than a copy.
+    fn return_data_slice<'b>(
+        &self,
+        bin: &Binary<'b>,
+        function: FunctionValue<'b>,
+        ns: &Namespace,
+    ) -> StructValue<'b> {
+        let v = self.return_data(bin, function);
+
+        bin.slice(bin.vector_bytes(v.into()), bin.vector_len(v.into()), ns)
+    }
+
     /// Return the value we received
     fn value_transferred<'b>(&self, binary: &Binary<'b>, ns: &Namespace) -> IntValue<'b>;
 
@@ -230,6 +230,8 @@ impl PolkadotTarget {
             i32_type,
             u8_ptr,
             u64_val,
+            u64_val,
+            u8_ptr,
             u8_ptr,
             u8_ptr,
             u32_val,
@@ -263,8 +263,46 @@ impl StorageSlot for PolkadotTarget {
                     .unwrap()
                     .into_pointer_value();
 
+                let (packing, _) = str_ty.definition(ns).storage_packing(ns);
+                // the slot shared by the fields which are being loaded
+                let mut shared = None;
+
                 for (i, field) in str_ty.definition(ns).fields.iter().enumerate() {
-                    let val = self.storage_load_slot(bin, &field.ty, slot, slot_ptr, function, ns);
+                    let val = if let Some(packed) = packing[i].1 {
+                        let slot_ty = Type::Uint(u16::from(packed.width) * 8);
+
+                        if packed.offset == 0 {
+                            shared = Some(
+                                self.storage_load_slot(bin, &slot_ty, slot, slot_ptr, function, ns)
+                                    .into_int_value(),
+                            );
+                        }
+
+                        let value = bin
+                            .builder
+                            .build_right_shift(
+                                shared.unwrap(),
+                                bin.number_literal(
+                                    slot_ty.bits(ns) as u32,
+                                    &BigInt::from(u32::from(packed.offset) * 8),
+                                    ns,
+                                ),
+                                false,
+                                "",
+                            )
+                            .unwrap();
+
+                        bin.builder
+                            .build_int_truncate(
+                                value,
+                                bin.llvm_type(&field.ty, ns).into_int_type(),
+                                field.name_as_str(),
+                            )
+                            .unwrap()
+                            .into()
+                    } else {
+                        self.storage_load_slot(bin, &field.ty, slot, slot_ptr, function, ns)
+                    };
 
                     let elem = unsafe {
                         bin.builder
@@ -581,6 +619,10 @@ impl StorageSlot for PolkadotTarget {
                 }
             }
             Type::Struct(str_ty) => {
+                let (packing, _) = str_ty.definition(ns).storage_packing(ns);
+                // the fields which share the slot that is being filled
+                let mut shared = None;
+
                 for (i, field) in str_ty.definition(ns).fields.iter().enumerate() {
                     let mut elem = unsafe {
                         bin.builder
@@ -596,6 +638,75 @@ impl StorageSlot for PolkadotTarget {
                             .unwrap()
                     };
 
+                    if let Some(packed) = packing[i].1 {
+                        let slot_ty = Type::Uint(u16::from(packed.width) * 8);
+
+                        let value = bin
+                            .builder
+                            .build_load(bin.llvm_type(&field.ty, ns), elem, field.name_as_str())
+                            .unwrap()
+                            .into_int_value();
+
+                        let value = bin
+                            .builder
+                            .build_int_z_extend(
+                                value,
+                                bin.llvm_type(&slot_ty, ns).into_int_type(),
+                                "",
+                            )
+                            .unwrap();
+
+                        let value = bin
+                            .builder
+                            .build_left_shift(
+                                value,
+                                bin.number_literal(
+                                    slot_ty.bits(ns) as u32,
+                                    &BigInt::from(u32::from(packed.offset) * 8),
+                                    ns,
+                                ),
+                                "",
+                            )
+                            .unwrap();
+
+                        let value = match shared {
+                            Some(fields) => bin.builder.build_or(fields, value, "").unwrap(),
+                            None => value,
+                        };
+
+                        // the last field in the slot stores it
+                        if packing
+                            .get(i + 1)
+                            .and_then(|(_, next)| *next)
+                            .map_or(true, |next| next.offset == 0)
+                        {
+                            self.storage_store_slot(
+                                bin,
+                                &slot_ty,
+                                slot,
+                                slot_ptr,
+                                value.into(),
+                                function,
+                                ns,
+                            );
+
+                            *slot = bin
+                                .builder
+                                .build_int_add(
+                                    *slot,
+                                    bin.number_literal(256, &BigInt::one(), ns),
+                                    field.name_as_str(),
+                                )
+                                .unwrap();
+
+                            shared = None;
+                        } else {
+                            shared = Some(value);
+                        }
+
+                        continue;
+                    }
+
                     if field.ty.is_reference_type(ns) && !field.ty.is_fixed_reference_type(ns) {
                         let load_ty = bin
                             .llvm_type(&field.ty, ns)
@@ -807,7 +918,29 @@ impl StorageSlot for PolkadotTarget {
                 }
             }
             Type::Struct(str_ty) => {
-                for field in &str_ty.definition(ns).fields {
+                let (packing, _) = str_ty.definition(ns).storage_packing(ns);
+
+                for (i, field) in str_ty.definition(ns).fields.iter().enumerate() {
+                    if let Some(packed) = packing[i].1 {
+                        // the fields which share a slot are deleted together
+                        if packed.offset == 0 {
+                            bin.builder.build_store(slot_ptr, *slot).unwrap();
+
+                            self.storage_delete_single_slot(bin, slot_ptr);
+
+                            *slot = bin
+                                .builder
+                                .build_int_add(
+                                    *slot,
+                                    bin.number_literal(256, &BigInt::one(), ns),
+                                    field.name_as_str(),
+                                )
+                                .unwrap();
+                        }
+
+                        continue;
+                    }
+
                     self.storage_delete_slot(bin, &field.ty, slot, slot_ptr, function, ns);
 
                     if !field.ty.is_reference_type(ns)
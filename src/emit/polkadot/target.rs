@@ -14,6 +14,7 @@ use inkwell::types::{BasicType, BasicTypeEnum, IntType};
 use inkwell::values::BasicValue;
 use inkwell::values::{
     ArrayValue, BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
+    StructValue,
 };
 use inkwell::{AddressSpace, IntPredicate};
 use solang_parser::pt::Loc;
@@ -365,8 +366,7 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
             Some(loc),
             ns,
         );
-        let (revert_out, revert_out_len) = binary.panic_data_const(ns, PanicCode::ArrayIndexOob);
-        self.assert_failure(binary, revert_out, revert_out_len);
+        binary.assert_panic(self, ns, PanicCode::ArrayIndexOob);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -461,8 +461,7 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
             Some(loc),
             ns,
         );
-        let (revert_out, revert_out_len) = binary.panic_data_const(ns, PanicCode::ArrayIndexOob);
-        self.assert_failure(binary, revert_out, revert_out_len);
+        binary.assert_panic(self, ns, PanicCode::ArrayIndexOob);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -648,8 +647,7 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
             Some(loc),
             ns,
         );
-        let (revert_out, revert_out_len) = binary.panic_data_const(ns, PanicCode::EmptyArrayPop);
-        self.assert_failure(binary, revert_out, revert_out_len);
+        binary.assert_panic(self, ns, PanicCode::EmptyArrayPop);
 
         binary.builder.position_at_end(retrieve_block);
 
@@ -895,11 +893,29 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
             .build_store(scratch_len, i32_const!(SCRATCH_SIZE as u64 * 32))
             .unwrap();
 
+        // A storage deposit limit pointer of u32::MAX means no specific limit
+        let deposit_limit_ptr = match contract_args.storage_deposit_limit {
+            Some(limit) => {
+                let limit_ptr =
+                    binary.build_alloca(function, binary.value_type(ns), "storage_deposit_limit");
+                binary.builder.build_store(limit_ptr, limit).unwrap();
+                limit_ptr
+            }
+            None => binary
+                .context
+                .i32_type()
+                .const_all_ones()
+                .const_to_pointer(binary.context.i8_type().ptr_type(AddressSpace::default())),
+        };
+
         *success.unwrap() = call!(
             "instantiate",
             &[
                 codehash.into(),
                 contract_args.gas.unwrap().into(),
+                // The proof size limit; zero means no limit
+                i64_zero!().into(),
+                deposit_limit_ptr.into(),
                 value_ptr.into(),
                 encoded_args.into(),
                 encoded_args_len.into(),
@@ -970,6 +986,22 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
                 .into_int_value()
                 .as_basic_value_enum()
             }
+            ast::CallTy::Delegate if contract_args.code_hash.is_some() => call!(
+                "delegate_call",
+                &[
+                    contract_args.flags.unwrap_or(i32_zero!()).into(),
+                    contract_args.code_hash.unwrap().into(),
+                    payload.into(),
+                    payload_len.into(),
+                    scratch_buf.into(),
+                    scratch_len.into(),
+                ]
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value()
+            .as_basic_value_enum(),
             ast::CallTy::Delegate => {
                 // delegate_call asks for a code hash instead of an address
                 let hash_len = i32_const!(32); // FIXME: This is configurable like the address length
@@ -1117,6 +1149,25 @@ impl<'a> TargetRuntime<'a> for PolkadotTarget {
         .into_pointer_value()
     }
 
+    /// The return data is read from the scratch buffer without copying it
+    fn return_data_slice<'b>(
+        &self,
+        binary: &Binary<'b>,
+        _function: FunctionValue,
+        ns: &ast::Namespace,
+    ) -> StructValue<'b> {
+        emit_context!(binary);
+
+        let (scratch_buf, scratch_len) = scratch_buf!();
+        let length = binary
+            .builder
+            .build_load(binary.context.i32_type(), scratch_len, "scratch_len")
+            .unwrap()
+            .into_int_value();
+
+        binary.slice(scratch_buf, length, ns)
+    }
+
     /// Polkadot value is usually 128 bits
     fn value_transferred<'b>(&self, binary: &Binary<'b>, ns: &ast::Namespace) -> IntValue<'b> {
         emit_context!(binary);
@@ -7,6 +7,7 @@ use crate::Target;
 use std::cmp::Ordering;
 
 use crate::codegen::{cfg::ReturnCode, Options};
+use crate::lir::heap;
 use crate::sema::ast::{Namespace, StructType, Type};
 use inkwell::module::{Linkage, Module};
 use inkwell::types::BasicType;
@@ -31,6 +32,7 @@ impl SolanaTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         opt: &'a Options,
+        contract_no: usize,
     ) -> Binary<'a> {
         let mut target = SolanaTarget();
         let filename = ns.files[contract.loc.file_no()].file_name();
@@ -67,6 +69,16 @@ impl SolanaTarget {
             ReturnCode::AccountDataTooSmall,
             context.i64_type().const_int(5u64 << 32, false),
         );
+        // the size of the heap, for the allocator in the stdlib
+        let heap_size = binary.module.get_global("__heap_size").unwrap();
+        heap_size.set_initializer(
+            &context
+                .i32_type()
+                .const_int(heap::heap_size(contract_no, ns).into(), false),
+        );
+        heap_size.set_constant(true);
+        heap_size.set_linkage(Linkage::Internal);
+
         // externals
         target.declare_externals(&mut binary, ns);
 
@@ -8,6 +8,7 @@ use crate::emit::loop_builder::LoopBuilder;
 use crate::emit::solana::SolanaTarget;
 use crate::emit::{ContractArgs, TargetRuntime, Variable};
 use crate::sema::ast::{self, Namespace};
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::types::{BasicType, BasicTypeEnum, IntType};
 use inkwell::values::{
     ArrayValue, BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
@@ -1324,7 +1325,7 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
 
     fn assert_failure(&self, binary: &Binary, data: PointerValue, length: IntValue) {
         // the reason code should be null (and already printed)
-        binary
+        let call = binary
             .builder
             .build_call(
                 binary.module.get_function("sol_set_return_data").unwrap(),
@@ -1340,6 +1341,15 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
             )
             .unwrap();
 
+        // a revert returns rather than traps, so llvm does not know that this path is cold
+        // unless the call says so
+        call.add_attribute(
+            AttributeLoc::Function,
+            binary
+                .context
+                .create_enum_attribute(Attribute::get_named_enum_kind_id("cold"), 0),
+        );
+
         // return 1 for failure
         binary
             .builder
@@ -1649,18 +1659,93 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
         unreachable!();
     }
 
-    /// Send value to address
+    /// Move lamports from the data account to the account with the given address. The accounts
+    /// are checked by the stdlib; only a lack of lamports is left to the caller, so that `send`
+    /// can return false.
     fn value_transfer<'b>(
         &self,
-        _binary: &Binary<'b>,
+        binary: &Binary<'b>,
         _function: FunctionValue,
-        _success: Option<&mut BasicValueEnum<'b>>,
-        _address: PointerValue<'b>,
-        _value: IntValue<'b>,
-        _ns: &ast::Namespace,
-        _loc: Loc,
+        success: Option<&mut BasicValueEnum<'b>>,
+        address: PointerValue<'b>,
+        value: IntValue<'b>,
+        ns: &ast::Namespace,
+        loc: Loc,
     ) {
-        unreachable!();
+        let parameters = self.sol_parameters(binary);
+        let account = self.contract_storage_account(binary);
+
+        let ret = binary
+            .builder
+            .build_call(
+                binary.module.get_function("sol_transfer_lamports").unwrap(),
+                &[
+                    parameters.into(),
+                    account.into(),
+                    address.into(),
+                    value.into(),
+                ],
+                "ret",
+            )
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        let failed = binary
+            .builder
+            .build_int_compare(
+                IntPredicate::NE,
+                ret,
+                binary.context.i64_type().const_zero(),
+                "failed",
+            )
+            .unwrap();
+
+        if let Some(success) = success {
+            *success = binary
+                .builder
+                .build_int_z_extend(failed, binary.context.i32_type(), "success")
+                .unwrap()
+                .into();
+
+            return;
+        }
+
+        let function = binary
+            .builder
+            .get_insert_block()
+            .unwrap()
+            .get_parent()
+            .unwrap();
+
+        let bang_block = binary.context.append_basic_block(function, "bang_block");
+        let success_block = binary.context.append_basic_block(function, "success");
+
+        binary
+            .builder
+            .build_conditional_branch(failed, bang_block, success_block)
+            .unwrap();
+
+        binary.builder.position_at_end(bang_block);
+        binary.log_runtime_error(
+            self,
+            "insufficient lamports for transfer".to_string(),
+            Some(loc),
+            ns,
+        );
+        self.assert_failure(
+            binary,
+            binary
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::default())
+                .const_null(),
+            binary.context.i32_type().const_zero(),
+        );
+
+        binary.builder.position_at_end(success_block);
     }
 
     /// Terminate execution, destroy binary and send remaining funds to addr
@@ -1855,6 +1940,66 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
                     )
                     .unwrap()
             }
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Balance,
+                args,
+                ..
+            } => {
+                let parameters = self.sol_parameters(binary);
+
+                // The balance of this contract is the lamports of its data account
+                if args.is_empty() {
+                    let account = self.contract_storage_account(binary);
+
+                    let lamports = binary
+                        .builder
+                        .build_load(
+                            binary.context.i64_type().ptr_type(AddressSpace::default()),
+                            binary
+                                .builder
+                                .build_struct_gep(
+                                    binary
+                                        .module
+                                        .get_struct_type("struct.SolAccountInfo")
+                                        .unwrap(),
+                                    account,
+                                    1,
+                                    "lamports",
+                                )
+                                .unwrap(),
+                            "lamports",
+                        )
+                        .unwrap()
+                        .into_pointer_value();
+
+                    return binary
+                        .builder
+                        .build_load(binary.context.i64_type(), lamports, "balance")
+                        .unwrap();
+                }
+
+                let address = binary.build_alloca(function, binary.address_type(ns), "address");
+
+                binary
+                    .builder
+                    .build_store(
+                        address,
+                        expression(self, binary, &args[0], vartab, function, ns).into_array_value(),
+                    )
+                    .unwrap();
+
+                binary
+                    .builder
+                    .build_call(
+                        binary.module.get_function("sol_account_lamports").unwrap(),
+                        &[parameters.into(), address.into()],
+                        "lamports",
+                    )
+                    .unwrap()
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+            }
             codegen::Expression::Builtin {
                 kind: codegen::Builtin::Calldata,
                 args,
@@ -1957,6 +2102,27 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
                     .unwrap()
                     .into_pointer_value();
 
+                let input_len = binary
+                    .builder
+                    .build_load(
+                        binary.context.i64_type(),
+                        binary
+                            .builder
+                            .build_struct_gep(
+                                binary
+                                    .module
+                                    .get_struct_type("struct.SolParameters")
+                                    .unwrap(),
+                                sol_params,
+                                3,
+                                "input_len",
+                            )
+                            .unwrap(),
+                        "data_len",
+                    )
+                    .unwrap()
+                    .into_int_value();
+
                 let selector = binary
                     .builder
                     .build_load(binary.context.i64_type(), input, "selector")
@@ -1964,13 +2130,36 @@ impl<'a> TargetRuntime<'a> for SolanaTarget {
 
                 let bswap = binary.llvm_bswap(64);
 
-                binary
+                let selector = binary
                     .builder
                     .build_call(bswap, &[selector.into()], "")
                     .unwrap()
                     .try_as_basic_value()
                     .left()
                     .unwrap()
+                    .into_int_value();
+
+                // the instruction data may be too short to have a selector, e.g. in the fallback
+                // function, in which case msg.sig is zero
+                let has_selector = binary
+                    .builder
+                    .build_int_compare(
+                        IntPredicate::UGE,
+                        input_len,
+                        binary.context.i64_type().const_int(8, false),
+                        "has_selector",
+                    )
+                    .unwrap();
+
+                binary
+                    .builder
+                    .build_select(
+                        has_selector,
+                        selector,
+                        binary.context.i64_type().const_zero(),
+                        "selector",
+                    )
+                    .unwrap()
             }
             codegen::Expression::Builtin {
                 kind: codegen::Builtin::SignatureVerify,
@@ -1,24 +1,155 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(super) mod target;
+mod val;
+
 use crate::codegen::cfg::ControlFlowGraph;
 use crate::emit::cfg::emit_cfg;
 use crate::{
-    codegen::{cfg::ASTFunction, Options},
+    codegen::{cfg::ASTFunction, reachable::reachable_cfgs, Options},
     emit::Binary,
-    sema::ast,
+    sema::ast::{self, ArrayLength, Type},
 };
 use inkwell::{
     context::Context,
     module::{Linkage, Module},
+    types::BasicMetadataTypeEnum,
+    values::{BasicMetadataValueEnum, FunctionValue},
+    AddressSpace,
 };
+use solang_parser::pt;
 use soroban_sdk::xdr::{
     DepthLimitedWrite, ScEnvMetaEntry, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0,
-    ScSpecTypeDef, StringM, WriteXdr,
+    ScSpecTypeDef, ScSpecTypeVec, StringM, WriteXdr,
 };
 
 const SOROBAN_ENV_INTERFACE_VERSION: u64 = 85899345977;
 
+/// Soroban host functions are imported from single letter modules, e.g. "l" for ledger
+/// functions. The function is declared as "module.name", which the linker splits up again.
+#[derive(Clone, Copy)]
+pub(crate) enum HostFunctions {
+    LogFromLinearMemory,
+    PutContractData,
+    HasContractData,
+    GetContractData,
+    DelContractData,
+    ObjFromU64,
+    ObjToU64,
+    ObjFromI64,
+    ObjToI64,
+    ObjFromU128Pieces,
+    ObjToU128Lo64,
+    ObjToU128Hi64,
+    ObjFromI128Pieces,
+    ObjToI128Lo64,
+    ObjToI128Hi64,
+    VecLen,
+    VecNewFromLinearMemory,
+    VecUnpackToLinearMemory,
+    BytesCopyToLinearMemory,
+    BytesNewFromLinearMemory,
+    BytesPut,
+    BytesGet,
+    BytesLen,
+    StringCopyToLinearMemory,
+    StringNewFromLinearMemory,
+    StringLen,
+}
+
+impl HostFunctions {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            HostFunctions::LogFromLinearMemory => "x._",
+            HostFunctions::PutContractData => "l._",
+            HostFunctions::HasContractData => "l.0",
+            HostFunctions::GetContractData => "l.1",
+            HostFunctions::DelContractData => "l.2",
+            HostFunctions::ObjFromU64 => "i._",
+            HostFunctions::ObjToU64 => "i.0",
+            HostFunctions::ObjFromI64 => "i.1",
+            HostFunctions::ObjToI64 => "i.2",
+            HostFunctions::ObjFromU128Pieces => "i.3",
+            HostFunctions::ObjToU128Lo64 => "i.4",
+            HostFunctions::ObjToU128Hi64 => "i.5",
+            HostFunctions::ObjFromI128Pieces => "i.6",
+            HostFunctions::ObjToI128Lo64 => "i.7",
+            HostFunctions::ObjToI128Hi64 => "i.8",
+            HostFunctions::VecLen => "v.3",
+            HostFunctions::VecNewFromLinearMemory => "v.g",
+            HostFunctions::VecUnpackToLinearMemory => "v.h",
+            HostFunctions::BytesCopyToLinearMemory => "b.1",
+            HostFunctions::BytesNewFromLinearMemory => "b.3",
+            HostFunctions::BytesPut => "b.5",
+            HostFunctions::BytesGet => "b.6",
+            HostFunctions::BytesLen => "b.8",
+            HostFunctions::StringCopyToLinearMemory => "b.g",
+            HostFunctions::StringNewFromLinearMemory => "b.i",
+            HostFunctions::StringLen => "b.k",
+        }
+    }
+
+    fn params(&self) -> usize {
+        match self {
+            HostFunctions::ObjFromU64
+            | HostFunctions::ObjToU64
+            | HostFunctions::ObjFromI64
+            | HostFunctions::ObjToI64
+            | HostFunctions::ObjToU128Lo64
+            | HostFunctions::ObjToU128Hi64
+            | HostFunctions::ObjToI128Lo64
+            | HostFunctions::ObjToI128Hi64
+            | HostFunctions::VecLen
+            | HostFunctions::BytesLen
+            | HostFunctions::StringLen => 1,
+            HostFunctions::HasContractData
+            | HostFunctions::GetContractData
+            | HostFunctions::DelContractData
+            | HostFunctions::ObjFromU128Pieces
+            | HostFunctions::ObjFromI128Pieces
+            | HostFunctions::VecNewFromLinearMemory
+            | HostFunctions::BytesNewFromLinearMemory
+            | HostFunctions::BytesGet
+            | HostFunctions::StringNewFromLinearMemory => 2,
+            HostFunctions::PutContractData
+            | HostFunctions::VecUnpackToLinearMemory
+            | HostFunctions::BytesPut => 3,
+            HostFunctions::LogFromLinearMemory
+            | HostFunctions::BytesCopyToLinearMemory
+            | HostFunctions::StringCopyToLinearMemory => 4,
+        }
+    }
+
+    const ALL: [HostFunctions; 26] = [
+        HostFunctions::LogFromLinearMemory,
+        HostFunctions::PutContractData,
+        HostFunctions::HasContractData,
+        HostFunctions::GetContractData,
+        HostFunctions::DelContractData,
+        HostFunctions::ObjFromU64,
+        HostFunctions::ObjToU64,
+        HostFunctions::ObjFromI64,
+        HostFunctions::ObjToI64,
+        HostFunctions::ObjFromU128Pieces,
+        HostFunctions::ObjToU128Lo64,
+        HostFunctions::ObjToU128Hi64,
+        HostFunctions::ObjFromI128Pieces,
+        HostFunctions::ObjToI128Lo64,
+        HostFunctions::ObjToI128Hi64,
+        HostFunctions::VecLen,
+        HostFunctions::VecNewFromLinearMemory,
+        HostFunctions::VecUnpackToLinearMemory,
+        HostFunctions::BytesCopyToLinearMemory,
+        HostFunctions::BytesNewFromLinearMemory,
+        HostFunctions::BytesPut,
+        HostFunctions::BytesGet,
+        HostFunctions::BytesLen,
+        HostFunctions::StringCopyToLinearMemory,
+        HostFunctions::StringNewFromLinearMemory,
+        HostFunctions::StringLen,
+    ];
+}
+
 pub struct SorobanTarget;
 
 impl SorobanTarget {
@@ -41,12 +172,32 @@ impl SorobanTarget {
             None,
         );
 
+        binary.vector_init_empty = context
+            .i32_type()
+            .const_all_ones()
+            .const_to_pointer(context.i8_type().ptr_type(AddressSpace::default()));
+
+        Self::declare_externals(&binary);
         Self::emit_functions_with_spec(contract, &mut binary, ns, context, contract_no);
         Self::emit_env_meta_entries(context, &mut binary);
 
         binary
     }
 
+    fn declare_externals(binary: &Binary) {
+        let i64_type = binary.context.i64_type();
+
+        for func in HostFunctions::ALL {
+            let args: Vec<BasicMetadataTypeEnum> = vec![i64_type.into(); func.params()];
+
+            binary.module.add_function(
+                func.name(),
+                i64_type.fn_type(&args, false),
+                Some(Linkage::External),
+            );
+        }
+    }
+
     // In Soroban, the public functions specifications is embeded in the contract binary.
     // for each function, emit both the function spec entry and the function body.
     fn emit_functions_with_spec<'a>(
@@ -57,8 +208,14 @@ impl SorobanTarget {
         contract_no: usize,
     ) {
         let mut defines = Vec::new();
+        let mut exports = Vec::new();
+        let reachable = reachable_cfgs(contract);
 
         for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
+            if !reachable[cfg_no] {
+                continue;
+            }
+
             let ftype = binary.function_type(
                 &cfg.params.iter().map(|p| p.ty.clone()).collect::<Vec<_>>(),
                 &cfg.returns.iter().map(|p| p.ty.clone()).collect::<Vec<_>>(),
@@ -90,29 +247,89 @@ impl SorobanTarget {
 
             Self::emit_function_spec_entry(context, cfg, name.clone(), binary);
 
-            let linkage = if cfg.public {
-                Linkage::External
-            } else {
-                Linkage::Internal
-            };
-
-            let func_decl = if let Some(func) = binary.module.get_function(name) {
+            // The function itself takes and returns Solidity values. Public functions are
+            // exported through a wrapper which converts from and to Soroban values.
+            let func_decl = if let Some(func) = binary.module.get_function(&cfg.name) {
                 // must not have a body yet
                 assert_eq!(func.get_first_basic_block(), None);
 
                 func
             } else {
-                binary.module.add_function(name, ftype, Some(linkage))
+                binary
+                    .module
+                    .add_function(&cfg.name, ftype, Some(Linkage::Internal))
             };
 
             binary.functions.insert(cfg_no, func_decl);
 
+            if cfg.public && !cfg.is_placeholder() && cfg.ty != pt::FunctionTy::Constructor {
+                exports.push((name.clone(), func_decl, cfg));
+            }
+
             defines.push((func_decl, cfg));
         }
 
         for (func_decl, cfg) in defines {
             emit_cfg(&mut SorobanTarget, binary, contract, cfg, func_decl, ns);
         }
+
+        for (name, func_decl, cfg) in exports {
+            Self::emit_function_wrapper(binary, &name, func_decl, cfg, ns);
+        }
+    }
+
+    /// Emit the exported function, which decodes the Soroban values it is called with,
+    /// calls the function, and encodes the return value.
+    fn emit_function_wrapper<'a>(
+        binary: &Binary<'a>,
+        name: &str,
+        func_decl: FunctionValue<'a>,
+        cfg: &ControlFlowGraph,
+        ns: &ast::Namespace,
+    ) {
+        let i64_type = binary.context.i64_type();
+
+        let args: Vec<BasicMetadataTypeEnum> = vec![i64_type.into(); cfg.params.len()];
+
+        let wrapper = binary.module.add_function(
+            name,
+            i64_type.fn_type(&args, false),
+            Some(Linkage::External),
+        );
+
+        let entry = binary.context.append_basic_block(wrapper, "entry");
+
+        binary.builder.position_at_end(entry);
+
+        binary
+            .builder
+            .build_call(binary.module.get_function("__init_heap").unwrap(), &[], "")
+            .unwrap();
+
+        let args = cfg
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let val = wrapper.get_nth_param(i as u32).unwrap().into_int_value();
+
+                val::decode(binary, wrapper, &param.ty, val, ns).into()
+            })
+            .collect::<Vec<BasicMetadataValueEnum>>();
+
+        let ret = binary
+            .builder
+            .build_call(func_decl, &args, "")
+            .unwrap()
+            .try_as_basic_value()
+            .left();
+
+        let ret = match (cfg.returns.first(), ret) {
+            (Some(param), Some(ret)) => val::encode(binary, wrapper, &param.ty, ret, ns),
+            _ => i64_type.const_int(val::TAG_VOID, false),
+        };
+
+        binary.builder.build_return(Some(&ret)).unwrap();
     }
 
     fn emit_env_meta_entries<'a>(context: &'a Context, binary: &mut Binary<'a>) {
@@ -148,8 +365,8 @@ impl SorobanTarget {
                             .unwrap_or_else(|| i.to_string())
                             .try_into()
                             .expect("function input name exceeds limit"),
-                        type_: ScSpecTypeDef::U32, // TODO: Map type.
-                        doc: StringM::default(),   // TODO: Add doc.
+                        type_: Self::spec_type(&p.ty),
+                        doc: StringM::default(), // TODO: Add doc.
                     })
                     .collect::<Vec<_>>()
                     .try_into()
@@ -157,7 +374,7 @@ impl SorobanTarget {
                 outputs: cfg
                     .returns
                     .iter()
-                    .map(|_| ScSpecTypeDef::U32) // TODO: Map type.
+                    .map(|p| Self::spec_type(&p.ty))
                     .collect::<Vec<_>>()
                     .try_into()
                     .expect("function output count exceeds limit"),
@@ -170,6 +387,28 @@ impl SorobanTarget {
         }
     }
 
+    /// The type of a function argument or return value in the contract spec
+    fn spec_type(ty: &Type) -> ScSpecTypeDef {
+        match ty {
+            Type::Bool => ScSpecTypeDef::Bool,
+            Type::Enum(_) => ScSpecTypeDef::U32,
+            Type::Int(n) if *n <= 32 => ScSpecTypeDef::I32,
+            Type::Uint(n) if *n <= 32 => ScSpecTypeDef::U32,
+            Type::Int(n) if *n <= 64 => ScSpecTypeDef::I64,
+            Type::Uint(n) if *n <= 64 => ScSpecTypeDef::U64,
+            Type::Int(n) if *n <= 128 => ScSpecTypeDef::I128,
+            Type::Uint(n) if *n <= 128 => ScSpecTypeDef::U128,
+            Type::String => ScSpecTypeDef::String,
+            Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+                ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                    element_type: Box::new(Self::spec_type(&ty.array_elem())),
+                }))
+            }
+            // everything else is passed as bytes
+            _ => ScSpecTypeDef::Bytes,
+        }
+    }
+
     fn add_custom_section<'a>(
         context: &'a Context,
         module: &Module<'a>,
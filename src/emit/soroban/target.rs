@@ -1,21 +1,127 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::codegen::cfg::HashTy;
+use crate::codegen::revert::PanicCode;
 use crate::codegen::Expression;
 use crate::emit::binary::Binary;
-use crate::emit::soroban::SorobanTarget;
+use crate::emit::soroban::{val, HostFunctions, SorobanTarget};
 use crate::emit::ContractArgs;
 use crate::emit::{TargetRuntime, Variable};
 use crate::sema::ast;
 use crate::sema::ast::CallTy;
-use crate::sema::ast::{Function, Namespace, Type};
-use inkwell::types::{BasicTypeEnum, IntType};
+use crate::sema::ast::{ArrayLength, Function, Namespace, Type};
+use inkwell::types::{BasicType, BasicTypeEnum, IntType};
 use inkwell::values::{
     ArrayValue, BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
 };
+use inkwell::IntPredicate;
 use solang_parser::pt::Loc;
 use std::collections::HashMap;
 
+/// All contract data is kept in persistent storage
+fn storage_type<'a>(bin: &Binary<'a>) -> IntValue<'a> {
+    bin.context.i64_type().const_int(1, false)
+}
+
+fn has_contract_data<'a>(bin: &Binary<'a>, key: IntValue<'a>) -> IntValue<'a> {
+    let has = val::call(
+        bin,
+        HostFunctions::HasContractData,
+        &[key, storage_type(bin)],
+    );
+
+    bin.builder
+        .build_int_compare(
+            IntPredicate::EQ,
+            has,
+            bin.context.i64_type().const_int(val::TAG_TRUE, false),
+            "has",
+        )
+        .unwrap()
+}
+
+fn put_contract_data<'a>(bin: &Binary<'a>, key: IntValue<'a>, value: IntValue<'a>) {
+    val::call(
+        bin,
+        HostFunctions::PutContractData,
+        &[key, value, storage_type(bin)],
+    );
+}
+
+/// The length of a dynamic storage array is stored under the key of the array itself
+fn array_length<'a>(bin: &Binary<'a>, function: FunctionValue, key: IntValue<'a>) -> IntValue<'a> {
+    val::select_with(
+        bin,
+        function,
+        has_contract_data(bin, key),
+        || {
+            let length = val::call(
+                bin,
+                HostFunctions::GetContractData,
+                &[key, storage_type(bin)],
+            );
+
+            val::u32_from_val(bin, length).into()
+        },
+        || bin.context.i32_type().const_zero().into(),
+    )
+    .into_int_value()
+}
+
+/// The key of an array element or mapping value is the vector `[parent key, index]`
+fn element_key<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    key: IntValue<'a>,
+    index: IntValue<'a>,
+) -> IntValue<'a> {
+    let i64_type = bin.context.i64_type();
+    let vals = bin.build_alloca(function, i64_type.array_type(2), "key");
+
+    bin.builder.build_store(vals, key).unwrap();
+
+    let second = unsafe {
+        bin.builder
+            .build_gep(
+                i64_type,
+                vals,
+                &[bin.context.i32_type().const_int(1, false)],
+                "index",
+            )
+            .unwrap()
+    };
+
+    bin.builder.build_store(second, index).unwrap();
+
+    val::call(
+        bin,
+        HostFunctions::VecNewFromLinearMemory,
+        &[
+            val::pointer_val(bin, vals),
+            val::u32_val(bin, bin.context.i32_type().const_int(2, false)),
+        ],
+    )
+}
+
+/// The value of a storage variable which has not been written yet
+fn zero_value<'a>(bin: &Binary<'a>, ty: &Type, ns: &Namespace) -> BasicValueEnum<'a> {
+    if matches!(ty, Type::String | Type::DynamicBytes) {
+        let i32_type = bin.context.i32_type();
+
+        return bin
+            .vector_new(i32_type.const_zero(), i32_type.const_int(1, false), None)
+            .into();
+    }
+
+    match bin.llvm_var_ty(ty, ns) {
+        BasicTypeEnum::IntType(ty) => ty.const_zero().into(),
+        BasicTypeEnum::PointerType(ty) => ty.const_null().into(),
+        BasicTypeEnum::ArrayType(ty) => ty.const_zero().into(),
+        BasicTypeEnum::StructType(ty) => ty.const_zero().into(),
+        _ => unreachable!(),
+    }
+}
+
 // TODO: Implement TargetRuntime for SorobanTarget.
 #[allow(unused_variables)]
 impl<'a> TargetRuntime<'a> for SorobanTarget {
@@ -37,7 +143,67 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         function: FunctionValue<'a>,
         ns: &ast::Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!()
+        match ty {
+            Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+                let key = *slot;
+                let elem_ty = ty.array_elem();
+                let len = array_length(binary, function, key);
+
+                let elem_size = binary
+                    .llvm_field_ty(&elem_ty, ns)
+                    .size_of()
+                    .unwrap()
+                    .const_cast(binary.context.i32_type(), false);
+
+                let v = binary.vector_new(len, elem_size, None);
+
+                let mut unused = binary.context.i32_type().const_zero();
+
+                binary.emit_loop_cond_first_with_int(
+                    function,
+                    binary.context.i32_type().const_zero(),
+                    len,
+                    &mut unused,
+                    |index, _| {
+                        let mut elem_key =
+                            element_key(binary, function, key, val::u32_val(binary, index));
+
+                        let elem = self.storage_load(binary, &elem_ty, &mut elem_key, function, ns);
+
+                        let elem_ptr = binary.array_subscript(ty, v, index, ns);
+
+                        binary.builder.build_store(elem_ptr, elem).unwrap();
+                    },
+                );
+
+                v.into()
+            }
+            Type::Struct(_) | Type::Array(..) | Type::Mapping(..) => {
+                unreachable!(
+                    "sema does not allow {} in storage on Soroban",
+                    ty.to_string(ns)
+                )
+            }
+            _ => {
+                let key = *slot;
+
+                val::select_with(
+                    binary,
+                    function,
+                    has_contract_data(binary, key),
+                    || {
+                        let value = val::call(
+                            binary,
+                            HostFunctions::GetContractData,
+                            &[key, storage_type(binary)],
+                        );
+
+                        val::decode(binary, function, ty, value, ns)
+                    },
+                    || zero_value(binary, ty, ns),
+                )
+            }
+        }
     }
 
     /// Recursively store a type to storage
@@ -51,7 +217,72 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         function: FunctionValue<'a>,
         ns: &ast::Namespace,
     ) {
-        unimplemented!()
+        match ty {
+            Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+                let key = *slot;
+                let elem_ty = ty.array_elem();
+                let len = binary.vector_len(dest);
+                let old_len = array_length(binary, function, key);
+
+                put_contract_data(binary, key, val::u32_val(binary, len));
+
+                let mut unused = binary.context.i32_type().const_zero();
+
+                binary.emit_loop_cond_first_with_int(
+                    function,
+                    binary.context.i32_type().const_zero(),
+                    len,
+                    &mut unused,
+                    |index, _| {
+                        let mut elem_key =
+                            element_key(binary, function, key, val::u32_val(binary, index));
+
+                        let elem_ptr =
+                            binary.array_subscript(ty, dest.into_pointer_value(), index, ns);
+
+                        let elem = binary
+                            .builder
+                            .build_load(binary.llvm_field_ty(&elem_ty, ns), elem_ptr, "elem")
+                            .unwrap();
+
+                        self.storage_store(
+                            binary,
+                            &elem_ty,
+                            true,
+                            &mut elem_key,
+                            elem,
+                            function,
+                            ns,
+                        );
+                    },
+                );
+
+                // remove any elements beyond the new length
+                binary.emit_loop_cond_first_with_int(
+                    function,
+                    len,
+                    old_len,
+                    &mut unused,
+                    |index, _| {
+                        let mut elem_key =
+                            element_key(binary, function, key, val::u32_val(binary, index));
+
+                        self.storage_delete(binary, &elem_ty, &mut elem_key, function, ns);
+                    },
+                );
+            }
+            Type::Struct(_) | Type::Array(..) | Type::Mapping(..) => {
+                unreachable!(
+                    "sema does not allow {} in storage on Soroban",
+                    ty.to_string(ns)
+                )
+            }
+            _ => {
+                let value = val::encode(binary, function, ty, dest, ns);
+
+                put_contract_data(binary, *slot, value);
+            }
+        }
     }
 
     /// Recursively clear storage. The default implementation is for slot-based storage
@@ -63,7 +294,47 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         function: FunctionValue<'a>,
         ns: &Namespace,
     ) {
-        unimplemented!()
+        match ty {
+            Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+                let key = *slot;
+                let elem_ty = ty.array_elem();
+                let len = array_length(bin, function, key);
+
+                let mut unused = bin.context.i32_type().const_zero();
+
+                bin.emit_loop_cond_first_with_int(
+                    function,
+                    bin.context.i32_type().const_zero(),
+                    len,
+                    &mut unused,
+                    |index, _| {
+                        let mut elem_key =
+                            element_key(bin, function, key, val::u32_val(bin, index));
+
+                        self.storage_delete(bin, &elem_ty, &mut elem_key, function, ns);
+                    },
+                );
+
+                val::call(
+                    bin,
+                    HostFunctions::DelContractData,
+                    &[key, storage_type(bin)],
+                );
+            }
+            Type::Struct(_) | Type::Array(..) | Type::Mapping(..) => {
+                unreachable!(
+                    "sema does not allow {} in storage on Soroban",
+                    ty.to_string(ns)
+                )
+            }
+            _ => {
+                val::call(
+                    bin,
+                    HostFunctions::DelContractData,
+                    &[*slot, storage_type(bin)],
+                );
+            }
+        }
     }
 
     // Bytes and string have special storage layout
@@ -116,7 +387,22 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         loc: Loc,
         ns: &Namespace,
     ) -> IntValue<'a> {
-        unimplemented!()
+        let bytes = val::call(
+            bin,
+            HostFunctions::GetContractData,
+            &[slot, storage_type(bin)],
+        );
+
+        // the host checks the index is in bounds
+        let value = val::call(
+            bin,
+            HostFunctions::BytesGet,
+            &[bytes, val::u32_val(bin, index)],
+        );
+
+        bin.builder
+            .build_int_truncate(val::u32_from_val(bin, value), bin.context.i8_type(), "byte")
+            .unwrap()
     }
 
     fn set_storage_bytes_subscript(
@@ -129,7 +415,19 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         ns: &Namespace,
         loc: Loc,
     ) {
-        unimplemented!()
+        let bytes = val::call(
+            bin,
+            HostFunctions::GetContractData,
+            &[slot, storage_type(bin)],
+        );
+
+        let bytes = val::call(
+            bin,
+            HostFunctions::BytesPut,
+            &[bytes, val::u32_val(bin, index), val::u32_val(bin, value)],
+        );
+
+        put_contract_data(bin, slot, bytes);
     }
 
     fn storage_subscript(
@@ -141,7 +439,23 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         index: BasicValueEnum<'a>,
         ns: &Namespace,
     ) -> IntValue<'a> {
-        unimplemented!()
+        let index = match ty.deref_any() {
+            Type::Mapping(ast::Mapping { key, .. }) => val::encode(bin, function, key, index, ns),
+            _ => {
+                let index = bin
+                    .builder
+                    .build_int_truncate_or_bit_cast(
+                        index.into_int_value(),
+                        bin.context.i32_type(),
+                        "index",
+                    )
+                    .unwrap();
+
+                val::u32_val(bin, index)
+            }
+        };
+
+        element_key(bin, function, slot, index)
     }
 
     fn storage_push(
@@ -150,10 +464,29 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         function: FunctionValue<'a>,
         ty: &Type,
         slot: IntValue<'a>,
-        val: Option<BasicValueEnum<'a>>,
+        value: Option<BasicValueEnum<'a>>,
         ns: &Namespace,
     ) -> BasicValueEnum<'a> {
-        unimplemented!()
+        let len = array_length(bin, function, slot);
+
+        let mut elem_key = element_key(bin, function, slot, val::u32_val(bin, len));
+
+        if let Some(value) = value {
+            self.storage_store(bin, ty, true, &mut elem_key, value, function, ns);
+        }
+
+        let new_len = bin
+            .builder
+            .build_int_add(len, bin.context.i32_type().const_int(1, false), "new_len")
+            .unwrap();
+
+        put_contract_data(bin, slot, val::u32_val(bin, new_len));
+
+        if ty.is_reference_type(ns) {
+            elem_key.into()
+        } else {
+            value.unwrap_or_else(|| zero_value(bin, ty, ns))
+        }
     }
 
     fn storage_pop(
@@ -166,18 +499,104 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
         ns: &Namespace,
         loc: Loc,
     ) -> Option<BasicValueEnum<'a>> {
-        unimplemented!()
+        let len = array_length(bin, function, slot);
+
+        let not_empty = bin
+            .builder
+            .build_int_compare(
+                IntPredicate::NE,
+                len,
+                bin.context.i32_type().const_zero(),
+                "not_empty",
+            )
+            .unwrap();
+
+        let pop_block = bin.context.append_basic_block(function, "pop");
+        let empty_block = bin.context.append_basic_block(function, "empty");
+
+        bin.builder
+            .build_conditional_branch(not_empty, pop_block, empty_block)
+            .unwrap();
+
+        bin.builder.position_at_end(empty_block);
+        bin.log_runtime_error(
+            self,
+            "pop from empty storage array".to_string(),
+            Some(loc),
+            ns,
+        );
+        bin.assert_panic(self, ns, PanicCode::EmptyArrayPop);
+
+        bin.builder.position_at_end(pop_block);
+
+        let new_len = bin
+            .builder
+            .build_int_sub(len, bin.context.i32_type().const_int(1, false), "new_len")
+            .unwrap();
+
+        let mut elem_key = element_key(bin, function, slot, val::u32_val(bin, new_len));
+
+        let value = if load {
+            Some(self.storage_load(bin, ty, &mut elem_key, function, ns))
+        } else {
+            None
+        };
+
+        self.storage_delete(bin, ty, &mut elem_key, function, ns);
+
+        put_contract_data(bin, slot, val::u32_val(bin, new_len));
+
+        value
     }
 
     fn storage_array_length(
         &self,
-        _bin: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: IntValue<'a>,
-        _elem_ty: &Type,
-        _ns: &Namespace,
+        bin: &Binary<'a>,
+        function: FunctionValue,
+        slot: IntValue<'a>,
+        elem_ty: &Type,
+        ns: &Namespace,
     ) -> IntValue<'a> {
-        unimplemented!()
+        // bytes are stored as a bytes object, arrays store their length
+        val::select_with(
+            bin,
+            function,
+            has_contract_data(bin, slot),
+            || {
+                let value = val::call(
+                    bin,
+                    HostFunctions::GetContractData,
+                    &[slot, storage_type(bin)],
+                );
+
+                let is_bytes = bin
+                    .builder
+                    .build_int_compare(
+                        IntPredicate::EQ,
+                        val::tag(bin, value),
+                        bin.context
+                            .i64_type()
+                            .const_int(val::TAG_BYTES_OBJECT, false),
+                        "is_bytes",
+                    )
+                    .unwrap();
+
+                let length = bin
+                    .builder
+                    .build_select(
+                        is_bytes,
+                        val::call(bin, HostFunctions::BytesLen, &[value]),
+                        value,
+                        "length",
+                    )
+                    .unwrap()
+                    .into_int_value();
+
+                val::u32_from_val(bin, length).into()
+            },
+            || bin.context.i32_type().const_zero().into(),
+        )
+        .into_int_value()
     }
 
     /// keccak256 hash
@@ -193,8 +612,17 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
     }
 
     /// Prints a string
-    fn print(&self, bin: &Binary, string: PointerValue, length: IntValue) {
-        unimplemented!()
+    fn print<'b>(&self, bin: &Binary<'b>, string: PointerValue<'b>, length: IntValue<'b>) {
+        val::call(
+            bin,
+            HostFunctions::LogFromLinearMemory,
+            &[
+                val::pointer_val(bin, string),
+                val::u32_val(bin, length),
+                val::u32_val(bin, bin.context.i32_type().const_zero()),
+                val::u32_val(bin, bin.context.i32_type().const_zero()),
+            ],
+        );
     }
 
     /// Return success without any result
@@ -209,7 +637,8 @@ impl<'a> TargetRuntime<'a> for SorobanTarget {
 
     /// Return failure without any result
     fn assert_failure(&self, bin: &Binary, data: PointerValue, length: IntValue) {
-        unimplemented!()
+        // Soroban has no return data on failure; trapping fails the invocation
+        bin.builder.build_unreachable().unwrap();
     }
 
     fn builtin_function(
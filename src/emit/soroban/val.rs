@@ -0,0 +1,697 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion between Solidity values and Soroban host values. A Soroban value (`Val`) is a
+//! 64 bit integer; the lower 8 bits are the tag and the upper 56 bits are the body. Small
+//! values are stored in the body directly, larger values and containers are host objects
+//! which are referenced by a handle in the upper 32 bits.
+
+use crate::emit::binary::Binary;
+use crate::emit::soroban::HostFunctions;
+use crate::sema::ast::{ArrayLength, Namespace, Type};
+use inkwell::types::BasicType;
+use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
+use inkwell::IntPredicate;
+
+pub(super) const TAG_TRUE: u64 = 1;
+pub(super) const TAG_VOID: u64 = 2;
+const TAG_U32: u64 = 4;
+const TAG_I32: u64 = 5;
+const TAG_U64_SMALL: u64 = 6;
+const TAG_I64_SMALL: u64 = 7;
+const TAG_U128_SMALL: u64 = 10;
+const TAG_I128_SMALL: u64 = 11;
+pub(super) const TAG_BYTES_OBJECT: u64 = 72;
+
+/// Call a host function. All arguments and return values of host functions are 64 bit values.
+pub(super) fn call<'a>(
+    bin: &Binary<'a>,
+    func: HostFunctions,
+    args: &[IntValue<'a>],
+) -> IntValue<'a> {
+    let args = args.iter().map(|arg| (*arg).into()).collect::<Vec<_>>();
+
+    bin.builder
+        .build_call(bin.module.get_function(func.name()).unwrap(), &args, "")
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value()
+}
+
+/// Encode a 32 bit integer as a U32Val
+pub(super) fn u32_val<'a>(bin: &Binary<'a>, value: IntValue<'a>) -> IntValue<'a> {
+    let i64_type = bin.context.i64_type();
+
+    let value = bin
+        .builder
+        .build_int_z_extend_or_bit_cast(value, i64_type, "")
+        .unwrap();
+
+    let body = bin
+        .builder
+        .build_left_shift(value, i64_type.const_int(32, false), "")
+        .unwrap();
+
+    bin.builder
+        .build_or(body, i64_type.const_int(TAG_U32, false), "u32_val")
+        .unwrap()
+}
+
+/// Decode a U32Val to a 32 bit integer
+pub(super) fn u32_from_val<'a>(bin: &Binary<'a>, val: IntValue<'a>) -> IntValue<'a> {
+    let value = bin
+        .builder
+        .build_right_shift(val, bin.context.i64_type().const_int(32, false), false, "")
+        .unwrap();
+
+    bin.builder
+        .build_int_truncate(value, bin.context.i32_type(), "u32")
+        .unwrap()
+}
+
+/// Linear memory positions are passed to the host as U32Val
+pub(super) fn pointer_val<'a>(bin: &Binary<'a>, ptr: PointerValue<'a>) -> IntValue<'a> {
+    let pos = bin
+        .builder
+        .build_ptr_to_int(ptr, bin.context.i32_type(), "pos")
+        .unwrap();
+
+    u32_val(bin, pos)
+}
+
+/// The tag of a value
+pub(super) fn tag<'a>(bin: &Binary<'a>, val: IntValue<'a>) -> IntValue<'a> {
+    bin.builder
+        .build_and(val, bin.context.i64_type().const_int(0xff, false), "tag")
+        .unwrap()
+}
+
+/// Emit a conditional with a value on both sides
+pub(super) fn select_with<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue,
+    cond: IntValue<'a>,
+    then: impl FnOnce() -> BasicValueEnum<'a>,
+    otherwise: impl FnOnce() -> BasicValueEnum<'a>,
+) -> BasicValueEnum<'a> {
+    let then_block = bin.context.append_basic_block(function, "then");
+    let else_block = bin.context.append_basic_block(function, "else");
+    let done_block = bin.context.append_basic_block(function, "done");
+
+    bin.builder
+        .build_conditional_branch(cond, then_block, else_block)
+        .unwrap();
+
+    bin.builder.position_at_end(then_block);
+    let then_value = then();
+    let then_block = bin.builder.get_insert_block().unwrap();
+    bin.builder.build_unconditional_branch(done_block).unwrap();
+
+    bin.builder.position_at_end(else_block);
+    let else_value = otherwise();
+    let else_block = bin.builder.get_insert_block().unwrap();
+    bin.builder.build_unconditional_branch(done_block).unwrap();
+
+    bin.builder.position_at_end(done_block);
+
+    let phi = bin.builder.build_phi(then_value.get_type(), "").unwrap();
+    phi.add_incoming(&[(&then_value, then_block), (&else_value, else_block)]);
+
+    phi.as_basic_value()
+}
+
+/// Encode a Solidity value as a Soroban value. Strings, bytes and dynamic arrays become host
+/// objects. Values which have no Soroban representation are passed as their bytes.
+pub(super) fn encode<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    ty: &Type,
+    value: BasicValueEnum<'a>,
+    ns: &Namespace,
+) -> IntValue<'a> {
+    let i64_type = bin.context.i64_type();
+
+    match ty {
+        Type::Bool => bin
+            .builder
+            .build_int_z_extend(value.into_int_value(), i64_type, "bool_val")
+            .unwrap(),
+        Type::Enum(_) => u32_val(bin, value.into_int_value()),
+        Type::Int(n) | Type::Uint(n) if *n <= 32 => {
+            let signed = matches!(ty, Type::Int(_));
+            let value = value.into_int_value();
+
+            let value = if signed {
+                let value = bin
+                    .builder
+                    .build_int_s_extend_or_bit_cast(value, bin.context.i32_type(), "")
+                    .unwrap();
+                bin.builder.build_int_z_extend(value, i64_type, "").unwrap()
+            } else {
+                bin.builder
+                    .build_int_z_extend_or_bit_cast(value, i64_type, "")
+                    .unwrap()
+            };
+
+            let body = bin
+                .builder
+                .build_left_shift(value, i64_type.const_int(32, false), "")
+                .unwrap();
+
+            bin.builder
+                .build_or(
+                    body,
+                    i64_type.const_int(if signed { TAG_I32 } else { TAG_U32 }, false),
+                    "int_val",
+                )
+                .unwrap()
+        }
+        Type::Int(n) | Type::Uint(n) if *n <= 64 => {
+            let signed = matches!(ty, Type::Int(_));
+            let value = value.into_int_value();
+
+            let value = if signed {
+                bin.builder
+                    .build_int_s_extend_or_bit_cast(value, i64_type, "")
+                    .unwrap()
+            } else {
+                bin.builder
+                    .build_int_z_extend_or_bit_cast(value, i64_type, "")
+                    .unwrap()
+            };
+
+            let small = fits_in_body(bin, value, signed);
+
+            select_with(
+                bin,
+                function,
+                small,
+                || {
+                    small_val(
+                        bin,
+                        value,
+                        if signed { TAG_I64_SMALL } else { TAG_U64_SMALL },
+                    )
+                    .into()
+                },
+                || {
+                    let func = if signed {
+                        HostFunctions::ObjFromI64
+                    } else {
+                        HostFunctions::ObjFromU64
+                    };
+                    call(bin, func, &[value]).into()
+                },
+            )
+            .into_int_value()
+        }
+        Type::Int(n) | Type::Uint(n) if *n <= 128 => {
+            let signed = matches!(ty, Type::Int(_));
+            let i128_type = bin.context.custom_width_int_type(128);
+            let value = value.into_int_value();
+
+            let value = if signed {
+                bin.builder
+                    .build_int_s_extend_or_bit_cast(value, i128_type, "")
+                    .unwrap()
+            } else {
+                bin.builder
+                    .build_int_z_extend_or_bit_cast(value, i128_type, "")
+                    .unwrap()
+            };
+
+            let lo = bin
+                .builder
+                .build_int_truncate(value, i64_type, "lo")
+                .unwrap();
+
+            // the value is small when it fits in 64 bits, and then in the body
+            let extended = if signed {
+                bin.builder.build_int_s_extend(lo, i128_type, "").unwrap()
+            } else {
+                bin.builder.build_int_z_extend(lo, i128_type, "").unwrap()
+            };
+
+            let small = bin
+                .builder
+                .build_and(
+                    bin.builder
+                        .build_int_compare(IntPredicate::EQ, extended, value, "")
+                        .unwrap(),
+                    fits_in_body(bin, lo, signed),
+                    "small",
+                )
+                .unwrap();
+
+            select_with(
+                bin,
+                function,
+                small,
+                || {
+                    small_val(
+                        bin,
+                        lo,
+                        if signed {
+                            TAG_I128_SMALL
+                        } else {
+                            TAG_U128_SMALL
+                        },
+                    )
+                    .into()
+                },
+                || {
+                    let hi = bin
+                        .builder
+                        .build_right_shift(value, i128_type.const_int(64, false), signed, "")
+                        .unwrap();
+                    let hi = bin.builder.build_int_truncate(hi, i64_type, "hi").unwrap();
+
+                    let func = if signed {
+                        HostFunctions::ObjFromI128Pieces
+                    } else {
+                        HostFunctions::ObjFromU128Pieces
+                    };
+                    call(bin, func, &[hi, lo]).into()
+                },
+            )
+            .into_int_value()
+        }
+        Type::String | Type::DynamicBytes => {
+            let data = bin.vector_bytes(value);
+            let len = bin.vector_len(value);
+
+            let func = if *ty == Type::String {
+                HostFunctions::StringNewFromLinearMemory
+            } else {
+                HostFunctions::BytesNewFromLinearMemory
+            };
+
+            call(bin, func, &[pointer_val(bin, data), u32_val(bin, len)])
+        }
+        Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+            let elem_ty = ty.array_elem();
+            let len = bin.vector_len(value);
+
+            let size = bin
+                .builder
+                .build_int_mul(len, bin.context.i32_type().const_int(8, false), "size")
+                .unwrap();
+
+            let vals = bin
+                .builder
+                .build_call(
+                    bin.module.get_function("__malloc").unwrap(),
+                    &[size.into()],
+                    "vals",
+                )
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_pointer_value();
+
+            let mut vals_ptr = vals;
+
+            bin.emit_loop_cond_first_with_pointer(
+                function,
+                bin.context.i32_type().const_zero(),
+                len,
+                &mut vals_ptr,
+                |index, vals_ptr| {
+                    let elem = bin.array_subscript(ty, value.into_pointer_value(), index, ns);
+                    let elem = bin
+                        .builder
+                        .build_load(bin.llvm_field_ty(&elem_ty, ns), elem, "elem")
+                        .unwrap();
+
+                    let val = encode(bin, function, &elem_ty, elem, ns);
+
+                    bin.builder.build_store(*vals_ptr, val).unwrap();
+
+                    *vals_ptr = unsafe {
+                        bin.builder
+                            .build_gep(
+                                i64_type,
+                                *vals_ptr,
+                                &[bin.context.i32_type().const_int(1, false)],
+                                "next_val",
+                            )
+                            .unwrap()
+                    };
+                },
+            );
+
+            call(
+                bin,
+                HostFunctions::VecNewFromLinearMemory,
+                &[pointer_val(bin, vals), u32_val(bin, len)],
+            )
+        }
+        Type::Struct(_) | Type::Array(..) | Type::Mapping(..) => {
+            unreachable!(
+                "sema does not allow {} as a Soroban value",
+                ty.to_string(ns)
+            )
+        }
+        _ => {
+            // address, bytesN and integers wider than 128 bits are passed as bytes
+            let llvm_ty = bin.llvm_type(ty, ns);
+            let buf = bin.build_alloca(function, llvm_ty, "buf");
+            bin.builder.build_store(buf, value).unwrap();
+
+            let len = llvm_ty
+                .size_of()
+                .unwrap()
+                .const_cast(bin.context.i32_type(), false);
+
+            call(
+                bin,
+                HostFunctions::BytesNewFromLinearMemory,
+                &[pointer_val(bin, buf), u32_val(bin, len)],
+            )
+        }
+    }
+}
+
+/// Decode a Soroban value into a Solidity value of the given type. The host fails the
+/// invocation if an object has the wrong type.
+pub(super) fn decode<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    ty: &Type,
+    val: IntValue<'a>,
+    ns: &Namespace,
+) -> BasicValueEnum<'a> {
+    let i64_type = bin.context.i64_type();
+
+    match ty {
+        Type::Bool => bin
+            .builder
+            .build_int_compare(
+                IntPredicate::EQ,
+                val,
+                i64_type.const_int(TAG_TRUE, false),
+                "bool",
+            )
+            .unwrap()
+            .into(),
+        Type::Enum(_) => bin
+            .builder
+            .build_int_truncate(u32_from_val(bin, val), bin.context.i8_type(), "enum")
+            .unwrap()
+            .into(),
+        Type::Int(n) | Type::Uint(n) if *n <= 32 => {
+            let signed = matches!(ty, Type::Int(_));
+
+            let value = bin
+                .builder
+                .build_right_shift(val, i64_type.const_int(32, false), signed, "")
+                .unwrap();
+
+            bin.builder
+                .build_int_truncate(value, bin.context.custom_width_int_type(*n as u32), "int")
+                .unwrap()
+                .into()
+        }
+        Type::Int(n) | Type::Uint(n) if *n <= 64 => {
+            let signed = matches!(ty, Type::Int(_));
+
+            let small = bin
+                .builder
+                .build_int_compare(
+                    IntPredicate::EQ,
+                    tag(bin, val),
+                    i64_type.const_int(if signed { TAG_I64_SMALL } else { TAG_U64_SMALL }, false),
+                    "small",
+                )
+                .unwrap();
+
+            let value = select_with(
+                bin,
+                function,
+                small,
+                || {
+                    bin.builder
+                        .build_right_shift(val, i64_type.const_int(8, false), signed, "")
+                        .unwrap()
+                        .into()
+                },
+                || {
+                    let func = if signed {
+                        HostFunctions::ObjToI64
+                    } else {
+                        HostFunctions::ObjToU64
+                    };
+                    call(bin, func, &[val]).into()
+                },
+            )
+            .into_int_value();
+
+            bin.builder
+                .build_int_truncate_or_bit_cast(
+                    value,
+                    bin.context.custom_width_int_type(*n as u32),
+                    "int",
+                )
+                .unwrap()
+                .into()
+        }
+        Type::Int(n) | Type::Uint(n) if *n <= 128 => {
+            let signed = matches!(ty, Type::Int(_));
+            let i128_type = bin.context.custom_width_int_type(128);
+
+            let small = bin
+                .builder
+                .build_int_compare(
+                    IntPredicate::EQ,
+                    tag(bin, val),
+                    i64_type.const_int(
+                        if signed {
+                            TAG_I128_SMALL
+                        } else {
+                            TAG_U128_SMALL
+                        },
+                        false,
+                    ),
+                    "small",
+                )
+                .unwrap();
+
+            let value = select_with(
+                bin,
+                function,
+                small,
+                || {
+                    let value = bin
+                        .builder
+                        .build_right_shift(val, i64_type.const_int(8, false), signed, "")
+                        .unwrap();
+
+                    if signed {
+                        bin.builder.build_int_s_extend(value, i128_type, "")
+                    } else {
+                        bin.builder.build_int_z_extend(value, i128_type, "")
+                    }
+                    .unwrap()
+                    .into()
+                },
+                || {
+                    let (hi, lo) = if signed {
+                        (HostFunctions::ObjToI128Hi64, HostFunctions::ObjToI128Lo64)
+                    } else {
+                        (HostFunctions::ObjToU128Hi64, HostFunctions::ObjToU128Lo64)
+                    };
+
+                    let hi = bin
+                        .builder
+                        .build_int_z_extend(call(bin, hi, &[val]), i128_type, "hi")
+                        .unwrap();
+                    let lo = bin
+                        .builder
+                        .build_int_z_extend(call(bin, lo, &[val]), i128_type, "lo")
+                        .unwrap();
+
+                    let hi = bin
+                        .builder
+                        .build_left_shift(hi, i128_type.const_int(64, false), "")
+                        .unwrap();
+
+                    bin.builder.build_or(hi, lo, "").unwrap().into()
+                },
+            )
+            .into_int_value();
+
+            bin.builder
+                .build_int_truncate_or_bit_cast(
+                    value,
+                    bin.context.custom_width_int_type(*n as u32),
+                    "int",
+                )
+                .unwrap()
+                .into()
+        }
+        Type::String | Type::DynamicBytes => {
+            let (len_func, copy_func) = if *ty == Type::String {
+                (
+                    HostFunctions::StringLen,
+                    HostFunctions::StringCopyToLinearMemory,
+                )
+            } else {
+                (
+                    HostFunctions::BytesLen,
+                    HostFunctions::BytesCopyToLinearMemory,
+                )
+            };
+
+            let len = u32_from_val(bin, call(bin, len_func, &[val]));
+
+            let v = bin.vector_new(len, bin.context.i32_type().const_int(1, false), None);
+
+            call(
+                bin,
+                copy_func,
+                &[
+                    val,
+                    u32_val(bin, bin.context.i32_type().const_zero()),
+                    pointer_val(bin, bin.vector_bytes(v.into())),
+                    u32_val(bin, len),
+                ],
+            );
+
+            v.into()
+        }
+        Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+            let elem_ty = ty.array_elem();
+            let llvm_elem_ty = bin.llvm_field_ty(&elem_ty, ns);
+
+            let len = u32_from_val(bin, call(bin, HostFunctions::VecLen, &[val]));
+
+            let size = bin
+                .builder
+                .build_int_mul(len, bin.context.i32_type().const_int(8, false), "size")
+                .unwrap();
+
+            let vals = bin
+                .builder
+                .build_call(
+                    bin.module.get_function("__malloc").unwrap(),
+                    &[size.into()],
+                    "vals",
+                )
+                .unwrap()
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_pointer_value();
+
+            call(
+                bin,
+                HostFunctions::VecUnpackToLinearMemory,
+                &[val, pointer_val(bin, vals), u32_val(bin, len)],
+            );
+
+            let elem_size = llvm_elem_ty
+                .size_of()
+                .unwrap()
+                .const_cast(bin.context.i32_type(), false);
+
+            let v = bin.vector_new(len, elem_size, None);
+
+            let mut vals_ptr = vals;
+
+            bin.emit_loop_cond_first_with_pointer(
+                function,
+                bin.context.i32_type().const_zero(),
+                len,
+                &mut vals_ptr,
+                |index, vals_ptr| {
+                    let elem_val = bin
+                        .builder
+                        .build_load(i64_type, *vals_ptr, "elem_val")
+                        .unwrap()
+                        .into_int_value();
+
+                    let elem = decode(bin, function, &elem_ty, elem_val, ns);
+
+                    let elem_ptr = bin.array_subscript(ty, v, index, ns);
+
+                    bin.builder.build_store(elem_ptr, elem).unwrap();
+
+                    *vals_ptr = unsafe {
+                        bin.builder
+                            .build_gep(
+                                i64_type,
+                                *vals_ptr,
+                                &[bin.context.i32_type().const_int(1, false)],
+                                "next_val",
+                            )
+                            .unwrap()
+                    };
+                },
+            );
+
+            v.into()
+        }
+        Type::Struct(_) | Type::Array(..) | Type::Mapping(..) => {
+            unreachable!(
+                "sema does not allow {} as a Soroban value",
+                ty.to_string(ns)
+            )
+        }
+        _ => {
+            let llvm_ty = bin.llvm_type(ty, ns);
+            let buf = bin.build_alloca(function, llvm_ty, "buf");
+
+            let len = llvm_ty
+                .size_of()
+                .unwrap()
+                .const_cast(bin.context.i32_type(), false);
+
+            call(
+                bin,
+                HostFunctions::BytesCopyToLinearMemory,
+                &[
+                    val,
+                    u32_val(bin, bin.context.i32_type().const_zero()),
+                    pointer_val(bin, buf),
+                    u32_val(bin, len),
+                ],
+            );
+
+            bin.builder.build_load(llvm_ty, buf, "value").unwrap()
+        }
+    }
+}
+
+/// Does the value fit in the 56 bit body of a small value
+fn fits_in_body<'a>(bin: &Binary<'a>, value: IntValue<'a>, signed: bool) -> IntValue<'a> {
+    let i64_type = bin.context.i64_type();
+
+    let shifted = bin
+        .builder
+        .build_left_shift(value, i64_type.const_int(8, false), "")
+        .unwrap();
+
+    let back = bin
+        .builder
+        .build_right_shift(shifted, i64_type.const_int(8, false), signed, "")
+        .unwrap();
+
+    bin.builder
+        .build_int_compare(IntPredicate::EQ, back, value, "fits")
+        .unwrap()
+}
+
+fn small_val<'a>(bin: &Binary<'a>, value: IntValue<'a>, tag: u64) -> IntValue<'a> {
+    let i64_type = bin.context.i64_type();
+
+    let body = bin
+        .builder
+        .build_left_shift(value, i64_type.const_int(8, false), "")
+        .unwrap();
+
+    bin.builder
+        .build_or(body, i64_type.const_int(tag, false), "small_val")
+        .unwrap()
+}
@@ -6,8 +6,9 @@ use crate::emit::expression::expression;
 use crate::emit::{TargetRuntime, Variable};
 use crate::sema::ast::{FormatArg, Namespace, RetrieveType, StringLocation, Type};
 use crate::Target;
-use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
-use inkwell::IntPredicate;
+use inkwell::values::{
+    BasicMetadataValueEnum, BasicValueEnum, FunctionValue, IntValue, PointerValue,
+};
 use std::collections::HashMap;
 
 /// Implement "...{}...{}".format(a, b)
@@ -19,81 +20,52 @@ pub(super) fn format_string<'a, T: TargetRuntime<'a> + ?Sized>(
     function: FunctionValue<'a>,
     ns: &Namespace,
 ) -> BasicValueEnum<'a> {
-    // first we need to calculate the space we need
-    let mut length = bin.context.i32_type().const_zero();
+    // codegen merges the constant parts of the string, so a string without any arguments is
+    // a single segment of static data
+    if let [(FormatArg::StringLiteral, Expression::BytesLiteral { value, .. })] = args {
+        return bin
+            .vector_new(
+                bin.context.i32_type().const_int(value.len() as u64, false),
+                bin.context.i32_type().const_int(1, false),
+                Some(value),
+            )
+            .into();
+    }
+
+    // the maximum length of everything but strings and bytes is known at compile time, so
+    // only their lengths need to be added at runtime
+    let mut fixed_length = 0;
+    let mut dynamic_length = Vec::new();
 
     let mut evaluated_arg = Vec::new();
 
     evaluated_arg.resize(args.len(), None);
 
     for (i, (spec, arg)) in args.iter().enumerate() {
-        let len = if *spec == FormatArg::StringLiteral {
-            if let Expression::BytesLiteral { value, .. } = arg {
-                bin.context.i32_type().const_int(value.len() as u64, false)
-            } else {
-                unreachable!();
-            }
-        } else {
-            match arg.ty() {
-                // bool: "true" or "false"
-                Type::Bool => bin.context.i32_type().const_int(5, false),
-                // hex encode bytes
-                Type::Contract(_) | Type::Address(_) => {
-                    let len = if ns.target == Target::Solana && *spec != FormatArg::Hex {
-                        base58_size(ns.address_length)
-                    } else {
-                        2 * ns.address_length
-                    };
-                    bin.context.i32_type().const_int(len as u64, false)
-                }
-                Type::Bytes(size) => bin.context.i32_type().const_int(size as u64 * 2, false),
-                Type::String => {
-                    let val = expression(target, bin, arg, vartab, function, ns);
-
-                    evaluated_arg[i] = Some(val);
+        if let Some(len) = max_length(*spec, arg, ns) {
+            fixed_length += len;
+            continue;
+        }
 
-                    bin.vector_len(val)
-                }
-                Type::DynamicBytes => {
-                    let val = expression(target, bin, arg, vartab, function, ns);
+        let val = expression(target, bin, arg, vartab, function, ns);
 
-                    evaluated_arg[i] = Some(val);
+        evaluated_arg[i] = Some(val);
 
-                    // will be hex encoded, so double
-                    let len = bin.vector_len(val);
+        let len = bin.vector_len(val);
 
-                    bin.builder.build_int_add(len, len, "hex_len").unwrap()
-                }
-                Type::Uint(bits) if *spec == FormatArg::Hex => {
-                    bin.context.i32_type().const_int(bits as u64 / 4 + 2, false)
-                }
-                Type::Int(bits) if *spec == FormatArg::Hex => {
-                    bin.context.i32_type().const_int(bits as u64 / 4 + 3, false)
-                }
-                Type::Uint(bits) if *spec == FormatArg::Binary => {
-                    bin.context.i32_type().const_int(bits as u64 + 2, false)
-                }
-                Type::Int(bits) if *spec == FormatArg::Binary => {
-                    bin.context.i32_type().const_int(bits as u64 + 3, false)
-                }
-                // bits / 2 is a rough over-estimate of how many decimals we need
-                Type::Uint(bits) if *spec == FormatArg::Default => {
-                    bin.context.i32_type().const_int(bits as u64 / 2, false)
-                }
-                Type::Int(bits) if *spec == FormatArg::Default => {
-                    bin.context.i32_type().const_int(bits as u64 / 2 + 1, false)
-                }
-                Type::Enum(enum_no) => bin
-                    .context
-                    .i32_type()
-                    .const_int(ns.enums[enum_no].ty.bits(ns) as u64 / 3, false),
-                _ => unimplemented!("can't format this argument: {:?}", arg),
-            }
-        };
-
-        length = bin.builder.build_int_add(length, len, "").unwrap();
+        if arg.ty() == Type::String {
+            dynamic_length.push(len);
+        } else {
+            // will be hex encoded, so double
+            dynamic_length.push(bin.builder.build_int_add(len, len, "hex_len").unwrap());
+        }
     }
 
+    let length = dynamic_length.into_iter().fold(
+        bin.context.i32_type().const_int(fixed_length, false),
+        |length, len| bin.builder.build_int_add(length, len, "").unwrap(),
+    );
+
     // allocate the string and
     let vector = bin.vector_new(length, bin.context.i32_type().const_int(1, false), None);
 
@@ -275,307 +247,15 @@ pub(super) fn format_string<'a, T: TargetRuntime<'a> + ?Sized>(
                     };
                 }
                 Type::Enum(_) => {
-                    let val = bin
-                        .builder
-                        .build_int_z_extend(
-                            val.into_int_value(),
-                            bin.context.i64_type(),
-                            "val_64bits",
-                        )
-                        .unwrap();
-
-                    output = bin
-                        .builder
-                        .build_call(
-                            bin.module.get_function("uint2dec").unwrap(),
-                            &[output.into(), val.into()],
-                            "",
-                        )
-                        .unwrap()
-                        .try_as_basic_value()
-                        .left()
-                        .unwrap()
-                        .into_pointer_value();
+                    output = format_int(bin, function, output, val.into_int_value(), false, *spec);
                 }
-                Type::Uint(bits) => {
-                    if *spec == FormatArg::Default && bits <= 64 {
-                        let val = if bits == 64 {
-                            val.into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val.into_int_value(),
-                                    bin.context.i64_type(),
-                                    "val_64bits",
-                                )
-                                .unwrap()
-                        };
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint2dec").unwrap(),
-                                &[output.into(), val.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else if *spec == FormatArg::Default && bits <= 128 {
-                        let val = if bits == 128 {
-                            val.into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val.into_int_value(),
-                                    bin.context.custom_width_int_type(128),
-                                    "val_128bits",
-                                )
-                                .unwrap()
-                        };
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint128dec").unwrap(),
-                                &[output.into(), val.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else if *spec == FormatArg::Default {
-                        let val = if bits == 256 {
-                            val.into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val.into_int_value(),
-                                    bin.context.custom_width_int_type(256),
-                                    "val_256bits",
-                                )
-                                .unwrap()
-                        };
-
-                        let pval = bin.build_alloca(
-                            function,
-                            bin.context.custom_width_int_type(256),
-                            "int",
-                        );
-
-                        bin.builder.build_store(pval, val).unwrap();
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint256dec").unwrap(),
-                                &[output.into(), pval.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else {
-                        let buf = bin.build_alloca(function, bin.llvm_type(&arg_ty, ns), "uint");
-
-                        bin.builder.build_store(buf, val.into_int_value()).unwrap();
-
-                        let len = bin.context.i32_type().const_int(bits as u64 / 8, false);
-
-                        let func_name = if *spec == FormatArg::Hex {
-                            "uint2hex"
-                        } else {
-                            "uint2bin"
-                        };
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function(func_name).unwrap(),
-                                &[output.into(), buf.into(), len.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    }
+                Type::Uint(_) | Type::Value => {
+                    output = format_int(bin, function, output, val.into_int_value(), false, *spec);
                 }
-                Type::Int(bits) => {
-                    let val = val.into_int_value();
-
-                    let is_negative = bin
-                        .builder
-                        .build_int_compare(
-                            IntPredicate::SLT,
-                            val,
-                            val.get_type().const_zero(),
-                            "negative",
-                        )
-                        .unwrap();
-
-                    let entry = bin.builder.get_insert_block().unwrap();
-                    let positive = bin.context.append_basic_block(function, "int_positive");
-                    let negative = bin.context.append_basic_block(function, "int_negative");
-
-                    bin.builder
-                        .build_conditional_branch(is_negative, negative, positive)
-                        .unwrap();
-
-                    bin.builder.position_at_end(negative);
-
-                    // add "-" to output and negate our val
-                    bin.builder
-                        .build_store(output, bin.context.i8_type().const_int('-' as u64, false))
-                        .unwrap();
-
-                    let minus_len = bin.context.i32_type().const_int(1, false);
-
-                    let neg_data = unsafe {
-                        bin.builder
-                            .build_gep(bin.context.i8_type(), output, &[minus_len], "")
-                            .unwrap()
-                    };
-                    let neg_val = bin.builder.build_int_neg(val, "negative_int").unwrap();
-
-                    bin.builder.build_unconditional_branch(positive).unwrap();
-
-                    bin.builder.position_at_end(positive);
-
-                    let data_phi = bin.builder.build_phi(output.get_type(), "data").unwrap();
-                    let val_phi = bin.builder.build_phi(val.get_type(), "val").unwrap();
-
-                    data_phi.add_incoming(&[(&neg_data, negative), (&output, entry)]);
-                    val_phi.add_incoming(&[(&neg_val, negative), (&val, entry)]);
-
-                    if *spec == FormatArg::Default && bits <= 64 {
-                        let val = if bits == 64 {
-                            val_phi.as_basic_value().into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val_phi.as_basic_value().into_int_value(),
-                                    bin.context.i64_type(),
-                                    "val_64bits",
-                                )
-                                .unwrap()
-                        };
-
-                        let output_after_minus = data_phi.as_basic_value().into_pointer_value();
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint2dec").unwrap(),
-                                &[output_after_minus.into(), val.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else if *spec == FormatArg::Default && bits <= 128 {
-                        let val = if bits == 128 {
-                            val_phi.as_basic_value().into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val_phi.as_basic_value().into_int_value(),
-                                    bin.context.custom_width_int_type(128),
-                                    "val_128bits",
-                                )
-                                .unwrap()
-                        };
-
-                        let output_after_minus = data_phi.as_basic_value().into_pointer_value();
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint128dec").unwrap(),
-                                &[output_after_minus.into(), val.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else if *spec == FormatArg::Default {
-                        let val = if bits == 256 {
-                            val_phi.as_basic_value().into_int_value()
-                        } else {
-                            bin.builder
-                                .build_int_z_extend(
-                                    val_phi.as_basic_value().into_int_value(),
-                                    bin.context.custom_width_int_type(256),
-                                    "val_256bits",
-                                )
-                                .unwrap()
-                        };
-
-                        let pval = bin.build_alloca(
-                            function,
-                            bin.context.custom_width_int_type(256),
-                            "int",
-                        );
-
-                        bin.builder.build_store(pval, val).unwrap();
-
-                        let output_after_minus = data_phi.as_basic_value().into_pointer_value();
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function("uint256dec").unwrap(),
-                                &[output_after_minus.into(), pval.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    } else {
-                        let buf = bin.build_alloca(function, bin.llvm_type(&arg_ty, ns), "int");
-
-                        bin.builder
-                            .build_store(buf, val_phi.as_basic_value().into_int_value())
-                            .unwrap();
-
-                        let len = bin.context.i32_type().const_int(bits as u64 / 8, false);
-
-                        let func_name = if *spec == FormatArg::Hex {
-                            "uint2hex"
-                        } else {
-                            "uint2bin"
-                        };
-
-                        let output_after_minus = data_phi.as_basic_value().into_pointer_value();
-
-                        output = bin
-                            .builder
-                            .build_call(
-                                bin.module.get_function(func_name).unwrap(),
-                                &[output_after_minus.into(), buf.into(), len.into()],
-                                "",
-                            )
-                            .unwrap()
-                            .try_as_basic_value()
-                            .left()
-                            .unwrap()
-                            .into_pointer_value();
-                    }
+                Type::Int(_) => {
+                    output = format_int(bin, function, output, val.into_int_value(), true, *spec);
                 }
-                _ => unimplemented!(),
+                _ => unreachable!("sema does not allow formatting {}", arg_ty.to_string(ns)),
             }
         }
     }
@@ -613,6 +293,120 @@ pub(super) fn format_string<'a, T: TargetRuntime<'a> + ?Sized>(
     vector.into()
 }
 
+/// The maximum length of a formatted argument, if it does not depend on the value
+fn max_length(spec: FormatArg, arg: &Expression, ns: &Namespace) -> Option<u64> {
+    let len = if spec == FormatArg::StringLiteral {
+        if let Expression::BytesLiteral { value, .. } = arg {
+            value.len()
+        } else {
+            unreachable!();
+        }
+    } else {
+        let ty = match arg.ty() {
+            Type::Value => Type::Uint(ns.value_length as u16 * 8),
+            ty => ty,
+        };
+
+        match ty {
+            // bool: "true" or "false"
+            Type::Bool => 5,
+            // hex encode bytes
+            Type::Contract(_) | Type::Address(_) => {
+                if ns.target == Target::Solana && spec != FormatArg::Hex {
+                    base58_size(ns.address_length)
+                } else {
+                    2 * ns.address_length
+                }
+            }
+            Type::Bytes(size) => size as usize * 2,
+            Type::String | Type::DynamicBytes => return None,
+            Type::Uint(bits) if spec == FormatArg::Hex => bits as usize / 4 + 2,
+            Type::Int(bits) if spec == FormatArg::Hex => bits as usize / 4 + 3,
+            Type::Uint(bits) if spec == FormatArg::Binary => bits as usize + 2,
+            Type::Int(bits) if spec == FormatArg::Binary => bits as usize + 3,
+            // bits / 2 is a rough over-estimate of how many decimals we need
+            Type::Uint(bits) if spec == FormatArg::Default => bits as usize / 2,
+            Type::Int(bits) if spec == FormatArg::Default => bits as usize / 2 + 1,
+            Type::Enum(enum_no) => ns.enums[enum_no].ty.bits(ns) as usize / 3,
+            _ => unreachable!("sema does not allow formatting {}", ty.to_string(ns)),
+        }
+    };
+
+    Some(len as u64)
+}
+
+/// Format an integer by calling the formatting function for its size from stdlib/format.c, and
+/// return the end of the output. The signed variants write the minus sign, so no code for it is
+/// generated at each call site.
+fn format_int<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    output: PointerValue<'a>,
+    val: IntValue<'a>,
+    signed: bool,
+    spec: FormatArg,
+) -> PointerValue<'a> {
+    let bits = val.get_type().get_bit_width();
+    let prefix = if signed { "int" } else { "uint" };
+
+    let (name, args): (String, Vec<BasicMetadataValueEnum>) = if spec == FormatArg::Default {
+        let width = if bits <= 64 {
+            64
+        } else if bits <= 128 {
+            128
+        } else {
+            256
+        };
+
+        let val = if bits == width {
+            val
+        } else if signed {
+            bin.builder
+                .build_int_s_extend(val, bin.context.custom_width_int_type(width), "val_ext")
+                .unwrap()
+        } else {
+            bin.builder
+                .build_int_z_extend(val, bin.context.custom_width_int_type(width), "val_ext")
+                .unwrap()
+        };
+
+        match width {
+            64 => (format!("{prefix}2dec"), vec![output.into(), val.into()]),
+            128 => (format!("{prefix}128dec"), vec![output.into(), val.into()]),
+            _ => {
+                // 256 bit values are passed by reference
+                let pval = bin.build_alloca(function, val.get_type(), "int");
+
+                bin.builder.build_store(pval, val).unwrap();
+
+                (format!("{prefix}256dec"), vec![output.into(), pval.into()])
+            }
+        }
+    } else {
+        let buf = bin.build_alloca(function, val.get_type(), "int");
+
+        bin.builder.build_store(buf, val).unwrap();
+
+        let len = bin.context.i32_type().const_int(bits as u64 / 8, false);
+
+        let name = if spec == FormatArg::Hex {
+            format!("{prefix}2hex")
+        } else {
+            format!("{prefix}2bin")
+        };
+
+        (name, vec![output.into(), buf.into(), len.into()])
+    };
+
+    bin.builder
+        .build_call(bin.module.get_function(&name).unwrap(), &args, "")
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_pointer_value()
+}
+
 /// Load a string from expression or create global
 pub(super) fn string_location<'a, T: TargetRuntime<'a> + ?Sized>(
     target: &T,
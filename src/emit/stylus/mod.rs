@@ -0,0 +1,253 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::Options;
+use crate::sema::ast::{Contract, Namespace};
+use inkwell::context::Context;
+use inkwell::module::{Linkage, Module};
+use inkwell::values::{BasicMetadataValueEnum, FunctionValue, IntValue, PointerValue};
+use inkwell::AddressSpace;
+
+use crate::codegen::dispatch::polkadot::DispatchType;
+use crate::emit::functions::emit_functions;
+use crate::emit::{Binary, TargetRuntime};
+
+pub(super) mod target;
+
+// Buffer for return data and other host function results.
+const SCRATCH_SIZE: u32 = 32 * 1024;
+
+/// The Arbitrum Stylus target. Stylus programs are WebAssembly modules which export a
+/// `user_entrypoint` function and talk to the chain through the `vm_hooks` host functions.
+/// Stylus programs have no constructor; the program is activated after deployment.
+pub struct StylusTarget;
+
+impl StylusTarget {
+    pub fn build<'a>(
+        context: &'a Context,
+        std_lib: &Module<'a>,
+        contract: &'a Contract,
+        ns: &'a Namespace,
+        opt: &'a Options,
+    ) -> Binary<'a> {
+        let filename = ns.files[contract.loc.file_no()].file_name();
+        let mut binary = Binary::new(
+            context,
+            ns.target,
+            &contract.id.name,
+            filename.as_str(),
+            opt,
+            std_lib,
+            None,
+        );
+
+        let ptr = binary.context.i8_type().ptr_type(AddressSpace::default());
+
+        binary.vector_init_empty = binary
+            .context
+            .i32_type()
+            .const_all_ones()
+            .const_to_pointer(ptr);
+        binary.set_early_value_aborts(contract, ns);
+
+        let scratch_len = binary.module.add_global(
+            context.i32_type(),
+            Some(AddressSpace::default()),
+            "scratch_len",
+        );
+        scratch_len.set_linkage(Linkage::Internal);
+        scratch_len.set_initializer(&context.i32_type().get_undef());
+
+        binary.scratch_len = Some(scratch_len);
+
+        let scratch = binary.module.add_global(
+            context.i8_type().array_type(SCRATCH_SIZE),
+            Some(AddressSpace::default()),
+            "scratch",
+        );
+        scratch.set_linkage(Linkage::Internal);
+        scratch.set_initializer(&context.i8_type().array_type(SCRATCH_SIZE).get_undef());
+        binary.scratch = Some(scratch);
+
+        let mut target = StylusTarget;
+
+        target.declare_externals(&binary);
+
+        emit_functions(&mut target, &mut binary, contract, ns);
+
+        target.emit_entrypoint(&mut binary, ns);
+
+        binary.internalize(&[
+            "user_entrypoint",
+            "read_args",
+            "write_result",
+            "exit_early",
+            "storage_load_bytes32",
+            "storage_cache_bytes32",
+            "storage_flush_cache",
+            "msg_sender",
+            "msg_value",
+            "contract_address",
+            "account_balance",
+            "tx_origin",
+            "tx_gas_price",
+            "block_number",
+            "block_timestamp",
+            "block_basefee",
+            "block_coinbase",
+            "block_gas_limit",
+            "chainid",
+            "evm_gas_left",
+            "call_contract",
+            "delegate_call_contract",
+            "static_call_contract",
+            "read_return_data",
+            "emit_log",
+            "native_keccak256",
+        ]);
+
+        binary
+    }
+
+    /// Read the call arguments into a newly allocated buffer
+    fn entrypoint_prelude<'a>(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+    ) -> (PointerValue<'a>, IntValue<'a>) {
+        let entry = binary.context.append_basic_block(function, "entry");
+
+        binary.builder.position_at_end(entry);
+
+        // init our heap
+        binary
+            .builder
+            .build_call(binary.module.get_function("__init_heap").unwrap(), &[], "")
+            .unwrap();
+
+        let args_length = function.get_nth_param(0).unwrap().into_int_value();
+
+        let args = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__malloc").unwrap(),
+                &[args_length.into()],
+                "",
+            )
+            .unwrap()
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        binary
+            .builder
+            .build_call(
+                binary.module.get_function("read_args").unwrap(),
+                &[args.into()],
+                "",
+            )
+            .unwrap();
+
+        // store the length in case someone wants it via msg.data
+        binary
+            .builder
+            .build_store(binary.calldata_len.as_pointer_value(), args_length)
+            .unwrap();
+
+        (args, args_length)
+    }
+
+    fn declare_externals(&self, binary: &Binary) {
+        let ctx = binary.context;
+        let u8_ptr = ctx.i8_type().ptr_type(AddressSpace::default()).into();
+        let u32_val = ctx.i32_type().into();
+        let u32_ptr = ctx.i32_type().ptr_type(AddressSpace::default()).into();
+        let u64_val = ctx.i64_type().into();
+
+        macro_rules! external {
+            ($name:literal, $fn_type:ident, $( $args:expr ),*) => {
+                binary.module.add_function(
+                    $name,
+                    ctx.$fn_type().fn_type(&[$($args),*], false),
+                    Some(Linkage::External),
+                );
+            };
+        }
+
+        external!("read_args", void_type, u8_ptr);
+        external!("write_result", void_type, u8_ptr, u32_val);
+        external!("exit_early", void_type, u32_val);
+        external!("storage_load_bytes32", void_type, u8_ptr, u8_ptr);
+        external!("storage_cache_bytes32", void_type, u8_ptr, u8_ptr);
+        external!("storage_flush_cache", void_type, u32_val);
+        external!("msg_sender", void_type, u8_ptr);
+        external!("msg_value", void_type, u8_ptr);
+        external!("contract_address", void_type, u8_ptr);
+        external!("account_balance", void_type, u8_ptr, u8_ptr);
+        external!("tx_origin", void_type, u8_ptr);
+        external!("tx_gas_price", void_type, u8_ptr);
+        external!("block_number", i64_type,);
+        external!("block_timestamp", i64_type,);
+        external!("block_basefee", void_type, u8_ptr);
+        external!("block_coinbase", void_type, u8_ptr);
+        external!("block_gas_limit", i64_type,);
+        external!("chainid", i64_type,);
+        external!("evm_gas_left", i64_type,);
+        external!(
+            "call_contract",
+            i32_type,
+            u8_ptr,
+            u8_ptr,
+            u32_val,
+            u8_ptr,
+            u64_val,
+            u32_ptr
+        );
+        external!(
+            "delegate_call_contract",
+            i32_type,
+            u8_ptr,
+            u8_ptr,
+            u32_val,
+            u64_val,
+            u32_ptr
+        );
+        external!(
+            "static_call_contract",
+            i32_type,
+            u8_ptr,
+            u8_ptr,
+            u32_val,
+            u64_val,
+            u32_ptr
+        );
+        external!("read_return_data", i32_type, u8_ptr, u32_val, u32_val);
+        external!("emit_log", void_type, u8_ptr, u32_val, u32_val);
+        external!("native_keccak256", void_type, u8_ptr, u32_val, u8_ptr);
+    }
+
+    /// Emits the exported "user_entrypoint" function, which hands the call arguments to
+    /// the dispatcher. The dispatcher never returns; results are written with "write_result"
+    /// and execution ends with "exit_early".
+    fn emit_entrypoint(&mut self, bin: &mut Binary, ns: &Namespace) {
+        let ty = bin
+            .context
+            .i32_type()
+            .fn_type(&[bin.context.i32_type().into()], false);
+        let func = bin.module.add_function("user_entrypoint", ty, None);
+        let (input, input_length) = self.entrypoint_prelude(bin, func);
+        let args = vec![
+            BasicMetadataValueEnum::PointerValue(input),
+            BasicMetadataValueEnum::IntValue(input_length),
+            BasicMetadataValueEnum::IntValue(self.value_transferred(bin, ns)),
+            BasicMetadataValueEnum::PointerValue(bin.selector.as_pointer_value()),
+        ];
+        let dispatch_cfg_name = &DispatchType::Call.to_string();
+        let cfg = bin.module.get_function(dispatch_cfg_name).unwrap();
+        bin.builder
+            .build_call(cfg, &args, dispatch_cfg_name)
+            .unwrap();
+
+        bin.builder.build_unreachable().unwrap();
+    }
+}
@@ -0,0 +1,1106 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::cfg::HashTy;
+use crate::emit::binary::Binary;
+use crate::emit::expression::expression;
+use crate::emit::stylus::StylusTarget;
+use crate::emit::{ContractArgs, TargetRuntime, Variable};
+use crate::sema::ast;
+use crate::sema::ast::{Function, Namespace, Type};
+use crate::{codegen, emit_context};
+use inkwell::types::{BasicTypeEnum, IntType};
+use inkwell::values::{
+    ArrayValue, BasicMetadataValueEnum, BasicValue, BasicValueEnum, FunctionValue, IntValue,
+    PointerValue,
+};
+use inkwell::{AddressSpace, IntPredicate};
+use num_bigint::BigInt;
+use num_traits::One;
+use solang_parser::pt::Loc;
+use std::collections::HashMap;
+
+/// Address of the sha256 precompile
+const SHA256_PRECOMPILE: u8 = 2;
+
+impl StylusTarget {
+    /// Stylus storage keys are 32 byte big endian words
+    fn storage_key<'a>(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        slot: IntValue<'a>,
+    ) -> PointerValue<'a> {
+        emit_context!(binary);
+
+        let slot_ptr = binary.build_alloca(function, slot.get_type(), "slot");
+        binary.builder.build_store(slot_ptr, slot).unwrap();
+
+        let key = binary.build_array_alloca(
+            function,
+            binary.context.i8_type(),
+            i32_const!(32),
+            "storage_key",
+        );
+
+        call!(
+            "__leNtobeN",
+            &[slot_ptr.into(), key.into(), i32_const!(32).into()]
+        );
+
+        key
+    }
+
+    /// Read a big endian word written by a host function, and truncate it to the given type
+    fn load_word<'a>(
+        &self,
+        binary: &Binary<'a>,
+        word: PointerValue<'a>,
+        ty: IntType<'a>,
+    ) -> IntValue<'a> {
+        emit_context!(binary);
+
+        let word_ty = binary.context.custom_width_int_type(256);
+        let value = binary.builder.build_alloca(word_ty, "word").unwrap();
+
+        call!(
+            "__beNtoleN",
+            &[word.into(), value.into(), i32_const!(32).into()]
+        );
+
+        let value = binary
+            .builder
+            .build_load(word_ty, value, "word")
+            .unwrap()
+            .into_int_value();
+
+        if ty.get_bit_width() < 256 {
+            binary
+                .builder
+                .build_int_truncate(value, ty, "value")
+                .unwrap()
+        } else {
+            value
+        }
+    }
+
+    /// Call a host function which writes a 32 byte big endian value
+    fn host_word<'a>(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        name: &str,
+        ty: IntType<'a>,
+    ) -> IntValue<'a> {
+        emit_context!(binary);
+
+        let word = binary.build_array_alloca(
+            function,
+            binary.context.i8_type(),
+            i32_const!(32),
+            "host_word",
+        );
+
+        call!(name, &[word.into()]);
+
+        self.load_word(binary, word, ty)
+    }
+
+    /// Call a host function which writes an address
+    fn host_address<'a>(&self, binary: &Binary<'a>, name: &str, ns: &Namespace) -> ArrayValue<'a> {
+        emit_context!(binary);
+
+        let (scratch_buf, _) = scratch_buf!();
+
+        call!(name, &[scratch_buf.into()]);
+
+        binary
+            .builder
+            .build_load(binary.address_type(ns), scratch_buf, "address")
+            .unwrap()
+            .into_array_value()
+    }
+
+    /// Write any changed storage slots. This must happen before any other code
+    /// can observe our storage, and before returning successfully.
+    fn flush_storage_cache(&self, binary: &Binary) {
+        emit_context!(binary);
+
+        call!("storage_flush_cache", &[i32_zero!().into()]);
+    }
+}
+
+impl<'a> TargetRuntime<'a> for StylusTarget {
+    fn get_storage_int(
+        &self,
+        binary: &Binary<'a>,
+        _function: FunctionValue,
+        slot: PointerValue<'a>,
+        ty: IntType<'a>,
+    ) -> IntValue<'a> {
+        emit_context!(binary);
+
+        let word = binary
+            .builder
+            .build_array_alloca(binary.context.i8_type(), i32_const!(32), "storage_word")
+            .unwrap();
+
+        call!("storage_load_bytes32", &[slot.into(), word.into()]);
+
+        self.load_word(binary, word, ty)
+    }
+
+    /// Storage is a map of 32 byte words. Only value types, which fit into a single word,
+    /// are supported so far.
+    fn storage_load(
+        &self,
+        binary: &Binary<'a>,
+        ty: &Type,
+        slot: &mut IntValue<'a>,
+        function: FunctionValue<'a>,
+        ns: &Namespace,
+    ) -> BasicValueEnum<'a> {
+        emit_context!(binary);
+
+        let ty = ty.deref_any().clone().unwrap_user_type(ns);
+        let key = self.storage_key(binary, function, *slot);
+
+        let ret = match &ty {
+            Type::Address(_) | Type::Contract(_) => {
+                let word = binary.build_array_alloca(
+                    function,
+                    binary.context.i8_type(),
+                    i32_const!(32),
+                    "storage_word",
+                );
+
+                call!("storage_load_bytes32", &[key.into(), word.into()]);
+
+                // addresses are right aligned in the word
+                let address = unsafe {
+                    binary
+                        .builder
+                        .build_gep(
+                            binary.context.i8_type(),
+                            word,
+                            &[i32_const!(32 - ns.address_length as u64)],
+                            "address",
+                        )
+                        .unwrap()
+                };
+
+                binary
+                    .builder
+                    .build_load(binary.address_type(ns), address, "address")
+                    .unwrap()
+            }
+            Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Value => self
+                .get_storage_int(
+                    binary,
+                    function,
+                    key,
+                    binary.llvm_type(&ty, ns).into_int_type(),
+                )
+                .into(),
+            _ => unreachable!(
+                "sema does not allow storage of type {} on Stylus",
+                ty.to_string(ns)
+            ),
+        };
+
+        *slot = binary
+            .builder
+            .build_int_add(
+                *slot,
+                binary.number_literal(256, &BigInt::one(), ns),
+                "slot",
+            )
+            .unwrap();
+
+        ret
+    }
+
+    fn storage_store(
+        &self,
+        binary: &Binary<'a>,
+        ty: &Type,
+        _existing: bool,
+        slot: &mut IntValue<'a>,
+        dest: BasicValueEnum<'a>,
+        function: FunctionValue<'a>,
+        ns: &Namespace,
+    ) {
+        emit_context!(binary);
+
+        let ty = ty.deref_any().clone().unwrap_user_type(ns);
+        let key = self.storage_key(binary, function, *slot);
+
+        let word = binary.build_array_alloca(
+            function,
+            binary.context.i8_type(),
+            i32_const!(32),
+            "storage_word",
+        );
+
+        match &ty {
+            Type::Address(_) | Type::Contract(_) => {
+                call!("__bzero8", &[word.into(), i32_const!(4).into()]);
+
+                // addresses are right aligned in the word
+                let address = unsafe {
+                    binary
+                        .builder
+                        .build_gep(
+                            binary.context.i8_type(),
+                            word,
+                            &[i32_const!(32 - ns.address_length as u64)],
+                            "address",
+                        )
+                        .unwrap()
+                };
+
+                if dest.is_pointer_value() {
+                    call!(
+                        "__memcpy",
+                        &[
+                            address.into(),
+                            dest.into_pointer_value().into(),
+                            i32_const!(ns.address_length as u64).into(),
+                        ]
+                    );
+                } else {
+                    binary
+                        .builder
+                        .build_store(address, dest.into_array_value())
+                        .unwrap();
+                }
+            }
+            Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Value => {
+                let value = if dest.is_int_value() {
+                    dest.into_int_value()
+                } else {
+                    binary
+                        .builder
+                        .build_load(
+                            binary.llvm_type(&ty, ns),
+                            dest.into_pointer_value(),
+                            "value",
+                        )
+                        .unwrap()
+                        .into_int_value()
+                };
+
+                let word_ty = binary.context.custom_width_int_type(256);
+
+                let value = if value.get_type().get_bit_width() < 256 {
+                    if ty.is_signed_int(ns) {
+                        binary
+                            .builder
+                            .build_int_s_extend(value, word_ty, "value")
+                            .unwrap()
+                    } else {
+                        binary
+                            .builder
+                            .build_int_z_extend(value, word_ty, "value")
+                            .unwrap()
+                    }
+                } else {
+                    value
+                };
+
+                let value_ptr = binary.build_alloca(function, word_ty, "value");
+                binary.builder.build_store(value_ptr, value).unwrap();
+
+                call!(
+                    "__leNtobeN",
+                    &[value_ptr.into(), word.into(), i32_const!(32).into()]
+                );
+            }
+            _ => unreachable!(
+                "sema does not allow storage of type {} on Stylus",
+                ty.to_string(ns)
+            ),
+        }
+
+        call!("storage_cache_bytes32", &[key.into(), word.into()]);
+    }
+
+    fn storage_delete(
+        &self,
+        binary: &Binary<'a>,
+        ty: &Type,
+        slot: &mut IntValue<'a>,
+        function: FunctionValue<'a>,
+        ns: &Namespace,
+    ) {
+        emit_context!(binary);
+
+        match ty.deref_any().clone().unwrap_user_type(ns) {
+            Type::Mapping(..) => {
+                // nothing to do, step over it
+            }
+            Type::Address(_)
+            | Type::Contract(_)
+            | Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Value => {
+                let key = self.storage_key(binary, function, *slot);
+
+                let word = binary.build_array_alloca(
+                    function,
+                    binary.context.i8_type(),
+                    i32_const!(32),
+                    "storage_word",
+                );
+
+                call!("__bzero8", &[word.into(), i32_const!(4).into()]);
+
+                call!("storage_cache_bytes32", &[key.into(), word.into()]);
+            }
+            _ => unreachable!(
+                "sema does not allow storage of type {} on Stylus",
+                ty.to_string(ns)
+            ),
+        }
+    }
+
+    fn set_storage_string(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _slot: PointerValue<'a>,
+        _dest: BasicValueEnum<'a>,
+    ) {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn get_storage_string(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: PointerValue<'a>,
+    ) -> PointerValue<'a> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn set_storage_extfunc(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: PointerValue,
+        _dest: PointerValue,
+        _dest_ty: BasicTypeEnum,
+    ) {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn get_storage_extfunc(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: PointerValue<'a>,
+        _ns: &Namespace,
+    ) -> PointerValue<'a> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn get_storage_bytes_subscript(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: IntValue<'a>,
+        _index: IntValue<'a>,
+        _loc: Loc,
+        _ns: &Namespace,
+    ) -> IntValue<'a> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn set_storage_bytes_subscript(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: IntValue<'a>,
+        _index: IntValue<'a>,
+        _value: IntValue<'a>,
+        _ns: &Namespace,
+        _loc: Loc,
+    ) {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn storage_subscript(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _ty: &Type,
+        _slot: IntValue<'a>,
+        _index: BasicValueEnum<'a>,
+        _ns: &Namespace,
+    ) -> IntValue<'a> {
+        unreachable!("mappings and arrays are subscripted by slot on Stylus")
+    }
+
+    fn storage_push(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _ty: &Type,
+        _slot: IntValue<'a>,
+        _val: Option<BasicValueEnum<'a>>,
+        _ns: &Namespace,
+    ) -> BasicValueEnum<'a> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn storage_pop(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _ty: &Type,
+        _slot: IntValue<'a>,
+        _load: bool,
+        _ns: &Namespace,
+        _loc: Loc,
+    ) -> Option<BasicValueEnum<'a>> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    fn storage_array_length(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: IntValue<'a>,
+        _elem_ty: &Type,
+        _ns: &Namespace,
+    ) -> IntValue<'a> {
+        unreachable!("sema only allows value types in storage on Stylus")
+    }
+
+    /// Call the keccak256 host function
+    fn keccak256_hash(
+        &self,
+        binary: &Binary<'a>,
+        src: PointerValue,
+        length: IntValue,
+        dest: PointerValue,
+        _ns: &Namespace,
+    ) {
+        emit_context!(binary);
+
+        call!(
+            "native_keccak256",
+            &[src.into(), length.into(), dest.into()]
+        );
+    }
+
+    /// Stylus only provides debug printing on development nodes, so print is ignored
+    fn print(&self, _binary: &Binary, _string: PointerValue, _length: IntValue) {}
+
+    fn return_empty_abi(&self, binary: &Binary) {
+        emit_context!(binary);
+
+        self.flush_storage_cache(binary);
+
+        call!("exit_early", &[i32_zero!().into()]);
+
+        binary.builder.build_unreachable().unwrap();
+    }
+
+    fn return_code<'b>(&self, binary: &'b Binary, _ret: IntValue<'b>) {
+        emit_context!(binary);
+
+        // we can't return specific errors
+        self.assert_failure(binary, byte_ptr!().const_zero(), i32_zero!());
+    }
+
+    fn assert_failure(&self, binary: &Binary, data: PointerValue, length: IntValue) {
+        emit_context!(binary);
+
+        // A non-zero status reverts, which also discards the storage cache
+        call!("write_result", &[data.into(), length.into()]);
+        call!("exit_early", &[i32_const!(1).into()]);
+
+        binary.builder.build_unreachable().unwrap();
+    }
+
+    fn builtin_function(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _builtin_func: &Function,
+        _args: &[BasicMetadataValueEnum<'a>],
+        _first_arg_type: Option<BasicTypeEnum>,
+        _ns: &Namespace,
+    ) -> Option<BasicValueEnum<'a>> {
+        unreachable!("Stylus has no builtin functions or syscalls")
+    }
+
+    fn create_contract<'b>(
+        &mut self,
+        _binary: &Binary<'b>,
+        _function: FunctionValue<'b>,
+        _success: Option<&mut BasicValueEnum<'b>>,
+        _contract_no: usize,
+        _address: PointerValue<'b>,
+        _encoded_args: BasicValueEnum<'b>,
+        _encoded_args_len: BasicValueEnum<'b>,
+        _contract_args: ContractArgs<'b>,
+        _ns: &Namespace,
+        _loc: Loc,
+    ) {
+        unreachable!("sema does not allow creating contracts on Stylus")
+    }
+
+    /// Call external binary
+    fn external_call<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue<'b>,
+        success: Option<&mut BasicValueEnum<'b>>,
+        payload: PointerValue<'b>,
+        payload_len: IntValue<'b>,
+        address: Option<PointerValue<'b>>,
+        contract_args: ContractArgs<'b>,
+        call_type: ast::CallTy,
+        ns: &Namespace,
+        _loc: Loc,
+    ) {
+        emit_context!(binary);
+
+        // The callee may read our storage, or call us back
+        self.flush_storage_cache(binary);
+
+        let (_, scratch_len) = scratch_buf!();
+        let address = address.unwrap();
+        let gas = contract_args.gas.unwrap();
+
+        let status = match call_type {
+            ast::CallTy::Regular => {
+                let value = contract_args
+                    .value
+                    .unwrap_or_else(|| binary.value_type(ns).const_zero());
+                let value_ptr = binary.build_alloca(function, binary.value_type(ns), "value");
+                binary.builder.build_store(value_ptr, value).unwrap();
+
+                let value_be = binary.build_array_alloca(
+                    function,
+                    binary.context.i8_type(),
+                    i32_const!(ns.value_length as u64),
+                    "value_be",
+                );
+
+                call!(
+                    "__leNtobeN",
+                    &[
+                        value_ptr.into(),
+                        value_be.into(),
+                        i32_const!(ns.value_length as u64).into()
+                    ]
+                );
+
+                call!(
+                    "call_contract",
+                    &[
+                        address.into(),
+                        payload.into(),
+                        payload_len.into(),
+                        value_be.into(),
+                        gas.into(),
+                        scratch_len.into(),
+                    ]
+                )
+            }
+            ast::CallTy::Delegate => call!(
+                "delegate_call_contract",
+                &[
+                    address.into(),
+                    payload.into(),
+                    payload_len.into(),
+                    gas.into(),
+                    scratch_len.into(),
+                ]
+            ),
+            ast::CallTy::Static => call!(
+                "static_call_contract",
+                &[
+                    address.into(),
+                    payload.into(),
+                    payload_len.into(),
+                    gas.into(),
+                    scratch_len.into(),
+                ]
+            ),
+        }
+        .try_as_basic_value()
+        .left()
+        .unwrap()
+        .into_int_value();
+
+        if let Some(success) = success {
+            *success = status.as_basic_value_enum();
+            return;
+        }
+
+        // No status was requested, so pass on the revert reason of the callee
+        let is_success = binary
+            .builder
+            .build_int_compare(IntPredicate::EQ, status, i32_zero!(), "success")
+            .unwrap();
+
+        let success_block = binary.context.append_basic_block(function, "success");
+        let bail_block = binary.context.append_basic_block(function, "bail");
+
+        binary
+            .builder
+            .build_conditional_branch(is_success, success_block, bail_block)
+            .unwrap();
+
+        binary.builder.position_at_end(bail_block);
+
+        let return_data = self.return_data(binary, function);
+        self.assert_failure(
+            binary,
+            binary.vector_bytes(return_data.into()),
+            binary.vector_len(return_data.into()),
+        );
+
+        binary.builder.position_at_end(success_block);
+    }
+
+    fn value_transfer<'b>(
+        &self,
+        _binary: &Binary<'b>,
+        _function: FunctionValue,
+        _success: Option<&mut BasicValueEnum<'b>>,
+        _address: PointerValue<'b>,
+        _value: IntValue<'b>,
+        _ns: &Namespace,
+        _loc: Loc,
+    ) {
+        unreachable!("value is transferred with an external call on Stylus")
+    }
+
+    /// builtin expressions
+    fn builtin<'b>(
+        &self,
+        binary: &Binary<'b>,
+        expr: &codegen::Expression,
+        vartab: &HashMap<usize, Variable<'b>>,
+        function: FunctionValue<'b>,
+        ns: &Namespace,
+    ) -> BasicValueEnum<'b> {
+        emit_context!(binary);
+
+        match expr {
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Calldata,
+                ..
+            } => {
+                let calldata_len = binary
+                    .builder
+                    .build_load(
+                        binary.context.i32_type(),
+                        binary.calldata_len.as_pointer_value(),
+                        "calldata_len",
+                    )
+                    .unwrap();
+
+                let v = call!(
+                    "vector_new",
+                    &[
+                        calldata_len.into(),
+                        i32_const!(1).into(),
+                        binary.vector_init_empty.into(),
+                    ]
+                )
+                .try_as_basic_value()
+                .left()
+                .unwrap();
+
+                call!("read_args", &[binary.vector_bytes(v).into()]);
+
+                v
+            }
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::BlockNumber,
+                ..
+            } => call!("block_number", &[], "block_number")
+                .try_as_basic_value()
+                .left()
+                .unwrap(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Timestamp,
+                ..
+            } => call!("block_timestamp", &[], "timestamp")
+                .try_as_basic_value()
+                .left()
+                .unwrap(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Gasleft,
+                ..
+            } => call!("evm_gas_left", &[], "gas_left")
+                .try_as_basic_value()
+                .left()
+                .unwrap(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::GasLimit,
+                ..
+            } => call!("block_gas_limit", &[], "gas_limit")
+                .try_as_basic_value()
+                .left()
+                .unwrap(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::ChainId,
+                ..
+            } => {
+                let chain_id = call!("chainid", &[], "chainid")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap()
+                    .into_int_value();
+
+                binary
+                    .builder
+                    .build_int_z_extend(
+                        chain_id,
+                        binary.context.custom_width_int_type(256),
+                        "chainid",
+                    )
+                    .unwrap()
+                    .into()
+            }
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Gasprice,
+                ..
+            } => self
+                .host_word(binary, function, "tx_gas_price", binary.value_type(ns))
+                .into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::BaseFee,
+                ..
+            } => self
+                .host_word(
+                    binary,
+                    function,
+                    "block_basefee",
+                    binary.context.custom_width_int_type(256),
+                )
+                .into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Sender,
+                ..
+            } => self.host_address(binary, "msg_sender", ns).into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Origin,
+                ..
+            } => self.host_address(binary, "tx_origin", ns).into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::BlockCoinbase,
+                ..
+            } => self.host_address(binary, "block_coinbase", ns).into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Value,
+                ..
+            } => self.value_transferred(binary, ns).into(),
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::GetAddress,
+                ..
+            } => {
+                let (scratch_buf, _) = scratch_buf!();
+
+                call!("contract_address", &[scratch_buf.into()], "address");
+
+                // As on Polkadot, the scratch buffer gets overwritten by many host functions,
+                // so the address should be loaded before it is used.
+                scratch_buf.as_basic_value_enum()
+            }
+            codegen::Expression::Builtin {
+                kind: codegen::Builtin::Balance,
+                args,
+                ..
+            } => {
+                let address = expression(self, binary, &args[0], vartab, function, ns);
+
+                let address_ptr = if address.is_pointer_value() {
+                    address.into_pointer_value()
+                } else {
+                    let address_ptr =
+                        binary.build_alloca(function, binary.address_type(ns), "address");
+                    binary.builder.build_store(address_ptr, address).unwrap();
+                    address_ptr
+                };
+
+                let balance = binary.build_array_alloca(
+                    function,
+                    binary.context.i8_type(),
+                    i32_const!(32),
+                    "balance",
+                );
+
+                call!("account_balance", &[address_ptr.into(), balance.into()]);
+
+                self.load_word(binary, balance, binary.value_type(ns))
+                    .into()
+            }
+            _ => unreachable!("{:?}", expr),
+        }
+    }
+
+    /// The return data of the last external call
+    fn return_data<'b>(&self, binary: &Binary<'b>, _function: FunctionValue) -> PointerValue<'b> {
+        emit_context!(binary);
+
+        let (_, scratch_len) = scratch_buf!();
+        let length = binary
+            .builder
+            .build_load(binary.context.i32_type(), scratch_len, "return_data_len")
+            .unwrap()
+            .into_int_value();
+
+        let v = call!(
+            "vector_new",
+            &[
+                length.into(),
+                i32_const!(1).into(),
+                binary.vector_init_empty.into(),
+            ]
+        )
+        .try_as_basic_value()
+        .left()
+        .unwrap();
+
+        call!(
+            "read_return_data",
+            &[
+                binary.vector_bytes(v).into(),
+                i32_zero!().into(),
+                length.into()
+            ]
+        );
+
+        v.into_pointer_value()
+    }
+
+    /// Stylus value is 256 bits
+    fn value_transferred<'b>(&self, binary: &Binary<'b>, ns: &Namespace) -> IntValue<'b> {
+        emit_context!(binary);
+
+        let (scratch_buf, _) = scratch_buf!();
+
+        call!("msg_value", &[scratch_buf.into()], "value_transferred");
+
+        let value = binary
+            .builder
+            .build_alloca(binary.value_type(ns), "value")
+            .unwrap();
+
+        call!(
+            "__beNtoleN",
+            &[
+                scratch_buf.into(),
+                value.into(),
+                i32_const!(ns.value_length as u64).into()
+            ]
+        );
+
+        binary
+            .builder
+            .build_load(binary.value_type(ns), value, "value_transferred")
+            .unwrap()
+            .into_int_value()
+    }
+
+    fn selfdestruct<'b>(&self, _binary: &Binary<'b>, _addr: ArrayValue<'b>, _ns: &Namespace) {
+        unreachable!("sema does not allow selfdestruct on Stylus")
+    }
+
+    /// Crypto Hash
+    fn hash<'b>(
+        &self,
+        binary: &Binary<'b>,
+        function: FunctionValue<'b>,
+        hash: HashTy,
+        input: PointerValue<'b>,
+        input_len: IntValue<'b>,
+        ns: &Namespace,
+    ) -> IntValue<'b> {
+        emit_context!(binary);
+
+        let hashlen = match hash {
+            HashTy::Ripemd160 => 20,
+            _ => 32,
+        };
+
+        let res = binary.build_array_alloca(
+            function,
+            binary.context.i8_type(),
+            i32_const!(hashlen),
+            "res",
+        );
+
+        match hash {
+            HashTy::Keccak256 => {
+                call!(
+                    "native_keccak256",
+                    &[input.into(), input_len.into(), res.into()],
+                    "hash"
+                );
+            }
+            HashTy::Ripemd160 => {
+                call!(
+                    "ripemd160",
+                    &[input.into(), input_len.into(), res.into()],
+                    "hash"
+                );
+            }
+            HashTy::Sha256 => {
+                // sha256 is provided by the precompile at address 2
+                let precompile = binary.build_alloca(function, binary.address_type(ns), "sha256");
+                let mut address = vec![0u8; ns.address_length];
+                *address.last_mut().unwrap() = SHA256_PRECOMPILE;
+                binary
+                    .builder
+                    .build_store(precompile, binary.context.const_string(&address, false))
+                    .unwrap();
+
+                let gas = call!("evm_gas_left", &[], "gas_left")
+                    .try_as_basic_value()
+                    .left()
+                    .unwrap();
+
+                let (_, scratch_len) = scratch_buf!();
+
+                call!(
+                    "static_call_contract",
+                    &[
+                        precompile.into(),
+                        input.into(),
+                        input_len.into(),
+                        gas.into(),
+                        scratch_len.into(),
+                    ]
+                );
+
+                call!(
+                    "read_return_data",
+                    &[res.into(), i32_zero!().into(), i32_const!(hashlen).into()]
+                );
+            }
+            HashTy::Blake2_128 | HashTy::Blake2_256 => {
+                unreachable!("blake2 is not available on Stylus")
+            }
+        }
+
+        // bytes32 needs to reverse bytes
+        let temp = binary
+            .builder
+            .build_alloca(binary.llvm_type(&Type::Bytes(hashlen as u8), ns), "hash")
+            .unwrap();
+
+        call!(
+            "__beNtoleN",
+            &[res.into(), temp.into(), i32_const!(hashlen).into()]
+        );
+
+        binary
+            .builder
+            .build_load(
+                binary.llvm_type(&Type::Bytes(hashlen as u8), ns),
+                temp,
+                "hash",
+            )
+            .unwrap()
+            .into_int_value()
+    }
+
+    /// Emit event. The log data passed to the host starts with the topics.
+    fn emit_event<'b>(
+        &self,
+        binary: &Binary<'b>,
+        _function: FunctionValue<'b>,
+        data: BasicValueEnum<'b>,
+        topics: &[BasicValueEnum<'b>],
+    ) {
+        emit_context!(binary);
+
+        let topics_len = i32_const!(32 * topics.len() as u64);
+        let data_len = binary.vector_len(data);
+        let log_len = binary
+            .builder
+            .build_int_add(topics_len, data_len, "log_len")
+            .unwrap();
+
+        let log = call!("__malloc", &[log_len.into()])
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        let mut dest = log;
+
+        for topic in topics.iter() {
+            call!(
+                "__memcpy",
+                &[
+                    dest.into(),
+                    binary.vector_bytes(*topic).into(),
+                    i32_const!(32).into(),
+                ]
+            );
+
+            dest = unsafe {
+                binary
+                    .builder
+                    .build_gep(binary.context.i8_type(), dest, &[i32_const!(32)], "dest")
+                    .unwrap()
+            };
+        }
+
+        call!(
+            "__memcpy",
+            &[
+                dest.into(),
+                binary.vector_bytes(data).into(),
+                data_len.into(),
+            ]
+        );
+
+        call!(
+            "emit_log",
+            &[
+                log.into(),
+                log_len.into(),
+                i32_const!(topics.len() as u64).into()
+            ]
+        );
+    }
+
+    fn return_abi_data<'b>(
+        &self,
+        binary: &Binary<'b>,
+        data: PointerValue<'b>,
+        data_len: BasicValueEnum<'b>,
+    ) {
+        emit_context!(binary);
+
+        self.flush_storage_cache(binary);
+
+        call!("write_result", &[data.into(), data_len.into()]);
+        call!("exit_early", &[i32_zero!().into()]);
+
+        binary.builder.build_unreachable().unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::emit::binary::Binary;
+use crate::sema::ast::{Function, Namespace, Syscall, Type};
+use crate::Target;
+use inkwell::attributes::AttributeLoc;
+use inkwell::module::Linkage;
+use inkwell::types::{BasicMetadataTypeEnum, BasicType};
+use inkwell::values::{BasicMetadataValueEnum, BasicValueEnum, FunctionValue};
+use inkwell::AddressSpace;
+
+/// Call a function declared with the `@syscall` annotation. The arguments are passed as they are,
+/// except for addresses which are passed by pointer, and bytes which are passed as a pointer and
+/// a length. The return value, if any, is returned.
+pub(super) fn syscall<'a>(
+    bin: &Binary<'a>,
+    function: FunctionValue<'a>,
+    callee: &Function,
+    args: &[BasicValueEnum<'a>],
+    ns: &Namespace,
+) -> Option<BasicValueEnum<'a>> {
+    let (_, syscall) = callee.syscall.as_ref().expect("function is not a syscall");
+
+    let ptr_type = bin.context.i8_type().ptr_type(AddressSpace::default());
+    let len_type = if ns.target == Target::Solana {
+        bin.context.i64_type()
+    } else {
+        bin.context.i32_type()
+    };
+
+    let mut param_types: Vec<BasicMetadataTypeEnum> = Vec::new();
+    let mut parms: Vec<BasicMetadataValueEnum> = Vec::new();
+
+    for (param, arg) in callee.params.iter().zip(args) {
+        match &param.ty {
+            Type::Address(_) => {
+                let address = bin.build_alloca(function, bin.address_type(ns), "address");
+
+                bin.builder.build_store(address, *arg).unwrap();
+
+                param_types.push(ptr_type.into());
+                parms.push(address.into());
+            }
+            Type::DynamicBytes => {
+                let len = bin
+                    .builder
+                    .build_int_z_extend_or_bit_cast(bin.vector_len(*arg), len_type, "len")
+                    .unwrap();
+
+                param_types.push(ptr_type.into());
+                param_types.push(len_type.into());
+                parms.push(bin.vector_bytes(*arg).into());
+                parms.push(len.into());
+            }
+            ty => {
+                param_types.push(bin.llvm_type(ty, ns).into());
+                parms.push((*arg).into());
+            }
+        }
+    }
+
+    let fn_type = match callee.returns.first() {
+        Some(ret) => bin.llvm_type(&ret.ty, ns).fn_type(&param_types, false),
+        None => bin.context.void_type().fn_type(&param_types, false),
+    };
+
+    let callable = match syscall {
+        Syscall::Name { module, name } => {
+            // the linker imports the function from the module given by these attributes
+            let symbol = match module {
+                Some(module) => format!("{module}.{name}"),
+                None => name.to_owned(),
+            };
+
+            let func = bin.module.get_function(&symbol).unwrap_or_else(|| {
+                let func = bin
+                    .module
+                    .add_function(&symbol, fn_type, Some(Linkage::External));
+
+                if let Some(module) = module {
+                    func.add_attribute(
+                        AttributeLoc::Function,
+                        bin.context
+                            .create_string_attribute("wasm-import-module", module),
+                    );
+                    func.add_attribute(
+                        AttributeLoc::Function,
+                        bin.context
+                            .create_string_attribute("wasm-import-name", name),
+                    );
+                }
+
+                func
+            });
+
+            func.as_global_value().as_pointer_value()
+        }
+        Syscall::Number(number) => {
+            // Solana resolves the syscall by the murmur3 hash of its name, which is the address
+            // the program calls
+            bin.context
+                .i64_type()
+                .const_int(*number as u64, false)
+                .const_to_pointer(fn_type.ptr_type(AddressSpace::default()))
+        }
+    };
+
+    bin.builder
+        .build_indirect_call(fn_type, callable, &parms, "")
+        .unwrap()
+        .try_as_basic_value()
+        .left()
+}
@@ -6,10 +6,11 @@ use normalize_path::NormalizePath;
 use solang_parser::pt::Loc;
 use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
 
 #[derive(Default)]
 pub struct FileResolver {
@@ -19,6 +20,102 @@ pub struct FileResolver {
     cached_paths: HashMap<PathBuf, usize>,
     /// The actual file contents
     files: Vec<ResolvedFile>,
+    /// Files read by earlier compilations, if the resolver is part of a session
+    sources: Option<Arc<SourceCache>>,
+}
+
+/// The contents of the source files which have been read, shared by the file resolvers of a
+/// session. A file is read again if its modification time or its length has changed since it was
+/// read.
+#[derive(Default)]
+pub struct SourceCache {
+    files: RwLock<HashMap<PathBuf, CachedSource>>,
+}
+
+/// File systems store the modification time with a limited granularity, as coarse as two seconds
+/// on FAT. A file which is modified within this time of being read may keep the same
+/// modification time.
+const MODIFIED_GRANULARITY: Duration = Duration::from_secs(2);
+
+struct CachedSource {
+    modified: SystemTime,
+    len: u64,
+    /// When the file was read
+    read: SystemTime,
+    contents: Arc<str>,
+}
+
+impl CachedSource {
+    /// The file may have been changed again without its modification time or length changing,
+    /// if it was modified shortly before it was read
+    fn racy(&self) -> bool {
+        self.read
+            .duration_since(self.modified)
+            .map_or(true, |age| age < MODIFIED_GRANULARITY)
+    }
+}
+
+impl SourceCache {
+    /// Read a file, or return the contents read earlier if the file has not been modified since.
+    /// The modification time may not change when a file is written twice in quick succession, so
+    /// the length of the file is compared too, and a file which was read shortly after it was
+    /// modified is always read again.
+    pub fn read(&self, path: &Path) -> Result<Arc<str>, String> {
+        let stat = fs::metadata(path)
+            .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+            .ok();
+
+        if let Some((modified, len)) = stat {
+            if let Some(cached) = self.files.read().unwrap().get(path) {
+                if cached.modified == modified && cached.len == len && !cached.racy() {
+                    return Ok(cached.contents.clone());
+                }
+            }
+        }
+
+        let read = SystemTime::now();
+        let contents: Arc<str> = Arc::from(read_file(path)?);
+
+        // without a modification time, we cannot tell if the file has changed
+        if let Some((modified, len)) = stat {
+            self.files.write().unwrap().insert(
+                path.to_path_buf(),
+                CachedSource {
+                    modified,
+                    len,
+                    read,
+                    contents: contents.clone(),
+                },
+            );
+        }
+
+        Ok(contents)
+    }
+
+    /// Forget all the files which have been read
+    pub fn clear(&self) {
+        self.files.write().unwrap().clear();
+    }
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    let mut f = match File::open(path) {
+        Err(err_info) => {
+            return Err(format!(
+                "cannot open file '{}': {}",
+                path.display(),
+                err_info
+            ));
+        }
+        Ok(file) => file,
+    };
+
+    let mut contents = String::new();
+    if let Err(e) = f.read_to_string(&mut contents) {
+        return Err(format!("failed to read file '{}': {}", path.display(), e));
+    }
+
+    Ok(contents)
 }
 
 /// When we resolve a file, we need to know its base compared to the import so
@@ -38,6 +135,14 @@ pub struct ResolvedFile {
 }
 
 impl FileResolver {
+    /// Create a file resolver which reads files through the given cache
+    pub fn with_sources(sources: Arc<SourceCache>) -> Self {
+        FileResolver {
+            sources: Some(sources),
+            ..Default::default()
+        }
+    }
+
     /// Add import path
     pub fn add_import_path(&mut self, path: &Path) {
         assert!(!self.import_paths.contains(&(None, path.to_path_buf())));
@@ -144,30 +249,18 @@ impl FileResolver {
             }
         }
 
-        // read the file
-        let mut f = match File::open(path) {
-            Err(err_info) => {
-                return Err(format!(
-                    "cannot open file '{}': {}",
-                    path.display(),
-                    err_info
-                ));
-            }
-            Ok(file) => file,
+        let contents = match &self.sources {
+            Some(sources) => sources.read(path)?,
+            None => Arc::from(read_file(path)?),
         };
 
-        let mut contents = String::new();
-        if let Err(e) = f.read_to_string(&mut contents) {
-            return Err(format!("failed to read file '{}': {}", path.display(), e));
-        }
-
         let pos = self.files.len();
 
         self.files.push(ResolvedFile {
             path: filename.into(),
             full_path: path.to_path_buf(),
             import_no,
-            contents: Arc::from(contents),
+            contents,
         });
 
         self.cached_paths.insert(path.to_path_buf(), pos);
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Flatten a Solidity source file and everything it imports into a single source file, for block
+//! explorers which can only verify a contract from one file.
+//!
+//! Each file comes after the files it imports, in the order of the imports, so the output is the
+//! same on every run. The import directives are removed. The `SPDX-License-Identifier` comments
+//! are combined into one at the top, and the pragmas are listed once at the top, in the order they
+//! are first seen. Imports which give a symbol or the file another name cannot be flattened,
+//! since the names would no longer resolve.
+
+use crate::build_info::source_path;
+use crate::file_resolver::FileResolver;
+use crate::sema::ast::Namespace;
+use crate::sema::file::PathDisplay;
+use solang_parser::pt::{self, CodeLocation};
+use std::ops::Range;
+
+const LICENSE: &str = "SPDX-License-Identifier:";
+
+/// Flatten the top file of the namespace and the files it imports. The namespace should not have
+/// any errors.
+pub fn flatten(ns: &Namespace, resolver: &FileResolver) -> Result<String, String> {
+    let mut order = Vec::new();
+    let mut visited = vec![false; ns.files.len()];
+
+    imports_first(ns, ns.top_file_no(), &mut visited, &mut order);
+
+    let top_dir = ns.files[ns.top_file_no()].path.parent();
+    let mut licenses: Vec<String> = Vec::new();
+    let mut pragmas: Vec<String> = Vec::new();
+    let mut bodies = Vec::new();
+
+    for file_no in order {
+        let file = &ns.files[file_no];
+
+        // builtin files have no source
+        let Some(contents) = file
+            .cache_no
+            .and_then(|cache_no| resolver.get_contents_of_file_no(cache_no))
+        else {
+            continue;
+        };
+
+        let (unit, comments) = solang_parser::parse(&contents, file_no)
+            .map_err(|_| format!("cannot parse '{file}'"))?;
+
+        let mut removed = Vec::new();
+
+        for part in &unit.0 {
+            match part {
+                pt::SourceUnitPart::ImportDirective(import) => {
+                    let aliased = match import {
+                        pt::Import::Plain(..) => false,
+                        pt::Import::GlobalSymbol(..) => true,
+                        pt::Import::Rename(_, symbols, _) => symbols.iter().any(|(id, alias)| {
+                            alias.as_ref().is_some_and(|alias| alias.name != id.name)
+                        }),
+                    };
+
+                    if aliased {
+                        return Err(format!(
+                            "{}: import with an alias cannot be flattened",
+                            ns.loc_to_string(PathDisplay::FullPath, &import.loc())
+                        ));
+                    }
+
+                    removed.push(directive(&contents, &import.loc()));
+                }
+                pt::SourceUnitPart::PragmaDirective(pragma) => {
+                    let range = directive(&contents, &pragma.loc());
+
+                    // the same pragma may be written with different spacing
+                    let text = contents[range.clone()]
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .replace(" ;", ";");
+
+                    if !pragmas.contains(&text) {
+                        pragmas.push(text);
+                    }
+
+                    removed.push(range);
+                }
+                _ => (),
+            }
+        }
+
+        for comment in &comments {
+            let (pt::Comment::Line(loc, text) | pt::Comment::Block(loc, text)) = comment else {
+                continue;
+            };
+
+            if let Some(pos) = text.find(LICENSE) {
+                let license = text[pos + LICENSE.len()..]
+                    .trim_end_matches("*/")
+                    .trim()
+                    .to_string();
+
+                if !license.is_empty() && !licenses.contains(&license) {
+                    licenses.push(license);
+                }
+
+                removed.push(whole_lines(&contents, loc.start()..loc.end()));
+            }
+        }
+
+        removed.sort_by_key(|range| range.start);
+
+        let mut body = String::new();
+        let mut offset = 0;
+
+        for range in removed {
+            body.push_str(&contents[offset..range.start.max(offset)]);
+            offset = offset.max(range.end);
+        }
+
+        body.push_str(&contents[offset..]);
+
+        let path = source_path(&file.path, file.import_no, top_dir, resolver);
+
+        bodies.push(format!("// File: {path}\n\n{}\n", body.trim()));
+    }
+
+    let mut output = String::new();
+
+    if !licenses.is_empty() {
+        output.push_str(&format!("// {LICENSE} {}\n", license_expression(&licenses)));
+    }
+
+    for pragma in &pragmas {
+        output.push_str(pragma);
+        output.push('\n');
+    }
+
+    for body in bodies {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+
+        output.push_str(&body);
+    }
+
+    Ok(output)
+}
+
+/// Add the file and the files it imports to the order, with every file after the files it
+/// imports. Import cycles are broken where a file is seen the second time.
+fn imports_first(ns: &Namespace, file_no: usize, visited: &mut [bool], order: &mut Vec<usize>) {
+    if visited[file_no] {
+        return;
+    }
+
+    visited[file_no] = true;
+
+    for (_, import_no) in &ns.files[file_no].imports {
+        imports_first(ns, *import_no, visited, order);
+    }
+
+    order.push(file_no);
+}
+
+/// The source of an import or pragma directive. The location of the directive does not include
+/// the semicolon.
+fn directive(contents: &str, loc: &pt::Loc) -> Range<usize> {
+    let end = contents[loc.end()..]
+        .find(';')
+        .map_or(loc.end(), |pos| loc.end() + pos + 1);
+
+    whole_lines(contents, loc.start()..end)
+}
+
+/// If nothing else is on the lines of the range, extend it to the whole lines so that no empty
+/// lines are left when it is removed
+fn whole_lines(contents: &str, range: Range<usize>) -> Range<usize> {
+    let line_start = contents[..range.start].rfind('\n').map_or(0, |pos| pos + 1);
+    let line_end = contents[range.end..]
+        .find('\n')
+        .map_or(contents.len(), |pos| range.end + pos + 1);
+
+    if contents[line_start..range.start].trim().is_empty()
+        && contents[range.end..line_end].trim().is_empty()
+    {
+        line_start..line_end
+    } else {
+        range
+    }
+}
+
+/// All the licenses apply to the flattened file. A license which is an expression itself is put
+/// in parentheses, so that `OR` does not bind to the other licenses.
+fn license_expression(licenses: &[String]) -> String {
+    if licenses.len() == 1 {
+        return licenses[0].clone();
+    }
+
+    licenses
+        .iter()
+        .map(|license| {
+            if license.contains(char::is_whitespace) {
+                format!("({license})")
+            } else {
+                license.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_and_resolve, Target};
+    use std::ffi::OsStr;
+
+    fn flatten_files(files: &[(&str, &str)]) -> Result<String, String> {
+        let mut resolver = FileResolver::default();
+
+        for (path, contents) in files {
+            resolver.set_file_contents(path, contents.to_string());
+        }
+
+        let ns = parse_and_resolve(OsStr::new(files[0].0), &mut resolver, Target::EVM);
+
+        assert!(!ns.diagnostics.any_errors());
+
+        flatten(&ns, &resolver)
+    }
+
+    #[test]
+    fn flatten_imports() {
+        let flat = flatten_files(&[
+            (
+                "a.sol",
+                "// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;\n\nimport \"b.sol\";\nimport {C} from \"c.sol\";\n\ncontract A is B, C {}\n",
+            ),
+            (
+                "b.sol",
+                "// SPDX-License-Identifier: Apache-2.0\npragma solidity ^0.8.0;\nimport \"c.sol\";\n\ncontract B is C {}\n",
+            ),
+            (
+                "c.sol",
+                "// SPDX-License-Identifier: MIT OR GPL-3.0\npragma solidity   >=0.8.4 ;\n/// The base\ncontract C {}\n",
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            flat,
+            "// SPDX-License-Identifier: (MIT OR GPL-3.0) AND Apache-2.0 AND MIT\n\
+             pragma solidity >=0.8.4;\n\
+             pragma solidity ^0.8.0;\n\
+             \n\
+             // File: c.sol\n\
+             \n\
+             /// The base\n\
+             contract C {}\n\
+             \n\
+             // File: b.sol\n\
+             \n\
+             contract B is C {}\n\
+             \n\
+             // File: a.sol\n\
+             \n\
+             contract A is B, C {}\n"
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(
+            flatten_files(&[
+                (
+                    "a.sol",
+                    "import {B as D} from \"b.sol\";\ncontract A is D {}\n"
+                ),
+                ("b.sol", "contract B {}\n"),
+            ])
+            .unwrap_err(),
+            "a.sol:1:1-29: import with an alias cannot be flattened"
+        );
+    }
+}
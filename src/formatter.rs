@@ -0,0 +1,1137 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Format Solidity source code. This is used by `solang fmt` and by the language server, so that
+//! both produce the same layout.
+//!
+//! The layout follows the parse tree: every item and statement starts on a new line, and the
+//! contents of contracts, structs, enums, blocks and assembly blocks are indented. The code of a
+//! single item or statement is written out from its tokens with the spacing normalized, and the
+//! widest bracketed list in it is broken over several lines when it does not fit on one line.
+//! A long expression without such a list is broken before its operators of lowest precedence.
+//!
+//! Comments and the order of items are preserved, and at most one blank line is kept between
+//! items; only whitespace, indentation and line wrapping are changed. Code which has a comment
+//! inside it is kept as written, apart from its indentation. The formatted source is checked to
+//! have the same tokens and comments as the original, so that formatting never changes the code.
+
+use solang_parser::{
+    lexer::{Lexer, Spanned, Token},
+    pt::{self, CodeLocation},
+};
+use std::fmt;
+
+/// Maximum line length of formatted code
+pub const LINE_LENGTH: usize = 80;
+
+/// Default number of spaces per indentation level
+pub const TAB_WIDTH: usize = 4;
+
+/// Why formatting failed
+#[derive(Debug)]
+pub enum FormatError {
+    /// The source does not parse; formatting is only possible for valid Solidity
+    Parse(Vec<String>),
+    /// The formatter could not lay out the parsed source
+    Format(String),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Parse(messages) => write!(f, "parse failed: {}", messages.join(", ")),
+            FormatError::Format(message) => write!(f, "format failed: {message}"),
+        }
+    }
+}
+
+/// Format the Solidity source, indenting with `tab_width` spaces per level
+pub fn format_source(source: &str, tab_width: usize) -> Result<String, FormatError> {
+    let (tree, comments) = solang_parser::parse(source, 0).map_err(|diagnostics| {
+        FormatError::Parse(
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect(),
+        )
+    })?;
+
+    let mut lexer_comments = Vec::new();
+    let mut lexer_errors = Vec::new();
+
+    let tokens = Lexer::new(source, 0, &mut lexer_comments, &mut lexer_errors).collect();
+
+    let mut formatter = Formatter {
+        src: source,
+        tokens,
+        comments,
+        tab_width,
+        out: String::new(),
+        indent: 0,
+        pos: 0,
+        fresh: true,
+        needs_break: false,
+        yul: false,
+        spacing: Spacing::default(),
+    };
+
+    formatter.source_unit(&tree);
+
+    let formatted = formatter.out;
+
+    if !same_code(source, &formatted) {
+        return Err(FormatError::Format(
+            "the formatted source would not have the same tokens and comments".into(),
+        ));
+    }
+
+    Ok(formatted)
+}
+
+/// A part of the parse tree which is laid out over lines
+#[derive(Clone, Copy)]
+enum Node<'t> {
+    SourceUnit(&'t pt::SourceUnitPart),
+    Contract(&'t pt::ContractPart),
+    Statement(&'t pt::Statement),
+    Yul(&'t pt::YulStatement),
+    /// Code which is written from its tokens, like a struct field or an enum value
+    Code(pt::Loc),
+}
+
+impl Node<'_> {
+    fn loc(&self) -> pt::Loc {
+        match self {
+            Node::SourceUnit(part) => part.loc(),
+            Node::Contract(part) => part.loc(),
+            Node::Statement(stmt) => stmt.loc(),
+            Node::Yul(stmt) => stmt.loc(),
+            Node::Code(loc) => *loc,
+        }
+    }
+
+    /// Items which span several lines are separated from their neighbours by a blank line
+    fn is_block_item(&self) -> bool {
+        match self {
+            Node::SourceUnit(pt::SourceUnitPart::ContractDefinition(_))
+            | Node::SourceUnit(pt::SourceUnitPart::StructDefinition(_))
+            | Node::SourceUnit(pt::SourceUnitPart::EnumDefinition(_))
+            | Node::Contract(pt::ContractPart::StructDefinition(_))
+            | Node::Contract(pt::ContractPart::EnumDefinition(_)) => true,
+            Node::SourceUnit(pt::SourceUnitPart::FunctionDefinition(func))
+            | Node::Contract(pt::ContractPart::FunctionDefinition(func)) => func.body.is_some(),
+            _ => false,
+        }
+    }
+}
+
+/// The state needed to decide whether the next token on a line is preceded by a space
+#[derive(Clone, Default)]
+struct Spacing<'a> {
+    /// The last token written on the current line
+    last: Option<Token<'a>>,
+    /// The last token was a prefix operator
+    unary: bool,
+    /// The last token ends an operand, so a following `-` or `++` is not a prefix operator
+    operand: bool,
+    /// The last token was a colon in a ternary operator
+    ternary_colon: bool,
+    /// A comment was written last on the current line
+    comment: bool,
+    /// Writing the version of a pragma, where operators are attached to the version numbers
+    version: bool,
+    /// The open brackets of the code being written, and the number of `?` without a `:` in each
+    brackets: Vec<(Token<'a>, usize)>,
+}
+
+impl<'a> Spacing<'a> {
+    fn space_before(&self, next: &Token, yul: bool) -> bool {
+        let Some(last) = self.last else {
+            return self.comment;
+        };
+
+        if self.unary {
+            return false;
+        }
+
+        if self.version
+            && matches!(
+                last,
+                Token::BitwiseXor
+                    | Token::Assign
+                    | Token::Less
+                    | Token::LessEqual
+                    | Token::More
+                    | Token::MoreEqual
+            )
+        {
+            return false;
+        }
+
+        if matches!(
+            next,
+            Token::Comma | Token::Semicolon | Token::CloseParenthesis | Token::CloseBracket
+        ) || matches!(next, Token::Member)
+            || matches!(last, Token::OpenParenthesis | Token::OpenBracket)
+            || matches!(last, Token::Member)
+        {
+            return false;
+        }
+
+        let in_brackets = self.brackets.last().map(|(bracket, _)| *bracket);
+
+        match next {
+            Token::Increment | Token::Decrement if self.operand => false,
+            Token::OpenParenthesis if yul => !is_word(&last),
+            Token::OpenParenthesis => {
+                !(self.operand
+                    || matches!(
+                        last,
+                        Token::Payable
+                            | Token::Type
+                            | Token::Mapping
+                            | Token::Function
+                            | Token::Constructor
+                            | Token::Fallback
+                            | Token::Receive
+                            | Token::Revert
+                            | Token::Annotation(_)
+                            | Token::CloseCurlyBrace
+                    ))
+            }
+            Token::OpenBracket => !(self.operand || matches!(last, Token::Payable)),
+            Token::OpenCurlyBrace if !yul => !matches!(last, Token::Identifier(_)),
+            Token::CloseCurlyBrace => yul && !matches!(last, Token::OpenCurlyBrace),
+            Token::Colon => self.is_ternary_colon(),
+            _ => match last {
+                Token::OpenCurlyBrace => yul,
+                Token::Colon => self.ternary_colon || in_brackets != Some(Token::OpenBracket),
+                _ => true,
+            },
+        }
+    }
+
+    fn is_ternary_colon(&self) -> bool {
+        self.brackets
+            .last()
+            .map(|(_, questions)| *questions > 0)
+            .unwrap_or(false)
+    }
+
+    fn push(&mut self, token: Token<'a>) {
+        let operand = self.operand;
+
+        self.ternary_colon = false;
+        self.comment = false;
+
+        match token {
+            Token::OpenParenthesis | Token::OpenBracket | Token::OpenCurlyBrace => {
+                self.brackets.push((token, 0));
+            }
+            Token::CloseParenthesis | Token::CloseBracket | Token::CloseCurlyBrace => {
+                self.brackets.pop();
+            }
+            Token::Question => {
+                if let Some((_, questions)) = self.brackets.last_mut() {
+                    *questions += 1;
+                } else {
+                    self.brackets.push((Token::Question, 1));
+                }
+            }
+            Token::Colon if self.is_ternary_colon() => {
+                if let Some((_, questions)) = self.brackets.last_mut() {
+                    *questions -= 1;
+                }
+                self.ternary_colon = true;
+            }
+            Token::Identifier(_) if self.last == Some(Token::Pragma) => self.version = true,
+            Token::Semicolon => self.version = false,
+            _ => (),
+        }
+
+        self.unary = match token {
+            Token::Not | Token::BitwiseNot => true,
+            Token::Subtract | Token::Add | Token::Increment | Token::Decrement => !operand,
+            _ => false,
+        };
+
+        self.operand = match token {
+            Token::Increment | Token::Decrement => operand,
+            _ => ends_operand(&token),
+        };
+
+        self.last = Some(token);
+    }
+
+    /// A new line starts; the brackets which are still open are kept
+    fn newline(&mut self) {
+        self.last = None;
+        self.unary = false;
+        self.operand = false;
+        self.ternary_colon = false;
+        self.comment = false;
+    }
+}
+
+/// Can the token end an operand, like an identifier, a literal or a closing bracket
+fn ends_operand(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Identifier(_)
+            | Token::StringLiteral(..)
+            | Token::AddressLiteral(_)
+            | Token::HexLiteral(_)
+            | Token::Number(..)
+            | Token::RationalNumber(..)
+            | Token::HexNumber(_)
+            | Token::True
+            | Token::False
+            | Token::CloseParenthesis
+            | Token::CloseBracket
+            | Token::Uint(_)
+            | Token::Int(_)
+            | Token::Bytes(_)
+            | Token::Byte
+            | Token::DynamicBytes
+            | Token::Bool
+            | Token::Address
+            | Token::String
+    )
+}
+
+/// How good brackets are to break: whether they are not square brackets, whether they
+/// enclose a list, and the width of the code they enclose
+type BracketRank = (bool, bool, usize);
+
+/// Is the token an identifier or a keyword, which in Yul can be the name of a builtin function
+fn is_word(token: &Token) -> bool {
+    token
+        .to_string()
+        .starts_with(|c: char| c.is_ascii_alphabetic() || c == '_' || c == '$')
+}
+
+struct Formatter<'a> {
+    src: &'a str,
+    /// All the tokens of the source, in order
+    tokens: Vec<Spanned<'a>>,
+    /// All the comments of the source, in order
+    comments: Vec<pt::Comment>,
+    tab_width: usize,
+    out: String,
+    /// The current indentation level
+    indent: usize,
+    /// The offset in the source up to which everything has been written
+    pos: usize,
+    /// Nothing has been written yet in the current block, so no blank line is needed
+    fresh: bool,
+    /// A line comment was written, so anything after it must go on the next line
+    needs_break: bool,
+    /// Formatting the inside of an assembly block
+    yul: bool,
+    spacing: Spacing<'a>,
+}
+
+impl<'a> Formatter<'a> {
+    fn source_unit(&mut self, tree: &pt::SourceUnit) {
+        let nodes: Vec<Node> = tree.0.iter().map(Node::SourceUnit).collect();
+
+        self.lines(&nodes);
+
+        let mut blank = false;
+        self.gap(self.src.len(), &mut blank);
+
+        let len = self.out.trim_end().len();
+        self.out.truncate(len);
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+    }
+
+    /// Write each node on a new line, with the comments between them
+    fn lines(&mut self, nodes: &[Node]) {
+        let mut prev: Option<&Node> = None;
+
+        for node in nodes {
+            let loc = node.loc();
+
+            let mut blank = prev.is_some_and(|prev| prev.is_block_item() || node.is_block_item());
+
+            self.gap(loc.start(), &mut blank);
+            self.break_line(loc.start(), blank);
+            self.node(node);
+
+            prev = Some(node);
+        }
+    }
+
+    fn node(&mut self, node: &Node) {
+        match node {
+            Node::SourceUnit(pt::SourceUnitPart::ContractDefinition(contract)) => {
+                self.contract(contract)
+            }
+            Node::SourceUnit(pt::SourceUnitPart::FunctionDefinition(func))
+            | Node::Contract(pt::ContractPart::FunctionDefinition(func)) => self.function(func),
+            Node::SourceUnit(pt::SourceUnitPart::StructDefinition(def))
+            | Node::Contract(pt::ContractPart::StructDefinition(def)) => {
+                let fields: Vec<Node> = def.fields.iter().map(|f| Node::Code(f.loc)).collect();
+
+                self.braces(&def.loc, &fields);
+            }
+            Node::SourceUnit(pt::SourceUnitPart::EnumDefinition(def))
+            | Node::Contract(pt::ContractPart::EnumDefinition(def)) => {
+                let values: Vec<Node> = def
+                    .values
+                    .iter()
+                    .flatten()
+                    .map(|v| Node::Code(v.loc))
+                    .collect();
+
+                self.braces(&def.loc, &values);
+            }
+            Node::Statement(stmt) => self.statement(stmt),
+            Node::Yul(stmt) => self.yul_statement(stmt),
+            _ => {
+                let loc = node.loc();
+
+                self.code(loc.start(), loc.end());
+            }
+        }
+    }
+
+    fn contract(&mut self, contract: &pt::ContractDefinition) {
+        let parts: Vec<Node> = contract.parts.iter().map(Node::Contract).collect();
+
+        self.braces(&contract.loc, &parts);
+    }
+
+    fn function(&mut self, func: &pt::FunctionDefinition) {
+        match &func.body {
+            Some(body) => {
+                self.code(func.loc.start(), body.loc().start());
+                self.statement(body);
+            }
+            None => self.code(func.loc.start(), func.loc.end()),
+        }
+    }
+
+    fn statement(&mut self, stmt: &pt::Statement) {
+        let loc = stmt.loc();
+
+        match stmt {
+            pt::Statement::Block { statements, .. } => {
+                let nodes: Vec<Node> = statements.iter().map(Node::Statement).collect();
+
+                self.braces(&loc, &nodes);
+            }
+            pt::Statement::If(_, _, then, otherwise) => {
+                self.code(loc.start(), then.loc().start());
+                self.statement(then);
+
+                if let Some(otherwise) = otherwise {
+                    self.code(then.loc().end(), otherwise.loc().start());
+                    self.statement(otherwise);
+                }
+            }
+            pt::Statement::While(_, _, body) | pt::Statement::For(_, _, _, _, Some(body)) => {
+                self.code(loc.start(), body.loc().start());
+                self.statement(body);
+            }
+            pt::Statement::DoWhile(_, body, _) => {
+                self.code(loc.start(), body.loc().start());
+                self.statement(body);
+                self.code(body.loc().end(), loc.end());
+            }
+            pt::Statement::Try(_, _, returns, catches) => {
+                let mut pos = loc.start();
+
+                let blocks =
+                    returns
+                        .iter()
+                        .map(|(_, block)| block.as_ref())
+                        .chain(catches.iter().map(|catch| match catch {
+                            pt::CatchClause::Simple(_, _, block)
+                            | pt::CatchClause::Named(_, _, _, block) => block,
+                        }));
+
+                for block in blocks {
+                    self.code(pos, block.loc().start());
+                    self.statement(block);
+                    pos = block.loc().end();
+                }
+
+                self.code(pos, loc.end());
+            }
+            pt::Statement::Assembly { block, .. } => {
+                self.code(loc.start(), block.loc.start());
+                self.yul_block(block);
+            }
+            _ => self.code(loc.start(), loc.end()),
+        }
+    }
+
+    fn yul_block(&mut self, block: &pt::YulBlock) {
+        let yul = std::mem::replace(&mut self.yul, true);
+
+        let nodes: Vec<Node> = block.statements.iter().map(Node::Yul).collect();
+
+        self.braces(&block.loc, &nodes);
+
+        self.yul = yul;
+    }
+
+    fn yul_statement(&mut self, stmt: &pt::YulStatement) {
+        let loc = stmt.loc();
+
+        match stmt {
+            pt::YulStatement::Block(block) => self.yul_block(block),
+            pt::YulStatement::If(_, _, block) => {
+                self.code(loc.start(), block.loc.start());
+                self.yul_block(block);
+            }
+            pt::YulStatement::For(pt::YulFor {
+                execution_block, ..
+            }) => {
+                self.code(loc.start(), execution_block.loc.start());
+                self.yul_block(execution_block);
+            }
+            pt::YulStatement::FunctionDefinition(def) => {
+                self.code(loc.start(), def.body.loc.start());
+                self.yul_block(&def.body);
+            }
+            pt::YulStatement::Switch(switch) => {
+                let options: Vec<&pt::YulSwitchOptions> =
+                    switch.cases.iter().chain(&switch.default).collect();
+
+                let first = options.first().map_or(loc.end(), |o| o.loc().start());
+
+                self.code(loc.start(), first);
+
+                for option in options {
+                    let (option_loc, block) = match option {
+                        pt::YulSwitchOptions::Case(loc, _, block)
+                        | pt::YulSwitchOptions::Default(loc, block) => (loc, block),
+                    };
+
+                    let mut blank = false;
+
+                    self.gap(option_loc.start(), &mut blank);
+                    self.break_line(option_loc.start(), blank);
+                    self.code(option_loc.start(), block.loc.start());
+                    self.yul_block(block);
+                }
+            }
+            _ => self.code(loc.start(), loc.end()),
+        }
+    }
+
+    /// Write the code before the opening brace, and then the nodes inside the braces on their
+    /// own lines, indented
+    fn braces(&mut self, loc: &pt::Loc, nodes: &[Node]) {
+        let (Some(open), Some(close)) = (self.open_brace(loc), self.close_brace(loc)) else {
+            return self.code(loc.start(), loc.end());
+        };
+
+        let (open_start, _, open_end) = self.tokens[open];
+        let (close_start, _, close_end) = self.tokens[close];
+
+        self.code(loc.start(), open_start);
+
+        // the brace is always separated from the code before it
+        self.write("{", true);
+        self.spacing.newline();
+        self.pos = open_end;
+
+        let inside = self.out.len();
+
+        self.fresh = true;
+        self.indent_by(1);
+        self.lines(nodes);
+
+        let mut blank = false;
+        self.gap(close_start, &mut blank);
+        self.indent_by(-1);
+
+        if self.out.len() != inside {
+            self.newline();
+        }
+
+        self.write("}", false);
+        self.spacing.newline();
+        self.spacing.last = Some(Token::CloseCurlyBrace);
+        self.pos = close_end;
+        self.fresh = false;
+    }
+
+    /// Write the tokens and comments between the current position and `end`, which are not part
+    /// of a node, like the semicolon after a statement or the comments before it
+    fn gap(&mut self, end: usize, blank: &mut bool) {
+        let tokens = self.tokens_in(self.pos, end).to_vec();
+        let mut tokens = tokens.iter().peekable();
+        let comments: Vec<pt::Comment> = self.comments_in(self.pos, end).to_vec();
+        let mut comments = comments.iter().peekable();
+
+        loop {
+            let token_start = tokens.peek().map(|(start, ..)| *start);
+            let comment_start = comments.peek().map(|c| c.loc().start());
+
+            match (token_start, comment_start) {
+                (Some(token), comment) if comment.map_or(true, |comment| token < comment) => {
+                    let &(start, token, end) = tokens.next().unwrap();
+
+                    self.token(start, token, end);
+                }
+                (_, Some(_)) => {
+                    let comment = comments.next().unwrap();
+                    let loc = comment.loc();
+
+                    let trailing = !self.at_line_start()
+                        && !self.src[self.pos.min(loc.start())..loc.start()].contains('\n');
+
+                    if !trailing {
+                        self.break_line(loc.start(), *blank);
+                        *blank = false;
+                    }
+
+                    self.verbatim(loc.start(), loc.end(), true);
+
+                    self.needs_break = comment.is_line();
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Write the code from `start` to `end`; its tokens are spaced and wrapped, unless it
+    /// contains comments, in which case it is written as it is
+    fn code(&mut self, start: usize, end: usize) {
+        let tokens = self.tokens_in(start, end).to_vec();
+        let comments = self.comments_in(start, end).to_vec();
+
+        if comments.is_empty() {
+            if let Some(&(_, _, last_end)) = tokens.last() {
+                // the semicolon or opening brace which follows goes on the same line
+                let next = self.token_range(start, end).end;
+                let reserve = match self.tokens.get(next) {
+                    Some((_, Token::Semicolon | Token::Comma, _)) => 1,
+                    Some((_, Token::OpenCurlyBrace, _)) => 2,
+                    _ => 0,
+                };
+
+                self.spacing.brackets.clear();
+                self.wrap(start, end, reserve);
+                self.pos = last_end;
+            }
+        } else {
+            let first = tokens.first().map_or(usize::MAX, |t| t.0);
+            let last = tokens.last().map_or(0, |t| t.2);
+
+            let comment_start = comments[0].loc().start();
+            let last_comment = comments.last().unwrap();
+
+            let verbatim_start = first.min(comment_start);
+            let verbatim_end = last.max(last_comment.loc().end());
+
+            let space = match tokens.first() {
+                Some((start, token, _)) if *start < comment_start => {
+                    self.spacing.space_before(token, self.yul)
+                }
+                _ => true,
+            };
+
+            self.verbatim(verbatim_start, verbatim_end, space);
+
+            self.needs_break = last_comment.is_line() && last_comment.loc().end() > last;
+
+            if let Some(&(_, token, _)) = tokens.last() {
+                self.spacing.newline();
+                self.spacing.push(token);
+            }
+        }
+    }
+
+    /// Write the tokens between `start` and `end` on the current line if they fit with `reserve`
+    /// more characters after them, or else break the widest bracketed list in them with one
+    /// element per line
+    fn wrap(&mut self, start: usize, end: usize, reserve: usize) {
+        let range = self.token_range(start, end);
+
+        if range.is_empty() {
+            return;
+        }
+
+        if self.line_width() + self.width(range.clone()) + reserve <= LINE_LENGTH {
+            for no in range {
+                let (start, token, end) = self.tokens[no];
+                self.token(start, token, end);
+            }
+            return;
+        }
+
+        let brackets = self.widest_brackets(range.clone());
+        let operators = self.operator_breaks(range.clone());
+
+        if !operators.is_empty() && !matches!(brackets, Some((_, _, true))) {
+            let first = self.tokens[operators[0] - 1].2;
+            self.wrap(start, first, 0);
+            self.indent_by(1);
+
+            for (i, &no) in operators.iter().enumerate() {
+                self.newline();
+
+                match operators.get(i + 1) {
+                    Some(&next) => self.wrap(self.tokens[no].0, self.tokens[next - 1].2, 0),
+                    None => self.wrap(self.tokens[no].0, end, reserve),
+                }
+            }
+
+            self.indent_by(-1);
+            return;
+        }
+
+        let Some((open, close, _)) = brackets else {
+            for no in range {
+                let (start, token, end) = self.tokens[no];
+                self.token(start, token, end);
+            }
+            return;
+        };
+
+        for no in range.start..=open {
+            let (start, token, end) = self.tokens[no];
+            self.token(start, token, end);
+        }
+
+        if close > open + 1 {
+            self.indent_by(1);
+
+            let mut element_start = open + 1;
+            let mut depth = 0;
+
+            for no in open + 1..close {
+                match self.tokens[no].1 {
+                    Token::OpenParenthesis | Token::OpenBracket | Token::OpenCurlyBrace => {
+                        depth += 1
+                    }
+                    Token::CloseParenthesis | Token::CloseBracket | Token::CloseCurlyBrace => {
+                        depth -= 1
+                    }
+                    Token::Comma if depth == 0 => {
+                        self.newline();
+                        self.wrap(self.tokens[element_start].0, self.tokens[no].2, 0);
+                        element_start = no + 1;
+                    }
+                    _ => (),
+                }
+            }
+
+            if element_start < close {
+                self.newline();
+                self.wrap(self.tokens[element_start].0, self.tokens[close - 1].2, 0);
+            }
+
+            self.indent_by(-1);
+            self.newline();
+        }
+
+        self.wrap(self.tokens[close].0, end, reserve);
+    }
+
+    /// The outermost brackets in the tokens which are best to break: parentheses and braces
+    /// rather than square brackets, then lists rather than single elements, and then the ones
+    /// which enclose the most code. The last field says whether they enclose a list
+    fn widest_brackets(&self, range: std::ops::Range<usize>) -> Option<(usize, usize, bool)> {
+        let mut widest: Option<(BracketRank, usize, usize)> = None;
+        let mut open = None;
+        let mut list = false;
+        let mut depth = 0;
+
+        for no in range {
+            match self.tokens[no].1 {
+                Token::OpenParenthesis | Token::OpenBracket | Token::OpenCurlyBrace => {
+                    if depth == 0 {
+                        open = Some(no);
+                        list = false;
+                    }
+                    depth += 1;
+                }
+                Token::CloseParenthesis | Token::CloseBracket | Token::CloseCurlyBrace => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        let open = open.take()?;
+
+                        let rank = (
+                            self.tokens[open].1 != Token::OpenBracket,
+                            list,
+                            self.tokens[no].0 - self.tokens[open].2,
+                        );
+
+                        if widest.map_or(true, |(widest, ..)| rank > widest) {
+                            widest = Some((rank, open, no));
+                        }
+                    }
+                }
+                Token::Comma if depth == 1 => list = true,
+                _ => (),
+            }
+        }
+
+        widest.map(|((_, list, _), open, close)| (open, close, list))
+    }
+
+    /// The binary operators outside any brackets with the lowest precedence, before which
+    /// a long expression can be broken
+    fn operator_breaks(&self, range: std::ops::Range<usize>) -> Vec<usize> {
+        let mut operators: Vec<usize> = Vec::new();
+        let mut lowest = usize::MAX;
+        let mut depth = 0;
+
+        for no in range.clone() {
+            let precedence = match self.tokens[no].1 {
+                Token::OpenParenthesis | Token::OpenBracket | Token::OpenCurlyBrace => {
+                    depth += 1;
+                    continue;
+                }
+                Token::CloseParenthesis | Token::CloseBracket | Token::CloseCurlyBrace => {
+                    depth -= 1;
+                    continue;
+                }
+                Token::Or => 0,
+                Token::And => 1,
+                Token::BitwiseOr => 2,
+                Token::BitwiseXor => 3,
+                Token::BitwiseAnd => 4,
+                Token::Add | Token::Subtract => 5,
+                _ => continue,
+            };
+
+            if depth != 0 || no == range.start || !ends_operand(&self.tokens[no - 1].1) {
+                continue;
+            }
+
+            if precedence < lowest {
+                lowest = precedence;
+                operators.clear();
+            }
+
+            if precedence == lowest {
+                operators.push(no);
+            }
+        }
+
+        operators
+    }
+
+    /// The width of the tokens when written on the current line
+    fn width(&self, range: std::ops::Range<usize>) -> usize {
+        let mut spacing = self.spacing.clone();
+        let mut width = 0;
+
+        for no in range {
+            let (start, token, end) = self.tokens[no];
+
+            if spacing.space_before(&token, self.yul) {
+                width += 1;
+            }
+
+            width += self.src[start..end].chars().count();
+            spacing.push(token);
+        }
+
+        width
+    }
+
+    fn token(&mut self, start: usize, token: Token<'a>, end: usize) {
+        let space = self.spacing.space_before(&token, self.yul);
+
+        self.write(&self.src[start..end], space);
+        self.spacing.push(token);
+        self.pos = end;
+    }
+
+    /// Write source code as it is, but indented for where it is written
+    fn verbatim(&mut self, start: usize, end: usize, space: bool) {
+        let line_start = self.src[..start].rfind('\n').map_or(0, |n| n + 1);
+        let original_indent = self.src[line_start..start]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+
+        let mut lines = self.src[start..end].lines();
+
+        if let Some(first) = lines.next() {
+            self.write(first.trim_end(), space);
+        }
+
+        for line in lines {
+            let line = line.trim_end();
+
+            // blank lines are kept, since they are part of a comment
+            let len = self.out.trim_end_matches(' ').len();
+            self.out.truncate(len);
+            self.out.push('\n');
+
+            let skip = line
+                .chars()
+                .take(original_indent)
+                .take_while(|c| c.is_whitespace())
+                .map(char::len_utf8)
+                .sum::<usize>();
+
+            if !line[skip..].is_empty() {
+                self.write(&line[skip..], false);
+            }
+        }
+
+        self.spacing.newline();
+        self.spacing.comment = true;
+        self.pos = end;
+    }
+
+    fn write(&mut self, text: &str, space: bool) {
+        if self.needs_break {
+            self.newline();
+        }
+
+        if self.at_line_start() {
+            let indent = self.indent_width();
+            self.out.push_str(&" ".repeat(indent));
+        } else if space {
+            self.out.push(' ');
+        }
+
+        self.out.push_str(text);
+        self.fresh = false;
+    }
+
+    /// Start a new line, and keep a blank line if there is one in the source before `next`, or
+    /// if `blank` is set
+    fn break_line(&mut self, next: usize, blank: bool) {
+        if self.out.is_empty() {
+            return;
+        }
+
+        let between = &self.src[self.pos.min(next)..next];
+        let blank_in_source = between.chars().filter(|c| *c == '\n').count() > 1;
+
+        self.newline();
+
+        if (blank || blank_in_source) && !self.fresh {
+            self.out.push('\n');
+        }
+    }
+
+    fn newline(&mut self) {
+        let len = self.out.trim_end_matches(' ').len();
+        self.out.truncate(len);
+
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+
+        self.needs_break = false;
+        self.spacing.newline();
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with('\n')
+    }
+
+    fn line_width(&self) -> usize {
+        let line = self.out.rsplit('\n').next().unwrap_or_default();
+
+        if line.is_empty() {
+            self.indent_width()
+        } else {
+            line.chars().count()
+        }
+    }
+
+    fn indent_width(&self) -> usize {
+        self.indent * self.tab_width
+    }
+
+    fn indent_by(&mut self, levels: isize) {
+        self.indent = self.indent.saturating_add_signed(levels);
+    }
+
+    /// The number of the opening brace of the node, which is the first one outside any brackets
+    fn open_brace(&self, loc: &pt::Loc) -> Option<usize> {
+        let mut depth = 0;
+
+        for no in self.token_range(loc.start(), loc.end()) {
+            match self.tokens[no].1 {
+                Token::OpenCurlyBrace if depth == 0 => return Some(no),
+                Token::OpenParenthesis | Token::OpenBracket => depth += 1,
+                Token::CloseParenthesis | Token::CloseBracket => depth -= 1,
+                _ => (),
+            }
+        }
+
+        None
+    }
+
+    /// The number of the closing brace which ends the node
+    fn close_brace(&self, loc: &pt::Loc) -> Option<usize> {
+        let no = self.token_range(loc.start(), loc.end()).last()?;
+
+        (self.tokens[no].1 == Token::CloseCurlyBrace).then_some(no)
+    }
+
+    /// The numbers of the tokens which start between `start` and `end`
+    fn token_range(&self, start: usize, end: usize) -> std::ops::Range<usize> {
+        let first = self.tokens.partition_point(|(s, ..)| *s < start);
+        let last = self.tokens.partition_point(|(s, ..)| *s < end);
+
+        first..last.max(first)
+    }
+
+    fn tokens_in(&self, start: usize, end: usize) -> &[Spanned<'a>] {
+        &self.tokens[self.token_range(start, end)]
+    }
+
+    fn comments_in(&self, start: usize, end: usize) -> &[pt::Comment] {
+        let first = self.comments.partition_point(|c| c.loc().start() < start);
+        let last = self.comments.partition_point(|c| c.loc().start() < end);
+
+        &self.comments[first..last.max(first)]
+    }
+}
+
+/// Do both sources have the same tokens, and the same comments apart from their indentation
+fn same_code(original: &str, formatted: &str) -> bool {
+    lex(original) == lex(formatted)
+}
+
+/// The tokens and the comments of the source, with the indentation of the comments removed
+fn lex(source: &str) -> (Vec<String>, Vec<String>) {
+    let mut comments = Vec::new();
+    let mut errors = Vec::new();
+
+    let tokens = Lexer::new(source, 0, &mut comments, &mut errors)
+        .map(|(_, token, _)| format!("{token:?}"))
+        .collect();
+
+    let comments = comments
+        .iter()
+        .map(|comment| {
+            comment
+                .value()
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect();
+
+    (tokens, comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(source: &str) -> String {
+        format_with(source, TAB_WIDTH)
+    }
+
+    fn format_with(source: &str, tab_width: usize) -> String {
+        let formatted = format_source(source, tab_width).unwrap();
+
+        assert_eq!(format_source(&formatted, tab_width).unwrap(), formatted);
+
+        formatted
+    }
+
+    #[test]
+    fn layout() {
+        assert_eq!(
+            format(
+                "pragma solidity ^0.8.0;\ncontract c{\nuint   x ;\n    function f(uint a,uint b)public pure returns(uint){return a+b;}\n}\n"
+            ),
+            "pragma solidity ^0.8.0;\n\ncontract c {\n    uint x;\n\n    function f(uint a, uint b) public pure returns (uint) {\n        return a + b;\n    }\n}\n"
+        );
+
+        assert_eq!(
+            format_with(
+                "contract c {\n\tfunction f() public {\n\t\tassembly { let x := add(1,2) }\n\t}\n}",
+                2
+            ),
+            "contract c {\n  function f() public {\n    assembly {\n      let x := add(1, 2)\n    }\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn wrap_long_lines() {
+        assert_eq!(
+            format("contract c {\n    function f() public {\n        emit SomethingHappened(first_argument, second_argument, third_argument, fourth);\n    }\n}\n"),
+            "contract c {\n    function f() public {\n        emit SomethingHappened(\n            first_argument,\n            second_argument,\n            third_argument,\n            fourth\n        );\n    }\n}\n"
+        );
+
+        assert_eq!(
+            format("contract c {\n    function f(bytes8 input) public pure returns (bytes8 out) {\n        out = ((input << 56) & hex\"ff00_0000_0000_0000\") | ((input << 40) & hex\"00ff_0000_0000_0000\");\n    }\n}\n"),
+            "contract c {\n    function f(bytes8 input) public pure returns (bytes8 out) {\n        out = ((input << 56) & hex\"ff00_0000_0000_0000\")\n            | ((input << 40) & hex\"00ff_0000_0000_0000\");\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn comments() {
+        let source = "// SPDX-License-Identifier: MIT\n\n/// The contract\ncontract c {\n    // keep me\n    uint x; // and me\n\n    /* and\n       me */\n    uint y;\n}\n";
+
+        assert_eq!(format(source), source);
+    }
+
+    #[test]
+    fn contract_testcases() {
+        let mut failed = Vec::new();
+        let mut dirs = vec![std::path::PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/contract_testcases"
+        ))];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+
+                if path.extension().and_then(|ext| ext.to_str()) != Some("sol") {
+                    continue;
+                }
+
+                let Ok(source) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+
+                // some of the test cases do not parse
+                let formatted = match format_source(&source, TAB_WIDTH) {
+                    Ok(formatted) => formatted,
+                    Err(FormatError::Parse(_)) => continue,
+                    Err(err) => {
+                        failed.push(format!("{}: {err}", path.display()));
+                        continue;
+                    }
+                };
+
+                if !same_code(&source, &formatted) {
+                    failed.push(format!("{}: code changed", path.display()));
+                } else if format_source(&formatted, TAB_WIDTH).ok().as_ref() != Some(&formatted) {
+                    failed.push(format!("{}: formatting is not stable", path.display()));
+                }
+            }
+        }
+
+        assert!(failed.is_empty(), "{}", failed.join("\n"));
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(matches!(
+            format_source("contract c {", TAB_WIDTH),
+            Err(FormatError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,568 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspect the artifacts which Solang produces: the sections, exports and imports of a wasm
+//! or Solana ELF binary, and the metadata and dispatch table from the `.contract` file or the
+//! Anchor IDL. This helps to verify what was actually deployed.
+
+use crate::abi::anchor::function_discriminator;
+use crate::build_info::{self, BuildInfo};
+use crate::metadata::{self, MetadataHash};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use wasmparser::{ExternalKind, Parser, Payload, TypeRef};
+
+#[derive(Serialize, Debug, Default)]
+pub struct Inspection {
+    /// Either `wasm` or `elf`
+    pub format: &'static str,
+    /// The size of the binary in bytes
+    pub size: usize,
+    /// Fields from the metadata, like the contract name, version and compiler
+    pub metadata: BTreeMap<String, String>,
+    pub dispatch: Vec<DispatchEntry>,
+    pub sections: Vec<Section>,
+    pub exports: Vec<String>,
+    /// Host functions on wasm, and syscalls on Solana
+    pub imports: Vec<Import>,
+    /// The build information, if it was embedded with `--embed-build-info`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build_info: Option<BuildInfo>,
+    /// The hash of the solc style metadata, if it was embedded with `--metadata`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata_hash: Option<MetadataHash>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct DispatchEntry {
+    /// `constructor`, `message` or `instruction`
+    pub kind: &'static str,
+    pub name: String,
+    pub selector: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Import {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    pub name: String,
+}
+
+/// Inspect a wasm binary, for Polkadot, Soroban or Stylus
+pub fn inspect_wasm(code: &[u8]) -> Result<Inspection, String> {
+    let mut inspection = Inspection {
+        format: "wasm",
+        size: code.len(),
+        ..Default::default()
+    };
+
+    for payload in Parser::new(0).parse_all(code) {
+        let payload = payload.map_err(|err| format!("invalid wasm: {err}"))?;
+
+        match &payload {
+            Payload::ImportSection(reader) => {
+                for import in reader.clone() {
+                    let import = import.map_err(|err| format!("invalid wasm: {err}"))?;
+
+                    if let TypeRef::Func(_) = import.ty {
+                        inspection.imports.push(Import {
+                            module: Some(import.module.to_string()),
+                            name: import.name.to_string(),
+                        });
+                    }
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader.clone() {
+                    let export = export.map_err(|err| format!("invalid wasm: {err}"))?;
+
+                    if export.kind == ExternalKind::Func {
+                        inspection.exports.push(export.name.to_string());
+                    }
+                }
+            }
+            Payload::CustomSection(reader) => {
+                inspection.sections.push(Section {
+                    name: format!("custom {}", reader.name()),
+                    size: reader.data().len(),
+                });
+
+                continue;
+            }
+            _ => (),
+        }
+
+        if let Some((id, range)) = payload.as_section() {
+            inspection.sections.push(Section {
+                name: wasm_section_name(id).to_string(),
+                size: range.len(),
+            });
+        }
+    }
+
+    inspection.build_info = build_info::extract(code)?;
+    inspection.metadata_hash = metadata::extract(code)?;
+
+    Ok(inspection)
+}
+
+fn wasm_section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        _ => "unknown",
+    }
+}
+
+const SHT_DYNSYM: u32 = 11;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+
+/// Inspect a Solana program, which is a 64 bit little endian ELF shared object
+pub fn inspect_elf(code: &[u8]) -> Result<Inspection, String> {
+    let elf = Elf::new(code)?;
+    let sections = elf.sections()?;
+
+    let mut inspection = Inspection {
+        format: "elf",
+        size: code.len(),
+        ..Default::default()
+    };
+
+    // section 0 is always empty
+    for section in sections.iter().skip(1) {
+        inspection.sections.push(Section {
+            name: section.name.clone(),
+            size: section.size,
+        });
+
+        if section.ty != SHT_DYNSYM {
+            continue;
+        }
+
+        // the symbol names are in the string table given by sh_link
+        let strings_offset = sections
+            .get(section.link)
+            .ok_or_else(|| format!("section {} links to a missing section", section.name))?
+            .offset;
+
+        if section.entry_size == 0 {
+            return Err(format!("section {} has no entry size", section.name));
+        }
+
+        let symbols = section.offset..section.offset.saturating_add(section.size);
+
+        for symbol in symbols.step_by(section.entry_size) {
+            let name = elf.string(strings_offset.saturating_add(elf.u32(symbol)? as usize))?;
+            let binding = elf.u8(symbol + 4)? >> 4;
+            let symbol_section = elf.u16(symbol + 6)?;
+
+            if name.is_empty() || !matches!(binding, STB_GLOBAL | STB_WEAK) {
+                continue;
+            }
+
+            if symbol_section == 0 {
+                inspection.imports.push(Import { module: None, name });
+            } else {
+                inspection.exports.push(name);
+            }
+        }
+    }
+
+    inspection.build_info = build_info::extract(code)?;
+    inspection.metadata_hash = metadata::extract(code)?;
+
+    Ok(inspection)
+}
+
+/// Bounds checked reads from an ELF file
+pub(crate) struct Elf<'a> {
+    pub code: &'a [u8],
+    pub section_offset: usize,
+    pub section_header_size: usize,
+    pub section_count: usize,
+    /// The section which has the names of the sections
+    pub string_section: usize,
+}
+
+/// A section header from an ELF file
+pub(crate) struct ElfSection {
+    pub name: String,
+    pub ty: u32,
+    pub flags: u64,
+    /// The address of the section once loaded
+    pub addr: u64,
+    pub offset: usize,
+    pub size: usize,
+    pub link: usize,
+    pub align: usize,
+    pub entry_size: usize,
+}
+
+impl<'a> Elf<'a> {
+    pub fn new(code: &'a [u8]) -> Result<Self, String> {
+        if code.get(..4) != Some(b"\x7fELF") {
+            return Err("not an ELF file".into());
+        }
+
+        if code.get(4) != Some(&2) || code.get(5) != Some(&1) {
+            return Err("not a 64 bit little endian ELF file".into());
+        }
+
+        let mut elf = Elf {
+            code,
+            section_offset: 0,
+            section_header_size: 0,
+            section_count: 0,
+            string_section: 0,
+        };
+
+        elf.section_offset = elf.u64(0x28)? as usize;
+        elf.section_header_size = elf.u16(0x3a)? as usize;
+        elf.section_count = elf.u16(0x3c)? as usize;
+        elf.string_section = elf.u16(0x3e)? as usize;
+
+        Ok(elf)
+    }
+
+    /// The offset of a section header
+    pub fn header(&self, section_no: usize) -> usize {
+        self.section_offset
+            .saturating_add(section_no.saturating_mul(self.section_header_size))
+    }
+
+    /// All the section headers, including the empty section 0
+    pub fn sections(&self) -> Result<Vec<ElfSection>, String> {
+        let names_offset = self.u64(self.header(self.string_section) + 24)? as usize;
+
+        (0..self.section_count)
+            .map(|section_no| {
+                let header = self.header(section_no);
+
+                Ok(ElfSection {
+                    name: self.string(names_offset.saturating_add(self.u32(header)? as usize))?,
+                    ty: self.u32(header + 4)?,
+                    flags: self.u64(header + 8)?,
+                    addr: self.u64(header + 16)?,
+                    offset: self.u64(header + 24)? as usize,
+                    size: self.u64(header + 32)? as usize,
+                    link: self.u32(header + 40)? as usize,
+                    align: self.u64(header + 48)? as usize,
+                    entry_size: self.u64(header + 56)? as usize,
+                })
+            })
+            .collect()
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], String> {
+        offset
+            .checked_add(N)
+            .and_then(|end| self.code.get(offset..end))
+            .map(|bytes| bytes.try_into().unwrap())
+            .ok_or_else(|| format!("ELF file truncated at offset {offset}"))
+    }
+
+    pub fn u8(&self, offset: usize) -> Result<u8, String> {
+        self.bytes::<1>(offset).map(|bytes| bytes[0])
+    }
+
+    pub fn u16(&self, offset: usize) -> Result<u16, String> {
+        self.bytes(offset).map(u16::from_le_bytes)
+    }
+
+    pub fn u32(&self, offset: usize) -> Result<u32, String> {
+        self.bytes(offset).map(u32::from_le_bytes)
+    }
+
+    pub fn u64(&self, offset: usize) -> Result<u64, String> {
+        self.bytes(offset).map(u64::from_le_bytes)
+    }
+
+    /// A nul terminated string
+    pub fn string(&self, offset: usize) -> Result<String, String> {
+        let bytes = self
+            .code
+            .get(offset..)
+            .ok_or_else(|| format!("ELF file truncated at offset {offset}"))?;
+
+        let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+        Ok(String::from_utf8_lossy(&bytes[..len]).into_owned())
+    }
+}
+
+/// Inspect a Polkadot `.contract` file, which has the metadata and the wasm code
+pub fn inspect_contract(contents: &str) -> Result<Inspection, String> {
+    let json: Value =
+        serde_json::from_str(contents).map_err(|err| format!("invalid contract file: {err}"))?;
+
+    let mut inspection = inspect_wasm(&contract_code(&json)?)?;
+
+    inspection.add_ink_metadata(&json);
+
+    Ok(inspection)
+}
+
+/// The wasm code from a Polkadot `.contract` file
+pub fn contract_code(json: &Value) -> Result<Vec<u8>, String> {
+    let wasm = json["source"]["wasm"]
+        .as_str()
+        .ok_or("contract file does not contain the wasm code")?;
+
+    hex::decode(wasm.trim_start_matches("0x"))
+        .map_err(|err| format!("invalid wasm code in contract file: {err}"))
+}
+
+impl Inspection {
+    /// Add the metadata and dispatch table from the ink! metadata of a Polkadot contract
+    pub fn add_ink_metadata(&mut self, json: &Value) {
+        for (key, value) in [
+            ("name", &json["contract"]["name"]),
+            ("version", &json["contract"]["version"]),
+            ("compiler", &json["source"]["compiler"]),
+            ("language", &json["source"]["language"]),
+            ("hash", &json["source"]["hash"]),
+        ] {
+            if let Some(value) = value.as_str() {
+                self.metadata.insert(key.into(), value.into());
+            }
+        }
+
+        for (kind, entries) in [
+            ("constructor", &json["spec"]["constructors"]),
+            ("message", &json["spec"]["messages"]),
+        ] {
+            for entry in entries.as_array().into_iter().flatten() {
+                if let (Some(name), Some(selector)) =
+                    (entry["label"].as_str(), entry["selector"].as_str())
+                {
+                    self.dispatch.push(DispatchEntry {
+                        kind,
+                        name: name.into(),
+                        selector: selector.into(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Add the metadata and dispatch table from the Anchor IDL of a Solana program. The IDL does
+    /// not list the discriminators, so they are calculated from the instruction names.
+    pub fn add_anchor_idl(&mut self, idl: &Value) {
+        for (key, value) in [
+            ("name", &idl["name"]),
+            ("version", &idl["version"]),
+            ("address", &idl["metadata"]["address"]),
+        ] {
+            if let Some(value) = value.as_str() {
+                self.metadata.insert(key.into(), value.into());
+            }
+        }
+
+        for instruction in idl["instructions"].as_array().into_iter().flatten() {
+            if let Some(name) = instruction["name"].as_str() {
+                self.dispatch.push(DispatchEntry {
+                    kind: if name == "new" {
+                        "constructor"
+                    } else {
+                        "instruction"
+                    },
+                    name: name.into(),
+                    selector: format!("0x{}", hex::encode(function_discriminator(name))),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wasm_encoder::{
+        CodeSection, CustomSection, EntityType, ExportKind, ExportSection, Function,
+        FunctionSection, ImportSection, Instruction, MemoryType, Module, TypeSection,
+    };
+
+    fn wasm() -> Vec<u8> {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.function([], []);
+        module.section(&types);
+
+        let mut imports = ImportSection::new();
+        imports.import("seal0", "seal_return", EntityType::Function(0));
+        imports.import(
+            "env",
+            "memory",
+            EntityType::Memory(MemoryType {
+                minimum: 1,
+                maximum: None,
+                memory64: false,
+                shared: false,
+            }),
+        );
+        module.section(&imports);
+
+        let mut functions = FunctionSection::new();
+        functions.function(0);
+        functions.function(0);
+        module.section(&functions);
+
+        let mut exports = ExportSection::new();
+        exports.export("deploy", ExportKind::Func, 1);
+        exports.export("call", ExportKind::Func, 2);
+        module.section(&exports);
+
+        let mut code = CodeSection::new();
+        for _ in 0..2 {
+            let mut function = Function::new([]);
+            function.instruction(&Instruction::Call(0));
+            function.instruction(&Instruction::End);
+            code.function(&function);
+        }
+        module.section(&code);
+
+        module.section(&CustomSection {
+            name: "name".into(),
+            data: [0u8; 3].as_slice().into(),
+        });
+
+        module.finish()
+    }
+
+    #[test]
+    fn wasm_binary() {
+        let code = wasm();
+        let inspection = inspect_wasm(&code).unwrap();
+
+        assert_eq!(inspection.format, "wasm");
+        assert_eq!(inspection.size, code.len());
+        assert_eq!(inspection.exports, vec!["deploy", "call"]);
+        assert_eq!(
+            inspection.imports,
+            vec![Import {
+                module: Some("seal0".into()),
+                name: "seal_return".into()
+            }]
+        );
+
+        let sections: Vec<&str> = inspection
+            .sections
+            .iter()
+            .map(|section| section.name.as_str())
+            .collect();
+
+        assert_eq!(
+            sections,
+            vec![
+                "type",
+                "import",
+                "function",
+                "export",
+                "code",
+                "custom name"
+            ]
+        );
+        assert_eq!(inspection.sections[5].size, 3);
+
+        assert!(inspect_wasm(b"\0asm\x01").is_err());
+        assert!(inspect_elf(&code).is_err());
+    }
+
+    #[test]
+    fn contract_file() {
+        let contract = json!({
+            "source": {
+                "hash": "0x1234",
+                "language": "Solidity 0.8.0",
+                "compiler": "solang 0.3.3",
+                "wasm": format!("0x{}", hex::encode(wasm())),
+            },
+            "contract": {
+                "name": "flipper",
+                "version": "0.0.1",
+            },
+            "spec": {
+                "constructors": [{ "label": "new", "selector": "0x9bae9d5e" }],
+                "messages": [
+                    { "label": "flip", "selector": "0xcde4efa9" },
+                    { "label": "get", "selector": "0x6d4ce63c" },
+                ],
+            },
+        });
+
+        let inspection = inspect_contract(&contract.to_string()).unwrap();
+
+        assert_eq!(inspection.metadata["name"], "flipper");
+        assert_eq!(inspection.metadata["compiler"], "solang 0.3.3");
+        assert_eq!(inspection.metadata.len(), 5);
+        assert_eq!(
+            inspection.dispatch,
+            vec![
+                DispatchEntry {
+                    kind: "constructor",
+                    name: "new".into(),
+                    selector: "0x9bae9d5e".into()
+                },
+                DispatchEntry {
+                    kind: "message",
+                    name: "flip".into(),
+                    selector: "0xcde4efa9".into()
+                },
+                DispatchEntry {
+                    kind: "message",
+                    name: "get".into(),
+                    selector: "0x6d4ce63c".into()
+                },
+            ]
+        );
+        assert_eq!(inspection.exports, vec!["deploy", "call"]);
+
+        assert_eq!(
+            inspect_contract("{}").unwrap_err(),
+            "contract file does not contain the wasm code"
+        );
+    }
+
+    #[test]
+    fn anchor_idl() {
+        let mut inspection = Inspection::default();
+
+        inspection.add_anchor_idl(&json!({
+            "version": "0.1.0",
+            "name": "flipper",
+            "instructions": [{ "name": "new" }, { "name": "flip" }],
+            "metadata": { "address": "F1ipperKF9EfD821ZbbYjS319LXYiBmjhzkkf5a26rC" },
+        }));
+
+        assert_eq!(inspection.metadata["version"], "0.1.0");
+        assert_eq!(
+            inspection.metadata["address"],
+            "F1ipperKF9EfD821ZbbYjS319LXYiBmjhzkkf5a26rC"
+        );
+        assert_eq!(inspection.dispatch[0].kind, "constructor");
+        assert_eq!(inspection.dispatch[1].kind, "instruction");
+        assert_eq!(
+            inspection.dispatch[1].selector,
+            format!("0x{}", hex::encode(function_discriminator("flip")))
+        );
+    }
+}
@@ -1,13 +1,29 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod abi;
+pub mod build_info;
 pub mod codegen;
+pub mod coverage;
 #[cfg(feature = "llvm")]
 pub mod emit;
 pub mod file_resolver;
+pub mod flatten;
+#[cfg(feature = "formatter")]
+pub mod formatter;
+pub mod inspect;
 #[cfg(feature = "llvm")]
 mod linker;
+pub mod metadata;
+pub mod sarif;
+pub mod session;
+pub mod size_report;
+pub mod source_map;
 pub mod standard_json;
+pub mod target;
+#[cfg(feature = "test_runner")]
+pub mod test_runner;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 // In Sema, we use result unit for returning early
 // when code-misparses. The error will be added to the namespace diagnostics, no need to have anything but unit
@@ -33,6 +49,8 @@ pub enum Target {
     /// Ethereum EVM, see <https://ethereum.org/en/developers/docs/evm/>
     EVM,
     Soroban,
+    /// Arbitrum Stylus, see <https://arbitrum.io/stylus>
+    Stylus,
 }
 
 impl fmt::Display for Target {
@@ -42,6 +60,7 @@ impl fmt::Display for Target {
             Target::Polkadot { .. } => write!(f, "Polkadot"),
             Target::EVM => write!(f, "EVM"),
             Target::Soroban => write!(f, "Soroban"),
+            Target::Stylus => write!(f, "Stylus"),
         }
     }
 }
@@ -55,6 +74,7 @@ impl PartialEq for Target {
             Target::Polkadot { .. } => matches!(other, Target::Polkadot { .. }),
             Target::EVM => matches!(other, Target::EVM),
             Target::Soroban => matches!(other, Target::Soroban),
+            Target::Stylus => matches!(other, Target::Stylus),
         }
     }
 }
@@ -79,6 +99,7 @@ impl Target {
             "solana" => Some(Target::Solana),
             "polkadot" => Some(Target::default_polkadot()),
             "evm" => Some(Target::EVM),
+            "stylus" => Some(Target::Stylus),
             _ => None,
         }
     }
@@ -104,6 +125,12 @@ impl Target {
         }
     }
 
+    /// Can immutable variables be part of the code, rather than storage. On Solana, the code of
+    /// a program is shared by all its data accounts, so immutables are always in account data.
+    pub fn immutables_in_code(&self) -> bool {
+        matches!(self, Target::Polkadot { .. } | Target::EVM)
+    }
+
     /// This function returns the byte length for a selector, given the target
     pub fn selector_length(&self) -> u8 {
         match self {
@@ -169,8 +196,16 @@ pub fn parse_and_resolve(
     resolver: &mut FileResolver,
     target: Target,
 ) -> sema::ast::Namespace {
-    let mut ns = sema::ast::Namespace::new(target);
+    parse_and_resolve_into(filename, resolver, sema::ast::Namespace::new(target))
+}
 
+/// Like [`parse_and_resolve`], into a namespace which was created for the target and configured
+/// beforehand, e.g. with strict conversions.
+pub fn parse_and_resolve_into(
+    filename: &OsStr,
+    resolver: &mut FileResolver,
+    mut ns: sema::ast::Namespace,
+) -> sema::ast::Namespace {
     match resolver.resolve_file(None, filename) {
         Err(message) => {
             ns.diagnostics.push(sema::ast::Diagnostic {
@@ -100,9 +100,12 @@ fn generate_import_section(section: SectionLimited<Import>, module: &mut Module)
         };
         let module_name = match import.name {
             "memory" => import.module,
-            "set_storage" => "seal2",
-            "clear_storage" | "contains_storage" | "get_storage" | "instantiate" | "terminate"
-            | "seal_call" => "seal1",
+            "set_storage" | "instantiate" => "seal2",
+            "clear_storage" | "contains_storage" | "get_storage" | "terminate" | "seal_call" => {
+                "seal1"
+            }
+            // imports declared with a module in a `@syscall` annotation
+            _ if import.module != "env" => import.module,
             _ => "seal0",
         };
         imports.import(module_name, import.name, import_type);
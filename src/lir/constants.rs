@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a constant in the constant pool holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConstantKind {
+    /// The contents of a string or bytes literal
+    Bytes,
+    /// The elements of a constant array, in the layout they have in memory
+    Array,
+    /// The selector of a public function
+    Selector,
+}
+
+/// Constant data which is placed in read-only data, rather than built each time it is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constant {
+    pub kind: ConstantKind,
+    pub data: Vec<u8>,
+}
+
+/// The constant data of a module. Adding the same data more than once returns the same
+/// constant, so each literal is only present once however often it is used.
+#[derive(Debug, Default, Clone)]
+pub struct ConstantPool {
+    constants: Vec<Constant>,
+    lookup: HashMap<(ConstantKind, Vec<u8>), usize>,
+}
+
+impl ConstantPool {
+    /// Add constant data to the pool, and return its number
+    pub fn add(&mut self, kind: ConstantKind, data: &[u8]) -> usize {
+        if let Some(global_no) = self.lookup.get(&(kind, data.to_vec())) {
+            return *global_no;
+        }
+
+        let global_no = self.constants.len();
+
+        self.constants.push(Constant {
+            kind,
+            data: data.to_vec(),
+        });
+        self.lookup.insert((kind, data.to_vec()), global_no);
+
+        global_no
+    }
+
+    /// Get a constant by its number
+    pub fn get(&self, global_no: usize) -> &Constant {
+        &self.constants[global_no]
+    }
+
+    /// The constants, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &Constant> {
+        self.constants.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.constants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constants.is_empty()
+    }
+}
+
+impl fmt::Display for ConstantKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstantKind::Bytes => write!(f, "bytes"),
+            ConstantKind::Array => write!(f, "array"),
+            ConstantKind::Selector => write!(f, "selector"),
+        }
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} hex\"{}\"", self.kind, hex::encode(&self.data))
+    }
+}
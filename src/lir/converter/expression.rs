@@ -5,11 +5,14 @@ use core::panic;
 use solang_parser::pt::Loc;
 
 use crate::codegen;
+use crate::lir::constants::ConstantKind;
 use crate::lir::converter::Converter;
 use crate::lir::expressions::{BinaryOperator, Expression, Operand, UnaryOperator};
 use crate::lir::instructions::Instruction;
 use crate::lir::vartable::Vartable;
 use crate::sema::ast;
+use num_bigint::Sign;
+use num_traits::ToPrimitive;
 
 impl Converter<'_> {
     /**
@@ -135,9 +138,11 @@ impl Converter<'_> {
                 left,
                 right,
                 ty,
-                ..
+                overflowing,
             } => {
-                let operator = BinaryOperator::Div;
+                let operator = BinaryOperator::Div {
+                    overflowing: *overflowing,
+                };
                 self.binary_operation(dest, loc, ty, operator, left, right, vartable, results)
             }
             codegen::Expression::Equal {
@@ -406,9 +411,7 @@ impl Converter<'_> {
                 };
                 self.unary_operation(dest, loc, operator, expr, vartable, results)
             }
-            codegen::Expression::Undefined { .. } => {
-                panic!("Undefined expression shouldn't be here")
-            }
+            codegen::Expression::Undefined { .. } => self.undefined(dest, results),
             codegen::Expression::Variable { loc, var_no, .. } => {
                 self.variable(dest, loc, var_no, results)
             }
@@ -736,6 +739,20 @@ impl Converter<'_> {
         vartable: &mut Vartable,
         results: &mut Vec<Instruction>,
     ) {
+        // an array of literals is placed in the constant pool
+        if let Some(data) = self.const_array_data(values) {
+            results.push(Instruction::Set {
+                loc: *loc,
+                res: dest.get_id_or_error(),
+                expr: Expression::GlobalRef {
+                    loc: *loc,
+                    ty: self.lower_ast_type(ty),
+                    global_no: self.add_constant(ConstantKind::Array, &data),
+                },
+            });
+            return;
+        }
+
         let value_ops = values
             .iter()
             .map(|value| self.to_operand_and_insns(value, vartable, results))
@@ -884,11 +901,39 @@ impl Converter<'_> {
                 loc: *loc,
                 ty: self.lower_ast_type(ty),
                 size: Box::new(size_op),
-                initializer: initializer.clone(),
+                initializer: initializer
+                    .as_ref()
+                    .map(|data| self.add_constant(ConstantKind::Bytes, data)),
             },
         });
     }
 
+    /// The elements of a constant array in the layout they have in memory, if they are all
+    /// literals. Integers are little endian, like the targets.
+    fn const_array_data(&self, values: &[codegen::Expression]) -> Option<Vec<u8>> {
+        let mut data = Vec::new();
+
+        for value in values {
+            match value {
+                codegen::Expression::NumberLiteral { ty, value, .. } => {
+                    let size = ty.memory_size_of(self.ns).to_usize()?;
+                    let fill = if value.sign() == Sign::Minus { 0xff } else { 0 };
+                    let mut bytes = value.to_signed_bytes_le();
+
+                    bytes.resize(size, fill);
+                    data.extend(bytes);
+                }
+                codegen::Expression::BoolLiteral { value, .. } => data.push(*value as u8),
+                codegen::Expression::BytesLiteral { value, .. } => {
+                    data.extend(value.iter().rev());
+                }
+                _ => return None,
+            }
+        }
+
+        Some(data)
+    }
+
     fn bytes_literal(
         &self,
         dest: &Operand,
@@ -1000,6 +1045,14 @@ impl Converter<'_> {
         });
     }
 
+    fn undefined(&self, dest: &Operand, results: &mut Vec<Instruction>) {
+        results.push(Instruction::Set {
+            loc: Loc::Codegen,
+            res: dest.get_id_or_error(),
+            expr: Expression::Undefined { loc: Loc::Codegen },
+        });
+    }
+
     fn return_data(&self, dest: &Operand, loc: &Loc, results: &mut Vec<Instruction>) {
         results.push(Instruction::Set {
             loc: *loc,
@@ -44,11 +44,15 @@ impl Converter<'_> {
                 });
             }
             Instr::PushMemory {
-                res, array, value, ..
+                loc,
+                res,
+                array,
+                value,
+                ..
             } => {
                 let value_op = self.to_operand_and_insns(value, vartable, results);
                 results.push(Instruction::PushMemory {
-                    loc: /*missing from cfg*/ Loc::Codegen,
+                    loc: *loc,
                     res: *res,
                     array: *array,
                     value: value_op,
@@ -63,6 +67,16 @@ impl Converter<'_> {
                     loc: *loc,
                 });
             }
+            Instr::ReserveMemory {
+                loc, array, size, ..
+            } => {
+                let size_op = self.to_operand_and_insns(size, vartable, results);
+                results.push(Instruction::ReserveMemory {
+                    loc: *loc,
+                    array: *array,
+                    size: size_op,
+                });
+            }
 
             Instr::Branch { block } => {
                 results.push(Instruction::Branch {
@@ -349,6 +363,7 @@ impl Converter<'_> {
                 address,
                 seeds,
                 accounts,
+                storage_deposit_limit,
                 loc,
             } => {
                 let args_op = self.to_operand_and_insns(encoded_args, vartable, results);
@@ -359,6 +374,9 @@ impl Converter<'_> {
                 let seeds_op = self.to_operand_option_and_insns(seeds, vartable, results);
                 let accounts =
                     self.to_external_call_accounts_and_insns(accounts, vartable, results);
+                let storage_deposit_limit_op = self
+                    .to_operand_option_and_insns(storage_deposit_limit, vartable, results)
+                    .map(Box::new);
 
                 results.push(Instruction::Constructor {
                     loc: *loc,
@@ -373,6 +391,7 @@ impl Converter<'_> {
                     address: address_op,
                     seeds: seeds_op,
                     accounts,
+                    storage_deposit_limit: storage_deposit_limit_op,
                 });
             }
         }
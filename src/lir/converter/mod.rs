@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::codegen::cfg::BasicBlock;
+use crate::lir::constants::{ConstantKind, ConstantPool};
 use crate::lir::{Block, LIR};
 use crate::{
     codegen::{
@@ -13,6 +14,7 @@ use super::lir_type::LIRType;
 use super::{
     expressions::Operand, instructions::Instruction, lir_type::InternalCallTy, vartable::Vartable,
 };
+use std::cell::RefCell;
 
 mod expression;
 mod instruction;
@@ -27,12 +29,38 @@ pub struct Converter<'a> {
     ns: &'a Namespace,
     /// a reference to the ControlFlowGraph is used to retrieve the instructions.
     cfg: &'a ControlFlowGraph,
+    /// the constant data used by the function is added to this pool.
+    constants: RefCell<ConstantPool>,
 }
 
 impl<'input> Converter<'input> {
     /// Create a new Converter with a reference to the Namespace and the ControlFlowGraph.
     pub fn new(ns: &'input Namespace, cfg: &'input ControlFlowGraph) -> Self {
-        Self { ns, cfg }
+        Self::with_constants(ns, cfg, ConstantPool::default())
+    }
+
+    /// Create a new Converter which adds the constant data of the function to an existing
+    /// pool, so that the functions of a module share their constants.
+    pub fn with_constants(
+        ns: &'input Namespace,
+        cfg: &'input ControlFlowGraph,
+        constants: ConstantPool,
+    ) -> Self {
+        Self {
+            ns,
+            cfg,
+            constants: RefCell::new(constants),
+        }
+    }
+
+    /// The constant pool, with the constants used by the converted function.
+    pub fn into_constants(self) -> ConstantPool {
+        self.constants.into_inner()
+    }
+
+    /// add constant data to the constant pool, and return its number.
+    pub(crate) fn add_constant(&self, kind: ConstantKind, data: &[u8]) -> usize {
+        self.constants.borrow_mut().add(kind, data)
     }
 
     /// get the selector length from the Namespace.
@@ -212,6 +240,11 @@ impl<'input> Converter<'input> {
             .map(|p| self.to_lir_typed_parameter(p))
             .collect::<Vec<Parameter<LIRType>>>();
 
+        // the dispatcher compares the selector with the input
+        if self.cfg.public && !self.cfg.selector.is_empty() {
+            self.add_constant(ConstantKind::Selector, &self.cfg.selector);
+        }
+
         LIR {
             name: self.cfg.name.clone(),
             function_no: self.cfg.function_no,
@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! An estimate of the worst-case cost of the external functions of a contract, from the LIR.
+//! Each instruction is given a fixed cost from a simple model of the target, so the result is
+//! only a guide: the size of data which is hashed, encoded or stored is not taken into account,
+//! and every loop is counted as if it runs once.
+
+use crate::codegen::cfg::{ASTFunction, ControlFlowGraph};
+use crate::codegen::Builtin;
+use crate::lir::converter::Converter;
+use crate::lir::expressions::Expression;
+use crate::lir::instructions::Instruction;
+use crate::lir::lir_type::InternalCallTy;
+use crate::lir::LIR;
+use crate::sema::ast::Namespace;
+use crate::Target;
+use serde::Serialize;
+use solang_parser::pt::FunctionTy;
+use std::ops::AddAssign;
+
+/// The cost of some code, broken down by category
+#[derive(Serialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Cost {
+    pub storage: u64,
+    pub hashing: u64,
+    pub encoding: u64,
+    pub calls: u64,
+    pub events: u64,
+    pub compute: u64,
+}
+
+impl Cost {
+    pub fn total(&self) -> u64 {
+        self.storage + self.hashing + self.encoding + self.calls + self.events + self.compute
+    }
+}
+
+impl AddAssign for Cost {
+    fn add_assign(&mut self, other: Cost) {
+        self.storage += other.storage;
+        self.hashing += other.hashing;
+        self.encoding += other.encoding;
+        self.calls += other.calls;
+        self.events += other.events;
+        self.compute += other.compute;
+    }
+}
+
+#[derive(Serialize)]
+pub struct CostReport {
+    pub contract: String,
+    pub target: String,
+    /// What the costs are measured in, e.g. compute units on Solana
+    pub unit: &'static str,
+    pub functions: Vec<FunctionCost>,
+}
+
+#[derive(Serialize)]
+pub struct FunctionCost {
+    pub function: String,
+    pub selector: String,
+    pub total: u64,
+    pub cost: Cost,
+    /// False if the function has loops, recursion, or calls through function pointers, so the
+    /// cost could be higher than estimated
+    pub bounded: bool,
+}
+
+/// The cost of each kind of instruction on a target
+struct CostModel {
+    unit: &'static str,
+    instruction: u64,
+    storage_load: u64,
+    storage_store: u64,
+    hash: u64,
+    signature_verify: u64,
+    encode: u64,
+    call: u64,
+    create: u64,
+    transfer: u64,
+    event: u64,
+    event_topic: u64,
+    host_function: u64,
+}
+
+impl CostModel {
+    fn new(target: Target) -> Self {
+        match target {
+            // weight in picoseconds of ref_time, using the database weights of substrate
+            Target::Polkadot { .. } => CostModel {
+                unit: "weight",
+                instruction: 5_000,
+                storage_load: 25_000_000,
+                storage_store: 100_000_000,
+                hash: 2_000_000,
+                signature_verify: 50_000_000,
+                encode: 20_000,
+                call: 400_000_000,
+                create: 1_000_000_000,
+                transfer: 200_000_000,
+                event: 5_000_000,
+                event_topic: 1_000_000,
+                host_function: 1_000_000,
+            },
+            // compute units, where a cross program invocation costs 1000
+            Target::Solana => CostModel {
+                unit: "compute units",
+                instruction: 2,
+                storage_load: 10,
+                storage_store: 10,
+                hash: 100,
+                signature_verify: 25_000,
+                encode: 5,
+                call: 1_000,
+                create: 2_500,
+                transfer: 1_200,
+                event: 100,
+                event_topic: 0,
+                host_function: 100,
+            },
+            Target::Soroban => CostModel {
+                unit: "cpu instructions",
+                instruction: 4,
+                storage_load: 5_000,
+                storage_store: 10_000,
+                hash: 3_000,
+                signature_verify: 400_000,
+                encode: 50,
+                call: 20_000,
+                create: 50_000,
+                transfer: 20_000,
+                event: 5_000,
+                event_topic: 1_000,
+                host_function: 1_000,
+            },
+            // gas, with cold storage and account access
+            Target::EVM | Target::Stylus => CostModel {
+                unit: "gas",
+                instruction: 3,
+                storage_load: 2_100,
+                storage_store: 20_000,
+                hash: 36,
+                signature_verify: 3_000,
+                encode: 6,
+                call: 2_600,
+                create: 32_000,
+                transfer: 9_000,
+                event: 375,
+                event_topic: 375,
+                host_function: 100,
+            },
+        }
+    }
+}
+
+/// Estimate the worst-case cost of each external function of a contract. Codegen must have
+/// been run.
+pub fn gen_cost_report(contract_no: usize, ns: &Namespace) -> CostReport {
+    let contract = &ns.contracts[contract_no];
+    let model = CostModel::new(ns.target);
+
+    let mut estimator = Estimator {
+        model: &model,
+        ns,
+        cfgs: &contract.cfg,
+        function_costs: vec![None; contract.cfg.len()],
+        in_progress: vec![false; contract.cfg.len()],
+    };
+
+    let functions = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| cfg.public && !cfg.is_placeholder())
+        .map(|(cfg_no, cfg)| {
+            let (cost, bounded) = estimator.function_cost(cfg_no);
+
+            FunctionCost {
+                function: function_name(cfg, ns),
+                selector: format!("0x{}", hex::encode(&cfg.selector)),
+                total: cost.total(),
+                cost,
+                bounded,
+            }
+        })
+        .collect();
+
+    CostReport {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        unit: model.unit,
+        functions,
+    }
+}
+
+pub(super) fn function_name(cfg: &ControlFlowGraph, ns: &Namespace) -> String {
+    let function_no = match cfg.function_no {
+        ASTFunction::SolidityFunction(function_no) => function_no,
+        ASTFunction::YulFunction(function_no) => {
+            return format!("{}()", ns.yul_functions[function_no].name)
+        }
+        // the default constructor
+        ASTFunction::None => return format!("{}()", cfg.ty),
+    };
+
+    let func = &ns.functions[function_no];
+
+    match func.ty {
+        FunctionTy::Function => func.signature.clone(),
+        FunctionTy::Constructor => format!("constructor{}", func.signature),
+        ty => format!("{ty}()"),
+    }
+}
+
+struct Estimator<'a> {
+    model: &'a CostModel,
+    ns: &'a Namespace,
+    cfgs: &'a [ControlFlowGraph],
+    /// The worst-case cost of each cfg, once known
+    function_costs: Vec<Option<(Cost, bool)>>,
+    /// To detect recursion
+    in_progress: Vec<bool>,
+}
+
+impl Estimator<'_> {
+    /// The most expensive path through a function, and whether that path is bounded
+    fn function_cost(&mut self, cfg_no: usize) -> (Cost, bool) {
+        if let Some(cost) = self.function_costs[cfg_no] {
+            return cost;
+        }
+
+        if self.in_progress[cfg_no] {
+            // recursion
+            return (Cost::default(), false);
+        }
+
+        let cfg = &self.cfgs[cfg_no];
+
+        if cfg.is_placeholder() {
+            return (Cost::default(), true);
+        }
+
+        self.in_progress[cfg_no] = true;
+
+        let lir = Converter::new(self.ns, cfg).get_lir();
+
+        let mut bounded = true;
+
+        let block_costs: Vec<Cost> = lir
+            .blocks
+            .iter()
+            .map(|block| {
+                let mut cost = Cost::default();
+
+                for instr in &block.instructions {
+                    bounded &= self.instruction_cost(instr, &mut cost);
+                }
+
+                cost
+            })
+            .collect();
+
+        let mut path = PathFinder {
+            lir: &lir,
+            block_costs: &block_costs,
+            worst: vec![None; lir.blocks.len()],
+            on_stack: vec![false; lir.blocks.len()],
+            bounded: true,
+        };
+
+        let cost = path.worst_path(0);
+        let result = (cost, bounded && path.bounded);
+
+        self.in_progress[cfg_no] = false;
+        self.function_costs[cfg_no] = Some(result);
+
+        result
+    }
+
+    /// Add the cost of an instruction. Returns false if the cost is not bounded.
+    fn instruction_cost(&mut self, instr: &Instruction, cost: &mut Cost) -> bool {
+        let model = self.model;
+
+        match instr {
+            Instruction::Nop | Instruction::Phi { .. } => (),
+            Instruction::LoadStorage { .. } => cost.storage += model.storage_load,
+            Instruction::SetStorage { .. }
+            | Instruction::SetStorageBytes { .. }
+            | Instruction::ClearStorage { .. } => cost.storage += model.storage_store,
+            Instruction::PushStorage { .. } | Instruction::PopStorage { .. } => {
+                // read the length, then write the element and the length
+                cost.storage += model.storage_load + 2 * model.storage_store
+            }
+            Instruction::WriteBuffer { .. } => cost.encoding += model.encode,
+            Instruction::ExternalCall { .. } => cost.calls += model.call,
+            Instruction::Constructor { .. } => cost.calls += model.create,
+            Instruction::ValueTransfer { .. } | Instruction::SelfDestruct { .. } => {
+                cost.calls += model.transfer
+            }
+            Instruction::EmitEvent { topics, .. } => {
+                cost.events += model.event + topics.len() as u64 * model.event_topic
+            }
+            Instruction::Print { .. } => cost.events += model.event,
+            Instruction::Set { expr, .. } => self.expression_cost(expr, cost),
+            Instruction::Call { call, .. } => {
+                cost.compute += model.instruction;
+
+                match call {
+                    InternalCallTy::Static { cfg_no } => {
+                        let (callee, bounded) = self.function_cost(*cfg_no);
+
+                        *cost += callee;
+
+                        return bounded;
+                    }
+                    InternalCallTy::Dynamic(_) => return false,
+                    InternalCallTy::Builtin { .. } => cost.compute += model.host_function,
+                }
+            }
+            _ => cost.compute += model.instruction,
+        }
+
+        true
+    }
+
+    fn expression_cost(&self, expr: &Expression, cost: &mut Cost) {
+        let model = self.model;
+
+        match expr {
+            Expression::Keccak256 { .. } => cost.hashing += model.hash,
+            Expression::StorageArrayLength { .. } => cost.storage += model.storage_load,
+            Expression::Builtin { kind, .. } => match kind {
+                Builtin::Keccak256
+                | Builtin::Sha256
+                | Builtin::Ripemd160
+                | Builtin::Blake2_128
+                | Builtin::Blake2_256 => cost.hashing += model.hash,
+                Builtin::SignatureVerify => cost.hashing += model.signature_verify,
+                Builtin::ReadFromBuffer
+                | Builtin::SliceFromBuffer
+                | Builtin::WriteAddress
+                | Builtin::WriteInt8
+                | Builtin::WriteInt16LE
+                | Builtin::WriteInt32LE
+                | Builtin::WriteInt64LE
+                | Builtin::WriteInt128LE
+                | Builtin::WriteInt256LE
+                | Builtin::WriteUint16LE
+                | Builtin::WriteUint32LE
+                | Builtin::WriteUint64LE
+                | Builtin::WriteUint128LE
+                | Builtin::WriteUint256LE
+                | Builtin::WriteBytes => cost.encoding += model.encode,
+                Builtin::ArrayLength
+                | Builtin::Concat
+                | Builtin::AddMod
+                | Builtin::MulMod
+                | Builtin::MulDiv
+                | Builtin::Sqrt
+                | Builtin::Log2
+                | Builtin::IndexOf
+                | Builtin::ParseInt => cost.compute += model.instruction,
+                // everything else asks the runtime
+                _ => cost.compute += model.host_function,
+            },
+            _ => cost.compute += model.instruction,
+        }
+    }
+}
+
+/// Finds the most expensive path through the blocks of a function. Back edges are not followed,
+/// so the body of a loop is counted once.
+struct PathFinder<'a> {
+    lir: &'a LIR,
+    block_costs: &'a [Cost],
+    worst: Vec<Option<Cost>>,
+    on_stack: Vec<bool>,
+    bounded: bool,
+}
+
+impl PathFinder<'_> {
+    fn worst_path(&mut self, block_no: usize) -> Cost {
+        if let Some(cost) = self.worst[block_no] {
+            return cost;
+        }
+
+        self.on_stack[block_no] = true;
+
+        let mut worst_successor = Cost::default();
+
+        for successor in successors(self.lir, block_no) {
+            if self.on_stack[successor] {
+                // a loop
+                self.bounded = false;
+                continue;
+            }
+
+            let cost = self.worst_path(successor);
+
+            if cost.total() > worst_successor.total() {
+                worst_successor = cost;
+            }
+        }
+
+        self.on_stack[block_no] = false;
+
+        let mut cost = self.block_costs[block_no];
+        cost += worst_successor;
+
+        self.worst[block_no] = Some(cost);
+
+        cost
+    }
+}
+
+pub(super) fn successors(lir: &LIR, block_no: usize) -> Vec<usize> {
+    match lir.blocks[block_no].instructions.last() {
+        Some(Instruction::Branch { block, .. }) => vec![*block],
+        Some(Instruction::BranchCond {
+            true_block,
+            false_block,
+            ..
+        }) => vec![*true_block, *false_block],
+        Some(Instruction::Switch { cases, default, .. }) => cases
+            .iter()
+            .map(|(_, block)| *block)
+            .chain(std::iter::once(*default))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
@@ -35,7 +35,7 @@ pub enum BinaryOperator {
     Mul { overflowing: bool },
     Pow { overflowing: bool },
 
-    Div,
+    Div { overflowing: bool },
     UDiv,
 
     Mod,
@@ -153,11 +153,18 @@ pub enum Expression {
         to_ty: LIRType,
     },
 
+    /// Allocate a vector, which may be initialized with a constant from the constant pool
     AllocDynamicBytes {
         loc: Loc,
         ty: LIRType,
         size: Box<Operand>,
-        initializer: Option<Vec<u8>>,
+        initializer: Option<usize>,
+    },
+    /// A pointer to a constant in the constant pool, which is in read-only data
+    GlobalRef {
+        loc: Loc,
+        ty: LIRType,
+        global_no: usize,
     },
 
     /// address-of
@@ -231,6 +238,10 @@ pub enum Expression {
     ReturnData {
         loc: Loc,
     },
+    // A value which has not been initialized, e.g. a variable declared without a value
+    Undefined {
+        loc: Loc,
+    },
 }
 
 impl fmt::Display for BinaryOperator {
@@ -248,7 +259,9 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::Pow { overflowing } => {
                 write!(f, "{}", if *overflowing { "(of)**" } else { "**" })
             }
-            BinaryOperator::Div => write!(f, "/"),
+            BinaryOperator::Div { overflowing } => {
+                write!(f, "{}", if *overflowing { "(of)/" } else { "/" })
+            }
             // example: uint8 a = b (u)/ c
             BinaryOperator::UDiv => write!(f, "(u)/"),
             BinaryOperator::Mod => write!(f, "%"),
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The heap of contracts: its size on Solana, and the sites in each function which allocate
+//! memory on it, from the LIR. On Solana the heap is 32KB unless the transaction requests a
+//! larger heap frame, and with the default bump allocator memory is not reclaimed during an
+//! instruction, so allocations in loops are the usual cause of running out of heap.
+//!
+//! A contract can be built for a larger heap with `@custom:heap-size <bytes>`; transactions
+//! which call it must then request a heap frame of at least that size.
+
+use crate::codegen::cfg::ControlFlowGraph;
+use crate::codegen::Builtin;
+use crate::lir::converter::Converter;
+use crate::lir::cost::successors;
+use crate::lir::expressions::{Expression, Operand};
+use crate::lir::instructions::Instruction;
+use crate::lir::lir_type::LIRType;
+use crate::lir::stack::cfg_name;
+use crate::lir::LIR;
+use crate::sema::ast::{Namespace, Type};
+use crate::sema::diagnostics::Diagnostics;
+use crate::sema::file::PathDisplay;
+use crate::Target;
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use solang_parser::diagnostics::Diagnostic;
+use solang_parser::pt::Loc;
+
+/// The tag which sets the size of the heap of a contract on Solana
+pub const HEAP_SIZE_TAG: &str = "custom:heap-size";
+
+/// The heap of a Solana program, unless the transaction requests a larger heap frame
+pub const DEFAULT_HEAP_SIZE: u32 = 32 * 1024;
+
+/// The largest heap frame a transaction can request
+pub const MAX_HEAP_SIZE: u32 = 256 * 1024;
+
+/// The length and capacity in front of the elements of a dynamic array, string or bytes
+const VECTOR_HEADER_SIZE: u64 = 8;
+
+#[derive(Serialize)]
+pub struct HeapReport {
+    pub contract: String,
+    pub target: String,
+    /// The size of the heap, on Solana
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heap_size: Option<u32>,
+    /// The functions which allocate memory
+    pub functions: Vec<FunctionAllocations>,
+}
+
+#[derive(Serialize)]
+pub struct FunctionAllocations {
+    pub function: String,
+    pub public: bool,
+    /// The bytes allocated by the sites with a known size, if each of them runs once
+    pub fixed: u64,
+    pub sites: Vec<AllocationSite>,
+}
+
+#[derive(Serialize)]
+pub struct AllocationSite {
+    pub loc: String,
+    /// What is allocated, e.g. `struct literal`
+    pub kind: &'static str,
+    /// The number of bytes, if known at compile time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// True if the site is in a loop, so it may allocate many times
+    pub in_loop: bool,
+}
+
+/// The size of the heap of a contract
+pub fn heap_size(contract_no: usize, ns: &Namespace) -> u32 {
+    heap_size_tag(contract_no, ns)
+        .and_then(Result::ok)
+        .unwrap_or(DEFAULT_HEAP_SIZE)
+}
+
+/// Give an error for each Solana contract with an invalid heap size
+pub fn check_heap_sizes(ns: &mut Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for contract_no in 0..ns.contracts.len() {
+        if ns.contracts[contract_no].instantiable {
+            if let Some(Err(diagnostic)) = heap_size_tag(contract_no, ns) {
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The heap size of a contract, if it has one
+fn heap_size_tag(contract_no: usize, ns: &Namespace) -> Option<Result<u32, Diagnostic>> {
+    let tag = ns.contracts[contract_no]
+        .tags
+        .iter()
+        .find(|tag| tag.tag == HEAP_SIZE_TAG)?;
+
+    let size = tag
+        .value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|size| (DEFAULT_HEAP_SIZE..=MAX_HEAP_SIZE).contains(size) && size % 1024 == 0);
+
+    Some(size.ok_or_else(|| {
+        Diagnostic::error(
+            tag.loc,
+            format!(
+                "invalid heap size '{}', expected a multiple of 1024 bytes from {DEFAULT_HEAP_SIZE} \
+                to {MAX_HEAP_SIZE}",
+                tag.value
+            ),
+        )
+    }))
+}
+
+/// Find the sites in each function of a contract which allocate memory on the heap. Codegen must
+/// have been run.
+pub fn gen_heap_report(contract_no: usize, ns: &Namespace) -> HeapReport {
+    let contract = &ns.contracts[contract_no];
+
+    let functions = contract
+        .cfg
+        .iter()
+        .filter(|cfg| !cfg.is_placeholder())
+        .filter_map(|cfg| {
+            let sites = allocation_sites(cfg, ns);
+
+            if sites.is_empty() {
+                return None;
+            }
+
+            Some(FunctionAllocations {
+                function: cfg_name(cfg, ns),
+                public: cfg.public,
+                fixed: sites.iter().filter_map(|site| site.size).sum(),
+                sites,
+            })
+        })
+        .collect();
+
+    HeapReport {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        heap_size: (ns.target == Target::Solana).then(|| heap_size(contract_no, ns)),
+        functions,
+    }
+}
+
+fn allocation_sites(cfg: &ControlFlowGraph, ns: &Namespace) -> Vec<AllocationSite> {
+    let lir = Converter::new(ns, cfg).get_lir();
+    let in_loop = blocks_in_loops(&lir);
+
+    let mut sites = Vec::new();
+
+    for (block_no, block) in lir.blocks.iter().enumerate() {
+        for instr in &block.instructions {
+            if let Some((loc, kind, size)) = allocation(instr, &lir, ns) {
+                sites.push((*loc, kind, size, in_loop[block_no]));
+            }
+        }
+    }
+
+    // in the order of the source, rather than the order of the blocks
+    sites.sort_by_key(|(loc, ..)| *loc);
+
+    sites
+        .into_iter()
+        .map(|(loc, kind, size, in_loop)| AllocationSite {
+            loc: ns.loc_to_string(PathDisplay::Filename, &loc),
+            kind,
+            size,
+            in_loop,
+        })
+        .collect()
+}
+
+/// What an instruction allocates, and how many bytes if that is known
+fn allocation<'a>(
+    instr: &'a Instruction,
+    lir: &LIR,
+    ns: &Namespace,
+) -> Option<(&'a Loc, &'static str, Option<u64>)> {
+    match instr {
+        Instruction::Set { loc, expr, .. } => match expr {
+            Expression::AllocDynamicBytes { ty, size, .. } => {
+                Some((loc, "new", vector_size(ty, size, ns)))
+            }
+            Expression::StructLiteral { ty, .. } => Some((
+                loc,
+                "struct literal",
+                ty.ast_type.memory_size_of(ns).to_u64(),
+            )),
+            Expression::ArrayLiteral { ty, .. } => Some((
+                loc,
+                "array literal",
+                ty.ast_type.memory_size_of(ns).to_u64(),
+            )),
+            Expression::FormatString { .. } => Some((loc, "format string", None)),
+            Expression::StringConcat { .. }
+            | Expression::Builtin {
+                kind: Builtin::Concat,
+                ..
+            } => Some((loc, "concatenation", None)),
+            _ => None,
+        },
+        Instruction::PushMemory { loc, .. } => Some((loc, "push", None)),
+        Instruction::ReserveMemory { loc, array, size } => Some((
+            loc,
+            "reserve",
+            vector_size(lir.vartable.get_type(array), size, ns),
+        )),
+        Instruction::LoadStorage { loc, res, .. }
+            if lir.vartable.get_type(res).ast_type.is_dynamic(ns) =>
+        {
+            Some((loc, "storage load", None))
+        }
+        _ => None,
+    }
+}
+
+/// The size of a new dynamic array, string or bytes, if it has a constant length
+fn vector_size(ty: &LIRType, length: &Operand, ns: &Namespace) -> Option<u64> {
+    let Operand::NumberLiteral { value, .. } = length else {
+        return None;
+    };
+
+    let elem_size = match &ty.ast_type {
+        Type::String | Type::DynamicBytes => 1,
+        Type::Array(..) | Type::Slice(_) => ty.ast_type.array_elem().memory_size_of(ns).to_u64()?,
+        _ => return None,
+    };
+
+    Some(VECTOR_HEADER_SIZE + value.to_u64()? * elem_size)
+}
+
+/// For each block, whether it is part of a loop, i.e. it can reach itself
+fn blocks_in_loops(lir: &LIR) -> Vec<bool> {
+    (0..lir.blocks.len())
+        .map(|start| {
+            let mut visited = vec![false; lir.blocks.len()];
+            let mut todo = successors(lir, start);
+
+            while let Some(block_no) = todo.pop() {
+                if block_no == start {
+                    return true;
+                }
+
+                if !visited[block_no] {
+                    visited[block_no] = true;
+                    todo.extend(successors(lir, block_no));
+                }
+            }
+
+            false
+        })
+        .collect()
+}
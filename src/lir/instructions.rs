@@ -45,6 +45,11 @@ pub enum Instruction {
         res: usize,
         array: usize,
     },
+    ReserveMemory {
+        loc: Loc,
+        array: usize,
+        size: Operand,
+    },
     Constructor {
         loc: Loc,
         success: Option<usize>,
@@ -58,6 +63,7 @@ pub enum Instruction {
         address: Option<Operand>,
         seeds: Option<Operand>,
         accounts: ExternalCallAccounts<Operand>,
+        storage_deposit_limit: Option<Box<Operand>>,
     },
 
     LoadStorage {
@@ -1,17 +1,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod constants;
 pub mod converter;
+pub mod cost;
 pub mod expressions;
+pub mod heap;
 pub mod instructions;
 pub mod lir_type;
 pub mod printer;
+pub mod stack;
 pub mod vartable;
 
 use crate::codegen::cfg::ASTFunction;
+use crate::lir::constants::ConstantPool;
+use crate::lir::converter::Converter;
 use crate::lir::instructions::Instruction;
 use crate::lir::vartable::Vartable;
 use crate::pt::FunctionTy;
-use crate::sema::ast::Parameter;
+use crate::sema::ast::{Contract, Namespace, Parameter};
 
 use self::lir_type::LIRType;
 
@@ -49,3 +55,37 @@ pub struct Block {
     /// The instructions in the block.
     pub instructions: Vec<Instruction>,
 }
+
+/// The functions of a contract in the Lower Intermediate Representation, with the constant data
+/// they share. `Expression::GlobalRef` refers to the constants by their number in the pool.
+#[derive(Debug)]
+pub struct Module {
+    /// The name of the contract.
+    pub name: String,
+    /// The string literals, constant arrays and selectors used by the functions.
+    pub constants: ConstantPool,
+    /// The functions of the contract.
+    pub functions: Vec<LIR>,
+}
+
+impl Module {
+    /// Lower all the functions of a contract. Codegen must have been run.
+    pub fn new(contract: &Contract, ns: &Namespace) -> Self {
+        let mut constants = ConstantPool::default();
+        let mut functions = Vec::new();
+
+        for cfg in contract.cfg.iter().filter(|cfg| !cfg.is_placeholder()) {
+            let converter = Converter::with_constants(ns, cfg, constants);
+
+            functions.push(converter.get_lir());
+
+            constants = converter.into_constants();
+        }
+
+        Module {
+            name: contract.id.name.clone(),
+            constants,
+            functions,
+        }
+    }
+}
@@ -131,27 +131,16 @@ impl Printer<'_> {
                 initializer,
                 ..
             } => {
-                if initializer.is_none() {
-                    write!(f, "alloc {}[", ty).unwrap();
-                    self.print_rhs_operand(f, size);
-                    return write!(f, "]").unwrap();
-                }
-
                 write!(f, "alloc {}[", ty).unwrap();
                 self.print_rhs_operand(f, size);
-                write!(f, "] {{").unwrap();
-                initializer
-                    .as_ref()
-                    .unwrap()
-                    .iter()
-                    .enumerate()
-                    .for_each(|(i, byte)| {
-                        if i != 0 {
-                            write!(f, ", ").unwrap();
-                        }
-                        write!(f, "{:02x}", byte).unwrap();
-                    });
-                write!(f, "}}").unwrap();
+                write!(f, "]").unwrap();
+
+                if let Some(global_no) = initializer {
+                    write!(f, " @{}", global_no).unwrap();
+                }
+            }
+            Expression::GlobalRef { ty, global_no, .. } => {
+                write!(f, "{} @{}", ty, global_no).unwrap();
             }
             Expression::GetRef { operand, .. } => {
                 write!(f, "&").unwrap();
@@ -253,6 +242,7 @@ impl Printer<'_> {
                 write!(f, ")").unwrap();
             }
             Expression::ReturnData { .. } => write!(f, "(extern_call_ret_data)").unwrap(),
+            Expression::Undefined { .. } => write!(f, "undef").unwrap(),
             Expression::NumberLiteral { value, .. } => {
                 write!(f, "{}", value).unwrap();
             }
@@ -58,6 +58,14 @@ impl Printer<'_> {
                 self.print_rhs_operand(f, &array_op);
                 write!(f, ";").unwrap();
             }
+            Instruction::ReserveMemory { array, size, .. } => {
+                let array_op = self.get_var_operand(array);
+                write!(f, "reserve_mem ").unwrap();
+                self.print_rhs_operand(f, &array_op);
+                write!(f, " ").unwrap();
+                self.print_rhs_operand(f, size);
+                write!(f, ";").unwrap();
+            }
             Instruction::Constructor {
                 success,
                 res,
@@ -70,6 +78,7 @@ impl Printer<'_> {
                 seeds,
                 accounts,
                 constructor_no,
+                storage_deposit_limit,
                 ..
             } => {
                 // success
@@ -162,6 +171,11 @@ impl Printer<'_> {
                         write!(f, "accounts:absent").unwrap()
                     }
                 }
+
+                if let Some(limit) = storage_deposit_limit {
+                    write!(f, " storage_deposit_limit:").unwrap();
+                    self.print_rhs_operand(f, limit);
+                }
             }
             Instruction::LoadStorage { res, storage, .. } => {
                 let res_op = self.get_var_operand(res);
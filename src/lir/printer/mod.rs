@@ -3,8 +3,10 @@
 use super::expressions::Operand;
 use super::lir_type::LIRType;
 use crate::codegen::cfg::ASTFunction;
+use crate::lir::constants::ConstantPool;
 use crate::lir::vartable::Vartable;
-use crate::lir::{Block, LIR};
+use crate::lir::{Block, Module, LIR};
+use crate::sema::ast::{Contract, Namespace};
 use std::io::Write;
 
 pub mod expression;
@@ -78,6 +80,13 @@ impl<'a> Printer<'a> {
         }
     }
 
+    /// print the constant pool, which the functions refer to with `@<number>`.
+    pub fn print_constants(f: &mut dyn Write, constants: &ConstantPool) {
+        for (global_no, constant) in constants.iter().enumerate() {
+            writeln!(f, "@{} = {}", global_no, constant).unwrap();
+        }
+    }
+
     pub fn print_block(&self, f: &mut dyn Write, block: &Block) {
         for insn in &block.instructions {
             write!(f, "    ").unwrap();
@@ -86,3 +95,26 @@ impl<'a> Printer<'a> {
         }
     }
 }
+
+impl Contract {
+    /// Print the LIR of all the functions of the contract. Codegen must have been run.
+    pub fn print_lir(&self, ns: &Namespace) -> String {
+        let mut out = Vec::new();
+
+        writeln!(out, "#\n# Contract: {}\n#", self.id).unwrap();
+
+        let module = Module::new(self, ns);
+
+        if !module.constants.is_empty() {
+            writeln!(out).unwrap();
+            Printer::print_constants(&mut out, &module.constants);
+        }
+
+        for lir in &module.functions {
+            writeln!(out).unwrap();
+            Printer::new(&lir.vartable).print_lir(&mut out, lir);
+        }
+
+        String::from_utf8(out).unwrap()
+    }
+}
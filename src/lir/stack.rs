@@ -0,0 +1,363 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! An estimate of the stack used by the functions of a contract, from the LIR. The frame of a
+//! function is estimated from the sizes of its variables, and the stack used by a function is its
+//! frame plus the stack used by the deepest chain of internal calls it makes. Many temporaries
+//! end up in registers, so the estimate is an upper bound.
+//!
+//! A function can be given a budget with `@custom:stack-budget <bytes>`, and the build fails if
+//! the function may use more stack than that.
+
+use crate::codegen::cfg::{ASTFunction, ControlFlowGraph};
+use crate::lir::converter::Converter;
+use crate::lir::cost::function_name;
+use crate::lir::instructions::Instruction;
+use crate::lir::lir_type::{InternalCallTy, StructType, Type};
+use crate::sema::ast::{ArrayLength, Namespace};
+use crate::sema::diagnostics::Diagnostics;
+use crate::Target;
+use num_traits::ToPrimitive;
+use serde::Serialize;
+use solang_parser::diagnostics::{Diagnostic, Note};
+use solang_parser::pt::FunctionTy;
+
+/// The tag which sets the stack budget of a function
+pub const STACK_BUDGET_TAG: &str = "custom:stack-budget";
+
+#[derive(Serialize)]
+pub struct StackReport {
+    pub contract: String,
+    pub target: String,
+    /// The largest frame a function may have, if the target has a limit
+    pub frame_limit: Option<u64>,
+    /// The deepest calls may be nested, if the target has a limit
+    pub call_depth_limit: Option<usize>,
+    pub functions: Vec<FunctionStack>,
+}
+
+#[derive(Serialize)]
+pub struct FunctionStack {
+    pub function: String,
+    pub public: bool,
+    /// The estimated size of the frame of the function, in bytes
+    pub frame: u64,
+    /// The estimated stack used by the function and the functions it calls, in bytes
+    pub stack: u64,
+    /// The number of frames on the deepest call path, including the function itself
+    pub depth: usize,
+    /// The functions on the call path which uses the most stack
+    pub path: Vec<String>,
+    /// False if the function may recurse or call through function pointers, so the stack could
+    /// be larger than estimated
+    pub bounded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<u64>,
+}
+
+/// The limits on the stack of a target. On Solana, each frame is 4KB and calls can be nested
+/// 64 deep.
+fn limits(target: Target) -> (Option<u64>, Option<usize>) {
+    match target {
+        Target::Solana => (Some(4096), Some(64)),
+        _ => (None, None),
+    }
+}
+
+/// Estimate the stack usage of each function of a contract. Codegen must have been run.
+pub fn gen_stack_report(contract_no: usize, ns: &Namespace) -> StackReport {
+    let contract = &ns.contracts[contract_no];
+    let (frame_limit, call_depth_limit) = limits(ns.target);
+
+    let mut estimator = Estimator::new(ns, &contract.cfg);
+
+    let functions = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| !cfg.is_placeholder())
+        .map(|(cfg_no, cfg)| {
+            let usage = estimator.stack(cfg_no);
+
+            FunctionStack {
+                function: cfg_name(cfg, ns),
+                public: cfg.public,
+                frame: usage.frame,
+                stack: usage.stack,
+                depth: usage.path.len(),
+                path: usage
+                    .path
+                    .iter()
+                    .map(|cfg_no| cfg_name(&contract.cfg[*cfg_no], ns))
+                    .collect(),
+                bounded: usage.bounded,
+                budget: cfg_function(cfg)
+                    .and_then(|function_no| budget(function_no, ns))
+                    .and_then(Result::ok),
+            }
+        })
+        .collect();
+
+    StackReport {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        frame_limit,
+        call_depth_limit,
+        functions,
+    }
+}
+
+/// Check the functions of each contract which have a stack budget, and give an error for each
+/// function which may exceed its budget. Codegen must have been run.
+pub fn check_stack_budgets(ns: &mut Namespace) {
+    let mut diagnostics = Diagnostics::default();
+
+    for contract in &ns.contracts {
+        if !contract.instantiable {
+            continue;
+        }
+
+        let mut estimator = Estimator::new(ns, &contract.cfg);
+
+        // the cfg of a function with modifiers is the first modifier
+        for (function_no, cfg_no) in &contract.all_functions {
+            let func = &ns.functions[*function_no];
+
+            let budget = match budget(*function_no, ns) {
+                Some(Ok(budget)) => budget,
+                Some(Err(diagnostic)) => {
+                    diagnostics.push(diagnostic);
+                    continue;
+                }
+                None => continue,
+            };
+
+            let usage = estimator.stack(*cfg_no);
+
+            if !usage.bounded {
+                diagnostics.push(Diagnostic::error(
+                    func.loc_prototype,
+                    format!(
+                        "function '{}' has a stack budget of {budget} bytes, but its stack usage \
+                        is unbounded because of recursion or calls through function pointers",
+                        func.id
+                    ),
+                ));
+            } else if usage.stack > budget {
+                let notes = usage
+                    .path
+                    .iter()
+                    .skip(1)
+                    .filter_map(|cfg_no| {
+                        let function_no = cfg_function(&contract.cfg[*cfg_no])?;
+
+                        Some(Note {
+                            loc: ns.functions[function_no].loc_prototype,
+                            message: format!(
+                                "calls function '{}' with a frame of {} bytes",
+                                ns.functions[function_no].id,
+                                estimator.stack(*cfg_no).frame
+                            ),
+                        })
+                    })
+                    .collect();
+
+                diagnostics.push(Diagnostic::error_with_notes(
+                    func.loc_prototype,
+                    format!(
+                        "function '{}' may use {} bytes of stack, which exceeds its stack budget \
+                        of {budget} bytes",
+                        func.id, usage.stack
+                    ),
+                    notes,
+                ));
+            }
+        }
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// The stack budget of a function, if it has one
+fn budget(function_no: usize, ns: &Namespace) -> Option<Result<u64, Diagnostic>> {
+    let tag = ns.functions[function_no]
+        .tags
+        .iter()
+        .find(|tag| tag.tag == STACK_BUDGET_TAG)?;
+
+    Some(tag.value.trim().parse().map_err(|_| {
+        Diagnostic::error(
+            tag.loc,
+            format!(
+                "invalid stack budget '{}', expected a number of bytes",
+                tag.value
+            ),
+        )
+    }))
+}
+
+/// The Solidity function of a cfg, which is the function a modifier is applied to for the
+/// first modifier
+fn cfg_function(cfg: &ControlFlowGraph) -> Option<usize> {
+    match (cfg.modifier, &cfg.function_no) {
+        (Some(function_no), _) => Some(function_no),
+        (None, ASTFunction::SolidityFunction(function_no)) => Some(*function_no),
+        _ => None,
+    }
+}
+
+pub(super) fn cfg_name(cfg: &ControlFlowGraph, ns: &Namespace) -> String {
+    match (cfg_function(cfg), &cfg.function_no) {
+        (Some(function_no), _) if ns.functions[function_no].ty == FunctionTy::Function => {
+            ns.functions[function_no].signature.clone()
+        }
+        // the modifiers after the first one
+        (None, ASTFunction::None) if cfg.ty == FunctionTy::Function => cfg.name.clone(),
+        _ => function_name(cfg, ns),
+    }
+}
+
+#[derive(Clone)]
+struct StackUsage {
+    frame: u64,
+    stack: u64,
+    /// The cfgs on the call path which uses the most stack
+    path: Vec<usize>,
+    bounded: bool,
+}
+
+struct Estimator<'a> {
+    ns: &'a Namespace,
+    cfgs: &'a [ControlFlowGraph],
+    /// The stack usage of each cfg, once known
+    usage: Vec<Option<StackUsage>>,
+    /// To detect recursion
+    in_progress: Vec<bool>,
+}
+
+impl<'a> Estimator<'a> {
+    fn new(ns: &'a Namespace, cfgs: &'a [ControlFlowGraph]) -> Self {
+        Estimator {
+            ns,
+            cfgs,
+            usage: vec![None; cfgs.len()],
+            in_progress: vec![false; cfgs.len()],
+        }
+    }
+
+    /// The stack used by a function and the deepest chain of calls it makes
+    fn stack(&mut self, cfg_no: usize) -> StackUsage {
+        if let Some(usage) = &self.usage[cfg_no] {
+            return usage.clone();
+        }
+
+        let cfg = &self.cfgs[cfg_no];
+
+        if self.in_progress[cfg_no] || cfg.is_placeholder() {
+            // recursion is not bounded; placeholders have no code
+            return StackUsage {
+                frame: 0,
+                stack: 0,
+                path: Vec::new(),
+                bounded: !self.in_progress[cfg_no],
+            };
+        }
+
+        self.in_progress[cfg_no] = true;
+
+        let lir = Converter::new(self.ns, cfg).get_lir();
+
+        let frame = lir
+            .vartable
+            .vars
+            .values()
+            .map(|var| self.slot_size(&var.ty.lir_type))
+            .sum();
+
+        let mut deepest: Option<StackUsage> = None;
+        let mut bounded = true;
+
+        for instr in lir.blocks.iter().flat_map(|block| &block.instructions) {
+            let Instruction::Call { call, .. } = instr else {
+                continue;
+            };
+
+            match call {
+                InternalCallTy::Static { cfg_no } => {
+                    let callee = self.stack(*cfg_no);
+
+                    bounded &= callee.bounded;
+
+                    if deepest
+                        .as_ref()
+                        .map_or(true, |deepest| callee.stack > deepest.stack)
+                    {
+                        deepest = Some(callee);
+                    }
+                }
+                InternalCallTy::Dynamic(_) => bounded = false,
+                InternalCallTy::Builtin { .. } => (),
+            }
+        }
+
+        let mut path = vec![cfg_no];
+        let mut stack = frame;
+
+        if let Some(deepest) = deepest {
+            stack += deepest.stack;
+            path.extend(deepest.path);
+        }
+
+        let usage = StackUsage {
+            frame,
+            stack,
+            path,
+            bounded,
+        };
+
+        self.in_progress[cfg_no] = false;
+        self.usage[cfg_no] = Some(usage.clone());
+
+        usage
+    }
+
+    /// The size of a variable on the stack, rounded up to the size of a pointer
+    fn slot_size(&self, ty: &Type) -> u64 {
+        let pointer_size = (self.ns.target.ptr_size() / 8) as u64;
+        let size = self.size_of(ty);
+
+        (size + pointer_size - 1) / pointer_size * pointer_size
+    }
+
+    fn size_of(&self, ty: &Type) -> u64 {
+        let ns = self.ns;
+        let pointer_size = (ns.target.ptr_size() / 8) as u64;
+
+        match ty {
+            Type::Bool => 1,
+            Type::Int(bits) | Type::Uint(bits) => (*bits as u64 + 7) / 8,
+            Type::Bytes(n) => *n as u64,
+            Type::Array(elem, dims) => dims
+                .iter()
+                .try_fold(self.size_of(elem), |size, dim| match dim {
+                    ArrayLength::Fixed(n) => n.to_u64().map(|n| size * n),
+                    _ => None,
+                })
+                // dynamic arrays are pointers
+                .unwrap_or(pointer_size),
+            Type::Struct(StructType::UserDefined(struct_no)) => ns.structs[*struct_no]
+                .fields
+                .iter()
+                .map(|field| field.ty.memory_size_of(ns).to_u64().unwrap_or(0))
+                .sum(),
+            // address and selector
+            Type::Struct(StructType::ExternalFunction) => ns.address_length as u64 + 4,
+            // pointer and length
+            Type::Slice(_) => 2 * pointer_size,
+            Type::Ptr(_)
+            | Type::StoragePtr(..)
+            | Type::Function { .. }
+            | Type::Mapping { .. }
+            | Type::Struct(_) => pointer_size,
+        }
+    }
+}
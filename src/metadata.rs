@@ -0,0 +1,442 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Metadata in the same style as solc, so that verification services can work with Solang
+//! output. The metadata is a canonical json file with the compiler, the settings, the hashes of
+//! the sources, and the hash of the code. The keys of json objects are sorted, and there is no
+//! whitespace, so the same build always gives the same metadata.
+//!
+//! The IPFS hash of the metadata is embedded in the contract, CBOR encoded like the metadata
+//! which solc appends to EVM code. On wasm, this is a custom section, and on Solana a section
+//! which is not loaded. The sources have IPFS urls too, so the metadata and sources can be
+//! published and found by their hashes.
+
+use crate::build_info::{self, keccak256, source_path, BuildInfo, BuildOptions};
+use crate::codegen::Options;
+use crate::file_resolver::FileResolver;
+use crate::sema::ast::Namespace;
+use base58::ToBase58;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+/// The name of the wasm custom section with the metadata hash. On Solana, the ELF section has
+/// the same name, with a leading dot.
+pub const SECTION_NAME: &str = "solang.meta";
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Metadata {
+    pub compiler: Compiler,
+    pub language: String,
+    pub output: Output,
+    pub settings: Settings,
+    /// The sources by path, relative to the file which was compiled or to its import path
+    pub sources: BTreeMap<String, Source>,
+    pub version: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Compiler {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Output {
+    /// The keccak256 hash of the code, without the metadata hash
+    pub keccak256: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    /// The path of the source with the contract, and the name of the contract
+    pub compilation_target: BTreeMap<String, String>,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub address_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value_length: Option<usize>,
+    pub options: BuildOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct Source {
+    pub keccak256: String,
+    pub urls: Vec<String>,
+}
+
+/// The metadata hash which is embedded in a contract
+#[derive(Serialize, Debug, PartialEq, Eq, Clone)]
+pub struct MetadataHash {
+    /// The IPFS hash of the metadata, base58 encoded
+    pub ipfs: String,
+    /// The version of Solang
+    pub solang: String,
+}
+
+impl Metadata {
+    /// The metadata of a contract, with the code it was compiled to. The sources are all the
+    /// files which were read to compile the contract.
+    pub fn new(
+        contract_no: usize,
+        ns: &Namespace,
+        resolver: &FileResolver,
+        opt: &Options,
+        code: &[u8],
+    ) -> Self {
+        let top_dir = ns.files[ns.top_file_no()].path.parent();
+        let path = |file_no: usize| {
+            let file = &ns.files[file_no];
+
+            source_path(&file.path, file.import_no, top_dir, resolver)
+        };
+
+        let sources = ns
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(file_no, file)| {
+                let contents = resolver.get_contents_of_file_no(file.cache_no?)?;
+
+                Some((
+                    path(file_no),
+                    Source {
+                        keccak256: format!("0x{}", hex::encode(keccak256(contents.as_bytes()))),
+                        urls: vec![format!("dweb:/ipfs/{}", ipfs_hash(contents.as_bytes()))],
+                    },
+                ))
+            })
+            .collect();
+
+        let contract = &ns.contracts[contract_no];
+        let info = BuildInfo::new(contract_no, ns, resolver, opt);
+
+        Metadata {
+            compiler: Compiler {
+                name: "solang".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
+            },
+            language: "Solidity".into(),
+            output: Output {
+                keccak256: format!("0x{}", hex::encode(keccak256(code))),
+            },
+            settings: Settings {
+                compilation_target: BTreeMap::from([(
+                    path(contract.loc.file_no()),
+                    contract.id.name.clone(),
+                )]),
+                target: info.target,
+                address_length: info.address_length,
+                value_length: info.value_length,
+                options: info.options,
+            },
+            sources,
+            version: 1,
+        }
+    }
+
+    /// The canonical json of the metadata
+    pub fn canonical_json(&self) -> String {
+        // the objects of a json value are sorted by key
+        serde_json::to_value(self).unwrap().to_string()
+    }
+}
+
+/// Embed the hash of the metadata in a wasm module or Solana ELF file. Like solc, this is a CBOR
+/// map with the IPFS hash of the metadata and the compiler version, followed by the length of the
+/// CBOR as a big endian 16 bit number.
+pub fn embed(code: &[u8], metadata: &Metadata) -> Result<Vec<u8>, String> {
+    let json = metadata.canonical_json();
+
+    let mut data = Vec::new();
+
+    // a map with two entries
+    data.push(0xa2);
+    cbor_text(&mut data, "ipfs");
+    cbor_header(&mut data, 0x40, 34);
+    data.extend_from_slice(&ipfs_multihash(json.as_bytes()));
+    cbor_text(&mut data, "solang");
+    cbor_text(&mut data, &metadata.compiler.version);
+
+    let len = data.len() as u16;
+    data.extend_from_slice(&len.to_be_bytes());
+
+    build_info::embed_section(code, SECTION_NAME, &data)
+}
+
+/// Read the metadata hash from a wasm module or Solana ELF file, if it has one
+pub fn extract(code: &[u8]) -> Result<Option<MetadataHash>, String> {
+    build_info::extract_section(code, SECTION_NAME)?
+        .map(|data| decode(data).ok_or_else(|| "invalid metadata hash".to_string()))
+        .transpose()
+}
+
+fn decode(data: &[u8]) -> Option<MetadataHash> {
+    let (cbor, len) = data.split_at(data.len().checked_sub(2)?);
+
+    if u16::from_be_bytes([len[0], len[1]]) as usize != cbor.len() {
+        return None;
+    }
+
+    let mut reader = CborReader { data: cbor };
+
+    let (0xa0, entries) = reader.header()? else {
+        return None;
+    };
+
+    let mut ipfs = None;
+    let mut solang = None;
+
+    for _ in 0..entries {
+        let key = reader.text()?;
+
+        match key {
+            "ipfs" => ipfs = Some(reader.bytes()?.to_base58()),
+            "solang" => solang = Some(reader.text()?.to_string()),
+            _ => return None,
+        }
+    }
+
+    if !reader.data.is_empty() {
+        return None;
+    }
+
+    Some(MetadataHash {
+        ipfs: ipfs?,
+        solang: solang?,
+    })
+}
+
+/// The header of a CBOR item, with the major type in the top three bits
+fn cbor_header(out: &mut Vec<u8>, major: u8, len: usize) {
+    match len {
+        0..=23 => out.push(major | len as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, len as u8]),
+        _ => {
+            out.push(major | 25);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+    }
+}
+
+fn cbor_text(out: &mut Vec<u8>, text: &str) {
+    cbor_header(out, 0x60, text.len());
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// Reads the CBOR items which [`embed`] writes
+struct CborReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CborReader<'a> {
+    /// The major type and the length
+    fn header(&mut self) -> Option<(u8, usize)> {
+        let (first, rest) = self.data.split_first()?;
+        let major = first & 0xe0;
+
+        let (len, rest) = match first & 0x1f {
+            len @ 0..=23 => (len as usize, rest),
+            24 => (*rest.first()? as usize, rest.get(1..)?),
+            25 => (
+                u16::from_be_bytes([*rest.first()?, *rest.get(1)?]) as usize,
+                rest.get(2..)?,
+            ),
+            _ => return None,
+        };
+
+        self.data = rest;
+
+        Some((major, len))
+    }
+
+    fn contents(&mut self, major: u8) -> Option<&'a [u8]> {
+        let (ty, len) = self.header()?;
+
+        if ty != major {
+            return None;
+        }
+
+        let contents = self.data.get(..len)?;
+        self.data = &self.data[len..];
+
+        Some(contents)
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        self.contents(0x40)
+    }
+
+    fn text(&mut self) -> Option<&'a str> {
+        std::str::from_utf8(self.contents(0x60)?).ok()
+    }
+}
+
+/// The largest block of a file in IPFS
+const IPFS_CHUNK_SIZE: usize = 256 * 1024;
+
+/// The most links in a node of the IPFS tree of a file
+const IPFS_MAX_LINKS: usize = 174;
+
+/// A node of the IPFS tree of a file
+struct IpfsNode {
+    multihash: Vec<u8>,
+    /// The size of the node, and all the nodes it links to
+    block_size: usize,
+    /// The size of the file data in the node
+    file_size: usize,
+}
+
+/// The IPFS hash of a file, as `ipfs add` gives it with the default settings: a CIDv0, which is
+/// the base58 encoded sha256 multihash of the protobuf encoded root node.
+pub fn ipfs_hash(data: &[u8]) -> String {
+    ipfs_multihash(data).to_base58()
+}
+
+fn ipfs_multihash(data: &[u8]) -> Vec<u8> {
+    let mut nodes: Vec<IpfsNode> = if data.is_empty() {
+        vec![ipfs_leaf(data)]
+    } else {
+        data.chunks(IPFS_CHUNK_SIZE).map(ipfs_leaf).collect()
+    };
+
+    while nodes.len() > 1 {
+        nodes = nodes.chunks(IPFS_MAX_LINKS).map(ipfs_links).collect();
+    }
+
+    nodes.remove(0).multihash
+}
+
+/// A node with file data
+fn ipfs_leaf(data: &[u8]) -> IpfsNode {
+    // the unixfs data, of type file
+    let mut unixfs = vec![0x08, 0x02];
+
+    if !data.is_empty() {
+        protobuf_bytes(&mut unixfs, 0x12, data);
+    }
+
+    unixfs.push(0x18);
+    varint(&mut unixfs, data.len());
+
+    let mut block = Vec::new();
+    protobuf_bytes(&mut block, 0x0a, &unixfs);
+
+    IpfsNode {
+        multihash: sha256_multihash(&block),
+        block_size: block.len(),
+        file_size: data.len(),
+    }
+}
+
+/// A node which links to other nodes
+fn ipfs_links(links: &[IpfsNode]) -> IpfsNode {
+    let mut block = Vec::new();
+    let mut unixfs = vec![0x08, 0x02, 0x18];
+
+    let file_size = links.iter().map(|link| link.file_size).sum();
+    varint(&mut unixfs, file_size);
+
+    for link in links {
+        // the hash, an empty name, and the size
+        let mut encoded = Vec::new();
+        protobuf_bytes(&mut encoded, 0x0a, &link.multihash);
+        encoded.extend_from_slice(&[0x12, 0x00, 0x18]);
+        varint(&mut encoded, link.block_size);
+
+        protobuf_bytes(&mut block, 0x12, &encoded);
+
+        unixfs.push(0x20);
+        varint(&mut unixfs, link.file_size);
+    }
+
+    protobuf_bytes(&mut block, 0x0a, &unixfs);
+
+    IpfsNode {
+        multihash: sha256_multihash(&block),
+        block_size: block.len() + links.iter().map(|link| link.block_size).sum::<usize>(),
+        file_size,
+    }
+}
+
+fn sha256_multihash(data: &[u8]) -> Vec<u8> {
+    let mut multihash = vec![0x12, 0x20];
+
+    multihash.extend_from_slice(&Sha256::digest(data));
+
+    multihash
+}
+
+fn protobuf_bytes(out: &mut Vec<u8>, key: u8, data: &[u8]) {
+    out.push(key);
+    varint(out, data.len());
+    out.extend_from_slice(data);
+}
+
+fn varint(out: &mut Vec<u8>, mut value: usize) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_and_resolve, Target};
+    use std::ffi::OsStr;
+
+    #[test]
+    fn ipfs_hashes() {
+        // the hashes which `ipfs add` gives
+        assert_eq!(
+            ipfs_hash(b""),
+            "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH"
+        );
+        assert_eq!(
+            ipfs_hash(b"hello world\n"),
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o"
+        );
+    }
+
+    #[test]
+    fn embed_and_extract() {
+        let mut resolver = FileResolver::default();
+
+        resolver.set_file_contents("a.sol", "import \"b.sol\"; contract A is B {}".into());
+        resolver.set_file_contents("b.sol", "contract B {}".into());
+
+        let ns = parse_and_resolve(OsStr::new("a.sol"), &mut resolver, Target::Solana);
+
+        assert!(!ns.diagnostics.any_errors());
+
+        let code = b"\0asm\x01\0\0\0";
+        let metadata = Metadata::new(0, &ns, &resolver, &Options::default(), code);
+
+        assert_eq!(
+            metadata.settings.compilation_target,
+            BTreeMap::from([("a.sol".to_string(), "A".to_string())])
+        );
+        assert_eq!(
+            metadata.sources.keys().collect::<Vec<_>>(),
+            vec!["a.sol", "b.sol"]
+        );
+
+        let json = metadata.canonical_json();
+
+        assert!(json.starts_with(r#"{"compiler":{"name":"solang","version":"#));
+        assert!(!json.contains(' '));
+
+        let code = embed(code, &metadata).unwrap();
+
+        assert_eq!(
+            extract(&code).unwrap(),
+            Some(MetadataHash {
+                ipfs: ipfs_hash(json.as_bytes()),
+                solang: env!("CARGO_PKG_VERSION").into(),
+            })
+        );
+    }
+}
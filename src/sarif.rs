@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the SARIF 2.1.0 format for diagnostics and lint findings, which code
+//! scanning tools in CI systems can import. Only the parts of the format which Solang fills in
+//! are defined.
+
+use crate::sema::ast::{Diagnostic, ErrorType, Level, Namespace};
+use crate::sema::lint::{Finding, Fix, Rule};
+use serde::Serialize;
+use solang_parser::pt::Loc;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+#[derive(Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+impl SarifLog {
+    /// A log with a single run of Solang
+    pub fn new(
+        tool_version: &str,
+        rules: Vec<ReportingDescriptor>,
+        results: Vec<SarifResult>,
+    ) -> Self {
+        SarifLog {
+            schema: SCHEMA,
+            version: "2.1.0",
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "solang",
+                        version: tool_version.to_string(),
+                        information_uri: env!("CARGO_PKG_HOMEPAGE"),
+                        rules,
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: String,
+    pub information_uri: &'static str,
+    pub rules: Vec<ReportingDescriptor>,
+}
+
+/// Describes a rule
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+    pub id: &'static str,
+    pub short_description: Message,
+}
+
+#[derive(Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: &'static str,
+    pub level: &'static str,
+    pub message: Message,
+    pub locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<SarifFix>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    pub region: Region,
+}
+
+#[derive(Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+/// Lines and columns start at one
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifFix {
+    pub description: Message,
+    pub artifact_changes: Vec<ArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactChange {
+    pub artifact_location: ArtifactLocation,
+    pub replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replacement {
+    pub deleted_region: Region,
+    pub inserted_content: Message,
+}
+
+/// The rules for compiler diagnostics, one for each type of diagnostic
+pub fn diagnostic_rules() -> Vec<ReportingDescriptor> {
+    [
+        ("error", "compiler error"),
+        ("warning", "compiler warning"),
+        ("info", "compiler information"),
+        ("parser-error", "the source could not be parsed"),
+        ("syntax-error", "invalid syntax"),
+        ("declaration-error", "invalid declaration"),
+        ("cast-error", "invalid conversion between types"),
+        ("type-error", "type mismatch"),
+    ]
+    .into_iter()
+    .map(|(id, description)| ReportingDescriptor {
+        id,
+        short_description: Message {
+            text: description.to_string(),
+        },
+    })
+    .collect()
+}
+
+/// The rules for lint findings
+pub fn lint_rules() -> Vec<ReportingDescriptor> {
+    Rule::ALL
+        .iter()
+        .map(|rule| ReportingDescriptor {
+            id: rule.name(),
+            short_description: Message {
+                text: rule.description().to_string(),
+            },
+        })
+        .collect()
+}
+
+impl Namespace {
+    /// Convert the diagnostics to SARIF results. Debug diagnostics are left out.
+    pub fn diagnostics_as_sarif(&self) -> Vec<SarifResult> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.level != Level::Debug)
+            .map(|diagnostic| {
+                let rule_id = match (&diagnostic.ty, &diagnostic.level) {
+                    (ErrorType::ParserError, _) => "parser-error",
+                    (ErrorType::SyntaxError, _) => "syntax-error",
+                    (ErrorType::DeclarationError, _) => "declaration-error",
+                    (ErrorType::CastError, _) => "cast-error",
+                    (ErrorType::TypeError, _) => "type-error",
+                    (_, Level::Error) => "error",
+                    (_, Level::Warning) => "warning",
+                    (_, _) => "info",
+                };
+
+                self.sarif_result(rule_id, diagnostic, None)
+            })
+            .collect()
+    }
+
+    /// Convert the lint findings to SARIF results
+    pub fn findings_as_sarif(&self, findings: &[Finding]) -> Vec<SarifResult> {
+        findings
+            .iter()
+            .map(|finding| {
+                self.sarif_result(
+                    finding.rule.name(),
+                    &finding.diagnostic,
+                    finding.fix.as_ref(),
+                )
+            })
+            .collect()
+    }
+
+    fn sarif_result(
+        &self,
+        rule_id: &'static str,
+        diagnostic: &Diagnostic,
+        fix: Option<&Fix>,
+    ) -> SarifResult {
+        let level = match diagnostic.level {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Info | Level::Debug => "note",
+        };
+
+        SarifResult {
+            rule_id,
+            level,
+            message: Message {
+                text: diagnostic.message.clone(),
+            },
+            locations: self
+                .sarif_location(&diagnostic.loc, None)
+                .into_iter()
+                .collect(),
+            related_locations: diagnostic
+                .notes
+                .iter()
+                .filter_map(|note| self.sarif_location(&note.loc, Some(note.message.clone())))
+                .collect(),
+            fixes: fix
+                .and_then(|fix| self.sarif_fix(fix))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn sarif_location(&self, loc: &Loc, message: Option<String>) -> Option<Location> {
+        let (artifact_location, region) = self.sarif_region(loc)?;
+
+        Some(Location {
+            physical_location: PhysicalLocation {
+                artifact_location,
+                region,
+            },
+            message: message.map(|text| Message { text }),
+        })
+    }
+
+    fn sarif_fix(&self, fix: &Fix) -> Option<SarifFix> {
+        let (artifact_location, deleted_region) = self.sarif_region(&fix.loc)?;
+
+        Some(SarifFix {
+            description: Message {
+                text: fix.description.clone(),
+            },
+            artifact_changes: vec![ArtifactChange {
+                artifact_location,
+                replacements: vec![Replacement {
+                    deleted_region,
+                    inserted_content: Message {
+                        text: fix.replacement.clone(),
+                    },
+                }],
+            }],
+        })
+    }
+
+    fn sarif_region(&self, loc: &Loc) -> Option<(ArtifactLocation, Region)> {
+        let Loc::File(file_no, start, end) = loc else {
+            return None;
+        };
+
+        let file = &self.files[*file_no];
+        let (start_line, start_column) = file.offset_to_line_column(*start);
+        let (end_line, end_column) = file.offset_to_line_column(*end);
+
+        Some((
+            ArtifactLocation {
+                uri: if file.path.is_absolute() {
+                    format!("file://{file}")
+                } else {
+                    file.to_string()
+                },
+            },
+            Region {
+                start_line: start_line + 1,
+                start_column: start_column + 1,
+                end_line: end_line + 1,
+                end_column: end_column + 1,
+            },
+        ))
+    }
+}
@@ -5,6 +5,7 @@ use crate::abi::anchor::function_discriminator;
 use crate::codegen::cfg::{ControlFlowGraph, Instr};
 use crate::diagnostics::Diagnostics;
 use crate::sema::ast::ExternalCallAccounts::{AbsentArgument, NoAccount};
+use crate::sema::warnings::Suppression;
 use crate::sema::yul::ast::{InlineAssembly, YulFunction};
 use crate::sema::Recurse;
 use crate::{codegen, Target};
@@ -205,6 +206,16 @@ impl ErrorDecl {
             None => self.name.to_string(),
         }
     }
+
+    /// The selector of an error is the first four bytes of the keccak256 hash of its
+    /// signature, on every target
+    pub fn selector(&self, ns: &Namespace) -> [u8; 4] {
+        let mut buf = [0u8; 32];
+        let mut hasher = Keccak::v256();
+        hasher.update(ns.signature(&self.name, &self.fields).as_bytes());
+        hasher.finalize(&mut buf);
+        [buf[0], buf[1], buf[2], buf[3]]
+    }
 }
 
 impl fmt::Display for StructDecl {
@@ -266,6 +277,18 @@ pub struct ParameterAnnotation {
     pub id: pt::Identifier,
 }
 
+/// On Polkadot, these parameter annotations change the SCALE encoding of the parameters and
+/// return values of public functions
+#[derive(Debug, Eq, Clone, Copy, PartialEq)]
+pub enum ScaleAnnotation {
+    /// `@compact uint64 x` is encoded as `Compact<u64>`
+    Compact,
+    /// `returns (@option bool, T)` is encoded as `Option<T>`
+    Option,
+    /// `returns (@result bool, T)` is encoded as `Result<T, ()>`
+    Result,
+}
+
 impl Parameter<Type> {
     /// Create a new instance of the given `Type`, with all other values set to their default.
     pub fn new_default(ty: Type) -> Self {
@@ -289,6 +312,16 @@ impl Parameter<Type> {
             ""
         }
     }
+
+    /// The SCALE encoding annotation of this parameter, if any
+    pub fn scale_annotation(&self) -> Option<ScaleAnnotation> {
+        match self.annotation.as_ref()?.id.name.as_str() {
+            "compact" => Some(ScaleAnnotation::Compact),
+            "option" => Some(ScaleAnnotation::Option),
+            "result" => Some(ScaleAnnotation::Result),
+            _ => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Hash, Debug)]
@@ -340,6 +373,12 @@ pub struct Function {
     pub is_override: Option<(pt::Loc, Vec<usize>)>,
     /// The selector (known as discriminator on Solana/Anchor)
     pub selector: Option<(pt::Loc, Vec<u8>)>,
+    /// Should calls to this function be traced, from the `@trace` annotation
+    pub trace: Option<(pt::Loc, bool)>,
+    /// Are reentrant calls to this function rejected, from the `@nonreentrant` annotation
+    pub nonreentrant: Option<(pt::Loc, bool)>,
+    /// The syscall or host function which implements this function, from the `@syscall` annotation
+    pub syscall: Option<(pt::Loc, Syscall)>,
     /// Was the function declared with a body
     pub has_body: bool,
     /// The resolved body (if any)
@@ -358,6 +397,22 @@ pub struct Function {
     pub solana_accounts: RefCell<IndexMap<String, SolanaAccount>>,
     /// List of contracts this function creates
     pub creates: Vec<(pt::Loc, usize)>,
+    /// Indexes of the parameters which are only read and do not escape the function body
+    pub read_only_params: HashSet<usize>,
+}
+
+/// A function which is provided by the chain rather than compiled, declared with the `@syscall`
+/// annotation on a free function without a body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Syscall {
+    /// A Solana syscall by name, or a host function import. The module of the import is only
+    /// given on Polkadot.
+    Name {
+        module: Option<String>,
+        name: String,
+    },
+    /// A Solana static syscall, which is called by its number
+    Number(u32),
 }
 
 /// This struct represents a Solana account. There is no name field, because
@@ -454,6 +509,9 @@ impl Function {
             bases: BTreeMap::new(),
             modifiers: Vec::new(),
             selector: None,
+            trace: None,
+            nonreentrant: None,
+            syscall: None,
             is_virtual: false,
             is_accessor: false,
             has_body: false,
@@ -466,6 +524,7 @@ impl Function {
             mangled_name_contracts: HashSet::new(),
             solana_accounts: IndexMap::new().into(),
             creates: Vec::new(),
+            read_only_params: HashSet::new(),
         }
     }
 
@@ -582,6 +641,8 @@ impl fmt::Display for UserTypeDecl {
 pub struct Variable {
     pub tags: Vec<Tag>,
     pub name: String,
+    /// The location of the name of the variable
+    pub name_loc: pt::Loc,
     pub loc: pt::Loc,
     pub ty: Type,
     pub visibility: pt::Visibility,
@@ -590,6 +651,44 @@ pub struct Variable {
     pub initializer: Option<Expression>,
     pub assigned: bool,
     pub read: bool,
+    /// For a mapping declared `@enumerable`, the storage variables which keep track of its keys
+    pub enumerable: Option<EnumerableMapping>,
+}
+
+/// The hidden storage variables of an `@enumerable` mapping. They are in the same contract as
+/// the mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnumerableMapping {
+    pub contract_no: usize,
+    /// The dynamic array with all the keys which have been assigned
+    pub keys: usize,
+    /// The mapping from each key to its position in the keys array, plus one; zero if the key
+    /// is not in the keys array
+    pub positions: usize,
+}
+
+impl EnumerableMapping {
+    /// The storage array with the keys
+    pub fn keys_array(&self, loc: pt::Loc, ns: &Namespace) -> Expression {
+        self.storage_variable(loc, self.keys, ns)
+    }
+
+    /// The storage mapping with the position of each key
+    pub fn positions_mapping(&self, loc: pt::Loc, ns: &Namespace) -> Expression {
+        self.storage_variable(loc, self.positions, ns)
+    }
+
+    fn storage_variable(&self, loc: pt::Loc, var_no: usize, ns: &Namespace) -> Expression {
+        Expression::StorageVariable {
+            loc,
+            ty: Type::StorageRef(
+                false,
+                Box::new(ns.contracts[self.contract_no].variables[var_no].ty.clone()),
+            ),
+            contract_no: self.contract_no,
+            var_no,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -663,6 +762,8 @@ pub struct File {
     /// Index into FileResolver.import_paths. This is `None` when this File was
     /// created not during `parse_and_resolve` (e.g., builtins)
     pub import_no: Option<usize>,
+    /// The files imported by this file, with the location of the import path
+    pub imports: Vec<(pt::Loc, usize)>,
 }
 
 /// When resolving a Solidity file, this holds all the resolved items
@@ -702,6 +803,10 @@ pub struct Namespace {
     pub var_constants: HashMap<pt::Loc, codegen::Expression>,
     /// Overrides for hover in the language server
     pub hover_overrides: HashMap<pt::Loc, String>,
+    /// Lines with warnings which are suppressed by a comment
+    pub warning_suppressions: Vec<Suppression>,
+    /// Reject the implicit conversions which solc 0.8 does not allow, rather than doing them
+    pub strict_conversions: bool,
 }
 
 #[derive(Debug)]
@@ -773,6 +878,17 @@ pub struct Layout {
     pub contract_no: usize,
     pub var_no: usize,
     pub ty: Type,
+    /// Where the variable is in its slot, if it shares the slot with other variables
+    pub packed: Option<PackedSlot>,
+}
+
+/// A small value which shares its storage slot with its neighbours. The slot is stored as a
+/// single unsigned integer of `width` bytes, a power of two, and the value is `offset` bytes
+/// from its least significant end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedSlot {
+    pub offset: u8,
+    pub width: u8,
 }
 
 #[derive(Debug)]
@@ -791,7 +907,8 @@ pub struct Using {
 
 #[derive(Debug)]
 pub enum UsingList {
-    Library(usize),
+    /// The location of the library name, and the library
+    Library(pt::Loc, usize),
     Functions(Vec<UsingFunction>),
 }
 
@@ -812,6 +929,12 @@ pub struct Contract {
     pub bases: Vec<Base>,
     pub using: Vec<Using>,
     pub layout: Vec<Layout>,
+    /// The storage variables which were left out of the layout, because they are never read or
+    /// never written. Only available after codegen with `strip_dead_storage`.
+    pub stripped_variables: Vec<(usize, usize)>,
+    /// The immutable variables whose value is known at compile time, which are part of the code
+    /// rather than storage, with their value. Only available after codegen.
+    pub immutables_in_code: BTreeMap<(usize, usize), Expression>,
     pub fixed_layout_size: BigInt,
     pub functions: Vec<usize>,
     pub all_functions: BTreeMap<usize, usize>,
@@ -828,12 +951,18 @@ pub struct Contract {
     pub initializer: Option<usize>,
     pub default_constructor: Option<(Function, usize)>,
     pub cfg: Vec<ControlFlowGraph>,
+    /// The locations of the code counted by each coverage counter. Only available after codegen
+    /// with coverage.
+    pub coverage_counters: Vec<Vec<pt::Loc>>,
     /// Compiled program. Only available after emit.
     pub code: OnceCell<Vec<u8>>,
     /// Can the contract be instantiated, i.e. not abstract, no errors, etc.
     pub instantiable: bool,
     /// Account of deployed program code on Solana
     pub program_id: Option<Vec<u8>>,
+    /// The Anchor discriminator at the start of the data account on Solana, unless the legacy
+    /// layout is used. Only available after codegen.
+    pub account_discriminator: Option<Vec<u8>>,
 }
 
 impl Contract {
@@ -947,6 +1076,8 @@ pub enum Expression {
     Divide {
         loc: pt::Loc,
         ty: Type,
+        /// Do not check for overflow of signed division, i.e. in `unchecked {}` block
+        unchecked: bool,
         left: Box<Expression>,
         right: Box<Expression>,
     },
@@ -1276,6 +1407,7 @@ pub struct CallArgs {
     pub seeds: Option<Box<Expression>>,
     pub flags: Option<Box<Expression>>,
     pub program_id: Option<Box<Expression>>,
+    pub storage_deposit_limit: Option<Box<Expression>>,
 }
 
 /// This enum manages the accounts in an external call on Solana. There can be three options:
@@ -1353,6 +1485,9 @@ impl Recurse for CallArgs {
         if let Some(flags) = &self.flags {
             flags.recurse(cx, f);
         }
+        if let Some(storage_deposit_limit) = &self.storage_deposit_limit {
+            storage_deposit_limit.recurse(cx, f);
+        }
     }
 }
 
@@ -1643,7 +1778,7 @@ impl CodeLocation for Instr {
             | Instr::ReturnData { data: expr, .. }
             | Instr::Print { expr } => expr.loc(),
 
-            Instr::PushMemory { value: expr, .. } => expr.loc(),
+            Instr::PushMemory { loc, .. } | Instr::ReserveMemory { loc, .. } => *loc,
 
             Instr::MemCopy {
                 source,
@@ -1699,6 +1834,7 @@ pub enum Builtin {
     PayableTransfer,
     ArrayPush,
     ArrayPop,
+    ArrayReserve,
     ArrayLength,
     Assert,
     Print,
@@ -1726,14 +1862,20 @@ pub enum Builtin {
     Origin,
     BlockHash,
     MinimumBalance,
+    Create2Address,
     AbiDecode,
     AbiEncode,
     AbiEncodePacked,
     AbiEncodeWithSelector,
     AbiEncodeWithSignature,
     AbiEncodeCall,
+    AbiEthDecode,
+    AbiEthEncode,
     MulMod,
     AddMod,
+    MulDiv,
+    Sqrt,
+    Log2,
     ChainId,
     ExternalFunctionAddress,
     FunctionSelector,
@@ -1772,6 +1914,11 @@ pub enum Builtin {
     ECRecover,
     StringConcat,
     BytesConcat,
+    BytesSlice,
+    IndexOf,
+    ToString,
+    ToHexString,
+    ParseInt,
     TypeMin,
     TypeMax,
     TypeName,
@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::ast::{
-    ArrayLength, Builtin, Diagnostic, Expression, File, Function, Namespace, Parameter, StructType,
-    Symbol, Type,
+    ArrayLength, Builtin, Diagnostic, Expression, File, FormatArg, Function, Namespace, Parameter,
+    StructType, Symbol, Type,
 };
 use super::diagnostics::Diagnostics;
 use super::eval::eval_const_number;
@@ -13,7 +13,9 @@ use crate::sema::{
     expression::{function_call::evaluate_argument, resolve_expression::expression},
     namespace::ResolveTypeContext,
     statements::parameter_list_to_expr_list,
+    unused_variable::used_variable,
 };
+use crate::target::Capability;
 use crate::Target;
 use num_bigint::BigInt;
 use num_traits::One;
@@ -36,7 +38,7 @@ pub struct Prototype {
 }
 
 // A list of all Solidity builtins functions
-pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
+pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 37]> = Lazy::new(|| {
     [
         Prototype {
             builtin: Builtin::Assert,
@@ -89,7 +91,7 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             name: "selfdestruct",
             params: vec![Type::Address(true)],
             ret: vec![Type::Unreachable],
-            target: vec![Target::EVM, Target::default_polkadot()],
+            target: vec![],
             doc: "Destroys current account and deposits any remaining balance to address",
             constant: false,
         },
@@ -155,7 +157,7 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             name: "gasleft",
             params: vec![],
             ret: vec![Type::Uint(64)],
-            target: vec![Target::default_polkadot(), Target::EVM],
+            target: vec![Target::default_polkadot(), Target::EVM, Target::Stylus],
             doc: "Return remaining gas left in current call",
             constant: false,
         },
@@ -170,6 +172,33 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             doc: "Returns the block hash for given block number",
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::Create2Address,
+            namespace: None,
+            method: vec![],
+            name: "create2_address",
+            params: vec![Type::Address(false), Type::Bytes(32), Type::Bytes(32)],
+            ret: vec![Type::Address(false)],
+            target: vec![Target::EVM, Target::Stylus],
+            doc: "Address of a contract created by deployer with salt, given the hash of its creation code",
+            constant: true,
+        },
+        Prototype {
+            builtin: Builtin::Create2Address,
+            namespace: None,
+            method: vec![],
+            name: "create2_address",
+            params: vec![
+                Type::Address(false),
+                Type::Bytes(32),
+                Type::Bytes(32),
+                Type::DynamicBytes,
+            ],
+            ret: vec![Type::Address(false)],
+            target: vec![Target::default_polkadot()],
+            doc: "Address of a contract instantiated by deployer with salt, code hash and constructor input",
+            constant: true,
+        },
         Prototype {
             builtin: Builtin::AbiDecode,
             namespace: Some("abi"),
@@ -241,6 +270,29 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             // it should be allowed in constant context, but we don't support that yet
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::AbiEthDecode,
+            namespace: Some("abi"),
+            method: vec![],
+            name: "ethDecode",
+            params: vec![Type::DynamicBytes],
+            ret: vec![],
+            target: vec![],
+            doc: "Decode Ethereum ABI encoded byte array with the given types",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::AbiEthEncode,
+            namespace: Some("abi"),
+            method: vec![],
+            name: "ethEncode",
+            params: vec![],
+            ret: vec![],
+            target: vec![],
+            doc: "Encode given arguments using the Ethereum ABI",
+            // it should be allowed in constant context, but we don't support that yet
+            constant: false,
+        },
         Prototype {
             builtin: Builtin::Gasprice,
             namespace: Some("tx"),
@@ -276,6 +328,39 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             // it should be allowed in constant context, but we don't support that yet
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::MulDiv,
+            namespace: Some("math"),
+            method: vec![],
+            name: "mulDiv",
+            params: vec![Type::Uint(256), Type::Uint(256), Type::Uint(256)],
+            ret: vec![Type::Uint(256)],
+            target: vec![],
+            doc: "Multiply first two arguments, and divide by the last argument rounding down. The multiplication does not overflow; reverts if the result does not fit or the divisor is zero",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::Sqrt,
+            namespace: Some("math"),
+            method: vec![],
+            name: "sqrt",
+            params: vec![Type::Uint(256)],
+            ret: vec![Type::Uint(256)],
+            target: vec![],
+            doc: "Square root, rounded down",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::Log2,
+            namespace: Some("math"),
+            method: vec![],
+            name: "log2",
+            params: vec![Type::Uint(256)],
+            ret: vec![Type::Uint(256)],
+            target: vec![],
+            doc: "Base 2 logarithm, rounded down. The logarithm of 0 is 0",
+            constant: false,
+        },
         Prototype {
             builtin: Builtin::SignatureVerify,
             namespace: None,
@@ -347,6 +432,39 @@ pub static BUILTIN_FUNCTIONS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             doc: "Concatenate bytes",
             constant: true,
         },
+        Prototype {
+            builtin: Builtin::ToString,
+            namespace: Some("string"),
+            method: vec![],
+            name: "toString",
+            params: vec![Type::Int(256)],
+            ret: vec![Type::String],
+            target: vec![],
+            doc: "Format an integer as a decimal string",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::ToHexString,
+            namespace: Some("string"),
+            method: vec![],
+            name: "toHexString",
+            params: vec![Type::Int(256)],
+            ret: vec![Type::String],
+            target: vec![],
+            doc: "Format an integer as a hexadecimal string with 0x prefix",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::ParseInt,
+            namespace: Some("string"),
+            method: vec![],
+            name: "parseInt",
+            params: vec![Type::String],
+            ret: vec![Type::Int(256)],
+            target: vec![],
+            doc: "Parse a decimal integer with an optional minus sign. Reverts if the string is not a valid int256",
+            constant: false,
+        },
     ]
 });
 
@@ -360,7 +478,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "coinbase",
             params: vec![],
             ret: vec![Type::Address(true)],
-            target: vec![Target::EVM],
+            target: vec![Target::EVM, Target::Stylus],
             doc: "The address of the current block miner",
             constant: false,
         },
@@ -382,7 +500,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "gaslimit",
             params: vec![],
             ret: vec![Type::Uint(64)],
-            target: vec![Target::EVM],
+            target: vec![Target::EVM, Target::Stylus],
             doc: "The gas limit",
             constant: false,
         },
@@ -437,7 +555,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "chainid",
             params: vec![],
             ret: vec![Type::Uint(256)],
-            target: vec![Target::EVM],
+            target: vec![Target::EVM, Target::Stylus],
             doc: "Current chain id",
             constant: false,
         },
@@ -448,7 +566,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "basefee",
             params: vec![],
             ret: vec![Type::Uint(256)],
-            target: vec![Target::EVM],
+            target: vec![Target::EVM, Target::Stylus],
             doc: "Current block's base fee",
             constant: false,
         },
@@ -514,7 +632,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "gasprice",
             params: vec![],
             ret: vec![Type::Value],
-            target: vec![Target::default_polkadot(), Target::EVM],
+            target: vec![Target::default_polkadot(), Target::EVM, Target::Stylus],
             doc: "gas price for one gas unit",
             constant: false,
         },
@@ -525,7 +643,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
             name: "origin",
             params: vec![],
             ret: vec![Type::Address(false)],
-            target: vec![Target::EVM],
+            target: vec![],
             doc: "Original address of sender current transaction",
             constant: false,
         },
@@ -547,7 +665,7 @@ pub static BUILTIN_VARIABLE: Lazy<[Prototype; 17]> = Lazy::new(|| {
 });
 
 // A list of all Solidity builtins methods
-pub static BUILTIN_METHODS: Lazy<[Prototype; 27]> = Lazy::new(|| {
+pub static BUILTIN_METHODS: Lazy<[Prototype; 31]> = Lazy::new(|| {
     [
         Prototype {
             builtin: Builtin::ReadInt8,
@@ -846,6 +964,50 @@ pub static BUILTIN_METHODS: Lazy<[Prototype; 27]> = Lazy::new(|| {
             doc: "Write the contents of a bytes array (without its length) to the specified offset",
             constant: false,
         },
+        Prototype {
+            builtin: Builtin::BytesSlice,
+            namespace: None,
+            method: vec![Type::DynamicBytes],
+            name: "slice",
+            params: vec![Type::Uint(32), Type::Uint(32)],
+            ret: vec![Type::DynamicBytes],
+            target: vec![],
+            doc: "Copy the bytes from the start offset up to but not including the end offset",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::BytesSlice,
+            namespace: None,
+            method: vec![Type::String],
+            name: "slice",
+            params: vec![Type::Uint(32), Type::Uint(32)],
+            ret: vec![Type::String],
+            target: vec![],
+            doc: "Copy the bytes of the string from the start offset up to but not including the end offset",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::IndexOf,
+            namespace: None,
+            method: vec![Type::DynamicBytes],
+            name: "indexOf",
+            params: vec![Type::DynamicBytes],
+            ret: vec![Type::Int(64)],
+            target: vec![],
+            doc: "Offset of the first occurrence of the argument, or -1 if it does not occur",
+            constant: false,
+        },
+        Prototype {
+            builtin: Builtin::IndexOf,
+            namespace: None,
+            method: vec![Type::String],
+            name: "indexOf",
+            params: vec![Type::String],
+            ret: vec![Type::Int(64)],
+            target: vec![],
+            doc: "Byte offset of the first occurrence of the argument, or -1 if it does not occur",
+            constant: false,
+        },
     ]
 });
 
@@ -858,6 +1020,17 @@ pub fn is_builtin_call(namespace: Option<&str>, fname: &str, ns: &Namespace) ->
     })
 }
 
+/// The target capability a builtin depends on, if any
+fn capability(builtin: Builtin) -> Option<Capability> {
+    match builtin {
+        Builtin::Value => Some(Capability::MsgValue),
+        Builtin::SelfDestruct => Some(Capability::SelfDestruct),
+        Builtin::Origin => Some(Capability::TxOrigin),
+        Builtin::Calldata | Builtin::Signature => Some(Capability::CallData),
+        _ => None,
+    }
+}
+
 /// Get the prototype for a builtin. If the prototype has arguments, it is a function else
 /// it is a variable.
 pub fn get_prototype(builtin: Builtin) -> Option<&'static Prototype> {
@@ -889,13 +1062,8 @@ pub fn builtin_var(
                     ),
                 ));
             }
-            if ns.target == Target::Solana && p.builtin == Builtin::Value {
-                diagnostics.push(Diagnostic::error(
-                    *loc,
-                    String::from(
-                        "Solana Cross Program Invocation (CPI) cannot transfer native value. See https://solang.readthedocs.io/en/latest/language/functions.html#value_transfer",
-                    ),
-                ));
+            if let Some(capability) = capability(p.builtin) {
+                ns.target_supports(loc, capability, diagnostics);
             }
             if ns.target == Target::Solana && p.builtin == Builtin::Sender {
                 diagnostics.push(Diagnostic::error(
@@ -940,6 +1108,116 @@ pub fn is_reserved(fname: &str) -> bool {
     })
 }
 
+/// Whether a builtin is a function like `keccak256()`, a variable like `block.number`, or a
+/// method like `address.balance`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinKind {
+    Function,
+    Variable,
+    Method,
+}
+
+/// A builtin which can be used on a target
+#[derive(Debug, Clone)]
+pub struct BuiltinDescriptor {
+    pub builtin: Builtin,
+    pub kind: BuiltinKind,
+    /// The namespace of the builtin, like `block` in `block.number`
+    pub namespace: Option<&'static str>,
+    pub name: &'static str,
+    /// For methods, the type of the value the method is called on
+    pub method: Option<Type>,
+    pub params: Vec<Type>,
+    pub ret: Vec<Type>,
+    pub doc: &'static str,
+    /// Can it be used in a constant expression
+    pub constant: bool,
+}
+
+impl BuiltinDescriptor {
+    /// The name with its namespace, like `block.number`
+    pub fn full_name(&self) -> String {
+        match self.namespace {
+            Some(namespace) => format!("{namespace}.{}", self.name),
+            None => self.name.to_string(),
+        }
+    }
+
+    /// The signature of the builtin in Solidity syntax, like `keccak256(bytes) returns (bytes32)`.
+    /// Variables have no parameter list. The user type of `wrap()` and `unwrap()` is shown as `T`.
+    pub fn signature(&self, ns: &Namespace) -> String {
+        let type_name = |ty: &Type| match ty {
+            Type::UserType(_) => "T".to_string(),
+            _ => ty.to_string(ns),
+        };
+
+        let mut signature = match &self.method {
+            Some(ty) => format!("{}.{}", type_name(ty), self.name),
+            None => self.full_name(),
+        };
+
+        if self.kind != BuiltinKind::Variable {
+            signature.push_str(&format!(
+                "({})",
+                self.params
+                    .iter()
+                    .map(type_name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        if !self.ret.is_empty() && self.ret != [Type::Void] && self.ret != [Type::Unreachable] {
+            signature.push_str(&format!(
+                " returns ({})",
+                self.ret
+                    .iter()
+                    .map(type_name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        signature
+    }
+}
+
+/// The builtins which can be used on the target, in the order they are declared. Besides the
+/// targets listed in its prototype, a builtin may need a capability of the target; builtins which
+/// sema would reject on the target, like `msg.sender` on Solana, are left out.
+pub fn available(target: Target) -> Vec<BuiltinDescriptor> {
+    let prototypes = BUILTIN_FUNCTIONS
+        .iter()
+        .map(|p| (BuiltinKind::Function, p))
+        .chain(BUILTIN_VARIABLE.iter().map(|p| (BuiltinKind::Variable, p)))
+        .chain(BUILTIN_METHODS.iter().map(|p| (BuiltinKind::Method, p)));
+
+    prototypes
+        .filter(|(kind, p)| {
+            (p.target.is_empty() || p.target.contains(&target))
+                && match capability(p.builtin) {
+                    Some(capability) => target.capabilities().supports(capability),
+                    None => true,
+                }
+                && !(target == Target::Solana && p.builtin == Builtin::Sender)
+                && !(target.is_polkadot()
+                    && p.builtin == Builtin::Gasprice
+                    && *kind == BuiltinKind::Variable)
+        })
+        .map(|(kind, p)| BuiltinDescriptor {
+            builtin: p.builtin,
+            kind,
+            namespace: p.namespace,
+            name: p.name,
+            method: p.method.first().cloned(),
+            params: p.params.clone(),
+            ret: p.ret.clone(),
+            doc: p.doc,
+            constant: p.constant,
+        })
+        .collect()
+}
+
 /// Resolve a builtin call
 pub(super) fn resolve_call(
     loc: &pt::Loc,
@@ -953,9 +1231,20 @@ pub(super) fn resolve_call(
 ) -> Result<Expression, ()> {
     let funcs = BUILTIN_FUNCTIONS
         .iter()
-        .filter(|p| p.name == id && p.namespace == namespace && p.method.is_empty())
+        .filter(|p| {
+            p.name == id
+                && p.namespace == namespace
+                && p.method.is_empty()
+                && (p.target.is_empty() || p.target.contains(&ns.target))
+        })
         .collect::<Vec<&Prototype>>();
 
+    if let Some(capability) = funcs.first().and_then(|func| capability(func.builtin)) {
+        if !ns.target_supports(loc, capability, diagnostics) {
+            return Err(());
+        }
+    }
+
     // try to resolve the arguments, give up if there are any errors
     if args.iter().fold(false, |acc, arg| {
         acc | expression(arg, context, ns, symtable, diagnostics, ResolveTo::Unknown).is_err()
@@ -1043,6 +1332,94 @@ pub(super) fn resolve_call(
     Err(())
 }
 
+/// The type of a checked conversion function like `toUint64()` or `toInt128()`. These are only
+/// builtins if no function with the same name is visible, so that code which defines its own
+/// `toUint64()` like the SafeCast library still compiles.
+pub fn checked_conversion(fname: &str) -> Option<Type> {
+    let (bits, signed) = match fname.strip_prefix("toUint") {
+        Some(bits) => (bits, false),
+        None => (fname.strip_prefix("toInt")?, true),
+    };
+
+    if !bits.starts_with(|c: char| ('1'..='9').contains(&c)) {
+        return None;
+    }
+
+    let bits = bits
+        .parse::<u16>()
+        .ok()
+        .filter(|bits| bits % 8 == 0 && (8..=256).contains(bits))?;
+
+    Some(if signed {
+        Type::Int(bits)
+    } else {
+        Type::Uint(bits)
+    })
+}
+
+/// Resolve a checked conversion like `toUint64(x)`. This fails at runtime if the value does not
+/// fit into the type, rather than truncating it like `uint64(x)`.
+pub(super) fn resolve_checked_conversion(
+    loc: &pt::Loc,
+    name: &str,
+    to: Type,
+    args: &[pt::Expression],
+    context: &mut ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    let [arg] = args else {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!(
+                "builtin function '{name}' expects 1 arguments, {} provided",
+                args.len()
+            ),
+        ));
+        return Err(());
+    };
+
+    let expr = expression(arg, context, ns, symtable, diagnostics, ResolveTo::Integer)?;
+
+    used_variable(ns, &expr, symtable);
+
+    // a literal which does not fit is an error at compile time
+    if matches!(expr, Expression::NumberLiteral { .. }) {
+        return expr.cast(&arg.loc(), &to, true, ns, diagnostics);
+    }
+
+    let from = expr.ty().deref_any().clone();
+
+    if !matches!(from, Type::Uint(_) | Type::Int(_) | Type::Value) {
+        diagnostics.push(Diagnostic::error(
+            arg.loc(),
+            format!(
+                "builtin function '{name}' expects an integer, not '{}'",
+                from.to_string(ns)
+            ),
+        ));
+        return Err(());
+    }
+
+    let expr = expr.cast(&arg.loc(), &from, true, ns, diagnostics)?;
+
+    // the largest values of the types, as powers of two
+    let from_bits = from.bits(ns) - u16::from(from.is_signed_int(ns));
+    let to_bits = to.bits(ns) - u16::from(to.is_signed_int(ns));
+
+    if from_bits <= to_bits && (to.is_signed_int(ns) || !from.is_signed_int(ns)) {
+        // every value fits, so no check is needed
+        expr.cast(loc, &to, false, ns, diagnostics)
+    } else {
+        Ok(Expression::CheckingTrunc {
+            loc: *loc,
+            to,
+            expr: Box::new(expr),
+        })
+    }
+}
+
 /// Resolve a builtin namespace call. The takes the unresolved arguments, since it has
 /// to handle the special case "abi.decode(foo, (int32, bool, address))" where the
 /// second argument is a type list. The generic expression resolver cannot deal with
@@ -1087,8 +1464,12 @@ pub(super) fn resolve_namespace_call(
         });
     }
 
+    if namespace == "string" && (name == "toString" || name == "toHexString") {
+        return format_integer(loc, name, args, context, ns, symtable, diagnostics);
+    }
+
     // The abi.* functions need special handling, others do not
-    if namespace != "abi" && namespace != "string" {
+    if namespace != "abi" {
         return resolve_call(
             loc,
             Some(namespace),
@@ -1108,10 +1489,12 @@ pub(super) fn resolve_namespace_call(
         "encodeWithSelector" => Builtin::AbiEncodeWithSelector,
         "encodeWithSignature" => Builtin::AbiEncodeWithSignature,
         "encodeCall" => Builtin::AbiEncodeCall,
+        "ethDecode" => Builtin::AbiEthDecode,
+        "ethEncode" => Builtin::AbiEthEncode,
         _ => unreachable!(),
     };
 
-    if builtin == Builtin::AbiDecode {
+    if matches!(builtin, Builtin::AbiDecode | Builtin::AbiEthDecode) {
         if args.len() != 2 {
             diagnostics.push(Diagnostic::error(
                 *loc,
@@ -1150,6 +1533,11 @@ pub(super) fn resolve_namespace_call(
                     format!("Invalid type '{}': mappings and recursive types cannot be abi decoded or encoded", ty.to_string(ns))
                 ));
                 broken = true;
+            } else if (builtin == Builtin::AbiEthDecode || ns.target == Target::Stylus)
+                && !eth_abi_type(&ty, ns)
+            {
+                diagnostics.push(Diagnostic::error(arg.loc(), eth_abi_type_error(&ty, ns)));
+                broken = true;
             }
 
             tys.push(ty);
@@ -1352,6 +1740,25 @@ pub(super) fn resolve_namespace_call(
             expr = expr.cast(&arg.loc(), &Type::String, true, ns, diagnostics)?;
         }
 
+        if builtin == Builtin::AbiEncodePacked {
+            if let Some(message) = packed_encoding_error(&expr.ty(), ns) {
+                diagnostics.push(Diagnostic::error(arg.loc(), message));
+
+                return Err(());
+            }
+        }
+
+        if (builtin == Builtin::AbiEthEncode || ns.target == Target::Stylus)
+            && !eth_abi_type(&expr.ty(), ns)
+        {
+            diagnostics.push(Diagnostic::error(
+                arg.loc(),
+                eth_abi_type_error(&expr.ty(), ns),
+            ));
+
+            return Err(());
+        }
+
         resolved_args.push(expr);
     }
 
@@ -1363,6 +1770,113 @@ pub(super) fn resolve_namespace_call(
     })
 }
 
+/// The packed encoding has no lengths, so a type can only be encoded if every array element has
+/// a fixed size. Structs cannot be packed encoded either.
+fn packed_encoding_error(ty: &Type, ns: &Namespace) -> Option<String> {
+    let (elem_ty, inner_dims) = match ty {
+        Type::Array(elem_ty, dims) => (elem_ty.as_ref(), &dims[..dims.len() - 1]),
+        Type::Slice(elem_ty) => (elem_ty.as_ref(), &[][..]),
+        _ => (ty, &[][..]),
+    };
+
+    if matches!(elem_ty, Type::Struct(_)) {
+        Some(format!(
+            "Invalid type '{}': structs cannot be packed encoded",
+            ty.to_string(ns)
+        ))
+    } else if elem_ty != ty
+        && (elem_ty.is_dynamic(ns) || inner_dims.contains(&ArrayLength::Dynamic))
+    {
+        Some(format!(
+            "Invalid type '{}': arrays with elements of dynamic size cannot be packed encoded",
+            ty.to_string(ns)
+        ))
+    } else {
+        None
+    }
+}
+
+/// Can the type be encoded by `abi.ethEncode()` and decoded by `abi.ethDecode()`. Stylus uses the
+/// same encoder for its calls, events and `abi.encode()`.
+pub(super) fn eth_abi_type(ty: &Type, ns: &Namespace) -> bool {
+    matches!(
+        ty.clone().unwrap_user_type(ns),
+        Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Value
+            | Type::Address(_)
+            | Type::Contract(_)
+            | Type::Enum(_)
+            | Type::Bytes(_)
+            | Type::DynamicBytes
+            | Type::String
+    )
+}
+
+fn eth_abi_type_error(ty: &Type, ns: &Namespace) -> String {
+    format!(
+        "Invalid type '{}': only value types, 'bytes' and 'string' can be Ethereum ABI encoded or decoded",
+        ty.to_string(ns)
+    )
+}
+
+/// Resolve `string.toString(x)` and `string.toHexString(x)`. These take an integer of any size,
+/// and are the same as formatting the integer with `"{}".format(x)` or `"{:x}".format(x)`.
+fn format_integer(
+    loc: &pt::Loc,
+    name: &str,
+    args: &[pt::Expression],
+    context: &mut ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    if args.len() != 1 {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!("function expects {} arguments, {} provided", 1, args.len()),
+        ));
+
+        return Err(());
+    }
+
+    let expr = expression(
+        &args[0],
+        context,
+        ns,
+        symtable,
+        diagnostics,
+        ResolveTo::Integer,
+    )?;
+
+    let ty = expr.ty().deref_any().clone();
+
+    if !matches!(ty, Type::Int(_) | Type::Uint(_)) {
+        diagnostics.push(Diagnostic::error(
+            args[0].loc(),
+            format!(
+                "'string.{}' expects an integer argument, not '{}'",
+                name,
+                ty.to_string(ns)
+            ),
+        ));
+
+        return Err(());
+    }
+
+    let spec = if name == "toHexString" {
+        FormatArg::Hex
+    } else {
+        FormatArg::Default
+    };
+
+    Ok(Expression::FormatString {
+        loc: *loc,
+        format: vec![(spec, expr.cast(loc, &ty, true, ns, diagnostics)?)],
+    })
+}
+
 /// Resolve a builtin call
 pub(super) fn resolve_method_call(
     expr: &Expression,
@@ -1471,6 +1985,7 @@ impl Namespace {
             line_starts: Vec::new(),
             cache_no: None,
             import_no: None,
+            imports: Vec::new(),
         });
 
         let id = pt::Identifier {
@@ -1667,6 +2182,7 @@ impl Namespace {
             line_starts: Vec::new(),
             cache_no: None,
             import_no: None,
+            imports: Vec::new(),
         });
 
         // The Hash type from ink primitives.
@@ -1826,6 +2342,60 @@ impl Namespace {
                 }],
                 self,
             ),
+            // terminate API
+            Function::new(
+                loc,
+                loc,
+                pt::Identifier {
+                    name: "terminate".to_string(),
+                    loc,
+                },
+                None,
+                Vec::new(),
+                pt::FunctionTy::Function,
+                None,
+                pt::Visibility::Public(Some(loc)),
+                vec![Parameter {
+                    loc,
+                    id: Some(identifier("beneficiary")),
+                    ty: Type::Address(true),
+                    ty_loc: Some(loc),
+                    readonly: false,
+                    indexed: false,
+                    infinite_size: false,
+                    recursive: false,
+                    annotation: None,
+                }],
+                vec![],
+                self,
+            ),
+            // minimum_balance API
+            Function::new(
+                loc,
+                loc,
+                pt::Identifier {
+                    name: "minimum_balance".to_string(),
+                    loc,
+                },
+                None,
+                Vec::new(),
+                pt::FunctionTy::Function,
+                Some(pt::Mutability::View(loc)),
+                pt::Visibility::Public(Some(loc)),
+                vec![],
+                vec![Parameter {
+                    loc,
+                    id: Some(identifier("minimum_balance")),
+                    ty: Type::Value,
+                    ty_loc: Some(loc),
+                    readonly: false,
+                    indexed: false,
+                    infinite_size: false,
+                    recursive: false,
+                    annotation: None,
+                }],
+                self,
+            ),
             // caller_is_root API
             Function::new(
                 loc,
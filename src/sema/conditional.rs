@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::ast::{Diagnostic, Namespace};
+use solang_parser::pt::{self, CodeLocation};
+
+/// Names which can be compared with `target` in an `@if` condition
+const TARGET_NAMES: [&str; 5] = ["evm", "polkadot", "solana", "soroban", "stylus"];
+
+/// Items can be compiled for some targets only, with an annotation like
+/// `@if(target == "solana" || target == "polkadot")`. The `@if` annotations are removed from the
+/// list, and the result is whether the item should be included for the current target.
+pub(super) fn included(annotations: &mut Vec<&pt::Annotation>, ns: &mut Namespace) -> bool {
+    let mut include = true;
+
+    annotations.retain(|note| {
+        if note.id.name != "if" {
+            return true;
+        }
+
+        // the parser requires a value for annotations
+        let cond = note.value.as_ref().unwrap();
+
+        // errors are reported, and the item is left out
+        include &= condition(cond, ns).unwrap_or(false);
+
+        false
+    });
+
+    include
+}
+
+fn condition(expr: &pt::Expression, ns: &mut Namespace) -> Result<bool, ()> {
+    match expr {
+        pt::Expression::BoolLiteral(_, value) => Ok(*value),
+        pt::Expression::Parenthesis(_, expr) => condition(expr, ns),
+        pt::Expression::Not(_, expr) => Ok(!condition(expr, ns)?),
+        pt::Expression::And(_, left, right) => {
+            let left = condition(left, ns)?;
+            let right = condition(right, ns)?;
+
+            Ok(left && right)
+        }
+        pt::Expression::Or(_, left, right) => {
+            let left = condition(left, ns)?;
+            let right = condition(right, ns)?;
+
+            Ok(left || right)
+        }
+        pt::Expression::Equal(loc, left, right) => target_equals(loc, left, right, ns),
+        pt::Expression::NotEqual(loc, left, right) => Ok(!target_equals(loc, left, right, ns)?),
+        _ => {
+            ns.diagnostics.push(Diagnostic::error(
+                expr.loc(),
+                "'@if' condition should compare 'target' with a string, e.g. '@if(target == \"solana\")'"
+                    .into(),
+            ));
+            Err(())
+        }
+    }
+}
+
+fn target_equals(
+    loc: &pt::Loc,
+    left: &pt::Expression,
+    right: &pt::Expression,
+    ns: &mut Namespace,
+) -> Result<bool, ()> {
+    let name = match (left, right) {
+        (pt::Expression::Variable(id), pt::Expression::StringLiteral(name))
+        | (pt::Expression::StringLiteral(name), pt::Expression::Variable(id))
+            if id.name == "target" =>
+        {
+            name
+        }
+        _ => {
+            ns.diagnostics.push(Diagnostic::error(
+                *loc,
+                "'@if' condition should compare 'target' with a string, e.g. '@if(target == \"solana\")'"
+                    .into(),
+            ));
+            return Err(());
+        }
+    };
+
+    let loc = name[0].loc;
+    let name: String = name.iter().map(|s| s.string.as_str()).collect();
+
+    if !TARGET_NAMES.contains(&name.as_str()) {
+        ns.diagnostics.push(Diagnostic::error(
+            loc,
+            format!(
+                "unknown target '{}', expected one of {}",
+                name,
+                TARGET_NAMES.join(", ")
+            ),
+        ));
+        return Err(());
+    }
+
+    Ok(ns.target.to_string().to_lowercase() == name)
+}
@@ -8,10 +8,12 @@ use super::{
     symtable::Symtable,
     using, variables, ContractDefinition,
 };
+use crate::codegen::revert::{ERROR_SELECTOR, PANIC_SELECTOR};
 use crate::sema::ast::SolanaAccount;
 use crate::sema::expression::constructor::match_constructor_to_args;
-use crate::{sema::ast::Namespace, sema::unused_variable::emit_warning_local_variable};
+use crate::{sema::ast::Namespace, sema::unused_variable::emit_warning_local_variable, Target};
 use indexmap::{IndexMap, IndexSet};
+use itertools::Itertools;
 use num_bigint::BigInt;
 use num_traits::Zero;
 use once_cell::unsync::OnceCell;
@@ -39,6 +41,8 @@ impl ast::Contract {
             bases: Vec::new(),
             using: Vec::new(),
             layout: Vec::new(),
+            stripped_variables: Vec::new(),
+            immutables_in_code: BTreeMap::new(),
             fixed_layout_size: BigInt::zero(),
             tags,
             functions: Vec::new(),
@@ -51,9 +55,11 @@ impl ast::Contract {
             initializer: None,
             default_constructor: None,
             cfg: Vec::new(),
+            coverage_counters: Vec::new(),
             code: OnceCell::new(),
             instantiable,
             program_id: None,
+            account_discriminator: None,
         }
     }
 
@@ -91,6 +97,7 @@ pub fn resolve(contracts: &[ContractDefinition], file_no: usize, ns: &mut ast::N
         check_inheritance(contract_no, ns);
         mangle_function_names(contract_no, ns);
         verify_unique_selector(contract_no, ns);
+        verify_unique_error_selector(contract_no, ns);
         polkadot_requires_public_functions(contract_no, ns);
         unique_constructor_names(contract_no, ns);
         check_mangled_function_names(contract_no, ns);
@@ -275,6 +282,46 @@ pub fn is_base(base: usize, derived: usize, ns: &ast::Namespace) -> bool {
         .any(|parent| is_base(base, parent.contract_no, ns))
 }
 
+/// If a function is inherited from a base contract, a note with the chain of contracts it is
+/// inherited through, at the base in the list of bases of the contract
+fn inherited_through(
+    contract_no: usize,
+    func: &ast::Function,
+    ns: &ast::Namespace,
+) -> Option<ast::Note> {
+    let base_no = func.contract_no.filter(|base_no| *base_no != contract_no)?;
+
+    let mut path = vec![contract_no];
+
+    while let Some(base) = ns.contracts[*path.last().unwrap()]
+        .bases
+        .iter()
+        .find(|base| is_base(base_no, base.contract_no, ns))
+    {
+        path.push(base.contract_no);
+
+        if base.contract_no == base_no {
+            break;
+        }
+    }
+
+    let loc = ns.contracts[contract_no]
+        .bases
+        .iter()
+        .find(|base| base.contract_no == path[1])?
+        .loc;
+
+    Some(ast::Note {
+        loc,
+        message: format!(
+            "inherited through {}",
+            path.iter()
+                .map(|contract_no| format!("'{}'", ns.contracts[*contract_no].id))
+                .join(" -> ")
+        ),
+    })
+}
+
 /// Check the inheritance of all functions and other symbols
 fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
     let mut function_syms: HashMap<String, ast::Symbol> = HashMap::new();
@@ -624,28 +671,31 @@ fn check_inheritance(contract_no: usize, ns: &mut ast::Namespace) {
         // virtual functions without a body
         if list.len() == 1 {
             let loc = ns.contracts[contract_no].loc;
-            match func.ty {
-                pt::FunctionTy::Fallback | pt::FunctionTy::Receive => {
-                    diagnostics.push(ast::Diagnostic::error_with_note(
-                        loc,
-                        format!(
-                            "contract '{}' missing override for {} function",
-                            ns.contracts[contract_no].id, func.ty
-                        ),
-                        func.loc_prototype,
-                        format!("declaration of {} function", func.ty),
-                    ));
-                }
-                _ => diagnostics.push(ast::Diagnostic::error_with_note(
-                    loc,
+            let (message, declaration) = match func.ty {
+                pt::FunctionTy::Fallback | pt::FunctionTy::Receive => (
+                    format!(
+                        "contract '{}' missing override for {} function",
+                        ns.contracts[contract_no].id, func.ty
+                    ),
+                    format!("declaration of {} function", func.ty),
+                ),
+                _ => (
                     format!(
                         "contract '{}' missing override for function '{}'",
                         ns.contracts[contract_no].id, func.id
                     ),
-                    func.loc_prototype,
                     format!("declaration of function '{}'", func.id),
-                )),
-            }
+                ),
+            };
+
+            let notes = std::iter::once(ast::Note {
+                loc: func.loc_prototype,
+                message: declaration,
+            })
+            .chain(inherited_through(contract_no, func, ns))
+            .collect();
+
+            diagnostics.push(ast::Diagnostic::error_with_notes(loc, message, notes));
 
             continue;
         }
@@ -1285,7 +1335,7 @@ fn mangle_function_names(contract_no: usize, ns: &mut Namespace) {
 
 /// This check guarantees that each public Solidity function has a unique selector.
 fn verify_unique_selector(contract_no: usize, ns: &mut Namespace) {
-    let mut selectors: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut selectors: HashMap<(bool, Vec<u8>), usize> = HashMap::new();
     let mut diagnostics: Vec<Diagnostic> = Vec::new();
     for func_no in ns.contracts[contract_no].all_functions.keys() {
         let func = &ns.functions[*func_no];
@@ -1312,22 +1362,37 @@ fn verify_unique_selector(contract_no: usize, ns: &mut Namespace) {
             ));
         }
 
+        // Only functions which are dispatched on their selector can collide
+        if !matches!(func.ty, FunctionTy::Function | FunctionTy::Constructor) || !func.is_public() {
+            continue;
+        }
+
+        // On Solana, constructors are dispatched alongside functions. On other targets, they
+        // have their own dispatcher.
+        let selector = (
+            func.ty == FunctionTy::Constructor && ns.target != Target::Solana,
+            selector,
+        );
+
         if let Some(other_func_no) = selectors.get(&selector) {
             let other = &ns.functions[*other_func_no];
 
+            // Having more than one constructor is reported elsewhere
             if other.signature != func.signature
-                && func.ty != FunctionTy::Constructor
-                && func.is_public()
-                && other.is_public()
+                && (func.ty != FunctionTy::Constructor || other.ty != FunctionTy::Constructor)
             {
                 diagnostics.push(ast::Diagnostic::error_with_note(
                     func.loc_prototype,
                     format!(
-                        "{} '{}' selector is the same as {} '{}'",
-                        func.ty, func.id, other.ty, other.id
+                        "{} '{}' selector 0x{} is the same as {} '{}'",
+                        func.ty,
+                        func.signature,
+                        hex::encode(&selector.1),
+                        other.ty,
+                        other.signature
                     ),
                     other.loc_prototype,
-                    format!("definition of {} '{}'", other.ty, other.id),
+                    format!("definition of {} '{}'", other.ty, other.signature),
                 ));
             }
         } else {
@@ -1337,3 +1402,52 @@ fn verify_unique_selector(contract_no: usize, ns: &mut Namespace) {
 
     ns.diagnostics.append(&mut diagnostics);
 }
+
+/// Errors are identified by their four byte selector on every target. The errors a contract
+/// can revert with must have unique selectors, which must not be the same as the selectors of
+/// the builtin `Error(string)` and `Panic(uint256)`.
+fn verify_unique_error_selector(contract_no: usize, ns: &mut Namespace) {
+    let bases = ns.contract_bases(contract_no);
+    let mut selectors: HashMap<[u8; 4], (String, Option<pt::Loc>)> = HashMap::from([
+        (ERROR_SELECTOR, ("Error(string)".to_owned(), None)),
+        (PANIC_SELECTOR, ("Panic(uint256)".to_owned(), None)),
+    ]);
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+    for error in &ns.errors {
+        if matches!(error.contract, Some(error_contract_no) if !bases.contains(&error_contract_no))
+        {
+            continue;
+        }
+
+        let selector = error.selector(ns);
+        let signature = ns.signature(&error.name, &error.fields);
+
+        if let Some((other, other_loc)) = selectors.get(&selector) {
+            if *other == signature {
+                continue;
+            }
+
+            let message = format!(
+                "error '{}' selector 0x{} is the same as error '{}'",
+                signature,
+                hex::encode(selector),
+                other
+            );
+
+            diagnostics.push(match other_loc {
+                Some(other_loc) => ast::Diagnostic::error_with_note(
+                    error.loc,
+                    message,
+                    *other_loc,
+                    format!("definition of error '{other}'"),
+                ),
+                None => ast::Diagnostic::error(error.loc, message),
+            });
+        } else {
+            selectors.insert(selector, (signature, Some(error.loc)));
+        }
+    }
+
+    ns.diagnostics.append(&mut diagnostics);
+}
@@ -58,6 +58,12 @@ impl Diagnostics {
         self.contents.append(diagnostics);
     }
 
+    /// Keep the diagnostics for which the function returns true. The function may change them.
+    pub fn retain_mut(&mut self, f: impl FnMut(&mut Diagnostic) -> bool) {
+        self.contents.retain_mut(f);
+        self.has_error = self.contents.iter().any(|m| m.level == Level::Error);
+    }
+
     pub fn first_error(&self) -> String {
         match self.contents.iter().find(|m| m.level == Level::Error) {
             Some(m) => m.message.to_owned(),
@@ -127,6 +133,11 @@ impl Diagnostics {
         self.contents.sort();
         self.contents.dedup();
     }
+
+    /// Remove repeated entries, keeping the order
+    pub fn dedup(&mut self) {
+        self.contents.dedup();
+    }
 }
 
 fn convert_diagnostic(
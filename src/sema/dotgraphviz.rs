@@ -9,6 +9,7 @@ use crate::sema::{
         builtin::YulBuiltInFunction,
     },
 };
+use serde_json::json;
 use solang_parser::{pt, pt::Loc};
 use std::fmt::Write;
 
@@ -77,6 +78,37 @@ impl Dot {
         result
     }
 
+    /// Convert a node and its children into a JSON object. The edges from each node are given
+    /// in `children`.
+    fn json(
+        &self,
+        children: &[Vec<&Edge>],
+        node_no: usize,
+        relation: Option<&str>,
+    ) -> serde_json::Value {
+        let node = &self.nodes[node_no];
+
+        let children: Vec<serde_json::Value> = children[node_no]
+            .iter()
+            .map(|edge| self.json(children, edge.to, edge.label.as_deref()))
+            .collect();
+
+        let mut value = json!({
+            "node": node.name,
+            "labels": node.labels,
+        });
+
+        if let Some(relation) = relation {
+            value["relation"] = json!(relation);
+        }
+
+        if !children.is_empty() {
+            value["children"] = json!(children);
+        }
+
+        value
+    }
+
     fn add_node(
         &mut self,
         mut node: Node,
@@ -444,6 +476,7 @@ impl Dot {
                 ty,
                 left,
                 right,
+                ..
             } => {
                 let labels = vec![
                     String::from("divide"),
@@ -1455,6 +1488,15 @@ impl Dot {
         if let Some(flags) = &call_args.flags {
             self.add_expression(flags, func, ns, node, String::from("flags"));
         }
+        if let Some(storage_deposit_limit) = &call_args.storage_deposit_limit {
+            self.add_expression(
+                storage_deposit_limit,
+                func,
+                ns,
+                node,
+                String::from("storage_deposit_limit"),
+            );
+        }
     }
 
     fn add_string_location(
@@ -2437,6 +2479,30 @@ impl Dot {
 
 impl Namespace {
     pub fn dotgraphviz(&self) -> String {
+        let (dot, _) = self.build_dot();
+
+        dot.write()
+    }
+
+    /// Serialize the resolved AST of a contract as JSON. This is the same tree as the contract
+    /// node of [`Namespace::dotgraphviz`], and includes its bases, variables and functions.
+    pub fn ast_json(&self, contract_no: usize) -> String {
+        let (dot, contracts) = self.build_dot();
+
+        let mut children = vec![Vec::new(); dot.nodes.len()];
+
+        for edge in &dot.edges {
+            children[edge.from].push(edge);
+        }
+
+        let contract = children[contracts][contract_no].to;
+
+        serde_json::to_string_pretty(&dot.json(&children, contract, None)).unwrap()
+    }
+
+    /// Build the graph of the entire namespace. The node which has all the contracts as children
+    /// is returned as well.
+    fn build_dot(&self) -> (Dot, usize) {
         let mut dot = Dot {
             filename: format!("{}", self.files[self.top_file_no()].path.display()),
             nodes: Vec::new(),
@@ -2688,7 +2754,7 @@ impl Namespace {
                             label
                         })
                         .collect(),
-                    UsingList::Library(library_no) => {
+                    UsingList::Library(_, library_no) => {
                         let library = &self.contracts[*library_no];
 
                         vec![format!("library {}", library.id)]
@@ -2785,6 +2851,6 @@ impl Namespace {
             }
         }
 
-        dot.write()
+        (dot, contracts)
     }
 }
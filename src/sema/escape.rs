@@ -0,0 +1,204 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{
+    ast::{
+        ArrayLength, Builtin, DestructureField, Expression, Function, Namespace, Statement, Type,
+    },
+    Recurse,
+};
+use solang_parser::pt;
+use std::collections::HashSet;
+
+/// Find the parameters of external functions which are only ever read. A `bytes` or
+/// array-of-value-types parameter which is never assigned, modified, passed to another
+/// function or used in any way other than subscript reads and `.length` does not escape
+/// the function body. Codegen may decode such a parameter as a slice pointing into the
+/// input buffer, rather than copying it to the heap.
+pub fn escape_analysis(file_no: usize, ns: &mut Namespace) {
+    if ns.diagnostics.any_errors() {
+        return;
+    }
+
+    for func_no in 0..ns.functions.len() {
+        let func = &ns.functions[func_no];
+
+        if func.loc_prototype.try_file_no() != Some(file_no)
+            || func.ty != pt::FunctionTy::Function
+            || !matches!(func.visibility, pt::Visibility::External(_))
+            || !func.has_body
+        {
+            continue;
+        }
+
+        let read_only_params = read_only_params(func, ns);
+
+        ns.functions[func_no].read_only_params = read_only_params;
+    }
+}
+
+/// While we recurse through the AST, maintain the parameters that have not escaped
+struct EscapeCheck {
+    /// Maps the variable number of the candidate parameter to its index
+    candidates: Vec<(usize, usize)>,
+}
+
+impl EscapeCheck {
+    fn is_candidate(&self, var_no: usize) -> bool {
+        self.candidates.iter().any(|(no, _)| *no == var_no)
+    }
+
+    fn escape(&mut self, var_no: usize) {
+        self.candidates.retain(|(no, _)| *no != var_no);
+    }
+}
+
+fn read_only_params(func: &Function, ns: &Namespace) -> HashSet<usize> {
+    let candidates = func
+        .symtable
+        .arguments
+        .iter()
+        .enumerate()
+        .filter_map(|(param_no, var_no)| {
+            let var_no = (*var_no)?;
+            let var = &func.symtable.vars[&var_no];
+
+            if !var.assigned && is_candidate_type(&func.params[param_no].ty, ns) {
+                Some((var_no, param_no))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        return HashSet::new();
+    }
+
+    let mut state = EscapeCheck { candidates };
+
+    for modifier in &func.modifiers {
+        modifier.recurse(&mut state, check_expression);
+    }
+
+    recurse_statements(&func.body, &mut state);
+
+    state
+        .candidates
+        .into_iter()
+        .map(|(_, param_no)| param_no)
+        .collect()
+}
+
+/// Only `bytes` and single dimension dynamic arrays of value types can be sliced
+fn is_candidate_type(ty: &Type, ns: &Namespace) -> bool {
+    match ty {
+        Type::DynamicBytes => true,
+        Type::Array(elem_ty, dims) => {
+            dims.as_slice() == [ArrayLength::Dynamic] && !elem_ty.is_reference_type(ns)
+        }
+        _ => false,
+    }
+}
+
+fn recurse_statements(stmts: &[Statement], state: &mut EscapeCheck) {
+    for stmt in stmts {
+        if state.candidates.is_empty() {
+            return;
+        }
+
+        match stmt {
+            Statement::Block { statements, .. } => recurse_statements(statements, state),
+            Statement::VariableDecl(_, _, _, Some(expr)) => {
+                expr.recurse(state, check_expression);
+            }
+            Statement::Expression(_, _, expr) | Statement::Return(_, Some(expr)) => {
+                expr.recurse(state, check_expression);
+            }
+            Statement::VariableDecl(_, _, _, None)
+            | Statement::Return(_, None)
+            | Statement::Delete(..)
+            | Statement::Break(_)
+            | Statement::Continue(_)
+            | Statement::Underscore(_) => (),
+            Statement::If(_, _, expr, then_, else_) => {
+                expr.recurse(state, check_expression);
+                recurse_statements(then_, state);
+                recurse_statements(else_, state);
+            }
+            Statement::DoWhile(_, _, body, expr) | Statement::While(_, _, expr, body) => {
+                expr.recurse(state, check_expression);
+                recurse_statements(body, state);
+            }
+            Statement::For {
+                init,
+                cond,
+                next,
+                body,
+                ..
+            } => {
+                recurse_statements(init, state);
+                if let Some(cond) = cond {
+                    cond.recurse(state, check_expression);
+                }
+                if let Some(next) = next {
+                    next.recurse(state, check_expression);
+                }
+                recurse_statements(body, state);
+            }
+            Statement::Destructure(_, fields, expr) => {
+                expr.recurse(state, check_expression);
+
+                for field in fields {
+                    if let DestructureField::Expression(expr) = field {
+                        expr.recurse(state, check_expression);
+                    }
+                }
+            }
+            Statement::TryCatch(_, _, try_catch) => {
+                try_catch.expr.recurse(state, check_expression);
+                recurse_statements(&try_catch.ok_stmt, state);
+                for clause in &try_catch.errors {
+                    recurse_statements(&clause.stmt, state);
+                }
+                if let Some(clause) = &try_catch.catch_all {
+                    recurse_statements(&clause.stmt, state);
+                }
+            }
+            Statement::Emit { args, .. } | Statement::Revert { args, .. } => {
+                for arg in args {
+                    arg.recurse(state, check_expression);
+                }
+            }
+            // Inline assembly can take the offset or pointer of any variable
+            Statement::Assembly(..) => state.candidates.clear(),
+        }
+    }
+}
+
+fn check_expression(expr: &Expression, state: &mut EscapeCheck) -> bool {
+    match expr {
+        Expression::Subscript { array, index, .. } => {
+            if let Expression::Variable { var_no, .. } = array.as_ref() {
+                if state.is_candidate(*var_no) {
+                    index.recurse(state, check_expression);
+                    return false;
+                }
+            }
+        }
+        Expression::Builtin {
+            kind: Builtin::ArrayLength,
+            args,
+            ..
+        } => {
+            if let [Expression::Variable { var_no, .. }] = args.as_slice() {
+                if state.is_candidate(*var_no) {
+                    return false;
+                }
+            }
+        }
+        Expression::Variable { var_no, .. } => state.escape(*var_no),
+        _ => (),
+    }
+
+    true
+}
@@ -476,6 +476,7 @@ pub(crate) fn eval_constants_in_expression(
             ty,
             left,
             right,
+            ..
         } => {
             let left = eval_constants_in_expression(left, diagnostics).0;
             let right = eval_constants_in_expression(right, diagnostics).0;
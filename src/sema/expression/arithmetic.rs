@@ -397,6 +397,7 @@ pub(super) fn divide(
     Ok(Expression::Divide {
         loc: *loc,
         ty: ty.clone(),
+        unchecked: context.unchecked,
         left: Box::new(left.cast(&l.loc(), &ty, true, ns, diagnostics)?),
         right: Box::new(right.cast(&r.loc(), &ty, true, ns, diagnostics)?),
     })
@@ -482,22 +483,55 @@ pub(super) fn power(
         };
     }
 
-    let exp = expression(e, context, ns, symtable, diagnostics, resolve_to)?;
+    let base_type = base.ty();
+
+    // A signed base is raised to an unsigned exponent, and the result has the
+    // type of the base
+    let exp = if base_type.is_signed_int(ns) {
+        expression(e, context, ns, symtable, diagnostics, ResolveTo::Integer)?
+    } else {
+        expression(e, context, ns, symtable, diagnostics, resolve_to)?
+    };
 
     check_var_usage_expression(ns, &base, &exp, symtable);
 
-    let base_type = base.ty();
     let exp_type = exp.ty();
 
-    // solc-0.5.13 does not allow either base or exp to be signed
-    if base_type.is_signed_int(ns) || exp_type.is_signed_int(ns) {
+    if exp_type.is_signed_int(ns) {
         diagnostics.push(Diagnostic::error(
             *loc,
-            "exponation (**) is not allowed with signed types".to_string(),
+            "exponent of exponation (**) must be unsigned".to_string(),
         ));
         return Err(());
     }
 
+    if base_type.is_signed_int(ns) {
+        let (base_bits, _) = type_bits_and_sign(&base_type, &b.loc(), false, ns, diagnostics)?;
+        let (exp_bits, _) = type_bits_and_sign(&exp_type, &e.loc(), false, ns, diagnostics)?;
+
+        if exp_bits > base_bits {
+            diagnostics.push(Diagnostic::error(
+                e.loc(),
+                format!(
+                    "exponent type '{}' is wider than base type '{}'",
+                    exp_type.to_string(ns),
+                    base_type.to_string(ns)
+                ),
+            ));
+            return Err(());
+        }
+
+        // The exponent is only ever inspected bit by bit, so reinterpreting it
+        // as the signed base type does not change its value
+        return Ok(Expression::Power {
+            loc: *loc,
+            ty: base_type.clone(),
+            unchecked: context.unchecked,
+            exp: Box::new(exp.cast(&e.loc(), &base_type, false, ns, diagnostics)?),
+            base: Box::new(base),
+        });
+    }
+
     let ty = coerce_number(
         &base_type,
         &b.loc(),
@@ -958,7 +992,8 @@ pub(super) fn incr_decr(
 
 // When generating shifts, llvm wants both arguments to have the same width. We want the
 // result of the shift to be left argument, so this function coercies the right argument
-// into the right length.
+// into the right length. The right argument is extended according to its own sign, so that
+// `int256(x) << uint8(200)` does not shift by a negative amount.
 pub fn cast_shift_arg(
     loc: &pt::Loc,
     expr: Expression,
@@ -967,16 +1002,17 @@ pub fn cast_shift_arg(
     ns: &Namespace,
 ) -> Expression {
     let to_width = ty.bits(ns);
+    let signed = expr.ty().deref_any().is_signed_int(ns);
 
     if from_width == to_width {
         expr
-    } else if from_width < to_width && ty.is_signed_int(ns) {
+    } else if from_width < to_width && signed {
         Expression::SignExt {
             loc: *loc,
             to: ty.clone(),
             expr: Box::new(expr),
         }
-    } else if from_width < to_width && !ty.is_signed_int(ns) {
+    } else if from_width < to_width && !signed {
         Expression::ZeroExt {
             loc: *loc,
             to: ty.clone(),
@@ -305,6 +305,7 @@ pub(super) fn assign_expr(
             pt::Expression::AssignDivide(..) => Expression::Divide {
                 loc: *loc,
                 ty: ty.clone(),
+                unchecked: context.unchecked,
                 left: Box::new(assign),
                 right: Box::new(set),
             },
@@ -10,6 +10,7 @@ use crate::sema::expression::{ExprContext, ResolveTo};
 use crate::sema::namespace::ResolveTypeContext;
 use crate::sema::symtable::Symtable;
 use crate::sema::unused_variable::used_variable;
+use crate::target::Capability;
 use solang_parser::diagnostics::Diagnostic;
 use solang_parser::pt;
 use solang_parser::pt::{CodeLocation, Visibility};
@@ -252,6 +253,10 @@ pub fn constructor_named_args(
         }
     };
 
+    if !ns.target_supports(loc, Capability::ContractCreation, diagnostics) {
+        return Err(());
+    }
+
     let call_args = parse_call_args(
         loc,
         &call_args,
@@ -553,6 +558,10 @@ pub fn new(
         }
         Type::String | Type::DynamicBytes => {}
         Type::Contract(n) => {
+            if !ns.target_supports(loc, Capability::ContractCreation, diagnostics) {
+                return Err(());
+            }
+
             let call_args = parse_call_args(
                 loc,
                 &call_args,
@@ -15,8 +15,9 @@ use crate::sema::expression::{ExprContext, ResolveTo};
 use crate::sema::format::string_format;
 use crate::sema::namespace::ResolveTypeContext;
 use crate::sema::symtable::Symtable;
-use crate::sema::unused_variable::check_function_call;
+use crate::sema::unused_variable::{check_function_call, used_variable};
 use crate::sema::{builtin, using};
+use crate::target::Capability;
 use crate::Target;
 use num_bigint::{BigInt, Sign};
 use solang_parser::diagnostics::{Diagnostic, Note};
@@ -671,7 +672,12 @@ fn try_namespace(
     };
 
     if let Some(namespace) = &namespace {
-        if builtin::is_builtin_call(Some(&namespace.name), &func.name, ns) {
+        // math.* was added after many contracts already declared their own math library
+        let shadowed = namespace.name == "math"
+            && (symtable.find(context, &namespace.name).is_some()
+                || ns.resolve_contract(context.file_no, namespace).is_some());
+
+        if !shadowed && builtin::is_builtin_call(Some(&namespace.name), &func.name, ns) {
             if let Some(loc) = call_args_loc {
                 diagnostics.push(Diagnostic::error(
                     loc,
@@ -992,6 +998,19 @@ fn try_storage_reference(
                     return Err(());
                 }
 
+                // Soroban keeps bytes in storage as a host object, which cannot be told apart
+                // from a storage array of bytes1 when pushing or popping
+                if ns.target == Target::Soroban && (func.name == "push" || func.name == "pop") {
+                    diagnostics.push(Diagnostic::error(
+                        func.loc,
+                        format!(
+                            "method '{}()' on storage bytes is not supported on {}",
+                            func.name, ns.target
+                        ),
+                    ));
+                    return Err(());
+                }
+
                 if func.name == "push" {
                     let mut builtin_args = vec![var_expr.clone()];
 
@@ -1052,6 +1071,75 @@ fn try_storage_reference(
                     }));
                 }
             }
+            Type::Mapping(_) if func.name == "keys" || func.name == "keyAt" => {
+                let Some(enumerable) = var_expr.enumerable_mapping(ns) else {
+                    return Ok(None);
+                };
+
+                if let Some(loc) = call_args_loc {
+                    diagnostics.push(Diagnostic::error(
+                        loc,
+                        "call arguments not allowed on mappings".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                used_variable(ns, var_expr, symtable);
+
+                let keys = enumerable.keys_array(var_expr.loc(), ns);
+                let keys_ty = keys.ty();
+
+                if func.name == "keys" {
+                    if !args.is_empty() {
+                        diagnostics.push(Diagnostic::error(
+                            func.loc,
+                            "method 'keys()' does not take any arguments".to_string(),
+                        ));
+                        return Err(());
+                    }
+
+                    // a copy of the keys in memory
+                    return Ok(Some(keys.cast(
+                        loc,
+                        keys_ty.deref_any(),
+                        true,
+                        ns,
+                        diagnostics,
+                    )?));
+                }
+
+                if args.len() != 1 {
+                    diagnostics.push(Diagnostic::error(
+                        func.loc,
+                        "method 'keyAt()' takes 1 argument".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                let index = expression(
+                    &args[0],
+                    context,
+                    ns,
+                    symtable,
+                    diagnostics,
+                    ResolveTo::Type(&Type::Uint(256)),
+                )?
+                .cast(&args[0].loc(), &Type::Uint(256), true, ns, diagnostics)?;
+
+                let elem_ty = keys_ty.storage_array_elem();
+                let key_ty = elem_ty.deref_any().clone();
+
+                return Ok(Some(
+                    Expression::Subscript {
+                        loc: *loc,
+                        ty: elem_ty,
+                        array_ty: keys_ty,
+                        array: Box::new(keys),
+                        index: Box::new(index),
+                    }
+                    .cast(loc, &key_ty, true, ns, diagnostics)?,
+                ));
+            }
             _ => {}
         }
     }
@@ -1261,9 +1349,74 @@ fn try_type_method(
                     args: vec![var_expr.clone()],
                 }));
             }
+            if func.name == "reserve" {
+                if var_ty.is_contract_storage() {
+                    diagnostics.push(Diagnostic::error(
+                        func.loc,
+                        "method 'reserve()' is only available for memory arrays".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                if args.len() != 1 {
+                    diagnostics.push(Diagnostic::error(
+                        func.loc,
+                        "method 'reserve()' takes 1 argument".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                let expected_ty = Type::Uint(32);
+
+                let size_expr = expression(
+                    &args[0],
+                    context,
+                    ns,
+                    symtable,
+                    diagnostics,
+                    ResolveTo::Type(&expected_ty),
+                )?;
+
+                used_variable(ns, &size_expr, symtable);
+
+                let size_ty = size_expr.ty();
+
+                if !matches!(size_ty.deref_any(), Type::Uint(_)) {
+                    diagnostics.push(Diagnostic::error(
+                        size_expr.loc(),
+                        "method 'reserve()' takes an unsigned length".to_string(),
+                    ));
+                    return Err(());
+                }
+
+                let size_loc = size_expr.loc();
+
+                let size = if size_ty.deref_any().bits(ns) > 32 {
+                    Expression::CheckingTrunc {
+                        loc: size_loc,
+                        to: expected_ty,
+                        expr: Box::new(size_expr.cast(
+                            &size_loc,
+                            &size_ty,
+                            true,
+                            ns,
+                            diagnostics,
+                        )?),
+                    }
+                } else {
+                    size_expr.cast(&size_loc, &expected_ty, true, ns, diagnostics)?
+                };
+
+                return Ok(Some(Expression::Builtin {
+                    loc: *loc,
+                    tys: vec![Type::Void],
+                    kind: Builtin::ArrayReserve,
+                    args: vec![var_expr.clone(), size],
+                }));
+            }
         }
 
-        Type::Array(..) if func.name == "push" || func.name == "pop" => {
+        Type::Array(..) if func.name == "push" || func.name == "pop" || func.name == "reserve" => {
             diagnostics.push(Diagnostic::error(
                 func.loc,
                 format!(
@@ -1292,16 +1445,7 @@ fn try_type_method(
 
         Type::Address(is_payable) => {
             if func.name == "transfer" || func.name == "send" {
-                if ns.target == Target::Solana {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "method '{}' not available on Solana. Use the lamports \
-                        field from the AccountInfo struct directly to operate on balances.",
-                            func.name
-                        ),
-                    ));
-
+                if !ns.target_supports(loc, Capability::ValueTransfer, diagnostics) {
                     return Err(());
                 }
 
@@ -1371,101 +1515,164 @@ fn try_type_method(
 
             let ty = match func.name.as_str() {
                 "call" => Some(CallTy::Regular),
-                "delegatecall" if ns.target != Target::Solana => Some(CallTy::Delegate),
-                "staticcall" if ns.target == Target::EVM => Some(CallTy::Static),
+                "delegatecall" => {
+                    if !ns.target_supports(&func.loc, Capability::DelegateCall, diagnostics) {
+                        return Err(());
+                    }
+
+                    Some(CallTy::Delegate)
+                }
+                "staticcall" if matches!(ns.target, Target::EVM | Target::Stylus) => {
+                    Some(CallTy::Static)
+                }
                 _ => None,
             };
 
             if let Some(ty) = ty {
-                let call_args = parse_call_args(
+                let address = var_expr.cast(
+                    &var_expr.loc(),
+                    &Type::Address(*is_payable),
+                    true,
+                    ns,
+                    diagnostics,
+                )?;
+
+                return raw_external_call(
                     loc,
+                    func,
+                    ty,
+                    address,
+                    args,
                     call_args,
-                    None,
-                    true,
                     context,
                     ns,
                     symtable,
                     diagnostics,
-                )?;
+                )
+                .map(Some);
+            }
+        }
 
-                if ty != CallTy::Regular && call_args.value.is_some() {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!("'{}' cannot have value specified", func.name,),
-                    ));
+        Type::UserType(no) if func.name == "delegatecall" && is_polkadot_code_hash(*no, ns) => {
+            // On Polkadot, delegatecall may also target a code hash directly
+            return raw_external_call(
+                loc,
+                func,
+                CallTy::Delegate,
+                var_expr.clone(),
+                args,
+                call_args,
+                context,
+                ns,
+                symtable,
+                diagnostics,
+            )
+            .map(Some);
+        }
 
-                    return Err(());
-                }
+        _ => (),
+    }
 
-                if ty == CallTy::Delegate && ns.target.is_polkadot() && call_args.gas.is_some() {
-                    diagnostics.push(Diagnostic::warning(
-                        *loc,
-                        "'gas' specified on 'delegatecall' will be ignored".into(),
-                    ));
-                }
+    Ok(None)
+}
 
-                if args.len() != 1 {
-                    diagnostics.push(Diagnostic::error(
-                        *loc,
-                        format!(
-                            "'{}' expects 1 argument, {} provided",
-                            func.name,
-                            args.len()
-                        ),
-                    ));
+/// Is the given user type the builtin `Hash` type, i.e. a Polkadot code hash?
+fn is_polkadot_code_hash(type_no: usize, ns: &Namespace) -> bool {
+    let decl = &ns.user_types[type_no];
 
-                    return Err(());
-                }
+    ns.target.is_polkadot() && decl.loc == pt::Loc::Builtin && decl.name == "Hash"
+}
 
-                let args = expression(
-                    &args[0],
-                    context,
-                    ns,
-                    symtable,
-                    diagnostics,
-                    ResolveTo::Type(&Type::DynamicBytes),
-                )?;
+/// Resolve a low level `call`, `delegatecall` or `staticcall` to either an address or,
+/// for `delegatecall` on Polkadot, a code hash.
+fn raw_external_call(
+    loc: &pt::Loc,
+    func: &pt::Identifier,
+    ty: CallTy,
+    address: Expression,
+    args: &[pt::Expression],
+    call_args: &[&pt::NamedArgument],
+    context: &mut ExprContext,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Diagnostics,
+) -> Result<Expression, ()> {
+    let call_args = parse_call_args(
+        loc,
+        call_args,
+        None,
+        true,
+        context,
+        ns,
+        symtable,
+        diagnostics,
+    )?;
 
-                let mut args_ty = args.ty();
+    if ty != CallTy::Regular && call_args.value.is_some() {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!("'{}' cannot have value specified", func.name,),
+        ));
 
-                match args_ty.deref_any() {
-                    Type::DynamicBytes => (),
-                    Type::Bytes(_) => {
-                        args_ty = Type::DynamicBytes;
-                    }
-                    Type::Array(..) | Type::Struct(..) if !args_ty.is_dynamic(ns) => {}
-                    _ => {
-                        diagnostics.push(Diagnostic::error(
-                            args.loc(),
-                            format!("'{}' is not fixed length type", args_ty.to_string(ns),),
-                        ));
+        return Err(());
+    }
 
-                        return Err(());
-                    }
-                }
+    if ty == CallTy::Delegate && ns.target.is_polkadot() && call_args.gas.is_some() {
+        diagnostics.push(Diagnostic::warning(
+            *loc,
+            "'gas' specified on 'delegatecall' will be ignored".into(),
+        ));
+    }
 
-                let args = args.cast(&args.loc(), args_ty.deref_any(), true, ns, diagnostics)?;
+    if args.len() != 1 {
+        diagnostics.push(Diagnostic::error(
+            *loc,
+            format!(
+                "'{}' expects 1 argument, {} provided",
+                func.name,
+                args.len()
+            ),
+        ));
 
-                return Ok(Some(Expression::ExternalFunctionCallRaw {
-                    loc: *loc,
-                    ty,
-                    args: Box::new(args),
-                    address: Box::new(var_expr.cast(
-                        &var_expr.loc(),
-                        &Type::Address(*is_payable),
-                        true,
-                        ns,
-                        diagnostics,
-                    )?),
-                    call_args,
-                }));
-            }
+        return Err(());
+    }
+
+    let args = expression(
+        &args[0],
+        context,
+        ns,
+        symtable,
+        diagnostics,
+        ResolveTo::Type(&Type::DynamicBytes),
+    )?;
+
+    let mut args_ty = args.ty();
+
+    match args_ty.deref_any() {
+        Type::DynamicBytes => (),
+        Type::Bytes(_) => {
+            args_ty = Type::DynamicBytes;
         }
+        Type::Array(..) | Type::Struct(..) if !args_ty.is_dynamic(ns) => {}
+        _ => {
+            diagnostics.push(Diagnostic::error(
+                args.loc(),
+                format!("'{}' is not fixed length type", args_ty.to_string(ns),),
+            ));
 
-        _ => (),
+            return Err(());
+        }
     }
 
-    Ok(None)
+    let args = args.cast(&args.loc(), args_ty.deref_any(), true, ns, diagnostics)?;
+
+    Ok(Expression::ExternalFunctionCallRaw {
+        loc: *loc,
+        ty,
+        args: Box::new(args),
+        address: Box::new(address),
+        call_args,
+    })
 }
 
 /// Resolve a method call with positional arguments
@@ -1975,12 +2182,7 @@ pub(super) fn parse_call_args(
     for arg in args.values() {
         match arg.name.name.as_str() {
             "value" => {
-                if ns.target == Target::Solana {
-                    diagnostics.push(Diagnostic::error(
-                        arg.loc,
-                        "Solana Cross Program Invocation (CPI) cannot transfer native value. See https://solang.readthedocs.io/en/latest/language/functions.html#value_transfer".to_string(),
-                    ));
-
+                if !ns.target_supports(&arg.loc, Capability::CallValue, diagnostics) {
                     expression(
                         &arg.expr,
                         context,
@@ -2041,14 +2243,7 @@ pub(super) fn parse_call_args(
                 )?));
             }
             "salt" => {
-                if ns.target == Target::Solana {
-                    diagnostics.push(Diagnostic::error(
-                        arg.loc,
-                        format!(
-                            "'salt' not permitted for external calls or constructors on {}",
-                            ns.target
-                        ),
-                    ));
+                if !ns.target_supports(&arg.loc, Capability::Create2Salt, diagnostics) {
                     return Err(());
                 }
 
@@ -2211,6 +2406,28 @@ pub(super) fn parse_call_args(
                 let flags = expr.cast(&arg.expr.loc(), &ty, true, ns, diagnostics)?;
                 res.flags = Some(flags.into());
             }
+            "storage_deposit_limit" => {
+                if !ns.target.is_polkadot() || external_call {
+                    diagnostics.push(Diagnostic::error(
+                        arg.loc,
+                        "'storage_deposit_limit' is only permitted for constructors on polkadot"
+                            .into(),
+                    ));
+                    return Err(());
+                }
+
+                let ty = Type::Value;
+                let expr = expression(
+                    &arg.expr,
+                    context,
+                    ns,
+                    symtable,
+                    diagnostics,
+                    ResolveTo::Type(&ty),
+                )?;
+                let limit = expr.cast(&arg.expr.loc(), &ty, true, ns, diagnostics)?;
+                res.storage_deposit_limit = Some(limit.into());
+            }
             _ => {
                 diagnostics.push(Diagnostic::error(
                     arg.loc,
@@ -2505,6 +2722,24 @@ pub fn function_call_expr(
                     return Err(());
                 }
 
+                let function_nos =
+                    available_functions(&id.name, true, context.file_no, context.contract_no, ns);
+
+                if function_nos.is_empty() {
+                    if let Some(to) = builtin::checked_conversion(&id.name) {
+                        return builtin::resolve_checked_conversion(
+                            loc,
+                            &id.name,
+                            to,
+                            args,
+                            context,
+                            ns,
+                            symtable,
+                            diagnostics,
+                        );
+                    }
+                }
+
                 let id_path = pt::IdentifierPath {
                     loc: id.loc,
                     identifiers: vec![id.clone()],
@@ -2515,7 +2750,7 @@ pub fn function_call_expr(
                     &id_path,
                     pt::FunctionTy::Function,
                     args,
-                    available_functions(&id.name, true, context.file_no, context.contract_no, ns),
+                    function_nos,
                     true,
                     context,
                     ns,
@@ -133,7 +133,7 @@ pub(crate) fn hex_number_literal(
     if n.starts_with("0x") && !n.chars().any(|c| c == '_') && n.len() == 42 {
         let address = to_hexstr_eip55(n);
 
-        if ns.target == Target::EVM {
+        if matches!(ns.target, Target::EVM | Target::Stylus) {
             return if address == *n {
                 let s: String = address.chars().skip(2).collect();
 
@@ -544,7 +544,9 @@ pub(crate) fn unit_literal(
 ) -> BigInt {
     if let Some(unit) = unit {
         match unit.name.as_str() {
-            "wei" | "gwei" | "ether" if ns.target != crate::Target::EVM => {
+            "wei" | "gwei" | "ether"
+                if !matches!(ns.target, crate::Target::EVM | crate::Target::Stylus) =>
+            {
                 diagnostics.push(Diagnostic::warning(
                     *loc,
                     format!("ethereum currency unit used while targeting {}", ns.target),
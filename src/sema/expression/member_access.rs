@@ -350,38 +350,53 @@ pub(super) fn member_access(
                     });
                 }
             }
+            Type::Mapping(_) if id.name == "length" => {
+                // the number of keys of an enumerable mapping
+                if let Some(enumerable) = expr.enumerable_mapping(ns) {
+                    used_variable(ns, &expr, symtable);
+
+                    let keys = enumerable.keys_array(expr.loc(), ns);
+                    let elem_ty = keys.ty().storage_array_elem().deref_into();
+
+                    return Ok(Expression::StorageArrayLength {
+                        loc: id.loc,
+                        ty: ns.storage_type(),
+                        array: Box::new(keys),
+                        elem_ty,
+                    });
+                }
+            }
             _ => {}
         },
         Type::Address(_) if id.name == "balance" => {
-            if ns.target.is_polkadot() {
-                let mut is_this = false;
-
-                if let Expression::Cast { expr: this, .. } = &expr {
-                    if let Expression::Builtin {
-                        kind: Builtin::GetAddress,
-                        ..
-                    } = this.as_ref()
-                    {
-                        is_this = true;
-                    }
-                }
+            let mut is_this = false;
 
-                if !is_this {
-                    diagnostics.push(Diagnostic::error(
-                        expr.loc(),
-                        "polkadot can only retrieve balance of 'this', like 'address(this).balance'"
-                            .to_string(),
-                    ));
-                    return Err(());
+            if let Expression::Cast { expr: this, .. } = &expr {
+                if let Expression::Builtin {
+                    kind: Builtin::GetAddress,
+                    ..
+                } = this.as_ref()
+                {
+                    is_this = true;
                 }
-            } else if ns.target == Target::Solana {
+            }
+
+            if ns.target.is_polkadot() && !is_this {
                 diagnostics.push(Diagnostic::error(
                     expr.loc(),
-                    "balance is not available on Solana. Use \
-                    tx.accounts.account_name.lamports to fetch the balance."
+                    "polkadot can only retrieve balance of 'this', like 'address(this).balance'"
                         .to_string(),
                 ));
                 return Err(());
+            } else if ns.target == Target::Solana && is_this {
+                // On Solana, the lamports of a contract are held by its data account, since
+                // a program cannot spend the lamports of its program account
+                return Ok(Expression::Builtin {
+                    loc: *loc,
+                    tys: vec![Type::Value],
+                    kind: Builtin::Balance,
+                    args: Vec::new(),
+                });
             }
             used_variable(ns, &expr, symtable);
             return Ok(Expression::Builtin {
@@ -14,12 +14,16 @@ mod subscript;
 mod tests;
 mod variable;
 
-use super::ast::{ArrayLength, Diagnostic, Expression, Mutability, Namespace, RetrieveType, Type};
+use super::ast::{
+    ArrayLength, Diagnostic, EnumerableMapping, Expression, Mutability, Namespace, RetrieveType,
+    Type,
+};
 use super::diagnostics::Diagnostics;
 use super::eval::eval_const_rational;
 use super::symtable::{Symtable, VarScope};
 use crate::sema::contracts::is_base;
 use crate::sema::eval::eval_const_number;
+use crate::sema::warnings::STRICT_CONVERSION_NOTE;
 use crate::sema::{symtable::LoopScopes, using::user_defined_operator_binding};
 use num_bigint::{BigInt, Sign};
 use num_rational::BigRational;
@@ -103,6 +107,21 @@ impl Expression {
         }
     }
 
+    /// If this is a mapping state variable declared `@enumerable`, the storage variables which
+    /// keep track of its keys
+    pub fn enumerable_mapping(&self, ns: &Namespace) -> Option<EnumerableMapping> {
+        if let Expression::StorageVariable {
+            contract_no,
+            var_no,
+            ..
+        } = self
+        {
+            ns.contracts[*contract_no].variables[*var_no].enumerable
+        } else {
+            None
+        }
+    }
+
     /// Return the type for this expression.
     pub fn tys(&self) -> Vec<Type> {
         match self {
@@ -572,6 +591,11 @@ impl Expression {
                 }),
             },
             (Type::Uint(from_len), Type::Int(to_len)) if to_len > from_len => {
+                if implicit && ns.strict_conversions {
+                    diagnostics.push(strict_conversion_error(loc, "change sign", from, to, ns));
+                    return Err(());
+                }
+
                 Ok(Expression::ZeroExt {
                     loc: *loc,
                     to: to.clone(),
@@ -923,6 +947,10 @@ impl Expression {
                 to: to.clone(),
                 expr: Box::new(self.clone()),
             }),
+            (Type::DynamicBytes, Type::Bytes(_)) if implicit && ns.strict_conversions => {
+                diagnostics.push(strict_conversion_error(loc, "truncate", from, to, ns));
+                Err(())
+            }
             (Type::Bytes(_), Type::DynamicBytes) | (Type::DynamicBytes, Type::Bytes(_)) => {
                 Ok(Expression::BytesCast {
                     loc: *loc,
@@ -1299,6 +1327,28 @@ impl Expression {
     }
 }
 
+/// An implicit conversion which solc 0.8 does not do, and which is an error with strict
+/// conversions. The note gives the explicit conversion to write around the expression.
+fn strict_conversion_error(
+    loc: &pt::Loc,
+    change: &str,
+    from: &Type,
+    to: &Type,
+    ns: &Namespace,
+) -> Diagnostic {
+    let to = to.to_string(ns);
+
+    Diagnostic::cast_error_with_note(
+        *loc,
+        format!(
+            "implicit conversion would {change} from {} to {to}",
+            from.to_string(ns)
+        ),
+        *loc,
+        format!("{STRICT_CONVERSION_NOTE}'{to}(...)'"),
+    )
+}
+
 /// Can this type be cast to a bytes slice
 fn can_cast_to_slice(ty: &Type) -> bool {
     matches!(
@@ -136,7 +136,19 @@ pub(super) fn variable(
             }
 
             if name_matches == 1 {
-                Ok(expr.unwrap())
+                let expr = expr.unwrap();
+
+                if let Expression::InternalFunction { function_no, .. } = &expr {
+                    if ns.functions[*function_no].syscall.is_some() {
+                        diagnostics.push(Diagnostic::error(
+                            id.loc,
+                            format!("syscall '{}' can only be called directly", id.name),
+                        ));
+                        return Err(());
+                    }
+                }
+
+                Ok(expr)
             } else {
                 diagnostics.push(Diagnostic::error(
                     id.loc,
@@ -30,6 +30,7 @@ impl File {
             line_starts,
             cache_no: Some(cache_no),
             import_no,
+            imports: Vec::new(),
         }
     }
 
@@ -1,14 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
-    ast::{Diagnostic, Expression, Function, Namespace, Type},
+    ast::{Diagnostic, Expression, Function, Mutability, Namespace, Syscall, Type},
     diagnostics::Diagnostics,
     eval::overflow_diagnostic,
     expression::literals::{hex_number_literal, unit_literal},
     expression::{ExprContext, ResolveTo},
     Symtable,
 };
-use crate::sema::ast::{ConstructorAnnotations, SolanaAccount};
+use crate::sema::ast::{ConstructorAnnotations, Parameter, ScaleAnnotation, SolanaAccount};
 use crate::sema::eval::{eval_const_number, EvaluationError};
 use crate::sema::expression::literals::number_literal;
 use crate::sema::expression::resolve_expression::expression;
@@ -35,6 +35,100 @@ pub(super) fn unexpected_parameter_annotation(loc: pt::Loc) -> Diagnostic {
     Diagnostic::error(loc, "unexpected parameter annotation".to_string())
 }
 
+/// On Polkadot, some parameter annotations change the SCALE encoding, see [`ScaleAnnotation`].
+/// Parameters can be `@compact`, and return values can also be `@option` or `@result`.
+pub(super) fn is_scale_annotation(
+    annotation: &pt::Annotation,
+    is_return: bool,
+    ns: &Namespace,
+) -> bool {
+    ns.target.is_polkadot()
+        && match annotation.id.name.as_str() {
+            "compact" => true,
+            "option" | "result" => is_return,
+            _ => false,
+        }
+}
+
+/// Check that the SCALE encoding annotations are used on public functions, and on
+/// parameters of the right type. Returns false if any are not.
+pub(super) fn scale_annotations(
+    params: &[Parameter<Type>],
+    returns: &[Parameter<Type>],
+    externally_callable: bool,
+    ns: &Namespace,
+    diagnostics: &mut Diagnostics,
+) -> bool {
+    let mut success = true;
+
+    for (param_no, param) in params.iter().chain(returns).enumerate() {
+        let Some(annotation) = param.scale_annotation() else {
+            continue;
+        };
+
+        let note = param.annotation.as_ref().unwrap();
+
+        if !externally_callable {
+            diagnostics.push(Diagnostic::error(
+                note.loc,
+                format!(
+                    "'@{}' annotation is only allowed on public or external functions",
+                    note.id.name
+                ),
+            ));
+            success = false;
+            continue;
+        }
+
+        match annotation {
+            ScaleAnnotation::Compact => {
+                if !matches!(param.ty, Type::Uint(n) if n <= 128) {
+                    diagnostics.push(Diagnostic::error(
+                        note.loc,
+                        format!(
+                            "'@compact' annotation requires an unsigned integer of at most 128 bits, not '{}'",
+                            param.ty.to_string(ns)
+                        ),
+                    ));
+                    success = false;
+                }
+            }
+            ScaleAnnotation::Option | ScaleAnnotation::Result => {
+                if param_no != params.len() || returns.len() != 2 || param.ty != Type::Bool {
+                    diagnostics.push(Diagnostic::error(
+                        note.loc,
+                        format!(
+                            "'@{}' annotation should be on the first of two return values, which must be of type 'bool'",
+                            note.id.name
+                        ),
+                    ));
+                    success = false;
+                } else if !optional_value_type(&returns[1].ty, ns) {
+                    diagnostics.push(Diagnostic::error(
+                        note.loc,
+                        format!(
+                            "'@{}' annotation cannot be used with a return value of type '{}'",
+                            note.id.name,
+                            returns[1].ty.to_string(ns)
+                        ),
+                    ));
+                    success = false;
+                }
+            }
+        }
+    }
+
+    success
+}
+
+/// Only value types, `string` and `bytes` can be the value of an `@option` or `@result`, since
+/// a default value is returned when the value is absent
+fn optional_value_type(ty: &Type, ns: &Namespace) -> bool {
+    let ty = ty.clone().unwrap_user_type(ns);
+
+    ty.is_primitive() || matches!(ty, Type::Enum(_) | Type::String | Type::DynamicBytes)
+}
+
 /// Resolve the prototype annotation for functions (just the selector). These
 /// annotations can be resolved for functions without a body. This means they
 /// do not need to access the symbol table, like `@seed(foo)` annotations do.
@@ -48,6 +142,10 @@ pub fn function_prototype_annotations(
     for annotation in annotations {
         match annotation.id.name.as_str() {
             "selector" => function_selector(func, annotation, &mut diagnostics, ns),
+            "trace" if func.has_body => function_trace(func, annotation, &mut diagnostics),
+            "nonreentrant" if func.has_body => {
+                function_nonreentrant(func, annotation, &mut diagnostics, ns)
+            }
             "account" | "signer" | "mutableAccount" | "mutableSigner"
                 if ns.target == Target::Solana =>
             {
@@ -186,6 +284,238 @@ fn function_selector(
     }
 }
 
+/// Parse `@trace(true)` or `@trace(false)`, which turns tracing of calls to the function on or off
+fn function_trace(func: &mut Function, annotation: &pt::Annotation, diagnostics: &mut Diagnostics) {
+    if let Some((prev, _)) = &func.trace {
+        duplicate_annotation(
+            diagnostics,
+            "trace",
+            annotation.loc,
+            *prev,
+            func.ty.as_str(),
+        );
+        return;
+    }
+
+    match annotation.value.as_ref().unwrap() {
+        pt::Expression::BoolLiteral(_, value) => func.trace = Some((annotation.loc, *value)),
+        expr => diagnostics.push(Diagnostic::error(
+            expr.loc(),
+            "'@trace' annotation should be 'true' or 'false'".into(),
+        )),
+    }
+}
+
+/// Parse `@nonreentrant(true)` or `@nonreentrant(false)`. A guarded function sets a flag in
+/// storage while it runs, so it cannot be `view` or `pure`.
+fn function_nonreentrant(
+    func: &mut Function,
+    annotation: &pt::Annotation,
+    diagnostics: &mut Diagnostics,
+    ns: &Namespace,
+) {
+    if let Some((prev, _)) = &func.nonreentrant {
+        duplicate_annotation(
+            diagnostics,
+            "nonreentrant",
+            annotation.loc,
+            *prev,
+            func.ty.as_str(),
+        );
+        return;
+    }
+
+    let guarded = match annotation.value.as_ref().unwrap() {
+        pt::Expression::BoolLiteral(_, value) => *value,
+        expr => {
+            diagnostics.push(Diagnostic::error(
+                expr.loc(),
+                "'@nonreentrant' annotation should be 'true' or 'false'".into(),
+            ));
+            return;
+        }
+    };
+
+    if matches!(ns.target, Target::Solana | Target::Soroban) {
+        diagnostics.push(Diagnostic::error(
+            annotation.loc,
+            format!(
+                "'@nonreentrant' annotation is not supported on {}",
+                ns.target
+            ),
+        ));
+    } else if !matches!(
+        func.ty,
+        pt::FunctionTy::Function | pt::FunctionTy::Fallback | pt::FunctionTy::Receive
+    ) {
+        diagnostics.push(Diagnostic::error(
+            annotation.loc,
+            format!("'@nonreentrant' annotation not allowed on {}", func.ty),
+        ));
+    } else if guarded && matches!(func.mutability, Mutability::View(_) | Mutability::Pure(_)) {
+        diagnostics.push(Diagnostic::error(
+            annotation.loc,
+            format!(
+                "'@nonreentrant' annotation not allowed on function declared '{}', since the guard writes to state",
+                func.mutability
+            ),
+        ));
+    } else {
+        func.nonreentrant = Some((annotation.loc, guarded));
+    }
+}
+
+/// Parse `@syscall("name")` or `@syscall(0x...)` on a free function without a body. On Solana,
+/// the function is a syscall, either by name or by number for static syscalls. On Polkadot, it is
+/// a host function import, written as `"module.name"` or just `"name"`.
+pub(super) fn function_syscall(
+    func: &mut Function,
+    annotation: &pt::Annotation,
+    diagnostics: &mut Diagnostics,
+    ns: &mut Namespace,
+) {
+    if ns.target != Target::Solana && !ns.target.is_polkadot() {
+        diagnostics.push(Diagnostic::error(
+            annotation.loc,
+            format!("'@syscall' annotation is not supported on {}", ns.target),
+        ));
+        return;
+    }
+
+    let value = annotation.value.as_ref().unwrap();
+
+    let syscall = match value {
+        pt::Expression::StringLiteral(literals) => {
+            let literal: String = literals.iter().map(|s| s.string.as_str()).collect();
+
+            let (module, name) = match literal.split_once('.') {
+                Some((module, name)) if ns.target.is_polkadot() => {
+                    (Some(module.to_owned()), name.to_owned())
+                }
+                _ => (None, literal.clone()),
+            };
+
+            let valid =
+                |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+            if !valid(&name) || !module.as_deref().map_or(true, valid) {
+                diagnostics.push(Diagnostic::error(
+                    value.loc(),
+                    format!("'{literal}' is not a valid syscall name"),
+                ));
+                return;
+            }
+
+            Syscall::Name { module, name }
+        }
+        pt::Expression::HexNumberLiteral(..) | pt::Expression::NumberLiteral(..) => {
+            if ns.target != Target::Solana {
+                diagnostics.push(Diagnostic::error(
+                    value.loc(),
+                    "syscall numbers are only supported on Solana".into(),
+                ));
+                return;
+            }
+
+            let uint32 = Type::Uint(32);
+
+            let expr = match value {
+                pt::Expression::HexNumberLiteral(loc, n, None) => {
+                    hex_number_literal(loc, n, ns, diagnostics, ResolveTo::Type(&uint32))
+                }
+                pt::Expression::NumberLiteral(loc, base, exp, unit) => {
+                    let unit = unit_literal(loc, unit, ns, diagnostics);
+
+                    number_literal(
+                        loc,
+                        base,
+                        exp,
+                        ns,
+                        &unit,
+                        diagnostics,
+                        ResolveTo::Type(&uint32),
+                    )
+                }
+                _ => Err(()),
+            };
+
+            match expr {
+                Ok(Expression::NumberLiteral { loc, value, .. }) => {
+                    if let Some(diagnostic) = overflow_diagnostic(&value, &uint32, &loc) {
+                        diagnostics.push(diagnostic);
+                        return;
+                    }
+
+                    Syscall::Number(value.to_u32().unwrap())
+                }
+                _ => {
+                    diagnostics.push(Diagnostic::error(
+                        value.loc(),
+                        "literal number expected".into(),
+                    ));
+                    return;
+                }
+            }
+        }
+        _ => {
+            diagnostics.push(Diagnostic::error(
+                value.loc(),
+                "'@syscall' annotation should be a string or a number".into(),
+            ));
+            return;
+        }
+    };
+
+    // The arguments are passed in registers, so only types which fit in one can be used. Bytes
+    // are passed as a pointer and a length, and addresses as a pointer.
+    let mut valid_types = true;
+
+    for param in func.params.iter() {
+        let ok = match &param.ty {
+            Type::Int(n) | Type::Uint(n) => *n <= 64,
+            Type::Bool | Type::DynamicBytes => true,
+            Type::Address(_) => ns.target == Target::Solana,
+            _ => false,
+        };
+
+        if !ok {
+            valid_types = false;
+            diagnostics.push(Diagnostic::error(
+                param.loc,
+                format!(
+                    "type '{}' not allowed as parameter of a syscall",
+                    param.ty.to_string(ns)
+                ),
+            ));
+        }
+    }
+
+    if func.returns.len() > 1 {
+        valid_types = false;
+        diagnostics.push(Diagnostic::error(
+            func.loc_prototype,
+            "a syscall can return at most one value".into(),
+        ));
+    }
+
+    for ret in func.returns.iter() {
+        if !matches!(ret.ty, Type::Int(n) | Type::Uint(n) if n <= 64) && ret.ty != Type::Bool {
+            valid_types = false;
+            diagnostics.push(Diagnostic::error(
+                ret.loc,
+                format!(
+                    "type '{}' not allowed as return value of a syscall",
+                    ret.ty.to_string(ns)
+                ),
+            ));
+        }
+    }
+
+    if valid_types {
+        func.syscall = Some((annotation.loc, syscall));
+    }
+}
+
 /// Collect the seeds, bump, payer, and space for constructors. This is a no-op on Polkadot/EVM since
 /// there should be no seed or bump annotations permitted on other targets.
 ///
@@ -213,8 +543,8 @@ pub(super) fn function_body_annotations(
 
     for note in body_annotations {
         match note.id.name.as_str() {
-            "selector" => {
-                // selectors already done in function_prototype_annotations
+            "selector" | "trace" | "nonreentrant" => {
+                // selectors, tracing and reentrancy guards already done in function_prototype_annotations
                 // without using a symbol table
             }
             "seed" if is_solana_constructor => {
@@ -346,6 +676,10 @@ pub(super) fn function_body_annotations(
                 );
             }
 
+            "compact" if ns.target.is_polkadot() => {
+                // checked in scale_annotations()
+            }
+
             "payer" => {
                 diagnostics.push(Diagnostic::error(
                     ns.functions[function_no].params[unresolved.parameter_no]
@@ -1,7 +1,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
-    ast::{Diagnostic, Function, Mutability, Namespace, Parameter, StructType, Symbol, Type},
+    ast::{
+        ArrayLength, Diagnostic, Function, Mutability, Namespace, Parameter, StructType, Symbol,
+        Type,
+    },
+    builtin::eth_abi_type,
     contracts::is_base,
     diagnostics::Diagnostics,
     function_annotation::function_prototype_annotations,
@@ -9,7 +13,9 @@ use super::{
     ContractDefinition,
 };
 use crate::sema::ast::ParameterAnnotation;
-use crate::sema::function_annotation::unexpected_parameter_annotation;
+use crate::sema::function_annotation::{
+    function_syscall, is_scale_annotation, scale_annotations, unexpected_parameter_annotation,
+};
 use crate::sema::namespace::ResolveTypeContext;
 use crate::Target;
 use solang_parser::pt::{FunctionTy, Identifier};
@@ -69,7 +75,7 @@ pub fn contract_function(
                 return None;
             }
         }
-        pt::FunctionTy::Fallback | pt::FunctionTy::Receive => {
+        pt::FunctionTy::Receive => {
             if !func.returns.is_empty() {
                 ns.diagnostics.push(Diagnostic::error(
                     func.loc_prototype,
@@ -92,6 +98,16 @@ pub fn contract_function(
                 return None;
             }
         }
+        pt::FunctionTy::Fallback => {
+            // the parameters and return values are checked once their types are resolved
+            if func.name.is_some() {
+                ns.diagnostics.push(Diagnostic::error(
+                    func.loc_prototype,
+                    format!("{} function cannot have a name", func.ty),
+                ));
+                return None;
+            }
+        }
         pt::FunctionTy::Modifier => {
             if !func.returns.is_empty() {
                 ns.diagnostics.push(Diagnostic::error(
@@ -326,6 +342,27 @@ pub fn contract_function(
         &mut diagnostics,
     );
 
+    // fallback() gets the raw call data, and returns raw data
+    if func.ty == pt::FunctionTy::Fallback
+        && params_success
+        && returns_success
+        && !(params.is_empty() && returns.is_empty())
+        && !(params.len() == 1
+            && params[0].ty == Type::DynamicBytes
+            && returns.len() == 1
+            && returns[0].ty == Type::DynamicBytes)
+    {
+        ns.diagnostics.push(Diagnostic::error(
+            func.loc_prototype,
+            "fallback function must be declared as 'fallback()' or 'fallback(bytes calldata) returns (bytes memory)'".to_string(),
+        ));
+        success = false;
+    }
+
+    let externally_callable = !storage_allowed && func.ty != pt::FunctionTy::Modifier;
+    let annotations_success =
+        scale_annotations(&params, &returns, externally_callable, ns, &mut diagnostics);
+
     ns.diagnostics.extend(diagnostics);
 
     if ns.contracts[contract_no].is_interface() {
@@ -424,7 +461,7 @@ pub fn contract_function(
         }
     }
 
-    if !success || !returns_success || !params_success {
+    if !success || !returns_success || !params_success || !annotations_success {
         return None;
     }
 
@@ -462,6 +499,17 @@ pub fn contract_function(
         ))
     }
 
+    if matches!(
+        visibility,
+        pt::Visibility::External(_) | pt::Visibility::Public(_)
+    ) {
+        match ns.target {
+            Target::Soroban => soroban_abi_check(func, &params, &returns, ns),
+            Target::Stylus => stylus_abi_check(&params, &returns, ns),
+            _ => (),
+        }
+    }
+
     let mut fdecl = Function::new(
         func.loc_prototype,
         func.loc,
@@ -592,15 +640,7 @@ pub fn contract_function(
                 return None;
             }
 
-            if fdecl.is_payable() {
-                if func.ty == pt::FunctionTy::Fallback {
-                    ns.diagnostics.push(Diagnostic::error(
-                    func.loc_prototype,
-                    format!("{} function must not be declare payable, use 'receive() external payable' instead", func.ty),
-                ));
-                    return None;
-                }
-            } else if func.ty == pt::FunctionTy::Receive {
+            if !fdecl.is_payable() && func.ty == pt::FunctionTy::Receive {
                 ns.diagnostics.push(Diagnostic::error(
                     func.loc_prototype,
                     format!("{} function must be declared payable", func.ty),
@@ -665,10 +705,32 @@ pub fn function(
     func: &pt::FunctionDefinition,
     file_no: usize,
     tags: &[DocComment],
+    annotations: &[&pt::Annotation],
     ns: &mut Namespace,
 ) -> Option<usize> {
     let mut success = true;
 
+    // free functions can only be declared as a syscall
+    let mut syscall = None;
+
+    for note in annotations {
+        if note.id.name == "syscall" && syscall.is_none() {
+            syscall = Some(*note);
+        } else if note.id.name == "syscall" {
+            ns.diagnostics.push(Diagnostic::error_with_note(
+                note.loc,
+                "duplicate @syscall annotation for function".into(),
+                syscall.unwrap().loc,
+                "previous @syscall".into(),
+            ));
+        } else {
+            ns.diagnostics.push(Diagnostic::error(
+                note.loc,
+                "annotations not allowed on function".into(),
+            ));
+        }
+    }
+
     let mut mutability: Option<pt::Mutability> = None;
 
     for a in &func.attributes {
@@ -758,17 +820,29 @@ pub fn function(
     let (returns, returns_success) =
         resolve_returns(&func.returns, true, file_no, None, ns, &mut diagnostics);
 
+    let annotations_success = scale_annotations(&params, &returns, false, ns, &mut diagnostics);
+
     ns.diagnostics.extend(diagnostics);
 
-    if func.body.is_none() {
-        ns.diagnostics.push(Diagnostic::error(
-            func.loc_prototype,
-            String::from("missing function body"),
-        ));
-        success = false;
+    match (&func.body, syscall) {
+        (None, None) => {
+            ns.diagnostics.push(Diagnostic::error(
+                func.loc_prototype,
+                String::from("missing function body"),
+            ));
+            success = false;
+        }
+        (Some(_), Some(note)) => {
+            ns.diagnostics.push(Diagnostic::error(
+                note.loc,
+                String::from("function with '@syscall' annotation cannot have a body"),
+            ));
+            success = false;
+        }
+        _ => (),
     }
 
-    if !success || !returns_success || !params_success {
+    if !success || !returns_success || !params_success || !annotations_success {
         return None;
     }
 
@@ -807,7 +881,21 @@ pub fn function(
         ns,
     );
 
-    fdecl.has_body = true;
+    fdecl.has_body = syscall.is_none();
+
+    if let Some(note) = syscall {
+        let mut diagnostics = Diagnostics::default();
+
+        function_syscall(&mut fdecl, note, &mut diagnostics, ns);
+
+        let any_errors = diagnostics.any_errors();
+
+        ns.diagnostics.extend(diagnostics);
+
+        if any_errors {
+            return None;
+        }
+    }
 
     let id = func.name.as_ref().unwrap();
 
@@ -864,7 +952,11 @@ pub fn resolve_params(
                     ));
                     success = false;
                     continue;
-                } else if annotation.is_some() && ns.target != Target::Solana {
+                } else if annotation
+                    .as_ref()
+                    .is_some_and(|note| !is_scale_annotation(note, false, ns))
+                    && ns.target != Target::Solana
+                {
                     diagnostics.push(unexpected_parameter_annotation(
                         annotation.as_ref().unwrap().loc,
                     ));
@@ -978,6 +1070,89 @@ pub fn resolve_params(
     (params, success)
 }
 
+/// Soroban passes arguments and return values as host values, so only types which have a
+/// Soroban value representation can be used in public functions.
+fn soroban_abi_check(
+    func: &pt::FunctionDefinition,
+    params: &[Parameter<Type>],
+    returns: &[Parameter<Type>],
+    ns: &mut Namespace,
+) {
+    for param in params {
+        if !is_soroban_abi_type(&param.ty) {
+            ns.diagnostics.push(Diagnostic::error(
+                param.ty_loc.unwrap_or(param.loc),
+                format!(
+                    "parameter of type '{}' is not supported on {}",
+                    param.ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+        }
+    }
+
+    for ret in returns {
+        if !is_soroban_abi_type(&ret.ty) {
+            ns.diagnostics.push(Diagnostic::error(
+                ret.ty_loc.unwrap_or(ret.loc),
+                format!(
+                    "return value of type '{}' is not supported on {}",
+                    ret.ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+        }
+    }
+
+    if returns.len() > 1 {
+        ns.diagnostics.push(Diagnostic::error(
+            func.loc_prototype,
+            format!("functions on {} can return at most one value", ns.target),
+        ));
+    }
+}
+
+fn is_soroban_abi_type(ty: &Type) -> bool {
+    match ty {
+        Type::Bool | Type::Enum(_) | Type::String | Type::DynamicBytes => true,
+        Type::Int(n) | Type::Uint(n) => *n <= 128,
+        Type::Array(_, dim) if dim.last() == Some(&ArrayLength::Dynamic) => {
+            is_soroban_abi_type(&ty.array_elem())
+        }
+        _ => false,
+    }
+}
+
+/// Stylus encodes arguments and return values with the Ethereum ABI encoder, which only supports
+/// value types, `bytes` and `string`.
+fn stylus_abi_check(params: &[Parameter<Type>], returns: &[Parameter<Type>], ns: &mut Namespace) {
+    for param in params {
+        if !eth_abi_type(&param.ty, ns) {
+            ns.diagnostics.push(Diagnostic::error(
+                param.ty_loc.unwrap_or(param.loc),
+                format!(
+                    "parameter of type '{}' is not supported on {}",
+                    param.ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+        }
+    }
+
+    for ret in returns {
+        if !eth_abi_type(&ret.ty, ns) {
+            ns.diagnostics.push(Diagnostic::error(
+                ret.ty_loc.unwrap_or(ret.loc),
+                format!(
+                    "return value of type '{}' is not supported on {}",
+                    ret.ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+        }
+    }
+}
+
 /// Resolve the return values
 pub fn resolve_returns(
     returns: &[(pt::Loc, Option<pt::Parameter>)],
@@ -995,7 +1170,7 @@ pub fn resolve_returns(
             Some(pt::Parameter {
                 annotation: Some(annotation),
                 ..
-            }) => {
+            }) if !is_scale_annotation(annotation, true, ns) => {
                 diagnostics.push(unexpected_parameter_annotation(annotation.loc));
                 success = false;
                 continue;
@@ -1087,6 +1262,11 @@ pub fn resolve_returns(
                     }
                 };
 
+                let annotation = r.annotation.as_ref().map(|e| ParameterAnnotation {
+                    loc: e.loc,
+                    id: e.id.clone(),
+                });
+
                 resolved_returns.push(Parameter {
                     loc: *loc,
                     id: r.name.clone(),
@@ -1096,7 +1276,7 @@ pub fn resolve_returns(
                     readonly: false,
                     infinite_size: false,
                     recursive: false,
-                    annotation: None,
+                    annotation,
                 });
             }
             Err(()) => success = false,
@@ -0,0 +1,291 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Graphs of the relations between the items in a namespace: which contracts inherit from
+//! which, which functions call which, and which files import which. These can be written as
+//! graphviz dot or as JSON.
+
+use crate::sema::ast::{DestructureField, Expression, Namespace, Statement};
+use crate::sema::Recurse;
+use itertools::Itertools;
+use serde::Serialize;
+use solang_parser::pt;
+use std::fmt::Write;
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Graph {
+    pub name: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub kind: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl Graph {
+    fn new(name: String) -> Self {
+        Graph {
+            name,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    fn add_edge(&mut self, from: String, to: String, label: Option<&str>) {
+        let edge = GraphEdge {
+            from,
+            to,
+            label: label.map(String::from),
+        };
+
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Write the graph in graphviz dot format
+    pub fn dot(&self) -> String {
+        let mut result = format!("digraph \"{}\" {{\n", self.name);
+
+        for node in &self.nodes {
+            writeln!(
+                result,
+                "\t{} [label=\"{}\" kind=\"{}\"]",
+                node.id,
+                node.label.replace('"', "\\\""),
+                node.kind
+            )
+            .unwrap();
+        }
+
+        for edge in &self.edges {
+            if let Some(label) = &edge.label {
+                writeln!(
+                    result,
+                    "\t{} -> {} [label=\"{}\"]",
+                    edge.from, edge.to, label
+                )
+                .unwrap();
+            } else {
+                writeln!(result, "\t{} -> {}", edge.from, edge.to).unwrap();
+            }
+        }
+
+        result.push_str("}\n");
+
+        result
+    }
+
+    pub fn json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+impl Namespace {
+    /// The inheritance hierarchy of all contracts. There is an edge from each contract to each
+    /// of its direct bases.
+    pub fn inheritance_graph(&self) -> Graph {
+        let mut graph = Graph::new(self.graph_name());
+
+        for (contract_no, contract) in self.contracts.iter().enumerate() {
+            graph.nodes.push(GraphNode {
+                id: format!("contract_{contract_no}"),
+                label: contract.id.name.clone(),
+                kind: contract.ty.to_string(),
+            });
+
+            for base in &contract.bases {
+                graph.add_edge(
+                    format!("contract_{contract_no}"),
+                    format!("contract_{}", base.contract_no),
+                    None,
+                );
+            }
+        }
+
+        graph
+    }
+
+    /// The calls between functions with a body, from the resolved AST. Edges are labelled
+    /// `external` for external calls, `modifier` for modifier invocations, and `creates` for
+    /// constructors of contracts created with `new`. Calls to virtual functions point to the
+    /// function which is called statically, not to any overrides.
+    pub fn call_graph(&self) -> Graph {
+        let mut graph = Graph::new(self.graph_name());
+        let mut calls = Calls { edges: Vec::new() };
+
+        for (function_no, func) in self.functions.iter().enumerate() {
+            if !func.has_body {
+                continue;
+            }
+
+            for modifier in &func.modifiers {
+                modifier.recurse(&mut calls, call_expression);
+
+                if let Expression::InternalFunctionCall { function, .. } = modifier {
+                    if let Expression::InternalFunction {
+                        function_no: modifier_no,
+                        ..
+                    } = function.as_ref()
+                    {
+                        calls
+                            .edges
+                            .retain(|(callee, label)| !(callee == modifier_no && label.is_none()));
+                        calls.edges.push((*modifier_no, Some("modifier")));
+                    }
+                }
+            }
+
+            for stmt in &func.body {
+                stmt.recurse(&mut calls, call_statement);
+            }
+
+            for (callee, label) in calls.edges.drain(..) {
+                graph.add_edge(
+                    format!("function_{function_no}"),
+                    format!("function_{callee}"),
+                    label,
+                );
+            }
+        }
+
+        // only add the functions which have a body or are called
+        for (function_no, func) in self.functions.iter().enumerate() {
+            let id = format!("function_{function_no}");
+
+            if func.has_body || graph.edges.iter().any(|edge| edge.to == id) {
+                graph.nodes.push(GraphNode {
+                    id,
+                    label: self.function_label(function_no),
+                    kind: func.ty.to_string(),
+                });
+            }
+        }
+
+        graph
+    }
+
+    /// The files and the files they import. Builtin files like `solana` are included.
+    pub fn import_graph(&self) -> Graph {
+        let mut graph = Graph::new(self.graph_name());
+
+        for (file_no, file) in self.files.iter().enumerate() {
+            graph.nodes.push(GraphNode {
+                id: format!("file_{file_no}"),
+                label: file.to_string(),
+                kind: if file.cache_no.is_some() {
+                    "file".into()
+                } else {
+                    "builtin".into()
+                },
+            });
+
+            for (_, import_no) in &file.imports {
+                graph.add_edge(format!("file_{file_no}"), format!("file_{import_no}"), None);
+            }
+        }
+
+        graph
+    }
+
+    fn graph_name(&self) -> String {
+        format!("{}", self.files[self.top_file_no()].path.display())
+    }
+
+    /// The contract name, function name and parameter types of a function
+    fn function_label(&self, function_no: usize) -> String {
+        let func = &self.functions[function_no];
+
+        let name = match func.ty {
+            pt::FunctionTy::Function | pt::FunctionTy::Modifier => func.id.name.clone(),
+            ty => ty.to_string(),
+        };
+
+        let params = func
+            .params
+            .iter()
+            .map(|param| param.ty.to_string(self))
+            .join(",");
+
+        if let Some(contract_no) = func.contract_no {
+            format!("{}.{name}({params})", self.contracts[contract_no].id.name)
+        } else {
+            format!("{name}({params})")
+        }
+    }
+}
+
+struct Calls {
+    /// The functions called, with the label of the edge
+    edges: Vec<(usize, Option<&'static str>)>,
+}
+
+fn call_statement(stmt: &Statement, calls: &mut Calls) -> bool {
+    match stmt {
+        Statement::VariableDecl(_, _, _, Some(expr)) => {
+            expr.recurse(calls, call_expression);
+        }
+        Statement::If(_, _, expr, ..)
+        | Statement::While(_, _, expr, _)
+        | Statement::DoWhile(_, _, _, expr)
+        | Statement::Expression(_, _, expr)
+        | Statement::Delete(_, _, expr)
+        | Statement::Return(_, Some(expr)) => {
+            expr.recurse(calls, call_expression);
+        }
+        Statement::For { cond, next, .. } => {
+            for expr in [cond, next].into_iter().flatten() {
+                expr.recurse(calls, call_expression);
+            }
+        }
+        Statement::Destructure(_, fields, expr) => {
+            expr.recurse(calls, call_expression);
+
+            for field in fields {
+                if let DestructureField::Expression(expr) = field {
+                    expr.recurse(calls, call_expression);
+                }
+            }
+        }
+        Statement::Revert { args, .. } | Statement::Emit { args, .. } => {
+            for arg in args {
+                arg.recurse(calls, call_expression);
+            }
+        }
+        Statement::TryCatch(_, _, try_catch) => {
+            try_catch.expr.recurse(calls, call_expression);
+        }
+        _ => (),
+    }
+
+    true
+}
+
+fn call_expression(expr: &Expression, calls: &mut Calls) -> bool {
+    let edge = match expr {
+        Expression::InternalFunction { function_no, .. } => (*function_no, None),
+        Expression::ExternalFunction { function_no, .. } => (*function_no, Some("external")),
+        Expression::Constructor {
+            constructor_no: Some(constructor_no),
+            ..
+        } => (*constructor_no, Some("creates")),
+        _ => return true,
+    };
+
+    if !calls.edges.contains(&edge) {
+        calls.edges.push(edge);
+    }
+
+    true
+}
@@ -0,0 +1,590 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Lint rules which look for suspicious patterns in code which compiles without errors. Unlike
+//! the warnings given during semantic analysis, lints are only checked when asked for, and each
+//! rule can be switched off or given a different severity.
+
+use crate::sema::ast::{
+    Builtin, CallTy, DestructureField, Diagnostic, Expression, Function, Level, Mutability,
+    Namespace, RetrieveType, Statement, Type,
+};
+use crate::sema::mutability::inferred_mutability;
+use crate::sema::storage_usage::{dead_storage, DeadStorage};
+use crate::sema::Recurse;
+use solang_parser::pt;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rule {
+    /// `tx.origin` is compared against an address, which is usually an authorization check
+    TxOrigin,
+    /// The success value of a low-level call or `send()` is ignored
+    UncheckedCall,
+    /// Contract storage is written after an external call
+    Reentrancy,
+    /// A state variable is never read, or never written, by the code which can run
+    DeadStateVariable,
+    /// A function could be declared `view` or `pure`, given what the functions it calls do
+    StateMutability,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 5] = [
+        Rule::TxOrigin,
+        Rule::UncheckedCall,
+        Rule::Reentrancy,
+        Rule::DeadStateVariable,
+        Rule::StateMutability,
+    ];
+
+    /// Name of the rule, as used on the command line and in solang.toml
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rule::TxOrigin => "tx-origin",
+            Rule::UncheckedCall => "unchecked-call",
+            Rule::Reentrancy => "reentrancy",
+            Rule::DeadStateVariable => "dead-state-variable",
+            Rule::StateMutability => "state-mutability",
+        }
+    }
+
+    /// One line description of the rule
+    pub fn description(&self) -> &'static str {
+        match self {
+            Rule::TxOrigin => "tx.origin should not be used for authorization",
+            Rule::UncheckedCall => "the success of low-level calls and send() should be checked",
+            Rule::Reentrancy => "contract storage should not be written after an external call",
+            Rule::DeadStateVariable => "state variables should be both read and written",
+            Rule::StateMutability => {
+                "functions should be declared with the strictest state mutability they allow"
+            }
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rule::ALL
+            .into_iter()
+            .find(|rule| rule.name() == s)
+            .ok_or_else(|| format!("unknown lint rule '{s}'"))
+    }
+}
+
+/// The severity of each rule. A rule without a severity is not checked.
+#[derive(Clone, Debug)]
+pub struct LintConfig {
+    levels: HashMap<Rule, Option<Level>>,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            levels: Rule::ALL
+                .into_iter()
+                .map(|rule| (rule, Some(Level::Warning)))
+                .collect(),
+        }
+    }
+}
+
+impl LintConfig {
+    /// Set the severity of a rule, or `None` to switch it off
+    pub fn set(&mut self, rule: Rule, level: Option<Level>) {
+        self.levels.insert(rule, level);
+    }
+
+    pub fn level(&self, rule: Rule) -> Option<Level> {
+        self.levels.get(&rule).cloned().flatten()
+    }
+}
+
+/// A lint which was found, with the rule that found it
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub rule: Rule,
+    /// The contract of the function the finding is in, if any
+    pub contract_no: Option<usize>,
+    pub diagnostic: Diagnostic,
+    pub fix: Option<Fix>,
+}
+
+/// A change to the source code which resolves a finding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    pub description: String,
+    /// The code to be replaced
+    pub loc: pt::Loc,
+    pub replacement: String,
+}
+
+/// Check the functions and state variables in the top file of the namespace against the lint
+/// rules. The namespace should have resolved without errors.
+pub fn lint(ns: &Namespace, config: &LintConfig) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for func in &ns.functions {
+        if !func.has_body || func.loc.try_file_no() != Some(ns.top_file_no()) {
+            continue;
+        }
+
+        let mut state = LintCheck {
+            config,
+            contract_no: func.contract_no,
+            findings: Vec::new(),
+            external_call: None,
+            reentrancy_reported: !check_reentrancy(func, ns),
+        };
+
+        recurse_statements(&func.body, &mut state);
+
+        findings.append(&mut state.findings);
+    }
+
+    if let Some(level) = config.level(Rule::DeadStateVariable) {
+        findings.extend(dead_state_variables(ns, level));
+    }
+
+    if let Some(level) = config.level(Rule::StateMutability) {
+        findings.extend(state_mutability(ns, level));
+    }
+
+    findings.sort_by_key(|finding| finding.diagnostic.loc);
+
+    findings
+}
+
+/// The state variables in the top file which are dead in every contract which can be deployed.
+/// A variable of a base contract may be used in one contract which inherits it, but not another.
+fn dead_state_variables(ns: &Namespace, level: Level) -> Vec<Finding> {
+    let mut dead: HashMap<(usize, usize), Option<DeadStorage>> = HashMap::new();
+
+    for contract_no in (0..ns.contracts.len()).filter(|no| ns.contracts[*no].instantiable) {
+        let found = dead_storage(contract_no, ns);
+
+        for base_no in ns.contract_bases(contract_no) {
+            for var_no in 0..ns.contracts[base_no].variables.len() {
+                let kind = found
+                    .iter()
+                    .find(|(c, v, _)| (*c, *v) == (base_no, var_no))
+                    .map(|(_, _, kind)| *kind);
+
+                dead.entry((base_no, var_no))
+                    .and_modify(|existing| {
+                        if *existing != kind {
+                            *existing = None;
+                        }
+                    })
+                    .or_insert(kind);
+            }
+        }
+    }
+
+    dead.into_iter()
+        .filter_map(|((contract_no, var_no), kind)| {
+            let var = &ns.contracts[contract_no].variables[var_no];
+
+            if var.loc.try_file_no() != Some(ns.top_file_no()) {
+                return None;
+            }
+
+            let message = match kind? {
+                DeadStorage::NeverRead => {
+                    format!("storage variable '{}' is written, but never read", var.name)
+                }
+                DeadStorage::NeverWritten => format!(
+                    "storage variable '{}' is read, but never written, so it always has its default value",
+                    var.name
+                ),
+                DeadStorage::Unused => {
+                    format!("storage variable '{}' is never read or written", var.name)
+                }
+            };
+
+            let mut diagnostic = Diagnostic::warning(var.loc, message);
+            diagnostic.level = level.clone();
+
+            Some(Finding {
+                rule: Rule::DeadStateVariable,
+                contract_no: Some(contract_no),
+                diagnostic,
+                fix: None,
+            })
+        })
+        .collect()
+}
+
+/// The functions in the top file which could be declared `view` or `pure`. Unlike the compiler
+/// warning, a call to another function counts as what that function does, rather than as what it
+/// is declared to do.
+fn state_mutability(ns: &Namespace, level: Level) -> Vec<Finding> {
+    inferred_mutability(ns)
+        .into_iter()
+        .zip(&ns.functions)
+        .filter_map(|(mutability, func)| {
+            let mutability = mutability?;
+
+            if func.loc.try_file_no() != Some(ns.top_file_no()) {
+                return None;
+            }
+
+            let message = if func.mutability.is_default() {
+                format!("function can be declared '{mutability}'")
+            } else {
+                format!(
+                    "function declared '{}' can be declared '{mutability}'",
+                    func.mutability
+                )
+            };
+
+            let mut diagnostic = Diagnostic::warning(func.loc_prototype, message);
+            diagnostic.level = level.clone();
+
+            // a nonpayable function has no keyword which can be replaced
+            let fix = match func.mutability {
+                Mutability::View(loc) | Mutability::Pure(loc) => Some(Fix {
+                    description: format!("declare the function '{mutability}'"),
+                    loc,
+                    replacement: mutability.to_string(),
+                }),
+                _ => None,
+            };
+
+            Some(Finding {
+                rule: Rule::StateMutability,
+                contract_no: func.contract_no,
+                diagnostic,
+                fix,
+            })
+        })
+        .collect()
+}
+
+/// Functions which cannot write storage, or which have a modifier which looks like a reentrancy
+/// guard, are not checked for reentrancy
+fn check_reentrancy(func: &Function, ns: &Namespace) -> bool {
+    if matches!(func.mutability, Mutability::Pure(_) | Mutability::View(_)) {
+        return false;
+    }
+
+    !func.modifiers.iter().any(|modifier| {
+        if let Expression::InternalFunctionCall { function, .. } = modifier {
+            if let Expression::InternalFunction { function_no, .. } = function.as_ref() {
+                return ns.functions[*function_no]
+                    .id
+                    .name
+                    .to_lowercase()
+                    .contains("reentran");
+            }
+        }
+
+        false
+    })
+}
+
+struct LintCheck<'a> {
+    config: &'a LintConfig,
+    contract_no: Option<usize>,
+    findings: Vec<Finding>,
+    /// The first external call made so far in the function
+    external_call: Option<pt::Loc>,
+    /// Only one reentrancy finding is given per function
+    reentrancy_reported: bool,
+}
+
+impl LintCheck<'_> {
+    fn report(&mut self, rule: Rule, mut diagnostic: Diagnostic, fix: Option<Fix>) {
+        if let Some(level) = self.config.level(rule) {
+            diagnostic.level = level;
+            self.findings.push(Finding {
+                rule,
+                contract_no: self.contract_no,
+                diagnostic,
+                fix,
+            });
+        }
+    }
+
+    fn call(&mut self, loc: &pt::Loc) {
+        if self.external_call.is_none() {
+            self.external_call = Some(*loc);
+        }
+    }
+
+    fn write(&mut self, loc: &pt::Loc) {
+        if let Some(call_loc) = self.external_call {
+            if !self.reentrancy_reported {
+                self.reentrancy_reported = true;
+
+                self.report(
+                    Rule::Reentrancy,
+                    Diagnostic::warning_with_note(
+                        *loc,
+                        "contract storage is written after an external call, which allows reentrancy"
+                            .into(),
+                        call_loc,
+                        "external call".into(),
+                    ),
+                    None,
+                );
+            }
+        }
+    }
+}
+
+fn recurse_statements(stmts: &[Statement], state: &mut LintCheck) {
+    for stmt in stmts {
+        match stmt {
+            Statement::Block { statements, .. } => {
+                recurse_statements(statements, state);
+            }
+            Statement::VariableDecl(_, _, _, Some(expr)) => {
+                expr.recurse(state, read_expression);
+            }
+            Statement::VariableDecl(_, _, _, None) => (),
+            Statement::If(_, _, expr, then_, else_) => {
+                expr.recurse(state, read_expression);
+
+                // an external call in one branch does not precede a write in the other branch
+                let before = state.external_call;
+                recurse_statements(then_, state);
+                let after_then = state.external_call;
+                state.external_call = before;
+                recurse_statements(else_, state);
+                state.external_call = state.external_call.or(after_then);
+            }
+            Statement::DoWhile(_, _, body, expr) | Statement::While(_, _, expr, body) => {
+                expr.recurse(state, read_expression);
+                recurse_statements(body, state);
+            }
+            Statement::For {
+                init,
+                cond,
+                next,
+                body,
+                ..
+            } => {
+                recurse_statements(init, state);
+                if let Some(cond) = cond {
+                    cond.recurse(state, read_expression);
+                }
+                recurse_statements(body, state);
+                if let Some(next) = next {
+                    next.recurse(state, read_expression);
+                }
+            }
+            Statement::Expression(_, _, expr) => {
+                unchecked_call(expr, state);
+                expr.recurse(state, read_expression);
+            }
+            Statement::Delete(_, _, expr) => {
+                expr.recurse(state, write_expression);
+            }
+            Statement::Destructure(_, fields, expr) => {
+                if matches!(fields.first(), Some(DestructureField::None)) {
+                    unchecked_call(expr, state);
+                }
+
+                expr.recurse(state, read_expression);
+
+                for field in fields {
+                    if let DestructureField::Expression(expr) = field {
+                        expr.recurse(state, write_expression);
+                    }
+                }
+            }
+            Statement::Return(_, None) => (),
+            Statement::Return(_, Some(expr)) => {
+                expr.recurse(state, read_expression);
+            }
+            Statement::TryCatch(_, _, try_catch) => {
+                try_catch.expr.recurse(state, read_expression);
+                recurse_statements(&try_catch.ok_stmt, state);
+                for clause in &try_catch.errors {
+                    recurse_statements(&clause.stmt, state);
+                }
+                if let Some(clause) = try_catch.catch_all.as_ref() {
+                    recurse_statements(&clause.stmt, state);
+                }
+            }
+            Statement::Emit { args, .. } | Statement::Revert { args, .. } => {
+                for arg in args {
+                    arg.recurse(state, read_expression);
+                }
+            }
+            Statement::Break(_)
+            | Statement::Continue(_)
+            | Statement::Underscore(_)
+            | Statement::Assembly(..) => (),
+        }
+    }
+}
+
+/// The expression is a statement by itself, or the success value is not assigned
+fn unchecked_call(expr: &Expression, state: &mut LintCheck) {
+    match expr {
+        Expression::ExternalFunctionCallRaw { loc, ty, .. } => {
+            let call = match ty {
+                CallTy::Regular => "call",
+                CallTy::Delegate => "delegatecall",
+                CallTy::Static => "staticcall",
+            };
+
+            state.report(
+                Rule::UncheckedCall,
+                Diagnostic::warning(
+                    *loc,
+                    format!("return value of low-level {call} not checked"),
+                ),
+                None,
+            );
+        }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::PayableSend,
+            ..
+        } => {
+            state.report(
+                Rule::UncheckedCall,
+                Diagnostic::warning(*loc, "return value of send not checked".into()),
+                None,
+            );
+        }
+        _ => (),
+    }
+}
+
+fn read_expression(expr: &Expression, state: &mut LintCheck) -> bool {
+    match expr {
+        Expression::Equal { loc, left, right } | Expression::NotEqual { loc, left, right } => {
+            let find = |builtin: Builtin| {
+                find_builtin(left, builtin).or_else(|| find_builtin(right, builtin))
+            };
+
+            // `tx.origin == msg.sender` checks the caller is not a contract, which is fine
+            if let (Some(origin), None) = (find(Builtin::Origin), find(Builtin::Sender)) {
+                state.report(
+                    Rule::TxOrigin,
+                    Diagnostic::warning(
+                        *loc,
+                        "tx.origin used for authorization, use msg.sender instead".into(),
+                    ),
+                    Some(Fix {
+                        description: "replace tx.origin with msg.sender".into(),
+                        loc: origin,
+                        replacement: "msg.sender".into(),
+                    }),
+                );
+            }
+        }
+        Expression::PreIncrement { expr, .. }
+        | Expression::PreDecrement { expr, .. }
+        | Expression::PostIncrement { expr, .. }
+        | Expression::PostDecrement { expr, .. } => {
+            expr.recurse(state, write_expression);
+        }
+        Expression::Assign { left, right, .. } => {
+            // the right hand side is evaluated before the assignment
+            right.recurse(state, read_expression);
+            left.recurse(state, write_expression);
+            return false;
+        }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::ArrayPush | Builtin::ArrayPop,
+            args,
+            ..
+        } if args[0].ty().is_contract_storage() => {
+            // the arguments are evaluated before the push or pop
+            for arg in args {
+                arg.recurse(state, read_expression);
+            }
+            state.write(loc);
+            return false;
+        }
+        Expression::ExternalFunctionCall {
+            loc,
+            function,
+            args,
+            ..
+        } => {
+            for arg in args {
+                arg.recurse(state, read_expression);
+            }
+
+            if let Type::ExternalFunction {
+                mutability: Mutability::Nonpayable(_) | Mutability::Payable(_),
+                ..
+            } = function.ty()
+            {
+                state.call(loc);
+            }
+            return false;
+        }
+        Expression::ExternalFunctionCallRaw {
+            loc,
+            ty: CallTy::Regular,
+            address,
+            args,
+            ..
+        } => {
+            address.recurse(state, read_expression);
+            args.recurse(state, read_expression);
+            state.call(loc);
+            return false;
+        }
+        _ => (),
+    }
+
+    true
+}
+
+fn write_expression(expr: &Expression, state: &mut LintCheck) -> bool {
+    match expr {
+        Expression::StructMember {
+            loc, expr: array, ..
+        }
+        | Expression::Subscript { loc, array, .. }
+            if array.ty().is_contract_storage() =>
+        {
+            state.write(loc);
+            false
+        }
+        Expression::Variable { loc, ty, .. }
+            if ty.is_contract_storage() && !expr.ty().is_contract_storage() =>
+        {
+            state.write(loc);
+            false
+        }
+        Expression::StorageVariable { loc, .. } => {
+            state.write(loc);
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Find where the expression uses the builtin, if it does
+fn find_builtin(expr: &Expression, builtin: Builtin) -> Option<pt::Loc> {
+    let mut found = (builtin, None);
+
+    expr.recurse(&mut found, |expr, found| {
+        if let Expression::Builtin { loc, kind, .. } = expr {
+            if *kind == found.0 {
+                found.1 = Some(*loc);
+            }
+        }
+
+        found.1.is_none()
+    });
+
+    found.1
+}
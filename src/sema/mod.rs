@@ -21,9 +21,11 @@ mod address;
 pub mod ast;
 pub mod builtin;
 pub mod builtin_structs;
+mod conditional;
 pub(crate) mod contracts;
 pub mod diagnostics;
 mod dotgraphviz;
+mod escape;
 pub(crate) mod eval;
 pub(crate) mod expression;
 mod external_functions;
@@ -31,11 +33,14 @@ pub mod file;
 mod format;
 mod function_annotation;
 mod functions;
+pub mod graph;
+pub mod lint;
 mod mutability;
 mod namespace;
 mod pragma;
 pub(crate) mod solana_accounts;
 mod statements;
+pub mod storage_usage;
 pub mod symtable;
 pub mod tags;
 mod tests;
@@ -43,6 +48,7 @@ mod types;
 mod unused_variable;
 mod using;
 mod variables;
+pub mod warnings;
 pub(crate) mod yul;
 
 pub type ArrayDimension = Option<(pt::Loc, BigInt)>;
@@ -90,6 +96,8 @@ pub fn sema(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Name
         check_unused_events(ns);
         check_unused_errors(ns);
     }
+
+    ns.suppress_warnings();
 }
 
 /// Parse and resolve a file and its imports in a recursive manner.
@@ -114,6 +122,8 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
         }
     };
 
+    ns.add_suppressions(&comments);
+
     let tree = collect_annotations_doccomments(&pt, &comments, ns);
 
     // first resolve all the types we can find
@@ -145,9 +155,9 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
     for item in &tree.items {
         match item.part {
             pt::SourceUnitPart::FunctionDefinition(func) => {
-                annotions_not_allowed(&item.annotations, "function", ns);
-
-                if let Some(func_no) = functions::function(func, file_no, &item.doccomments, ns) {
+                if let Some(func_no) =
+                    functions::function(func, file_no, &item.doccomments, &item.annotations, ns)
+                {
                     resolve_bodies.push((func_no, func));
                 }
             }
@@ -215,6 +225,9 @@ fn sema_file(file: &ResolvedFile, resolver: &mut FileResolver, ns: &mut ast::Nam
 
     // now check state mutability for all contracts
     mutability::mutability(file_no, ns);
+
+    // find the parameters which can be decoded without copying
+    escape::escape_analysis(file_no, ns);
 }
 
 /// Find import file, resolve it by calling sema and add it to the namespace
@@ -301,6 +314,10 @@ fn resolve_import(
         }
     };
 
+    ns.files[file_no]
+        .imports
+        .push((filename.loc, import_file_no));
+
     match import {
         pt::Import::Rename(_, renames, _) => {
             for (from, rename_to) in renames {
@@ -448,7 +465,9 @@ fn collect_annotations_doccomments<'a>(
 
         let doccomments = parse_doccomments(comments, doc_comment_start, loc.start());
 
-        if let pt::SourceUnitPart::ContractDefinition(contract) = part {
+        if !conditional::included(&mut annotations, ns) {
+            // not compiled for this target
+        } else if let pt::SourceUnitPart::ContractDefinition(contract) = part {
             let mut parts = Vec::new();
             let mut parts_annotations: Vec<&pt::Annotation> = Vec::new();
             let mut doc_comment_start = contract.loc.start();
@@ -459,6 +478,10 @@ fn collect_annotations_doccomments<'a>(
                         parts_annotations.push(note);
                         continue;
                     }
+                    _ if !conditional::included(&mut parts_annotations, ns) => {
+                        // not compiled for this target
+                        parts_annotations = Vec::new();
+                    }
                     _ => {
                         let tags =
                             parse_doccomments(comments, doc_comment_start, part.loc().start());
@@ -17,7 +17,7 @@ use bitflags::bitflags;
 use solang_parser::pt::Loc;
 use solang_parser::{helpers::CodeLocation, pt};
 
-#[derive(Clone, Copy, Hash, Eq, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
 enum Access {
     None,
     Read,
@@ -42,6 +42,17 @@ impl Access {
     }
 }
 
+impl From<&Mutability> for Access {
+    fn from(mutability: &Mutability) -> Self {
+        match mutability {
+            Mutability::Pure(_) => Access::None,
+            Mutability::View(_) => Access::Read,
+            Mutability::Nonpayable(_) => Access::Write,
+            Mutability::Payable(_) => Access::Value,
+        }
+    }
+}
+
 /// check state mutability
 pub fn mutability(file_no: usize, ns: &mut Namespace) {
     if !ns.diagnostics.any_errors() {
@@ -59,6 +70,68 @@ pub fn mutability(file_no: usize, ns: &mut Namespace) {
     }
 }
 
+/// The stricter state mutability that each function could be declared with, if any. Unlike the
+/// warnings given by [`mutability`], this follows the call graph: a function which calls another
+/// function directly needs the access that function needs, rather than the access it is declared
+/// with. Virtual calls, calls through function pointers and external calls may run code which is
+/// not known here, so they need the access they are declared with.
+pub(crate) fn inferred_mutability(ns: &Namespace) -> Vec<Option<Mutability>> {
+    let mut inferred = vec![Access::None; ns.functions.len()];
+
+    // the access needed by a function only increases as that of the functions it calls does, so
+    // this terminates once no function needs more access
+    loop {
+        let mut changed = false;
+
+        for (function_no, func) in ns.functions.iter().enumerate() {
+            if !func.has_body || func.ty == pt::FunctionTy::Modifier {
+                continue;
+            }
+
+            let required = check_function(func, ns, Some(&inferred)).required_access;
+
+            if required > inferred[function_no] {
+                inferred[function_no] = required;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    ns.functions
+        .iter()
+        .zip(inferred)
+        .map(|(func, required)| {
+            if func.has_body && func.ty != pt::FunctionTy::Modifier {
+                stricter_mutability(func, required)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The state mutability the function could be declared with, if the access it needs is less than
+/// its mutability allows. Virtual functions are left alone, since a function which overrides them
+/// may need the access.
+fn stricter_mutability(func: &Function, required: Access) -> Option<Mutability> {
+    if func.ty != pt::FunctionTy::Function || func.is_accessor || func.is_virtual {
+        return None;
+    }
+
+    match (&func.mutability, required) {
+        (Mutability::Nonpayable(_) | Mutability::View(_), Access::None) => {
+            Some(Mutability::Pure(func.loc_prototype))
+        }
+        // don't suggest marking payable as view
+        (Mutability::Nonpayable(_), Access::Read) => Some(Mutability::View(func.loc_prototype)),
+        _ => None,
+    }
+}
+
 /// While we recurse through the AST, maintain some state
 struct StateCheck<'a> {
     diagnostic: Diagnostics,
@@ -68,6 +141,8 @@ struct StateCheck<'a> {
     modifier: Option<pt::Loc>,
     ns: &'a Namespace,
     data_account: DataAccountUsage,
+    /// The access needed by each function found so far, when following the call graph
+    inferred: Option<&'a [Access]>,
 }
 
 impl<'a> StateCheck<'a> {
@@ -86,6 +161,31 @@ impl<'a> StateCheck<'a> {
         self.required_access.increase_to(Access::Read);
     }
 
+    /// A call needs the access the called function is declared with. When following the call
+    /// graph, a direct call to a function with a body needs the access that function needs.
+    /// Calling a payable function does not access the value sent to this function.
+    fn call(&mut self, loc: &pt::Loc, function: &Expression, mutability: &Mutability) {
+        let declared = Access::from(mutability).min(Access::Write);
+
+        self.check_level(loc, declared);
+
+        let required = match (self.inferred, function) {
+            (
+                Some(inferred),
+                Expression::InternalFunction {
+                    function_no,
+                    signature: None,
+                    ..
+                },
+            ) if self.ns.functions[*function_no].has_body => {
+                inferred[*function_no].min(Access::Write)
+            }
+            _ => declared,
+        };
+
+        self.required_access.increase_to(required);
+    }
+
     /// Compare the declared access level to the desired access level.
     /// If there is an access violation, it'll be reported to the diagnostics.
     fn check_level(&mut self, loc: &pt::Loc, desired: Access) {
@@ -125,23 +225,89 @@ impl<'a> StateCheck<'a> {
 }
 
 fn check_mutability(func: &Function, ns: &Namespace) -> Diagnostics {
-    if func.is_virtual {
+    // a syscall has no body, what it does to state is what it is declared as
+    if func.syscall.is_some() {
         return Default::default();
     }
 
+    let mut state = check_function(func, ns, None);
+
+    match stricter_mutability(func, state.required_access) {
+        Some(Mutability::Pure(_)) if func.mutability.is_default() => {
+            state.diagnostic.push(Diagnostic::warning(
+                func.loc_prototype,
+                "function can be declared 'pure'".to_string(),
+            ));
+        }
+        Some(Mutability::Pure(_)) => {
+            state.diagnostic.push(Diagnostic::warning(
+                func.loc_prototype,
+                format!(
+                    "function declared '{}' can be declared 'pure'",
+                    func.mutability
+                ),
+            ));
+        }
+        Some(_) => {
+            state.diagnostic.push(Diagnostic::warning(
+                func.loc_prototype,
+                "function can be declared 'view'".to_string(),
+            ));
+        }
+        None => (),
+    }
+
+    if state.data_account != DataAccountUsage::NONE && ns.target == Target::Solana {
+        func.solana_accounts.borrow_mut().insert(
+            BuiltinAccounts::DataAccount.to_string(),
+            SolanaAccount {
+                loc: Loc::Codegen,
+                is_signer: false,
+                is_writer: (state.data_account & DataAccountUsage::WRITE)
+                    == DataAccountUsage::WRITE,
+                generated: true,
+            },
+        );
+    }
+
+    if func.is_constructor() {
+        func.solana_accounts.borrow_mut().insert(
+            BuiltinAccounts::DataAccount.to_string(),
+            SolanaAccount {
+                loc: Loc::Codegen,
+                is_writer: true,
+                // With a @payer annotation, the account is created on-chain and needs a signer. The client
+                // provides an address that does not exist yet, so SystemProgram.CreateAccount is called
+                // on-chain.
+                //
+                // However, if a @seed is also provided, the program can sign for the account
+                // with the seed using program derived address (pda) when SystemProgram.CreateAccount is called,
+                // so no signer is required from the client.
+                is_signer: func.has_payer_annotation() && !func.has_seed_annotation(),
+                generated: true,
+            },
+        );
+    }
+
+    state.diagnostic
+}
+
+/// Find the access the function needs, and report where it needs more access than it is declared
+/// with
+fn check_function<'a>(
+    func: &'a Function,
+    ns: &'a Namespace,
+    inferred: Option<&'a [Access]>,
+) -> StateCheck<'a> {
     let mut state = StateCheck {
         diagnostic: Default::default(),
-        declared_access: match func.mutability {
-            Mutability::Pure(_) => Access::None,
-            Mutability::View(_) => Access::Read,
-            Mutability::Nonpayable(_) => Access::Write,
-            Mutability::Payable(_) => Access::Value,
-        },
+        declared_access: Access::from(&func.mutability),
         required_access: Access::None,
         func,
         modifier: None,
         ns,
         data_account: DataAccountUsage::NONE,
+        inferred,
     };
 
     for arg in &func.modifiers {
@@ -185,70 +351,12 @@ fn check_mutability(func: &Function, ns: &Namespace) -> Diagnostics {
 
     recurse_statements(&func.body, ns, &mut state);
 
-    if pt::FunctionTy::Function == func.ty && !func.is_accessor {
-        if state.required_access == Access::None {
-            match func.mutability {
-                Mutability::Payable(_) | Mutability::Pure(_) => (),
-                Mutability::Nonpayable(_) => {
-                    state.diagnostic.push(Diagnostic::warning(
-                        func.loc_prototype,
-                        "function can be declared 'pure'".to_string(),
-                    ));
-                }
-                _ => {
-                    state.diagnostic.push(Diagnostic::warning(
-                        func.loc_prototype,
-                        format!(
-                            "function declared '{}' can be declared 'pure'",
-                            func.mutability
-                        ),
-                    ));
-                }
-            }
-        }
-
-        // don't suggest marking payable as view (declared_access == Value)
-        if state.required_access == Access::Read && state.declared_access == Access::Write {
-            state.diagnostic.push(Diagnostic::warning(
-                func.loc_prototype,
-                "function can be declared 'view'".to_string(),
-            ));
-        }
-    }
-
-    if state.data_account != DataAccountUsage::NONE && ns.target == Target::Solana {
-        func.solana_accounts.borrow_mut().insert(
-            BuiltinAccounts::DataAccount.to_string(),
-            SolanaAccount {
-                loc: Loc::Codegen,
-                is_signer: false,
-                is_writer: (state.data_account & DataAccountUsage::WRITE)
-                    == DataAccountUsage::WRITE,
-                generated: true,
-            },
-        );
+    // the reentrancy guard sets a flag in storage
+    if func.nonreentrant.is_some_and(|(_, guarded)| guarded) {
+        state.required_access.increase_to(Access::Write);
     }
 
-    if func.is_constructor() {
-        func.solana_accounts.borrow_mut().insert(
-            BuiltinAccounts::DataAccount.to_string(),
-            SolanaAccount {
-                loc: Loc::Codegen,
-                is_writer: true,
-                // With a @payer annotation, the account is created on-chain and needs a signer. The client
-                // provides an address that does not exist yet, so SystemProgram.CreateAccount is called
-                // on-chain.
-                //
-                // However, if a @seed is also provided, the program can sign for the account
-                // with the seed using program derived address (pda) when SystemProgram.CreateAccount is called,
-                // so no signer is required from the client.
-                is_signer: func.has_payer_annotation() && !func.has_seed_annotation(),
-                generated: true,
-            },
-        );
-    }
-
-    state.diagnostic
+    state
 }
 
 fn recurse_statements(stmts: &[Statement], ns: &Namespace, state: &mut StateCheck) {
@@ -371,6 +479,16 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
                 return false;
             }
         }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::Balance,
+            args,
+            ..
+        } if args.is_empty() => {
+            // the balance of this contract is held by the data account on Solana
+            state.data_account |= DataAccountUsage::READ;
+            state.read(loc)
+        }
         Expression::Builtin {
             loc,
             kind:
@@ -395,7 +513,16 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
 
         Expression::Builtin {
             loc,
-            kind: Builtin::PayableSend | Builtin::PayableTransfer | Builtin::SelfDestruct,
+            kind: Builtin::PayableSend | Builtin::PayableTransfer,
+            ..
+        } => {
+            // lamports are moved from the data account on Solana
+            state.data_account |= DataAccountUsage::WRITE;
+            state.write(loc)
+        }
+        Expression::Builtin {
+            loc,
+            kind: Builtin::SelfDestruct,
             ..
         } => state.write(loc),
         Expression::Builtin {
@@ -428,11 +555,7 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
         | Expression::InternalFunctionCall { loc, function, .. } => match function.ty() {
             Type::ExternalFunction { mutability, .. }
             | Type::InternalFunction { mutability, .. } => {
-                match mutability {
-                    Mutability::Nonpayable(_) | Mutability::Payable(_) => state.write(loc),
-                    Mutability::View(_) => state.read(loc),
-                    Mutability::Pure(_) => (),
-                };
+                state.call(loc, function, &mutability);
             }
             _ => unreachable!(),
         },
@@ -497,11 +620,7 @@ fn recurse_yul_statements(stmts: &[YulStatement], state: &mut StateCheck) {
                 }
             }
             YulStatement::BuiltInCall(loc, _, builtin_ty, args) => {
-                if builtin_ty.read_state() {
-                    state.read(loc);
-                } else if builtin_ty.modify_state() {
-                    state.write(loc);
-                }
+                yul_builtin(loc, *builtin_ty, state);
                 for arg in args {
                     arg.recurse(state, check_expression_mutability_yul);
                 }
@@ -555,25 +674,29 @@ fn recurse_yul_statements(stmts: &[YulStatement], state: &mut StateCheck) {
 fn check_expression_mutability_yul(expr: &YulExpression, state: &mut StateCheck) -> bool {
     match expr {
         YulExpression::BuiltInCall(loc, builtin_ty, _) => {
-            if builtin_ty.read_state() {
-                state.read(loc);
-            } else if builtin_ty.modify_state() {
-                state.write(loc);
-            }
-
-            match builtin_ty {
-                YulBuiltInFunction::SStore => {
-                    state.data_account |= DataAccountUsage::WRITE;
-                }
-                YulBuiltInFunction::SLoad => {
-                    state.data_account |= DataAccountUsage::READ;
-                }
-                _ => (),
-            }
-
+            yul_builtin(loc, *builtin_ty, state);
             true
         }
         YulExpression::FunctionCall(..) => true,
         _ => false,
     }
 }
+
+/// A yul builtin is called, either as a statement or in an expression
+fn yul_builtin(loc: &pt::Loc, builtin_ty: YulBuiltInFunction, state: &mut StateCheck) {
+    if builtin_ty.read_state() {
+        state.read(loc);
+    } else if builtin_ty.modify_state() {
+        state.write(loc);
+    }
+
+    match builtin_ty {
+        YulBuiltInFunction::SStore => {
+            state.data_account |= DataAccountUsage::WRITE;
+        }
+        YulBuiltInFunction::SLoad => {
+            state.data_account |= DataAccountUsage::READ;
+        }
+        _ => (),
+    }
+}
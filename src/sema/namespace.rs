@@ -9,10 +9,12 @@ use super::{
     diagnostics::Diagnostics,
     eval::eval_const_number,
     expression::{resolve_expression::expression, ExprContext, ResolveTo},
+    function_annotation::scale_annotations,
     resolve_params, resolve_returns,
     symtable::Symtable,
     ArrayDimension,
 };
+use crate::target::Capability;
 use crate::Target;
 use itertools::Itertools;
 use num_bigint::BigInt;
@@ -35,7 +37,7 @@ impl Namespace {
     /// Create a namespace and populate with the parameters for the target
     pub fn new(target: Target) -> Self {
         let (address_length, value_length) = match target {
-            Target::EVM => (20, 32),
+            Target::EVM | Target::Stylus => (20, 32),
             Target::Polkadot {
                 address_length,
                 value_length,
@@ -66,6 +68,8 @@ impl Namespace {
             next_id: 0,
             var_constants: HashMap::new(),
             hover_overrides: HashMap::new(),
+            warning_suppressions: Vec::new(),
+            strict_conversions: false,
         };
 
         match target {
@@ -78,6 +82,31 @@ impl Namespace {
         ns
     }
 
+    /// Check that the target supports a language feature. If it does not, report an error
+    /// at the given location.
+    pub(super) fn target_supports(
+        &self,
+        loc: &pt::Loc,
+        capability: Capability,
+        diagnostics: &mut Diagnostics,
+    ) -> bool {
+        if self.target.capabilities().supports(capability) {
+            return true;
+        }
+
+        let message = match capability.help(self.target) {
+            Some(help) => format!(
+                "{} is not available on {}. {}",
+                capability, self.target, help
+            ),
+            None => format!("{} is not available on {}", capability, self.target),
+        };
+
+        diagnostics.push(Diagnostic::error(*loc, message));
+
+        false
+    }
+
     /// Add symbol to symbol table; either returns true for success, or adds an appropriate error
     pub fn add_symbol(
         &mut self,
@@ -1098,6 +1127,9 @@ impl Namespace {
                         diagnostics,
                     );
 
+                    let annotations_success =
+                        scale_annotations(&params, &returns, false, self, diagnostics);
+
                     // trailing attribute should not be there
                     // trailing visibility for contract variables should be removed already
                     for a in trailing_attributes {
@@ -1127,7 +1159,7 @@ impl Namespace {
                         }
                     }
 
-                    if !success || !params_success || !returns_success {
+                    if !success || !params_success || !returns_success || !annotations_success {
                         return Err(());
                     }
 
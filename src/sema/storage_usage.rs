@@ -0,0 +1,413 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Which storage variables are read and written by the code of a contract which can run. Unlike
+//! the unused variable warnings, this follows the calls from the public functions, so a variable
+//! which is only used by functions which are never called is dead too.
+
+use crate::sema::ast::{Builtin, DestructureField, Expression, Namespace, RetrieveType, Statement};
+use crate::sema::Recurse;
+use solang_parser::pt;
+use std::collections::{HashMap, HashSet};
+
+/// How a storage variable is dead
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadStorage {
+    /// The variable is written, but its value is never read
+    NeverRead,
+    /// The variable is read, but never written, so it always has its default value
+    NeverWritten,
+    /// The variable is neither read nor written
+    Unused,
+}
+
+/// The storage variables of an instantiable contract, including those of its base contracts,
+/// which are not both read and written by the functions which can run once the contract is
+/// deployed. The accessor function of a public variable reads it. Nothing is reported if any of
+/// these functions use inline assembly, since assembly can access any storage slot.
+pub fn dead_storage(contract_no: usize, ns: &Namespace) -> Vec<(usize, usize, DeadStorage)> {
+    let mut usage = Usage::walk(contract_no, ns, false);
+
+    if usage.assembly {
+        return Vec::new();
+    }
+
+    let bases = ns.contract_bases(contract_no);
+
+    for base_no in &bases {
+        for (var_no, var) in ns.contracts[*base_no].variables.iter().enumerate() {
+            if var.initializer.is_some() {
+                usage.written.insert((*base_no, var_no));
+            }
+
+            if matches!(var.visibility, pt::Visibility::Public(_)) {
+                usage.read.insert((*base_no, var_no));
+            }
+
+            // the hidden variables of an enumerable mapping are used whenever the mapping is
+            if let Some(enumerable) = &var.enumerable {
+                for hidden in [enumerable.keys, enumerable.positions] {
+                    usage.read.insert((enumerable.contract_no, hidden));
+                    usage.written.insert((enumerable.contract_no, hidden));
+                }
+            }
+        }
+    }
+
+    bases
+        .into_iter()
+        .flat_map(|base_no| {
+            ns.contracts[base_no]
+                .variables
+                .iter()
+                .enumerate()
+                .filter(|(_, var)| !var.constant)
+                .map(move |(var_no, _)| (base_no, var_no))
+        })
+        .filter_map(|var| {
+            let dead = match (usage.read.contains(&var), usage.written.contains(&var)) {
+                (true, true) => return None,
+                (false, true) => DeadStorage::NeverRead,
+                (true, false) => DeadStorage::NeverWritten,
+                (false, false) => DeadStorage::Unused,
+            };
+
+            Some((var.0, var.1, dead))
+        })
+        .collect()
+}
+
+/// The storage variables which are written by the functions of an instantiable contract which
+/// can run, not counting their initializers. Returns `None` if any of these functions use inline
+/// assembly, since assembly can write any storage slot.
+pub fn written_storage(contract_no: usize, ns: &Namespace) -> Option<HashSet<(usize, usize)>> {
+    let usage = Usage::walk(contract_no, ns, false);
+
+    if usage.assembly {
+        None
+    } else {
+        Some(usage.written)
+    }
+}
+
+/// The immutable variables of an instantiable contract which are assigned exactly once when the
+/// contract is deployed, by a statement in the body of the only constructor of a contract, and
+/// which are not read by any of the code which runs during deployment. Returns the assigned
+/// expression for each of them. Nothing is returned if the deployment code uses inline assembly.
+pub fn constructor_assigned_immutables(
+    contract_no: usize,
+    ns: &Namespace,
+) -> Vec<((usize, usize), &Expression)> {
+    let usage = Usage::walk(contract_no, ns, true);
+
+    if usage.assembly {
+        return Vec::new();
+    }
+
+    let mut assigned = Vec::new();
+
+    for base_no in ns.contract_bases(contract_no) {
+        let [function_no] = ns.contracts[base_no].constructors(ns)[..] else {
+            continue;
+        };
+
+        let func = &ns.functions[function_no];
+
+        // a modifier may not run the body of the constructor, and an early return may skip the
+        // assignment
+        if !func.modifiers.is_empty() || returns(&func.body) {
+            continue;
+        }
+
+        for stmt in top_level(&func.body) {
+            let Statement::Expression(_, _, Expression::Assign { left, right, .. }) = stmt else {
+                continue;
+            };
+
+            let Expression::StorageVariable {
+                contract_no: var_contract_no,
+                var_no,
+                ..
+            } = left.as_ref()
+            else {
+                continue;
+            };
+
+            let var = (*var_contract_no, *var_no);
+
+            if ns.contracts[var.0].variables[var.1].immutable
+                && !usage.read.contains(&var)
+                && usage.writes.get(&var) == Some(&1)
+            {
+                assigned.push((var, right.as_ref()));
+            }
+        }
+    }
+
+    assigned
+}
+
+/// The statements which always run when the function body runs, except when it reverts
+fn top_level(body: &[Statement]) -> Vec<&Statement> {
+    body.iter()
+        .flat_map(|stmt| match stmt {
+            Statement::Block { statements, .. } => top_level(statements),
+            _ => vec![stmt],
+        })
+        .collect()
+}
+
+/// Does the function body contain a return statement
+fn returns(body: &[Statement]) -> bool {
+    let mut found = false;
+
+    for stmt in body {
+        stmt.recurse(&mut found, |stmt, found| {
+            if matches!(stmt, Statement::Return(..)) {
+                *found = true;
+            }
+
+            !*found
+        });
+    }
+
+    found
+}
+
+struct Usage<'a> {
+    ns: &'a Namespace,
+    contract_no: usize,
+    read: HashSet<(usize, usize)>,
+    written: HashSet<(usize, usize)>,
+    /// How many times each variable is written in the code, counting each write once
+    writes: HashMap<(usize, usize), usize>,
+    /// The functions which can run, which have not been visited yet
+    functions: Vec<usize>,
+    assembly: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Access {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl<'a> Usage<'a> {
+    /// Follow the calls from the constructors, public functions, fallback and receive of the
+    /// contract, and from the initializers of its storage variables. If `deployment` is set, only
+    /// the code which runs when the contract is deployed is followed.
+    fn walk(contract_no: usize, ns: &'a Namespace, deployment: bool) -> Self {
+        let mut usage = Usage {
+            ns,
+            contract_no,
+            read: HashSet::new(),
+            written: HashSet::new(),
+            writes: HashMap::new(),
+            functions: Vec::new(),
+            assembly: false,
+        };
+
+        if !deployment {
+            for function_no in ns.contracts[contract_no].all_functions.keys() {
+                let func = &ns.functions[*function_no];
+
+                if func.is_public()
+                    || matches!(func.ty, pt::FunctionTy::Fallback | pt::FunctionTy::Receive)
+                {
+                    usage.functions.push(*function_no);
+                }
+            }
+        }
+
+        for base_no in ns.contract_bases(contract_no) {
+            let base = &ns.contracts[base_no];
+
+            usage.functions.extend(base.constructors(ns));
+
+            for (_, args) in base
+                .bases
+                .iter()
+                .filter_map(|base| base.constructor.as_ref())
+            {
+                for arg in args {
+                    arg.recurse(&mut usage, read_expression);
+                }
+            }
+
+            for init in base
+                .variables
+                .iter()
+                .filter_map(|var| var.initializer.as_ref())
+            {
+                init.recurse(&mut usage, read_expression);
+            }
+        }
+
+        let mut visited = HashSet::new();
+
+        while let Some(function_no) = usage.functions.pop() {
+            if !visited.insert(function_no) {
+                continue;
+            }
+
+            let func = &ns.functions[function_no];
+
+            for modifier in &func.modifiers {
+                modifier.recurse(&mut usage, read_expression);
+            }
+
+            for (_, _, args) in func.bases.values() {
+                for arg in args {
+                    arg.recurse(&mut usage, read_expression);
+                }
+            }
+
+            for stmt in &func.body {
+                stmt.recurse(&mut usage, statement);
+            }
+        }
+
+        usage
+    }
+
+    fn access(&mut self, var: (usize, usize), access: Access) {
+        if access != Access::Write {
+            self.read.insert(var);
+        }
+
+        if access != Access::Read {
+            self.written.insert(var);
+            *self.writes.entry(var).or_default() += 1;
+        }
+    }
+}
+
+fn statement(stmt: &Statement, usage: &mut Usage) -> bool {
+    match stmt {
+        Statement::VariableDecl(_, _, _, Some(expr)) => {
+            expr.recurse(usage, read_expression);
+        }
+        Statement::If(_, _, expr, ..)
+        | Statement::While(_, _, expr, _)
+        | Statement::DoWhile(_, _, _, expr)
+        | Statement::Expression(_, _, expr)
+        | Statement::Return(_, Some(expr)) => {
+            expr.recurse(usage, read_expression);
+        }
+        Statement::For { cond, next, .. } => {
+            for expr in [cond, next].into_iter().flatten() {
+                expr.recurse(usage, read_expression);
+            }
+        }
+        Statement::Delete(_, _, expr) => {
+            place(expr, Access::Write, usage);
+        }
+        Statement::Destructure(_, fields, expr) => {
+            expr.recurse(usage, read_expression);
+
+            for field in fields {
+                if let DestructureField::Expression(expr) = field {
+                    place(expr, Access::Write, usage);
+                }
+            }
+        }
+        Statement::Revert { args, .. } | Statement::Emit { args, .. } => {
+            for arg in args {
+                arg.recurse(usage, read_expression);
+            }
+        }
+        Statement::TryCatch(_, _, try_catch) => {
+            try_catch.expr.recurse(usage, read_expression);
+        }
+        Statement::Assembly(..) => {
+            usage.assembly = true;
+        }
+        _ => (),
+    }
+
+    true
+}
+
+fn read_expression(expr: &Expression, usage: &mut Usage) -> bool {
+    match expr {
+        Expression::StorageLoad { expr, .. } => {
+            place(expr, Access::Read, usage);
+        }
+        Expression::StorageArrayLength { array, .. } => {
+            place(array, Access::Read, usage);
+        }
+        Expression::Assign { left, right, .. } => {
+            right.recurse(usage, read_expression);
+            place(left, Access::Write, usage);
+        }
+        Expression::PreIncrement { expr, .. }
+        | Expression::PreDecrement { expr, .. }
+        | Expression::PostIncrement { expr, .. }
+        | Expression::PostDecrement { expr, .. } => {
+            place(expr, Access::ReadWrite, usage);
+        }
+        Expression::Builtin {
+            kind: Builtin::ArrayPush | Builtin::ArrayPop,
+            args,
+            ..
+        } if args[0].ty().is_contract_storage() => {
+            place(&args[0], Access::ReadWrite, usage);
+
+            for arg in &args[1..] {
+                arg.recurse(usage, read_expression);
+            }
+        }
+        Expression::StorageVariable {
+            contract_no,
+            var_no,
+            ..
+        } => {
+            // a reference to storage which is not loaded right away, e.g. one which is assigned
+            // to a local storage variable or passed to a function, may be read and written
+            usage.access((*contract_no, *var_no), Access::ReadWrite);
+        }
+        Expression::InternalFunction {
+            function_no,
+            signature,
+            ..
+        } => {
+            let contract = &usage.ns.contracts[usage.contract_no];
+
+            // a virtual function is resolved to the function which overrides it
+            let function_no = signature
+                .as_ref()
+                .and_then(|signature| contract.virtual_functions.get(signature))
+                .and_then(|functions| functions.last())
+                .unwrap_or(function_no);
+
+            usage.functions.push(*function_no);
+            return true;
+        }
+        Expression::UserDefinedOperator { function_no, .. } => {
+            usage.functions.push(*function_no);
+            return true;
+        }
+        _ => return true,
+    }
+
+    false
+}
+
+/// The expression is read or written. If it is in storage, find the variable it is part of.
+fn place(expr: &Expression, access: Access, usage: &mut Usage) {
+    match expr {
+        Expression::StorageVariable {
+            contract_no,
+            var_no,
+            ..
+        } => usage.access((*contract_no, *var_no), access),
+        Expression::Subscript { array, index, .. } => {
+            index.recurse(usage, read_expression);
+            place(array, access, usage);
+        }
+        Expression::StructMember { expr, .. } => place(expr, access, usage),
+        // writing to a value which was loaded from storage does not change storage
+        Expression::StorageLoad { expr, .. } => place(expr, Access::Read, usage),
+        _ => expr.recurse(usage, read_expression),
+    }
+}
@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sema::graph::Graph;
+use crate::sema::tests::parse;
+use crate::{parse_and_resolve, FileResolver, Target};
+use std::ffi::OsStr;
+
+/// The edges of the graph, using the node labels rather than the ids
+fn edges(graph: &Graph) -> Vec<(String, String, Option<String>)> {
+    let label = |id: &str| {
+        graph
+            .nodes
+            .iter()
+            .find(|node| node.id == id)
+            .map(|node| node.label.clone())
+            .unwrap()
+    };
+
+    graph
+        .edges
+        .iter()
+        .map(|edge| (label(&edge.from), label(&edge.to), edge.label.clone()))
+        .collect()
+}
+
+#[test]
+fn inheritance() {
+    let ns = parse(
+        r#"
+        interface I {
+            function f() external;
+        }
+
+        abstract contract A is I {}
+
+        contract B {}
+
+        contract C is A, B {
+            function f() external override {}
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let graph = ns.inheritance_graph();
+
+    assert_eq!(
+        edges(&graph),
+        vec![
+            ("A".into(), "I".into(), None),
+            ("C".into(), "A".into(), None),
+            ("C".into(), "B".into(), None),
+        ]
+    );
+
+    assert_eq!(graph.nodes[0].kind, "interface");
+    assert_eq!(graph.nodes[1].kind, "abstract contract");
+
+    assert_eq!(
+        graph.dot(),
+        r#"digraph "test.sol" {
+	contract_0 [label="I" kind="interface"]
+	contract_1 [label="A" kind="abstract contract"]
+	contract_2 [label="B" kind="contract"]
+	contract_3 [label="C" kind="contract"]
+	contract_1 -> contract_0
+	contract_3 -> contract_1
+	contract_3 -> contract_2
+}
+"#
+    );
+}
+
+#[test]
+fn calls() {
+    let ns = parse(
+        r#"
+        contract D {
+            constructor() {}
+
+            function d(int64 x) public pure returns (int64) {
+                return x;
+            }
+        }
+
+        contract C {
+            modifier only() {
+                _;
+            }
+
+            function f() public only returns (int64) {
+                D d = new D();
+                return g(1) + d.d(2);
+            }
+
+            function g(int64 x) internal pure returns (int64) {
+                return h(x) + h(x);
+            }
+        }
+
+        function h(int64 x) pure returns (int64) {
+            return x;
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let graph = ns.call_graph();
+
+    assert_eq!(
+        edges(&graph),
+        vec![
+            ("C.f()".into(), "C.only()".into(), Some("modifier".into())),
+            (
+                "C.f()".into(),
+                "D.constructor()".into(),
+                Some("creates".into())
+            ),
+            ("C.f()".into(), "C.g(int64)".into(), None),
+            ("C.f()".into(), "D.d(int64)".into(), Some("external".into())),
+            ("C.g(int64)".into(), "h(int64)".into(), None),
+        ]
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&graph.json()).unwrap();
+
+    assert_eq!(json["name"], "test.sol");
+    assert!(json["edges"][2].get("label").is_none());
+}
+
+#[test]
+fn imports() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "a.sol",
+        r#"
+        import "b.sol";
+        import "c.sol";
+        contract A is B, C {}"#
+            .into(),
+    );
+    cache.set_file_contents("b.sol", "import \"c.sol\"; contract B is C {}".into());
+    cache.set_file_contents("c.sol", "contract C {}".into());
+
+    let ns = parse_and_resolve(OsStr::new("a.sol"), &mut cache, Target::EVM);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let graph = ns.import_graph();
+
+    assert_eq!(
+        edges(&graph),
+        vec![
+            ("a.sol".into(), "b.sol".into(), None),
+            ("a.sol".into(), "c.sol".into(), None),
+            ("b.sol".into(), "c.sol".into(), None),
+        ]
+    );
+}
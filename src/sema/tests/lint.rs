@@ -0,0 +1,318 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sema::ast::Level;
+use crate::sema::lint::{lint, Finding, LintConfig, Rule};
+use crate::sema::tests::parse;
+use solang_parser::pt::Loc;
+
+fn lint_source(src: &'static str, config: &LintConfig) -> Vec<Finding> {
+    let ns = parse(src);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    lint(&ns, config)
+}
+
+fn rules(findings: &[Finding]) -> Vec<Rule> {
+    findings.iter().map(|finding| finding.rule).collect()
+}
+
+#[test]
+fn tx_origin() {
+    let findings = lint_source(
+        r#"
+        contract c {
+            address owner;
+
+            function f() public view {
+                require(tx.origin == owner);
+            }
+
+            function g() public view {
+                require(tx.origin == msg.sender);
+            }
+
+            constructor() {
+                owner = msg.sender;
+            }
+        }"#,
+        &LintConfig::default(),
+    );
+
+    assert_eq!(rules(&findings), vec![Rule::TxOrigin]);
+    assert_eq!(
+        findings[0].diagnostic.message,
+        "tx.origin used for authorization, use msg.sender instead"
+    );
+
+    let fix = findings[0].fix.as_ref().unwrap();
+
+    assert_eq!(fix.replacement, "msg.sender");
+    assert_eq!(fix.loc, Loc::File(0, 113, 122));
+}
+
+#[test]
+fn unchecked_call() {
+    let findings = lint_source(
+        r#"
+        contract c {
+            function f(address a) public {
+                a.call("");
+                (, bytes memory b) = a.call("");
+                (bool ok, ) = a.call(b);
+                require(ok);
+            }
+        }"#,
+        &LintConfig::default(),
+    );
+
+    assert_eq!(
+        rules(&findings),
+        vec![Rule::UncheckedCall, Rule::UncheckedCall]
+    );
+    assert_eq!(
+        findings[0].diagnostic.message,
+        "return value of low-level call not checked"
+    );
+}
+
+#[test]
+fn reentrancy() {
+    let src = r#"
+        contract c {
+            mapping(address => uint) balances;
+
+            function withdraw() public {
+                (bool ok, ) = msg.sender.call{value: balances[msg.sender]}("");
+                require(ok);
+                balances[msg.sender] = 0;
+            }
+
+            function safe() public {
+                uint amount = balances[msg.sender];
+                balances[msg.sender] = 0;
+                (bool ok, ) = msg.sender.call{value: amount}("");
+                require(ok);
+            }
+
+            function branches(bool x) public {
+                if (x) {
+                    (bool ok, ) = msg.sender.call("");
+                    require(ok);
+                } else {
+                    balances[msg.sender] = 1;
+                }
+            }
+
+            modifier nonReentrant() {
+                _;
+            }
+
+            function guarded() public nonReentrant {
+                (bool ok, ) = msg.sender.call("");
+                require(ok);
+                balances[msg.sender] = 0;
+            }
+        }"#;
+
+    let findings = lint_source(src, &LintConfig::default());
+
+    assert_eq!(rules(&findings), vec![Rule::Reentrancy]);
+    assert_eq!(findings[0].diagnostic.level, Level::Warning);
+    assert_eq!(findings[0].diagnostic.notes[0].message, "external call");
+
+    let mut config = LintConfig::default();
+    config.set(Rule::Reentrancy, Some(Level::Error));
+
+    let findings = lint_source(src, &config);
+
+    assert_eq!(findings[0].diagnostic.level, Level::Error);
+
+    config.set(Rule::Reentrancy, None);
+
+    assert!(lint_source(src, &config).is_empty());
+}
+
+#[test]
+fn dead_state_variable() {
+    let src = r#"
+        contract base {
+            int inherited;
+
+            function set(int v) internal {
+                inherited = v;
+            }
+        }
+
+        contract c is base {
+            int written;
+            bool read;
+            uint unused;
+            uint public counter;
+            uint[] list;
+            uint used_by_dead_code;
+
+            function f(int v) public returns (bool) {
+                written = v;
+                counter++;
+                list.push(1);
+                set(v);
+                return read;
+            }
+
+            function g() public view returns (uint) {
+                return list.length + uint(inherited);
+            }
+
+            function h() internal view returns (uint) {
+                return used_by_dead_code;
+            }
+        }"#;
+
+    let findings = lint_source(src, &LintConfig::default());
+
+    assert_eq!(
+        findings
+            .iter()
+            .map(|finding| finding.diagnostic.message.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "storage variable 'written' is written, but never read",
+            "storage variable 'read' is read, but never written, so it always has its default value",
+            "storage variable 'unused' is never read or written",
+            "storage variable 'used_by_dead_code' is never read or written",
+        ]
+    );
+    assert!(findings
+        .iter()
+        .all(|finding| finding.rule == Rule::DeadStateVariable));
+
+    let findings = lint_source(
+        r#"
+        contract c {
+            int x;
+
+            function f() public view returns (int r) {
+                assembly {
+                    r := sload(0)
+                }
+            }
+        }"#,
+        &LintConfig::default(),
+    );
+
+    assert!(findings.is_empty());
+}
+
+#[test]
+fn state_mutability() {
+    let findings = lint_source(
+        r#"
+        contract c {
+            int x;
+
+            function get() internal returns (int) {
+                return x;
+            }
+
+            function square(int v) internal view returns (int) {
+                return v * v;
+            }
+
+            function f() public returns (int) {
+                return get();
+            }
+
+            function g(int v) public view returns (int) {
+                return square(v);
+            }
+
+            function h() public {
+                x = square(2);
+            }
+
+            function recurse(uint n) public returns (uint) {
+                return n == 0 ? 0 : recurse(n - 1);
+            }
+        }"#,
+        &LintConfig::default(),
+    );
+
+    assert_eq!(
+        findings
+            .iter()
+            .map(|finding| finding.diagnostic.message.as_str())
+            .collect::<Vec<_>>(),
+        vec![
+            "function can be declared 'view'",
+            "function declared 'view' can be declared 'pure'",
+            "function can be declared 'view'",
+            "function declared 'view' can be declared 'pure'",
+            "function can be declared 'pure'",
+        ]
+    );
+    assert!(findings
+        .iter()
+        .all(|finding| finding.rule == Rule::StateMutability));
+
+    // a keyword can be replaced, but there is no keyword for nonpayable
+    assert_eq!(findings[0].fix, None);
+
+    let fix = findings[1].fix.as_ref().unwrap();
+
+    assert_eq!(fix.replacement, "pure");
+    assert_eq!(fix.loc, Loc::File(0, 179, 183));
+}
+
+#[test]
+fn rule_names() {
+    for rule in Rule::ALL {
+        assert_eq!(rule.name().parse::<Rule>(), Ok(rule));
+    }
+
+    assert!("foo".parse::<Rule>().is_err());
+}
+
+#[test]
+fn sarif() {
+    let ns = parse(
+        r#"
+        contract c {
+            function f(address owner) public view returns (bool) {
+                int unused;
+                return tx.origin == owner;
+            }
+        }"#,
+    );
+
+    let results = ns.diagnostics_as_sarif();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].rule_id, "warning");
+    assert_eq!(results[0].message.text, "local variable 'unused' is unused");
+    assert!(results[0].fixes.is_empty());
+
+    let errors =
+        parse("contract c { function f() public { int x = true; } }").diagnostics_as_sarif();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].rule_id, "cast-error");
+    assert_eq!(errors[0].level, "error");
+
+    let findings = lint(&ns, &LintConfig::default());
+    let results = ns.findings_as_sarif(&findings);
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].rule_id, "tx-origin");
+    assert_eq!(results[0].level, "warning");
+
+    let region = &results[0].locations[0].physical_location.region;
+
+    assert_eq!((region.start_line, region.start_column), (5, 24));
+    assert_eq!((region.end_line, region.end_column), (5, 42));
+
+    let replacement = &results[0].fixes[0].artifact_changes[0].replacements[0];
+
+    assert_eq!(replacement.inserted_content.text, "msg.sender");
+    assert_eq!(replacement.deleted_region.end_column, 33);
+}
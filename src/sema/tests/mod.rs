@@ -3,6 +3,9 @@
 #![cfg(test)]
 
 mod data_account;
+mod graph;
+mod lint;
+mod warnings;
 
 use crate::sema::ast::{Expression, Parameter, Statement, TryCatch, Type};
 use crate::sema::yul::ast::InlineAssembly;
@@ -655,3 +658,104 @@ fn get_import_path() {
         assert_eq!(Some(&(None, examples.clone())), import_path);
     }
 }
+
+#[test]
+fn ast_json() {
+    let ns = parse(
+        r#"
+        contract First {}
+
+        contract Second is First {
+            int64 count = 1;
+        }
+        "#,
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&ns.ast_json(1)).unwrap();
+
+    assert_eq!(json["labels"][0], "contract Second");
+
+    let children = json["children"].as_array().unwrap();
+
+    assert_eq!(children[0]["relation"], "base");
+    assert_eq!(children[0]["labels"][0], "base First");
+    assert_eq!(children[1]["relation"], "variable");
+    assert_eq!(children[1]["labels"][0], "variable count");
+    assert_eq!(children[1]["children"][0]["relation"], "initializer");
+}
+
+#[test]
+fn math_builtins_shadowed() {
+    let file = r#"
+library math {
+    function sqrt(uint x, uint y) internal pure returns (uint) {
+        return x + y;
+    }
+}
+
+contract c {
+    function f(uint x) public pure returns (uint) {
+        return math.sqrt(x, 2);
+    }
+
+    function g(uint x) public pure returns (uint) {
+        uint math = x;
+        return math + 1;
+    }
+}
+    "#;
+
+    let ns = parse(file);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let file = r#"
+contract c {
+    function f(uint x) public pure returns (uint) {
+        return math.sqrt(x) + math.log2(x) + math.mulDiv(x, x, 3);
+    }
+}
+    "#;
+
+    let ns = parse(file);
+
+    assert!(!ns.diagnostics.any_errors());
+}
+
+#[test]
+fn string_builtins() {
+    let file = r#"
+contract c {
+    function f(string s, bytes b, int8 x) public pure returns (string, bytes, int64, int64, int) {
+        string t = string.concat(string.toString(x), string.toHexString(uint64(7)), s.slice(1, 2));
+        return (t, b.slice(0, 1), s.indexOf("a"), b.indexOf(hex"01"), string.parseInt(s));
+    }
+}
+    "#;
+
+    let ns = parse(file);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let file = r#"
+contract c {
+    function f(bytes b) public pure returns (string, int64) {
+        return (string.toString(true), b.indexOf("a"));
+    }
+
+    function g(string s) public pure returns (int) {
+        return string.parseInt(s, 10);
+    }
+}
+    "#;
+
+    let ns = parse(file);
+
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "'string.toString' expects an integer argument, not 'bool'"
+    );
+    assert!(ns
+        .diagnostics
+        .contains_message("builtin function 'parseInt' expects 1 arguments, 2 provided"));
+}
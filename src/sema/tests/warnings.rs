@@ -0,0 +1,217 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::sema::ast::{Diagnostic, Level, Namespace};
+use crate::sema::tests::parse;
+use crate::sema::warnings::{WarningCode, WarningConfig};
+use crate::{parse_and_resolve_into, FileResolver, Target};
+use std::ffi::OsStr;
+
+fn warnings(ns: &Namespace) -> Vec<(WarningCode, &Diagnostic)> {
+    ns.diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == Level::Warning)
+        .map(|diagnostic| (WarningCode::of(diagnostic), diagnostic))
+        .collect()
+}
+
+#[test]
+fn codes() {
+    let ns = parse(
+        r#"
+        contract c {
+            uint64 stored;
+            event Unused();
+
+            function f(int64 a) public returns (int32 r) {
+                int64 x = 1;
+                int16 shadowed = int16(int64(2));
+                return;
+                x = 2;
+            }
+
+            function g() public view returns (uint64) {
+                return 1;
+            }
+        }"#,
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let mut codes: Vec<WarningCode> = warnings(&ns).iter().map(|(code, _)| *code).collect();
+    codes.sort();
+    codes.dedup();
+
+    assert_eq!(
+        codes,
+        vec![
+            WarningCode::UnusedVariable,
+            WarningCode::UnusedParameter,
+            WarningCode::UnassignedReturn,
+            WarningCode::UnusedStorage,
+            WarningCode::UnusedEvent,
+            WarningCode::UnreachableCode,
+            WarningCode::Mutability,
+        ]
+    );
+
+    assert_eq!(
+        WarningCode::resolve("unused").unwrap().len(),
+        WarningCode::ALL
+            .iter()
+            .filter(|code| code.group() == "unused")
+            .count()
+    );
+    assert_eq!(
+        WarningCode::resolve("warnings").unwrap().len(),
+        WarningCode::ALL.len()
+    );
+    assert_eq!(
+        WarningCode::resolve("shadowing").unwrap(),
+        vec![WarningCode::Shadowing]
+    );
+    assert_eq!(
+        WarningCode::resolve("foo").unwrap_err(),
+        "unknown warning code or group 'foo'"
+    );
+}
+
+#[test]
+fn config() {
+    let src = r#"
+        contract c {
+            function f(int64 a) public pure {
+                int64 x = 1;
+            }
+        }"#;
+
+    let mut ns = parse(src);
+    let mut config = WarningConfig::default();
+
+    config.set("unused-parameter", None).unwrap();
+    config.set("unused-variable", Some(Level::Error)).unwrap();
+
+    ns.apply_warning_config(&config);
+
+    assert!(ns.diagnostics.any_errors());
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "local variable 'x' is unused [unused-variable]"
+    );
+    assert!(!ns
+        .diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.message.starts_with("function parameter")));
+
+    // deny everything, except unused variables
+    let mut ns = parse(src);
+    let mut config = WarningConfig::default();
+
+    config.set("warnings", Some(Level::Error)).unwrap();
+    config.set("unused-variable", Some(Level::Warning)).unwrap();
+
+    ns.apply_warning_config(&config);
+
+    assert_eq!(ns.diagnostics.errors().len(), 1);
+    assert_eq!(
+        ns.diagnostics.first_error(),
+        "function parameter 'a' is unused [unused-parameter]"
+    );
+    assert!(ns
+        .diagnostics
+        .warning_contains("local variable 'x' is unused [unused-variable]"));
+}
+
+#[test]
+fn disable_next_line() {
+    let ns = parse(
+        r#"
+        contract c {
+            // solang-disable-next-line unused-parameter
+            function f(int64 a) public pure {
+                // solang-disable-next-line
+                int64 x = 1;
+                // solang-disable-next-line unused-parameter
+                int64 y = 1;
+            }
+
+            // solang-disable-next-line unused, foo
+            function g(int64 b) public pure {}
+        }"#,
+    );
+
+    let messages: Vec<&str> = warnings(&ns)
+        .iter()
+        .map(|(_, diagnostic)| diagnostic.message.as_str())
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "local variable 'y' is unused",
+            "unknown warning code or group 'foo'",
+        ]
+    );
+}
+
+#[test]
+fn strict_conversions() {
+    let src = r#"
+        contract c {
+            function f(uint8 a) public pure returns (int16) {
+                int16 d = a;
+                return d;
+            }
+
+            function g(uint8 a, int16 b) public pure returns (int16) {
+                int16 e = a + b;
+                return e;
+            }
+
+            function h(bytes memory c) public pure returns (bytes4) {
+                bytes4 r = c;
+                return r;
+            }
+        }"#;
+
+    let ns = parse(src);
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let mut ns = Namespace::new(Target::EVM);
+    ns.strict_conversions = true;
+
+    let ns = parse_and_resolve_into(OsStr::new("test.sol"), &mut cache, ns);
+
+    let errors: Vec<_> = ns
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.level == Level::Error)
+        .map(|diagnostic| {
+            (
+                diagnostic.message.as_str(),
+                diagnostic.notes[0].message.as_str(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "implicit conversion would change sign from uint8 to int16",
+                "convert explicitly with 'int16(...)'"
+            ),
+            (
+                "implicit conversion would change sign from uint8 to int16",
+                "convert explicitly with 'int16(...)'"
+            ),
+            (
+                "implicit conversion would truncate from bytes to bytes4",
+                "convert explicitly with 'bytes4(...)'"
+            ),
+        ]
+    );
+}
@@ -1,11 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
+use super::builtin::eth_abi_type;
 use super::tags::resolve_tags;
 use super::{annotions_not_allowed, ast, SourceUnit, SOLANA_BUCKET_SIZE};
 use super::{
     ast::{
         ArrayLength, Contract, Diagnostic, EnumDecl, ErrorDecl, EventDecl, Mapping, Namespace,
-        Parameter, StructDecl, StructType, Symbol, Tag, Type, UserTypeDecl,
+        PackedSlot, Parameter, StructDecl, StructType, Symbol, Tag, Type, UserTypeDecl,
     },
     diagnostics::Diagnostics,
     ContractDefinition, SOLANA_SPARSE_ARRAY_SIZE,
@@ -834,6 +835,18 @@ fn event_decl(
             ty = Type::Unresolved;
         }
 
+        if ns.target == Target::Stylus && ty != Type::Unresolved && !eth_abi_type(&ty, ns) {
+            ns.diagnostics.push(Diagnostic::error(
+                field.ty.loc(),
+                format!(
+                    "event field of type '{}' is not supported on {}",
+                    ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+            ty = Type::Unresolved;
+        }
+
         let name = if let Some(name) = &field.name {
             if let Some(other) = fields
                 .iter()
@@ -957,6 +970,18 @@ fn error_decl(
             ty = Type::Unresolved;
         }
 
+        if ns.target == Target::Stylus && ty != Type::Unresolved && !eth_abi_type(&ty, ns) {
+            ns.diagnostics.push(Diagnostic::error(
+                field.ty.loc(),
+                format!(
+                    "error field of type '{}' is not supported on {}",
+                    ty.to_string(ns),
+                    ns.target
+                ),
+            ));
+            ty = Type::Unresolved;
+        }
+
         let id = if let Some(name) = &field.name {
             if let Some(other) = fields
                 .iter()
@@ -1188,6 +1213,95 @@ fn struct_offsets(ns: &mut Namespace) {
     }
 }
 
+impl Namespace {
+    /// Lay out values in consecutive storage slots. Consecutive small values share a slot while
+    /// they fit in it. A value of `None` takes no storage and is not packed, like a struct field
+    /// of infinite size.
+    ///
+    /// Returns the slot of each value relative to the first slot and where it is in that slot, if
+    /// it shares it, followed by the number of slots used overall.
+    pub fn pack_storage<'a>(
+        &self,
+        tys: impl IntoIterator<Item = Option<&'a Type>>,
+    ) -> (Vec<(BigInt, Option<PackedSlot>)>, BigInt) {
+        let mut layout = Vec::new();
+        let mut slot = BigInt::zero();
+        // the first value in the slot which is being filled, and the bytes used so far
+        let mut first = 0;
+        let mut width = 0;
+
+        for ty in tys {
+            let bytes = ty.and_then(|ty| ty.packed_storage_bytes(self));
+
+            // the slot which is being filled is done if this value does not go in it
+            if width > 0 && bytes.map_or(true, |bytes| width + bytes > 32) {
+                share_slot(&mut layout[first..], width);
+                slot += 1;
+                width = 0;
+            }
+
+            if let Some(bytes) = bytes {
+                if width == 0 {
+                    first = layout.len();
+                }
+
+                layout.push((
+                    slot.clone(),
+                    Some(PackedSlot {
+                        offset: width,
+                        width: 0,
+                    }),
+                ));
+
+                width += bytes;
+            } else {
+                layout.push((slot.clone(), None));
+
+                if let Some(ty) = ty {
+                    slot += ty.storage_slots(self);
+                }
+            }
+        }
+
+        if width > 0 {
+            share_slot(&mut layout[first..], width);
+            slot += 1;
+        }
+
+        (layout, slot)
+    }
+}
+
+/// Set the width of the slot on the values which share it. A value on its own in a slot does
+/// not share it, so it is stored as it would be otherwise.
+fn share_slot(values: &mut [(BigInt, Option<PackedSlot>)], width: u8) {
+    let shared = values.len() > 1;
+    // integers of other sizes would be stored with padding
+    let width = width.next_power_of_two();
+
+    for (_, packed) in values {
+        if shared {
+            if let Some(packed) = packed {
+                packed.width = width;
+            }
+        } else {
+            *packed = None;
+        }
+    }
+}
+
+impl StructDecl {
+    /// The storage layout of the fields, on targets where storage is made of slots. See
+    /// [`Namespace::pack_storage`].
+    pub fn storage_packing(&self, ns: &Namespace) -> (Vec<(BigInt, Option<PackedSlot>)>, BigInt) {
+        ns.pack_storage(
+            self.fields
+                .iter()
+                .map(|field| (!field.infinite_size).then_some(&field.ty)),
+        )
+    }
+}
+
 impl Type {
     /// Return the set of user defined structs this type encapsulates.
     pub fn user_struct_no(&self, ns: &Namespace) -> HashSet<usize> {
@@ -1772,13 +1886,7 @@ impl Type {
         } else {
             match self {
                 Type::StorageRef(_, r) | Type::Ref(r) => r.storage_slots(ns),
-                Type::Struct(str_ty) => str_ty
-                    .definition(ns)
-                    .fields
-                    .iter()
-                    .filter(|f| !f.infinite_size)
-                    .map(|f| f.ty.storage_slots(ns))
-                    .sum(),
+                Type::Struct(str_ty) => str_ty.definition(ns).storage_packing(ns).1,
                 Type::Array(_, dims) if dims.contains(&ArrayLength::Dynamic) => {
                     dynamic_array_size(dims)
                 }
@@ -1801,6 +1909,27 @@ impl Type {
         }
     }
 
+    /// The number of bytes of a value which may share its storage slot with its neighbours.
+    /// Only small value types can, and only on targets where storage is made of 32 byte slots.
+    pub fn packed_storage_bytes(&self, ns: &Namespace) -> Option<u8> {
+        if !matches!(ns.target, Target::Polkadot { .. } | Target::EVM) {
+            return None;
+        }
+
+        let bytes = match self {
+            Type::Bool
+            | Type::Int(_)
+            | Type::Uint(_)
+            | Type::Bytes(_)
+            | Type::Enum(_)
+            | Type::Value => self.bytes(ns),
+            Type::UserType(no) => return ns.user_types[*no].ty.packed_storage_bytes(ns),
+            _ => return None,
+        };
+
+        (bytes < 32).then_some(bytes)
+    }
+
     /// Alignment of elements in storage
     pub fn storage_align(&self, ns: &Namespace) -> BigInt {
         if ns.target == Target::Solana {
@@ -146,11 +146,12 @@ pub fn used_variable(ns: &mut Namespace, exp: &Expression, symtable: &mut Symtab
         }
 
         Expression::Builtin {
-            kind: Builtin::ArrayPush | Builtin::ArrayPop,
+            kind: Builtin::ArrayPush | Builtin::ArrayPop | Builtin::ArrayReserve,
             args,
             ..
         } => {
-            // Array push and pop return values, so they are both read and assigned.
+            // Array push and pop return values, so they are both read and assigned. Reserve may
+            // move the array, so it is assigned too.
             used_variable(ns, &args[0], symtable);
             assigned_variable(ns, &args[0], symtable);
         }
@@ -239,7 +240,7 @@ pub fn check_function_call(ns: &mut Namespace, exp: &Expression, symtable: &mut
             args,
             ..
         } => match expr_type {
-            Builtin::ArrayPush | Builtin::ArrayPop => {
+            Builtin::ArrayPush | Builtin::ArrayPop | Builtin::ArrayReserve => {
                 assigned_variable(ns, &args[0], symtable);
                 if args.len() > 1 {
                     used_variable(ns, &args[1], symtable);
@@ -285,6 +286,9 @@ fn check_call_args(ns: &mut Namespace, call_args: &CallArgs, symtable: &mut Symt
     if let Some(program_id) = &call_args.program_id {
         used_variable(ns, program_id.as_ref(), symtable);
     }
+    if let Some(storage_deposit_limit) = &call_args.storage_deposit_limit {
+        used_variable(ns, storage_deposit_limit.as_ref(), symtable);
+    }
 }
 
 /// Marks as used variables that appear in an expression with right and left hand side.
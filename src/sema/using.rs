@@ -73,7 +73,7 @@ pub(crate) fn using_decl(
                 ns.resolve_contract_with_namespace(file_no, library, &mut diagnostics)
             {
                 if ns.contracts[library_no].is_library() {
-                    UsingList::Library(library_no)
+                    UsingList::Library(library.loc, library_no)
                 } else {
                     ns.diagnostics.push(Diagnostic::error(
                         library.loc,
@@ -372,7 +372,7 @@ fn possible_functions(
         })
         .flat_map(|using| {
             let iterator: Box<dyn Iterator<Item = _>> = match &using.list {
-                UsingList::Library(library_no) => {
+                UsingList::Library(_, library_no) => {
                     Box::new(ns.contracts[*library_no].functions.iter())
                 }
                 UsingList::Functions(functions) => {
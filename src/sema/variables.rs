@@ -3,8 +3,8 @@
 use super::{
     annotions_not_allowed,
     ast::{
-        Diagnostic, Expression, Function, Mapping, Namespace, Parameter, Statement, StructType,
-        Symbol, Type, Variable,
+        ArrayLength, Diagnostic, EnumerableMapping, Expression, Function, Mapping, Namespace,
+        Parameter, Statement, StructType, Symbol, Type, Variable,
     },
     contracts::is_base,
     diagnostics::Diagnostics,
@@ -15,6 +15,8 @@ use super::{
 };
 use crate::sema::expression::resolve_expression::expression;
 use crate::sema::namespace::ResolveTypeContext;
+use crate::target::Capability;
+use crate::Target;
 use solang_parser::{
     doccomment::DocComment,
     pt::{self, CodeLocation, OptionalCodeLocation},
@@ -37,7 +39,8 @@ pub fn contract_variables<'a>(
 
     for part in &def.parts {
         if let pt::ContractPart::VariableDefinition(ref s) = &part.part {
-            annotions_not_allowed(&part.annotations, "variable", ns);
+            let enumerable = enumerable_annotation(&part.annotations, ns);
+            let var_no = ns.contracts[def.contract_no].variables.len();
 
             if let Some(delay) = variable_decl(
                 Some(def),
@@ -50,12 +53,123 @@ pub fn contract_variables<'a>(
             ) {
                 delayed.push(delay);
             }
+
+            // the variable is not added if it has errors
+            if let Some(loc) = enumerable {
+                if var_no < ns.contracts[def.contract_no].variables.len() {
+                    enumerable_mapping(loc, def.contract_no, var_no, ns);
+                }
+            }
         }
     }
 
     delayed
 }
 
+/// The only annotation allowed on state variables is `@enumerable(true)`. Return its location,
+/// if the variable has it.
+fn enumerable_annotation(annotations: &[&pt::Annotation], ns: &mut Namespace) -> Option<pt::Loc> {
+    let mut enumerable = None;
+
+    for note in annotations {
+        if note.id.name != "enumerable" {
+            annotions_not_allowed(&[note], "variable", ns);
+            continue;
+        }
+
+        if let Some(prev) = &enumerable {
+            ns.diagnostics.push(Diagnostic::error_with_note(
+                note.loc,
+                "duplicate '@enumerable' annotation".to_string(),
+                *prev,
+                "previous '@enumerable' annotation".to_string(),
+            ));
+            continue;
+        }
+
+        match &note.value {
+            Some(pt::Expression::BoolLiteral(_, value)) => {
+                enumerable = value.then_some(note.loc);
+            }
+            _ => {
+                ns.diagnostics.push(Diagnostic::error(
+                    note.loc,
+                    "annotation '@enumerable' expects 'true' or 'false'".to_string(),
+                ));
+            }
+        }
+    }
+
+    enumerable
+}
+
+/// Make a mapping state variable enumerable. This adds two hidden storage variables: an array
+/// with the keys, and a mapping from each key to its position in the array. Codegen keeps them
+/// up to date when the mapping is assigned to or deleted from.
+fn enumerable_mapping(loc: pt::Loc, contract_no: usize, var_no: usize, ns: &mut Namespace) {
+    let var = &ns.contracts[contract_no].variables[var_no];
+
+    let Type::Mapping(Mapping { key, .. }) = &var.ty else {
+        ns.diagnostics.push(Diagnostic::error(
+            loc,
+            format!(
+                "annotation '@enumerable' is only allowed on mappings, not '{}'",
+                var.ty.to_string(ns)
+            ),
+        ));
+        return;
+    };
+
+    let name = var.name.clone();
+    let name_loc = var.name_loc;
+    let var_loc = var.loc;
+    let key = key.as_ref().clone();
+    let position_ty = ns.storage_type();
+
+    let mut hidden = |suffix: &str, ty: Type| {
+        let var_no = ns.contracts[contract_no].variables.len();
+
+        // these variables have no symbol, so they can only be used through the mapping
+        ns.contracts[contract_no].variables.push(Variable {
+            tags: Vec::new(),
+            name: format!("{name}.{suffix}"),
+            name_loc,
+            loc: var_loc,
+            ty,
+            visibility: pt::Visibility::Private(None),
+            constant: false,
+            immutable: false,
+            initializer: None,
+            assigned: true,
+            read: true,
+            enumerable: None,
+        });
+
+        var_no
+    };
+
+    let keys = hidden(
+        "keys",
+        Type::Array(Box::new(key.clone()), vec![ArrayLength::Dynamic]),
+    );
+
+    let positions = hidden(
+        "positions",
+        Type::Mapping(Mapping {
+            key: Box::new(key),
+            key_name: None,
+            value: Box::new(position_ty),
+            value_name: None,
+        }),
+    );
+
+    ns.contracts[contract_no].variables[var_no].enumerable = Some(EnumerableMapping {
+        contract_no,
+        keys,
+        positions,
+    });
+}
+
 pub fn variable_decl<'a>(
     contract: Option<&ContractDefinition>,
     def: &'a pt::VariableDefinition,
@@ -319,6 +433,16 @@ pub fn variable_decl<'a>(
         return None;
     }
 
+    if !constant && !is_storage_value(&ty, ns) {
+        let mut diagnostics = Diagnostics::default();
+        ns.target_supports(
+            &def.ty.loc(),
+            Capability::StorageReferenceTypes,
+            &mut diagnostics,
+        );
+        ns.diagnostics.extend(diagnostics);
+    }
+
     let mut diagnostics = Diagnostics::default();
 
     let initializer = if constant {
@@ -383,6 +507,7 @@ pub fn variable_decl<'a>(
 
     let sdecl = Variable {
         name: def.name.as_ref().unwrap().name.to_string(),
+        name_loc: def.name.as_ref().unwrap().loc,
         loc: def.loc,
         tags,
         visibility: visibility.clone(),
@@ -392,6 +517,7 @@ pub fn variable_decl<'a>(
         assigned: def.initializer.is_some(),
         initializer,
         read: matches!(visibility, pt::Visibility::Public(_)),
+        enumerable: None,
     };
 
     let var_no = if let Some(contract_no) = contract_no {
@@ -517,6 +643,33 @@ pub fn variable_decl<'a>(
     ret
 }
 
+/// Can every target keep this type in storage: value types and mappings of them. On Soroban,
+/// `string`, `bytes` and dynamic arrays are host objects, which can be stored as well; an
+/// array of mappings has no host object.
+fn is_storage_value(ty: &Type, ns: &Namespace) -> bool {
+    match ty {
+        Type::Bool
+        | Type::Int(_)
+        | Type::Uint(_)
+        | Type::Bytes(_)
+        | Type::Enum(_)
+        | Type::Value
+        | Type::Address(_)
+        | Type::Contract(_) => true,
+        Type::String | Type::DynamicBytes => ns.target == Target::Soroban,
+        Type::Array(_, dims) if ns.target == Target::Soroban => {
+            let elem = ty.array_elem();
+
+            dims.last() == Some(&ArrayLength::Dynamic)
+                && !matches!(elem, Type::Mapping(..))
+                && is_storage_value(&elem, ns)
+        }
+        Type::UserType(no) => is_storage_value(&ns.user_types[*no].ty, ns),
+        Type::Mapping(Mapping { value, .. }) => is_storage_value(value, ns),
+        _ => false,
+    }
+}
+
 /// For accessor functions, create the parameter list and the return expression
 fn collect_parameters(
     ty: &Type,
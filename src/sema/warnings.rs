@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Codes for the warnings given during semantic analysis, so that they can be denied, allowed or
+//! suppressed. Codes are grouped, e.g. `unused` covers all the unused variable warnings, and
+//! `warnings` covers every code.
+//!
+//! A warning on a line can be suppressed with a comment on the line before it:
+//! `// solang-disable-next-line unused-variable`. Without any codes, all warnings on the next
+//! line are suppressed.
+
+use crate::sema::ast::{Diagnostic, Level, Namespace};
+use solang_parser::pt;
+use std::{collections::HashMap, fmt, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum WarningCode {
+    UnusedVariable,
+    UnusedParameter,
+    UnassignedReturn,
+    UnusedStorage,
+    UnusedConstant,
+    UnusedEvent,
+    UnusedError,
+    UnusedFunction,
+    UnreachableCode,
+    Shadowing,
+    Redefinition,
+    Truncation,
+    EnumOrdinal,
+    Mutability,
+    Deprecated,
+    Ignored,
+    Natspec,
+    CurrencyUnit,
+    Gasprice,
+    AmbiguousEvent,
+    DeleteNonStorage,
+    Other,
+}
+
+/// The name used for all the codes
+pub const ALL_WARNINGS: &str = "warnings";
+
+/// The comment which suppresses warnings on the next line
+pub const DISABLE_NEXT_LINE: &str = "solang-disable-next-line";
+
+/// The start of the note of a strict conversion error, followed by the explicit conversion to
+/// write around the expression, like `'int16(...)'`
+pub const STRICT_CONVERSION_NOTE: &str = "convert explicitly with ";
+
+impl WarningCode {
+    pub const ALL: [WarningCode; 22] = [
+        WarningCode::UnusedVariable,
+        WarningCode::UnusedParameter,
+        WarningCode::UnassignedReturn,
+        WarningCode::UnusedStorage,
+        WarningCode::UnusedConstant,
+        WarningCode::UnusedEvent,
+        WarningCode::UnusedError,
+        WarningCode::UnusedFunction,
+        WarningCode::UnreachableCode,
+        WarningCode::Shadowing,
+        WarningCode::Redefinition,
+        WarningCode::Truncation,
+        WarningCode::EnumOrdinal,
+        WarningCode::Mutability,
+        WarningCode::Deprecated,
+        WarningCode::Ignored,
+        WarningCode::Natspec,
+        WarningCode::CurrencyUnit,
+        WarningCode::Gasprice,
+        WarningCode::AmbiguousEvent,
+        WarningCode::DeleteNonStorage,
+        WarningCode::Other,
+    ];
+
+    /// Name of the code, as used on the command line, in solang.toml and in comments
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarningCode::UnusedVariable => "unused-variable",
+            WarningCode::UnusedParameter => "unused-parameter",
+            WarningCode::UnassignedReturn => "unassigned-return",
+            WarningCode::UnusedStorage => "unused-storage",
+            WarningCode::UnusedConstant => "unused-constant",
+            WarningCode::UnusedEvent => "unused-event",
+            WarningCode::UnusedError => "unused-error",
+            WarningCode::UnusedFunction => "unused-function",
+            WarningCode::UnreachableCode => "unreachable-code",
+            WarningCode::Shadowing => "shadowing",
+            WarningCode::Redefinition => "redefinition",
+            WarningCode::Truncation => "truncation",
+            WarningCode::EnumOrdinal => "enum-ordinal",
+            WarningCode::Mutability => "mutability",
+            WarningCode::Deprecated => "deprecated",
+            WarningCode::Ignored => "ignored",
+            WarningCode::Natspec => "natspec",
+            WarningCode::CurrencyUnit => "currency-unit",
+            WarningCode::Gasprice => "gasprice",
+            WarningCode::AmbiguousEvent => "ambiguous-event",
+            WarningCode::DeleteNonStorage => "delete-non-storage",
+            WarningCode::Other => "other",
+        }
+    }
+
+    /// The group the code belongs to
+    pub fn group(&self) -> &'static str {
+        match self {
+            WarningCode::UnusedVariable
+            | WarningCode::UnusedParameter
+            | WarningCode::UnassignedReturn
+            | WarningCode::UnusedStorage
+            | WarningCode::UnusedConstant
+            | WarningCode::UnusedEvent
+            | WarningCode::UnusedError
+            | WarningCode::UnusedFunction
+            | WarningCode::UnreachableCode => "unused",
+            WarningCode::Shadowing | WarningCode::Redefinition => "shadow",
+            WarningCode::Truncation | WarningCode::EnumOrdinal => "conversion",
+            WarningCode::Mutability
+            | WarningCode::Deprecated
+            | WarningCode::Ignored
+            | WarningCode::Natspec => "style",
+            WarningCode::CurrencyUnit | WarningCode::Gasprice => "target",
+            WarningCode::AmbiguousEvent | WarningCode::DeleteNonStorage | WarningCode::Other => {
+                "misc"
+            }
+        }
+    }
+
+    /// The codes with the given name, or in the group with the given name
+    pub fn resolve(name: &str) -> Result<Vec<WarningCode>, String> {
+        let codes: Vec<WarningCode> = WarningCode::ALL
+            .into_iter()
+            .filter(|code| name == ALL_WARNINGS || code.name() == name || code.group() == name)
+            .collect();
+
+        if codes.is_empty() {
+            Err(format!("unknown warning code or group '{name}'"))
+        } else {
+            Ok(codes)
+        }
+    }
+
+    /// The code of a warning. Warnings are recognized by their message.
+    pub fn of(diagnostic: &Diagnostic) -> WarningCode {
+        let message = diagnostic.message.as_str();
+
+        let unused_variable = [
+            "local variable '",
+            "destructure variable '",
+            "try-catch returns variable '",
+            "try-catch error string '",
+            "try-catch error bytes '",
+            "yul variable '",
+        ];
+
+        if unused_variable
+            .iter()
+            .any(|prefix| message.starts_with(prefix))
+        {
+            WarningCode::UnusedVariable
+        } else if message.starts_with("function parameter '") {
+            WarningCode::UnusedParameter
+        } else if message.starts_with("return variable '") {
+            WarningCode::UnassignedReturn
+        } else if message.starts_with("storage variable '") {
+            WarningCode::UnusedStorage
+        } else if message.starts_with("global constant '") {
+            WarningCode::UnusedConstant
+        } else if message.starts_with("event '") && message.ends_with("has never been emitted") {
+            WarningCode::UnusedEvent
+        } else if message.starts_with("error '") && message.ends_with("has never been used") {
+            WarningCode::UnusedError
+        } else if message == "yul function has never been used" {
+            WarningCode::UnusedFunction
+        } else if message.starts_with("unreachable ") {
+            WarningCode::UnreachableCode
+        } else if message.contains(" shadows ") {
+            WarningCode::Shadowing
+        } else if message.contains(" is already defined as ") {
+            WarningCode::Redefinition
+        } else if message.contains(" may not fit into ")
+            || message.contains(" may not be correctly represented ")
+            || message.starts_with("conversion truncates ")
+            || message.starts_with("Truncating ")
+            || message.starts_with("function selector ")
+        {
+            WarningCode::Truncation
+        } else if message.starts_with("enum ") && message.contains(" has no value with ordinal ") {
+            WarningCode::EnumOrdinal
+        } else if message.contains(" can be declared ") {
+            WarningCode::Mutability
+        } else if message.contains(" is deprecated") {
+            WarningCode::Deprecated
+        } else if message.ends_with(" is ignored")
+            || message.ends_with(" will be ignored")
+            || message.ends_with(" are implicitly virtual")
+            || message.ends_with(" cannot be named")
+            || message.ends_with(" redefined to same function")
+            || message.starts_with("flag '")
+        {
+            WarningCode::Ignored
+        } else if message.contains("tag '@") || message.starts_with("'@param' used in stead") {
+            WarningCode::Natspec
+        } else if message.contains(" currency unit used while targeting ") {
+            WarningCode::CurrencyUnit
+        } else if message.contains("tx.gasprice") {
+            WarningCode::Gasprice
+        } else if message.starts_with("emit can be resolved to multiple incompatible events") {
+            WarningCode::AmbiguousEvent
+        } else if message.starts_with("argument to 'delete' ") {
+            WarningCode::DeleteNonStorage
+        } else {
+            WarningCode::Other
+        }
+    }
+}
+
+impl fmt::Display for WarningCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for WarningCode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        WarningCode::ALL
+            .into_iter()
+            .find(|code| code.name() == s)
+            .ok_or_else(|| format!("unknown warning code '{s}'"))
+    }
+}
+
+/// The severity of each warning code. A code without a severity is not reported.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WarningConfig {
+    levels: HashMap<WarningCode, Option<Level>>,
+    /// Reject the implicit conversions which solc 0.8 does not allow: from unsigned to signed
+    /// integers, including the operands of mixed sign arithmetic, and from `bytes` to `bytesN`.
+    /// This must be set on the namespace before it is resolved.
+    pub strict_conversions: bool,
+}
+
+impl Default for WarningConfig {
+    fn default() -> Self {
+        WarningConfig {
+            levels: WarningCode::ALL
+                .into_iter()
+                .map(|code| (code, Some(Level::Warning)))
+                .collect(),
+            strict_conversions: false,
+        }
+    }
+}
+
+impl WarningConfig {
+    /// Set the severity of a code, a group of codes, or all codes. `None` allows the warnings,
+    /// so they are not reported.
+    pub fn set(&mut self, name: &str, level: Option<Level>) -> Result<(), String> {
+        for code in WarningCode::resolve(name)? {
+            self.levels.insert(code, level.clone());
+        }
+
+        Ok(())
+    }
+
+    pub fn level(&self, code: WarningCode) -> Option<Level> {
+        self.levels.get(&code).cloned().flatten()
+    }
+}
+
+/// Warnings on a line which are suppressed by a comment on the line before
+#[derive(Debug)]
+pub struct Suppression {
+    pub file_no: usize,
+    /// The line, based zero
+    pub line: usize,
+    /// The suppressed codes. If empty, all warnings are suppressed.
+    pub codes: Vec<WarningCode>,
+}
+
+impl Namespace {
+    /// Find the comments which suppress warnings on the next line
+    pub(super) fn add_suppressions(&mut self, comments: &[pt::Comment]) {
+        for comment in comments {
+            let pt::Comment::Line(loc, text) = comment else {
+                continue;
+            };
+
+            let Some(names) = text
+                .trim_start_matches('/')
+                .trim()
+                .strip_prefix(DISABLE_NEXT_LINE)
+            else {
+                continue;
+            };
+
+            // `solang-disable-next-lines` is not the same comment
+            if names.starts_with(|c: char| !c.is_whitespace() && c != ',') {
+                continue;
+            }
+
+            let mut codes = Vec::new();
+
+            for name in names.split(|c: char| c.is_whitespace() || c == ',') {
+                if name.is_empty() {
+                    continue;
+                }
+
+                match WarningCode::resolve(name) {
+                    Ok(resolved) => codes.extend(resolved),
+                    Err(message) => self.diagnostics.push(Diagnostic::warning(*loc, message)),
+                }
+            }
+
+            let file_no = loc.file_no();
+            let (line, _) = self.files[file_no].offset_to_line_column(loc.start());
+
+            self.warning_suppressions.push(Suppression {
+                file_no,
+                line: line + 1,
+                codes,
+            });
+        }
+    }
+
+    /// Remove the warnings which are suppressed by comments
+    pub(super) fn suppress_warnings(&mut self) {
+        if self.warning_suppressions.is_empty() {
+            return;
+        }
+
+        let suppressions = &self.warning_suppressions;
+        let files = &self.files;
+
+        self.diagnostics.retain_mut(|diagnostic| {
+            if diagnostic.level != Level::Warning {
+                return true;
+            }
+
+            let pt::Loc::File(file_no, start, _) = diagnostic.loc else {
+                return true;
+            };
+
+            let (line, _) = files[file_no].offset_to_line_column(start);
+            let code = WarningCode::of(diagnostic);
+
+            !suppressions.iter().any(|suppression| {
+                suppression.file_no == file_no
+                    && suppression.line == line
+                    && (suppression.codes.is_empty() || suppression.codes.contains(&code))
+            })
+        });
+    }
+
+    /// Change the severity of the warnings as configured. Warnings which are allowed are removed,
+    /// and warnings which are denied become errors. The code is added to the message of the
+    /// warnings which remain, so that they can be suppressed.
+    pub fn apply_warning_config(&mut self, config: &WarningConfig) {
+        self.diagnostics.retain_mut(|diagnostic| {
+            if diagnostic.level != Level::Warning {
+                return true;
+            }
+
+            let code = WarningCode::of(diagnostic);
+
+            match config.level(code) {
+                Some(level) => {
+                    diagnostic.level = level;
+                    diagnostic.message = format!("{} [{code}]", diagnostic.message);
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+}
@@ -18,7 +18,8 @@ impl YulBuiltinPrototype {
     pub fn is_available(&self, target: &Target) -> bool {
         match target {
             Target::EVM => self.availability[0],
-            Target::Polkadot { .. } => self.availability[1],
+            // Stylus shares the WebAssembly code generation with Polkadot
+            Target::Polkadot { .. } | Target::Stylus => self.availability[1],
             Target::Solana => self.availability[2],
             Target::Soroban => unimplemented!(),
         }
@@ -645,7 +646,7 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             doc: "calldataload(p) returns call data starting from position p (32 bytes)",
             ty: YulBuiltInFunction::CallDataLoad,
             stops_execution: false,
-            availability: [true, false, false],
+            availability: [true, true, true],
         },
         YulBuiltinPrototype {
             name: "calldatasize",
@@ -654,7 +655,7 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             doc: "Returns the size of call data in bytes",
             ty: YulBuiltInFunction::CallDataSize,
             stops_execution: false,
-            availability: [true, false, false],
+            availability: [true, true, true],
         },
         YulBuiltinPrototype {
             name: "calldatacopy",
@@ -708,7 +709,7 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             doc: "Returns the size of the last returndata",
             ty: YulBuiltInFunction::ReturnDataSize,
             stops_execution: false,
-            availability: [true, false, false],
+            availability: [true, true, false],
         },
         YulBuiltinPrototype {
             name: "returndatacopy",
@@ -789,7 +790,7 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             doc: "return(p, s) ends execution and returns data mem[p...(p+s)]",
             ty: YulBuiltInFunction::Return,
             stops_execution: true,
-            availability: [true, false, false],
+            availability: [true, true, false],
         },
         YulBuiltinPrototype {
             name: "revert",
@@ -798,7 +799,7 @@ static YUL_BUILTIN: [YulBuiltinPrototype; 77] =
             doc: "revert(p, s) ends execution, reverts state changes and returns data mem[p...(p+s)]",
             ty: YulBuiltInFunction::Revert,
             stops_execution: true,
-            availability: [true, false, false],
+            availability: [true, true, false],
         },
         YulBuiltinPrototype {
             name: "selfdestruct",
@@ -5,12 +5,15 @@ use crate::sema::diagnostics::Diagnostics;
 use crate::sema::expression::{strings::unescape, ExprContext};
 use crate::sema::symtable::{Symtable, VariableUsage};
 use crate::sema::yul::ast::{YulExpression, YulSuffix};
-use crate::sema::yul::builtin::{parse_builtin_keyword, yul_unsupported_builtin};
+use crate::sema::yul::builtin::{
+    parse_builtin_keyword, yul_unsupported_builtin, YulBuiltInFunction,
+};
 use crate::sema::yul::functions::FunctionsTable;
 use crate::sema::yul::types::{
     get_default_type_from_identifier, get_type_from_string, verify_type_from_expression,
 };
 use crate::sema::yul::unused_variable::{assigned_variable, used_variable};
+use crate::Target;
 use num_bigint::{BigInt, Sign};
 use num_rational::BigRational;
 use num_traits::{Num, Pow};
@@ -374,6 +377,24 @@ pub(crate) fn resolve_function_call(
         ));
         return Err(());
     }
+
+    if ns.target != Target::EVM {
+        if let Some(built_in @ (YulBuiltInFunction::Return | YulBuiltInFunction::Revert)) =
+            parse_builtin_keyword(func_call.id.name.as_str())
+        {
+            if built_in.get_prototype_info().is_available(&ns.target) {
+                return resolve_forward_bytes(
+                    *built_in,
+                    function_table,
+                    func_call,
+                    context,
+                    symtable,
+                    ns,
+                );
+            }
+        }
+    }
+
     let mut resolved_arguments: Vec<YulExpression> = Vec::with_capacity(func_call.arguments.len());
     for item in &func_call.arguments {
         let resolved_expr = resolve_yul_expression(item, context, symtable, function_table, ns)?;
@@ -476,6 +497,114 @@ pub(crate) fn resolve_function_call(
     Err(())
 }
 
+/// Memory cannot be addressed from assembly outside of EVM, so `return()` and `revert()` can only
+/// forward a `bytes memory` variable, e.g. the return data of a delegatecall in a proxy. This is
+/// written the same as on EVM: `revert(add(data, 32), mload(data))`. `return(0, 0)` and
+/// `revert(0, 0)` have no data, and resolve to a call without arguments.
+fn resolve_forward_bytes(
+    built_in: YulBuiltInFunction,
+    function_table: &mut FunctionsTable,
+    func_call: &YulFunctionCall,
+    context: &mut ExprContext,
+    symtable: &mut Symtable,
+    ns: &mut Namespace,
+) -> Result<YulExpression, ()> {
+    if func_call.arguments.len() == 2 && func_call.arguments.iter().all(|arg| literal_is(arg, 0)) {
+        return Ok(YulExpression::BuiltInCall(
+            func_call.loc,
+            built_in,
+            Vec::new(),
+        ));
+    }
+
+    let Some(id) = forwarded_variable(&func_call.arguments) else {
+        ns.diagnostics.push(Diagnostic::error(
+            func_call.loc,
+            format!(
+                "on target {}, '{}' can only forward a 'bytes memory' variable, \
+                like '{}(add(data, 32), mload(data))'",
+                ns.target, func_call.id.name, func_call.id.name
+            ),
+        ));
+        return Err(());
+    };
+
+    let data = resolve_yul_expression(
+        &pt::YulExpression::Variable(id.clone()),
+        context,
+        symtable,
+        function_table,
+        ns,
+    )?;
+
+    if let Some(diagnostic) = check_type(&data, context, ns, symtable) {
+        ns.diagnostics.push(diagnostic);
+        return Err(());
+    }
+
+    match &data {
+        YulExpression::SolidityLocalVariable(
+            _,
+            Type::DynamicBytes,
+            None | Some(StorageLocation::Memory(_)),
+            _,
+        ) => Ok(YulExpression::BuiltInCall(
+            func_call.loc,
+            built_in,
+            vec![data],
+        )),
+        _ => {
+            ns.diagnostics.push(Diagnostic::error(
+                id.loc,
+                format!(
+                    "on target {}, '{}' can only forward a 'bytes memory' variable",
+                    ns.target, func_call.id.name
+                ),
+            ));
+            Err(())
+        }
+    }
+}
+
+/// Match the arguments `add(data, 32), mload(data)` and return `data`
+fn forwarded_variable(arguments: &[pt::YulExpression]) -> Option<&Identifier> {
+    let [pt::YulExpression::FunctionCall(add), pt::YulExpression::FunctionCall(mload)] = arguments
+    else {
+        return None;
+    };
+
+    if add.id.name != "add" || mload.id.name != "mload" {
+        return None;
+    }
+
+    let [pt::YulExpression::Variable(length)] = mload.arguments.as_slice() else {
+        return None;
+    };
+
+    match add.arguments.as_slice() {
+        [pt::YulExpression::Variable(data), offset]
+        | [offset, pt::YulExpression::Variable(data)]
+            if literal_is(offset, 32) && data.name == length.name =>
+        {
+            Some(data)
+        }
+        _ => None,
+    }
+}
+
+/// Is the expression a decimal or hex number literal with the given value
+fn literal_is(expr: &pt::YulExpression, expected: u64) -> bool {
+    match expr {
+        pt::YulExpression::NumberLiteral(_, value, exp, None) if exp.is_empty() => {
+            value.replace('_', "").parse() == Ok(expected)
+        }
+        pt::YulExpression::HexNumberLiteral(_, value, None) => {
+            u64::from_str_radix(&value[2..].replace('_', ""), 16) == Ok(expected)
+        }
+        _ => false,
+    }
+}
+
 /// Check if the provided argument is compatible with the declared parameters of a function.
 fn check_function_argument(
     parameter: &Parameter<Type>,
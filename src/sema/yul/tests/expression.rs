@@ -363,6 +363,7 @@ fn resolve_variable_contract() {
         tags: vec![],
         name: "var1".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Bool,
         visibility: Visibility::Public(None),
         constant: true,
@@ -370,11 +371,13 @@ fn resolve_variable_contract() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
     contract.variables.push(Variable {
         tags: vec![],
         name: "var2".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Int(128),
         visibility: Visibility::Public(None),
         constant: false,
@@ -382,12 +385,14 @@ fn resolve_variable_contract() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
 
     contract.variables.push(Variable {
         tags: vec![],
         name: "imut".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Int(128),
         visibility: Visibility::Public(None),
         constant: false,
@@ -395,6 +400,7 @@ fn resolve_variable_contract() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
 
     ns.contracts.push(contract);
@@ -403,6 +409,7 @@ fn resolve_variable_contract() {
         tags: vec![],
         name: "var3".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Uint(32),
         visibility: Visibility::Public(None),
         constant: true,
@@ -410,6 +417,7 @@ fn resolve_variable_contract() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
 
     ns.variable_symbols.insert(
@@ -914,6 +922,7 @@ fn test_member_access() {
         tags: vec![],
         name: "var1".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Bool,
         visibility: Visibility::Public(None),
         constant: false,
@@ -921,6 +930,7 @@ fn test_member_access() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
 
     ns.contracts.push(contract);
@@ -1036,6 +1046,7 @@ fn test_check_types() {
         tags: vec![],
         name: "var1".to_string(),
         loc,
+        name_loc: loc,
         ty: Type::Bool,
         visibility: Visibility::Public(None),
         constant: true,
@@ -1043,6 +1054,7 @@ fn test_check_types() {
         initializer: None,
         assigned: false,
         read: false,
+        enumerable: None,
     });
     ns.contracts.push(contract);
     let mut symtable = Symtable::default();
@@ -1715,3 +1727,114 @@ contract foo {
 
     assert!(ns.diagnostics.contains_message("builtin 'log0' is not available for target Solana. Please, open a GitHub issue at https://github.com/hyperledger/solang/issues if there is need to support this function"));
 }
+
+#[test]
+fn forward_bytes() {
+    let file = r#"
+contract Proxy {
+    address implementation;
+
+    fallback() external payable {
+        (bool ok, bytes memory ret) = implementation.delegatecall(msg.data);
+        if (!ok) {
+            assembly {
+                revert(add(ret, 32), mload(ret))
+            }
+        }
+        assembly {
+            return(add(0x20, ret), mload(ret))
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let file = r#"
+contract Bad {
+    function f(bytes calldata c) public {
+        bytes memory m = c;
+        assembly {
+            revert(0, mload(m))
+        }
+    }
+
+    function g(bytes calldata c) public {
+        assembly {
+            return(add(c, 32), mload(c))
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+
+    assert!(ns.diagnostics.contains_message(
+        "on target Polkadot, 'revert' can only forward a 'bytes memory' variable, like 'revert(add(data, 32), mload(data))'"
+    ));
+    assert!(ns.diagnostics.contains_message(
+        "on target Polkadot, 'return' can only forward a 'bytes memory' variable"
+    ));
+
+    let file = r#"
+contract Empty {
+    function f(bool b) public {
+        assembly {
+            if b {
+                revert(0, 0)
+            }
+            return(0x0, 0)
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+
+    assert!(!ns.diagnostics.any_errors());
+
+    let file = r#"
+contract Proxy {
+    address implementation;
+
+    fallback() external {
+        (bool ok, bytes memory ret) = implementation.delegatecall(msg.data);
+        assembly {
+            return(add(ret, 32), mload(ret))
+        }
+    }
+}
+    "#;
+
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("test.sol", file.to_string());
+
+    let ns = parse_and_resolve(OsStr::new("test.sol"), &mut cache, Target::Solana);
+
+    assert!(ns
+        .diagnostics
+        .contains_message("'delegatecall' is not available on Solana"));
+}
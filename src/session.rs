@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::codegen::{self, Options};
+use crate::file_resolver::{FileResolver, SourceCache};
+use crate::sema::ast::Namespace;
+use crate::Target;
+use std::ffi::OsStr;
+use std::sync::Arc;
+
+/// A compilation session holds what can be reused between compilations: the target, the codegen
+/// options and the source files which have been read. A session can be cloned and shared
+/// between threads, so a long running process like the language server does not have to read
+/// every imported file again each time a file is compiled.
+#[derive(Clone)]
+pub struct Session {
+    target: Target,
+    options: Options,
+    sources: Arc<SourceCache>,
+}
+
+impl Session {
+    pub fn new(target: Target, options: Options) -> Self {
+        Session {
+            target,
+            options,
+            sources: Arc::default(),
+        }
+    }
+
+    pub fn target(&self) -> Target {
+        self.target
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// The same session for another target. The source files are still shared.
+    pub fn with_target(&self, target: Target) -> Self {
+        Session {
+            target,
+            ..self.clone()
+        }
+    }
+
+    /// Create a file resolver for a compilation, which reads files through the session. Import
+    /// paths and maps are added to the resolver, since these may differ for each compilation.
+    pub fn file_resolver(&self) -> FileResolver {
+        FileResolver::with_sources(self.sources.clone())
+    }
+
+    /// Parse and resolve a file for the target of the session
+    pub fn parse_and_resolve(&self, filename: &OsStr, resolver: &mut FileResolver) -> Namespace {
+        crate::parse_and_resolve(filename, resolver, self.target)
+    }
+
+    /// Like [`Session::parse_and_resolve`], into a namespace which was created for the target of
+    /// the session and configured beforehand, e.g. with strict conversions
+    pub fn parse_and_resolve_into(
+        &self,
+        filename: &OsStr,
+        resolver: &mut FileResolver,
+        ns: Namespace,
+    ) -> Namespace {
+        assert_eq!(ns.target, self.target);
+
+        crate::parse_and_resolve_into(filename, resolver, ns)
+    }
+
+    /// Generate the cfg of all the contracts, with the options of the session
+    pub fn codegen(&self, ns: &mut Namespace) {
+        codegen::codegen(ns, &self.options);
+    }
+
+    /// Forget the source files which have been read, for example when files may have been
+    /// changed without their modification time changing
+    pub fn clear_sources(&self) {
+        self.sources.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread;
+
+    #[test]
+    fn shared_between_threads() {
+        let dir = tempfile::tempdir().unwrap();
+
+        fs::write(
+            dir.path().join("a.sol"),
+            "import \"b.sol\"; contract A is B {}",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.sol"),
+            "contract B { function f() public {} }",
+        )
+        .unwrap();
+
+        let session = Session::new(Target::Solana, Options::default());
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let session = session.clone();
+                let dir = dir.path().to_path_buf();
+
+                thread::spawn(move || {
+                    let mut resolver = session.file_resolver();
+                    resolver.add_import_path(&dir);
+
+                    let mut ns = session.parse_and_resolve(OsStr::new("a.sol"), &mut resolver);
+                    session.codegen(&mut ns);
+
+                    assert!(!ns.diagnostics.any_errors());
+                    assert_eq!(ns.contracts.len(), 2);
+                })
+            })
+            .collect();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        // a file which was just modified is read again, even if its modification time and its
+        // length are the same
+        fs::write(
+            dir.path().join("b.sol"),
+            "contract B { function f() public {} {",
+        )
+        .unwrap();
+
+        let mut resolver = session.file_resolver();
+        resolver.add_import_path(dir.path());
+
+        let ns = session.parse_and_resolve(OsStr::new("a.sol"), &mut resolver);
+
+        assert!(ns.diagnostics.any_errors());
+    }
+}
@@ -0,0 +1,222 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! The size of the code of a compiled contract, by the Solidity function it was generated from
+//! and by the code which the compiler generates itself: the dispatch, the argument decoders and
+//! the runtime library. Since the binary is measured after optimization, code which was folded
+//! away by constant propagation or dead code elimination is not counted, and code which was
+//! inlined is counted for the function it was written in. This helps to find what to shrink when
+//! a program is near the size limit of its chain.
+//!
+//! Each instruction is attributed with the source map, so the contract must have been built with
+//! line tables. Instructions without a source location are attributed to the function they are
+//! in. Instructions outside any function with debug information, like the runtime library and
+//! the entry points, belong to the runtime.
+//!
+//! The code which only runs when a function reverts is moved out of the function by the cold
+//! splitting pass, into a function named after it with a `.cold.` suffix. This code is counted
+//! for the function it came from, and also as cold, so that the size of the hot path can be
+//! measured too.
+
+use crate::codegen::cfg::ASTFunction;
+use crate::codegen::dispatch::{is_decoder_cfg, is_dispatch_cfg};
+use crate::sema::ast::Namespace;
+use crate::sema::file::PathDisplay;
+use crate::source_map::{code_functions, gen_source_map, SourceRange};
+use serde::Serialize;
+use solang_parser::pt;
+use std::collections::HashMap;
+
+#[derive(Serialize)]
+pub struct SizeReport {
+    pub contract: String,
+    pub target: String,
+    /// The size of the code in bytes, without the data and debug information
+    pub code_size: u64,
+    /// The size of the code which only runs when reverting, included in the code size
+    pub cold_size: u64,
+    /// By size, largest first
+    pub entries: Vec<SizeEntry>,
+}
+
+#[derive(Serialize)]
+pub struct SizeEntry {
+    pub name: String,
+    /// `function`, `dispatch`, `decoder`, `helper` or `runtime`
+    pub kind: &'static str,
+    /// Where the function is declared, if it is in the source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc: Option<String>,
+    /// The size of its code in bytes
+    pub size: u64,
+    /// The size of its code which was moved out of the hot path, included in the size
+    pub cold_size: u64,
+}
+
+/// What an instruction is attributed to
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Owner {
+    Function(usize),
+    YulFunction(usize),
+    /// A function generated by the compiler, by its name
+    Generated(String),
+    Runtime,
+}
+
+/// Attribute the code size of a contract to the functions it was generated from. The binary
+/// must have been built with line tables.
+pub fn gen_size_report(
+    contract_no: usize,
+    ns: &Namespace,
+    code: &[u8],
+) -> Result<SizeReport, String> {
+    let source_map = gen_source_map(contract_no, ns, code)?;
+    let functions = code_functions(code)?;
+    let contract = &ns.contracts[contract_no];
+
+    let source_ranges: Vec<(pt::Loc, Owner)> = ns
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(function_no, func)| (func.loc, Owner::Function(function_no)))
+        .chain(
+            ns.yul_functions
+                .iter()
+                .enumerate()
+                .map(|(function_no, func)| (func.loc, Owner::YulFunction(function_no))),
+        )
+        .filter(|(loc, _)| matches!(loc, pt::Loc::File(..)))
+        .collect();
+
+    // the innermost function which has the source, since yul functions are inside functions
+    let source_owner = |source: &SourceRange| {
+        source_ranges
+            .iter()
+            .filter(|(loc, _)| {
+                matches!(loc, pt::Loc::File(file_no, start, end)
+                    if *file_no == source.file && *start <= source.start && source.start < *end)
+            })
+            .min_by_key(|(loc, _)| loc.end() - loc.start())
+            .map(|(_, owner)| owner)
+    };
+
+    // code without a source belongs to the function it is in, which is a Solidity function if
+    // it has a cfg for one
+    let code_owner = |offset: u64| {
+        let function = functions
+            .iter()
+            .find(|function| function.ranges.iter().any(|range| range.contains(&offset)))?;
+
+        let name = cold_function_parent(&function.name).unwrap_or(&function.name);
+
+        let owner = match contract.cfg.iter().find(|cfg| cfg.name == name) {
+            Some(cfg) => match cfg.function_no {
+                ASTFunction::SolidityFunction(function_no) => Owner::Function(function_no),
+                ASTFunction::YulFunction(function_no) => Owner::YulFunction(function_no),
+                ASTFunction::None => Owner::Generated(cfg.name.clone()),
+            },
+            None => Owner::Generated(name.to_owned()),
+        };
+
+        Some(owner)
+    };
+
+    let is_cold = |offset: u64| {
+        functions.iter().any(|function| {
+            cold_function_parent(&function.name).is_some()
+                && function.ranges.iter().any(|range| range.contains(&offset))
+        })
+    };
+
+    // the size and the cold size of each owner
+    let mut sizes: HashMap<Owner, (u64, u64)> = HashMap::new();
+
+    for instr in &source_map.instructions {
+        let owner = instr
+            .source
+            .as_ref()
+            .and_then(source_owner)
+            .cloned()
+            .or_else(|| code_owner(instr.offset))
+            .unwrap_or(Owner::Runtime);
+
+        let (size, cold_size) = sizes.entry(owner).or_default();
+
+        *size += instr.size;
+
+        if is_cold(instr.offset) {
+            *cold_size += instr.size;
+        }
+    }
+
+    let code_size = sizes.values().map(|(size, _)| size).sum();
+    let cold_size = sizes.values().map(|(_, cold_size)| cold_size).sum();
+
+    let mut entries: Vec<SizeEntry> = sizes
+        .into_iter()
+        .map(|(owner, (size, cold_size))| {
+            let (name, kind, loc) = match owner {
+                Owner::Function(function_no) => {
+                    let func = &ns.functions[function_no];
+
+                    let name = match func.ty {
+                        pt::FunctionTy::Function => func.signature.clone(),
+                        pt::FunctionTy::Constructor => format!("constructor{}", func.signature),
+                        pt::FunctionTy::Modifier => format!("modifier {}", func.signature),
+                        ty => format!("{ty}()"),
+                    };
+
+                    // functions from base contracts and libraries have the contract name
+                    let name = match func.contract_no {
+                        Some(no) if no != contract_no => format!("{}.{name}", ns.contracts[no].id),
+                        _ => name,
+                    };
+
+                    (name, "function", Some(func.loc_prototype))
+                }
+                Owner::YulFunction(function_no) => {
+                    let func = &ns.yul_functions[function_no];
+
+                    (format!("{}()", func.name), "function", Some(func.loc))
+                }
+                Owner::Generated(name) => {
+                    let kind = if is_dispatch_cfg(&name) {
+                        "dispatch"
+                    } else if is_decoder_cfg(&name) {
+                        "decoder"
+                    } else {
+                        "helper"
+                    };
+
+                    (name, kind, None)
+                }
+                Owner::Runtime => ("runtime".into(), "runtime", None),
+            };
+
+            SizeEntry {
+                name,
+                kind,
+                loc: loc.map(|loc| ns.loc_to_string(PathDisplay::Filename, &loc)),
+                size,
+                cold_size,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(SizeReport {
+        contract: contract.id.name.clone(),
+        target: ns.target.to_string(),
+        code_size,
+        cold_size,
+        entries,
+    })
+}
+
+/// The name of the function which the code of a function outlined by llvm's hot/cold splitting
+/// pass came from, like `foo` for `foo.cold.1`
+fn cold_function_parent(name: &str) -> Option<&str> {
+    let (parent, no) = name.rsplit_once(".cold.")?;
+
+    no.parse::<u32>().is_ok().then_some(parent)
+}
@@ -0,0 +1,569 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Source maps, which map each instruction of a compiled contract back to the Solidity source it
+//! was generated from, so that debuggers and coverage tools can attribute execution. The map is
+//! read from the DWARF line table in the binary, so the contract must have been built with line
+//! tables.
+//!
+//! The compact form is the `s:l:f:j` format of solc, with an entry for each instruction: the
+//! byte offset and length of the source range, the file number, and whether the instruction
+//! jumps into (`i`) or out of (`o`) a function. A field which is the same as in the entry
+//! before it is left out, and instructions without a source have `-1` as the file.
+
+use crate::inspect::{Elf, ElfSection};
+use crate::sema::ast::Namespace;
+use gimli::{ColumnType, Dwarf, EndianSlice, LittleEndian, SectionId};
+use serde::Serialize;
+use solang_parser::pt::{self, CodeLocation};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use wasmparser::{Operator, Parser, Payload};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceMap {
+    pub contract: String,
+    pub target: String,
+    /// The source files, by file number
+    pub sources: Vec<String>,
+    /// The map in the compact `s:l:f:j` format
+    pub source_map: String,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Instruction {
+    /// The offset of the instruction in the code: the `.text` section on Solana, or the contents
+    /// of the code section on wasm, like the addresses in DWARF for wasm
+    pub offset: u64,
+    /// The size of the instruction in bytes
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceRange>,
+    pub jump: Jump,
+}
+
+/// Lines and columns start at 1
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SourceRange {
+    pub file: usize,
+    pub start: usize,
+    pub length: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub enum Jump {
+    #[serde(rename = "i")]
+    Into,
+    #[serde(rename = "o")]
+    Out,
+    #[serde(rename = "-")]
+    Regular,
+}
+
+impl fmt::Display for Jump {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Jump::Into => "i",
+            Jump::Out => "o",
+            Jump::Regular => "-",
+        })
+    }
+}
+
+/// The solana instructions which jump into and out of functions
+const SBF_CALL: u8 = 0x85;
+const SBF_CALLX: u8 = 0x8d;
+const SBF_EXIT: u8 = 0x95;
+/// Loading a 64 bit immediate takes two instructions
+const SBF_LDDW: u8 = 0x18;
+
+/// Generate the source map for the binary of a contract, which must have been built with line
+/// tables.
+pub fn gen_source_map(
+    contract_no: usize,
+    ns: &Namespace,
+    code: &[u8],
+) -> Result<SourceMap, String> {
+    let (instructions, base) = if code.starts_with(b"\0asm") {
+        wasm_instructions(code)?
+    } else {
+        elf_instructions(code)?
+    };
+
+    let rows = line_rows(code)?;
+
+    if rows.is_empty() {
+        return Err("the binary has no line tables".into());
+    }
+
+    let files: HashMap<PathBuf, usize> = ns
+        .files
+        .iter()
+        .enumerate()
+        .map(|(file_no, file)| (normalize(&file.path), file_no))
+        .collect();
+
+    let ends = source_ends(contract_no, ns);
+
+    let instructions: Vec<Instruction> = instructions
+        .into_iter()
+        .map(|(offset, size, jump)| {
+            // the last row at or before the instruction
+            let row = rows.partition_point(|row| row.address <= base + offset);
+
+            let source = row
+                .checked_sub(1)
+                .and_then(|row| rows[row].location.as_ref())
+                .and_then(|(path, line, column)| {
+                    let file_no = *files.get(path)?;
+                    let start = ns.files[file_no].get_offset(line - 1, column - 1)?;
+                    let end = ends.get(&(file_no, start)).copied().unwrap_or(start);
+
+                    Some(SourceRange {
+                        file: file_no,
+                        start,
+                        length: end - start,
+                        line: *line,
+                        column: *column,
+                    })
+                });
+
+            Instruction {
+                offset,
+                size,
+                source,
+                jump,
+            }
+        })
+        .collect();
+
+    Ok(SourceMap {
+        contract: ns.contracts[contract_no].id.name.clone(),
+        target: ns.target.to_string(),
+        sources: ns.files.iter().map(|file| file.to_string()).collect(),
+        source_map: compact(&instructions),
+        instructions,
+    })
+}
+
+/// The source map in the `s:l:f:j` format
+fn compact(instructions: &[Instruction]) -> String {
+    let mut entries = Vec::new();
+    let mut last: [String; 4] = Default::default();
+
+    for instr in instructions {
+        let fields = match &instr.source {
+            Some(source) => [
+                source.start.to_string(),
+                source.length.to_string(),
+                source.file.to_string(),
+                instr.jump.to_string(),
+            ],
+            None => [
+                "-1".into(),
+                "-1".into(),
+                "-1".into(),
+                instr.jump.to_string(),
+            ],
+        };
+
+        let mut entry: Vec<&str> = fields
+            .iter()
+            .zip(&last)
+            .map(|(field, last)| if field == last { "" } else { field.as_str() })
+            .collect();
+
+        while entry.last() == Some(&"") {
+            entry.pop();
+        }
+
+        entries.push(entry.join(":"));
+
+        last = fields;
+    }
+
+    entries.join(";")
+}
+
+/// The offset, size and jump type of an instruction
+type InstructionOffset = (u64, u64, Jump);
+
+/// The `.text` section of a Solana program
+fn text_section(elf: &Elf) -> Result<ElfSection, String> {
+    elf.sections()?
+        .into_iter()
+        .find(|section| section.name == ".text")
+        .ok_or_else(|| "the program has no .text section".into())
+}
+
+/// The offset, size and jump type of each instruction in the `.text` section of a Solana program,
+/// and the address of the section
+fn elf_instructions(code: &[u8]) -> Result<(Vec<InstructionOffset>, u64), String> {
+    let elf = Elf::new(code)?;
+    let text = text_section(&elf)?;
+
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+
+    while offset + 8 <= text.size {
+        let opcode = elf.u8(text.offset + offset)?;
+
+        let jump = match opcode {
+            SBF_CALL | SBF_CALLX => Jump::Into,
+            SBF_EXIT => Jump::Out,
+            _ => Jump::Regular,
+        };
+
+        let size = if opcode == SBF_LDDW { 16 } else { 8 };
+
+        instructions.push((offset as u64, size as u64, jump));
+
+        offset += size;
+    }
+
+    Ok((instructions, text.addr))
+}
+
+/// The offset, size and jump type of each instruction in the code section of a wasm module. The
+/// end of a function body returns from the function. The offsets are from the start of the code
+/// section, which is where the addresses in the line table start on wasm.
+fn wasm_instructions(code: &[u8]) -> Result<(Vec<InstructionOffset>, u64), String> {
+    let mut instructions = Vec::new();
+    let mut code_start = 0;
+
+    for payload in Parser::new(0).parse_all(code) {
+        match payload.map_err(|err| format!("invalid wasm: {err}"))? {
+            Payload::CodeSectionStart { range, .. } => code_start = range.start,
+            Payload::CodeSectionEntry(body) => {
+                let mut reader = body
+                    .get_operators_reader()
+                    .map_err(|err| format!("invalid wasm: {err}"))?;
+
+                while !reader.eof() {
+                    let (op, offset) = reader
+                        .read_with_offset()
+                        .map_err(|err| format!("invalid wasm: {err}"))?;
+
+                    let jump = match op {
+                        Operator::Call { .. } | Operator::CallIndirect { .. } => Jump::Into,
+                        Operator::Return => Jump::Out,
+                        Operator::End if reader.eof() => Jump::Out,
+                        _ => Jump::Regular,
+                    };
+
+                    let size = reader.original_position() - offset;
+
+                    instructions.push(((offset - code_start) as u64, size as u64, jump));
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok((instructions, 0))
+}
+
+/// A row of the line table. Rows without a location end a sequence of instructions.
+struct Row {
+    address: u64,
+    location: Option<(PathBuf, usize, usize)>,
+}
+
+type Reader<'a> = EndianSlice<'a, LittleEndian>;
+
+fn load_dwarf<'a>(sections: &HashMap<&'static str, &'a [u8]>) -> Result<Dwarf<Reader<'a>>, String> {
+    Dwarf::load(|id: SectionId| -> Result<_, String> {
+        Ok(EndianSlice::new(
+            sections.get(id.name()).copied().unwrap_or_default(),
+            LittleEndian,
+        ))
+    })
+}
+
+fn invalid(err: gimli::Error) -> String {
+    format!("invalid debug information: {err}")
+}
+
+/// The rows of the DWARF line tables in the binary, by address
+fn line_rows(code: &[u8]) -> Result<Vec<Row>, String> {
+    let sections = debug_sections(code)?;
+    let dwarf = load_dwarf(&sections)?;
+
+    let mut rows = Vec::new();
+    let mut units = dwarf.units();
+
+    while let Some(header) = units.next().map_err(invalid)? {
+        let unit = dwarf.unit(header).map_err(invalid)?;
+
+        let Some(program) = unit.line_program.clone() else {
+            continue;
+        };
+
+        let mut program_rows = program.rows();
+
+        while let Some((header, row)) = program_rows.next_row().map_err(invalid)? {
+            if row.end_sequence() {
+                rows.push(Row {
+                    address: row.address(),
+                    location: None,
+                });
+                continue;
+            }
+
+            let path = match row.file(header) {
+                Some(file) => {
+                    let name = dwarf
+                        .attr_string(&unit, file.path_name())
+                        .map_err(invalid)?;
+
+                    let dir = match file.directory(header) {
+                        Some(dir) => dwarf.attr_string(&unit, dir).map_err(invalid)?,
+                        None => EndianSlice::new(&[], LittleEndian),
+                    };
+
+                    Some(normalize(
+                        &Path::new(dir.to_string_lossy().as_ref())
+                            .join(name.to_string_lossy().as_ref()),
+                    ))
+                }
+                None => None,
+            };
+
+            let column = match row.column() {
+                ColumnType::LeftEdge => 1,
+                ColumnType::Column(column) => column.get() as usize,
+            };
+
+            rows.push(Row {
+                address: row.address(),
+                location: path
+                    .zip(row.line())
+                    .map(|(path, line)| (path, line.get() as usize, column)),
+            });
+        }
+    }
+
+    // a sequence may start where another ends
+    rows.sort_by_key(|row| (row.address, row.location.is_some()));
+
+    Ok(rows)
+}
+
+/// A function with debug information in a binary
+pub(crate) struct CodeFunction {
+    /// The name of the function in llvm, which is the name of its cfg
+    pub name: String,
+    /// The address ranges of the function, as offsets in the code like the offsets of the
+    /// instructions
+    pub ranges: Vec<Range<u64>>,
+}
+
+/// The functions with debug information in a binary, which must have been built with line tables
+pub(crate) fn code_functions(code: &[u8]) -> Result<Vec<CodeFunction>, String> {
+    let base = if code.starts_with(b"\0asm") {
+        0
+    } else {
+        text_section(&Elf::new(code)?)?.addr
+    };
+
+    let sections = debug_sections(code)?;
+    let dwarf = load_dwarf(&sections)?;
+
+    let mut functions = Vec::new();
+    let mut units = dwarf.units();
+
+    while let Some(header) = units.next().map_err(invalid)? {
+        let unit = dwarf.unit(header).map_err(invalid)?;
+        let mut entries = unit.entries();
+
+        while let Some((_, entry)) = entries.next_dfs().map_err(invalid)? {
+            if entry.tag() != gimli::DW_TAG_subprogram {
+                continue;
+            }
+
+            let name = match entry
+                .attr_value(gimli::DW_AT_linkage_name)
+                .map_err(invalid)?
+            {
+                Some(name) => Some(name),
+                None => entry.attr_value(gimli::DW_AT_name).map_err(invalid)?,
+            };
+
+            let Some(name) = name else {
+                continue;
+            };
+
+            let name = dwarf.attr_string(&unit, name).map_err(invalid)?;
+
+            let mut ranges = Vec::new();
+            let mut die_ranges = dwarf.die_ranges(&unit, entry).map_err(invalid)?;
+
+            while let Some(range) = die_ranges.next().map_err(invalid)? {
+                if range.begin < range.end {
+                    ranges.push(range.begin.saturating_sub(base)..range.end.saturating_sub(base));
+                }
+            }
+
+            // functions which were inlined everywhere have no code of their own
+            if !ranges.is_empty() {
+                functions.push(CodeFunction {
+                    name: name.to_string_lossy().into_owned(),
+                    ranges,
+                });
+            }
+        }
+    }
+
+    Ok(functions)
+}
+
+/// The contents of the DWARF sections, by name. These are ELF sections on Solana, and custom
+/// sections on wasm.
+fn debug_sections(code: &[u8]) -> Result<HashMap<&'static str, &[u8]>, String> {
+    let names = [
+        SectionId::DebugAbbrev,
+        SectionId::DebugAddr,
+        SectionId::DebugInfo,
+        SectionId::DebugLine,
+        SectionId::DebugLineStr,
+        SectionId::DebugRanges,
+        SectionId::DebugRngLists,
+        SectionId::DebugStr,
+        SectionId::DebugStrOffsets,
+    ]
+    .map(SectionId::name);
+
+    let mut sections = HashMap::new();
+
+    if code.starts_with(b"\0asm") {
+        for payload in Parser::new(0).parse_all(code) {
+            if let Payload::CustomSection(reader) =
+                payload.map_err(|err| format!("invalid wasm: {err}"))?
+            {
+                if let Some(name) = names.iter().find(|name| **name == reader.name()) {
+                    sections.insert(*name, reader.data());
+                }
+            }
+        }
+    } else {
+        let elf = Elf::new(code)?;
+
+        for section in elf.sections()? {
+            if let Some(name) = names.iter().find(|name| **name == section.name) {
+                let contents = code
+                    .get(section.offset..section.offset.saturating_add(section.size))
+                    .ok_or_else(|| format!("ELF section {} is truncated", section.name))?;
+
+                sections.insert(*name, contents);
+            }
+        }
+    }
+
+    Ok(sections)
+}
+
+/// The end of the source range which starts at an offset, for the instructions of a contract.
+/// When several ranges start at the same offset, the longest is used.
+fn source_ends(contract_no: usize, ns: &Namespace) -> HashMap<(usize, usize), usize> {
+    let contract = &ns.contracts[contract_no];
+
+    let locs = contract
+        .cfg
+        .iter()
+        .flat_map(|cfg| &cfg.blocks)
+        .flat_map(|block| &block.instr)
+        .map(|instr| instr.loc())
+        .chain(ns.functions.iter().map(|func| func.loc_prototype));
+
+    let mut ends = HashMap::new();
+
+    for loc in locs {
+        if let pt::Loc::File(file_no, start, end) = loc {
+            let longest = ends.entry((file_no, start)).or_insert(end);
+
+            *longest = end.max(*longest);
+        }
+    }
+
+    ends
+}
+
+/// Paths without `.` components, since the compile unit is in `.`
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_encoder::{CodeSection, Function, FunctionSection, Module, TypeSection};
+
+    fn instr(offset: u64, source: Option<(usize, usize, usize)>, jump: Jump) -> Instruction {
+        Instruction {
+            offset,
+            size: 8,
+            source: source.map(|(file, start, length)| SourceRange {
+                file,
+                start,
+                length,
+                line: 1,
+                column: 1,
+            }),
+            jump,
+        }
+    }
+
+    #[test]
+    fn compact_format() {
+        let instructions = [
+            instr(0, Some((0, 10, 20)), Jump::Regular),
+            instr(8, Some((0, 10, 20)), Jump::Regular),
+            instr(16, Some((0, 15, 3)), Jump::Into),
+            instr(24, None, Jump::Regular),
+            instr(32, Some((1, 15, 3)), Jump::Out),
+        ];
+
+        assert_eq!(
+            compact(&instructions),
+            "10:20:0:-;;15:3::i;-1:-1:-1:-;15:3:1:o"
+        );
+    }
+
+    #[test]
+    fn wasm_calls_and_returns() {
+        let mut module = Module::new();
+
+        let mut types = TypeSection::new();
+        types.function([], []);
+        module.section(&types);
+
+        let mut functions = FunctionSection::new();
+        functions.function(0);
+        module.section(&functions);
+
+        let mut code = CodeSection::new();
+        let mut function = Function::new([]);
+        function.instruction(&wasm_encoder::Instruction::Nop);
+        function.instruction(&wasm_encoder::Instruction::Call(0));
+        function.instruction(&wasm_encoder::Instruction::End);
+        code.function(&function);
+        module.section(&code);
+
+        let (instructions, base) = wasm_instructions(&module.finish()).unwrap();
+
+        // the code section starts with the number of functions, then the size of the body and
+        // the number of locals
+        assert_eq!(base, 0);
+        assert_eq!(
+            instructions,
+            vec![(3, 1, Jump::Regular), (4, 2, Jump::Into), (6, 1, Jump::Out)]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Target;
+use std::fmt;
+
+/// A language feature which is not available on every target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// Sending native value with `transfer()` or `send()`
+    ValueTransfer,
+    /// Sending native value with the `value` call argument
+    CallValue,
+    /// Reading `msg.value`
+    MsgValue,
+    /// The `selfdestruct()` builtin
+    SelfDestruct,
+    /// The `delegatecall()` method on addresses
+    DelegateCall,
+    /// Reading `tx.origin`
+    TxOrigin,
+    /// The `salt` call argument when creating contracts
+    Create2Salt,
+    /// Creating contracts with `new`
+    ContractCreation,
+    /// Reading the raw call data with `msg.data` and `msg.sig`
+    CallData,
+    /// State variables of types which are not stored as a single value, like structs, or
+    /// function types
+    StorageReferenceTypes,
+}
+
+impl fmt::Display for Capability {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Capability::ValueTransfer => write!(f, "value transfer"),
+            Capability::CallValue => write!(f, "sending value with a call"),
+            Capability::MsgValue => write!(f, "'msg.value'"),
+            Capability::SelfDestruct => write!(f, "'selfdestruct'"),
+            Capability::DelegateCall => write!(f, "'delegatecall'"),
+            Capability::TxOrigin => write!(f, "'tx.origin'"),
+            Capability::Create2Salt => write!(f, "'salt'"),
+            Capability::ContractCreation => write!(f, "creating contracts"),
+            Capability::CallData => write!(f, "call data"),
+            Capability::StorageReferenceTypes => write!(f, "storage of this type"),
+        }
+    }
+}
+
+impl Capability {
+    /// Suggestion on how to do without this feature on the given target
+    pub fn help(&self, target: Target) -> Option<&'static str> {
+        match (self, target) {
+            (Capability::CallValue | Capability::MsgValue, Target::Solana) => Some(
+                "Solana Cross Program Invocation (CPI) cannot transfer native value. See https://solang.readthedocs.io/en/latest/language/functions.html#value_transfer",
+            ),
+            (Capability::ContractCreation, Target::Stylus) => {
+                Some("Stylus programs must be activated after deployment")
+            }
+            (Capability::StorageReferenceTypes, Target::Stylus) => {
+                Some("Stylus storage is limited to value types, and mappings of value types")
+            }
+            (Capability::StorageReferenceTypes, Target::Soroban) => Some(
+                "Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these",
+            ),
+            (Capability::CallData, Target::Soroban) => Some(
+                "Soroban contracts are invoked with the arguments of the function, rather than with encoded call data",
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// The language features a target supports. Sema consults this table, so that unsupported
+/// constructs are rejected before codegen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub value_transfer: bool,
+    pub call_value: bool,
+    pub msg_value: bool,
+    pub selfdestruct: bool,
+    pub delegatecall: bool,
+    pub tx_origin: bool,
+    pub create2_salt: bool,
+    pub contract_creation: bool,
+    pub call_data: bool,
+    pub storage_reference_types: bool,
+}
+
+impl Capabilities {
+    /// Does the target support the given feature
+    pub fn supports(&self, capability: Capability) -> bool {
+        match capability {
+            Capability::ValueTransfer => self.value_transfer,
+            Capability::CallValue => self.call_value,
+            Capability::MsgValue => self.msg_value,
+            Capability::SelfDestruct => self.selfdestruct,
+            Capability::DelegateCall => self.delegatecall,
+            Capability::TxOrigin => self.tx_origin,
+            Capability::Create2Salt => self.create2_salt,
+            Capability::ContractCreation => self.contract_creation,
+            Capability::CallData => self.call_data,
+            Capability::StorageReferenceTypes => self.storage_reference_types,
+        }
+    }
+}
+
+const EVM: Capabilities = Capabilities {
+    value_transfer: true,
+    call_value: true,
+    msg_value: true,
+    selfdestruct: true,
+    delegatecall: true,
+    tx_origin: true,
+    create2_salt: true,
+    contract_creation: true,
+    call_data: true,
+    storage_reference_types: true,
+};
+
+const POLKADOT: Capabilities = Capabilities {
+    tx_origin: false,
+    ..EVM
+};
+
+const SOLANA: Capabilities = Capabilities {
+    // transfer() and send() move lamports from the data account of the contract
+    value_transfer: true,
+    call_value: false,
+    msg_value: false,
+    selfdestruct: false,
+    delegatecall: false,
+    tx_origin: false,
+    create2_salt: false,
+    contract_creation: true,
+    call_data: true,
+    storage_reference_types: true,
+};
+
+const SOROBAN: Capabilities = Capabilities {
+    value_transfer: false,
+    contract_creation: false,
+    call_data: false,
+    // string, bytes, dynamic arrays and mappings are kept as host objects
+    storage_reference_types: false,
+    ..SOLANA
+};
+
+const STYLUS: Capabilities = Capabilities {
+    selfdestruct: false,
+    create2_salt: false,
+    contract_creation: false,
+    storage_reference_types: false,
+    ..EVM
+};
+
+impl Target {
+    /// The language features supported by this target
+    pub fn capabilities(&self) -> Capabilities {
+        match self {
+            Target::EVM => EVM,
+            Target::Polkadot { .. } => POLKADOT,
+            Target::Solana => SOLANA,
+            Target::Soroban => SOROBAN,
+            Target::Stylus => STYLUS,
+        }
+    }
+}
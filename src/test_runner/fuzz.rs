@@ -0,0 +1,597 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Invariant testing by calling the functions of a test contract in random sequences.
+//!
+//! An invariant is a public function of a test contract whose name starts with `invariant` and
+//! which has no parameters. It is broken if it reverts or returns `false`. Invariants are checked
+//! after the test contract is set up, and after each call in a sequence of calls to the other
+//! public functions of the test contract, with random arguments generated from the types of
+//! the parameters. Calls which revert are allowed, and do not change the state.
+//!
+//! The sequence which breaks an invariant is shrunk, by removing calls and simplifying their
+//! arguments for as long as the invariant is still broken.
+
+use super::failure_reason;
+use crate::sema::ast::{ArrayLength, Mutability, Namespace, StructType, Type};
+use crate::testing::differential::scale_encode;
+use crate::testing::polkadot::{Address, Execution, MockPolkadot};
+use crate::testing::solana::BorshToken;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, ToPrimitive, Zero};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+use solang_parser::pt;
+
+/// How invariants are fuzzed
+#[derive(Debug, Clone)]
+pub struct FuzzOptions {
+    /// The number of call sequences to try for each invariant
+    pub runs: usize,
+    /// The number of calls in a sequence
+    pub depth: usize,
+    /// Seed of the random generator, so that a failure can be reproduced
+    pub seed: u64,
+}
+
+impl Default for FuzzOptions {
+    fn default() -> Self {
+        FuzzOptions {
+            runs: 64,
+            depth: 16,
+            seed: 0,
+        }
+    }
+}
+
+/// The longest fixed length array which is generated
+const MAX_FIXED_ARRAY: usize = 32;
+
+/// How deep dynamic arrays can nest before they are always empty
+const MAX_NESTING: usize = 3;
+
+/// A call of a function of the test contract
+#[derive(Debug, Clone)]
+struct Call {
+    function_no: usize,
+    args: Vec<BorshToken>,
+}
+
+/// An invariant which was broken
+pub(super) struct Broken {
+    /// The calls which break the invariant, formatted like `transfer(2, 0x1234..)`
+    pub calls: Vec<String>,
+    pub reason: String,
+    /// The check of the invariant which failed
+    pub execution: Execution,
+}
+
+/// The public functions of the contract which are invariants
+pub(super) fn invariant_functions(
+    contract_no: usize,
+    ns: &Namespace,
+    filter: Option<&str>,
+) -> Vec<usize> {
+    let mut invariants: Vec<usize> = ns.contracts[contract_no]
+        .all_functions
+        .keys()
+        .copied()
+        .filter(|function_no| {
+            let func = &ns.functions[*function_no];
+
+            func.ty == pt::FunctionTy::Function
+                && func.id.name.starts_with("invariant")
+                && filter.map_or(true, |filter| func.id.name.contains(filter))
+                && ns.function_externally_callable(contract_no, Some(*function_no))
+        })
+        .collect();
+
+    invariants.sort_by(|a, b| ns.functions[*a].id.name.cmp(&ns.functions[*b].id.name));
+
+    invariants
+}
+
+/// The functions which the fuzzer calls: the public functions of the contract which can modify
+/// state and are not tests, invariants or `setUp()`, and whose parameter types are supported.
+fn target_functions(contract_no: usize, ns: &Namespace) -> Vec<usize> {
+    let mut targets: Vec<usize> = ns.contracts[contract_no]
+        .all_functions
+        .keys()
+        .copied()
+        .filter(|function_no| {
+            let func = &ns.functions[*function_no];
+
+            func.ty == pt::FunctionTy::Function
+                && !matches!(func.mutability, Mutability::View(_) | Mutability::Pure(_))
+                && !func.id.name.starts_with("test")
+                && !func.id.name.starts_with("invariant")
+                && func.id.name != "setUp"
+                && ns.function_externally_callable(contract_no, Some(*function_no))
+                && func.params.iter().all(|param| supported(&param.ty, ns))
+        })
+        .collect();
+
+    targets.sort_by(|a, b| ns.functions[*a].id.name.cmp(&ns.functions[*b].id.name));
+
+    targets
+}
+
+/// Fuzz an invariant of the test contract deployed in `account`. Returns the shrunk sequence of
+/// calls which breaks the invariant, if one is found.
+pub(super) fn fuzz_invariant(
+    invariant: usize,
+    contract_no: usize,
+    account: usize,
+    deployed: &MockPolkadot,
+    ns: &Namespace,
+    options: &FuzzOptions,
+) -> Result<(), Box<Broken>> {
+    let fuzzer = Fuzzer {
+        invariant,
+        contract_no,
+        account,
+        deployed,
+        ns,
+        targets: target_functions(contract_no, ns),
+    };
+
+    // the seed is mixed with the invariant, so each invariant gets different sequences
+    let mut rng = StdRng::seed_from_u64(options.seed ^ invariant as u64);
+
+    if let Some((reason, execution)) = fuzzer.check(deployed) {
+        return Err(fuzzer.broken(&[], reason, execution));
+    }
+
+    if fuzzer.targets.is_empty() {
+        return Ok(());
+    }
+
+    for _ in 0..options.runs {
+        let mut mock = deployed.clone();
+        let mut sequence = Vec::new();
+
+        for _ in 0..options.depth {
+            let call = fuzzer.random_call(&mut rng, &mock);
+
+            fuzzer.call(&call, &mut mock);
+            sequence.push(call);
+
+            if fuzzer.check(&mock).is_some() {
+                let sequence = fuzzer.shrink(sequence);
+                let (len, reason, execution) = fuzzer.replay(&sequence).unwrap();
+
+                return Err(fuzzer.broken(&sequence[..len], reason, execution));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Fuzzer<'a> {
+    invariant: usize,
+    contract_no: usize,
+    account: usize,
+    deployed: &'a MockPolkadot,
+    ns: &'a Namespace,
+    targets: Vec<usize>,
+}
+
+impl Fuzzer<'_> {
+    /// Check the invariant, without keeping any changes it makes. Returns why it is broken, and
+    /// the execution of the check.
+    fn check(&self, mock: &MockPolkadot) -> Option<(String, Execution)> {
+        let func = &self.ns.functions[self.invariant];
+        let execution = mock
+            .clone()
+            .call(self.account, func.selector(self.ns, &self.contract_no));
+
+        if let Some(reason) = failure_reason(&execution, self.ns) {
+            return Some((reason, execution));
+        }
+
+        match &execution.result {
+            Ok((_, data)) if func.returns.len() == 1 && func.returns[0].ty == Type::Bool => {
+                if data.first() == Some(&0) {
+                    Some(("returned false".to_string(), execution))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn call(&self, call: &Call, mock: &mut MockPolkadot) {
+        let mut input = self.ns.functions[call.function_no].selector(self.ns, &self.contract_no);
+
+        for arg in &call.args {
+            scale_encode(arg, &mut input);
+        }
+
+        mock.call(self.account, input);
+    }
+
+    /// Run the sequence from the deployed state. If the invariant is broken, returns the number
+    /// of calls after which it was broken, and why.
+    fn replay(&self, sequence: &[Call]) -> Option<(usize, String, Execution)> {
+        let mut mock = self.deployed.clone();
+
+        for (no, call) in sequence.iter().enumerate() {
+            self.call(call, &mut mock);
+
+            if let Some((reason, execution)) = self.check(&mock) {
+                return Some((no + 1, reason, execution));
+            }
+        }
+
+        None
+    }
+
+    /// Remove calls and simplify arguments, for as long as the invariant is still broken
+    fn shrink(&self, mut sequence: Vec<Call>) -> Vec<Call> {
+        loop {
+            let mut shrunk = false;
+
+            for no in (0..sequence.len()).rev() {
+                // the sequence may have been truncated
+                if no >= sequence.len() {
+                    continue;
+                }
+
+                let mut candidate = sequence.clone();
+                candidate.remove(no);
+
+                if let Some((len, ..)) = self.replay(&candidate) {
+                    candidate.truncate(len);
+                    sequence = candidate;
+                    shrunk = true;
+                }
+            }
+
+            for call_no in 0..sequence.len() {
+                for arg_no in 0..sequence[call_no].args.len() {
+                    while let Some(simpler) = simplify(&sequence[call_no].args[arg_no])
+                        .into_iter()
+                        .find(|simpler| {
+                            let mut candidate = sequence.clone();
+                            candidate[call_no].args[arg_no] = simpler.clone();
+                            self.replay(&candidate).is_some()
+                        })
+                    {
+                        sequence[call_no].args[arg_no] = simpler;
+                        shrunk = true;
+                    }
+                }
+            }
+
+            if !shrunk {
+                return sequence;
+            }
+        }
+    }
+
+    fn random_call(&self, rng: &mut StdRng, mock: &MockPolkadot) -> Call {
+        let function_no = self.targets[rng.gen_range(0..self.targets.len())];
+
+        let addresses: Vec<Address> = (0..mock.accounts())
+            .map(|account| mock.address(account))
+            .collect();
+
+        let args = self.ns.functions[function_no]
+            .params
+            .iter()
+            .map(|param| random_value(&param.ty, self.ns, &addresses, rng, 0))
+            .collect();
+
+        Call { function_no, args }
+    }
+
+    fn broken(&self, sequence: &[Call], reason: String, execution: Execution) -> Box<Broken> {
+        let calls = sequence
+            .iter()
+            .map(|call| {
+                format!(
+                    "{}({})",
+                    self.ns.functions[call.function_no].id.name,
+                    call.args
+                        .iter()
+                        .map(format_value)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })
+            .collect();
+
+        Box::new(Broken {
+            calls,
+            reason,
+            execution,
+        })
+    }
+}
+
+/// Can random values of the type be generated
+fn supported(ty: &Type, ns: &Namespace) -> bool {
+    match ty {
+        Type::Bool
+        | Type::Uint(_)
+        | Type::Int(_)
+        | Type::Address(_)
+        | Type::Contract(_)
+        | Type::Bytes(_)
+        | Type::DynamicBytes
+        | Type::String
+        | Type::Enum(_) => true,
+        Type::UserType(no) => supported(&ns.user_types[*no].ty, ns),
+        Type::Array(elem, dims) => {
+            supported(elem, ns)
+                && dims.iter().all(|dim| match dim {
+                    ArrayLength::Fixed(len) => {
+                        len.to_usize().unwrap_or(usize::MAX) <= MAX_FIXED_ARRAY
+                    }
+                    ArrayLength::Dynamic => true,
+                    ArrayLength::AnyFixed => false,
+                })
+        }
+        Type::Struct(StructType::UserDefined(no)) => ns.structs[*no]
+            .fields
+            .iter()
+            .all(|field| supported(&field.ty, ns)),
+        _ => false,
+    }
+}
+
+/// A random value of the type. Integers are often small or at the edges of their range, and
+/// addresses are often of existing accounts, since those are more likely to break invariants.
+fn random_value(
+    ty: &Type,
+    ns: &Namespace,
+    addresses: &[Address],
+    rng: &mut StdRng,
+    nesting: usize,
+) -> BorshToken {
+    match ty {
+        Type::Bool => BorshToken::Bool(rng.gen()),
+        Type::Uint(width) => {
+            let max = (BigInt::one() << *width) - 1;
+
+            let value = match rng.gen_range(0..4) {
+                0 => vec![BigInt::zero(), BigInt::one(), max].swap_remove(rng.gen_range(0..3)),
+                1 => BigInt::from(rng.gen_range(0..=16u8)),
+                _ => random_bits(*width, rng),
+            };
+
+            BorshToken::Uint {
+                width: *width,
+                value,
+            }
+        }
+        Type::Int(width) => {
+            let max = (BigInt::one() << (*width - 1)) - 1;
+            let min = -(BigInt::one() << (*width - 1));
+
+            let value = match rng.gen_range(0..4) {
+                0 => vec![BigInt::zero(), BigInt::one(), -BigInt::one(), min, max]
+                    .swap_remove(rng.gen_range(0..5)),
+                1 => BigInt::from(rng.gen_range(-16..=16i8)),
+                _ => {
+                    let value = random_bits(*width, rng);
+
+                    if value > max {
+                        value - (BigInt::one() << *width)
+                    } else {
+                        value
+                    }
+                }
+            };
+
+            BorshToken::Int {
+                width: *width,
+                value,
+            }
+        }
+        Type::Address(_) | Type::Contract(_) => {
+            if !addresses.is_empty() && rng.gen_bool(0.5) {
+                BorshToken::Address(addresses[rng.gen_range(0..addresses.len())])
+            } else {
+                BorshToken::Address(rng.gen())
+            }
+        }
+        Type::Bytes(len) => BorshToken::FixedBytes(random_bytes(*len as usize, rng)),
+        Type::DynamicBytes => {
+            let len = rng.gen_range(0..32);
+
+            BorshToken::Bytes(random_bytes(len, rng))
+        }
+        Type::String => {
+            let len = rng.gen_range(0..16);
+
+            BorshToken::String((0..len).map(|_| rng.sample(Alphanumeric) as char).collect())
+        }
+        Type::Enum(no) => BorshToken::Uint {
+            width: 8,
+            value: rng.gen_range(0..ns.enums[*no].values.len()).into(),
+        },
+        Type::UserType(no) => random_value(&ns.user_types[*no].ty, ns, addresses, rng, nesting),
+        Type::Array(elem, dims) => {
+            let (outer, inner) = dims.split_last().unwrap();
+
+            let elem = if inner.is_empty() {
+                *elem.clone()
+            } else {
+                Type::Array(elem.clone(), inner.to_vec())
+            };
+
+            match outer {
+                ArrayLength::Fixed(len) => BorshToken::FixedArray(
+                    (0..len.to_usize().unwrap())
+                        .map(|_| random_value(&elem, ns, addresses, rng, nesting + 1))
+                        .collect(),
+                ),
+                _ => {
+                    let len = if nesting < MAX_NESTING {
+                        rng.gen_range(0..4)
+                    } else {
+                        0
+                    };
+
+                    BorshToken::Array(
+                        (0..len)
+                            .map(|_| random_value(&elem, ns, addresses, rng, nesting + 1))
+                            .collect(),
+                    )
+                }
+            }
+        }
+        Type::Struct(StructType::UserDefined(no)) => BorshToken::Tuple(
+            ns.structs[*no]
+                .fields
+                .iter()
+                .map(|field| random_value(&field.ty, ns, addresses, rng, nesting + 1))
+                .collect(),
+        ),
+        _ => unreachable!("{ty:?} is not supported"),
+    }
+}
+
+fn random_bits(width: u16, rng: &mut StdRng) -> BigInt {
+    BigInt::from_bytes_le(Sign::Plus, &random_bytes(width as usize / 8, rng))
+}
+
+fn random_bytes(len: usize, rng: &mut StdRng) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    rng.fill(&mut bytes[..]);
+    bytes
+}
+
+/// Simpler values of the same type. Every value returned is strictly simpler, so shrinking
+/// always ends.
+fn simplify(value: &BorshToken) -> Vec<BorshToken> {
+    match value {
+        BorshToken::Bool(true) => vec![BorshToken::Bool(false)],
+        BorshToken::Uint { width, value } if !value.is_zero() => [BigInt::zero(), value / 2]
+            .into_iter()
+            .map(|value| BorshToken::Uint {
+                width: *width,
+                value,
+            })
+            .collect(),
+        BorshToken::Int { width, value } if !value.is_zero() => [BigInt::zero(), value / 2]
+            .into_iter()
+            .map(|value| BorshToken::Int {
+                width: *width,
+                value,
+            })
+            .collect(),
+        BorshToken::FixedBytes(bytes) if bytes.iter().any(|b| *b != 0) => {
+            vec![BorshToken::FixedBytes(vec![0; bytes.len()])]
+        }
+        BorshToken::Address(address) if address.iter().any(|b| *b != 0) => {
+            vec![BorshToken::Address([0; 32])]
+        }
+        BorshToken::Bytes(bytes) if !bytes.is_empty() => vec![
+            BorshToken::Bytes(Vec::new()),
+            BorshToken::Bytes(bytes[..bytes.len() - 1].to_vec()),
+        ],
+        BorshToken::String(string) if !string.is_empty() => vec![
+            BorshToken::String(String::new()),
+            BorshToken::String(string[..string.len() - 1].to_string()),
+        ],
+        BorshToken::Array(elems) if !elems.is_empty() => {
+            let mut simpler = vec![
+                BorshToken::Array(Vec::new()),
+                BorshToken::Array(elems[..elems.len() - 1].to_vec()),
+            ];
+
+            simpler.extend(simplify_elements(elems).into_iter().map(BorshToken::Array));
+
+            simpler
+        }
+        BorshToken::FixedArray(elems) => simplify_elements(elems)
+            .into_iter()
+            .map(BorshToken::FixedArray)
+            .collect(),
+        BorshToken::Tuple(fields) => simplify_elements(fields)
+            .into_iter()
+            .map(BorshToken::Tuple)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Simplify one of the elements
+fn simplify_elements(elems: &[BorshToken]) -> Vec<Vec<BorshToken>> {
+    let mut simpler = Vec::new();
+
+    for (no, elem) in elems.iter().enumerate() {
+        for value in simplify(elem) {
+            let mut elems = elems.to_vec();
+            elems[no] = value;
+            simpler.push(elems);
+        }
+    }
+
+    simpler
+}
+
+/// Format a value like it would be written in Solidity
+fn format_value(value: &BorshToken) -> String {
+    let list = |values: &[BorshToken]| {
+        values
+            .iter()
+            .map(format_value)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match value {
+        BorshToken::Bool(value) => value.to_string(),
+        BorshToken::Uint { value, .. } | BorshToken::Int { value, .. } => value.to_string(),
+        BorshToken::Address(bytes) => format!("0x{}", hex::encode(bytes)),
+        BorshToken::FixedBytes(bytes) | BorshToken::Bytes(bytes) => {
+            format!("hex\"{}\"", hex::encode(bytes))
+        }
+        BorshToken::String(string) => format!("{string:?}"),
+        BorshToken::Array(elems) | BorshToken::FixedArray(elems) => format!("[{}]", list(elems)),
+        BorshToken::Tuple(fields) => format!("({})", list(fields)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simplify_values() {
+        let value = BorshToken::Tuple(vec![
+            BorshToken::Int {
+                width: 32,
+                value: (-9).into(),
+            },
+            BorshToken::String("ab".into()),
+        ]);
+
+        assert_eq!(format_value(&value), "(-9, \"ab\")");
+
+        let simpler: Vec<String> = simplify(&value).iter().map(format_value).collect();
+
+        assert_eq!(
+            simpler,
+            ["(0, \"ab\")", "(-4, \"ab\")", "(-9, \"\")", "(-9, \"a\")"]
+        );
+
+        // shrinking ends
+        let mut value = BorshToken::Uint {
+            width: 256,
+            value: BigInt::one() << 255,
+        };
+        let mut steps = 0;
+
+        while let Some(simpler) = simplify(&value).pop() {
+            value = simpler;
+            steps += 1;
+        }
+
+        assert_eq!(format_value(&value), "0");
+        assert_eq!(steps, 256);
+    }
+}
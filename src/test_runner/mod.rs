@@ -0,0 +1,336 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run the tests in Solidity test contracts on a mock of the target chain.
+//!
+//! A test contract is a contract defined in a file whose name ends in `.t.sol`. Every public
+//! function of a test contract whose name starts with `test` is a test. Each test is run on a
+//! freshly deployed test contract, after calling its `setUp()` function if it has one. A test
+//! passes if it does not revert.
+//!
+//! Test contracts can also declare invariants, which are checked after random sequences of calls
+//! to the test contract; see [`FuzzOptions`].
+
+use crate::abi::{decode_polkadot_event, ethereum};
+use crate::codegen::revert::{PanicCode, ERROR_SELECTOR, PANIC_SELECTOR};
+use crate::codegen::Options;
+use crate::sema::ast::Namespace;
+use crate::testing::polkadot::{Execution, MockPolkadot};
+use crate::Target;
+use parity_scale_codec::Decode;
+use solang_parser::pt;
+use std::collections::HashMap;
+
+mod fuzz;
+mod snapshot;
+
+pub use fuzz::FuzzOptions;
+pub use snapshot::{GasRegression, GasSnapshot};
+
+/// The outcome of a single test function
+#[derive(Debug)]
+pub struct TestResult {
+    pub contract: String,
+    pub function: String,
+    /// `None` if the test passed, otherwise the reason it failed
+    pub failure: Option<String>,
+    /// Gas used by the test function, not including deploying the test contract and `setUp()`.
+    /// On Polkadot this is the wasmi fuel consumed.
+    pub gas: u64,
+    /// Output of `print()` and any runtime errors during the test
+    pub logs: String,
+    /// The events emitted by the test, decoded if possible, otherwise in hex
+    pub events: Vec<String>,
+    /// For a broken invariant, the calls after which it was broken
+    pub calls: Vec<String>,
+}
+
+impl TestResult {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Is this contract a test contract
+pub fn is_test_contract(contract_no: usize, ns: &Namespace) -> bool {
+    let contract = &ns.contracts[contract_no];
+
+    contract.instantiable
+        && ns.files[contract.loc.file_no()]
+            .path
+            .to_string_lossy()
+            .ends_with(".t.sol")
+}
+
+/// Run the tests of all test contracts in the namespace, which must have been through codegen
+/// without errors. If `filter` is given, only tests whose name contains it are run.
+///
+/// Returns an error if tests cannot be run on the target of the namespace.
+pub fn run_tests(
+    ns: &Namespace,
+    opt: &Options,
+    filter: Option<&str>,
+) -> Result<Vec<TestResult>, String> {
+    run_tests_with_fuzzing(ns, opt, filter, &FuzzOptions::default())
+}
+
+/// Run the tests like [`run_tests`], fuzzing the invariants of the test contracts with the
+/// given options.
+pub fn run_tests_with_fuzzing(
+    ns: &Namespace,
+    opt: &Options,
+    filter: Option<&str>,
+    fuzz_options: &FuzzOptions,
+) -> Result<Vec<TestResult>, String> {
+    match ns.target {
+        Target::Polkadot {
+            address_length: 32,
+            value_length: 16,
+        } => (),
+        Target::Polkadot { .. } => {
+            return Err(
+                "tests can only be run with the default address and value length".to_string(),
+            )
+        }
+        _ => return Err(format!("running tests is not supported on {}", ns.target)),
+    }
+
+    // test contracts in imported files are run when their file is tested
+    let test_contracts: Vec<usize> = (0..ns.contracts.len())
+        .filter(|contract_no| {
+            ns.contracts[*contract_no].loc.file_no() == ns.top_file_no()
+                && is_test_contract(*contract_no, ns)
+        })
+        .collect();
+
+    if test_contracts.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Upload the code of every contract, so test contracts can create them
+    let mut code_no = HashMap::new();
+    let mut blobs = Vec::new();
+    let mut abis = Vec::new();
+
+    for (contract_no, contract) in ns.contracts.iter().enumerate() {
+        if contract.instantiable {
+            code_no.insert(contract_no, blobs.len());
+            blobs.push(contract.emit(ns, opt, contract_no));
+            abis.push(ethereum::gen_abi(contract_no, ns));
+        }
+    }
+
+    let mock = MockPolkadot::new(blobs);
+    let mut results = Vec::new();
+
+    for contract_no in test_contracts {
+        let tests = test_functions(contract_no, ns, filter);
+        let invariants = fuzz::invariant_functions(contract_no, ns, filter);
+
+        if tests.is_empty() && invariants.is_empty() {
+            continue;
+        }
+
+        let mut deployed = mock.clone();
+
+        let setup = deploy(contract_no, code_no[&contract_no], &mut deployed, ns);
+
+        for function_no in tests {
+            let func = &ns.functions[function_no];
+            let mut result = TestResult {
+                contract: ns.contracts[contract_no].id.name.clone(),
+                function: func.id.name.clone(),
+                failure: None,
+                gas: 0,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            };
+
+            match &setup {
+                Err(failure) => result.failure = Some(failure.clone()),
+                Ok(_) if !func.params.is_empty() => {
+                    result.failure = Some("test functions cannot have parameters".to_string());
+                }
+                Ok(account) => {
+                    let selector = func.selector(ns, &contract_no);
+                    let execution = deployed.clone().call(*account, selector);
+
+                    result.failure = failure_reason(&execution, ns);
+                    result.gas = execution.gas;
+                    result.events = decode_events(&execution, &abis);
+                    result.logs = execution.debug_buffer;
+                }
+            }
+
+            results.push(result);
+        }
+
+        for function_no in invariants {
+            let func = &ns.functions[function_no];
+            let mut result = TestResult {
+                contract: ns.contracts[contract_no].id.name.clone(),
+                function: func.id.name.clone(),
+                failure: None,
+                gas: 0,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            };
+
+            match &setup {
+                Err(failure) => result.failure = Some(failure.clone()),
+                Ok(_) if !func.params.is_empty() => {
+                    result.failure = Some("invariants cannot have parameters".to_string());
+                }
+                Ok(account) => {
+                    if let Err(broken) = fuzz::fuzz_invariant(
+                        function_no,
+                        contract_no,
+                        *account,
+                        &deployed,
+                        ns,
+                        fuzz_options,
+                    ) {
+                        result.failure = Some(format!("invariant broken: {}", broken.reason));
+                        result.gas = broken.execution.gas;
+                        result.events = decode_events(&broken.execution, &abis);
+                        result.logs = broken.execution.debug_buffer;
+                        result.calls = broken.calls;
+                    }
+                }
+            }
+
+            results.push(result);
+        }
+    }
+
+    Ok(results)
+}
+
+/// Decode the events emitted during the execution, or format them in hex if they cannot be
+fn decode_events(execution: &Execution, abis: &[Vec<ethereum::ABI>]) -> Vec<String> {
+    execution
+        .events
+        .iter()
+        .map(|event| {
+            decode_polkadot_event(&abis[event.code_no], &event.data, 32)
+                .map(|event| event.to_string())
+                .unwrap_or_else(|_| format!("0x{}", hex::encode(&event.data)))
+        })
+        .collect()
+}
+
+/// The public functions of the contract which are tests
+fn test_functions(contract_no: usize, ns: &Namespace, filter: Option<&str>) -> Vec<usize> {
+    let mut tests: Vec<usize> = ns.contracts[contract_no]
+        .all_functions
+        .keys()
+        .copied()
+        .filter(|function_no| {
+            let func = &ns.functions[*function_no];
+
+            func.ty == pt::FunctionTy::Function
+                && func.id.name.starts_with("test")
+                && filter.map_or(true, |filter| func.id.name.contains(filter))
+                && ns.function_externally_callable(contract_no, Some(*function_no))
+        })
+        .collect();
+
+    tests.sort_by(|a, b| ns.functions[*a].id.name.cmp(&ns.functions[*b].id.name));
+
+    tests
+}
+
+/// Deploy the test contract and call its `setUp()` function, if it has one. Returns the account
+/// of the test contract, or why it could not be set up.
+fn deploy(
+    contract_no: usize,
+    code_no: usize,
+    mock: &mut MockPolkadot,
+    ns: &Namespace,
+) -> Result<usize, String> {
+    let contract = &ns.contracts[contract_no];
+
+    let constructor = contract
+        .functions
+        .iter()
+        .map(|function_no| &ns.functions[*function_no])
+        .filter(|func| func.is_constructor())
+        .chain(contract.default_constructor.as_ref().map(|(func, _)| func))
+        .find(|func| func.params.is_empty())
+        .ok_or_else(|| "the test contract has no constructor without parameters".to_string())?;
+
+    let (account, execution) = mock.deploy(code_no, constructor.selector(ns, &contract_no));
+
+    let account = account.ok_or_else(|| {
+        format!(
+            "constructor failed: {}",
+            failure_reason(&execution, ns).unwrap_or_default()
+        )
+    })?;
+
+    let setup = contract.all_functions.keys().copied().find(|function_no| {
+        let func = &ns.functions[*function_no];
+
+        func.ty == pt::FunctionTy::Function
+            && func.id.name == "setUp"
+            && func.params.is_empty()
+            && ns.function_externally_callable(contract_no, Some(*function_no))
+    });
+
+    if let Some(function_no) = setup {
+        let selector = ns.functions[function_no].selector(ns, &contract_no);
+        let execution = mock.call(account, selector);
+
+        if let Some(reason) = failure_reason(&execution, ns) {
+            return Err(format!("setUp() failed: {reason}"));
+        }
+    }
+
+    Ok(account)
+}
+
+/// Why did the execution fail, or `None` if it succeeded
+fn failure_reason(execution: &Execution, ns: &Namespace) -> Option<String> {
+    match &execution.result {
+        Ok((0, _)) => None,
+        Ok((_, data)) => Some(revert_reason(data, ns)),
+        Err(trap) => Some(format!("trapped: {trap}")),
+    }
+}
+
+/// Decode the data returned by a revert
+fn revert_reason(data: &[u8], ns: &Namespace) -> String {
+    if data.len() < 4 {
+        return "reverted".to_string();
+    }
+
+    let (selector, args) = data.split_at(4);
+
+    if selector == ERROR_SELECTOR {
+        if let Ok(reason) = String::decode(&mut &args[..]) {
+            return reason;
+        }
+    }
+
+    if selector == PANIC_SELECTOR && args.len() == 32 && args[1..].iter().all(|b| *b == 0) {
+        return match PanicCode::try_from(args[0]) {
+            Ok(code) => format!("panic: {code} ({:#04x})", args[0]),
+            Err(code) => format!("panic: {code:#04x}"),
+        };
+    }
+
+    if let Some(error) = ns
+        .errors
+        .iter()
+        .find(|error| error.selector(ns) == selector)
+    {
+        return if args.is_empty() {
+            format!("{}()", error.name)
+        } else {
+            format!("{}(0x{})", error.name, hex::encode(args))
+        };
+    }
+
+    format!("reverted with 0x{}", hex::encode(data))
+}
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Snapshots of the gas used by tests, so that regressions can be found.
+//!
+//! A snapshot file has a line for every test which passed, like
+//! `CounterTest:testIncrement() (gas: 1234)`, sorted by contract and test name so that the file
+//! can be kept in version control and diffed.
+
+use super::TestResult;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// The gas used by each test which passed
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GasSnapshot {
+    gas: BTreeMap<String, u64>,
+}
+
+/// A test which uses more gas than in the snapshot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasRegression {
+    /// The test, as `Contract:testName()`
+    pub test: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl GasRegression {
+    /// How much more gas the test uses, in percent
+    pub fn percent(&self) -> f64 {
+        (self.after - self.before) as f64 * 100.0 / self.before.max(1) as f64
+    }
+}
+
+impl fmt::Display for GasRegression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} used {} gas, was {} (+{:.2}%)",
+            self.test,
+            self.after,
+            self.before,
+            self.percent()
+        )
+    }
+}
+
+impl GasSnapshot {
+    /// Add the gas used by the tests which passed. Invariants are not included, since the gas
+    /// they use depends on the random calls.
+    pub fn record(&mut self, results: &[TestResult]) {
+        for result in results {
+            if result.passed() && result.function.starts_with("test") {
+                self.gas.insert(
+                    format!("{}:{}()", result.contract, result.function),
+                    result.gas,
+                );
+            }
+        }
+    }
+
+    /// The gas used by a test, given as `Contract:testName()`
+    pub fn gas(&self, test: &str) -> Option<u64> {
+        self.gas.get(test).copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gas.is_empty()
+    }
+
+    /// Parse the contents of a snapshot file
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut gas = BTreeMap::new();
+
+        for (no, line) in contents.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let entry = line
+                .strip_suffix(')')
+                .and_then(|line| line.rsplit_once(" (gas: "))
+                .and_then(|(test, used)| Some((test, used.parse().ok()?)));
+
+            let Some((test, used)) = entry else {
+                return Err(format!("line {}: expected 'test (gas: number)'", no + 1));
+            };
+
+            gas.insert(test.to_string(), used);
+        }
+
+        Ok(GasSnapshot { gas })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {err}", path.display()))?;
+
+        GasSnapshot::parse(&contents).map_err(|err| format!("{}: {err}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|err| format!("cannot write {}: {err}", path.display()))
+    }
+
+    /// The tests which use more than `tolerance` percent more gas than in this snapshot. Tests
+    /// which are not in both snapshots are ignored.
+    pub fn regressions(&self, current: &GasSnapshot, tolerance: f64) -> Vec<GasRegression> {
+        current
+            .gas
+            .iter()
+            .filter_map(|(test, after)| {
+                let before = self.gas(test)?;
+
+                let regression = GasRegression {
+                    test: test.clone(),
+                    before,
+                    after: *after,
+                };
+
+                (*after > before && regression.percent() > tolerance).then_some(regression)
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for GasSnapshot {
+    /// The contents of the snapshot file
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (test, gas) in &self.gas {
+            writeln!(f, "{test} (gas: {gas})")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot() {
+        let before = GasSnapshot::parse(
+            "CounterTest:testDecrement() (gas: 1000)\n\nCounterTest:testIncrement() (gas: 2000)\n",
+        )
+        .unwrap();
+
+        assert_eq!(before.gas("CounterTest:testIncrement()"), Some(2000));
+        assert_eq!(GasSnapshot::parse(&before.to_string()), Ok(before.clone()));
+
+        let mut after = GasSnapshot::default();
+
+        after.record(&[
+            TestResult {
+                contract: "CounterTest".into(),
+                function: "testDecrement".into(),
+                failure: None,
+                gas: 1050,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            },
+            TestResult {
+                contract: "CounterTest".into(),
+                function: "testIncrement".into(),
+                failure: None,
+                gas: 1900,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            },
+            TestResult {
+                contract: "CounterTest".into(),
+                function: "testNew".into(),
+                failure: None,
+                gas: 500,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            },
+            TestResult {
+                contract: "CounterTest".into(),
+                function: "testFails".into(),
+                failure: Some("reverted".into()),
+                gas: 500,
+                logs: String::new(),
+                events: Vec::new(),
+                calls: Vec::new(),
+            },
+        ]);
+
+        assert_eq!(
+            after.to_string(),
+            "CounterTest:testDecrement() (gas: 1050)\nCounterTest:testIncrement() (gas: 1900)\n\
+             CounterTest:testNew() (gas: 500)\n"
+        );
+
+        let regressions = before.regressions(&after, 1.0);
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(
+            regressions[0].to_string(),
+            "CounterTest:testDecrement() used 1050 gas, was 1000 (+5.00%)"
+        );
+
+        assert!(before.regressions(&after, 5.0).is_empty());
+
+        assert_eq!(
+            GasSnapshot::parse("CounterTest:testIncrement() 2000"),
+            Err("line 1: expected 'test (gas: number)'".to_string())
+        );
+    }
+}
@@ -0,0 +1,580 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Differential testing of the compiler against reference implementations.
+//!
+//! The ABI encoding is tested by generating functions with random argument types, and calling
+//! them with random values on the mock of the target. The function returns `abi.encode()` of its
+//! arguments, which must be the same as the encoding of the arguments by the reference encoder,
+//! SCALE on Polkadot and Borsh on Solana. Since the arguments are decoded by the contract first,
+//! this tests both the decoder and the encoder.
+//!
+//! The Ethereum ABI of contracts is tested by compiling them with solc as well, and comparing the
+//! functions, events and errors in both ABIs.
+
+use super::polkadot::MockPolkadot;
+use super::solana::{build_solidity, BorshToken};
+use crate::abi::ethereum::{gen_abi, ABIParam, ABI};
+use crate::codegen::Options;
+use crate::file_resolver::FileResolver;
+use crate::{compile, parse_and_resolve, Target};
+use num_bigint::{BigInt, Sign};
+use parity_scale_codec::{Compact, Encode};
+use rand::{distributions::Alphanumeric, rngs::StdRng, Rng, SeedableRng};
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::process::Command;
+
+/// The selectors of the constructor and the function under test on Polkadot
+const CONSTRUCTOR_SELECTOR: [u8; 4] = [0, 0, 0, 1];
+const ECHO_SELECTOR: [u8; 4] = [0, 0, 0, 2];
+
+/// The type of an argument of the function under test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgType {
+    Bool,
+    Uint(u16),
+    Int(u16),
+    Address,
+    FixedBytes(u8),
+    Bytes,
+    String,
+    /// An array of fixed length, or a dynamic array
+    Array(Box<ArgType>, Option<usize>),
+    Struct(Vec<ArgType>),
+}
+
+impl ArgType {
+    /// A random type, with up to `depth` levels of arrays and structs
+    pub fn random(rng: &mut impl Rng, depth: usize) -> Self {
+        match rng.gen_range(0..if depth == 0 { 7 } else { 9 }) {
+            0 => ArgType::Bool,
+            // only the integer widths which SCALE has
+            1 => ArgType::Uint(8 << rng.gen_range(0..6)),
+            2 => ArgType::Int(8 << rng.gen_range(0..6)),
+            3 => ArgType::Address,
+            4 => ArgType::FixedBytes(rng.gen_range(1..=32)),
+            5 => ArgType::Bytes,
+            6 => ArgType::String,
+            7 => {
+                let elem = ArgType::random(rng, depth - 1);
+                let len = rng.gen_bool(0.5).then(|| rng.gen_range(1..4));
+
+                ArgType::Array(Box::new(elem), len)
+            }
+            _ => ArgType::Struct(
+                (0..rng.gen_range(1..4))
+                    .map(|_| ArgType::random(rng, depth - 1))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// A random value of the type
+    pub fn random_value(&self, rng: &mut impl Rng) -> BorshToken {
+        fn bytes(rng: &mut impl Rng, len: usize) -> Vec<u8> {
+            let mut bytes = vec![0; len];
+            rng.fill(&mut bytes[..]);
+            bytes
+        }
+
+        match self {
+            ArgType::Bool => BorshToken::Bool(rng.gen()),
+            ArgType::Uint(width) => BorshToken::Uint {
+                width: *width,
+                value: BigInt::from_bytes_le(Sign::Plus, &bytes(rng, *width as usize / 8)),
+            },
+            ArgType::Int(width) => BorshToken::Int {
+                width: *width,
+                value: BigInt::from_signed_bytes_le(&bytes(rng, *width as usize / 8)),
+            },
+            ArgType::Address => BorshToken::Address(bytes(rng, 32).try_into().unwrap()),
+            ArgType::FixedBytes(len) => BorshToken::FixedBytes(bytes(rng, *len as usize)),
+            ArgType::Bytes => {
+                let len = rng.gen_range(0..40);
+
+                BorshToken::Bytes(bytes(rng, len))
+            }
+            ArgType::String => {
+                let len = rng.gen_range(0..40);
+
+                BorshToken::String((0..len).map(|_| rng.sample(Alphanumeric) as char).collect())
+            }
+            ArgType::Array(elem, Some(len)) => {
+                BorshToken::FixedArray((0..*len).map(|_| elem.random_value(rng)).collect())
+            }
+            ArgType::Array(elem, None) => {
+                let len = rng.gen_range(0..4);
+
+                BorshToken::Array((0..len).map(|_| elem.random_value(rng)).collect())
+            }
+            ArgType::Struct(fields) => {
+                BorshToken::Tuple(fields.iter().map(|field| field.random_value(rng)).collect())
+            }
+        }
+    }
+
+    /// The type in Solidity. Structs are declared in `structs`.
+    fn solidity(&self, structs: &mut Vec<String>) -> String {
+        match self {
+            ArgType::Bool => "bool".into(),
+            ArgType::Uint(width) => format!("uint{width}"),
+            ArgType::Int(width) => format!("int{width}"),
+            ArgType::Address => "address".into(),
+            ArgType::FixedBytes(len) => format!("bytes{len}"),
+            ArgType::Bytes => "bytes".into(),
+            ArgType::String => "string".into(),
+            ArgType::Array(elem, Some(len)) => format!("{}[{len}]", elem.solidity(structs)),
+            ArgType::Array(elem, None) => format!("{}[]", elem.solidity(structs)),
+            ArgType::Struct(fields) => {
+                let mut decl = String::new();
+
+                for (no, field) in fields.iter().enumerate() {
+                    write!(decl, " {} f{no};", field.solidity(structs)).unwrap();
+                }
+
+                let name = format!("S{}", structs.len());
+
+                structs.push(format!("struct {name} {{{decl} }}"));
+
+                name
+            }
+        }
+    }
+
+    fn is_value_type(&self) -> bool {
+        !matches!(
+            self,
+            ArgType::Bytes | ArgType::String | ArgType::Array(..) | ArgType::Struct(_)
+        )
+    }
+}
+
+/// A function under test, and the arguments to call it with
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub types: Vec<ArgType>,
+    pub args: Vec<BorshToken>,
+}
+
+impl Case {
+    /// A random case with between 1 and `max_args` arguments
+    pub fn random(rng: &mut impl Rng, max_args: usize) -> Self {
+        let types: Vec<ArgType> = (0..rng.gen_range(1..=max_args.max(1)))
+            .map(|_| ArgType::random(rng, 2))
+            .collect();
+
+        let args = types.iter().map(|ty| ty.random_value(rng)).collect();
+
+        Case { types, args }
+    }
+
+    /// The contract with the function under test
+    pub fn source(&self, target: &Target) -> String {
+        let mut structs = Vec::new();
+        let mut params = Vec::new();
+
+        for (no, ty) in self.types.iter().enumerate() {
+            let location = if ty.is_value_type() { "" } else { " memory" };
+
+            params.push(format!("{}{location} a{no}", ty.solidity(&mut structs)));
+        }
+
+        let mut src = String::from("contract Echo {\n");
+
+        for decl in &structs {
+            writeln!(src, "    {decl}").unwrap();
+        }
+
+        if target.is_polkadot() {
+            writeln!(
+                src,
+                "    @selector({CONSTRUCTOR_SELECTOR:?})\n    constructor() {{}}\n    @selector({ECHO_SELECTOR:?})"
+            )
+            .unwrap();
+        }
+
+        writeln!(
+            src,
+            "    function echo({}) public pure returns (bytes memory) {{\n        return abi.encode({});\n    }}\n}}",
+            params.join(", "),
+            (0..self.types.len())
+                .map(|no| format!("a{no}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+        .unwrap();
+
+        src
+    }
+
+    /// The encoding of the arguments by the reference encoder of the target
+    pub fn encode(&self, target: &Target) -> Vec<u8> {
+        let mut buffer = Vec::new();
+
+        for arg in &self.args {
+            if target.is_polkadot() {
+                scale_encode(arg, &mut buffer);
+            } else {
+                arg.encode(&mut buffer);
+            }
+        }
+
+        buffer
+    }
+
+    /// Compile the contract and call the function on the mock of the target. Returns why the
+    /// result is not the reference encoding.
+    pub fn check(&self, target: &Target) -> Result<(), String> {
+        let src = self.source(target);
+        let expected = self.encode(target);
+
+        match target {
+            Target::Polkadot {
+                address_length: 32,
+                value_length: 16,
+            } => check_polkadot(&src, &expected),
+            Target::Solana => catch_unwind(AssertUnwindSafe(|| {
+                check_solana(&src, &self.args, &expected)
+            }))
+            .unwrap_or_else(|panic| {
+                Err(panic
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .or_else(|| panic.downcast_ref::<&str>().map(|s| s.to_string()))
+                    .unwrap_or_else(|| "the mock panicked".into()))
+            }),
+            _ => Err(format!("differential testing is not supported on {target}")),
+        }
+    }
+}
+
+/// The SCALE encoding is the same as Borsh, except lengths are compact integers
+pub(crate) fn scale_encode(token: &BorshToken, buffer: &mut Vec<u8>) {
+    match token {
+        BorshToken::Bytes(bytes) => {
+            Compact(bytes.len() as u32).encode_to(buffer);
+            buffer.extend_from_slice(bytes);
+        }
+        BorshToken::String(string) => {
+            Compact(string.len() as u32).encode_to(buffer);
+            buffer.extend_from_slice(string.as_bytes());
+        }
+        BorshToken::Array(elems) => {
+            Compact(elems.len() as u32).encode_to(buffer);
+
+            for elem in elems {
+                scale_encode(elem, buffer);
+            }
+        }
+        BorshToken::FixedArray(elems) | BorshToken::Tuple(elems) => {
+            for elem in elems {
+                scale_encode(elem, buffer);
+            }
+        }
+        _ => token.encode(buffer),
+    }
+}
+
+fn check_polkadot(src: &str, expected: &[u8]) -> Result<(), String> {
+    let mut cache = FileResolver::default();
+    cache.set_file_contents("echo.sol", src.to_string());
+
+    let opt = Options {
+        log_runtime_errors: true,
+        ..Default::default()
+    };
+
+    let (code, ns) = compile(
+        OsStr::new("echo.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+        &opt,
+        Vec::new(),
+        "0.0.1",
+    );
+
+    if ns.diagnostics.any_errors() {
+        return Err(format!(
+            "does not compile: {}",
+            ns.diagnostics.first_error()
+        ));
+    }
+
+    let mut mock = MockPolkadot::new(code.into_iter().map(|(code, _)| code).collect());
+
+    let (Some(account), _) = mock.deploy(0, CONSTRUCTOR_SELECTOR.to_vec()) else {
+        return Err("cannot deploy".into());
+    };
+
+    let input = [&ECHO_SELECTOR, expected].concat();
+    let execution = mock.call(account, input);
+
+    match execution.result {
+        Ok((0, output)) if output == expected.encode() => Ok(()),
+        Ok((0, output)) => Err(format!(
+            "expected {}, found {}",
+            hex::encode(expected.encode()),
+            hex::encode(output)
+        )),
+        Ok((_, output)) => Err(format!(
+            "reverted with {}: {}",
+            hex::encode(output),
+            execution.debug_buffer.trim()
+        )),
+        Err(trap) => Err(format!(
+            "trapped: {trap}: {}",
+            execution.debug_buffer.trim()
+        )),
+    }
+}
+
+fn check_solana(src: &str, args: &[BorshToken], expected: &[u8]) -> Result<(), String> {
+    let mut vm = build_solidity(src);
+
+    match vm.function("echo").arguments(args).call_with_error_code() {
+        Ok(Some(BorshToken::Bytes(output))) if output == expected => Ok(()),
+        Ok(Some(BorshToken::Bytes(output))) => Err(format!(
+            "expected {}, found {}",
+            hex::encode(expected),
+            hex::encode(output)
+        )),
+        Ok(output) => Err(format!("unexpected return value {output:?}")),
+        Err(code) => Err(format!("failed with {code:#x}: {}", vm.logs.trim())),
+    }
+}
+
+/// Check random cases on the target. Each case which fails is passed to `failed` with the
+/// reason, and the number of failures is returned.
+pub fn fuzz(
+    target: &Target,
+    seed: u64,
+    runs: usize,
+    max_args: usize,
+    mut failed: impl FnMut(usize, &Case, &str),
+) -> usize {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut failures = 0;
+
+    for run in 0..runs {
+        let case = Case::random(&mut rng, max_args);
+
+        if let Err(reason) = case.check(target) {
+            failed(run, &case, &reason);
+            failures += 1;
+        }
+    }
+
+    failures
+}
+
+/// Compile the file with solang and solc for EVM, and return the differences between the
+/// Ethereum ABIs of the contracts. Contracts are matched by name.
+pub fn compare_with_solc(
+    solc: &Path,
+    file: &Path,
+    resolver: &mut FileResolver,
+) -> Result<Vec<String>, String> {
+    let output = Command::new(solc)
+        .arg("--combined-json")
+        .arg("abi")
+        .arg(file)
+        .output()
+        .map_err(|err| format!("cannot run {}: {err}", solc.display()))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "solc failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|err| format!("cannot parse the output of solc: {err}"))?;
+
+    let mut solc_abis: BTreeMap<String, Vec<ABI>> = BTreeMap::new();
+
+    for (name, contract) in json["contracts"].as_object().into_iter().flatten() {
+        let name = name.rsplit(':').next().unwrap_or(name).to_string();
+
+        // solc before 0.8.10 writes the abi as a string
+        let abi = match &contract["abi"] {
+            serde_json::Value::String(abi) => serde_json::from_str(abi),
+            abi => serde_json::from_value(abi.clone()),
+        }
+        .map_err(|err| format!("cannot parse the abi of {name} from solc: {err}"))?;
+
+        solc_abis.insert(name, abi);
+    }
+
+    let ns = parse_and_resolve(file.as_os_str(), resolver, Target::EVM);
+
+    if ns.diagnostics.any_errors() {
+        return Err(format!(
+            "does not compile with solang: {}",
+            ns.diagnostics.first_error()
+        ));
+    }
+
+    let solang_abis: BTreeMap<String, Vec<ABI>> = (0..ns.contracts.len())
+        .map(|contract_no| {
+            (
+                ns.contracts[contract_no].id.name.clone(),
+                gen_abi(contract_no, &ns),
+            )
+        })
+        .collect();
+
+    let mut differences = Vec::new();
+
+    let names: BTreeSet<&String> = solc_abis.keys().chain(solang_abis.keys()).collect();
+
+    for name in names {
+        match (solc_abis.get(name), solang_abis.get(name)) {
+            (Some(solc_abi), Some(solang_abi)) => {
+                compare_abis(name, solc_abi, solang_abi, &mut differences)
+            }
+            (Some(_), None) => differences.push(format!("{name}: contract missing in solang")),
+            (None, Some(_)) => differences.push(format!("{name}: contract missing in solc")),
+            (None, None) => unreachable!(),
+        }
+    }
+
+    Ok(differences)
+}
+
+/// Compare the entries of the ABIs by their signature
+fn compare_abis(contract: &str, solc: &[ABI], solang: &[ABI], differences: &mut Vec<String>) {
+    let entries = |abi: &[ABI]| -> BTreeMap<String, String> {
+        abi.iter()
+            .map(|entry| {
+                let signature = format!(
+                    "{} {}({})",
+                    entry.ty,
+                    entry.name,
+                    params(entry.inputs.as_deref().unwrap_or_default())
+                );
+
+                let mut details = format!(
+                    "returns ({}) {}",
+                    params(entry.outputs.as_deref().unwrap_or_default()),
+                    entry.mutability
+                );
+
+                if entry.anonymous == Some(true) {
+                    details.push_str(" anonymous");
+                }
+
+                (signature, details.trim().to_string())
+            })
+            .collect()
+    };
+
+    let solc = entries(solc);
+    let solang = entries(solang);
+
+    for (signature, solc_details) in &solc {
+        match solang.get(signature) {
+            Some(solang_details) if solang_details != solc_details => differences.push(format!(
+                "{contract}: {signature}: solc has '{solc_details}', solang has '{solang_details}'"
+            )),
+            Some(_) => (),
+            None => differences.push(format!("{contract}: {signature} missing in solang")),
+        }
+    }
+
+    for signature in solang.keys() {
+        if !solc.contains_key(signature) {
+            differences.push(format!("{contract}: {signature} missing in solc"));
+        }
+    }
+}
+
+/// The types of the parameters, with tuples written out and indexed parameters marked
+fn params(params: &[ABIParam]) -> String {
+    params
+        .iter()
+        .map(|param| {
+            let ty = match param.ty.strip_prefix("tuple") {
+                Some(dims) => format!("({}){dims}", self::params(&param.components)),
+                None => param.ty.clone(),
+            };
+
+            if param.indexed {
+                format!("{ty} indexed")
+            } else {
+                ty
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reference_encodings() {
+        let case = Case {
+            types: vec![
+                ArgType::Uint(16),
+                ArgType::String,
+                ArgType::Array(Box::new(ArgType::Bool), None),
+            ],
+            args: vec![
+                BorshToken::Uint {
+                    width: 16,
+                    value: 0x102.into(),
+                },
+                BorshToken::String("abc".into()),
+                BorshToken::Array(vec![BorshToken::Bool(true), BorshToken::Bool(false)]),
+            ],
+        };
+
+        assert_eq!(
+            case.encode(&Target::default_polkadot()),
+            [2, 1, 0x0c, b'a', b'b', b'c', 0x08, 1, 0]
+        );
+        assert_eq!(
+            case.encode(&Target::Solana),
+            [2, 1, 3, 0, 0, 0, b'a', b'b', b'c', 2, 0, 0, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn random_source() {
+        let case = Case {
+            types: vec![
+                ArgType::Struct(vec![
+                    ArgType::Int(64),
+                    ArgType::Struct(vec![ArgType::FixedBytes(4)]),
+                ]),
+                ArgType::Array(Box::new(ArgType::Address), Some(2)),
+            ],
+            args: Vec::new(),
+        };
+
+        assert_eq!(
+            case.source(&Target::Solana),
+            "contract Echo {\n    struct S0 { bytes4 f0; }\n    struct S1 { int64 f0; S0 f1; }\n    \
+             function echo(S1 memory a0, address[2] memory a1) public pure returns (bytes memory) {\n        \
+             return abi.encode(a0, a1);\n    }\n}\n"
+        );
+
+        // the same seed gives the same cases
+        let mut a = StdRng::seed_from_u64(7);
+        let mut b = StdRng::seed_from_u64(7);
+
+        for _ in 0..10 {
+            let a = Case::random(&mut a, 4);
+            let b = Case::random(&mut b, 4);
+
+            assert_eq!(a.types, b.types);
+            assert_eq!(a.args, b.args);
+            assert_eq!(a.args.len(), a.types.len());
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Run compiled contracts in-process, so that they can be tested without a node.
+
+pub mod differential;
+pub mod polkadot;
+pub mod solana;
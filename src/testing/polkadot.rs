@@ -0,0 +1,1137 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! A mock of the contracts pallet, so that contracts can be executed without a node. Each call is
+//! run in a fresh wasmi instance; the fuel consumed by wasmi is reported as the gas used.
+//!
+//! Accounts are numbered in the order they are created. The first account is the caller, which
+//! deploys and calls the contracts and has some balance. Contracts can instantiate and call each
+//! other, as long as their code was uploaded when the mock was created.
+
+use blake2_rfc::blake2b::blake2b;
+use parity_scale_codec::Decode;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    rc::Rc,
+};
+use tiny_keccak::{Hasher, Keccak};
+use wasmi::core::{HostError, Trap};
+use wasmi::{Caller, Config, Engine, Error, Linker, Memory, MemoryType, Module, Store};
+
+pub type StorageKey = [u8; 32];
+pub type Address = [u8; 32];
+pub type Hash = [u8; 32];
+
+/// The fuel available to a single call, so that a test which never returns is stopped
+const FUEL_LIMIT: u64 = 1_000_000_000;
+
+/// The account which deploys and calls the test contracts
+const CALLER: Address = [0xaa; 32];
+
+/// Return codes of the host functions, same as in pallet contracts
+const SUCCESS: u32 = 0;
+const CALLEE_TRAPPED: u32 = 1;
+const CALLEE_REVERTED: u32 = 2;
+const KEY_NOT_FOUND: u32 = 3;
+const TRANSFER_FAILED: u32 = 5;
+const CODE_NOT_FOUND: u32 = 7;
+const NOT_CALLABLE: u32 = 8;
+
+/// In pallet contracts, u32::MAX is the "none sentinel"
+const SENTINEL: u32 = u32::MAX;
+
+#[derive(Clone, Copy)]
+enum CallFlags {
+    ForwardInput = 1,
+    CloneInput = 2,
+    TailCall = 4,
+    AllowReentry = 8,
+}
+
+impl CallFlags {
+    /// Returns true if this flag is set in the given `flags`.
+    fn set(&self, flags: u32) -> bool {
+        flags & *self as u32 != 0
+    }
+}
+
+/// Reason for halting execution. Same as in pallet contracts.
+#[derive(Default, Debug, Clone)]
+enum HostReturn {
+    /// The contract was terminated (deleted).
+    #[default]
+    Terminate,
+    /// Flags and data returned by the contract.
+    Data(u32, Vec<u8>),
+}
+
+impl HostReturn {
+    fn as_data(&self) -> (u32, Vec<u8>) {
+        match self {
+            HostReturn::Data(flags, data) => (*flags, data.to_vec()),
+            HostReturn::Terminate => (0, vec![]),
+        }
+    }
+}
+
+impl fmt::Display for HostReturn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::Terminate => write!(f, "return: terminate"),
+            Self::Data(flags, data) => write!(f, "return {flags} {data:?}"),
+        }
+    }
+}
+
+impl HostError for HostReturn {}
+
+/// Uploaded contract code
+#[derive(Clone)]
+struct WasmCode {
+    hash: Hash,
+    blob: Rc<Vec<u8>>,
+}
+
+/// If contract is `Some`, this is a contract account.
+#[derive(Default, Clone)]
+struct Account {
+    address: Address,
+    value: u128,
+    contract: Option<Contract>,
+}
+
+#[derive(Clone)]
+struct Contract {
+    code: WasmCode,
+    storage: HashMap<StorageKey, Vec<u8>>,
+}
+
+/// An event deposited by a contract
+#[derive(Clone)]
+pub struct Event {
+    /// The uploaded code of the contract which deposited the event
+    pub code_no: usize,
+    pub topics: Vec<Hash>,
+    pub data: Vec<u8>,
+}
+
+/// Why a call did not complete
+struct Failure {
+    error: Error,
+    debug_buffer: String,
+    gas: u64,
+}
+
+/// The outcome of calling a contract
+pub struct Execution {
+    /// The flags and data returned by the contract, or the reason it trapped
+    pub result: Result<(u32, Vec<u8>), String>,
+    /// The fuel used by the call and any calls it made
+    pub gas: u64,
+    /// Combined output of all `debug_message` calls, which includes prints and runtime errors
+    pub debug_buffer: String,
+    /// The events deposited, if the call succeeded
+    pub events: Vec<Event>,
+}
+
+impl Execution {
+    /// Did the call return without reverting
+    pub fn success(&self) -> bool {
+        matches!(self.result, Ok((0, _)))
+    }
+}
+
+/// The state of the mocked node during contract execution.
+#[derive(Default, Clone)]
+struct Runtime {
+    accounts: Vec<Account>,
+    blobs: Vec<WasmCode>,
+    /// Index into accounts of the account that is being executed.
+    account: usize,
+    /// Index into accounts of the calling account.
+    caller_account: usize,
+    memory: Option<Memory>,
+    input: Option<Vec<u8>>,
+    output: HostReturn,
+    transferred_value: u128,
+    debug_buffer: String,
+    /// Events deposited by calls which did not revert
+    events: Vec<Event>,
+    /// The accounts on the call stack, needed for reentrancy protection.
+    called_accounts: HashSet<usize>,
+    /// Fuel used by calls made from this execution
+    nested_gas: u64,
+}
+
+fn gas_used(store: &Store<Runtime>) -> u64 {
+    store.fuel_consumed().unwrap_or_default() + store.data().nested_gas
+}
+
+/// Run the exported function `export` of the code in the given `runtime` context.
+fn execute(
+    code: &WasmCode,
+    export: &str,
+    runtime: Runtime,
+) -> Result<Store<Runtime>, Box<Failure>> {
+    let mut config = Config::default();
+    config.consume_fuel(true);
+
+    let engine = Engine::new(&config);
+    let mut store = Store::new(&engine, runtime);
+    store.add_fuel(FUEL_LIMIT).unwrap();
+
+    let mut linker = <Linker<Runtime>>::new(&engine);
+    Runtime::define(&mut linker);
+    let memory = Memory::new(&mut store, MemoryType::new(16, Some(16)).unwrap()).unwrap();
+    linker.define("env", "memory", memory).unwrap();
+    store.data_mut().memory = Some(memory);
+
+    let result = Module::new(&engine, &mut &code.blob[..])
+        .and_then(|module| linker.instantiate(&mut store, &module))
+        .and_then(|instance| Ok(instance.ensure_no_start(&mut store)?))
+        .and_then(|instance| {
+            instance
+                .get_export(&store, export)
+                .and_then(|export| export.into_func())
+                .ok_or_else(|| Error::from(Trap::new(format!("'{export}' is not exported"))))?
+                .call(&mut store, &[], &mut [])
+        });
+
+    let error = match result {
+        Ok(_) => return Ok(store),
+        Err(Error::Trap(trap)) => match trap.downcast_ref::<HostReturn>().cloned() {
+            Some(output) => {
+                store.data_mut().output = output;
+                return Ok(store);
+            }
+            None => Error::Trap(trap),
+        },
+        Err(error) => error,
+    };
+
+    Err(Box::new(Failure {
+        error,
+        debug_buffer: store.data().debug_buffer.clone(),
+        gas: gas_used(&store),
+    }))
+}
+
+impl Runtime {
+    /// Create the runtime context for calling account `callee`, from the current one.
+    fn new_context(&self, callee: usize, input: Vec<u8>, value: u128) -> Self {
+        let mut runtime = self.clone();
+        runtime.caller_account = self.account;
+        runtime.account = callee;
+        runtime.transferred_value = value;
+        runtime.accounts[callee].value += value;
+        runtime.input = Some(input);
+        runtime.output = Default::default();
+        runtime.called_accounts.insert(self.account);
+        runtime.nested_gas = 0;
+        runtime
+    }
+
+    /// After a successful call, take over the state of the callee.
+    fn accept_state(&mut self, callee_state: Self, transferred_value: u128) {
+        self.debug_buffer = callee_state.debug_buffer;
+        self.accounts = callee_state.accounts;
+        self.events = callee_state.events;
+        self.accounts[self.account].value -= transferred_value;
+    }
+
+    /// Access the contract that is currently being executed.
+    fn contract(&mut self) -> &mut Contract {
+        self.accounts[self.account].contract.as_mut().unwrap()
+    }
+
+    fn find_account(&self, address: &Address) -> Option<usize> {
+        self.accounts
+            .iter()
+            .position(|account| account.address == *address)
+    }
+
+    /// Call an exported function of the contract at account `callee`.
+    ///
+    /// Returns `None` if the account has no contract.
+    fn call(
+        &mut self,
+        export: &str,
+        callee: usize,
+        input: Vec<u8>,
+        value: u128,
+    ) -> Option<Result<Store<Runtime>, Box<Failure>>> {
+        let code = self.accounts[callee].contract.as_ref()?.code.clone();
+
+        Some(self.run(&code, export, self.new_context(callee, input, value)))
+    }
+
+    /// Run code in a new context, and keep the debug output and gas used, whatever the outcome.
+    fn run(
+        &mut self,
+        code: &WasmCode,
+        export: &str,
+        context: Runtime,
+    ) -> Result<Store<Runtime>, Box<Failure>> {
+        let result = execute(code, export, context);
+
+        match &result {
+            Ok(store) => {
+                self.nested_gas += gas_used(store);
+                self.debug_buffer = store.data().debug_buffer.clone();
+            }
+            Err(failure) => {
+                self.nested_gas += failure.gas;
+                self.debug_buffer = failure.debug_buffer.clone();
+            }
+        }
+
+        result
+    }
+
+    /// Add a new contract account and call its "deploy" function.
+    ///
+    /// Returns `None` if there is no code with the given `code_hash`.
+    fn deploy(
+        &mut self,
+        code_hash: Hash,
+        value: u128,
+        salt: &[u8],
+        input: Vec<u8>,
+    ) -> Option<Result<Store<Runtime>, Box<Failure>>> {
+        let code = self
+            .blobs
+            .iter()
+            .find(|code| code.hash == code_hash)?
+            .clone();
+
+        let mut seed = code.hash.to_vec();
+        seed.extend_from_slice(salt);
+        let address = Address::try_from(blake2b(32, &[], &seed).as_bytes()).unwrap();
+
+        if self.find_account(&address).is_some() {
+            return Some(Err(Box::new(Failure {
+                error: Trap::new("contract address already in use").into(),
+                debug_buffer: self.debug_buffer.clone(),
+                gas: 0,
+            })));
+        }
+
+        // the new account is only kept if the constructor succeeds, in which case the state of the
+        // callee is accepted
+        self.accounts.push(Account {
+            address,
+            value: 0,
+            contract: Some(Contract {
+                code,
+                storage: HashMap::new(),
+            }),
+        });
+
+        let result = self.call("deploy", self.accounts.len() - 1, input, value);
+
+        self.accounts.pop();
+
+        result
+    }
+}
+
+fn read_len(mem: &[u8], ptr: u32) -> usize {
+    u32::from_le_bytes(mem[ptr as usize..ptr as usize + 4].try_into().unwrap()) as usize
+}
+
+fn write_buf(mem: &mut [u8], ptr: u32, buf: &[u8]) {
+    mem[ptr as usize..ptr as usize + buf.len()].copy_from_slice(buf);
+}
+
+/// Write to an output buffer and its length, like all the host functions which return data
+fn write_output(mem: &mut [u8], out_ptr: u32, out_len_ptr: u32, buf: &[u8]) -> Result<(), Trap> {
+    if read_len(mem, out_len_ptr) < buf.len() {
+        return Err(Trap::new("output buffer too small"));
+    }
+
+    write_buf(mem, out_ptr, buf);
+    write_buf(mem, out_len_ptr, &(buf.len() as u32).to_le_bytes());
+
+    Ok(())
+}
+
+fn read_buf(mem: &[u8], ptr: u32, len: u32) -> Vec<u8> {
+    mem[ptr as usize..(ptr + len) as usize].to_vec()
+}
+
+fn read_value(mem: &[u8], ptr: u32) -> u128 {
+    u128::from_le_bytes(read_buf(mem, ptr, 16).try_into().unwrap())
+}
+
+fn read_hash(mem: &[u8], ptr: u32) -> Hash {
+    Hash::try_from(&mem[ptr as usize..(ptr + 32) as usize]).unwrap()
+}
+
+fn read_storage_key(mem: &[u8], ptr: u32, len: u32) -> Result<StorageKey, Trap> {
+    StorageKey::try_from(read_buf(mem, ptr, len))
+        .map_err(|_| Trap::new("storage key size must be 32 bytes"))
+}
+
+/// Get the memory and the runtime of the caller
+fn host<'a>(caller: &'a mut Caller<'_, Runtime>) -> (&'a mut [u8], &'a mut Runtime) {
+    let memory = caller.data().memory.unwrap();
+    memory.data_and_store_mut(caller)
+}
+
+/// Write the output of a call or instantiation, and take over its state if it succeeded
+fn call_result(
+    mem: &mut [u8],
+    vm: &mut Runtime,
+    state: Store<Runtime>,
+    value: u128,
+    output_ptr: u32,
+    output_len_ptr: u32,
+) -> Result<u32, Trap> {
+    let (flags, data) = state.data().output.as_data();
+
+    if output_len_ptr != SENTINEL {
+        write_output(mem, output_ptr, output_len_ptr, &data)?;
+    }
+
+    if flags == 0 {
+        vm.accept_state(state.into_data(), value);
+        Ok(SUCCESS)
+    } else {
+        Ok(CALLEE_REVERTED)
+    }
+}
+
+/// Input for a call, which may be forwarded or cloned from the input of the caller
+fn call_input(
+    mem: &[u8],
+    vm: &mut Runtime,
+    flags: u32,
+    input_ptr: u32,
+    input_len: u32,
+) -> Option<Vec<u8>> {
+    if CallFlags::ForwardInput.set(flags) {
+        vm.input.take()
+    } else if CallFlags::CloneInput.set(flags) {
+        vm.input.clone()
+    } else {
+        Some(read_buf(mem, input_ptr, input_len))
+    }
+}
+
+/// Host functions mock the original implementation, refer to the [pallet docs][1] for more information.
+///
+/// [1]: https://docs.rs/pallet-contracts/latest/pallet_contracts/api_doc/index.html
+impl Runtime {
+    fn define(linker: &mut Linker<Runtime>) {
+        linker
+            .func_wrap(
+                "seal0",
+                "input",
+                |mut caller: Caller<'_, Runtime>, dest_ptr: u32, len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let data = vm
+                        .input
+                        .as_ref()
+                        .ok_or_else(|| Trap::new("input was forwarded"))?;
+                    write_output(mem, dest_ptr, len_ptr, data)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "seal_return",
+                |mut caller: Caller<'_, Runtime>, flags: u32, data_ptr: u32, data_len: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let output = read_buf(mem, data_ptr, data_len);
+                    Err::<(), Trap>(HostReturn::Data(flags, output).into())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "value_transferred",
+                |mut caller: Caller<'_, Runtime>, dest_ptr: u32, out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let value = vm.transferred_value.to_le_bytes();
+                    write_output(mem, dest_ptr, out_len_ptr, &value)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "debug_message",
+                |mut caller: Caller<'_, Runtime>, data_ptr: u32, len: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let buf = read_buf(mem, data_ptr, len);
+                    vm.debug_buffer.push_str(&String::from_utf8_lossy(&buf));
+                    Ok::<u32, Trap>(SUCCESS)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal1",
+                "get_storage",
+                |mut caller: Caller<'_, Runtime>,
+                 key_ptr: u32,
+                 key_len: u32,
+                 out_ptr: u32,
+                 out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let key = read_storage_key(mem, key_ptr, key_len)?;
+
+                    match vm.contract().storage.get(&key) {
+                        Some(value) => {
+                            write_output(mem, out_ptr, out_len_ptr, value)?;
+                            Ok(SUCCESS)
+                        }
+                        None => Ok(KEY_NOT_FOUND),
+                    }
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal2",
+                "set_storage",
+                |mut caller: Caller<'_, Runtime>,
+                 key_ptr: u32,
+                 key_len: u32,
+                 value_ptr: u32,
+                 value_len: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let key = read_storage_key(mem, key_ptr, key_len)?;
+                    let value = read_buf(mem, value_ptr, value_len);
+
+                    Ok::<u32, Trap>(match vm.contract().storage.insert(key, value) {
+                        Some(value) => value.len() as u32,
+                        None => SENTINEL,
+                    })
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal1",
+                "clear_storage",
+                |mut caller: Caller<'_, Runtime>, key_ptr: u32, key_len: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let key = read_storage_key(mem, key_ptr, key_len)?;
+
+                    Ok::<u32, Trap>(match vm.contract().storage.remove(&key) {
+                        Some(value) => value.len() as u32,
+                        None => SENTINEL,
+                    })
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "hash_keccak_256",
+                |mut caller: Caller<'_, Runtime>,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let mut hasher = Keccak::v256();
+                    let mut hash = [0u8; 32];
+                    hasher.update(&read_buf(mem, input_ptr, input_len));
+                    hasher.finalize(&mut hash);
+                    write_buf(mem, output_ptr, &hash);
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "hash_sha2_256",
+                |mut caller: Caller<'_, Runtime>,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let mut hasher = Sha256::new();
+                    hasher.update(read_buf(mem, input_ptr, input_len));
+                    write_buf(mem, output_ptr, &hasher.finalize());
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "hash_blake2_128",
+                |mut caller: Caller<'_, Runtime>,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let data = read_buf(mem, input_ptr, input_len);
+                    write_buf(mem, output_ptr, blake2b(16, &[], &data).as_bytes());
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "hash_blake2_256",
+                |mut caller: Caller<'_, Runtime>,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let data = read_buf(mem, input_ptr, input_len);
+                    write_buf(mem, output_ptr, blake2b(32, &[], &data).as_bytes());
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal1",
+                "seal_call",
+                |mut caller: Caller<'_, Runtime>,
+                 flags: u32,
+                 callee_ptr: u32,
+                 _gas: u64,
+                 value_ptr: u32,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32,
+                 output_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    let Some(input) = call_input(mem, vm, flags, input_ptr, input_len) else {
+                        return Ok(CALLEE_TRAPPED);
+                    };
+                    let value = read_value(mem, value_ptr);
+
+                    let Some(callee) = vm.find_account(&read_hash(mem, callee_ptr)) else {
+                        return Ok(NOT_CALLABLE);
+                    };
+
+                    if vm.called_accounts.contains(&callee) && !CallFlags::AllowReentry.set(flags) {
+                        return Ok(CALLEE_TRAPPED);
+                    }
+
+                    if value > vm.accounts[vm.account].value {
+                        return Ok(TRANSFER_FAILED);
+                    }
+
+                    let state = match vm.call("call", callee, input, value) {
+                        Some(Ok(state)) => state,
+                        Some(Err(_)) => return Ok(CALLEE_TRAPPED),
+                        None => return Ok(NOT_CALLABLE),
+                    };
+
+                    if CallFlags::TailCall.set(flags) {
+                        let (flags, data) = state.data().output.as_data();
+                        return Err(HostReturn::Data(flags, data).into());
+                    }
+
+                    call_result(mem, vm, state, value, output_ptr, output_len_ptr)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "delegate_call",
+                |mut caller: Caller<'_, Runtime>,
+                 flags: u32,
+                 code_hash_ptr: u32,
+                 input_ptr: u32,
+                 input_len: u32,
+                 output_ptr: u32,
+                 output_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    let Some(input) = call_input(mem, vm, flags, input_ptr, input_len) else {
+                        return Ok(CALLEE_TRAPPED);
+                    };
+                    let code_hash = read_hash(mem, code_hash_ptr);
+
+                    let Some(code) = vm.blobs.iter().find(|code| code.hash == code_hash).cloned()
+                    else {
+                        return Ok(CODE_NOT_FOUND);
+                    };
+
+                    // the code runs on the storage of the current contract, with the same caller
+                    // and transferred value
+                    let mut context = vm.clone();
+                    context.input = Some(input);
+                    context.output = Default::default();
+                    context.nested_gas = 0;
+
+                    let state = match vm.run(&code, "call", context) {
+                        Ok(state) => state,
+                        Err(_) => return Ok(CALLEE_TRAPPED),
+                    };
+
+                    if CallFlags::TailCall.set(flags) {
+                        let (flags, data) = state.data().output.as_data();
+                        return Err(HostReturn::Data(flags, data).into());
+                    }
+
+                    let (ret, data) = state.data().output.as_data();
+
+                    if output_len_ptr != SENTINEL {
+                        write_output(mem, output_ptr, output_len_ptr, &data)?;
+                    }
+
+                    if ret == 0 {
+                        vm.accounts = state.into_data().accounts;
+                        Ok(SUCCESS)
+                    } else {
+                        Ok(CALLEE_REVERTED)
+                    }
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "code_hash",
+                |mut caller: Caller<'_, Runtime>,
+                 account_ptr: u32,
+                 out_ptr: u32,
+                 out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    match vm
+                        .find_account(&read_hash(mem, account_ptr))
+                        .and_then(|account| vm.accounts[account].contract.as_ref())
+                    {
+                        Some(contract) => {
+                            write_output(mem, out_ptr, out_len_ptr, &contract.code.hash)?;
+                            Ok(SUCCESS)
+                        }
+                        None => Ok(KEY_NOT_FOUND),
+                    }
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "instantiation_nonce",
+                |caller: Caller<'_, Runtime>| caller.data().accounts.len() as u64,
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "minimum_balance",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    write_output(mem, out_ptr, out_len_ptr, &500u128.to_le_bytes())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal2",
+                "instantiate",
+                |mut caller: Caller<'_, Runtime>,
+                 code_hash_ptr: u32,
+                 _ref_time_limit: u64,
+                 _proof_size_limit: u64,
+                 _deposit_limit_ptr: u32,
+                 value_ptr: u32,
+                 input_data_ptr: u32,
+                 input_data_len: u32,
+                 address_ptr: u32,
+                 address_len_ptr: u32,
+                 output_ptr: u32,
+                 output_len_ptr: u32,
+                 salt_ptr: u32,
+                 salt_len: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    let code_hash = read_hash(mem, code_hash_ptr);
+                    let salt = read_buf(mem, salt_ptr, salt_len);
+                    let input = read_buf(mem, input_data_ptr, input_data_len);
+                    let value = read_value(mem, value_ptr);
+
+                    if value > vm.accounts[vm.account].value {
+                        return Ok(TRANSFER_FAILED);
+                    }
+
+                    let state = match vm.deploy(code_hash, value, &salt, input) {
+                        Some(Ok(state)) => state,
+                        Some(Err(_)) => return Ok(CALLEE_TRAPPED),
+                        None => return Ok(CODE_NOT_FOUND),
+                    };
+
+                    let address = state.data().accounts[vm.accounts.len()].address;
+                    if address_len_ptr != SENTINEL {
+                        write_output(mem, address_ptr, address_len_ptr, &address)?;
+                    }
+
+                    call_result(mem, vm, state, value, output_ptr, output_len_ptr)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "transfer",
+                |mut caller: Caller<'_, Runtime>,
+                 account_ptr: u32,
+                 _account_len: u32,
+                 value_ptr: u32,
+                 _value_len: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    let value = read_value(mem, value_ptr);
+                    if value > vm.accounts[vm.account].value {
+                        return Ok::<u32, Trap>(TRANSFER_FAILED);
+                    }
+
+                    match vm.find_account(&read_hash(mem, account_ptr)) {
+                        Some(to) => {
+                            vm.accounts[to].value += value;
+                            vm.accounts[vm.account].value -= value;
+                            Ok(SUCCESS)
+                        }
+                        None => Ok(TRANSFER_FAILED),
+                    }
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "address",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let address = vm.accounts[vm.account].address;
+                    write_output(mem, out_ptr, out_len_ptr, &address)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "caller",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let address = vm.accounts[vm.caller_account].address;
+                    write_output(mem, out_ptr, out_len_ptr, &address)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "balance",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let balance = vm.accounts[vm.account].value.to_le_bytes();
+                    write_output(mem, out_ptr, out_len_ptr, &balance)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "block_number",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    write_output(mem, out_ptr, out_len_ptr, &950_119_597u32.to_le_bytes())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "now",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    write_output(mem, out_ptr, out_len_ptr, &1594035638000u64.to_le_bytes())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "gas_left",
+                |mut caller: Caller<'_, Runtime>, out_ptr: u32, out_len_ptr: u32| {
+                    let gas = FUEL_LIMIT - caller.fuel_consumed().unwrap_or_default();
+                    let (mem, _) = host(&mut caller);
+                    write_output(mem, out_ptr, out_len_ptr, &gas.to_le_bytes())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "weight_to_fee",
+                |mut caller: Caller<'_, Runtime>, gas: u64, out_ptr: u32, out_len_ptr: u32| {
+                    let (mem, _) = host(&mut caller);
+                    let price = (59_541_253_813_967 * gas as u128).to_le_bytes();
+                    write_output(mem, out_ptr, out_len_ptr, &price)
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal1",
+                "terminate",
+                |mut caller: Caller<'_, Runtime>, beneficiary_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let free = vm.accounts[vm.account].value;
+
+                    if let Some(to) = vm.find_account(&read_hash(mem, beneficiary_ptr)) {
+                        vm.accounts[to].value += free;
+                    }
+
+                    // keep the account indices intact, so the account is emptied rather than removed
+                    let account = &mut vm.accounts[vm.account];
+                    account.value = 0;
+                    account.contract = None;
+
+                    Err::<(), Trap>(HostReturn::Terminate.into())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "deposit_event",
+                |mut caller: Caller<'_, Runtime>,
+                 topics_ptr: u32,
+                 topics_len: u32,
+                 data_ptr: u32,
+                 data_len: u32| {
+                    let (mem, vm) = host(&mut caller);
+
+                    let topics = if topics_len > 0 {
+                        <Vec<Hash>>::decode(&mut &read_buf(mem, topics_ptr, topics_len)[..])
+                            .map_err(|_| Trap::new("invalid event topics"))?
+                    } else {
+                        Vec::new()
+                    };
+
+                    let data = read_buf(mem, data_ptr, data_len);
+                    let code_hash = vm.contract().code.hash;
+                    let code_no = vm
+                        .blobs
+                        .iter()
+                        .position(|code| code.hash == code_hash)
+                        .unwrap();
+
+                    vm.events.push(Event {
+                        code_no,
+                        topics,
+                        data,
+                    });
+
+                    Ok::<(), Trap>(())
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "call_chain_extension",
+                |_: Caller<'_, Runtime>, id: u32, _: u32, _: u32, _: u32, _: u32| {
+                    Err::<u32, Trap>(Trap::new(format!(
+                        "chain extension {id} is not available in tests"
+                    )))
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "is_contract",
+                |mut caller: Caller<'_, Runtime>, input_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let address = read_hash(mem, input_ptr);
+
+                    vm.accounts
+                        .iter()
+                        .any(|account| account.contract.is_some() && account.address == address)
+                        as u32
+                },
+            )
+            .unwrap();
+
+        linker
+            .func_wrap("seal0", "caller_is_root", |caller: Caller<'_, Runtime>| {
+                let vm = caller.data();
+                (vm.accounts[vm.caller_account].address == [0; 32]) as u32
+            })
+            .unwrap();
+
+        linker
+            .func_wrap(
+                "seal0",
+                "set_code_hash",
+                |mut caller: Caller<'_, Runtime>, code_hash_ptr: u32| {
+                    let (mem, vm) = host(&mut caller);
+                    let hash = read_hash(mem, code_hash_ptr);
+
+                    match vm.blobs.iter().find(|code| code.hash == hash).cloned() {
+                        Some(code) => {
+                            vm.contract().code = code;
+                            SUCCESS
+                        }
+                        None => CODE_NOT_FOUND,
+                    }
+                },
+            )
+            .unwrap();
+    }
+}
+
+/// A mock node with uploaded contract code, and a caller account with some balance
+#[derive(Clone)]
+pub struct MockPolkadot(Runtime);
+
+impl MockPolkadot {
+    /// Upload the code of all contracts, so that they can instantiate each other
+    pub fn new(blobs: Vec<Vec<u8>>) -> Self {
+        let blobs = blobs
+            .into_iter()
+            .map(|blob| WasmCode {
+                hash: blake2b(32, &[], &blob).as_bytes().try_into().unwrap(),
+                blob: Rc::new(blob),
+            })
+            .collect();
+
+        MockPolkadot(Runtime {
+            accounts: vec![Account {
+                address: CALLER,
+                value: u64::MAX.into(),
+                contract: None,
+            }],
+            blobs,
+            ..Default::default()
+        })
+    }
+
+    /// Deploy the uploaded code `code_no` by calling its constructor with the given input.
+    /// If the deploy succeeds, the state is kept and the new account is returned.
+    pub fn deploy(&mut self, code_no: usize, input: Vec<u8>) -> (Option<usize>, Execution) {
+        let code_hash = self.0.blobs[code_no].hash;
+        let account = self.0.accounts.len();
+
+        self.0.nested_gas = 0;
+        let mut state = self.0.clone();
+        let result = state.deploy(code_hash, 0, &[], input).unwrap();
+        let execution = self.execution(result, &mut state);
+
+        if execution.success() {
+            self.0.accounts = state.accounts;
+            (Some(account), execution)
+        } else {
+            (None, execution)
+        }
+    }
+
+    /// Call the contract at `account` with the given input. If the call succeeds, the state is kept.
+    /// Panics if the account has no contract.
+    pub fn call(&mut self, account: usize, input: Vec<u8>) -> Execution {
+        self.0.nested_gas = 0;
+        let mut state = self.0.clone();
+        let result = state.call("call", account, input, 0).unwrap();
+        let execution = self.execution(result, &mut state);
+
+        if execution.success() {
+            self.0.accounts = state.accounts;
+        }
+
+        execution
+    }
+
+    /// The number of accounts, including the caller
+    pub fn accounts(&self) -> usize {
+        self.0.accounts.len()
+    }
+
+    pub fn address(&self, account: usize) -> Address {
+        self.0.accounts[account].address
+    }
+
+    pub fn balance(&self, account: usize) -> u128 {
+        self.0.accounts[account].value
+    }
+
+    pub fn set_balance(&mut self, account: usize, value: u128) {
+        self.0.accounts[account].value = value;
+    }
+
+    /// The storage of the contract at `account`, or `None` if the account has no contract
+    pub fn storage(&self, account: usize) -> Option<&HashMap<StorageKey, Vec<u8>>> {
+        self.0.accounts[account]
+            .contract
+            .as_ref()
+            .map(|contract| &contract.storage)
+    }
+
+    fn execution(
+        &mut self,
+        result: Result<Store<Runtime>, Box<Failure>>,
+        state: &mut Runtime,
+    ) -> Execution {
+        let gas = state.nested_gas;
+        let debug_buffer = std::mem::take(&mut state.debug_buffer);
+
+        let result = match result {
+            Ok(store) => {
+                let output = store.data().output.as_data();
+                if output.0 == 0 {
+                    state.accept_state(store.into_data(), 0);
+                }
+                Ok(output)
+            }
+            Err(failure) => Err(match failure.error {
+                Error::Trap(trap) => match trap.trap_code() {
+                    Some(code) => code.trap_message().to_string(),
+                    None => trap.to_string(),
+                },
+                error => error.to_string(),
+            }),
+        };
+
+        Execution {
+            result,
+            gas,
+            debug_buffer,
+            events: std::mem::take(&mut state.events),
+        }
+    }
+}
@@ -1,13 +1,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
+//! Encode the arguments of Solana functions and decode what they return, in the Borsh encoding
+
 use anchor_syn::idl::types::{IdlType, IdlTypeDefinition, IdlTypeDefinitionTy};
-use byte_slice_cast::AsByteSlice;
 use num_bigint::{BigInt, Sign};
 use num_traits::ToPrimitive;
 use serde::Deserialize;
 use std::cmp::Ordering;
 
-/// This is the token that should be used for each function call in Solana runtime tests
+/// The value of an argument or return value of a function
 #[derive(Debug, PartialEq, Clone, Deserialize)]
 pub enum BorshToken {
     Address([u8; 32]),
@@ -49,7 +50,7 @@ impl BorshToken {
             BorshToken::String(data) => {
                 let len = data.len() as u32;
                 buffer.extend_from_slice(&len.to_le_bytes());
-                buffer.extend_from_slice(data.as_byte_slice());
+                buffer.extend_from_slice(data.as_bytes());
             }
             BorshToken::Tuple(data) | BorshToken::FixedArray(data) => {
                 for item in data {
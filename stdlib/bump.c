@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: Apache-2.0
+
+#include <stdint.h>
+#include <stddef.h>
+#include <stdbool.h>
+#include "stdlib.h"
+#include "solana_sdk.h"
+
+/*
+  A bump allocator for Solana. Memory is handed out from the start of the heap upwards,
+  and only the most recent allocation can be given back or grown in place. A program runs
+  for a single instruction and then its heap is thrown away, so this wastes little memory,
+  and it is much cheaper than walking a list of chunks on every allocation.
+
+  The heap starts with a pointer to the first free byte. Each allocation is preceded by a
+  header with its length, so that __realloc() knows how much to copy.
+*/
+#define HEAP_START ((uint8_t *)0x300000000)
+
+// The size of the heap, defined by the compiler from the @custom:heap-size tag
+extern const uint32_t __heap_size;
+
+struct header
+{
+    uint32_t length;
+    uint32_t allocated;
+};
+
+#define HEAP_TOP (*(uint8_t **)HEAP_START)
+
+void __init_heap()
+{
+    HEAP_TOP = HEAP_START + sizeof(uint8_t *);
+}
+
+static void out_of_memory()
+{
+    sol_log("out of heap memory");
+    sol_panic();
+}
+
+// Set the length of the allocation at the top of the heap
+static void set_length(struct header *hdr, uint32_t size)
+{
+    // round up to nearest 8 bytes
+    uint64_t length = ((uint64_t)size + 7) & ~7ull;
+    uint8_t *top = (uint8_t *)(hdr + 1) + length;
+
+    if (top > HEAP_START + __heap_size)
+        out_of_memory();
+
+    hdr->length = (uint32_t)length;
+    HEAP_TOP = top;
+}
+
+static bool is_last(struct header *hdr)
+{
+    return (uint8_t *)(hdr + 1) + hdr->length == HEAP_TOP;
+}
+
+void *__attribute__((noinline)) __malloc(uint32_t size)
+{
+    struct header *hdr = (struct header *)HEAP_TOP;
+
+    set_length(hdr, size);
+    hdr->allocated = true;
+
+    return hdr + 1;
+}
+
+void __attribute__((noinline)) __free(void *m)
+{
+    if (m)
+    {
+        struct header *hdr = m;
+        hdr--;
+
+        hdr->allocated = false;
+
+        // only the last allocation can be given back
+        if (is_last(hdr))
+            HEAP_TOP = (uint8_t *)hdr;
+    }
+}
+
+void *__realloc(void *m, uint32_t size)
+{
+    struct header *hdr = m;
+    hdr--;
+
+    if (is_last(hdr))
+    {
+        set_length(hdr, size);
+        return m;
+    }
+    else
+    {
+        // allocate new area and copy old data
+        uint32_t len = hdr->length;
+
+        // if new size is smaller than the old data, only copy remaining data
+        if (size < len)
+            len = size;
+
+        void *n = __malloc(size);
+
+        // __memcpy8() copies 8 bytes at once; round up to the nearest 8 bytes
+        // this is permitted because allocations are always aligned on 8 byte
+        // boundaries anyway.
+        __memcpy8(n, m, (len + 7) / 8);
+        hdr->allocated = false;
+        return n;
+    }
+}
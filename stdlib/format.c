@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 #include <stdint.h>
+#include <stdbool.h>
 
 void hex_encode(char *output, uint8_t *input, uint32_t length)
 {
@@ -240,6 +241,121 @@ char *uint256dec(char *output, uint256_t *val256)
     return output;
 }
 
+// The signed variants write a minus sign and then format the absolute value, so that
+// this is not done inline wherever a signed integer is formatted.
+
+// Negate a little endian two's complement number in place
+static void negate(uint8_t *input, uint32_t length)
+{
+    uint16_t carry = 1;
+
+    for (int i = 0; i < length; i++)
+    {
+        carry += (uint8_t)~input[i];
+        input[i] = carry;
+        carry >>= 8;
+    }
+}
+
+char *int2hex(char *output, uint8_t *input, uint32_t length)
+{
+    if (input[length - 1] & 0x80)
+    {
+        *output++ = '-';
+        negate(input, length);
+    }
+
+    return uint2hex(output, input, length);
+}
+
+char *int2bin(char *output, uint8_t *input, uint32_t length)
+{
+    if (input[length - 1] & 0x80)
+    {
+        *output++ = '-';
+        negate(input, length);
+    }
+
+    return uint2bin(output, input, length);
+}
+
+char *int2dec(char *output, int64_t val)
+{
+    if (val < 0)
+    {
+        *output++ = '-';
+        return uint2dec(output, -(uint64_t)val);
+    }
+
+    return uint2dec(output, val);
+}
+
+char *int128dec(char *output, __int128_t val)
+{
+    if (val < 0)
+    {
+        *output++ = '-';
+        return uint128dec(output, -(__uint128_t)val);
+    }
+
+    return uint128dec(output, val);
+}
+
+char *int256dec(char *output, uint256_t *val256)
+{
+    if (*val256 >> 255)
+    {
+        uint256_t val = -*val256;
+
+        *output++ = '-';
+        return uint256dec(output, &val);
+    }
+
+    return uint256dec(output, val256);
+}
+
+// Parse a decimal integer with an optional minus sign, like "-1234". Returns false if there are
+// no digits, there are any other characters, or the value does not fit into an int256.
+bool dec2int256(uint8_t *input, uint32_t length, uint256_t *output)
+{
+    bool negative = length > 0 && *input == '-';
+
+    if (negative)
+    {
+        input++;
+        length--;
+    }
+
+    if (length == 0)
+        return false;
+
+    // the largest magnitude is 2**255, which is only valid when negative
+    const uint256_t limit = (uint256_t)1 << 255;
+    const uint256_t limit_div10 = limit / 10;
+    const uint8_t limit_mod10 = limit % 10;
+    uint256_t val = 0;
+
+    while (length--)
+    {
+        uint8_t digit = *input++ - '0';
+
+        if (digit > 9)
+            return false;
+
+        if (val > limit_div10 || (val == limit_div10 && digit > limit_mod10))
+            return false;
+
+        val = val * 10 + digit;
+    }
+
+    if (!negative && val == limit)
+        return false;
+
+    *output = negative ? -val : val;
+
+    return true;
+}
+
 static const char b58digits[] = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
 
 // https://github.com/bitcoin/libbase58/blob/b1dd03fa8d1be4be076bb6152325c6b5cf64f678/base58.c inspired this code.
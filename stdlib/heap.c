@@ -38,12 +38,15 @@ void __init_heap()
 #else
 #define HEAP_START ((struct chunk *)0x300000000)
 
+// The size of the heap, defined by the compiler from the @custom:heap-size tag
+extern const uint32_t __heap_size;
+
 void __init_heap()
 {
     struct chunk *first = HEAP_START;
     first->next = first->prev = NULL;
     first->allocated = false;
-    first->length = (32 * 1024) - sizeof(struct chunk);
+    first->length = __heap_size - sizeof(struct chunk);
 }
 #endif
 
@@ -195,10 +195,66 @@ struct clock_layout *sol_clock(SolParameters *params)
     return clock_data;
 }
 
+// Find the account with the given address, which must have been passed to the instruction
+SolAccountInfo *sol_account_lookup(SolParameters *params, SolPubkey *address)
+{
+    for (int account_no = 0; account_no < params->ka_num; account_no++)
+    {
+        SolAccountInfo *acc = &params->ka[account_no];
+
+        if (SolPubkey_same(address, acc->key))
+        {
+            return acc;
+        }
+    }
+
+    sol_log("account missing from transaction");
+    sol_panic();
+
+    return NULL;
+}
+
+// The lamports of the account with the given address
+uint64_t sol_account_lamports(SolParameters *params, SolPubkey *address)
+{
+    return *sol_account_lookup(params, address)->lamports;
+}
+
+// Move lamports from the data account of the executing contract, which is passed by the caller,
+// to the account with the given address. Only the owner of an account may take lamports from it,
+// and both accounts must be writable.
+uint64_t sol_transfer_lamports(SolParameters *params, SolAccountInfo *from, SolPubkey *to, uint64_t lamports)
+{
+    SolAccountInfo *acc = sol_account_lookup(params, to);
+
+    if (!SolPubkey_same(from->owner, params->program_id))
+    {
+        sol_log("data account not owned by program");
+        sol_panic();
+    }
+
+    if (!from->is_writable || !acc->is_writable)
+    {
+        sol_log("account for lamport transfer is not writable");
+        sol_panic();
+    }
+
+    if (*from->lamports < lamports)
+    {
+        return ERROR_INSUFFICIENT_FUNDS;
+    }
+
+    *from->lamports -= lamports;
+    *acc->lamports += lamports;
+
+    return 0;
+}
+
+// The account discriminator, or the magic number of the contract followed by 4 unused bytes with the
+// legacy layout
 struct account_data_header
 {
-    uint32_t magic;
-    uint32_t returndata_len;
+    uint64_t discriminator;
     uint32_t returndata_offset;
     uint32_t heap_offset;
 };
@@ -633,7 +689,7 @@ int main()
 
     memset(data, 0, sizeof(data));
     struct account_data_header *hdr = data;
-    hdr->magic = 0x41424344;
+    hdr->discriminator = 0x41424344;
     hdr->heap_offset = 0x20;
 
     memset(offs, 0, sizeof(offs));
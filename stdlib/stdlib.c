@@ -6,6 +6,10 @@
 
 #include "stdlib.h"
 
+#if !defined(__wasm__) && !defined(TEST)
+#include "solana_sdk.h"
+#endif
+
 /*
  */
 void __memset8(void *_dest, uint64_t val, uint32_t length)
@@ -155,6 +159,27 @@ bool __memcmp(uint8_t *left, uint32_t left_len, uint8_t *right, uint32_t right_l
     return true;
 }
 
+// Return the offset of the first occurrence of needle in haystack, or -1 if there is none.
+// An empty needle is found at offset 0.
+int64_t __index_of(uint8_t *haystack, uint32_t haystack_len, uint8_t *needle, uint32_t needle_len)
+{
+    if (needle_len > haystack_len)
+        return -1;
+
+    for (uint32_t offset = 0; offset <= haystack_len - needle_len; offset++)
+    {
+        uint32_t i = 0;
+
+        while (i < needle_len && haystack[offset + i] == needle[i])
+            i++;
+
+        if (i == needle_len)
+            return offset;
+    }
+
+    return -1;
+}
+
 #ifndef TEST
 
 #ifdef __wasm__
@@ -193,4 +218,46 @@ struct vector *vector_new(uint32_t members, uint32_t size, uint8_t *initial)
     return v;
 }
 
+// The size of the vector does not fit in 32 bits, so go bang like __malloc does when it is
+// out of memory. This must trap rather than be unreachable, so the check is not optimized away.
+static void vector_overflow()
+{
+#ifdef __wasm__
+    __builtin_trap();
+#else
+    sol_log("out of heap memory");
+    sol_panic();
+#endif
+}
+
+// Make room for at least members in a vector, so that it can be pushed onto without
+// reallocating it every time. The room is at least doubled, so pushing n members onto
+// a vector only reallocates it O(log n) times.
+struct vector *vector_reserve(struct vector *v, uint32_t members, uint32_t size)
+{
+    // an empty vector may be a null pointer
+    uint32_t len = v ? v->len : 0;
+    uint32_t room = v ? v->size : 0;
+
+    if (members <= room)
+        return v;
+
+    if (room > UINT32_MAX / 2)
+        vector_overflow();
+
+    if (members < room * 2)
+        members = room * 2;
+
+    if (size != 0 && members > (UINT32_MAX - sizeof(*v)) / size)
+        vector_overflow();
+
+    uint32_t length = sizeof(*v) + members * size;
+
+    v = v ? __realloc(v, length) : __malloc(length);
+    v->len = len;
+    v->size = members;
+
+    return v;
+}
+
 #endif
@@ -1,7 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 /*
- * Vector is used for dynamic array
+ * Vector is used for dynamic array. The size is the number of members there
+ * is room for, which may be more than the length.
  */
 struct vector
 {
@@ -11,6 +12,7 @@ struct vector
 };
 
 extern void *__malloc(uint32_t size);
+extern void *__realloc(void *m, uint32_t size);
 extern void __memset(void *dest, uint8_t val, size_t length);
 extern void *__memcpy(void *dest, const void *src, uint32_t length);
 extern void __memcpy8(void *_dest, void *_src, uint32_t length);
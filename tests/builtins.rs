@@ -2,6 +2,7 @@
 
 use solang::sema::ast;
 use solang::sema::builtin;
+use solang::Target;
 
 #[test]
 fn builtin_prototype() {
@@ -11,3 +12,44 @@ fn builtin_prototype() {
     assert_eq!(p.name, "timestamp");
     assert!(p.params.is_empty());
 }
+
+#[test]
+fn available_builtins() {
+    let solana = builtin::available(Target::Solana);
+
+    let find = |builtins: &[builtin::BuiltinDescriptor], name: &str| {
+        builtins
+            .iter()
+            .find(|builtin| builtin.full_name() == name)
+            .cloned()
+    };
+
+    let timestamp = find(&solana, "block.timestamp").unwrap();
+    assert_eq!(timestamp.kind, builtin::BuiltinKind::Variable);
+    assert_eq!(timestamp.builtin, ast::Builtin::Timestamp);
+
+    // only on Solana
+    assert!(find(&solana, "signatureVerify").is_some());
+
+    // rejected by sema on Solana
+    assert!(find(&solana, "msg.sender").is_none());
+    assert!(find(&solana, "msg.value").is_none());
+    assert!(find(&solana, "selfdestruct").is_none());
+
+    let polkadot = builtin::available(Target::default_polkadot());
+
+    assert!(find(&polkadot, "signatureVerify").is_none());
+    assert!(find(&polkadot, "msg.sender").is_some());
+    assert!(find(&polkadot, "block.timestamp").is_some());
+
+    let ns = ast::Namespace::new(Target::Solana);
+
+    assert_eq!(
+        find(&solana, "keccak256").unwrap().signature(&ns),
+        "keccak256(bytes) returns (bytes32)"
+    );
+    assert_eq!(
+        find(&solana, "block.timestamp").unwrap().signature(&ns),
+        "block.timestamp returns (uint64)"
+    );
+}
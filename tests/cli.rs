@@ -130,3 +130,491 @@ fn basic_compilation_from_toml() {
 
     compile_cmd.current_dir(polkadot_test).assert().success();
 }
+
+#[test]
+fn new_from_template() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    for (target, template, file) in [
+        ("solana", "counter", "counter.sol"),
+        ("solana", "erc20", "erc20.sol"),
+        ("solana", "spl-token-wrapper", "spl_token_wrapper.sol"),
+        ("polkadot", "counter", "counter.sol"),
+        ("polkadot", "erc20", "erc20.sol"),
+    ] {
+        let project = tmp.path().join(format!("{target}_{template}"));
+
+        Command::cargo_bin("solang")
+            .unwrap()
+            .arg("new")
+            .arg(&project)
+            .args(["--target", target, "--template", template])
+            .assert()
+            .success();
+
+        File::open(project.join(file)).expect("should exist");
+
+        Command::cargo_bin("solang")
+            .unwrap()
+            .arg("compile")
+            .current_dir(&project)
+            .assert()
+            .success();
+    }
+
+    // the spl token program only exists on Solana
+    Command::cargo_bin("solang")
+        .unwrap()
+        .arg("new")
+        .arg(tmp.path().join("polkadot_spl"))
+        .args(["--target", "polkadot", "--template", "spl-token-wrapper"])
+        .assert()
+        .failure();
+
+    assert!(!tmp.path().join("polkadot_spl").exists());
+}
+
+#[test]
+fn inspect_artifacts() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/solana/flipper.sol",
+        "--target",
+        "solana",
+        "--output",
+    ])
+    .arg(tmp.path())
+    .assert()
+    .success();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args(["inspect", "--format", "json"])
+        .arg(tmp.path().join("flipper.so"))
+        .assert()
+        .success();
+
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+    assert_eq!(json[0]["format"], "elf");
+    assert_eq!(json[0]["metadata"]["name"], "flipper");
+    assert_eq!(json[0]["dispatch"][0]["kind"], "constructor");
+    assert!(json[0]["exports"]
+        .as_array()
+        .unwrap()
+        .contains(&"entrypoint".into()));
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/polkadot/flipper.sol",
+        "--target",
+        "polkadot",
+        "--output",
+    ])
+    .arg(tmp.path())
+    .assert()
+    .success();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .arg("inspect")
+        .arg(tmp.path().join("flipper.contract"))
+        .assert()
+        .success();
+
+    let output = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+
+    assert!(output.contains("wasm binary"));
+    assert!(output.contains("message     0xcde4efa9 flip"));
+    assert!(output.contains("imports:"));
+}
+
+#[test]
+fn reproducible_build_and_verify() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    let build = |dir: &str| {
+        let mut cmd = Command::cargo_bin("solang").unwrap();
+
+        cmd.args([
+            "compile",
+            "examples/solana/flipper.sol",
+            "--target",
+            "solana",
+            "--embed-build-info",
+            "--output",
+        ])
+        .arg(tmp.path().join(dir))
+        .assert()
+        .success();
+
+        std::fs::read(tmp.path().join(dir).join("flipper.so")).unwrap()
+    };
+
+    assert_eq!(build("a"), build("b"));
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args(["verify", "--target", "solana", "--artifact"])
+        .arg(tmp.path().join("a").join("flipper.so"))
+        .arg("examples/solana/flipper.sol")
+        .assert()
+        .success();
+
+    assert!(
+        String::from_utf8_lossy(&assert.get_output().stdout).contains("verified contract flipper")
+    );
+
+    // the build information records the options, so a different optimization level on the
+    // command line is ignored
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args(["verify", "--target", "solana", "-O", "none", "--artifact"])
+        .arg(tmp.path().join("a").join("flipper.so"))
+        .arg("examples/solana/flipper.sol")
+        .assert()
+        .success();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args(["inspect", "--format", "json"])
+        .arg(tmp.path().join("a").join("flipper.so"))
+        .assert()
+        .success();
+
+    let json: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout).unwrap();
+
+    assert_eq!(json[0]["build_info"]["contract"], "flipper");
+    assert_eq!(json[0]["build_info"]["sources"][0]["path"], "flipper.sol");
+
+    // without build information, the contract must be built with the same options
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/solana/flipper.sol",
+        "--target",
+        "solana",
+        "--output",
+    ])
+    .arg(tmp.path().join("c"))
+    .assert()
+    .success();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args(["verify", "--target", "solana", "-O", "none", "--artifact"])
+        .arg(tmp.path().join("c").join("flipper.so"))
+        .arg("examples/solana/flipper.sol")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn contract_bundle() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/solana/flipper.sol",
+        "--target",
+        "solana",
+        "--bundle",
+        "--output",
+    ])
+    .arg(tmp.path())
+    .assert()
+    .success();
+
+    let code = std::fs::read(tmp.path().join("flipper.so")).unwrap();
+    let bundle: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(tmp.path().join("flipper.bundle.json")).unwrap())
+            .unwrap();
+
+    assert_eq!(bundle["_format"], "solang-bundle-1");
+    assert_eq!(bundle["contractName"], "flipper");
+    assert_eq!(bundle["sourceName"], "flipper.sol");
+    assert_eq!(bundle["bytecode"], format!("0x{}", hex::encode(code)));
+    assert_eq!(bundle["metadata"]["name"], "flipper");
+    assert_eq!(bundle["storageLayout"]["storage"][0]["label"], "value");
+    assert_eq!(bundle["sourceMap"][0]["name"], "new");
+}
+
+#[test]
+fn size_report() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    let source = tmp.path().join("limit.sol");
+
+    // the message of the require is only formatted when it fails, so it is moved out as cold code
+    std::fs::write(
+        &source,
+        r#"contract limit {
+            uint64 value;
+
+            function set(uint64 v) public {
+                require(v < 1000, "value {} is too large".format(v));
+                value = v;
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.arg("compile")
+        .arg(&source)
+        .args(["--target", "solana", "--size-report", "json", "--output"])
+        .arg(tmp.path())
+        .assert()
+        .success();
+
+    let code = std::fs::read(tmp.path().join("limit.so")).unwrap();
+
+    // the debug information names the functions outlined by the cold splitting pass
+    assert!(code.windows(6).any(|name| name == b".cold."));
+
+    let report: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(tmp.path().join("limit.size.json")).unwrap())
+            .unwrap();
+
+    assert_eq!(report["contract"], "limit");
+
+    let entries = report["entries"].as_array().unwrap();
+
+    let size = |kind: &str, name: &str| {
+        entries
+            .iter()
+            .find(|entry| entry["kind"] == kind && entry["name"] == name)
+            .map(|entry| entry["size"].as_u64().unwrap())
+    };
+
+    assert!(size("function", "set(uint64)").is_some_and(|size| size > 0));
+    assert!(size("runtime", "runtime").is_some_and(|size| size > 0));
+
+    // every instruction is attributed to something
+    let total: u64 = entries
+        .iter()
+        .map(|entry| entry["size"].as_u64().unwrap())
+        .sum();
+
+    assert_eq!(report["code_size"].as_u64(), Some(total));
+
+    // the cold code is part of the code of the functions it was moved out of
+    let cold: u64 = entries
+        .iter()
+        .map(|entry| entry["cold_size"].as_u64().unwrap())
+        .sum();
+
+    assert_eq!(report["cold_size"].as_u64(), Some(cold));
+    assert!(cold > 0 && cold < total);
+}
+
+#[test]
+fn constructor_args() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    cmd.args([
+        "compile",
+        "examples/solana/flipper.sol",
+        "--target",
+        "solana",
+        "--constructor-args",
+        "--output",
+    ])
+    .arg(tmp.path())
+    .assert()
+    .success();
+
+    let args: solang::abi::constructor::ConstructorArgs = serde_json::from_slice(
+        &std::fs::read(tmp.path().join("flipper.constructor.json")).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(args.contract, "flipper");
+    assert_eq!(args.constructors.len(), 1);
+    assert_eq!(args.constructors[0].inputs[0].ty, "bool");
+    assert!(args.constructors[0]
+        .accounts
+        .iter()
+        .any(|account| account.name == "dataAccount" && account.is_writable));
+
+    let payload =
+        solang::abi::constructor::encode_constructor_args(&args, &[serde_json::json!(true)])
+            .unwrap();
+
+    assert_eq!(payload[8..], [1]);
+}
+
+#[test]
+fn flatten() {
+    let tmp = TempDir::new_in("tests").unwrap();
+
+    std::fs::write(
+        tmp.path().join("base.sol"),
+        "// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;\n\ncontract base {}\n",
+    )
+    .unwrap();
+
+    let token = tmp.path().join("token.sol");
+
+    std::fs::write(
+        &token,
+        "// SPDX-License-Identifier: Apache-2.0\npragma solidity ^0.8.0;\n\nimport \"base.sol\";\n\ncontract token is base {}\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("solang").unwrap();
+
+    let assert = cmd
+        .args(["flatten", "--target", "evm"])
+        .arg(&token)
+        .assert()
+        .success();
+
+    assert_eq!(
+        String::from_utf8_lossy(&assert.get_output().stdout),
+        "// SPDX-License-Identifier: MIT AND Apache-2.0\n\
+         pragma solidity ^0.8.0;\n\
+         \n\
+         // File: base.sol\n\
+         \n\
+         contract base {}\n\
+         \n\
+         // File: token.sol\n\
+         \n\
+         contract token is base {}\n"
+    );
+}
+
+#[test]
+fn check_upgrade() {
+    let tmp = TempDir::new_in("tests").unwrap();
+    let old = tmp.path().join("C.storage.json");
+
+    std::fs::write(
+        &old,
+        r#"{
+            "contract": "C",
+            "target": "EVM",
+            "storage": [{ "label": "count", "contract": "C", "slot": "0", "type": "uint256" }],
+            "types": { "uint256": { "encoding": "inplace", "length": "1" } }
+        }"#,
+    )
+    .unwrap();
+
+    let check = |src: &str| {
+        let source = tmp.path().join("C.sol");
+        std::fs::write(&source, src).unwrap();
+
+        let mut cmd = Command::cargo_bin("solang").unwrap();
+
+        cmd.args(["check-upgrade", "--target", "evm"])
+            .arg(&old)
+            .arg(&source)
+            .assert()
+    };
+
+    check("contract C {\n    uint256 count;\n    bool paused;\n}\n").success();
+
+    let assert = check("contract C {\n    bool paused;\n    uint256 count;\n}\n").failure();
+
+    assert_eq!(
+        String::from_utf8_lossy(&assert.get_output().stdout),
+        "error: storage variable 'C.count' has moved from slot 0 to slot 1\n"
+    );
+}
+
+#[test]
+fn abstract_contracts() {
+    let tmp = TempDir::new_in("tests").unwrap();
+    let source = tmp.path().join("token.sol");
+
+    std::fs::write(
+        &source,
+        "abstract contract base {\n    function name() public virtual returns (string memory);\n}\n\n\
+         contract token is base {\n    function name() public override returns (string memory) {\n        return \"token\";\n    }\n}\n",
+    )
+    .unwrap();
+
+    let compile = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("solang").unwrap();
+
+        cmd.args(["compile", "--target", "solana", "--output"])
+            .arg(tmp.path())
+            .args(args)
+            .arg(&source)
+            .assert()
+    };
+
+    // an abstract contract is not a deploy target
+    let assert = compile(&["--contract", "base"]).failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("abstract contract base cannot be deployed"));
+
+    compile(&["--contract", "base", "--abstract-abi"]).success();
+
+    let abi: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(tmp.path().join("base.abi")).unwrap()).unwrap();
+
+    assert!(abi
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|entry| entry["name"] == "name"));
+    assert!(!tmp.path().join("base.so").exists());
+
+    // only the selected contract is written
+    assert!(!tmp.path().join("token.so").exists());
+}
+
+#[test]
+fn deny_and_allow_warnings() {
+    let tmp = TempDir::new_in("tests").unwrap();
+    let source = tmp.path().join("unused.sol");
+
+    std::fs::write(
+        &source,
+        "contract unused {\n    function f(int64 a) public pure {}\n}\n",
+    )
+    .unwrap();
+
+    let compile = |args: &[&str]| {
+        let mut cmd = Command::cargo_bin("solang").unwrap();
+
+        cmd.args(["compile", "--target", "solana", "--output"])
+            .arg(tmp.path())
+            .args(args)
+            .arg(&source)
+            .assert()
+    };
+
+    let assert = compile(&[]).success();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("warning: function parameter 'a' is unused [unused-parameter]"));
+
+    let assert = compile(&["--deny", "unused"]).failure();
+    let stderr = String::from_utf8_lossy(&assert.get_output().stderr).to_string();
+
+    assert!(stderr.contains("error: function parameter 'a' is unused [unused-parameter]"));
+
+    let assert = compile(&["--deny", "warnings", "--allow", "unused-parameter"]).success();
+
+    assert!(assert.get_output().stderr.is_empty());
+}
@@ -0,0 +1,34 @@
+// RUN: --target solana --emit cfg
+
+contract c {
+    // BEGIN-CHECK: c::c::function::decode__bytes
+    function decode(bytes memory data) public pure returns (uint64, string memory) {
+        (uint64 a, string memory b) = abi.decode(data, (uint64, string));
+        return (a, b);
+    }
+    // CHECK: ty:uint32 %temp.5 = (builtin ArrayLength ((arg #0)))
+    // CHECK: ty:uint32 %failed_offset.temp.7 = uint32 8
+    // CHECK: branchcond (unsigned uint32 8 <= %temp.5), block2, block3
+
+    // CHECK: block1: # decoding_failed
+    // CHECK: # phis: failed_field.temp.6,failed_offset.temp.7
+    // CHECK: ty:string %decoding_error.temp.11 = (format string ( hex"616269206465636f64696e67206661696c6564206174206669656c6420"), ( %failed_field.temp.6), ( hex"206f666673657420"), ( %failed_offset.temp.7), ( hex"20627566666572206c656e67746820"), ( %temp.5))
+    // CHECK: print (format string ( hex"72756e74696d655f6572726f723a20"), ( %decoding_error.temp.11), ( hex"2c0a"))
+    // CHECK: assert-failure
+
+    // CHECK: block2: # inbounds
+    // CHECK: ty:uint32 %failed_offset.temp.7 = uint32 12
+    // CHECK: branchcond (unsigned uint32 12 <= %temp.5), block4, block5
+
+    // CHECK: block3: # out_of_bounds
+    // CHECK: ty:uint32 %failed_field.temp.6 = uint32 0
+    // CHECK: branch block1
+
+    // CHECK: block5: # out_of_bounds
+    // CHECK: ty:uint32 %failed_field.temp.6 = uint32 1
+    // CHECK: branch block1
+
+    // CHECK: block8: # not_all_bytes_read
+    // CHECK: ty:uint32 %failed_field.temp.6 = uint32 2
+    // CHECK: branch block1
+}
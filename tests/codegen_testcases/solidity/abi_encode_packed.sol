@@ -0,0 +1,27 @@
+// RUN: --target polkadot --emit cfg
+
+contract packer {
+    // BEGIN-CHECK: packer::packer::function::literals
+    function literals() public pure returns (bytes) {
+        // CHECK: ty:bytes %abi_encoded.temp.5 = (alloc bytes uint32 9 hex"0161626300fffeff01")
+        // CHECK: return %abi_encoded.temp.5
+        return abi.encodePacked(uint8(1), "abc", hex"00ff", int16(-2), true);
+    }
+
+    // BEGIN-CHECK: packer::packer::function::array__uint32:
+    function array(uint32[] a) public pure returns (bytes) {
+        // CHECK: ty:uint32 %temp.6 = ((builtin ArrayLength ((arg #0))) * uint32 4)
+        // CHECK: ty:bytes %abi_encoded.temp.7 = (alloc bytes len %temp.6)
+        // CHECK: memcpy src: (arg #0), dest: %abi_encoded.temp.7, bytes_len: (%temp.8 * uint32 4)
+        // CHECK: return %abi_encoded.temp.7
+        return abi.encodePacked(a);
+    }
+
+    // BEGIN-CHECK: packer::packer::function::odd__int24:
+    function odd(int24[] a) public pure returns (bytes) {
+        // CHECK: ty:uint32 %temp.9 = ((builtin ArrayLength ((arg #0))) * uint32 4)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.10 offset:%temp.11 value:(sext int32 (load (subscript int24[] (arg #0)[%for_i_0.temp.12])))
+        // CHECK: ty:uint32 %temp.11 = (uint32 4 + %temp.11)
+        return abi.encodePacked(a);
+    }
+}
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-loop-unroll
 
 contract Array_bound_Test {
     // BEGIN-CHECK: Array_bound_Test::Array_bound_Test::function::array_bound__uint256:
@@ -0,0 +1,29 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    // BEGIN-CHECK: c::c::function::squares__uint32
+    function squares(uint32 n) public pure returns (uint64[] memory) {
+        uint64[] memory a = new uint64[](0);
+
+        // CHECK: %a = reserve array ty:uint64[] size:(arg #0)
+        a.reserve(n);
+
+        for (uint64 i = 0; i < n; i++) {
+            // CHECK: push array ty:uint64[] value:(%i * %i)
+            a.push(i * i);
+        }
+
+        return a;
+    }
+
+    // BEGIN-CHECK: c::c::function::greeting
+    function greeting() public pure returns (bytes memory) {
+        // NOT-CHECK: alloc slice
+        bytes b = "hi";
+
+        // CHECK: %b = reserve array ty:bytes size:uint32 3
+        b.reserve(3);
+        b.push("!");
+
+        return b;
+    }
+}
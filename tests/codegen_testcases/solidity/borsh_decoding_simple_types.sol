@@ -6,20 +6,20 @@ contract Testing {
         (address a, address b) = abi.decode(buffer, (address, address));
 	    // CHECK: ty:bytes %buffer = (arg #0)
 	    // CHECK: ty:uint32 %temp.60 = (builtin ArrayLength ((arg #0)))
-	    // CHECK: branchcond (unsigned uint32 64 <= %temp.60), block1, block2
-        // CHECK: block1: # inbounds
-        // CHECK: ty:address %temp.61 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-        // CHECK: ty:address %temp.62 = (builtin ReadFromBuffer ((arg #0), uint32 32))
-        // CHECK: branchcond (unsigned less uint32 64 < %temp.60), block3, block4
-        // CHECK: block2: # out_of_bounds
-        // CHECK: assert-failure
-
-        // CHECK: block3: # not_all_bytes_read
-	    // CHECK: assert-failure
-
-        // CHECK: block4: # buffer_read
-	    // CHECK: ty:address %a = %temp.61
-	    // CHECK: ty:address %b = %temp.62
+	    // CHECK: branchcond (unsigned uint32 64 <= %temp.60), block2, block3
+        // CHECK: block2: # inbounds
+        // CHECK: ty:address %temp.63 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+        // CHECK: ty:address %temp.64 = (builtin ReadFromBuffer ((arg #0), uint32 32))
+        // CHECK: branchcond (unsigned less uint32 64 < %temp.60), block4, block5
+        // CHECK: block3: # out_of_bounds
+        // CHECK: branch block1
+
+        // CHECK: block4: # not_all_bytes_read
+	    // CHECK: branch block1
+
+        // CHECK: block5: # buffer_read
+	    // CHECK: ty:address %a = %temp.63
+	    // CHECK: ty:address %b = %temp.64
         return (a, b);
     }
 
@@ -30,31 +30,31 @@ contract Testing {
         abi.decode(buffer, (uint8, uint16, uint32, uint64, uint128, uint256));
 
 	    // CHECK: ty:bytes %buffer = (arg #0)
-	    // CHECK: ty:uint32 %temp.63 = (builtin ArrayLength ((arg #0)))
-	    // CHECK: branchcond (unsigned uint32 63 <= %temp.63), block1, block2
-
-        // CHECK: block1: # inbounds
-	    // CHECK: ty:uint8 %temp.64 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	    // CHECK: ty:uint16 %temp.65 = (builtin ReadFromBuffer ((arg #0), uint32 1))
-	    // CHECK: ty:uint32 %temp.66 = (builtin ReadFromBuffer ((arg #0), uint32 3))
-	    // CHECK: ty:uint64 %temp.67 = (builtin ReadFromBuffer ((arg #0), uint32 7))
-	    // CHECK: ty:uint128 %temp.68 = (builtin ReadFromBuffer ((arg #0), uint32 15))
-	    // CHECK: ty:uint256 %temp.69 = (builtin ReadFromBuffer ((arg #0), uint32 31))
-	    // CHECK: branchcond (unsigned less uint32 63 < %temp.63), block3, block4
-
-        // CHECK: block2: # out_of_bounds
-        // CHECK: assert-failure
-
-        // CHECK: block3: # not_all_bytes_read
-	    // CHECK: assert-failure
-
-        // CHECK: block4: # buffer_read
-	    // CHECK: ty:uint8 %a = %temp.64
-	    // CHECK: ty:uint16 %b = %temp.65
-	    // CHECK: ty:uint32 %c = %temp.66
-	    // CHECK: ty:uint64 %d = %temp.67
-	    // CHECK: ty:uint128 %e = %temp.68
-	    // CHECK: ty:uint256 %f = %temp.69
+	    // CHECK: ty:uint32 %temp.66 = (builtin ArrayLength ((arg #0)))
+	    // CHECK: branchcond (unsigned uint32 63 <= %temp.66), block2, block3
+
+        // CHECK: block2: # inbounds
+	    // CHECK: ty:uint8 %temp.69 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	    // CHECK: ty:uint16 %temp.70 = (builtin ReadFromBuffer ((arg #0), uint32 1))
+	    // CHECK: ty:uint32 %temp.71 = (builtin ReadFromBuffer ((arg #0), uint32 3))
+	    // CHECK: ty:uint64 %temp.72 = (builtin ReadFromBuffer ((arg #0), uint32 7))
+	    // CHECK: ty:uint128 %temp.73 = (builtin ReadFromBuffer ((arg #0), uint32 15))
+	    // CHECK: ty:uint256 %temp.74 = (builtin ReadFromBuffer ((arg #0), uint32 31))
+	    // CHECK: branchcond (unsigned less uint32 63 < %temp.66), block4, block5
+
+        // CHECK: block3: # out_of_bounds
+        // CHECK: branch block1
+
+        // CHECK: block4: # not_all_bytes_read
+	    // CHECK: branch block1
+
+        // CHECK: block5: # buffer_read
+	    // CHECK: ty:uint8 %a = %temp.69
+	    // CHECK: ty:uint16 %b = %temp.70
+	    // CHECK: ty:uint32 %c = %temp.71
+	    // CHECK: ty:uint64 %d = %temp.72
+	    // CHECK: ty:uint128 %e = %temp.73
+	    // CHECK: ty:uint256 %f = %temp.74
 
         return (a, b, c, d, e, f);
     }
@@ -65,31 +65,31 @@ contract Testing {
         (int8 a, int16 b, int32 c, int64 d, int128 e, int256 f) =
         abi.decode(buffer, (int8, int16, int32, int64, int128, int256));
 
-        // CHECK: ty:uint32 %temp.70 = (builtin ArrayLength ((arg #0)))
-	    // CHECK: branchcond (unsigned uint32 63 <= %temp.70), block1, block2
+        // CHECK: ty:uint32 %temp.76 = (builtin ArrayLength ((arg #0)))
+	    // CHECK: branchcond (unsigned uint32 63 <= %temp.76), block2, block3
 
-        // CHECK: block1: # inbounds
-	    // CHECK: ty:int8 %temp.71 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	    // CHECK: ty:int16 %temp.72 = (builtin ReadFromBuffer ((arg #0), uint32 1))
-	    // CHECK: ty:int32 %temp.73 = (builtin ReadFromBuffer ((arg #0), uint32 3))
-	    // CHECK: ty:int64 %temp.74 = (builtin ReadFromBuffer ((arg #0), uint32 7))
-	    // CHECK: ty:int128 %temp.75 = (builtin ReadFromBuffer ((arg #0), uint32 15))
-	    // CHECK: ty:int256 %temp.76 = (builtin ReadFromBuffer ((arg #0), uint32 31))
-	    // CHECK: branchcond (unsigned less uint32 63 < %temp.70), block3, block4
+        // CHECK: block2: # inbounds
+	    // CHECK: ty:int8 %temp.79 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	    // CHECK: ty:int16 %temp.80 = (builtin ReadFromBuffer ((arg #0), uint32 1))
+	    // CHECK: ty:int32 %temp.81 = (builtin ReadFromBuffer ((arg #0), uint32 3))
+	    // CHECK: ty:int64 %temp.82 = (builtin ReadFromBuffer ((arg #0), uint32 7))
+	    // CHECK: ty:int128 %temp.83 = (builtin ReadFromBuffer ((arg #0), uint32 15))
+	    // CHECK: ty:int256 %temp.84 = (builtin ReadFromBuffer ((arg #0), uint32 31))
+	    // CHECK: branchcond (unsigned less uint32 63 < %temp.76), block4, block5
 
-        // CHECK: block2: # out_of_bounds
-	    // CHECK: assert-failure
+        // CHECK: block3: # out_of_bounds
+	    // CHECK: branch block1
 
-        // CHECK: block3: # not_all_bytes_read
-	    // CHECK: assert-failure
+        // CHECK: block4: # not_all_bytes_read
+	    // CHECK: branch block1
 
-        // CHECK: block4: # buffer_read
-		// CHECK: ty:int8 %a = %temp.71
-	    // CHECK: ty:int16 %b = %temp.72
-	    // CHECK: ty:int32 %c = %temp.73
-	    // CHECK: ty:int64 %d = %temp.74
-	    // CHECK: ty:int128 %e = %temp.75
-	    // CHECK: ty:int256 %f = %temp.76
+        // CHECK: block5: # buffer_read
+		// CHECK: ty:int8 %a = %temp.79
+	    // CHECK: ty:int16 %b = %temp.80
+	    // CHECK: ty:int32 %c = %temp.81
+	    // CHECK: ty:int64 %d = %temp.82
+	    // CHECK: ty:int128 %e = %temp.83
+	    // CHECK: ty:int256 %f = %temp.84
 
         return (a, b, c, d, e, f);
      }
@@ -98,27 +98,27 @@ contract Testing {
     function fixedBytes(bytes memory buffer) public pure returns (bytes1, bytes5, bytes20, bytes32) {
         (bytes1 a, bytes5 b, bytes20 c, bytes32 d) = abi.decode(buffer, (bytes1, bytes5, bytes20, bytes32));
 
-        // CHECK: ty:uint32 %temp.77 = (builtin ArrayLength ((arg #0)))
-	    // CHECK: branchcond (unsigned uint32 58 <= %temp.77), block1, block2
+        // CHECK: ty:uint32 %temp.86 = (builtin ArrayLength ((arg #0)))
+	    // CHECK: branchcond (unsigned uint32 58 <= %temp.86), block2, block3
 
-        // CHECK: block1: # inbounds
-	    // CHECK: ty:bytes1 %temp.78 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	    // CHECK: ty:bytes5 %temp.79 = (builtin ReadFromBuffer ((arg #0), uint32 1))
-	    // CHECK: ty:bytes20 %temp.80 = (builtin ReadFromBuffer ((arg #0), uint32 6))
-	    // CHECK: ty:bytes32 %temp.81 = (builtin ReadFromBuffer ((arg #0), uint32 26))
-	    // CHECK: branchcond (unsigned less uint32 58 < %temp.77), block3, block4
+        // CHECK: block2: # inbounds
+	    // CHECK: ty:bytes1 %temp.89 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	    // CHECK: ty:bytes5 %temp.90 = (builtin ReadFromBuffer ((arg #0), uint32 1))
+	    // CHECK: ty:bytes20 %temp.91 = (builtin ReadFromBuffer ((arg #0), uint32 6))
+	    // CHECK: ty:bytes32 %temp.92 = (builtin ReadFromBuffer ((arg #0), uint32 26))
+	    // CHECK: branchcond (unsigned less uint32 58 < %temp.86), block4, block5
 
-        // CHECK: block2: # out_of_bounds
-	    // CHECK: assert-failure
+        // CHECK: block3: # out_of_bounds
+	    // CHECK: branch block1
 
-        // CHECK: block3: # not_all_bytes_read
-	    // CHECK: assert-failure
+        // CHECK: block4: # not_all_bytes_read
+	    // CHECK: branch block1
 
-        // CHECK: block4: # buffer_read
-		// CHECK: ty:bytes1 %a = %temp.78
-	    // CHECK: ty:bytes5 %b = %temp.79
-	    // CHECK: ty:bytes20 %c = %temp.80
-	    // CHECK: ty:bytes32 %d = %temp.81
+        // CHECK: block5: # buffer_read
+		// CHECK: ty:bytes1 %a = %temp.89
+	    // CHECK: ty:bytes5 %b = %temp.90
+	    // CHECK: ty:bytes20 %c = %temp.91
+	    // CHECK: ty:bytes32 %d = %temp.92
 
         return (a, b, c, d);
     }
@@ -128,48 +128,48 @@ contract Testing {
         (bytes memory a, string memory b) = abi.decode(buffer, (bytes, string));
 
 		// CHECK: ty:bytes %buffer = (arg #0)
-		// CHECK: ty:uint32 %temp.82 = (builtin ArrayLength ((arg #0)))
-		// CHECK: ty:uint32 %temp.83 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-		// CHECK: branchcond (unsigned uint32 4 <= %temp.82), block1, block2
+		// CHECK: ty:uint32 %temp.94 = (builtin ArrayLength ((arg #0)))
+		// CHECK: ty:uint32 %temp.97 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+		// CHECK: branchcond (unsigned uint32 4 <= %temp.94), block2, block3
 
-        // CHECK: block1: # inbounds
-        // CHECK: ty:uint32 %1.cse_temp = (uint32 0 + (%temp.83 + uint32 4))
-        // CHECK: branchcond (unsigned %1.cse_temp <= %temp.82), block3, block4
+		// CHECK: block2: # inbounds
+		// CHECK: ty:uint32 %1.cse_temp = (uint32 0 + (%temp.97 + uint32 4))
+		// CHECK: branchcond (unsigned %1.cse_temp <= %temp.94), block4, block5
 
-        // CHECK: block2: # out_of_bounds
-        // CHECK: assert-failure
+		// CHECK: block3: # out_of_bounds
+		// CHECK: branch block1
 
-        // CHECK: block3: # inbounds
-        // CHECK: ty:bytes %temp.84 = (alloc bytes len %temp.83)
-        // CHECK: memcpy src: (advance ptr: %buffer, by: uint32 4), dest: %temp.84, bytes_len: %temp.83
-        // CHECK: ty:uint32 %temp.85 = (builtin ReadFromBuffer ((arg #0), (uint32 0 + (%temp.83 + uint32 4))))
-        // CHECK: ty:uint32 %2.cse_temp = (%1.cse_temp + uint32 4)
-        // CHECK: branchcond (unsigned %2.cse_temp <= %temp.82), block5, block6
+		// CHECK: block4: # inbounds
+		// CHECK: ty:bytes %temp.98 = (alloc bytes len %temp.97)
+		// CHECK: memcpy src: (advance ptr: %buffer, by: uint32 4), dest: %temp.98, bytes_len: %temp.97
+		// CHECK: ty:uint32 %temp.99 = (builtin ReadFromBuffer ((arg #0), (uint32 0 + (%temp.97 + uint32 4))))
+		// CHECK: ty:uint32 %2.cse_temp = (%1.cse_temp + uint32 4)
+		// CHECK: branchcond (unsigned %2.cse_temp <= %temp.94), block6, block7
 
-        // CHECK: block4: # out_of_bounds
-        // CHECK: assert-failure
+		// CHECK: block5: # out_of_bounds
+		// CHECK: branch block1
 
-        // CHECK: block5: # inbounds
-        // CHECK: ty:uint32 %3.cse_temp = (%1.cse_temp + (%temp.85 + uint32 4))
-        // CHECK: branchcond (unsigned %3.cse_temp <= %temp.82), block7, block8
+		// CHECK: block6: # inbounds
+		// CHECK: ty:uint32 %3.cse_temp = (%1.cse_temp + (%temp.99 + uint32 4))
+		// CHECK: branchcond (unsigned %3.cse_temp <= %temp.94), block8, block9
 
-        // CHECK: block6: # out_of_bounds
-        // CHECK: assert-failure
+		// CHECK: block7: # out_of_bounds
+		// CHECK: branch block1
 
-        // CHECK: block7: # inbounds
-        // CHECK: ty:string %temp.86 = (alloc string len %temp.85)
-        // CHECK: memcpy src: (advance ptr: %buffer, by: %2.cse_temp), dest: %temp.86, bytes_len: %temp.85
-        // CHECK: branchcond (unsigned less %3.cse_temp < %temp.82), block9, block10
+		// CHECK: block8: # inbounds
+		// CHECK: ty:string %temp.100 = (alloc string len %temp.99)
+		// CHECK: memcpy src: (advance ptr: %buffer, by: %2.cse_temp), dest: %temp.100, bytes_len: %temp.99
+		// CHECK: branchcond (unsigned less %3.cse_temp < %temp.94), block10, block11
 
-        // CHECK: block8: # out_of_bounds
-        // CHECK: assert-failure
+		// CHECK: block9: # out_of_bounds
+		// CHECK: branch block1
 
-        // CHECK: block9: # not_all_bytes_read
-        // CHECK: assert-failure
+		// CHECK: block10: # not_all_bytes_read
+		// CHECK: branch block1
 
-        // CHECK: block10: # buffer_read
-        // CHECK: ty:bytes %a = %temp.84
-        // CHECK: ty:string %b = %temp.86
+		// CHECK: block11: # buffer_read
+		// CHECK: ty:bytes %a = %temp.98
+		// CHECK: ty:string %b = %temp.100
 
         return (a, b);
     }
@@ -183,21 +183,21 @@ contract Testing {
         WeekDays a = abi.decode(buffer, (WeekDays));
 
 		// CHECK: ty:bytes %buffer = (arg #0)
-		// CHECK: ty:uint32 %temp.90 = (builtin ArrayLength ((arg #0)))
-		// CHECK: branchcond (unsigned uint32 1 <= %temp.90), block1, block2
+		// CHECK: ty:uint32 %temp.105 = (builtin ArrayLength ((arg #0)))
+		// CHECK: branchcond (unsigned uint32 1 <= %temp.105), block2, block3
 
-		// CHECK: block1: # inbounds
-		// CHECK: ty:enum Testing.WeekDays %temp.91 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-		// CHECK: branchcond (unsigned less uint32 1 < %temp.90), block3, block4
+		// CHECK: block2: # inbounds
+		// CHECK: ty:enum Testing.WeekDays %temp.108 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+		// CHECK: branchcond (unsigned less uint32 1 < %temp.105), block4, block5
 
-		// CHECK: block2: # out_of_bounds
-		// CHECK: assert-failure
+		// CHECK: block3: # out_of_bounds
+		// CHECK: branch block1
 
-		// CHECK: block3: # not_all_bytes_read
-		// CHECK: assert-failure
+		// CHECK: block4: # not_all_bytes_read
+		// CHECK: branch block1
 
-		// CHECK: block4: # buffer_read
-		// CHECK: ty:enum Testing.WeekDays %a = %temp.91
+		// CHECK: block5: # buffer_read
+		// CHECK: ty:enum Testing.WeekDays %a = %temp.108
 
         return a;
     }
@@ -217,27 +217,27 @@ contract Testing {
     function decodeStruct(bytes memory buffer) public pure returns (noPadStruct memory, PaddedStruct memory) {
         (noPadStruct memory a, PaddedStruct memory b) = abi.decode(buffer, (noPadStruct, PaddedStruct));
 
-		// CHECK: ty:uint32 %temp.92 = (builtin ArrayLength ((arg #0)))
-		// CHECK: branchcond (unsigned uint32 57 <= %temp.92), block1, block2
-
-		// CHECK: block1: # inbounds
-		// CHECK: ty:struct Testing.noPadStruct %temp.93 = struct {  }
-		// CHECK: memcpy src: %buffer, dest: %temp.93, bytes_len: uint32 8
-        // CHECK: ty:uint128 %temp.94 = (builtin ReadFromBuffer ((arg #0), uint32 8))
-        // CHECK: ty:uint8 %temp.95 = (builtin ReadFromBuffer ((arg #0), uint32 24))
-        // CHECK: ty:bytes32 %temp.96 = (builtin ReadFromBuffer ((arg #0), uint32 25))
-        // CHECK: ty:struct Testing.PaddedStruct %temp.97 = struct { %temp.94, %temp.95, %temp.96 }
-        // CHECK: branchcond (unsigned less uint32 57 < %temp.92), block3, block4
+		// CHECK: ty:uint32 %temp.110 = (builtin ArrayLength ((arg #0)))
+		// CHECK: branchcond (unsigned uint32 57 <= %temp.110), block2, block3
+
+		// CHECK: block2: # inbounds
+		// CHECK: ty:struct Testing.noPadStruct %temp.113 = struct {  }
+		// CHECK: memcpy src: %buffer, dest: %temp.113, bytes_len: uint32 8
+        // CHECK: ty:uint128 %temp.114 = (builtin ReadFromBuffer ((arg #0), uint32 8))
+        // CHECK: ty:uint8 %temp.115 = (builtin ReadFromBuffer ((arg #0), uint32 24))
+        // CHECK: ty:bytes32 %temp.116 = (builtin ReadFromBuffer ((arg #0), uint32 25))
+        // CHECK: ty:struct Testing.PaddedStruct %temp.117 = struct { %temp.114, %temp.115, %temp.116 }
+        // CHECK: branchcond (unsigned less uint32 57 < %temp.110), block4, block5
 		
-		// CHECK: block2: # out_of_bounds
-		// CHECK: assert-failure
+		// CHECK: block3: # out_of_bounds
+		// CHECK: branch block1
 
-		// CHECK: block3: # not_all_bytes_read
-		// CHECK: assert-failure
+		// CHECK: block4: # not_all_bytes_read
+		// CHECK: branch block1
 
-		// CHECK: block4: # buffer_read
-		// CHECK: ty:struct Testing.noPadStruct %a = %temp.93
-		// CHECK: ty:struct Testing.PaddedStruct %b = %temp.97
+		// CHECK: block5: # buffer_read
+		// CHECK: ty:struct Testing.noPadStruct %a = %temp.113
+		// CHECK: ty:struct Testing.PaddedStruct %b = %temp.117
 
         return (a, b);
     }
@@ -247,42 +247,44 @@ contract Testing {
         (uint32[4] memory a, noPadStruct[2] memory b, noPadStruct[] memory c) =
         abi.decode(buffer, (uint32[4], noPadStruct[2], noPadStruct[]));
 
-		// CHECK: ty:uint32 %temp.98 = (builtin ArrayLength ((arg #0)))
-        // CHECK: branchcond (unsigned uint32 32 <= %temp.98), block1, block2
-
-		// CHECK: block1: # inbounds
-        // CHECK: ty:uint32[4] %temp.99 =  [  ]
-        // CHECK: memcpy src: %buffer, dest: %temp.99, bytes_len: uint32 16
-        // CHECK: ty:struct Testing.noPadStruct[2] %temp.100 =  [  ]
-        // CHECK: memcpy src: (advance ptr: %buffer, by: uint32 16), dest: %temp.100, bytes_len: uint32 16
-        // CHECK: ty:uint32 %temp.101 = (builtin ReadFromBuffer ((arg #0), uint32 32))
-        // CHECK: branchcond (unsigned uint32 36 <= %temp.98), block3, block4
-		
-		// CHECK: block2: # out_of_bounds
-        // CHECK: assert-failure
-
-		// CHECK: block3: # inbounds
-        // CHECK: ty:struct Testing.noPadStruct[] %temp.102 = (alloc struct Testing.noPadStruct[] len %temp.101)
-        // CHECK: ty:uint32 %1.cse_temp = (%temp.101 * uint32 8)
-        // CHECK: branchcond (unsigned (uint32 36 + %1.cse_temp) <= %temp.98), block5, block6
-
-		// CHECK: block4: # out_of_bounds
-        // CHECK: assert-failure
-
-		// CHECK: block5: # inbounds
-        // CHECK: memcpy src: (advance ptr: %buffer, by: uint32 36), dest: %temp.102, bytes_len: %1.cse_temp
-        // CHECK: branchcond (unsigned less (uint32 32 + (%1.cse_temp + uint32 4)) < %temp.98), block7, block8
-
-		// CHECK: block6: # out_of_bounds
-        // CHECK: assert-failure
-
-		// CHECK: block7: # not_all_bytes_read
-        // CHECK: assert-failure
-
-		// CHECK: block8: # buffer_read
-        // CHECK: ty:uint32[4] %a = %temp.99
-        // CHECK: ty:struct Testing.noPadStruct[2] %b = %temp.100
-        // CHECK: ty:struct Testing.noPadStruct[] %c = %temp.102
+		// CHECK: ty:bytes %buffer = (arg #0)
+		// CHECK: ty:uint32 %temp.119 = (builtin ArrayLength ((arg #0)))
+		// CHECK: branchcond (unsigned uint32 32 <= %temp.119), block2, block3
+
+		// CHECK: block2: # inbounds
+		// CHECK: ty:uint32[4] %temp.122 =  [  ]
+		// CHECK: memcpy src: %buffer, dest: %temp.122, bytes_len: uint32 16
+		// CHECK: ty:struct Testing.noPadStruct[2] %temp.123 =  [  ]
+		// CHECK: memcpy src: (advance ptr: %buffer, by: uint32 16), dest: %temp.123, bytes_len: uint32 16
+		// CHECK: ty:uint32 %temp.124 = (builtin ReadFromBuffer ((arg #0), uint32 32))
+		// CHECK: branchcond (unsigned uint32 36 <= %temp.119), block4, block5
+
+		// CHECK: block3: # out_of_bounds
+		// CHECK: branch block1
+
+		// CHECK: block4: # inbounds
+		// CHECK: ty:struct Testing.noPadStruct[] %temp.125 = (alloc struct Testing.noPadStruct[] len %temp.124)
+		// CHECK: ty:uint32 %2.cse_temp = (%temp.124 * uint32 8)
+		// CHECK: ty:uint32 %1.cse_temp = (uint32 36 + %2.cse_temp)
+		// CHECK: branchcond (unsigned %1.cse_temp <= %temp.119), block6, block7
+
+		// CHECK: block5: # out_of_bounds
+		// CHECK: branch block1
+
+		// CHECK: block6: # inbounds
+		// CHECK: memcpy src: (advance ptr: %buffer, by: uint32 36), dest: %temp.125, bytes_len: %2.cse_temp
+		// CHECK: branchcond (unsigned less %failed_offset.temp.121 < %temp.119), block8, block9
+
+		// CHECK: block7: # out_of_bounds
+		// CHECK: branch block1
+
+		// CHECK: block8: # not_all_bytes_read
+		// CHECK: branch block1
+
+		// CHECK: block9: # buffer_read
+		// CHECK: ty:uint32[4] %a = %temp.122
+		// CHECK: ty:struct Testing.noPadStruct[2] %b = %temp.123
+		// CHECK: ty:struct Testing.noPadStruct[] %c = %temp.125
 
         return (a, b, c);
     }
@@ -156,15 +156,13 @@ contract EncodingTest {
         bytes memory b1 = abi.encode(test_vec_1, mem_vec, str_vec);
         // CHECK: %temp.66 = load storage slot(uint32 16) ty:struct EncodingTest.noPadStruct[]
 	    // CHECK: ty:uint32 %temp.67 = ((builtin ArrayLength (%temp.66)) * uint32 8)
-	    // CHECK: ty:uint32 %temp.68 = uint32 16
-	    // CHECK: ty:uint32 %temp.69 = uint32 16
-	    // CHECK: ty:bytes %abi_encoded.temp.70 = (alloc bytes len (((%temp.67 + uint32 4) + uint32 16) + uint32 16))
-	    // CHECK: ty:uint32 %temp.71 = (builtin ArrayLength (%temp.66))
-	    // CHECK: writebuffer buffer:%abi_encoded.temp.70 offset:uint32 0 value:%temp.71
-	    // CHECK: memcpy src: %temp.66, dest: (advance ptr: %abi_encoded.temp.70, by: uint32 4), bytes_len: (%temp.71 * uint32 8)
-	    // CHECK: memcpy src: %mem_vec, dest: (advance ptr: %abi_encoded.temp.70, by: (uint32 0 + ((%temp.71 * uint32 8) + uint32 4))), bytes_len: uint32 16
-	    // CHECK: memcpy src: %str_vec, dest: (advance ptr: %abi_encoded.temp.70, by: ((uint32 0 + ((%temp.71 * uint32 8) + uint32 4)) + uint32 16)), bytes_len: uint32 16
-	    // CHECK: ty:bytes %b1 = %abi_encoded.temp.70
+	    // CHECK: ty:bytes %abi_encoded.temp.68 = (alloc bytes len (uint32 32 + (%temp.67 + uint32 4)))
+	    // CHECK: ty:uint32 %temp.69 = (builtin ArrayLength (%temp.66))
+	    // CHECK: writebuffer buffer:%abi_encoded.temp.68 offset:uint32 0 value:%temp.69
+	    // CHECK: memcpy src: %temp.66, dest: (advance ptr: %abi_encoded.temp.68, by: uint32 4), bytes_len: (%temp.69 * uint32 8)
+	    // CHECK: memcpy src: %mem_vec, dest: (advance ptr: %abi_encoded.temp.68, by: (uint32 0 + ((%temp.69 * uint32 8) + uint32 4))), bytes_len: uint32 16
+	    // CHECK: memcpy src: %str_vec, dest: (advance ptr: %abi_encoded.temp.68, by: ((uint32 0 + ((%temp.69 * uint32 8) + uint32 4)) + uint32 16)), bytes_len: uint32 16
+	    // CHECK: ty:bytes %b1 = %abi_encoded.temp.68
 
         return b1;
     }
@@ -178,10 +176,10 @@ contract EncodingTest {
         function (int64, int64) external returns (int64) fPtr = this.doThis;
         uint64 pr = 9234;
 
-        // CHECK: ty:bytes %abi_encoded.temp.74 = (alloc bytes len uint32 48)
-	    // CHECK: writebuffer buffer:%abi_encoded.temp.74 offset:uint32 0 value:(load (struct %fPtr field 0))
-	    // CHECK: writebuffer buffer:%abi_encoded.temp.74 offset:uint32 8 value:(load (struct %fPtr field 1))
-	    // CHECK: writebuffer buffer:%abi_encoded.temp.74 offset:uint32 40 value:uint64 9234
+        // CHECK: ty:bytes %abi_encoded.temp.72 = (alloc bytes len uint32 48)
+	    // CHECK: writebuffer buffer:%abi_encoded.temp.72 offset:uint32 0 value:(load (struct %fPtr field 0))
+	    // CHECK: writebuffer buffer:%abi_encoded.temp.72 offset:uint32 8 value:(load (struct %fPtr field 1))
+	    // CHECK: writebuffer buffer:%abi_encoded.temp.72 offset:uint32 40 value:uint64 9234
 
         bytes memory b = abi.encode(fPtr, pr);
         return b;
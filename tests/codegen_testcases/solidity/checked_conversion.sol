@@ -0,0 +1,36 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    // BEGIN-CHECK: c::c::function::narrow__int256
+    function narrow(int256 x) public pure returns (uint64) {
+        // CHECK: >= int256 18446744073709551616), block1, block2
+        // CHECK: < int256 0), block1, block3
+        // CHECK: (trunc uint64
+        return toUint64(x);
+    }
+
+    // BEGIN-CHECK: c::c::function::signed__int256
+    function signed(int256 x) public pure returns (int32) {
+        // CHECK: >= int256 2147483648), block1, block2
+        // CHECK: < int256 -2147483648), block1, block3
+        // CHECK: (trunc int32
+        return toInt32(x);
+    }
+
+    // BEGIN-CHECK: c::c::function::sign__uint8
+    function sign(uint8 x) public pure returns (int8) {
+        // CHECK: >= uint8 128), block1, block2
+        return toInt8(x);
+    }
+
+    // BEGIN-CHECK: c::c::function::widen__uint32
+    function widen(uint32 x) public pure returns (int64) {
+        // CHECK: (zext int64 (arg #0))
+        return toInt64(x);
+    }
+
+    // BEGIN-CHECK: c::c::function::literal
+    function literal() public pure returns (uint16) {
+        // CHECK: return uint16 300
+        return toUint16(300);
+    }
+}
@@ -0,0 +1,16 @@
+// RUN: --target solana --emit llvm-ir
+// READ: Vault.ll
+contract Vault {
+    uint64 limit;
+
+    function deposit(uint64 amount) public {
+        require(amount > 0, "invalid amount");
+        limit += amount;
+    }
+}
+
+// The branch to the revert is unlikely, and the call which sets the revert data is cold
+// BEGIN-CHECK: !prof
+// BEGIN-CHECK: !"branch_weights"
+// BEGIN-CHECK: attributes
+// CHECK: cold
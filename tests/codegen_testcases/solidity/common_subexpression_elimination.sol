@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-loop-unroll
 
 // Tests control commands
 contract c1 {
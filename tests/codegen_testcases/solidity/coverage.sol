@@ -0,0 +1,16 @@
+// RUN: --target polkadot --emit cfg --coverage
+contract Counter {
+    // BEGIN-CHECK: Counter::Counter::function::count__uint64
+    function count(uint64 a) public pure returns (uint64) {
+        // CHECK: block0: # entry
+        // CHECK: print (alloc slice bytes1 uint32 20 "coverage: Counter 0\n")
+        if (a > 10) {
+            // CHECK: block1: # then
+            // CHECK: print (alloc slice bytes1 uint32 20 "coverage: Counter 1\n")
+            return 1;
+        }
+        // CHECK: block2: # endif
+        // CHECK: print (alloc slice bytes1 uint32 20 "coverage: Counter 2\n")
+        return a;
+    }
+}
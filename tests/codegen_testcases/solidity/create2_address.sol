@@ -0,0 +1,24 @@
+// RUN: --target evm --emit cfg
+
+contract factory {
+    // BEGIN-CHECK: factory::factory::function::folded
+    function folded() public pure returns (address) {
+        // CHECK: return address 0x4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38
+        // example 1 from EIP-1014
+        return create2_address(
+            address(0),
+            0,
+            hex"bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a"
+        );
+    }
+
+    // BEGIN-CHECK: factory::factory::function::predict__bytes32_bytes32
+    function predict(bytes32 salt, bytes32 code_hash) public view returns (address) {
+        // CHECK: writebuffer buffer:%abi_encoded.temp.4 offset:uint32 0 value:bytes1 255
+        // CHECK: writebuffer buffer:%abi_encoded.temp.4 offset:uint32 1 value:address((load (builtin GetAddress ())))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.4 offset:uint32 21 value:(arg #0)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.4 offset:uint32 53 value:(arg #1)
+        // CHECK: return address((trunc uint160 uint256((builtin Keccak256 (%abi_encoded.temp.4)))))
+        return create2_address(address(this), salt, code_hash);
+    }
+}
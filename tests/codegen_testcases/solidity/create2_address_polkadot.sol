@@ -0,0 +1,14 @@
+// RUN: --target polkadot --emit cfg
+
+contract factory {
+    // BEGIN-CHECK: factory::factory::function::folded
+    function folded() public pure returns (address) {
+        // CHECK: return address 0x4ec027850a18b7ec5dc1feb555f1bfe5db577cea27c6cfbae9fb9b16d4172136
+        return create2_address(
+            address(0),
+            0,
+            hex"bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a",
+            hex"0102"
+        );
+    }
+}
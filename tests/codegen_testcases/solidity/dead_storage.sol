@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-storage-cache
 contract deadstorage {
     int a;
 
@@ -184,9 +184,9 @@ contract foo {
         S[] storage ptrArr = arr;
         ptrArr.push(S({f1: 1}));
         ptrArr.push(S({f1: 2}));
-        // CHECK: %.temp.119, %.temp.120 = call foo::foo::function::g
-        // CHECK: %temp.121 = load storage slot(%.temp.119) ty:struct foo.S
-        // CHECK: %temp.122 = load storage slot(%.temp.120) ty:struct foo.S
+        // CHECK: %.temp.140, %.temp.141 = call foo::foo::function::g
+        // CHECK: %temp.142 = load storage slot(%.temp.140) ty:struct foo.S
+        // CHECK: %temp.143 = load storage slot(%.temp.141) ty:struct foo.S
         return g();
     }
 }
@@ -0,0 +1,15 @@
+// RUN: --target solana --emit llvm-ir -g
+// READ: Counter.ll
+contract Counter {
+    function add(uint64 a, uint64 b) public pure returns (uint64) {
+        uint64 total = a + b;
+        return total * 2;
+    }
+}
+
+// BEGIN-CHECK: !DISubprogram(name: "add", linkageName: "Counter::Counter::function::add__uint64_uint64"
+// BEGIN-CHECK: !DIFile(filename: "debug_info.sol"
+// BEGIN-CHECK: !DILocalVariable(name: "total"
+// BEGIN-CHECK: line: 5, type:
+// BEGIN-CHECK: !DILocalVariable(name: "a"
+// BEGIN-CHECK: !DIBasicType(name: "uint64", size: 64, encoding: DW_ATE_unsigned)
@@ -0,0 +1,41 @@
+// RUN: --target polkadot --emit cfg
+library L {
+    function add(uint a, uint b) internal pure returns (uint) {
+        return a + b;
+    }
+
+    function mul(uint a, uint b) internal pure returns (uint) {
+        return a * b;
+    }
+}
+
+contract C {
+    // BEGIN-CHECK: C::C::function::single__uint256
+    function single(uint x) public pure returns (uint) {
+        function(uint, uint) internal pure returns (uint) op = L.add;
+        // CHECK: = call C::L::function::add__uint256_uint256 (arg #0), uint256 1
+        return op(x, 1);
+    }
+
+    // BEGIN-CHECK: C::C::function::same__uint256_bool
+    function same(uint x, bool b) public pure returns (uint) {
+        function(uint, uint) internal pure returns (uint) op;
+        if (b) {
+            op = L.add;
+        } else {
+            op = L.add;
+        }
+        // CHECK: = call C::L::function::add__uint256_uint256 (arg #0), uint256 2
+        return op(x, 2);
+    }
+
+    // BEGIN-CHECK: C::C::function::either__uint256_bool
+    function either(uint x, bool b) public pure returns (uint) {
+        function(uint, uint) internal pure returns (uint) op = L.mul;
+        if (b) {
+            op = L.add;
+        }
+        // CHECK: = call %op (arg #0), uint256 3
+        return op(x, 3);
+    }
+}
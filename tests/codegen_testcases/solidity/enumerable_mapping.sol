@@ -0,0 +1,41 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    @enumerable(true)
+    mapping(address => uint64) balances;
+
+    // BEGIN-CHECK: c::c::function::set__address_uint64
+    function set(address a, uint64 v) public {
+        // CHECK: %temp.6 = load storage slot((keccak256 uint256 2, (arg #0))) ty:uint256
+        // CHECK: branchcond (%temp.6 == uint256 0), block1, block2
+        // CHECK: block1: # enumerable_insert
+        // CHECK: ty:uint256 %1.storage_cache = (overflowing %temp.7 + uint256 1)
+        // CHECK: store storage slot(uint256 1) ty:uint256 = %1.storage_cache
+        // CHECK: store storage slot((keccak256 uint256 2, (arg #0))) ty:uint256 = %temp.11
+        // CHECK: block2: # enumerable_done
+        // CHECK: store storage slot((keccak256 uint256 0, (arg #0))) ty:uint64 = (arg #1)
+        balances[a] = v;
+    }
+
+    // BEGIN-CHECK: c::c::function::remove__address
+    function remove(address a) public {
+        // CHECK: branchcond (%position.temp.15 == uint256 0), block2, block1
+        // CHECK: block1: # enumerable_remove
+        // CHECK: block2: # enumerable_done
+        // CHECK: clear storage slot((keccak256 uint256 0, (arg #0))) ty:uint64
+        // CHECK: store storage slot((keccak256 uint256 2, %last_key.temp.19)) ty:uint256 = %temp.27
+        // CHECK: clear storage slot((keccak256 uint256 2, (arg #0))) ty:uint256
+        delete balances[a];
+    }
+
+    // BEGIN-CHECK: c::c::function::count
+    function count() public view returns (uint) {
+        // CHECK: %temp.33 = load storage slot(uint256 1) ty:uint256
+        return balances.length;
+    }
+
+    // BEGIN-CHECK: c::c::function::all
+    function all() public view returns (address[] memory) {
+        // CHECK: %temp.34 = load storage slot(uint256 1) ty:address[]
+        return balances.keys();
+    }
+}
@@ -0,0 +1,27 @@
+// RUN: --target polkadot --emit cfg
+
+contract bridge {
+    // BEGIN-CHECK: bridge::bridge::function::encode__uint64_address_bytes
+    function encode(uint64 nonce, address to, bytes payload) public pure returns (bytes) {
+        // CHECK: ty:uint32 %temp.8 = (builtin ArrayLength ((arg #2)))
+        // CHECK: ty:bytes %abi_encoded.temp.9 = (alloc bytes len (uint32 128 + ((%temp.8 + uint32 31) & uint32 4294967264)))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 0 value:bytes32((zext uint256 (arg #0)))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 32 value:(arg #1)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 64 value:bytes32(uint256 96)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 96 value:bytes32((zext uint256 %temp.8))
+        // CHECK: memcpy src: (arg #2), dest: (advance ptr: %abi_encoded.temp.9, by: uint32 128), bytes_len: %temp.8
+        return abi.ethEncode(nonce, to, payload);
+    }
+
+    // BEGIN-CHECK: bridge::bridge::function::decode__bytes
+    function decode(bytes data) public pure returns (int32, bool, string) {
+        // CHECK: branchcond (unsigned uint32 96 <= %temp.11), block2, block3
+        // CHECK: ty:int32 %temp.14 = (trunc int32 int256((builtin ReadFromBuffer ((arg #0), uint32 0))))
+        // CHECK: ty:bool %temp.15 = (builtin ReadFromBuffer ((arg #0), uint32 63))
+        // CHECK: ty:uint32 %temp.16 = uint32((builtin ReadFromBuffer ((arg #0), uint32 92)))
+        // CHECK: ty:uint32 %temp.17 = uint32((builtin ReadFromBuffer ((arg #0), (%temp.16 + uint32 28))))
+        // CHECK: ty:string %temp.18 = (alloc string len %temp.17)
+        // CHECK: return %temp.14, %temp.15, %temp.18
+        return abi.ethDecode(data, (int32, bool, string));
+    }
+}
@@ -0,0 +1,27 @@
+// RUN: --target evm --emit cfg
+
+// As in Solidity, receive() is only called when there is no call data. A non-payable fallback
+// reverts when it receives value.
+contract both {
+	// BEGIN-CHECK: # function polkadot_call_dispatch public:false selector: nonpayable:false
+	// CHECK: block2: # fb_or_recv
+	// CHECK: 	branchcond ((arg #1) == uint32 0), block6, block7
+	// CHECK: block6: # receive
+	// CHECK: 	 = call both::both::receive 
+	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
+	// CHECK: block7: # fallback
+	// CHECK: 	branchcond (unsigned more (arg #2) > uint256 0), block8, block9
+	// CHECK: block8: # func_1_got_value
+	// CHECK: 	assert-failure
+	// CHECK: block9: # func_1_no_value
+	// CHECK: 	%fallback_output.temp.15 = call both::both::fallback (builtin Calldata ())
+	// CHECK: 	return data %fallback_output.temp.15, data length: (builtin ArrayLength (%fallback_output.temp.15))
+	uint32 public calls;
+
+	fallback(bytes calldata input) external returns (bytes memory) {
+		calls += 1;
+		return input;
+	}
+
+	receive() external payable {}
+}
@@ -0,0 +1,20 @@
+// RUN: --target polkadot --emit cfg
+
+// The fallback gets the raw call data, and its output is returned without ABI encoding it
+contract echo {
+	// BEGIN-CHECK: # fallback echo::echo::fallback public:true selector:24a3f96a nonpayable:false
+	// CHECK: # params: bytes input
+	// CHECK: # returns: bytes
+	// CHECK: return (arg #0)
+
+	// BEGIN-CHECK: # function polkadot_call_dispatch public:false selector: nonpayable:false
+	// CHECK: block2: # fb_or_recv
+	// CHECK: 	%fallback_output.temp.15 = call echo::echo::fallback (builtin Calldata ())
+	// CHECK: 	return data %fallback_output.temp.15, data length: (builtin ArrayLength (%fallback_output.temp.15))
+	uint32 public calls;
+
+	fallback(bytes calldata input) external payable returns (bytes memory) {
+		calls += 1;
+		return input;
+	}
+}
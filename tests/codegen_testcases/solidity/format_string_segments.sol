@@ -0,0 +1,17 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    // BEGIN-CHECK: c::c::function::constant_args
+    function constant_args() public pure {
+        // "print: flag true count 42 hex -0x2a bin 0b101,\n"
+        // CHECK: print (format string ( hex"7072696e743a20666c6167207472756520636f756e7420343220686578202d307832612062696e2030623130312c0a"))
+        print("flag {} count {} hex {:x} bin {:b}".format(true, 42, int8(-42), uint8(5)));
+    }
+
+    // BEGIN-CHECK: c::c::function::folded_args
+    function folded_args(uint64 a) public pure {
+        uint64 b = 100;
+        // "print: a=", a, " b=100,\n"
+        // CHECK: print (format string ( hex"7072696e743a20613d"), ( (arg #0)), ( hex"20623d3130302c0a"))
+        print("a={} b={}".format(a, b));
+    }
+}
@@ -0,0 +1,39 @@
+// RUN: --target solana --emit cfg
+
+contract Combine {
+    // BEGIN-CHECK: Combine::Combine::function::f
+    function f(uint64 a, uint64 b) public pure returns (uint64) {
+        // CHECK: ty:uint64 %b = (arg #1)
+        // NOT-CHECK: ty:bool %small
+        // CHECK: branchcond (unsigned less (arg #0) < (arg #1)), block1, block2
+        bool small = a < b;
+        if (small) {
+            return a;
+        }
+
+        // CHECK: branchcond ((arg #0) == uint64 3), block4, block3
+        if (!(a == 3)) {
+            return 7;
+        }
+
+        // CHECK: branchcond (unsigned more %a > uint64 10), block7, block6
+        while (!(a > 10)) {
+            a += b;
+        }
+
+        return b;
+    }
+
+    // BEGIN-CHECK: Combine::Combine::function::g
+    function g(uint64 a, uint64 b) public pure returns (bool) {
+        // the comparison is used again, so it is kept in a variable
+        // CHECK: ty:bool %small = (unsigned less (arg #0) < (arg #1))
+        // CHECK: branchcond %small, block1, block2
+        bool small = a < b;
+        if (small) {
+            return small;
+        }
+
+        return false;
+    }
+}
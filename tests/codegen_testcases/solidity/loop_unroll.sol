@@ -0,0 +1,34 @@
+// RUN: --target solana --emit cfg
+
+contract Unroll {
+    // BEGIN-CHECK: Unroll::Unroll::function::seeds
+    function seeds(bytes32 seed) public pure returns (bytes32) {
+        // CHECK: writebuffer buffer:%abi_encoded.temp.7 offset:uint32 32 value:uint8 0
+        // CHECK: block4: # endfor
+        // CHECK: return %seed
+        // CHECK: writebuffer buffer:%abi_encoded.temp.7 offset:uint32 32 value:uint8 1
+        // CHECK: writebuffer buffer:%abi_encoded.temp.7 offset:uint32 32 value:uint8 2
+        // CHECK: branch block4
+        for (uint8 i = 0; i < 3; i++) {
+            seed = keccak256(abi.encode(seed, i));
+        }
+        return seed;
+    }
+
+    // BEGIN-CHECK: Unroll::Unroll::function::too_many
+    function too_many() public pure returns (uint64 sum) {
+        // CHECK: branchcond (unsigned less %i < uint64 17), block1, block4
+        for (uint64 i = 0; i < 17; i++) {
+            sum += i;
+        }
+    }
+
+    // BEGIN-CHECK: Unroll::Unroll::function::tagged
+    /// @custom:unroll 17
+    function tagged() public pure returns (uint64 sum) {
+        // CHECK-ABSENT: branchcond (unsigned less %i < uint64 17)
+        for (uint64 i = 0; i < 17; i++) {
+            sum += i;
+        }
+    }
+}
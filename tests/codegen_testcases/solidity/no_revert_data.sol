@@ -0,0 +1,28 @@
+// RUN: --target polkadot --emit cfg --no-revert-data
+contract c {
+    // BEGIN-CHECK: c::c::function::get__uint256
+    function get(uint256 i) public pure returns (uint64) {
+        uint64[] memory a = new uint64[](2);
+        assert(i != 7);
+        require(i != 8, "eight");
+        return a[i];
+    }
+    // The Panic(uint256) selector
+    // CHECK-ABSENT: 4e487b71
+
+    // CHECK: block2: # doassert
+    // CHECK: assert-failure
+    // CHECK: block4: # doassert
+    // CHECK: assert-failure: buffer: (alloc slice bytes1 uint32 10 hex"08c379a0146569676874")
+    // CHECK: block5: # out_of_bounds
+    // CHECK-ABSENT: assert-failure: buffer
+
+    // BEGIN-CHECK: c::c::function::decode__bytes
+    function decode(bytes memory data) public pure returns (uint64) {
+        return abi.decode(data, (uint64));
+    }
+    // CHECK: block1: # decoding_failed
+    // CHECK: print (format string ( hex"72756e74696d655f6572726f723a20"), ( %decoding_error
+    // CHECK: assert-failure
+    // CHECK-ABSENT: assert-failure: buffer
+}
@@ -0,0 +1,51 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    uint64 total;
+
+    modifier positive(uint64 a) {
+        require(a > 0);
+        _;
+    }
+
+    // BEGIN-CHECK: c::c::function::add__uint64
+    // CHECK: %reentrancy_guard.temp.6 = load storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: branchcond %reentrancy_guard.temp.6, block1, block2
+    // CHECK: block1: # reentered
+    // CHECK: assert-failure: buffer: (alloc slice bytes1 uint32 19 hex"08c379a0387265656e7472616e742063616c6c")
+    // CHECK: block3: # then
+    // CHECK: clear storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: return uint64 0
+    // CHECK: block4: # endif
+    // CHECK: clear storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: return %temp.9
+    @nonreentrant(true)
+    function add(uint64 a) public returns (uint64) {
+        if (a > 100) {
+            return 0;
+        }
+        total += a;
+        return total;
+    }
+
+    // The guard is checked before the modifier runs
+    // BEGIN-CHECK: c::c::call__address_uint64::modifier0::positive__uint64
+    // CHECK: branchcond %reentrancy_guard.temp.13, block1, block2
+    // CHECK: block2: # guarded
+    // CHECK: store storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool = true
+    // CHECK: branchcond (unsigned more (arg #1) > uint64 0), block3, block4
+    // CHECK: = call c::c::function::call__address_uint64 (arg #0), (arg #1)
+    // CHECK: clear storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: return
+    @nonreentrant(true)
+    function call(address payable to, uint64 a) public positive(a) {
+        to.transfer(a);
+        total = 0;
+    }
+
+    // BEGIN-CHECK: c::c::function::get
+    // CHECK-ABSENT: slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053)
+    @nonreentrant(false)
+    function get() public view returns (uint64) {
+        return total;
+    }
+}
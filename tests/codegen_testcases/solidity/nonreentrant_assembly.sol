@@ -0,0 +1,35 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    uint64 total;
+
+    modifier positive(uint64 a) {
+        require(a > 0);
+        _;
+    }
+
+    // BEGIN-CHECK: c::c::function::stop__uint64
+    // CHECK: block2: # guarded
+    // CHECK: store storage slot(uint256 0) ty:uint64 = (arg #0)
+    // CHECK: clear storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: return data
+    @nonreentrant(true)
+    function stop(uint64 a) public {
+        total = a;
+        assembly {
+            return(0, 0)
+        }
+    }
+
+    // The guard is set by the modifier, but returning from assembly ends the call
+    // BEGIN-CHECK: c::c::function::stopModified__uint64
+    // CHECK: store storage slot(uint256 0) ty:uint64 = (arg #0)
+    // CHECK: clear storage slot(uint256 33121977748879207429973189780605692659696309702257851030481335531950249328053) ty:bool
+    // CHECK: return data
+    @nonreentrant(true)
+    function stopModified(uint64 a) public positive(a) {
+        total = a;
+        assembly {
+            return(0, 0)
+        }
+    }
+}
@@ -0,0 +1,12 @@
+// RUN: --target polkadot --emit cfg
+import "polkadot";
+
+contract CodeHashDelegate {
+    function delegate_call(Hash _code_hash, uint32 _flags) public returns (bytes ret) {
+        (bool ok, ret) = _code_hash.delegatecall{flags: _flags}(hex"deadbeef");
+        // CHECK: ty:usertype Hash %_code_hash = (arg #0)
+        // CHECK: %success.temp.4 = external call::delegate address:(arg #0) payload:(alloc bytes uint32 4 hex"deadbeef") value:uint128 0 gas:uint64 0 accounts: seeds: contract|function:_ flags:(arg #1)
+        // CHECK: ty:bytes %ret = (external call return data)
+        require(ok);
+    }
+}
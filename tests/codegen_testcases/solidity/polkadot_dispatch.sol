@@ -33,13 +33,15 @@ contract has_fallback_and_receive {
 	// CHECK: 	switch %selector.temp.8:
 	// CHECK: 		default: goto block #2
 	// CHECK: block2: # fb_or_recv
-	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block4, block3
-	// CHECK: block3: # fallback
-	// CHECK: 	 = call has_fallback_and_receive::has_fallback_and_receive::fallback 
-	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
-	// CHECK: block4: # receive
+	// CHECK: 	branchcond ((arg #1) == uint32 0), block3, block4
+	// CHECK: block3: # receive
 	// CHECK: 	 = call has_fallback_and_receive::has_fallback_and_receive::receive 
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
+	// CHECK: block4: # has_input
+	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block3, block5
+	// CHECK: block5: # fallback
+	// CHECK: 	 = call has_fallback_and_receive::has_fallback_and_receive::fallback 
+	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
 
 	fallback() external {}
 	receive() payable external {}
@@ -80,12 +82,12 @@ contract has_fallback {
 	// CHECK: 	switch %selector.temp.16:
 	// CHECK: 		default: goto block #2
 	// CHECK: block2: # fb_or_recv
-	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block4, block3
-	// CHECK: block3: # fallback
+	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block3, block4
+	// CHECK: block3: # func_0_got_value
+	// CHECK: 	assert-failure
+	// CHECK: block4: # func_0_no_value
 	// CHECK: 	 = call has_fallback::has_fallback::fallback 
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
-	// CHECK: block4: # receive
-	// CHECK: 	return code: function selector invalid
 
 	fallback() external {}
 }
@@ -127,12 +129,14 @@ contract has_receive {
 	// CHECK: 	switch %selector.temp.24:
 	// CHECK: 		default: goto block #2
 	// CHECK: block2: # fb_or_recv
-	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block4, block3
-	// CHECK: block3: # fallback
-	// CHECK: 	return code: function selector invalid
-	// CHECK: block4: # receive
+	// CHECK: 	branchcond ((arg #1) == uint32 0), block3, block4
+	// CHECK: block3: # receive
 	// CHECK: 	 = call has_receive::has_receive::receive 
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
+	// CHECK: block4: # has_input
+	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block3, block5
+	// CHECK: block5: # fallback
+	// CHECK: 	return code: function selector invalid
 
 	receive() payable external {}
 }
@@ -227,14 +231,14 @@ contract overloaded {
 	// CHECK: 	ty:buffer_pointer %input_ptr.temp.39 = (arg #0)
 	// CHECK: 	branchcond (unsigned less (arg #1) < uint32 4), block2, block1
 	// CHECK: block1: # start_dispatch
-	// CHECK: 	ty:uint32 %selector.temp.42 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	// CHECK: 	store (arg #3), %selector.temp.42
-	// CHECK: 	switch %selector.temp.42:
+	// CHECK: 	ty:uint32 %selector.temp.49 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	// CHECK: 	store (arg #3), %selector.temp.49
+	// CHECK: 	switch %selector.temp.49:
 	// CHECK: 		case uint32 4028568102: goto block #3
 	// CHECK: 		case uint32 2338643635: goto block #4
 	// CHECK: 		default: goto block #2
 	// CHECK: block2: # fb_or_recv
-	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block12, block11
+	// CHECK: 	branchcond ((arg #1) == uint32 0), block26, block27
 	// CHECK: block3: # func_2_dispatch
 	// CHECK: 	 = call overloaded::overloaded::function::f 
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
@@ -243,23 +247,25 @@ contract overloaded {
 	// CHECK: block5: # func_3_got_value
 	// CHECK: 	assert-failure
 	// CHECK: block6: # func_3_no_value
-	// CHECK: 	branchcond (unsigned uint32 32 <= (trunc uint32 ((arg #1) - uint32 4))), block7, block8
-	// CHECK: block7: # inbounds
-	// CHECK: 	ty:uint256 %temp.41 = (builtin ReadFromBuffer ((advance ptr: %input_ptr.temp.39, by: uint32 4), uint32 0))
-	// CHECK: 	branchcond (unsigned less uint32 32 < (trunc uint32 ((arg #1) - uint32 4))), block9, block10
-	// CHECK: block8: # out_of_bounds
-	// CHECK: 	assert-failure
-	// CHECK: block9: # not_all_bytes_read
-	// CHECK: 	assert-failure
-	// CHECK: block10: # buffer_read
-	// CHECK: 	 = call overloaded::overloaded::function::f__uint256 %temp.41
+	// CHECK: 	branchcond (unsigned uint32 32 <= (trunc uint32 ((arg #1) - uint32 4))), block8, block9
+	// CHECK: block8: # inbounds
+	// CHECK: 	ty:uint256 %temp.43 = (builtin ReadFromBuffer ((advance ptr: %input_ptr.temp.39, by: uint32 4), uint32 0))
+	// CHECK: 	branchcond (unsigned less uint32 32 < (trunc uint32 ((arg #1) - uint32 4))), block10, block11
+	// CHECK: block9: # out_of_bounds
+	// CHECK: 	branch block7
+	// CHECK: block10: # not_all_bytes_read
+	// CHECK: 	branch block7
+	// CHECK: block11: # buffer_read
+	// CHECK: 	 = call overloaded::overloaded::function::f__uint256 %temp.43
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
-	// CHECK: block11: # fallback
-	// CHECK: 	 = call overloaded::overloaded::fallback 
-	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
-	// CHECK: block12: # receive
+	// CHECK: block26: # receive
 	// CHECK: 	 = call overloaded::overloaded::receive 
 	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
+	// CHECK: block27: # has_input
+	// CHECK: 	branchcond (unsigned more (arg #2) > uint128 0), block26, block28
+	// CHECK: block28: # fallback
+	// CHECK: 	 = call overloaded::overloaded::fallback 
+	// CHECK: 	return data (alloc bytes len uint32 0), data length: uint32 0
 
 	constructor foo() payable {}
 	constructor bar() payable {}
@@ -276,14 +282,14 @@ contract simple {
 	// CHECK: # params: buffer_pointer,uint32,uint128,uint32
 	// CHECK: # returns: 
 	// CHECK: block0: # entry
-	// CHECK: 	ty:uint32 %input_len.temp.43 = (arg #1)
-	// CHECK: 	ty:uint128 %value.temp.44 = (arg #2)
-	// CHECK: 	ty:buffer_pointer %input_ptr.temp.45 = (arg #0)
+	// CHECK: 	ty:uint32 %input_len.temp.50 = (arg #1)
+	// CHECK: 	ty:uint128 %value.temp.51 = (arg #2)
+	// CHECK: 	ty:buffer_pointer %input_ptr.temp.52 = (arg #0)
 	// CHECK: 	branchcond (unsigned less (arg #1) < uint32 4), block2, block1
 	// CHECK: block1: # start_dispatch
-	// CHECK: 	ty:uint32 %selector.temp.46 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	// CHECK: 	store (arg #3), %selector.temp.46
-	// CHECK: 	switch %selector.temp.46:
+	// CHECK: 	ty:uint32 %selector.temp.53 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	// CHECK: 	store (arg #3), %selector.temp.53
+	// CHECK: 	switch %selector.temp.53:
 	// CHECK: 		case uint32 3576764294: goto block #3
 	// NOT-CHECK: 	case uint32 2018875586: goto block #3
 	// CHECK: 		default: goto block #2
@@ -297,14 +303,14 @@ contract simple {
 	// CHECK: # params: buffer_pointer,uint32,uint128,uint32
 	// CHECK: # returns: 
 	// CHECK: block0: # entry
-	// CHECK: 	ty:uint32 %input_len.temp.47 = (arg #1)
-	// CHECK: 	ty:uint128 %value.temp.48 = (arg #2)
-	// CHECK: 	ty:buffer_pointer %input_ptr.temp.49 = (arg #0)
+	// CHECK: 	ty:uint32 %input_len.temp.54 = (arg #1)
+	// CHECK: 	ty:uint128 %value.temp.55 = (arg #2)
+	// CHECK: 	ty:buffer_pointer %input_ptr.temp.56 = (arg #0)
 	// CHECK: 	branchcond (unsigned less (arg #1) < uint32 4), block2, block1
 	// CHECK: block1: # start_dispatch
-	// CHECK: 	ty:uint32 %selector.temp.50 = (builtin ReadFromBuffer ((arg #0), uint32 0))
-	// CHECK: 	store (arg #3), %selector.temp.50
-	// CHECK: 	switch %selector.temp.50:
+	// CHECK: 	ty:uint32 %selector.temp.57 = (builtin ReadFromBuffer ((arg #0), uint32 0))
+	// CHECK: 	store (arg #3), %selector.temp.57
+	// CHECK: 	switch %selector.temp.57:
 	// CHECK: 		case uint32 2018875586: goto block #3
 	// NOT-CHECK: 	case uint32 3576764294: goto block #3
 	// CHECK: 		default: goto block #2
@@ -326,7 +332,7 @@ contract nonpayableConstructor {
 	
 	// CHECK: # function polkadot_deploy_dispatch public:false selector: nonpayable:false
 
-	// CHECK: switch %selector.temp.54:
+	// CHECK: switch %selector.temp.61:
     // CHECK: case uint32 2371928013: goto block #3
 
 	// CHECK: block3: # func_0_dispatch
@@ -0,0 +1,23 @@
+// RUN: --target polkadot --emit cfg
+
+contract a {
+    event E(string indexed s, bool indexed b, uint256 indexed n);
+
+    // BEGIN-CHECK: a::a::function::emit_event__string
+    function emit_event(string s) public {
+        // CHECK: ty:bytes %temp.6 = (builtin Concat ((alloc slice bytes1 uint32 8 "\u{1c}a::E::s"), %abi_encoded.temp.3))
+        // CHECK: ty:bytes %temp.7 = (alloc bytes len uint32 32)
+        // CHECK: branchcond (unsigned more (builtin ArrayLength (%temp.6)) > uint32 32), block15, block16
+        // CHECK: block15: # hash_topic
+        // CHECK: writebuffer buffer:%temp.7 offset:uint32 0 value:(builtin Blake2_256 (%temp.6))
+        // CHECK: block16: # pad_topic
+        // CHECK: memcpy src: %temp.6, dest: %temp.7, bytes_len: (builtin ArrayLength (%temp.6))
+        // CHECK: block17: # done
+        // CHECK: ty:bytes %temp.10 = (builtin Concat ((alloc slice bytes1 uint32 8 "\u{1c}a::E::b"), %abi_encoded.temp.9))
+        // CHECK: memcpy src: %temp.10, dest: %temp.11, bytes_len: (builtin ArrayLength (%temp.10))
+        // CHECK: ty:bytes %temp.14 = (builtin Concat ((alloc slice bytes1 uint32 8 "\u{1c}a::E::n"), %abi_encoded.temp.13))
+        // CHECK: writebuffer buffer:%temp.15 offset:uint32 0 value:(builtin Blake2_256 (%temp.14))
+        // CHECK: emit event a.E topics (alloc slice uint8 uint32 32 "\0a::E\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0"), %temp.7, %temp.11, %temp.15 data %abi_encoded.temp.17
+        emit E(s, true, 1);
+    }
+}
@@ -0,0 +1,46 @@
+// RUN: --target polkadot --emit cfg
+
+contract Proxy {
+    address implementation;
+
+    // BEGIN-CHECK: # fallback Proxy::Proxy::fallback
+    fallback() external payable {
+        (bool ok, bytes memory ret) = implementation.delegatecall(msg.data);
+        // CHECK: external call::delegate address:%temp.4 payload:(builtin Calldata ()) value:uint128 0
+        // CHECK: ty:bytes %ret = (external call return data)
+        uint256 size;
+        uint256 input;
+        assembly {
+            size := returndatasize()
+            input := calldatasize()
+        }
+        // CHECK: ty:uint256 %size = (zext uint256 (builtin ArrayLength ((external call return data))))
+        // CHECK: ty:uint256 %input = (zext uint256 (builtin ArrayLength ((builtin Calldata ()))))
+        if (!ok) {
+            assembly {
+                revert(add(ret, 32), mload(ret))
+            }
+        }
+        // CHECK: assert-failure: buffer: %ret
+        assembly {
+            return(add(ret, 0x20), mload(ret))
+        }
+        // CHECK: return data %ret, data length: (builtin ArrayLength (%ret))
+    }
+}
+
+contract Traced {
+    uint64 count;
+
+    // BEGIN-CHECK: Traced::Traced::function::done
+    @trace(true)
+    function done() public {
+        count += 1;
+        // CHECK: store storage slot(uint256 0) ty:uint64 =
+        assembly {
+            return(0, 0)
+        }
+        // CHECK: print (format string ( hex"74726163653a2065786974205472616365642e646f6e650a"))
+        // CHECK: return data (alloc bytes uint32 0 ""), data length: (builtin ArrayLength ((alloc bytes uint32 0 "")))
+    }
+}
@@ -0,0 +1,21 @@
+// RUN: --target polkadot --emit cfg
+import "polkadot";
+
+contract Creator {
+    // CHECK: constructor(no: ) salt: value: gas:uint64 0 address: seeds: Child encoded buffer: %abi_encoded.temp.24 accounts:  storage_deposit_limit:uint128 1000
+    function create() public returns (Child) {
+        return new Child{storage_deposit_limit: 1000}();
+    }
+}
+
+contract Child {
+    // CHECK: selfdestruct (arg #0)
+    function terminate_me(address payable beneficiary) public {
+        terminate(beneficiary);
+    }
+
+    // CHECK: return uint128((builtin MinimumBalance ()))
+    function balance_needed() public view returns (uint128) {
+        return minimum_balance();
+    }
+}
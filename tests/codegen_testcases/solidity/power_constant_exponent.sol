@@ -0,0 +1,47 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    // BEGIN-CHECK: c::c::function::cube__uint256
+    function cube(uint256 x) public pure returns (uint256) {
+        // CHECK: return (((arg #0) * (arg #0)) * (arg #0))
+        return x ** 3;
+    }
+
+    // BEGIN-CHECK: c::c::function::square__int64
+    function square(int64 x) public pure returns (int64) {
+        // CHECK: return ((arg #0) * (arg #0))
+        return x ** 2;
+    }
+
+    // BEGIN-CHECK: c::c::function::fourth__uint128
+    function fourth(uint128 x) public pure returns (uint128) {
+        unchecked {
+            // CHECK: ty:uint128 %1.cse_temp = (overflowing (arg #0) * (arg #0))
+            // CHECK: return (overflowing %1.cse_temp * %1.cse_temp)
+            return x ** 4;
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::one__int256
+    function one(int256 x) public pure returns (int256) {
+        // CHECK: return int256 1
+        return x ** 0;
+    }
+
+    // BEGIN-CHECK: c::c::function::large__uint32
+    function large(uint32 x) public pure returns (uint32) {
+        // CHECK: return ((arg #0) ** uint32 9)
+        return x ** 9;
+    }
+
+    // BEGIN-CHECK: c::c::function::variable__uint16_uint16
+    function variable(uint16 x, uint16 n) public pure returns (uint16) {
+        // CHECK: return ((arg #0) ** (arg #1))
+        return x ** n;
+    }
+
+    // BEGIN-CHECK: c::c::function::signed_variable__int64_uint64
+    function signed_variable(int64 x, uint64 n) public pure returns (int64) {
+        // CHECK: return ((arg #0) ** int64((arg #1)))
+        return x ** n;
+    }
+}
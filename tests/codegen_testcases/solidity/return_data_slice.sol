@@ -0,0 +1,32 @@
+// RUN: --target polkadot --emit cfg
+
+contract ReturnDataSlice {
+    // BEGIN-CHECK: ReturnDataSlice::ReturnDataSlice::function::decode__address
+    function decode(address a) public returns (uint64, bool) {
+        (bool ok, bytes memory ret) = a.call(abi.encodeWithSignature("get()"));
+        require(ok);
+        // the return data is only decoded, so it is not copied
+        // CHECK: ty:bytes %ret = (builtin ReturnDataSlice ())
+        return abi.decode(ret, (uint64, bool));
+    }
+
+    // BEGIN-CHECK: ReturnDataSlice::ReturnDataSlice::function::forward__address
+    function forward(address a) public returns (bytes memory) {
+        (bool ok, bytes memory ret) = a.call(abi.encodeWithSignature("get()"));
+        require(ok);
+        // the return data is returned, so it must be a vector
+        // CHECK: ty:bytes %ret = (external call return data)
+        return ret;
+    }
+
+    // BEGIN-CHECK: ReturnDataSlice::ReturnDataSlice::function::second_call__address
+    function second_call(address a) public returns (uint64) {
+        (bool ok, bytes memory ret) = a.call(abi.encodeWithSignature("get()"));
+        require(ok);
+        // the second call overwrites the return data before it is decoded
+        // CHECK: ty:bytes %ret = (external call return data)
+        (ok, ) = a.call(abi.encodeWithSignature("set()"));
+        require(ok);
+        return abi.decode(ret, (uint64));
+    }
+}
@@ -31,27 +31,27 @@ contract ExternalFunctions {
 
         // CHECK: block3: # ret_success
         // CHECK: ty:uint32 %temp.7 = (builtin ArrayLength ((external call return data)))
-        // CHECK: branchcond (unsigned uint32 8 <= %temp.7), block6, block7
+        // CHECK: branchcond (unsigned uint32 8 <= %temp.7), block7, block8
 
-        // CHECK: block6: # inbounds
-        // CHECK: ty:uint64 %temp.8 = (builtin ReadFromBuffer ((external call return data), uint32 0))
-        // CHECK: branchcond (unsigned less uint32 8 < %temp.7), block8, block9
+        // CHECK: block7: # inbounds
+        // CHECK: ty:uint64 %temp.10 = (builtin ReadFromBuffer ((external call return data), uint32 0))
+        // CHECK: branchcond (unsigned less uint32 8 < %temp.7), block9, block10
 
-        // CHECK: block7: # out_of_bounds
-        // CHECK: assert-failure
+        // CHECK: block8: # out_of_bounds
+        // CHECK: branch block6
 
-        // CHECK: block8: # not_all_bytes_read
-        // CHECK: assert-failure
+        // CHECK: block9: # not_all_bytes_read
+        // CHECK: branch block6
     }
 
     // BEGIN-CHECK: ExternalFunctions::ExternalFunctions::function::storage_callback
     function storage_callback() public {
-        // CHECK: %temp.9 = load storage slot(uint256 0) ty:function(int32) external returns (uint64)
-        // CHECK: ty:bytes %abi_encoded.temp.10 = (alloc bytes len uint32 40)
-        // CHECK: writebuffer buffer:%abi_encoded.temp.10 offset:uint32 0 value:hex"f503f5fe"
-        // CHECK: writebuffer buffer:%abi_encoded.temp.10 offset:uint32 4 value:(load (struct function(int32) external returns (uint64)(%temp.9) field 1))
-        // CHECK: writebuffer buffer:%abi_encoded.temp.10 offset:uint32 36 value:(load (struct function(int32) external returns (uint64)(%temp.9) field 0))
-        // CHECK: external call::regular address:(load (builtin GetAddress ())) payload:%abi_encoded.temp.10 value:uint128 0 gas:uint64 0 accounts: seeds:
+        // CHECK: %temp.16 = load storage slot(uint256 0) ty:function(int32) external returns (uint64)
+        // CHECK: ty:bytes %abi_encoded.temp.17 = (alloc bytes len uint32 40)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.17 offset:uint32 0 value:hex"f503f5fe"
+        // CHECK: writebuffer buffer:%abi_encoded.temp.17 offset:uint32 4 value:(load (struct function(int32) external returns (uint64)(%temp.16) field 1))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.17 offset:uint32 36 value:(load (struct function(int32) external returns (uint64)(%temp.16) field 0))
+        // CHECK: external call::regular address:(load (builtin GetAddress ())) payload:%abi_encoded.temp.17 value:uint128 0 gas:uint64 0 accounts: seeds:
         this.bar(func);
     }
 }
@@ -65,24 +65,24 @@ contract CompactEncoding {
         // CHECK: branchcond (unsigned more (builtin ArrayLength ((arg #0))) > uint32 1073741823), block6, block7
 
         // CHECK: block1: # small
-        // CHECK: ty:uint32 %temp.25 = uint32 1
+        // CHECK: ty:uint32 %temp.46 = uint32 1
         // CHECK: branch block5
 
         // CHECK: block2: # medium
-        // CHECK: ty:uint32 %temp.25 = uint32 2
+        // CHECK: ty:uint32 %temp.46 = uint32 2
         // CHECK: branch block5
 
         // CHECK: block3: # medium_or_big
         // CHECK: branchcond (unsigned more (builtin ArrayLength ((arg #0))) > uint32 16383), block4, block2
 
         // CHECK: block4: # big
-        // CHECK: ty:uint32 %temp.25 = uint32 4
+        // CHECK: ty:uint32 %temp.46 = uint32 4
         // CHECK: branch block5
 
         // CHECK: block5: # done
-        // CHECK: ty:bytes %abi_encoded.temp.26 = (alloc bytes len (%temp.25 + (builtin ArrayLength ((arg #0)))))
-        // CHECK: ty:uint32 %temp.27 = (builtin ArrayLength ((arg #0)))
-        // CHECK: branchcond (unsigned more %temp.27 > uint32 1073741823), block13, block14
+        // CHECK: ty:bytes %abi_encoded.temp.47 = (alloc bytes len (%temp.46 + (builtin ArrayLength ((arg #0)))))
+        // CHECK: ty:uint32 %temp.48 = (builtin ArrayLength ((arg #0)))
+        // CHECK: branchcond (unsigned more %temp.48 > uint32 1073741823), block13, block14
 
         // CHECK: block6: # fail
         // CHECK: assert-failure
@@ -91,76 +91,76 @@ contract CompactEncoding {
         // CHECK: branchcond (unsigned more (builtin ArrayLength ((arg #0))) > uint32 63), block3, block1
 
         // CHECK: block8: # small
-        // CHECK: writebuffer buffer:%abi_encoded.temp.26 offset:uint32 0 value:(trunc uint8 (%temp.27 * uint32 4))
-        // CHECK: ty:uint32 %temp.28 = uint32 1
+        // CHECK: writebuffer buffer:%abi_encoded.temp.47 offset:uint32 0 value:(trunc uint8 (%temp.48 * uint32 4))
+        // CHECK: ty:uint32 %temp.49 = uint32 1
         // CHECK: branch block12
 
         // CHECK: block9: # medium
-        // CHECK: writebuffer buffer:%abi_encoded.temp.26 offset:uint32 0 value:(trunc uint16 ((%temp.27 * uint32 4) | uint32 1))
-        // CHECK: ty:uint32 %temp.28 = uint32 2
+        // CHECK: writebuffer buffer:%abi_encoded.temp.47 offset:uint32 0 value:(trunc uint16 ((%temp.48 * uint32 4) | uint32 1))
+        // CHECK: ty:uint32 %temp.49 = uint32 2
         // CHECK: branch block12
 
         // CHECK: block10: # medium_or_big
-        // CHECK: branchcond (unsigned more %temp.27 > uint32 16383), block11, block9
+        // CHECK: branchcond (unsigned more %temp.48 > uint32 16383), block11, block9
 
         // CHECK: block11: # big
-        // CHECK: writebuffer buffer:%abi_encoded.temp.26 offset:uint32 0 value:((%temp.27 * uint32 4) | uint32 2)
-        // CHECK: ty:uint32 %temp.28 = uint32 4
+        // CHECK: writebuffer buffer:%abi_encoded.temp.47 offset:uint32 0 value:((%temp.48 * uint32 4) | uint32 2)
+        // CHECK: ty:uint32 %temp.49 = uint32 4
         // CHECK: branch block12
 
         // CHECK: block12: # done
-        // CHECK: memcpy src: (arg #0), dest: (advance ptr: %abi_encoded.temp.26, by: (uint32 0 + %temp.28)), bytes_len: %temp.27
-        // CHECK: ty:bytes %enc = %abi_encoded.temp.26
-        // CHECK: ty:uint32 %temp.29 = (builtin ArrayLength (%enc))
-        // CHECK: ty:uint32 %temp.31 = (zext uint32 (builtin ReadFromBuffer (%enc, uint32 0)))
-        // CHECK: switch (%temp.31 & uint32 3):
-        // CHECK:         case uint32 0: goto block #15
-        // CHECK:         case uint32 1: goto block #16
-        // CHECK:         case uint32 2: goto block #17
-        // CHECK:         default: goto block #18
+        // CHECK: memcpy src: (arg #0), dest: (advance ptr: %abi_encoded.temp.47, by: (uint32 0 + %temp.49)), bytes_len: %temp.48
+        // CHECK: ty:bytes %enc = %abi_encoded.temp.47
+        // CHECK: ty:uint32 %temp.50 = (builtin ArrayLength (%enc))
+        // CHECK: ty:uint32 %temp.54 = (zext uint32 (builtin ReadFromBuffer (%enc, uint32 0)))
+        // CHECK: switch (%temp.54 & uint32 3):
+        // CHECK:         case uint32 0: goto block #16
+        // CHECK:         case uint32 1: goto block #17
+        // CHECK:         case uint32 2: goto block #18
+        // CHECK:         default: goto block #19
 
         // CHECK: block13: # fail
         // CHECK: assert-failure
 
         // CHECK: block14: # prepare
-        // CHECK: branchcond (unsigned more %temp.27 > uint32 63), block10, block8
+        // CHECK: branchcond (unsigned more %temp.48 > uint32 63), block10, block8
 
-        // CHECK: block15: # case_0
-        // CHECK: ty:uint32 %temp.30 = (%temp.31 >> uint32 2)
-        // CHECK: ty:uint32 %temp.31 = uint32 1
-        // CHECK: branch block19
+        // CHECK: block16: # case_0
+        // CHECK: ty:uint32 %temp.53 = (%temp.54 >> uint32 2)
+        // CHECK: ty:uint32 %temp.54 = uint32 1
+        // CHECK: branch block20
 
-        // CHECK: block16: # case_1
-        // CHECK: ty:uint32 %temp.30 = ((zext uint32 (builtin ReadFromBuffer (%enc, uint32 0))) >> uint32 2)
-        // CHECK: ty:uint32 %temp.31 = uint32 2
-        // CHECK: branch block19
+        // CHECK: block17: # case_1
+        // CHECK: ty:uint32 %temp.53 = ((zext uint32 (builtin ReadFromBuffer (%enc, uint32 0))) >> uint32 2)
+        // CHECK: ty:uint32 %temp.54 = uint32 2
+        // CHECK: branch block20
 
-        // CHECK: block17: # case_2
-        // CHECK: ty:uint32 %temp.30 = ((builtin ReadFromBuffer (%enc, uint32 0)) >> uint32 2)
-        // CHECK: ty:uint32 %temp.31 = uint32 4
-        // CHECK: branch block19
+        // CHECK: block18: # case_2
+        // CHECK: ty:uint32 %temp.53 = ((builtin ReadFromBuffer (%enc, uint32 0)) >> uint32 2)
+        // CHECK: ty:uint32 %temp.54 = uint32 4
+        // CHECK: branch block20
 
-        // CHECK: block18: # case_default
+        // CHECK: block19: # case_default
         // CHECK: assert-failure
 
-        // CHECK: block19: # done
-        // CHECK: branchcond (unsigned (uint32 0 + %temp.31) <= %temp.29), block20, block21
+        // CHECK: block20: # done
+        // CHECK: branchcond (unsigned (uint32 0 + %temp.54) <= %temp.50), block21, block22
 
-        // CHECK: block20: # inbounds
-        // CHECK: branchcond (unsigned (uint32 0 + (%temp.30 + %temp.31)) <= %temp.29), block22, block23
+        // CHECK: block21: # inbounds
+        // CHECK: branchcond (unsigned (uint32 0 + (%temp.53 + %temp.54)) <= %temp.50), block23, block24
 
-        // CHECK: block21: # out_of_bounds
-        // CHECK: assert-failure
+        // CHECK: block22: # out_of_bounds
+        // CHECK: branch block15
 
-        // CHECK: block22: # inbounds
-        // CHECK: ty:string %temp.32 = (alloc string len %temp.30)
-        // CHECK: memcpy src: (advance ptr: %enc, by: (uint32 0 + %temp.31)), dest: %temp.32, bytes_len: %temp.30
-        // CHECK: branchcond (unsigned less (uint32 0 + (%temp.30 + %temp.31)) < %temp.29), block24, block25
+        // CHECK: block23: # inbounds
+        // CHECK: ty:string %temp.55 = (alloc string len %temp.53)
+        // CHECK: memcpy src: (advance ptr: %enc, by: (uint32 0 + %temp.54)), dest: %temp.55, bytes_len: %temp.53
+        // CHECK: branchcond (unsigned less (uint32 0 + (%temp.53 + %temp.54)) < %temp.50), block25, block26
 
-        // CHECK: block23: # out_of_bounds
-        // CHECK: assert-failure
+        // CHECK: block24: # out_of_bounds
+        // CHECK: branch block15
 
-        // CHECK: block24: # not_all_bytes_read
-        // CHECK: assert-failure
+        // CHECK: block25: # not_all_bytes_read
+        // CHECK: branch block15
     }
 }
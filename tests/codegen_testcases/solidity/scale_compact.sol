@@ -0,0 +1,30 @@
+// RUN: --target polkadot --emit cfg
+
+contract compact {
+    function get(@compact uint64 a) public pure returns (@compact uint32) {
+        return uint32(a);
+    }
+
+    function find(uint32 a) public pure returns (@option bool found, uint32 value) {
+        return (a != 0, a);
+    }
+
+    // BEGIN-CHECK: Contract: compact
+    // CHECK: # function polkadot_call_dispatch public:false selector: nonpayable:false
+    // CHECK: switch (%temp.17 & uint32 3):
+    // CHECK: ty:uint32 %temp.18 = ((%temp.17 >> uint32 2) + uint32 4)
+    // CHECK: branchcond (unsigned more %temp.18 > uint32 8), block18, block19
+    // CHECK: ty:uint64 %compact_value.temp.15 = (zext uint64 (%temp.17 >> uint32 2))
+    // CHECK: %temp.25 = call compact::compact::function::get__uint64 %compact_value.temp.15
+    // CHECK: branchcond (unsigned less %temp.25 < uint32 64), block42, block43
+    // CHECK: ty:bytes %abi_encoded.temp.27 = (alloc bytes len %compact_size.temp.26)
+    // CHECK: ty:uint32 %compact_size.temp.26 = uint32 5
+    // CHECK: writebuffer buffer:%abi_encoded.temp.27 offset:uint32 0 value:(trunc uint8 ((((%compact_size.temp.26 - uint32 1) - uint32 4) << uint32 2) | uint32 3))
+    // CHECK: writebuffer buffer:%abi_encoded.temp.27 offset:uint32 0 value:(trunc uint16 ((%temp.25 << uint32 2) | uint32 1))
+    // CHECK: %temp.37, %temp.38 = call compact::compact::function::find__uint32 %temp.31
+    // CHECK: ty:bytes %abi_encoded.temp.39 = (alloc bytes len (uint32 1 + (uint32 4 * (zext uint32 %temp.37))))
+    // CHECK: writebuffer buffer:%abi_encoded.temp.39 offset:uint32 0 value:%temp.37
+    // CHECK: branchcond %temp.37, block75, block76
+    // CHECK: block75: # present
+    // CHECK: writebuffer buffer:%abi_encoded.temp.39 offset:uint32 1 value:%temp.38
+}
@@ -0,0 +1,38 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    uint64 total;
+
+    // BEGIN-CHECK: # function abi_decode_args_0 public:false selector: nonpayable:false
+    // CHECK: # params: buffer_pointer,uint32
+    // CHECK: # returns: uint64,bool
+    // CHECK: return %temp.13, %temp.14
+
+    // BEGIN-CHECK: # function polkadot_call_dispatch public:false selector: nonpayable:false
+    // CHECK: block5: # func_0_no_value
+    // CHECK: %temp.27, %temp.28 = call abi_decode_args_0 (advance ptr: %input_ptr.temp.26, by: uint32 4), (trunc uint32 ((arg #1) - uint32 4))
+    // CHECK: = call c::c::function::add__uint64_bool %temp.27, %temp.28
+    function add(uint64 a, bool b) public {
+        if (b) {
+            total += a;
+        }
+    }
+
+    // CHECK: block8: # func_1_no_value
+    // CHECK: %temp.29, %temp.30 = call abi_decode_args_0 (advance ptr: %input_ptr.temp.26, by: uint32 4), (trunc uint32 ((arg #1) - uint32 4))
+    // CHECK: = call c::c::function::sub__uint64_bool %temp.29, %temp.30
+    function sub(uint64 a, bool b) public {
+        if (b) {
+            total -= a;
+        }
+    }
+
+    // A single function with these parameters decodes them inline
+    // CHECK: block11: # func_2_no_value
+    // NOT-CHECK: call abi_decode_args
+    // CHECK: ty:uint64 %temp.34 = (builtin ReadFromBuffer ((advance ptr: %input_ptr.temp.26, by: uint32 4), uint32 0))
+    // CHECK: = call c::c::function::set__uint64 %temp.34
+    // CHECK-ABSENT: abi_decode_args_1
+    function set(uint64 a) public {
+        total = a;
+    }
+}
@@ -0,0 +1,22 @@
+// RUN: --target polkadot --emit llvm-ir --no-log-runtime-errors
+// READ: Vault.ll
+contract Vault {
+    uint64 limit;
+
+    function deposit(uint64 amount) public {
+        require(amount > 0, "invalid amount");
+        require(amount < limit, "invalid amount");
+        limit -= amount;
+    }
+
+    function withdraw(uint64 amount) public {
+        require(amount > 0, "invalid amount");
+        limit += amount;
+    }
+}
+
+// The reason string is in the binary once, and the reverts with it share one function
+// BEGIN-CHECK: invalid amount
+// CHECK-ABSENT: invalid amount
+// BEGIN-CHECK: define internal void @revert_data_
+// BEGIN-CHECK: call void @revert_data_
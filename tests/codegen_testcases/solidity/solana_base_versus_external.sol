@@ -39,8 +39,8 @@ contract Other is Foo {
         ];
         // external calls
         Foo.get_b{program_id: id, accounts: meta}(id);
-        // CHECK: external call::regular address:(arg #0) payload:%abi_encoded.temp.35 value:uint64 0 gas:uint64 0 accounts:%meta seeds: contract|function:(0, 3) flags:
+        // CHECK: external call::regular address:(arg #0) payload:%scratch_buffer.temp.41 value:uint64 0 gas:uint64 0 accounts:%meta seeds: contract|function:(0, 3) flags:
         Foo.get_b2{program_id: id, accounts: meta}(id);
-        // CHECK: external call::regular address:(arg #0) payload:%abi_encoded.temp.36 value:uint64 0 gas:uint64 0 accounts:%meta seeds: contract|function:(0, 4) flags:
+        // CHECK: external call::regular address:(arg #0) payload:%scratch_buffer.temp.41 value:uint64 0 gas:uint64 0 accounts:%meta seeds: contract|function:(0, 4) flags:
     }
 }
\ No newline at end of file
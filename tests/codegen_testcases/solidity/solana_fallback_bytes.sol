@@ -0,0 +1,11 @@
+// RUN: --target solana --emit cfg
+
+contract echo {
+	// BEGIN-CHECK: # function solang_dispatch public:false selector: nonpayable:false
+	// CHECK: block2: # no_function_matched
+	// CHECK: 	%fallback_output.temp.6 = call echo::echo::fallback (builtin Calldata ())
+	// CHECK: 	return data %fallback_output.temp.6, data length: (zext uint64 (builtin ArrayLength (%fallback_output.temp.6)))
+	fallback(bytes calldata input) external returns (bytes memory) {
+		return input;
+	}
+}
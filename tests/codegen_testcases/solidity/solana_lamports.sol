@@ -0,0 +1,29 @@
+// RUN: --target solana --emit cfg
+
+contract Lamports {
+    // BEGIN-CHECK: Lamports::Lamports::function::balance_of__address
+    function balance_of(address a) public view returns (uint64) {
+        // CHECK: return uint64((builtin Balance ((arg #0))))
+        return a.balance;
+    }
+
+    // BEGIN-CHECK: Lamports::Lamports::function::mine
+    function mine() public view returns (uint64) {
+        // the data account holds the lamports of the contract
+        // CHECK: return uint64((builtin Balance ()))
+        return address(this).balance;
+    }
+
+    // BEGIN-CHECK: Lamports::Lamports::function::pay__address_uint64
+    function pay(address payable a, uint64 amount) public {
+        // CHECK: _ = value transfer address:%a value:uint64(%amount)
+        a.transfer(amount);
+    }
+
+    // BEGIN-CHECK: Lamports::Lamports::function::try_pay__address_uint64
+    function try_pay(address payable a, uint64 amount) public returns (bool) {
+        // CHECK: %success.temp.8 = value transfer address:%a value:uint64(%amount)
+        // CHECK: return (%success.temp.8 == uint32 0)
+        return a.send(amount);
+    }
+}
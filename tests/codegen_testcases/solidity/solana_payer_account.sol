@@ -4,7 +4,7 @@
 contract Builder {
     // BEGIN-CHECK: Builder::Builder::function::build_this
     function build_this() external {
-        // CHECK: external call::regular address:address 0x69be884fd55a2306354c305323cc6b7ce91768be33d32a021155ef608806bcb payload:%abi_encoded.temp.17 value:uint64 0 gas:uint64 0 accounts:[3] [ struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 3]) field 0)), true, false }, struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 2]) field 0)), true, true }, struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 0]) field 0)), false, false } ] seeds: contract|function:(1, 4) flags:
+        // CHECK: external call::regular address:address 0x69be884fd55a2306354c305323cc6b7ce91768be33d32a021155ef608806bcb payload:%abi_encoded.temp.23 value:uint64 0 gas:uint64 0 accounts:[3] [ struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 3]) field 0)), true, false }, struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 2]) field 0)), true, true }, struct { (load (struct (subscript struct AccountInfo[] (builtin Accounts ())[uint32 0]) field 0)), false, false } ] seeds: contract|function:(1, 4) flags:
         Built.new("my_seed");
     }
 
@@ -20,9 +20,9 @@ contract Built {
     @payer(payer_account)
     constructor(@seed bytes my_seed) {}
     // BEGIN-CHECK: solang_dispatch
-    // CHECK: ty:struct AccountInfo %temp.10 = (subscript struct AccountInfo[] (builtin Accounts ())[uint32 1])
-	// CHECK: ty:struct AccountInfo %temp.11 = (subscript struct AccountInfo[] (builtin Accounts ())[uint32 0])
-	// CHECK: ty:struct AccountMeta[2] %metas.temp.9 = [2] [ struct { (load (struct %temp.10 field 0)), true, true }, struct { (load (struct %temp.11 field 0)), true, true } ]
+    // CHECK: ty:struct AccountInfo %temp.13 = (subscript struct AccountInfo[] (builtin Accounts ())[uint32 1])
+	// CHECK: ty:struct AccountInfo %temp.14 = (subscript struct AccountInfo[] (builtin Accounts ())[uint32 0])
+	// CHECK: ty:struct AccountMeta[2] %metas.temp.12 = [2] [ struct { (load (struct %temp.13 field 0)), true, true }, struct { (load (struct %temp.14 field 0)), true, true } ]
 
     // The account metas should have the proper index in the AccountInfo array: 1
 
@@ -0,0 +1,30 @@
+// RUN: --target solana --emit cfg
+
+contract caller {
+    // BEGIN-CHECK: caller::caller::function::call_twice__address
+    function call_twice(address id) external {
+        // CHECK: block0: # entry
+        // CHECK: ty:bytes %scratch_buffer.temp.8 = (alloc bytes len uint32 24)
+        // CHECK: ty:bytes %scratch_buffer.temp.5 = (alloc bytes len uint32 16)
+        // CHECK: writebuffer buffer:%scratch_buffer.temp.5 offset:uint32 8 value:uint64 1
+        // CHECK: payload:%scratch_buffer.temp.5
+        Callee.set{program_id: id}(1);
+        // CHECK: writebuffer buffer:%scratch_buffer.temp.5 offset:uint32 8 value:uint64 2
+        // CHECK: payload:%scratch_buffer.temp.5
+        Callee.set{program_id: id}(2);
+        // the size of a string is not known at compile time
+        // CHECK: ty:bytes %abi_encoded.temp.6 = (alloc bytes len (uint32 8 + ((builtin ArrayLength ((alloc string uint32 3 "foo"))) + uint32 4)))
+        // CHECK: payload:%abi_encoded.temp.6
+        Callee.name{program_id: id}("foo");
+        // CHECK: writebuffer buffer:%scratch_buffer.temp.8 offset:uint32 16 value:uint64 4
+        // CHECK: payload:%scratch_buffer.temp.8
+        Callee.set_both{program_id: id}(3, 4);
+    }
+}
+
+@program_id("Chi1d5XD6nTAp2EyaNGqMxZzUjh6NvhXRxbGHP3D1RaT")
+contract Callee {
+    function set(uint64 a) external {}
+    function name(string s) external {}
+    function set_both(uint64 a, uint64 b) external {}
+}
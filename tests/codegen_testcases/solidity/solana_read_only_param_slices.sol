@@ -0,0 +1,37 @@
+// RUN: --target solana --emit cfg
+
+contract c {
+    // Both parameters are only read, so they are decoded as slices into the input
+    function sum(uint64[] calldata values, bytes calldata tag) external pure returns (uint64 total, bytes1 first) {
+        for (uint32 i = 0; i < values.length; i++) {
+            total += values[i];
+        }
+        first = tag[0];
+    }
+
+    // The parameter escapes through the return value
+    function copied(bytes calldata data) external pure returns (bytes memory) {
+        return data;
+    }
+
+    // The parameter is modified
+    function modified(uint32[] memory data) external pure returns (uint32) {
+        data[0] = 1;
+        return data[1];
+    }
+
+    // BEGIN-CHECK: solang_dispatch
+    // CHECK: ty:uint64[] %temp.24 = (builtin SliceFromBuffer ((advance ptr: %input.temp.18, by: uint32 8), uint32 4, %temp.23))
+    // CHECK: ty:bytes %temp.26 = (builtin SliceFromBuffer ((advance ptr: %input.temp.18, by: uint32 8), ((uint32 0 + ((%temp.23 * uint32 8) + uint32 4)) + uint32 4), %temp.25))
+    // CHECK: %temp.28, %temp.29 = call c::c::function::sum__uint64:_bytes %temp.24, %temp.26
+    // CHECK: ty:bytes %temp.35 = (alloc bytes len %temp.34)
+    // CHECK: memcpy src: (advance ptr: (advance ptr: %input.temp.18, by: uint32 8), by: uint32 4), dest: %temp.35, bytes_len: %temp.34
+    // CHECK: %temp.37 = call c::c::function::copied__bytes %temp.35
+    // CHECK: ty:uint32[] %temp.44 = (alloc uint32[] len %temp.43)
+    // CHECK: %temp.46 = call c::c::function::modified__uint32: %temp.44
+    // Elements which are padded in memory cannot be sliced
+    // CHECK: ty:int24[] %temp.54 = (alloc int24[] len %temp.53)
+    function padded(int24[] calldata data) external pure returns (int24) {
+        return data[0];
+    }
+}
@@ -0,0 +1,21 @@
+// RUN: --target solana --emit cfg
+
+@syscall("sol_log_")
+function log(bytes message);
+
+@syscall(0x5c2a3178)
+function log_64(uint64 a, uint64 b, uint64 c, uint64 d, uint64 e);
+
+@syscall("sol_remaining_compute_units")
+function remaining_compute_units() view returns (uint64);
+
+contract Syscalls {
+    // BEGIN-CHECK: Syscalls::Syscalls::function::f
+    function f() public {
+        // CHECK: = call builtin log (alloc bytes uint32 3 "ABC")
+        log(hex"414243");
+        // CHECK: %.temp.7 = call builtin remaining_compute_units
+        // CHECK: = call builtin log_64 uint64 1, uint64 2, uint64 3, uint64 4, %.temp.7
+        log_64(1, 2, 3, 4, remaining_compute_units());
+    }
+}
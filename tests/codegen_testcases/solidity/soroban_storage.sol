@@ -0,0 +1,34 @@
+// RUN: --target soroban --emit cfg
+
+contract store {
+    uint64 count;
+    mapping(string => uint64) balances;
+    uint32[] list;
+
+    // CHECK: # function store::store::function::inc public:true
+    function inc() public returns (uint64) {
+        // CHECK: load storage slot(uint64 4) ty:uint64
+        // CHECK: store storage slot(uint64 4) ty:uint64 =
+        count += 1;
+        return count;
+    }
+
+    // CHECK: # function store::store::function::balance__string public:true
+    function balance(string memory name) public view returns (uint64) {
+        // CHECK: load storage slot((subscript mapping(string => uint64) storage uint64 4294967300[(arg #0)])) ty:uint64
+        return balances[name];
+    }
+
+    // CHECK: # function store::store::function::add__uint32 public:true
+    function add(uint32 v) public {
+        // CHECK: push storage ty:uint32 slot:uint64 8589934596
+        list.push(v);
+    }
+
+    // CHECK: # function store::store::function::get__uint32 public:true
+    function get(uint32 i) public view returns (uint32) {
+        // CHECK: branchcond (unsigned %index.temp.11 >= (storage array length uint64 8589934596[uint32])), block1, block2
+        // CHECK: load storage slot((subscript uint32[] storage uint64 8589934596[(zext uint64
+        return list[i];
+    }
+}
@@ -0,0 +1,126 @@
+// RUN: --target polkadot --emit cfg
+contract c {
+    uint256 totalSupply;
+    uint64 count;
+    mapping(uint256 => uint256) balances;
+    uint256[] list;
+
+    // BEGIN-CHECK: c::c::function::sum__uint64
+    function sum(uint64 n) public {
+        // CHECK: block0: # entry
+        // CHECK: ty:bool %1.storage_dirty = false
+        // CHECK: branch block5
+        // CHECK: block1: # body
+        // CHECK: ty:uint64 %temp.17 = %1.storage_cache
+        // CHECK: ty:uint64 %1.storage_cache = %temp.18
+        // CHECK: ty:bool %1.storage_dirty = true
+        // CHECK: block2: # cond
+        // CHECK: # phis: i,1.storage_cache,1.storage_dirty
+        // CHECK: block4: # endfor
+        // CHECK: branchcond %1.storage_dirty, block6, block7
+        // CHECK: block5: # storage_cache
+        // CHECK: %1.storage_cache = load storage slot(uint256 1) ty:uint64
+        // CHECK: branch block2
+        // CHECK: block6: # storage_cache_store
+        // CHECK: store storage slot(uint256 1) ty:uint64 = %1.storage_cache
+        // CHECK: branch block7
+        // CHECK: block7: # storage_cache_stored
+        // CHECK: return
+        for (uint64 i = 0; i < n; i++) {
+            count += i;
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::share__uint256:
+    function share(uint256[] memory amounts) public view returns (uint256 total) {
+        // the slot is only loaded, so it is never written back
+        // CHECK: block2: # cond
+        // CHECK: # phis: total,i,0.storage_cache
+        // CHECK: block4: # endfor
+        // CHECK: return %total
+        // CHECK: block7: # storage_cache
+        // CHECK: %0.storage_cache = load storage slot(uint256 0) ty:uint256
+        // CHECK: branch block2
+        for (uint256 i = 0; i < amounts.length; i++) {
+            total += amounts[i] * 100 / totalSupply;
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::mint__uint256:
+    function mint(uint256[] memory ids) public {
+        // mappings do not alias the slot of totalSupply
+        // CHECK: %0.storage_cache = load storage slot(uint256 0) ty:uint256
+        for (uint256 i = 0; i < ids.length; i++) {
+            balances[ids[i]] += 1;
+            totalSupply += 1;
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::grow__uint256
+    function grow(uint256 n) public {
+        // the elements of the array do not overlap with the length or count
+        // CHECK: block5: # storage_cache
+        // CHECK: %1.storage_cache = load storage slot(uint256 1) ty:uint64
+        // CHECK: %3.storage_cache = load storage slot(uint256 3) ty:uint256
+        // CHECK: block6: # storage_cache_store
+        // CHECK: store storage slot(uint256 3) ty:uint256 = %3.storage_cache
+        for (uint256 i = 0; i < n; i++) {
+            list.push(count);
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::nested__uint64
+    function nested(uint64 n) public {
+        // the slot is cached over the outer loop
+        // CHECK: block0: # entry
+        // CHECK: branch block9
+        // CHECK: block9: # storage_cache
+        // CHECK: %1.storage_cache = load storage slot(uint256 1) ty:uint64
+        // CHECK: branch block2
+        for (uint64 i = 0; i < n; i++) {
+            for (uint64 j = 0; j < i; j++) {
+                count += j;
+            }
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::notify__address_uint64
+    function notify(address a, uint64 n) public {
+        // the external call could read count, so it is stored before the call
+        // CHECK: block1: # body
+        // CHECK: load storage slot(uint256 1) ty:uint64
+        // CHECK: store storage slot(uint256 1) ty:uint64
+        // CHECK: external call::regular
+        for (uint64 i = 0; i < n; i++) {
+            count += 1;
+            c(a).sum(i);
+        }
+    }
+
+    // BEGIN-CHECK: c::c::function::bump__address
+    function bump(address a) public {
+        // both stores are merged into one, which is written before the call
+        // CHECK: %0.storage_cache = load storage slot(uint256 0) ty:uint256
+        // CHECK: ty:uint256 %0.storage_cache = %temp.55
+        // CHECK: ty:uint256 %0.storage_cache = %temp.57
+        // CHECK: store storage slot(uint256 0) ty:uint256 = %0.storage_cache
+        // CHECK: external call::regular
+        totalSupply += 1;
+        totalSupply += 2;
+        c(a).sum(1);
+    }
+
+    // BEGIN-CHECK: c::c::function::choose__bool
+    function choose(bool b) public {
+        // the slot is only written on one path, so a flag records whether to write it back
+        // CHECK: block0: # entry
+        // CHECK: ty:bool %0.storage_dirty = false
+        // CHECK: ty:bool %0.storage_dirty = true
+        // CHECK: branchcond %0.storage_dirty
+        // CHECK: store storage slot(uint256 0) ty:uint256 = %0.storage_cache
+        if (b) {
+            totalSupply += 1;
+            totalSupply *= 2;
+        }
+    }
+}
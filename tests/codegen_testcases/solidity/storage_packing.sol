@@ -0,0 +1,47 @@
+// RUN: --target polkadot --emit cfg --no-storage-cache
+contract c {
+    bool flag;
+    uint64 count = 5;
+    int8 small;
+    uint256 big;
+
+    struct S {
+        uint32 a;
+        bool b;
+        bytes2 c;
+        uint256 d;
+    }
+    S s;
+
+    // BEGIN-CHECK: c::c::function::inc
+    function inc() public {
+        // CHECK: %temp.4 = load storage slot(uint256 0) ty:uint128
+        // CHECK: ty:uint64 %temp.3 = (trunc uint64 (%temp.4 >> uint128 8))
+        // CHECK: store storage slot(uint256 0) ty:uint128 = ((%temp.6 & uint128 340282366920938458741008124562122998015) | ((zext uint128 %temp.5) << uint128 8))
+        count++;
+        // CHECK: store storage slot(uint256 0) ty:uint128 = ((%temp.8 & uint128 340282366920937259259921475672238718975) | ((zext uint128 uint8(int8 -1)) << uint128 72))
+        small = -1;
+        // CHECK: store storage slot(uint256 0) ty:uint128 = (%temp.9 & uint128 340282366920937259259921475672238718975)
+        delete small;
+        // CHECK: store storage slot(uint256 1) ty:uint256 = uint256 1
+        big = 1;
+    }
+
+    // BEGIN-CHECK: c::c::function::set
+    function set() public {
+        // CHECK: %temp.13 = load storage slot(uint256 2) ty:uint64
+        // CHECK: store storage slot(uint256 2) ty:uint64 = ((%temp.13 & uint64 18446742978492891135) | ((zext uint64 true) << uint64 32))
+        s.b = true;
+        // CHECK: store storage slot(uint256 3) ty:uint256 = uint256 2
+        s.d = 2;
+    }
+
+    // BEGIN-CHECK: c::c::function::get
+    function get() public view returns (bool, int8, bytes2) {
+        // CHECK: return ((trunc uint8 (%temp.15 >> uint128 0)) != uint8 0), int8((trunc uint8 (%temp.16 >> uint128 72))), bytes2((trunc uint16 (%temp.17 >> uint64 40)))
+        return (flag, small, s.c);
+    }
+
+    // BEGIN-CHECK: function storage_initializer
+    // CHECK: store storage slot(uint256 0) ty:uint128 = ((%temp.18 & uint128 340282366920938458741008124562122998015) | uint128 1280)
+}
@@ -1,4 +1,4 @@
-// RUN: --target polkadot --emit cfg
+// RUN: --target polkadot --emit cfg --no-loop-unroll
 contract test {
 /******************/
 /* Multiply tests */
@@ -82,7 +82,7 @@ contract test {
         // we're upcasting to 256 bits, but known bits will track this
         uint i = arg1;
         print("i:{}".format(i / 1e6));
-// CHECK: (zext uint256 (unsigned divide (trunc uint64 %i) / uint64 1000000)
+// CHECK: (zext uint256 (unsigned divide (trunc uint64 (zext uint256 (arg #0))) / uint64 1000000)
     }
 
 // BEGIN-CHECK: test::function::f8
@@ -0,0 +1,34 @@
+// RUN: --target solana --emit cfg
+
+contract Router {
+    // BEGIN-CHECK: Router::Router::function::route__string
+    function route(string method) public pure returns (uint8) {
+        // CHECK: switch (trunc uint64 uint256((builtin Keccak256 ((arg #0))))):
+        // CHECK: case uint64 1463588345501078377: goto block #3
+        // CHECK: default: goto block #1
+        // CHECK: block3: # string_case
+        // CHECK: branchcond (strcmp ((arg #0)) ("add")), block7, block1
+        if (method == "add") {
+            return 1;
+        } else if (method == "sub") {
+            return 2;
+        } else if ("mul" == method) {
+            return 3;
+        } else if (method == "div") {
+            return 4;
+        } else {
+            return 0;
+        }
+    }
+
+    // BEGIN-CHECK: Router::Router::function::short__string
+    function short(string method) public pure returns (uint8) {
+        // NOT-CHECK: Keccak256
+        if (method == "add") {
+            return 1;
+        } else if (method == "sub") {
+            return 2;
+        }
+        return 0;
+    }
+}
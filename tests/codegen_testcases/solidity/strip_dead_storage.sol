@@ -0,0 +1,25 @@
+// RUN: --target polkadot --emit cfg --strip-dead-storage
+
+contract c {
+	uint64 private written;
+	bool private read;
+	int256 private counter;
+	int256 internal kept;
+
+	// BEGIN-CHECK: c::c::function::f__uint64
+	function f(uint64 v) public returns (bool) {
+		// CHECK-ABSENT: store storage slot(uint256 2)
+		written = v;
+		// CHECK: load storage slot(uint256 0) ty:int256
+		// CHECK: store storage slot(uint256 0) ty:int256 =
+		counter += 1;
+		// CHECK: false
+		return read;
+	}
+
+	// BEGIN-CHECK: c::c::function::g
+	function g() public view returns (int256) {
+		// CHECK: load storage slot(uint256 1) ty:int256
+		return kept + counter;
+	}
+}
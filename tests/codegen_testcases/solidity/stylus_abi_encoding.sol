@@ -0,0 +1,30 @@
+// RUN: --target stylus --emit cfg
+
+contract c {
+    error Oops(uint32 code, string reason);
+
+    // BEGIN-CHECK: c::c::function::encode__bytes4_int16_string
+    function encode(bytes4 sel, int16 a, string memory s) public pure returns (bytes memory) {
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 0 value:function_selector((arg #0))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 4 value:bytes32((sext int256 (arg #1)))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 36 value:bytes32(uint256 64)
+        // CHECK: writebuffer buffer:%abi_encoded.temp.9 offset:uint32 68 value:bytes32((zext uint256 %temp.8))
+        // CHECK: memcpy src: (arg #2), dest: (advance ptr: %abi_encoded.temp.9, by: uint32 100), bytes_len: %temp.8
+        return abi.encodeWithSelector(sel, a, s);
+    }
+
+    // BEGIN-CHECK: c::c::function::packed__uint24_address_string
+    function packed(uint24 a, address b, string memory s) public pure returns (bytes memory) {
+        // CHECK: ty:bytes %abi_encoded.temp.12 = (alloc bytes len (uint32 23 + %temp.11))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.12 offset:uint32 0 value:bytes3((arg #0))
+        // CHECK: writebuffer buffer:%abi_encoded.temp.12 offset:uint32 3 value:(arg #1)
+        // CHECK: memcpy src: (arg #2), dest: (advance ptr: %abi_encoded.temp.12, by: uint32 23), bytes_len: %temp.11
+        return abi.encodePacked(a, b, s);
+    }
+
+    // BEGIN-CHECK: c::c::function::fail
+    function fail() public pure {
+        // CHECK: assert-failure: buffer: (alloc slice bytes1 uint32 132 hex"10b88f5c0000000000000000000000000000000000000000000000000000000000000007000000000000000000000000000000000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000026e6f000000000000000000000000000000000000000000000000000000000000")
+        revert Oops(7, "no");
+    }
+}
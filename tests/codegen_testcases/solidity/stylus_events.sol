@@ -0,0 +1,19 @@
+// RUN: --target stylus --emit cfg
+
+contract c {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Named(string indexed name, bytes4 indexed tag, int8 indexed delta) anonymous;
+
+    // BEGIN-CHECK: c::c::function::transfer__address_uint256
+    function transfer(address to, uint256 value) public {
+        // CHECK: writebuffer buffer:%abi_encoded.temp.6 offset:uint32 0 value:bytes32((arg #1))
+        // CHECK: emit event c.Transfer topics (alloc slice uint8 uint32 32 hex"ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"), bytes from:bytes32 ((zext bytes32 uint160(%temp.3))), bytes from:bytes32 ((zext bytes32 uint160((arg #0)))) data %abi_encoded.temp.6
+        emit Transfer(msg.sender, to, value);
+    }
+
+    // BEGIN-CHECK: c::c::function::named__string
+    function named(string memory name) public {
+        // CHECK: emit event c.Named topics bytes from:bytes32 ((builtin Keccak256 ((arg #0)))), bytes from:bytes32 (((zext bytes32 hex"01020304") << bytes32 224)), (alloc bytes uint32 32 hex"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff") data (alloc bytes uint32 0 "")
+        emit Named(name, hex"01020304", -1);
+    }
+}
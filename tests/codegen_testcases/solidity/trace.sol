@@ -0,0 +1,24 @@
+// RUN: --target polkadot --emit cfg
+contract Counter {
+    uint64 total;
+
+    // BEGIN-CHECK: Counter::Counter::function::add__uint64
+    @trace(true)
+    function add(uint64 a) public returns (uint64) {
+        // CHECK: print (format string ( hex"74726163653a20656e74657220436f756e7465722e61646428"), ( (arg #0)), ( hex"290a"))
+        total += a;
+        // CHECK: print (format string ( hex"74726163653a20436f756e7465722e6164642073746f72657320"),
+        // CHECK: store storage slot(uint256 0) ty:uint64 =
+        return total;
+        // CHECK: print (format string ( hex"74726163653a206578697420436f756e7465722e6164642072657475726e732028"),
+        // CHECK: return
+    }
+
+    // BEGIN-CHECK: Counter::Counter::function::get
+    // CHECK: block0: # entry
+    // NOT-CHECK: print
+    @trace(false)
+    function get() public view returns (uint64) {
+        return total;
+    }
+}
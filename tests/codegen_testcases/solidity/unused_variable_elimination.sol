@@ -86,7 +86,7 @@ contract c3 {
         c2 ct = new c2();
 
         return 3;
-        // CHECK: constructor(no: ) salt: value: gas:uint64 0 address: seeds: c2 encoded buffer: %abi_encoded.temp.120 accounts:
+        // CHECK: constructor(no: ) salt: value: gas:uint64 0 address: seeds: c2 encoded buffer: %abi_encoded.temp.134 accounts:
     }
 
     // BEGIN-CHECK: c3::function::test7
@@ -143,7 +143,7 @@ contract c3 {
         int f = 4;
 
         int c = 32 + 4 * (f = it1 + it2);
-        // CHECK: ty:int256 %c = (int256 32 + (sext int256 (int64 4 * (trunc int64 (%temp.130 + %temp.131)))))
+        // CHECK: ty:int256 %c = (int256 32 + (sext int256 (int64 4 * (trunc int64 (%temp.144 + %temp.145)))))
         // NOT-CHECK: ty:int256 %f = (%temp.
         return c;
     }
@@ -183,7 +183,7 @@ contract c3 {
     function test14() public returns (int) {
         int[] storage ptrArr = testArr;
 
-        // CHECK: store storage slot(%temp.154) ty:int256 storage = int256 3
+        // CHECK: store storage slot(%temp.182) ty:int256 storage = int256 3
         ptrArr.push(3);
 
         return ptrArr[0];
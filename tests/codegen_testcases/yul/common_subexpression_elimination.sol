@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg
+// RUN: --target solana --emit cfg --no-loop-unroll
 
 contract testing  {
     // BEGIN-CHECK: testing::testing::function::general_test__uint64
@@ -1,4 +1,4 @@
-// RUN: --target solana --emit cfg -Onone --no-cse
+// RUN: --target solana --emit cfg --no-loop-unroll -Onone --no-cse
 
 contract testing {
 
@@ -36,6 +36,16 @@ fn evm_contracts() -> io::Result<()> {
     contract_tests("tests/contract_testcases/evm", Target::EVM)
 }
 
+#[test]
+fn soroban_contracts() -> io::Result<()> {
+    contract_tests("tests/contract_testcases/soroban", Target::Soroban)
+}
+
+#[test]
+fn stylus_contracts() -> io::Result<()> {
+    contract_tests("tests/contract_testcases/stylus", Target::Stylus)
+}
+
 fn contract_tests(file_path: &str, target: Target) -> io::Result<()> {
     let path = PathBuf::from(file_path);
     recurse_directory(path, target)
@@ -98,7 +108,7 @@ fn parse_file(path: PathBuf, target: Target) -> io::Result<()> {
 
             if contract.instantiable {
                 let code = match ns.target {
-                    Target::Solana | Target::Polkadot { .. } => {
+                    Target::Solana | Target::Polkadot { .. } | Target::Stylus => {
                         contract.emit(&ns, &Default::default(), contract_no)
                     }
                     Target::EVM => b"beep".to_vec(),
@@ -134,7 +144,7 @@ fn add_file(cache: &mut FileResolver, path: &Path, target: Target) -> io::Result
         if line.starts_with("import") {
             if let (Some(start), Some(end)) = (line.find('"'), line.rfind('"')) {
                 let file = &line[start + 1..end];
-                if !file.is_empty() && file != "solana" {
+                if !file.is_empty() && file != "solana" && file != "polkadot" {
                     let mut import_path = path.parent().unwrap().to_path_buf();
                     import_path.push(file);
                     println!("adding import {}", import_path.display());
@@ -0,0 +1,12 @@
+contract factory {
+    function predict(bytes32 salt, bytes32 code_hash) public view returns (address) {
+        return create2_address(address(this), salt, code_hash, hex"00");
+    }
+
+    function constant_code_hash(bytes32 salt) public view returns (address) {
+        return create2_address(address(this), salt, hex"00");
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:16-31: builtin function 'create2_address' expects 3 arguments, 4 provided
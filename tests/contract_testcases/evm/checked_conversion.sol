@@ -0,0 +1,26 @@
+contract C {
+	function f() public pure returns (uint8) {
+		return toUint8(300);
+	}
+
+	function g(bytes32 b) public pure returns (uint64) {
+		return toUint64(b);
+	}
+
+	function h() public pure returns (uint64) {
+		return toUint64(1, 2);
+	}
+
+	function toInt16(uint8 a) internal pure returns (int16) {
+		return int16(a);
+	}
+
+	function i(uint8 u) public pure returns (int16) {
+		return toInt16(u);
+	}
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:18-21: implicit conversion would truncate from 'uint16' to 'uint8'
+// error: 7:19-20: builtin function 'toUint64' expects an integer, not 'bytes32'
+// error: 11:10-24: builtin function 'toUint64' expects 1 arguments, 2 provided
@@ -0,0 +1,25 @@
+abstract contract A {
+    int64 foo;
+
+    function get() internal view virtual returns (int64) {
+        foo = 1;
+        return foo;
+    }
+
+    function peek() public view returns (int64) {
+        return get();
+    }
+}
+
+contract B is A {
+    function get() internal view override returns (int64) {
+        assembly {
+            sstore(0, 1)
+        }
+        return 2;
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 5:9-12: function declared 'view' but this expression writes to state
+// error: 17:13-25: function declared 'view' but this expression writes to state
@@ -8,5 +8,7 @@ contract c is b {
 // ---- Expect: diagnostics ----
 // error: 6:1-7:2: contract 'c' missing override for fallback function
 // 	note 3:2-29: declaration of fallback function
+// 	note 6:15-16: inherited through 'c' -> 'b'
 // error: 6:1-7:2: contract 'c' missing override for receive function
 // 	note 2:2-36: declaration of receive function
+// 	note 6:15-16: inherited through 'c' -> 'b'
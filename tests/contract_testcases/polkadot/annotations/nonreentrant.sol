@@ -0,0 +1,37 @@
+contract nonreentrant {
+	uint64 total;
+
+	@nonreentrant(true)
+	function f(uint64 a) public returns (uint64) {
+		total += a;
+		return total;
+	}
+
+	@nonreentrant(true)
+	@nonreentrant(false)
+	function g() public {}
+
+	@nonreentrant(1)
+	function h() public {}
+
+	@nonreentrant(true)
+	function v() public view returns (uint64) {
+		return total;
+	}
+
+	@nonreentrant(true)
+	constructor() {}
+
+	@nonreentrant(true)
+	function p() public {}
+
+	@nonreentrant(false)
+	function q() public pure {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 11:2-22: duplicate @nonreentrant annotation for function
+// 	note 10:2-21: previous @nonreentrant
+// error: 14:16-17: '@nonreentrant' annotation should be 'true' or 'false'
+// error: 17:2-21: '@nonreentrant' annotation not allowed on function declared 'view', since the guard writes to state
+// error: 22:2-21: '@nonreentrant' annotation not allowed on constructor
@@ -0,0 +1,78 @@
+type Amount is uint64;
+
+enum State { Open, Closed }
+
+struct S {
+    uint64 a;
+}
+
+function free_standing(@compact uint64 a) pure returns (uint64) {
+    return a;
+}
+
+contract c {
+    constructor(@compact uint128 a) {}
+
+    function compact(@compact uint32 a, @compact Amount b) public pure returns (@compact uint128) {
+        return a + Amount.unwrap(b);
+    }
+
+    function too_wide(@compact uint256 a) public pure returns (@compact int64) {
+        return int64(uint64(a));
+    }
+
+    function find(uint32 a) public pure returns (@option bool found, string value) {
+        if (a == 0) {
+            return (false, "");
+        }
+        return (true, "found");
+    }
+
+    function check(uint32 a) external pure returns (@result bool ok, @compact uint32 value) {
+        return (a != 0, a);
+    }
+
+    function state() public pure returns (@option bool, State) {
+        return (true, State.Closed);
+    }
+
+    function not_first() public pure returns (uint32, @option bool) {
+        return (1, true);
+    }
+
+    function not_bool() public pure returns (@option uint8, uint32) {
+        return (1, 2);
+    }
+
+    function three() public pure returns (@result bool, uint32, uint32) {
+        return (true, 2, 3);
+    }
+
+    function value_type() public pure returns (@option bool, S memory) {
+        return (true, S(1));
+    }
+
+    function option_arg(@option bool a) public pure {}
+
+    function internal_only(@compact uint32 a) internal pure returns (uint32) {
+        return a;
+    }
+
+    function test() public pure returns (uint32) {
+        function(@compact uint32) internal pure returns (uint32) f = internal_only;
+        return f(1);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 9:24-32: '@compact' annotation is only allowed on public or external functions
+// error: 16:41-49: '@compact' annotation requires an unsigned integer of at most 128 bits, not 'usertype Amount'
+// error: 20:23-31: '@compact' annotation requires an unsigned integer of at most 128 bits, not 'uint256'
+// error: 20:64-72: '@compact' annotation requires an unsigned integer of at most 128 bits, not 'int64'
+// error: 39:55-62: '@option' annotation should be on the first of two return values, which must be of type 'bool'
+// error: 43:46-53: '@option' annotation should be on the first of two return values, which must be of type 'bool'
+// error: 47:43-50: '@result' annotation should be on the first of two return values, which must be of type 'bool'
+// error: 51:48-55: '@option' annotation cannot be used with a return value of type 'struct S'
+// error: 55:25-32: unexpected parameter annotation
+// error: 57:28-36: '@compact' annotation is only allowed on public or external functions
+// error: 62:18-26: '@compact' annotation is only allowed on public or external functions
@@ -0,0 +1,22 @@
+@syscall("seal0.debug_message")
+function debug_message(bytes message) returns (uint32);
+
+@syscall("seal0.debug.message")
+function bad_module(bytes message);
+
+@syscall(1)
+function by_number();
+
+@syscall("now")
+function now(address a);
+
+contract c {
+	function f() public {
+		debug_message(hex"414243");
+	}
+}
+
+// ---- Expect: diagnostics ----
+// error: 4:10-31: 'seal0.debug.message' is not a valid syscall name
+// error: 7:10-11: syscall numbers are only supported on Solana
+// error: 11:14-23: type 'address' not allowed as parameter of a syscall
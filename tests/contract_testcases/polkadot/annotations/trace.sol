@@ -0,0 +1,18 @@
+contract trace {
+	@trace(true)
+	function f(uint64 a) public returns (uint64) {
+		return a + 1;
+	}
+
+	@trace(false)
+	@trace(true)
+	function g() public {}
+
+	@trace(1)
+	function h() public {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 8:2-14: duplicate @trace annotation for function
+// 	note 7:2-15: previous @trace
+// error: 11:9-10: '@trace' annotation should be 'true' or 'false'
@@ -0,0 +1,8 @@
+        contract foo {
+            function test() public {
+                int[] bar = new int[](2);
+                bar.reserve(10, 20);
+            }
+        }
+// ---- Expect: diagnostics ----
+// error: 4:21-28: method 'reserve()' takes 1 argument
@@ -0,0 +1,8 @@
+        contract foo {
+            function test(int64 n) public {
+                int[] bar = new int[](2);
+                bar.reserve(n);
+            }
+        }
+// ---- Expect: diagnostics ----
+// error: 4:29-30: method 'reserve()' takes an unsigned length
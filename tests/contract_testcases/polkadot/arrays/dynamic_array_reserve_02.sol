@@ -0,0 +1,9 @@
+        contract foo {
+            int[] bar;
+
+            function test() public {
+                bar.reserve(10);
+            }
+        }
+// ---- Expect: diagnostics ----
+// error: 5:21-28: method 'reserve()' is only available for memory arrays
@@ -0,0 +1,8 @@
+        contract foo {
+            function test() public {
+                int[4] bar;
+                bar.reserve(10);
+            }
+        }
+// ---- Expect: diagnostics ----
+// error: 4:21-28: method reserve() is not available for fixed length arrays
@@ -0,0 +1,9 @@
+        contract foo {
+            function test(uint256 n) public pure returns (bytes memory) {
+                bytes b = new bytes(1);
+                b.reserve(n);
+                b.push(0x41);
+                return b;
+            }
+        }
+// ---- Expect: diagnostics ----
@@ -0,0 +1,32 @@
+contract packer {
+    struct Point {
+        int32 x;
+        int32 y;
+    }
+
+    function point(Point p) public pure returns (bytes) {
+        return abi.encodePacked(p);
+    }
+
+    function points(Point[2] p) public pure returns (bytes) {
+        return abi.encodePacked(p);
+    }
+
+    function names(string[] n) public pure returns (bytes) {
+        return abi.encodePacked(n);
+    }
+
+    function matrix(uint16[][2] m, uint16[2][] n) public pure returns (bytes) {
+        return abi.encodePacked(m, n);
+    }
+
+    function ok(uint32[] a, int24[3] b, string s) public pure returns (bytes) {
+        return abi.encodePacked(a, b, s, hex"00ff");
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 8:33-34: Invalid type 'struct packer.Point': structs cannot be packed encoded
+// error: 12:33-34: Invalid type 'struct packer.Point[2]': structs cannot be packed encoded
+// error: 16:33-34: Invalid type 'string[]': arrays with elements of dynamic size cannot be packed encoded
+// error: 20:33-34: Invalid type 'uint16[][2]': arrays with elements of dynamic size cannot be packed encoded
@@ -0,0 +1,24 @@
+contract bridge {
+    struct Transfer {
+        address to;
+        uint128 amount;
+    }
+
+    function encode(Transfer t, uint64[] nonces) public pure returns (bytes) {
+        return abi.ethEncode(t, nonces);
+    }
+
+    function decode(bytes data) public pure returns (int32, string) {
+        (int32 a, , string b) = abi.ethDecode(data, (int32, uint64[], string));
+        return (a, b);
+    }
+
+    function ok(bytes data) public pure returns (bytes) {
+        (bytes32 h, address a, bool b) = abi.ethDecode(data, (bytes32, address, bool));
+        return abi.ethEncode(h, a, b, "hello");
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 8:30-31: Invalid type 'struct bridge.Transfer': only value types, 'bytes' and 'string' can be Ethereum ABI encoded or decoded
+// error: 12:61-69: Invalid type 'uint64[]': only value types, 'bytes' and 'string' can be Ethereum ABI encoded or decoded
@@ -0,0 +1,12 @@
+contract factory {
+    function predict(bytes32 salt, bytes32 code_hash) public view returns (address) {
+        return create2_address(address(this), salt, code_hash);
+    }
+
+    function with_input(bytes32 salt, bytes32 code_hash, bytes input) public view returns (address) {
+        return create2_address(address(this), salt, code_hash, input);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:16-31: builtin function 'create2_address' expects 4 arguments, 3 provided
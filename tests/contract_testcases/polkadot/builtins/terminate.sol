@@ -0,0 +1,18 @@
+import "polkadot";
+
+contract Child {
+    function terminate_me(address payable beneficiary) public {
+        terminate(beneficiary);
+    }
+
+    function balance_needed() public view returns (uint128) {
+        return minimum_balance();
+    }
+
+    function not_view() public pure returns (uint128) {
+        return minimum_balance();
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 13:16-33: function declared 'pure' but this expression reads from state
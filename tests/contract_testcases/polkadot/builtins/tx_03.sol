@@ -7,4 +7,5 @@
             }
         }
 // ---- Expect: diagnostics ----
-// error: 4:27-29: builtin 'tx.origin' does not exist
+// error: 4:27-36: 'tx.origin' is not available on Polkadot
+// error: 4:27-36: implicit conversion to address from int64 not allowed
@@ -0,0 +1,25 @@
+import "polkadot";
+
+contract Delegate {
+    function delegate(Hash code_hash, bytes input) public returns (bytes result) {
+        (bool ok, result) = code_hash.delegatecall(input);
+        require(ok);
+    }
+
+    function delegate_value(Hash code_hash, bytes input) public payable {
+        (bool ok, bytes result) = code_hash.delegatecall{value: 1}(input);
+    }
+
+    function delegate_gas(Hash code_hash) public {
+        (bool ok, bytes result) = code_hash.delegatecall{gas: 100}(hex"01");
+    }
+
+    function not_a_code_hash(bytes32 code_hash) public {
+        (bool ok, bytes result) = code_hash.delegatecall(hex"01");
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 10:35-74: 'delegatecall' cannot have value specified
+// warning: 14:35-76: 'gas' specified on 'delegatecall' will be ignored
+// error: 18:45-57: method 'delegatecall' does not exist
@@ -0,0 +1,21 @@
+// burn(uint256) and collate_propagate_storage(bytes16) share the selector 42966c68
+error burn(uint256 amount);
+
+contract Base {
+    error collate_propagate_storage(bytes16 data);
+
+    function foo() public pure {
+        revert burn(1);
+    }
+}
+
+contract Unrelated {
+    error collate_propagate_storage(bytes16 data);
+
+    function bar() public pure {}
+}
+// ---- Expect: diagnostics ----
+// error: 5:11-36: error 'collate_propagate_storage(bytes16)' selector 0x42966c68 is the same as error 'burn(uint256)'
+// 	note 2:7-11: definition of error 'burn(uint256)'
+// error: 13:11-36: error 'collate_propagate_storage(bytes16)' selector 0x42966c68 is the same as error 'burn(uint256)'
+// 	note 2:7-11: definition of error 'burn(uint256)'
@@ -4,4 +4,4 @@ contract test {
             }
        }
 // ---- Expect: diagnostics ----
-// error: 3:24-35: exponation (**) is not allowed with signed types
+// warning: 2:13-74: function can be declared 'pure'
@@ -4,4 +4,4 @@ contract test {
             }
        }
 // ---- Expect: diagnostics ----
-// error: 3:24-35: exponation (**) is not allowed with signed types
+// error: 3:24-35: exponent of exponation (**) must be unsigned
@@ -0,0 +1,35 @@
+contract a {
+	fallback(bytes calldata input) external returns (bytes memory) {
+		return input;
+	}
+}
+
+contract b {
+	fallback(bytes calldata input) external {}
+}
+
+contract c {
+	fallback() external returns (bytes memory) {}
+}
+
+contract d {
+	fallback(bytes calldata input) external returns (string memory) {}
+}
+
+contract e {
+	fallback(bytes calldata input, uint x) external returns (bytes memory) {}
+}
+
+contract f {
+	fallback(bytes calldata input) external payable returns (bytes memory output) {
+		output = input;
+	}
+
+	receive() external payable {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 8:2-41: fallback function must be declared as 'fallback()' or 'fallback(bytes calldata) returns (bytes memory)'
+// error: 12:2-44: fallback function must be declared as 'fallback()' or 'fallback(bytes calldata) returns (bytes memory)'
+// error: 16:2-65: fallback function must be declared as 'fallback()' or 'fallback(bytes calldata) returns (bytes memory)'
+// error: 20:2-72: fallback function must be declared as 'fallback()' or 'fallback(bytes calldata) returns (bytes memory)'
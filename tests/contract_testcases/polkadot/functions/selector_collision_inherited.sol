@@ -0,0 +1,17 @@
+// burn(uint256) and collate_propagate_storage(bytes16) share the selector 42966c68
+abstract contract Base {
+    function burn(uint256 amount) public virtual {}
+
+    function burn(uint128 amount) internal {}
+}
+
+contract Derived is Base {
+    function collate_propagate_storage(bytes16 data) public {}
+}
+
+contract Overridden is Base {
+    function burn(uint256 amount) public override {}
+}
+// ---- Expect: diagnostics ----
+// error: 9:5-60: function 'collate_propagate_storage(bytes16)' selector 0x42966c68 is the same as function 'burn(uint256)'
+// 	note 3:5-49: definition of function 'burn(uint256)'
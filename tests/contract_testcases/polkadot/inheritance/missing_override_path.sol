@@ -0,0 +1,18 @@
+interface I {
+    function foo() external;
+    function bar() external;
+}
+
+abstract contract A is I {
+    function bar() public override {}
+}
+
+abstract contract B is A {}
+
+contract C is B {
+    function baz() public {}
+}
+// ---- Expect: diagnostics ----
+// error: 12:1-14:2: contract 'C' missing override for function 'foo'
+// 	note 2:5-28: declaration of function 'foo'
+// 	note 12:15-16: inherited through 'C' -> 'B' -> 'A' -> 'I'
@@ -10,3 +10,4 @@
 // ---- Expect: diagnostics ----
 // error: 6:9-8:10: contract 'a' missing override for function 'bar'
 // 	note 3:17-47: declaration of function 'bar'
+// 	note 6:23-24: inherited through 'a' -> 'b'
@@ -0,0 +1,18 @@
+contract c {
+    /// @custom:unroll 4
+    function f() public pure returns (uint64 sum) {
+        for (uint64 i = 0; i < 4; i++) {
+            sum += i;
+        }
+    }
+
+    /// @custom:unroll -1
+    function g() public pure returns (uint64 sum) {
+        for (uint64 i = 0; i < 4; i++) {
+            sum += i;
+        }
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 9:9-26: invalid unroll count '-1', expected a number of iterations
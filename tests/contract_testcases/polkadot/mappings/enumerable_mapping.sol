@@ -0,0 +1,28 @@
+contract c {
+    @enumerable(true)
+    mapping(string => int64) scores;
+
+    function set(string name, int64 score) public {
+        scores[name] = score;
+    }
+
+    function bump(string name) public {
+        scores[name]++;
+    }
+
+    function remove(string name) public {
+        delete scores[name];
+    }
+
+    function total() public view returns (int64 sum) {
+        for (uint i = 0; i < scores.length; i++) {
+            sum += scores[scores.keyAt(i)];
+        }
+    }
+
+    function names() public view returns (string[] memory) {
+        return scores.keys();
+    }
+}
+
+// ---- Expect: diagnostics ----
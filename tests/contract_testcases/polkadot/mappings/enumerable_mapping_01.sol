@@ -0,0 +1,7 @@
+contract c {
+    @enumerable(true)
+    uint64[] scores;
+}
+
+// ---- Expect: diagnostics ----
+// error: 2:5-22: annotation '@enumerable' is only allowed on mappings, not 'uint64[]'
@@ -0,0 +1,7 @@
+contract c {
+    @enumerable(1)
+    mapping(address => uint64) scores;
+}
+
+// ---- Expect: diagnostics ----
+// error: 2:5-19: annotation '@enumerable' expects 'true' or 'false'
@@ -0,0 +1,10 @@
+contract c {
+    mapping(address => uint64) scores;
+
+    function all() public view returns (address[] memory) {
+        return scores.keys();
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 5:23-27: method 'keys' does not exist
@@ -0,0 +1,9 @@
+contract c {
+    @enumerable(true)
+    @enumerable(true)
+    mapping(address => uint64) scores;
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:5-22: duplicate '@enumerable' annotation
+// 	note 2:5-22: previous '@enumerable' annotation
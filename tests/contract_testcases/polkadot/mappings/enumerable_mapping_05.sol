@@ -0,0 +1,11 @@
+contract c {
+    @enumerable(true)
+    mapping(address => uint64) scores;
+
+    function at(uint32 i, uint32 j) public view returns (address) {
+        return scores.keyAt(i, j);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 6:23-28: method 'keyAt()' takes 1 argument
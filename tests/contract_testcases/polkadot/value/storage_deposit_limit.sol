@@ -0,0 +1,21 @@
+contract Creator {
+    function create() public returns (Child) {
+        return new Child{storage_deposit_limit: 1000}();
+    }
+
+    function call_child(Child c) public {
+        c.foo{storage_deposit_limit: 1000}();
+    }
+
+    function bad_limit() public returns (Child) {
+        return new Child{storage_deposit_limit: "foo"}();
+    }
+}
+
+contract Child {
+    function foo() public {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 7:15-42: 'storage_deposit_limit' is only permitted for constructors on polkadot
+// error: 11:49-54: conversion from bytes3 to uint128 not possible
@@ -0,0 +1,7 @@
+contract c {
+	@nonreentrant(true)
+	function f() public {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 2:2-21: '@nonreentrant' annotation is not supported on Solana
@@ -0,0 +1,53 @@
+@syscall("sol_log_")
+function log(bytes message) {}
+
+function nobody(uint64 a);
+
+@syscall("sol-log")
+function bad_name();
+
+@syscall(true)
+function bad_value();
+
+@syscall(0x1_0000_0000)
+function too_big();
+
+@syscall("a")
+@syscall("b")
+function twice();
+
+@space(10)
+function other();
+
+@syscall("x")
+function bad_param(string s);
+
+@syscall("y")
+function bad_ret() returns (uint64, bool);
+
+@syscall("z")
+function bad_ret2() returns (uint128);
+
+@syscall("sol_log_")
+function log2(bytes message);
+
+contract c {
+	function f() public {
+		function(bytes) internal g = log2;
+	}
+}
+
+// ---- Expect: diagnostics ----
+// error: 1:1-21: function with '@syscall' annotation cannot have a body
+// error: 4:1-26: missing function body
+// error: 6:10-19: 'sol-log' is not a valid syscall name
+// error: 9:10-14: '@syscall' annotation should be a string or a number
+// error: 12:10-23: value 4294967296 does not fit into type uint32.
+// error: 16:1-14: duplicate @syscall annotation for function
+// 	note 15:1-14: previous @syscall
+// error: 19:1-11: annotations not allowed on function
+// error: 20:1-17: missing function body
+// error: 23:20-28: type 'string' not allowed as parameter of a syscall
+// error: 26:1-42: a syscall can return at most one value
+// error: 29:30-37: type 'uint128' not allowed as return value of a syscall
+// error: 36:32-36: syscall 'log2' can only be called directly
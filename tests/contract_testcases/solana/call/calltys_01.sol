@@ -7,4 +7,4 @@
             }
         }
 // ---- Expect: diagnostics ----
-// error: 6:19-31: method 'delegatecall' does not exist
+// error: 6:19-31: 'delegatecall' is not available on Solana
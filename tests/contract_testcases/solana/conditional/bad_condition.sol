@@ -0,0 +1,19 @@
+contract c {
+    @if(target == "near")
+    function a() public {}
+
+    @if(target == 1)
+    function b() public {}
+
+    @if(chain == "solana")
+    function c() public {}
+
+    @if(target)
+    function d() public {}
+}
+
+// ---- Expect: diagnostics ----
+// error: 2:19-25: unknown target 'near', expected one of evm, polkadot, solana, soroban, stylus
+// error: 5:9-20: '@if' condition should compare 'target' with a string, e.g. '@if(target == "solana")'
+// error: 8:9-26: '@if' condition should compare 'target' with a string, e.g. '@if(target == "solana")'
+// error: 11:9-15: '@if' condition should compare 'target' with a string, e.g. '@if(target == "solana")'
@@ -0,0 +1,34 @@
+@if(target == "polkadot")
+contract only_polkadot {
+    function pay(address payable a) public {
+        a.transfer(1);
+    }
+}
+
+contract c {
+    @if(target == "solana")
+    uint64 x;
+
+    @if(!(target == "solana"))
+    int32 x;
+
+    @if(target == "solana" && target != "evm")
+    function get() public view returns (uint64) {
+        return x;
+    }
+
+    @if(target == "evm" || target == "polkadot")
+    function get() public view returns (address) {
+        return tx.origin;
+    }
+
+    @if(false)
+    function unused() public {}
+}
+
+@if(target == "solana")
+struct S {
+    uint64 f;
+}
+
+// ---- Expect: diagnostics ----
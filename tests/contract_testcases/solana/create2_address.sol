@@ -0,0 +1,8 @@
+contract factory {
+    function predict(bytes32 salt, bytes32 code_hash) public view returns (address) {
+        return create2_address(address(this), salt, code_hash);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:16-31: unknown function or type 'create2_address'
@@ -7,4 +7,4 @@
         contract x {}
     
 // ---- Expect: diagnostics ----
-// error: 4:23-32: 'salt' not permitted for external calls or constructors on Solana
+// error: 4:23-32: 'salt' is not available on Solana
@@ -2,6 +2,10 @@ contract c {
     function test(address addr) public view returns (uint64) {
         return addr.balance;
     }
+
+    function mine() public view returns (uint64) {
+        return address(this).balance;
+    }
 }
 
 contract c1 {
@@ -14,9 +18,15 @@ contract c1 {
     function transfer(address payable addr, uint64 amount) public {
         addr.transfer(amount);
     }
+
+    function literal(uint64 amount) public {
+        payable(address"SysvarRent111111111111111111111111111111111").transfer(amount);
+    }
+
+    function transfer_view(address payable addr, uint64 amount) public view {
+        addr.transfer(amount);
+    }
 }
 
 // ---- Expect: diagnostics ----
-// error: 3:16-20: balance is not available on Solana. Use tx.accounts.account_name.lamports to fetch the balance.
-// error: 9:16-33: method 'send' not available on Solana. Use the lamports field from the AccountInfo struct directly to operate on balances.
-// error: 15:9-30: method 'transfer' not available on Solana. Use the lamports field from the AccountInfo struct directly to operate on balances.
+// error: 27:9-30: function declared 'view' but this expression writes to state
@@ -5,4 +5,4 @@
             }
         }
 // ---- Expect: diagnostics ----
-// error: 4:24-26: builtin 'tx.origin' does not exist
+// error: 4:24-33: 'tx.origin' is not available on Solana
@@ -0,0 +1,42 @@
+contract types {
+    enum State { Active, Closed }
+
+    struct S {
+        int32 f;
+    }
+
+    function ok(uint64 a, int128 b, bool c, State d, string memory e, bytes memory f, uint32[][] memory g) public returns (uint32[][] memory) {
+        return g;
+    }
+
+    function addr(address a) public returns (uint64) {
+        return 1;
+    }
+
+    function wide(uint256 x) external returns (int256) {
+        return int256(x);
+    }
+
+    function fixed_array(uint32[2] memory a) public returns (bytes32) {
+        return 0;
+    }
+
+    function structs(S memory s) public {}
+
+    function pair() public returns (uint64, bool) {
+        return (1, true);
+    }
+
+    function internal_ok(address a, S memory s) internal returns (uint256, bytes32) {
+        return (1, 0);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 12:19-26: parameter of type 'address' is not supported on Soroban
+// error: 16:19-26: parameter of type 'uint256' is not supported on Soroban
+// error: 16:48-54: return value of type 'int256' is not supported on Soroban
+// error: 20:26-35: parameter of type 'uint32[2]' is not supported on Soroban
+// error: 20:62-69: return value of type 'bytes32' is not supported on Soroban
+// error: 24:22-23: parameter of type 'struct types.S' is not supported on Soroban
+// error: 26:5-50: functions on Soroban can return at most one value
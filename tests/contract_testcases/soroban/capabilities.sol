@@ -0,0 +1,47 @@
+contract other {
+    function foo() public {}
+}
+
+contract caps {
+    function value() internal returns (uint128) {
+        return msg.value;
+    }
+
+    function origin() internal returns (address) {
+        return tx.origin;
+    }
+
+    function destroy(address payable a) internal {
+        selfdestruct(a);
+    }
+
+    function delegate(address a) internal {
+        a.delegatecall("");
+    }
+
+    function transfer(address payable a) internal {
+        a.transfer(1);
+    }
+
+    function create() internal {
+        new other();
+    }
+
+    function data() internal returns (bytes) {
+        return msg.data;
+    }
+
+    function sig() internal returns (bytes4) {
+        return msg.sig;
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 7:16-25: 'msg.value' is not available on Soroban
+// error: 11:16-25: 'tx.origin' is not available on Soroban
+// error: 15:9-21: 'selfdestruct' is not available on Soroban
+// error: 19:11-23: 'delegatecall' is not available on Soroban
+// error: 23:9-22: value transfer is not available on Soroban
+// error: 27:9-20: creating contracts is not available on Soroban
+// error: 31:16-24: call data is not available on Soroban. Soroban contracts are invoked with the arguments of the function, rather than with encoded call data
+// error: 35:16-23: call data is not available on Soroban. Soroban contracts are invoked with the arguments of the function, rather than with encoded call data
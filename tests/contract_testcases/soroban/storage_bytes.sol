@@ -0,0 +1,16 @@
+contract c {
+    bytes b;
+
+    function push() public {
+        b.push(0x01);
+    }
+
+    function pop() public returns (bytes memory) {
+        b.pop();
+        return b;
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 5:11-15: method 'push()' on storage bytes is not supported on Soroban
+// error: 9:11-14: method 'pop()' on storage bytes is not supported on Soroban
@@ -0,0 +1,32 @@
+contract Storage {
+    struct Point {
+        uint64 x;
+        uint64 y;
+    }
+
+    uint64 count;
+    address owner;
+    string name;
+    bytes data;
+    uint32[] list;
+    uint32[][] lists;
+    mapping(string => uint64) balances;
+    mapping(uint32 => string[]) names;
+
+    Point point;
+    uint32[4] fixed;
+    uint32[4][] fixed_lists;
+    mapping(uint32 => Point) points;
+    Point[] point_list;
+    mapping(uint32 => uint64)[] map_list;
+    function() external internal callback;
+}
+
+// ---- Expect: diagnostics ----
+// error: 16:5-10: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 17:5-14: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 18:5-16: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 19:5-29: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 20:5-12: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 21:5-32: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
+// error: 22:5-33: storage of this type is not available on Soroban. Soroban storage is limited to value types, 'string', 'bytes', and dynamic arrays and mappings of these
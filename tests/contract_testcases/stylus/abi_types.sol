@@ -0,0 +1,42 @@
+contract AbiTypes {
+    struct Point {
+        uint64 x;
+        uint64 y;
+    }
+
+    event Moved(address indexed who, Point to);
+    event Tagged(string indexed tag, bytes data, uint8 kind);
+    error TooFar(uint64[] path);
+
+    function ok(int32 a, bytes4 b, string memory c, bytes memory d) public pure returns (address, bool) {
+        return (address(0), a == 0 && b == 0 && bytes(c).length == d.length);
+    }
+
+    function point(Point memory p) public pure returns (uint64[] memory) {
+        return new uint64[](p.x);
+    }
+
+    function encode() public pure returns (bytes memory) {
+        uint64[2] memory a = [uint64(1), 2];
+        return abi.encode(a);
+    }
+
+    function decode(bytes memory data) public pure returns (uint64) {
+        (Point memory p) = abi.decode(data, (Point));
+        return p.y;
+    }
+
+    function internalOnly(Point memory p) internal pure returns (uint64[] memory) {
+        return new uint64[](p.y);
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 7:38-43: event field of type 'struct AbiTypes.Point' is not supported on Stylus
+// error: 9:18-26: error field of type 'uint64[]' is not supported on Stylus
+// error: 15:20-25: parameter of type 'struct AbiTypes.Point' is not supported on Stylus
+// error: 15:57-65: return value of type 'uint64[]' is not supported on Stylus
+// warning: 16:31-32: conversion truncates uint64 to uint32, as memory size is type uint32 on target Stylus
+// error: 21:27-28: Invalid type 'uint64[2]': only value types, 'bytes' and 'string' can be Ethereum ABI encoded or decoded
+// error: 25:46-51: Invalid type 'struct AbiTypes.Point': only value types, 'bytes' and 'string' can be Ethereum ABI encoded or decoded
+// warning: 30:31-32: conversion truncates uint64 to uint32, as memory size is type uint32 on target Stylus
@@ -0,0 +1,26 @@
+contract Builtins {
+    event Paid(address indexed from, uint256 value);
+
+    function info() public view returns (uint64, uint256, address, address, uint64) {
+        return (block.gaslimit, block.chainid, block.coinbase, tx.origin, gasleft());
+    }
+
+    function fees() public view returns (uint256, uint256) {
+        return (block.basefee, tx.gasprice);
+    }
+
+    function pay() public payable {
+        emit Paid(msg.sender, msg.value);
+    }
+
+    function call(address a, bytes memory data) public view returns (bool, bytes memory) {
+        return a.staticcall(data);
+    }
+
+    function transfer(address payable a, uint256 value) public returns (bool) {
+        a.transfer(value);
+        return a.send(value);
+    }
+}
+
+// ---- Expect: diagnostics ----
@@ -0,0 +1,17 @@
+contract Creator {
+    function create() public returns (Child) {
+        return new Child();
+    }
+
+    function create_named() public returns (Child) {
+        return new Child{salt: hex"01"}();
+    }
+}
+
+contract Child {
+    uint64 public x;
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:16-27: creating contracts is not available on Stylus. Stylus programs must be activated after deployment
+// error: 7:16-42: creating contracts is not available on Stylus. Stylus programs must be activated after deployment
@@ -0,0 +1,31 @@
+contract Storage {
+    type Price is uint128;
+    enum State { Open, Closed }
+    struct Point { uint64 x; uint64 y; }
+
+    uint256 total;
+    address owner;
+    bytes32 hash;
+    State state;
+    Price price;
+    mapping(address => uint256) balances;
+    mapping(string => mapping(uint256 => bool)) seen;
+    string constant NAME = "storage";
+
+    string name;
+    bytes data;
+    uint64[] list;
+    uint64[4] fixed;
+    Point point;
+    mapping(address => bytes) blobs;
+    function() external internal callback;
+}
+
+// ---- Expect: diagnostics ----
+// error: 15:5-11: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 16:5-10: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 17:5-13: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 18:5-14: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 19:5-10: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 20:5-30: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
+// error: 21:5-33: storage of this type is not available on Stylus. Stylus storage is limited to value types, and mappings of value types
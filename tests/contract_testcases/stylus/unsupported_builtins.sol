@@ -0,0 +1,13 @@
+contract Unsupported {
+    function gone(address payable a) public {
+        selfdestruct(a);
+    }
+
+    function minimum() public view returns (uint256) {
+        return block.minimum_balance;
+    }
+}
+
+// ---- Expect: diagnostics ----
+// error: 3:9-21: 'selfdestruct' is not available on Stylus
+// error: 7:16-21: builtin 'block.minimum_balance' does not exist
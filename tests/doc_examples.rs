@@ -54,6 +54,10 @@ fn try_compile(path: &str, target: Target) -> Result<(), Diagnostics> {
             strength_reduce: true,
             vector_to_slice: true,
             common_subexpression_elimination: true,
+            storage_cache: true,
+            string_switch: true,
+            loop_unroll: true,
+            cold_split: true,
             opt_level: OptimizationLevel::Default,
             ..Default::default()
         },
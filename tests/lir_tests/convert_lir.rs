@@ -5,7 +5,7 @@ use std::ffi::OsStr;
 use solang::{
     codegen::codegen,
     file_resolver::FileResolver,
-    lir::{converter::Converter, printer::Printer},
+    lir::{constants::ConstantKind, converter::Converter, printer::Printer, Module},
     parse_and_resolve,
     sema::ast::Namespace,
     Target,
@@ -660,7 +660,7 @@ fn test_assertion_using_require() {
     assert_polkadot_lir_str_eq(
         src,
         0,
-        r#"public function sol#4 Test::Test::function::test__int32 (int32):
+        r#"public function sol#6 Test::Test::function::test__int32 (int32):
 block#0 entry:
     int32 %num = int32(arg#0);
     bool %temp.ssa_ir.1 = int32(%num) > int32(10);
@@ -670,7 +670,7 @@ block#1 noassert:
     return;
 
 block#2 doassert:
-    ptr<slice<bytes1>> %temp.ssa_ir.2 = alloc ptr<slice<bytes1>>[uint32(9)] {08, c3, 79, a0, 10, 73, 65, 73, 61};
+    ptr<slice<bytes1>> %temp.ssa_ir.2 = alloc ptr<slice<bytes1>>[uint32(9)] @0;
     assert_failure ptr<slice<bytes1>>(%temp.ssa_ir.2);"#,
     );
 }
@@ -690,7 +690,7 @@ fn test_call_1() {
     assert_polkadot_lir_str_eq(
         src,
         0,
-        r#"public function sol#4 Test::Test::function::test__int32 (int32):
+        r#"public function sol#6 Test::Test::function::test__int32 (int32):
 block#0 entry:
     int32 %num = int32(arg#0);
      = call function#1(int32(%num));
@@ -754,7 +754,7 @@ fn test_value_transfer() {
     assert_polkadot_lir_str_eq(
         src,
         0,
-        r#"public function sol#4 Test::Test::function::transfer__address_uint128 (uint8[32], uint128):
+        r#"public function sol#6 Test::Test::function::transfer__address_uint128 (uint8[32], uint128):
 block#0 entry:
     uint8[32] %addr = uint8[32](arg#0);
     uint128 %amount = uint128(arg#1);
@@ -928,7 +928,7 @@ fn test_keccak256() {
     assert_polkadot_lir_str_eq(
         src,
         0,
-        r#"public function sol#4 b::b::function::add__string_address (ptr<struct.vector<uint8>>, uint8[32]):
+        r#"public function sol#6 b::b::function::add__string_address (ptr<struct.vector<uint8>>, uint8[32]):
 block#0 entry:
     ptr<struct.vector<uint8>> %name = ptr<struct.vector<uint8>>(arg#0);
     uint8[32] %addr = uint8[32](arg#1);
@@ -960,12 +960,12 @@ fn test_internal_function_cfg() {
     assert_polkadot_lir_str_eq(
         src,
         1,
-        r#"public function sol#5 A::A::function::bar__uint256 (uint256) returns (uint256):
+        r#"public function sol#7 A::A::function::bar__uint256 (uint256) returns (uint256):
 block#0 entry:
     uint256 %b = uint256(arg#0);
     ptr<function (uint256) returns (uint256)> %temp.ssa_ir.6 = function#0;
     ptr<function (uint256) returns (uint256)> %fPtr = (cast ptr<function (uint256) returns (uint256)>(%temp.ssa_ir.6) to ptr<function (uint256) returns (uint256)>);
-    uint256 %.temp.5 = call ptr<function (uint256) returns (uint256)>(%fPtr)(uint256(%b));
+    uint256 %.temp.5 = call function#0(uint256(%b));
     return uint256(%.temp.5);"#,
     )
 }
@@ -1031,7 +1031,7 @@ block#1 out_of_bounds:
     assert_failure;
 
 block#2 in_bounds:
-    ptr<uint32[5]> %temp.ssa_ir.5 = const ptr<uint32[5]> [uint32(1), uint32(2), uint32(3), uint32(4), uint32(5)];
+    ptr<uint32[5]> %temp.ssa_ir.5 = ptr<uint32[5]> @0;
     ptr<uint32> %temp.ssa_ir.4 = ptr<uint32[5]>(%temp.ssa_ir.5)[uint32(0)];
     uint32 %temp.ssa_ir.3 = *ptr<uint32>(%temp.ssa_ir.4);
     return uint32(%temp.ssa_ir.3);"#,
@@ -1079,27 +1079,26 @@ fn test_account_meta() {
         0,
         r#"public function sol#2 creator::creator::function::create_with_metas ():
 block#0 entry:
+    ptr<struct.vector<uint8>> %scratch_buffer.temp.12 = alloc ptr<struct.vector<uint8>>[uint32(8)];
+    ptr<struct.SolAccountInfo[]> %temp.ssa_ir.13 = builtin: Accounts();
+    ptr<struct.SolAccountInfo> %temp.10 = ptr<struct.SolAccountInfo[]>(%temp.ssa_ir.13)[uint32(0)];
     ptr<struct.SolAccountInfo[]> %temp.ssa_ir.14 = builtin: Accounts();
-    ptr<struct.SolAccountInfo> %temp.10 = ptr<struct.SolAccountInfo[]>(%temp.ssa_ir.14)[uint32(0)];
-    ptr<struct.SolAccountInfo[]> %temp.ssa_ir.15 = builtin: Accounts();
-    ptr<struct.SolAccountInfo> %temp.11 = ptr<struct.SolAccountInfo[]>(%temp.ssa_ir.15)[uint32(1)];
-    ptr<ptr<uint8[32]>> %temp.ssa_ir.18 = access ptr<struct.SolAccountInfo>(%temp.10) member 0;
-    ptr<uint8[32]> %temp.ssa_ir.17 = *ptr<ptr<uint8[32]>>(%temp.ssa_ir.18);
-    ptr<struct.SolAccountMeta> %temp.ssa_ir.16 = struct { ptr<uint8[32]>(%temp.ssa_ir.17), true, true };
-    ptr<ptr<uint8[32]>> %temp.ssa_ir.21 = access ptr<struct.SolAccountInfo>(%temp.11) member 0;
-    ptr<uint8[32]> %temp.ssa_ir.20 = *ptr<ptr<uint8[32]>>(%temp.ssa_ir.21);
-    ptr<struct.SolAccountMeta> %temp.ssa_ir.19 = struct { ptr<uint8[32]>(%temp.ssa_ir.20), true, true };
-    ptr<uint8[32]> %temp.ssa_ir.23 = &uint8[32](0);
-    ptr<struct.SolAccountMeta> %temp.ssa_ir.22 = struct { ptr<uint8[32]>(%temp.ssa_ir.23), false, false };
-    ptr<struct.SolAccountMeta[3]> %metas = ptr<struct.SolAccountMeta[3]> [ptr<struct.SolAccountMeta>(%temp.ssa_ir.16), ptr<struct.SolAccountMeta>(%temp.ssa_ir.19), ptr<struct.SolAccountMeta>(%temp.ssa_ir.22)];
-    ptr<struct.vector<uint8>> %abi_encoded.temp.12 = alloc ptr<struct.vector<uint8>>[uint32(8)];
-    bytes8 %temp.ssa_ir.24 = bytes8 hex"87_2c_cd_c6_19_01_48_bc";
-    write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.12) offset:uint32(0) value:bytes8(%temp.ssa_ir.24);
-    _ = call_ext [regular] address:uint8[32](78642644713358252795404932596995255556623171005675782810573618728006773308276) payload:ptr<struct.vector<uint8>>(%abi_encoded.temp.12) value:uint64(0) gas:uint64(0) accounts:ptr<struct.SolAccountMeta[3]>(%metas) seeds:_ contract_no:1, function_no:3 flags:_;
-    ptr<struct.vector<uint8>> %abi_encoded.temp.13 = alloc ptr<struct.vector<uint8>>[uint32(8)];
-    bytes8 %temp.ssa_ir.25 = bytes8 hex"97_f8_3c_a2_18_9f_26_9d";
-    write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.13) offset:uint32(0) value:bytes8(%temp.ssa_ir.25);
-    _ = call_ext [regular] address:uint8[32](78642644713358252795404932596995255556623171005675782810573618728006773308276) payload:ptr<struct.vector<uint8>>(%abi_encoded.temp.13) value:uint64(0) gas:uint64(0) accounts:none seeds:_ contract_no:1, function_no:4 flags:_;
+    ptr<struct.SolAccountInfo> %temp.11 = ptr<struct.SolAccountInfo[]>(%temp.ssa_ir.14)[uint32(1)];
+    ptr<ptr<uint8[32]>> %temp.ssa_ir.17 = access ptr<struct.SolAccountInfo>(%temp.10) member 0;
+    ptr<uint8[32]> %temp.ssa_ir.16 = *ptr<ptr<uint8[32]>>(%temp.ssa_ir.17);
+    ptr<struct.SolAccountMeta> %temp.ssa_ir.15 = struct { ptr<uint8[32]>(%temp.ssa_ir.16), true, true };
+    ptr<ptr<uint8[32]>> %temp.ssa_ir.20 = access ptr<struct.SolAccountInfo>(%temp.11) member 0;
+    ptr<uint8[32]> %temp.ssa_ir.19 = *ptr<ptr<uint8[32]>>(%temp.ssa_ir.20);
+    ptr<struct.SolAccountMeta> %temp.ssa_ir.18 = struct { ptr<uint8[32]>(%temp.ssa_ir.19), true, true };
+    ptr<uint8[32]> %temp.ssa_ir.22 = &uint8[32](0);
+    ptr<struct.SolAccountMeta> %temp.ssa_ir.21 = struct { ptr<uint8[32]>(%temp.ssa_ir.22), false, false };
+    ptr<struct.SolAccountMeta[3]> %metas = ptr<struct.SolAccountMeta[3]> [ptr<struct.SolAccountMeta>(%temp.ssa_ir.15), ptr<struct.SolAccountMeta>(%temp.ssa_ir.18), ptr<struct.SolAccountMeta>(%temp.ssa_ir.21)];
+    bytes8 %temp.ssa_ir.23 = bytes8 hex"87_2c_cd_c6_19_01_48_bc";
+    write_buf ptr<struct.vector<uint8>>(%scratch_buffer.temp.12) offset:uint32(0) value:bytes8(%temp.ssa_ir.23);
+    _ = call_ext [regular] address:uint8[32](78642644713358252795404932596995255556623171005675782810573618728006773308276) payload:ptr<struct.vector<uint8>>(%scratch_buffer.temp.12) value:uint64(0) gas:uint64(0) accounts:ptr<struct.SolAccountMeta[3]>(%metas) seeds:_ contract_no:1, function_no:3 flags:_;
+    bytes8 %temp.ssa_ir.24 = bytes8 hex"97_f8_3c_a2_18_9f_26_9d";
+    write_buf ptr<struct.vector<uint8>>(%scratch_buffer.temp.12) offset:uint32(0) value:bytes8(%temp.ssa_ir.24);
+    _ = call_ext [regular] address:uint8[32](78642644713358252795404932596995255556623171005675782810573618728006773308276) payload:ptr<struct.vector<uint8>>(%scratch_buffer.temp.12) value:uint64(0) gas:uint64(0) accounts:none seeds:_ contract_no:1, function_no:4 flags:_;
     return;"#,
     )
 }
@@ -1124,14 +1123,14 @@ fn test_constructor() {
     assert_polkadot_lir_str_eq(
         src,
         0,
-        r#"public function sol#4 B::B::function::test__uint256 (uint256):
+        r#"public function sol#6 B::B::function::test__uint256 (uint256):
 block#0 entry:
     uint256 %a = uint256(arg#0);
     ptr<struct.vector<uint8>> %abi_encoded.temp.18 = alloc ptr<struct.vector<uint8>>[uint32(36)];
     uint32 %temp.ssa_ir.20 = uint32 hex"58_16_c4_25";
     write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.18) offset:uint32(0) value:uint32(%temp.ssa_ir.20);
     write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.18) offset:uint32(4) value:uint256(%a);
-    uint32 %success.temp.17, uint8[32] %temp.16 = constructor(no: 6, contract_no:1) salt:_ value:_ gas:uint64(0) address:_ seeds:_ encoded-buffer:ptr<struct.vector<uint8>>(%abi_encoded.temp.18) accounts:absent
+    uint32 %success.temp.17, uint8[32] %temp.16 = constructor(no: 8, contract_no:1) salt:_ value:_ gas:uint64(0) address:_ seeds:_ encoded-buffer:ptr<struct.vector<uint8>>(%abi_encoded.temp.18) accounts:absent
     switch uint32(%success.temp.17):
     case:    uint32(0) => block#1, 
     case:    uint32(2) => block#2
@@ -1286,7 +1285,7 @@ block#0 entry:
     uint64 %temp.ssa_ir.15 = uint64(%temp.ssa_ir.16) (of)* uint64(600);
     uint256 %temp.9 = (zext uint64(%temp.ssa_ir.15) to uint256);
     set_storage uint32(64) uint256(%temp.9);
-    ptr<struct.vector<uint8>> %temp.10 = alloc ptr<slice<bytes1>>[uint32(0)] {};
+    ptr<struct.vector<uint8>> %temp.10 = alloc ptr<slice<bytes1>>[uint32(0)] @0;
     set_storage uint32(96) ptr<struct.vector<uint8>>(%temp.10);
     bytes1 %temp.11 = 255;
     set_storage_bytes uint32(100) offset:uint32(1) value:bytes1(255);
@@ -1460,17 +1459,17 @@ fn test_call_ext() {
         r#"public function sol#2 adult::adult::function::test__address (uint8[32]):
 block#0 entry:
     uint8[32] %id = uint8[32](arg#0);
-    ptr<struct.vector<uint8>> %temp.ssa_ir.15 = alloc ptr<struct.vector<uint8>>[uint32(4)] {6c, 75, 6e, 61};
+    ptr<struct.vector<uint8>> %temp.ssa_ir.15 = alloc ptr<struct.vector<uint8>>[uint32(4)] @0;
     uint32 %temp.ssa_ir.14 = builtin: ArrayLength(ptr<struct.vector<uint8>>(%temp.ssa_ir.15));
     uint32 %temp.ssa_ir.13 = uint32(%temp.ssa_ir.14) + uint32(4);
     uint32 %temp.ssa_ir.12 = uint32(8) + uint32(%temp.ssa_ir.13);
     ptr<struct.vector<uint8>> %abi_encoded.temp.10 = alloc ptr<struct.vector<uint8>>[uint32(%temp.ssa_ir.12)];
     bytes8 %temp.ssa_ir.16 = bytes8 hex"87_2c_cd_c6_19_01_48_bc";
     write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.10) offset:uint32(0) value:bytes8(%temp.ssa_ir.16);
-    ptr<struct.vector<uint8>> %temp.ssa_ir.17 = alloc ptr<struct.vector<uint8>>[uint32(4)] {6c, 75, 6e, 61};
+    ptr<struct.vector<uint8>> %temp.ssa_ir.17 = alloc ptr<struct.vector<uint8>>[uint32(4)] @0;
     uint32 %temp.11 = builtin: ArrayLength(ptr<struct.vector<uint8>>(%temp.ssa_ir.17));
     write_buf ptr<struct.vector<uint8>>(%abi_encoded.temp.10) offset:uint32(8) value:uint32(%temp.11);
-    ptr<struct.vector<uint8>> %temp.ssa_ir.18 = alloc ptr<struct.vector<uint8>>[uint32(4)] {6c, 75, 6e, 61};
+    ptr<struct.vector<uint8>> %temp.ssa_ir.18 = alloc ptr<struct.vector<uint8>>[uint32(4)] @0;
     ptr<uint8> %temp.ssa_ir.19 = ptr_add(ptr<struct.vector<uint8>>(%abi_encoded.temp.10), uint32(12));
     memcopy ptr<struct.vector<uint8>>(%temp.ssa_ir.18) to ptr<uint8>(%temp.ssa_ir.19) for uint32(%temp.11) bytes;
     ptr<struct.SolAccountInfo[]> %temp.ssa_ir.25 = builtin: Accounts();
@@ -1522,8 +1521,8 @@ fn test_fmt_string_and_print() {
         0,
         r#"public function sol#2 Test::Test::function::test ():
 block#0 entry:
-    ptr<struct.vector<uint8>> %temp.ssa_ir.2 = ptr<struct.vector<uint8>> hex"4e_75_6d_62_65_72_3a_20";
-    ptr<struct.vector<uint8>> %temp.ssa_ir.1 = fmt_str(ptr<struct.vector<uint8>>(%temp.ssa_ir.2), uint8(123));
+    ptr<struct.vector<uint8>> %temp.ssa_ir.2 = ptr<struct.vector<uint8>> hex"4e_75_6d_62_65_72_3a_20_31_32_33";
+    ptr<struct.vector<uint8>> %temp.ssa_ir.1 = fmt_str(ptr<struct.vector<uint8>>(%temp.ssa_ir.2));
     print ptr<struct.vector<uint8>>(%temp.ssa_ir.1);
     return;"#,
     )
@@ -1547,3 +1546,98 @@ block#0 entry:
     return bytes14(%temp.ssa_ir.2);"#,
     )
 }
+
+#[test]
+fn test_undefined() {
+    let src = r#"contract Test {
+        function test() public pure returns (int64[] memory) {
+            int64[] memory a;
+            return a;
+        }
+    }"#;
+
+    assert_solana_lir_str_eq(
+        src,
+        0,
+        r#"public function sol#2 Test::Test::function::test () returns (ptr<int64[]>):
+block#0 entry:
+    ptr<int64[]> %a = undef;
+    uint32 %array_length.temp.2 = 0;
+    return ptr<int64[]>(%a);"#,
+    )
+}
+
+#[test]
+fn test_print_contract_lir() {
+    let src = r#"contract Test {
+        function test(int64 a) public pure returns (int64) {
+            return a + 1;
+        }
+    }"#;
+
+    let mut resolver = new_file_resolver(src);
+    let mut ns: Namespace =
+        parse_and_resolve(OsStr::new("test.sol"), &mut resolver, Target::Solana);
+    codegen(&mut ns, &Default::default());
+
+    let lir = ns.contracts[0].print_lir(&ns);
+
+    assert!(lir.starts_with("#\n# Contract: Test\n#\n\n@0 = selector hex"));
+    assert!(lir.contains(
+        "\npublic function sol#2 Test::Test::function::test__int64 (int64) returns (int64):\n"
+    ));
+    assert!(lir.contains("\nprivate function none solang_dispatch ():\n"));
+}
+
+#[test]
+fn test_constant_pool() {
+    let src = r#"contract Test {
+        uint32[3] constant primes = [2, 3, 5];
+
+        function hello() public pure returns (bytes) {
+            return "luna";
+        }
+
+        function world() public pure returns (bytes) {
+            return "luna";
+        }
+
+        function prime(uint32 i) public pure returns (uint32) {
+            return primes[i];
+        }
+    }"#;
+
+    let mut resolver = new_file_resolver(src);
+    let mut ns: Namespace =
+        parse_and_resolve(OsStr::new("test.sol"), &mut resolver, Target::Solana);
+    codegen(&mut ns, &Default::default());
+
+    let module = Module::new(&ns.contracts[0], &ns);
+
+    let bytes = module
+        .constants
+        .iter()
+        .filter(|constant| constant.kind == ConstantKind::Bytes)
+        .collect::<Vec<_>>();
+
+    // the same literal in two functions is only present once
+    assert_eq!(bytes.len(), 1);
+    assert_eq!(bytes[0].data, b"luna");
+
+    let arrays = module
+        .constants
+        .iter()
+        .filter(|constant| constant.kind == ConstantKind::Array)
+        .collect::<Vec<_>>();
+
+    assert_eq!(arrays.len(), 1);
+    assert_eq!(arrays[0].data, vec![2, 0, 0, 0, 3, 0, 0, 0, 5, 0, 0, 0]);
+
+    let selectors = module
+        .constants
+        .iter()
+        .filter(|constant| constant.kind == ConstantKind::Selector)
+        .count();
+
+    assert_eq!(selectors, 4);
+}
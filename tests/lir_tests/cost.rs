@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use solang::{
+    codegen::codegen, file_resolver::FileResolver, lir::cost::gen_cost_report, parse_and_resolve,
+    Target,
+};
+use std::ffi::OsStr;
+
+#[test]
+fn cost_report() {
+    let src = r#"
+contract Counter {
+    uint64 count;
+    event Counted(uint64 indexed count);
+
+    function get() public view returns (uint64) {
+        return count;
+    }
+
+    function increment() public {
+        count = add(count, 1);
+        emit Counted(count);
+    }
+
+    function hash(bytes memory data) public pure returns (bytes32) {
+        return keccak256(data);
+    }
+
+    function repeat(uint64 n) public {
+        for (uint64 i = 0; i < n; i++) {
+            count += i;
+        }
+    }
+
+    function add(uint64 a, uint64 b) internal pure returns (uint64) {
+        return a + b;
+    }
+}"#;
+
+    let mut resolver = FileResolver::default();
+    resolver.set_file_contents("test.sol", src.to_string());
+    let mut ns = parse_and_resolve(
+        OsStr::new("test.sol"),
+        &mut resolver,
+        Target::default_polkadot(),
+    );
+    codegen(&mut ns, &Default::default());
+
+    let report = gen_cost_report(0, &ns);
+
+    assert_eq!(report.unit, "weight");
+
+    let functions: Vec<(&str, bool)> = report
+        .functions
+        .iter()
+        .map(|f| (f.function.as_str(), f.bounded))
+        .collect();
+
+    // internal functions are not listed, and the loop makes repeat() unbounded
+    assert_eq!(
+        functions,
+        vec![
+            ("get()", true),
+            ("increment()", true),
+            ("hash(bytes)", true),
+            ("repeat(uint64)", false),
+            ("constructor()", true),
+        ]
+    );
+
+    let get = &report.functions[0];
+    assert_eq!(get.selector, "0x6d4ce63c");
+    assert_eq!(get.cost.storage, 25_000_000);
+    assert_eq!(get.total, get.cost.storage + get.cost.compute);
+
+    let increment = &report.functions[1];
+    // two loads and a store
+    assert_eq!(increment.cost.storage, 150_000_000);
+    assert!(increment.cost.events > 0);
+    assert!(increment.cost.encoding > 0);
+
+    let hash = &report.functions[2];
+    assert_eq!(hash.cost.storage, 0);
+    assert_eq!(hash.cost.hashing, 2_000_000);
+
+    // the loop body is counted once
+    let repeat = &report.functions[3];
+    assert_eq!(repeat.cost.storage, 125_000_000);
+}
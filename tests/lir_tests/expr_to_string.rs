@@ -109,11 +109,27 @@ fn test_stringfy_binary_expr() {
     assert_eq!(
         stringfy_expr!(
             &printer,
-            &binop_expr(identifier(1), BinaryOperator::Div, identifier(2))
+            &binop_expr(
+                identifier(1),
+                BinaryOperator::Div { overflowing: false },
+                identifier(2)
+            )
         ),
         "int16(%temp.ssa_ir.1) / int16(%temp.ssa_ir.2)"
     );
 
+    assert_eq!(
+        stringfy_expr!(
+            &printer,
+            &binop_expr(
+                identifier(1),
+                BinaryOperator::Div { overflowing: true },
+                identifier(2)
+            )
+        ),
+        "int16(%temp.ssa_ir.1) (of)/ int16(%temp.ssa_ir.2)"
+    );
+
     // UDiv,
     assert_eq!(
         stringfy_expr!(
@@ -669,8 +685,8 @@ fn test_stringfy_alloc_dyn_bytes() {
     );
 
     // case2: allocating a dynamic bytes with initializer:
-    //        Solidity: bytes memory a = new bytes(3) { 0x01, 0x02, 0x03 };
-    //        rhs print: alloc bytes1[] {0x01, 0x02, 0x03}
+    //        Solidity: bytes memory a = hex"010203";
+    //        rhs print: alloc bytes1[uint8(3)] @0, where @0 is the constant hex"010203"
     assert_eq!(
         stringfy_expr!(
             &new_printer(&new_vartable()),
@@ -678,10 +694,10 @@ fn test_stringfy_alloc_dyn_bytes() {
                 loc: Loc::Codegen,
                 ty: new_lir_type(Type::Bytes(1)),
                 size: Box::new(num_literal!(3)),
-                initializer: Some(vec![b'\x01', b'\x02', b'\x03']),
+                initializer: Some(0),
             }
         ),
-        "alloc bytes1[uint8(3)] {01, 02, 03}"
+        "alloc bytes1[uint8(3)] @0"
     );
 }
 
@@ -0,0 +1,173 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use solang::{
+    codegen::codegen,
+    file_resolver::FileResolver,
+    lir::heap::{gen_heap_report, heap_size},
+    parse_and_resolve,
+    sema::ast::Namespace,
+    Target,
+};
+use std::ffi::OsStr;
+
+fn build(src: &str, target: Target) -> Namespace {
+    let mut resolver = FileResolver::default();
+    resolver.set_file_contents("test.sol", src.to_string());
+    let mut ns = parse_and_resolve(OsStr::new("test.sol"), &mut resolver, target);
+    codegen(&mut ns, &Default::default());
+    ns
+}
+
+#[test]
+fn heap_report() {
+    let src = r#"
+contract Allocs {
+    struct Pair {
+        uint64 a;
+        uint64 b;
+    }
+
+    string name;
+
+    function pair(uint64 a) public pure returns (uint64) {
+        Pair memory p = Pair(a, a);
+        return p.a + p.b;
+    }
+
+    function buffers(uint64 n) public pure returns (uint64) {
+        uint64 total = 0;
+        for (uint64 i = 0; i < n; i++) {
+            bytes memory b = new bytes(n);
+            total += uint64(b.length);
+        }
+        uint32[] memory fixed = new uint32[](4);
+        return total + fixed[0];
+    }
+
+    function greet() public view returns (string memory) {
+        return name;
+    }
+
+    function squares(uint32 n) public pure returns (uint64[] memory) {
+        uint64[] memory a = new uint64[](0);
+        a.reserve(8);
+        for (uint64 i = 0; i < n; i++) {
+            a.push(i * i);
+        }
+        return a;
+    }
+
+    function add(uint64 a, uint64 b) public pure returns (uint64) {
+        return a + b;
+    }
+}"#;
+
+    let ns = build(src, Target::Solana);
+    let report = gen_heap_report(0, &ns);
+
+    assert_eq!(report.heap_size, Some(32 * 1024));
+
+    // functions which do not allocate are left out
+    assert!(report
+        .functions
+        .iter()
+        .all(|f| f.function != "add(uint64,uint64)"));
+
+    let pair = report
+        .functions
+        .iter()
+        .find(|f| f.function == "pair(uint64)")
+        .unwrap();
+
+    assert_eq!(pair.sites.len(), 1);
+    assert_eq!(pair.sites[0].kind, "struct literal");
+    assert_eq!(pair.sites[0].size, Some(16));
+    assert!(!pair.sites[0].in_loop);
+    assert_eq!(pair.fixed, 16);
+
+    let buffers = report
+        .functions
+        .iter()
+        .find(|f| f.function == "buffers(uint64)")
+        .unwrap();
+
+    assert_eq!(buffers.sites.len(), 2);
+    assert_eq!(buffers.sites[0].kind, "new");
+    assert_eq!(buffers.sites[0].size, None);
+    assert!(buffers.sites[0].in_loop);
+    assert_eq!(buffers.sites[1].kind, "new");
+    assert_eq!(buffers.sites[1].size, Some(8 + 4 * 4));
+    assert!(!buffers.sites[1].in_loop);
+    assert_eq!(buffers.fixed, 24);
+
+    let greet = report
+        .functions
+        .iter()
+        .find(|f| f.function == "greet()")
+        .unwrap();
+
+    assert!(greet.sites.iter().any(|site| site.kind == "storage load"));
+
+    let squares = report
+        .functions
+        .iter()
+        .find(|f| f.function == "squares(uint32)")
+        .unwrap();
+
+    assert_eq!(squares.sites.len(), 3);
+    assert_eq!(squares.sites[0].kind, "new");
+    assert_eq!(squares.sites[0].size, Some(8));
+    assert_eq!(squares.sites[1].kind, "reserve");
+    assert_eq!(squares.sites[1].size, Some(8 + 8 * 8));
+    assert_eq!(squares.sites[2].kind, "push");
+    assert_eq!(squares.sites[2].size, None);
+    assert!(squares.sites[2].in_loop);
+    assert!(squares.sites[2].loc.starts_with("test.sol:"));
+
+    // the heap size is only reported on Solana
+    let ns = build(src, Target::default_polkadot());
+    let report = gen_heap_report(0, &ns);
+
+    assert_eq!(report.heap_size, None);
+}
+
+#[test]
+fn heap_size_tag() {
+    let src = r#"
+/// @custom:heap-size 65536
+contract Large {
+    function f() public pure {}
+}
+
+/// @custom:heap-size 40000
+contract Odd {
+    function f() public pure {}
+}
+
+/// @custom:heap-size lots
+contract Invalid {
+    function f() public pure {}
+}
+
+contract Default {
+    function f() public pure {}
+}"#;
+
+    let ns = build(src, Target::Solana);
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(
+        errors[0].message,
+        "invalid heap size '40000', expected a multiple of 1024 bytes from 32768 to 262144"
+    );
+    assert_eq!(
+        errors[1].message,
+        "invalid heap size 'lots', expected a multiple of 1024 bytes from 32768 to 262144"
+    );
+
+    assert_eq!(heap_size(0, &ns), 65536);
+    assert_eq!(heap_size(1, &ns), 32 * 1024);
+    assert_eq!(heap_size(3, &ns), 32 * 1024);
+}
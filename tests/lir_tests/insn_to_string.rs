@@ -181,6 +181,33 @@ fn test_stringfy_pop_memory_insn() {
     );
 }
 
+#[test]
+fn test_stringfy_reserve_memory_insn() {
+    let mut v = new_vartable();
+
+    set_tmp(
+        &mut v,
+        3,
+        Type::Ptr(Box::new(Type::Array(
+            Box::new(Type::Uint(32)),
+            vec![ArrayLength::Dynamic],
+        ))),
+    );
+    let printer = new_printer(&v);
+
+    assert_eq!(
+        stringfy_insn!(
+            &printer,
+            &Instruction::ReserveMemory {
+                loc: Loc::Codegen,
+                array: 3,
+                size: num_literal!(10, 32),
+            }
+        ),
+        "reserve_mem ptr<uint32[]>(%temp.ssa_ir.3) uint32(10);"
+    );
+}
+
 // LoadStorage
 #[test]
 fn test_stringfy_load_storage_insn() {
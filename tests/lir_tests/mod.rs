@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod convert_lir;
+mod cost;
 mod expr_to_string;
+mod heap;
 mod helpers;
 mod insn_to_string;
 mod lir_to_string;
+mod stack;
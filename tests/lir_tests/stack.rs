@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use solang::{
+    codegen::codegen, file_resolver::FileResolver, lir::stack::gen_stack_report, parse_and_resolve,
+    sema::ast::Namespace, Target,
+};
+use std::ffi::OsStr;
+
+fn build(src: &str, target: Target) -> Namespace {
+    let mut resolver = FileResolver::default();
+    resolver.set_file_contents("test.sol", src.to_string());
+    let mut ns = parse_and_resolve(OsStr::new("test.sol"), &mut resolver, target);
+    codegen(&mut ns, &Default::default());
+    ns
+}
+
+#[test]
+fn stack_report() {
+    let src = r#"
+contract Frames {
+    function outer(uint64 a) public pure returns (uint64) {
+        return middle(a) + 1;
+    }
+
+    function middle(uint64 a) internal pure returns (uint64) {
+        uint64[64] memory buf;
+        buf[1] = a;
+        return inner(buf[1]);
+    }
+
+    function inner(uint64 a) internal pure returns (uint64) {
+        return a * 2;
+    }
+
+    function fib(uint64 n) public pure returns (uint64) {
+        if (n < 2) {
+            return n;
+        }
+        return fib(n - 1) + fib(n - 2);
+    }
+}"#;
+
+    let ns = build(src, Target::Solana);
+    let report = gen_stack_report(0, &ns);
+
+    assert_eq!(report.frame_limit, Some(4096));
+    assert_eq!(report.call_depth_limit, Some(64));
+
+    let outer = &report.functions[0];
+    assert_eq!(outer.function, "outer(uint64)");
+    assert!(outer.public);
+    assert!(outer.bounded);
+    assert_eq!(outer.depth, 3);
+    assert_eq!(
+        outer.path,
+        vec!["outer(uint64)", "middle(uint64)", "inner(uint64)"]
+    );
+
+    let middle = &report.functions[1];
+    let inner = &report.functions[2];
+    assert!(!middle.public);
+    assert_eq!(inner.stack, inner.frame);
+    assert_eq!(middle.stack, middle.frame + inner.stack);
+    assert_eq!(outer.stack, outer.frame + middle.stack);
+
+    // recursion is not bounded
+    let fib = &report.functions[3];
+    assert_eq!(fib.function, "fib(uint64)");
+    assert!(!fib.bounded);
+
+    // no limits on other targets
+    let ns = build(src, Target::default_polkadot());
+    let report = gen_stack_report(0, &ns);
+
+    assert_eq!(report.frame_limit, None);
+    assert_eq!(report.call_depth_limit, None);
+}
+
+#[test]
+fn stack_budget() {
+    let src = r#"
+contract Budget {
+    /// @custom:stack-budget 4096
+    function roomy(uint64 a) public pure returns (uint64) {
+        return helper(a);
+    }
+
+    /// @custom:stack-budget 16
+    function tight(uint64 a) public pure returns (uint64) {
+        return helper(a) + 1;
+    }
+
+    /// @custom:stack-budget 4096
+    function fib(uint64 n) public pure returns (uint64) {
+        if (n < 2) {
+            return n;
+        }
+        return fib(n - 1) + fib(n - 2);
+    }
+
+    /// @custom:stack-budget lots
+    function invalid() public pure {}
+
+    function helper(uint64 a) internal pure returns (uint64) {
+        return a * 2;
+    }
+}"#;
+
+    let ns = build(src, Target::Solana);
+
+    let errors = ns.diagnostics.errors();
+
+    assert_eq!(errors.len(), 3);
+    assert!(errors[0].message.starts_with("function 'tight' may use "));
+    assert!(errors[0]
+        .message
+        .ends_with(" bytes of stack, which exceeds its stack budget of 16 bytes"));
+    assert_eq!(errors[0].notes.len(), 1);
+    assert!(errors[0].notes[0]
+        .message
+        .starts_with("calls function 'helper' with a frame of "));
+    assert_eq!(
+        errors[1].message,
+        "function 'fib' has a stack budget of 4096 bytes, but its stack usage is unbounded \
+        because of recursion or calls through function pointers"
+    );
+    assert_eq!(
+        errors[2].message,
+        "invalid stack budget 'lots', expected a number of bytes"
+    );
+
+    let report = gen_stack_report(0, &ns);
+
+    assert_eq!(report.functions[0].budget, Some(4096));
+    assert_eq!(report.functions[1].budget, Some(16));
+}
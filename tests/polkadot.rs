@@ -565,10 +565,12 @@ impl Runtime {
         Ok(())
     }
 
-    #[seal(1)]
+    #[seal(2)]
     fn instantiate(
         code_hash_ptr: u32,
-        _gas: u64,
+        _ref_time_limit: u64,
+        _proof_size_limit: u64,
+        _deposit_limit_ptr: u32,
         value_ptr: u32,
         input_data_ptr: u32,
         input_data_len: u32,
@@ -38,13 +38,6 @@ fn abi_decode() {
 fn abi_encode() {
     let mut runtime = build_solidity(
         r#"
-        struct s {
-            int32 f1;
-            uint8 f2;
-            string f3;
-            uint16[2] f4;
-        }
-
         contract bar {
             function test() public {
                 uint16 a = 0xfd01;
@@ -106,9 +99,9 @@ fn abi_encode_packed() {
             }
 
             function test3() public {
-                s x = s({ f1: 511, f2: 0xf7, f3: "testie", f4: [ uint16(4), 5 ] });
+                uint16[2] f4 = [ uint16(4), 5 ];
 
-                assert(abi.encodePacked(x) == hex"ff010000f774657374696504000500");
+                assert(abi.encodePacked(int32(511), uint8(0xf7), "testie", f4) == hex"ff010000f774657374696504000500");
             }
         }"#,
     );
@@ -335,6 +328,20 @@ fn block() {
     );
 
     runtime.function("test", Vec::new());
+
+    let mut runtime = build_solidity(
+        r##"
+        import { minimum_balance } from "polkadot";
+
+        contract bar {
+            function test() public view returns (uint128) {
+                return minimum_balance();
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+    assert_eq!(runtime.output(), 500u128.encode());
 }
 
 #[test]
@@ -569,3 +569,37 @@ fn internal_dyn_call_on_nil() {
         PanicData::from(InternalFunctionUninitialized).encode()
     );
 }
+
+#[test]
+fn nonreentrant() {
+    let mut runtime = build_solidity(
+        r#"contract Guarded {
+        uint64 count;
+
+        @nonreentrant(true)
+        function inc() public returns (uint64) {
+            count += 1;
+            return count;
+        }
+
+        @nonreentrant(true)
+        function twice() public {
+            inc();
+            inc();
+        }
+    }"#,
+    );
+
+    // the guard is cleared when the function returns
+    runtime.function("inc", vec![]);
+    assert_eq!(runtime.output(), 1u64.encode());
+    runtime.function("inc", vec![]);
+    assert_eq!(runtime.output(), 2u64.encode());
+
+    runtime.function_expect_failure("twice", vec![]);
+    assert!(runtime.debug_buffer().contains("reentrant call"));
+    assert_eq!(
+        runtime.output(),
+        ErrorData::from("reentrant call".to_string()).encode()
+    );
+}
@@ -316,3 +316,38 @@ fn different_contract() {
     };
     assert_eq!(event.topics[1], topic_hash(&expected_topic.encode()));
 }
+
+#[test]
+fn hashed_topics() {
+    let mut runtime = build_solidity(
+        r##"
+        contract a {
+            event E(string indexed s, bool indexed b);
+            function emit_event(string s) public {
+                emit E(s, true);
+            }
+        }"##,
+    );
+
+    let s = "this string is long enough for the topic to be hashed".to_string();
+
+    runtime.constructor(0, Vec::new());
+    runtime.function("emit_event", s.encode());
+
+    assert_eq!(runtime.events().len(), 1);
+    let event = &runtime.events()[0];
+    assert_eq!(event.data, (0u8, s.clone(), true).encode());
+    assert_eq!(event.topics.len(), 3);
+    assert_eq!(event.topics[0], topic_hash(b"\0a::E"));
+    let expected_topic = PrefixedValue {
+        prefix: b"a::E::s",
+        value: &s,
+    };
+    assert_eq!(event.topics[1], topic_hash(&expected_topic.encode()));
+    // the short topic must not contain any bytes of the hashed topic before it
+    let expected_topic = PrefixedValue {
+        prefix: b"a::E::b",
+        value: &true,
+    };
+    assert_eq!(event.topics[2], topic_hash(&expected_topic.encode()));
+}
@@ -974,6 +974,67 @@ fn test_power_overflow_boundaries() {
     }
 }
 
+#[test]
+fn signed_power() {
+    let encode = |n: BigInt, len: usize| {
+        let fill = if n.sign() == Sign::Minus { 0xff } else { 0 };
+        let mut data = n.to_signed_bytes_le();
+        data.resize(len, fill);
+        data
+    };
+
+    for width in [8, 16, 32, 64, 128, 256] {
+        let src = r#"
+        contract test {
+            function cube(intN a) public returns (intN) {
+                return a ** 3;
+            }
+
+            function pow(intN a, uintN b) public returns (intN) {
+                return a ** b;
+            }
+        }"#
+        .replace("intN", &format!("int{width}"));
+
+        let mut contract = build_solidity_with_options(&src, false);
+
+        let width_rounded = (width / 8usize).next_power_of_two();
+
+        // -5 ** 3 = -125
+        contract.function("cube", encode(BigInt::from(-5), width_rounded));
+
+        assert_eq!(
+            contract.output()[..width / 8],
+            encode(BigInt::from(-125), width / 8)
+        );
+
+        // -2 ** (width - 1) is the smallest value of the type
+        let base_data = encode(BigInt::from(-2), width_rounded);
+
+        contract.function(
+            "pow",
+            base_data
+                .iter()
+                .copied()
+                .chain(encode(BigInt::from(width - 1), width_rounded))
+                .collect(),
+        );
+
+        let res = BigInt::from(-2).pow((width - 1).try_into().unwrap());
+
+        assert_eq!(contract.output()[..width / 8], encode(res, width / 8));
+
+        // -2 ** width does not fit
+        contract.function_expect_failure(
+            "pow",
+            base_data
+                .into_iter()
+                .chain(encode(BigInt::from(width), width_rounded))
+                .collect(),
+        );
+    }
+}
+
 #[test]
 fn multiply() {
     let mut rng = rand::thread_rng();
@@ -1776,6 +1837,106 @@ fn unchecked_multiplication_overflow() {
     runtime.function("bar", Vec::new());
 }
 
+#[test]
+fn signed_division_overflow() {
+    let mut runtime = build_solidity(
+        r#"
+        contract c {
+            function div(int64 x, int64 y) public returns (int64) {
+                return x / y;
+            }
+
+            function div256(int256 x, int256 y) public returns (int256) {
+                return x / y;
+            }
+
+            function unchecked_div(int64 x, int64 y) public returns (int64) {
+                unchecked {
+                    return x / y;
+                }
+            }
+
+            function modulo(int64 x, int64 y) public returns (int64) {
+                return x % y;
+            }
+
+            function neg(int64 x) public returns (int64) {
+                return -x;
+            }
+        }"#,
+    );
+
+    runtime.function("div", (i64::MIN, 1i64).encode());
+    assert_eq!(runtime.output(), i64::MIN.encode());
+
+    runtime.function_expect_failure("div", (i64::MIN, -1i64).encode());
+
+    let mut min256 = [0u8; 32];
+    min256[31] = 0x80;
+    let minus_one = [0xffu8; 32];
+
+    runtime.function_expect_failure("div256", (min256, minus_one).encode());
+
+    runtime.function("unchecked_div", (i64::MIN, -1i64).encode());
+    assert_eq!(runtime.output(), i64::MIN.encode());
+
+    runtime.function("unchecked_div", (-7i64, -1i64).encode());
+    assert_eq!(runtime.output(), 7i64.encode());
+
+    runtime.function("modulo", (i64::MIN, -1i64).encode());
+    assert_eq!(runtime.output(), 0i64.encode());
+
+    runtime.function_expect_failure("neg", i64::MIN.encode());
+}
+
+#[test]
+fn shift_by_width() {
+    let mut runtime = build_solidity(
+        r#"
+        contract c {
+            function shl(uint64 x, uint64 y) public returns (uint64) {
+                return x << y;
+            }
+
+            function sar(int64 x, uint64 y) public returns (int64) {
+                return x >> y;
+            }
+
+            function shl_wide(uint8 x, uint256 y) public returns (uint8) {
+                return x << y;
+            }
+
+            function shl_narrow(int256 x, uint8 y) public returns (int256) {
+                return x << y;
+            }
+        }"#,
+    );
+
+    runtime.function("shl", (1u64, 63u64).encode());
+    assert_eq!(runtime.output(), (1u64 << 63).encode());
+
+    runtime.function("shl", (1u64, 64u64).encode());
+    assert_eq!(runtime.output(), 0u64.encode());
+
+    runtime.function("sar", (-8i64, 200u64).encode());
+    assert_eq!(runtime.output(), (-1i64).encode());
+
+    runtime.function("sar", (8i64, 64u64).encode());
+    assert_eq!(runtime.output(), 0i64.encode());
+
+    let mut amount = [0u8; 32];
+    amount[1] = 1;
+    runtime.function("shl_wide", (1u8, amount).encode());
+    assert_eq!(runtime.output(), 0u8.encode());
+
+    let mut one = [0u8; 32];
+    one[0] = 1;
+    runtime.function("shl_narrow", (one, 200u8).encode());
+    let mut expected = [0u8; 32];
+    expected[25] = 1;
+    assert_eq!(runtime.output(), expected.encode());
+}
+
 #[test]
 fn address_compare() {
     #[derive(Debug, PartialEq, Eq, Encode, Decode)]
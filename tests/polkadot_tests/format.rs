@@ -242,6 +242,22 @@ fn output() {
     runtime.function("e", Vec::new());
 
     assert_eq!(runtime.output(), "number<2>".encode());
+
+    let mut runtime = build_solidity(
+        r#"
+        contract format {
+            function foo() public payable {
+                print("value:{} hex:{:x}".format(msg.value, msg.value));
+            }
+        }"#,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.set_transferred_value(1000);
+    runtime.function("foo", Vec::new());
+
+    assert_eq!(runtime.debug_buffer(), "print: value:1000 hex:0x3e8,\n");
 }
 
 #[test]
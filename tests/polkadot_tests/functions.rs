@@ -158,6 +158,65 @@ fn fallback() {
     assert_eq!(runtime.output(), Val(356).encode());
 }
 
+#[test]
+fn fallback_bytes() {
+    let mut runtime = build_solidity(
+        r#"
+        contract test {
+            uint32 public received;
+
+            fallback(bytes calldata input) external payable returns (bytes memory) {
+                received = uint32(msg.value);
+                return input;
+            }
+        }"#,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    // the call data is returned as it is, without ABI encoding
+    runtime.raw_function([0xaa, 0xbb, 0xcc, 0xdd, 0xff].to_vec());
+    assert_eq!(runtime.output(), [0xaa, 0xbb, 0xcc, 0xdd, 0xff]);
+
+    // call data which is too short for a selector
+    runtime.raw_function([0x01].to_vec());
+    assert_eq!(runtime.output(), [0x01]);
+
+    // without receive(), a payable fallback gets the value
+    runtime.set_transferred_value(5);
+    runtime.raw_function([0xaa, 0xbb, 0xcc, 0xdd].to_vec());
+    runtime.function("received", Vec::new());
+    assert_eq!(runtime.output(), 5u32.encode());
+}
+
+#[test]
+fn receive_empty_call_data() {
+    let mut runtime = build_solidity(
+        r#"
+        contract test {
+            int32 public x;
+
+            fallback() external {
+                x = 2;
+            }
+
+            receive() external payable {
+                x = 3;
+            }
+        }"#,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.raw_function(Vec::new());
+    runtime.function("x", Vec::new());
+    assert_eq!(runtime.output(), 3i32.encode());
+
+    runtime.raw_function([0xaa, 0xbb, 0xcc, 0xdd].to_vec());
+    runtime.function("x", Vec::new());
+    assert_eq!(runtime.output(), 2i32.encode());
+}
+
 #[test]
 fn function_wrong_selector() {
     let mut runtime = build_solidity(
@@ -29,6 +29,10 @@ mod statements;
 mod storage;
 mod strings;
 mod structs;
+#[cfg(feature = "test_runner")]
+mod test_runner;
+#[cfg(feature = "testing")]
+mod testing;
 mod value;
 mod variables;
 mod yul;
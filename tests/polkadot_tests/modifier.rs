@@ -184,14 +184,17 @@ fn inherit_modifier() {
 
     runtime.constructor(0, Vec::new());
 
-    let mut slot = [0u8; 32];
-    slot[0] = 1;
+    // s1 and s2 share a slot, and s2 is after s1
+    let slot = [0u8; 32];
 
     assert_eq!(runtime.storage().get(&slot), None);
 
     runtime.function("test", Vec::new());
 
-    assert_eq!(runtime.storage().get(&slot).unwrap(), &vec!(7, 0, 0, 0));
+    assert_eq!(
+        runtime.storage().get(&slot).unwrap(),
+        &vec!(0, 7, 0, 0, 0, 0, 0, 0)
+    );
 
     // now override it
     let mut runtime = build_solidity(
@@ -223,14 +226,16 @@ fn inherit_modifier() {
 
     runtime.constructor(0, Vec::new());
 
-    let mut slot = [0u8; 32];
-    slot[0] = 1;
+    let slot = [0u8; 32];
 
     assert_eq!(runtime.storage().get(&slot), None);
 
     runtime.function("test", Vec::new());
 
-    assert_eq!(runtime.storage().get(&slot).unwrap(), &vec!(10, 0, 0, 0));
+    assert_eq!(
+        runtime.storage().get(&slot).unwrap(),
+        &vec!(0, 10, 0, 0, 0, 0, 0, 0)
+    );
 }
 
 #[test]
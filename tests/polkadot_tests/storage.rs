@@ -2,6 +2,7 @@
 
 use crate::build_solidity;
 use parity_scale_codec::{Decode, Encode};
+use primitive_types::U256;
 
 #[test]
 fn storage_load_on_return() {
@@ -51,3 +52,233 @@ fn storage_initializer_addr_type() {
     runtime.function("owner", Vec::new());
     assert_eq!(runtime.output(), runtime.caller());
 }
+
+#[test]
+fn packed_storage() {
+    #[derive(Debug, PartialEq, Eq, Encode, Decode)]
+    struct Packed {
+        a: u32,
+        b: bool,
+        c: [u8; 2],
+        d: u64,
+    }
+
+    let mut runtime = build_solidity(
+        r##"
+contract c {
+    enum State { Idle, Busy, Done }
+
+    bool public flag = true;
+    int8 public small = -3;
+    State public state;
+    uint64 public count;
+    uint256 big;
+
+    struct S { uint32 a; bool b; bytes2 c; uint64 d; }
+    S s;
+    S[] list;
+
+    function update() public {
+        count += 1000;
+        small--;
+        state = State.Done;
+        flag = !flag;
+        big = 7;
+    }
+
+    function clear() public {
+        delete small;
+    }
+
+    function set_struct() public {
+        s.a = 0xdeadbeef;
+        s.c = hex"0102";
+        s.b = true;
+        s.d = 42;
+        list.push(s);
+        list.push(S({ a: 1, b: false, c: hex"ffff", d: 2 }));
+        delete list[1].c;
+    }
+
+    function get_struct() public view returns (S, S, S) {
+        return (s, list[0], list[1]);
+    }
+}
+        "##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("flag", Vec::new());
+    assert_eq!(runtime.output(), true.encode());
+
+    runtime.function("small", Vec::new());
+    assert_eq!(runtime.output(), (-3i8).encode());
+
+    runtime.function("update", Vec::new());
+
+    runtime.function("flag", Vec::new());
+    assert_eq!(runtime.output(), false.encode());
+
+    runtime.function("small", Vec::new());
+    assert_eq!(runtime.output(), (-4i8).encode());
+
+    runtime.function("state", Vec::new());
+    assert_eq!(runtime.output(), 2u8.encode());
+
+    runtime.function("count", Vec::new());
+    assert_eq!(runtime.output(), 1000u64.encode());
+
+    runtime.function("clear", Vec::new());
+
+    runtime.function("small", Vec::new());
+    assert_eq!(runtime.output(), 0i8.encode());
+
+    runtime.function("count", Vec::new());
+    assert_eq!(runtime.output(), 1000u64.encode());
+
+    runtime.function("set_struct", Vec::new());
+    runtime.function("get_struct", Vec::new());
+    assert_eq!(
+        runtime.output(),
+        (
+            Packed {
+                a: 0xdeadbeef,
+                b: true,
+                c: [1, 2],
+                d: 42
+            },
+            Packed {
+                a: 0xdeadbeef,
+                b: true,
+                c: [1, 2],
+                d: 42
+            },
+            Packed {
+                a: 1,
+                b: false,
+                c: [0, 0],
+                d: 2
+            }
+        )
+            .encode()
+    );
+}
+
+#[test]
+fn storage_cache_in_loops() {
+    let mut runtime = build_solidity(
+        r##"
+contract c {
+    uint256 public total;
+    bool public flag;
+    uint64 public count;
+    mapping(uint256 => uint256) public balances;
+
+    function add(uint64 n) public {
+        for (uint64 i = 1; i <= n; i++) {
+            total += i;
+            count++;
+            balances[i] = total;
+        }
+    }
+
+    function add_until(uint64 n, uint256 limit) public returns (uint256) {
+        while (true) {
+            if (total >= limit) {
+                return total;
+            }
+            total += n;
+        }
+    }
+
+    function add_then_fail(uint64 n) public {
+        for (uint64 i = 0; i < 10; i++) {
+            total += 1;
+            require(i < n, "too many");
+        }
+    }
+
+    function nested(uint64 n) public {
+        for (uint64 i = 0; i < n; i++) {
+            for (uint64 j = 0; j < i; j++) {
+                count += 1;
+            }
+            flag = !flag;
+        }
+    }
+}
+        "##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("add", 4u64.encode());
+
+    runtime.function("total", Vec::new());
+    assert_eq!(runtime.output(), U256::from(10).encode());
+
+    runtime.function("count", Vec::new());
+    assert_eq!(runtime.output(), 4u64.encode());
+
+    runtime.function("balances", U256::from(3).encode());
+    assert_eq!(runtime.output(), U256::from(6).encode());
+
+    runtime.function("add_until", (5u64, U256::from(23)).encode());
+    assert_eq!(runtime.output(), U256::from(25).encode());
+
+    runtime.function("total", Vec::new());
+    assert_eq!(runtime.output(), U256::from(25).encode());
+
+    runtime.function_expect_failure("add_then_fail", 3u64.encode());
+
+    runtime.function("total", Vec::new());
+    assert_eq!(runtime.output(), U256::from(25).encode());
+
+    runtime.function("nested", 4u64.encode());
+
+    runtime.function("count", Vec::new());
+    assert_eq!(runtime.output(), 10u64.encode());
+
+    runtime.function("flag", Vec::new());
+    assert_eq!(runtime.output(), false.encode());
+}
+
+#[test]
+fn storage_cache_in_function() {
+    let mut runtime = build_solidity(
+        r##"
+contract c {
+    uint256 public total;
+    uint64 public count;
+
+    function bump(bool b, uint64 n) public returns (uint256) {
+        if (b) {
+            total += n;
+            total *= 2;
+        }
+        count += 1;
+        count += n;
+        return total + count;
+    }
+}
+        "##,
+    );
+
+    runtime.constructor(0, Vec::new());
+
+    runtime.function("bump", (false, 3u64).encode());
+    assert_eq!(runtime.output(), U256::from(4).encode());
+
+    runtime.function("total", Vec::new());
+    assert_eq!(runtime.output(), U256::from(0).encode());
+
+    runtime.function("bump", (true, 3u64).encode());
+    assert_eq!(runtime.output(), U256::from(14).encode());
+
+    runtime.function("total", Vec::new());
+    assert_eq!(runtime.output(), U256::from(6).encode());
+
+    runtime.function("count", Vec::new());
+    assert_eq!(runtime.output(), 8u64.encode());
+}
@@ -0,0 +1,236 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use solang::codegen::{codegen, Options};
+use solang::file_resolver::FileResolver;
+use solang::test_runner::{run_tests, run_tests_with_fuzzing, FuzzOptions, GasSnapshot};
+use solang::Target;
+use std::ffi::OsStr;
+
+#[test]
+fn run_test_contract() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "counter.sol",
+        r#"
+        contract Counter {
+            uint64 public count;
+
+            event Incremented(uint64 value);
+
+            function increment() public {
+                count += 1;
+                emit Incremented(count);
+            }
+
+            function decrement() public {
+                count -= 1;
+            }
+        }"#
+        .to_string(),
+    );
+
+    cache.set_file_contents(
+        "counter.t.sol",
+        r#"
+        import "counter.sol";
+
+        contract CounterTest {
+            Counter counter;
+
+            function setUp() public {
+                counter = new Counter();
+                counter.increment();
+            }
+
+            function testIncrement() public {
+                counter.increment();
+                require(counter.count() == 2, "count should be 2");
+            }
+
+            function testDecrement() public {
+                counter.decrement();
+                counter.decrement();
+            }
+
+            function testRequire() public pure {
+                require(false, "not true");
+            }
+
+            function testAssert() public {
+                print("before assert");
+                assert(counter.count() == 0);
+            }
+
+            function testWithParameter(int64 a) public {}
+
+            function helper() public {}
+        }"#
+        .to_string(),
+    );
+
+    let mut ns = solang::parse_and_resolve(
+        OsStr::new("counter.t.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+
+    let opt = Options {
+        log_runtime_errors: true,
+        ..Default::default()
+    };
+
+    codegen(&mut ns, &opt);
+
+    ns.print_diagnostics_in_plain(&cache, false);
+    assert!(!ns.diagnostics.any_errors());
+
+    let results = run_tests(&ns, &opt, None).unwrap();
+
+    let outcome: Vec<_> = results
+        .iter()
+        .map(|result| (result.function.as_str(), result.failure.as_deref()))
+        .collect();
+
+    assert_eq!(outcome.len(), 5);
+    assert_eq!(outcome[0], ("testAssert", Some("panic: Assertion (0x01)")));
+    assert_eq!(outcome[1].0, "testDecrement");
+    assert!(outcome[1].1.is_some());
+    assert_eq!(outcome[2], ("testIncrement", None));
+    assert_eq!(outcome[3], ("testRequire", Some("not true")));
+    assert_eq!(
+        outcome[4],
+        (
+            "testWithParameter",
+            Some("test functions cannot have parameters")
+        )
+    );
+
+    assert!(results
+        .iter()
+        .all(|result| result.contract == "CounterTest"));
+    assert!(results[2].gas > 0);
+    assert_eq!(results[2].events, vec!["Incremented(value: 2)".to_string()]);
+    assert!(results[0].events.is_empty());
+    assert!(results[0].logs.contains("before assert"));
+    assert!(results[3]
+        .logs
+        .contains("runtime_error: not true require condition failed in counter.t.sol"));
+
+    // only tests which passed are in the snapshot
+    let mut snapshot = GasSnapshot::default();
+    snapshot.record(&results);
+
+    assert_eq!(
+        snapshot.to_string(),
+        format!("CounterTest:testIncrement() (gas: {})\n", results[2].gas)
+    );
+
+    let results = run_tests(&ns, &opt, Some("Incr")).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].function, "testIncrement");
+    assert!(results[0].passed());
+}
+
+#[test]
+fn fuzz_invariants() {
+    let mut cache = FileResolver::default();
+
+    cache.set_file_contents(
+        "vault.t.sol",
+        r#"
+        contract Vault {
+            uint64 public total;
+
+            function deposit(uint64 amount) public {
+                total += amount;
+            }
+
+            function withdraw(uint64 amount) public {
+                require(amount <= total, "not enough");
+                total -= amount;
+            }
+        }
+
+        contract VaultTest {
+            Vault vault;
+            uint64 deposited;
+
+            function setUp() public {
+                vault = new Vault();
+            }
+
+            function deposit(uint32 amount) public {
+                vault.deposit(amount);
+                deposited += amount;
+            }
+
+            function withdraw(uint32 amount) public {
+                vault.withdraw(amount);
+                deposited -= amount;
+            }
+
+            function miscount(uint8 x) public {
+                if (x == 3) {
+                    deposited += 1;
+                }
+            }
+
+            function invariantNoop() public view {}
+
+            function invariantWithParameter(int64 a) public view {}
+
+            function invariant_total() public view returns (bool) {
+                return vault.total() == deposited;
+            }
+        }"#
+        .to_string(),
+    );
+
+    let mut ns = solang::parse_and_resolve(
+        OsStr::new("vault.t.sol"),
+        &mut cache,
+        Target::default_polkadot(),
+    );
+
+    let opt = Options::default();
+
+    codegen(&mut ns, &opt);
+
+    ns.print_diagnostics_in_plain(&cache, false);
+    assert!(!ns.diagnostics.any_errors());
+
+    let fuzz_options = FuzzOptions {
+        seed: 42,
+        ..Default::default()
+    };
+
+    let results = run_tests_with_fuzzing(&ns, &opt, None, &fuzz_options).unwrap();
+
+    let outcome: Vec<_> = results
+        .iter()
+        .map(|result| (result.function.as_str(), result.failure.as_deref()))
+        .collect();
+
+    assert_eq!(
+        outcome,
+        vec![
+            ("invariantNoop", None),
+            (
+                "invariantWithParameter",
+                Some("invariants cannot have parameters")
+            ),
+            ("invariant_total", Some("invariant broken: returned false")),
+        ]
+    );
+
+    // the sequence is shrunk to the one call which breaks the invariant
+    assert_eq!(results[2].calls, vec!["miscount(3)".to_string()]);
+    assert!(results[0].calls.is_empty());
+
+    let results = run_tests(&ns, &opt, Some("total")).unwrap();
+
+    assert_eq!(results.len(), 1);
+    assert!(!results[0].passed());
+}
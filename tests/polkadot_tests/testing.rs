@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::build_wasm;
+use parity_scale_codec::Encode;
+use solang::testing::differential::{ArgType, Case};
+use solang::testing::polkadot::MockPolkadot;
+use solang::testing::solana::BorshToken;
+use solang::Target;
+
+#[test]
+fn mock_polkadot() {
+    let wasm = build_wasm(
+        r#"
+        contract Counter {
+            uint64 count;
+
+            event Incremented(uint64 value);
+
+            @selector([0, 0, 0, 1])
+            constructor() {}
+
+            @selector([0, 0, 0, 2])
+            function increment(uint64 by) public {
+                count += by;
+                emit Incremented(count);
+            }
+
+            @selector([0, 0, 0, 3])
+            function get() public view returns (uint64) {
+                return count;
+            }
+        }"#,
+        false,
+    );
+
+    let mut mock = MockPolkadot::new(wasm.into_iter().map(|(code, _)| code).collect());
+
+    let (account, execution) = mock.deploy(0, vec![0, 0, 0, 1]);
+
+    assert!(execution.success());
+    assert_eq!(account, Some(1));
+    assert_eq!(mock.accounts(), 2);
+    assert_eq!(mock.address(0), [0xaa; 32]);
+    assert!(mock.storage(0).is_none());
+
+    let execution = mock.call(1, [vec![0, 0, 0, 2], 5u64.encode()].concat());
+
+    assert!(execution.success());
+    assert_eq!(execution.events.len(), 1);
+    assert_eq!(execution.events[0].code_no, 0);
+    assert_eq!(execution.events[0].topics.len(), 1);
+    assert_eq!(execution.events[0].data, [vec![0], 5u64.encode()].concat());
+
+    assert!(mock
+        .storage(1)
+        .unwrap()
+        .values()
+        .any(|value| *value == 5u64.encode()));
+
+    let execution = mock.call(1, vec![0, 0, 0, 3]);
+
+    assert_eq!(execution.result, Ok((0, 5u64.encode())));
+
+    mock.set_balance(1, 100);
+    assert_eq!(mock.balance(1), 100);
+}
+
+#[test]
+fn differential_abi() {
+    let case = Case {
+        types: vec![
+            ArgType::Uint(64),
+            ArgType::Struct(vec![ArgType::Bool, ArgType::String]),
+            ArgType::Array(Box::new(ArgType::Int(16)), None),
+        ],
+        args: vec![
+            BorshToken::Uint {
+                width: 64,
+                value: 0xdead_beef_u64.into(),
+            },
+            BorshToken::Tuple(vec![
+                BorshToken::Bool(true),
+                BorshToken::String("solang".into()),
+            ]),
+            BorshToken::Array(vec![
+                BorshToken::Int {
+                    width: 16,
+                    value: (-300).into(),
+                },
+                BorshToken::Int {
+                    width: 16,
+                    value: 7.into(),
+                },
+            ]),
+        ],
+    };
+
+    assert_eq!(case.check(&Target::default_polkadot()), Ok(()));
+}
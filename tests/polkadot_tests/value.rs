@@ -399,6 +399,43 @@ fn selfdestruct() {
     assert_eq!(runtime.balance(0), 20000);
 }
 
+#[test]
+fn terminate() {
+    let mut runtime = build_solidity(
+        r##"
+        import "polkadot";
+
+        contract c {
+            other o;
+            constructor() payable {}
+            function step1() public {
+                o = new other{value: 511, storage_deposit_limit: 1000}();
+            }
+
+            function step2() public {
+                o.goaway(payable(address(this)));
+            }
+        }
+
+        contract other {
+            constructor() payable {}
+            function goaway(address payable recipient) public {
+                terminate(recipient);
+            }
+        }"##,
+    );
+
+    runtime.set_transferred_value(20000);
+    runtime.constructor(0, Vec::new());
+
+    runtime.set_transferred_value(0);
+    runtime.function("step1", Vec::new());
+    assert_eq!(runtime.balance(0), 20000 - 511);
+
+    runtime.function("step2", Vec::new());
+    assert_eq!(runtime.balance(0), 20000);
+}
+
 #[test]
 fn send_and_transfer() {
     let mut runtime = build_solidity(
@@ -6,13 +6,6 @@ use crate::build_solidity;
 fn packed() {
     let mut vm = build_solidity(
         r#"
-        struct s {
-            int32 f1;
-            uint8 f2;
-            string f3;
-            uint16[2] f4;
-        }
-
         contract bar {
             function test() public {
                 uint16 a = 0xfd01;
@@ -31,9 +24,9 @@ fn packed() {
             }
 
             function test3() public {
-                s x = s({ f1: 511, f2: 0xf7, f3: "testie", f4: [ 4, 5 ] });
+                uint16[2] f4 = [ uint16(4), 5 ];
 
-                assert(abi.encodePacked(x) == hex"ff010000f774657374696504000500");
+                assert(abi.encodePacked(int32(511), uint8(0xf7), "testie", f4) == hex"ff010000f774657374696504000500");
             }
 
             function test4() public {
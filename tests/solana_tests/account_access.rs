@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::borsh_encoding::BorshToken;
-use crate::{account_new, build_solidity, create_program_address, AccountState};
+use crate::{
+    account_new, build_solidity, create_program_address, AccountState, VirtualMachineBuilder,
+};
 use anchor_syn::idl::types::{IdlAccount, IdlAccountItem, IdlInstruction};
+use solang::abi::anchor::account_discriminator;
+use solang::codegen::Options;
 
 #[test]
 fn access_payer() {
@@ -122,6 +126,65 @@ contract hatchling {
     assert_eq!(res.unwrap(), 2);
 }
 
+#[test]
+fn account_discriminator_is_checked() {
+    let src = r#"
+        contract flipper {
+            bool value;
+
+            function flip() public {
+                value = !value;
+            }
+        }"#;
+
+    let mut vm = build_solidity(src);
+
+    let data_account = vm.initialize_data_account();
+
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    vm.function("flip")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    assert_eq!(
+        vm.account_data[&data_account].data[0..8],
+        account_discriminator("flipper")
+    );
+
+    let idl = vm.stack[0].idl.as_ref().unwrap();
+
+    assert_eq!(idl.accounts.len(), 1);
+    assert_eq!(idl.accounts[0].name, "flipper");
+
+    // the legacy layout starts with a 4 byte magic number
+    let mut vm = VirtualMachineBuilder::new(src)
+        .opts(Options {
+            account_discriminator: false,
+            log_runtime_errors: true,
+            ..Default::default()
+        })
+        .build();
+
+    let data_account = vm.initialize_data_account();
+
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    vm.function("flip")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let data = &vm.account_data[&data_account].data;
+
+    assert_ne!(data[0..4], [0; 4]);
+    assert_eq!(data[4..8], [0; 4]);
+    assert!(vm.stack[0].idl.as_ref().unwrap().accounts.is_empty());
+}
+
 #[test]
 fn accounts_on_constructors() {
     let mut vm = build_solidity(
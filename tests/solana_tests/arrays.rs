@@ -2099,6 +2099,97 @@ fn dynamic_array_push_pop_loop() {
     runtime.function("test").call();
 }
 
+#[test]
+fn dynamic_array_reserve() {
+    let mut runtime = build_solidity(
+        r#"
+        contract foo {
+            function squares(uint32 n) public returns (uint64[] memory) {
+                uint64[] memory a = new uint64[](0);
+
+                a.reserve(n);
+                print("reserve");
+
+                for (uint64 i = 0; i < n; i++) {
+                    a.push(i * i);
+                    print("push");
+                }
+
+                // does not shrink the array
+                a.reserve(1);
+                print("reserve");
+
+                a.pop();
+                print("pop");
+                a.push(1000);
+                print("push");
+
+                return a;
+            }
+
+            function append(uint32 n) public returns (bytes memory) {
+                bytes b = new bytes(2);
+                b[0] = 0xff;
+
+                b.reserve(n);
+                print("reserve");
+
+                b.push(0x41);
+                b.push(0x42);
+
+                return b;
+            }
+        }"#,
+    );
+
+    let data_account = runtime.initialize_data_account();
+    runtime
+        .function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    for n in [1u64, 5, 40] {
+        let returns = runtime
+            .function("squares")
+            .arguments(&[BorshToken::Uint {
+                width: 32,
+                value: BigInt::from(n),
+            }])
+            .call()
+            .unwrap();
+
+        let mut expected = (0..n).map(|i| i * i).collect::<Vec<_>>();
+        expected.pop();
+        expected.push(1000);
+
+        assert_eq!(
+            returns,
+            BorshToken::Array(
+                expected
+                    .into_iter()
+                    .map(|v| BorshToken::Uint {
+                        width: 64,
+                        value: BigInt::from(v),
+                    })
+                    .collect()
+            )
+        );
+    }
+
+    for n in [0u64, 3, 100] {
+        let returns = runtime
+            .function("append")
+            .arguments(&[BorshToken::Uint {
+                width: 32,
+                value: BigInt::from(n),
+            }])
+            .call()
+            .unwrap();
+
+        assert_eq!(returns, BorshToken::Bytes(vec![0xff, 0, 0x41, 0x42]));
+    }
+}
+
 #[test]
 fn double_index() {
     let src = r#"
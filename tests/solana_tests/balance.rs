@@ -1,7 +1,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::build_solidity;
+use crate::{account_new, build_solidity, AccountMeta, BorshToken, ProgramResult, Pubkey};
 use anchor_syn::idl::types::IdlInstruction;
+use num_bigint::BigInt;
 
 #[test]
 fn fallback() {
@@ -37,3 +38,158 @@ fn fallback() {
 
     assert_eq!(vm.logs, "fallback");
 }
+
+#[test]
+fn fallback_bytes() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            fallback(bytes calldata input) external returns (bytes memory) {
+                return input;
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    // the instruction data does not match any function, so it is echoed back by the fallback
+    let input = b"no such function".to_vec();
+
+    let res = vm.execute(&[], &input);
+
+    assert!(matches!(res, ProgramResult::Ok(0)));
+    assert_eq!(vm.return_data.as_ref().unwrap().1, input);
+}
+
+#[test]
+fn lamports() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function balance_of(address a) public view returns (uint64) {
+                return a.balance;
+            }
+
+            function mine() public view returns (uint64) {
+                return address(this).balance;
+            }
+
+            function pay(address payable a, uint64 amount) public {
+                a.transfer(amount);
+            }
+
+            function try_pay(address payable a, uint64 amount) public returns (bool) {
+                return a.send(amount);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    vm.set_lamports(&data_account, 100);
+
+    let to = account_new();
+    vm.set_lamports(&to, 5);
+
+    let writable = [AccountMeta {
+        pubkey: Pubkey(to),
+        is_signer: false,
+        is_writable: true,
+    }];
+
+    let returns = vm
+        .function("balance_of")
+        .arguments(&[BorshToken::Address(to)])
+        .remaining_accounts(&writable)
+        .call()
+        .unwrap();
+
+    assert_eq!(returns.into_bigint().unwrap(), BigInt::from(5u8));
+
+    let returns = vm
+        .function("mine")
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(returns.into_bigint().unwrap(), BigInt::from(100u8));
+
+    vm.function("pay")
+        .arguments(&[
+            BorshToken::Address(to),
+            BorshToken::Uint {
+                width: 64,
+                value: BigInt::from(30u8),
+            },
+        ])
+        .accounts(vec![("dataAccount", data_account)])
+        .remaining_accounts(&writable)
+        .call();
+
+    assert_eq!(vm.account_data[&data_account].lamports, 70);
+    assert_eq!(vm.account_data[&to].lamports, 35);
+
+    // send returns false if the data account does not have enough lamports
+    let returns = vm
+        .function("try_pay")
+        .arguments(&[
+            BorshToken::Address(to),
+            BorshToken::Uint {
+                width: 64,
+                value: BigInt::from(71u8),
+            },
+        ])
+        .accounts(vec![("dataAccount", data_account)])
+        .remaining_accounts(&writable)
+        .call()
+        .unwrap();
+
+    assert_eq!(returns, BorshToken::Bool(false));
+    assert_eq!(vm.account_data[&data_account].lamports, 70);
+
+    let res = vm
+        .function("pay")
+        .arguments(&[
+            BorshToken::Address(to),
+            BorshToken::Uint {
+                width: 64,
+                value: BigInt::from(71u8),
+            },
+        ])
+        .accounts(vec![("dataAccount", data_account)])
+        .remaining_accounts(&writable)
+        .must_fail();
+
+    assert_eq!(res.unwrap(), 1);
+    assert!(vm.logs.contains("insufficient lamports for transfer"));
+
+    // the account which receives the lamports must be writable
+    let res = vm
+        .function("pay")
+        .arguments(&[
+            BorshToken::Address(to),
+            BorshToken::Uint {
+                width: 64,
+                value: BigInt::from(1u8),
+            },
+        ])
+        .accounts(vec![("dataAccount", data_account)])
+        .remaining_accounts(&[AccountMeta {
+            pubkey: Pubkey(to),
+            is_signer: false,
+            is_writable: false,
+        }])
+        .must_fail();
+
+    assert!(matches!(res, ProgramResult::Err(_)));
+    assert!(vm
+        .logs
+        .contains("account for lamport transfer is not writable"));
+    assert_eq!(vm.account_data[&to].lamports, 35);
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{build_solidity, BorshToken};
+use crate::{build_solidity, BorshToken, Clock};
 use base58::{FromBase58, ToBase58};
 use num_bigint::BigInt;
 
@@ -83,6 +83,25 @@ fn builtins() {
         },
     );
 
+    vm.set_clock(Clock {
+        unix_timestamp: 1700000000,
+        ..Clock::default()
+    });
+
+    let returns = vm
+        .function("mr_now")
+        .accounts(vec![("clock", clock_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(
+        returns,
+        BorshToken::Uint {
+            width: 64,
+            value: BigInt::from(1700000000u64)
+        }
+    );
+
     let returns = vm
         .function("msg_data")
         .arguments(&[BorshToken::Uint {
@@ -319,3 +338,105 @@ fn out_of_bounds_string_write() {
         .call();
     let _ = vm.function("testStringOut").call();
 }
+
+#[test]
+fn checked_conversion() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function narrow(int256 x) public pure returns (uint64) {
+                return toUint64(x);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let returns = vm
+        .function("narrow")
+        .arguments(&[BorshToken::Int {
+            width: 256,
+            value: BigInt::from(u64::MAX),
+        }])
+        .call()
+        .unwrap();
+
+    assert_eq!(
+        returns,
+        BorshToken::Uint {
+            width: 64,
+            value: BigInt::from(u64::MAX)
+        }
+    );
+
+    for value in [BigInt::from(u64::MAX) + 1, BigInt::from(-1)] {
+        let res = vm
+            .function("narrow")
+            .arguments(&[BorshToken::Int { width: 256, value }])
+            .must_fail();
+
+        assert_ne!(res.unwrap(), 0);
+    }
+}
+
+#[test]
+fn calldata_assembly() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function load(uint32 x, uint64 offset) public returns (uint256 word, uint256 size) {
+                assembly {
+                    word := calldataload(offset)
+                    size := calldatasize()
+                }
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    for (offset, word) in [
+        (0u64, "53ecbb4ce79f56acfecaadde0000000000000000"),
+        (8, "fecaadde08000000000000000000000000000000"),
+        (20, "0"),
+        (1000, "0"),
+    ] {
+        let returns = vm
+            .function("load")
+            .arguments(&[
+                BorshToken::Uint {
+                    width: 32,
+                    value: BigInt::from(0xdeadcafeu32),
+                },
+                BorshToken::Uint {
+                    width: 64,
+                    value: BigInt::from(offset),
+                },
+            ])
+            .call()
+            .unwrap();
+
+        // the word is padded with zeros on the right
+        let word = BigInt::parse_bytes(word.as_bytes(), 16).unwrap() << (12 * 8);
+
+        assert_eq!(
+            returns,
+            BorshToken::Tuple(vec![
+                BorshToken::Uint {
+                    width: 256,
+                    value: word,
+                },
+                BorshToken::Uint {
+                    width: 256,
+                    value: BigInt::from(20u8),
+                },
+            ])
+        );
+    }
+}
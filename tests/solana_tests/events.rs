@@ -3,6 +3,7 @@
 use crate::{borsh_encoding::BorshToken, build_solidity};
 use borsh::BorshDeserialize;
 use borsh_derive::BorshDeserialize;
+use num_bigint::BigInt;
 use solang::abi::anchor::event_discriminator;
 
 #[test]
@@ -141,3 +142,58 @@ fn less_simple_event() {
         )
     );
 }
+
+#[test]
+fn decoded_events() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            event Deposit(address indexed from, int64 amount);
+            event Note(string text);
+
+            function go(int64 amount) public {
+                emit Deposit(address(this), amount);
+                emit Note("deposited");
+                emit Deposit(address(this), -amount);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    vm.function("go")
+        .arguments(&[BorshToken::Int {
+            width: 64,
+            value: BigInt::from(7),
+        }])
+        .call();
+
+    let events = vm.decoded_events();
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[1].contract, "c");
+    assert_eq!(events[1].name, "Note");
+    assert_eq!(
+        events[1].fields["text"],
+        BorshToken::String("deposited".into())
+    );
+
+    let deposits = vm.events_named("c", "Deposit");
+
+    assert_eq!(deposits.len(), 2);
+    assert_eq!(deposits[0]["from"], BorshToken::Address(vm.stack[0].id));
+    assert_eq!(
+        deposits[1]["amount"],
+        BorshToken::Int {
+            width: 64,
+            value: BigInt::from(-7),
+        }
+    );
+
+    vm.clear_events();
+
+    assert!(vm.decoded_events().is_empty());
+}
@@ -1200,3 +1200,97 @@ function getArrAmt() public view returns (uint) {
         }
     );
 }
+
+#[test]
+fn enumerable_mapping() {
+    let mut vm = build_solidity(
+        r#"
+        contract foo {
+            @enumerable(true)
+            mapping (uint64 => uint64) map;
+
+            function set(uint64 index, uint64 val) public {
+                map[index] = val;
+            }
+
+            function rm(uint64 index) public {
+                delete map[index];
+            }
+
+            function keys() public view returns (uint64[] memory) {
+                return map.keys();
+            }
+
+            function total() public view returns (uint64 sum) {
+                for (uint32 i = 0; i < map.length; i++) {
+                    sum += map[map.keyAt(i)];
+                }
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let uint64 = |value: u64| BorshToken::Uint {
+        width: 64,
+        value: BigInt::from(value),
+    };
+
+    for (index, val) in [(7, 100), (3, 20), (7, 300), (11, 4)] {
+        vm.function("set")
+            .arguments(&[uint64(index), uint64(val)])
+            .accounts(vec![("dataAccount", data_account)])
+            .call();
+    }
+
+    // assigning to an existing key does not add it again
+    let returns = vm
+        .function("keys")
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(
+        returns,
+        BorshToken::Array(vec![uint64(7), uint64(3), uint64(11)])
+    );
+
+    let returns = vm
+        .function("total")
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(returns, uint64(324));
+
+    // the last key takes the place of the deleted key
+    vm.function("rm")
+        .arguments(&[uint64(7)])
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    // deleting a key which is not there does nothing
+    vm.function("rm")
+        .arguments(&[uint64(5)])
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let returns = vm
+        .function("keys")
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(returns, BorshToken::Array(vec![uint64(11), uint64(3)]));
+
+    let returns = vm
+        .function("total")
+        .accounts(vec![("dataAccount", data_account)])
+        .call()
+        .unwrap();
+
+    assert_eq!(returns, uint64(24));
+}
@@ -155,3 +155,94 @@ fn safe_math() {
         ])
         .must_fail();
 }
+
+#[test]
+fn math_builtins() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function mul_div(uint a, uint b, uint denominator) public pure returns (uint) {
+                return math.mulDiv(a, b, denominator);
+            }
+
+            function sqrt(uint x) public pure returns (uint) {
+                return math.sqrt(x);
+            }
+
+            function log2(uint x) public pure returns (uint) {
+                return math.log2(x);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let uint = |value: BigInt| BorshToken::Uint { width: 256, value };
+    let max: BigInt = (BigInt::from(1) << 256) - 1;
+
+    // the product does not fit into 256 bits, but the result does
+    let returns = vm
+        .function("mul_div")
+        .arguments(&[uint(max.clone()), uint(max.clone()), uint(max.clone())])
+        .call()
+        .unwrap();
+    assert_eq!(returns, uint(max.clone()));
+
+    let returns = vm
+        .function("mul_div")
+        .arguments(&[
+            uint(BigInt::from(1) << 200),
+            uint(BigInt::from(3) << 100),
+            uint(BigInt::from(7) << 60),
+        ])
+        .call()
+        .unwrap();
+    assert_eq!(
+        returns,
+        uint((BigInt::from(3) << 300) / (BigInt::from(7) << 60))
+    );
+
+    vm.function("mul_div")
+        .arguments(&[
+            uint(max.clone()),
+            uint(BigInt::from(2)),
+            uint(BigInt::from(1)),
+        ])
+        .must_fail();
+
+    vm.function("mul_div")
+        .arguments(&[
+            uint(BigInt::from(1)),
+            uint(BigInt::from(1)),
+            uint(BigInt::from(0)),
+        ])
+        .must_fail();
+
+    for (x, root) in [
+        (BigInt::from(0), BigInt::from(0)),
+        (BigInt::from(1), BigInt::from(1)),
+        (BigInt::from(2), BigInt::from(1)),
+        (BigInt::from(15), BigInt::from(3)),
+        (BigInt::from(16), BigInt::from(4)),
+        (BigInt::from(1) << 200, BigInt::from(1) << 100),
+        (max.clone(), (BigInt::from(1) << 128) - 1),
+    ] {
+        let returns = vm.function("sqrt").arguments(&[uint(x)]).call().unwrap();
+        assert_eq!(returns, uint(root));
+    }
+
+    for (x, log) in [
+        (BigInt::from(0), 0),
+        (BigInt::from(1), 0),
+        (BigInt::from(2), 1),
+        (BigInt::from(1023), 9),
+        (BigInt::from(1024), 10),
+        (max, 255),
+    ] {
+        let returns = vm.function("log2").arguments(&[uint(x)]).call().unwrap();
+        assert_eq!(returns, uint(BigInt::from(log)));
+    }
+}
@@ -29,6 +29,10 @@ static NO_OPTIMIZATIONS: Lazy<Options> = Lazy::new(|| Options {
     strength_reduce: false,
     vector_to_slice: false,
     common_subexpression_elimination: false,
+    storage_cache: false,
+    string_switch: false,
+    loop_unroll: false,
+    cold_split: false,
     ..Default::default()
 });
 
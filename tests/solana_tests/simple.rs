@@ -216,7 +216,7 @@ fn flipper() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..17].to_vec(),
-        hex::decode("6fc90ec500000000000000001800000001").unwrap()
+        hex::decode("c64c05ffeec53e7d000000001800000001").unwrap()
     );
 
     let returns = vm
@@ -233,7 +233,7 @@ fn flipper() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..17].to_vec(),
-        hex::decode("6fc90ec500000000000000001800000000").unwrap()
+        hex::decode("c64c05ffeec53e7d000000001800000000").unwrap()
     );
 
     let returns = vm
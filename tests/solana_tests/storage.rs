@@ -97,7 +97,7 @@ fn string() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![65, 177, 160, 100, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
+        vec![144, 60, 10, 51, 126, 194, 247, 206, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
     );
 
     let returns = vm
@@ -115,7 +115,7 @@ fn string() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![65, 177, 160, 100, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
+        vec![144, 60, 10, 51, 126, 194, 247, 206, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
     );
 
     assert_eq!(
@@ -148,7 +148,7 @@ fn string() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![65, 177, 160, 100, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
+        vec![144, 60, 10, 51, 126, 194, 247, 206, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
     );
 
     // Try setting this to an empty string. This is also a special case where
@@ -168,7 +168,7 @@ fn string() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![65, 177, 160, 100, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
+        vec![144, 60, 10, 51, 126, 194, 247, 206, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
     );
 }
 
@@ -204,7 +204,7 @@ fn bytes() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![11, 66, 182, 57, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
+        vec![114, 218, 16, 248, 101, 110, 115, 143, 0, 0, 0, 0, 24, 0, 0, 0, 0, 0, 0, 0]
     );
 
     let returns = vm
@@ -230,7 +230,7 @@ fn bytes() {
 
     assert_eq!(
         vm.account_data[&data_account].data[0..20].to_vec(),
-        vec![11, 66, 182, 57, 0, 0, 0, 0, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
+        vec![114, 218, 16, 248, 101, 110, 115, 143, 0, 0, 0, 0, 24, 0, 0, 0, 40, 0, 0, 0]
     );
 
     for (i, b) in b"The shoemaker always wears the worst shoes"
@@ -133,3 +133,128 @@ fn load_string_vector() {
         .unwrap();
     assert_eq!(returns, BorshToken::String("sixsix".to_string()));
 }
+
+#[test]
+fn string_builtins() {
+    let mut vm = build_solidity(
+        r#"
+        contract c {
+            function slice(string s, uint32 start, uint32 end) public pure returns (string) {
+                return s.slice(start, end);
+            }
+
+            function index_of(bytes haystack, bytes needle) public pure returns (int64) {
+                return haystack.indexOf(needle);
+            }
+
+            function to_string(int64 x) public pure returns (string, string) {
+                return (string.toString(x), string.toHexString(uint64(x)));
+            }
+
+            function parse_int(string s) public pure returns (int) {
+                return string.parseInt(s);
+            }
+        }"#,
+    );
+
+    let data_account = vm.initialize_data_account();
+    vm.function("new")
+        .accounts(vec![("dataAccount", data_account)])
+        .call();
+
+    let uint32 = |value: u32| BorshToken::Uint {
+        width: 32,
+        value: BigInt::from(value),
+    };
+
+    let returns = vm
+        .function("slice")
+        .arguments(&[
+            BorshToken::String("coffee_tastes_good".to_string()),
+            uint32(7),
+            uint32(13),
+        ])
+        .call()
+        .unwrap();
+    assert_eq!(returns, BorshToken::String("tastes".to_string()));
+
+    let returns = vm
+        .function("slice")
+        .arguments(&[BorshToken::String("tea".to_string()), uint32(3), uint32(3)])
+        .call()
+        .unwrap();
+    assert_eq!(returns, BorshToken::String(String::new()));
+
+    vm.function("slice")
+        .arguments(&[BorshToken::String("tea".to_string()), uint32(2), uint32(1)])
+        .must_fail();
+
+    vm.function("slice")
+        .arguments(&[BorshToken::String("tea".to_string()), uint32(0), uint32(4)])
+        .must_fail();
+
+    for (haystack, needle, index) in [
+        ("coffee", "fee", 3),
+        ("coffee", "e", 4),
+        ("coffee", "", 0),
+        ("coffee", "tea", -1),
+        ("tea", "teapot", -1),
+    ] {
+        let returns = vm
+            .function("index_of")
+            .arguments(&[
+                BorshToken::Bytes(haystack.as_bytes().to_vec()),
+                BorshToken::Bytes(needle.as_bytes().to_vec()),
+            ])
+            .call()
+            .unwrap();
+        assert_eq!(
+            returns,
+            BorshToken::Int {
+                width: 64,
+                value: BigInt::from(index),
+            }
+        );
+    }
+
+    let returns = vm
+        .function("to_string")
+        .arguments(&[BorshToken::Int {
+            width: 64,
+            value: BigInt::from(-255),
+        }])
+        .call()
+        .unwrap()
+        .unwrap_tuple();
+    assert_eq!(returns[0], BorshToken::String("-255".to_string()));
+    assert_eq!(
+        returns[1],
+        BorshToken::String("0xffffffffffffff01".to_string())
+    );
+
+    let max: BigInt = (BigInt::one() << 255) - 1;
+    let min: BigInt = -&max - BigInt::one();
+    let too_large: BigInt = &max + BigInt::one();
+
+    for (s, value) in [
+        ("0", BigInt::zero()),
+        ("-0", BigInt::zero()),
+        ("00042", BigInt::from(42)),
+        ("-1234", BigInt::from(-1234)),
+        (max.to_string().as_str(), max.clone()),
+        (min.to_string().as_str(), min.clone()),
+    ] {
+        let returns = vm
+            .function("parse_int")
+            .arguments(&[BorshToken::String(s.to_string())])
+            .call()
+            .unwrap();
+        assert_eq!(returns, BorshToken::Int { width: 256, value });
+    }
+
+    for s in ["", "-", "12a", " 1", "+1", too_large.to_string().as_str()] {
+        vm.function("parse_int")
+            .arguments(&[BorshToken::String(s.to_string())])
+            .must_fail();
+    }
+}
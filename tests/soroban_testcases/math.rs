@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::build_solidity;
-use soroban_sdk::Val;
+use soroban_sdk::{IntoVal, Val};
 
 #[test]
 fn math() {
@@ -21,9 +21,10 @@ fn math() {
     let res = env.invoke_contract(
         addr,
         "max",
-        vec![*Val::from_u32(4).as_val(), *Val::from_u32(5).as_val()],
+        vec![4_u64.into_val(&env.env), 5_u64.into_val(&env.env)],
     );
-    assert!(Val::from_u32(5).as_val().shallow_eq(&res))
+    let expected: Val = 5_u64.into_val(&env.env);
+    assert!(expected.shallow_eq(&res))
 }
 
 #[test]
@@ -61,18 +62,20 @@ fn math_same_name() {
     let res = src.invoke_contract(
         addr,
         "max_uint64_uint64",
-        vec![*Val::from_u32(4).as_val(), *Val::from_u32(5).as_val()],
+        vec![4_u64.into_val(&src.env), 5_u64.into_val(&src.env)],
     );
-    assert!(Val::from_u32(5).as_val().shallow_eq(&res));
+    let expected: Val = 5_u64.into_val(&src.env);
+    assert!(expected.shallow_eq(&res));
 
     let res = src.invoke_contract(
         addr,
         "max_uint64_uint64_uint64",
         vec![
-            *Val::from_u32(4).as_val(),
-            *Val::from_u32(5).as_val(),
-            *Val::from_u32(6).as_val(),
+            4_u64.into_val(&src.env),
+            5_u64.into_val(&src.env),
+            6_u64.into_val(&src.env),
         ],
     );
-    assert!(Val::from_u32(6).as_val().shallow_eq(&res));
+    let expected: Val = 6_u64.into_val(&src.env);
+    assert!(expected.shallow_eq(&res));
 }
@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::build_solidity;
+use soroban_sdk::{IntoVal, String, Val};
+
+#[test]
+fn counter() {
+    let env = build_solidity(
+        r#"contract counter {
+        uint64 count;
+
+        function increment() public returns (uint64) {
+            count += 1;
+            return count;
+        }
+
+        function get() public view returns (uint64) {
+            return count;
+        }
+    }"#,
+    );
+
+    let addr = env.contracts.last().unwrap();
+
+    let res = env.invoke_contract(addr, "increment", vec![]);
+    let expected: Val = 1_u64.into_val(&env.env);
+    assert!(expected.shallow_eq(&res));
+
+    let res = env.invoke_contract(addr, "increment", vec![]);
+    let expected: Val = 2_u64.into_val(&env.env);
+    assert!(expected.shallow_eq(&res));
+}
+
+#[test]
+fn mapping_and_string() {
+    let env = build_solidity(
+        r#"contract names {
+        mapping(uint32 => string) names;
+
+        function set(uint32 id, string name) public {
+            names[id] = name;
+        }
+
+        function get(uint32 id) public view returns (string) {
+            return names[id];
+        }
+    }"#,
+    );
+
+    let addr = env.contracts.last().unwrap();
+
+    env.invoke_contract(
+        addr,
+        "set",
+        vec![
+            7_u32.into_val(&env.env),
+            String::from_slice(&env.env, "alice").into_val(&env.env),
+        ],
+    );
+
+    let res = env.invoke_contract(addr, "get", vec![7_u32.into_val(&env.env)]);
+    let name: String = res.into_val(&env.env);
+    assert_eq!(name, String::from_slice(&env.env, "alice"));
+
+    let res = env.invoke_contract(addr, "get", vec![8_u32.into_val(&env.env)]);
+    let name: String = res.into_val(&env.env);
+    assert_eq!(name, String::from_slice(&env.env, ""));
+}
@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
-use solang::codegen::{codegen, OptimizationLevel, Options};
+use solang::codegen::{codegen, HeapAllocator, OptimizationLevel, Options};
 use solang::file_resolver::FileResolver;
 use solang::sema::ast::Diagnostic;
 use solang::sema::ast::Namespace;
@@ -21,10 +21,23 @@ fn parse_and_codegen(src: &'static str) -> Namespace {
         strength_reduce: false,
         vector_to_slice: false,
         common_subexpression_elimination: false,
+        storage_cache: false,
+        string_switch: false,
+        loop_unroll: false,
+        cold_split: false,
+        account_discriminator: true,
+        strip_dead_storage: false,
         opt_level: OptimizationLevel::Default,
         generate_debug_information: false,
+        generate_line_tables: false,
         log_runtime_errors: false,
         log_prints: true,
+        revert_data: true,
+        coverage: false,
+        trace: false,
+        llvm_passes: None,
+        inline_threshold: None,
+        heap_allocator: HeapAllocator::Bump,
         #[cfg(feature = "wasm_opt")]
         wasm_opt: None,
     };
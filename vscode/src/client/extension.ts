@@ -54,6 +54,10 @@ async function bootstrapExtension(context: vscode.ExtensionContext, serverpath:
       { language: 'solidity', scheme: 'file' },
       { language: 'solidity', scheme: 'untitled' },
     ],
+    synchronize: {
+      // the server is sent the solang settings when they change
+      configurationSection: 'solang',
+    },
   };
 
   const client = new LanguageClient('solidity', 'Solang Solidity Compiler', serverOptions, clientOptions).start();
@@ -62,6 +62,19 @@ suite('Extension Test Suite', function () {
     ]);
   });
 
+  this.timeout(20000);
+  const diagnosdoc5 = getDocUri('capability.sol');
+  test('Testing for diagnostics of target capabilities.', async () => {
+    await testdiagnos(diagnosdoc5, [
+      {
+        message: `'selfdestruct' is not available on Solana`,
+        range: toRange(2, 8, 2, 20),
+        severity: vscode.DiagnosticSeverity.Error,
+        source: `solidity`,
+      },
+    ]);
+  });
+
   // Tests for hover.
   this.timeout(20000);
   const hoverdoc1 = getDocUri('hover1.sol');
@@ -69,6 +82,12 @@ suite('Extension Test Suite', function () {
     await testhover(hoverdoc1);
   });
 
+  this.timeout(20000);
+  const hoverdoc2 = getDocUri('hover2.sol');
+  test('Testing for Hover details', async () => {
+    await testhoverdetails(hoverdoc2);
+  });
+
   // Tests for goto-definitions.
   this.timeout(20000);
   const defdoc1 = getDocUri('defs.sol');
@@ -76,6 +95,14 @@ suite('Extension Test Suite', function () {
     await testdefs(defdoc1);
   });
 
+  // Tests for goto-definitions across imports and inherited members.
+  this.timeout(20000);
+  const defdoc2 = getDocUri('defs_import.sol');
+  const defdoc3 = getDocUri('defs_base.sol');
+  test('Testing for GotoDefinitions across imports', async () => {
+    await testimportdefs(defdoc2, defdoc3);
+  });
+
   // Tests for goto-type-definitions.
   this.timeout(20000);
   const typedefdoc1 = getDocUri('defs.sol');
@@ -111,6 +138,13 @@ suite('Extension Test Suite', function () {
     await testrename(renamedoc1);
   });
 
+  // Tests for renaming names which are part of the contract ABI
+  this.timeout(20000);
+  const renamedoc2 = getDocUri('rename_abi.sol');
+  test('Testing for Rename of ABI names', async () => {
+    await testrenameabi(renamedoc2);
+  });
+
   // Tests for formatting
   this.timeout(20000);
   const formatdoc1 = getDocUri('format.sol');
@@ -124,6 +158,51 @@ suite('Extension Test Suite', function () {
   test('Testing for Completion', async () => {
     await testcompletion(compldoc1);
   });
+
+  // Tests for completion of builtins, calls and import paths
+  this.timeout(20000);
+  const compldoc2 = getDocUri('completion2.sol');
+  test('Testing for Completion of builtins, calls and imports', async () => {
+    await testcompletion2(compldoc2);
+  });
+
+  // Tests for the outline and workspace symbol search
+  this.timeout(20000);
+  const symboldoc1 = getDocUri('symbols.sol');
+  test('Testing for Document and Workspace Symbols', async () => {
+    await testsymbols(symboldoc1);
+  });
+
+  // Tests for signature help and inlay hints
+  this.timeout(20000);
+  const sigdoc1 = getDocUri('signatures.sol');
+  test('Testing for Signature Help and Inlay Hints', async () => {
+    await testsignatures(sigdoc1);
+  });
+
+  // Tests for semantic tokens
+  this.timeout(20000);
+  const tokensdoc1 = getDocUri('tokens.sol');
+  test('Testing for Semantic Tokens', async () => {
+    await testsemantictokens(tokensdoc1);
+  });
+
+  // Tests for code actions
+  this.timeout(20000);
+  const fixesdoc1 = getDocUri('fixes.sol');
+  const fixesdoc2 = getDocUri('fixes_override.sol');
+  const fixesdoc3 = getDocUri('fixes_import.sol');
+  const fixesdoc4 = getDocUri('defs_base.sol');
+  test('Testing for Code Actions', async () => {
+    await testcodeactions(fixesdoc1, fixesdoc2, fixesdoc3, fixesdoc4);
+  });
+
+  // Tests for call and type hierarchies
+  this.timeout(20000);
+  const hierarchydoc1 = getDocUri('hierarchy.sol');
+  test('Testing for Call and Type Hierarchies', async () => {
+    await testhierarchy(hierarchydoc1);
+  });
 });
 
 function toRange(lineno1: number, charno1: number, lineno2: number, charno2: number) {
@@ -201,6 +280,78 @@ async function testdefs(docUri: vscode.Uri) {
   assert.strictEqual(loc5.uri.path, docUri.path);
 }
 
+async function testimportdefs(docUri: vscode.Uri, baseUri: vscode.Uri) {
+  await activate(docUri);
+
+  // the import path
+  const pos1 = new vscode.Position(0, 12);
+  const actualdef1 = (await vscode.commands.executeCommand(
+    'vscode.executeDefinitionProvider',
+    docUri,
+    pos1
+  )) as vscode.Location[];
+  const loc1 = actualdef1[0];
+  assert.strictEqual(loc1.range.start.line, 0);
+  assert.strictEqual(loc1.range.start.character, 0);
+  assert.strictEqual(loc1.uri.path, baseUri.path);
+
+  // an inherited modifier
+  const pos2 = new vscode.Position(5, 43);
+  const actualdef2 = (await vscode.commands.executeCommand(
+    'vscode.executeDefinitionProvider',
+    docUri,
+    pos2
+  )) as vscode.Location[];
+  const loc2 = actualdef2[0];
+  assert.strictEqual(loc2.range.start.line, 9);
+  assert.strictEqual(loc2.range.start.character, 13);
+  assert.strictEqual(loc2.range.end.line, 9);
+  assert.strictEqual(loc2.range.end.character, 22);
+  assert.strictEqual(loc2.uri.path, baseUri.path);
+
+  // an inherited state variable
+  const pos3 = new vscode.Position(6, 18);
+  const actualdef3 = (await vscode.commands.executeCommand(
+    'vscode.executeDefinitionProvider',
+    docUri,
+    pos3
+  )) as vscode.Location[];
+  const loc3 = actualdef3[0];
+  assert.strictEqual(loc3.range.start.line, 7);
+  assert.strictEqual(loc3.range.start.character, 12);
+  assert.strictEqual(loc3.range.end.line, 7);
+  assert.strictEqual(loc3.range.end.character, 17);
+  assert.strictEqual(loc3.uri.path, baseUri.path);
+
+  // the library of a library call
+  const pos4 = new vscode.Position(7, 17);
+  const actualdef4 = (await vscode.commands.executeCommand(
+    'vscode.executeDefinitionProvider',
+    docUri,
+    pos4
+  )) as vscode.Location[];
+  const loc4 = actualdef4[0];
+  assert.strictEqual(loc4.range.start.line, 0);
+  assert.strictEqual(loc4.range.start.character, 8);
+  assert.strictEqual(loc4.range.end.line, 0);
+  assert.strictEqual(loc4.range.end.character, 15);
+  assert.strictEqual(loc4.uri.path, baseUri.path);
+
+  // a library function attached with `using for`
+  const pos5 = new vscode.Position(7, 38);
+  const actualdef5 = (await vscode.commands.executeCommand(
+    'vscode.executeDefinitionProvider',
+    docUri,
+    pos5
+  )) as vscode.Location[];
+  const loc5 = actualdef5[0];
+  assert.strictEqual(loc5.range.start.line, 1);
+  assert.strictEqual(loc5.range.start.character, 13);
+  assert.strictEqual(loc5.range.end.line, 1);
+  assert.strictEqual(loc5.range.end.character, 19);
+  assert.strictEqual(loc5.uri.path, baseUri.path);
+}
+
 async function testtypedefs(docUri: vscode.Uri) {
   await activate(docUri);
 
@@ -486,6 +637,54 @@ async function testrename(docUri: vscode.Uri) {
   assert.strictEqual(loc03.newText, newname0);
 }
 
+async function testrenameabi(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  // a public state variable cannot be renamed without forcing it
+  const pos0 = new vscode.Position(4, 9);
+  await assert.rejects(
+    vscode.commands.executeCommand(
+      'vscode.executeDocumentRenameProvider',
+      docUri,
+      pos0,
+      'changed',
+    ) as Thenable<vscode.WorkspaceEdit>,
+  );
+
+  // a parameter can be renamed
+  const pos1 = new vscode.Position(4, 18);
+  const newname1 = 'value';
+  const rename1 = (await vscode.commands.executeCommand(
+    'vscode.executeDocumentRenameProvider',
+    docUri,
+    pos1,
+    newname1,
+  )) as vscode.WorkspaceEdit;
+
+  const loc1 = rename1.get(docUri);
+  assert.strictEqual(loc1.length, 2);
+  const loc10 = loc1[0] as vscode.TextEdit;
+  assert.strictEqual(loc10.range.start.line, 3);
+  assert.strictEqual(loc10.range.start.character, 24);
+  assert.strictEqual(loc10.range.end.line, 3);
+  assert.strictEqual(loc10.range.end.character, 30);
+  assert.strictEqual(loc10.newText, newname1);
+  const loc11 = loc1[1] as vscode.TextEdit;
+  assert.strictEqual(loc11.range.start.line, 4);
+  assert.strictEqual(loc11.range.start.character, 17);
+  assert.strictEqual(loc11.range.end.line, 4);
+  assert.strictEqual(loc11.range.end.character, 23);
+  assert.strictEqual(loc11.newText, newname1);
+
+  // references to the state variable include its declaration
+  const refs = (await vscode.commands.executeCommand(
+    'vscode.executeReferenceProvider',
+    docUri,
+    pos0,
+  )) as vscode.Location[];
+  assert.strictEqual(refs.length, 2);
+}
+
 async function testformat(docUri: vscode.Uri) {
   await activate(docUri);
 
@@ -641,7 +840,44 @@ async function testhover(docUri: vscode.Uri) {
 
   const contentarr5 = actualhover5[0].contents as vscode.MarkdownString[];
 
-  assert.strictEqual(contentarr5[0].value, '```solidity\nfunction foo.sum(uint64 a, uint64 b) returns (uint64)\n\n```');
+  assert.strictEqual(
+    contentarr5[0].value,
+    '```solidity\nfunction foo.sum(uint64 a, uint64 b) returns (uint64)\n\n```\n\ndiscriminator: `0xa4d80da3bb021cf5`'
+  );
+}
+
+async function testhoverdetails(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  const hovers = async (line: number, character: number) => {
+    const actualhover = (await vscode.commands.executeCommand(
+      'vscode.executeHoverProvider',
+      docUri,
+      new vscode.Position(line, character)
+    )) as vscode.Hover[];
+
+    return (actualhover[0].contents as vscode.MarkdownString[])[0].value;
+  };
+
+  assert.strictEqual(
+    await hovers(9, 14),
+    'Set the price\n\n```solidity\nfunction Shop.setPrice(usertype Price p) returns ()\n\n```\n\ndiscriminator: `0x1013b608955348b5`'
+  );
+
+  assert.strictEqual(
+    await hovers(10, 8),
+    '```solidity\nusertype Price storage Shop.price\n```\n\noffset in account data: 48\n\n`usertype Price` is `uint64`'
+  );
+
+  assert.strictEqual(
+    await hovers(11, 22),
+    'The owner of the shop\n\n```solidity\naddress storage Shop.owner\n```\n\noffset in account data: 16\n\n`address` is 32 bytes on Solana'
+  );
+
+  assert.strictEqual(
+    await hovers(11, 16),
+    '```solidity\naddress who\n```\n\n`address` is 32 bytes on Solana'
+  );
 }
 
 async function testdiagnos(docUri: vscode.Uri, expecteddiag: vscode.Diagnostic[]) {
@@ -660,3 +896,319 @@ async function testdiagnos(docUri: vscode.Uri, expecteddiag: vscode.Diagnostic[]
     console.error('the diagnostics are incorrect', actualDiagnostics);
   }
 }
+
+async function testcompletion2(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  const get_labels = (list: vscode.CompletionList) => list.items.map(item => item.label);
+
+  // members of a builtin namespace
+  const pos0 = new vscode.Position(8, 26);
+  const suggestions0 = (await vscode.commands.executeCommand(
+    'vscode.executeCompletionItemProvider',
+    docUri,
+    pos0,
+    '.'
+  )) as vscode.CompletionList;
+  const labels0 = get_labels(suggestions0);
+  assert.ok(labels0.includes('timestamp') && !labels0.includes('sender') && !labels0.includes('ts'));
+
+  // functions are completed with a snippet for their arguments
+  const pos1 = new vscode.Position(9, 15);
+  const suggestions1 = (await vscode.commands.executeCommand(
+    'vscode.executeCompletionItemProvider',
+    docUri,
+    pos1
+  )) as vscode.CompletionList;
+  const sum = suggestions1.items.find(item => item.label === 'sum');
+  assert.ok(sum);
+  assert.strictEqual((sum.insertText as vscode.SnippetString).value, 'sum(${1:left}, ${2:right})');
+  assert.ok(get_labels(suggestions1).includes('block'));
+
+  // import paths
+  const pos2 = new vscode.Position(0, 10);
+  const suggestions2 = (await vscode.commands.executeCommand(
+    'vscode.executeCompletionItemProvider',
+    docUri,
+    pos2
+  )) as vscode.CompletionList;
+  const labels2 = get_labels(suggestions2);
+  assert.ok(labels2.includes('defs_base.sol') && labels2.includes('completion.sol'));
+}
+
+async function testsymbols(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  // the outline nests the code objects in their contract
+  const symbols = (await vscode.commands.executeCommand(
+    'vscode.executeDocumentSymbolProvider',
+    docUri
+  )) as vscode.DocumentSymbol[];
+  assert.deepStrictEqual(
+    symbols.map(symbol => symbol.name),
+    ['Ledger', 'checkedAmount']
+  );
+  const ledger = symbols[0];
+  assert.strictEqual(ledger.kind, vscode.SymbolKind.Class);
+  assert.deepStrictEqual(ledger.range, toRange(2, 0, 25, 1));
+  assert.deepStrictEqual(ledger.selectionRange, toRange(2, 9, 2, 15));
+  assert.deepStrictEqual(
+    ledger.children.map(symbol => [symbol.name, symbol.kind]),
+    [
+      ['Entry', vscode.SymbolKind.Struct],
+      ['Status', vscode.SymbolKind.Enum],
+      ['Recorded', vscode.SymbolKind.Event],
+      ['total', vscode.SymbolKind.Field],
+      ['constructor', vscode.SymbolKind.Constructor],
+      ['record', vscode.SymbolKind.Method],
+    ]
+  );
+  assert.deepStrictEqual(
+    ledger.children[0].children.map(symbol => symbol.name),
+    ['account', 'amount']
+  );
+  assert.strictEqual(symbols[1].kind, vscode.SymbolKind.Function);
+
+  // workspace symbols include the imported files, and the query matches fuzzily
+  const found = (await vscode.commands.executeCommand(
+    'vscode.executeWorkspaceSymbolProvider',
+    'onlyown'
+  )) as vscode.SymbolInformation[];
+  const only_owner = found.find(symbol => symbol.name === 'onlyOwner');
+  assert.ok(only_owner);
+  assert.strictEqual(only_owner.containerName, 'Owned');
+  assert.strictEqual(only_owner.location.uri.fsPath, getDocUri('defs_base.sol').fsPath);
+
+  const fuzzy = (await vscode.commands.executeCommand(
+    'vscode.executeWorkspaceSymbolProvider',
+    'chkamt'
+  )) as vscode.SymbolInformation[];
+  assert.ok(fuzzy.some(symbol => symbol.name === 'checkedAmount'));
+}
+
+async function testsignatures(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  // both overloads are offered, and the first one with enough parameters is active
+  const pos1 = new vscode.Position(18, 28);
+  const help1 = (await vscode.commands.executeCommand(
+    'vscode.executeSignatureHelpProvider',
+    docUri,
+    pos1
+  )) as vscode.SignatureHelp;
+  assert.deepStrictEqual(
+    help1.signatures.map(signature => signature.label),
+    [
+      'function Payments.pay(address to, uint64 amount)',
+      'function Payments.pay(address to, uint64 amount, string memo)',
+    ]
+  );
+  assert.strictEqual(help1.activeSignature, 0);
+  assert.strictEqual(help1.activeParameter, 1);
+
+  // events
+  const pos2 = new vscode.Position(4, 23);
+  const help2 = (await vscode.commands.executeCommand(
+    'vscode.executeSignatureHelpProvider',
+    docUri,
+    pos2
+  )) as vscode.SignatureHelp;
+  assert.strictEqual(help2.signatures[0].label, 'event Paid(address to, uint64 amount)');
+  assert.strictEqual(help2.activeParameter, 1);
+
+  const hints = (await vscode.commands.executeCommand(
+    'vscode.executeInlayHintProvider',
+    docUri,
+    toRange(0, 0, 24, 1)
+  )) as vscode.InlayHint[];
+  const labels = hints.map(hint => [hint.position.line, hint.position.character, hint.label]);
+  assert.deepStrictEqual(labels, [
+    [7, 50, 'memory'],
+    [11, 27, 'memory'],
+    [18, 27, 'amount:'],
+    [20, 17, ': u256'],
+  ]);
+}
+
+async function testsemantictokens(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  const legend = (await vscode.commands.executeCommand(
+    'vscode.provideDocumentSemanticTokensLegend',
+    docUri
+  )) as vscode.SemanticTokensLegend;
+  const tokens = (await vscode.commands.executeCommand(
+    'vscode.provideDocumentSemanticTokens',
+    docUri
+  )) as vscode.SemanticTokens;
+
+  // each token is relative to the one before it
+  const decoded = [];
+  let line = 0;
+  let character = 0;
+  for (let i = 0; i < tokens.data.length; i += 5) {
+    line += tokens.data[i];
+    character = tokens.data[i] === 0 ? character + tokens.data[i + 1] : tokens.data[i + 1];
+    const modifiers = legend.tokenModifiers.filter((_, no) => tokens.data[i + 4] & (1 << no));
+    decoded.push([line, character, tokens.data[i + 2], legend.tokenTypes[tokens.data[i + 3]], modifiers]);
+  }
+
+  const expected = [
+    [5, 20, 5, 'property', ['declaration', 'readonly', 'constant']],
+    [6, 21, 7, 'property', ['declaration', 'readonly', 'immutable']],
+    [7, 32, 8, 'property', ['declaration', 'storage']],
+    [9, 10, 9, 'event', ['declaration']],
+    [11, 13, 6, 'modifier', ['declaration']],
+    [12, 8, 7, 'function', ['defaultLibrary']],
+    [12, 16, 15, 'variable', ['readonly', 'defaultLibrary']],
+    [20, 57, 6, 'modifier', []],
+    [21, 8, 7, 'struct', []],
+    [21, 24, 7, 'variable', ['declaration', 'storage']],
+    [22, 23, 4, 'variable', ['declaration', 'memory']],
+    [23, 27, 6, 'parameter', []],
+    [24, 13, 9, 'event', []],
+  ];
+
+  for (const token of expected) {
+    assert.deepStrictEqual(
+      decoded.find(found => found[0] === token[0] && found[1] === token[1]),
+      token
+    );
+  }
+}
+
+async function codeactions(docUri: vscode.Uri, range: vscode.Range, kind?: string) {
+  return (await vscode.commands.executeCommand(
+    'vscode.executeCodeActionProvider',
+    docUri,
+    range,
+    kind
+  )) as vscode.CodeAction[];
+}
+
+// the edits of an action, as [start line, start character, end line, end character, new text]
+function actionedits(action: vscode.CodeAction, docUri: vscode.Uri) {
+  return (action.edit?.get(docUri) ?? []).map(edit => [
+    edit.range.start.line,
+    edit.range.start.character,
+    edit.range.end.line,
+    edit.range.end.character,
+    edit.newText,
+  ]);
+}
+
+async function testcodeactions(
+  docUri: vscode.Uri,
+  overrideUri: vscode.Uri,
+  importUri: vscode.Uri,
+  baseUri: vscode.Uri
+) {
+  await activate(docUri);
+
+  // unused variables are removed with their line, unless the initializer has an effect
+  const actions1 = await codeactions(docUri, toRange(6, 15, 6, 21));
+  const remove = actions1.find(action => action.title === "Remove unused variable 'unused'");
+  assert.ok(remove);
+  assert.deepStrictEqual(actionedits(remove, docUri), [[6, 0, 7, 0, '']]);
+
+  const actions2 = await codeactions(docUri, toRange(11, 15, 11, 23));
+  assert.ok(!actions2.some(action => action.title === "Remove unused variable 'previous'"));
+
+  const actions3 = await codeactions(docUri, toRange(5, 18, 5, 30));
+  const location = actions3.find(action => action.title === "Add data location 'memory'");
+  assert.ok(location);
+  assert.deepStrictEqual(actionedits(location, docUri), [[5, 24, 5, 24, ' memory']]);
+
+  const fixall = await codeactions(docUri, toRange(0, 0, 0, 0), 'source.fixAll');
+  assert.strictEqual(fixall.length, 1);
+  assert.strictEqual(fixall[0].title, 'Fix all problems in file');
+  assert.deepStrictEqual(actionedits(fixall[0], docUri), [
+    [6, 0, 7, 0, ''],
+    [11, 34, 11, 49, ''],
+  ]);
+
+  await activate(overrideUri);
+
+  const actions4 = await codeactions(overrideUri, toRange(3, 4, 3, 46));
+  const override = actions4.find(action => action.title === "Add 'override'");
+  assert.ok(override);
+  assert.deepStrictEqual(actionedits(override, overrideUri), [[3, 20, 3, 20, ' override']]);
+
+  // the file which declares the name must have been seen
+  await activate(baseUri);
+  await activate(importUri);
+
+  const actions5 = await codeactions(importUri, toRange(2, 18, 2, 23));
+  const importfix = actions5.find(action => action.title === 'Import \'Owned\' from "./defs_base.sol"');
+  assert.ok(importfix);
+  assert.deepStrictEqual(actionedits(importfix, importUri), [
+    [1, 0, 1, 0, 'import "./defs_base.sol";\n'],
+  ]);
+}
+
+async function testhierarchy(docUri: vscode.Uri) {
+  await activate(docUri);
+
+  // at a call of `add`
+  const calls = (await vscode.commands.executeCommand(
+    'vscode.prepareCallHierarchy',
+    docUri,
+    new vscode.Position(15, 9)
+  )) as vscode.CallHierarchyItem[];
+  assert.strictEqual(calls.length, 1);
+  assert.strictEqual(calls[0].name, 'add');
+  assert.deepStrictEqual(calls[0].selectionRange, toRange(8, 13, 8, 16));
+
+  const incoming = (await vscode.commands.executeCommand(
+    'vscode.provideIncomingCalls',
+    calls[0]
+  )) as vscode.CallHierarchyIncomingCall[];
+  assert.deepStrictEqual(
+    incoming.map(call => [call.from.name, call.fromRanges.map(range => range.start.line)]),
+    [
+      ['increment', [15]],
+      ['incrementTwice', [19, 21]],
+      ['addMany', [27]],
+    ]
+  );
+
+  const outgoing = (await vscode.commands.executeCommand(
+    'vscode.provideOutgoingCalls',
+    incoming[1].from
+  )) as vscode.CallHierarchyOutgoingCall[];
+  assert.deepStrictEqual(
+    outgoing.map(call => [call.to.name, call.fromRanges.length]),
+    [
+      ['add', 2],
+      ['increment', 1],
+    ]
+  );
+
+  // modifiers are called too
+  const modifiers = (await vscode.commands.executeCommand(
+    'vscode.provideOutgoingCalls',
+    calls[0]
+  )) as vscode.CallHierarchyOutgoingCall[];
+  assert.deepStrictEqual(modifiers.map(call => call.to.name), ['positive']);
+
+  // at the declaration of `Counter`
+  const types = (await vscode.commands.executeCommand(
+    'vscode.prepareTypeHierarchy',
+    docUri,
+    new vscode.Position(13, 10)
+  )) as vscode.TypeHierarchyItem[];
+  assert.strictEqual(types.length, 1);
+  assert.strictEqual(types[0].name, 'Counter');
+
+  const supertypes = (await vscode.commands.executeCommand(
+    'vscode.provideSupertypes',
+    types[0]
+  )) as vscode.TypeHierarchyItem[];
+  assert.deepStrictEqual(supertypes.map(item => item.name), ['Base']);
+
+  const subtypes = (await vscode.commands.executeCommand(
+    'vscode.provideSubtypes',
+    supertypes[0]
+  )) as vscode.TypeHierarchyItem[];
+  assert.deepStrictEqual(subtypes.map(item => item.name), ['Counter', 'BigCounter']);
+}
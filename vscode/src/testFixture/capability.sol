@@ -0,0 +1,5 @@
+contract Vault {
+    function close(address payable to) public {
+        selfdestruct(to);
+    }
+}
@@ -0,0 +1,12 @@
+import "./defs_base.sol";
+
+contract completer {
+    function sum(uint64 left, uint64 right) public pure returns (uint64) {
+        return left + right;
+    }
+
+    function test() public view returns (uint64) {
+        uint64 ts = block.timestamp;
+        return sum(ts, 1);
+    }
+}
@@ -0,0 +1,14 @@
+library MathLib {
+    function double(uint64 x) internal pure returns (uint64) {
+        return x * 2;
+    }
+}
+
+abstract contract Owned {
+    address owner;
+
+    modifier onlyOwner() {
+        require(owner != address(0));
+        _;
+    }
+}
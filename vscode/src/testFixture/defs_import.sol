@@ -0,0 +1,10 @@
+import "./defs_base.sol";
+
+contract wallet is Owned {
+    using MathLib for uint64;
+
+    function twice(uint64 v) public view onlyOwner returns (uint64) {
+        require(owner != address(0));
+        return MathLib.double(v) + v.double();
+    }
+}
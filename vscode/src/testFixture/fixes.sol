@@ -0,0 +1,19 @@
+pragma solidity ^0.8.0;
+
+contract Counter {
+    uint64 count;
+
+    function size(string label) public pure returns (uint64) {
+        uint64 unused = 5;
+        return uint64(bytes(label).length);
+    }
+
+    function increment() public {
+        uint64 previous = next(); uint64 skipped;
+        count += 1;
+    }
+
+    function next() internal returns (uint64) {
+        return count + 1;
+    }
+}
@@ -0,0 +1,19 @@
+pragma solidity ^0.8.0;
+
+contract Derived is Base, Other {
+    function value() internal returns (uint64) {
+        return 2;
+    }
+
+    function total() public override returns (uint64) {
+        return 3;
+    }
+}
+
+abstract contract Base {
+    function value() internal virtual returns (uint64);
+}
+
+abstract contract Other {
+    function total() public virtual returns (uint64);
+}
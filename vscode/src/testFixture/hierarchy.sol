@@ -0,0 +1,30 @@
+abstract contract Base {
+    uint64 total;
+
+    modifier positive(uint64 amount) {
+        require(amount > 0);
+        _;
+    }
+
+    function add(uint64 amount) internal positive(amount) {
+        total += amount;
+    }
+}
+
+contract Counter is Base {
+    function increment() public {
+        add(1);
+    }
+
+    function incrementTwice() public {
+        add(1);
+        increment();
+        add(1);
+    }
+}
+
+contract BigCounter is Base {
+    function addMany(uint64 amount) public {
+        add(amount);
+    }
+}
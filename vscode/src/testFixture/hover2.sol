@@ -0,0 +1,15 @@
+type Price is uint64;
+
+contract Shop {
+    /// @notice The owner of the shop
+    address owner;
+    Price price;
+
+    /// @notice Set the price
+    /// @param p the new price
+    function setPrice(Price p) public {
+        price = p;
+        address who = owner;
+        require(who != address(0));
+    }
+}
@@ -0,0 +1,7 @@
+contract counter {
+    uint64 public total;
+
+    function add(uint64 amount) public {
+        total += amount;
+    }
+}
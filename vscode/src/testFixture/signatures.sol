@@ -0,0 +1,25 @@
+contract Payments {
+    event Paid(address to, uint64 amount);
+
+    function pay(address to, uint64 amount) public {
+        emit Paid(to, amount);
+    }
+
+    function pay(address to, uint64 amount, string memo) public {
+        emit Paid(to, amount + uint64(bytes(memo).length));
+    }
+
+    function total(uint64[] amounts) public pure returns (uint64 sum) {
+        for (uint32 i = 0; i < amounts.length; i++) {
+            sum += amounts[i];
+        }
+    }
+
+    function run() public returns (uint256 y) {
+        pay(address(this), 5);
+        assembly {
+            let x := 1
+            y := x
+        }
+    }
+}
@@ -0,0 +1,30 @@
+import "./defs_base.sol";
+
+contract Ledger is Owned {
+    struct Entry {
+        address account;
+        uint64 amount;
+    }
+
+    enum Status {
+        Open,
+        Closed
+    }
+
+    event Recorded(address account, uint64 amount);
+
+    uint64 public total;
+
+    constructor() {
+        total = 0;
+    }
+
+    function record(address account, uint64 amount) public onlyOwner {
+        total += amount;
+        emit Recorded(account, amount);
+    }
+}
+
+function checkedAmount(uint64 amount) pure returns (uint64) {
+    return MathLib.double(amount);
+}
@@ -0,0 +1,27 @@
+contract Tokens {
+    struct Account {
+        uint64 balance;
+    }
+
+    uint64 constant LIMIT = 100;
+    uint64 immutable created;
+    mapping(address => Account) accounts;
+
+    event Deposited(address from, uint64 amount);
+
+    modifier recent() {
+        require(block.timestamp < created + LIMIT);
+        _;
+    }
+
+    constructor() {
+        created = block.timestamp;
+    }
+
+    function deposit(address from, uint64 amount) public recent {
+        Account storage account = accounts[from];
+        Account memory copy = account;
+        account.balance += amount + copy.balance;
+        emit Deposited(from, amount);
+    }
+}